@@ -0,0 +1,628 @@
+//! `rpg-encoder doctor`: one cron-able pass over the graph.
+//!
+//! Runs every [`Check`] in order, writes a markdown report to
+//! `.rpg/doctor-report.md`, and folds the results into one exit code:
+//! 0 when everything passed (or was skipped), 1 on warnings, 2 on failures.
+
+use anyhow::Result;
+use rpg_core::config::RpgConfig;
+use rpg_core::graph::{HierarchyNode, RPGraph};
+use std::path::Path;
+
+/// Report file name under `.rpg/`.
+pub(crate) const REPORT_FILE: &str = "doctor-report.md";
+
+/// Stats history file name under `.rpg/` (one JSON object per line).
+pub(crate) const STATS_HISTORY_FILE: &str = "stats-history.jsonl";
+
+/// Pending routing and relift work older than this is reported.
+const MAX_QUEUE_AGE_DAYS: i64 = 7;
+
+/// Changed files listed in the staleness section.
+const MAX_LISTED_CHANGES: usize = 10;
+
+/// An area holding more than this share of entities is reported as oversized.
+const OVERSIZED_AREA_SHARE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    Pass,
+    Skip,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Skip => "skip",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+        }
+    }
+
+    fn exit_code(self) -> i32 {
+        match self {
+            Self::Pass | Self::Skip => 0,
+            Self::Warn => 1,
+            Self::Fail => 2,
+        }
+    }
+}
+
+/// Outcome of one check: a one-line summary plus detail lines for the report.
+pub(crate) struct CheckResult {
+    pub(crate) status: Status,
+    pub(crate) summary: String,
+    pub(crate) details: Vec<String>,
+}
+
+impl CheckResult {
+    fn new(status: Status, summary: impl Into<String>) -> Self {
+        Self {
+            status,
+            summary: summary.into(),
+            details: Vec::new(),
+        }
+    }
+
+    fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// State shared by the checks of one doctor run.
+pub(crate) struct DoctorContext<'a> {
+    pub(crate) project_root: &'a Path,
+    pub(crate) config: RpgConfig,
+    pub(crate) graph: RPGraph,
+    /// Repair what can be repaired (`--fix`).
+    pub(crate) fix: bool,
+    /// Results of the checks that already ran, in order.
+    pub(crate) results: Vec<(&'static str, CheckResult)>,
+}
+
+impl DoctorContext<'_> {
+    fn worst_status(&self) -> Status {
+        worst(self.results.iter().map(|(_, r)| r.status))
+    }
+}
+
+fn worst(statuses: impl Iterator<Item = Status>) -> Status {
+    statuses
+        .max_by_key(|s| s.exit_code())
+        .unwrap_or(Status::Pass)
+}
+
+/// One doctor check. An `Err` is reported as a failure of that check; the
+/// remaining checks still run.
+pub(crate) trait Check {
+    fn name(&self) -> &'static str;
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult>;
+}
+
+/// The checks `rpg-encoder doctor` runs, in order. Stats history goes last so
+/// it can record the overall status.
+pub(crate) fn default_checks() -> Vec<Box<dyn Check>> {
+    vec![
+        Box::new(StalenessCheck),
+        Box::new(ValidateCheck),
+        Box::new(HierarchyCheck),
+        Box::new(HealthCheck),
+        Box::new(EmbeddingsCheck),
+        Box::new(QueueAgeCheck),
+        Box::new(StatsHistoryCheck),
+    ]
+}
+
+/// A finished doctor run.
+pub(crate) struct DoctorReport {
+    pub(crate) results: Vec<(&'static str, CheckResult)>,
+}
+
+impl DoctorReport {
+    pub(crate) fn status(&self) -> Status {
+        worst(self.results.iter().map(|(_, r)| r.status))
+    }
+
+    pub(crate) fn exit_code(&self) -> i32 {
+        self.status().exit_code()
+    }
+
+    pub(crate) fn to_markdown(&self) -> String {
+        let status = self.status();
+        let mut out = format!(
+            "# RPG doctor report\n\nGenerated: {}\nResult: {} (exit {})\n\n",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            status.as_str().to_uppercase(),
+            status.exit_code(),
+        );
+        out.push_str("| Check | Status | Summary |\n|-------|--------|---------|\n");
+        for (name, result) in &self.results {
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                name,
+                result.status.as_str(),
+                result.summary.replace('|', "\\|"),
+            ));
+        }
+        for (name, result) in &self.results {
+            out.push_str(&format!(
+                "\n## {}\n\n{}: {}\n",
+                name,
+                result.status.as_str(),
+                result.summary
+            ));
+            if !result.details.is_empty() {
+                out.push('\n');
+                for line in &result.details {
+                    out.push_str(&format!("- {}\n", line));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Run `checks` against the graph in `project_root`. A missing or unreadable
+/// graph is reported as a failed `graph` check.
+pub(crate) fn run(project_root: &Path, fix: bool, checks: &[Box<dyn Check>]) -> DoctorReport {
+    let loaded = if rpg_core::storage::rpg_exists(project_root) {
+        RpgConfig::load(project_root)
+            .and_then(|config| Ok((config, rpg_core::storage::load(project_root)?)))
+    } else {
+        Err(anyhow::anyhow!(
+            "No RPG found. Run `rpg-encoder build` first."
+        ))
+    };
+    let (config, graph) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return DoctorReport {
+                results: vec![("graph", CheckResult::new(Status::Fail, e.to_string()))],
+            };
+        }
+    };
+
+    let mut ctx = DoctorContext {
+        project_root,
+        config,
+        graph,
+        fix,
+        results: Vec::new(),
+    };
+    for check in checks {
+        let result = check
+            .run(&mut ctx)
+            .unwrap_or_else(|e| CheckResult::new(Status::Fail, format!("check failed: {:#}", e)));
+        ctx.results.push((check.name(), result));
+    }
+    DoctorReport {
+        results: ctx.results,
+    }
+}
+
+/// Source files changed since the graph was built.
+struct StalenessCheck;
+
+impl Check for StalenessCheck {
+    fn name(&self) -> &'static str {
+        "staleness"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        use rpg_encoder::evolution::FileChange;
+
+        let changes =
+            match rpg_encoder::evolution::detect_workdir_changes(ctx.project_root, &ctx.graph) {
+                Ok(changes) => changes,
+                Err(e) => {
+                    return Ok(CheckResult::new(
+                        Status::Skip,
+                        format!("cannot diff against the graph's commit: {:#}", e),
+                    ));
+                }
+            };
+        let changes = rpg_encoder::evolution::filter_rpgignore_changes(ctx.project_root, changes);
+        let changes = rpg_encoder::evolution::filter_source_changes(
+            ctx.project_root,
+            changes,
+            &super::graph_languages(&ctx.graph),
+        );
+        if changes.is_empty() {
+            return Ok(CheckResult::new(
+                Status::Pass,
+                "graph matches the working tree",
+            ));
+        }
+
+        let risk =
+            rpg_encoder::evolution::assess_change_risk(&ctx.graph, &changes, &ctx.config.risk);
+        let mut details: Vec<String> = changes
+            .iter()
+            .take(MAX_LISTED_CHANGES)
+            .map(|change| match change {
+                FileChange::Added(p) => format!("added: {}", p.display()),
+                FileChange::Modified(p) => format!("modified: {}", p.display()),
+                FileChange::Deleted(p) => format!("deleted: {}", p.display()),
+                FileChange::Renamed { from, to } => {
+                    format!("renamed: {} -> {}", from.display(), to.display())
+                }
+            })
+            .collect();
+        if changes.len() > MAX_LISTED_CHANGES {
+            details.push(format!(
+                "... and {} more",
+                changes.len() - MAX_LISTED_CHANGES
+            ));
+        }
+        Ok(CheckResult::new(
+            Status::Warn,
+            format!(
+                "{} source file(s) changed since the graph was built, {} — run `rpg-encoder update`",
+                changes.len(),
+                risk.summary()
+            ),
+        )
+        .with_details(details))
+    }
+}
+
+/// Graph integrity (`rpg-encoder validate`), repaired with `--fix`.
+struct ValidateCheck;
+
+impl Check for ValidateCheck {
+    fn name(&self) -> &'static str {
+        "validate"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        let findings = super::validate_graph(&ctx.graph);
+        if findings.is_empty() {
+            return Ok(CheckResult::new(Status::Pass, "no integrity issues"));
+        }
+        if !ctx.fix {
+            return Ok(CheckResult::new(
+                Status::Fail,
+                format!(
+                    "{} integrity issue(s) — run `rpg-encoder doctor --fix`",
+                    findings.len()
+                ),
+            )
+            .with_details(findings.into_iter().map(|f| f.message).collect()));
+        }
+
+        let repaired = repair_graph(&mut ctx.graph);
+        ctx.graph.refresh_metadata();
+        rpg_core::storage::save_with_config(ctx.project_root, &ctx.graph, &ctx.config.storage)?;
+        let remaining = super::validate_graph(&ctx.graph);
+        let status = if remaining.is_empty() {
+            Status::Pass
+        } else {
+            Status::Fail
+        };
+        Ok(CheckResult::new(
+            status,
+            format!(
+                "repaired {} of {} integrity issue(s), {} remaining",
+                repaired,
+                findings.len(),
+                remaining.len()
+            ),
+        )
+        .with_details(remaining.into_iter().map(|f| f.message).collect()))
+    }
+}
+
+/// Drop dangling edges, hierarchy references to missing entities, and stale
+/// `file_index` entries. Returns the number of references removed.
+fn repair_graph(graph: &mut RPGraph) -> usize {
+    let exists = |graph: &RPGraph, id: &str| {
+        graph.entities.contains_key(id) || graph.find_hierarchy_node_by_id(id).is_some()
+    };
+    let mut repaired = 0;
+
+    let edges = std::mem::take(&mut graph.edges);
+    let before = edges.len();
+    let kept: Vec<_> = edges
+        .into_iter()
+        .filter(|e| exists(graph, &e.source) && exists(graph, &e.target))
+        .collect();
+    repaired += before - kept.len();
+    graph.edges = kept;
+    graph.rebuild_edge_index();
+
+    let entities = &graph.entities;
+    for node in graph.hierarchy.values_mut() {
+        repaired += drop_missing_entities(node, &|id| entities.contains_key(id));
+    }
+    for ids in graph.file_index.values_mut() {
+        let before = ids.len();
+        ids.retain(|id| entities.contains_key(id));
+        repaired += before - ids.len();
+    }
+    graph.file_index.retain(|_, ids| !ids.is_empty());
+    repaired
+}
+
+fn drop_missing_entities(node: &mut HierarchyNode, exists: &dyn Fn(&str) -> bool) -> usize {
+    let before = node.entities.len();
+    node.entities.retain(|id| exists(id));
+    let mut dropped = before - node.entities.len();
+    for child in node.children.values_mut() {
+        dropped += drop_missing_entities(child, exists);
+    }
+    dropped
+}
+
+/// Hierarchy shape: unplaced entities, off-spec semantic paths, and areas
+/// that swallow most of the graph.
+struct HierarchyCheck;
+
+impl Check for HierarchyCheck {
+    fn name(&self) -> &'static str {
+        "hierarchy"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        let graph = &ctx.graph;
+        if graph.hierarchy.is_empty() {
+            return Ok(CheckResult::new(Status::Skip, "no hierarchy"));
+        }
+        let mut details = Vec::new();
+
+        let placed = |e: &&rpg_core::graph::Entity| e.kind != rpg_core::graph::EntityKind::Module;
+        let unplaced = graph
+            .entities
+            .values()
+            .filter(placed)
+            .filter(|e| e.hierarchy_path.is_empty())
+            .count();
+        if unplaced > 0 {
+            details.push(format!("{} entities have no hierarchy path", unplaced));
+        }
+
+        if graph.metadata.semantic_hierarchy {
+            let off_spec = graph
+                .entities
+                .values()
+                .filter(placed)
+                .filter(|e| {
+                    !e.hierarchy_path.is_empty() && e.hierarchy_path.split('/').count() != 3
+                })
+                .count();
+            if off_spec > 0 {
+                details.push(format!(
+                    "{} entities are not on an Area/category/subcategory path",
+                    off_spec
+                ));
+            }
+        }
+
+        let total: usize = graph.hierarchy.values().map(|a| a.entity_count()).sum();
+        if graph.hierarchy.len() >= 3 && total > 0 {
+            for (name, area) in &graph.hierarchy {
+                let share = area.entity_count() as f64 / total as f64;
+                if share > OVERSIZED_AREA_SHARE {
+                    details.push(format!(
+                        "area {} holds {:.0}% of placed entities — consider splitting it",
+                        name,
+                        share * 100.0
+                    ));
+                }
+            }
+        }
+
+        let summary = format!(
+            "{} areas, {} placed entities ({})",
+            graph.hierarchy.len(),
+            total,
+            if graph.metadata.semantic_hierarchy {
+                "semantic"
+            } else {
+                "structural"
+            }
+        );
+        let status = if details.is_empty() {
+            Status::Pass
+        } else {
+            Status::Warn
+        };
+        Ok(CheckResult::new(status, summary).with_details(details))
+    }
+}
+
+/// Coupling summary from `rpg-encoder health`. Informational.
+struct HealthCheck;
+
+impl Check for HealthCheck {
+    fn name(&self) -> &'static str {
+        "health"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        let report =
+            rpg_nav::health::compute_health(&ctx.graph, &rpg_nav::health::HealthConfig::default());
+        let s = &report.summary;
+        let details = report
+            .top_god_objects
+            .iter()
+            .map(|e| format!("god object: {}", e.entity_id))
+            .collect();
+        Ok(CheckResult::new(
+            Status::Pass,
+            format!(
+                "{} entities analyzed, {} dependency edges, {} god objects, {} hubs, {} highly unstable",
+                s.analyzed_entities,
+                s.total_dependency_edges,
+                s.god_object_count,
+                s.hub_count,
+                s.highly_unstable_count
+            ),
+        )
+        .with_details(details))
+    }
+}
+
+/// Saved embedding index versus the graph.
+struct EmbeddingsCheck;
+
+impl Check for EmbeddingsCheck {
+    fn name(&self) -> &'static str {
+        "embeddings"
+    }
+
+    #[cfg(feature = "embeddings")]
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        if !rpg_core::storage::embeddings_meta_file(ctx.project_root).exists() {
+            return Ok(CheckResult::new(Status::Skip, "no embedding index"));
+        }
+        let findings = super::validate_embeddings(ctx.project_root, &ctx.graph)?;
+        if findings.is_empty() {
+            return Ok(CheckResult::new(
+                Status::Pass,
+                "embedding index matches the graph",
+            ));
+        }
+        Ok(CheckResult::new(
+            Status::Warn,
+            format!("{} embedding index issue(s)", findings.len()),
+        )
+        .with_details(findings.into_iter().map(|f| f.message).collect()))
+    }
+
+    #[cfg(not(feature = "embeddings"))]
+    fn run(&self, _ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        Ok(CheckResult::new(
+            Status::Skip,
+            "built without the embeddings feature",
+        ))
+    }
+}
+
+/// Work waiting on the agent: pending routing decisions and lifted entities
+/// whose source changed after they were lifted.
+struct QueueAgeCheck;
+
+impl Check for QueueAgeCheck {
+    fn name(&self) -> &'static str {
+        "queues"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        let now = chrono::Utc::now();
+        let max_age = chrono::Duration::days(MAX_QUEUE_AGE_DAYS);
+        let mut details = Vec::new();
+        let mut overdue = false;
+
+        let pending_path = rpg_core::storage::pending_routing_file(ctx.project_root);
+        let pending = std::fs::read_to_string(&pending_path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+            .and_then(|v| v["entries"].as_array().map(Vec::len))
+            .unwrap_or(0);
+        if pending > 0 {
+            let age = modified_at(&pending_path).map(|t| now - t);
+            overdue |= age.is_some_and(|a| a > max_age);
+            details.push(format!(
+                "pending routing: {} entities, waiting {}",
+                pending,
+                age.map_or_else(|| "unknown".to_string(), format_age)
+            ));
+        }
+
+        // Lifted entities whose file changed after their features were written.
+        let mut relift = 0usize;
+        let mut oldest: Option<chrono::DateTime<chrono::Utc>> = None;
+        for entity in ctx.graph.entities.values() {
+            let Some(lifted_at) = entity.lifted_at else {
+                continue;
+            };
+            if let Some(changed) = modified_at(&ctx.project_root.join(&entity.file))
+                && changed > lifted_at
+            {
+                relift += 1;
+                oldest = Some(oldest.map_or(changed, |o| o.min(changed)));
+            }
+        }
+        if let Some(oldest) = oldest {
+            let age = now - oldest;
+            overdue |= age > max_age;
+            details.push(format!(
+                "relift: {} lifted entities have newer source, oldest change {} ago",
+                relift,
+                format_age(age)
+            ));
+        }
+
+        let status = if overdue { Status::Warn } else { Status::Pass };
+        let summary = if details.is_empty() {
+            "no pending routing or relift work".to_string()
+        } else if overdue {
+            format!(
+                "queued work older than {} days — run get_routing_candidates / get_entities_for_lifting",
+                MAX_QUEUE_AGE_DAYS
+            )
+        } else {
+            format!("queued work is under {} days old", MAX_QUEUE_AGE_DAYS)
+        };
+        Ok(CheckResult::new(status, summary).with_details(details))
+    }
+}
+
+fn modified_at(path: &Path) -> Option<chrono::DateTime<chrono::Utc>> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from)
+}
+
+fn format_age(age: chrono::Duration) -> String {
+    if age.num_days() > 0 {
+        format!("{}d", age.num_days())
+    } else if age.num_hours() > 0 {
+        format!("{}h", age.num_hours())
+    } else {
+        format!("{}m", age.num_minutes().max(0))
+    }
+}
+
+/// Append graph size and the run's status so far to `.rpg/stats-history.jsonl`.
+struct StatsHistoryCheck;
+
+impl Check for StatsHistoryCheck {
+    fn name(&self) -> &'static str {
+        "stats-history"
+    }
+
+    fn run(&self, ctx: &mut DoctorContext<'_>) -> Result<CheckResult> {
+        use std::io::Write;
+
+        let graph = &ctx.graph;
+        let (lifted, total) = graph.lifting_coverage();
+        let record = serde_json::json!({
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "entities": graph.entities.len(),
+            "files": graph.file_index.len(),
+            "lifted": lifted,
+            "liftable": total,
+            "edges": graph.edges.len(),
+            "areas": graph.hierarchy.len(),
+            "import_cycles": graph.metadata.import_cycles,
+            "status": ctx.worst_status().as_str(),
+        });
+        let path = rpg_core::storage::rpg_dir(ctx.project_root).join(STATS_HISTORY_FILE);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        writeln!(file, "{}", record)?;
+        let records = std::fs::read_to_string(&path)?.lines().count();
+        Ok(CheckResult::new(
+            Status::Pass,
+            format!("appended record {} to .rpg/{}", records, STATS_HISTORY_FILE),
+        ))
+    }
+}
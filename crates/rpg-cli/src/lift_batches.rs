@@ -0,0 +1,220 @@
+//! `rpg-encoder lift export-batches` / `lift import-results`: the interactive
+//! lifting protocol run through files, for offline or manual processing.
+//!
+//! Batches are cut by the same queue as `get_entities_for_lifting`, and
+//! results go through the same matching and drift checks as
+//! `submit_lift_results`.
+
+use anyhow::{Context, Result};
+use rpg_core::config::RpgConfig;
+use rpg_encoder::lift_queue;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Batch file name for a 0-based batch index: `batch-001.md`.
+fn batch_file_name(index: usize) -> String {
+    format!("batch-{:03}.md", index + 1)
+}
+
+/// What `export` wrote.
+pub(crate) struct ExportSummary {
+    pub(crate) files: Vec<PathBuf>,
+    pub(crate) entities: usize,
+    pub(crate) auto_lifted: usize,
+}
+
+/// Write every lifting batch for `scope` into `out`, replacing batch files
+/// from an earlier export. Trivial entities are auto-lifted and saved first,
+/// as in the interactive flow.
+pub(crate) fn export(project_root: &Path, scope: &str, out: &Path) -> Result<ExportSummary> {
+    rpg_nav::scope::validate(scope)?;
+    let config = RpgConfig::load(project_root).unwrap_or_default();
+    let mut graph = rpg_core::storage::load(project_root)?;
+    let estimator = rpg_encoder::tokens::from_config(&config.tokens, None, project_root);
+
+    let queue = lift_queue::build_lift_queue(
+        &mut graph,
+        scope,
+        project_root,
+        &config.encoding,
+        &HashSet::new(),
+        estimator.as_ref(),
+    )?;
+    if queue.auto_lifted > 0 {
+        graph.refresh_metadata();
+        rpg_core::storage::save(project_root, &graph)?;
+    }
+
+    std::fs::create_dir_all(out).with_context(|| format!("failed to create {}", out.display()))?;
+    for entry in std::fs::read_dir(out)?.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with("batch-") && name.ends_with(".md") {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+
+    let project_name = project_root
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let total = queue.batch_ranges.len();
+    let mut files = Vec::with_capacity(total);
+    for index in 0..total {
+        let Some(batch) = queue.batch(index) else {
+            break;
+        };
+        let mut output = lift_queue::format_batch_header(&graph, &queue, index, batch.len());
+        if index == 0 {
+            output.push_str(&lift_queue::format_batch_preamble(
+                &graph,
+                &queue,
+                &project_name,
+            ));
+        }
+        output.push_str(&lift_queue::format_batch_code(&graph, batch));
+        output.push_str(&format!(
+            "Submit: write a JSON object with keys exactly as shown in the ### headers above (e.g., `{{\"src/lib.rs:MyStruct::method\": [\"feature1\", ...]}}`) to `{}`, then run `rpg-encoder lift import-results` on the result files.\n",
+            batch_file_name(index).replace(".md", ".json"),
+        ));
+
+        let path = out.join(batch_file_name(index));
+        std::fs::write(&path, output)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        files.push(path);
+    }
+
+    Ok(ExportSummary {
+        files,
+        entities: queue.raw_entities.len(),
+        auto_lifted: queue.auto_lifted,
+    })
+}
+
+/// Apply feature JSON files (`{"entity_id": ["feature", ...]}`) and return a
+/// combined report. Every file is parsed before anything is applied, so a
+/// malformed file leaves the graph untouched. When the same key appears in
+/// several files, the last file wins.
+pub(crate) fn import(project_root: &Path, files: &[PathBuf]) -> Result<String> {
+    let config = RpgConfig::load(project_root).unwrap_or_default();
+    let mut graph = rpg_core::storage::load(project_root)?;
+
+    let mut parsed = Vec::with_capacity(files.len());
+    let mut normalization = rpg_encoder::semantic_lifting::NormalizationReport::default();
+    for file in files {
+        let content = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        let mut features: HashMap<String, Vec<String>> = serde_json::from_str(&content)
+            .with_context(|| format!("invalid features JSON in {}", file.display()))?;
+        let file_report = rpg_encoder::semantic_lifting::normalize_features(
+            &mut features,
+            &config.encoding.feature_normalization,
+        );
+        normalization.split.extend(file_report.split);
+        normalization.truncated.extend(file_report.truncated);
+        normalization.dropped.extend(file_report.dropped);
+        parsed.push((file, features));
+    }
+
+    let mut file_lines = Vec::new();
+    let mut updated = 0usize;
+    let mut unmatched = Vec::new();
+    let mut drift_reports = Vec::new();
+    let mut needs_routing: Vec<String> = Vec::new();
+    let mut resolved: HashMap<String, Vec<String>> = HashMap::new();
+    let mut copied = Vec::new();
+    for (file, features) in &parsed {
+        let applied = lift_queue::apply_features(&mut graph, features, &config.encoding);
+        file_lines.push(format!(
+            "  {}: {} updated, {} unmatched",
+            file.display(),
+            applied.updated,
+            applied.unmatched.len(),
+        ));
+        updated += applied.updated;
+        unmatched.extend(applied.unmatched);
+        drift_reports.extend(applied.drift_reports);
+        needs_routing.extend(applied.drifted);
+        needs_routing.extend(applied.borderline.into_iter().map(|(id, _)| id));
+        needs_routing.extend(applied.newly_lifted);
+        resolved.extend(applied.resolved);
+        copied.extend(applied.copied);
+    }
+
+    // No agent to judge routing offline: place entities the way
+    // `finalize_lifting` does for anything left unrouted.
+    let mut routed = 0usize;
+    if graph.metadata.semantic_hierarchy && !needs_routing.is_empty() {
+        needs_routing.sort();
+        needs_routing.dedup();
+        for id in &needs_routing {
+            if rpg_encoder::evolution::route_new_entity(&mut graph, id).is_some() {
+                routed += 1;
+            }
+        }
+        graph.aggregate_hierarchy_features();
+        graph.assign_hierarchy_ids();
+        graph.materialize_containment_edges();
+    }
+
+    graph.refresh_metadata();
+    rpg_core::storage::save(project_root, &graph)?;
+    rpg_nav::vocab::rebuild(project_root, &graph)?;
+
+    let (lifted, total) = graph.lifting_coverage();
+    let mut report = format!(
+        "Imported {} result file(s): {} entities updated, {} unmatched key(s)\n",
+        files.len(),
+        updated,
+        unmatched.len(),
+    );
+    for line in &file_lines {
+        report.push_str(line);
+        report.push('\n');
+    }
+    if !unmatched.is_empty() {
+        unmatched.sort();
+        report.push_str("\nUnmatched keys (must match the ### headers of the exported batches):\n");
+        for key in &unmatched {
+            report.push_str(&format!("  {}\n", key));
+        }
+    }
+    if !drift_reports.is_empty() {
+        drift_reports.sort();
+        report.push_str(&format!(
+            "\nDrift detection ({} entities re-lifted):\n",
+            drift_reports.len()
+        ));
+        for line in &drift_reports {
+            report.push_str(line);
+            report.push('\n');
+        }
+    }
+    if routed > 0 {
+        report.push_str(&format!(
+            "\nRouted: {} entities moved in the hierarchy\n",
+            routed
+        ));
+    }
+
+    let mut ids: Vec<&String> = resolved.keys().collect();
+    ids.sort();
+    let warnings: Vec<_> = ids
+        .into_iter()
+        .flat_map(|id| rpg_encoder::critic::critique(id, &resolved[id]))
+        .collect();
+    report.push_str(&rpg_encoder::critic::format_warnings(&warnings));
+    report.push_str(&rpg_encoder::semantic_lifting::format_normalization_report(
+        &normalization,
+    ));
+    report.push_str(&rpg_encoder::critic::format_copied_feature_sets(&copied));
+
+    let pct = if total > 0 {
+        lifted as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    };
+    report.push_str(&format!("\ncoverage: {}/{} ({:.0}%)\n", lifted, total, pct));
+    Ok(report)
+}
@@ -3,8 +3,12 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use rpg_core::config::RpgConfig;
+use rpg_nav::export::sarif::{self, Level, Rule};
 use std::path::{Path, PathBuf};
 
+mod doctor;
+mod lift_batches;
+
 #[derive(Parser)]
 #[command(name = "rpg-encoder", about = "Repository Planning Graph encoder")]
 struct Cli {
@@ -39,6 +43,11 @@ enum Commands {
         /// Do not preserve lifted features from the previous graph
         #[arg(long)]
         no_preserve: bool,
+
+        /// Read sources from this git revision instead of the worktree (works
+        /// in a bare repository)
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
     },
 
     /// Incrementally update the RPG from git changes
@@ -57,7 +66,8 @@ enum Commands {
         #[arg(short, long, default_value = "auto")]
         mode: String,
 
-        /// Restrict search to a hierarchy scope
+        /// Restrict search to a hierarchy scope or scope expression
+        /// (e.g., "area:Security AND kind:class")
         #[arg(long)]
         scope: Option<String>,
 
@@ -68,12 +78,104 @@ enum Commands {
         /// Glob pattern to filter entities by file path (e.g., "src/**/*.rs")
         #[arg(long)]
         file_pattern: Option<String>,
+
+        /// Only search entities touched by `git diff <REV>...HEAD` (changed hunks)
+        #[arg(long, value_name = "REV")]
+        changed_since: Option<String>,
+
+        /// Result order: relevance, churn (most frequently changed first; run
+        /// `rpg-encoder churn` first), entry_distance (closest to an entry
+        /// point first)
+        #[arg(long, default_value = "relevance")]
+        sort: String,
+
+        /// Include entities in test fixture files (`encoding.fixture_globs`)
+        #[arg(long)]
+        include_fixtures: bool,
+
+        /// Leave out test functions and blocks, and entities in the test layer
+        #[arg(long)]
+        exclude_tests: bool,
+        /// List a file's matching members as separate results instead of
+        /// under its matching Module (features mode groups by default)
+        #[arg(long)]
+        no_group_by_file: bool,
+
+        /// Treat this git revision as HEAD: `--changed-since` diffs up to it
+        /// and the graph is checked against it (works in a bare repository)
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Answer "where is X implemented" in one go: the best matches with
+    /// their features, a source excerpt, and direct callers and callees
+    Explain {
+        /// What to look for (e.g. "retry logic")
+        query: String,
+
+        /// Number of results to explain (at most 10)
+        #[arg(long, default_value = "3")]
+        top: usize,
+
+        /// Restrict to a hierarchy scope or scope expression
+        #[arg(long)]
+        scope: Option<String>,
+
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
     },
 
     /// Fetch detailed info about a specific entity
     Fetch {
         /// Entity ID
-        entity_id: String,
+        #[arg(required_unless_present = "file")]
+        entity_id: Option<String>,
+
+        /// Fetch every entity in this file (relative to the project root):
+        /// its Module entity first, then the rest in line order
+        #[arg(long, conflicts_with = "entity_id")]
+        file: Option<String>,
+
+        /// Read source from this git revision instead of the worktree (works
+        /// in a bare repository)
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
+    },
+
+    /// Outline of a file's entities (classes with their methods, functions),
+    /// ordered by line
+    Symbols {
+        /// File path relative to the project root
+        file: String,
+
+        /// Print LSP DocumentSymbol-style JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print one source file's entities, imports, calls, and inheritance as
+    /// JSON. Needs no graph and never touches .rpg/ (for editor integrations)
+    Analyze {
+        /// Source file, or `-` to read from stdin
+        file: String,
+
+        /// Language (detected from the file extension if not specified)
+        #[arg(short, long)]
+        lang: Option<String>,
+
+        /// Paradigm to apply, e.g. `react` (repeatable)
+        #[arg(long = "paradigm")]
+        paradigms: Vec<String>,
+
+        /// Path to analyze stdin as, for entity IDs, language detection, and
+        /// paradigm file rules
+        #[arg(long)]
+        stdin_filename: Option<String>,
     },
 
     /// Explore dependency graph from an entity
@@ -90,23 +192,103 @@ enum Commands {
         depth: usize,
     },
 
+    /// Save, list, and run named explore/impact queries (.rpg/queries.toml)
+    Query {
+        #[command(subcommand)]
+        action: QueryAction,
+    },
+
+    /// Add or remove user tags on entities (e.g., deprecated, hot-path)
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Manage the semantic hierarchy (area charters)
+    Hierarchy {
+        #[command(subcommand)]
+        action: HierarchyAction,
+    },
+
+    /// Import key/value annotations (e.g., last reviewer from PR metadata)
+    /// onto entities by ID or file line range
+    Annotate {
+        /// Annotation document: {"source": "github", "annotations":
+        /// [{"file": "src/a.py", "lines": [10, 24], "values": {"last_reviewer": "alice"}}]}
+        #[arg(long, value_name = "FILE")]
+        from_json: PathBuf,
+    },
+
     /// Show RPG statistics
-    Info,
+    #[command(visible_alias = "status")]
+    Info {
+        /// Also show dependency resolution per language and the top
+        /// unresolved import prefixes
+        #[arg(short, long)]
+        verbose: bool,
+        /// Also show feature sources and ages per area, and a re-lift plan
+        #[arg(long)]
+        sources: bool,
+    },
 
     /// Export graph as DOT (Graphviz) or Mermaid flowchart
     Export {
-        /// Output format: dot, mermaid
+        /// Output format: dot, mermaid, features-jsonl, features-parquet
+        /// (needs the `arrow` feature)
         #[arg(short, long, default_value = "dot")]
         format: String,
+
+        /// Only export entities in scope: hierarchy path, file glob, or scope
+        /// expression (e.g., "area:Security AND NOT glob:**/tests/**")
+        #[arg(long)]
+        scope: Option<String>,
+
+        /// Write to this file instead of stdout (required for features-parquet)
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        /// Only export this entity's neighborhood (dot format): the entities
+        /// `explore` reaches from it, colored by hierarchy area
+        #[arg(long, value_name = "ID")]
+        entity: Option<String>,
+
+        /// Neighborhood depth (with --entity)
+        #[arg(long, default_value = "2")]
+        depth: usize,
+
+        /// Neighborhood direction (with --entity): up, down, both
+        #[arg(long, default_value = "both")]
+        direction: String,
+
+        /// Only follow edges of this kind (with --entity), e.g. invokes, imports
+        #[arg(long)]
+        edge_filter: Option<String>,
     },
 
     /// Show what would change without updating (dry-run)
     Diff {
-        /// Base commit to diff from (defaults to RPG's base_commit)
+        /// Commit to diff from (defaults to `[staleness] compare_to`, itself
+        /// defaulting to the RPG's base_commit)
         #[arg(long)]
         since: Option<String>,
     },
 
+    /// Public functions, classes, and methods added, removed, or with changed
+    /// signatures between two git revisions, grouped by area and file
+    ApiDiff {
+        /// Older revision (tag, branch, or commit)
+        #[arg(long)]
+        from: String,
+
+        /// Newer revision
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+
+        /// Output format: markdown, json
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+    },
+
     /// Build a paper-style reconstruction execution plan (topological + batches)
     ReconstructPlan {
         /// Maximum number of entities per execution batch
@@ -123,7 +305,45 @@ enum Commands {
     },
 
     /// Validate graph integrity (check for orphans, dangling edges, etc.)
-    Validate,
+    Validate {
+        /// Output format: text, sarif
+        #[arg(short, long, default_value = "text")]
+        format: String,
+        /// Exit with an error when files import each other in a cycle
+        #[arg(long)]
+        fail_on_import_cycles: bool,
+    },
+
+    /// Download a graph built elsewhere (e.g. a CI artifact), check that it
+    /// belongs to this repository, and install it as .rpg/graph.json
+    FetchGraph {
+        /// URL (http/https) or local path of the graph.json artifact
+        url: String,
+    },
+
+    /// Score entities by how often their lines changed in git history
+    /// (commits and changed lines since SINCE); shown by `health` and
+    /// `search --sort churn`
+    Churn {
+        /// History window: 90d, 12w, 6m, 1y, or a date (2024-01-31)
+        #[arg(long, default_value = "6m")]
+        since: String,
+    },
+
+    /// Analyze code health (coupling, instability, god objects, hubs)
+    Health {
+        /// Output format: text, json, sarif
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Run staleness, integrity, hierarchy, health, embedding, and queue checks
+    /// and write .rpg/doctor-report.md. Exit code: 0 ok, 1 warnings, 2 failures
+    Doctor {
+        /// Repair integrity issues (dangling edges, orphaned references)
+        #[arg(long)]
+        fix: bool,
+    },
 
     /// Install or uninstall the git pre-commit hook for auto-sync
     Hook {
@@ -131,9 +351,13 @@ enum Commands {
         action: String,
     },
 
-    /// Autonomous LLM-driven semantic lifting (fire-and-forget)
-    #[cfg(feature = "lift")]
+    /// Autonomous LLM-driven semantic lifting (fire-and-forget), or export
+    /// batches for offline processing and import the results
+    #[command(args_conflicts_with_subcommands = true)]
     Lift {
+        #[command(subcommand)]
+        action: Option<LiftAction>,
+
         /// LLM provider: "anthropic" or "openai"
         #[arg(long, default_value = "anthropic")]
         provider: String,
@@ -154,7 +378,7 @@ enum Commands {
         #[arg(long)]
         dry_run: bool,
 
-        /// Scope: file glob, hierarchy path, or "*" for all unlifted
+        /// Scope: file glob, hierarchy path, scope expression, or "*" for all unlifted
         #[arg(long, default_value = "*")]
         scope: String,
     },
@@ -164,6 +388,99 @@ enum Commands {
     Serve,
 }
 
+#[derive(Subcommand)]
+enum LiftAction {
+    /// Write each lifting batch as a markdown file, as get_entities_for_lifting
+    /// would return it
+    ExportBatches {
+        /// Scope: file glob, hierarchy path, scope expression, or "*" for all unlifted
+        #[arg(long, default_value = "*")]
+        scope: String,
+
+        /// Output directory (batch-001.md, batch-002.md, ...)
+        #[arg(long, default_value = "lift-batches")]
+        out: PathBuf,
+    },
+
+    /// Apply feature JSON files ({"entity_id": ["feature", ...]}) and print a
+    /// combined report
+    ImportResults {
+        /// Result files
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueryAction {
+    /// Save the last executed explore/impact query under a name
+    Save {
+        /// Query name (letters, digits, '-', '_', '.')
+        name: String,
+
+        /// Overwrite an existing query with the same name
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List saved queries
+    List,
+
+    /// Run a saved query against the current graph
+    Run {
+        /// Query name
+        name: String,
+    },
+
+    /// Delete a saved query
+    Delete {
+        /// Query name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum HierarchyAction {
+    /// Show, set, or clear the charter of a hierarchy node: a human-written
+    /// statement of what the area is for, kept across hierarchy rebuilds
+    Charter {
+        /// Hierarchy path or node ID (e.g., "Storage" or "h:Storage/persist graph")
+        path: String,
+
+        /// Charter text; omit to show the current charter
+        text: Option<String>,
+
+        /// Remove the charter
+        #[arg(long, conflicts_with = "text")]
+        clear: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TagAction {
+    /// Add tags to the selected entities
+    Add(TagArgs),
+
+    /// Remove tags from the selected entities
+    Remove(TagArgs),
+}
+
+#[derive(clap::Args)]
+struct TagArgs {
+    /// Tags (lowercased; no whitespace, quotes, parentheses, or commas)
+    #[arg(required = true)]
+    tags: Vec<String>,
+
+    /// Entity ID to select (repeatable)
+    #[arg(long = "id")]
+    ids: Vec<String>,
+
+    /// Select entities by hierarchy path, file glob, or scope expression
+    /// (e.g., "area:Legacy AND kind:function")
+    #[arg(long)]
+    scope: Option<String>,
+}
+
 fn get_project_root(cli: &Cli) -> Result<PathBuf> {
     match &cli.project {
         Some(p) => Ok(p.clone()),
@@ -189,7 +506,16 @@ fn main() -> Result<()> {
             exclude,
             force,
             no_preserve,
-        } => cmd_build(&project_root, lang, include, exclude, force, no_preserve),
+            rev,
+        } => cmd_build(
+            &project_root,
+            lang,
+            include,
+            exclude,
+            force,
+            no_preserve,
+            rev.as_deref(),
+        ),
         Commands::Update { since } => cmd_update(&project_root, since),
         Commands::Search {
             query,
@@ -197,32 +523,117 @@ fn main() -> Result<()> {
             scope,
             line_range,
             file_pattern,
+            changed_since,
+            sort,
+            include_fixtures,
+            exclude_tests,
+            no_group_by_file,
+            rev,
+            format,
         } => cmd_search(
             &project_root,
             &query,
             &mode,
-            scope.as_deref(),
-            line_range.as_deref(),
-            file_pattern.as_deref(),
+            &SearchFilters {
+                scope: scope.as_deref(),
+                line_range: line_range.as_deref(),
+                file_pattern: file_pattern.as_deref(),
+                changed_since: changed_since.as_deref(),
+                sort: &sort,
+                include_fixtures,
+                exclude_tests,
+                group_by_file: !no_group_by_file,
+                rev: rev.as_deref(),
+            },
+            &format,
+        ),
+        Commands::Explain {
+            query,
+            top,
+            scope,
+            json,
+        } => cmd_explain(&project_root, &query, top, scope.as_deref(), json),
+        Commands::Fetch {
+            entity_id,
+            file,
+            rev,
+        } => match file {
+            Some(file) => cmd_fetch_file(&project_root, &file, rev.as_deref()),
+            None => cmd_fetch(
+                &project_root,
+                entity_id.as_deref().unwrap_or_default(),
+                rev.as_deref(),
+            ),
+        },
+        Commands::Symbols { file, json } => cmd_symbols(&project_root, &file, json),
+        Commands::Analyze {
+            file,
+            lang,
+            paradigms,
+            stdin_filename,
+        } => cmd_analyze(
+            &project_root,
+            &file,
+            lang.as_deref(),
+            &paradigms,
+            stdin_filename.as_deref(),
         ),
-        Commands::Fetch { entity_id } => cmd_fetch(&project_root, &entity_id),
         Commands::Explore {
             entity_id,
             direction,
             depth,
         } => cmd_explore(&project_root, &entity_id, &direction, depth),
-        Commands::Info => cmd_info(&project_root),
-        Commands::Export { format } => cmd_export(&project_root, &format),
+        Commands::Query { action } => cmd_query(&project_root, action),
+        Commands::Tag { action } => cmd_tag(&project_root, action),
+        Commands::Hierarchy { action } => cmd_hierarchy(&project_root, action),
+        Commands::Annotate { from_json } => cmd_annotate(&project_root, &from_json),
+        Commands::Info { verbose, sources } => cmd_info(&project_root, verbose, sources),
+        Commands::Export {
+            format,
+            scope,
+            out,
+            entity,
+            depth,
+            direction,
+            edge_filter,
+        } => {
+            let neighborhood = entity.map(|entity| Neighborhood {
+                entity,
+                depth,
+                direction,
+                edge_filter,
+            });
+            cmd_export(
+                &project_root,
+                &format,
+                scope.as_deref(),
+                out.as_deref(),
+                neighborhood.as_ref(),
+            )
+        }
         Commands::Diff { since } => cmd_diff(&project_root, since),
+        Commands::ApiDiff { from, to, format } => cmd_api_diff(&project_root, &from, &to, &format),
         Commands::ReconstructPlan {
             max_batch_size,
             format,
             include_modules,
         } => cmd_reconstruct_plan(&project_root, max_batch_size, &format, include_modules),
-        Commands::Validate => cmd_validate(&project_root),
+        Commands::Validate {
+            format,
+            fail_on_import_cycles,
+        } => cmd_validate(&project_root, &format, fail_on_import_cycles),
+        Commands::FetchGraph { url } => cmd_fetch_graph(&project_root, &url),
+        Commands::Churn { since } => cmd_churn(&project_root, &since),
+        Commands::Health { format } => cmd_health(&project_root, &format),
+        Commands::Doctor { fix } => cmd_doctor(&project_root, fix),
         Commands::Hook { action } => cmd_hook(&project_root, &action),
+        Commands::Lift {
+            action: Some(action),
+            ..
+        } => cmd_lift_files(&project_root, action),
         #[cfg(feature = "lift")]
         Commands::Lift {
+            action: None,
             provider,
             model,
             api_key,
@@ -238,6 +649,10 @@ fn main() -> Result<()> {
             dry_run,
             &scope,
         ),
+        #[cfg(not(feature = "lift"))]
+        Commands::Lift { action: None, .. } => anyhow::bail!(
+            "Autonomous lifting needs the `lift` feature. Use `lift export-batches` and `lift import-results` instead."
+        ),
         Commands::Serve => {
             eprintln!("MCP server not yet implemented. Use rpg-mcp binary instead.");
             Ok(())
@@ -245,40 +660,63 @@ fn main() -> Result<()> {
     }
 }
 
-/// Collect source files matching language and glob filters.
+/// Compiled `--include`/`--exclude` patterns.
+struct PathGlobs {
+    include: Option<globset::GlobSet>,
+    exclude: Option<globset::GlobSet>,
+}
+
+impl PathGlobs {
+    /// Compile both lists, failing on the first malformed pattern.
+    fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: glob_set(include, "include")?,
+            exclude: glob_set(exclude, "exclude")?,
+        })
+    }
+
+    /// Whether `rel_path` passes both filters.
+    fn admits(&self, rel_path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|inc| inc.is_match(rel_path))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|exc| exc.is_match(rel_path))
+    }
+}
+
+/// Patterns as one set that matches if any does, or `None` when empty.
+fn glob_set(patterns: &[String], flag: &str) -> Result<Option<globset::GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for p in patterns {
+        builder.add(
+            rpg_nav::scope::compile_glob(p).map_err(|e| anyhow::anyhow!("--{}: {}", flag, e))?,
+        );
+    }
+    let set = builder
+        .build()
+        .with_context(|| format!("invalid --{} patterns", flag))?;
+    Ok(Some(set))
+}
+
+/// Collect source files matching language and glob filters, plus the number
+/// skipped as vendored, generated, or documentation per `.gitattributes`.
 fn collect_source_files(
     project_root: &Path,
     languages: &[rpg_parser::languages::Language],
-    include: &[String],
-    exclude: &[String],
-) -> Vec<(std::path::PathBuf, String)> {
+    globs: &PathGlobs,
+    linguist: &rpg_encoder::gitattributes::LinguistAttributes,
+    encoding: &rpg_core::config::EncodingConfig,
+) -> (Vec<(std::path::PathBuf, String)>, usize) {
     use indicatif::{ProgressBar, ProgressStyle};
     use rpg_parser::languages::Language;
 
-    let include_set = if include.is_empty() {
-        None
-    } else {
-        let mut builder = globset::GlobSetBuilder::new();
-        for p in include {
-            builder.add(globset::Glob::new(p).expect("invalid --include glob"));
-        }
-        Some(builder.build().expect("invalid --include glob set"))
-    };
-    let exclude_set = if exclude.is_empty() {
-        None
-    } else {
-        let mut builder = globset::GlobSetBuilder::new();
-        for p in exclude {
-            builder.add(globset::Glob::new(p).expect("invalid --exclude glob"));
-        }
-        Some(builder.build().expect("invalid --exclude glob set"))
-    };
-
-    let walker = ignore::WalkBuilder::new(project_root)
-        .hidden(true)
-        .git_ignore(true)
-        .add_custom_ignore_filename(".rpgignore")
-        .build();
+    let walker = rpg_encoder::nested_repos::source_walker(project_root, encoding);
 
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(
@@ -289,26 +727,23 @@ fn collect_source_files(
     spinner.set_message("Scanning files...");
 
     let mut files_to_parse = Vec::new();
+    let mut linguist_skipped = 0;
 
     for entry in walker.flatten() {
         let path = entry.path();
         if !path.is_file() {
             continue;
         }
-        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        let file_lang = Language::from_extension(ext);
+        let file_lang = Language::from_path(path);
         if !file_lang.is_some_and(|l| languages.contains(&l)) {
             continue;
         }
         let rel_path_for_glob = path.strip_prefix(project_root).unwrap_or(path);
-        if let Some(ref inc) = include_set
-            && !inc.is_match(rel_path_for_glob)
-        {
+        if !globs.admits(rel_path_for_glob) {
             continue;
         }
-        if let Some(ref exc) = exclude_set
-            && exc.is_match(rel_path_for_glob)
-        {
+        if linguist.excludes(rel_path_for_glob) {
+            linguist_skipped += 1;
             continue;
         }
 
@@ -323,11 +758,108 @@ fn collect_source_files(
         }
     }
     spinner.finish_and_clear();
-    files_to_parse
+    (files_to_parse, linguist_skipped)
+}
+
+/// Files in a git revision's tree that a worktree walk would visit: hidden
+/// paths and `.rpgignore` patterns (read from the revision) are skipped.
+fn revision_candidates(files: &rpg_encoder::git_files::GitFileProvider) -> Result<Vec<PathBuf>> {
+    use rpg_core::files::FileProvider;
+
+    let mut builder = ignore::gitignore::GitignoreBuilder::new("");
+    if let Ok(text) = files.read_to_string(Path::new(".rpgignore")) {
+        for line in text.lines() {
+            let _ = builder.add_line(None, line);
+        }
+    }
+    let rpgignore = builder
+        .build()
+        .unwrap_or_else(|_| ignore::gitignore::Gitignore::empty());
+
+    Ok(files
+        .files()?
+        .into_iter()
+        .filter(|path| {
+            !path
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        })
+        .filter(|path| {
+            !rpgignore
+                .matched_path_or_any_parents(path, false)
+                .is_ignore()
+        })
+        .collect())
+}
+
+/// Language of a revision file: from its extension, or for extensionless
+/// files from the shebang in its blob.
+fn revision_language(
+    files: &rpg_encoder::git_files::GitFileProvider,
+    path: &Path,
+) -> Option<rpg_parser::languages::Language> {
+    use rpg_core::files::FileProvider;
+    use rpg_parser::languages::Language;
+
+    match path.extension() {
+        Some(ext) => ext.to_str().and_then(Language::from_extension),
+        None => Language::from_source(path, &files.read_to_string(path).ok()?),
+    }
+}
+
+/// Languages in a git revision, ordered by file count, descending.
+fn detect_revision_languages(
+    files: &rpg_encoder::git_files::GitFileProvider,
+) -> Result<Vec<rpg_parser::languages::Language>> {
+    let mut counts: Vec<(rpg_parser::languages::Language, usize)> = Vec::new();
+    for path in revision_candidates(files)? {
+        let Some(language) = revision_language(files, &path) else {
+            continue;
+        };
+        match counts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((language, 1)),
+        }
+    }
+    counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+    Ok(counts.into_iter().map(|(language, _)| language).collect())
+}
+
+/// [`collect_source_files`] for the tree of a git revision.
+fn collect_revision_files(
+    files: &rpg_encoder::git_files::GitFileProvider,
+    languages: &[rpg_parser::languages::Language],
+    globs: &PathGlobs,
+    linguist: &rpg_encoder::gitattributes::LinguistAttributes,
+) -> Result<(Vec<(PathBuf, String)>, usize)> {
+    use rpg_core::files::FileProvider;
+    let mut files_to_parse = Vec::new();
+    let mut linguist_skipped = 0;
+
+    for path in revision_candidates(files)? {
+        if !revision_language(files, &path).is_some_and(|l| languages.contains(&l)) {
+            continue;
+        }
+        if !globs.admits(&path) {
+            continue;
+        }
+        if linguist.excludes(&path) {
+            linguist_skipped += 1;
+            continue;
+        }
+        if let Ok(source) = files.read_to_string(&path) {
+            files_to_parse.push((path, source));
+        }
+    }
+    Ok((files_to_parse, linguist_skipped))
 }
 
 /// Structural-only build: insert entities, create Module nodes, file-path hierarchy.
-fn build_structural(graph: &mut rpg_core::graph::RPGraph, entities: Vec<rpg_core::graph::Entity>) {
+fn build_structural(
+    graph: &mut rpg_core::graph::RPGraph,
+    entities: Vec<rpg_core::graph::Entity>,
+    project_root: &Path,
+) {
     for entity in entities {
         graph.insert_entity(entity);
     }
@@ -336,7 +868,8 @@ fn build_structural(graph: &mut rpg_core::graph::RPGraph, entities: Vec<rpg_core
     graph.create_module_entities();
 
     eprintln!("  Building file-path hierarchy (structural)...");
-    graph.build_file_path_hierarchy();
+    let packages = rpg_core::structure::detect_packages(project_root);
+    graph.build_file_path_hierarchy_with_packages(&packages);
 }
 
 fn cmd_build(
@@ -346,10 +879,20 @@ fn cmd_build(
     exclude: Vec<String>,
     force: bool,
     no_preserve: bool,
+    rev: Option<&str>,
 ) -> Result<()> {
     use indicatif::{ProgressBar, ProgressStyle};
+    use rpg_core::files::FileProvider;
     use rpg_parser::languages::Language;
 
+    let globs = PathGlobs::new(&include, &exclude)?;
+    let revision = rev
+        .map(|rev| rpg_encoder::git_files::GitFileProvider::open(project_root, rev))
+        .transpose()?;
+    if let Some(ref revision) = revision {
+        eprintln!("Reading sources from revision {}", revision.commit());
+    }
+
     // Check if RPG already exists
     if rpg_core::storage::rpg_exists(project_root) && !force {
         anyhow::bail!(
@@ -405,7 +948,10 @@ fn cmd_build(
             .ok_or_else(|| anyhow::anyhow!("unsupported language: {}", l))?;
         vec![lang]
     } else {
-        let detected = Language::detect_all(project_root);
+        let detected = match revision {
+            Some(ref revision) => detect_revision_languages(revision)?,
+            None => Language::detect_all(project_root),
+        };
         if detected.is_empty() {
             let extensions = ["py", "rs", "ts", "js", "go", "java", "c", "cpp", "h"];
             anyhow::bail!(
@@ -428,7 +974,7 @@ fn cmd_build(
     graph.metadata.languages = languages.iter().map(|l| l.name().to_string()).collect();
 
     // Load TOML paradigm definitions + compile tree-sitter queries
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
         anyhow::anyhow!(
             "paradigm definition errors: {}",
             errs.iter()
@@ -449,7 +995,44 @@ fn cmd_build(
     }
 
     // Collect and parse source files
-    let files_to_parse = collect_source_files(project_root, &languages, &include, &exclude);
+    let (files_to_parse, linguist_skipped) = match revision {
+        Some(ref revision) => {
+            let linguist = match revision.read_to_string(Path::new(".gitattributes")) {
+                Ok(text) if config.encoding.honor_gitattributes => {
+                    rpg_encoder::gitattributes::LinguistAttributes::parse(&text)
+                }
+                _ => rpg_encoder::gitattributes::LinguistAttributes::default(),
+            };
+            collect_revision_files(revision, &languages, &globs, &linguist)?
+        }
+        None => {
+            let linguist = rpg_encoder::gitattributes::LinguistAttributes::load(
+                project_root,
+                &config.encoding,
+            );
+            graph.metadata.nested_repos =
+                rpg_encoder::nested_repos::find_nested_repos(project_root, &config.encoding);
+            let skipped = graph
+                .metadata
+                .nested_repos
+                .iter()
+                .filter(|r| !r.indexed)
+                .count();
+            if skipped > 0 {
+                eprintln!(
+                    "  Skipped {} nested repositories (set encoding.include_submodules to index them)",
+                    skipped
+                );
+            }
+            collect_source_files(
+                project_root,
+                &languages,
+                &globs,
+                &linguist,
+                &config.encoding,
+            )
+        }
+    };
     let file_count = files_to_parse.len();
 
     let pb = ProgressBar::new(file_count as u64);
@@ -461,11 +1044,8 @@ fn cmd_build(
     );
 
     // Parse all files in parallel with paradigm pipeline (classify/query/features)
-    let all_raw_entities = if active_defs.is_empty() {
-        rpg_parser::parse_files_parallel(files_to_parse)
-    } else {
-        rpg_parser::parse_files_with_paradigms(files_to_parse, &active_defs, &qcache)
-    };
+    let (all_raw_entities, parse_diagnostics) =
+        rpg_parser::parse_files_with_diagnostics(files_to_parse, &active_defs, &qcache);
     pb.finish_and_clear();
 
     eprintln!(
@@ -473,6 +1053,16 @@ fn cmd_build(
         all_raw_entities.len(),
         file_count
     );
+    if let Some(report) = rpg_parser::diagnostics::format_report(&parse_diagnostics) {
+        let mut lines = report.lines();
+        if let Some(summary) = lines.next() {
+            eprintln!("  Warning: {}", summary);
+        }
+        for line in lines {
+            eprintln!("  {}", line);
+        }
+    }
+    graph.metadata.parse_errors = rpg_parser::diagnostics::error_counts(&parse_diagnostics);
 
     // Convert to graph entities
     let entities: Vec<rpg_core::graph::Entity> = all_raw_entities
@@ -481,9 +1071,12 @@ fn cmd_build(
         .collect();
 
     // Build structural graph
-    build_structural(&mut graph, entities);
+    build_structural(&mut graph, entities, project_root);
+    rpg_encoder::ownership::assign_owners(&mut graph, project_root);
+    rpg_encoder::layers::assign_layers(&mut graph, project_root, None);
 
     // Hierarchy node enrichment
+    graph.metadata.max_node_features = config.encoding.max_node_features;
     graph.assign_hierarchy_ids();
     graph.aggregate_hierarchy_features();
     graph.materialize_containment_edges();
@@ -494,19 +1087,37 @@ fn cmd_build(
         active_defs,
         qcache: &qcache,
     };
-    rpg_encoder::grounding::populate_entity_deps(
+    let worktree = rpg_core::files::WorktreeFiles::new(project_root);
+    let files: &dyn FileProvider = match revision {
+        Some(ref revision) => revision,
+        None => &worktree,
+    };
+    rpg_encoder::grounding::populate_entity_deps_from(
         &mut graph,
-        project_root,
+        files,
         config.encoding.broadcast_imports,
         None,
         Some(&paradigm_ctx),
     );
     rpg_encoder::grounding::ground_hierarchy(&mut graph);
     rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    rpg_nav::orient::assign_entry_distances(&mut graph);
+    let import_cycles = rpg_encoder::grounding::record_import_cycles(
+        &mut graph,
+        config.encoding.ignore_same_directory_import_cycles,
+    );
+    graph.set_liftable_kinds(&config.encoding.liftable_kinds);
 
     // Set git commit if available
-    if let Ok(sha) = rpg_encoder::evolution::get_head_sha(project_root) {
-        graph.base_commit = Some(sha);
+    if let Some(ref revision) = revision {
+        graph.base_commit = Some(revision.commit().to_string());
+        graph.metadata.repo_fingerprint =
+            rpg_encoder::artifact::repo_fingerprint_at(project_root, revision.commit());
+    } else {
+        if let Ok(sha) = rpg_encoder::evolution::get_head_sha(project_root) {
+            graph.base_commit = Some(sha);
+        }
+        graph.metadata.repo_fingerprint = rpg_encoder::artifact::repo_fingerprint(project_root);
     }
 
     // Auto-preserve lifted features from previous graph
@@ -521,12 +1132,24 @@ fn cmd_build(
             graph.aggregate_hierarchy_features();
             graph.materialize_containment_edges();
         }
+        // Charters are human-written: carry them over by path
+        graph.restore_charters(&old.hierarchy_charters());
 
         Some(stats)
     } else {
         None
     };
 
+    // Tag fixtures after merging, which restores the previous graph's tags
+    let fixture_files =
+        rpg_encoder::fixtures::tag_fixtures(&mut graph, &config.encoding.fixture_globs);
+
+    // Source signatures for source-similarity duplicate detection (opt-in)
+    if config.encoding.source_signatures {
+        rpg_nav::duplication::compute_source_signatures(&mut graph, project_root, None);
+        graph.metadata.source_signatures = true;
+    }
+
     // Refresh metadata and save
     graph.refresh_metadata();
     rpg_core::storage::save_with_config(project_root, &graph, &config.storage)?;
@@ -538,6 +1161,15 @@ fn cmd_build(
     eprintln!("\nRPG built successfully!");
     eprintln!("  Entities: {}", graph.metadata.total_entities);
     eprintln!("  Files: {}", graph.metadata.total_files);
+    if fixture_files > 0 {
+        eprintln!("  Fixture files: {} (indexed, not lifted)", fixture_files);
+    }
+    if linguist_skipped > 0 {
+        eprintln!(
+            "  Skipped files: {} (vendored/generated/documentation per .gitattributes)",
+            linguist_skipped
+        );
+    }
     eprintln!("  Lifted: {}/{}", lifted, total);
     eprintln!(
         "  Hierarchy: {}",
@@ -553,11 +1185,31 @@ fn cmd_build(
     eprintln!("  Total edges: {}", graph.metadata.total_edges);
     eprintln!("  Saved to: .rpg/graph.json");
 
+    let resolution = &graph.metadata.edge_resolution;
+    let table = rpg_encoder::grounding::format_resolution_table(resolution);
+    if !table.is_empty() {
+        eprintln!("\n  Dependency resolution:");
+        for line in table.lines() {
+            eprintln!("    {}", line);
+        }
+    }
+    for warning in rpg_encoder::grounding::resolution_warnings(resolution) {
+        eprintln!("  Warning: {}", warning);
+    }
+    if !import_cycles.is_empty() {
+        eprintln!("\n  Import cycles: {}", import_cycles.len());
+        for cycle in &import_cycles {
+            eprintln!("    {}", cycle.representation());
+        }
+    }
+
     if let Some(stats) = merge_stats {
         eprintln!("\n  Auto-preserved from previous graph:");
         eprintln!("    Features restored: {}", stats.features_restored);
         eprintln!("    Hierarchy paths restored: {}", stats.hierarchy_restored);
         eprintln!("    Module features restored: {}", stats.modules_restored);
+        eprintln!("    Tags restored: {}", stats.tags_restored);
+        eprintln!("    Annotations restored: {}", stats.annotations_restored);
         eprintln!("    Orphaned (old entities gone): {}", stats.orphaned);
         eprintln!("    New entities: {}", stats.new_entities);
     } else if total > 0 && lifted == 0 {
@@ -574,25 +1226,12 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let mut graph = rpg_core::storage::load(project_root)?;
+    let mut graph = load_graph(project_root)?;
     let config = RpgConfig::load(project_root)?;
 
     // Detect paradigms for framework-aware entity classification
-    // Backward compat: fall back to singular `language` field for older graphs
-    let detected_langs: Vec<rpg_parser::languages::Language> =
-        if !graph.metadata.languages.is_empty() {
-            graph
-                .metadata
-                .languages
-                .iter()
-                .filter_map(|l| rpg_parser::languages::Language::from_name(l))
-                .collect()
-        } else {
-            rpg_parser::languages::Language::from_name(&graph.metadata.language)
-                .into_iter()
-                .collect()
-        };
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
+    let detected_langs = graph_languages(&graph);
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
         anyhow::anyhow!(
             "paradigm definition errors: {}",
             errs.iter()
@@ -634,6 +1273,7 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
     if summary.entities_added == 0
         && summary.entities_modified == 0
         && summary.entities_removed == 0
+        && summary.regrounded_nodes.is_empty()
     {
         eprintln!("RPG is up to date. No source changes detected.");
     } else {
@@ -643,31 +1283,89 @@ fn cmd_update(project_root: &Path, since: Option<String>) -> Result<()> {
         eprintln!("  Entities removed: {}", summary.entities_removed);
         eprintln!("  Edges added: {}", summary.edges_added);
         eprintln!("  Edges removed: {}", summary.edges_removed);
+        if !summary.regrounded_nodes.is_empty() {
+            eprintln!(
+                "  Re-grounded after directory moves: {}",
+                summary.regrounded_nodes.join(", ")
+            );
+        }
     }
 
     Ok(())
 }
 
+/// Languages recorded in the graph. Backward compat: falls back to the
+/// singular `language` field for older graphs.
+fn graph_languages(graph: &rpg_core::graph::RPGraph) -> Vec<rpg_parser::languages::Language> {
+    use rpg_parser::languages::Language;
+    if !graph.metadata.languages.is_empty() {
+        graph
+            .metadata
+            .languages
+            .iter()
+            .filter_map(|l| Language::from_name(l))
+            .collect()
+    } else {
+        Language::from_name(&graph.metadata.language)
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Candidate filters for `search`.
+struct SearchFilters<'a> {
+    scope: Option<&'a str>,
+    line_range: Option<&'a str>,
+    file_pattern: Option<&'a str>,
+    changed_since: Option<&'a str>,
+    sort: &'a str,
+    include_fixtures: bool,
+    exclude_tests: bool,
+    /// Group members under their file's Module (features mode only).
+    group_by_file: bool,
+    /// Git revision standing in for HEAD.
+    rev: Option<&'a str>,
+}
+
 fn cmd_search(
     project_root: &Path,
     query: &str,
     mode: &str,
-    scope: Option<&str>,
-    line_range: Option<&str>,
-    file_pattern: Option<&str>,
+    filters: &SearchFilters,
+    format: &str,
 ) -> Result<()> {
-    let graph = rpg_core::storage::load(project_root)?;
-    let config = RpgConfig::load(project_root)?;
-    let search_mode = match mode {
-        "features" => rpg_nav::search::SearchMode::Features,
-        "snippets" => rpg_nav::search::SearchMode::Snippets,
-        _ => rpg_nav::search::SearchMode::Auto,
-    };
+    if format != "text" && format != "json" {
+        anyhow::bail!("Unknown format: {}. Use 'text' or 'json'.", format);
+    }
+    let sort = rpg_nav::search::SearchSort::parse(filters.sort).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Unknown sort: {}. Use 'relevance', 'churn', or 'entry_distance'.",
+            filters.sort
+        )
+    })?;
+    let scope = filters.scope;
+    if let Some(scope) = scope {
+        rpg_nav::scope::validate(scope)?;
+    }
+    if let Some(pattern) = filters.file_pattern {
+        rpg_nav::scope::compile_glob(pattern)
+            .map_err(|e| anyhow::anyhow!("--file-pattern: {}", e))?;
+    }
+    let graph = load_graph_at(project_root, filters.rev)?;
+    if sort == rpg_nav::search::SearchSort::Churn && graph.metadata.churn.is_none() {
+        eprintln!("No churn analysis in the graph; run `rpg-encoder churn` first.");
+    }
+    let config = RpgConfig::load(project_root)?;
+    let search_mode = match mode {
+        "features" => rpg_nav::search::SearchMode::Features,
+        "snippets" => rpg_nav::search::SearchMode::Snippets,
+        _ => rpg_nav::search::SearchMode::Auto,
+    };
 
     let limit = config.navigation.search_result_limit;
 
     // Parse line range if provided
-    let line_nums = line_range.and_then(|lr| {
+    let line_nums = filters.line_range.and_then(|lr| {
         let parts: Vec<&str> = lr.split('-').collect();
         if parts.len() == 2 {
             let start = parts[0].parse::<usize>().ok()?;
@@ -678,7 +1376,16 @@ fn cmd_search(
         }
     });
 
-    let results = rpg_nav::search::search_with_params(
+    let diff_scope = filters
+        .changed_since
+        .map(|since| match filters.rev {
+            Some(rev) => rpg_encoder::evolution::diff_scope_at(project_root, &graph, since, rev),
+            None => rpg_encoder::evolution::diff_scope(project_root, &graph, since),
+        })
+        .transpose()?;
+    let vocabulary = rpg_nav::vocab::Vocabulary::load(project_root);
+
+    let mut outcome = rpg_nav::search::search_with_facets(
         &graph,
         &rpg_nav::search::SearchParams {
             query,
@@ -686,66 +1393,113 @@ fn cmd_search(
             scope,
             limit,
             line_nums,
-            file_pattern,
+            file_pattern: filters.file_pattern,
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores: None,
             diff_context: None,
+            changed_entities: diff_scope.as_ref().map(|d| &d.entities),
+            vocabulary: vocabulary.as_ref(),
+            sort,
+            include_fixtures: filters.include_fixtures,
+            exclude_tests: filters.exclude_tests,
+            async_only: false,
+            deadline: None,
+            group_by_file: filters.group_by_file
+                && search_mode == rpg_nav::search::SearchMode::Features,
         },
-    );
+    )?;
+    if let Some(ref diff_scope) = diff_scope {
+        for result in &mut outcome.results {
+            result.stale = diff_scope.stale_files.contains(Path::new(&result.file));
+            for member in &mut result.members {
+                member.stale = result.stale;
+            }
+        }
+    }
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+        return Ok(());
+    }
 
+    let results = &outcome.results;
     if results.is_empty() {
         eprintln!("No results found for: {}", query);
         return Ok(());
     }
 
+    let facets = &outcome.facets;
+    if facets.total_matches > results.len() {
+        let summarize = |facet: &[rpg_nav::search::FacetCount]| {
+            facet
+                .iter()
+                .map(|f| format!("{}={}", f.value, f.count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        println!(
+            "Showing {} of {} matches",
+            results.len(),
+            facets.total_matches
+        );
+        println!("  kinds: {}", summarize(&facets.kinds));
+        println!("  areas: {}", summarize(&facets.areas));
+        println!("  files: {}", summarize(&facets.files));
+        println!();
+    }
+    if !outcome.expansions.is_empty() {
+        let expanded: Vec<String> = outcome
+            .expansions
+            .iter()
+            .map(|(term, neighbors)| format!("{} → {}", term, neighbors.join(", ")))
+            .collect();
+        println!("Expanded: {}", expanded.join("; "));
+        println!();
+    }
+
     for (i, result) in results.iter().enumerate() {
         println!(
-            "{}. {} [{}:{}] (score: {:.2})",
+            "{}. {} [{}:{}] (score: {:.2}){}",
             i + 1,
             result.entity_name,
             result.file,
             result.line_start,
-            result.score
+            result.score,
+            if result.stale { " [stale]" } else { "" }
         );
         if !result.matched_features.is_empty() {
             println!("   features: {}", result.matched_features.join(", "));
         }
+        if !result.expanded_via.is_empty() {
+            println!("   via: {}", result.expanded_via.join(", "));
+        }
+        for member in &result.members {
+            println!(
+                "   - {} [line {}] (score: {:.2})",
+                member.entity_name, member.line_start, member.score
+            );
+            if !member.matched_features.is_empty() {
+                println!("     features: {}", member.matched_features.join(", "));
+            }
+        }
     }
 
     Ok(())
 }
 
-fn cmd_fetch(project_root: &Path, entity_id: &str) -> Result<()> {
-    let graph = rpg_core::storage::load(project_root)?;
-    let output = rpg_nav::fetch::fetch(&graph, entity_id, project_root)?;
+fn cmd_fetch(project_root: &Path, entity_id: &str, rev: Option<&str>) -> Result<()> {
+    let graph = load_graph_at(project_root, rev)?;
+    let output = match rev {
+        Some(rev) => {
+            let files = rpg_encoder::git_files::GitFileProvider::open(project_root, rev)?;
+            rpg_nav::fetch::fetch_from(&graph, entity_id, &files)?
+        }
+        None => rpg_nav::fetch::fetch(&graph, entity_id, project_root)?,
+    };
 
     match output {
-        rpg_nav::fetch::FetchOutput::Entity(result) => {
-            println!("Entity: {}", result.entity.name);
-            println!("Type: {:?}", result.entity.kind);
-            println!(
-                "File: {}:{}-{}",
-                result.entity.file.display(),
-                result.entity.line_start,
-                result.entity.line_end
-            );
-            println!("Hierarchy: {}", result.entity.hierarchy_path);
-
-            if !result.entity.semantic_features.is_empty() {
-                println!("Features: {}", result.entity.semantic_features.join(", "));
-            }
-
-            if let Some(code) = &result.source_code {
-                println!("\n--- Source ---\n{}", code);
-            }
-
-            if !result.entity.deps.invokes.is_empty() {
-                println!("\nInvokes: {}", result.entity.deps.invokes.join(", "));
-            }
-            if !result.entity.deps.invoked_by.is_empty() {
-                println!("Invoked by: {}", result.entity.deps.invoked_by.join(", "));
-            }
-        }
+        rpg_nav::fetch::FetchOutput::Entity(result) => print_fetched_entity(&result),
         rpg_nav::fetch::FetchOutput::Hierarchy(result) => {
             println!("Hierarchy Node: {}", result.node.name);
             println!("ID: {}", result.node.id);
@@ -762,6 +1516,9 @@ fn cmd_fetch(project_root: &Path, entity_id: &str) -> Result<()> {
             if !result.child_names.is_empty() {
                 println!("Children: {}", result.child_names.join(", "));
             }
+            if !result.node.owners.is_empty() {
+                println!("Owners: {}", rpg_nav::toon::format_owners(&result.node));
+            }
             if !result.node.semantic_features.is_empty() {
                 println!(
                     "Features: {}",
@@ -781,8 +1538,183 @@ fn cmd_fetch(project_root: &Path, entity_id: &str) -> Result<()> {
     Ok(())
 }
 
+fn cmd_fetch_file(project_root: &Path, file: &str, rev: Option<&str>) -> Result<()> {
+    let graph = load_graph_at(project_root, rev)?;
+    let results = match rev {
+        Some(rev) => {
+            let files = rpg_encoder::git_files::GitFileProvider::open(project_root, rev)?;
+            rpg_nav::fetch::fetch_file_from(&graph, file, &files)?
+        }
+        None => rpg_nav::fetch::fetch_file(&graph, file, project_root)?,
+    };
+    for (i, result) in results.iter().enumerate() {
+        if i > 0 {
+            println!("\n---\n");
+        }
+        print_fetched_entity(result);
+    }
+    Ok(())
+}
+
+fn print_fetched_entity(result: &rpg_nav::fetch::FetchResult) {
+    println!("Entity: {}", result.entity.name);
+    println!("Type: {:?}", result.entity.kind);
+    let modifiers = result.entity.modifiers();
+    if !modifiers.is_empty() {
+        println!("Modifiers: {}", modifiers.join(", "));
+    }
+    println!(
+        "File: {}:{}-{}",
+        result.entity.file.display(),
+        result.entity.line_start,
+        result.entity.line_end
+    );
+    println!("Hierarchy: {}", result.entity.hierarchy_path);
+    if let Some(distance) = result.entity.entry_distance {
+        println!("Entry distance: {}", distance);
+    }
+
+    if !result.entity.semantic_features.is_empty() {
+        println!("Features: {}", result.entity.semantic_features.join(", "));
+    }
+
+    if let Some(code) = &result.source_code {
+        println!("\n--- Source ---\n{}", code);
+    }
+
+    if !result.entity.deps.invokes.is_empty() {
+        println!("\nInvokes: {}", result.entity.deps.invokes.join(", "));
+    }
+    if !result.entity.deps.invoked_by.is_empty() {
+        println!("Invoked by: {}", result.entity.deps.invoked_by.join(", "));
+    }
+}
+
+fn cmd_explain(
+    project_root: &Path,
+    query: &str,
+    top: usize,
+    scope: Option<&str>,
+    json: bool,
+) -> Result<()> {
+    if let Some(scope) = scope {
+        rpg_nav::scope::validate(scope)?;
+    }
+    let graph = load_graph(project_root)?;
+
+    // Hybrid search when a saved embedding index is present
+    #[cfg(feature = "embeddings")]
+    let embedding_scores = if project_root.join(".rpg/embeddings.bin").exists() {
+        let updated_at = graph.updated_at.to_rfc3339();
+        rpg_nav::embeddings::EmbeddingIndex::load_or_init(project_root, &updated_at)
+            .and_then(|mut index| index.score_all(query))
+            .ok()
+            .filter(|scores| !scores.is_empty())
+    } else {
+        None
+    };
+    #[cfg(not(feature = "embeddings"))]
+    let embedding_scores: Option<std::collections::HashMap<String, f64>> = None;
+
+    let answers = rpg_nav::answer::answer_query(
+        &graph,
+        project_root,
+        query,
+        scope,
+        top,
+        embedding_scores.as_ref(),
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&answers)?);
+    } else {
+        print!("{}", rpg_nav::answer::format_answers(query, &answers));
+    }
+    Ok(())
+}
+
+fn cmd_symbols(project_root: &Path, file: &str, json: bool) -> Result<()> {
+    let graph = load_graph(project_root)?;
+    let source = std::fs::read_to_string(project_root.join(file)).ok();
+    let symbols = rpg_nav::symbols::document_symbols(&graph, file, source.as_deref())
+        .ok_or_else(|| anyhow::anyhow!(rpg_nav::fetch::missing_file_message(&graph, file)))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&symbols)?);
+    } else {
+        print!("{}", rpg_nav::symbols::format_outline(file, &symbols));
+    }
+    Ok(())
+}
+
+fn cmd_analyze(
+    project_root: &Path,
+    file: &str,
+    lang: Option<&str>,
+    paradigms: &[String],
+    stdin_filename: Option<&str>,
+) -> Result<()> {
+    use rpg_parser::languages::Language;
+    use std::io::Read;
+
+    let (path, source) = if file == "-" {
+        let mut source = String::new();
+        std::io::stdin()
+            .read_to_string(&mut source)
+            .context("failed to read stdin")?;
+        (PathBuf::from(stdin_filename.unwrap_or("<stdin>")), source)
+    } else {
+        let source =
+            std::fs::read_to_string(file).with_context(|| format!("failed to read {}", file))?;
+        (PathBuf::from(file), source)
+    };
+
+    let language = match lang {
+        Some(l) => Language::from_name(l)
+            .or_else(|| Language::from_extension(l))
+            .ok_or_else(|| anyhow::anyhow!("unsupported language: {}", l))?,
+        None => Language::from_source(&path, &source).ok_or_else(|| {
+            anyhow::anyhow!(
+                "cannot detect the language of {}; pass --lang",
+                path.display()
+            )
+        })?,
+    };
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
+        anyhow::anyhow!(
+            "paradigm definition errors: {}",
+            errs.iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("; ")
+        )
+    })?;
+    if let Some(unknown) = paradigms
+        .iter()
+        .find(|name| !paradigm_defs.iter().any(|d| &d.name == *name))
+    {
+        let known: Vec<&str> = paradigm_defs.iter().map(|d| d.name.as_str()).collect();
+        anyhow::bail!(
+            "unknown paradigm: {} (available: {})",
+            unknown,
+            known.join(", ")
+        );
+    }
+    let active_defs: Vec<&rpg_parser::paradigms::defs::ParadigmDef> = paradigm_defs
+        .iter()
+        .filter(|d| paradigms.contains(&d.name))
+        .collect();
+    let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs)
+        .map_err(|errs| anyhow::anyhow!("query compile errors: {}", errs.join("; ")))?;
+
+    let analysis =
+        rpg_parser::analyze::analyze_file(&path, &source, language, &active_defs, &qcache);
+    println!("{}", serde_json::to_string_pretty(&analysis)?);
+    Ok(())
+}
+
 fn cmd_explore(project_root: &Path, entity_id: &str, direction: &str, depth: usize) -> Result<()> {
-    let graph = rpg_core::storage::load(project_root)?;
+    let graph = load_graph(project_root)?;
     let dir = match direction {
         "up" | "upstream" => rpg_nav::explore::Direction::Upstream,
         "down" | "downstream" => rpg_nav::explore::Direction::Downstream,
@@ -793,6 +1725,17 @@ fn cmd_explore(project_root: &Path, entity_id: &str, direction: &str, depth: usi
     match rpg_nav::explore::explore(&graph, entity_id, dir, depth, None) {
         Some(tree) => {
             print!("{}", rpg_nav::explore::format_tree(&tree, 0));
+            let mut params = toml::Table::new();
+            params.insert("entity_id".into(), entity_id.into());
+            params.insert("direction".into(), direction.into());
+            params.insert("depth".into(), i64::try_from(depth).unwrap_or(-1).into());
+            let query = rpg_nav::queries::SavedQuery {
+                tool: "explore_rpg".to_string(),
+                params,
+            };
+            if let Err(e) = rpg_nav::queries::record_last(project_root, &query) {
+                eprintln!("Warning: failed to record query: {}", e);
+            }
         }
         None => {
             eprintln!("Entity not found: {}", entity_id);
@@ -802,6 +1745,150 @@ fn cmd_explore(project_root: &Path, entity_id: &str, direction: &str, depth: usi
     Ok(())
 }
 
+fn cmd_tag(project_root: &Path, action: TagAction) -> Result<()> {
+    let (add, args) = match action {
+        TagAction::Add(args) => (true, args),
+        TagAction::Remove(args) => (false, args),
+    };
+    if args.ids.is_empty() && args.scope.is_none() {
+        anyhow::bail!("Select entities with --id, --scope, or both.");
+    }
+    let tags = args
+        .tags
+        .iter()
+        .map(|t| {
+            rpg_core::graph::normalize_tag(t).ok_or_else(|| anyhow::anyhow!("Invalid tag: '{}'", t))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut graph = load_graph(project_root)?;
+    let selection = rpg_nav::scope::select(&graph, &args.ids, args.scope.as_deref())?;
+    for id in &selection.unknown_ids {
+        eprintln!("Unknown entity: {}", id);
+    }
+    let changed = if add {
+        graph.tag_entities(&selection.entity_ids, &tags)
+    } else {
+        graph.untag_entities(&selection.entity_ids, &tags)
+    };
+    if changed > 0 {
+        rpg_core::storage::save(project_root, &graph)?;
+    }
+    eprintln!(
+        "{} {} of {} selected entities.",
+        if add { "Tagged" } else { "Untagged" },
+        changed,
+        selection.entity_ids.len()
+    );
+    for (tag, count) in graph.tag_counts() {
+        println!("{}  {}", tag, count);
+    }
+    Ok(())
+}
+
+fn cmd_hierarchy(project_root: &Path, action: HierarchyAction) -> Result<()> {
+    match action {
+        HierarchyAction::Charter { path, text, clear } => {
+            let mut graph = load_graph(project_root)?;
+            let Some(node) = graph.find_hierarchy_node(&path) else {
+                anyhow::bail!("No hierarchy node at '{}'.", path);
+            };
+            if text.is_none() && !clear {
+                match &node.charter {
+                    Some(charter) => println!("{}", charter),
+                    None => eprintln!("No charter set for '{}'.", path),
+                }
+                return Ok(());
+            }
+            let charter = text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+            if charter.is_none() && !clear {
+                anyhow::bail!("Charter text is empty. Use --clear to remove a charter.");
+            }
+            graph.set_charter(&path, charter);
+            rpg_core::storage::save(project_root, &graph)?;
+            eprintln!(
+                "{} charter for '{}'.",
+                if clear { "Cleared" } else { "Set" },
+                path
+            );
+        }
+    }
+    Ok(())
+}
+
+fn cmd_annotate(project_root: &Path, from_json: &Path) -> Result<()> {
+    let doc = rpg_encoder::annotations::load_annotations(from_json)?;
+    let mut graph = load_graph(project_root)?;
+    let report = rpg_encoder::annotations::apply_annotations(&mut graph, &doc);
+    if report.values_set + report.values_removed > 0 {
+        rpg_core::storage::save(project_root, &graph)?;
+    }
+    eprint!("{}", rpg_encoder::annotations::format_report(&report));
+    Ok(())
+}
+
+fn cmd_query(project_root: &Path, action: QueryAction) -> Result<()> {
+    match action {
+        QueryAction::Save { name, force } => {
+            let Some(query) = rpg_nav::queries::load_last(project_root)? else {
+                anyhow::bail!(
+                    "No explore/impact query has been run yet. Run `rpg-encoder explore` \
+                     (or explore_rpg / impact_radius via MCP) first."
+                );
+            };
+            if !force && rpg_nav::queries::load_all(project_root)?.contains_key(&name) {
+                anyhow::bail!("Query '{}' already exists. Use --force to overwrite.", name);
+            }
+            rpg_nav::queries::save(project_root, &name, &query)?;
+            eprintln!(
+                "Saved query '{}' ({}: {})",
+                name,
+                query.tool,
+                describe_params(&query.params)
+            );
+        }
+        QueryAction::List => {
+            let queries = rpg_nav::queries::load_all(project_root)?;
+            if queries.is_empty() {
+                eprintln!("No saved queries. Use `rpg-encoder query save <name>`.");
+            }
+            for (name, query) in &queries {
+                println!(
+                    "{}  {}  {}",
+                    name,
+                    query.tool,
+                    describe_params(&query.params)
+                );
+            }
+        }
+        QueryAction::Run { name } => {
+            let query = rpg_nav::queries::load(project_root, &name)?;
+            let graph = load_graph(project_root)?;
+            let output = rpg_nav::queries::run(&graph, &query)?;
+            println!("{}", output.trim_end());
+        }
+        QueryAction::Delete { name } => {
+            if !rpg_nav::queries::remove(project_root, &name)? {
+                anyhow::bail!("No saved query named '{}'", name);
+            }
+            eprintln!("Deleted query '{}'", name);
+        }
+    }
+    Ok(())
+}
+
+/// `key=value` pairs for display, e.g. `entity_id=h:Storage, max_depth=3`.
+fn describe_params(params: &toml::Table) -> String {
+    params
+        .iter()
+        .map(|(k, v)| match v {
+            toml::Value::String(s) => format!("{}={}", k, s),
+            other => format!("{}={}", k, other),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 const PRECOMMIT_HOOK: &str = r#"#!/bin/sh
 # RPG-Encoder: auto-update semantic graph before commit
 # Installed by: rpg-encoder hook install
@@ -872,22 +1959,149 @@ fn cmd_hook(project_root: &Path, action: &str) -> Result<()> {
     Ok(())
 }
 
-fn cmd_export(project_root: &Path, format: &str) -> Result<()> {
+/// `export --entity`: the start entity and how to walk out from it.
+struct Neighborhood {
+    entity: String,
+    depth: usize,
+    direction: String,
+    edge_filter: Option<String>,
+}
+
+fn cmd_export(
+    project_root: &Path,
+    format: &str,
+    scope: Option<&str>,
+    out: Option<&Path>,
+    neighborhood: Option<&Neighborhood>,
+) -> Result<()> {
     if !rpg_core::storage::rpg_exists(project_root) {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
+    let mut graph = load_graph(project_root)?;
+    if let Some(scope) = scope {
+        let expr = rpg_nav::scope::parse_lenient(scope)?;
+        graph = rpg_nav::export::scoped_graph(&graph, &expr);
+    }
+    if let Some(neighborhood) = neighborhood {
+        if !matches!(format, "dot" | "graphviz") {
+            anyhow::bail!("--entity exports DOT only; got format '{}'.", format);
+        }
+        let output = export_neighborhood(&graph, neighborhood)?;
+        match out {
+            Some(path) => std::fs::write(path, output)?,
+            None => print!("{}", output),
+        }
+        return Ok(());
+    }
 
     let export_format = match format {
         "dot" | "graphviz" => rpg_nav::export::ExportFormat::Dot,
         "mermaid" | "md" => rpg_nav::export::ExportFormat::Mermaid,
-        _ => anyhow::bail!("Unknown export format: {}. Use 'dot' or 'mermaid'.", format),
+        "features-jsonl" | "features-parquet" => {
+            return export_features(project_root, &graph, format, out);
+        }
+        _ => anyhow::bail!(
+            "Unknown export format: {}. Use 'dot', 'mermaid', 'features-jsonl', or 'features-parquet'.",
+            format
+        ),
     };
 
     let output = rpg_nav::export::export(&graph, export_format);
-    print!("{}", output);
+    match out {
+        Some(path) => std::fs::write(path, output)?,
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+/// DOT of the subgraph `explore` reaches from one entity.
+fn export_neighborhood(
+    graph: &rpg_core::graph::RPGraph,
+    neighborhood: &Neighborhood,
+) -> Result<String> {
+    let dir = match neighborhood.direction.as_str() {
+        "up" | "upstream" => rpg_nav::explore::Direction::Upstream,
+        "down" | "downstream" => rpg_nav::explore::Direction::Downstream,
+        "both" => rpg_nav::explore::Direction::Both,
+        other => anyhow::bail!("Unknown direction: {}. Use 'up', 'down', or 'both'.", other),
+    };
+    let edge_filter = neighborhood
+        .edge_filter
+        .as_deref()
+        .map(|kind| {
+            serde_json::from_value::<rpg_core::graph::EdgeKind>(serde_json::Value::String(
+                kind.trim().to_string(),
+            ))
+            .map_err(|_| anyhow::anyhow!("Unknown edge kind: {}", kind))
+        })
+        .transpose()?;
+
+    let Some(tree) = rpg_nav::explore::explore_filtered(
+        graph,
+        &neighborhood.entity,
+        dir,
+        neighborhood.depth,
+        edge_filter,
+        rpg_nav::explore::NeighborFilter::default(),
+    ) else {
+        anyhow::bail!("Entity not found: {}", neighborhood.entity);
+    };
+    Ok(rpg_nav::export::export_neighborhood_dot(graph, &tree))
+}
+
+/// Per-file feature rows (one per Module entity) for external ML pipelines.
+fn export_features(
+    project_root: &Path,
+    graph: &rpg_core::graph::RPGraph,
+    format: &str,
+    out: Option<&Path>,
+) -> Result<()> {
+    #[cfg(feature = "embeddings")]
+    let embeddings = rpg_nav::embeddings::load_saved_mean_vectors(project_root)?;
+    #[cfg(not(feature = "embeddings"))]
+    let embeddings: Option<std::collections::HashMap<String, Vec<f32>>> = {
+        let _ = project_root;
+        None
+    };
+    let rows = rpg_nav::export::features::file_feature_rows(graph, embeddings.as_ref());
+
+    if format == "features-jsonl" {
+        let output = rpg_nav::export::features::to_jsonl(&rows);
+        match out {
+            Some(path) => std::fs::write(path, output)?,
+            None => print!("{}", output),
+        }
+    } else {
+        let Some(path) = out else {
+            anyhow::bail!("features-parquet needs --out <file>");
+        };
+        #[cfg(feature = "arrow")]
+        rpg_nav::export::features::write_parquet(&rows, path)?;
+        #[cfg(not(feature = "arrow"))]
+        anyhow::bail!(
+            "features-parquet needs the `arrow` feature (cargo install rpg-cli --features arrow); use features-jsonl instead. Not written: {}",
+            path.display()
+        );
+    }
+    if let Some(path) = out {
+        eprintln!("Exported {} file rows to {}", rows.len(), path.display());
+    }
+    Ok(())
+}
 
+fn cmd_api_diff(project_root: &Path, from: &str, to: &str, format: &str) -> Result<()> {
+    if !matches!(format, "markdown" | "json") {
+        anyhow::bail!("Unknown format: {}. Use 'markdown' or 'json'.", format);
+    }
+    let config = RpgConfig::load(project_root).unwrap_or_default();
+    let diff =
+        rpg_encoder::api_diff::diff_api(project_root, from, to, &config.encoding.fixture_globs)?;
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&diff)?),
+        _ => print!("{}", rpg_encoder::api_diff::format_markdown(&diff)),
+    }
     Ok(())
 }
 
@@ -898,15 +2112,29 @@ fn cmd_diff(project_root: &Path, since: Option<String>) -> Result<()> {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
+    let graph = load_graph(project_root)?;
+    let config = RpgConfig::load(project_root)?;
 
-    let changes = rpg_encoder::evolution::detect_changes(project_root, &graph, since.as_deref())?;
+    // `--since` wins; otherwise diff from `[staleness] compare_to`.
+    let (since, label) = match since {
+        Some(since) => (since.clone(), since),
+        None => {
+            let point = rpg_encoder::evolution::resolve_compare_to(
+                project_root,
+                &graph,
+                &config.staleness.compare_to,
+            )?;
+            (point.commit, point.label)
+        }
+    };
+    let changes = rpg_encoder::evolution::detect_changes(project_root, &graph, Some(&since))?;
     let changes = rpg_encoder::evolution::filter_rpgignore_changes(project_root, changes);
 
     if changes.is_empty() {
-        eprintln!("No changes detected since last build.");
+        eprintln!("No changes detected since {}.", label);
         return Ok(());
     }
+    eprintln!("Changes since {}:", label);
 
     let mut added = Vec::new();
     let mut modified = Vec::new();
@@ -954,6 +2182,22 @@ fn cmd_diff(project_root: &Path, since: Option<String>) -> Result<()> {
         deleted.len(),
         renamed.len()
     );
+
+    let risk = rpg_encoder::evolution::assess_change_risk(&graph, &changes, &config.risk);
+    println!("{}", risk.summary());
+    for file in risk
+        .files
+        .iter()
+        .filter(|f| f.level > rpg_encoder::evolution::RiskLevel::Low)
+    {
+        println!(
+            "  {} {}: fan-in {}, {} public entities",
+            file.level,
+            file.path.display(),
+            file.fan_in,
+            file.public_entities
+        );
+    }
     eprintln!("Run `rpg-encoder update` to apply these changes.");
 
     Ok(())
@@ -969,7 +2213,7 @@ fn cmd_reconstruct_plan(
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
+    let graph = load_graph(project_root)?;
     let plan = rpg_encoder::reconstruction::schedule_reconstruction(
         &graph,
         rpg_encoder::reconstruction::ReconstructionOptions {
@@ -1012,13 +2256,148 @@ fn cmd_reconstruct_plan(
     Ok(())
 }
 
-fn cmd_validate(project_root: &Path) -> Result<()> {
+const VALIDATE_DANGLING_SOURCE: Rule = Rule {
+    id: "integrity/dangling-edge-source",
+    description: "Edge source is not an entity or hierarchy node",
+    level: Level::Warning,
+};
+const VALIDATE_DANGLING_TARGET: Rule = Rule {
+    id: "integrity/dangling-edge-target",
+    description: "Edge target is not an entity or hierarchy node",
+    level: Level::Warning,
+};
+const VALIDATE_HIERARCHY_ORPHAN: Rule = Rule {
+    id: "integrity/hierarchy-missing-entity",
+    description: "Hierarchy node references a missing entity",
+    level: Level::Warning,
+};
+const VALIDATE_ENTITY_ID: Rule = Rule {
+    id: "integrity/entity-id-format",
+    description: "Entity ID is not in file:name format",
+    level: Level::Warning,
+};
+const VALIDATE_FILE_INDEX: Rule = Rule {
+    id: "integrity/file-index-missing-entity",
+    description: "File index references a missing entity",
+    level: Level::Warning,
+};
+const VALIDATE_EMBEDDING_ORPHAN: Rule = Rule {
+    id: "integrity/embedding-orphan",
+    description: "Embedding index holds vectors for an entity that is gone or unlifted",
+    level: Level::Warning,
+};
+const VALIDATE_EMBEDDING_MISSING: Rule = Rule {
+    id: "integrity/embedding-missing",
+    description: "Lifted entity has no vectors in the embedding index",
+    level: Level::Warning,
+};
+const VALIDATE_EMBEDDING_MISMATCH: Rule = Rule {
+    id: "integrity/embedding-fingerprint-mismatch",
+    description: "Entity vectors were embedded from outdated features",
+    level: Level::Warning,
+};
+const VALIDATE_GROUNDED_PATH: Rule = Rule {
+    id: "integrity/grounded-path-missing",
+    description: "Hierarchy node is grounded to a directory that does not exist",
+    level: Level::Warning,
+};
+const VALIDATE_IMPORT_CYCLE: Rule = Rule {
+    id: "structure/import-cycle",
+    description: "Files import each other in a cycle",
+    level: Level::Warning,
+};
+
+/// Rules reported by `rpg-encoder validate`.
+const VALIDATE_RULES: &[Rule] = &[
+    VALIDATE_DANGLING_SOURCE,
+    VALIDATE_DANGLING_TARGET,
+    VALIDATE_HIERARCHY_ORPHAN,
+    VALIDATE_ENTITY_ID,
+    VALIDATE_FILE_INDEX,
+    VALIDATE_EMBEDDING_ORPHAN,
+    VALIDATE_EMBEDDING_MISSING,
+    VALIDATE_EMBEDDING_MISMATCH,
+    VALIDATE_GROUNDED_PATH,
+    VALIDATE_IMPORT_CYCLE,
+];
+
+fn cmd_validate(project_root: &Path, format: &str, fail_on_import_cycles: bool) -> Result<()> {
+    if !matches!(format, "text" | "sarif") {
+        anyhow::bail!("Unknown format: {}. Use 'text' or 'sarif'.", format);
+    }
     if !rpg_core::storage::rpg_exists(project_root) {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
-    let mut issues = 0;
+    let graph = load_graph(project_root)?;
+    let mut findings = validate_graph(&graph);
+    findings.extend(validate_grounded_paths(project_root, &graph));
+    #[cfg(feature = "embeddings")]
+    let findings = {
+        let mut findings = findings;
+        findings.extend(validate_embeddings(project_root, &graph)?);
+        findings
+    };
+    let config = RpgConfig::load(project_root).unwrap_or_default();
+    let import_cycles = rpg_core::imports::import_cycles(
+        &graph,
+        config.encoding.ignore_same_directory_import_cycles,
+    );
+    let check_import_cycles = || {
+        if fail_on_import_cycles && !import_cycles.is_empty() {
+            anyhow::bail!("{} import cycle(s) found", import_cycles.len());
+        }
+        Ok(())
+    };
+
+    if format == "sarif" {
+        let mut findings = findings;
+        findings.extend(import_cycles.iter().map(|cycle| {
+            sarif::Finding::new(
+                &VALIDATE_IMPORT_CYCLE,
+                format!("import cycle: {}", cycle.representation()),
+                Some(sarif::Location::file(Path::new(&cycle.chain[0]))),
+            )
+        }));
+        println!("{}", sarif::to_sarif(VALIDATE_RULES, &findings));
+        return check_import_cycles();
+    }
+
+    for finding in &findings {
+        println!("WARN: {}", finding.message);
+    }
+    if findings.is_empty() {
+        eprintln!("Graph is valid. No integrity issues found.");
+        eprintln!(
+            "  {} entities, {} edges, {} files",
+            graph.entities.len(),
+            graph.edges.len(),
+            graph.file_index.len()
+        );
+    } else {
+        eprintln!("\nFound {} integrity issue(s).", findings.len());
+    }
+    if !import_cycles.is_empty() {
+        eprintln!("\nImport cycles: {}", import_cycles.len());
+        for cycle in &import_cycles {
+            eprintln!("  {}", cycle.representation());
+        }
+    }
+    if let Some(report) = &graph.metadata.unresolved_imports
+        && report.unresolved > 0
+    {
+        eprint!(
+            "\n{}",
+            rpg_encoder::unresolved_imports::format_report(report, UNRESOLVED_IMPORT_REPORT_LIMIT)
+        );
+    }
+
+    check_import_cycles()
+}
+
+fn validate_graph(graph: &rpg_core::graph::RPGraph) -> Vec<sarif::Finding> {
+    let mut findings = Vec::new();
+    let entity_location = |id: &str| graph.entities.get(id).map(sarif::Location::entity);
 
     // 1. Dangling edge targets (edge references entity ID not in entities or hierarchy)
     for edge in &graph.edges {
@@ -1027,26 +2406,36 @@ fn cmd_validate(project_root: &Path) -> Result<()> {
         let target_exists = graph.entities.contains_key(&edge.target)
             || graph.find_hierarchy_node_by_id(&edge.target).is_some();
 
+        // Each finding points at the endpoint that still exists, if any.
         if !source_exists {
-            println!("WARN: dangling edge source: {}", edge.source);
-            issues += 1;
+            findings.push(sarif::Finding::new(
+                &VALIDATE_DANGLING_SOURCE,
+                format!("dangling edge source: {}", edge.source),
+                entity_location(&edge.target),
+            ));
         }
         if !target_exists {
-            println!("WARN: dangling edge target: {}", edge.target);
-            issues += 1;
+            findings.push(sarif::Finding::new(
+                &VALIDATE_DANGLING_TARGET,
+                format!("dangling edge target: {}", edge.target),
+                entity_location(&edge.source),
+            ));
         }
     }
 
     // 2. Orphan entity references in hierarchy
     for (area_name, node) in &graph.hierarchy {
-        check_hierarchy_orphans(node, area_name, &graph, &mut issues);
+        check_hierarchy_orphans(node, area_name, graph, &mut findings);
     }
 
     // 3. Entity IDs not matching file:name format
     for (id, entity) in &graph.entities {
         if entity.kind != rpg_core::graph::EntityKind::Module && !id.contains(':') {
-            println!("WARN: entity ID missing file:name format: {}", id);
-            issues += 1;
+            findings.push(sarif::Finding::new(
+                &VALIDATE_ENTITY_ID,
+                format!("entity ID missing file:name format: {}", id),
+                Some(sarif::Location::entity(entity)),
+            ));
         }
     }
 
@@ -1054,50 +2443,257 @@ fn cmd_validate(project_root: &Path) -> Result<()> {
     for (file, ids) in &graph.file_index {
         for id in ids {
             if !graph.entities.contains_key(id) {
-                println!(
-                    "WARN: file_index references missing entity: {} in {}",
-                    id,
-                    file.display()
-                );
-                issues += 1;
+                findings.push(sarif::Finding::new(
+                    &VALIDATE_FILE_INDEX,
+                    format!(
+                        "file_index references missing entity: {} in {}",
+                        id,
+                        file.display()
+                    ),
+                    Some(sarif::Location::file(file)),
+                ));
             }
         }
     }
 
-    if issues == 0 {
-        eprintln!("Graph is valid. No integrity issues found.");
-        eprintln!(
-            "  {} entities, {} edges, {} files",
-            graph.entities.len(),
-            graph.edges.len(),
-            graph.file_index.len()
-        );
-    } else {
-        eprintln!("\nFound {} integrity issue(s).", issues);
+    findings
+}
+
+/// Check the saved embedding index against the graph (no model load). The
+/// result is recorded in the index's meta sidecar for `rpg_info`.
+#[cfg(feature = "embeddings")]
+fn validate_embeddings(
+    project_root: &Path,
+    graph: &rpg_core::graph::RPGraph,
+) -> Result<Vec<sarif::Finding>> {
+    let Some(report) = rpg_nav::embeddings::validate_saved(project_root, graph)? else {
+        return Ok(Vec::new());
+    };
+    let entity_location = |id: &str| graph.entities.get(id).map(sarif::Location::entity);
+    let mut findings = Vec::new();
+    for id in &report.orphans {
+        findings.push(sarif::Finding::new(
+            &VALIDATE_EMBEDDING_ORPHAN,
+            format!("embedding index has orphan vectors: {}", id),
+            entity_location(id),
+        ));
+    }
+    for id in &report.missing {
+        findings.push(sarif::Finding::new(
+            &VALIDATE_EMBEDDING_MISSING,
+            format!("embedding index is missing lifted entity: {}", id),
+            entity_location(id),
+        ));
     }
+    for id in &report.mismatched {
+        findings.push(sarif::Finding::new(
+            &VALIDATE_EMBEDDING_MISMATCH,
+            format!("embedding vectors are outdated for: {}", id),
+            entity_location(id),
+        ));
+    }
+    Ok(findings)
+}
 
-    Ok(())
+/// Hierarchy nodes grounded to directories missing from disk, e.g. after a
+/// directory move the graph hasn't been updated for.
+fn validate_grounded_paths(
+    project_root: &Path,
+    graph: &rpg_core::graph::RPGraph,
+) -> Vec<sarif::Finding> {
+    fn walk(
+        node: &rpg_core::graph::HierarchyNode,
+        path: &str,
+        project_root: &Path,
+        findings: &mut Vec<sarif::Finding>,
+    ) {
+        for grounded in &node.grounded_paths {
+            if !project_root.join(grounded).exists() {
+                findings.push(sarif::Finding::new(
+                    &VALIDATE_GROUNDED_PATH,
+                    format!(
+                        "hierarchy node '{}' is grounded to missing path: {}",
+                        path,
+                        grounded.display()
+                    ),
+                    None,
+                ));
+            }
+        }
+        for (child_name, child) in &node.children {
+            walk(
+                child,
+                &format!("{}/{}", path, child_name),
+                project_root,
+                findings,
+            );
+        }
+    }
+
+    let mut findings = Vec::new();
+    for (area_name, node) in &graph.hierarchy {
+        walk(node, area_name, project_root, &mut findings);
+    }
+    findings
 }
 
 fn check_hierarchy_orphans(
     node: &rpg_core::graph::HierarchyNode,
     path: &str,
     graph: &rpg_core::graph::RPGraph,
-    issues: &mut usize,
+    findings: &mut Vec<sarif::Finding>,
 ) {
     for entity_id in &node.entities {
         if !graph.entities.contains_key(entity_id) {
-            println!(
-                "WARN: hierarchy node '{}' references missing entity: {}",
-                path, entity_id
-            );
-            *issues += 1;
+            findings.push(sarif::Finding::new(
+                &VALIDATE_HIERARCHY_ORPHAN,
+                format!(
+                    "hierarchy node '{}' references missing entity: {}",
+                    path, entity_id
+                ),
+                None,
+            ));
         }
     }
     for (child_name, child) in &node.children {
         let child_path = format!("{}/{}", path, child_name);
-        check_hierarchy_orphans(child, &child_path, graph, issues);
+        check_hierarchy_orphans(child, &child_path, graph, findings);
+    }
+}
+
+fn cmd_doctor(project_root: &Path, fix: bool) -> Result<()> {
+    let report = doctor::run(project_root, fix, &doctor::default_checks());
+    let markdown = report.to_markdown();
+    let dir = rpg_core::storage::rpg_dir(project_root);
+    std::fs::create_dir_all(&dir)?;
+    let report_path = dir.join(doctor::REPORT_FILE);
+    std::fs::write(&report_path, &markdown)?;
+    print!("{}", markdown);
+    eprintln!("Report written to {}", report_path.display());
+
+    let code = report.exit_code();
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Load the graph and verify it against the checkout, noting when it lags HEAD.
+fn load_graph(project_root: &Path) -> Result<rpg_core::graph::RPGraph> {
+    load_graph_at(project_root, None)
+}
+
+/// [`load_graph`], verified against `rev` instead of HEAD when given.
+fn load_graph_at(project_root: &Path, rev: Option<&str>) -> Result<rpg_core::graph::RPGraph> {
+    let (graph, report) = match rev {
+        Some(rev) => rpg_encoder::artifact::load_verified_at(project_root, rev)?,
+        None => rpg_encoder::artifact::load_verified(project_root)?,
+    };
+    if let Some(summary) = report.summary() {
+        eprintln!("Note: {}", summary);
+    }
+    Ok(graph)
+}
+
+/// Largest graph artifact `fetch-graph` will download.
+const GRAPH_DOWNLOAD_LIMIT: u64 = 1 << 30;
+
+fn cmd_fetch_graph(project_root: &Path, url: &str) -> Result<()> {
+    let raw = if url.starts_with("http://") || url.starts_with("https://") {
+        eprintln!("Downloading {}...", url);
+        ureq::get(url)
+            .call()
+            .with_context(|| format!("failed to download {}", url))?
+            .body_mut()
+            .with_config()
+            .limit(GRAPH_DOWNLOAD_LIMIT)
+            .read_to_vec()
+            .with_context(|| format!("failed to read {}", url))?
+    } else {
+        std::fs::read(url).with_context(|| format!("failed to read {}", url))?
+    };
+
+    let (graph, report) = rpg_encoder::artifact::install(project_root, &raw)?;
+    eprintln!(
+        "Installed graph: {} entities in {} files.",
+        graph.metadata.total_entities, graph.metadata.total_files
+    );
+    match report.fingerprint_matches {
+        Some(true) => eprintln!("Fingerprint matches HEAD."),
+        Some(false) => eprintln!("Fingerprint differs from HEAD (files changed since the build)."),
+        None => eprintln!("Graph has no repository fingerprint; only its commit was checked."),
+    }
+    if let Some(summary) = report.summary() {
+        eprintln!("Note: {}", summary);
+    }
+    Ok(())
+}
+
+/// Churn hotspots printed by `churn`.
+const CHURN_REPORT_LIMIT: usize = 20;
+
+fn cmd_churn(project_root: &Path, since: &str) -> Result<()> {
+    if !rpg_core::storage::rpg_exists(project_root) {
+        anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
+    }
+    let mut graph = load_graph(project_root)?;
+    let range = rpg_encoder::churn::analyze_churn(&mut graph, project_root, since)?;
+    rpg_core::storage::save(project_root, &graph)?;
+
+    let report = rpg_nav::health::compute_health(
+        &graph,
+        &rpg_nav::health::HealthConfig {
+            top_n: CHURN_REPORT_LIMIT,
+            ..Default::default()
+        },
+    );
+    eprintln!(
+        "Analyzed {} commits since {} ({}); {} entities changed.",
+        range.commits,
+        range.from.format("%Y-%m-%d"),
+        range.since,
+        graph
+            .entities
+            .values()
+            .filter(|e| e.churn.is_some())
+            .count()
+    );
+    for entity in &report.top_churn {
+        let churn = entity.churn.unwrap_or_default();
+        println!(
+            "{:>4} commits {:>6} lines  {}",
+            churn.commits, churn.lines, entity.entity_id
+        );
     }
+    Ok(())
+}
+
+fn cmd_health(project_root: &Path, format: &str) -> Result<()> {
+    if !matches!(format, "text" | "json" | "sarif") {
+        anyhow::bail!(
+            "Unknown format: {}. Use 'text', 'json', or 'sarif'.",
+            format
+        );
+    }
+    if !rpg_core::storage::rpg_exists(project_root) {
+        anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
+    }
+
+    let graph = load_graph(project_root)?;
+    let report = rpg_nav::health::compute_health(&graph, &rpg_nav::health::HealthConfig::default());
+
+    match format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "sarif" => println!(
+            "{}",
+            sarif::to_sarif(
+                sarif::HEALTH_RULES,
+                &sarif::health_findings(&graph, &report)
+            )
+        ),
+        _ => print!("{}", rpg_nav::toon::format_health_report(&report)),
+    }
+    Ok(())
 }
 
 #[cfg(feature = "lift")]
@@ -1113,6 +2709,7 @@ fn cmd_lift(
     if !rpg_core::storage::rpg_exists(project_root) {
         anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
     }
+    rpg_nav::scope::validate(scope)?;
 
     // Resolve API key from arg or environment
     let api_key = api_key
@@ -1137,11 +2734,14 @@ fn cmd_lift(
     let provider = rpg_lift::create_provider(provider_name, &api_key, model, base_url)
         .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-    let mut graph = rpg_core::storage::load(project_root)?;
+    let mut graph = load_graph(project_root)?;
+    let tokens = RpgConfig::load(project_root).unwrap_or_default().tokens;
+    let estimator = rpg_encoder::tokens::from_config(&tokens, Some(provider_name), project_root);
 
     // Dry run: estimate cost and exit
     if dry_run {
-        let estimate = rpg_lift::estimate_cost(&graph, provider.as_ref(), project_root);
+        let estimate =
+            rpg_lift::estimate_cost(&graph, provider.as_ref(), project_root, estimator.as_ref());
         eprintln!("\n{}", estimate);
         return Ok(());
     }
@@ -1159,6 +2759,7 @@ fn cmd_lift(
         max_retries: 2,
         batch_size: 25,
         batch_tokens: 8000,
+        token_estimator: estimator.as_ref(),
     };
 
     let report =
@@ -1186,6 +2787,8 @@ fn cmd_lift(
         report.total_input_tokens, report.total_output_tokens
     );
     eprintln!("  Cost: ${:.4}", report.total_cost_usd);
+    let terms = rpg_nav::vocab::rebuild(project_root, &graph)?;
+    eprintln!("  Vocabulary: {} terms", terms);
 
     if !report.errors.is_empty() {
         eprintln!("\n  Warnings ({}):", report.errors.len());
@@ -1197,13 +2800,47 @@ fn cmd_lift(
     Ok(())
 }
 
-fn cmd_info(project_root: &Path) -> Result<()> {
+fn cmd_lift_files(project_root: &Path, action: LiftAction) -> Result<()> {
+    if !rpg_core::storage::rpg_exists(project_root) {
+        anyhow::bail!("No RPG found. Run `rpg-encoder build` first.");
+    }
+    match action {
+        LiftAction::ExportBatches { scope, out } => {
+            let summary = lift_batches::export(project_root, &scope, &out)?;
+            if summary.auto_lifted > 0 {
+                eprintln!("Auto-lifted {} trivial entities", summary.auto_lifted);
+            }
+            if summary.files.is_empty() {
+                eprintln!("No entities need LLM lifting for scope: {}", scope);
+                return Ok(());
+            }
+            eprintln!(
+                "Exported {} batches ({} entities) to {}",
+                summary.files.len(),
+                summary.entities,
+                out.display()
+            );
+            for file in &summary.files {
+                println!("{}", file.display());
+            }
+        }
+        LiftAction::ImportResults { files } => {
+            print!("{}", lift_batches::import(project_root, &files)?);
+        }
+    }
+    Ok(())
+}
+
+/// Unresolved import prefixes shown by `info --verbose` and `validate`.
+const UNRESOLVED_IMPORT_REPORT_LIMIT: usize = 20;
+
+fn cmd_info(project_root: &Path, verbose: bool, sources: bool) -> Result<()> {
     if !rpg_core::storage::rpg_exists(project_root) {
         eprintln!("No RPG found. Run `rpg-encoder build` first.");
         return Ok(());
     }
 
-    let graph = rpg_core::storage::load(project_root)?;
+    let (graph, report) = rpg_encoder::artifact::load_verified(project_root)?;
 
     println!("RPG v{}", graph.version);
     println!("Language: {}", graph.metadata.language);
@@ -1212,6 +2849,14 @@ fn cmd_info(project_root: &Path) -> Result<()> {
     if let Some(sha) = &graph.base_commit {
         println!("Base commit: {}", &sha[..8.min(sha.len())]);
     }
+    match report.fingerprint_matches {
+        Some(true) => println!("Fingerprint: matches HEAD"),
+        Some(false) => println!("Fingerprint: differs from HEAD"),
+        None => {}
+    }
+    if let Some(summary) = report.summary() {
+        println!("Note: {}", summary);
+    }
     println!();
     println!("Entities: {}", graph.metadata.total_entities);
     println!("Files: {}", graph.metadata.total_files);
@@ -1229,6 +2874,22 @@ fn cmd_info(project_root: &Path) -> Result<()> {
     println!("Dependency edges: {}", graph.metadata.dependency_edges);
     println!("Containment edges: {}", graph.metadata.containment_edges);
     println!("Total edges: {}", graph.metadata.total_edges);
+    let tags = graph.tag_counts();
+    if !tags.is_empty() {
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|(tag, n)| format!("{} ({})", tag, n))
+            .collect();
+        println!("Tags: {}", tags.join(", "));
+    }
+    let parse_errors = &graph.metadata.parse_errors;
+    if !parse_errors.is_empty() {
+        let files: Vec<String> = parse_errors
+            .iter()
+            .map(|(file, regions)| format!("{} ({})", file, regions))
+            .collect();
+        println!("Syntax errors: {}", files.join(", "));
+    }
     if let Some(summary) = &graph.metadata.repo_summary {
         println!("\nSummary: {}", summary);
     }
@@ -1243,5 +2904,50 @@ fn cmd_info(project_root: &Path) -> Result<()> {
         }
     }
 
+    let charters = graph.hierarchy_charters();
+    if !charters.is_empty() {
+        println!("\nCharters:");
+        for (path, charter) in &charters {
+            println!("  {}: {}", path, charter);
+        }
+    }
+
+    if verbose {
+        let table =
+            rpg_encoder::grounding::format_resolution_table(&graph.metadata.edge_resolution);
+        if !table.is_empty() {
+            println!("\nDependency resolution:");
+            print!("{}", table);
+        }
+        match &graph.metadata.unresolved_imports {
+            Some(report) => print!(
+                "\n{}",
+                rpg_encoder::unresolved_imports::format_report(
+                    report,
+                    UNRESOLVED_IMPORT_REPORT_LIMIT
+                )
+            ),
+            None => println!(
+                "\nunresolved_imports: not recorded (rebuild with `rpg-encoder build` to collect)"
+            ),
+        }
+    }
+
+    if sources {
+        let config = RpgConfig::load(project_root).unwrap_or_default();
+        let estimator = rpg_encoder::tokens::from_config(&config.tokens, None, project_root);
+        let report = rpg_encoder::feature_sources::analyze(
+            &graph,
+            &rpg_core::files::WorktreeFiles::new(project_root),
+            &config.encoding,
+            estimator.as_ref(),
+            chrono::Utc::now(),
+        );
+        print!(
+            "\nFeature sources:\n{}",
+            rpg_encoder::feature_sources::format_report(&report, usize::MAX)
+        );
+    }
+
     Ok(())
 }
@@ -0,0 +1,191 @@
+//! `rpg-encoder analyze`: single-file JSON analysis for editor integrations.
+
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn fixture(rel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../tests/fixtures")
+        .join(rel)
+}
+
+/// Run `analyze` in an empty directory with `stdin` piped in.
+fn analyze(args: &[&str], stdin: &str) -> (bool, String, String) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .current_dir(tmp.path())
+        .arg("analyze")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    // Analysis never creates a graph.
+    assert!(!tmp.path().join(".rpg").exists());
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+fn parse(stdout: &str) -> Value {
+    serde_json::from_str(stdout).unwrap_or_else(|e| panic!("{}: {}", e, stdout))
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value[key]
+        .as_str()
+        .unwrap_or_else(|| panic!("{} is not a string in {}", key, value))
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a Vec<Value> {
+    value[key]
+        .as_array()
+        .unwrap_or_else(|| panic!("{} is not an array in {}", key, value))
+}
+
+/// Check every field of the documented schema, returning the analysis.
+fn validate_schema(stdout: &str) -> Value {
+    let analysis = parse(stdout);
+    assert_eq!(analysis["schema_version"], 1);
+    str_field(&analysis, "file");
+    str_field(&analysis, "language");
+    for paradigm in array(&analysis, "paradigms") {
+        assert!(paradigm.is_string());
+    }
+    for entity in array(&analysis, "entities") {
+        for key in ["id", "name", "kind"] {
+            str_field(entity, key);
+        }
+        assert!(entity["line_start"].as_u64().unwrap() <= entity["line_end"].as_u64().unwrap());
+        if let Some(parent) = entity.get("parent") {
+            assert!(parent.is_string());
+        }
+        if let Some(signature) = entity.get("signature") {
+            for param in array(signature, "parameters") {
+                str_field(param, "name");
+            }
+        }
+    }
+    for import in array(&analysis, "imports") {
+        str_field(import, "module");
+        array(import, "symbols");
+    }
+    for call in array(&analysis, "calls") {
+        str_field(call, "caller");
+        str_field(call, "callee");
+    }
+    for inherit in array(&analysis, "inherits") {
+        str_field(inherit, "child");
+        str_field(inherit, "parent");
+    }
+    analysis
+}
+
+fn entity_ids(analysis: &Value) -> Vec<&str> {
+    array(analysis, "entities")
+        .iter()
+        .map(|e| str_field(e, "id"))
+        .collect()
+}
+
+#[test]
+fn test_analyze_stdin_fixture() {
+    let source = std::fs::read_to_string(fixture("python_project/src/auth/login.py")).unwrap();
+    let (ok, stdout, stderr) = analyze(
+        &[
+            "--lang",
+            "python",
+            "--stdin-filename",
+            "src/auth/login.py",
+            "-",
+        ],
+        &source,
+    );
+    assert!(ok, "{}", stderr);
+
+    let analysis = validate_schema(&stdout);
+    assert_eq!(analysis["file"], "src/auth/login.py");
+    assert_eq!(analysis["language"], "python");
+    let ids = entity_ids(&analysis);
+    assert!(ids.contains(&"src/auth/login.py:User"), "{:?}", ids);
+    assert!(
+        ids.contains(&"src/auth/login.py:User::is_admin"),
+        "{:?}",
+        ids
+    );
+    assert!(ids.contains(&"src/auth/login.py:authenticate"), "{:?}", ids);
+    // Entities come in source order.
+    let starts: Vec<u64> = array(&analysis, "entities")
+        .iter()
+        .map(|e| e["line_start"].as_u64().unwrap())
+        .collect();
+    assert!(starts.windows(2).all(|w| w[0] <= w[1]), "{:?}", starts);
+}
+
+#[test]
+fn test_analyze_path_with_paradigm() {
+    let path = fixture("nextjs_project/src/components/PostList.tsx");
+    let (ok, stdout, stderr) = analyze(&["--paradigm", "react", path.to_str().unwrap()], "");
+    assert!(ok, "{}", stderr);
+
+    let analysis = validate_schema(&stdout);
+    assert_eq!(analysis["language"], "typescript");
+    assert_eq!(analysis["paradigms"], serde_json::json!(["react"]));
+    let post_list = array(&analysis, "entities")
+        .iter()
+        .find(|e| e["name"] == "PostList")
+        .unwrap();
+    assert_eq!(post_list["kind"], "component");
+    assert!(
+        array(&analysis, "imports")
+            .iter()
+            .any(|i| i["module"] == "../state/api")
+    );
+    assert!(
+        array(&analysis, "calls")
+            .iter()
+            .any(|c| c["caller"] == "PostList" && c["callee"] == "useGetPostsQuery")
+    );
+}
+
+#[test]
+fn test_analyze_reports_inheritance() {
+    let source =
+        "class Base:\n    pass\n\n\nclass Admin(Base):\n    def grant(self):\n        audit()\n";
+    let (ok, stdout, stderr) = analyze(&["--stdin-filename", "app/users.py", "-"], source);
+    assert!(ok, "{}", stderr);
+
+    let analysis = validate_schema(&stdout);
+    assert_eq!(
+        analysis["inherits"],
+        serde_json::json!([{ "child": "Admin", "parent": "Base" }])
+    );
+    assert!(
+        array(&analysis, "calls")
+            .iter()
+            .any(|c| c["callee"] == "audit")
+    );
+}
+
+#[test]
+fn test_analyze_rejects_unknown_language_and_paradigm() {
+    let (ok, _, stderr) = analyze(&["-"], "fn main() {}\n");
+    assert!(!ok);
+    assert!(stderr.contains("pass --lang"), "{}", stderr);
+
+    let (ok, _, stderr) = analyze(&["--lang", "rust", "--paradigm", "nope", "-"], "");
+    assert!(!ok);
+    assert!(stderr.contains("unknown paradigm: nope"), "{}", stderr);
+}
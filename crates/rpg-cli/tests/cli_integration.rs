@@ -2,6 +2,7 @@
 //! Tests the underlying library functions that the CLI commands invoke.
 
 use rpg_core::graph::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str) -> Entity {
@@ -15,9 +16,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: vec!["test feature".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Core/test".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -64,7 +78,8 @@ fn test_search_on_saved_graph() {
         rpg_nav::search::SearchMode::Snippets,
         None,
         10,
-    );
+    )
+    .unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "main");
 }
@@ -0,0 +1,157 @@
+//! `rpg-encoder doctor` end to end: exit codes and report sections on a
+//! healthy graph and on one with integrity problems.
+
+use rpg_core::graph::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn make_entity(file: &str, name: &str, area: &str) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: vec![format!("{} things", name)],
+        feature_source: Some("llm".to_string()),
+        lifted_at: None,
+        hierarchy_path: area.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// A saved graph with two areas and one call edge between them.
+fn healthy_project() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut graph = RPGraph::new("rust");
+    for (file, name, area) in [
+        ("src/store.rs", "save", "Storage/write"),
+        ("src/store.rs", "load", "Storage/read"),
+        ("src/api.rs", "handle", "Api/routes"),
+    ] {
+        graph.insert_entity(make_entity(file, name, area));
+        graph.insert_into_hierarchy(area, &format!("{}:{}", file, name));
+    }
+    graph.edges.push(DependencyEdge {
+        source: "src/api.rs:handle".to_string(),
+        target: "src/store.rs:save".to_string(),
+        kind: EdgeKind::Invokes,
+    });
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+    rpg_core::storage::save(tmp.path(), &graph).unwrap();
+    tmp
+}
+
+/// The healthy graph plus a dangling edge and a hierarchy reference to a
+/// deleted entity.
+fn broken_project() -> tempfile::TempDir {
+    let tmp = healthy_project();
+    let mut graph = rpg_core::storage::load(tmp.path()).unwrap();
+    graph.edges.push(DependencyEdge {
+        source: "src/api.rs:handle".to_string(),
+        target: "src/gone.rs:vanished".to_string(),
+        kind: EdgeKind::Invokes,
+    });
+    graph.insert_into_hierarchy("Api/routes", "src/gone.rs:vanished");
+    rpg_core::storage::save(tmp.path(), &graph).unwrap();
+    tmp
+}
+
+/// Run doctor; returns the exit code and the report written to disk.
+fn doctor(root: &Path, fix: bool) -> (i32, String) {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"));
+    cmd.arg("--project").arg(root).arg("doctor");
+    if fix {
+        cmd.arg("--fix");
+    }
+    let output = cmd.output().unwrap();
+    let report = std::fs::read_to_string(root.join(".rpg/doctor-report.md")).unwrap();
+    (output.status.code().unwrap(), report)
+}
+
+const SECTIONS: &[&str] = &[
+    "## staleness",
+    "## validate",
+    "## hierarchy",
+    "## health",
+    "## embeddings",
+    "## queues",
+    "## stats-history",
+];
+
+#[test]
+fn test_doctor_healthy_graph_exits_zero() {
+    let tmp = healthy_project();
+    let (code, report) = doctor(tmp.path(), false);
+    assert_eq!(code, 0, "{}", report);
+    assert!(report.contains("Result: PASS (exit 0)"), "{}", report);
+    for section in SECTIONS {
+        assert!(report.contains(section), "missing {}:\n{}", section, report);
+    }
+    assert!(report.contains("| validate | pass | no integrity issues |"));
+    // Not a git repository, so there is nothing to diff against.
+    assert!(report.contains("| staleness | skip |"), "{}", report);
+
+    let history = std::fs::read_to_string(tmp.path().join(".rpg/stats-history.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(history.trim()).unwrap();
+    assert_eq!(record["entities"], 3);
+    assert_eq!(record["status"], "pass");
+}
+
+#[test]
+fn test_doctor_broken_graph_fails_until_fixed() {
+    let tmp = broken_project();
+    let (code, report) = doctor(tmp.path(), false);
+    assert_eq!(code, 2, "{}", report);
+    assert!(report.contains("Result: FAIL (exit 2)"), "{}", report);
+    assert!(report.contains("| validate | fail | 2 integrity issue(s)"));
+    assert!(report.contains("- dangling edge target: src/gone.rs:vanished"));
+    // Later checks still ran.
+    for section in SECTIONS {
+        assert!(report.contains(section), "missing {}:\n{}", section, report);
+    }
+
+    let (code, report) = doctor(tmp.path(), true);
+    assert_eq!(code, 0, "{}", report);
+    assert!(report.contains("repaired 2 of 2 integrity issue(s), 0 remaining"));
+    let graph = rpg_core::storage::load(tmp.path()).unwrap();
+    assert_eq!(graph.edges.len(), 1);
+
+    let (code, _) = doctor(tmp.path(), false);
+    assert_eq!(code, 0);
+    let history = std::fs::read_to_string(tmp.path().join(".rpg/stats-history.jsonl")).unwrap();
+    let statuses: Vec<String> = history
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap()["status"].to_string())
+        .collect();
+    assert_eq!(statuses, vec!["\"fail\"", "\"pass\"", "\"pass\""]);
+}
+
+#[test]
+fn test_doctor_without_graph_fails() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (code, report) = doctor(tmp.path(), false);
+    assert_eq!(code, 2);
+    assert!(
+        report.contains("| graph | fail | No RPG found."),
+        "{}",
+        report
+    );
+}
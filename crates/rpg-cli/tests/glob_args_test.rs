@@ -0,0 +1,48 @@
+//! Malformed glob arguments are reported as errors rather than panics.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+#[test]
+fn test_malformed_globs_fail_without_panicking() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/app.py"), "def run():\n    return 1\n").unwrap();
+
+    let (ok, log) = rpg(root, &["build", "--include", "src/["]);
+    assert!(!ok, "{}", log);
+    assert!(
+        log.contains("--include: Invalid glob pattern 'src/['"),
+        "{}",
+        log
+    );
+    assert!(!log.contains("panicked"), "{}", log);
+
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+
+    let (ok, log) = rpg(root, &["search", "run", "--file-pattern", "{a,b"]);
+    assert!(!ok, "{}", log);
+    assert!(
+        log.contains("--file-pattern: Invalid glob pattern '{a,b'"),
+        "{}",
+        log
+    );
+    assert!(!log.contains("panicked"), "{}", log);
+}
@@ -0,0 +1,87 @@
+//! File-level import cycles: reported by `build` and `validate`, counted in
+//! the graph metadata, and optionally fatal for `validate`.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+/// Two Python modules in `pkg/` that import each other.
+fn project() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    let pkg = tmp.path().join("pkg");
+    std::fs::create_dir_all(&pkg).unwrap();
+    std::fs::write(
+        pkg.join("a.py"),
+        "from b import g\n\ndef f():\n    return g()\n",
+    )
+    .unwrap();
+    std::fs::write(
+        pkg.join("b.py"),
+        "from a import f\n\ndef g():\n    return 1\n\ndef h():\n    return f()\n",
+    )
+    .unwrap();
+    tmp
+}
+
+#[test]
+fn test_two_file_import_cycle_reported() {
+    let tmp = project();
+    let root = tmp.path();
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+    assert!(log.contains("Import cycles: 1"), "{}", log);
+    assert!(log.contains("pkg/a.py → pkg/b.py → pkg/a.py"), "{}", log);
+    let graph = rpg_core::storage::load(root).unwrap();
+    assert_eq!(graph.metadata.import_cycles, 1);
+
+    let (ok, log) = rpg(root, &["validate"]);
+    assert!(ok, "{}", log);
+    assert!(log.contains("pkg/a.py → pkg/b.py → pkg/a.py"), "{}", log);
+
+    let (ok, log) = rpg(root, &["validate", "--fail-on-import-cycles"]);
+    assert!(!ok);
+    assert!(log.contains("1 import cycle(s) found"), "{}", log);
+
+    let (ok, log) = rpg(root, &["validate", "--format", "sarif"]);
+    assert!(ok, "{}", log);
+    assert!(log.contains("structure/import-cycle"), "{}", log);
+}
+
+#[test]
+fn test_same_directory_cycles_ignored_by_config() {
+    let tmp = project();
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join(".rpg")).unwrap();
+    std::fs::write(
+        root.join(".rpg/config.toml"),
+        "[encoding]\nignore_same_directory_import_cycles = true\n",
+    )
+    .unwrap();
+
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+    assert!(!log.contains("Import cycles"), "{}", log);
+    assert_eq!(
+        rpg_core::storage::load(root)
+            .unwrap()
+            .metadata
+            .import_cycles,
+        0
+    );
+
+    let (ok, log) = rpg(root, &["validate", "--fail-on-import-cycles"]);
+    assert!(ok, "{}", log);
+}
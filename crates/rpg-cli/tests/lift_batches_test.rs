@@ -0,0 +1,216 @@
+//! `rpg-encoder lift export-batches` / `lift import-results` round trip:
+//! exported batches match the interactive queue, and imported features land
+//! on the graph.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+const LIB_RS: &str = r"pub fn parse_header(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim().to_lowercase();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key, value.trim().to_string()))
+}
+
+pub fn parse_headers(text: &str) -> Vec<(String, String)> {
+    text.lines().filter_map(parse_header).collect()
+}
+
+pub fn checksum(data: &[u8]) -> u32 {
+    let mut sum = 0u32;
+    for (i, b) in data.iter().enumerate() {
+        sum = sum.wrapping_mul(31).wrapping_add(*b as u32 ^ i as u32);
+    }
+    sum
+}
+";
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+/// A built project whose token budget puts each function in its own batch.
+fn project() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/lib.rs"), LIB_RS).unwrap();
+    let (ok, out) = rpg(tmp.path(), &["build"]);
+    assert!(ok, "{}", out);
+    std::fs::write(
+        tmp.path().join(".rpg/config.toml"),
+        "[encoding]\nmax_batch_tokens = 60\n",
+    )
+    .unwrap();
+    tmp
+}
+
+/// Entity IDs from the `### <id> (<Kind>)` headers of a batch file.
+fn batch_ids(markdown: &str) -> Vec<String> {
+    markdown
+        .lines()
+        .filter_map(|l| l.strip_prefix("### "))
+        .filter_map(|l| l.split_once(" (").map(|(id, _)| id.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_export_and_import_round_trip() {
+    let tmp = project();
+    let root = tmp.path();
+    let out = root.join("lift-batches");
+
+    // Batch boundaries come from the same queue the MCP session uses.
+    let mut graph = rpg_core::storage::load(root).unwrap();
+    let config = rpg_core::config::RpgConfig::load(root).unwrap();
+    let queue = rpg_encoder::lift_queue::build_lift_queue(
+        &mut graph,
+        "*",
+        root,
+        &config.encoding,
+        &HashSet::new(),
+        &rpg_encoder::tokens::Heuristic,
+    )
+    .unwrap();
+    assert_eq!(queue.batch_ranges.len(), 3);
+
+    let (ok, log) = rpg(
+        root,
+        &[
+            "lift",
+            "export-batches",
+            "--scope",
+            "*",
+            "--out",
+            out.to_str().unwrap(),
+        ],
+    );
+    assert!(ok, "{}", log);
+    assert!(log.contains("Exported 3 batches (3 entities)"), "{}", log);
+
+    let mut results = Vec::new();
+    for index in 0..3 {
+        let name = format!("batch-{:03}.md", index + 1);
+        let markdown = std::fs::read_to_string(out.join(&name)).unwrap();
+        assert!(markdown.starts_with(&format!("BATCH {}/3 (1 entities)", index + 1)));
+        // Instructions ride on the first batch only, as in the interactive flow.
+        assert_eq!(markdown.contains("<repo_info>"), index == 0, "{}", name);
+
+        let ids = batch_ids(&markdown);
+        let expected: Vec<String> = queue.batch(index).unwrap().iter().map(|r| r.id()).collect();
+        assert_eq!(ids, expected);
+
+        let features: HashMap<&str, Vec<String>> = ids
+            .iter()
+            .map(|id| {
+                (
+                    id.as_str(),
+                    vec![format!("Handle {}", id.rsplit(':').next().unwrap())],
+                )
+            })
+            .collect();
+        let path = root.join(format!("batch-{:03}.json", index + 1));
+        std::fs::write(&path, serde_json::to_string(&features).unwrap()).unwrap();
+        results.push(path);
+    }
+    std::fs::write(
+        root.join("extra.json"),
+        r#"{"src/lib.rs:vanished": ["do nothing"]}"#,
+    )
+    .unwrap();
+    results.push(root.join("extra.json"));
+
+    let mut args = vec!["lift", "import-results"];
+    args.extend(results.iter().map(|p| p.to_str().unwrap()));
+    let (ok, report) = rpg(root, &args);
+    assert!(ok, "{}", report);
+    assert!(
+        report.contains("Imported 4 result file(s): 3 entities updated, 1 unmatched key(s)"),
+        "{}",
+        report
+    );
+    assert!(report.contains("extra.json: 0 updated, 1 unmatched"));
+    assert!(report.contains("  src/lib.rs:vanished\n"));
+    assert!(report.contains("coverage: 3/3 (100%)"), "{}", report);
+
+    let graph = rpg_core::storage::load(root).unwrap();
+    let checksum = &graph.entities["src/lib.rs:checksum"];
+    assert_eq!(checksum.semantic_features, vec!["handle checksum"]);
+    assert_eq!(checksum.feature_source.as_deref(), Some("llm"));
+
+    // Re-lifting with different features goes through drift detection.
+    std::fs::write(
+        root.join("relift.json"),
+        r#"{"src/lib.rs:checksum": ["mix bytes into hash"]}"#,
+    )
+    .unwrap();
+    let (ok, report) = rpg(
+        root,
+        &[
+            "lift",
+            "import-results",
+            root.join("relift.json").to_str().unwrap(),
+        ],
+    );
+    assert!(ok, "{}", report);
+    assert!(
+        report.contains("src/lib.rs:checksum drifted (1.00; ignore 0.30, auto 0.70)"),
+        "{}",
+        report
+    );
+
+    // Nothing left to export once everything is lifted; old batches go.
+    let (ok, log) = rpg(
+        root,
+        &["lift", "export-batches", "--out", out.to_str().unwrap()],
+    );
+    assert!(ok, "{}", log);
+    assert!(
+        log.contains("No entities need LLM lifting for scope: *"),
+        "{}",
+        log
+    );
+    assert_eq!(std::fs::read_dir(&out).unwrap().count(), 0);
+}
+
+#[test]
+fn test_import_rejects_malformed_file_before_applying() {
+    let tmp = project();
+    let root = tmp.path();
+    std::fs::write(
+        root.join("good.json"),
+        r#"{"src/lib.rs:checksum": ["compute checksum"]}"#,
+    )
+    .unwrap();
+    std::fs::write(root.join("bad.json"), "not json").unwrap();
+
+    let (ok, log) = rpg(
+        root,
+        &[
+            "lift",
+            "import-results",
+            root.join("good.json").to_str().unwrap(),
+            root.join("bad.json").to_str().unwrap(),
+        ],
+    );
+    assert!(!ok);
+    assert!(log.contains("invalid features JSON in"), "{}", log);
+    let graph = rpg_core::storage::load(root).unwrap();
+    assert!(
+        graph.entities["src/lib.rs:checksum"]
+            .semantic_features
+            .is_empty()
+    );
+}
@@ -0,0 +1,48 @@
+//! A file with syntax errors is reported by `build` and `info` without
+//! failing the build.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+#[test]
+fn test_build_reports_broken_fixture() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/fixtures/broken_syntax");
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    for name in ["ok.py", "broken.py"] {
+        std::fs::copy(fixture.join(name), root.join(name)).unwrap();
+    }
+
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+    assert!(
+        log.contains("Warning: 1 file had syntax errors (1 region skipped)"),
+        "{}",
+        log
+    );
+    assert!(log.contains("broken.py: lines 9-16"), "{}", log);
+
+    let graph = rpg_core::storage::load(root).unwrap();
+    assert_eq!(graph.metadata.parse_errors.get("broken.py"), Some(&1));
+    assert!(!graph.metadata.parse_errors.contains_key("ok.py"));
+    assert!(graph.entities.contains_key("ok.py:greet"));
+    assert!(graph.entities.contains_key("broken.py:load"));
+
+    let (ok, log) = rpg(root, &["info"]);
+    assert!(ok, "{}", log);
+    assert!(log.contains("Syntax errors: broken.py (1)"), "{}", log);
+}
@@ -0,0 +1,152 @@
+//! `build`, `fetch`, and `search` with `--rev` against a bare repository:
+//! sources come from git objects, never a checkout.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+/// Write `files`, commit them on HEAD, and tag the commit.
+fn commit(repo: &git2::Repository, files: &[(&str, &str)], tag: &str) {
+    let root = repo.workdir().unwrap();
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo
+        .commit(Some("HEAD"), &sig, &sig, tag, &tree, &parents)
+        .unwrap();
+    repo.tag_lightweight(tag, &repo.find_object(oid, None).unwrap(), false)
+        .unwrap();
+}
+
+/// A bare mirror of a two-commit repository tagged `v1` and `v2`.
+fn bare_mirror(dir: &Path) -> std::path::PathBuf {
+    let work = dir.join("work");
+    let repo = git2::Repository::init(&work).unwrap();
+    commit(
+        &repo,
+        &[
+            (
+                "app/service.py",
+                "from app.store import save\n\ndef handle(order):\n    return save(order)\n",
+            ),
+            ("app/store.py", "def save(order):\n    return order\n"),
+            (".rpgignore", "scratch/\n"),
+            ("scratch/notes.py", "def scribble():\n    pass\n"),
+        ],
+        "v1",
+    );
+    commit(
+        &repo,
+        &[(
+            "app/store.py",
+            "def save(order):\n    return dict(order)\n\ndef purge(order):\n    return None\n",
+        )],
+        "v2",
+    );
+
+    let bare = dir.join("mirror.git");
+    git2::build::RepoBuilder::new()
+        .bare(true)
+        .clone(work.to_str().unwrap(), &bare)
+        .unwrap();
+    bare
+}
+
+#[test]
+fn test_build_and_fetch_from_bare_revision() {
+    let tmp = tempfile::tempdir().unwrap();
+    let bare = bare_mirror(tmp.path());
+
+    let (ok, log) = rpg(&bare, &["build", "--rev", "v1"]);
+    assert!(ok, "{}", log);
+    let graph = rpg_core::storage::load(&bare).unwrap();
+    assert!(graph.entities.contains_key("app/service.py:handle"));
+    assert!(graph.entities.contains_key("app/store.py:save"));
+    // v2's function and `.rpgignore`d files are not indexed.
+    assert!(!graph.entities.contains_key("app/store.py:purge"));
+    assert!(!graph.entities.contains_key("scratch/notes.py:scribble"));
+    // Paths stay repo-relative and calls still resolve.
+    assert_eq!(
+        graph.entities["app/service.py:handle"].file,
+        Path::new("app/service.py")
+    );
+    assert!(
+        graph
+            .edges
+            .iter()
+            .any(|e| e.source == "app/service.py:handle"
+                && e.target == "app/store.py:save"
+                && e.kind == rpg_core::graph::EdgeKind::Invokes)
+    );
+    let v1 = git2::Repository::open_bare(&bare)
+        .unwrap()
+        .revparse_single("v1")
+        .unwrap()
+        .id()
+        .to_string();
+    assert_eq!(graph.base_commit.as_deref(), Some(v1.as_str()));
+
+    let (ok, out) = rpg(&bare, &["fetch", "app/service.py:handle", "--rev", "v1"]);
+    assert!(ok, "{}", out);
+    assert!(out.contains("return save(order)"), "{}", out);
+
+    let (ok, out) = rpg(&bare, &["fetch", "--file", "app/store.py", "--rev", "v2"]);
+    assert!(ok, "{}", out);
+    assert!(out.contains("def save(order):"), "{}", out);
+
+    let (ok, out) = rpg(&bare, &["fetch", "app/service.py:handle", "--rev", "nope"]);
+    assert!(!ok);
+    assert!(out.contains("unknown revision: nope"), "{}", out);
+}
+
+#[test]
+fn test_search_changed_since_up_to_revision() {
+    let tmp = tempfile::tempdir().unwrap();
+    let bare = bare_mirror(tmp.path());
+    let (ok, log) = rpg(&bare, &["build", "--rev", "v1"]);
+    assert!(ok, "{}", log);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(&bare)
+        .args(["search", "save", "--rev", "v2", "--changed-since", "v1"])
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let outcome: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = outcome["results"].as_array().unwrap();
+    // Only v1...v2 hunks count, and the graph predates v2.
+    assert!(!results.is_empty());
+    assert!(
+        results
+            .iter()
+            .all(|r| r["file"] == "app/store.py" && r["stale"] == true),
+        "{}",
+        outcome
+    );
+}
@@ -0,0 +1,47 @@
+//! Extensionless scripts are parsed by `build` when their shebang names a
+//! supported interpreter.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+#[test]
+fn test_build_parses_shebang_scripts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::write(root.join("app.py"), "def run():\n    return 1\n").unwrap();
+    std::fs::write(
+        root.join("bin/deploy"),
+        "#!/usr/bin/env python3\nfrom app import run\n\ndef deploy():\n    return run()\n",
+    )
+    .unwrap();
+    let mut binary = b"#!/usr/bin/env python3\n".to_vec();
+    binary.extend([0u8, 1, 2, 3]);
+    std::fs::write(root.join("bin/blob"), binary).unwrap();
+    std::fs::write(root.join("Makefile"), "all:\n\tpython app.py\n").unwrap();
+
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+    let graph = rpg_core::storage::load(root).unwrap();
+    assert!(graph.entities.contains_key("bin/deploy:deploy"));
+    assert!(
+        graph
+            .entities
+            .values()
+            .all(|e| e.file != Path::new("bin/blob") && e.file != Path::new("Makefile"))
+    );
+}
@@ -0,0 +1,54 @@
+//! `rpg-encoder validate`: integrity findings against the working tree.
+
+use std::path::Path;
+use std::process::Command;
+
+fn rpg(root: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_rpg-encoder"))
+        .arg("--project")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned()
+            + &String::from_utf8_lossy(&output.stderr),
+    )
+}
+
+#[test]
+fn test_validate_flags_grounded_paths_missing_on_disk() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let net = root.join("src/net");
+    std::fs::create_dir_all(&net).unwrap();
+    std::fs::write(net.join("http.py"), "def get(url):\n    return url\n").unwrap();
+    std::fs::write(
+        net.join("socket.py"),
+        "def connect(host):\n    return host\n",
+    )
+    .unwrap();
+
+    let (ok, log) = rpg(root, &["build"]);
+    assert!(ok, "{}", log);
+    let (ok, log) = rpg(root, &["validate"]);
+    assert!(ok, "{}", log);
+    assert!(!log.contains("grounded to missing path"), "{}", log);
+
+    std::fs::rename(&net, root.join("src/network")).unwrap();
+    let (ok, log) = rpg(root, &["validate"]);
+    assert!(ok, "{}", log);
+    assert!(
+        log.contains("is grounded to missing path: src/net"),
+        "{}",
+        log
+    );
+
+    let (_, sarif) = rpg(root, &["validate", "--format", "sarif"]);
+    assert!(
+        sarif.contains("integrity/grounded-path-missing"),
+        "{}",
+        sarif
+    );
+}
@@ -1,5 +1,6 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use rpg_core::graph::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hint::black_box;
 use std::path::PathBuf;
 
@@ -14,9 +15,22 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>) -> Entity
         parent_class: None,
         semantic_features: features.into_iter().map(String::from).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: format!("Area/category/{}", name),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -2,9 +2,11 @@
 //!
 //! Load order: `.rpg/config.toml` → environment variables → defaults.
 
+use crate::graph::{DEFAULT_LIFTABLE_KINDS, DEFAULT_MAX_NODE_FEATURES, EntityKind};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 /// Top-level RPG configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -13,6 +15,78 @@ pub struct RpgConfig {
     pub encoding: EncodingConfig,
     pub navigation: NavigationConfig,
     pub storage: StorageConfig,
+    pub risk: RiskConfig,
+    pub staleness: StalenessConfig,
+    pub mcp: McpConfig,
+    pub tokens: TokensConfig,
+}
+
+/// MCP server output configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpConfig {
+    /// How much next-step coaching tools append to their output.
+    pub guidance: GuidanceMode,
+    /// Largest tool response, in bytes. Longer responses are split: the first
+    /// chunk ends with a `continuation: <token>` line and `continue_output`
+    /// returns the rest. Unset means no limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_bytes: Option<usize>,
+    /// Per-tool overrides of `max_response_bytes`, by tool name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tool_max_response_bytes: BTreeMap<String, usize>,
+}
+
+/// Smallest accepted response limit: room for a continuation line and some output.
+pub const MIN_RESPONSE_BYTES: usize = 256;
+
+impl McpConfig {
+    /// Response limit for `tool`: its override, else `max_response_bytes`.
+    pub fn max_response_bytes_for(&self, tool: &str) -> Option<usize> {
+        self.tool_max_response_bytes
+            .get(tool)
+            .copied()
+            .or(self.max_response_bytes)
+    }
+}
+
+/// Next-step guidance appended to MCP tool output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GuidanceMode {
+    /// Full NEXT STEP blocks, including dispatch recipes and fallbacks.
+    #[default]
+    Full,
+    /// A single `next: <tool>` line.
+    Terse,
+    /// No next-step guidance.
+    Off,
+}
+
+impl GuidanceMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Terse => "terse",
+            Self::Off => "off",
+        }
+    }
+}
+
+impl std::str::FromStr for GuidanceMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "terse" => Ok(Self::Terse),
+            "off" => Ok(Self::Off),
+            other => anyhow::bail!(
+                "unknown guidance mode '{}' (expected full, terse, or off)",
+                other
+            ),
+        }
+    }
 }
 
 /// Storage configuration.
@@ -24,9 +98,178 @@ pub struct StorageConfig {
     pub compress: bool,
 }
 
+/// Change-risk scoring thresholds (staleness notices, `update_rpg`, `rpg-encoder diff`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Fan-in (distinct dependents in other files) at which a changed file is high risk.
+    pub high_fan_in: usize,
+    /// Fan-in at which a changed file is medium risk.
+    pub medium_fan_in: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            high_fan_in: 10,
+            medium_fan_in: 3,
+        }
+    }
+}
+
+/// What staleness notices and `rpg-encoder diff` measure changes from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StalenessConfig {
+    pub compare_to: CompareTo,
+}
+
+/// A comparison point for staleness, written as `base_commit`,
+/// `merge-base:<rev>`, or any other revision.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CompareTo {
+    /// The commit the graph was built or last updated at.
+    #[default]
+    BaseCommit,
+    /// Where HEAD forked from a mainline, e.g. `merge-base:origin/main`, so a
+    /// long-lived branch sees only its own changes.
+    MergeBase(String),
+    /// A revision (branch, tag, or commit) as given.
+    Rev(String),
+}
+
+impl std::fmt::Display for CompareTo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BaseCommit => f.write_str("base_commit"),
+            Self::MergeBase(rev) => write!(f, "merge-base:{}", rev),
+            Self::Rev(rev) => f.write_str(rev),
+        }
+    }
+}
+
+impl std::str::FromStr for CompareTo {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            anyhow::bail!(
+                "staleness.compare_to is empty (expected base_commit, merge-base:<rev>, or a revision)"
+            );
+        }
+        if s == "base_commit" {
+            return Ok(Self::BaseCommit);
+        }
+        match s.strip_prefix("merge-base:") {
+            Some(rev) if rev.trim().is_empty() => {
+                anyhow::bail!(
+                    "staleness.compare_to 'merge-base:' needs a revision, e.g. merge-base:origin/main"
+                )
+            }
+            Some(rev) => Ok(Self::MergeBase(rev.trim().to_string())),
+            None => Ok(Self::Rev(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for CompareTo {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<CompareTo> for String {
+    fn from(target: CompareTo) -> Self {
+        target.to_string()
+    }
+}
+
+/// How token counts are estimated for lifting batches, cost estimates, and
+/// `context_pack` budgets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokensConfig {
+    pub estimator: EstimatorKind,
+    /// Characters per token for the `ratio` estimator.
+    pub chars_per_token: f64,
+    /// Per-provider overrides of `chars_per_token`, keyed by provider name
+    /// (`[tokens.provider_chars_per_token]`, e.g. `anthropic = 3.0`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub provider_chars_per_token: BTreeMap<String, f64>,
+    /// Provider assumed when the caller does not name one (interactive
+    /// lifting, `context_pack`). `auto_lift` uses its own provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// HuggingFace `tokenizer.json` for the `bpe` estimator, relative to the
+    /// project root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bpe_tokenizer: Option<PathBuf>,
+}
+
+impl Default for TokensConfig {
+    fn default() -> Self {
+        Self {
+            estimator: EstimatorKind::Heuristic,
+            chars_per_token: 3.5,
+            provider_chars_per_token: BTreeMap::new(),
+            provider: None,
+            bpe_tokenizer: None,
+        }
+    }
+}
+
+impl TokensConfig {
+    /// The ratio for `provider` (else the configured `provider`), falling
+    /// back to `chars_per_token`, with the provider it was configured for.
+    pub fn chars_per_token_for(&self, provider: Option<&str>) -> (f64, Option<String>) {
+        let provider = provider.or(self.provider.as_deref());
+        match provider.and_then(|p| self.provider_chars_per_token.get_key_value(p)) {
+            Some((name, ratio)) => (*ratio, Some(name.clone())),
+            None => (self.chars_per_token, None),
+        }
+    }
+}
+
+/// Token estimator selected by `tokens.estimator`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EstimatorKind {
+    /// Four bytes per token.
+    #[default]
+    Heuristic,
+    /// A fixed characters-per-token ratio, configurable per provider.
+    Ratio,
+    /// Byte-pair encoding with `tokens.bpe_tokenizer` (needs the `bpe` feature).
+    Bpe,
+}
+
+impl std::str::FromStr for EstimatorKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "heuristic" => Ok(Self::Heuristic),
+            "ratio" => Ok(Self::Ratio),
+            "bpe" => Ok(Self::Bpe),
+            other => anyhow::bail!(
+                "unknown token estimator '{}' (expected heuristic, ratio, or bpe)",
+                other
+            ),
+        }
+    }
+}
+
+/// Default for [`EncodingConfig::routing_neighbor_weight`].
+pub const DEFAULT_ROUTING_NEIGHBOR_WEIGHT: f64 = 0.4;
+
 /// Encoding pipeline configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct EncodingConfig {
     /// Maximum number of entities per batch (hard cap).
     pub batch_size: usize,
@@ -49,14 +292,206 @@ pub struct EncodingConfig {
     /// Maximum depth for the structural file-path fallback hierarchy.
     /// The semantic hierarchy is always 3-level per paper spec.
     pub max_hierarchy_depth: usize,
+    /// Store a MinHash signature of each entity's source at build time so
+    /// source-similarity duplicate detection needs no disk reads.
+    pub source_signatures: bool,
+    /// Share of the routing score that comes from where an entity's dependency
+    /// neighbors live, versus feature similarity (0.0–1.0).
+    pub routing_neighbor_weight: f64,
+    /// Leave circular imports out of build reports when every file in the
+    /// cycle lives in the same directory (package-internal cycles).
+    pub ignore_same_directory_import_cycles: bool,
+    /// A lift submission is flagged when more than this many entities share
+    /// the same (or nearly the same) feature set.
+    pub copied_features_max_entities: usize,
+    /// Word-level Jaccard similarity at which two feature sets count as
+    /// copies of each other (0.0–1.0).
+    pub copied_features_similarity: f64,
+    /// Entity kinds that count toward lifting coverage and are sent for
+    /// lifting (snake_case names, e.g. `["function", "class", "method", "enum"]`).
+    /// Modules are aggregated from their files and cannot be listed.
+    pub liftable_kinds: Vec<EntityKind>,
+    /// Overrides for parts of the hierarchy, keyed by hierarchy path prefix
+    /// (`[encoding.per_area."Core/parsing"]`). The longest matching prefix wins.
+    pub per_area: BTreeMap<String, AreaOverride>,
+    /// Globs (relative to the project root) for test fixture files. They are
+    /// indexed structurally and tagged `fixture`, but not lifted, synthesized,
+    /// or placed in the semantic hierarchy, and search skips them by default.
+    pub fixture_globs: Vec<String>,
+    /// Skip files `.gitattributes` marks `linguist-vendored`,
+    /// `linguist-generated`, or `linguist-documentation` when collecting
+    /// source files.
+    pub honor_gitattributes: bool,
+    /// Index the files of git submodules and other nested repositories, with
+    /// change detection asking each one's own git state. When off, nested
+    /// repositories are skipped and only listed in the graph metadata.
+    pub include_submodules: bool,
+    /// Post-processing of submitted features
+    /// (`[encoding.feature_normalization]`).
+    pub feature_normalization: FeatureNormalizationConfig,
+    /// Most features each hierarchy node keeps when entity features are
+    /// aggregated into it, heaviest first; 0 keeps all.
+    pub max_node_features: usize,
+    /// Naming rules for submitted hierarchy paths
+    /// (`[encoding.hierarchy_style]`).
+    pub hierarchy_style: HierarchyStyleConfig,
+}
+
+/// How submitted features are cleaned up before they are stored. Features
+/// are always trimmed, lowercased, and deduplicated; these options add to that.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureNormalizationConfig {
+    /// Split a feature on top-level `" and "` or `;` when every part reads as
+    /// its own verb-object phrase (`"parse config and validate input"`).
+    pub split_compound: bool,
+    /// Most features kept per entity; the most distinct by word overlap are
+    /// kept. Unset keeps all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_features_per_entity: Option<usize>,
+    /// Longest feature, in words; longer features are cut. Unset keeps all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_words_per_feature: Option<usize>,
+}
+
+impl Default for FeatureNormalizationConfig {
+    fn default() -> Self {
+        Self {
+            split_compound: true,
+            max_features_per_entity: None,
+            max_words_per_feature: None,
+        }
+    }
+}
+
+/// What happens to a submitted hierarchy path that breaks the style guide.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HierarchyStyleMode {
+    /// No style checks; any 3-level path is accepted.
+    #[default]
+    Off,
+    /// Refuse the submission, listing every violation.
+    Reject,
+    /// Fix casing and separators, report each rewrite, and refuse only what
+    /// can't be fixed (word counts, banned names, missing verbs).
+    Normalize,
+}
+
+/// Naming rules for hierarchy paths submitted by `submit_hierarchy` and
+/// `submit_routing_decisions`. The rules are stated in the hierarchy prompt
+/// whenever `mode` is not `off`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HierarchyStyleConfig {
+    pub mode: HierarchyStyleMode,
+    /// Areas are PascalCase (`DataAccess`).
+    pub pascal_case_areas: bool,
+    /// Categories and subcategories are lowercase words separated by single
+    /// spaces (`manage sessions`).
+    pub lowercase_categories: bool,
+    /// Categories and subcategories open with a verb.
+    pub verb_phrases: bool,
+    /// Most words in an area name.
+    pub max_area_words: usize,
+    /// Most words in a category or subcategory.
+    pub max_category_words: usize,
+    /// Segment names refused at any level, compared case-insensitively.
+    pub banned_names: Vec<String>,
 }
 
+/// Default `encoding.hierarchy_style.banned_names`.
+pub const DEFAULT_BANNED_HIERARCHY_NAMES: &[&str] = &[
+    "utils", "util", "misc", "other", "others", "general", "common", "helpers", "stuff",
+];
+
+impl Default for HierarchyStyleConfig {
+    fn default() -> Self {
+        Self {
+            mode: HierarchyStyleMode::Off,
+            pascal_case_areas: true,
+            lowercase_categories: true,
+            verb_phrases: true,
+            max_area_words: 3,
+            max_category_words: 5,
+            banned_names: DEFAULT_BANNED_HIERARCHY_NAMES
+                .iter()
+                .map(|n| n.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Encoding settings for one hierarchy area. Unset fields fall back to the
+/// global [`EncodingConfig`] values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AreaOverride {
+    pub drift_ignore_threshold: Option<f64>,
+    pub drift_auto_threshold: Option<f64>,
+    pub batch_size: Option<usize>,
+    /// Whether confident entities may be auto-lifted without the agent.
+    pub auto_lift: Option<bool>,
+}
+
+/// Effective encoding settings for an entity, from [`EncodingConfig::for_area`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaSettings {
+    /// The `per_area` key that matched, if any.
+    pub area: Option<String>,
+    pub drift_ignore_threshold: f64,
+    pub drift_auto_threshold: f64,
+    pub batch_size: usize,
+    pub auto_lift: bool,
+}
+
+impl EncodingConfig {
+    /// Settings for an entity at `hierarchy_path`. A prefix matches whole
+    /// segments only: `Core` covers `Core/parsing` but not `CoreUtils`.
+    pub fn for_area(&self, hierarchy_path: &str) -> AreaSettings {
+        let matched = self
+            .per_area
+            .iter()
+            .filter(|(prefix, _)| area_matches(prefix, hierarchy_path))
+            .max_by_key(|(prefix, _)| prefix.trim_end_matches('/').len());
+        let area = matched.map(|(_, o)| o);
+        AreaSettings {
+            area: matched.map(|(prefix, _)| prefix.clone()),
+            drift_ignore_threshold: area
+                .and_then(|o| o.drift_ignore_threshold)
+                .unwrap_or(self.drift_ignore_threshold),
+            drift_auto_threshold: area
+                .and_then(|o| o.drift_auto_threshold)
+                .unwrap_or(self.drift_auto_threshold),
+            batch_size: area.and_then(|o| o.batch_size).unwrap_or(self.batch_size),
+            auto_lift: area.and_then(|o| o.auto_lift).unwrap_or(true),
+        }
+    }
+}
+
+fn area_matches(prefix: &str, hierarchy_path: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    prefix.is_empty()
+        || hierarchy_path == prefix
+        || hierarchy_path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Default `encoding.fixture_globs`.
+pub const DEFAULT_FIXTURE_GLOBS: &[&str] =
+    &["tests/fixtures/**", "**/__fixtures__/**", "testdata/**"];
+
 /// Navigation and search configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct NavigationConfig {
     /// Maximum number of search results returned.
     pub search_result_limit: usize,
+    /// Wall-clock budget for one explore, impact, or search tool call, in
+    /// seconds. Past it the call returns what it has so far and says how to
+    /// resume.
+    pub max_tool_seconds: f64,
 }
 
 impl Default for EncodingConfig {
@@ -70,6 +505,22 @@ impl Default for EncodingConfig {
             drift_auto_threshold: 0.7,
             broadcast_imports: false,
             max_hierarchy_depth: 3,
+            source_signatures: false,
+            routing_neighbor_weight: DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+            ignore_same_directory_import_cycles: false,
+            copied_features_max_entities: 5,
+            copied_features_similarity: 0.8,
+            liftable_kinds: DEFAULT_LIFTABLE_KINDS.to_vec(),
+            per_area: BTreeMap::new(),
+            fixture_globs: DEFAULT_FIXTURE_GLOBS
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+            honor_gitattributes: true,
+            include_submodules: false,
+            feature_normalization: FeatureNormalizationConfig::default(),
+            max_node_features: DEFAULT_MAX_NODE_FEATURES,
+            hierarchy_style: HierarchyStyleConfig::default(),
         }
     }
 }
@@ -78,6 +529,7 @@ impl Default for NavigationConfig {
     fn default() -> Self {
         Self {
             search_result_limit: 10,
+            max_tool_seconds: 30.0,
         }
     }
 }
@@ -123,10 +575,21 @@ impl RpgConfig {
             &mut config.encoding.hierarchy_chunk_size,
         );
         env_override("RPG_DRIFT_THRESHOLD", &mut config.encoding.drift_threshold);
+        env_override(
+            "RPG_ROUTING_NEIGHBOR_WEIGHT",
+            &mut config.encoding.routing_neighbor_weight,
+        );
         env_override(
             "RPG_SEARCH_LIMIT",
             &mut config.navigation.search_result_limit,
         );
+        env_override(
+            "RPG_MAX_TOOL_SECONDS",
+            &mut config.navigation.max_tool_seconds,
+        );
+        env_override("RPG_GUIDANCE", &mut config.mcp.guidance);
+        env_override("RPG_STALENESS_COMPARE_TO", &mut config.staleness.compare_to);
+        env_override("RPG_TOKEN_ESTIMATOR", &mut config.tokens.estimator);
 
         // Validate drift thresholds
         if config.encoding.drift_ignore_threshold >= config.encoding.drift_auto_threshold {
@@ -136,6 +599,92 @@ impl RpgConfig {
                 config.encoding.drift_auto_threshold,
             );
         }
+        if !(0.0..=1.0).contains(&config.encoding.routing_neighbor_weight) {
+            anyhow::bail!(
+                "routing_neighbor_weight ({}) must be between 0.0 and 1.0",
+                config.encoding.routing_neighbor_weight,
+            );
+        }
+        if !(0.0..=1.0).contains(&config.encoding.copied_features_similarity) {
+            anyhow::bail!(
+                "copied_features_similarity ({}) must be between 0.0 and 1.0",
+                config.encoding.copied_features_similarity,
+            );
+        }
+        let normalization = &config.encoding.feature_normalization;
+        for (name, limit) in [
+            (
+                "max_features_per_entity",
+                normalization.max_features_per_entity,
+            ),
+            ("max_words_per_feature", normalization.max_words_per_feature),
+        ] {
+            if limit == Some(0) {
+                anyhow::bail!("feature_normalization.{} must be at least 1", name);
+            }
+        }
+        let style = &config.encoding.hierarchy_style;
+        for (name, limit) in [
+            ("max_area_words", style.max_area_words),
+            ("max_category_words", style.max_category_words),
+        ] {
+            if limit == 0 {
+                anyhow::bail!("hierarchy_style.{} must be at least 1", name);
+            }
+        }
+        if !(config.navigation.max_tool_seconds.is_finite()
+            && config.navigation.max_tool_seconds > 0.0)
+        {
+            anyhow::bail!(
+                "max_tool_seconds ({}) must be a positive number",
+                config.navigation.max_tool_seconds,
+            );
+        }
+        if config.encoding.liftable_kinds.contains(&EntityKind::Module) {
+            anyhow::bail!(
+                "liftable_kinds cannot include \"module\" (modules are aggregated from their files)"
+            );
+        }
+        let response_limits = config
+            .mcp
+            .max_response_bytes
+            .iter()
+            .chain(config.mcp.tool_max_response_bytes.values());
+        if let Some(limit) = response_limits.min()
+            && *limit < MIN_RESPONSE_BYTES
+        {
+            anyhow::bail!(
+                "max_response_bytes ({}) must be at least {}",
+                limit,
+                MIN_RESPONSE_BYTES
+            );
+        }
+        let ratios = std::iter::once(("chars_per_token", &config.tokens.chars_per_token)).chain(
+            config
+                .tokens
+                .provider_chars_per_token
+                .iter()
+                .map(|(provider, ratio)| (provider.as_str(), ratio)),
+        );
+        for (name, ratio) in ratios {
+            if !(ratio.is_finite() && *ratio > 0.0) {
+                anyhow::bail!("tokens: {} ({}) must be a positive number", name, ratio);
+            }
+        }
+        if config.tokens.estimator == EstimatorKind::Bpe && config.tokens.bpe_tokenizer.is_none() {
+            anyhow::bail!("tokens.estimator = \"bpe\" requires tokens.bpe_tokenizer");
+        }
+        for prefix in config.encoding.per_area.keys() {
+            let area = config.encoding.for_area(prefix);
+            if area.drift_ignore_threshold >= area.drift_auto_threshold {
+                anyhow::bail!(
+                    "per_area \"{}\": drift_ignore_threshold ({}) must be less than drift_auto_threshold ({})",
+                    prefix,
+                    area.drift_ignore_threshold,
+                    area.drift_auto_threshold,
+                );
+            }
+        }
 
         Ok(config)
     }
@@ -155,22 +704,48 @@ mod tests {
         assert_eq!(config.encoding.drift_ignore_threshold, 0.3);
         assert_eq!(config.encoding.drift_auto_threshold, 0.7);
         assert_eq!(config.navigation.search_result_limit, 10);
+        assert_eq!(config.navigation.max_tool_seconds, 30.0);
+        assert!(!config.encoding.source_signatures);
+        assert_eq!(config.encoding.routing_neighbor_weight, 0.4);
+        assert_eq!(config.encoding.copied_features_max_entities, 5);
+        assert_eq!(
+            config.encoding.liftable_kinds,
+            vec![
+                EntityKind::Function,
+                EntityKind::Class,
+                EntityKind::Method,
+                EntityKind::Enum
+            ]
+        );
+        assert_eq!(config.risk.high_fan_in, 10);
+        assert_eq!(config.risk.medium_fan_in, 3);
+        assert_eq!(config.mcp.guidance, GuidanceMode::Full);
+        assert_eq!(
+            config.encoding.fixture_globs,
+            vec!["tests/fixtures/**", "**/__fixtures__/**", "testdata/**"]
+        );
+        assert!(config.encoding.honor_gitattributes);
+        assert!(!config.encoding.include_submodules);
     }
 
     #[test]
     fn test_config_from_toml() {
-        let toml_str = r"
+        let toml_str = r#"
 [encoding]
 batch_size = 64
 max_batch_tokens = 24000
 
 [navigation]
 search_result_limit = 20
-";
+
+[mcp]
+guidance = "terse"
+"#;
         let config: RpgConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.encoding.batch_size, 64);
         assert_eq!(config.encoding.max_batch_tokens, 24000);
         assert_eq!(config.navigation.search_result_limit, 20);
+        assert_eq!(config.mcp.guidance, GuidanceMode::Terse);
         // Defaults for unspecified fields
         assert_eq!(config.encoding.hierarchy_chunk_size, 50);
         assert_eq!(config.encoding.drift_threshold, 0.5);
@@ -181,4 +756,214 @@ search_result_limit = 20
         let config = RpgConfig::load(Path::new("/nonexistent/path")).unwrap();
         assert_eq!(config.encoding.batch_size, 50);
     }
+
+    #[test]
+    fn test_per_area_longest_prefix_wins() {
+        let toml_str = r#"
+[encoding]
+batch_size = 40
+
+[encoding.per_area."Core"]
+drift_auto_threshold = 0.5
+batch_size = 10
+
+[encoding.per_area."Core/parsing"]
+drift_ignore_threshold = 0.1
+auto_lift = false
+"#;
+        let config: RpgConfig = toml::from_str(toml_str).unwrap();
+        let encoding = &config.encoding;
+
+        let parsing = encoding.for_area("Core/parsing/tokenize");
+        assert_eq!(parsing.area.as_deref(), Some("Core/parsing"));
+        assert_eq!(parsing.drift_ignore_threshold, 0.1);
+        // Unset fields fall back to the global values, not the parent area.
+        assert_eq!(parsing.drift_auto_threshold, 0.7);
+        assert_eq!(parsing.batch_size, 40);
+        assert!(!parsing.auto_lift);
+
+        let core = encoding.for_area("Core/storage");
+        assert_eq!(core.area.as_deref(), Some("Core"));
+        assert_eq!(core.drift_auto_threshold, 0.5);
+        assert_eq!(core.batch_size, 10);
+        assert!(core.auto_lift);
+
+        // Prefixes match whole segments.
+        let other = encoding.for_area("CoreUtils/strings");
+        assert_eq!(other.area, None);
+        assert_eq!(other.batch_size, 40);
+        assert_eq!(other.drift_ignore_threshold, 0.3);
+    }
+
+    #[test]
+    fn test_per_area_thresholds_validated() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding.per_area.\"Core\"]\ndrift_ignore_threshold = 0.8\n",
+        )
+        .unwrap();
+        let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("per_area \"Core\""), "{}", err);
+    }
+
+    #[test]
+    fn test_liftable_kinds_parsed_and_module_rejected() {
+        let config: RpgConfig =
+            toml::from_str("[encoding]\nliftable_kinds = [\"function\", \"route\"]\n").unwrap();
+        assert_eq!(
+            config.encoding.liftable_kinds,
+            vec![EntityKind::Function, EntityKind::Route]
+        );
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding]\nliftable_kinds = [\"module\"]\n",
+        )
+        .unwrap();
+        let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("liftable_kinds"), "{}", err);
+    }
+
+    #[test]
+    fn test_feature_normalization_parsed_and_zero_limit_rejected() {
+        let defaults = FeatureNormalizationConfig::default();
+        assert!(defaults.split_compound);
+        assert_eq!(defaults.max_features_per_entity, None);
+
+        let config: RpgConfig = toml::from_str(
+            "[encoding.feature_normalization]\nsplit_compound = false\nmax_words_per_feature = 8\n",
+        )
+        .unwrap();
+        let normalization = &config.encoding.feature_normalization;
+        assert!(!normalization.split_compound);
+        assert_eq!(normalization.max_words_per_feature, Some(8));
+        assert_eq!(normalization.max_features_per_entity, None);
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding.feature_normalization]\nmax_features_per_entity = 0\n",
+        )
+        .unwrap();
+        let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("max_features_per_entity"), "{}", err);
+    }
+
+    #[test]
+    fn test_hierarchy_style_parsed_and_zero_limit_rejected() {
+        let defaults = HierarchyStyleConfig::default();
+        assert_eq!(defaults.mode, HierarchyStyleMode::Off);
+        assert!(defaults.banned_names.contains(&"utils".to_string()));
+
+        let config: RpgConfig = toml::from_str(
+            "[encoding.hierarchy_style]\nmode = \"normalize\"\nverb_phrases = false\nbanned_names = [\"misc\"]\n",
+        )
+        .unwrap();
+        let style = &config.encoding.hierarchy_style;
+        assert_eq!(style.mode, HierarchyStyleMode::Normalize);
+        assert!(!style.verb_phrases);
+        assert!(style.pascal_case_areas);
+        assert_eq!(style.banned_names, vec!["misc".to_string()]);
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding.hierarchy_style]\nmax_area_words = 0\n",
+        )
+        .unwrap();
+        let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("max_area_words"), "{}", err);
+    }
+
+    #[test]
+    fn test_staleness_compare_to_parsed() {
+        assert_eq!(
+            RpgConfig::default().staleness.compare_to,
+            CompareTo::BaseCommit
+        );
+        let parse = |value: &str| {
+            toml::from_str::<RpgConfig>(&format!("[staleness]\ncompare_to = \"{}\"\n", value))
+                .map(|c| c.staleness.compare_to)
+        };
+        assert_eq!(parse("base_commit").unwrap(), CompareTo::BaseCommit);
+        assert_eq!(
+            parse("merge-base:origin/main").unwrap(),
+            CompareTo::MergeBase("origin/main".to_string())
+        );
+        assert_eq!(
+            parse("v1.2.0").unwrap(),
+            CompareTo::Rev("v1.2.0".to_string())
+        );
+        let err = parse("merge-base:").unwrap_err().to_string();
+        assert!(err.contains("needs a revision"), "{}", err);
+
+        let target = CompareTo::MergeBase("origin/main".to_string());
+        assert_eq!(target.to_string(), "merge-base:origin/main");
+        assert_eq!(target.to_string().parse::<CompareTo>().unwrap(), target);
+    }
+
+    #[test]
+    fn test_response_limits_per_tool_and_minimum() {
+        let config: RpgConfig = toml::from_str(
+            "[mcp]\nmax_response_bytes = 50000\n\n[mcp.tool_max_response_bytes]\nsemantic_snapshot = 200000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.mcp.max_response_bytes_for("semantic_snapshot"),
+            Some(200_000)
+        );
+        assert_eq!(
+            config.mcp.max_response_bytes_for("fetch_node"),
+            Some(50_000)
+        );
+        assert_eq!(
+            McpConfig::default().max_response_bytes_for("fetch_node"),
+            None
+        );
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[mcp.tool_max_response_bytes]\nfetch_node = 10\n",
+        )
+        .unwrap();
+        let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+        assert!(err.contains("at least 256"), "{}", err);
+    }
+
+    #[test]
+    fn test_tokens_provider_ratio_and_validation() {
+        let config: RpgConfig = toml::from_str(
+            "[tokens]\nestimator = \"ratio\"\nchars_per_token = 3.5\nprovider = \"openai\"\n\n[tokens.provider_chars_per_token]\nanthropic = 2.8\n",
+        )
+        .unwrap();
+        assert_eq!(config.tokens.estimator, EstimatorKind::Ratio);
+        assert_eq!(
+            config.tokens.chars_per_token_for(Some("anthropic")),
+            (2.8, Some("anthropic".to_string()))
+        );
+        // The configured provider has no override: the global ratio applies.
+        assert_eq!(config.tokens.chars_per_token_for(None), (3.5, None));
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        for (toml, expected) in [
+            (
+                "[tokens.provider_chars_per_token]\nopenai = 0.0\n",
+                "openai",
+            ),
+            ("[tokens]\nestimator = \"bpe\"\n", "bpe_tokenizer"),
+        ] {
+            std::fs::write(tmp.path().join(".rpg/config.toml"), toml).unwrap();
+            let err = RpgConfig::load(tmp.path()).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}", err);
+        }
+    }
 }
@@ -0,0 +1,120 @@
+//! Entry points into the codebase and each entity's call distance from them.
+
+use crate::graph::{EdgeKind, Entity, EntityKind, RPGraph};
+use std::collections::{HashMap, VecDeque};
+
+/// A detected entry point into the codebase.
+pub struct EntryPoint {
+    pub entity_id: String,
+    /// Why it counts as an entry point: "main", "route", or "cli".
+    pub reason: &'static str,
+}
+
+/// Directory names that mark a file as part of a command-line front end.
+const CLI_DIRS: &[&str] = &["bin", "cli", "cmd", "commands"];
+
+/// Detect entry points: `main` functions, Route entities, and CLI command handlers.
+///
+/// CLI handlers are top-level functions named `cmd_*`/`command_*`, or top-level
+/// functions in a file under a `bin/`, `cli/`, `cmd/`, or `commands/` directory
+/// that also defines `main`. Results are ordered main → route → cli, then by ID.
+pub fn detect_entry_points(graph: &RPGraph) -> Vec<EntryPoint> {
+    let main_files: Vec<&std::path::Path> = graph
+        .entities
+        .values()
+        .filter(|e| is_main(e))
+        .map(|e| e.file.as_path())
+        .collect();
+
+    let mut points: Vec<EntryPoint> = Vec::new();
+    for (id, entity) in &graph.entities {
+        let reason = if is_main(entity) {
+            "main"
+        } else if entity.kind == EntityKind::Route {
+            "route"
+        } else if is_cli_command(entity, &main_files) {
+            "cli"
+        } else {
+            continue;
+        };
+        points.push(EntryPoint {
+            entity_id: id.clone(),
+            reason,
+        });
+    }
+
+    let rank = |r: &str| match r {
+        "main" => 0,
+        "route" => 1,
+        _ => 2,
+    };
+    points.sort_by(|a, b| {
+        rank(a.reason)
+            .cmp(&rank(b.reason))
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+    points
+}
+
+/// Set each entity's `entry_distance`: the fewest Invokes/Dispatches hops
+/// from any [`detect_entry_points`] entry, or `None` if no entry reaches it.
+/// A breadth-first pass over the whole graph, so it is cheap enough to
+/// rerun after every update. Returns the number of reachable entities.
+pub fn assign_entry_distances(graph: &mut RPGraph) -> usize {
+    let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &graph.edges {
+        if matches!(edge.kind, EdgeKind::Invokes | EdgeKind::Dispatches) {
+            callees
+                .entry(edge.source.as_str())
+                .or_default()
+                .push(edge.target.as_str());
+        }
+    }
+
+    let entry_ids: Vec<String> = detect_entry_points(graph)
+        .into_iter()
+        .map(|p| p.entity_id)
+        .collect();
+    let mut distances: HashMap<&str, u32> = HashMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for id in &entry_ids {
+        distances.insert(id.as_str(), 0);
+        queue.push_back(id.as_str());
+    }
+    while let Some(id) = queue.pop_front() {
+        let next = distances[id] + 1;
+        for &callee in callees.get(id).into_iter().flatten() {
+            if !distances.contains_key(callee) && graph.entities.contains_key(callee) {
+                distances.insert(callee, next);
+                queue.push_back(callee);
+            }
+        }
+    }
+
+    let distances: HashMap<String, u32> = distances
+        .into_iter()
+        .map(|(id, d)| (id.to_string(), d))
+        .collect();
+    for (id, entity) in &mut graph.entities {
+        entity.entry_distance = distances.get(id).copied();
+    }
+    distances.len()
+}
+
+fn is_main(entity: &Entity) -> bool {
+    entity.kind == EntityKind::Function && entity.name == "main" && entity.parent_class.is_none()
+}
+
+fn is_cli_command(entity: &Entity, main_files: &[&std::path::Path]) -> bool {
+    if entity.kind != EntityKind::Function || entity.parent_class.is_some() {
+        return false;
+    }
+    if entity.name.starts_with("cmd_") || entity.name.starts_with("command_") {
+        return true;
+    }
+    let in_cli_dir = entity.file.components().any(|c| match c {
+        std::path::Component::Normal(s) => s.to_str().is_some_and(|s| CLI_DIRS.contains(&s)),
+        _ => false,
+    });
+    in_cli_dir && main_files.contains(&entity.file.as_path())
+}
@@ -0,0 +1,42 @@
+//! Source file access for builds, lifting, and fetches.
+//!
+//! Files are addressed by repo-relative path, so a graph built from a git
+//! revision is interchangeable with one built from the worktree. The git
+//! provider, which reads blobs without a checkout, lives in `rpg-encoder`
+//! (`git_files::GitFileProvider`).
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Reads source files by path relative to the repository root.
+pub trait FileProvider {
+    /// Contents of `rel_path` as UTF-8.
+    fn read_to_string(&self, rel_path: &Path) -> io::Result<String>;
+
+    /// Whether `rel_path` holds exactly `contents`. Providers that can compare
+    /// by hash override this to avoid reading the file.
+    fn has_contents(&self, rel_path: &Path, contents: &str) -> bool {
+        self.read_to_string(rel_path)
+            .is_ok_and(|current| current == contents)
+    }
+}
+
+/// Files on disk under a project root.
+#[derive(Debug, Clone)]
+pub struct WorktreeFiles {
+    pub root: PathBuf,
+}
+
+impl WorktreeFiles {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+}
+
+impl FileProvider for WorktreeFiles {
+    fn read_to_string(&self, rel_path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(self.root.join(rel_path))
+    }
+}
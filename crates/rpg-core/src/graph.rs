@@ -7,8 +7,8 @@ pub fn normalize_path(path: &std::path::Path) -> String {
     path.display().to_string().replace('\\', "/")
 }
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 
 /// The complete Repository Planning Graph: G = (V, E) where V = V_H ∪ V_L.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +26,10 @@ pub struct RPGraph {
     pub edges: Vec<DependencyEdge>,
     /// Reverse index: file path → entity IDs in that file.
     pub file_index: BTreeMap<PathBuf, Vec<String>>,
+    /// Imports of each file, as written, for import-aware dependency
+    /// resolution.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_imports: BTreeMap<PathBuf, Vec<FileImport>>,
     /// Performance index: entity ID → edge indices in `edges` vec.
     /// Rebuilt on load and after edge mutations via `rebuild_edge_index()`.
     #[serde(skip)]
@@ -36,6 +40,19 @@ pub struct RPGraph {
     pub hierarchy_node_index: HashMap<String, Vec<String>>,
 }
 
+/// One import statement of a file: the module path as written
+/// (`crate::graph`, `./utils`, `pkg.models`) and the names it brings in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileImport {
+    pub module: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+    /// The file the module maps to through project configuration
+    /// (tsconfig `paths` and `baseUrl`), which the path alone doesn't name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+}
+
 /// Aggregate statistics and metadata for the graph.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphMetadata {
@@ -64,6 +81,226 @@ pub struct GraphMetadata {
     /// Detected paradigms/frameworks (e.g., "react", "nextjs", "redux").
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub paradigms: Vec<String>,
+    /// Whether entities carry source signatures (kept fresh by incremental updates).
+    #[serde(default)]
+    pub source_signatures: bool,
+    /// Per-language, per-edge-kind counts from the last dependency resolution.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edge_resolution: Vec<EdgeResolutionStats>,
+    /// Number of circular imports between files found at the last build or update.
+    #[serde(default)]
+    pub import_cycles: usize,
+    /// Files with syntax errors at the last full build, with the number of
+    /// error regions tree-sitter skipped in each (normalized paths).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parse_errors: BTreeMap<String, usize>,
+    /// Entity kinds that count toward lifting coverage and get lifted, from
+    /// `encoding.liftable_kinds` (see [`RPGraph::set_liftable_kinds`]).
+    #[serde(default = "default_liftable_kinds")]
+    pub liftable_kinds: Vec<EntityKind>,
+    /// Imports that matched no in-repo entity, from the last full build.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unresolved_imports: Option<UnresolvedImports>,
+    /// History window of the last churn analysis (`rpg-encoder churn`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<ChurnRange>,
+    /// Hash of the source files tracked at `base_commit` (paths and sizes),
+    /// used to reject a graph loaded into a different repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_fingerprint: Option<String>,
+    /// Verdicts from the last `submit_verification` round.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature_verification: Option<FeatureVerification>,
+    /// Most features each hierarchy node keeps when features are aggregated,
+    /// from `encoding.max_node_features` (0 keeps all).
+    #[serde(default = "default_max_node_features")]
+    pub max_node_features: usize,
+    /// Git submodules and other nested repositories inside the project.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nested_repos: Vec<NestedRepo>,
+}
+
+/// A git submodule or other nested repository (a directory with its own
+/// `.git`) inside the project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NestedRepo {
+    /// Directory relative to the project root, `/`-separated.
+    pub path: String,
+    /// Commit recorded at the last build or update: the one the superproject
+    /// pins for a skipped submodule, otherwise the checked-out HEAD (which
+    /// an indexed repository's files reflect).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Whether its files are indexed (`encoding.include_submodules`).
+    #[serde(default)]
+    pub indexed: bool,
+    /// False for a submodule whose checkout is missing.
+    #[serde(default = "default_true")]
+    pub checked_out: bool,
+}
+
+/// Hierarchy node feature limit when `encoding.max_node_features` is not set.
+pub const DEFAULT_MAX_NODE_FEATURES: usize = 50;
+
+fn default_max_node_features() -> usize {
+    DEFAULT_MAX_NODE_FEATURES
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Entity kinds lifted when `encoding.liftable_kinds` is not set.
+pub const DEFAULT_LIFTABLE_KINDS: &[EntityKind] = &[
+    EntityKind::Function,
+    EntityKind::Class,
+    EntityKind::Method,
+    EntityKind::Enum,
+];
+
+/// Entity attribute set to `"false"` on paradigm entities whose TOML
+/// definition opts out of lifting (`liftable = false`).
+pub const LIFTABLE_ATTRIBUTE: &str = "liftable";
+
+/// Entity attribute carrying `; `-separated features a paradigm synthesized
+/// from structure (`handle GET /users/{id}`). They seed the entity's
+/// `semantic_features` (source `"paradigm"`) when it enters the graph.
+pub const FEATURES_ATTRIBUTE: &str = "features";
+
+/// Tag on entities in test fixture files (`encoding.fixture_globs`). They are
+/// indexed structurally but never lifted or placed in the semantic hierarchy.
+pub const FIXTURE_TAG: &str = "fixture";
+
+/// Prefix of imported annotation attributes: `annotation.<source>.<key>`
+/// (e.g. `annotation.github.last_reviewer`). Unlike extraction attributes,
+/// these are restored across rebuilds.
+pub const ANNOTATION_ATTRIBUTE_PREFIX: &str = "annotation.";
+
+fn default_liftable_kinds() -> Vec<EntityKind> {
+    DEFAULT_LIFTABLE_KINDS.to_vec()
+}
+
+/// Dependency resolution counts for one language and edge kind: how many
+/// dependency names were extracted from source and how many became edges.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeResolutionStats {
+    pub language: String,
+    pub kind: EdgeKind,
+    /// Non-module entities in this language.
+    pub entities: usize,
+    /// Dependency names extracted from source.
+    pub raw: usize,
+    /// Extracted names that resolved to an edge.
+    pub resolved: usize,
+}
+
+impl EdgeResolutionStats {
+    /// Extracted names that matched no entity, or more than one.
+    pub fn unresolved(&self) -> usize {
+        self.raw.saturating_sub(self.resolved)
+    }
+}
+
+/// Prefix groups kept in [`UnresolvedImports::prefixes`].
+pub const UNRESOLVED_IMPORT_PREFIX_LIMIT: usize = 50;
+
+/// Import statements whose imported names matched no in-repo entity, so they
+/// produced no edge, grouped by module prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedImports {
+    /// Import statements seen.
+    pub imports: usize,
+    /// Import statements that matched no in-repo entity.
+    pub unresolved: usize,
+    /// The largest groups, by count (at most [`UNRESOLVED_IMPORT_PREFIX_LIMIT`]).
+    pub prefixes: Vec<UnresolvedImportPrefix>,
+}
+
+/// Unresolved imports sharing a module prefix (`react`, `@app/utils`, `crate::graph`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnresolvedImportPrefix {
+    pub prefix: String,
+    /// Unresolved import statements under this prefix.
+    pub count: usize,
+    /// Files containing at least one of them.
+    pub files: usize,
+    /// One module imported under this prefix, as written.
+    pub example: String,
+    pub origin: ImportOrigin,
+}
+
+/// Where an unresolved import most likely points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportOrigin {
+    /// Declared in a manifest or lockfile (package.json, Cargo.toml, go.mod, ...).
+    ThirdParty,
+    /// A relative path, path alias, or local package: in-repo code the
+    /// resolver failed to map.
+    InRepo,
+    /// Neither, typically the standard library or an undeclared dependency.
+    Unknown,
+}
+
+impl ImportOrigin {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ThirdParty => "third-party",
+            Self::InRepo => "in-repo-unmapped",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// The git history window a churn analysis covered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChurnRange {
+    /// The `--since` value as given (`6m`, `90d`, `2024-01-01`).
+    pub since: String,
+    /// Commits at or after this time were counted.
+    pub from: DateTime<Utc>,
+    /// HEAD commit the history walk started from.
+    pub head: String,
+    /// Non-merge commits in the window.
+    pub commits: usize,
+    pub analyzed_at: DateTime<Utc>,
+}
+
+/// Agent verdicts on a sample of lifted entities, checked against their
+/// current source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureVerification {
+    /// Features that still describe the code.
+    pub accurate: usize,
+    /// Features that described an earlier version of the code.
+    pub outdated: usize,
+    /// Features that never described the code.
+    pub wrong: usize,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl FeatureVerification {
+    /// Entities given a verdict.
+    pub fn sampled(&self) -> usize {
+        self.accurate + self.outdated + self.wrong
+    }
+
+    /// Share of sampled entities whose features were accurate.
+    pub fn accuracy(&self) -> f64 {
+        match self.sampled() {
+            0 => 0.0,
+            n => self.accurate as f64 / n as f64,
+        }
+    }
+}
+
+/// How often an entity's lines changed within the [`ChurnRange`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EntityChurn {
+    /// Commits with a hunk overlapping the entity's line range.
+    pub commits: usize,
+    /// Added plus deleted lines in those hunks.
+    pub lines: usize,
 }
 
 /// A code entity (V_L node): function, class, or method.
@@ -82,11 +319,148 @@ pub struct Entity {
     /// Provenance of semantic features: "auto", "llm", or "synthesized".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub feature_source: Option<String>,
+    /// When the current semantic features were set (lifted or synthesized).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifted_at: Option<DateTime<Utc>>,
     pub hierarchy_path: String,
     pub deps: EntityDeps,
     /// Typed function/method signature extracted from AST.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub signature: Option<Signature>,
+    /// Paradigm-specific metadata (e.g., `route: GET /users`, `store: auth`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+    /// b-bit MinHash signature of the normalized token stream, used for
+    /// source-similarity duplicate detection. Empty unless
+    /// `encoding.source_signatures` is enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_signature: Vec<u16>,
+    /// Code owners (e.g., `@org/team`) from the project's CODEOWNERS file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Architectural layer, inferred at build time. Advisory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<Layer>,
+    /// User labels (e.g., `deprecated`, `hot-path`), set with `tag_entities`.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    /// Change frequency from the last churn analysis; `None` if the entity
+    /// was not touched in the analyzed window (or churn was never analyzed).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub churn: Option<EntityChurn>,
+    /// Docstring or doc comment from the source, without comment markers
+    /// and truncated to a few lines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_comment: Option<String>,
+    /// Python and JS/TS decorators as written, without the `@`
+    /// (`app.route("/users")`, `dataclass`, `Get(':id')`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub decorators: Vec<String>,
+    /// `async def` / `async fn` / `async function`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+    /// Python functions that `yield`, JS/TS `function*` and `*method()`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_generator: bool,
+    /// Rust `unsafe fn`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_unsafe: bool,
+    /// Fewest Invokes/Dispatches hops from an entry point (0 for the entry
+    /// point itself); `None` if unreachable or the graph has no entry points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_distance: Option<u32>,
+}
+
+impl Entity {
+    /// The function modifiers that are set, of `unsafe`, `async`, and
+    /// `generator` (in that order).
+    pub fn modifiers(&self) -> Vec<&'static str> {
+        [
+            (self.is_unsafe, "unsafe"),
+            (self.is_async, "async"),
+            (self.is_generator, "generator"),
+        ]
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+        .collect()
+    }
+
+    /// How much the entity should count when its features are aggregated
+    /// into the hierarchy: grows with its size and its fan-in, on a log
+    /// scale so one huge entity does not drown out everything else.
+    pub fn importance(&self) -> f64 {
+        let lines = self.line_end.saturating_sub(self.line_start) + 1;
+        (1.0 + lines as f64).ln() + (1.0 + self.deps.fan_in() as f64).ln()
+    }
+
+    /// Render paradigm attributes as `key: value` pairs joined by `; ` (empty if none).
+    pub fn attribute_summary(&self) -> String {
+        self.attributes
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Whether the entity lives in a test fixture file.
+    pub fn is_fixture(&self) -> bool {
+        self.tags.contains(FIXTURE_TAG)
+    }
+
+    /// Whether the entity is test code: a test function or block, or any
+    /// entity in the test layer (test files and directories).
+    pub fn is_test(&self) -> bool {
+        self.kind == EntityKind::Test || self.layer == Some(Layer::Test)
+    }
+}
+
+/// Coarse architectural layer of an entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layer {
+    /// Entry points: request handlers, routes, controllers, CLI commands.
+    Api,
+    /// Business logic with no I/O of its own.
+    Domain,
+    /// Wrappers around databases, files, the network, and other external systems.
+    Infrastructure,
+    /// Tests and test fixtures.
+    Test,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 4] = [
+        Layer::Api,
+        Layer::Domain,
+        Layer::Infrastructure,
+        Layer::Test,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Layer::Api => "api",
+            Layer::Domain => "domain",
+            Layer::Infrastructure => "infrastructure",
+            Layer::Test => "test",
+        }
+    }
+
+    /// Parse a layer name (case-insensitive; `infra` is accepted).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "api" => Some(Layer::Api),
+            "domain" => Some(Layer::Domain),
+            "infrastructure" | "infra" => Some(Layer::Infrastructure),
+            "test" | "tests" => Some(Layer::Test),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Resolved dependency relationships for an entity (forward and reverse).
@@ -107,6 +481,30 @@ pub struct EntityDeps {
     pub dispatches: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub data_flows_to: Vec<String>,
+    /// Callees invoked inside closures handed to a task or thread spawner
+    /// (`tokio::spawn`, `thread::spawn`, `scope.spawn`). Also in `invokes`;
+    /// their Invokes edges cross an async boundary.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spawns: Vec<String>,
+    /// Non-test entities this test invokes (resolved IDs, set by grounding).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<String>,
+    /// Configuration keys (environment variables, config lookups) read by
+    /// string literal in the entity's body. Keys, not entity IDs: no edges.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_reads: Vec<String>,
+    /// Event names emitted by string literal (`emitter.emit('job:done')`).
+    /// Names, not IDs: grounding adds Dispatches edges to their handlers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub emits: Vec<String>,
+    /// Event names this entity is registered to handle
+    /// (`emitter.on('job:done', handler)`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handles: Vec<String>,
+    /// Event names given as expressions rather than literals
+    /// (`emit(name)`), as written. They produce no edges.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unresolved_events: Vec<String>,
     pub imported_by: Vec<String>,
     pub invoked_by: Vec<String>,
     pub inherited_by: Vec<String>,
@@ -122,6 +520,8 @@ pub struct EntityDeps {
     pub dispatched_by: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub data_flows_from: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tested_by: Vec<String>,
 }
 
 impl EntityDeps {
@@ -136,6 +536,12 @@ impl EntityDeps {
         self.writes_state.clear();
         self.dispatches.clear();
         self.data_flows_to.clear();
+        self.spawns.clear();
+        self.tests.clear();
+        self.env_reads.clear();
+        self.emits.clear();
+        self.handles.clear();
+        self.unresolved_events.clear();
     }
 
     /// Clear all reverse dependency vectors.
@@ -149,6 +555,7 @@ impl EntityDeps {
         self.state_written_by.clear();
         self.dispatched_by.clear();
         self.data_flows_from.clear();
+        self.tested_by.clear();
     }
 
     /// Iterate all forward dep vectors with their edge kinds.
@@ -178,6 +585,7 @@ impl EntityDeps {
             EdgeKind::WritesState => &mut self.state_written_by,
             EdgeKind::Dispatches => &mut self.dispatched_by,
             EdgeKind::DataFlow => &mut self.data_flows_from,
+            EdgeKind::Tests => &mut self.tested_by,
             EdgeKind::Contains => return,
         };
         if !vec.contains(&source_id) {
@@ -185,6 +593,20 @@ impl EntityDeps {
         }
     }
 
+    /// Number of reverse dependency edges pointing at the entity (test
+    /// coverage excluded).
+    pub fn fan_in(&self) -> usize {
+        self.imported_by.len()
+            + self.invoked_by.len()
+            + self.inherited_by.len()
+            + self.composed_by.len()
+            + self.rendered_by.len()
+            + self.state_read_by.len()
+            + self.state_written_by.len()
+            + self.dispatched_by.len()
+            + self.data_flows_from.len()
+    }
+
     /// Whether this entity has any call-site dependency info (invokes, inherits, or
     /// paradigm-specific edges like renders/reads_state/writes_state/dispatches).
     pub fn has_callsite_info(&self) -> bool {
@@ -205,23 +627,76 @@ impl EntityDeps {
             || self.writes_state.iter().any(|s| s == sym)
             || self.dispatches.iter().any(|s| s == sym)
     }
+
+    /// Compact one-line dependency summary, or an empty string without deps.
+    /// Format: `Called by: a, b | Calls: c, d | Inherits: e`, each list capped
+    /// at 5 entries.
+    pub fn summary(&self) -> String {
+        [
+            ("Called by", &self.invoked_by),
+            ("Calls", &self.invokes),
+            ("Inherits", &self.inherits),
+            ("Renders", &self.renders),
+            ("Data flows to", &self.data_flows_to),
+            ("Data from", &self.data_flows_from),
+        ]
+        .into_iter()
+        .filter(|(_, ids)| !ids.is_empty())
+        .map(|(label, ids)| {
+            let names: Vec<&str> = ids.iter().take(5).map(String::as_str).collect();
+            format!("{}: {}", label, names.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+    }
+}
+
+/// ID of the hierarchy node at `path` ("Area/category/subcategory").
+pub fn hierarchy_node_id(path: &str) -> String {
+    format!("h:{}", path)
 }
 
 /// A node in the semantic hierarchy tree (V_H node).
 /// Unified with Entity as a proper graph node: has id, semantic_features.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HierarchyNode {
-    /// Unique ID: "h:Area/Category/Subcategory"
+    /// Unique ID: "h:Area/Category/Subcategory". Derived only from the node's
+    /// path (see [`hierarchy_node_id`]), so it is the same on every rebuild and
+    /// machine and safe to reference from outside the graph.
     pub id: String,
     pub name: String,
     /// LCA-grounded directory paths for this subtree.
     pub grounded_paths: Vec<PathBuf>,
     pub children: BTreeMap<String, HierarchyNode>,
     pub entities: Vec<String>,
-    /// Aggregated semantic features from all entities in this subtree.
+    /// Aggregated semantic features from all entities in this subtree,
+    /// heaviest first (see [`HierarchyNode::aggregate_features`]).
     pub semantic_features: Vec<String>,
+    /// Weight of each aggregated feature: the summed
+    /// [`Entity::importance`] of the entities carrying it, scaled so the
+    /// heaviest feature is 1.0.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub feature_weights: BTreeMap<String, f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Top owners of this subtree by entity count, most entities first
+    /// (at most [`MAX_NODE_OWNERS`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<OwnerShare>,
+    /// Human-written statement of what the area is *for* ("new storage
+    /// backends go here"). Kept across hierarchy rebuilds by path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charter: Option<String>,
+}
+
+/// Maximum number of owners kept per hierarchy node.
+pub const MAX_NODE_OWNERS: usize = 3;
+
+/// An owner of a hierarchy subtree and how many of its entities they own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OwnerShare {
+    pub owner: String,
+    pub entities: usize,
 }
 
 impl HierarchyNode {
@@ -233,7 +708,10 @@ impl HierarchyNode {
             children: BTreeMap::new(),
             entities: Vec::new(),
             semantic_features: Vec::new(),
+            feature_weights: BTreeMap::new(),
             description: None,
+            owners: Vec::new(),
+            charter: None,
         }
     }
 
@@ -295,29 +773,80 @@ impl HierarchyNode {
         } else {
             format!("{}/{}", path_prefix, self.name)
         };
-        self.id = format!("h:{}", my_path);
+        self.id = hierarchy_node_id(&my_path);
         for child in self.children.values_mut() {
             child.assign_ids(&my_path);
         }
     }
 
-    /// Bottom-up aggregation: collect deduplicated semantic features from all children.
-    pub fn aggregate_features(&mut self, entities: &BTreeMap<String, Entity>) {
+    /// Bottom-up aggregation: weight each feature by the importance of the
+    /// entities carrying it, so a subtree is described mostly by its large,
+    /// heavily used code rather than by many small helpers. Keeps the
+    /// `max_features` heaviest (0 keeps all), ties broken by name. Returns the
+    /// unscaled weights of the whole subtree for the parent to merge.
+    pub fn aggregate_features(
+        &mut self,
+        entities: &BTreeMap<String, Entity>,
+        max_features: usize,
+    ) -> BTreeMap<String, f64> {
+        let mut weights: BTreeMap<String, f64> = BTreeMap::new();
         for child in self.children.values_mut() {
-            child.aggregate_features(entities);
+            for (feature, weight) in child.aggregate_features(entities, max_features) {
+                *weights.entry(feature).or_default() += weight;
+            }
         }
-        let mut all: Vec<String> = Vec::new();
         for eid in &self.entities {
             if let Some(entity) = entities.get(eid) {
-                all.extend(entity.semantic_features.iter().cloned());
+                let importance = entity.importance();
+                for feature in &entity.semantic_features {
+                    *weights.entry(feature.clone()).or_default() += importance;
+                }
             }
         }
-        for child in self.children.values() {
-            all.extend(child.semantic_features.iter().cloned());
+        let mut ranked: Vec<(&String, f64)> = weights.iter().map(|(f, &w)| (f, w)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        if max_features > 0 {
+            ranked.truncate(max_features);
         }
-        all.sort();
-        all.dedup();
-        self.semantic_features = all;
+        let heaviest = ranked.first().map_or(1.0, |&(_, w)| w);
+        self.semantic_features = ranked.iter().map(|(f, _)| (*f).clone()).collect();
+        self.feature_weights = ranked
+            .iter()
+            .map(|&(f, w)| (f.clone(), w / heaviest))
+            .collect();
+        weights
+    }
+
+    /// Aggregate entity owners bottom-up, keeping the top owners of each node.
+    /// Returns the full per-owner entity counts for this subtree.
+    pub fn aggregate_owners(
+        &mut self,
+        entities: &BTreeMap<String, Entity>,
+    ) -> BTreeMap<String, usize> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for child in self.children.values_mut() {
+            for (owner, count) in child.aggregate_owners(entities) {
+                *counts.entry(owner).or_default() += count;
+            }
+        }
+        for eid in &self.entities {
+            if let Some(entity) = entities.get(eid) {
+                for owner in &entity.owners {
+                    *counts.entry(owner.clone()).or_default() += 1;
+                }
+            }
+        }
+        let mut owners: Vec<OwnerShare> = counts
+            .iter()
+            .map(|(owner, &entities)| OwnerShare {
+                owner: owner.clone(),
+                entities,
+            })
+            .collect();
+        owners.sort_by_key(|o| std::cmp::Reverse(o.entities));
+        owners.truncate(MAX_NODE_OWNERS);
+        self.owners = owners;
+        counts
     }
 }
 
@@ -351,6 +880,8 @@ pub enum EdgeKind {
     Dispatches,
     /// E_dep: data flow between entities (parameter passing and return values).
     DataFlow,
+    /// E_dep: a test entity invokes a non-test entity (the test covers it).
+    Tests,
     /// E_feature: hierarchy containment (parent → child).
     Contains,
 }
@@ -363,6 +894,25 @@ pub struct Signature {
     pub return_type: Option<String>,
 }
 
+/// Renders as `(x: i32, y: String) -> bool`.
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params: Vec<String> = self
+            .parameters
+            .iter()
+            .map(|p| match &p.type_annotation {
+                Some(t) => format!("{}: {}", p.name, t),
+                None => p.name.clone(),
+            })
+            .collect();
+        write!(f, "({})", params.join(", "))?;
+        match &self.return_type {
+            Some(rt) => write!(f, " -> {}", rt),
+            None => Ok(()),
+        }
+    }
+}
+
 /// A single parameter in a function signature.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Param {
@@ -371,6 +921,17 @@ pub struct Param {
     pub type_annotation: Option<String>,
 }
 
+/// Normalize a user tag: trimmed and lowercased. Returns `None` for empty
+/// tags and tags containing whitespace, quotes, parentheses, or commas.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    let valid = !tag.is_empty()
+        && !tag
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '(' | ')' | ','));
+    valid.then_some(tag)
+}
+
 /// The kind of code entity extracted from source.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -390,6 +951,13 @@ pub enum EntityKind {
     Middleware,
     Route,
     Test,
+    /// An enumeration (Rust/TS/Java `enum`, Python `Enum` subclass).
+    Enum,
+    /// A module-level constant.
+    Constant,
+    /// A pytest fixture (`@pytest.fixture`). Unrelated to fixture *files*
+    /// ([`FIXTURE_TAG`]), which hold sample code for tests to parse.
+    Fixture,
 }
 
 impl RPGraph {
@@ -415,11 +983,23 @@ impl RPGraph {
                 semantic_hierarchy: false,
                 repo_summary: None,
                 paradigms: Vec::new(),
+                source_signatures: false,
+                edge_resolution: Vec::new(),
+                import_cycles: 0,
+                parse_errors: BTreeMap::new(),
+                unresolved_imports: None,
+                churn: None,
+                repo_fingerprint: None,
+                feature_verification: None,
+                max_node_features: DEFAULT_MAX_NODE_FEATURES,
+                liftable_kinds: default_liftable_kinds(),
+                nested_repos: Vec::new(),
             },
             hierarchy: BTreeMap::new(),
             entities: BTreeMap::new(),
             edges: Vec::new(),
             file_index: BTreeMap::new(),
+            file_imports: BTreeMap::new(),
             edge_index: HashMap::new(),
             hierarchy_node_index: HashMap::new(),
         }
@@ -499,26 +1079,67 @@ impl RPGraph {
         }
     }
 
-    /// Return (lifted, total) entity counts.
+    /// Replace the liftable kinds (Module is never liftable).
+    pub fn set_liftable_kinds(&mut self, kinds: &[EntityKind]) {
+        let mut kinds: Vec<EntityKind> = kinds
+            .iter()
+            .copied()
+            .filter(|k| *k != EntityKind::Module)
+            .collect();
+        kinds.dedup();
+        self.metadata.liftable_kinds = kinds;
+    }
+
+    /// Whether `entity` counts toward lifting coverage and is queued for
+    /// lifting: its kind is liftable, its paradigm did not opt it out, and it
+    /// is not in a fixture file. Modules get features by aggregation instead.
+    pub fn is_liftable(&self, entity: &Entity) -> bool {
+        entity.kind != EntityKind::Module
+            && !entity.is_fixture()
+            && self.metadata.liftable_kinds.contains(&entity.kind)
+            && entity
+                .attributes
+                .get(LIFTABLE_ATTRIBUTE)
+                .is_none_or(|v| v != "false")
+    }
+
+    /// Non-module entities left out of lifting, counted by reason:
+    /// `fixture` for entities in fixture files, `kind <name>` when the kind
+    /// is not liftable, `paradigm opt-out` when a paradigm definition marks
+    /// the entity `liftable = false`.
+    pub fn lifting_exclusions(&self) -> BTreeMap<String, usize> {
+        let mut exclusions = BTreeMap::new();
+        for entity in self.entities.values() {
+            if entity.kind == EntityKind::Module || self.is_liftable(entity) {
+                continue;
+            }
+            let reason = if entity.is_fixture() {
+                FIXTURE_TAG.to_string()
+            } else if self.metadata.liftable_kinds.contains(&entity.kind) {
+                "paradigm opt-out".to_string()
+            } else {
+                format!("kind {}", format!("{:?}", entity.kind).to_lowercase())
+            };
+            *exclusions.entry(reason).or_insert(0) += 1;
+        }
+        exclusions
+    }
+
+    /// Return (lifted, total) counts over liftable entities.
     pub fn lifting_coverage(&self) -> (usize, usize) {
-        let non_module = self
-            .entities
-            .values()
-            .filter(|e| e.kind != EntityKind::Module);
-        let total = non_module.clone().count();
-        let lifted = non_module
-            .filter(|e| !e.semantic_features.is_empty())
-            .count();
+        let liftable = self.entities.values().filter(|e| self.is_liftable(e));
+        let total = liftable.clone().count();
+        let lifted = liftable.filter(|e| !e.semantic_features.is_empty()).count();
         (lifted, total)
     }
 
-    /// Return unlifted non-module entities grouped by file path.
-    /// Each entry is (file_display_string, Vec<entity_id>), sorted by count descending.
+    /// Return unlifted liftable entities grouped by file path.
+    /// Each entry is (file_display_string, Vec<entity_id>), sorted by count descending, then path.
     pub fn unlifted_by_file(&self) -> Vec<(String, Vec<String>)> {
         let mut by_file: std::collections::HashMap<String, Vec<String>> =
             std::collections::HashMap::new();
         for (id, entity) in &self.entities {
-            if entity.kind != EntityKind::Module && entity.semantic_features.is_empty() {
+            if self.is_liftable(entity) && entity.semantic_features.is_empty() {
                 by_file
                     .entry(entity.file.to_string_lossy().to_string())
                     .or_default()
@@ -526,23 +1147,25 @@ impl RPGraph {
             }
         }
         let mut result: Vec<(String, Vec<String>)> = by_file.into_iter().collect();
-        result.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+        result.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
         result
     }
 
-    /// Return per-area lifting coverage: Vec of (area_name, lifted, total).
+    /// Return per-area lifting coverage over liftable entities: Vec of
+    /// (area_name, lifted, total).
     pub fn area_coverage(&self) -> Vec<(String, usize, usize)> {
         let mut result = Vec::new();
         for (area_name, node) in &self.hierarchy {
-            let ids = node.all_entity_ids();
-            let total = ids.len();
-            let lifted = ids
+            let liftable: Vec<&Entity> = node
+                .all_entity_ids()
                 .iter()
-                .filter(|id| {
-                    self.entities
-                        .get(*id)
-                        .is_some_and(|e| !e.semantic_features.is_empty())
-                })
+                .filter_map(|id| self.entities.get(id))
+                .filter(|e| self.is_liftable(e))
+                .collect();
+            let total = liftable.len();
+            let lifted = liftable
+                .iter()
+                .filter(|e| !e.semantic_features.is_empty())
                 .count();
             result.push((area_name.clone(), lifted, total));
         }
@@ -551,45 +1174,31 @@ impl RPGraph {
     }
 
     /// Build a hierarchy from file paths (structural fallback when no LLM is available).
-    /// Groups entities by directory structure: top-dir / sub-dir / file-stem.
+    /// Groups entities by directory structure: top-dir / sub-dir / file-stem, with
+    /// single-child directory chains collapsed and oversized nodes capped
+    /// (see [`crate::structure`]).
     pub fn build_file_path_hierarchy(&mut self) {
+        self.build_file_path_hierarchy_with_packages(&[]);
+    }
+
+    /// Like [`Self::build_file_path_hierarchy`], but each workspace package
+    /// directory (see [`crate::structure::detect_packages`]) becomes an area.
+    pub fn build_file_path_hierarchy_with_packages(&mut self, packages: &[PathBuf]) {
+        let charters = self.hierarchy_charters();
         self.hierarchy.clear();
         self.metadata.semantic_hierarchy = false;
 
-        let entity_ids: Vec<String> = self.entities.keys().cloned().collect();
-        for id in &entity_ids {
-            let entity = &self.entities[id];
-            let components: Vec<&str> = entity
-                .file
-                .components()
-                .filter_map(|c| match c {
-                    std::path::Component::Normal(s) => s.to_str(),
-                    _ => None,
-                })
-                .collect();
+        let mut files: BTreeMap<PathBuf, usize> = BTreeMap::new();
+        for entity in self.entities.values() {
+            *files.entry(entity.file.clone()).or_default() += 1;
+        }
+        let paths = crate::structure::structural_paths(&files, packages);
 
-            let path = match components.len() {
-                0 => continue,
-                1 => {
-                    // Single file at root: use file stem
-                    let stem = components[0]
-                        .rsplit_once('.')
-                        .map_or(components[0], |(s, _)| s);
-                    stem.to_string()
-                }
-                2 => {
-                    // dir/file.ext → dir/file_stem
-                    let stem = components[1]
-                        .rsplit_once('.')
-                        .map_or(components[1], |(s, _)| s);
-                    format!("{}/{}", components[0], stem)
-                }
-                _ => {
-                    // dir/subdir/.../file.ext → dir/subdir/file_stem
-                    let last = components.last().unwrap();
-                    let stem = last.rsplit_once('.').map_or(*last, |(s, _)| s);
-                    format!("{}/{}/{}", components[0], components[1], stem)
-                }
+        let mut entity_ids: Vec<String> = self.entities.keys().cloned().collect();
+        entity_ids.sort();
+        for id in &entity_ids {
+            let Some(path) = paths.get(&self.entities[id].file).cloned() else {
+                continue;
             };
 
             // Update entity's hierarchy_path
@@ -598,6 +1207,7 @@ impl RPGraph {
             }
             self.insert_into_hierarchy(&path, id);
         }
+        self.restore_charters(&charters);
     }
 
     pub fn insert_entity(&mut self, entity: Entity) {
@@ -613,6 +1223,7 @@ impl RPGraph {
                 ids.retain(|i| i != id);
                 if ids.is_empty() {
                     self.file_index.remove(&entity.file);
+                    self.file_imports.remove(&entity.file);
                 }
             }
             self.edges.retain(|e| e.source != id && e.target != id);
@@ -678,6 +1289,119 @@ impl RPGraph {
         self.entities.get(id)
     }
 
+    /// The Module entity standing for `file`, if one has been created.
+    pub fn module_entity(&self, file: &Path) -> Option<&Entity> {
+        self.file_index
+            .get(file)?
+            .iter()
+            .filter_map(|id| self.entities.get(id))
+            .find(|e| e.kind == EntityKind::Module)
+    }
+
+    /// Whether the Invokes edge `source → target` crosses a task or thread
+    /// spawn: `source` calls `target` inside a spawned closure.
+    pub fn is_async_boundary(&self, source: &str, target: &str) -> bool {
+        let (Some(source), Some(target)) = (self.entities.get(source), self.entities.get(target))
+        else {
+            return false;
+        };
+        source
+            .deps
+            .spawns
+            .iter()
+            .any(|callee| callee.rsplit("::").next() == Some(target.name.as_str()))
+    }
+
+    /// Add `tags` to each entity in `entity_ids`. Returns the number of
+    /// entities that gained a tag; unknown IDs are skipped.
+    pub fn tag_entities(&mut self, entity_ids: &[String], tags: &[String]) -> usize {
+        let mut changed = 0;
+        for id in entity_ids {
+            if let Some(entity) = self.entities.get_mut(id) {
+                let before = entity.tags.len();
+                entity.tags.extend(tags.iter().cloned());
+                if entity.tags.len() != before {
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Remove `tags` from each entity in `entity_ids`. Returns the number of
+    /// entities that lost a tag; unknown IDs are skipped.
+    pub fn untag_entities(&mut self, entity_ids: &[String], tags: &[String]) -> usize {
+        let mut changed = 0;
+        for id in entity_ids {
+            if let Some(entity) = self.entities.get_mut(id) {
+                let before = entity.tags.len();
+                entity.tags.retain(|t| !tags.contains(t));
+                if entity.tags.len() != before {
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Number of entities carrying each tag.
+    pub fn tag_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for tag in self.entities.values().flat_map(|e| &e.tags) {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Charters of all hierarchy nodes, by node path ("Storage/persist graph").
+    pub fn hierarchy_charters(&self) -> BTreeMap<String, String> {
+        fn collect(node: &HierarchyNode, path: &str, out: &mut BTreeMap<String, String>) {
+            if let Some(charter) = &node.charter {
+                out.insert(path.to_string(), charter.clone());
+            }
+            for (key, child) in &node.children {
+                collect(child, &format!("{}/{}", path, key), out);
+            }
+        }
+        let mut out = BTreeMap::new();
+        for (key, area) in &self.hierarchy {
+            collect(area, key, &mut out);
+        }
+        out
+    }
+
+    /// Set the charter of the node at `id_or_path` ("h:Storage" or
+    /// "Storage"), or clear it with `None`. Returns false when there is no
+    /// such node.
+    pub fn set_charter(&mut self, id_or_path: &str, charter: Option<String>) -> bool {
+        let path = id_or_path.strip_prefix("h:").unwrap_or(id_or_path);
+        let mut parts = path.trim_matches('/').split('/');
+        let Some(mut node) = parts.next().and_then(|area| self.hierarchy.get_mut(area)) else {
+            return false;
+        };
+        for part in parts {
+            match node.children.get_mut(part) {
+                Some(child) => node = child,
+                None => return false,
+            }
+        }
+        node.charter = charter;
+        true
+    }
+
+    /// Reattach `charters` (from [`Self::hierarchy_charters`]) to the nodes
+    /// with the same path after the hierarchy was rebuilt. Returns the
+    /// number of charters restored.
+    pub fn restore_charters(&mut self, charters: &BTreeMap<String, String>) -> usize {
+        let mut restored = 0;
+        for (path, charter) in charters {
+            if self.set_charter(path, Some(charter.clone())) {
+                restored += 1;
+            }
+        }
+        restored
+    }
+
     /// Find a hierarchy node (V_H) by its ID (e.g., "h:Auth/login/validation").
     pub fn find_hierarchy_node_by_id(&self, id: &str) -> Option<&HierarchyNode> {
         // Use the index for O(1) lookup when available
@@ -702,6 +1426,12 @@ impl RPGraph {
         None
     }
 
+    /// Find a hierarchy node by ID ("h:Auth/login") or by path ("Auth/login").
+    pub fn find_hierarchy_node(&self, id_or_path: &str) -> Option<&HierarchyNode> {
+        let path = id_or_path.strip_prefix("h:").unwrap_or(id_or_path);
+        self.find_hierarchy_node_by_id(&hierarchy_node_id(path.trim_matches('/')))
+    }
+
     fn find_node_by_id_recursive<'a>(
         node: &'a HierarchyNode,
         id: &str,
@@ -771,16 +1501,18 @@ impl RPGraph {
         self.rebuild_hierarchy_index();
     }
 
-    /// Aggregate semantic features bottom-up through the hierarchy.
+    /// Aggregate semantic features and owners bottom-up through the hierarchy.
     /// Uses split borrows to avoid cloning the entire entity map.
     pub fn aggregate_hierarchy_features(&mut self) {
         let Self {
             entities,
             hierarchy,
+            metadata,
             ..
         } = self;
         for area in hierarchy.values_mut() {
-            area.aggregate_features(entities);
+            area.aggregate_features(entities, metadata.max_node_features);
+            area.aggregate_owners(entities);
         }
     }
 
@@ -820,9 +1552,22 @@ impl RPGraph {
                 parent_class: None,
                 semantic_features: Vec::new(),
                 feature_source: None,
+                lifted_at: None,
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             };
             self.entities.insert(module_id.clone(), entity);
             self.file_index.entry(file).or_default().push(module_id);
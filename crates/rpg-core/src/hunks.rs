@@ -0,0 +1,146 @@
+//! Changed line ranges from unified diffs, and the entities they touch.
+
+use crate::graph::RPGraph;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+/// Changed line ranges per file (new-side, 1-based, inclusive), from a unified diff.
+pub type ChangedHunks = BTreeMap<PathBuf, Vec<(usize, usize)>>;
+
+/// Parse a unified diff (`git diff -U0` style) into the changed line ranges of
+/// each file, keyed by the new path.
+///
+/// Ranges come from the new side of each `@@ -a,b +c,d @@` header. A pure
+/// deletion (`d == 0`) is recorded as the single line it follows, so the entity
+/// that lost lines still counts as touched. Deleted files are skipped.
+pub fn parse_unified_diff(diff: &str) -> ChangedHunks {
+    let mut hunks = ChangedHunks::new();
+    let mut current: Option<PathBuf> = None;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            let path = path.trim_end().trim_matches('"');
+            current = (path != "/dev/null")
+                .then(|| PathBuf::from(path.strip_prefix("b/").unwrap_or(path)));
+            if let Some(ref file) = current {
+                hunks.entry(file.clone()).or_default();
+            }
+        } else if line.starts_with("@@ ")
+            && let Some(ref file) = current
+            && let Some(range) = parse_hunk_header(line)
+        {
+            hunks.entry(file.clone()).or_default().push(range);
+        }
+    }
+    hunks
+}
+
+/// New-side line range of a `@@ -a,b +c,d @@` hunk header.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let new_side = line
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match new_side.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (new_side.parse::<usize>().ok()?, 1),
+    };
+    let start = start.max(1);
+    Some((start, start + count.max(1) - 1))
+}
+
+/// Entities whose line ranges intersect a changed hunk in their file.
+pub fn entities_in_hunks(graph: &RPGraph, hunks: &ChangedHunks) -> HashSet<String> {
+    let mut touched = HashSet::new();
+    for (file, ranges) in hunks {
+        let Some(ids) = graph.file_index.get(file) else {
+            continue;
+        };
+        for id in ids {
+            let Some(entity) = graph.entities.get(id) else {
+                continue;
+            };
+            if ranges
+                .iter()
+                .any(|&(start, end)| entity.line_start <= end && entity.line_end >= start)
+            {
+                touched.insert(id.clone());
+            }
+        }
+    }
+    touched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{Entity, EntityDeps, EntityKind};
+    use std::collections::BTreeSet;
+
+    fn entity(id: &str, file: &str, (line_start, line_end): (usize, usize)) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind: EntityKind::Function,
+            name: id.to_string(),
+            file: PathBuf::from(file),
+            line_start,
+            line_end,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_unified_diff_hunks() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3,2 +3,4 @@ fn a() {
++    one();
+@@ -10 +12 @@
+-x
++y
+@@ -20,3 +21,0 @@
+-gone
+diff --git a/src/old.rs b/src/old.rs
+--- a/src/old.rs
++++ /dev/null
+@@ -1,5 +0,0 @@
+";
+        let hunks = parse_unified_diff(diff);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(
+            hunks[&PathBuf::from("src/a.rs")],
+            vec![(3, 6), (12, 12), (21, 21)]
+        );
+    }
+
+    #[test]
+    fn test_entities_in_hunks_intersects_line_ranges() {
+        let mut graph = RPGraph::new("rust");
+        for (id, lines) in [("a.rs:first", (1, 5)), ("a.rs:second", (7, 12))] {
+            graph.insert_entity(entity(id, "a.rs", lines));
+        }
+        graph.insert_entity(entity("b.rs:other", "b.rs", (1, 10)));
+
+        let mut hunks = ChangedHunks::new();
+        hunks.insert(PathBuf::from("a.rs"), vec![(12, 14)]);
+        let touched = entities_in_hunks(&graph, &hunks);
+        assert_eq!(touched, HashSet::from(["a.rs:second".to_string()]));
+    }
+}
@@ -0,0 +1,248 @@
+//! Circular imports between files, from the graph's resolved Imports edges.
+
+use crate::graph::{EdgeKind, RPGraph, normalize_path};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+/// A circular import between files: a strongly connected component of the
+/// file-level import graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportCycle {
+    /// A shortest import chain from the component's first file (by path)
+    /// back to itself, without the closing repeat.
+    pub chain: Vec<String>,
+    /// Every file in the component, sorted. Longer than `chain` when the
+    /// files form several interlocking cycles.
+    pub files: Vec<String>,
+}
+
+impl ImportCycle {
+    /// The chain as `a.py → b.py → a.py`.
+    pub fn representation(&self) -> String {
+        let mut chain = self.chain.join(" → ");
+        if let Some(first) = self.chain.first() {
+            chain.push_str(" → ");
+            chain.push_str(first);
+        }
+        chain
+    }
+}
+
+/// Circular imports between files, from resolved Imports edges (Tarjan's
+/// SCC algorithm over the file graph). With `ignore_same_directory`, cycles
+/// whose files all live in one directory are left out.
+pub fn import_cycles(graph: &RPGraph, ignore_same_directory: bool) -> Vec<ImportCycle> {
+    let file_of = |id: &str| graph.entities.get(id).map(|e| normalize_path(&e.file));
+    let mut adj: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for edge in &graph.edges {
+        if edge.kind != EdgeKind::Imports {
+            continue;
+        }
+        if let (Some(source), Some(target)) = (file_of(&edge.source), file_of(&edge.target))
+            && source != target
+        {
+            adj.entry(source).or_default().insert(target);
+        }
+    }
+
+    let mut cycles: Vec<ImportCycle> = strongly_connected_components(&adj)
+        .into_iter()
+        .filter(|files| files.len() > 1)
+        .filter(|files| {
+            let dir = |f: &str| Path::new(f).parent().map(Path::to_path_buf);
+            !(ignore_same_directory && files.iter().all(|f| dir(f) == dir(&files[0])))
+        })
+        .map(|files| ImportCycle {
+            chain: shortest_cycle(&adj, &files),
+            files,
+        })
+        .collect();
+    cycles.sort_by(|a, b| a.files.cmp(&b.files));
+    cycles
+}
+
+/// Strongly connected components of `adj`, each sorted. Iterative Tarjan, so
+/// long import chains cannot overflow the stack.
+fn strongly_connected_components(adj: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    let empty = BTreeSet::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut lowlink: HashMap<&str, usize> = HashMap::new();
+    let mut on_stack: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut components = Vec::new();
+
+    for root in adj.keys() {
+        if index.contains_key(root.as_str()) {
+            continue;
+        }
+        index.insert(root, index.len());
+        lowlink.insert(root, index[root.as_str()]);
+        stack.push(root);
+        on_stack.insert(root);
+        let mut work = vec![(root.as_str(), adj.get(root).unwrap_or(&empty).iter())];
+
+        while let Some((node, successors)) = work.last_mut() {
+            let node = *node;
+            if let Some(next) = successors.next() {
+                let next = next.as_str();
+                if !index.contains_key(next) {
+                    index.insert(next, index.len());
+                    lowlink.insert(next, index[next]);
+                    stack.push(next);
+                    on_stack.insert(next);
+                    work.push((next, adj.get(next).unwrap_or(&empty).iter()));
+                } else if on_stack.contains(next) {
+                    let low = lowlink[node].min(index[next]);
+                    lowlink.insert(node, low);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some((parent, _)) = work.last() {
+                let low = lowlink[parent].min(lowlink[node]);
+                lowlink.insert(parent, low);
+            }
+            if lowlink[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Shortest import chain from the first file of a component back to itself,
+/// staying inside the component (breadth-first search).
+fn shortest_cycle(adj: &BTreeMap<String, BTreeSet<String>>, files: &[String]) -> Vec<String> {
+    let members: HashSet<&str> = files.iter().map(String::as_str).collect();
+    let start = files[0].as_str();
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut queue = std::collections::VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for next in adj.get(node).into_iter().flatten() {
+            let next = next.as_str();
+            if next == start {
+                let mut chain = vec![node.to_string()];
+                let mut current = node;
+                while let Some(&prev) = previous.get(current) {
+                    chain.push(prev.to_string());
+                    current = prev;
+                }
+                if current != start {
+                    chain.push(start.to_string());
+                }
+                chain.reverse();
+                return chain;
+            }
+            if members.contains(next) && next != start && !previous.contains_key(next) {
+                previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+    files.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use std::path::PathBuf;
+
+    fn module_entity(id: &str, file: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind: EntityKind::Module,
+            name: "module".to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 5,
+            parent_class: None,
+            semantic_features: vec![],
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    /// Module entities per file with Imports edges between them.
+    fn make_import_graph(imports: &[(&str, &str)]) -> RPGraph {
+        let mut graph = RPGraph::new("python");
+        for (source, target) in imports {
+            for file in [source, target] {
+                let id = format!("{}:module", file);
+                graph
+                    .entities
+                    .entry(id.clone())
+                    .or_insert_with(|| module_entity(&id, file));
+            }
+            graph.edges.push(DependencyEdge {
+                source: format!("{}:module", source),
+                target: format!("{}:module", target),
+                kind: EdgeKind::Imports,
+            });
+        }
+        graph
+    }
+
+    #[test]
+    fn test_import_cycles_two_files() {
+        let graph = make_import_graph(&[("pkg/a.py", "pkg/b.py"), ("pkg/b.py", "pkg/a.py")]);
+        let cycles = import_cycles(&graph, false);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].files, vec!["pkg/a.py", "pkg/b.py"]);
+        assert_eq!(cycles[0].representation(), "pkg/a.py → pkg/b.py → pkg/a.py");
+
+        // Both files live in pkg/, so the cycle can be ignored.
+        assert!(import_cycles(&graph, true).is_empty());
+    }
+
+    #[test]
+    fn test_import_cycles_shortest_chain_and_acyclic_files() {
+        // a -> b -> c -> a and a -> c; d/x only imports into the cycle.
+        let graph = make_import_graph(&[
+            ("a.py", "b.py"),
+            ("b.py", "c.py"),
+            ("c.py", "a.py"),
+            ("a.py", "c.py"),
+            ("d/x.py", "a.py"),
+        ]);
+        let cycles = import_cycles(&graph, false);
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].files, vec!["a.py", "b.py", "c.py"]);
+        assert_eq!(cycles[0].chain, vec!["a.py", "c.py"]);
+
+        let graph = make_import_graph(&[("a.py", "b.py"), ("b.py", "c.py")]);
+        assert!(import_cycles(&graph, false).is_empty());
+    }
+
+    #[test]
+    fn test_import_cycles_across_directories_survive_ignore_flag() {
+        let graph = make_import_graph(&[("app/a.py", "lib/b.py"), ("lib/b.py", "app/a.py")]);
+        assert_eq!(import_cycles(&graph, true).len(), 1);
+    }
+}
@@ -1,10 +1,23 @@
 //! Core types and storage for the Repository Planning Graph (RPG).
 //!
 //! Provides the graph data model ([`graph::RPGraph`]), entity types, dependency edges,
-//! hierarchy nodes, JSON persistence, and LCA-based directory grounding.
+//! hierarchy nodes, JSON persistence, and LCA-based directory grounding, plus the
+//! graph analyses the encoder and navigation build on: scope expressions
+//! ([`scope`]), source signatures ([`signature`]), diff hunks ([`hunks`]),
+//! import cycles ([`imports`]), entry points ([`entry_points`]), and entity
+//! visibility ([`visibility`]).
 
 pub mod config;
+pub mod entry_points;
+pub mod files;
 pub mod graph;
+pub mod hunks;
+pub mod imports;
 pub mod lca;
 pub mod schema;
+pub mod scope;
+pub mod signature;
 pub mod storage;
+pub mod structure;
+pub mod tokens;
+pub mod visibility;
@@ -138,6 +138,7 @@ pub fn from_json(json: &str) -> Result<RPGraph> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
 
     fn graph_with_version(version: &str) -> RPGraph {
         let json = format!(
@@ -231,9 +232,22 @@ mod tests {
                 parent_class: None,
                 semantic_features: vec!["validates user credentials".to_string()],
                 feature_source: Some("llm".to_string()),
+                lifted_at: None,
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
         );
 
@@ -0,0 +1,640 @@
+//! Scope expressions: combine hierarchy areas, file globs, entity kinds, and tags.
+//!
+//! Grammar (keywords are case-insensitive, `NOT` binds tighter than `AND`,
+//! which binds tighter than `OR`):
+//!
+//! ```text
+//! expr   := and ( "OR" and )*
+//! and    := unary ( "AND" unary )*
+//! unary  := "NOT" unary | "(" expr ")" | filter
+//! filter := ("area" | "glob" | "kind" | "tag") ":" value
+//! value  := bare-word | "\"" quoted text "\""
+//! ```
+//!
+//! Example: `area:Security AND glob:src/** AND kind:class AND NOT glob:**/tests/**`.
+//! `tag:deprecated` matches entities carrying that user tag.
+//! Area paths containing spaces must be quoted: `area:"Security/manage sessions"`.
+//!
+//! Strings without any `area:`/`glob:`/`kind:`/`tag:` filter are not expressions;
+//! callers keep their existing interpretation (hierarchy path, glob, or entity IDs).
+
+use crate::graph::{Entity, EntityKind, RPGraph};
+use globset::{Glob, GlobMatcher};
+use std::fmt;
+
+const FILTER_KEYS: [&str; 4] = ["area", "glob", "kind", "tag"];
+
+/// A parsed scope expression.
+#[derive(Debug, Clone)]
+pub enum ScopeExpr {
+    /// Entity's hierarchy path equals or is nested under this path.
+    Area(String),
+    /// Entity's file matches this glob.
+    Glob(GlobMatcher),
+    /// Entity is of this kind.
+    Kind(EntityKind),
+    /// Entity carries this tag.
+    Tag(String),
+    Not(Box<ScopeExpr>),
+    And(Box<ScopeExpr>, Box<ScopeExpr>),
+    Or(Box<ScopeExpr>, Box<ScopeExpr>),
+}
+
+/// A malformed scope expression, with the 0-based character offset of the problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeError {
+    pub input: String,
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Invalid scope expression at column {}: {}",
+            self.position + 1,
+            self.message
+        )?;
+        writeln!(f, "  {}", self.input)?;
+        write!(f, "  {}^", " ".repeat(self.position))
+    }
+}
+
+impl std::error::Error for ScopeError {}
+
+impl ScopeExpr {
+    /// Whether an entity satisfies this expression.
+    pub fn matches(&self, entity: &Entity) -> bool {
+        match self {
+            Self::Area(path) => {
+                entity.hierarchy_path == *path
+                    || entity
+                        .hierarchy_path
+                        .strip_prefix(path.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            Self::Glob(matcher) => matcher.is_match(&entity.file),
+            Self::Kind(kind) => entity.kind == *kind,
+            Self::Tag(tag) => entity.tags.contains(tag),
+            Self::Not(inner) => !inner.matches(entity),
+            Self::And(a, b) => a.matches(entity) && b.matches(entity),
+            Self::Or(a, b) => a.matches(entity) || b.matches(entity),
+        }
+    }
+
+    /// IDs of all entities in the graph that satisfy this expression.
+    pub fn resolve(&self, graph: &RPGraph) -> Vec<String> {
+        graph
+            .entities
+            .iter()
+            .filter(|(_, e)| self.matches(e))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+/// Whether `input` uses the expression syntax (contains an `area:`, `glob:`,
+/// `kind:`, or `tag:` filter). Plain hierarchy paths, globs, and entity IDs return false.
+pub fn is_expression(input: &str) -> bool {
+    input
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .any(|word| {
+            word.split_once(':')
+                .is_some_and(|(key, _)| FILTER_KEYS.contains(&key.to_ascii_lowercase().as_str()))
+        })
+}
+
+/// Check a scope string: plain scopes always pass, expressions must parse.
+pub fn validate(input: &str) -> Result<(), ScopeError> {
+    if is_expression(input) {
+        parse(input)?;
+    }
+    Ok(())
+}
+
+/// Compile a user-supplied glob (`--include`, `file_pattern`, ...). The error
+/// names the pattern, says what is wrong with it, and suggests a fix.
+pub fn compile_glob(pattern: &str) -> Result<Glob, String> {
+    Glob::new(pattern).map_err(|e| {
+        // Alternate-group errors already suggest an escape.
+        let hint = match e.kind() {
+            globset::ErrorKind::UnclosedClass => {
+                "; close it with `]`, or match a literal `[` with `[[]`"
+            }
+            globset::ErrorKind::InvalidRange(..) => "; write ranges low to high, like `[a-z]`",
+            globset::ErrorKind::InvalidRecursive => "; use it alone, like `src/**/*.rs`",
+            globset::ErrorKind::DanglingEscape => "; remove it or escape it as `\\\\`",
+            _ => "",
+        };
+        format!("Invalid glob pattern '{}': {}{}", pattern, e.kind(), hint)
+    })
+}
+
+/// Parse `input` as an expression, or treat a plain string as a single filter:
+/// `glob:` if it contains `*` or `?`, otherwise `area:`.
+pub fn parse_lenient(input: &str) -> Result<ScopeExpr, ScopeError> {
+    let trimmed = input.trim();
+    if is_expression(trimmed) {
+        return parse(trimmed);
+    }
+    if trimmed.contains('*') || trimmed.contains('?') {
+        return compile_glob(trimmed)
+            .map(|g| ScopeExpr::Glob(g.compile_matcher()))
+            .map_err(|message| ScopeError {
+                input: input.to_string(),
+                position: 0,
+                message,
+            });
+    }
+    Ok(ScopeExpr::Area(trimmed.trim_end_matches('/').to_string()))
+}
+
+/// Entities picked by explicit IDs and/or a scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// Matched entity IDs, sorted and deduplicated.
+    pub entity_ids: Vec<String>,
+    /// Explicit IDs that are not in the graph.
+    pub unknown_ids: Vec<String>,
+}
+
+/// Select `entity_ids` plus every entity matched by `scope` (hierarchy path,
+/// file glob, or expression; see [`parse_lenient`]).
+pub fn select(
+    graph: &RPGraph,
+    entity_ids: &[String],
+    scope: Option<&str>,
+) -> Result<Selection, ScopeError> {
+    let mut selected = std::collections::BTreeSet::new();
+    let mut unknown_ids = Vec::new();
+    for id in entity_ids {
+        if graph.entities.contains_key(id) {
+            selected.insert(id.clone());
+        } else {
+            unknown_ids.push(id.clone());
+        }
+    }
+    if let Some(scope) = scope {
+        selected.extend(parse_lenient(scope)?.resolve(graph));
+    }
+    Ok(Selection {
+        entity_ids: selected.into_iter().collect(),
+        unknown_ids,
+    })
+}
+
+/// Parse a scope expression.
+pub fn parse(input: &str) -> Result<ScopeExpr, ScopeError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        input,
+        tokens,
+        pos: 0,
+    };
+    let expr = parser.parse_or()?;
+    if let Some(tok) = parser.peek() {
+        let message = match tok.kind {
+            TokenKind::RParen => "unmatched ')'".to_string(),
+            _ => "expected AND or OR between filters".to_string(),
+        };
+        return Err(parser.error(tok.pos, message));
+    }
+    Ok(expr)
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Filter { key: String, value: String },
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Character offset of the token start.
+    pos: usize,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ScopeError> {
+    let chars: Vec<char> = input.chars().collect();
+    let err = |position: usize, message: String| ScopeError {
+        input: input.to_string(),
+        position,
+        message,
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c == '(' || c == ')' {
+            let kind = if c == '(' {
+                TokenKind::LParen
+            } else {
+                TokenKind::RParen
+            };
+            tokens.push(Token { kind, pos: i });
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len()
+            && !chars[i].is_whitespace()
+            && chars[i] != '('
+            && chars[i] != ')'
+            && chars[i] != ':'
+        {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect();
+
+        if i >= chars.len() || chars[i] != ':' {
+            let kind = match word.to_ascii_uppercase().as_str() {
+                "AND" => TokenKind::And,
+                "OR" => TokenKind::Or,
+                "NOT" => TokenKind::Not,
+                _ => {
+                    return Err(err(
+                        start,
+                        format!(
+                            "unexpected '{}' (expected area:, glob:, kind:, tag:, AND, OR, NOT, or parentheses)",
+                            word
+                        ),
+                    ));
+                }
+            };
+            tokens.push(Token { kind, pos: start });
+            continue;
+        }
+
+        let key = word.to_ascii_lowercase();
+        if !FILTER_KEYS.contains(&key.as_str()) {
+            return Err(err(
+                start,
+                format!(
+                    "unknown filter '{}' (expected area, glob, kind, or tag)",
+                    word
+                ),
+            ));
+        }
+        i += 1; // skip ':'
+
+        let value = if i < chars.len() && chars[i] == '"' {
+            let quote = i;
+            i += 1;
+            let value_start = i;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err(err(quote, "unterminated quote".to_string()));
+            }
+            let value: String = chars[value_start..i].iter().collect();
+            i += 1; // skip closing quote
+            value
+        } else {
+            let value_start = i;
+            while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '(' && chars[i] != ')'
+            {
+                i += 1;
+            }
+            chars[value_start..i].iter().collect()
+        };
+        if value.is_empty() {
+            return Err(err(start, format!("empty value for '{}:'", key)));
+        }
+        tokens.push(Token {
+            kind: TokenKind::Filter { key, value },
+            pos: start,
+        });
+    }
+
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn end_pos(&self) -> usize {
+        self.input.chars().count()
+    }
+
+    fn error(&self, position: usize, message: impl Into<String>) -> ScopeError {
+        ScopeError {
+            input: self.input.to_string(),
+            position,
+            message: message.into(),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<ScopeExpr, ScopeError> {
+        let mut left = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = ScopeExpr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<ScopeExpr, ScopeError> {
+        let mut left = self.parse_unary()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            let right = self.parse_unary()?;
+            left = ScopeExpr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<ScopeExpr, ScopeError> {
+        let Some(tok) = self.peek().cloned() else {
+            return Err(self.error(self.end_pos(), "expected a filter, found end of input"));
+        };
+        self.pos += 1;
+        match tok.kind {
+            TokenKind::Not => Ok(ScopeExpr::Not(Box::new(self.parse_unary()?))),
+            TokenKind::LParen => {
+                let inner = self.parse_or()?;
+                match self.peek() {
+                    Some(t) if t.kind == TokenKind::RParen => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err(self.error(tok.pos, "unclosed '('")),
+                }
+            }
+            TokenKind::Filter { key, value } => self.build_filter(&key, value, tok.pos),
+            TokenKind::RParen => Err(self.error(tok.pos, "expected a filter, found ')'")),
+            TokenKind::And | TokenKind::Or => Err(self.error(
+                tok.pos,
+                "expected a filter, found an operator (AND/OR need a filter on each side)",
+            )),
+        }
+    }
+
+    fn build_filter(&self, key: &str, value: String, pos: usize) -> Result<ScopeExpr, ScopeError> {
+        match key {
+            "area" => Ok(ScopeExpr::Area(value.trim_end_matches('/').to_string())),
+            "glob" => compile_glob(&value)
+                .map(|g| ScopeExpr::Glob(g.compile_matcher()))
+                .map_err(|message| self.error(pos, message)),
+            "tag" => crate::graph::normalize_tag(&value)
+                .map(ScopeExpr::Tag)
+                .ok_or_else(|| self.error(pos, format!("invalid tag '{}'", value))),
+            _ => parse_kind(&value)
+                .map(ScopeExpr::Kind)
+                .ok_or_else(|| self.error(pos, format!("unknown entity kind '{}'", value))),
+        }
+    }
+}
+
+/// Parse an entity kind name. "file" and "directory" are aliases for Module.
+fn parse_kind(name: &str) -> Option<EntityKind> {
+    match name.to_ascii_lowercase().as_str() {
+        "function" => Some(EntityKind::Function),
+        "class" => Some(EntityKind::Class),
+        "method" => Some(EntityKind::Method),
+        "page" => Some(EntityKind::Page),
+        "layout" => Some(EntityKind::Layout),
+        "component" => Some(EntityKind::Component),
+        "hook" => Some(EntityKind::Hook),
+        "store" => Some(EntityKind::Store),
+        "module" | "file" | "directory" => Some(EntityKind::Module),
+        "controller" => Some(EntityKind::Controller),
+        "model" => Some(EntityKind::Model),
+        "service" => Some(EntityKind::Service),
+        "middleware" => Some(EntityKind::Middleware),
+        "route" => Some(EntityKind::Route),
+        "test" => Some(EntityKind::Test),
+        "enum" => Some(EntityKind::Enum),
+        "constant" => Some(EntityKind::Constant),
+        "fixture" => Some(EntityKind::Fixture),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::EntityDeps;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::PathBuf;
+
+    fn entity(file: &str, kind: EntityKind, path: &str) -> Entity {
+        Entity {
+            id: format!("{}:x", file),
+            kind,
+            name: "x".to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 2,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: path.to_string(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    #[test]
+    fn test_is_expression_detection() {
+        assert!(is_expression("area:Security"));
+        assert!(is_expression("NOT glob:**/tests/**"));
+        assert!(is_expression("(kind:class OR kind:function)"));
+        assert!(!is_expression("Security/auth"));
+        assert!(!is_expression("src/auth/**"));
+        assert!(!is_expression("src/foo.rs:bar,src/baz.rs:qux"));
+        assert!(!is_expression("*"));
+    }
+
+    #[test]
+    fn test_combined_expression_matches() {
+        let expr =
+            parse("area:Security AND glob:src/** AND kind:class AND NOT glob:**/tests/**").unwrap();
+        assert!(expr.matches(&entity(
+            "src/auth/session.rs",
+            EntityKind::Class,
+            "Security/auth/session"
+        )));
+        assert!(!expr.matches(&entity(
+            "src/auth/tests/session.rs",
+            EntityKind::Class,
+            "Security/auth/session"
+        )));
+        assert!(!expr.matches(&entity(
+            "src/auth/session.rs",
+            EntityKind::Function,
+            "Security/auth/session"
+        )));
+        assert!(!expr.matches(&entity(
+            "src/db.rs",
+            EntityKind::Class,
+            "SecurityTools/db/query"
+        )));
+    }
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        let e = entity("src/a.rs", EntityKind::Function, "Api/http/users");
+        // NOT > AND > OR: "kind:class OR kind:function AND area:Storage" = class OR (function AND Storage)
+        assert!(
+            !parse("kind:class OR kind:function AND area:Storage")
+                .unwrap()
+                .matches(&e)
+        );
+        assert!(
+            parse("(kind:class OR kind:function) AND area:Api")
+                .unwrap()
+                .matches(&e)
+        );
+        assert!(parse("NOT NOT kind:function").unwrap().matches(&e));
+    }
+
+    #[test]
+    fn test_quoted_area_and_case_insensitive_keywords() {
+        let e = entity(
+            "src/a.rs",
+            EntityKind::Method,
+            "Security/manage sessions/handle login",
+        );
+        let expr = parse(r#"area:"Security/manage sessions" and not Kind:class"#).unwrap();
+        assert!(expr.matches(&e));
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let err = parse("area:Security AND").unwrap_err();
+        assert_eq!(err.position, 17);
+        assert!(err.message.contains("end of input"));
+
+        let err = parse("area:Security kind:class").unwrap_err();
+        assert_eq!(err.position, 14);
+        assert!(err.message.contains("expected AND or OR"));
+
+        let err = parse("area:Security AND kind:widget").unwrap_err();
+        assert_eq!(err.position, 18);
+        assert!(err.message.contains("unknown entity kind"));
+
+        let err = parse("(area:Security OR kind:class").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert!(err.message.contains("unclosed"));
+
+        let err = parse("area:Security)").unwrap_err();
+        assert_eq!(err.position, 13);
+
+        let err = parse("path:src AND kind:class").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert!(err.message.contains("unknown filter"));
+
+        let err = parse(r#"area:"Security/auth AND kind:class"#).unwrap_err();
+        assert_eq!(err.position, 5);
+        assert!(err.message.contains("unterminated"));
+
+        let err = parse("glob:src/[ AND kind:class").unwrap_err();
+        assert_eq!(err.position, 0);
+        assert_eq!(err.message, compile_glob("src/[").unwrap_err());
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let mut e = entity("src/a.rs", EntityKind::Function, "Api/http/users");
+        e.tags.insert("deprecated".to_string());
+        assert!(is_expression("tag:deprecated"));
+        assert!(parse("tag:Deprecated AND area:Api").unwrap().matches(&e));
+        assert!(!parse("tag:hot-path").unwrap().matches(&e));
+        assert!(parse("NOT tag:hot-path").unwrap().matches(&e));
+    }
+
+    #[test]
+    fn test_error_display_points_at_column() {
+        let err = parse("kind:class AND AND area:Api").unwrap_err();
+        let rendered = err.to_string();
+        assert!(rendered.starts_with("Invalid scope expression at column 16:"));
+        assert!(rendered.ends_with(&format!("  {}^", " ".repeat(15))));
+    }
+
+    #[test]
+    fn test_parse_lenient_plain_strings() {
+        let e = entity(
+            "src/auth/login.rs",
+            EntityKind::Function,
+            "Security/auth/login",
+        );
+        assert!(parse_lenient("Security/auth").unwrap().matches(&e));
+        assert!(parse_lenient("src/auth/**").unwrap().matches(&e));
+        assert!(!parse_lenient("Storage").unwrap().matches(&e));
+        assert!(
+            parse_lenient("area:Security AND kind:function")
+                .unwrap()
+                .matches(&e)
+        );
+    }
+
+    #[test]
+    fn test_invalid_glob_reported_alike_in_plain_and_expression_scopes() {
+        let plain = parse_lenient("src/[oops*").unwrap_err();
+        let filter = parse_lenient("glob:src/[oops*").unwrap_err();
+        assert_eq!(plain.message, filter.message);
+        assert_eq!(plain.message, compile_glob("src/[oops*").unwrap_err());
+    }
+
+    #[test]
+    fn test_validate_passes_plain_scopes() {
+        assert!(validate("Security/auth").is_ok());
+        assert!(validate("src/**").is_ok());
+        assert!(validate("area:Security AND").is_err());
+    }
+
+    #[test]
+    fn test_compile_glob_reports_pattern_and_hint() {
+        assert!(compile_glob("src/**/*.rs").is_ok());
+        let err = compile_glob("src/[oops").unwrap_err();
+        assert!(
+            err.starts_with("Invalid glob pattern 'src/[oops'"),
+            "{}",
+            err
+        );
+        assert!(err.contains("`[[]`"), "{}", err);
+    }
+}
@@ -0,0 +1,421 @@
+//! Normalized source tokens and MinHash source signatures.
+//!
+//! Tokenization strips whitespace and comments and normalizes identifiers and
+//! literals, so renamed (Type-2) clones produce the same token stream. Signatures
+//! are computed when a graph is built or updated and compared by duplication
+//! detection.
+
+use crate::graph::{Entity, EntityKind, RPGraph};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Base multiplier for rolling hash (per paper: typically 256)
+pub const HASH_BASE: u64 = 256;
+
+/// Large prime modulus to prevent overflow (per paper: 10^9 + 7)
+pub const HASH_MOD: u64 = 1_000_000_007;
+
+/// Minimum duplicate length in tokens to report (filters noise).
+/// Lowered from 30 (file-level) to 15 for entity-level snippets.
+pub const MIN_DUPLICATE_TOKENS: usize = 15;
+
+/// Number of MinHash values per source signature.
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// Tokens per shingle when building source signatures.
+const SHINGLE_SIZE: usize = 6;
+
+/// Token type for normalized code representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenType {
+    /// Identifier (variable, function, class name) - normalized
+    Identifier,
+    /// Keyword (if, else, fn, let, etc.)
+    Keyword,
+    /// Operator (+, -, *, /, =, etc.)
+    Operator,
+    /// Literal (number, string - replaced with placeholder)
+    Literal,
+    /// Punctuation ({, }, (, ), ;, etc.)
+    Punctuation,
+}
+
+/// A normalized token for fingerprinting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub kind: TokenType,
+    pub value: u64,
+}
+
+/// Tokenize source code into normalized tokens.
+///
+/// Per paper §3.4: strip whitespace/comments, normalize identifiers,
+/// replace literals with placeholders for Type-2 clone detection.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            // Skip whitespace
+            ' ' | '\t' | '\n' | '\r' => {
+                chars.next();
+            }
+            // Single-line comment
+            '/' if chars.clone().nth(1) == Some('/') => {
+                chars.next();
+                chars.next();
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            // Multi-line comment (Rust-style)
+            '/' if chars.clone().nth(1) == Some('*') => {
+                chars.next();
+                chars.next();
+                while let Some(&c) = chars.peek() {
+                    if c == '*' && chars.clone().nth(1) == Some('/') {
+                        chars.next();
+                        chars.next();
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            // String literal
+            '"' | '\'' => {
+                let quote = ch;
+                chars.next();
+                while let Some(&c) = chars.peek() {
+                    chars.next();
+                    if c == quote {
+                        break;
+                    }
+                    if c == '\\' {
+                        chars.next();
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenType::Literal,
+                    value: hash_str("LIT"),
+                });
+            }
+            // Number literal
+            '0'..='9' => {
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit()
+                        || c == '.'
+                        || c == 'x'
+                        || c == 'X'
+                        || c == 'e'
+                        || c == 'E'
+                    {
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token {
+                    kind: TokenType::Literal,
+                    value: hash_str("LIT"),
+                });
+            }
+            // Identifier or keyword
+            'a'..='z' | 'A'..='Z' | '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let kind = if is_keyword(&ident) {
+                    TokenType::Keyword
+                } else {
+                    TokenType::Identifier
+                };
+                // Normalize identifiers: hash by kind, not by name (Type-2 detection)
+                tokens.push(Token {
+                    kind,
+                    value: if kind == TokenType::Keyword {
+                        hash_str(&ident)
+                    } else {
+                        hash_str("ID")
+                    },
+                });
+            }
+            // Operators (multi-char first)
+            '<' | '>' | '=' | '!' | '&' | '|' | '+' | '-' | '*' | '/' | '%' | '^' => {
+                let mut op = String::new();
+                op.push(chars.next().unwrap());
+                // Check for two-char operators
+                if let Some(&c) = chars.peek()
+                    && matches!(c, '=' | '&' | '|' | '<' | '>' | '+')
+                {
+                    op.push(c);
+                    chars.next();
+                }
+                tokens.push(Token {
+                    kind: TokenType::Operator,
+                    value: hash_str(&op),
+                });
+            }
+            // Punctuation
+            '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' | '#' | '@' | '~' | '?' => {
+                tokens.push(Token {
+                    kind: TokenType::Punctuation,
+                    value: hash_str(&ch.to_string()),
+                });
+                chars.next();
+            }
+            // Unknown - skip
+            _ => {
+                chars.next();
+            }
+        }
+    }
+
+    tokens
+}
+
+/// Check if a string is a programming language keyword.
+#[allow(clippy::match_same_arms)]
+fn is_keyword(s: &str) -> bool {
+    matches!(
+        s,
+        // Rust
+        "fn" | "let" | "mut" | "const" | "static" | "pub" | "mod" | "use" | "crate" | "self"
+        | "Self" | "super" | "struct" | "enum" | "impl" | "trait" | "type" | "where" | "async"
+        | "await" | "move" | "ref" | "match" | "if" | "else" | "loop" | "while" | "for" | "in"
+        | "return" | "break" | "continue" | "unsafe" | "extern" | "dyn" | "as"
+        // TypeScript/JavaScript
+        | "function" | "var" | "class" | "interface" | "extends" | "implements" | "import"
+        | "export" | "from" | "default" | "new" | "this" | "typeof" | "instanceof" | "void"
+        | "null" | "undefined" | "true" | "false" | "try" | "catch" | "finally" | "throw"
+        | "switch" | "case" | "do" | "delete" | "yield" | "constructor" | "readonly"
+        // Python
+        | "def" | "lambda" | "pass" | "raise" | "except" | "with" | "assert" | "global"
+        | "nonlocal" | "print" | "elif"
+        // Go
+        | "package" | "go" | "chan" | "select" | "defer" | "fallthrough" | "goto" | "range"
+        | "map" | "make" | "append" | "copy"
+        // Java
+        | "public" | "private" | "protected" | "final" | "abstract" | "synchronized"
+        | "volatile" | "transient" | "native" | "strictfp" | "throws"
+        // C/C++
+        | "int" | "char" | "float" | "double" | "long" | "short" | "unsigned" | "signed"
+        | "auto" | "register" | "inline" | "restrict" | "sizeof" | "typedef"
+    )
+}
+
+/// Hash a string to a u64 value.
+fn hash_str(s: &str) -> u64 {
+    let mut hash: u64 = 0;
+    for byte in s.bytes() {
+        hash = (hash.wrapping_mul(HASH_BASE).wrapping_add(u64::from(byte))) % HASH_MOD;
+    }
+    hash
+}
+
+/// Compute the b-bit MinHash signature of a source snippet.
+///
+/// The snippet is tokenized with [`tokenize`] (identifiers and literals
+/// normalized, comments stripped), split into shingles of consecutive tokens, and
+/// hashed under [`SIGNATURE_SIZE`] seeded mixers. Only the top 16 bits of each
+/// minimum are kept. Snippets shorter than the minimum duplicate length return an
+/// empty signature.
+pub fn source_signature(source: &str) -> Vec<u16> {
+    let tokens = tokenize(source);
+    if tokens.len() < MIN_DUPLICATE_TOKENS {
+        return Vec::new();
+    }
+    let shingles: HashSet<u64> = tokens
+        .windows(SHINGLE_SIZE)
+        .map(|window| {
+            window.iter().fold(0xcbf2_9ce4_8422_2325_u64, |h, t| {
+                (h ^ t.value).wrapping_mul(0x0100_0000_01b3)
+            })
+        })
+        .collect();
+
+    (0..SIGNATURE_SIZE as u64)
+        .map(|i| {
+            let seed = mix64(i + 1);
+            let min = shingles
+                .iter()
+                .map(|&shingle| mix64(shingle ^ seed))
+                .min()
+                .unwrap_or(u64::MAX);
+            u16::try_from(min >> 48).unwrap_or(u16::MAX)
+        })
+        .collect()
+}
+
+/// Estimated Jaccard similarity of two source signatures (0.0 if incomparable).
+pub fn signature_similarity(a: &[u16], b: &[u16]) -> f64 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let matches = a.iter().zip(b).filter(|(x, y)| x == y).count();
+    matches as f64 / a.len() as f64
+}
+
+/// SplitMix64 finalizer: a cheap, well-distributed 64-bit mixer.
+fn mix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Extract an entity's source lines (1-indexed, inclusive) from its file contents.
+pub fn entity_source(file_source: &str, entity: &Entity) -> Option<String> {
+    let lines: Vec<&str> = file_source.lines().collect();
+    let start = entity.line_start.saturating_sub(1);
+    let end = entity.line_end.min(lines.len());
+    (start < end).then(|| lines[start..end].join("\n"))
+}
+
+/// Compute and store source signatures for non-Module entities.
+///
+/// With `files`, only entities in those files are refreshed (incremental update);
+/// otherwise every entity is. Returns the number of entities given a signature.
+pub fn compute_source_signatures(
+    graph: &mut RPGraph,
+    project_root: &Path,
+    files: Option<&[PathBuf]>,
+) -> usize {
+    let target_files: Vec<PathBuf> = match files {
+        Some(files) => files.to_vec(),
+        None => graph.file_index.keys().cloned().collect(),
+    };
+
+    let mut count = 0;
+    for file in target_files {
+        let Some(ids) = graph.file_index.get(&file).cloned() else {
+            continue;
+        };
+        let source = std::fs::read_to_string(project_root.join(&file)).ok();
+        for id in ids {
+            let Some(entity) = graph.entities.get_mut(&id) else {
+                continue;
+            };
+            if entity.kind == EntityKind::Module {
+                continue;
+            }
+            entity.source_signature = source
+                .as_deref()
+                .and_then(|s| entity_source(s, entity))
+                .map(|s| source_signature(&s))
+                .unwrap_or_default();
+            if !entity.source_signature.is_empty() {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_simple() {
+        let source = "fn foo() { let x = 1; }";
+        let tokens = tokenize(source);
+
+        // Should have tokens for: fn, foo, (, ), {, let, x, =, 1, ;, }
+        assert!(!tokens.is_empty());
+        assert!(tokens.len() >= 8);
+    }
+
+    #[test]
+    fn test_tokenize_normalizes_identifiers() {
+        let source1 = "let foo = 1;";
+        let source2 = "let bar = 2;";
+
+        let tokens1 = tokenize(source1);
+        let tokens2 = tokenize(source2);
+
+        // Both should have same token sequence (identifiers normalized to "ID")
+        let values1: Vec<_> = tokens1.iter().map(|t| t.value).collect();
+        let values2: Vec<_> = tokens2.iter().map(|t| t.value).collect();
+
+        // Keywords and structure should match
+        assert_eq!(tokens1.len(), tokens2.len());
+        assert_eq!(values1, values2);
+    }
+
+    #[test]
+    fn test_tokenize_strips_comments() {
+        let source = "fn foo() { /* comment */ let x = 1; }\n// line comment\nlet y = 2;";
+        let tokens = tokenize(source);
+
+        // Comments should be stripped
+        let token_values: Vec<_> = tokens.iter().map(|t| t.value).collect();
+        assert!(!token_values.iter().any(|&v| v == hash_str("comment")));
+    }
+
+    #[test]
+    fn test_tokenize_normalizes_literals() {
+        let source1 = "let x = 42;";
+        let source2 = "let x = 999999;";
+
+        let tokens1 = tokenize(source1);
+        let tokens2 = tokenize(source2);
+
+        // Literals should both be normalized to same value
+        let lit1 = tokens1.iter().find(|t| t.kind == TokenType::Literal);
+        let lit2 = tokens2.iter().find(|t| t.kind == TokenType::Literal);
+
+        assert_eq!(lit1.map(|t| t.value), lit2.map(|t| t.value));
+    }
+
+    #[test]
+    fn test_type2_clone_detection() {
+        // Type-2: same structure, renamed identifiers
+        let source1 = r"
+            fn process_data(input: &str) -> String {
+                let result = input.to_uppercase();
+                result.trim().to_string()
+            }
+        ";
+
+        let source2 = r"
+            fn handle_text(data: &str) -> String {
+                let output = data.to_uppercase();
+                output.trim().to_string()
+            }
+        ";
+
+        let tokens1 = tokenize(source1);
+        let tokens2 = tokenize(source2);
+
+        // Structure should be identical after normalization
+        let values1: Vec<_> = tokens1.iter().map(|t| t.value).collect();
+        let values2: Vec<_> = tokens2.iter().map(|t| t.value).collect();
+
+        assert_eq!(
+            values1, values2,
+            "Type-2 clones should normalize to same tokens"
+        );
+    }
+
+    #[test]
+    fn test_is_keyword() {
+        assert!(is_keyword("fn"));
+        assert!(is_keyword("let"));
+        assert!(is_keyword("function"));
+        assert!(is_keyword("class"));
+        assert!(is_keyword("def"));
+        assert!(!is_keyword("my_function"));
+        assert!(!is_keyword("MyClass"));
+        assert!(!is_keyword("variable_name"));
+    }
+}
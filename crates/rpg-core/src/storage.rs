@@ -62,17 +62,22 @@ pub fn load(project_root: &Path) -> Result<RPGraph> {
     let path = rpg_file(project_root);
     let raw =
         fs::read(&path).with_context(|| format!("failed to read RPG from {}", path.display()))?;
+    from_bytes(&raw)
+}
 
+/// Decode a graph file's contents (plain or zstd-compressed JSON), e.g. a
+/// downloaded CI artifact, and rebuild its indexes.
+pub fn from_bytes(raw: &[u8]) -> Result<RPGraph> {
     let json = if raw.len() >= 4 && raw[..4] == ZSTD_MAGIC {
         // Decompress zstd
-        let mut decoder = zstd::Decoder::new(&raw[..]).context("failed to init zstd decoder")?;
+        let mut decoder = zstd::Decoder::new(raw).context("failed to init zstd decoder")?;
         let mut decompressed = String::new();
         decoder
             .read_to_string(&mut decompressed)
             .context("failed to decompress graph.json")?;
         decompressed
     } else {
-        String::from_utf8(raw).context("graph.json is not valid UTF-8")?
+        String::from_utf8(raw.to_vec()).context("graph.json is not valid UTF-8")?
     };
 
     let mut graph = schema::from_json(&json)?;
@@ -118,7 +123,7 @@ pub fn save_with_config(
     if !inner_gitignore.exists() {
         let _ = fs::write(
             &inner_gitignore,
-            "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\npending_routing.json\n",
+            "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\nembeddings.clusters.bin\npending_routing.json\nlast_query.toml\ndoctor-report.md\nreconstruction_state.json\n",
         );
     }
 
@@ -142,7 +147,7 @@ pub fn ensure_gitignore(project_root: &Path) -> Result<bool> {
     }
     fs::write(
         &inner_gitignore,
-        "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\npending_routing.json\n",
+        "config.toml\nmodels/\nembeddings.bin\nembeddings.meta.json\nembeddings.clusters.bin\npending_routing.json\nlast_query.toml\ndoctor-report.md\nreconstruction_state.json\n",
     )?;
     Ok(false)
 }
@@ -152,6 +157,11 @@ pub fn pending_routing_file(project_root: &Path) -> PathBuf {
     rpg_dir(project_root).join("pending_routing.json")
 }
 
+/// Get the path to the reconstruction progress file.
+pub fn reconstruction_state_file(project_root: &Path) -> PathBuf {
+    rpg_dir(project_root).join("reconstruction_state.json")
+}
+
 /// Get the path to the embeddings binary file.
 pub fn embeddings_file(project_root: &Path) -> PathBuf {
     rpg_dir(project_root).join("embeddings.bin")
@@ -0,0 +1,309 @@
+//! Structural (file-path) hierarchy layout, used before semantic lifting.
+//!
+//! Paths have up to three levels — area / category / file stem — derived from
+//! the directory tree with three adjustments:
+//!
+//! - Workspace packages (from [`detect_packages`]) become areas, named after
+//!   their directory, so `crates/rpg-core/src/graph.rs` lands in `rpg-core`.
+//! - Chains of directories that hold nothing but one subdirectory collapse into
+//!   a single level (`lib/vendored/json` → `lib.vendored.json`). A package
+//!   absorbs such a chain under its own name.
+//! - A node with more than [`MAX_STRUCTURAL_CHILDREN`] children keeps the
+//!   largest ones and groups the rest under [`OVERFLOW_NODE`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+/// Maximum children per structural hierarchy node, including the overflow bucket.
+pub const MAX_STRUCTURAL_CHILDREN: usize = 16;
+
+/// Name of the node that collects children beyond [`MAX_STRUCTURAL_CHILDREN`].
+pub const OVERFLOW_NODE: &str = "other";
+
+/// Separator for directory names merged into one level. Not `/`, which
+/// separates hierarchy levels.
+const CHAIN_SEPARATOR: &str = ".";
+
+/// Workspace member directories, relative to `project_root`, declared by a
+/// Cargo workspace (`[workspace] members`, minus `exclude`) or an npm/yarn
+/// workspace (`workspaces` in package.json). Sorted, without duplicates.
+///
+/// `*` and `?` match within one path component; `**` is treated as `*`.
+pub fn detect_packages(project_root: &Path) -> Vec<PathBuf> {
+    let mut members: BTreeSet<PathBuf> = BTreeSet::new();
+    let mut excluded: BTreeSet<PathBuf> = BTreeSet::new();
+
+    if let Ok(content) = std::fs::read_to_string(project_root.join("Cargo.toml"))
+        && let Ok(manifest) = content.parse::<toml::Table>()
+        && let Some(workspace) = manifest.get("workspace").and_then(|w| w.as_table())
+    {
+        for pattern in string_array(workspace.get("members")) {
+            members.extend(expand_member(project_root, pattern));
+        }
+        for pattern in string_array(workspace.get("exclude")) {
+            excluded.extend(expand_member(project_root, pattern));
+        }
+    }
+
+    if let Ok(content) = std::fs::read_to_string(project_root.join("package.json"))
+        && let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content)
+    {
+        let workspaces = &manifest["workspaces"];
+        let patterns = workspaces
+            .as_array()
+            .or_else(|| workspaces["packages"].as_array());
+        for pattern in patterns.into_iter().flatten().filter_map(|p| p.as_str()) {
+            members.extend(expand_member(project_root, pattern));
+        }
+    }
+
+    members
+        .into_iter()
+        .filter(|m| !m.as_os_str().is_empty() && !excluded.contains(m))
+        .collect()
+}
+
+fn string_array(value: Option<&toml::Value>) -> impl Iterator<Item = &str> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+}
+
+/// Existing directories matching a member pattern like `crates/*`.
+fn expand_member(project_root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut current = vec![PathBuf::new()];
+    for part in pattern.split('/').filter(|p| !p.is_empty() && *p != ".") {
+        if !part.contains(['*', '?']) {
+            for path in &mut current {
+                path.push(part);
+            }
+            continue;
+        }
+        let mut next = Vec::new();
+        for dir in &current {
+            let Ok(entries) = std::fs::read_dir(project_root.join(dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !name.starts_with('.')
+                    && entry.file_type().is_ok_and(|t| t.is_dir())
+                    && wildcard_match(part, &name)
+                {
+                    next.push(dir.join(name));
+                }
+            }
+        }
+        current = next;
+    }
+    current.retain(|p| project_root.join(p).is_dir());
+    current
+}
+
+/// Match `*` (any run) and `?` (one character) within a single component.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matches[j]: pattern[..i] matches name[..j]
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for &p in &pattern {
+        let mut next = vec![false; name.len() + 1];
+        if p == '*' {
+            let mut any = false;
+            for j in 0..=name.len() {
+                any |= matches[j];
+                next[j] = any;
+            }
+        } else {
+            for j in 1..=name.len() {
+                next[j] = matches[j - 1] && (p == '?' || p == name[j - 1]);
+            }
+        }
+        matches = next;
+    }
+    matches[name.len()]
+}
+
+#[derive(Default)]
+struct DirNode {
+    /// Keeps its own name when absorbing a single-child chain.
+    package: bool,
+    /// File path → file stem, for files directly in this directory.
+    files: BTreeMap<PathBuf, String>,
+    children: BTreeMap<String, DirNode>,
+}
+
+impl DirNode {
+    /// Merge directories that contain only a single subdirectory into one level.
+    fn collapse(self, name: String) -> (String, DirNode) {
+        let mut name = name;
+        let mut node = self;
+        while node.files.is_empty() && node.children.len() == 1 {
+            let (child_name, child) = node.children.pop_first().unwrap();
+            if !node.package {
+                name = format!("{}{}{}", name, CHAIN_SEPARATOR, child_name);
+            }
+            node = DirNode {
+                package: node.package,
+                ..child
+            };
+        }
+        node.children = collapse_children(node.children);
+        (name, node)
+    }
+
+    fn merge(&mut self, other: DirNode) {
+        self.package |= other.package;
+        self.files.extend(other.files);
+        for (name, child) in other.children {
+            self.children.entry(name).or_default().merge(child);
+        }
+    }
+
+    /// Record each file's path: the first two directory levels plus its stem.
+    fn paths(&self, prefix: &[&str], out: &mut BTreeMap<PathBuf, Vec<String>>) {
+        for (file, stem) in &self.files {
+            let mut path: Vec<String> = prefix.iter().take(2).map(|s| s.to_string()).collect();
+            path.push(stem.clone());
+            out.insert(file.clone(), path);
+        }
+        for (name, child) in &self.children {
+            let mut child_prefix = prefix.to_vec();
+            child_prefix.push(name);
+            child.paths(&child_prefix, out);
+        }
+    }
+}
+
+fn collapse_children(children: BTreeMap<String, DirNode>) -> BTreeMap<String, DirNode> {
+    let mut collapsed: BTreeMap<String, DirNode> = BTreeMap::new();
+    for (name, child) in children {
+        let (name, child) = child.collapse(name);
+        collapsed.entry(name).or_default().merge(child);
+    }
+    collapsed
+}
+
+fn file_stem(name: &str) -> &str {
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Compute the structural hierarchy path of every file. `files` maps each
+/// file to its entity count, which decides which children survive the cap.
+pub fn structural_paths(
+    files: &BTreeMap<PathBuf, usize>,
+    packages: &[PathBuf],
+) -> BTreeMap<PathBuf, String> {
+    // Area names for packages: the directory name, or the full path when two
+    // packages share a directory name.
+    let mut name_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for package in packages {
+        if let Some(name) = package.file_name().and_then(|n| n.to_str()) {
+            *name_counts.entry(name).or_default() += 1;
+        }
+    }
+    let package_name = |package: &Path| -> String {
+        let name = package
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if name_counts.get(name).copied().unwrap_or(0) > 1 {
+            normal_components(package).join(CHAIN_SEPARATOR)
+        } else {
+            name.to_string()
+        }
+    };
+
+    let mut root = DirNode::default();
+    for file in files.keys() {
+        let package = packages
+            .iter()
+            .filter(|p| file.starts_with(p) && file != *p)
+            .max_by_key(|p| p.components().count());
+        let (mut node, rel) = match package {
+            Some(package) => {
+                let node = root.children.entry(package_name(package)).or_default();
+                node.package = true;
+                (node, file.strip_prefix(package).unwrap_or(file))
+            }
+            None => (&mut root, file.as_path()),
+        };
+        let components = normal_components(rel);
+        let Some((name, dirs)) = components.split_last() else {
+            continue;
+        };
+        for dir in dirs {
+            node = node.children.entry((*dir).to_string()).or_default();
+        }
+        node.files.insert(file.clone(), file_stem(name).to_string());
+    }
+    root.children = collapse_children(root.children);
+
+    let mut paths: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+    root.paths(&[], &mut paths);
+    cap_children(&mut paths, files);
+
+    paths
+        .into_iter()
+        .map(|(file, path)| (file, path.join("/")))
+        .collect()
+}
+
+fn normal_components(path: &Path) -> Vec<&str> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => s.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Top-down, replace all but the largest `MAX_STRUCTURAL_CHILDREN - 1`
+/// children of any node with [`OVERFLOW_NODE`]. Size is the entity count
+/// below the child; ties go to the alphabetically first name.
+fn cap_children(paths: &mut BTreeMap<PathBuf, Vec<String>>, files: &BTreeMap<PathBuf, usize>) {
+    let depth = paths.values().map(Vec::len).max().unwrap_or(0);
+    for level in 0..depth {
+        // Parent path → child name → entity count
+        let mut sizes: BTreeMap<Vec<String>, BTreeMap<String, usize>> = BTreeMap::new();
+        for (file, path) in paths.iter() {
+            if let Some(child) = path.get(level) {
+                *sizes
+                    .entry(path[..level].to_vec())
+                    .or_default()
+                    .entry(child.clone())
+                    .or_default() += files.get(file).copied().unwrap_or(0);
+            }
+        }
+
+        let mut overflow: BTreeMap<Vec<String>, BTreeSet<String>> = BTreeMap::new();
+        for (parent, children) in sizes {
+            if children.len() <= MAX_STRUCTURAL_CHILDREN {
+                continue;
+            }
+            let mut ranked: Vec<(String, usize)> = children.into_iter().collect();
+            ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let dropped = ranked
+                .into_iter()
+                .skip(MAX_STRUCTURAL_CHILDREN - 1)
+                .map(|(name, _)| name)
+                .collect();
+            overflow.insert(parent, dropped);
+        }
+        if overflow.is_empty() {
+            continue;
+        }
+        for path in paths.values_mut() {
+            if path.len() > level
+                && overflow
+                    .get(&path[..level])
+                    .is_some_and(|dropped| dropped.contains(&path[level]))
+            {
+                path[level] = OVERFLOW_NODE.to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Token estimation for lifting batches and retrieval budgets.
+//!
+//! Estimators only need to be close enough to keep a batch or context pack
+//! inside the model's window; exact counts come back from the provider after
+//! the call. The BPE estimator lives in `rpg-encoder` behind its `bpe` feature.
+
+/// Estimates how many tokens a model will count for a piece of text.
+pub trait TokenEstimator: Send + Sync {
+    /// Estimated token count of `text`.
+    fn estimate(&self, text: &str) -> usize;
+
+    /// Short description for batch headers, e.g. `3.2 chars/token (anthropic)`.
+    fn describe(&self) -> String;
+}
+
+/// Four bytes per token: the historical default. Under-counts dense code.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Heuristic;
+
+impl TokenEstimator for Heuristic {
+    fn estimate(&self, text: &str) -> usize {
+        text.len() / 4
+    }
+
+    fn describe(&self) -> String {
+        "chars/4 heuristic".to_string()
+    }
+}
+
+/// A fixed characters-per-token ratio, rounded up.
+#[derive(Debug, Clone)]
+pub struct CharsPerToken {
+    pub ratio: f64,
+    /// Provider the ratio was configured for, shown in [`describe`](TokenEstimator::describe).
+    pub provider: Option<String>,
+}
+
+impl TokenEstimator for CharsPerToken {
+    fn estimate(&self, text: &str) -> usize {
+        let chars = text.chars().count();
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        {
+            (chars as f64 / self.ratio).ceil() as usize
+        }
+    }
+
+    fn describe(&self) -> String {
+        match &self.provider {
+            Some(provider) => format!("{} chars/token ({})", self.ratio, provider),
+            None => format!("{} chars/token", self.ratio),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_matches_bytes_over_four() {
+        assert_eq!(Heuristic.estimate(""), 0);
+        assert_eq!(Heuristic.estimate("fn main() {}"), 3);
+    }
+
+    #[test]
+    fn test_ratio_rounds_up_and_describes_provider() {
+        let ratio = CharsPerToken {
+            ratio: 2.5,
+            provider: Some("anthropic".to_string()),
+        };
+        assert_eq!(ratio.estimate("fn main() {}"), 5);
+        assert_eq!(ratio.describe(), "2.5 chars/token (anthropic)");
+    }
+}
@@ -0,0 +1,95 @@
+//! Best-effort entity visibility from declaration keywords and the naming
+//! conventions of each language.
+
+use crate::graph::{Entity, EntityKind};
+use serde::Serialize;
+
+/// Best-effort visibility, read from the declaration line and naming
+/// conventions of the file's language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Public,
+    Private,
+    Unknown,
+}
+
+impl Visibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Private => "private",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// First line in the entity's range that mentions its name, skipping
+/// decorators and attributes above the declaration. 0-based.
+pub fn declaration_line<'a>(entity: &Entity, lines: &[&'a str]) -> Option<(usize, &'a str)> {
+    if entity.kind == EntityKind::Module {
+        return None;
+    }
+    let start = entity.line_start.saturating_sub(1);
+    let end = entity.line_end.min(lines.len());
+    (start..end)
+        .map(|i| (i, lines[i]))
+        .find(|(_, text)| text.contains(entity.name.as_str()))
+}
+
+/// Visibility of `entity`, reading its declaration from `lines` (the file's
+/// source, one entry per line).
+pub fn entity_visibility(entity: &Entity, lines: &[&str]) -> Visibility {
+    infer_visibility(
+        entity,
+        declaration_line(entity, lines).map(|(_, text)| text),
+    )
+}
+
+/// Visibility from declaration keywords, then the file language's naming
+/// convention.
+pub fn infer_visibility(entity: &Entity, decl: Option<&str>) -> Visibility {
+    if entity.kind == EntityKind::Module {
+        return Visibility::Public;
+    }
+    let ext = entity
+        .file
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    let name = entity.name.as_str();
+
+    if let Some(decl) = decl {
+        let words: Vec<&str> = decl
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|w| !w.is_empty())
+            .collect();
+        let has = |w: &str| words.contains(&w);
+        if has("private") || has("protected") || has("internal") || has("fileprivate") {
+            return Visibility::Private;
+        }
+        if has("pub") || has("public") || has("export") {
+            return Visibility::Public;
+        }
+        if ext == "rs" {
+            return Visibility::Private;
+        }
+    }
+
+    match ext {
+        "py" | "pyi" => {
+            if name.starts_with('_') && !(name.starts_with("__") && name.ends_with("__")) {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            }
+        }
+        "go" => match name.chars().next() {
+            Some(c) if c.is_uppercase() => Visibility::Public,
+            Some(_) => Visibility::Private,
+            None => Visibility::Unknown,
+        },
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" if name.starts_with('#') => Visibility::Private,
+        _ => Visibility::Unknown,
+    }
+}
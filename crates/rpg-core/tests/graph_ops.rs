@@ -1,4 +1,5 @@
 use rpg_core::graph::*;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str) -> Entity {
@@ -12,9 +13,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: vec!["test feature".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -255,6 +269,26 @@ fn test_assign_hierarchy_ids() {
     assert_eq!(graph.hierarchy["DataAccess"].id, "h:DataAccess");
 }
 
+#[test]
+fn test_find_hierarchy_node_by_id_or_path() {
+    let mut graph = RPGraph::new("rust");
+    graph.insert_into_hierarchy("Security/auth/token", "e1");
+    graph.assign_hierarchy_ids();
+
+    let by_id = graph.find_hierarchy_node("h:Security/auth").unwrap();
+    let by_path = graph.find_hierarchy_node("Security/auth").unwrap();
+    assert_eq!(by_id.id, "h:Security/auth");
+    assert_eq!(by_path.id, by_id.id);
+    assert_eq!(
+        graph
+            .find_hierarchy_node("/Security/auth/token/")
+            .unwrap()
+            .id,
+        hierarchy_node_id("Security/auth/token")
+    );
+    assert!(graph.find_hierarchy_node("Security/missing").is_none());
+}
+
 #[test]
 fn test_aggregate_features() {
     let mut graph = RPGraph::new("rust");
@@ -290,6 +324,73 @@ fn test_aggregate_features() {
     );
 }
 
+/// One large, heavily used hub and thirty small helpers in the same area.
+fn hub_and_helpers_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let mut hub = make_entity("src/core.rs:Engine", "Engine", "src/core.rs");
+    hub.line_end = 2000;
+    hub.semantic_features = vec![
+        "build dependency graph".into(),
+        "parse source files".into(),
+        "resolve symbols".into(),
+    ];
+    hub.deps.invoked_by = (0..20).map(|i| format!("caller{}", i)).collect();
+    graph.insert_entity(hub);
+    graph.insert_into_hierarchy("Core/engine/run", "src/core.rs:Engine");
+    for i in 0..30 {
+        let id = format!("src/util{}.rs:helper", i);
+        let mut helper = make_entity(&id, "helper", &format!("src/util{}.rs", i));
+        helper.line_end = 3;
+        helper.semantic_features = vec![format!("format helper value {}", i)];
+        graph.insert_entity(helper);
+        graph.insert_into_hierarchy("Core/util/format", &id);
+    }
+    graph
+}
+
+#[test]
+fn test_aggregate_features_weighted_by_importance() {
+    let hub_features = [
+        "build dependency graph",
+        "parse source files",
+        "resolve symbols",
+    ];
+
+    // Without a limit every feature is kept, as the flat union did: the
+    // helpers make up 30 of the area's 33 features.
+    let mut graph = hub_and_helpers_graph();
+    graph.metadata.max_node_features = 0;
+    graph.aggregate_hierarchy_features();
+    let core = &graph.hierarchy["Core"];
+    assert_eq!(core.semantic_features.len(), 33);
+    let helpers = core
+        .semantic_features
+        .iter()
+        .filter(|f| f.starts_with("format helper"))
+        .count();
+    assert_eq!(helpers, 30);
+
+    // After: the hub's features lead the area and fill the top of its budget.
+    let mut graph = hub_and_helpers_graph();
+    graph.metadata.max_node_features = 5;
+    graph.aggregate_hierarchy_features();
+    let core = &graph.hierarchy["Core"];
+    assert_eq!(core.semantic_features.len(), 5);
+    assert_eq!(&core.semantic_features[..3], &hub_features);
+    for feature in hub_features {
+        assert_eq!(core.feature_weights[feature], 1.0);
+    }
+    let helper_weight = core.feature_weights[&core.semantic_features[3]];
+    assert!(helper_weight < 0.3, "helper weight {}", helper_weight);
+    // Each level keeps its own top features.
+    assert_eq!(
+        graph.hierarchy["Core"].children["util"]
+            .semantic_features
+            .len(),
+        5
+    );
+}
+
 #[test]
 fn test_materialize_containment_edges() {
     let mut graph = RPGraph::new("rust");
@@ -420,3 +521,36 @@ fn test_clear_reverse_includes_data_flows_from() {
     deps.clear_reverse();
     assert!(deps.data_flows_from.is_empty());
 }
+
+#[test]
+fn test_deps_summary_empty() {
+    let deps = EntityDeps::default();
+    assert!(deps.summary().is_empty());
+}
+
+#[test]
+fn test_deps_summary_populated() {
+    let deps = EntityDeps {
+        invoked_by: vec!["main".to_string()],
+        invokes: vec!["helper".to_string()],
+        inherits: vec!["Base".to_string()],
+        renders: vec!["Widget".to_string()],
+        ..Default::default()
+    };
+    let summary = deps.summary();
+    assert!(summary.contains("Called by: main"));
+    assert!(summary.contains("Calls: helper"));
+    assert!(summary.contains("Inherits: Base"));
+    assert!(summary.contains("Renders: Widget"));
+}
+
+#[test]
+fn test_deps_summary_caps_at_five() {
+    let deps = EntityDeps {
+        invokes: (1..=10).map(|i| format!("fn{}", i)).collect(),
+        ..Default::default()
+    };
+    let summary = deps.summary();
+    assert!(summary.contains("fn5"));
+    assert!(!summary.contains("fn6"));
+}
@@ -1,5 +1,6 @@
 use rpg_core::graph::*;
 use rpg_core::storage;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
@@ -14,9 +15,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: vec!["test".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Area/cat/sub".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -147,6 +161,30 @@ fn test_signature_none_backward_compat() {
     assert!(e.signature.is_none());
 }
 
+#[test]
+fn test_function_modifiers_roundtrip() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+
+    let mut graph = RPGraph::new("rust");
+    let mut fetch = make_entity("f.rs:fetch", "fetch", "f.rs");
+    fetch.is_async = true;
+    fetch.is_unsafe = true;
+    graph.insert_entity(fetch);
+    graph.insert_entity(make_entity("f.rs:main", "main", "f.rs"));
+    graph.refresh_metadata();
+    storage::save(root, &graph).unwrap();
+
+    let loaded = storage::load(root).unwrap();
+    let fetch = loaded.entities.get("f.rs:fetch").unwrap();
+    assert_eq!(fetch.modifiers(), vec!["unsafe", "async"]);
+    // Unset flags are left out of graph.json and default to false.
+    let main = loaded.entities.get("f.rs:main").unwrap();
+    assert!(main.modifiers().is_empty());
+    let json = serde_json::to_string(main).unwrap();
+    assert!(!json.contains("is_async"), "{}", json);
+}
+
 #[test]
 fn test_data_flow_deps_roundtrip() {
     let tmp = TempDir::new().unwrap();
@@ -172,3 +210,83 @@ fn test_data_flow_deps_roundtrip() {
     assert_eq!(e.deps.data_flows_from, vec!["f.rs:source".to_string()]);
     assert_eq!(loaded.edges[0].kind, EdgeKind::DataFlow);
 }
+
+#[test]
+fn test_graph_without_enum_or_constant_kinds_loads() {
+    // A graph written before enums and constants were entities: enums were
+    // classes, and constants were not extracted.
+    let legacy = r#"{
+        "version": "2.1.0",
+        "created_at": "2025-01-01T00:00:00Z",
+        "updated_at": "2025-01-01T00:00:00Z",
+        "metadata": {
+            "language": "rust",
+            "total_files": 1,
+            "total_entities": 2,
+            "functional_areas": 0,
+            "total_edges": 0,
+            "dependency_edges": 0,
+            "containment_edges": 0,
+            "lifted_entities": 0,
+            "liftable_kinds": ["function", "class", "method"]
+        },
+        "hierarchy": {},
+        "entities": {
+            "src/kind.rs:Kind": {
+                "id": "src/kind.rs:Kind", "kind": "class", "name": "Kind",
+                "file": "src/kind.rs", "line_start": 1, "line_end": 4,
+                "parent_class": null, "semantic_features": [],
+                "hierarchy_path": "", "deps": {
+                    "imports": [], "invokes": [], "inherits": [],
+                    "imported_by": [], "invoked_by": [], "inherited_by": []
+                }
+            },
+            "src/kind.rs:is_leaf": {
+                "id": "src/kind.rs:is_leaf", "kind": "function", "name": "is_leaf",
+                "file": "src/kind.rs", "line_start": 6, "line_end": 8,
+                "parent_class": null, "semantic_features": [],
+                "hierarchy_path": "", "deps": {
+                    "imports": [], "invokes": [], "inherits": [],
+                    "imported_by": [], "invoked_by": [], "inherited_by": []
+                }
+            }
+        },
+        "edges": [],
+        "file_index": {"src/kind.rs": ["src/kind.rs:Kind", "src/kind.rs:is_leaf"]}
+    }"#;
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+    std::fs::write(tmp.path().join(".rpg/graph.json"), legacy).unwrap();
+
+    let loaded = storage::load(tmp.path()).unwrap();
+    assert_eq!(loaded.entities["src/kind.rs:Kind"].kind, EntityKind::Class);
+    assert_eq!(
+        loaded.metadata.liftable_kinds,
+        vec![EntityKind::Function, EntityKind::Class, EntityKind::Method]
+    );
+}
+
+#[test]
+fn test_enum_and_constant_kinds_roundtrip() {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = RPGraph::new("rust");
+    let mut kind = make_entity("src/kind.rs:Kind", "Kind", "src/kind.rs");
+    kind.kind = EntityKind::Enum;
+    let mut max = make_entity("src/kind.rs:MAX_DEPTH", "MAX_DEPTH", "src/kind.rs");
+    max.kind = EntityKind::Constant;
+    graph.insert_entity(kind);
+    graph.insert_entity(max);
+    graph.refresh_metadata();
+    storage::save(tmp.path(), &graph).unwrap();
+
+    let loaded = storage::load(tmp.path()).unwrap();
+    assert_eq!(loaded.entities["src/kind.rs:Kind"].kind, EntityKind::Enum);
+    assert_eq!(
+        loaded.entities["src/kind.rs:MAX_DEPTH"].kind,
+        EntityKind::Constant
+    );
+    assert_eq!(
+        serde_json::to_string(&[EntityKind::Enum, EntityKind::Constant]).unwrap(),
+        r#"["enum","constant"]"#
+    );
+}
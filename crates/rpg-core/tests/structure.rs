@@ -0,0 +1,239 @@
+use rpg_core::graph::*;
+use rpg_core::structure::{MAX_STRUCTURAL_CHILDREN, OVERFLOW_NODE, detect_packages};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn make_entity(file: &str, name: &str) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+const WORKSPACE_FILES: &[&str] = &[
+    "crates/rpg-core/src/graph.rs",
+    "crates/rpg-core/src/storage.rs",
+    "crates/rpg-core/src/lca/mod.rs",
+    "crates/rpg-nav/src/search.rs",
+    "crates/rpg-nav/src/export/sarif.rs",
+    "crates/rpg-nav/build.rs",
+    "tools/codegen/src/main.rs",
+    "scripts/release/publish/upload.py",
+    "setup.py",
+];
+
+/// A cargo workspace on disk: members via glob plus an explicit path, one
+/// excluded crate, and the entity files above.
+fn make_workspace() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    std::fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\", \"tools/codegen\"]\nexclude = [\"crates/legacy\"]\n",
+    )
+    .unwrap();
+    for dir in ["crates/legacy/src", "scripts"] {
+        std::fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    for file in WORKSPACE_FILES {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+    tmp
+}
+
+fn make_graph(files: &[&str]) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for file in files {
+        graph.insert_entity(make_entity(file, "f"));
+    }
+    graph
+}
+
+fn path_of(graph: &RPGraph, file: &str) -> String {
+    graph.entities[&format!("{}:f", file)]
+        .hierarchy_path
+        .clone()
+}
+
+#[test]
+fn test_detect_cargo_workspace_members() {
+    let tmp = make_workspace();
+    assert_eq!(
+        detect_packages(tmp.path()),
+        vec![
+            PathBuf::from("crates/rpg-core"),
+            PathBuf::from("crates/rpg-nav"),
+            PathBuf::from("tools/codegen"),
+        ]
+    );
+    assert!(detect_packages(&tmp.path().join("scripts")).is_empty());
+}
+
+#[test]
+fn test_detect_npm_workspaces() {
+    let tmp = TempDir::new().unwrap();
+    for dir in ["packages/web", "packages/api", "apps/docs"] {
+        std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+    }
+    std::fs::write(
+        tmp.path().join("package.json"),
+        r#"{"workspaces": {"packages": ["packages/*", "apps/d?cs"]}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        detect_packages(tmp.path()),
+        vec![
+            PathBuf::from("apps/docs"),
+            PathBuf::from("packages/api"),
+            PathBuf::from("packages/web"),
+        ]
+    );
+}
+
+#[test]
+fn test_workspace_members_become_areas() {
+    let tmp = make_workspace();
+    let mut graph = make_graph(WORKSPACE_FILES);
+    graph.build_file_path_hierarchy_with_packages(&detect_packages(tmp.path()));
+
+    let areas: Vec<&str> = graph.hierarchy.keys().map(String::as_str).collect();
+    assert_eq!(
+        areas,
+        vec![
+            "codegen",
+            "rpg-core",
+            "rpg-nav",
+            "scripts.release.publish",
+            "setup"
+        ]
+    );
+    // A package absorbs its lone `src` directory.
+    assert_eq!(
+        path_of(&graph, "crates/rpg-core/src/graph.rs"),
+        "rpg-core/graph"
+    );
+    assert_eq!(
+        path_of(&graph, "crates/rpg-core/src/lca/mod.rs"),
+        "rpg-core/lca/mod"
+    );
+    assert_eq!(path_of(&graph, "tools/codegen/src/main.rs"), "codegen/main");
+    // With a build script beside it, `src` stays a level of its own.
+    assert_eq!(path_of(&graph, "crates/rpg-nav/build.rs"), "rpg-nav/build");
+    assert_eq!(
+        path_of(&graph, "crates/rpg-nav/src/export/sarif.rs"),
+        "rpg-nav/src/sarif"
+    );
+    // Outside any package, single-child chains merge into one level.
+    assert_eq!(
+        path_of(&graph, "scripts/release/publish/upload.py"),
+        "scripts.release.publish/upload"
+    );
+    assert_eq!(path_of(&graph, "setup.py"), "setup");
+    assert!(!graph.metadata.semantic_hierarchy);
+}
+
+#[test]
+fn test_single_child_chain_collapses_without_manifest() {
+    let mut graph = make_graph(&[
+        "crates/rpg-core/src/graph.rs",
+        "crates/rpg-core/src/storage.rs",
+    ]);
+    graph.build_file_path_hierarchy();
+    assert_eq!(
+        path_of(&graph, "crates/rpg-core/src/graph.rs"),
+        "crates.rpg-core.src/graph"
+    );
+    assert_eq!(graph.hierarchy.len(), 1);
+
+    // Unchanged layout for an ordinary tree.
+    let mut graph = make_graph(&["src/main.rs", "src/auth/login.rs", "src/auth/jwt/verify.rs"]);
+    graph.build_file_path_hierarchy();
+    assert_eq!(path_of(&graph, "src/main.rs"), "src/main");
+    assert_eq!(path_of(&graph, "src/auth/login.rs"), "src/auth/login");
+    assert_eq!(path_of(&graph, "src/auth/jwt/verify.rs"), "src/auth/verify");
+}
+
+#[test]
+fn test_overflow_children_grouped_deterministically() {
+    let count = MAX_STRUCTURAL_CHILDREN + 4;
+    let mut files: Vec<String> = (0..count).map(|i| format!("src/mod{:02}.rs", i)).collect();
+    files.push("src/other_dir/x.rs".to_string());
+    let mut graph = RPGraph::new("rust");
+    for (i, file) in files.iter().enumerate() {
+        graph.insert_entity(make_entity(file, "f"));
+        // The last module is the biggest, so it survives the cap.
+        if i == count - 1 {
+            graph.insert_entity(make_entity(file, "g"));
+        }
+    }
+    graph.build_file_path_hierarchy();
+
+    let src = &graph.hierarchy["src"];
+    assert_eq!(src.children.len(), MAX_STRUCTURAL_CHILDREN);
+    assert!(src.children.contains_key(OVERFLOW_NODE));
+    assert!(src.children.contains_key(&format!("mod{:02}", count - 1)));
+    assert!(src.children.contains_key("mod00"));
+    assert_eq!(path_of(&graph, &files[count - 2]), "src/other");
+    assert_eq!(path_of(&graph, "src/other_dir/x.rs"), "src/other/x");
+    assert_eq!(src.all_entity_ids().len(), count + 2);
+
+    // Rebuilding from the same graph gives the same layout.
+    let before: Vec<String> = graph
+        .hierarchy
+        .values()
+        .flat_map(|n| n.all_entity_ids())
+        .collect();
+    graph.build_file_path_hierarchy();
+    let after: Vec<String> = graph
+        .hierarchy
+        .values()
+        .flat_map(|n| n.all_entity_ids())
+        .collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_charters_survive_structural_rebuild() {
+    let mut graph = make_graph(&["src/net/http.rs", "src/net/tcp.rs", "src/db/pool.rs"]);
+    graph.build_file_path_hierarchy();
+    let area = path_of(&graph, "src/net/http.rs");
+    let area = area.rsplit_once('/').map_or(area.as_str(), |(dir, _)| dir);
+    assert!(graph.set_charter(area, Some("Wire protocols only".to_string())));
+    assert!(!graph.set_charter("nowhere", Some("x".to_string())));
+
+    graph.build_file_path_hierarchy();
+    assert_eq!(
+        graph.hierarchy_charters(),
+        BTreeMap::from([(area.to_string(), "Wire protocols only".to_string())])
+    );
+
+    // Clearing removes it.
+    assert!(graph.set_charter(&format!("h:{}", area), None));
+    assert!(graph.hierarchy_charters().is_empty());
+}
@@ -0,0 +1,249 @@
+//! Key/value annotations imported from external metadata (PR reviewers,
+//! ticket links, ...), via `rpg-encoder annotate --from-json` or the
+//! `annotate_entities` MCP tool.
+//!
+//! An annotation document names its `source` and targets entities by ID or
+//! by a file line range:
+//!
+//! ```json
+//! {
+//!   "source": "github",
+//!   "annotations": [
+//!     {"entity": "src/auth.py:login", "values": {"last_reviewer": "alice"}},
+//!     {"file": "src/auth.py", "lines": [10, 24], "values": {"pr": "#123"}}
+//!   ]
+//! }
+//! ```
+//!
+//! Values are stored in entity `attributes` as
+//! `annotation.<source>.<key>` ([`ANNOTATION_ATTRIBUTE_PREFIX`]), which
+//! [`crate::evolution::merge_features`] carries over rebuilds. A `null` value
+//! removes the annotation.
+
+use anyhow::{Context, Result};
+use rpg_core::graph::{ANNOTATION_ATTRIBUTE_PREFIX, EntityKind, RPGraph, normalize_path};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// A parsed annotation document.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnnotationDocument {
+    /// Where the annotations come from (e.g. `github`); part of every key.
+    pub source: String,
+    pub annotations: Vec<Annotation>,
+}
+
+/// One annotation entry: a target and the values to set on it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Annotation {
+    /// Entity ID target.
+    #[serde(default)]
+    pub entity: Option<String>,
+    /// File target, narrowed by `lines`.
+    #[serde(default)]
+    pub file: Option<String>,
+    /// 1-based inclusive `[start, end]` within `file`. Defaults to the whole file.
+    #[serde(default)]
+    pub lines: Option<[usize; 2]>,
+    /// Values by key; `null` removes the key.
+    pub values: BTreeMap<String, Option<String>>,
+}
+
+impl Annotation {
+    /// Human-readable target, for reports.
+    pub fn target(&self) -> String {
+        match (&self.entity, &self.file, self.lines) {
+            (Some(id), _, _) => id.clone(),
+            (None, Some(file), Some([start, end])) => format!("{}:{}-{}", file, start, end),
+            (None, Some(file), None) => file.clone(),
+            (None, None, _) => "(no target)".to_string(),
+        }
+    }
+}
+
+/// Outcome of [`apply_annotations`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AnnotationReport {
+    /// Entities with at least one attribute changed.
+    pub entities_annotated: usize,
+    /// Attributes set (added or changed).
+    pub values_set: usize,
+    /// Attributes removed by `null` values.
+    pub values_removed: usize,
+    /// Targets that matched no entity.
+    pub unmatched: Vec<String>,
+}
+
+/// Whether `s` is a usable source or key: ASCII letters, digits, `_`, `-`.
+fn is_valid_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Parse and validate an annotation document.
+pub fn parse_annotations(json: &str) -> Result<AnnotationDocument> {
+    let doc: AnnotationDocument =
+        serde_json::from_str(json).context("invalid annotation document")?;
+    if !is_valid_name(&doc.source) {
+        anyhow::bail!(
+            "invalid annotation source '{}': use letters, digits, '_' or '-'",
+            doc.source
+        );
+    }
+    for annotation in &doc.annotations {
+        match (&annotation.entity, &annotation.file) {
+            (Some(_), None) if annotation.lines.is_none() => {}
+            (None, Some(_)) => {}
+            _ => anyhow::bail!(
+                "annotation for {} needs either \"entity\" or \"file\" (with optional \"lines\")",
+                annotation.target()
+            ),
+        }
+        if let Some([start, end]) = annotation.lines
+            && (start == 0 || end < start)
+        {
+            anyhow::bail!(
+                "invalid line range in annotation for {}",
+                annotation.target()
+            );
+        }
+        if let Some(key) = annotation.values.keys().find(|k| !is_valid_name(k)) {
+            anyhow::bail!(
+                "invalid annotation key '{}' for {}: use letters, digits, '_' or '-'",
+                key,
+                annotation.target()
+            );
+        }
+    }
+    Ok(doc)
+}
+
+/// Read and validate an annotation document from a file.
+pub fn load_annotations(path: &Path) -> Result<AnnotationDocument> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    parse_annotations(&json)
+}
+
+/// The attribute key for `key` from `source`.
+pub fn attribute_key(source: &str, key: &str) -> String {
+    format!("{}{}.{}", ANNOTATION_ATTRIBUTE_PREFIX, source, key)
+}
+
+/// Entities a line range of `file` annotates: the innermost non-Module
+/// entities it overlaps. An entity is skipped when the part of the range
+/// inside it falls entirely within one of its nested entities, so a range
+/// in one method annotates the method but not its class. Ranges that hit no
+/// entity (imports, module-level code) fall back to the file's Module.
+pub fn entities_in_range(graph: &RPGraph, file: &str, start: usize, end: usize) -> Vec<String> {
+    let Some(ids) = graph.file_index.get(Path::new(file)) else {
+        return Vec::new();
+    };
+    let entities: Vec<_> = ids.iter().filter_map(|id| graph.entities.get(id)).collect();
+    let overlapping: Vec<_> = entities
+        .iter()
+        .filter(|e| e.kind != EntityKind::Module && e.line_start <= end && start <= e.line_end)
+        .collect();
+
+    let mut matched: Vec<String> = overlapping
+        .iter()
+        .filter(|outer| {
+            let (lo, hi) = (start.max(outer.line_start), end.min(outer.line_end));
+            !overlapping.iter().any(|inner| {
+                inner.id != outer.id
+                    && outer.line_start <= inner.line_start
+                    && inner.line_end <= outer.line_end
+                    && inner.line_start <= lo
+                    && hi <= inner.line_end
+            })
+        })
+        .map(|e| e.id.clone())
+        .collect();
+    if matched.is_empty() {
+        matched = entities
+            .iter()
+            .filter(|e| e.kind == EntityKind::Module)
+            .map(|e| e.id.clone())
+            .collect();
+    }
+    matched.sort();
+    matched
+}
+
+/// Apply an annotation document to `graph`.
+pub fn apply_annotations(graph: &mut RPGraph, doc: &AnnotationDocument) -> AnnotationReport {
+    let mut report = AnnotationReport::default();
+    let mut annotated: BTreeSet<String> = BTreeSet::new();
+
+    for annotation in &doc.annotations {
+        let targets = match (&annotation.entity, &annotation.file) {
+            (Some(id), _) => {
+                if graph.entities.contains_key(id) {
+                    vec![id.clone()]
+                } else {
+                    Vec::new()
+                }
+            }
+            (None, Some(file)) => {
+                let file = normalize_path(Path::new(file.trim_start_matches("./")));
+                let [start, end] = annotation.lines.unwrap_or([1, usize::MAX]);
+                entities_in_range(graph, &file, start, end)
+            }
+            (None, None) => Vec::new(),
+        };
+        if targets.is_empty() {
+            report.unmatched.push(annotation.target());
+            continue;
+        }
+
+        for id in targets {
+            let Some(entity) = graph.entities.get_mut(&id) else {
+                continue;
+            };
+            let mut changed = false;
+            for (key, value) in &annotation.values {
+                let key = attribute_key(&doc.source, key);
+                match value {
+                    Some(value) => {
+                        if entity.attributes.get(&key) != Some(value) {
+                            entity.attributes.insert(key, value.clone());
+                            report.values_set += 1;
+                            changed = true;
+                        }
+                    }
+                    None => {
+                        if entity.attributes.remove(&key).is_some() {
+                            report.values_removed += 1;
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if changed {
+                annotated.insert(id);
+            }
+        }
+    }
+
+    report.entities_annotated = annotated.len();
+    report
+}
+
+/// One-line summary plus unmatched targets, for CLI and MCP output.
+pub fn format_report(report: &AnnotationReport) -> String {
+    let mut out = format!(
+        "Annotated {} entities ({} values set, {} removed).\n",
+        report.entities_annotated, report.values_set, report.values_removed
+    );
+    if !report.unmatched.is_empty() {
+        out.push_str(&format!(
+            "No entity matched: {}\n",
+            report.unmatched.join(", ")
+        ));
+    }
+    out
+}
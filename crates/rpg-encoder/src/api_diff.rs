@@ -0,0 +1,275 @@
+//! Public API changes between two git revisions (`rpg-encoder api-diff`).
+//!
+//! Each revision's source files are read straight from git, without a
+//! checkout, and parsed into an in-memory structural graph. Only public
+//! functions, classes, methods, enums, and constants are compared.
+//! Visibility is best-effort ([`rpg_core::visibility::infer_visibility`]):
+//! entities whose visibility cannot be told from keywords or naming
+//! conventions are left out, as are fixture files.
+
+use crate::hierarchy_changes::file_areas;
+use anyhow::{Context, Result};
+use rpg_core::graph::{EntityKind, RPGraph, Signature, normalize_path};
+use rpg_core::visibility::{Visibility, entity_visibility};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Entity kinds that make up the API surface.
+const API_KINDS: &[EntityKind] = &[
+    EntityKind::Function,
+    EntityKind::Class,
+    EntityKind::Method,
+    EntityKind::Enum,
+    EntityKind::Constant,
+];
+
+/// A public entity at one revision.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiEntity {
+    pub id: String,
+    pub name: String,
+    pub kind: EntityKind,
+    pub file: String,
+    /// Top-level hierarchy area of the file.
+    pub area: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+impl ApiEntity {
+    /// `Class::method` or `function`: the ID without its file.
+    fn qualified_name(&self) -> &str {
+        self.id
+            .strip_prefix(&self.file)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .unwrap_or(&self.name)
+    }
+}
+
+/// A public entity whose signature differs between the two revisions.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureChange {
+    /// The entity at the `to` revision.
+    #[serde(flatten)]
+    pub entity: ApiEntity,
+    pub previous_signature: Option<Signature>,
+}
+
+/// Public API differences from `from` to `to`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiDiff {
+    pub from: String,
+    pub to: String,
+    pub added: Vec<ApiEntity>,
+    pub removed: Vec<ApiEntity>,
+    pub changed: Vec<SignatureChange>,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Public API of the project at `rev`, by entity ID. Areas come from the
+/// file-path hierarchy of that revision.
+pub fn public_api_at(
+    project_root: &Path,
+    rev: &str,
+    fixture_globs: &[String],
+) -> Result<BTreeMap<String, ApiEntity>> {
+    let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+    let tree = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .with_context(|| format!("unknown revision: {}", rev))?;
+
+    let mut files: Vec<(PathBuf, Language, String)> = Vec::new();
+    tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob)
+            && let Some(name) = entry.name()
+            && let Some(language) = Path::new(name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(Language::from_extension)
+            && let Ok(blob) = repo.find_blob(entry.id())
+            && let Ok(source) = std::str::from_utf8(blob.content())
+        {
+            files.push((
+                PathBuf::from(format!("{}{}", dir, name)),
+                language,
+                source.to_string(),
+            ));
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+
+    let mut graph = RPGraph::new("");
+    let mut lines: HashMap<&Path, Vec<&str>> = HashMap::new();
+    for (path, language, source) in &files {
+        for raw in extract_entities(path, source, *language) {
+            graph.insert_entity(raw.into_entity());
+        }
+        lines.insert(path.as_path(), source.lines().collect());
+    }
+    graph.create_module_entities();
+    crate::fixtures::tag_fixtures(&mut graph, fixture_globs);
+    graph.build_file_path_hierarchy();
+    let areas = file_areas(&graph).areas;
+
+    let mut api = BTreeMap::new();
+    for entity in graph.entities.values() {
+        if !API_KINDS.contains(&entity.kind) || entity.is_fixture() {
+            continue;
+        }
+        let file_lines = lines
+            .get(entity.file.as_path())
+            .map_or(&[][..], Vec::as_slice);
+        if entity_visibility(entity, file_lines) != Visibility::Public {
+            continue;
+        }
+        let file = normalize_path(&entity.file);
+        api.insert(
+            entity.id.clone(),
+            ApiEntity {
+                id: entity.id.clone(),
+                name: entity.name.clone(),
+                kind: entity.kind,
+                area: areas.get(&file).cloned().unwrap_or_default(),
+                file,
+                signature: entity.signature.clone(),
+            },
+        );
+    }
+    Ok(api)
+}
+
+/// Compare the public API at two revisions. When the project has a saved
+/// graph, its areas (semantic, if lifted) replace the structural ones for
+/// the files it knows.
+pub fn diff_api(
+    project_root: &Path,
+    from: &str,
+    to: &str,
+    fixture_globs: &[String],
+) -> Result<ApiDiff> {
+    let mut before = public_api_at(project_root, from, fixture_globs)?;
+    let mut after = public_api_at(project_root, to, fixture_globs)?;
+    if let Ok(saved) = rpg_core::storage::load(project_root) {
+        let saved_areas = file_areas(&saved).areas;
+        for entity in before.values_mut().chain(after.values_mut()) {
+            if let Some(area) = saved_areas.get(&entity.file) {
+                entity.area.clone_from(area);
+            }
+        }
+    }
+
+    let mut diff = ApiDiff {
+        from: from.to_string(),
+        to: to.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (id, entity) in &after {
+        match before.remove(id) {
+            None => diff.added.push(entity.clone()),
+            Some(old) if old.signature != entity.signature => diff.changed.push(SignatureChange {
+                entity: entity.clone(),
+                previous_signature: old.signature,
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.removed = before.into_values().collect();
+    Ok(diff)
+}
+
+fn kind_name(kind: EntityKind) -> String {
+    format!("{:?}", kind).to_lowercase()
+}
+
+fn signature_text(signature: Option<&Signature>) -> String {
+    signature.map_or_else(|| "(no signature)".to_string(), ToString::to_string)
+}
+
+/// Markdown report, grouped by area, then file.
+pub fn format_markdown(diff: &ApiDiff) -> String {
+    let mut out = format!("# API diff: {}..{}\n\n", diff.from, diff.to);
+    if diff.is_empty() {
+        out.push_str("No public API changes.\n");
+        return out;
+    }
+    out.push_str(&format!(
+        "{} added, {} removed, {} signature changed (public functions, classes, methods, enums, and constants)\n",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    ));
+
+    let mut lines: Vec<(&ApiEntity, String)> = Vec::new();
+    let mut push = |entity, line| lines.push((entity, line));
+    for entity in &diff.added {
+        push(
+            entity,
+            format!(
+                "- added {} `{}{}`",
+                kind_name(entity.kind),
+                entity.qualified_name(),
+                entity
+                    .signature
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            ),
+        );
+    }
+    for entity in &diff.removed {
+        push(
+            entity,
+            format!(
+                "- removed {} `{}`",
+                kind_name(entity.kind),
+                entity.qualified_name()
+            ),
+        );
+    }
+    for change in &diff.changed {
+        let entity = &change.entity;
+        push(
+            entity,
+            format!(
+                "- changed {} `{}`: `{}` -> `{}`",
+                kind_name(entity.kind),
+                entity.qualified_name(),
+                signature_text(change.previous_signature.as_ref()),
+                signature_text(entity.signature.as_ref())
+            ),
+        );
+    }
+
+    let mut groups: BTreeMap<&str, BTreeMap<&str, Vec<String>>> = BTreeMap::new();
+    for (entity, line) in lines {
+        groups
+            .entry(entity.area.as_str())
+            .or_default()
+            .entry(entity.file.as_str())
+            .or_default()
+            .push(line);
+    }
+    for (area, files) in groups {
+        let area = if area.is_empty() { "(no area)" } else { area };
+        out.push_str(&format!("\n## {}\n", area));
+        for (file, lines) in files {
+            out.push_str(&format!("\n### {}\n", file));
+            for line in lines {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
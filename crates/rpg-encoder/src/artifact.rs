@@ -0,0 +1,243 @@
+//! Graphs built elsewhere (e.g. a CI artifact) loaded into a local checkout.
+//!
+//! A graph records the commit it was built at (`base_commit`) and a
+//! fingerprint of the source files tracked at that commit. On load the
+//! fingerprint is recomputed from HEAD: a match proves the graph describes
+//! this exact tree. Otherwise the graph is accepted as long as there is
+//! evidence it comes from this repository — its base commit exists locally,
+//! or most of its indexed files are tracked here — and the number of files
+//! changed since the base commit is reported. A fingerprinted graph with no
+//! such evidence is rejected.
+//!
+//! Walking the tree is only needed once per tree: a graph whose base commit
+//! is HEAD was fingerprinted from this very tree, and other fingerprints are
+//! cached by tree id for the life of the process, so reloads (e.g. the MCP
+//! server picking up a rebuilt graph) stay cheap.
+
+use anyhow::{Context, Result};
+use rpg_core::graph::{RPGraph, normalize_path};
+use rpg_core::storage;
+use rpg_parser::languages::Language;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// What loading a graph into a checkout found out about it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Commit the graph was built at.
+    pub base_commit: Option<String>,
+    /// HEAD of the checkout, if it is a git repository.
+    pub head: Option<String>,
+    /// Files changed between `base_commit` and HEAD; `None` when they are the
+    /// same commit or the base commit is not available locally.
+    pub changed_files: Option<usize>,
+    /// Whether the stored fingerprint matches HEAD; `None` when the graph has
+    /// no fingerprint or the checkout is not a git repository.
+    pub fingerprint_matches: Option<bool>,
+    /// `base_commit` is set but the commit is not in the local repository
+    /// (e.g. a shallow clone or an unfetched branch).
+    pub base_commit_missing: bool,
+}
+
+impl LoadReport {
+    /// One-line description for users, or `None` when the graph is current.
+    pub fn summary(&self) -> Option<String> {
+        let base = short(self.base_commit.as_deref()?);
+        let head = short(self.head.as_deref()?);
+        if base == head {
+            return None;
+        }
+        if self.base_commit_missing {
+            return Some(format!(
+                "graph was built at {}, which is not in this repository (HEAD is {}); run `rpg-encoder update` if results look stale",
+                base, head
+            ));
+        }
+        let changed = self.changed_files?;
+        Some(format!(
+            "graph was built at {}, {} file(s) changed since (HEAD is {}); run `rpg-encoder update` to catch up",
+            base, changed, head
+        ))
+    }
+}
+
+fn short(sha: &str) -> &str {
+    &sha[..8.min(sha.len())]
+}
+
+/// Fingerprint of the checkout at HEAD, or `None` outside a git repository.
+pub fn repo_fingerprint(project_root: &Path) -> Option<String> {
+    repo_fingerprint_at(project_root, "HEAD")
+}
+
+/// Fingerprint of the tree at `rev`, or `None` if it cannot be resolved.
+pub fn repo_fingerprint_at(project_root: &Path, rev: &str) -> Option<String> {
+    let repo = git2::Repository::open(project_root).ok()?;
+    let tree = repo.revparse_single(rev).ok()?.peel_to_tree().ok()?;
+    tree_fingerprint(&repo, &tree).ok()
+}
+
+/// Hash of the sorted paths and sizes of the source files in `tree`.
+///
+/// A tree id pins its contents, so results are cached by it and each tree
+/// is walked at most once per process.
+fn tree_fingerprint(repo: &git2::Repository, tree: &git2::Tree) -> Result<String> {
+    static CACHE: OnceLock<Mutex<HashMap<git2::Oid, String>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some(fingerprint) = cache.lock().unwrap().get(&tree.id()) {
+        return Ok(fingerprint.clone());
+    }
+    let fingerprint = walk_fingerprint(repo, tree)?;
+    cache.lock().unwrap().insert(tree.id(), fingerprint.clone());
+    Ok(fingerprint)
+}
+
+fn walk_fingerprint(repo: &git2::Repository, tree: &git2::Tree) -> Result<String> {
+    let odb = repo.odb()?;
+    let mut files = Vec::new();
+    tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob)
+            && let Some(name) = entry.name()
+            && Path::new(name)
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(Language::from_extension)
+                .is_some()
+            && let Ok((size, _)) = odb.read_header(entry.id())
+        {
+            files.push(format!("{}{}\t{}", dir, name, size));
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    files.sort();
+    Ok(blake3::hash(files.join("\n").as_bytes())
+        .to_hex()
+        .to_string())
+}
+
+/// Check that `graph` belongs to the repository at `project_root`.
+///
+/// Returns an error when the graph is fingerprinted and nothing ties it to
+/// this repository. Outside a git repository there is nothing to compare
+/// against and an empty report is returned.
+pub fn verify(graph: &RPGraph, project_root: &Path) -> Result<LoadReport> {
+    let outside_git = || LoadReport {
+        base_commit: graph.base_commit.clone(),
+        ..LoadReport::default()
+    };
+    let Ok(repo) = git2::Repository::open(project_root) else {
+        return Ok(outside_git());
+    };
+    let Ok(head) = repo.head().and_then(|h| h.peel_to_commit()) else {
+        return Ok(outside_git());
+    };
+    verify_against(graph, &repo, &head)
+}
+
+/// [`verify`] against the commit `rev` names instead of HEAD, e.g. in a bare
+/// repository. Fails if `rev` does not resolve.
+pub fn verify_at(graph: &RPGraph, project_root: &Path, rev: &str) -> Result<LoadReport> {
+    let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("unknown revision: {}", rev))?;
+    verify_against(graph, &repo, &commit)
+}
+
+fn verify_against(
+    graph: &RPGraph,
+    repo: &git2::Repository,
+    head: &git2::Commit<'_>,
+) -> Result<LoadReport> {
+    let mut report = LoadReport {
+        base_commit: graph.base_commit.clone(),
+        ..LoadReport::default()
+    };
+    let head_tree = head.tree()?;
+    let head_sha = head.id().to_string();
+    report.head = Some(head_sha.clone());
+    let at_head = graph.base_commit.as_deref() == Some(head_sha.as_str());
+
+    if let Some(expected) = &graph.metadata.repo_fingerprint {
+        // Built at HEAD: the fingerprint was taken from this tree.
+        report.fingerprint_matches =
+            Some(at_head || *expected == tree_fingerprint(repo, &head_tree)?);
+    }
+
+    let base = graph
+        .base_commit
+        .as_deref()
+        .filter(|_| !at_head)
+        .map(|sha| git2::Oid::from_str(sha).and_then(|oid| repo.find_commit(oid)));
+    match base {
+        Some(Ok(base)) => {
+            let diff = repo.diff_tree_to_tree(Some(&base.tree()?), Some(&head_tree), None)?;
+            report.changed_files = Some(diff.deltas().len());
+        }
+        Some(Err(_)) => report.base_commit_missing = true,
+        None => {}
+    }
+
+    if report.fingerprint_matches == Some(false) && report.changed_files.is_none() {
+        let (present, total) = tracked_overlap(graph, &head_tree);
+        if present * 2 < total {
+            anyhow::bail!(
+                "graph was built from a different repository: its fingerprint does not match HEAD{}, and only {} of its {} indexed files exist in this checkout. Rebuild with `rpg-encoder build --force`.",
+                match (&graph.base_commit, report.base_commit_missing) {
+                    (Some(sha), true) =>
+                        format!(", base commit {} is not in this repository", short(sha)),
+                    _ => String::new(),
+                },
+                present,
+                total
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// How many of the graph's indexed files are tracked in `tree`, out of how many.
+fn tracked_overlap(graph: &RPGraph, tree: &git2::Tree) -> (usize, usize) {
+    let mut tracked = HashSet::new();
+    let _ = tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if let Some(name) = entry.name() {
+            tracked.insert(format!("{}{}", dir, name));
+        }
+        git2::TreeWalkResult::Ok
+    });
+    let present = graph
+        .file_index
+        .keys()
+        .filter(|file| tracked.contains(&normalize_path(file)))
+        .count();
+    (present, graph.file_index.len())
+}
+
+/// Load `.rpg/graph.json` and [`verify`] it against the checkout.
+pub fn load_verified(project_root: &Path) -> Result<(RPGraph, LoadReport)> {
+    let graph = storage::load(project_root)?;
+    let report = verify(&graph, project_root)?;
+    Ok((graph, report))
+}
+
+/// Load `.rpg/graph.json` and [`verify_at`] it against `rev`.
+pub fn load_verified_at(project_root: &Path, rev: &str) -> Result<(RPGraph, LoadReport)> {
+    let graph = storage::load(project_root)?;
+    let report = verify_at(&graph, project_root, rev)?;
+    Ok((graph, report))
+}
+
+/// Verify a downloaded graph file against the checkout and, if it belongs
+/// here, install it as `.rpg/graph.json` (backing up any existing graph).
+/// The file is written as received, compressed or not.
+pub fn install(project_root: &Path, raw: &[u8]) -> Result<(RPGraph, LoadReport)> {
+    let graph = storage::from_bytes(raw).context("downloaded file is not an RPG graph")?;
+    let report = verify(&graph, project_root)?;
+    storage::ensure_gitignore(project_root)?;
+    storage::create_backup(project_root)?;
+    let path = storage::rpg_file(project_root);
+    std::fs::write(&path, raw).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok((graph, report))
+}
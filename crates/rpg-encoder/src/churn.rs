@@ -0,0 +1,132 @@
+//! Entity churn: how often each entity's lines changed in recent git history.
+//!
+//! Walks the non-merge commits reachable from HEAD within a time window, diffs
+//! each against its parent with zero context lines, and credits every hunk to
+//! the entities whose line ranges it overlaps.
+//!
+//! Attribution uses the entities' *current* line ranges, not the ranges they
+//! had at each commit. Hunks from older commits can land on the wrong entity
+//! when lines above them were added or removed since, and history before a
+//! file was renamed is not followed. Treat the scores as a ranking signal,
+//! not an exact count.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rpg_core::graph::{ChurnRange, EntityChurn, EntityKind, RPGraph, normalize_path};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Resolve a `--since` value to a cutoff time: a relative window (`90d`,
+/// `12w`, `6m`, `1y`; months are 30 days, years 365) or a date (`2024-01-31`).
+pub fn parse_since(since: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let since = since.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc());
+    }
+    let invalid = || {
+        anyhow::anyhow!(
+            "invalid --since '{}': use a window like 90d, 12w, 6m, 1y, or a date like 2024-01-31",
+            since
+        )
+    };
+    let split = since.len().checked_sub(1).ok_or_else(invalid)?;
+    let (count, unit) = since.split_at(split);
+    let count = i64::from(count.parse::<u32>().map_err(|_| invalid())?);
+    let days = match unit {
+        "d" => count,
+        "w" => count * 7,
+        "m" => count * 30,
+        "y" => count * 365,
+        _ => return Err(invalid()),
+    };
+    Ok(now - Duration::days(days))
+}
+
+/// Recompute churn for every entity from commits since `since`, replacing any
+/// earlier analysis, and record the window in `graph.metadata.churn`.
+/// Module entities span whole files and are left out.
+pub fn analyze_churn(graph: &mut RPGraph, project_root: &Path, since: &str) -> Result<ChurnRange> {
+    let from = parse_since(since, Utc::now())?;
+    let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let mut by_file: HashMap<String, Vec<(String, usize, usize)>> = HashMap::new();
+    for entity in graph.entities.values() {
+        if entity.kind != EntityKind::Module {
+            by_file
+                .entry(normalize_path(&entity.file))
+                .or_default()
+                .push((entity.id.clone(), entity.line_start, entity.line_end));
+        }
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.set_sorting(git2::Sort::TIME)?;
+
+    let mut churn: HashMap<String, EntityChurn> = HashMap::new();
+    let mut commits = 0;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < from.timestamp() {
+            break;
+        }
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        commits += 1;
+
+        let parent_tree = match commit.parent_count() {
+            0 => None,
+            _ => Some(commit.parent(0)?.tree()?),
+        };
+        let mut opts = git2::DiffOptions::new();
+        opts.context_lines(0);
+        let diff =
+            repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+
+        let mut touched: HashMap<String, usize> = HashMap::new();
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |delta, hunk| {
+                let Some(path) = delta.new_file().path() else {
+                    return true;
+                };
+                let Some(entities) = by_file.get(&normalize_path(path)) else {
+                    return true;
+                };
+                // A pure deletion has no new lines; anchor it at the line it follows.
+                let start = hunk.new_start() as usize;
+                let end = start + (hunk.new_lines() as usize).max(1) - 1;
+                let lines = (hunk.new_lines() + hunk.old_lines()) as usize;
+                for (id, line_start, line_end) in entities {
+                    if start <= *line_end && end >= *line_start {
+                        *touched.entry(id.clone()).or_default() += lines;
+                    }
+                }
+                true
+            }),
+            None,
+        )?;
+
+        for (id, lines) in touched {
+            let entry = churn.entry(id).or_default();
+            entry.commits += 1;
+            entry.lines += lines;
+        }
+    }
+
+    for entity in graph.entities.values_mut() {
+        entity.churn = churn.get(&entity.id).copied();
+    }
+    let range = ChurnRange {
+        since: since.to_string(),
+        from,
+        head: head.id().to_string(),
+        commits,
+        analyzed_at: Utc::now(),
+    };
+    graph.metadata.churn = Some(range.clone());
+    Ok(range)
+}
@@ -2,9 +2,10 @@
 //!
 //! Non-blocking: features are always applied, but warnings help the LLM self-correct
 //! on subsequent submissions. Checks for vague verbs, implementation details,
-//! too-short/too-long features, and duplicates.
+//! too-short/too-long features, and duplicates, plus feature sets copied across
+//! many entities of one submission.
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 
 /// A quality warning for a specific feature on an entity.
@@ -181,6 +182,93 @@ pub fn format_warnings(warnings: &[QualityWarning]) -> String {
     out
 }
 
+/// Entities of one submission that share the same or nearly the same features.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopiedFeatureSet {
+    /// Features of the first entity in the group.
+    pub features: Vec<String>,
+    /// Every entity in the group, sorted.
+    pub entity_ids: Vec<String>,
+}
+
+/// Word-level Jaccard similarity of two feature sets.
+fn feature_similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Groups of more than `max_entities` entities whose feature sets are at
+/// least `similarity` alike (word-level Jaccard). Entities are grouped
+/// greedily around the first unassigned entity in ID order.
+pub fn find_copied_feature_sets(
+    features: &HashMap<String, Vec<String>>,
+    max_entities: usize,
+    similarity: f64,
+) -> Vec<CopiedFeatureSet> {
+    if features.len() <= max_entities {
+        return Vec::new();
+    }
+    let mut ids: Vec<&String> = features.keys().collect();
+    ids.sort();
+    let words: Vec<BTreeSet<String>> = ids
+        .iter()
+        .map(|id| {
+            features[*id]
+                .iter()
+                .flat_map(|f| f.split_whitespace())
+                .map(str::to_lowercase)
+                .collect()
+        })
+        .collect();
+
+    let mut assigned = vec![false; ids.len()];
+    let mut groups = Vec::new();
+    for seed in 0..ids.len() {
+        if assigned[seed] || words[seed].is_empty() {
+            continue;
+        }
+        let members: Vec<usize> = (seed..ids.len())
+            .filter(|&i| !assigned[i] && feature_similarity(&words[seed], &words[i]) >= similarity)
+            .collect();
+        if members.len() > max_entities {
+            for &i in &members {
+                assigned[i] = true;
+            }
+            groups.push(CopiedFeatureSet {
+                features: features[ids[seed]].clone(),
+                entity_ids: members.iter().map(|&i| ids[i].clone()).collect(),
+            });
+        }
+    }
+    groups
+}
+
+/// Format copied feature groups as a markdown section for tool output.
+pub fn format_copied_feature_sets(groups: &[CopiedFeatureSet]) -> String {
+    if groups.is_empty() {
+        return String::new();
+    }
+    let flagged: usize = groups.iter().map(|g| g.entity_ids.len()).sum();
+    let mut out = format!(
+        "\n## COPIED FEATURES\n\n{} entities share copied feature sets and were marked for review (feature_source \"llm-review\"); they are not routed into the hierarchy. Re-submit features written for each entity's own code.\n",
+        flagged
+    );
+    for group in groups {
+        out.push_str(&format!(
+            "- {} entities: [{}]\n",
+            group.entity_ids.len(),
+            group.features.join(", ")
+        ));
+        for id in &group.entity_ids {
+            out.push_str(&format!("  - `{}`\n", id));
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,4 +353,59 @@ mod tests {
         assert!(result.contains("handle data"));
         assert!(result.contains("vague verb"));
     }
+
+    fn submission(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(id, feats)| {
+                (
+                    id.to_string(),
+                    feats.iter().map(|f| f.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_copied_feature_sets_near_identical() {
+        let copied: &[&str] = &["load user record", "validate user input"];
+        let tweaked: &[&str] = &["load user record", "validate user input", "log user"];
+        let mut entries: Vec<(String, &[&str])> = (0..4)
+            .map(|i| (format!("src/a.rs:f{}", i), copied))
+            .collect();
+        entries.push(("src/a.rs:g".to_string(), tweaked));
+        entries.push(("src/b.rs:parse".to_string(), &["parse config file"]));
+        let entries: Vec<(&str, &[&str])> =
+            entries.iter().map(|(id, f)| (id.as_str(), *f)).collect();
+        let features = submission(&entries);
+
+        let groups = find_copied_feature_sets(&features, 3, 0.8);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].entity_ids,
+            vec![
+                "src/a.rs:f0",
+                "src/a.rs:f1",
+                "src/a.rs:f2",
+                "src/a.rs:f3",
+                "src/a.rs:g"
+            ]
+        );
+        assert_eq!(groups[0].features, copied);
+
+        // At the threshold itself nothing is flagged.
+        assert!(find_copied_feature_sets(&features, 5, 0.8).is_empty());
+        // A stricter similarity leaves out the tweaked entity.
+        assert_eq!(
+            find_copied_feature_sets(&features, 3, 1.0)[0]
+                .entity_ids
+                .len(),
+            4
+        );
+
+        let output = format_copied_feature_sets(&groups);
+        assert!(output.contains("## COPIED FEATURES"));
+        assert!(output.contains("5 entities: [load user record, validate user input]"));
+        assert!(output.contains("  - `src/a.rs:g`"));
+    }
 }
@@ -100,6 +100,7 @@ pub fn compute_data_flow_edges(graph: &mut RPGraph) {
 mod tests {
     use super::*;
     use rpg_core::graph::{Entity, EntityDeps, EntityKind, Param, Signature};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(name: &str, sig: Option<Signature>) -> Entity {
@@ -113,9 +114,22 @@ mod tests {
             parent_class: None,
             semantic_features: Vec::new(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: sig,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -4,11 +4,15 @@
 
 use crate::grounding;
 use anyhow::{Context, Result};
-use rpg_core::graph::RPGraph;
+use rpg_core::config::{CompareTo, DEFAULT_ROUTING_NEIGHBOR_WEIGHT, RiskConfig};
+use rpg_core::files::FileProvider;
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_core::hunks::ChangedHunks;
 use rpg_parser::entities::RawEntity;
 use rpg_parser::languages::Language;
 use rpg_parser::paradigms::defs::ParadigmDef;
 use rpg_parser::paradigms::query_engine::QueryCache;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 /// Paradigm pipeline context for incremental updates.
@@ -28,6 +32,21 @@ pub enum FileChange {
     Renamed { from: PathBuf, to: PathBuf },
 }
 
+impl FileChange {
+    /// The same change with its paths under `dir`.
+    fn prefixed(self, dir: &Path) -> Self {
+        match self {
+            Self::Added(p) => Self::Added(dir.join(p)),
+            Self::Modified(p) => Self::Modified(dir.join(p)),
+            Self::Deleted(p) => Self::Deleted(dir.join(p)),
+            Self::Renamed { from, to } => Self::Renamed {
+                from: dir.join(from),
+                to: dir.join(to),
+            },
+        }
+    }
+}
+
 /// Summary of an incremental update.
 #[derive(Debug, Default)]
 pub struct UpdateSummary {
@@ -41,6 +60,8 @@ pub struct UpdateSummary {
     /// Entity IDs that were structurally modified but not re-lifted (features may be stale).
     /// These should be re-lifted interactively via MCP.
     pub modified_entity_ids: Vec<String>,
+    /// Hierarchy nodes whose grounded directories were moved by a directory rename.
+    pub regrounded_nodes: Vec<String>,
 }
 
 /// Statistics from merging an old graph's semantic data into a new graph.
@@ -52,13 +73,18 @@ pub struct MergeStats {
     pub hierarchy_restored: usize,
     /// Module entities whose synthesized features were restored.
     pub modules_restored: usize,
+    /// Entities whose user tags were restored.
+    pub tags_restored: usize,
+    /// Entities whose imported annotations were restored.
+    pub annotations_restored: usize,
     /// Entities in the old graph that no longer exist in the new graph.
     pub orphaned: usize,
     /// Entities in the new graph that did not exist in the old graph.
     pub new_entities: usize,
 }
 
-/// Merge semantic features, hierarchy paths, and Module features from an old graph
+/// Merge semantic features, hierarchy paths, Module features, user tags, and imported
+/// annotations from an old graph
 /// into a new graph by matching entity IDs. Used by `build_rpg` to auto-preserve
 /// lifted data across rebuilds.
 pub fn merge_features(new_graph: &mut RPGraph, old_graph: &RPGraph) -> MergeStats {
@@ -83,6 +109,7 @@ pub fn merge_features(new_graph: &mut RPGraph, old_graph: &RPGraph) -> MergeStat
             // Restore semantic features
             if new_entity.semantic_features.is_empty() && !old_entity.semantic_features.is_empty() {
                 new_entity.semantic_features = old_entity.semantic_features.clone();
+                new_entity.lifted_at = old_entity.lifted_at;
                 if new_entity.kind == rpg_core::graph::EntityKind::Module {
                     stats.modules_restored += 1;
                 } else {
@@ -103,6 +130,24 @@ pub fn merge_features(new_graph: &mut RPGraph, old_graph: &RPGraph) -> MergeStat
             if new_entity.signature.is_none() && old_entity.signature.is_some() {
                 new_entity.signature = old_entity.signature.clone();
             }
+
+            // Restore user tags (never produced by extraction)
+            if !old_entity.tags.is_empty() {
+                new_entity.tags.extend(old_entity.tags.iter().cloned());
+                stats.tags_restored += 1;
+            }
+
+            // Restore imported annotations (never produced by extraction)
+            let mut restored = false;
+            for (key, value) in &old_entity.attributes {
+                if key.starts_with(rpg_core::graph::ANNOTATION_ATTRIBUTE_PREFIX) {
+                    new_entity.attributes.insert(key.clone(), value.clone());
+                    restored = true;
+                }
+            }
+            if restored {
+                stats.annotations_restored += 1;
+            }
         }
     }
 
@@ -132,6 +177,7 @@ pub fn rebuild_hierarchy_from_entities(graph: &mut RPGraph, old_had_semantic_hie
     }
 
     // Clear the structural hierarchy
+    let charters = graph.hierarchy_charters();
     graph.hierarchy.clear();
 
     // Re-insert all entities at their restored hierarchy paths
@@ -145,6 +191,7 @@ pub fn rebuild_hierarchy_from_entities(graph: &mut RPGraph, old_had_semantic_hie
     for (entity_id, path) in &assignments {
         graph.insert_into_hierarchy(path, entity_id);
     }
+    graph.restore_charters(&charters);
 
     graph.metadata.semantic_hierarchy = true;
 }
@@ -173,71 +220,177 @@ pub fn detect_changes(
     let head = repo.head()?.peel_to_commit()?;
     let head_tree = head.tree()?;
 
-    let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
-
-    let mut changes = Vec::new();
-
-    diff.foreach(
-        &mut |delta, _| {
-            let status = delta.status();
-            match status {
-                git2::Delta::Added => {
-                    if let Some(path) = delta.new_file().path() {
-                        changes.push(FileChange::Added(path.to_path_buf()));
-                    }
-                }
-                git2::Delta::Deleted => {
-                    if let Some(path) = delta.old_file().path() {
-                        changes.push(FileChange::Deleted(path.to_path_buf()));
-                    }
-                }
-                git2::Delta::Modified => {
-                    if let Some(path) = delta.new_file().path() {
-                        changes.push(FileChange::Modified(path.to_path_buf()));
-                    }
-                }
-                git2::Delta::Renamed => {
-                    let from = delta.old_file().path().map(|p| p.to_path_buf());
-                    let to = delta.new_file().path().map(|p| p.to_path_buf());
-                    if let (Some(from), Some(to)) = (from, to) {
-                        changes.push(FileChange::Renamed { from, to });
-                    }
-                }
-                _ => {}
-            }
-            true
-        },
-        None,
-        None,
-        None,
-    )?;
+    let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
+    find_exact_renames(&mut diff)?;
 
+    let mut changes = collect_changes(&diff)?;
+    changes.extend(nested_repo_changes(project_root, graph, false));
     Ok(changes)
 }
 
+/// Pair deleted and added files with identical content into renames. Edited
+/// moves stay a deletion plus an addition, so renamed entities keep valid
+/// line spans.
+fn find_exact_renames(diff: &mut git2::Diff<'_>) -> Result<()> {
+    diff.find_similar(Some(
+        git2::DiffFindOptions::new()
+            .renames(true)
+            .exact_match_only(true),
+    ))?;
+    Ok(())
+}
+
 /// Detect ALL changes: committed since base_commit + staged + unstaged (working tree).
 /// This is the equivalent of `git diff <base_commit>` against the working directory.
 /// Catches everything regardless of whether the user has committed or not.
+/// Indexed nested repositories add their own changes ([`crate::nested_repos`]).
 pub fn detect_workdir_changes(project_root: &Path, graph: &RPGraph) -> Result<Vec<FileChange>> {
     let base_commit_str = graph
         .base_commit
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("no base_commit in RPG, cannot compute diff"))?;
+    let mut changes = detect_changes_against(project_root, base_commit_str)?;
+    changes.extend(nested_repo_changes(project_root, graph, true));
+    Ok(changes)
+}
+
+/// Changes inside the indexed nested repositories since the commit recorded
+/// for each, taken from each repository's own git state and prefixed with
+/// its directory. `workdir` counts staged and unstaged edits; otherwise the
+/// repository's HEAD is compared. A repository that can't be read (a
+/// submodule whose checkout is missing) is skipped with a warning.
+fn nested_repo_changes(project_root: &Path, graph: &RPGraph, workdir: bool) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+    for nested in graph.metadata.nested_repos.iter().filter(|r| r.indexed) {
+        let dir = Path::new(&nested.path);
+        match diff_nested_repo(&project_root.join(dir), nested.commit.as_deref(), workdir) {
+            Ok(found) => changes.extend(found.into_iter().map(|c| c.prefixed(dir))),
+            Err(e) => eprintln!(
+                "  Warning: skipping nested repository {}: {}",
+                nested.path, e
+            ),
+        }
+    }
+    changes
+}
+
+fn diff_nested_repo(dir: &Path, commit: Option<&str>, workdir: bool) -> Result<Vec<FileChange>> {
+    let repo = git2::Repository::open(dir).context("not checked out")?;
+    let base_tree = match commit {
+        Some(commit) => Some(
+            repo.revparse_single(commit)
+                .and_then(|obj| obj.peel_to_tree())
+                .with_context(|| format!("recorded commit {} not found", commit))?,
+        ),
+        None => None,
+    };
+    let mut diff = if workdir {
+        repo.diff_tree_to_workdir_with_index(base_tree.as_ref(), None)?
+    } else {
+        let head_tree = repo.head()?.peel_to_tree()?;
+        repo.diff_tree_to_tree(base_tree.as_ref(), Some(&head_tree), None)?
+    };
+    find_exact_renames(&mut diff)?;
+    collect_changes(&diff)
+}
 
+/// Detect ALL changes since `rev` (a commit SHA, branch, or tag): committed
+/// + staged + unstaged, like `git diff <rev>` against the working directory.
+///
+/// Only the repository at `project_root` is diffed, not nested ones.
+pub fn detect_changes_against(project_root: &Path, rev: &str) -> Result<Vec<FileChange>> {
     let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
-    let base_oid = git2::Oid::from_str(base_commit_str).context("invalid base_commit SHA")?;
-    let base_commit_obj = repo
-        .find_commit(base_oid)
-        .context("base commit not found")?;
-    let base_tree = base_commit_obj.tree()?;
+    let base_tree = repo
+        .revparse_single(rev)
+        .and_then(|obj| obj.peel_to_commit())
+        .with_context(|| format!("revision '{}' not found", rev))?
+        .tree()?;
 
     // Diff base tree vs working directory (includes staged + unstaged)
-    let diff = repo.diff_tree_to_workdir_with_index(Some(&base_tree), None)?;
+    let mut diff = repo.diff_tree_to_workdir_with_index(Some(&base_tree), None)?;
+    find_exact_renames(&mut diff)?;
+    collect_changes(&diff)
+}
+
+/// A staleness comparison point resolved to a commit (see [`resolve_compare_to`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparePoint {
+    pub commit: String,
+    /// How notices name it: `base_commit 1a2b3c4d`,
+    /// `merge-base with origin/main (1a2b3c4d)`, or `v1.2 (1a2b3c4d)`.
+    pub label: String,
+}
+
+/// Resolve `[staleness] compare_to` to a commit: the graph's base_commit,
+/// the merge base of HEAD and a mainline, or a revision as given.
+pub fn resolve_compare_to(
+    project_root: &Path,
+    graph: &RPGraph,
+    target: &CompareTo,
+) -> Result<ComparePoint> {
+    let short = |sha: &str| sha[..8.min(sha.len())].to_string();
+    if *target == CompareTo::BaseCommit {
+        let commit = graph
+            .base_commit
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("no base_commit in RPG, cannot compute diff"))?;
+        return Ok(ComparePoint {
+            label: format!("base_commit {}", short(&commit)),
+            commit,
+        });
+    }
+    let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+    let resolve = |rev: &str| -> Result<git2::Oid> {
+        Ok(repo
+            .revparse_single(rev)
+            .and_then(|obj| obj.peel_to_commit())
+            .with_context(|| format!("revision '{}' not found", rev))?
+            .id())
+    };
+    let (oid, label) = match target {
+        CompareTo::MergeBase(rev) => {
+            let head = repo.head()?.peel_to_commit()?.id();
+            let oid = repo
+                .merge_base(head, resolve(rev)?)
+                .with_context(|| format!("HEAD and '{}' share no history", rev))?;
+            (oid, format!("merge-base with {}", rev))
+        }
+        CompareTo::Rev(rev) => (resolve(rev)?, rev.clone()),
+        CompareTo::BaseCommit => unreachable!("handled above"),
+    };
+    let commit = oid.to_string();
+    Ok(ComparePoint {
+        label: format!("{} ({})", label, short(&commit)),
+        commit,
+    })
+}
 
+/// Changes in the working tree since the configured comparison point, for
+/// staleness notices.
+pub fn detect_stale_changes(
+    project_root: &Path,
+    graph: &RPGraph,
+    target: &CompareTo,
+) -> Result<(ComparePoint, Vec<FileChange>)> {
+    let point = resolve_compare_to(project_root, graph, target)?;
+    let mut changes = detect_changes_against(project_root, &point.commit)?;
+    changes.extend(nested_repo_changes(project_root, graph, true));
+    Ok((point, changes))
+}
+
+/// File-level changes of a diff, renames included.
+fn collect_changes(diff: &git2::Diff<'_>) -> Result<Vec<FileChange>> {
     let mut changes = Vec::new();
 
     diff.foreach(
         &mut |delta, _| {
+            // Submodule pointers aren't files; indexed nested repositories
+            // report their own changes.
+            if delta.old_file().mode() == git2::FileMode::Commit
+                || delta.new_file().mode() == git2::FileMode::Commit
+            {
+                return true;
+            }
             let status = delta.status();
             match status {
                 git2::Delta::Added => {
@@ -274,8 +427,107 @@ pub fn detect_workdir_changes(project_root: &Path, graph: &RPGraph) -> Result<Ve
     Ok(changes)
 }
 
+/// Changed line ranges for `git diff <rev>...HEAD`: everything committed on HEAD
+/// since its merge base with `rev`, diffed with zero context lines.
+pub fn diff_hunks_since(project_root: &Path, rev: &str) -> Result<ChangedHunks> {
+    diff_hunks_between(project_root, rev, "HEAD")
+}
+
+/// Changed line ranges for `git diff <rev>...<head_rev>`. Works in bare
+/// repositories.
+pub fn diff_hunks_between(project_root: &Path, rev: &str, head_rev: &str) -> Result<ChangedHunks> {
+    let repo = git2::Repository::open(project_root).context("failed to open git repo")?;
+    let since = repo
+        .revparse_single(rev)
+        .with_context(|| format!("unknown revision '{}'", rev))?
+        .peel_to_commit()?;
+    let head = repo
+        .revparse_single(head_rev)
+        .with_context(|| format!("unknown revision '{}'", head_rev))?
+        .peel_to_commit()?;
+    let base_oid = repo
+        .merge_base(since.id(), head.id())
+        .with_context(|| format!("no merge base between '{}' and '{}'", rev, head_rev))?;
+    let base_tree = repo.find_commit(base_oid)?.tree()?;
+
+    let mut opts = git2::DiffOptions::new();
+    opts.context_lines(0);
+    let diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head.tree()?), Some(&mut opts))?;
+
+    let mut patch = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin());
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(rpg_core::hunks::parse_unified_diff(&patch))
+}
+
+/// Entities touched by `git diff <rev>...HEAD`, for diff-scoped search.
+#[derive(Debug, Default)]
+pub struct DiffScope {
+    /// Entities whose line ranges intersect a changed hunk.
+    pub entities: HashSet<String>,
+    /// Touched files that also changed since the graph was built, so their
+    /// entity line ranges may not match the hunks.
+    pub stale_files: HashSet<PathBuf>,
+}
+
+/// Resolve the entities touched since `rev` and flag touched files the graph is stale for.
+pub fn diff_scope(project_root: &Path, graph: &RPGraph, rev: &str) -> Result<DiffScope> {
+    let hunks = diff_hunks_since(project_root, rev)?;
+    let entities = rpg_core::hunks::entities_in_hunks(graph, &hunks);
+    // Without a base commit there is nothing to compare against; treat as fresh.
+    let stale_files = detect_workdir_changes(project_root, graph)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|change| match change {
+            FileChange::Added(p) | FileChange::Modified(p) | FileChange::Deleted(p) => p,
+            FileChange::Renamed { to, .. } => to,
+        })
+        .filter(|p| hunks.contains_key(p))
+        .collect();
+    Ok(DiffScope {
+        entities,
+        stale_files,
+    })
+}
+
+/// [`diff_scope`] for `git diff <rev>...<head_rev>`, without a worktree.
+/// Touched files are stale when they changed between the graph's base
+/// commit and `head_rev`.
+pub fn diff_scope_at(
+    project_root: &Path,
+    graph: &RPGraph,
+    rev: &str,
+    head_rev: &str,
+) -> Result<DiffScope> {
+    let hunks = diff_hunks_between(project_root, rev, head_rev)?;
+    let entities = rpg_core::hunks::entities_in_hunks(graph, &hunks);
+    // Without a base commit there is nothing to compare against; treat as fresh.
+    let stale_files = match &graph.base_commit {
+        Some(base) => diff_hunks_between(project_root, base, head_rev)?
+            .into_keys()
+            .filter(|p| hunks.contains_key(p))
+            .collect(),
+        None => HashSet::new(),
+    };
+    Ok(DiffScope {
+        entities,
+        stale_files,
+    })
+}
+
 /// Filter changes to only include source files for the given languages.
-pub fn filter_source_changes(changes: Vec<FileChange>, languages: &[Language]) -> Vec<FileChange> {
+/// Extensionless scripts are identified by their shebang, so deleted ones
+/// are dropped here (`find_deleted_files` prunes them from the graph).
+pub fn filter_source_changes(
+    project_root: &Path,
+    changes: Vec<FileChange>,
+    languages: &[Language],
+) -> Vec<FileChange> {
     changes
         .into_iter()
         .filter(|change| {
@@ -283,9 +535,7 @@ pub fn filter_source_changes(changes: Vec<FileChange>, languages: &[Language]) -
                 FileChange::Added(p) | FileChange::Modified(p) | FileChange::Deleted(p) => p,
                 FileChange::Renamed { to, .. } => to,
             };
-            path.extension()
-                .and_then(|e| e.to_str())
-                .and_then(Language::from_extension)
+            Language::from_path(&project_root.join(path))
                 .is_some_and(|lang| languages.contains(&lang))
         })
         .collect()
@@ -328,6 +578,30 @@ pub fn filter_rpgignore_changes(project_root: &Path, changes: Vec<FileChange>) -
         .collect()
 }
 
+/// Filter out file changes `.gitattributes` marks vendored, generated, or
+/// documentation. As with [`filter_rpgignore_changes`], a rename onto such a
+/// path becomes a deletion of the source.
+pub fn filter_linguist_changes(
+    linguist: &crate::gitattributes::LinguistAttributes,
+    changes: Vec<FileChange>,
+) -> Vec<FileChange> {
+    changes
+        .into_iter()
+        .filter_map(|change| match &change {
+            FileChange::Added(p) | FileChange::Modified(p) | FileChange::Deleted(p) => {
+                (!linguist.excludes(p)).then_some(change)
+            }
+            FileChange::Renamed { from, to } => {
+                if linguist.excludes(to) {
+                    Some(FileChange::Deleted(from.clone()))
+                } else {
+                    Some(change)
+                }
+            }
+        })
+        .collect()
+}
+
 /// Find files currently in the graph's `file_index` that now match `.rpgignore` patterns.
 ///
 /// Returns `FileChange::Deleted` entries for each file that should be pruned.
@@ -385,11 +659,16 @@ pub fn apply_deletions(graph: &mut RPGraph, deleted_files: &[PathBuf]) -> usize
 /// Structurally updates line numbers for modified entities. New entities within
 /// modified files are inserted without features. Modified entities that previously
 /// had features are tracked in `modified_entity_ids` for interactive re-lifting.
+///
+/// When this thread still holds the tree parsed from a file's prior contents
+/// in `previous`, the file is reparsed incrementally from it. Otherwise it is
+/// parsed once from scratch and `previous` is not read.
 pub fn apply_modifications(
     graph: &mut RPGraph,
     modified_files: &[PathBuf],
     project_root: &Path,
     paradigm: Option<&ParadigmPipeline<'_>>,
+    previous: Option<&dyn FileProvider>,
 ) -> Result<(usize, usize, usize, Vec<String>)> {
     let mut modified_count = 0;
     let mut added_count = 0;
@@ -398,15 +677,17 @@ pub fn apply_modifications(
 
     for file in modified_files {
         let abs_path = project_root.join(file);
-        let file_lang = file
-            .extension()
-            .and_then(|e| e.to_str())
-            .and_then(Language::from_extension);
-        let Some(language) = file_lang else {
+        let Some(language) = Language::from_path(&abs_path) else {
             continue;
         };
         let source = std::fs::read_to_string(&abs_path)
             .with_context(|| format!("failed to read {}", abs_path.display()))?;
+        // Seeds this thread's tree cache, which the extractors below share.
+        if let Some(files) = previous {
+            rpg_parser::treesitter::parse_edited(&source, &language.ts_language(), |text| {
+                files.has_contents(file, text)
+            });
+        }
 
         let mut new_raw: Vec<RawEntity> =
             rpg_parser::entities::extract_entities(file, &source, language);
@@ -464,6 +745,11 @@ pub fn apply_modifications(
                 // Refresh structural fields that paradigm reclassification may change
                 entity.kind = raw.kind;
                 entity.parent_class = raw.parent_class.clone();
+                entity.doc_comment = raw.doc_comment.clone();
+                entity.decorators = raw.decorators.clone();
+                entity.is_async = raw.is_async;
+                entity.is_generator = raw.is_generator;
+                entity.is_unsafe = raw.is_unsafe;
                 modified_count += 1;
                 // Track entities with existing features that need re-lifting
                 if !entity.semantic_features.is_empty() {
@@ -530,11 +816,7 @@ pub fn apply_additions(
 
     for file in added_files {
         let abs_path = project_root.join(file);
-        let file_lang = file
-            .extension()
-            .and_then(|e| e.to_str())
-            .and_then(Language::from_extension);
-        let Some(language) = file_lang else {
+        let Some(language) = Language::from_path(&abs_path) else {
             continue;
         };
         let source = std::fs::read_to_string(&abs_path)
@@ -589,8 +871,10 @@ pub fn apply_additions(
     Ok(added_count)
 }
 
-/// Compute a structural hierarchy path from a file path, matching the logic
-/// in `RPGraph::build_file_path_hierarchy`.
+/// Compute a structural hierarchy path for a single new file: the
+/// `RPGraph::build_file_path_hierarchy` layout without chain collapsing or
+/// overflow grouping, which need the whole file set. `run_update` re-lays out
+/// structural hierarchies after additions.
 pub(crate) fn file_path_hierarchy(file: &Path) -> Option<String> {
     let components: Vec<&str> = file
         .components()
@@ -622,6 +906,39 @@ pub(crate) fn file_path_hierarchy(file: &Path) -> Option<String> {
     }
 }
 
+/// Directories moved by `renames`: for each file moved under the same name,
+/// the renamed directory (`src/net` → `src/network` for
+/// `src/net/http/client.rs` → `src/network/http/client.rs`). Directories
+/// that still exist on disk only lost some files and are left out.
+pub fn moved_directories(
+    project_root: &Path,
+    renames: &[(PathBuf, PathBuf)],
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut moved = std::collections::BTreeSet::new();
+    for (from, to) in renames {
+        let mut from = from.as_path();
+        let mut to = to.as_path();
+        let mut shared_suffix = 0;
+        while let (Some(from_name), Some(to_name)) = (from.file_name(), to.file_name()) {
+            if from_name != to_name {
+                break;
+            }
+            match (from.parent(), to.parent()) {
+                (Some(from_parent), Some(to_parent)) => {
+                    from = from_parent;
+                    to = to_parent;
+                    shared_suffix += 1;
+                }
+                _ => break,
+            }
+        }
+        if shared_suffix > 0 && !from.as_os_str().is_empty() && !project_root.join(from).exists() {
+            moved.insert((from.to_path_buf(), to.to_path_buf()));
+        }
+    }
+    moved.into_iter().collect()
+}
+
 /// Handle renamed files: update entity file paths, rekey entity IDs, and
 /// rewrite all references (file_index, edges, hierarchy) to use the new IDs.
 pub fn apply_renames(graph: &mut RPGraph, renames: &[(PathBuf, PathBuf)]) -> (usize, usize) {
@@ -659,6 +976,9 @@ pub fn apply_renames(graph: &mut RPGraph, renames: &[(PathBuf, PathBuf)]) -> (us
             graph.file_index.remove(from);
             let new_ids: Vec<String> = id_map.iter().map(|(_, new)| new.clone()).collect();
             graph.file_index.insert(to.clone(), new_ids);
+            if let Some(imports) = graph.file_imports.remove(from) {
+                graph.file_imports.insert(to.clone(), imports);
+            }
             migrated_files += 1;
 
             // Rewrite edge references
@@ -730,29 +1050,137 @@ pub fn semantic_similarity(a: &[String], b: &[String]) -> f64 {
     1.0 - compute_drift(a, b)
 }
 
-/// Find the best hierarchy path for an entity based on semantic feature similarity.
+/// How strongly an edge kind ties an entity to its neighbor's area.
+fn neighbor_edge_weight(kind: EdgeKind) -> f64 {
+    match kind {
+        EdgeKind::Invokes | EdgeKind::Inherits => 1.0,
+        EdgeKind::Composes | EdgeKind::Renders => 0.8,
+        EdgeKind::DataFlow
+        | EdgeKind::ReadsState
+        | EdgeKind::WritesState
+        | EdgeKind::Dispatches => 0.6,
+        EdgeKind::Imports => 0.4,
+        // Tests say nothing about where the covered code belongs
+        EdgeKind::Tests | EdgeKind::Contains => 0.0,
+    }
+}
+
+/// Where an entity's direct dependency neighbors live in the hierarchy,
+/// weighted by edge kind and normalized to sum to 1.
+#[derive(Debug, Clone, Default)]
+pub struct NeighborhoodVote {
+    /// Neighbor hierarchy path → share of the total edge weight.
+    paths: Vec<(String, f64)>,
+}
+
+impl NeighborhoodVote {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Share of the vote for `prefix` and everything below it.
+    pub fn share(&self, prefix: &str) -> f64 {
+        self.paths
+            .iter()
+            .filter(|(path, _)| {
+                path == prefix
+                    || path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .map(|(_, share)| share)
+            .sum()
+    }
+
+    /// Shares per top-level area, largest first.
+    pub fn areas(&self) -> Vec<(String, f64)> {
+        let mut areas: Vec<(String, f64)> = Vec::new();
+        for (path, share) in &self.paths {
+            let area = path.split('/').next().unwrap_or_default();
+            match areas.iter_mut().find(|(a, _)| a == area) {
+                Some((_, total)) => *total += share,
+                None => areas.push((area.to_string(), *share)),
+            }
+        }
+        areas.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        areas
+    }
+}
+
+/// Collect the [`NeighborhoodVote`] of `entity_id` from its dependency edges.
+/// Neighbors without a hierarchy path and containment edges don't vote.
+pub fn neighborhood_vote(graph: &RPGraph, entity_id: &str) -> NeighborhoodVote {
+    let mut weights: std::collections::BTreeMap<&str, f64> = std::collections::BTreeMap::new();
+    for edge in graph.edges_for(entity_id) {
+        let weight = neighbor_edge_weight(edge.kind);
+        let neighbor = if edge.source == entity_id {
+            &edge.target
+        } else {
+            &edge.source
+        };
+        if weight == 0.0 || neighbor == entity_id {
+            continue;
+        }
+        if let Some(entity) = graph.entities.get(neighbor)
+            && !entity.hierarchy_path.is_empty()
+        {
+            *weights.entry(entity.hierarchy_path.as_str()).or_default() += weight;
+        }
+    }
+    let total: f64 = weights.values().sum();
+    NeighborhoodVote {
+        paths: weights
+            .into_iter()
+            .map(|(path, weight)| (path.to_string(), weight / total))
+            .collect(),
+    }
+}
+
+/// Find the best hierarchy path for an entity based on semantic feature similarity
+/// and, when `entity_id` is given, the areas of its dependency neighbors.
 ///
 /// Implements the paper's Algorithm 4 (top-down semantic routing): starting from root,
 /// recursively select the child domain whose aggregated features best match the
 /// entity's features, drilling down until no child is a better fit than the current node.
+/// Each node's score blends feature similarity with the node's share of the
+/// [`NeighborhoodVote`]; `neighbor_weight` is the vote's part of the blend.
+/// Entities without placed neighbors are routed on features alone.
 ///
 /// Returns `None` if the hierarchy is empty or features are empty.
-pub fn find_best_hierarchy_path(graph: &RPGraph, features: &[String]) -> Option<String> {
+pub fn find_best_hierarchy_path(
+    graph: &RPGraph,
+    entity_id: Option<&str>,
+    features: &[String],
+    neighbor_weight: f64,
+) -> Option<String> {
     if features.is_empty() || graph.hierarchy.is_empty() {
         return None;
     }
 
+    let vote = entity_id
+        .map(|id| neighborhood_vote(graph, id))
+        .unwrap_or_default();
+    let weight = if vote.is_empty() {
+        0.0
+    } else {
+        neighbor_weight.clamp(0.0, 1.0)
+    };
+    let score = |path: &str, node: &rpg_core::graph::HierarchyNode| {
+        (1.0 - weight) * semantic_similarity(features, &node.semantic_features)
+            + weight * vote.share(path)
+    };
+
     // Step 1: Find the best top-level area
     let mut best_area: Option<(&str, f64)> = None;
     for (area_name, area_node) in &graph.hierarchy {
-        let sim = semantic_similarity(features, &area_node.semantic_features);
+        let sim = score(area_name, area_node);
         if best_area.is_none() || sim > best_area.unwrap().1 {
             best_area = Some((area_name.as_str(), sim));
         }
     }
 
     let (area_name, area_sim) = best_area?;
-    // Minimum similarity threshold — if even the best area is nearly zero,
+    // Minimum score threshold — if even the best area is nearly zero,
     // don't force routing (the entity doesn't fit anywhere)
     if area_sim < 0.05 {
         return None;
@@ -772,7 +1200,7 @@ pub fn find_best_hierarchy_path(graph: &RPGraph, features: &[String]) -> Option<
 
         let mut best_child: Option<(&str, f64)> = None;
         for (child_name, child_node) in &current_node.children {
-            let sim = semantic_similarity(features, &child_node.semantic_features);
+            let sim = score(&format!("{}/{}", current_path, child_name), child_node);
             if best_child.is_none() || sim > best_child.unwrap().1 {
                 best_child = Some((child_name.as_str(), sim));
             }
@@ -849,7 +1277,12 @@ pub fn check_drift_and_reroute(
     graph.aggregate_hierarchy_features();
 
     // Drift exceeds threshold — find new best path
-    let result = match find_best_hierarchy_path(graph, new_features) {
+    let result = match find_best_hierarchy_path(
+        graph,
+        Some(entity_id),
+        new_features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    ) {
         Some(new_path) if new_path != current_path => {
             // Found a better position — reroute
             if let Some(entity) = graph.entities.get_mut(entity_id) {
@@ -896,7 +1329,12 @@ pub fn route_new_entity(graph: &mut RPGraph, entity_id: &str) -> Option<String>
         return None;
     }
 
-    let new_path = find_best_hierarchy_path(graph, &features)?;
+    let new_path = find_best_hierarchy_path(
+        graph,
+        Some(entity_id),
+        &features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    )?;
 
     let current_path = graph
         .entities
@@ -979,15 +1417,46 @@ pub fn run_update_from_changes(
         ));
     }
 
+    let encoding = rpg_core::config::RpgConfig::load(project_root)
+        .unwrap_or_default()
+        .encoding;
+    let linguist = crate::gitattributes::LinguistAttributes::load(project_root, &encoding);
+
     let changes = filter_rpgignore_changes(project_root, changes);
-    let mut changes = filter_source_changes(changes, &languages);
+    let changes = filter_linguist_changes(&linguist, changes);
+    let mut changes = filter_source_changes(project_root, changes, &languages);
 
-    // Prune files that are now covered by .rpgignore but still indexed
+    // Prune files that are now covered by .rpgignore or .gitattributes
+    // linguist markers but still indexed
     let ignored_deletions = find_newly_ignored_files(project_root, graph);
     changes.extend(ignored_deletions);
+    let already_deleted: HashSet<PathBuf> = changes
+        .iter()
+        .filter_map(|change| match change {
+            FileChange::Deleted(path) => Some(path.clone()),
+            _ => None,
+        })
+        .collect();
+    changes.extend(
+        graph
+            .file_index
+            .keys()
+            .filter(|file| linguist.excludes(file) && !already_deleted.contains(*file))
+            .map(|file| FileChange::Deleted(file.clone())),
+    );
 
-    // Prune files that no longer exist on disk (e.g. untracked files removed after build)
-    let fs_deletions = find_deleted_files(project_root, graph);
+    // Prune files that no longer exist on disk (e.g. untracked files removed
+    // after build). Rename sources are gone too, but are migrated instead.
+    let rename_sources: HashSet<PathBuf> = changes
+        .iter()
+        .filter_map(|change| match change {
+            FileChange::Renamed { from, .. } => Some(from.clone()),
+            _ => None,
+        })
+        .collect();
+    let fs_deletions = find_deleted_files(project_root, graph)
+        .into_iter()
+        .filter(|change| !matches!(change, FileChange::Deleted(p) if rename_sources.contains(p)));
     changes.extend(fs_deletions);
 
     if changes.is_empty() {
@@ -1016,12 +1485,24 @@ pub fn run_update_from_changes(
     // Step 1: Deletions (Algorithm 2)
     summary.entities_removed = apply_deletions(graph, &deleted_files);
 
-    // Step 2: Renames
+    // Step 2: Renames. Grounded directories follow moved directories, so
+    // nodes with no entities left to re-ground from don't keep stale paths.
     let (_, _renamed) = apply_renames(graph, &renames);
+    summary.regrounded_nodes =
+        grounding::remap_grounded_paths(graph, &moved_directories(project_root, &renames));
 
     // Step 3: Modifications
-    let (modified, mod_added, mod_removed, mod_stale_ids) =
-        apply_modifications(graph, &modified_files, project_root, paradigm)?;
+    let previous = graph
+        .base_commit
+        .as_deref()
+        .and_then(|rev| crate::git_files::GitFileProvider::open(project_root, rev).ok());
+    let (modified, mod_added, mod_removed, mod_stale_ids) = apply_modifications(
+        graph,
+        &modified_files,
+        project_root,
+        paradigm,
+        previous.as_ref().map(|files| files as &dyn FileProvider),
+    )?;
     summary.entities_modified = modified;
     summary.modified_entity_ids = mod_stale_ids;
     summary.entities_added += mod_added;
@@ -1051,11 +1532,37 @@ pub fn run_update_from_changes(
     );
     grounding::resolve_dependencies(graph);
     crate::dataflow::compute_data_flow_edges(graph);
+    // Any edge change can move distances anywhere downstream, and a full
+    // pass is linear, so recompute rather than patch.
+    rpg_core::entry_points::assign_entry_distances(graph);
+    grounding::record_import_cycles(graph, encoding.ignore_same_directory_import_cycles);
+    graph.set_liftable_kinds(&encoding.liftable_kinds);
+    graph.metadata.max_node_features = encoding.max_node_features;
+
+    // Keep source signatures fresh for graphs built with them
+    if graph.metadata.source_signatures {
+        rpg_core::signature::compute_source_signatures(
+            graph,
+            project_root,
+            Some(&changed_file_list),
+        );
+    }
 
-    // Step 6: Re-ground hierarchy
+    // Step 6: Re-ground hierarchy. Structural hierarchies are laid out again
+    // first, since chain collapsing and overflow grouping depend on the whole
+    // file set.
+    if !graph.metadata.semantic_hierarchy {
+        let packages = rpg_core::structure::detect_packages(project_root);
+        graph.build_file_path_hierarchy_with_packages(&packages);
+    }
     grounding::ground_hierarchy(graph);
 
-    // Step 7: Hierarchy enrichment (V_H unification)
+    // Step 7: Hierarchy enrichment (V_H unification), with owners re-read
+    // from CODEOWNERS so ownership edits land on the next update. Layers are
+    // re-inferred for changed files; overrides apply to all entities.
+    crate::ownership::assign_owners(graph, project_root);
+    crate::layers::assign_layers(graph, project_root, Some(&changed_file_list));
+    crate::fixtures::tag_fixtures(graph, &encoding.fixture_globs);
     graph.assign_hierarchy_ids();
     graph.aggregate_hierarchy_features();
     graph.materialize_containment_edges();
@@ -1064,6 +1571,8 @@ pub fn run_update_from_changes(
     if let Ok(sha) = get_head_sha(project_root) {
         graph.base_commit = Some(sha);
     }
+    crate::nested_repos::refresh(project_root, &mut graph.metadata.nested_repos);
+    graph.metadata.repo_fingerprint = crate::artifact::repo_fingerprint(project_root);
     graph.refresh_metadata();
 
     // Compute deltas
@@ -1078,6 +1587,169 @@ pub fn run_update_from_changes(
     Ok(summary)
 }
 
+/// Risk level of a change set, from leaf utilities (low) to hubs (high).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskLevel {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        })
+    }
+}
+
+/// Risk of one changed file, scored against the graph before the change.
+#[derive(Debug, Clone)]
+pub struct FileRisk {
+    /// Path as indexed in the graph (the old path for renames).
+    pub path: PathBuf,
+    /// Distinct entities in other files that depend on this file's entities.
+    pub fan_in: usize,
+    /// Entities reachable from outside the file: used by another file, or routes.
+    pub public_entities: usize,
+    pub level: RiskLevel,
+}
+
+/// Aggregate risk of a change set: the highest file level, files riskiest first.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeRisk {
+    pub level: RiskLevel,
+    pub files: Vec<FileRisk>,
+}
+
+impl ChangeRisk {
+    /// One-line summary, e.g. `risk: high (3 high-fan-in files incl. src/graph.rs)`.
+    pub fn summary(&self) -> String {
+        let at_level: Vec<&FileRisk> = self
+            .files
+            .iter()
+            .filter(|f| f.level == self.level)
+            .collect();
+        let Some(top) = at_level.first() else {
+            return format!("risk: {} (leaf files only)", self.level);
+        };
+        let plural = if at_level.len() == 1 { "" } else { "s" };
+        match self.level {
+            RiskLevel::High => format!(
+                "risk: high ({} high-fan-in file{} incl. {})",
+                at_level.len(),
+                plural,
+                top.path.display()
+            ),
+            RiskLevel::Medium => format!(
+                "risk: medium ({} file{} with outside dependents incl. {})",
+                at_level.len(),
+                plural,
+                top.path.display()
+            ),
+            RiskLevel::Low => "risk: low (leaf files only)".to_string(),
+        }
+    }
+}
+
+/// Score changed files by how much of the graph depends on them.
+///
+/// Each file's fan-in counts distinct entities in *other* files with a dependency
+/// edge into it. Fan-in at `high_fan_in` is high risk, at `medium_fan_in` medium.
+/// Files with routes are at least medium — their callers live outside the graph.
+/// Added files have no dependents yet and score low.
+pub fn assess_change_risk(
+    graph: &RPGraph,
+    changes: &[FileChange],
+    config: &RiskConfig,
+) -> ChangeRisk {
+    use std::collections::HashMap;
+
+    let paths: Vec<&Path> = changes
+        .iter()
+        .map(|change| match change {
+            FileChange::Added(p) | FileChange::Modified(p) | FileChange::Deleted(p) => p.as_path(),
+            FileChange::Renamed { from, .. } => from.as_path(),
+        })
+        .collect();
+
+    // Entity ID -> index of the changed file containing it
+    let mut owner: HashMap<&str, usize> = HashMap::new();
+    for (idx, path) in paths.iter().enumerate() {
+        for id in graph.file_index.get(*path).into_iter().flatten() {
+            owner.insert(id.as_str(), idx);
+        }
+    }
+
+    let mut dependents: Vec<HashSet<&str>> = vec![HashSet::new(); paths.len()];
+    let mut public: Vec<HashSet<&str>> = vec![HashSet::new(); paths.len()];
+    for edge in &graph.edges {
+        if edge.kind == EdgeKind::Contains {
+            continue;
+        }
+        let Some(&idx) = owner.get(edge.target.as_str()) else {
+            continue;
+        };
+        let Some(source) = graph.entities.get(&edge.source) else {
+            continue;
+        };
+        if source.file.as_path() != paths[idx] {
+            dependents[idx].insert(edge.source.as_str());
+            public[idx].insert(edge.target.as_str());
+        }
+    }
+
+    let mut files: Vec<FileRisk> = paths
+        .iter()
+        .enumerate()
+        .map(|(idx, path)| {
+            let routes: Vec<&str> = graph
+                .file_index
+                .get(*path)
+                .into_iter()
+                .flatten()
+                .filter(|id| {
+                    graph
+                        .entities
+                        .get(*id)
+                        .is_some_and(|e| e.kind == EntityKind::Route)
+                })
+                .map(String::as_str)
+                .collect();
+            let has_routes = !routes.is_empty();
+            public[idx].extend(routes);
+            let fan_in = dependents[idx].len();
+            let level = if fan_in >= config.high_fan_in {
+                RiskLevel::High
+            } else if fan_in >= config.medium_fan_in || has_routes {
+                RiskLevel::Medium
+            } else {
+                RiskLevel::Low
+            };
+            FileRisk {
+                path: path.to_path_buf(),
+                fan_in,
+                public_entities: public[idx].len(),
+                level,
+            }
+        })
+        .collect();
+
+    files.sort_by(|a, b| {
+        b.level
+            .cmp(&a.level)
+            .then(b.fan_in.cmp(&a.fan_in))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    ChangeRisk {
+        level: files.first().map(|f| f.level).unwrap_or_default(),
+        files,
+    }
+}
+
 fn count_hierarchy_nodes(node: &rpg_core::graph::HierarchyNode) -> usize {
     1 + node
         .children
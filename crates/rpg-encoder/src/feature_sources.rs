@@ -0,0 +1,281 @@
+//! Feature provenance per area: how features were produced (`feature_source`),
+//! how old they are (`lifted_at`), and which areas a re-lift should start with.
+//!
+//! The re-lift plan favors areas whose features are mostly auto-lifted, then
+//! areas with the most churn, and sizes each one with the same token-aware
+//! batching `get_entities_for_lifting` uses.
+
+use crate::lift::{LiftScope, build_token_aware_batches, collect_raw_entities_from};
+use crate::tokens::TokenEstimator;
+use chrono::{DateTime, Duration, Utc};
+use rpg_core::config::EncodingConfig;
+use rpg_core::files::FileProvider;
+use rpg_core::graph::{EntityKind, RPGraph};
+use std::collections::BTreeMap;
+
+/// Column for entities without features.
+pub const UNLIFTED: &str = "unlifted";
+
+/// Column for features with no recorded source (graphs from older versions).
+pub const UNKNOWN: &str = "unknown";
+
+/// Area name for entities outside the hierarchy.
+pub const UNASSIGNED: &str = "(unassigned)";
+
+/// Sources counted as auto-lifted in the plan's auto-lift share.
+const AUTO_SOURCES: &[&str] = &["auto", "auto-review"];
+
+/// Known sources in display order; others follow alphabetically.
+const SOURCE_ORDER: &[&str] = &[
+    "auto",
+    "auto-review",
+    "llm",
+    "llm-review",
+    "synthesized",
+    "paradigm",
+];
+
+/// Feature sources and ages of one area's entities (liftable entities and
+/// Module entities, whose features are synthesized).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AreaSources {
+    pub area: String,
+    pub entities: usize,
+    /// `feature_source` → entity count, with [`UNLIFTED`] and [`UNKNOWN`].
+    pub sources: BTreeMap<String, usize>,
+    /// Entities whose features were set more than 30 days ago (includes
+    /// `older_than_90d`).
+    pub older_than_30d: usize,
+    /// Entities whose features were set more than 90 days ago.
+    pub older_than_90d: usize,
+    /// Entities with features but no `lifted_at` (set before it was recorded).
+    pub undated: usize,
+    /// Commits touching the area's entities in the last churn analysis.
+    pub churn: usize,
+}
+
+impl AreaSources {
+    fn new(area: &str) -> Self {
+        Self {
+            area: area.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn count(&self, source: &str) -> usize {
+        self.sources.get(source).copied().unwrap_or(0)
+    }
+
+    fn lifted(&self) -> usize {
+        self.entities - self.count(UNLIFTED)
+    }
+
+    /// Share of lifted entities whose features were auto-lifted.
+    pub fn auto_share(&self) -> f64 {
+        let lifted = self.lifted();
+        if lifted == 0 {
+            return 0.0;
+        }
+        let auto: usize = AUTO_SOURCES.iter().map(|s| self.count(s)).sum();
+        auto as f64 / lifted as f64
+    }
+}
+
+/// One area of the suggested re-lift plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReliftStep {
+    pub area: String,
+    pub auto_share: f64,
+    pub churn: usize,
+    /// Liftable entities a re-lift of the area covers.
+    pub entities: usize,
+    /// Lifting batches those entities fill.
+    pub batches: usize,
+}
+
+/// Area × source matrix, totals, and re-lift plan.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSourceReport {
+    /// Sorted by area name.
+    pub areas: Vec<AreaSources>,
+    pub total: AreaSources,
+    /// Areas with lifted entities, most auto-lifted first, then most churn.
+    pub plan: Vec<ReliftStep>,
+}
+
+/// Tally feature sources and ages per top-level area as of `now`, and plan a
+/// re-lift reading entity sources from `files` to size the batches.
+pub fn analyze(
+    graph: &RPGraph,
+    files: &dyn FileProvider,
+    encoding: &EncodingConfig,
+    estimator: &dyn TokenEstimator,
+    now: DateTime<Utc>,
+) -> FeatureSourceReport {
+    let mut areas: BTreeMap<String, AreaSources> = BTreeMap::new();
+    let mut liftable: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut total = AreaSources::new("(all)");
+    for entity in graph.entities.values() {
+        let is_module = entity.kind == EntityKind::Module;
+        if !is_module && !graph.is_liftable(entity) {
+            continue;
+        }
+        let area = entity
+            .hierarchy_path
+            .split('/')
+            .next()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(UNASSIGNED);
+        if !is_module {
+            liftable
+                .entry(area.to_string())
+                .or_default()
+                .push(entity.id.clone());
+        }
+
+        let lifted = !entity.semantic_features.is_empty();
+        let source = if lifted {
+            entity.feature_source.as_deref().unwrap_or(UNKNOWN)
+        } else {
+            UNLIFTED
+        };
+        let age = entity.lifted_at.map(|at| now - at);
+        let churn = entity.churn.as_ref().map_or(0, |c| c.commits);
+        for sources in [
+            areas
+                .entry(area.to_string())
+                .or_insert_with(|| AreaSources::new(area)),
+            &mut total,
+        ] {
+            sources.entities += 1;
+            *sources.sources.entry(source.to_string()).or_default() += 1;
+            match age {
+                _ if !lifted => {}
+                Some(age) => {
+                    sources.older_than_30d += usize::from(age > Duration::days(30));
+                    sources.older_than_90d += usize::from(age > Duration::days(90));
+                }
+                None => sources.undated += 1,
+            }
+            sources.churn += churn;
+        }
+    }
+
+    let mut plan: Vec<ReliftStep> = areas
+        .values()
+        .filter(|sources| sources.lifted() > 0)
+        .filter_map(|sources| {
+            let ids = liftable.get(&sources.area)?;
+            let raw = collect_raw_entities_from(
+                graph,
+                &LiftScope {
+                    entity_ids: ids.clone(),
+                },
+                files,
+            )
+            .unwrap_or_default();
+            let cap = encoding.for_area(&sources.area).batch_size.min(25);
+            Some(ReliftStep {
+                area: sources.area.clone(),
+                auto_share: sources.auto_share(),
+                churn: sources.churn,
+                entities: ids.len(),
+                batches: build_token_aware_batches(&raw, cap, encoding.max_batch_tokens, estimator)
+                    .len(),
+            })
+        })
+        .collect();
+    plan.sort_by(|a, b| {
+        b.auto_share
+            .total_cmp(&a.auto_share)
+            .then_with(|| b.churn.cmp(&a.churn))
+            .then_with(|| a.area.cmp(&b.area))
+    });
+
+    FeatureSourceReport {
+        areas: areas.into_values().collect(),
+        total,
+        plan,
+    }
+}
+
+/// Matrix, age buckets, and the first `max_plan` plan steps as text tables.
+pub fn format_report(report: &FeatureSourceReport, max_plan: usize) -> String {
+    let mut columns: Vec<&str> = SOURCE_ORDER
+        .iter()
+        .copied()
+        .filter(|s| report.total.sources.contains_key(*s))
+        .collect();
+    columns.extend(
+        report
+            .total
+            .sources
+            .keys()
+            .map(String::as_str)
+            .filter(|s| !SOURCE_ORDER.contains(s) && *s != UNKNOWN && *s != UNLIFTED),
+    );
+    columns.extend(
+        [UNKNOWN, UNLIFTED]
+            .into_iter()
+            .filter(|s| report.total.sources.contains_key(*s)),
+    );
+
+    let width = report
+        .areas
+        .iter()
+        .map(|a| a.area.len())
+        .chain([report.total.area.len(), "area".len()])
+        .max()
+        .unwrap_or(4);
+    let mut output = format!("{:<width$} {:>8}", "area", "entities");
+    for column in &columns {
+        output.push_str(&format!(" {:>w$}", column, w = column.len().max(5)));
+    }
+    output.push_str("  >30d  >90d undated  churn\n");
+    for sources in report.areas.iter().chain([&report.total]) {
+        output.push_str(&format!("{:<width$} {:>8}", sources.area, sources.entities));
+        for column in &columns {
+            output.push_str(&format!(
+                " {:>w$}",
+                sources.count(column),
+                w = column.len().max(5)
+            ));
+        }
+        output.push_str(&format!(
+            " {:>5} {:>5} {:>7} {:>6}\n",
+            sources.older_than_30d, sources.older_than_90d, sources.undated, sources.churn
+        ));
+    }
+
+    if report.plan.is_empty() {
+        output.push_str("\nre-lift plan: nothing lifted yet\n");
+        return output;
+    }
+    output.push_str(&format!(
+        "\nre-lift plan (most auto-lifted, then most churn):\n{:<width$} {:>5} {:>6} {:>8} {:>7}\n",
+        "area", "auto", "churn", "entities", "batches"
+    ));
+    for step in report.plan.iter().take(max_plan) {
+        output.push_str(&format!(
+            "{:<width$} {:>4.0}% {:>6} {:>8} {:>7}\n",
+            step.area,
+            step.auto_share * 100.0,
+            step.churn,
+            step.entities,
+            step.batches
+        ));
+    }
+    if report.plan.len() > max_plan {
+        output.push_str(&format!(
+            "... {} more area(s)\n",
+            report.plan.len() - max_plan
+        ));
+    }
+    let batches: usize = report.plan.iter().map(|s| s.batches).sum();
+    let entities: usize = report.plan.iter().map(|s| s.entities).sum();
+    output.push_str(&format!(
+        "re-lifting every area: {} entities in {} batches\n",
+        entities, batches
+    ));
+    output
+}
@@ -0,0 +1,43 @@
+//! Test fixture files (`encoding.fixture_globs`).
+//!
+//! Fixtures are sample projects checked in for tests. They are indexed like
+//! any other source so fetch, explore, and dependency queries still work, but
+//! their entities carry the [`FIXTURE_TAG`] tag, which keeps them out of
+//! lifting, file synthesis, semantic hierarchy construction, and default
+//! search results.
+
+use globset::{Glob, GlobSetBuilder};
+use rpg_core::graph::{FIXTURE_TAG, RPGraph, normalize_path};
+use std::collections::BTreeSet;
+
+/// Tag every entity in a file matching `globs` as a fixture and untag the
+/// rest, so edits to the globs take effect on the next build or update.
+/// Returns the number of fixture files.
+///
+/// Invalid globs are reported and skipped.
+pub fn tag_fixtures(graph: &mut RPGraph, globs: &[String]) -> usize {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in globs {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => eprintln!("  Warning: ignoring fixture glob {:?}: {}", pattern, e),
+        }
+    }
+    let Ok(set) = builder.build() else {
+        return 0;
+    };
+
+    let mut fixture_files = BTreeSet::new();
+    for entity in graph.entities.values_mut() {
+        let file = normalize_path(&entity.file);
+        if set.is_match(&file) {
+            entity.tags.insert(FIXTURE_TAG.to_string());
+            fixture_files.insert(file);
+        } else {
+            entity.tags.remove(FIXTURE_TAG);
+        }
+    }
+    fixture_files.len()
+}
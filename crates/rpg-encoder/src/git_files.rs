@@ -0,0 +1,76 @@
+//! Read source files from a git revision without a worktree checkout
+//! (`--rev` on build, search, and fetch).
+//!
+//! Blobs come straight from the object database, so this works against a
+//! bare mirror. Paths are repo-relative, matching a worktree build.
+//! Submodule entries are not blobs and are skipped.
+
+use anyhow::{Context, Result};
+use rpg_core::files::FileProvider;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Files in the tree of one commit.
+pub struct GitFileProvider {
+    repo: git2::Repository,
+    tree: git2::Oid,
+    commit: String,
+}
+
+impl GitFileProvider {
+    /// Open the repository at `repo_path` (bare or not) and resolve `rev`
+    /// (a SHA, branch, or tag) to a commit.
+    pub fn open(repo_path: &Path, rev: &str) -> Result<Self> {
+        let repo = git2::Repository::open(repo_path).context("failed to open git repo")?;
+        let (tree, commit) = {
+            let commit = repo
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .with_context(|| format!("unknown revision: {}", rev))?;
+            (commit.tree_id(), commit.id().to_string())
+        };
+        Ok(Self { repo, tree, commit })
+    }
+
+    /// Full SHA of the resolved commit.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// Every file path in the tree, in tree order.
+    pub fn files(&self) -> Result<Vec<PathBuf>> {
+        let tree = self.repo.find_tree(self.tree)?;
+        let mut files = Vec::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(git2::ObjectType::Blob)
+                && let Some(name) = entry.name()
+            {
+                files.push(PathBuf::from(format!("{}{}", dir, name)));
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        Ok(files)
+    }
+}
+
+impl FileProvider for GitFileProvider {
+    fn read_to_string(&self, rel_path: &Path) -> io::Result<String> {
+        let not_found = |e: git2::Error| io::Error::new(io::ErrorKind::NotFound, e.message());
+        let tree = self.repo.find_tree(self.tree).map_err(not_found)?;
+        let entry = tree.get_path(rel_path).map_err(not_found)?;
+        let blob = self.repo.find_blob(entry.id()).map_err(not_found)?;
+        String::from_utf8(blob.content().to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Compares blob ids, so the blob itself is never read.
+    fn has_contents(&self, rel_path: &Path, contents: &str) -> bool {
+        let Ok(tree) = self.repo.find_tree(self.tree) else {
+            return false;
+        };
+        tree.get_path(rel_path).is_ok_and(|entry| {
+            git2::Oid::hash_object(git2::ObjectType::Blob, contents.as_bytes())
+                .is_ok_and(|id| id == entry.id())
+        })
+    }
+}
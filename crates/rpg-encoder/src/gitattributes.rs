@@ -0,0 +1,143 @@
+//! Linguist markers in `.gitattributes` (`encoding.honor_gitattributes`).
+//!
+//! Repositories often flag vendored and generated code for GitHub's
+//! linguist already:
+//!
+//! ```text
+//! third_party/** linguist-vendored
+//! *.pb.go        linguist-generated=true
+//! docs/**        linguist-documentation
+//! ```
+//!
+//! Files marked `linguist-vendored`, `linguist-generated`, or
+//! `linguist-documentation` are skipped during file collection, so they need
+//! no `.rpgignore` entry. Only the project root's `.gitattributes` is read.
+//!
+//! Patterns follow gitattributes rules, not gitignore ones: a pattern without
+//! `/` matches a file name at any depth, any other pattern is anchored at the
+//! project root, and a pattern never matches the files inside a directory it
+//! names (`vendor/` and `vendor` match nothing below `vendor`; `vendor/**`
+//! does). Negative patterns are not allowed and are skipped. The last line
+//! that sets an attribute wins, so `-linguist-vendored` or
+//! `linguist-vendored=false` on a later line keeps a file.
+
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use rpg_core::config::EncodingConfig;
+use rpg_core::graph::normalize_path;
+use std::path::Path;
+
+/// The attributes that exclude a file when set.
+const EXCLUDING_ATTRIBUTES: [&str; 3] = [
+    "linguist-vendored",
+    "linguist-generated",
+    "linguist-documentation",
+];
+
+/// One `.gitattributes` line that mentions a linguist attribute.
+struct Rule {
+    matcher: GlobMatcher,
+    /// The pattern has no `/` and is matched against the file name.
+    basename: bool,
+    /// Per [`EXCLUDING_ATTRIBUTES`] entry: `Some(set)` when the line sets or
+    /// unsets it, `None` when it leaves it alone.
+    states: [Option<bool>; 3],
+}
+
+/// Parsed linguist rules from a project's `.gitattributes`.
+#[derive(Default)]
+pub struct LinguistAttributes {
+    rules: Vec<Rule>,
+}
+
+impl LinguistAttributes {
+    /// Rules from `project_root/.gitattributes`, or none when the file is
+    /// missing or `encoding.honor_gitattributes` is off.
+    pub fn load(project_root: &Path, encoding: &EncodingConfig) -> Self {
+        if !encoding.honor_gitattributes {
+            return Self::default();
+        }
+        std::fs::read_to_string(project_root.join(".gitattributes"))
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    /// Parse `.gitattributes` text, keeping only lines with linguist
+    /// attributes.
+    pub fn parse(text: &str) -> Self {
+        let rules = text.lines().filter_map(parse_line).collect();
+        Self { rules }
+    }
+
+    /// Whether `rel_path` (relative to the project root) is vendored,
+    /// generated, or documentation.
+    pub fn excludes(&self, rel_path: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let path = normalize_path(rel_path);
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        let mut states = [None; 3];
+        for rule in &self.rules {
+            let subject = if rule.basename { name } else { path.as_str() };
+            if !rule.matcher.is_match(subject) {
+                continue;
+            }
+            for (state, rule_state) in states.iter_mut().zip(rule.states) {
+                if rule_state.is_some() {
+                    *state = rule_state;
+                }
+            }
+        }
+        states.contains(&Some(true))
+    }
+}
+
+fn parse_line(line: &str) -> Option<Rule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+        return None;
+    }
+    let (pattern, attributes) = if let Some(rest) = line.strip_prefix('"') {
+        rest.split_once('"')?
+    } else {
+        line.split_once(char::is_whitespace)?
+    };
+    // Negative patterns are forbidden, and directory patterns never match
+    // the files below them.
+    if pattern.starts_with('!') || pattern.ends_with('/') {
+        return None;
+    }
+
+    let mut states = [None; 3];
+    for attribute in attributes.split_whitespace() {
+        // `-attr` unsets; `!attr` returns to unspecified, which for an
+        // exclusion marker means the same thing.
+        let (name, set) = if let Some(name) = attribute.strip_prefix(['-', '!']) {
+            (name, false)
+        } else if let Some((name, value)) = attribute.split_once('=') {
+            (name, value != "false")
+        } else {
+            (attribute, true)
+        };
+        if let Some(i) = EXCLUDING_ATTRIBUTES.iter().position(|a| *a == name) {
+            states[i] = Some(set);
+        }
+    }
+    if states.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let basename = !pattern.contains('/');
+    let glob = pattern.trim_start_matches('/');
+    let matcher = GlobBuilder::new(glob)
+        .literal_separator(true)
+        .build()
+        .or_else(|_| Glob::new(&globset::escape(glob)))
+        .ok()?
+        .compile_matcher();
+    Some(Rule {
+        matcher,
+        basename,
+        states,
+    })
+}
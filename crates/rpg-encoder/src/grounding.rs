@@ -1,13 +1,18 @@
 //! Artifact Grounding — anchor hierarchy to directories and resolve dependency edges.
 
-use rpg_core::graph::{DependencyEdge, EdgeKind, HierarchyNode, RPGraph};
+use rpg_core::files::{FileProvider, WorktreeFiles};
+use rpg_core::graph::{
+    DependencyEdge, EdgeKind, EdgeResolutionStats, EntityKind, FileImport, HierarchyNode, RPGraph,
+};
 use rpg_core::lca;
 use rpg_parser::deps;
+use rpg_parser::events::EventRole;
 use rpg_parser::languages::Language;
 use rpg_parser::paradigms::defs::ParadigmDef;
+use rpg_parser::paradigms::features::{FIXTURE_PREFIX, STATE_KEY_PREFIX};
 use rpg_parser::paradigms::query_engine::QueryCache;
-use std::collections::{BTreeMap, HashMap};
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Paradigm context for TOML-driven dependency extraction.
 /// When provided to `populate_entity_deps`, the TOML dep pipeline
@@ -40,6 +45,62 @@ fn ground_node(node: &mut HierarchyNode, entities: &BTreeMap<String, rpg_core::g
     }
 }
 
+/// Rewrite grounded directories under a moved directory (`from` → `to`
+/// prefix pairs), then re-ground the affected nodes. Nodes whose subtree has
+/// no entities keep the rewritten paths. Returns the `/`-joined hierarchy
+/// paths of the remapped nodes.
+pub fn remap_grounded_paths(graph: &mut RPGraph, moved_dirs: &[(PathBuf, PathBuf)]) -> Vec<String> {
+    let mut remapped = Vec::new();
+    if moved_dirs.is_empty() {
+        return remapped;
+    }
+    let entities = &graph.entities;
+    for (name, area) in &mut graph.hierarchy {
+        remap_node(area, name, moved_dirs, entities, &mut remapped);
+    }
+    remapped
+}
+
+fn remap_node(
+    node: &mut HierarchyNode,
+    path: &str,
+    moved_dirs: &[(PathBuf, PathBuf)],
+    entities: &BTreeMap<String, rpg_core::graph::Entity>,
+    remapped: &mut Vec<String>,
+) {
+    for (name, child) in &mut node.children {
+        remap_node(
+            child,
+            &format!("{}/{}", path, name),
+            moved_dirs,
+            entities,
+            remapped,
+        );
+    }
+
+    let mut changed = false;
+    for grounded in &mut node.grounded_paths {
+        // The longest moved prefix wins for nested moves.
+        let Some((from, to)) = moved_dirs
+            .iter()
+            .filter(|(from, _)| grounded.starts_with(from))
+            .max_by_key(|(from, _)| from.components().count())
+        else {
+            continue;
+        };
+        if let Ok(rest) = grounded.strip_prefix(from) {
+            *grounded = to.join(rest);
+            changed = true;
+        }
+    }
+    if changed {
+        node.grounded_paths.sort();
+        node.grounded_paths.dedup();
+        ground_node(node, entities);
+        remapped.push(path.to_string());
+    }
+}
+
 /// Populate entity deps from AST-extracted raw dependencies.
 /// This must be called before `resolve_dependencies` so that entity deps contain
 /// the callee/import/inherit names that resolve_dependencies will match to entity IDs.
@@ -48,19 +109,41 @@ fn ground_node(node: &mut HierarchyNode, entities: &BTreeMap<String, rpg_core::g
 /// get no import edges rather than all file-level imports being broadcast to them.
 ///
 /// When `changed_files` is `Some`, only entities in those files are re-populated (their
-/// forward deps are cleared first). When `None`, all files are processed.
+/// forward deps are cleared first). When `None`, all files are processed and the
+/// unresolved import report in the metadata is rebuilt.
 pub fn populate_entity_deps(
     graph: &mut RPGraph,
     project_root: &Path,
     broadcast_imports: bool,
     changed_files: Option<&[std::path::PathBuf]>,
     paradigm_ctx: Option<&ParadigmContext<'_>>,
+) {
+    populate_entity_deps_from(
+        graph,
+        &WorktreeFiles::new(project_root),
+        broadcast_imports,
+        changed_files,
+        paradigm_ctx,
+    );
+}
+
+/// [`populate_entity_deps`] reading sources from `files`, e.g. a git revision.
+pub fn populate_entity_deps_from(
+    graph: &mut RPGraph,
+    files: &dyn FileProvider,
+    broadcast_imports: bool,
+    changed_files: Option<&[std::path::PathBuf]>,
+    paradigm_ctx: Option<&ParadigmContext<'_>>,
 ) {
     // Scope to changed files or all files
     let file_list: Vec<_> = match changed_files {
         Some(files) => files.to_vec(),
         None => graph.file_index.keys().cloned().collect(),
     };
+    let mut file_imports = Vec::new();
+    let aliases =
+        crate::tsconfig::PathAliases::load(files, graph.file_index.keys().map(PathBuf::as_path));
+    let resolver = crate::import_paths::ImportResolver::new(graph).with_path_aliases(aliases);
 
     // Clear forward deps only for entities in scoped files
     for rel_path in &file_list {
@@ -81,8 +164,7 @@ pub fn populate_entity_deps(
         let Some(language) = file_lang else {
             continue;
         };
-        let abs_path = project_root.join(rel_path);
-        let Ok(source) = std::fs::read_to_string(&abs_path) else {
+        let Ok(source) = files.read_to_string(rel_path) else {
             continue;
         };
 
@@ -117,7 +199,26 @@ pub fn populate_entity_deps(
                     line_end: e.line_end,
                     parent_class: e.parent_class.clone(),
                     source_text: String::new(),
-                    signature: None,
+                    signature: e
+                        .signature
+                        .as_ref()
+                        .map(|sig| rpg_parser::entities::RawSignature {
+                            parameters: sig
+                                .parameters
+                                .iter()
+                                .map(|p| rpg_parser::entities::RawParam {
+                                    name: p.name.clone(),
+                                    type_annotation: p.type_annotation.clone(),
+                                })
+                                .collect(),
+                            return_type: sig.return_type.clone(),
+                        }),
+                    attributes: BTreeMap::new(),
+                    doc_comment: None,
+                    decorators: e.decorators.clone(),
+                    is_async: false,
+                    is_generator: false,
+                    is_unsafe: false,
                 })
                 .collect();
 
@@ -131,6 +232,28 @@ pub fn populate_entity_deps(
             );
         }
 
+        // Keep the file's imports for import-aware resolution
+        let importer = rpg_core::graph::normalize_path(rel_path);
+        let is_js = language == Language::TYPESCRIPT || language == Language::JAVASCRIPT;
+        if raw_deps.imports.is_empty() {
+            graph.file_imports.remove(rel_path);
+        } else {
+            graph.file_imports.insert(
+                rel_path.clone(),
+                raw_deps
+                    .imports
+                    .iter()
+                    .map(|imp| FileImport {
+                        module: imp.module.clone(),
+                        symbols: imp.symbols.clone(),
+                        file: is_js
+                            .then(|| resolver.js_aliased_file(&importer, &imp.module))
+                            .flatten(),
+                    })
+                    .collect(),
+            );
+        }
+
         // Get entity IDs for this file
         let entity_ids = match graph.file_index.get(rel_path) {
             Some(ids) => ids.clone(),
@@ -157,19 +280,56 @@ pub fn populate_entity_deps(
                 }
             })
             .collect();
+        if changed_files.is_none() {
+            file_imports.push((rel_path.clone(), raw_deps.imports.clone()));
+        }
+
+        // Imports naming files (Python relative imports, JS/TS relative and
+        // aliased specifiers): the Module entity imports each one by path,
+        // resolved to that file's Module entity.
+        if let Some(module_id) = graph.module_entity(rel_path).map(|e| e.id.clone()) {
+            let targets: Vec<String> =
+                graph
+                    .file_imports
+                    .get(rel_path)
+                    .map_or_else(Vec::new, |imports| {
+                        imports
+                            .iter()
+                            .flat_map(|import| resolver.module_files(&importer, import))
+                            .collect()
+                    });
+            if let Some(module) = graph.entities.get_mut(&module_id) {
+                for target in targets {
+                    if !module.deps.imports.contains(&target) {
+                        module.deps.imports.push(target);
+                    }
+                }
+            }
+        }
 
         // Map call-like deps (calls, renders, reads_state, writes_state, dispatches)
         // generically: match caller_entity name to actual entity, push to correct dep vector.
         for (edge_kind, call_deps) in raw_deps.call_dep_vectors() {
             for call in call_deps {
                 for id in &entity_ids {
-                    if let Some(entity) = graph.entities.get_mut(id) {
-                        let matches = entity.name == call.caller_entity
-                            || call.caller_entity.ends_with(&format!(".{}", entity.name))
-                            || call.caller_entity.ends_with(&format!("::{}", entity.name));
-                        if matches {
-                            push_forward_dep(&mut entity.deps, edge_kind, &call.callee);
-                        }
+                    if let Some(entity) = graph.entities.get_mut(id)
+                        && is_caller(&entity.name, &call.caller_entity)
+                    {
+                        push_forward_dep(&mut entity.deps, edge_kind, &call.callee);
+                    }
+                }
+            }
+        }
+
+        // Spawned calls: recorded as invokes too, so their edges exist.
+        for call in &raw_deps.spawns {
+            for id in &entity_ids {
+                if let Some(entity) = graph.entities.get_mut(id)
+                    && is_caller(&entity.name, &call.caller_entity)
+                {
+                    push_forward_dep(&mut entity.deps, EdgeKind::Invokes, &call.callee);
+                    if !entity.deps.spawns.contains(&call.callee) {
+                        entity.deps.spawns.push(call.callee.clone());
                     }
                 }
             }
@@ -187,18 +347,71 @@ pub fn populate_entity_deps(
             }
         }
 
-        // Map composes: assign only to Module entities (barrel re-exports are file-level)
+        // Map composes: barrel re-exports are file-level and go to the Module
+        // entity; field types go to the owning class or struct.
         for compose in &raw_deps.composes {
+            let is_reexport = compose.source_entity == "<module>";
+            // Rust modules (`mod foo;`, `pub use foo::*`) are named by path:
+            // compose the module's file, or nothing when it isn't in the graph.
+            let target_name = match compose.target_name.strip_suffix("::*") {
+                Some(path) if language == Language::RUST => {
+                    match resolver.rust_module_file(&importer, path) {
+                        Some(file) => file,
+                        None => continue,
+                    }
+                }
+                _ => compose.target_name.clone(),
+            };
             for id in &entity_ids {
                 if let Some(entity) = graph.entities.get_mut(id)
-                    && entity.kind == rpg_core::graph::EntityKind::Module
-                    && !entity.deps.composes.contains(&compose.target_name)
+                    && if is_reexport {
+                        entity.kind == rpg_core::graph::EntityKind::Module
+                    } else {
+                        entity.kind != rpg_core::graph::EntityKind::Module
+                            && entity.name == compose.source_entity
+                    }
+                    && !entity.deps.composes.contains(&target_name)
                 {
-                    entity.deps.composes.push(compose.target_name.clone());
+                    entity.deps.composes.push(target_name.clone());
                 }
             }
         }
 
+        // Config reads go to the innermost entity spanning the line, or to
+        // the Module entity for top-level reads.
+        for read in &raw_deps.config_reads {
+            let owner = line_owner(graph, &entity_ids, read.line);
+            if let Some(entity) = owner.and_then(|id| graph.entities.get_mut(&id))
+                && !entity.deps.env_reads.contains(&read.key)
+            {
+                entity.deps.env_reads.push(read.key.clone());
+            }
+        }
+
+        // Emits belong to the entity spanning the call; a listener's event
+        // goes to its named handler when one is found, else to the entity
+        // registering it (inline handlers).
+        for event in &raw_deps.events {
+            let owner = match &event.handler {
+                Some(handler) if event.role == EventRole::Listen => {
+                    handler_entity(graph, &entity_ids, handler)
+                        .or_else(|| line_owner(graph, &entity_ids, event.line))
+                }
+                _ => line_owner(graph, &entity_ids, event.line),
+            };
+            let Some(entity) = owner.and_then(|id| graph.entities.get_mut(&id)) else {
+                continue;
+            };
+            let names = match (event.literal, event.role) {
+                (false, _) => &mut entity.deps.unresolved_events,
+                (true, EventRole::Emit) => &mut entity.deps.emits,
+                (true, EventRole::Listen) => &mut entity.deps.handles,
+            };
+            if !names.contains(&event.event) {
+                names.push(event.event.clone());
+            }
+        }
+
         // Scoped import assignment: only assign imports that the entity actually references.
         // If the entity invokes or inherits a symbol that matches an import, assign it.
         // Fall back to broadcast if the entity has no call-site info.
@@ -221,6 +434,344 @@ pub fn populate_entity_deps(
             }
         }
     }
+
+    link_app_route_tree(graph);
+    link_state_keys(graph);
+    link_pytest_fixtures(graph);
+    link_go_interfaces(graph);
+    if changed_files.is_none() {
+        graph.metadata.unresolved_imports = Some(crate::unresolved_imports::summarize(
+            graph,
+            files,
+            &file_imports,
+        ));
+    }
+}
+
+/// Whether a call dep's `caller` scope names the entity `name`
+/// (`Type.method` and `Type::method` scopes included).
+fn is_caller(name: &str, caller: &str) -> bool {
+    caller == name
+        || caller.ends_with(&format!(".{}", name))
+        || caller.ends_with(&format!("::{}", name))
+}
+
+/// The innermost entity among `entity_ids` spanning `line`, or the file's
+/// Module entity for top-level code.
+fn line_owner(graph: &RPGraph, entity_ids: &[String], line: usize) -> Option<String> {
+    entity_ids
+        .iter()
+        .filter_map(|id| graph.entities.get(id))
+        .filter(|e| e.kind == EntityKind::Module || (e.line_start <= line && line <= e.line_end))
+        .min_by_key(|e| {
+            (
+                e.kind == EntityKind::Module,
+                e.line_end.saturating_sub(e.line_start),
+            )
+        })
+        .map(|e| e.id.clone())
+}
+
+/// The entity an event handler names: one in the same file first, else the
+/// only entity in the graph with that name.
+fn handler_entity(graph: &RPGraph, entity_ids: &[String], handler: &str) -> Option<String> {
+    let named = |e: &&rpg_core::graph::Entity| e.kind != EntityKind::Module && e.name == handler;
+    if let Some(local) = entity_ids
+        .iter()
+        .filter_map(|id| graph.entities.get(id))
+        .find(named)
+    {
+        return Some(local.id.clone());
+    }
+    let mut global = graph.entities.values().filter(named);
+    match (global.next(), global.next()) {
+        (Some(only), None) => Some(only.id.clone()),
+        _ => None,
+    }
+}
+
+/// Renders edges from Next.js App Router layouts to the pages, loading
+/// states, and layouts nested directly under them. Nesting comes from the
+/// directory tree, so every layout is relinked on each run and added or
+/// removed pages are picked up even when the layout file itself is unchanged.
+fn link_app_route_tree(graph: &mut RPGraph) {
+    let routes: Vec<(String, std::path::PathBuf, String)> = graph
+        .entities
+        .values()
+        .filter(|e| e.kind == EntityKind::Route && e.attributes.contains_key("route_file"))
+        .map(|e| (e.id.clone(), e.file.clone(), e.name.clone()))
+        .collect();
+    if routes.is_empty() {
+        return;
+    }
+
+    let files: Vec<&std::path::PathBuf> = routes.iter().map(|(_, file, _)| file).collect();
+    let nesting = rpg_parser::paradigms::features::app_route_nesting(&files);
+    for (id, _, _) in &routes {
+        if let Some(entity) = graph.entities.get_mut(id)
+            && entity.attributes.get("route_file").map(String::as_str) == Some("layout")
+        {
+            entity.deps.renders.clear();
+        }
+    }
+    for (layout, child) in nesting {
+        if let Some(entity) = graph.entities.get_mut(&routes[layout].0) {
+            push_forward_dep(&mut entity.deps, EdgeKind::Renders, &routes[child].2);
+        }
+    }
+}
+
+/// Resolve `state.<key>` reads (a selector reading `state.auth`) to the Store
+/// whose `store` attribute is that key. Reads of keys that no single store
+/// declares are dropped rather than left to match an unrelated entity.
+fn link_state_keys(graph: &mut RPGraph) {
+    let mut stores: HashMap<String, Vec<String>> = HashMap::new();
+    for entity in graph.entities.values() {
+        if entity.kind == EntityKind::Store
+            && let Some(key) = entity.attributes.get("store")
+        {
+            stores
+                .entry(key.clone())
+                .or_default()
+                .push(entity.name.clone());
+        }
+    }
+
+    for entity in graph.entities.values_mut() {
+        if !entity
+            .deps
+            .reads_state
+            .iter()
+            .any(|r| r.starts_with(STATE_KEY_PREFIX))
+        {
+            continue;
+        }
+        let reads = std::mem::take(&mut entity.deps.reads_state);
+        for read in reads {
+            let target = match read.strip_prefix(STATE_KEY_PREFIX) {
+                Some(key) => match stores.get(key).map(Vec::as_slice) {
+                    Some([store]) => store.clone(),
+                    _ => continue,
+                },
+                None => read,
+            };
+            if !entity.deps.reads_state.contains(&target) {
+                entity.deps.reads_state.push(target);
+            }
+        }
+    }
+}
+
+/// Resolve `fixture.<name>` composes (a test or fixture requesting a pytest
+/// fixture) the way pytest does: the requester's own module first, then
+/// `conftest.py` in its directory and in each parent up to the project root.
+/// The target is recorded by entity ID, since fixture names repeat across
+/// conftests. Names nothing in the chain provides (`tmp_path`, `request`,
+/// plugin fixtures) are dropped.
+fn link_pytest_fixtures(graph: &mut RPGraph) {
+    let requesters: Vec<String> = graph
+        .entities
+        .values()
+        .filter(|e| {
+            e.deps
+                .composes
+                .iter()
+                .any(|c| c.starts_with(FIXTURE_PREFIX))
+        })
+        .map(|e| e.id.clone())
+        .collect();
+    if requesters.is_empty() {
+        return;
+    }
+    // (file, fixture name) → fixture ID
+    let fixtures: HashMap<(PathBuf, String), String> = graph
+        .entities
+        .values()
+        .filter(|e| e.kind == EntityKind::Fixture)
+        .map(|e| ((e.file.clone(), fixture_name(e)), e.id.clone()))
+        .collect();
+
+    for id in requesters {
+        let Some(entity) = graph.entities.get_mut(&id) else {
+            continue;
+        };
+        let chain: Vec<PathBuf> = std::iter::once(entity.file.clone())
+            .chain(
+                entity
+                    .file
+                    .parent()
+                    .into_iter()
+                    .flat_map(Path::ancestors)
+                    .map(|dir| dir.join("conftest.py")),
+            )
+            .collect();
+        let composes = std::mem::take(&mut entity.deps.composes);
+        for target in composes {
+            let target = match target.strip_prefix(FIXTURE_PREFIX) {
+                // A fixture overriding a same-named one gets the outer one.
+                Some(name) => match chain
+                    .iter()
+                    .filter_map(|file| fixtures.get(&(file.clone(), name.to_string())))
+                    .find(|fixture| **fixture != id)
+                {
+                    Some(fixture) => fixture.clone(),
+                    None => continue,
+                },
+                None => target,
+            };
+            if !entity.deps.composes.contains(&target) {
+                entity.deps.composes.push(target);
+            }
+        }
+    }
+}
+
+/// The name tests request a fixture by: its function name, or the `name=`
+/// given to `@pytest.fixture`.
+fn fixture_name(entity: &rpg_core::graph::Entity) -> String {
+    entity
+        .decorators
+        .iter()
+        .filter(|d| d.split('(').next().is_some_and(|n| n.ends_with("fixture")))
+        .find_map(|d| {
+            let (_, rest) = d.split_once("name=")?;
+            let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            rest[1..].split(quote).next().map(str::to_string)
+        })
+        .unwrap_or_else(|| entity.name.clone())
+}
+
+/// Most interface × type comparisons [`link_go_interfaces`] makes in one
+/// Go package; larger packages are skipped.
+pub const GO_INTERFACE_MAX_COMPARISONS: usize = 250_000;
+
+/// Go method sets for one package: type name → (own methods, embedded types).
+type GoMethodSets = BTreeMap<String, (BTreeSet<String>, Vec<String>)>;
+
+/// Inherits deps from each Go type to the interfaces in its package whose
+/// methods it has. A type's methods include pointer-receiver methods and
+/// those promoted from embedded types; an interface's include those of the
+/// interfaces it embeds. Empty interfaces are skipped. Implementation is
+/// implicit and spans files, so every Go type is relinked on each run.
+fn link_go_interfaces(graph: &mut RPGraph) {
+    let is_go = |e: &rpg_core::graph::Entity| e.file.extension().is_some_and(|x| x == "go");
+    // package directory → (interfaces, types)
+    let mut packages: BTreeMap<PathBuf, (GoMethodSets, GoMethodSets)> = BTreeMap::new();
+    let mut type_ids: HashMap<(PathBuf, String), Vec<String>> = HashMap::new();
+    let split_list = |value: &str| -> Vec<String> {
+        value
+            .split(", ")
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+    for entity in graph.entities.values().filter(|e| is_go(e)) {
+        let package = entity
+            .file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let (interfaces, types) = packages.entry(package.clone()).or_default();
+        match entity.kind {
+            EntityKind::Class => {
+                let embeds = entity
+                    .attributes
+                    .get("embeds")
+                    .map(|v| split_list(v))
+                    .unwrap_or_default();
+                let (sets, own) = match entity.attributes.get("interface_methods") {
+                    Some(methods) => (interfaces, split_list(methods)),
+                    None => {
+                        type_ids
+                            .entry((package, entity.name.clone()))
+                            .or_default()
+                            .push(entity.id.clone());
+                        (types, Vec::new())
+                    }
+                };
+                let set = sets.entry(entity.name.clone()).or_default();
+                set.0.extend(own);
+                set.1.extend(embeds);
+            }
+            EntityKind::Method => {
+                // Receivers are stored without `*`; drop type parameters too.
+                if let Some(receiver) = entity.parent_class.as_deref() {
+                    let receiver = receiver.split('[').next().unwrap_or(receiver).trim();
+                    types
+                        .entry(receiver.to_string())
+                        .or_default()
+                        .0
+                        .insert(entity.name.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for ids in type_ids.values() {
+        for id in ids {
+            if let Some(entity) = graph.entities.get_mut(id) {
+                entity.deps.inherits.clear();
+            }
+        }
+    }
+
+    for (package, (interfaces, types)) in &packages {
+        let interface_sets: Vec<(&String, BTreeSet<String>)> = interfaces
+            .keys()
+            .map(|name| {
+                (
+                    name,
+                    go_method_set(name, interfaces, types, &mut HashSet::new()),
+                )
+            })
+            .filter(|(_, set)| !set.is_empty())
+            .collect();
+        if interface_sets.is_empty()
+            || interface_sets.len() * types.len() > GO_INTERFACE_MAX_COMPARISONS
+        {
+            continue;
+        }
+        for type_name in types.keys() {
+            let Some(ids) = type_ids.get(&(package.clone(), type_name.clone())) else {
+                continue;
+            };
+            let methods = go_method_set(type_name, interfaces, types, &mut HashSet::new());
+            for (interface, required) in &interface_sets {
+                if !required.is_subset(&methods) {
+                    continue;
+                }
+                for id in ids {
+                    if let Some(entity) = graph.entities.get_mut(id)
+                        && !entity.deps.inherits.contains(interface)
+                    {
+                        entity.deps.inherits.push((*interface).clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// All methods of `name`: its own plus those of every type it embeds,
+/// looked up among the package's interfaces and types.
+fn go_method_set(
+    name: &str,
+    interfaces: &GoMethodSets,
+    types: &GoMethodSets,
+    visited: &mut HashSet<String>,
+) -> BTreeSet<String> {
+    if !visited.insert(name.to_string()) {
+        return BTreeSet::new();
+    }
+    let Some((own, embeds)) = interfaces.get(name).or_else(|| types.get(name)) else {
+        return BTreeSet::new();
+    };
+    let mut methods = own.clone();
+    for embedded in embeds {
+        methods.extend(go_method_set(embedded, interfaces, types, visited));
+    }
+    methods
 }
 
 /// Push a callee to the correct forward dep vector for the given edge kind.
@@ -233,7 +784,11 @@ fn push_forward_dep(deps: &mut rpg_core::graph::EntityDeps, kind: EdgeKind, call
         EdgeKind::Dispatches => &mut deps.dispatches,
         EdgeKind::DataFlow => &mut deps.data_flows_to,
         // These edge kinds are not call-like and are handled separately
-        EdgeKind::Imports | EdgeKind::Inherits | EdgeKind::Composes | EdgeKind::Contains => return,
+        EdgeKind::Imports
+        | EdgeKind::Inherits
+        | EdgeKind::Composes
+        | EdgeKind::Tests
+        | EdgeKind::Contains => return,
     };
     if !vec.contains(&callee.to_string()) {
         vec.push(callee.to_string());
@@ -241,29 +796,14 @@ fn push_forward_dep(deps: &mut rpg_core::graph::EntityDeps, kind: EdgeKind, call
 }
 
 /// Resolve raw dependency references into proper entity-to-entity edges.
+///
+/// A name defined in several files resolves to the one in a file the
+/// caller's file imports ([`crate::import_paths`]), falling back to a
+/// unique match across the graph when none of the imported files defines it.
 pub fn resolve_dependencies(graph: &mut RPGraph) {
-    // Build a qualified name index: "file_display:name" → id
-    let qualified_index: HashMap<String, String> = graph
-        .entities
-        .iter()
-        .map(|(id, entity)| {
-            let key = format!(
-                "{}:{}",
-                rpg_core::graph::normalize_path(&entity.file),
-                entity.name
-            );
-            (key, id.clone())
-        })
-        .collect();
-
-    // Build a simple name-to-id index for fallback matching
-    let name_to_ids: HashMap<String, Vec<String>> = {
-        let mut map: HashMap<String, Vec<String>> = HashMap::new();
-        for (id, entity) in &graph.entities {
-            map.entry(entity.name.clone()).or_default().push(id.clone());
-        }
-        map
-    };
+    let index = NameIndex::new(graph);
+    let resolver = crate::import_paths::ImportResolver::new(graph);
+    let mut imported_files: HashMap<String, HashSet<String>> = HashMap::new();
 
     let mut edges = Vec::new();
 
@@ -280,22 +820,73 @@ pub fn resolve_dependencies(graph: &mut RPGraph) {
         })
         .collect();
 
+    // (language, kind) → (extracted, resolved)
+    let mut tallies: BTreeMap<(&str, EdgeKind), (usize, usize)> = BTreeMap::new();
     for (source_id, deps, source_file) in &entity_pairs {
+        let language = file_language(source_file);
+        let imported = imported_files
+            .entry(source_file.clone())
+            .or_insert_with(|| {
+                let imports = graph
+                    .file_imports
+                    .get(Path::new(source_file))
+                    .map_or(&[][..], Vec::as_slice);
+                resolver.imported_files(source_file, imports)
+            });
         // Resolve all forward dep kinds generically
         for (edge_kind, dep_names) in deps.forward_deps() {
             for target_name in dep_names {
-                resolve_dep(
-                    source_id,
-                    target_name,
-                    source_file,
-                    edge_kind,
-                    &qualified_index,
-                    &name_to_ids,
-                    &mut edges,
-                );
+                // A file imported or composed by path (Python relative
+                // imports, JS/TS relative and aliased specifiers, Rust modules)
+                let module_target = matches!(edge_kind, EdgeKind::Imports | EdgeKind::Composes)
+                    .then(|| graph.module_entity(Path::new(target_name)))
+                    .flatten();
+                let resolved = if let Some(target) = module_target {
+                    edges.push(DependencyEdge {
+                        source: source_id.clone(),
+                        target: target.id.clone(),
+                        kind: edge_kind,
+                    });
+                    true
+                } else if edge_kind == EdgeKind::Composes
+                    && graph.entities.contains_key(target_name)
+                {
+                    // Already resolved to an entity ID (pytest fixtures)
+                    edges.push(DependencyEdge {
+                        source: source_id.clone(),
+                        target: target_name.clone(),
+                        kind: edge_kind,
+                    });
+                    true
+                } else if edge_kind == EdgeKind::Composes && target_name.contains('/') {
+                    // `export * from './utils'` composes a module, named by path
+                    let edge =
+                        resolve_module_dep(graph, &resolver, source_id, target_name, source_file);
+                    let resolved = edge.is_some();
+                    edges.extend(edge);
+                    resolved
+                } else {
+                    resolve_dep(
+                        source_id,
+                        target_name,
+                        source_file,
+                        imported,
+                        edge_kind,
+                        &index,
+                        &mut edges,
+                    )
+                };
+                if let Some(language) = language {
+                    let tally = tallies.entry((language, edge_kind)).or_default();
+                    tally.0 += 1;
+                    tally.1 += usize::from(resolved);
+                }
             }
         }
     }
+    add_event_edges(graph, &mut edges, &mut tallies);
+    graph.metadata.edge_resolution = resolution_stats(graph, tallies);
+    add_test_edges(graph, &mut edges);
 
     // Clear all reverse dep vectors before repopulating (prevents stale refs on re-resolve)
     for entity in graph.entities.values_mut() {
@@ -315,23 +906,327 @@ pub fn resolve_dependencies(graph: &mut RPGraph) {
     graph.edges = edges;
 }
 
+/// Add a Dispatches edge from each entity emitting an event to the handlers
+/// registered for that event name: those in the emitter's file when there
+/// are any, else all of them. Emits count toward the Dispatches resolution
+/// stats; non-literal event names count as unresolved.
+fn add_event_edges(
+    graph: &RPGraph,
+    edges: &mut Vec<DependencyEdge>,
+    tallies: &mut BTreeMap<(&str, EdgeKind), (usize, usize)>,
+) {
+    let mut handlers: BTreeMap<&str, Vec<&rpg_core::graph::Entity>> = BTreeMap::new();
+    for entity in graph.entities.values() {
+        for event in &entity.deps.handles {
+            handlers.entry(event.as_str()).or_default().push(entity);
+        }
+    }
+
+    let mut seen: HashSet<(String, String)> = edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Dispatches)
+        .map(|e| (e.source.clone(), e.target.clone()))
+        .collect();
+    for emitter in graph.entities.values() {
+        let language = file_language(&rpg_core::graph::normalize_path(&emitter.file));
+        let mut resolved = 0;
+        for event in &emitter.deps.emits {
+            let registered = handlers.get(event.as_str()).map_or(&[][..], Vec::as_slice);
+            let local: Vec<&&rpg_core::graph::Entity> = registered
+                .iter()
+                .filter(|h| h.file == emitter.file)
+                .collect();
+            let targets: Vec<&&rpg_core::graph::Entity> = if local.is_empty() {
+                registered.iter().collect()
+            } else {
+                local
+            };
+            for handler in &targets {
+                if handler.id != emitter.id && seen.insert((emitter.id.clone(), handler.id.clone()))
+                {
+                    edges.push(DependencyEdge {
+                        source: emitter.id.clone(),
+                        target: handler.id.clone(),
+                        kind: EdgeKind::Dispatches,
+                    });
+                }
+            }
+            resolved += usize::from(!targets.is_empty());
+        }
+        let raw = emitter.deps.emits.len() + emitter.deps.unresolved_events.len();
+        if let Some(language) = language
+            && raw > 0
+        {
+            let tally = tallies.entry((language, EdgeKind::Dispatches)).or_default();
+            tally.0 += raw;
+            tally.1 += resolved;
+        }
+    }
+}
+
+/// Add a Tests edge alongside each Invokes edge from a test entity to a
+/// non-test entity, and record the targets in the test's `deps.tests`.
+fn add_test_edges(graph: &mut RPGraph, edges: &mut Vec<DependencyEdge>) {
+    let is_test = |id: &str| graph.entities.get(id).map(|e| e.kind) == Some(EntityKind::Test);
+    let test_edges: Vec<DependencyEdge> = edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Invokes)
+        .filter(|e| is_test(&e.source) && !is_test(&e.target))
+        .map(|e| DependencyEdge {
+            source: e.source.clone(),
+            target: e.target.clone(),
+            kind: EdgeKind::Tests,
+        })
+        .collect();
+
+    for entity in graph.entities.values_mut() {
+        entity.deps.tests.clear();
+    }
+    for edge in &test_edges {
+        if let Some(test) = graph.entities.get_mut(&edge.source)
+            && !test.deps.tests.contains(&edge.target)
+        {
+            test.deps.tests.push(edge.target.clone());
+        }
+    }
+    edges.extend(test_edges);
+}
+
+/// Find circular imports between files in the resolved graph and record
+/// their count in the metadata, so stats history can track it.
+pub fn record_import_cycles(
+    graph: &mut RPGraph,
+    ignore_same_directory: bool,
+) -> Vec<rpg_core::imports::ImportCycle> {
+    let cycles = rpg_core::imports::import_cycles(graph, ignore_same_directory);
+    graph.metadata.import_cycles = cycles.len();
+    cycles
+}
+
+/// Edge kinds every language is expected to produce once it has enough code.
+const EXPECTED_EDGE_KINDS: &[EdgeKind] = &[EdgeKind::Invokes];
+
+/// Languages with fewer non-module entities than this are too small to warn
+/// about: a handful of entities can legitimately have no resolved calls.
+pub const RESOLUTION_WARNING_MIN_ENTITIES: usize = 20;
+
+/// Language name for a graph file path, if its extension is a known language.
+fn file_language(file: &str) -> Option<&'static str> {
+    Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Language::from_extension)
+        .map(|l| l.name())
+}
+
+/// One row per language and edge kind that extracted anything, plus a row
+/// for each expected kind so a language that extracted none still shows up.
+fn resolution_stats(
+    graph: &RPGraph,
+    tallies: BTreeMap<(&str, EdgeKind), (usize, usize)>,
+) -> Vec<EdgeResolutionStats> {
+    let mut entities: BTreeMap<&str, usize> = BTreeMap::new();
+    for entity in graph.entities.values() {
+        if entity.kind == EntityKind::Module {
+            continue;
+        }
+        if let Some(language) = file_language(&rpg_core::graph::normalize_path(&entity.file)) {
+            *entities.entry(language).or_default() += 1;
+        }
+    }
+
+    let mut rows: BTreeMap<(&str, EdgeKind), (usize, usize)> = tallies;
+    for language in entities.keys() {
+        for kind in EXPECTED_EDGE_KINDS {
+            rows.entry((language, *kind)).or_default();
+        }
+    }
+    rows.into_iter()
+        .map(|((language, kind), (raw, resolved))| EdgeResolutionStats {
+            language: language.to_string(),
+            kind,
+            entities: entities.get(language).copied().unwrap_or(0),
+            raw,
+            resolved,
+        })
+        .collect()
+}
+
+/// Warnings for languages whose dependency resolution looks broken: at least
+/// [`RESOLUTION_WARNING_MIN_ENTITIES`] entities, yet fewer than one resolved
+/// edge of an expected kind per 100 entities.
+pub fn resolution_warnings(stats: &[EdgeResolutionStats]) -> Vec<String> {
+    stats
+        .iter()
+        .filter(|s| EXPECTED_EDGE_KINDS.contains(&s.kind))
+        .filter(|s| s.entities >= RESOLUTION_WARNING_MIN_ENTITIES && s.resolved * 100 < s.entities)
+        .map(|s| {
+            let cause = if s.raw == 0 {
+                "none extracted — likely an extraction bug".to_string()
+            } else {
+                format!(
+                    "{} of {} extracted names unresolved — likely a resolution bug",
+                    s.unresolved(),
+                    s.raw
+                )
+            };
+            format!(
+                "{} {:?} edges resolved for {} ({} entities; {})",
+                s.resolved, s.kind, s.language, s.entities, cause
+            )
+        })
+        .collect()
+}
+
+/// Resolution statistics as an aligned table, one row per language and edge
+/// kind. Empty when there are no statistics.
+pub fn format_resolution_table(stats: &[EdgeResolutionStats]) -> String {
+    if stats.is_empty() {
+        return String::new();
+    }
+    let mut output = format!(
+        "{:<12} {:<12} {:>8} {:>9} {:>8} {:>10}\n",
+        "language", "kind", "entities", "extracted", "resolved", "unresolved"
+    );
+    for s in stats {
+        output.push_str(&format!(
+            "{:<12} {:<12} {:>8} {:>9} {:>8} {:>10}\n",
+            s.language,
+            format!("{:?}", s.kind).to_lowercase(),
+            s.entities,
+            s.raw,
+            s.resolved,
+            s.unresolved(),
+        ));
+    }
+    output
+}
+
+/// Lookup tables for [`resolve_dep`], built once per resolution pass.
+struct NameIndex {
+    /// "file_display:name" → id
+    qualified: HashMap<String, String>,
+    /// name → (id, file), for fallback matching
+    by_name: HashMap<String, Vec<(String, String)>>,
+    /// (parent class, member name) → (id, file)
+    members: HashMap<(String, String), Vec<(String, String)>>,
+}
+
+impl NameIndex {
+    fn new(graph: &RPGraph) -> Self {
+        let mut qualified = HashMap::new();
+        let mut by_name: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let mut members: HashMap<(String, String), Vec<(String, String)>> = HashMap::new();
+        for (id, entity) in &graph.entities {
+            let file = rpg_core::graph::normalize_path(&entity.file);
+            qualified.insert(format!("{}:{}", file, entity.name), id.clone());
+            by_name
+                .entry(entity.name.clone())
+                .or_default()
+                .push((id.clone(), file.clone()));
+            if let Some(class) = &entity.parent_class {
+                members
+                    .entry((class.clone(), entity.name.clone()))
+                    .or_default()
+                    .push((id.clone(), file));
+            }
+        }
+        Self {
+            qualified,
+            by_name,
+            members,
+        }
+    }
+
+    /// Resolve a namespace-qualified member reference such as
+    /// `App\Services\Payment::charge`. When several classes share the simple
+    /// name, the one whose file path ends with the most namespace segments
+    /// wins (PSR-4 style: `App\Services\Payment` → `app/Services/Payment.php`).
+    fn resolve_member(&self, source_id: &str, class: &str, member: &str) -> Option<&str> {
+        let segments: Vec<&str> = class
+            .split(['\\', '.'])
+            .flat_map(|s| s.split("::"))
+            .filter(|s| !s.is_empty())
+            .collect();
+        let simple = segments.last()?;
+        let candidates: Vec<&(String, String)> = self
+            .members
+            .get(&(simple.to_string(), member.to_string()))?
+            .iter()
+            .filter(|(id, _)| id != source_id)
+            .collect();
+        if let [(id, _)] = candidates.as_slice() {
+            return Some(id);
+        }
+
+        let score = |file: &str| {
+            let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+            stem.rsplit('/')
+                .zip(segments.iter().rev())
+                .take_while(|(dir, seg)| dir.eq_ignore_ascii_case(seg))
+                .count()
+        };
+        let best = candidates.iter().map(|(_, file)| score(file)).max()?;
+        let mut winners = candidates.iter().filter(|(_, file)| score(file) == best);
+        match (winners.next(), winners.next()) {
+            (Some((id, _)), None) if best > 0 => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// Resolve a re-exported module specifier to a Composes edge onto the
+/// Module entity of the file it names.
+fn resolve_module_dep(
+    graph: &RPGraph,
+    resolver: &crate::import_paths::ImportResolver,
+    source_id: &str,
+    module: &str,
+    source_file: &str,
+) -> Option<DependencyEdge> {
+    let file = resolver.js_module_file(source_file, module)?;
+    let target = graph.module_entity(Path::new(&file))?;
+    Some(DependencyEdge {
+        source: source_id.to_string(),
+        target: target.id.clone(),
+        kind: EdgeKind::Composes,
+    })
+}
+
 /// Resolve a single dependency using qualified lookup first, then import-aware fallback.
+/// Returns whether an edge was created.
 ///
-/// The fallback only creates a cross-file edge if the target name is unambiguous
-/// (exactly one entity with that name across the entire graph). This avoids false
-/// edges for common names like `new`, `parse`, `build`, `run`.
+/// `Class::member` targets (namespaced PHP static calls) resolve only through
+/// the member index, never by bare name.
+///
+/// The fallback only considers entities in files the source file imports
+/// (`imported_files`) when any of them matches, and only creates a cross-file
+/// edge if the target name is unambiguous among the candidates. This avoids
+/// false edges for common names like `new`, `parse`, `build`, `run`.
 fn resolve_dep(
     source_id: &str,
     target_name: &str,
     source_file: &str,
+    imported_files: &HashSet<String>,
     kind: EdgeKind,
-    qualified_index: &HashMap<String, String>,
-    name_to_ids: &HashMap<String, Vec<String>>,
+    index: &NameIndex,
     edges: &mut Vec<DependencyEdge>,
-) {
+) -> bool {
+    if let Some((class, member)) = target_name.rsplit_once("::") {
+        let Some(target_id) = index.resolve_member(source_id, class, member) else {
+            return false;
+        };
+        edges.push(DependencyEdge {
+            source: source_id.to_string(),
+            target: target_id.to_string(),
+            kind,
+        });
+        return true;
+    }
+
     // Try qualified lookup first: same file
     let qualified_key = format!("{}:{}", source_file, target_name);
-    if let Some(target_id) = qualified_index.get(&qualified_key)
+    if let Some(target_id) = index.qualified.get(&qualified_key)
         && target_id != source_id
     {
         edges.push(DependencyEdge {
@@ -339,23 +1234,37 @@ fn resolve_dep(
             target: target_id.clone(),
             kind,
         });
-        return;
+        return true;
     }
 
-    // Fallback: name-based lookup — only if unambiguous (exactly one match outside this file)
-    if let Some(target_ids) = name_to_ids.get(target_name) {
-        let cross_file_targets: Vec<&String> = target_ids
+    // Fallback: name-based lookup outside this file, narrowed to imported
+    // files when one of them defines the name — only if unambiguous
+    if let Some(targets) = index.by_name.get(target_name) {
+        let cross_file_targets: Vec<&String> = targets
             .iter()
-            .filter(|id| *id != source_id && !id.starts_with(&format!("{}:", source_file)))
+            .filter(|(id, file)| id != source_id && file != source_file)
+            .map(|(id, _)| id)
             .collect();
+        let imported_targets: Vec<&String> = targets
+            .iter()
+            .filter(|(id, file)| id != source_id && imported_files.contains(file))
+            .map(|(id, _)| id)
+            .collect();
+        let candidates = if imported_targets.is_empty() {
+            cross_file_targets
+        } else {
+            imported_targets
+        };
 
         // Only create edge if there's exactly one candidate — refuse to guess among multiples
-        if cross_file_targets.len() == 1 {
+        if let [target_id] = candidates.as_slice() {
             edges.push(DependencyEdge {
                 source: source_id.to_string(),
-                target: cross_file_targets[0].clone(),
+                target: (*target_id).clone(),
                 kind,
             });
+            return true;
         }
     }
+    false
 }
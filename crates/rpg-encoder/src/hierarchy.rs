@@ -21,7 +21,10 @@ pub fn apply_hierarchy(graph: &mut RPGraph, assignments: &HashMap<String, String
         map
     };
 
-    for (key, path) in assignments {
+    // Sorted, so entities enter their hierarchy nodes in the same order every time
+    let mut sorted: Vec<(&String, &String)> = assignments.iter().collect();
+    sorted.sort();
+    for (key, path) in sorted {
         // 1. Try direct entity ID lookup (preferred — unambiguous)
         let entity_id = if graph.entities.contains_key(key) {
             Some(key.clone())
@@ -70,6 +73,24 @@ pub fn apply_hierarchy(graph: &mut RPGraph, assignments: &HashMap<String, String
     }
 }
 
+/// Prompt section listing the graph's area charters, or empty when none are
+/// set. Charters are human-written purpose statements, so the model is told
+/// to keep those paths and use them to place matching files.
+pub fn format_charter_guidance(graph: &RPGraph) -> String {
+    let charters = graph.hierarchy_charters();
+    if charters.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from(
+        "\n\n## Area Charters\n\nThese areas have maintainer-written charters. \
+         Keep their paths unchanged and assign files that fit a charter to that area.\n\n",
+    );
+    for (path, charter) in &charters {
+        out.push_str(&format!("- {}: {}\n", path, charter));
+    }
+    out
+}
+
 /// File cluster for sharded hierarchy construction.
 #[derive(Debug, Clone)]
 pub struct FileCluster {
@@ -94,11 +115,13 @@ pub fn cluster_files_for_hierarchy(
     graph: &RPGraph,
     target_cluster_size: usize,
 ) -> Vec<FileCluster> {
-    // Collect Module entities (file-level), sorted for deterministic clustering
+    // Collect non-fixture Module entities (file-level), sorted for deterministic clustering
     let mut modules: Vec<(String, Vec<String>)> = graph
         .entities
         .values()
-        .filter(|e| e.kind == EntityKind::Module && !e.semantic_features.is_empty())
+        .filter(|e| {
+            e.kind == EntityKind::Module && !e.semantic_features.is_empty() && !e.is_fixture()
+        })
         .map(|e| (normalize_path(&e.file), e.semantic_features.clone()))
         .collect();
     modules.sort_by(|a, b| a.0.cmp(&b.0));
@@ -184,6 +207,7 @@ fn balance_clusters(clusters: Vec<FileCluster>, target_size: usize) -> Vec<FileC
 mod tests {
     use super::*;
     use rpg_core::graph::{Entity, EntityDeps};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_module(file: &str, features: Vec<&str>) -> Entity {
@@ -197,9 +221,22 @@ mod tests {
             parent_class: None,
             semantic_features: features.iter().map(|s| (*s).to_string()).collect(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
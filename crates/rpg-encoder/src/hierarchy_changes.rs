@@ -0,0 +1,258 @@
+//! What moved between two versions of a graph's hierarchy.
+//!
+//! Both versions are reduced to file → area (the top-level hierarchy node).
+//! Files assigned in both are counted per (old area, new area) transition;
+//! an old area whose members mostly reappear under one new, previously
+//! unused name looks like a rename.
+
+use rpg_core::graph::{EntityKind, RPGraph, normalize_path};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Minimum member overlap (Jaccard) for an area pair to look like a rename.
+const RENAME_OVERLAP: f64 = 0.5;
+
+/// File → area assignments of one hierarchy version.
+#[derive(Debug, Clone, Default)]
+pub struct FileAreas {
+    /// Whether the hierarchy was semantic (LLM-built) or structural.
+    pub semantic: bool,
+    /// Normalized file path → area; files outside the hierarchy are absent.
+    pub areas: BTreeMap<String, String>,
+}
+
+/// Snapshot the file → area assignments of `graph`'s hierarchy. A file's
+/// Module entity decides; files without one in the hierarchy take the area
+/// of any of their entities.
+pub fn file_areas(graph: &RPGraph) -> FileAreas {
+    let mut areas = BTreeMap::new();
+    for (area, node) in &graph.hierarchy {
+        for id in node.all_entity_ids() {
+            let Some(entity) = graph.entities.get(&id) else {
+                continue;
+            };
+            let file = normalize_path(&entity.file);
+            if entity.kind == EntityKind::Module || !areas.contains_key(&file) {
+                areas.insert(file, area.clone());
+            }
+        }
+    }
+    FileAreas {
+        semantic: graph.metadata.semantic_hierarchy,
+        areas,
+    }
+}
+
+/// An old area whose files largely moved, together, to a new area name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AreaRename {
+    pub old: String,
+    pub new: String,
+    /// Files in both the old and the new area.
+    pub shared: usize,
+    /// Files in the old area.
+    pub old_files: usize,
+    /// Jaccard overlap of the two member sets.
+    pub overlap: f64,
+}
+
+/// A file assigned to different areas before and after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMove {
+    pub file: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Difference between two hierarchy versions.
+#[derive(Debug, Clone, Default)]
+pub struct HierarchyChanges {
+    /// Whether the previous hierarchy was semantic.
+    pub previous_semantic: bool,
+    /// (old area, new area) → number of files, for files assigned in both.
+    pub transitions: BTreeMap<(String, String), usize>,
+    /// Renamed-looking areas, by decreasing overlap.
+    pub renamed: Vec<AreaRename>,
+    /// Files whose area changed.
+    pub moved: Vec<FileMove>,
+    /// Files with an area now but none before.
+    pub newly_assigned: Vec<String>,
+    /// Files with an area before but none now.
+    pub unassigned: Vec<String>,
+}
+
+impl HierarchyChanges {
+    /// Files assigned in both versions.
+    pub fn compared(&self) -> usize {
+        self.transitions.values().sum()
+    }
+}
+
+/// Compare two hierarchy versions.
+pub fn compare(before: &FileAreas, after: &FileAreas) -> HierarchyChanges {
+    let mut changes = HierarchyChanges {
+        previous_semantic: before.semantic,
+        ..HierarchyChanges::default()
+    };
+    for (file, new) in &after.areas {
+        match before.areas.get(file) {
+            Some(old) => {
+                *changes
+                    .transitions
+                    .entry((old.clone(), new.clone()))
+                    .or_default() += 1;
+                if old != new {
+                    changes.moved.push(FileMove {
+                        file: file.clone(),
+                        old: old.clone(),
+                        new: new.clone(),
+                    });
+                }
+            }
+            None => changes.newly_assigned.push(file.clone()),
+        }
+    }
+    changes.unassigned = before
+        .areas
+        .keys()
+        .filter(|file| !after.areas.contains_key(*file))
+        .cloned()
+        .collect();
+
+    let members = |areas: &BTreeMap<String, String>| {
+        let mut by_area: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (file, area) in areas {
+            by_area
+                .entry(area.clone())
+                .or_default()
+                .insert(file.clone());
+        }
+        by_area
+    };
+    let old_members = members(&before.areas);
+    let new_members = members(&after.areas);
+    for (old, old_files) in &old_members {
+        if new_members.contains_key(old) {
+            continue;
+        }
+        let best = new_members
+            .iter()
+            .filter(|(new, _)| !old_members.contains_key(*new))
+            .map(|(new, new_files)| {
+                let shared = old_files.intersection(new_files).count();
+                let union = old_files.union(new_files).count();
+                (new, shared, shared as f64 / union as f64)
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((new, shared, overlap)) = best
+            && overlap >= RENAME_OVERLAP
+        {
+            changes.renamed.push(AreaRename {
+                old: old.clone(),
+                new: new.clone(),
+                shared,
+                old_files: old_files.len(),
+                overlap,
+            });
+        }
+    }
+    changes
+        .renamed
+        .sort_by(|a, b| b.overlap.total_cmp(&a.overlap));
+    changes
+}
+
+/// Transitions listed in the digest.
+const DIGEST_TRANSITIONS: usize = 5;
+
+/// Short summary for the `submit_hierarchy` response.
+pub fn format_digest(changes: &HierarchyChanges) -> String {
+    let mut out = format!(
+        "\nChanges vs previous {} hierarchy: {} of {} files moved, {} newly assigned, {} unassigned\n",
+        if changes.previous_semantic {
+            "semantic"
+        } else {
+            "structural"
+        },
+        changes.moved.len(),
+        changes.compared(),
+        changes.newly_assigned.len(),
+        changes.unassigned.len()
+    );
+    for rename in &changes.renamed {
+        out.push_str(&format!(
+            "  renamed? {} -> {} ({:.0}% overlap)\n",
+            rename.old,
+            rename.new,
+            rename.overlap * 100.0
+        ));
+    }
+    let mut moves: Vec<(&(String, String), &usize)> = changes
+        .transitions
+        .iter()
+        .filter(|((old, new), _)| old != new)
+        .collect();
+    moves.sort_by(|a, b| b.1.cmp(a.1));
+    for ((old, new), files) in moves.into_iter().take(DIGEST_TRANSITIONS) {
+        out.push_str(&format!("  {} -> {}: {} files\n", old, new, files));
+    }
+    out.push_str("Full report: hierarchy_changes\n");
+    out
+}
+
+/// Full report for the `hierarchy_changes` tool.
+pub fn format_report(changes: &HierarchyChanges) -> String {
+    let mut out = format!(
+        "## Hierarchy changes\nprevious: {} | files compared: {} | moved: {} | newly assigned: {} | unassigned: {}\n",
+        if changes.previous_semantic {
+            "semantic"
+        } else {
+            "structural"
+        },
+        changes.compared(),
+        changes.moved.len(),
+        changes.newly_assigned.len(),
+        changes.unassigned.len()
+    );
+
+    out.push_str("\n### Transitions (old area -> new area: files)\n");
+    for ((old, new), files) in &changes.transitions {
+        out.push_str(&format!("{} -> {}: {}\n", old, new, files));
+    }
+
+    if !changes.renamed.is_empty() {
+        out.push_str("\n### Renamed-looking areas\n");
+        for rename in &changes.renamed {
+            out.push_str(&format!(
+                "- {} -> {} ({}/{} files, {:.0}% overlap)\n",
+                rename.old,
+                rename.new,
+                rename.shared,
+                rename.old_files,
+                rename.overlap * 100.0
+            ));
+        }
+    }
+
+    let sections = [
+        (
+            "Moved files",
+            changes
+                .moved
+                .iter()
+                .map(|m| format!("{}: {} -> {}", m.file, m.old, m.new))
+                .collect::<Vec<_>>(),
+        ),
+        ("Newly assigned", changes.newly_assigned.clone()),
+        ("Unassigned", changes.unassigned.clone()),
+    ];
+    for (title, lines) in sections {
+        if lines.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n", title));
+        for line in lines {
+            out.push_str(&format!("- {}\n", line));
+        }
+    }
+    out
+}
@@ -0,0 +1,407 @@
+//! Style checks for submitted hierarchy paths (`[encoding.hierarchy_style]`).
+//!
+//! A segment is split into words on spaces, `_`, `-`, and camelCase
+//! boundaries, so `dataAccess`, `Data_Access`, and `data access` all read as
+//! `data` + `access`. Casing is judged against the segment rebuilt from those
+//! words — PascalCase for areas, lowercase words joined by single spaces for
+//! categories — which is also what `normalize` mode rewrites it to. Word
+//! counts, banned names, and missing verbs can't be fixed mechanically and
+//! are reported in either mode.
+
+use crate::semantic_lifting::is_verb;
+use rpg_core::config::{HierarchyStyleConfig, HierarchyStyleMode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Area,
+    Category,
+    Subcategory,
+}
+
+impl Level {
+    fn of(index: usize) -> Self {
+        match index {
+            0 => Self::Area,
+            1 => Self::Category,
+            _ => Self::Subcategory,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Area => "area",
+            Self::Category => "category",
+            Self::Subcategory => "subcategory",
+        }
+    }
+}
+
+/// `path` (`Area/category/subcategory`) brought in line with `style`: as
+/// given in `reject` mode, rewritten in `normalize` mode, untouched when off.
+/// Every violation left is returned instead.
+pub fn apply_style(path: &str, style: &HierarchyStyleConfig) -> Result<String, Vec<String>> {
+    if style.mode == HierarchyStyleMode::Off {
+        return Ok(path.to_string());
+    }
+    let mut violations = Vec::new();
+    let segments: Vec<String> = path
+        .split('/')
+        .enumerate()
+        .map(|(i, segment)| check_segment(segment, Level::of(i), style, &mut violations))
+        .collect();
+    if violations.is_empty() {
+        Ok(segments.join("/"))
+    } else {
+        Err(violations)
+    }
+}
+
+/// An area name alone, as registered by hierarchy domain discovery.
+pub fn apply_area_style(area: &str, style: &HierarchyStyleConfig) -> Result<String, Vec<String>> {
+    if style.mode == HierarchyStyleMode::Off {
+        return Ok(area.to_string());
+    }
+    let mut violations = Vec::new();
+    let area = check_segment(area, Level::Area, style, &mut violations);
+    if violations.is_empty() {
+        Ok(area)
+    } else {
+        Err(violations)
+    }
+}
+
+/// The segment as the style would write it; violations are pushed with the
+/// segment as given.
+fn check_segment(
+    segment: &str,
+    level: Level,
+    style: &HierarchyStyleConfig,
+    violations: &mut Vec<String>,
+) -> String {
+    let words = split_words(segment);
+    let styled = match level {
+        Level::Area if style.pascal_case_areas => pascal_case(&words),
+        Level::Category | Level::Subcategory if style.lowercase_categories => {
+            lowercase_words(&words)
+        }
+        _ => segment.to_string(),
+    };
+    if styled != segment && style.mode == HierarchyStyleMode::Reject {
+        let casing = if level == Level::Area {
+            "PascalCase"
+        } else {
+            "lowercase words"
+        };
+        violations.push(format!(
+            "{} '{}' should be {} ('{}')",
+            level.name(),
+            segment,
+            casing,
+            styled
+        ));
+    }
+
+    let max_words = if level == Level::Area {
+        style.max_area_words
+    } else {
+        style.max_category_words
+    };
+    if words.len() > max_words {
+        violations.push(format!(
+            "{} '{}' has {} words (max {})",
+            level.name(),
+            segment,
+            words.len(),
+            max_words
+        ));
+    }
+    let key = lowercase_words(&words);
+    if style
+        .banned_names
+        .iter()
+        .any(|banned| lowercase_words(&split_words(banned)) == key)
+    {
+        violations.push(format!(
+            "{} '{}' is too generic; name what the code does",
+            level.name(),
+            segment
+        ));
+    } else if level != Level::Area
+        && style.verb_phrases
+        && !words.first().is_some_and(|w| is_verb(&w.to_lowercase()))
+    {
+        violations.push(format!(
+            "{} '{}' should open with a verb (e.g. 'manage sessions')",
+            level.name(),
+            segment
+        ));
+    }
+    if style.mode == HierarchyStyleMode::Normalize {
+        styled
+    } else {
+        segment.to_string()
+    }
+}
+
+/// Words of a segment: split on whitespace, `_`, `-`, and camelCase
+/// boundaries (`HTTPClient` → `HTTP`, `Client`).
+fn split_words(segment: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for token in segment.split(|c: char| c.is_whitespace() || c == '_' || c == '-') {
+        let chars: Vec<char> = token.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if cur.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next_lower))
+            {
+                words.push(chars[start..i].iter().collect());
+                start = i;
+            }
+        }
+        if start < chars.len() {
+            words.push(chars[start..].iter().collect());
+        }
+    }
+    words
+}
+
+/// Capitalized words run together; all-caps words are kept as acronyms.
+fn pascal_case(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            if word.chars().count() > 1 && !word.chars().any(char::is_lowercase) {
+                return word.clone();
+            }
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                })
+                .into_iter()
+                .flatten()
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn lowercase_words(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Prompt section stating the active style, or empty when checks are off.
+pub fn format_style_guidance(style: &HierarchyStyleConfig) -> String {
+    let enforcement = match style.mode {
+        HierarchyStyleMode::Off => return String::new(),
+        HierarchyStyleMode::Reject => "paths that break it are rejected.",
+        HierarchyStyleMode::Normalize => {
+            "casing and separators are fixed automatically and reported; \
+             anything else that breaks it is rejected."
+        }
+    };
+    let mut out = format!(
+        "\n\n## Hierarchy Style\n\nThis project enforces a naming style for hierarchy paths; {}\n\n",
+        enforcement
+    );
+    if style.pascal_case_areas {
+        out.push_str(&format!(
+            "- Areas are PascalCase (`DataAccess`), at most {} words.\n",
+            style.max_area_words
+        ));
+    } else {
+        out.push_str(&format!(
+            "- Areas have at most {} words.\n",
+            style.max_area_words
+        ));
+    }
+    let mut category = String::from("- Categories and subcategories");
+    if style.lowercase_categories {
+        category.push_str(" are lowercase words");
+        if style.verb_phrases {
+            category.push_str(" opening with a verb");
+        }
+        category.push_str(" (`manage sessions`),");
+    } else if style.verb_phrases {
+        category.push_str(" open with a verb,");
+    }
+    out.push_str(&format!(
+        "{} at most {} words.\n",
+        category, style.max_category_words
+    ));
+    if !style.banned_names.is_empty() {
+        out.push_str(&format!(
+            "- Never use generic names: {}.\n",
+            style.banned_names.join(", ")
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(mode: HierarchyStyleMode) -> HierarchyStyleConfig {
+        HierarchyStyleConfig {
+            mode,
+            ..HierarchyStyleConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_split_words() {
+        let cases: &[(&str, &[&str])] = &[
+            ("dataAccess", &["data", "Access"]),
+            ("Data_Access", &["Data", "Access"]),
+            ("data access", &["data", "access"]),
+            ("data-access", &["data", "access"]),
+            ("  manage   sessions ", &["manage", "sessions"]),
+            ("HTTPClient", &["HTTP", "Client"]),
+            ("parseV2Config", &["parse", "V2", "Config"]),
+            ("", &[]),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(split_words(input), *expected, "input {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_normalize_paths() {
+        let normalize = style(HierarchyStyleMode::Normalize);
+        let cases = [
+            (
+                "dataAccess/run queries/build sql",
+                "DataAccess/run queries/build sql",
+            ),
+            (
+                "Data_Access/Run_Queries/build-sql",
+                "DataAccess/run queries/build sql",
+            ),
+            (
+                "data access/run  queries/build sql",
+                "DataAccess/run queries/build sql",
+            ),
+            (
+                "HTTPClient/send requests/retry calls",
+                "HTTPClient/send requests/retry calls",
+            ),
+            (
+                "DataAccess/Manage Sessions/validateToken",
+                "DataAccess/manage sessions/validate token",
+            ),
+            (
+                "DataAccess/run queries/build sql",
+                "DataAccess/run queries/build sql",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                apply_style(input, &normalize).as_deref(),
+                Ok(expected),
+                "input {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_unfixable_violations() {
+        let normalize = style(HierarchyStyleMode::Normalize);
+        let cases = [
+            (
+                "Utils/run queries/build sql",
+                "area 'Utils' is too generic; name what the code does",
+            ),
+            (
+                "DataAccess/misc/build sql",
+                "category 'misc' is too generic; name what the code does",
+            ),
+            (
+                "VeryLongAreaName/run queries/build sql",
+                "area 'VeryLongAreaName' has 4 words (max 3)",
+            ),
+            (
+                "DataAccess/run all the slow queries now/build sql",
+                "category 'run all the slow queries now' has 6 words (max 5)",
+            ),
+            (
+                "DataAccess/sessions/build sql",
+                "category 'sessions' should open with a verb (e.g. 'manage sessions')",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                apply_style(input, &normalize),
+                Err(vec![expected.to_string()]),
+                "input {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_mode_reports_casing_with_suggestion() {
+        let reject = style(HierarchyStyleMode::Reject);
+        assert_eq!(
+            apply_style("dataAccess/Run Queries/build sql", &reject),
+            Err(vec![
+                "area 'dataAccess' should be PascalCase ('DataAccess')".to_string(),
+                "category 'Run Queries' should be lowercase words ('run queries')".to_string(),
+            ])
+        );
+        assert_eq!(
+            apply_style("DataAccess/run queries/build sql", &reject).as_deref(),
+            Ok("DataAccess/run queries/build sql")
+        );
+    }
+
+    #[test]
+    fn test_off_mode_and_disabled_rules() {
+        let off = style(HierarchyStyleMode::Off);
+        assert_eq!(
+            apply_style("core/misc/x", &off).as_deref(),
+            Ok("core/misc/x")
+        );
+        assert_eq!(format_style_guidance(&off), "");
+
+        let relaxed = HierarchyStyleConfig {
+            mode: HierarchyStyleMode::Normalize,
+            pascal_case_areas: false,
+            verb_phrases: false,
+            banned_names: Vec::new(),
+            ..HierarchyStyleConfig::default()
+        };
+        assert_eq!(
+            apply_style("core/Misc/Zeta", &relaxed).as_deref(),
+            Ok("core/misc/zeta")
+        );
+        assert_eq!(
+            apply_area_style("data_access", &relaxed).as_deref(),
+            Ok("data_access")
+        );
+    }
+
+    #[test]
+    fn test_guidance_states_active_rules() {
+        let guidance = format_style_guidance(&style(HierarchyStyleMode::Normalize));
+        assert!(guidance.contains("fixed automatically"), "{}", guidance);
+        assert!(guidance.contains("PascalCase (`DataAccess`), at most 3 words"));
+        assert!(
+            guidance.contains(
+                "lowercase words opening with a verb (`manage sessions`), at most 5 words"
+            )
+        );
+        assert!(guidance.contains("Never use generic names: utils, util, misc"));
+    }
+}
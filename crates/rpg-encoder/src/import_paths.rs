@@ -0,0 +1,498 @@
+//! Import module paths → repository files, per language.
+//!
+//! [`crate::grounding::resolve_dependencies`] uses the files a caller's file
+//! imports to choose among same-named callees. Resolution is path-based and
+//! best-effort: Python dotted and relative modules, Rust `crate::`,
+//! `super::`, and `self::` paths (and workspace crates by directory name),
+//! JS/TS relative specifiers and path aliases (tsconfig `paths` and
+//! `baseUrl`, else the `@/` and `~/` conventions), Go package directories, and
+//! dotted or `\`-separated namespaces elsewhere (Java, Kotlin, C#, PHP),
+//! matched as case-insensitive path suffixes.
+
+use crate::tsconfig::PathAliases;
+use rpg_core::graph::{FileImport, RPGraph, normalize_path};
+use rpg_parser::languages::Language;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// File stems that stand for their directory (`pkg/__init__.py` is `pkg`).
+const PACKAGE_STEMS: &[&str] = &["__init__", "mod", "index"];
+
+/// Extensions tried for extensionless JS/TS specifiers, in order.
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Path aliases conventionally mapped to `src/`.
+const SRC_ALIASES: &[&str] = &["@/", "~/"];
+
+/// Languages whose files see their own package (directory) without imports.
+const DIRECTORY_PACKAGES: &[Language] = &[
+    Language::GO,
+    Language::JAVA,
+    Language::KOTLIN,
+    Language::SCALA,
+];
+
+/// Lookup tables over the graph's files.
+pub struct ImportResolver {
+    files: HashSet<String>,
+    /// Lowercase extensionless path suffixes (`pkg/models`, `models`) → files.
+    by_suffix: HashMap<String, Vec<String>>,
+    /// Lowercase directory path suffixes → files directly in that directory.
+    by_dir: HashMap<String, Vec<String>>,
+    aliases: PathAliases,
+}
+
+impl ImportResolver {
+    pub fn new(graph: &RPGraph) -> Self {
+        let mut resolver = Self {
+            files: HashSet::new(),
+            by_suffix: HashMap::new(),
+            by_dir: HashMap::new(),
+            aliases: PathAliases::default(),
+        };
+        for file in graph.file_index.keys() {
+            resolver.add_file(normalize_path(file));
+        }
+        resolver
+    }
+
+    /// Resolve JS/TS specifiers through the project's tsconfig aliases.
+    #[must_use]
+    pub fn with_path_aliases(mut self, aliases: PathAliases) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    fn add_file(&mut self, file: String) {
+        let lower = strip_extension(&file).to_lowercase();
+        let segments: Vec<&str> = lower.split('/').collect();
+        let (stem, dir) = segments.split_last().expect("split yields one segment");
+        for i in 0..segments.len() {
+            push_unique(&mut self.by_suffix, segments[i..].join("/"), &file);
+        }
+        if PACKAGE_STEMS.contains(stem) {
+            for i in 0..dir.len() {
+                push_unique(&mut self.by_suffix, dir[i..].join("/"), &file);
+            }
+        }
+        for i in 0..dir.len() {
+            push_unique(&mut self.by_dir, dir[i..].join("/"), &file);
+        }
+        if dir.is_empty() {
+            push_unique(&mut self.by_dir, String::new(), &file);
+        }
+        self.files.insert(file);
+    }
+
+    /// Files `importer` (a normalized graph path) imports, including its own
+    /// package for languages with directory-scoped packages.
+    pub fn imported_files(&self, importer: &str, imports: &[FileImport]) -> HashSet<String> {
+        let Some(language) = file_language(importer) else {
+            return HashSet::new();
+        };
+        let mut out: HashSet<String> = imports
+            .iter()
+            .flat_map(|import| self.resolve(importer, language, import))
+            .filter(|file| file != importer && same_family(file, language))
+            .collect();
+        if DIRECTORY_PACKAGES.contains(&language) {
+            let dir = parent_dir(importer).to_lowercase();
+            out.extend(
+                self.by_dir
+                    .get(&dir)
+                    .into_iter()
+                    .flatten()
+                    .filter(|file| *file != importer && same_family(file, language))
+                    .cloned(),
+            );
+        }
+        out
+    }
+
+    /// The file a JS/TS module specifier written in `importer` names
+    /// (`./utils` → `src/utils/index.ts`), for barrel re-exports.
+    pub fn js_module_file(&self, importer: &str, module: &str) -> Option<String> {
+        self.resolve_js(importer, module)
+            .into_iter()
+            .find(|file| file != importer)
+    }
+
+    /// The file a non-relative JS/TS specifier written in `importer` maps to
+    /// through path aliases (`@app/services/auth` → `src/services/auth.ts`).
+    pub fn js_aliased_file(&self, importer: &str, module: &str) -> Option<String> {
+        if module.starts_with('.') {
+            return None;
+        }
+        self.js_module_file(importer, module)
+    }
+
+    /// The files an import written in `importer` names as a path rather
+    /// than by package: Python relative imports (`from ..utils.helpers
+    /// import slugify` → `utils/helpers.py`, `from . import models` →
+    /// `models.py` or `models/__init__.py`), JS/TS relative or aliased
+    /// specifiers, and Rust `crate::`, `super::`, `self::`, and child module
+    /// paths (`use crate::utils::*` → `src/utils.rs` or `src/utils/mod.rs`).
+    /// Each Python dot past the first walks up a directory; packages without
+    /// `__init__.py` (namespace packages) resolve like any other directory.
+    pub fn module_files(&self, importer: &str, import: &FileImport) -> Vec<String> {
+        let Some(language) = file_language(importer) else {
+            return Vec::new();
+        };
+        let files = if language == Language::PYTHON && import.module.starts_with('.') {
+            self.resolve_python(importer, import)
+        } else if language == Language::TYPESCRIPT || language == Language::JAVASCRIPT {
+            self.resolve(importer, language, import)
+        } else if language == Language::RUST {
+            self.resolve_rust(importer, import)
+        } else {
+            Vec::new()
+        };
+        let mut out = Vec::new();
+        for file in files {
+            if file != importer && !out.contains(&file) {
+                out.push(file);
+            }
+        }
+        out
+    }
+
+    /// The file of the Rust module at `path` (`self::net`, `crate::utils`)
+    /// as seen from `importer`, for `mod` declarations and `pub use`
+    /// re-exports.
+    pub fn rust_module_file(&self, importer: &str, path: &str) -> Option<String> {
+        let import = FileImport {
+            module: path.to_string(),
+            symbols: Vec::new(),
+            file: None,
+        };
+        self.resolve_rust(importer, &import)
+            .into_iter()
+            .find(|file| file != importer)
+    }
+
+    fn resolve(&self, importer: &str, language: Language, import: &FileImport) -> Vec<String> {
+        if let Some(file) = &import.file {
+            return self.existing([file.clone()]);
+        }
+        if language == Language::PYTHON {
+            self.resolve_python(importer, import)
+        } else if language == Language::RUST {
+            self.resolve_rust(importer, import)
+        } else if language == Language::TYPESCRIPT || language == Language::JAVASCRIPT {
+            self.resolve_js(importer, &import.module)
+        } else if language == Language::GO {
+            self.resolve_go(&import.module)
+        } else {
+            self.resolve_namespace(importer, import)
+        }
+    }
+
+    /// `pkg.models`, `.models`, `..`, and `from pkg import submodule`.
+    fn resolve_python(&self, importer: &str, import: &FileImport) -> Vec<String> {
+        let module = import.module.as_str();
+        let mut modules = vec![module.to_string()];
+        for symbol in &import.symbols {
+            modules.push(if module.ends_with('.') {
+                format!("{}{}", module, symbol)
+            } else {
+                format!("{}.{}", module, symbol)
+            });
+        }
+
+        let mut out = Vec::new();
+        for module in &modules {
+            let dots = module.len() - module.trim_start_matches('.').len();
+            let segments: Vec<&str> = module[dots..]
+                .split('.')
+                .filter(|s| !s.is_empty())
+                .collect();
+            if dots == 0 {
+                out.extend(self.suffix(&segments));
+                continue;
+            }
+            let mut base = parent_dir(importer);
+            for _ in 1..dots {
+                base = parent_dir(base);
+            }
+            let path = join(base, &segments.join("/"));
+            out.extend(self.existing([format!("{}.py", path), join(&path, "__init__.py")]));
+        }
+        out
+    }
+
+    /// `crate::a::b`, `super::x`, `self::x`, and `other_crate::a` for
+    /// workspace crates. Trailing segments may name items rather than
+    /// modules, so the longest prefix that is a file wins.
+    fn resolve_rust(&self, importer: &str, import: &FileImport) -> Vec<String> {
+        let mut paths = vec![import.module.clone()];
+        for symbol in &import.symbols {
+            if symbol != "self" && symbol != "*" {
+                paths.push(format!("{}::{}", import.module, symbol));
+            }
+        }
+
+        let mut out = Vec::new();
+        for path in &paths {
+            let segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty()).collect();
+            let Some((first, rest)) = segments.split_first() else {
+                continue;
+            };
+            let base = match *first {
+                "crate" => Some(crate_src(importer)),
+                "self" => Some(rust_module_dir(importer)),
+                "super" => {
+                    let supers = segments.iter().take_while(|s| **s == "super").count();
+                    let mut dir = rust_module_dir(importer);
+                    for _ in 0..supers {
+                        dir = parent_dir(&dir).to_string();
+                    }
+                    out.extend(self.rust_module(&dir, &segments[supers..]));
+                    continue;
+                }
+                _ => None,
+            };
+            match base {
+                Some(base) => out.extend(self.rust_module(&base, rest)),
+                None => {
+                    // A child module named without `self::` (`mod utils;
+                    // pub use utils::*;`).
+                    let dir = rust_module_dir(importer);
+                    let child = join(&dir, first);
+                    if !self
+                        .existing([format!("{}.rs", child), join(&child, "mod.rs")])
+                        .is_empty()
+                    {
+                        out.extend(self.rust_module(&dir, &segments));
+                        continue;
+                    }
+                    // A workspace crate: `rpg_core::graph` → `rpg-core/src/graph.rs`.
+                    for name in [first.to_string(), first.replace('_', "-")] {
+                        let found = (0..=rest.len()).rev().find_map(|k| {
+                            let mut key = vec![name.as_str(), "src"];
+                            key.extend(if k == 0 { &["lib"][..] } else { &rest[..k] });
+                            let files = self.suffix(&key);
+                            (!files.is_empty()).then_some(files)
+                        });
+                        if let Some(files) = found {
+                            out.extend(files);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// The file for the longest prefix of `segments` under `base`, or the
+    /// crate or module root file when no segment is a module.
+    fn rust_module(&self, base: &str, segments: &[&str]) -> Vec<String> {
+        for k in (1..=segments.len()).rev() {
+            let path = join(base, &segments[..k].join("/"));
+            let found = self.existing([format!("{}.rs", path), join(&path, "mod.rs")]);
+            if !found.is_empty() {
+                return found;
+            }
+        }
+        let roots = ["lib.rs", "main.rs", "mod.rs"].map(|root| join(base, root));
+        let mut found = self.existing(roots);
+        if found.is_empty()
+            && let Some(file) = self.existing([format!("{}.rs", base)]).pop()
+        {
+            found.push(file);
+        }
+        found
+    }
+
+    /// `./utils`, `../lib/api.js`, `@app/services/auth` through tsconfig
+    /// `paths` (the first existing target only) or `baseUrl`, and
+    /// `@/components/Button` by convention. Other bare package specifiers
+    /// are third-party and resolve to nothing.
+    fn resolve_js(&self, importer: &str, module: &str) -> Vec<String> {
+        if !module.starts_with('.')
+            && let Some(paths) = self.aliases.paths_match(importer, module)
+        {
+            return paths
+                .iter()
+                .find_map(|path| self.js_file(path))
+                .into_iter()
+                .collect();
+        }
+        let mut paths = Vec::new();
+        if module.starts_with('.') {
+            paths.push(join(parent_dir(importer), module));
+        } else {
+            paths.extend(self.aliases.base_url_path(importer, module));
+            if let Some(rest) = SRC_ALIASES.iter().find_map(|a| module.strip_prefix(a)) {
+                paths.extend([join("src", rest), rest.to_string()]);
+            }
+        }
+        paths.iter().filter_map(|path| self.js_file(path)).collect()
+    }
+
+    /// The file a JS/TS path names: itself, with an extension, or as a
+    /// directory's index file.
+    fn js_file(&self, path: &str) -> Option<String> {
+        let stem = strip_extension(path);
+        let mut candidates = vec![path.to_string()];
+        for base in [path, stem] {
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| format!("{}.{}", base, ext)));
+        }
+        candidates.extend(
+            JS_EXTENSIONS
+                .iter()
+                .map(|ext| join(path, &format!("index.{}", ext))),
+        );
+        self.existing(candidates).into_iter().next()
+    }
+
+    /// A Go import names a package directory: the longest directory whose
+    /// path ends the import path (`github.com/org/repo/internal/util` →
+    /// `internal/util`).
+    fn resolve_go(&self, module: &str) -> Vec<String> {
+        let segments: Vec<&str> = module.split('/').filter(|s| !s.is_empty()).collect();
+        (0..segments.len())
+            .find_map(|i| self.by_dir.get(&segments[i..].join("/").to_lowercase()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Dotted, `\`-separated, or `::`-separated namespaces (`com.acme.Foo`,
+    /// `App\Services\Payment`) and path includes (`utils/log.h`): a file
+    /// named by the module and one of its symbols, else the module itself,
+    /// else every file of the package directory it names.
+    fn resolve_namespace(&self, importer: &str, import: &FileImport) -> Vec<String> {
+        let module = import.module.as_str();
+        if module.contains('/') {
+            let path = if module.starts_with('.') {
+                join(parent_dir(importer), module)
+            } else {
+                module.to_string()
+            };
+            let segments: Vec<&str> = strip_extension(&path).split('/').collect();
+            return self.suffix(&segments);
+        }
+
+        let segments: Vec<&str> = module
+            .split(['.', '\\'])
+            .flat_map(|s| s.split("::"))
+            .filter(|s| !s.is_empty())
+            .collect();
+        let mut out: Vec<String> = import
+            .symbols
+            .iter()
+            .flat_map(|symbol| {
+                let mut key = segments.clone();
+                key.push(symbol);
+                self.suffix(&key)
+            })
+            .collect();
+        if out.is_empty() {
+            out = self.suffix(&segments);
+        }
+        if out.is_empty() && !segments.is_empty() {
+            out = self
+                .by_dir
+                .get(&segments.join("/").to_lowercase())
+                .cloned()
+                .unwrap_or_default();
+        }
+        out
+    }
+
+    fn suffix(&self, segments: &[&str]) -> Vec<String> {
+        if segments.is_empty() {
+            return Vec::new();
+        }
+        self.by_suffix
+            .get(&segments.join("/").to_lowercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn existing(&self, candidates: impl IntoIterator<Item = String>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .filter(|path| self.files.contains(path))
+            .collect()
+    }
+}
+
+fn push_unique(map: &mut HashMap<String, Vec<String>>, key: String, file: &str) {
+    let files = map.entry(key).or_default();
+    if !files.iter().any(|f| f == file) {
+        files.push(file.to_string());
+    }
+}
+
+fn file_language(file: &str) -> Option<Language> {
+    Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Language::from_extension)
+}
+
+/// Whether `file` can define code `language` calls: the same language, or
+/// JS and TS, or C and C++.
+fn same_family(file: &str, language: Language) -> bool {
+    let family = |l: Language| {
+        if l == Language::JAVASCRIPT {
+            Language::TYPESCRIPT
+        } else if l == Language::C {
+            Language::CPP
+        } else {
+            l
+        }
+    };
+    file_language(file).is_some_and(|l| family(l) == family(language))
+}
+
+/// `a/b/c.py` → `a/b/c`; only the last segment's extension is removed.
+fn strip_extension(path: &str) -> &str {
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    match path[name_start..].rfind('.') {
+        Some(dot) if dot > 0 => &path[..name_start + dot],
+        _ => path,
+    }
+}
+
+/// Directory of a normalized path; `""` for top-level files.
+pub(crate) fn parent_dir(path: &str) -> &str {
+    path.rfind('/').map_or("", |i| &path[..i])
+}
+
+/// `dir` joined with a relative path, resolving `.` and `..` segments.
+pub(crate) fn join(dir: &str, rel: &str) -> String {
+    let mut parts: Vec<&str> = dir.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in rel.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(segment),
+        }
+    }
+    parts.join("/")
+}
+
+/// The `src` directory of the crate holding `file`, or the top level.
+fn crate_src(file: &str) -> String {
+    let segments: Vec<&str> = parent_dir(file).split('/').collect();
+    segments
+        .iter()
+        .rposition(|s| *s == "src")
+        .map_or_else(String::new, |i| segments[..=i].join("/"))
+}
+
+/// Directory holding the child modules of a Rust file: its own directory
+/// for `mod.rs`, `lib.rs`, and `main.rs`, else a directory named after it.
+fn rust_module_dir(file: &str) -> String {
+    let dir = parent_dir(file);
+    let stem = strip_extension(&file[dir.len()..]).trim_start_matches('/');
+    if ["mod", "lib", "main"].contains(&stem) {
+        dir.to_string()
+    } else {
+        join(dir, stem)
+    }
+}
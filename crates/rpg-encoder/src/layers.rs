@@ -0,0 +1,457 @@
+//! Architectural layer tags: api / domain / infrastructure / test.
+//!
+//! Each entity gets a coarse [`Layer`] from heuristics, first match wins:
+//!
+//! 1. Test code — test paradigm kind, test directories and file names, `test_*` names.
+//! 2. Entry points — route/controller/middleware/page kinds and `route` attributes.
+//! 3. Naming conventions — `*Handler` / `handle_*` are api, `*Repository` / `*Client` are
+//!    infrastructure.
+//! 4. Directory conventions — the deepest of `routes/`, `db/`, `domain/`, ... decides.
+//! 5. Library usage — the entity's body uses a name imported from a web framework (api)
+//!    or from a database, filesystem, or network library (infrastructure).
+//! 6. Everything else is domain logic.
+//!
+//! Tags are advisory. `.rpg/layers-overrides.toml` pins layers by path glob or
+//! entity ID:
+//!
+//! ```toml
+//! [paths]
+//! "src/legacy/**" = "domain"
+//!
+//! [entities]
+//! "src/app.rs:main" = "api"
+//! ```
+
+use globset::{Glob, GlobMatcher};
+use rpg_core::graph::{Entity, EntityKind, Layer, RPGraph};
+use rpg_parser::languages::Language;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// File holding manual layer overrides, relative to the `.rpg` directory.
+pub const OVERRIDES_FILE: &str = "layers-overrides.toml";
+
+/// Web framework modules; code using their names handles requests.
+const API_LIBRARIES: &[&str] = &[
+    // Rust
+    "axum",
+    "actix_web",
+    "rocket",
+    "warp",
+    "poem",
+    "tide",
+    // Python
+    "flask",
+    "fastapi",
+    "starlette",
+    "django.http",
+    "django.views",
+    "django.urls",
+    "rest_framework",
+    // JS/TS
+    "express",
+    "koa",
+    "fastify",
+    "hono",
+    "@nestjs/common",
+    "next/server",
+    // Go
+    "github.com/gin-gonic/gin",
+    "github.com/labstack/echo",
+    "github.com/gofiber/fiber",
+    "github.com/go-chi/chi",
+    "net/http",
+    // Java
+    "org.springframework.web",
+    "javax.ws.rs",
+    "jakarta.ws.rs",
+];
+
+/// Database, filesystem, and network client modules.
+const INFRASTRUCTURE_LIBRARIES: &[&str] = &[
+    // Rust
+    "std::fs",
+    "std::net",
+    "tokio::fs",
+    "tokio::net",
+    "reqwest",
+    "sqlx",
+    "diesel",
+    "rusqlite",
+    "sea_orm",
+    "redis",
+    "mongodb",
+    // Python
+    "sqlite3",
+    "sqlalchemy",
+    "psycopg2",
+    "psycopg",
+    "pymongo",
+    "requests",
+    "httpx",
+    "aiohttp",
+    "urllib",
+    "boto3",
+    "socket",
+    "shutil",
+    // JS/TS
+    "fs",
+    "axios",
+    "pg",
+    "mysql",
+    "mysql2",
+    "mongoose",
+    "@prisma/client",
+    "ioredis",
+    "knex",
+    "typeorm",
+    "sequelize",
+    "node-fetch",
+    // Go
+    "database/sql",
+    "net",
+    "gorm.io/gorm",
+    "github.com/jmoiron/sqlx",
+    // Java
+    "java.sql",
+    "java.net",
+    "java.nio.file",
+    "javax.persistence",
+    "jakarta.persistence",
+    "org.springframework.data",
+];
+
+const TEST_DIRS: &[&str] = &["test", "tests", "__tests__", "spec", "specs", "testing"];
+const API_DIRS: &[&str] = &[
+    "api",
+    "apis",
+    "routes",
+    "routers",
+    "handlers",
+    "controllers",
+    "endpoints",
+    "views",
+];
+const INFRASTRUCTURE_DIRS: &[&str] = &[
+    "db",
+    "database",
+    "storage",
+    "persistence",
+    "repository",
+    "repositories",
+    "infra",
+    "infrastructure",
+    "adapters",
+    "migrations",
+    "clients",
+    "dao",
+];
+const DOMAIN_DIRS: &[&str] = &["domain", "model", "models", "entities", "core"];
+
+/// Manual layer assignments from `.rpg/layers-overrides.toml`.
+#[derive(Default)]
+pub struct LayerOverrides {
+    paths: Vec<(String, GlobMatcher, Layer)>,
+    entities: HashMap<String, Layer>,
+}
+
+impl LayerOverrides {
+    /// Load the project's overrides. Missing file → no overrides.
+    pub fn load(project_root: &Path) -> anyhow::Result<Self> {
+        let path = rpg_core::storage::rpg_dir(project_root).join(OVERRIDES_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Parse overrides content. Unknown layer names and bad globs are errors.
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let table: toml::Table = content.parse()?;
+        let mut overrides = Self::default();
+        for (section, value) in &table {
+            let Some(entries) = value.as_table() else {
+                anyhow::bail!("[{}] must be a table", section);
+            };
+            for (key, value) in entries {
+                let layer = value.as_str().and_then(Layer::parse).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "{}.\"{}\": expected one of api, domain, infrastructure, test",
+                        section,
+                        key
+                    )
+                })?;
+                match section.as_str() {
+                    "paths" => {
+                        let matcher = Glob::new(key)?.compile_matcher();
+                        overrides.paths.push((key.clone(), matcher, layer));
+                    }
+                    "entities" => {
+                        overrides.entities.insert(key.clone(), layer);
+                    }
+                    other => anyhow::bail!("unknown section [{}]", other),
+                }
+            }
+        }
+        // The most specific (longest) pattern wins when several match.
+        overrides
+            .paths
+            .sort_by_key(|p| std::cmp::Reverse(p.0.len()));
+        Ok(overrides)
+    }
+
+    /// The pinned layer for an entity, if any. Entity IDs beat path globs.
+    pub fn layer_for(&self, entity: &Entity) -> Option<Layer> {
+        self.entities.get(&entity.id).copied().or_else(|| {
+            self.paths
+                .iter()
+                .find(|(_, matcher, _)| matcher.is_match(&entity.file))
+                .map(|(_, _, layer)| *layer)
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.entities.is_empty()
+    }
+}
+
+/// Local names a file binds to web-framework and infrastructure imports.
+#[derive(Default)]
+pub struct ImportedNames {
+    pub api: HashSet<String>,
+    pub infrastructure: HashSet<String>,
+}
+
+impl ImportedNames {
+    /// Classify a file's imports against the known library lists.
+    pub fn from_imports(imports: &[rpg_parser::deps::ImportDep]) -> Self {
+        let mut names = Self::default();
+        for import in imports {
+            let module = import.module.trim_start_matches("node:");
+            let bindings: Vec<(String, &str)> = if import.symbols.is_empty() {
+                let local = module.rsplit(['/', '.', ':']).next().unwrap_or(module);
+                vec![(module.to_string(), local)]
+            } else {
+                import
+                    .symbols
+                    .iter()
+                    .map(|s| (format!("{}::{}", module, s), s.as_str()))
+                    .collect()
+            };
+            for (path, local) in bindings {
+                if local.is_empty() || local == "*" || local == "self" {
+                    continue;
+                }
+                if API_LIBRARIES.iter().any(|lib| is_within(&path, lib)) {
+                    names.api.insert(local.to_string());
+                } else if INFRASTRUCTURE_LIBRARIES
+                    .iter()
+                    .any(|lib| is_within(&path, lib))
+                {
+                    names.infrastructure.insert(local.to_string());
+                }
+            }
+        }
+        names
+    }
+}
+
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '.', ':']).filter(|s| !s.is_empty())
+}
+
+/// Whether an import path is `library` or inside it, comparing path segments.
+fn is_within(path: &str, library: &str) -> bool {
+    let mut path = segments(path);
+    segments(library).all(|lib| path.next() == Some(lib))
+}
+
+/// Infer an entity's layer from its kind, name, path, and (when known) source.
+pub fn infer_layer(entity: &Entity, source: Option<&str>, imports: &ImportedNames) -> Layer {
+    let dirs: Vec<String> = entity
+        .file
+        .parent()
+        .into_iter()
+        .flat_map(Path::components)
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let file_name = entity
+        .file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if entity.kind == EntityKind::Test
+        || dirs.iter().any(|d| TEST_DIRS.contains(&d.as_str()))
+        || is_test_file(&file_name)
+        || is_test_name(&entity.name)
+    {
+        return Layer::Test;
+    }
+
+    if matches!(
+        entity.kind,
+        EntityKind::Route
+            | EntityKind::Controller
+            | EntityKind::Middleware
+            | EntityKind::Page
+            | EntityKind::Layout
+    ) || entity.attributes.contains_key("route")
+    {
+        return Layer::Api;
+    }
+
+    if let Some(layer) = layer_from_name(&entity.name) {
+        return layer;
+    }
+
+    for dir in dirs.iter().rev() {
+        if API_DIRS.contains(&dir.as_str()) {
+            return Layer::Api;
+        }
+        if INFRASTRUCTURE_DIRS.contains(&dir.as_str()) {
+            return Layer::Infrastructure;
+        }
+        if DOMAIN_DIRS.contains(&dir.as_str()) {
+            return Layer::Domain;
+        }
+    }
+
+    if let Some(source) = source {
+        let used = |names: &HashSet<String>| {
+            !names.is_empty() && identifiers(source).any(|ident| names.contains(ident))
+        };
+        if used(&imports.api) {
+            return Layer::Api;
+        }
+        if used(&imports.infrastructure)
+            || INFRASTRUCTURE_LIBRARIES
+                .iter()
+                .any(|lib| lib.contains("::") && source.contains(&format!("{}::", lib)))
+        {
+            return Layer::Infrastructure;
+        }
+    }
+
+    Layer::Domain
+}
+
+fn is_test_file(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or(name);
+    stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || stem == "tests"
+        || name == "conftest.py"
+        || (matches!(
+            Path::new(name).extension().and_then(|e| e.to_str()),
+            Some("java" | "kt" | "cs")
+        ) && (stem.ends_with("test") || stem.ends_with("tests")))
+}
+
+fn is_test_name(name: &str) -> bool {
+    name.starts_with("test_")
+        || name
+            .strip_prefix("Test")
+            .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+/// Naming conventions that mark entry points and external-system wrappers.
+fn layer_from_name(name: &str) -> Option<Layer> {
+    let lower = name.to_lowercase();
+    if lower.starts_with("handle_")
+        || ["handler", "controller", "endpoint", "_view"]
+            .iter()
+            .any(|suffix| lower.ends_with(suffix))
+    {
+        return Some(Layer::Api);
+    }
+    if ["repository", "dao", "client", "gateway", "adapter"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        return Some(Layer::Infrastructure);
+    }
+    None
+}
+
+fn identifiers(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .filter(|s| !s.is_empty())
+}
+
+/// Tag entities with their layer: heuristics for entities in `changed_files`
+/// (all files when `None`) and in files with untagged entities, then the
+/// overrides file for every entity.
+///
+/// A malformed overrides file is reported and skipped; inferred tags still apply.
+pub fn assign_layers(graph: &mut RPGraph, project_root: &Path, changed_files: Option<&[PathBuf]>) {
+    let files: BTreeSet<PathBuf> = match changed_files {
+        Some(files) => files
+            .iter()
+            .cloned()
+            .chain(
+                graph
+                    .entities
+                    .values()
+                    .filter(|e| e.layer.is_none())
+                    .map(|e| e.file.clone()),
+            )
+            .collect(),
+        None => graph.file_index.keys().cloned().collect(),
+    };
+
+    for rel_path in &files {
+        let Some(ids) = graph.file_index.get(rel_path).cloned() else {
+            continue;
+        };
+        let source = rel_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Language::from_extension)
+            .and_then(|language| {
+                let source = std::fs::read_to_string(project_root.join(rel_path)).ok()?;
+                Some((language, source))
+            });
+        let (imports, lines) = match &source {
+            Some((language, source)) => (
+                ImportedNames::from_imports(
+                    &rpg_parser::deps::extract_deps(rel_path, source, *language).imports,
+                ),
+                source.lines().collect(),
+            ),
+            None => (ImportedNames::default(), Vec::new()),
+        };
+
+        for id in ids {
+            if let Some(entity) = graph.entities.get_mut(&id) {
+                let body = (!lines.is_empty()).then(|| {
+                    let start = entity.line_start.saturating_sub(1).min(lines.len());
+                    let end = entity.line_end.clamp(start, lines.len());
+                    lines[start..end].join("\n")
+                });
+                entity.layer = Some(infer_layer(entity, body.as_deref(), &imports));
+            }
+        }
+    }
+
+    let overrides = match LayerOverrides::load(project_root) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            eprintln!("  Warning: ignoring .rpg/{}: {}", OVERRIDES_FILE, e);
+            return;
+        }
+    };
+    if overrides.is_empty() {
+        return;
+    }
+    for entity in graph.entities.values_mut() {
+        if let Some(layer) = overrides.layer_for(entity) {
+            entity.layer = Some(layer);
+        }
+    }
+}
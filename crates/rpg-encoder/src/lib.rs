@@ -7,11 +7,30 @@
 //! Semantic lifting is performed by the connected coding agent via the MCP interactive
 //! protocol (get_entities_for_lifting → submit_lift_results), not by external LLM API calls.
 
+pub mod annotations;
+pub mod api_diff;
+pub mod artifact;
+pub mod churn;
 pub mod critic;
 pub mod dataflow;
 pub mod evolution;
+pub mod feature_sources;
+pub mod fixtures;
+pub mod git_files;
+pub mod gitattributes;
 pub mod grounding;
 pub mod hierarchy;
+pub mod hierarchy_changes;
+pub mod hierarchy_style;
+pub mod import_paths;
+pub mod layers;
 pub mod lift;
+pub mod lift_queue;
+pub mod nested_repos;
+pub mod ownership;
 pub mod reconstruction;
 pub mod semantic_lifting;
+pub mod tokens;
+pub mod tsconfig;
+pub mod unresolved_imports;
+pub mod verification;
@@ -1,12 +1,15 @@
 //! On-demand semantic lifting: scope resolution, batching, and incremental update utilities.
 
+use crate::tokens::TokenEstimator;
 use anyhow::Result;
+use rpg_core::files::{FileProvider, WorktreeFiles};
 use rpg_core::graph::RPGraph;
+use rpg_core::scope::ScopeError;
 use rpg_parser::entities::RawEntity;
 use rpg_parser::languages::Language;
 use rpg_parser::paradigms::classify::matches_entity;
-use rpg_parser::paradigms::defs::{AutoLiftRule, ParadigmDef};
-use std::collections::{HashMap, HashSet};
+use rpg_parser::paradigms::defs::{AutoLiftRule, BoilerplateRule, ParadigmDef};
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use std::sync::OnceLock;
 
@@ -30,20 +33,53 @@ pub struct LiftResult {
 /// - Hierarchy path prefix: `Auth/login` — collects via hierarchy subtree
 /// - Comma-separated entity IDs: `src/foo.rs:bar,src/baz.rs:qux`
 /// - `*` or `all` — all unlifted entities
-pub fn resolve_scope(graph: &RPGraph, scope: &str) -> LiftScope {
+/// - Scope expressions: `area:Auth AND kind:function AND NOT glob:**/tests/**`
+///   (see `rpg_core::scope`) — unlifted entities matching the expression
+///
+/// Like `*`, a scope expression selects only unlifted entities; globs, hierarchy
+/// paths, and entity IDs name their targets explicitly and re-lift them even when
+/// they already have features. Entities in fixture files are never in scope.
+/// Fails when a scope expression does not parse.
+pub fn resolve_scope(graph: &RPGraph, scope: &str) -> Result<LiftScope, ScopeError> {
+    let mut resolved = resolve_scope_ids(graph, scope.trim())?;
+    resolved
+        .entity_ids
+        .retain(|id| graph.entities.get(id).is_none_or(|e| !e.is_fixture()));
+    Ok(resolved)
+}
+
+/// Whether `scope` selects only unlifted entities (`*`, `all`, or a scope
+/// expression) rather than naming entities to re-lift.
+pub fn is_unlifted_scope(scope: &str) -> bool {
     let scope = scope.trim();
+    scope == "*" || scope.eq_ignore_ascii_case("all") || rpg_core::scope::is_expression(scope)
+}
+
+fn resolve_scope_ids(graph: &RPGraph, scope: &str) -> Result<LiftScope, ScopeError> {
+    if rpg_core::scope::is_expression(scope) {
+        let expr = rpg_core::scope::parse(scope)?;
+        let entity_ids = expr
+            .resolve(graph)
+            .into_iter()
+            .filter(|id| {
+                graph
+                    .entities
+                    .get(id)
+                    .is_some_and(|e| e.semantic_features.is_empty() && graph.is_liftable(e))
+            })
+            .collect();
+        return Ok(LiftScope { entity_ids });
+    }
 
-    // "all" or "*" → all unlifted entities
+    // "all" or "*" → all unlifted liftable entities
     if scope == "*" || scope.eq_ignore_ascii_case("all") {
         let entity_ids = graph
             .entities
             .iter()
-            .filter(|(_, e)| {
-                e.semantic_features.is_empty() && e.kind != rpg_core::graph::EntityKind::Module
-            })
+            .filter(|(_, e)| e.semantic_features.is_empty() && graph.is_liftable(e))
             .map(|(id, _)| id.clone())
             .collect();
-        return LiftScope { entity_ids };
+        return Ok(LiftScope { entity_ids });
     }
 
     // Try as glob pattern (contains * or ?)
@@ -57,7 +93,7 @@ pub fn resolve_scope(graph: &RPGraph, scope: &str) -> LiftScope {
             .filter(|(_, e)| matcher.is_match(&e.file))
             .map(|(id, _)| id.clone())
             .collect();
-        return LiftScope { entity_ids };
+        return Ok(LiftScope { entity_ids });
     }
 
     // Try as hierarchy path prefix
@@ -88,9 +124,9 @@ pub fn resolve_scope(graph: &RPGraph, scope: &str) -> LiftScope {
         }
     }
     if !hierarchy_ids.is_empty() {
-        return LiftScope {
+        return Ok(LiftScope {
             entity_ids: hierarchy_ids,
-        };
+        });
     }
 
     // Try as comma-separated entity IDs
@@ -100,7 +136,7 @@ pub fn resolve_scope(graph: &RPGraph, scope: &str) -> LiftScope {
         .filter(|id| graph.entities.contains_key(id))
         .collect();
 
-    LiftScope { entity_ids }
+    Ok(LiftScope { entity_ids })
 }
 
 // ---------------------------------------------------------------------------
@@ -147,6 +183,23 @@ struct TaggedRule {
     languages: Vec<String>,
 }
 
+/// A paradigm's boilerplate rule with its file globs compiled.
+struct BoilerplateMatcher {
+    paradigm: String,
+    languages: Vec<String>,
+    files: globset::GlobSet,
+    rule: BoilerplateRule,
+}
+
+/// Features from a matching auto-lift or boilerplate rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoLift {
+    pub features: Vec<String>,
+    pub confidence: LiftConfidence,
+    /// The paradigm whose boilerplate skip-list matched, if any.
+    pub boilerplate: Option<String>,
+}
+
 /// Confidence level for auto-lifted features.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LiftConfidence {
@@ -164,9 +217,11 @@ pub enum LiftConfidence {
 /// `core.toml` (priority 100) is always included; framework-specific rules
 /// from detected paradigms are prepended (lower priority number = higher priority
 /// = checked first). Language-specific rules only match entities whose file
-/// extension belongs to the rule's source language.
+/// extension belongs to the rule's source language. Boilerplate rules from
+/// the same paradigms are checked before any auto-lift rule.
 pub struct AutoLiftEngine {
     rules: Vec<TaggedRule>,
+    boilerplate: Vec<BoilerplateMatcher>,
 }
 
 impl AutoLiftEngine {
@@ -176,6 +231,7 @@ impl AutoLiftEngine {
     /// `core.toml` (identified by `languages = []`) is always included regardless.
     pub fn new(all_defs: &[ParadigmDef], active_paradigm_names: &[String]) -> Self {
         let mut rules = Vec::new();
+        let mut boilerplate = Vec::new();
         // Defs are already sorted by priority (ascending = highest priority first).
         // Framework rules come before core rules, so framework patterns win.
         for def in all_defs {
@@ -188,9 +244,52 @@ impl AutoLiftEngine {
                         languages: def.languages.clone(),
                     });
                 }
+                for rule in &def.boilerplate {
+                    let mut files = globset::GlobSetBuilder::new();
+                    for pattern in &rule.files {
+                        // Patterns were validated at load time.
+                        if let Ok(glob) = globset::Glob::new(pattern) {
+                            files.add(glob);
+                        }
+                    }
+                    let Ok(files) = files.build() else {
+                        continue;
+                    };
+                    boilerplate.push(BoilerplateMatcher {
+                        paradigm: def.name.clone(),
+                        languages: def.languages.clone(),
+                        files,
+                        rule: rule.clone(),
+                    });
+                }
             }
         }
-        Self { rules }
+        Self { rules, boilerplate }
+    }
+
+    /// Match an entity against boilerplate skip-lists first, then auto-lift
+    /// rules. Boilerplate is always `Accept`; see
+    /// [`Self::try_lift_with_confidence`] for auto-lift confidence.
+    pub fn try_lift_detailed(&self, raw: &RawEntity) -> Option<AutoLift> {
+        let file_lang = Self::file_language(raw);
+        for matcher in &self.boilerplate {
+            if Self::language_matches(&matcher.languages, file_lang)
+                && matcher.files.is_match(&raw.file)
+                && matches_entity(&matcher.rule.match_rule, raw, &raw.file)
+            {
+                return Some(AutoLift {
+                    features: matcher.rule.features.clone(),
+                    confidence: LiftConfidence::Accept,
+                    boilerplate: Some(matcher.paradigm.clone()),
+                });
+            }
+        }
+        let (features, confidence) = self.try_lift_rules(raw)?;
+        Some(AutoLift {
+            features,
+            confidence,
+            boilerplate: None,
+        })
     }
 
     /// Try to match an entity against auto-lift rules with confidence scoring.
@@ -202,11 +301,16 @@ impl AutoLiftEngine {
     /// Returns `None` if no rule matches (entity needs full LLM lifting).
     ///
     /// Rules without structural gate fields (max_branches/max_loops/max_calls)
-    /// always produce `Accept` confidence when matched.
+    /// always produce `Accept` confidence when matched, as do boilerplate rules.
     pub fn try_lift_with_confidence(
         &self,
         raw: &RawEntity,
     ) -> Option<(Vec<String>, LiftConfidence)> {
+        self.try_lift_detailed(raw)
+            .map(|lift| (lift.features, lift.confidence))
+    }
+
+    fn try_lift_rules(&self, raw: &RawEntity) -> Option<(Vec<String>, LiftConfidence)> {
         let (features, has_structural_gates) = self.try_lift_internal(raw)?;
 
         if !has_structural_gates {
@@ -239,19 +343,10 @@ impl AutoLiftEngine {
 
     /// Internal match that also reports whether the matching rule had structural gates.
     fn try_lift_internal(&self, raw: &RawEntity) -> Option<(Vec<String>, bool)> {
-        let file_lang = raw
-            .file
-            .extension()
-            .and_then(|e| e.to_str())
-            .and_then(Language::from_extension);
+        let file_lang = Self::file_language(raw);
 
         for tagged in &self.rules {
-            // Skip language-specific rules that don't match the entity's file language.
-            // When the file language is unknown, language-specific rules are skipped
-            // (only core rules with empty languages apply).
-            if !tagged.languages.is_empty()
-                && !file_lang.is_some_and(|lang| tagged.languages.iter().any(|l| l == lang.name()))
-            {
+            if !Self::language_matches(&tagged.languages, file_lang) {
                 continue;
             }
             if matches_entity(&tagged.rule.match_rule, raw, &raw.file) {
@@ -267,6 +362,21 @@ impl AutoLiftEngine {
         None
     }
 
+    fn file_language(raw: &RawEntity) -> Option<Language> {
+        raw.file
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Language::from_extension)
+    }
+
+    /// Whether a rule from a paradigm for `languages` applies to a file in
+    /// `file_lang`. When the file language is unknown, language-specific rules
+    /// are skipped (only core rules with empty languages apply).
+    fn language_matches(languages: &[String], file_lang: Option<Language>) -> bool {
+        languages.is_empty()
+            || file_lang.is_some_and(|lang| languages.iter().any(|l| l == lang.name()))
+    }
+
     /// Expand template strings using entity context.
     fn expand_templates(rule: &AutoLiftRule, raw: &RawEntity) -> Vec<String> {
         let parent = raw.parent_class.as_deref().unwrap_or("instance");
@@ -323,6 +433,31 @@ pub fn try_auto_lift(raw: &RawEntity) -> Option<Vec<String>> {
     engine.try_lift(raw)
 }
 
+/// Concatenate one kind of prompt hint from each detected paradigm, under a
+/// `### <Paradigm> Guidelines` heading. Builtin defs are parsed once.
+pub fn paradigm_hints(
+    paradigm_names: &[String],
+    hint_selector: fn(&rpg_parser::paradigms::defs::PromptHints) -> &Option<String>,
+) -> String {
+    static PARADIGM_DEFS: OnceLock<Vec<ParadigmDef>> = OnceLock::new();
+    let defs = PARADIGM_DEFS
+        .get_or_init(|| rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default());
+    let mut hints = String::new();
+    for name in paradigm_names {
+        if let Some(def) = defs.iter().find(|d| &d.name == name)
+            && let Some(hint) = hint_selector(&def.prompt_hints)
+        {
+            if !hints.is_empty() {
+                hints.push('\n');
+            }
+            hints.push_str(&format!("### {} Guidelines\n", def.name));
+            hints.push_str(hint.trim());
+            hints.push('\n');
+        }
+    }
+    hints
+}
+
 /// Generate a compact repo overview from graph metadata (paper's `repo_info` context).
 /// Wraps output in `<repo_name>` and `<repo_info>` tags per paper §A.1.1.
 pub fn generate_repo_info(graph: &RPGraph, project_name: &str) -> String {
@@ -360,6 +495,191 @@ pub fn generate_repo_info(graph: &RPGraph, project_name: &str) -> String {
     )
 }
 
+/// Source lines shown per entity in lifting prompts.
+pub const LIFT_SOURCE_LINES: usize = 40;
+
+/// An entity's doc comment as a `Doc:` block of `> `-quoted lines, placed
+/// above its code in lifting prompts. Empty when there is no doc.
+pub fn format_doc_block(doc: Option<&str>) -> String {
+    let Some(doc) = doc else {
+        return String::new();
+    };
+    let mut out = String::from("Doc:\n");
+    for line in doc.lines() {
+        out.push('>');
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Keywords that branch or loop, in the languages we parse.
+const CONTROL_KEYWORDS: &[&str] = &[
+    "if", "else", "elif", "match", "switch", "case", "for", "foreach", "while", "loop", "do",
+    "try", "catch", "except", "finally", "when", "unless", "guard", "select",
+];
+
+/// Keywords that leave the function or loop early.
+const EXIT_KEYWORDS: &[&str] = &["return", "raise", "throw", "yield", "break", "continue"];
+
+/// Names of every entity in the graph, for spotting in-repo calls in
+/// [`condense_source`].
+pub fn entity_names(graph: &RPGraph) -> HashSet<&str> {
+    graph.entities.values().map(|e| e.name.as_str()).collect()
+}
+
+/// Shorten an entity's source to `max_lines` for a lifting prompt without
+/// cutting it blindly at the top.
+///
+/// The signature and closing line are always kept, and so is the doc comment,
+/// up to a quarter of the budget. The rest goes to body lines that exit early
+/// (`return`, `raise`, `?`), then to lines that branch or loop or call an
+/// entity in `known_names`, then to the remaining lines from the top. Each run
+/// of elided lines becomes one `…` marker at its indentation. Sources within
+/// budget are returned unchanged.
+pub fn condense_source(source: &str, max_lines: usize, known_names: &HashSet<&str>) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    if lines.len() <= max_lines {
+        return source.to_string();
+    }
+
+    let mut keep = vec![false; lines.len()];
+    let mut budget = max_lines;
+    let mut take = |i: usize| {
+        if budget > 0 && !keep[i] {
+            keep[i] = true;
+            budget -= 1;
+        }
+    };
+
+    // Leading doc comments, attributes, and decorators
+    let doc_end = lines
+        .iter()
+        .position(|l| !is_doc_line(l))
+        .unwrap_or(lines.len());
+    // Signature: through the line that opens the body
+    let sig_end = (doc_end..lines.len().min(doc_end + 8))
+        .find(|&i| opens_body(lines[i]))
+        .map_or(doc_end + 1, |i| i + 1)
+        .min(lines.len());
+    // Python docstring right after the signature
+    let body_start = docstring_end(&lines, sig_end);
+
+    for i in doc_end..sig_end {
+        take(i);
+    }
+    take(lines.len() - 1);
+    if body_start > sig_end {
+        // Close the docstring even when its middle is elided
+        take(body_start - 1);
+    }
+    for i in (0..doc_end).chain(sig_end..body_start).take(max_lines / 4) {
+        take(i);
+    }
+
+    let mut ranked: Vec<(usize, usize)> = (body_start..lines.len())
+        .filter_map(|i| {
+            let score = line_score(lines[i], known_names);
+            (score > 0).then_some((score, i))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in ranked {
+        take(i);
+    }
+    for (i, line) in lines.iter().enumerate().skip(body_start) {
+        if !line.trim().is_empty() {
+            take(i);
+        }
+    }
+
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if keep[i] {
+            out.push(lines[i].to_string());
+            i += 1;
+            continue;
+        }
+        let gap_end = (i..lines.len()).find(|&j| keep[j]).unwrap_or(lines.len());
+        let indent = lines[i..gap_end]
+            .iter()
+            .find(|l| !l.trim().is_empty())
+            .map_or("", |l| &l[..l.len() - l.trim_start().len()]);
+        out.push(format!("{}…", indent));
+        i = gap_end;
+    }
+    out.join("\n")
+}
+
+fn is_doc_line(line: &str) -> bool {
+    let line = line.trim_start();
+    line.is_empty()
+        || ["//", "/*", "*", "#", "@", "--"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+}
+
+fn opens_body(line: &str) -> bool {
+    let line = line.trim_end();
+    line.ends_with('{') || line.ends_with(':') || line.ends_with("=>") || line.ends_with('=')
+}
+
+/// Index of the first line after a docstring starting at `start`, or `start`.
+fn docstring_end(lines: &[&str], start: usize) -> usize {
+    let Some(first) = lines.get(start).map(|l| l.trim()) else {
+        return start;
+    };
+    let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|q| first.starts_with(q)) else {
+        return start;
+    };
+    if first.len() >= 6 && first[3..].contains(quote) {
+        return start + 1;
+    }
+    (start + 1..lines.len())
+        .find(|&i| lines[i].contains(quote))
+        .map_or(start + 1, |i| i + 1)
+}
+
+/// 3 for lines that exit early, 2 for branches, loops, and in-repo calls.
+fn line_score(line: &str, known_names: &HashSet<&str>) -> usize {
+    let code = line.trim();
+    let first_word = code
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|w| !w.is_empty())
+        .unwrap_or("");
+    if EXIT_KEYWORDS.contains(&first_word)
+        || code.contains("?;")
+        || code.contains("?)")
+        || code.ends_with('?')
+    {
+        return 3;
+    }
+    if CONTROL_KEYWORDS.contains(&first_word)
+        || code.starts_with("} else")
+        || called_names(code).any(|name| known_names.contains(name))
+    {
+        return 2;
+    }
+    0
+}
+
+/// Identifiers directly followed by `(`.
+fn called_names(code: &str) -> impl Iterator<Item = &str> {
+    code.match_indices('(').filter_map(|(at, _)| {
+        let head = &code[..at];
+        let start = head
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !c.is_alphanumeric() && c != '_')
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        (start < at).then(|| &head[start..])
+    })
+}
+
 /// Re-read source files and collect RawEntity objects for the scoped entities.
 /// This is needed because the graph Entity doesn't store source text.
 pub fn collect_raw_entities(
@@ -367,11 +687,21 @@ pub fn collect_raw_entities(
     scope: &LiftScope,
     project_root: &Path,
 ) -> Result<Vec<RawEntity>> {
-    let mut files_to_read: HashMap<std::path::PathBuf, Vec<String>> = HashMap::new();
+    collect_raw_entities_from(graph, scope, &WorktreeFiles::new(project_root))
+}
+
+/// [`collect_raw_entities`] reading sources from `files`, e.g. a git revision.
+pub fn collect_raw_entities_from(
+    graph: &RPGraph,
+    scope: &LiftScope,
+    files: &dyn FileProvider,
+) -> Result<Vec<RawEntity>> {
+    let mut files_to_read: BTreeMap<std::path::PathBuf, Vec<String>> = BTreeMap::new();
     for id in &scope.entity_ids {
         if let Some(entity) = graph.entities.get(id) {
-            // Skip Module entities — they get features via aggregation, not lifting
-            if entity.kind == rpg_core::graph::EntityKind::Module {
+            // Skip Module entities (they get features via aggregation) and
+            // kinds or paradigm entities excluded from lifting
+            if !graph.is_liftable(entity) {
                 continue;
             }
             files_to_read
@@ -383,23 +713,17 @@ pub fn collect_raw_entities(
 
     let mut raw_entities: Vec<RawEntity> = Vec::new();
     for (rel_path, entity_ids) in &files_to_read {
-        // Per-file language detection (multi-language graph support)
-        let file_lang = rel_path
-            .extension()
-            .and_then(|e| e.to_str())
-            .and_then(Language::from_extension);
-        let Some(language) = file_lang else {
-            continue;
-        };
-
-        let abs_path = project_root.join(rel_path);
-        let source = match std::fs::read_to_string(&abs_path) {
+        let source = match files.read_to_string(rel_path) {
             Ok(s) => s,
             Err(e) => {
                 eprintln!("  Warning: could not read {}: {}", rel_path.display(), e);
                 continue;
             }
         };
+        // Per-file language detection (multi-language graph support)
+        let Some(language) = Language::from_source(rel_path, &source) else {
+            continue;
+        };
 
         let file_raws = rpg_parser::entities::extract_entities(rel_path, &source, language);
 
@@ -421,11 +745,14 @@ pub fn collect_raw_entities(
 /// while respecting model context limits." Each batch is filled until either the
 /// token budget or entity count cap is reached.
 ///
+/// Entity sizes come from `estimator`.
+///
 /// Returns a list of `(start, end)` index ranges into the input slice.
 pub fn build_token_aware_batches(
     entities: &[RawEntity],
     max_count: usize,
     max_tokens: usize,
+    estimator: &dyn TokenEstimator,
 ) -> Vec<(usize, usize)> {
     let mut batches = Vec::new();
     let mut batch_start = 0;
@@ -433,8 +760,7 @@ pub fn build_token_aware_batches(
     let mut batch_count = 0usize;
 
     for (i, entity) in entities.iter().enumerate() {
-        // Estimate tokens: ~4 characters per token is a reasonable heuristic
-        let est_tokens = entity.source_text.len() / 4 + 1;
+        let est_tokens = estimator.estimate(&entity.source_text) + 1;
 
         // Flush if adding this entity would exceed budget (but always include at least 1)
         if batch_count > 0 && (batch_tokens + est_tokens > max_tokens || batch_count >= max_count) {
@@ -456,10 +782,44 @@ pub fn build_token_aware_batches(
     batches
 }
 
+/// Token-aware batching where each entity carries its own count cap, such as
+/// a per-area `batch_size`. Entities are regrouped so every batch shares one
+/// cap; groups keep the order of their first entity, and entities keep their
+/// order within a group.
+///
+/// Returns the reordered entities and `(start, end)` ranges into them.
+pub fn build_capped_batches(
+    entities: Vec<(RawEntity, usize)>,
+    max_tokens: usize,
+    estimator: &dyn TokenEstimator,
+) -> (Vec<RawEntity>, Vec<(usize, usize)>) {
+    let mut groups: Vec<(usize, Vec<RawEntity>)> = Vec::new();
+    for (entity, cap) in entities {
+        match groups.iter_mut().find(|(c, _)| *c == cap) {
+            Some((_, group)) => group.push(entity),
+            None => groups.push((cap, vec![entity])),
+        }
+    }
+
+    let mut ordered = Vec::new();
+    let mut batches = Vec::new();
+    for (cap, group) in groups {
+        let offset = ordered.len();
+        batches.extend(
+            build_token_aware_batches(&group, cap, max_tokens, estimator)
+                .into_iter()
+                .map(|(start, end)| (start + offset, end + offset)),
+        );
+        ordered.extend(group);
+    }
+    (ordered, batches)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rpg_core::graph::EntityKind;
+    use std::collections::BTreeMap;
 
     fn make_raw(name: &str, parent: Option<&str>, source: &str) -> RawEntity {
         make_raw_file(name, parent, source, "src/lib.rs")
@@ -475,9 +835,165 @@ mod tests {
             parent_class: parent.map(|s| s.to_string()),
             source_text: source.to_string(),
             signature: None,
+            attributes: BTreeMap::new(),
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        }
+    }
+
+    /// The old blind cut: the first `max_lines` lines.
+    fn head_truncate(source: &str, max_lines: usize) -> String {
+        source
+            .lines()
+            .take(max_lines)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn rust_fixture() -> String {
+        let mut src = String::new();
+        for i in 0..20 {
+            src.push_str(&format!(
+                "/// Detail {} about how sessions are validated.\n",
+                i
+            ));
+        }
+        src.push_str("pub fn validate_session(store: &Store, token: &str) -> Result<User> {\n");
+        for i in 0..25 {
+            src.push_str(&format!("    let step{} = prepare({});\n", i, i));
+        }
+        src.push_str("    let session = load_session(store, token)?;\n");
+        src.push_str("    if session.expired() {\n");
+        src.push_str("        return Err(Error::Expired);\n");
+        src.push_str("    }\n");
+        for i in 0..10 {
+            src.push_str(&format!("    log_step({});\n", i));
+        }
+        src.push_str("    return Ok(session.user);\n");
+        src.push_str("}\n");
+        src
+    }
+
+    fn python_fixture() -> String {
+        let mut src = String::from("def charge(order, gateway):\n    \"\"\"Charge an order.\n\n");
+        for i in 0..12 {
+            src.push_str(&format!("    Note {} on retries and idempotency.\n", i));
+        }
+        src.push_str("    \"\"\"\n");
+        for i in 0..30 {
+            src.push_str(&format!("    total_{} = order.line({}) * rate\n", i, i));
+        }
+        src.push_str("    if not order.items:\n");
+        src.push_str("        raise EmptyOrder(order.id)\n");
+        src.push_str("    receipt = submit_payment(gateway, order)\n");
+        src.push_str("    return receipt\n");
+        src
+    }
+
+    #[test]
+    fn test_condense_keeps_returns_head_truncation_drops() {
+        let known: HashSet<&str> = ["load_session", "submit_payment"].into_iter().collect();
+        for (source, must_keep) in [
+            (
+                rust_fixture(),
+                &[
+                    "pub fn validate_session(",
+                    "/// Detail 0",
+                    "load_session(store, token)?;",
+                    "return Err(Error::Expired);",
+                    "return Ok(session.user);",
+                    "if session.expired() {",
+                ][..],
+            ),
+            (
+                python_fixture(),
+                &[
+                    "def charge(order, gateway):",
+                    "\"\"\"Charge an order.",
+                    "\n    \"\"\"\n",
+                    "raise EmptyOrder(order.id)",
+                    "receipt = submit_payment(gateway, order)",
+                    "return receipt",
+                ],
+            ),
+        ] {
+            let naive = head_truncate(&source, LIFT_SOURCE_LINES);
+            let condensed = condense_source(&source, LIFT_SOURCE_LINES, &known);
+            assert!(
+                !naive.contains("return") && !naive.contains("raise"),
+                "fixture must defeat head truncation"
+            );
+            for line in must_keep {
+                assert!(
+                    condensed.contains(line),
+                    "missing {:?} in:\n{}",
+                    line,
+                    condensed
+                );
+            }
+            let kept = condensed
+                .lines()
+                .filter(|l| !l.trim().starts_with('…'))
+                .count();
+            assert!(kept <= LIFT_SOURCE_LINES, "{} lines kept", kept);
+            assert!(condensed.contains('…'));
         }
     }
 
+    #[test]
+    fn test_condense_marks_each_gap_once() {
+        let source = rust_fixture();
+        let condensed = condense_source(&source, LIFT_SOURCE_LINES, &HashSet::new());
+        let lines: Vec<&str> = condensed.lines().collect();
+        assert!(
+            lines
+                .windows(2)
+                .all(|w| !(w[0].ends_with('…') && w[1].ends_with('…')))
+        );
+        // Elided doc lines beyond a quarter of the budget
+        assert!(!condensed.contains("/// Detail 19"));
+        assert!(condensed.contains("    …"));
+        assert_eq!(lines.last(), Some(&"}"));
+    }
+
+    #[test]
+    fn test_condense_short_source_unchanged() {
+        let source = "fn f() -> u8 {\n    1\n}";
+        assert_eq!(condense_source(source, 40, &HashSet::new()), source);
+    }
+
+    #[test]
+    fn test_scope_expression_selects_unlifted_and_rejects_malformed() {
+        let mut graph = RPGraph::new("rust");
+        for name in ["parse", "render"] {
+            graph.insert_entity(make_raw(name, None, "fn x() {}").into_entity());
+        }
+        graph
+            .entities
+            .get_mut("src/lib.rs:render")
+            .unwrap()
+            .semantic_features = vec!["render output".to_string()];
+
+        let scope = resolve_scope(&graph, "glob:src/** AND kind:method").unwrap();
+        assert_eq!(scope.entity_ids, vec!["src/lib.rs:parse"]);
+        // A plain glob names its targets, so lifted ones are re-lifted.
+        assert_eq!(resolve_scope(&graph, "src/**").unwrap().entity_ids.len(), 2);
+
+        let err = resolve_scope(&graph, "kind:method AND").err().unwrap();
+        assert_eq!(err.position, "kind:method AND".len());
+    }
+
+    #[test]
+    fn test_called_names_after_multibyte_punctuation() {
+        let names: Vec<&str> = called_names("log(\"→done()\")").collect();
+        assert_eq!(names, vec!["log", "done"]);
+        let names: Vec<&str> = called_names("x = «parse(s)»").collect();
+        assert_eq!(names, vec!["parse"]);
+    }
+
     #[test]
     fn test_auto_lift_getter() {
         let raw = make_raw(
@@ -717,6 +1233,7 @@ mod tests {
                 strip_prefix: Vec::new(),
                 prefix_verb: std::collections::HashMap::new(),
             }],
+            boilerplate: Vec::new(),
             features: rpg_parser::paradigms::defs::FeatureFlags::default(),
             prompt_hints: rpg_parser::paradigms::defs::PromptHints::default(),
         };
@@ -1044,4 +1561,17 @@ mod tests {
             "non-matching entities return None (need LLM lifting → feature_source=\"llm\")"
         );
     }
+
+    #[test]
+    fn test_capped_batches_group_by_cap() {
+        let entities = ["a", "b", "c", "d", "e"]
+            .iter()
+            .zip([2, 1, 2, 2, 1])
+            .map(|(name, cap)| (make_raw(name, None, "fn x() {}"), cap))
+            .collect();
+        let (ordered, batches) = build_capped_batches(entities, 8000, &crate::tokens::Heuristic);
+        let names: Vec<&str> = ordered.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "c", "d", "b", "e"]);
+        assert_eq!(batches, vec![(0, 2), (2, 3), (3, 4), (4, 5)]);
+    }
 }
@@ -0,0 +1,580 @@
+//! Lifting batches and feature application shared by the interactive protocol
+//! (`get_entities_for_lifting` → `submit_lift_results`) and the offline
+//! `lift export-batches` / `lift import-results` commands. Both cut the same
+//! batch boundaries and apply features through the same matching and drift
+//! checks.
+
+use crate::lift::{self, AutoLift, AutoLiftEngine, LiftConfidence};
+use crate::tokens::TokenEstimator;
+use anyhow::Result;
+use rpg_core::config::{AreaSettings, EncodingConfig};
+use rpg_core::graph::{EntityDeps, EntityKind, RPGraph};
+use rpg_parser::entities::RawEntity;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Entities awaiting LLM lifting for one scope, cut into batches.
+#[derive(Debug, Default)]
+pub struct LiftQueue {
+    /// Entities the scope matched, before reading their source.
+    pub matched: usize,
+    /// Matched entities whose source was found.
+    pub collected: usize,
+    /// Entities that need LLM analysis, in batch order.
+    pub raw_entities: Vec<RawEntity>,
+    /// `(start, end)` ranges into `raw_entities`.
+    pub batch_ranges: Vec<(usize, usize)>,
+    /// Entities auto-lifted while building the queue.
+    pub auto_lifted: usize,
+    /// Of those, entities matched by a paradigm's boilerplate skip-list, by paradigm.
+    pub boilerplate: BTreeMap<String, usize>,
+    /// Entities auto-lifted with Review confidence, for the LLM to verify.
+    pub review_candidates: Vec<(String, Vec<String>)>,
+    /// Stale entities the auto-lifter wrote fresh features for.
+    pub auto_relifted_stale: Vec<String>,
+    /// The token estimator that sized the batches, as shown in batch headers.
+    pub estimator: String,
+}
+
+impl LiftQueue {
+    /// Entities in batch `index`, or `None` past the last batch.
+    pub fn batch(&self, index: usize) -> Option<&[RawEntity]> {
+        self.batch_ranges
+            .get(index)
+            .map(|&(start, end)| &self.raw_entities[start..end])
+    }
+}
+
+/// Resolve `scope`, auto-lift trivial entities in place, and batch the rest.
+///
+/// `stale` entities had their source change after they were lifted: they are
+/// re-lifted even though they have features, and the `*` scope queues them
+/// alongside unlifted entities. `estimator` sizes entities against
+/// `encoding.max_batch_tokens`. The caller persists the graph when
+/// `auto_lifted > 0`.
+pub fn build_lift_queue(
+    graph: &mut RPGraph,
+    scope: &str,
+    project_root: &Path,
+    encoding: &EncodingConfig,
+    stale: &HashSet<String>,
+    estimator: &dyn TokenEstimator,
+) -> Result<LiftQueue> {
+    graph.set_liftable_kinds(&encoding.liftable_kinds);
+    graph.metadata.max_node_features = encoding.max_node_features;
+    let mut resolved = lift::resolve_scope(graph, scope)?;
+
+    // The "*"/"all" scope and scope expressions keep only entities with *no*
+    // features, which leaves out stale ones (they still carry their old
+    // features). Other scope kinds don't filter by lifted state, so stale
+    // entities matching them are already present.
+    if lift::is_unlifted_scope(scope) {
+        let expr = rpg_core::scope::is_expression(scope.trim())
+            .then(|| rpg_core::scope::parse(scope.trim()))
+            .transpose()?;
+        let already: HashSet<&String> = resolved.entity_ids.iter().collect();
+        let to_add: Vec<String> = stale
+            .iter()
+            .filter(|id| !already.contains(id))
+            .filter(|id| {
+                graph.entities.get(*id).is_some_and(|e| {
+                    graph.is_liftable(e) && expr.as_ref().is_none_or(|expr| expr.matches(e))
+                })
+            })
+            .cloned()
+            .collect();
+        resolved.entity_ids.extend(to_add);
+    }
+
+    let mut queue = LiftQueue {
+        matched: resolved.entity_ids.len(),
+        estimator: estimator.describe(),
+        ..LiftQueue::default()
+    };
+    if queue.matched == 0 {
+        return Ok(queue);
+    }
+
+    let raw_entities = lift::collect_raw_entities(graph, &resolved, project_root)?;
+    queue.collected = raw_entities.len();
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(project_root);
+    let engine = AutoLiftEngine::new(&paradigm_defs, &graph.metadata.paradigms);
+    // Per-area settings decide auto-lift and each entity's batch cap.
+    let mut needs_llm = Vec::new();
+    for raw in raw_entities {
+        let raw_id = raw.id();
+        let is_stale = stale.contains(&raw_id);
+        // Otherwise, skip entities that already have curated features.
+        let already_lifted = !is_stale
+            && graph
+                .entities
+                .get(&raw_id)
+                .is_some_and(|e| !e.semantic_features.is_empty());
+        if already_lifted {
+            continue;
+        }
+        let area = encoding.for_area(
+            graph
+                .entities
+                .get(&raw_id)
+                .map_or("", |e| e.hierarchy_path.as_str()),
+        );
+        let batch_cap = area.batch_size.min(25);
+        if !area.auto_lift {
+            needs_llm.push((raw, batch_cap));
+            continue;
+        }
+        match engine.try_lift_detailed(&raw) {
+            Some(AutoLift {
+                features,
+                confidence: confidence @ (LiftConfidence::Accept | LiftConfidence::Review),
+                boilerplate,
+            }) => {
+                if let Some(entity) = graph.entities.get_mut(&raw_id) {
+                    entity.semantic_features = features.clone();
+                    entity.feature_source = Some("auto".to_string());
+                    entity.lifted_at = Some(chrono::Utc::now());
+                    queue.auto_lifted += 1;
+                    if let Some(paradigm) = boilerplate {
+                        *queue.boilerplate.entry(paradigm).or_default() += 1;
+                    }
+                    if is_stale {
+                        queue.auto_relifted_stale.push(raw_id.clone());
+                    }
+                }
+                // Medium confidence: applied, but flagged for review.
+                if confidence == LiftConfidence::Review {
+                    queue.review_candidates.push((raw_id, features));
+                }
+            }
+            Some(AutoLift {
+                confidence: LiftConfidence::Reject,
+                ..
+            })
+            | None => {
+                needs_llm.push((raw, batch_cap));
+            }
+        }
+    }
+
+    let (raw_entities, batch_ranges) =
+        lift::build_capped_batches(needs_llm, encoding.max_batch_tokens, estimator);
+    queue.raw_entities = raw_entities;
+    queue.batch_ranges = batch_ranges;
+    Ok(queue)
+}
+
+/// First line of a batch:
+/// `BATCH 1/3 (12 entities) | coverage: 40/95 (excludes 8 modules) | tokens: chars/4 heuristic`.
+pub fn format_batch_header(graph: &RPGraph, queue: &LiftQueue, index: usize, len: usize) -> String {
+    let (lifted, coverage_total) = graph.lifting_coverage();
+    let module_count = graph
+        .entities
+        .values()
+        .filter(|e| e.kind == EntityKind::Module)
+        .count();
+    format!(
+        "BATCH {}/{} ({} entities) | coverage: {}/{} (excludes {} modules) | tokens: {}\n",
+        index + 1,
+        queue.batch_ranges.len(),
+        len,
+        lifted,
+        coverage_total,
+        module_count,
+        queue.estimator,
+    )
+}
+
+/// What the first batch carries before its code: the auto-lift summary,
+/// review candidates, repo overview, lifting instructions, and
+/// framework-specific guidelines.
+pub fn format_batch_preamble(graph: &RPGraph, queue: &LiftQueue, project_name: &str) -> String {
+    let mut output = String::new();
+    if queue.auto_lifted > 0 {
+        output.push_str(&format!(
+            "AUTO-LIFTED: {} trivial entities (getters/setters/constructors). Override by re-submitting features.\n",
+            queue.auto_lifted,
+        ));
+        if !queue.boilerplate.is_empty() {
+            output.push_str(&format!(
+                "BOILERPLATE (canned features from paradigm skip-lists): {}\n",
+                format_boilerplate_counts(&queue.boilerplate)
+            ));
+        }
+        output.push('\n');
+    }
+    output.push_str(&format_review_candidates(&queue.review_candidates));
+    output.push_str(&lift::generate_repo_info(graph, project_name));
+    output.push_str("\n\n");
+    output.push_str(crate::semantic_lifting::SEMANTIC_PARSING_SYSTEM);
+    output.push('\n');
+
+    let lifting_hints = lift::paradigm_hints(&graph.metadata.paradigms, |h| &h.lifting);
+    if !lifting_hints.is_empty() {
+        output.push_str("\n## Framework-Specific Guidelines\n\n");
+        output.push_str(&lifting_hints);
+    }
+    output
+}
+
+/// Boilerplate counts by paradigm: `react 2, nextjs 1` (largest first).
+pub fn format_boilerplate_counts(counts: &BTreeMap<String, usize>) -> String {
+    let mut counts: Vec<(&String, &usize)> = counts.iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(paradigm, count)| format!("{} {}", paradigm, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Format the review candidates section. Empty when there are none.
+pub fn format_review_candidates(candidates: &[(String, Vec<String>)]) -> String {
+    if candidates.is_empty() {
+        return String::new();
+    }
+    let mut output = format!(
+        "## REVIEW CANDIDATES ({} entities)\n\nThese entities were auto-lifted but have moderate complexity. \
+         Verify the features below are correct. To override, include the entity in your `submit_lift_results` call.\n\n",
+        candidates.len(),
+    );
+    for (eid, features) in candidates {
+        output.push_str(&format!("- `{}`: {}\n", eid, features.join(", ")));
+    }
+    output.push('\n');
+    output
+}
+
+/// The `## Code` section: each entity's condensed source under a
+/// `### <id> (<Kind>) @decorators [attributes]` header, whose ID is the key to
+/// submit its features under, preceded by its doc comment and followed by its dependency line.
+pub fn format_batch_code(graph: &RPGraph, batch: &[RawEntity]) -> String {
+    let mut output = String::from("\n## Code\n\n");
+    // Signature plus key logic is enough for feature extraction and keeps
+    // large entities from overflowing the context.
+    let known_names = lift::entity_names(graph);
+    for entity in batch {
+        let truncated =
+            lift::condense_source(&entity.source_text, lift::LIFT_SOURCE_LINES, &known_names);
+        let decorators = entity.decorator_summary();
+        let decorators = if decorators.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", decorators)
+        };
+        let attributes = entity.attribute_summary();
+        let attributes = if attributes.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", attributes)
+        };
+        output.push_str(&format!(
+            "### {} ({:?}){}{}\n{}```\n{}\n```\n",
+            entity.id(),
+            entity.kind,
+            decorators,
+            attributes,
+            lift::format_doc_block(entity.doc_comment.as_deref()),
+            truncated,
+        ));
+        // Append compact dependency context when available
+        if let Some(graph_entity) = graph.entities.get(&entity.id()) {
+            let dep_line = format_dep_context(&graph_entity.deps);
+            if !dep_line.is_empty() {
+                output.push_str(&dep_line);
+                output.push('\n');
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Format a compact dependency context line for an entity.
+/// Returns empty string if the entity has no deps.
+/// Format: `Deps: Called by: a, b | Calls: c, d | Inherits: e`
+/// (see [`EntityDeps::summary`]).
+pub fn format_dep_context(deps: &EntityDeps) -> String {
+    let summary = deps.summary();
+    if summary.is_empty() {
+        String::new()
+    } else {
+        format!("Deps: {}", summary)
+    }
+}
+
+/// Outcome of [`apply_features`].
+#[derive(Debug, Default)]
+pub struct AppliedFeatures {
+    /// Entities whose features were written.
+    pub updated: usize,
+    /// Keys that matched no entity.
+    pub unmatched: Vec<String>,
+    /// One line per re-lifted entity whose features changed.
+    pub drift_reports: Vec<String>,
+    /// Re-lifted past the auto threshold; they need routing.
+    pub drifted: Vec<String>,
+    /// Re-lifted between the thresholds, with their drift; the agent judges.
+    pub borderline: Vec<(String, f64)>,
+    /// Lifted for the first time; candidates for semantic routing.
+    pub newly_lifted: Vec<String>,
+    /// Canonical entity ID → features written.
+    pub resolved: HashMap<String, Vec<String>>,
+    /// Feature sets copied across too many entities. Their entities are
+    /// marked `llm-review` and left out of `drifted`, `borderline`, and
+    /// `newly_lifted`, so they are not routed.
+    pub copied: Vec<crate::critic::CopiedFeatureSet>,
+}
+
+/// Write LLM features onto the graph. Keys are entity IDs or `file:name`,
+/// which matches every entity of that name in the file. Entities that
+/// already had features are checked for drift against their area's
+/// thresholds (paper Algorithm 3). Empty feature lists are skipped.
+/// Entities sharing a copied feature set are marked for review.
+pub fn apply_features(
+    graph: &mut RPGraph,
+    features: &HashMap<String, Vec<String>>,
+    encoding: &EncodingConfig,
+) -> AppliedFeatures {
+    let mut applied = AppliedFeatures::default();
+    // Key order, so reports list entities the same way on every call
+    let mut keys: Vec<&String> = features.keys().collect();
+    keys.sort();
+    for key in keys {
+        let feats = &features[key];
+        if feats.is_empty() {
+            continue;
+        }
+
+        // Direct ID match first, then file:name scan (all matches)
+        let entity_ids: Vec<String> = if graph.entities.contains_key(key) {
+            vec![key.clone()]
+        } else if let Some((file_part, name_part)) = key.rsplit_once(':') {
+            graph
+                .entities
+                .iter()
+                .filter(|(_, e)| {
+                    e.name == name_part && e.file.to_string_lossy().as_ref() == file_part
+                })
+                .map(|(id, _)| id.clone())
+                .collect()
+        } else {
+            vec![]
+        };
+
+        if entity_ids.is_empty() {
+            applied.unmatched.push(key.clone());
+            continue;
+        }
+        for eid in &entity_ids {
+            // Three-zone drift detection:
+            // < drift_ignore: minor edit, in-place update
+            // drift_ignore..drift_auto: borderline, ask agent to judge
+            // > drift_auto: clear drift, auto-route
+            let (old_feats, area) = graph
+                .entities
+                .get(eid)
+                .map(|e| {
+                    (
+                        e.semantic_features.clone(),
+                        encoding.for_area(&e.hierarchy_path),
+                    )
+                })
+                .unwrap_or_else(|| (Vec::new(), encoding.for_area("")));
+
+            if !old_feats.is_empty() {
+                let drift = crate::evolution::compute_drift(&old_feats, feats);
+                let limits = format_drift_limits(&area);
+                if drift > area.drift_auto_threshold {
+                    applied.drift_reports.push(format!(
+                        "  {} drifted ({:.2}; {}) — routing required",
+                        eid, drift, limits,
+                    ));
+                    applied.drifted.push(eid.clone());
+                } else if drift >= area.drift_ignore_threshold {
+                    applied.drift_reports.push(format!(
+                        "  {} borderline drift ({:.2}; {}) — agent review requested",
+                        eid, drift, limits,
+                    ));
+                    applied.borderline.push((eid.clone(), drift));
+                } else if drift > 0.0 {
+                    applied.drift_reports.push(format!(
+                        "  {} updated ({:.2} drift, below threshold; {})",
+                        eid, drift, limits,
+                    ));
+                }
+            } else {
+                applied.newly_lifted.push(eid.clone());
+            }
+
+            if let Some(entity) = graph.entities.get_mut(eid) {
+                entity.semantic_features = feats.clone();
+                entity.feature_source = Some("llm".to_string());
+                entity.lifted_at = Some(chrono::Utc::now());
+                applied.resolved.insert(eid.clone(), feats.clone());
+                applied.updated += 1;
+            }
+        }
+    }
+
+    applied.copied = crate::critic::find_copied_feature_sets(
+        &applied.resolved,
+        encoding.copied_features_max_entities,
+        encoding.copied_features_similarity,
+    );
+    let flagged: HashSet<&String> = applied
+        .copied
+        .iter()
+        .flat_map(|group| &group.entity_ids)
+        .collect();
+    if !flagged.is_empty() {
+        for id in &flagged {
+            if let Some(entity) = graph.entities.get_mut(*id) {
+                entity.feature_source = Some("llm-review".to_string());
+            }
+        }
+        applied.drifted.retain(|id| !flagged.contains(id));
+        applied.borderline.retain(|(id, _)| !flagged.contains(id));
+        applied.newly_lifted.retain(|id| !flagged.contains(id));
+    }
+    applied
+}
+
+/// Drift thresholds applied to an entity, for drift report lines:
+/// `ignore 0.30, auto 0.70`, plus the `per_area` key when one matched.
+pub fn format_drift_limits(area: &AreaSettings) -> String {
+    let limits = format!(
+        "ignore {:.2}, auto {:.2}",
+        area.drift_ignore_threshold, area.drift_auto_threshold
+    );
+    match &area.area {
+        Some(prefix) => format!("{} for {}", limits, prefix),
+        None => limits,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_in_batch_0_output() {
+        let candidates = vec![
+            (
+                "src/server.rs:Server::get_name".to_string(),
+                vec!["return name".to_string()],
+            ),
+            (
+                "src/config.rs:Config::get_value".to_string(),
+                vec!["return value".to_string()],
+            ),
+        ];
+        let output = format_review_candidates(&candidates);
+        assert!(output.contains("## REVIEW CANDIDATES (2 entities)"));
+        assert!(output.contains("src/server.rs:Server::get_name"));
+        assert!(output.contains("return name"));
+        assert!(output.contains("submit_lift_results"));
+    }
+
+    #[test]
+    fn test_format_batch_code_puts_doc_above_code() {
+        let entity = RawEntity {
+            name: "parse".to_string(),
+            kind: EntityKind::Function,
+            file: "src/config.rs".into(),
+            line_start: 3,
+            line_end: 5,
+            parent_class: None,
+            source_text: "fn parse(path: &str) -> Config {\n    load(path)\n}".to_string(),
+            signature: None,
+            attributes: std::collections::BTreeMap::new(),
+            doc_comment: Some("Parse the config file.\n\nFalls back to defaults.".to_string()),
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        };
+        let output = format_batch_code(&RPGraph::new("rust"), &[entity]);
+        assert!(
+            output.contains(
+                "### src/config.rs:parse (Function)\nDoc:\n> Parse the config file.\n>\n> Falls back to defaults.\n```\nfn parse"
+            ),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_format_batch_code_shows_decorators_in_header() {
+        let entity = RawEntity {
+            name: "list_users".to_string(),
+            kind: EntityKind::Function,
+            file: "app.py".into(),
+            line_start: 1,
+            line_end: 4,
+            parent_class: None,
+            source_text:
+                "@app.route(\"/users\")\n@login_required\ndef list_users():\n    return users()"
+                    .to_string(),
+            signature: None,
+            attributes: std::collections::BTreeMap::from([(
+                "route".to_string(),
+                "GET /users".to_string(),
+            )]),
+            doc_comment: None,
+            decorators: vec![
+                "app.route(\"/users\")".to_string(),
+                "login_required".to_string(),
+            ],
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        };
+        let output = format_batch_code(&RPGraph::new("python"), &[entity]);
+        assert!(
+            output.contains(
+                "### app.py:list_users (Function) @app.route(\"/users\") @login_required [route: GET /users]\n```"
+            ),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn test_review_candidates_empty() {
+        let output = format_review_candidates(&[]);
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_format_dep_context_with_deps() {
+        let deps = EntityDeps {
+            invoked_by: vec!["main".to_string(), "test".to_string()],
+            invokes: vec!["helper".to_string()],
+            ..Default::default()
+        };
+        let result = format_dep_context(&deps);
+        assert!(result.starts_with("Deps:"));
+        assert!(result.contains("Called by: main, test"));
+        assert!(result.contains("Calls: helper"));
+    }
+
+    #[test]
+    fn test_format_dep_context_empty() {
+        let deps = EntityDeps::default();
+        let result = format_dep_context(&deps);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_format_dep_context_caps_at_five() {
+        let deps = EntityDeps {
+            invokes: (1..=10).map(|i| format!("fn{}", i)).collect(),
+            ..Default::default()
+        };
+        let result = format_dep_context(&deps);
+        assert!(result.contains("fn5"));
+        assert!(!result.contains("fn6"));
+    }
+}
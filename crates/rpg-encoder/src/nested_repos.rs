@@ -0,0 +1,150 @@
+//! Git submodules and other nested repositories (`encoding.include_submodules`).
+//!
+//! A directory with its own `.git` belongs to another repository. By default
+//! file collection skips it, and it is only listed in `metadata.nested_repos`
+//! with the commit the superproject pins (or, for a repository that isn't a
+//! submodule, its checked-out HEAD). With `include_submodules = true` its
+//! files are indexed under their paths from the project root, and change
+//! detection asks the nested repository what changed since the commit
+//! recorded for it (see [`crate::evolution::detect_workdir_changes`]).
+//!
+//! A submodule whose checkout is missing is listed with `checked_out = false`;
+//! change detection warns about it and moves on.
+
+use rpg_core::config::EncodingConfig;
+use rpg_core::graph::{NestedRepo, normalize_path};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Whether `dir` is the root of a git repository (`.git` directory, or the
+/// `.git` file a submodule checkout has).
+pub fn is_repo_root(dir: &Path) -> bool {
+    dir.join(".git").exists()
+}
+
+fn walk_builder(project_root: &Path) -> ignore::WalkBuilder {
+    let mut builder = ignore::WalkBuilder::new(project_root);
+    builder
+        .hidden(true)
+        .git_ignore(true)
+        .add_custom_ignore_filename(".rpgignore");
+    builder
+}
+
+/// Whether a walk entry is a nested repository's root directory.
+fn is_nested_root(entry: &ignore::DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_type().is_some_and(|t| t.is_dir()) && is_repo_root(entry.path())
+}
+
+/// Walker over the files to index: hidden paths, `.gitignore`, and
+/// `.rpgignore` are skipped, and so are nested repositories unless
+/// `encoding.include_submodules` is set.
+pub fn source_walker(project_root: &Path, encoding: &EncodingConfig) -> ignore::Walk {
+    let include = encoding.include_submodules;
+    walk_builder(project_root)
+        .filter_entry(move |entry| include || !is_nested_root(entry))
+        .build()
+}
+
+/// The nested repositories under `project_root`, by path: checked-out
+/// repositories found by the same walk as [`source_walker`], plus
+/// submodules the superproject declares whose checkout is missing.
+pub fn find_nested_repos(project_root: &Path, encoding: &EncodingConfig) -> Vec<NestedRepo> {
+    let found = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&found);
+    let walker = walk_builder(project_root)
+        .filter_entry(move |entry| {
+            if !is_nested_root(entry) {
+                return true;
+            }
+            if let Ok(mut roots) = sink.lock() {
+                roots.push(entry.path().to_path_buf());
+            }
+            false
+        })
+        .build();
+    walker.for_each(drop);
+
+    let pins = submodule_pins(project_root);
+    let mut repos: BTreeMap<String, NestedRepo> = BTreeMap::new();
+    let roots = found.lock().map(|roots| roots.clone()).unwrap_or_default();
+    for root in roots {
+        let rel = root.strip_prefix(project_root).unwrap_or(&root);
+        let path = normalize_path(rel);
+        repos.insert(
+            path.clone(),
+            NestedRepo {
+                path,
+                commit: None,
+                indexed: encoding.include_submodules,
+                checked_out: true,
+            },
+        );
+    }
+    for path in pins.keys() {
+        repos.entry(path.clone()).or_insert_with(|| NestedRepo {
+            path: path.clone(),
+            commit: None,
+            indexed: encoding.include_submodules,
+            checked_out: false,
+        });
+    }
+
+    let mut repos: Vec<NestedRepo> = repos.into_values().collect();
+    refresh_with(project_root, &mut repos, &pins);
+    repos
+}
+
+/// Re-read each repository's checkout state and recorded commit, after an
+/// update has brought the graph in line with the working tree.
+pub fn refresh(project_root: &Path, repos: &mut [NestedRepo]) {
+    if repos.is_empty() {
+        return;
+    }
+    let pins = submodule_pins(project_root);
+    refresh_with(project_root, repos, &pins);
+}
+
+fn refresh_with(
+    project_root: &Path,
+    repos: &mut [NestedRepo],
+    pins: &BTreeMap<String, Option<String>>,
+) {
+    for repo in repos.iter_mut() {
+        let dir = project_root.join(&repo.path);
+        repo.checked_out = is_repo_root(&dir);
+        let pin = pins.get(&repo.path);
+        repo.commit = match pin {
+            Some(pinned) if !repo.indexed => pinned.clone(),
+            _ => head_commit(&dir)
+                .or_else(|| repo.commit.take())
+                .or_else(|| pin.cloned().flatten()),
+        };
+    }
+}
+
+/// Submodule path → the commit the superproject's HEAD pins it to.
+fn submodule_pins(project_root: &Path) -> BTreeMap<String, Option<String>> {
+    let Ok(repo) = git2::Repository::open(project_root) else {
+        return BTreeMap::new();
+    };
+    let Ok(submodules) = repo.submodules() else {
+        return BTreeMap::new();
+    };
+    submodules
+        .iter()
+        .map(|sm| {
+            (
+                normalize_path(sm.path()),
+                sm.head_id().map(|oid| oid.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn head_commit(dir: &Path) -> Option<String> {
+    let repo = git2::Repository::open(dir).ok()?;
+    let head = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(head.id().to_string())
+}
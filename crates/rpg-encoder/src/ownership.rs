@@ -0,0 +1,87 @@
+//! Code ownership from CODEOWNERS.
+//!
+//! Each entity gets the owners of its file, resolved with GitHub's CODEOWNERS
+//! rules (gitignore-style patterns, last matching rule wins). Hierarchy nodes
+//! then roll those up in [`RPGraph::aggregate_hierarchy_features`], so every
+//! area and category lists its top owners by entity count.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rpg_core::graph::RPGraph;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Where CODEOWNERS is looked up, in GitHub's precedence order.
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Parsed CODEOWNERS rules.
+pub struct CodeOwners {
+    rules: Vec<(Gitignore, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Load the project's CODEOWNERS file, if it has one.
+    pub fn load(project_root: &Path) -> Option<Self> {
+        CODEOWNERS_PATHS.iter().find_map(|rel| {
+            let content = std::fs::read_to_string(project_root.join(rel)).ok()?;
+            Some(Self::parse(project_root, &content))
+        })
+    }
+
+    /// Parse CODEOWNERS content. A rule without owners unsets ownership for the
+    /// paths it matches; unparseable patterns are skipped.
+    pub fn parse(project_root: &Path, content: &str) -> Self {
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let Some(pattern) = parts.next() else {
+                continue;
+            };
+            let mut builder = GitignoreBuilder::new(project_root);
+            if builder.add_line(None, pattern).is_err() {
+                continue;
+            }
+            let Ok(matcher) = builder.build() else {
+                continue;
+            };
+            rules.push((matcher, parts.map(String::from).collect()));
+        }
+        Self { rules }
+    }
+
+    /// Owners of a repo-relative path: those of the last matching rule.
+    pub fn owners_for(&self, path: &Path) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.matched_path_or_any_parents(path, false).is_ignore())
+            .map_or(&[], |(_, owners)| owners.as_slice())
+    }
+}
+
+/// Set every entity's owners from the project's CODEOWNERS file (clearing
+/// them when there is none). Returns the number of entities with an owner.
+///
+/// Call before [`RPGraph::aggregate_hierarchy_features`] so hierarchy nodes
+/// pick up the new owners.
+pub fn assign_owners(graph: &mut RPGraph, project_root: &Path) -> usize {
+    let codeowners = CodeOwners::load(project_root);
+    let mut by_file: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    let mut owned = 0;
+    for entity in graph.entities.values_mut() {
+        entity.owners = match codeowners {
+            Some(ref codeowners) => by_file
+                .entry(entity.file.clone())
+                .or_insert_with(|| codeowners.owners_for(&entity.file).to_vec())
+                .clone(),
+            None => Vec::new(),
+        };
+        if !entity.owners.is_empty() {
+            owned += 1;
+        }
+    }
+    owned
+}
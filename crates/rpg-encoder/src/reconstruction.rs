@@ -3,9 +3,11 @@
 //! The paper's reconstruction setting executes repository units in dependency-safe
 //! topological order, then groups adjacent units into semantically coherent batches.
 
+use anyhow::{Context, Result};
 use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
 
 /// Reconstruction scheduler options.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -186,6 +188,99 @@ pub fn schedule_reconstruction(
     }
 }
 
+/// Reconstruction progress, persisted in `.rpg/reconstruction_state.json` so a
+/// crashed session can resume where it stopped. The plan is a snapshot taken
+/// when the state was created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconstructionState {
+    pub options: ReconstructionOptions,
+    pub plan: ReconstructionPlan,
+    /// Entities reported as reconstructed.
+    pub done: BTreeSet<String>,
+}
+
+impl ReconstructionState {
+    /// Fresh state with a newly scheduled plan and nothing done.
+    pub fn new(graph: &RPGraph, options: ReconstructionOptions) -> Self {
+        Self {
+            options,
+            plan: schedule_reconstruction(graph, options),
+            done: BTreeSet::new(),
+        }
+    }
+
+    /// Load saved state; `None` if there is none.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = rpg_core::storage::reconstruction_state_file(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let state = serde_json::from_str(&data)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        Ok(Some(state))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = rpg_core::storage::reconstruction_state_file(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Batch by its 1-based `batch_index`.
+    pub fn batch(&self, batch_index: usize) -> Option<&ReconstructionBatch> {
+        self.plan
+            .batches
+            .iter()
+            .find(|b| b.batch_index == batch_index)
+    }
+
+    /// First batch with entities not yet done.
+    pub fn next_batch(&self) -> Option<&ReconstructionBatch> {
+        self.plan.batches.iter().find(|b| !self.is_batch_done(b))
+    }
+
+    pub fn is_batch_done(&self, batch: &ReconstructionBatch) -> bool {
+        batch.entity_ids.iter().all(|id| self.done.contains(id))
+    }
+
+    /// Mark entities done. Returns the IDs that are not part of the plan,
+    /// which are left out.
+    pub fn mark_done(&mut self, entity_ids: &[String]) -> Vec<String> {
+        let planned: HashSet<&str> = self
+            .plan
+            .topological_order
+            .iter()
+            .map(String::as_str)
+            .collect();
+        let mut unknown = Vec::new();
+        for id in entity_ids {
+            if planned.contains(id.as_str()) {
+                self.done.insert(id.clone());
+            } else {
+                unknown.push(id.clone());
+            }
+        }
+        unknown
+    }
+}
+
+/// Entities `entity_id` depends on (targets of its dependency edges), sorted.
+pub fn prerequisites(graph: &RPGraph, entity_id: &str) -> Vec<String> {
+    let prereqs: BTreeSet<String> = graph
+        .edges_for(entity_id)
+        .into_iter()
+        .filter(|e| is_dependency_edge(e.kind) && e.source == entity_id && e.target != entity_id)
+        .filter(|e| graph.entities.contains_key(&e.target))
+        .map(|e| e.target.clone())
+        .collect();
+    prereqs.into_iter().collect()
+}
+
 fn is_dependency_edge(kind: EdgeKind) -> bool {
     matches!(
         kind,
@@ -4,8 +4,9 @@
 //! for LLM output parsing. This is more resilient than JSON — partial corruption only
 //! loses individual lines, not the entire response.
 
+use rpg_core::config::FeatureNormalizationConfig;
 use rpg_core::graph::RPGraph;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// The semantic parsing system prompt (from Appendix A.1.1 of the paper).
 /// Uses pipe-delimited line format instead of JSON for LLM-resilient parsing.
@@ -86,17 +87,337 @@ pub fn parse_line_features(text: &str) -> HashMap<String, Vec<String>> {
     features
 }
 
+/// What [`normalize_features`] changed beyond trimming and deduplication.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NormalizationReport {
+    /// `(entity key, original feature, parts)` for each compound feature split.
+    pub split: Vec<(String, String, Vec<String>)>,
+    /// `(entity key, original feature)` for each feature cut to the word limit.
+    pub truncated: Vec<(String, String)>,
+    /// `(entity key, feature)` for each feature dropped by the per-entity limit.
+    pub dropped: Vec<(String, String)>,
+}
+
+impl NormalizationReport {
+    pub fn is_empty(&self) -> bool {
+        self.split.is_empty() && self.truncated.is_empty() && self.dropped.is_empty()
+    }
+}
+
 /// Normalize semantic features per paper's post-processing:
-/// trim whitespace, lowercase, deduplicate.
-pub fn normalize_features(features: &mut HashMap<String, Vec<String>>) {
-    for feats in features.values_mut() {
-        for f in feats.iter_mut() {
-            *f = f.trim().to_lowercase();
+/// trim whitespace, lowercase, deduplicate. Compound features are split and
+/// limits applied as `config` asks.
+pub fn normalize_features(
+    features: &mut HashMap<String, Vec<String>>,
+    config: &FeatureNormalizationConfig,
+) -> NormalizationReport {
+    let mut report = NormalizationReport::default();
+    let mut keys: Vec<&String> = features.keys().collect();
+    keys.sort();
+    let keys: Vec<String> = keys.into_iter().cloned().collect();
+    for key in keys {
+        let Some(feats) = features.get_mut(&key) else {
+            continue;
+        };
+        let mut normalized = Vec::with_capacity(feats.len());
+        for f in feats.iter() {
+            let f = f.trim().to_lowercase();
+            match split_compound_feature(&f).filter(|_| config.split_compound) {
+                Some(parts) => {
+                    normalized.extend(parts.iter().cloned());
+                    report.split.push((key.clone(), f, parts));
+                }
+                None => normalized.push(f),
+            }
+        }
+        if let Some(max_words) = config.max_words_per_feature {
+            for f in &mut normalized {
+                if f.split_whitespace().count() > max_words {
+                    let cut = f.split_whitespace().take(max_words).collect::<Vec<_>>();
+                    report.truncated.push((key.clone(), f.clone()));
+                    *f = cut.join(" ");
+                }
+            }
+        }
+        normalized.sort();
+        normalized.dedup();
+        normalized.retain(|f| !f.is_empty());
+        if let Some(max) = config.max_features_per_entity
+            && normalized.len() > max
+        {
+            let kept = most_distinct(&normalized, max);
+            for (i, f) in normalized.iter().enumerate() {
+                if !kept.contains(&i) {
+                    report.dropped.push((key.clone(), f.clone()));
+                }
+            }
+            normalized = kept.into_iter().map(|i| normalized[i].clone()).collect();
+        }
+        *feats = normalized;
+    }
+    report
+}
+
+/// Words that open a verb-object phrase even though they are not in
+/// [`FEATURE_VERBS`]: `-ize`, `-ise`, and `-ify` verbs.
+const VERB_SUFFIXES: &[&str] = &["ize", "ise", "ify"];
+
+/// Verbs that commonly open a feature. A compound is only split when every
+/// part starts with one, so `"compute mean and standard deviation"` stays whole.
+const FEATURE_VERBS: &[&str] = &[
+    "accept",
+    "add",
+    "aggregate",
+    "allocate",
+    "append",
+    "apply",
+    "assign",
+    "authenticate",
+    "authorize",
+    "build",
+    "cache",
+    "calculate",
+    "call",
+    "check",
+    "clean",
+    "clear",
+    "clone",
+    "close",
+    "collect",
+    "compare",
+    "compile",
+    "compose",
+    "compress",
+    "compute",
+    "configure",
+    "connect",
+    "construct",
+    "convert",
+    "copy",
+    "count",
+    "create",
+    "decode",
+    "decompress",
+    "decrypt",
+    "delete",
+    "deserialize",
+    "destroy",
+    "detect",
+    "dispatch",
+    "download",
+    "emit",
+    "encode",
+    "encrypt",
+    "ensure",
+    "enforce",
+    "evaluate",
+    "execute",
+    "expand",
+    "export",
+    "expose",
+    "extract",
+    "fetch",
+    "filter",
+    "find",
+    "flush",
+    "format",
+    "generate",
+    "get",
+    "group",
+    "handle",
+    "hash",
+    "import",
+    "index",
+    "initialize",
+    "insert",
+    "invoke",
+    "list",
+    "listen",
+    "load",
+    "lock",
+    "log",
+    "lookup",
+    "manage",
+    "map",
+    "mark",
+    "match",
+    "measure",
+    "merge",
+    "notify",
+    "open",
+    "parse",
+    "persist",
+    "prepare",
+    "print",
+    "process",
+    "publish",
+    "query",
+    "read",
+    "receive",
+    "record",
+    "register",
+    "reject",
+    "release",
+    "remove",
+    "render",
+    "report",
+    "reset",
+    "resolve",
+    "retry",
+    "return",
+    "run",
+    "save",
+    "scan",
+    "schedule",
+    "search",
+    "select",
+    "send",
+    "serialize",
+    "serve",
+    "set",
+    "sort",
+    "spawn",
+    "split",
+    "start",
+    "stop",
+    "store",
+    "stream",
+    "subscribe",
+    "track",
+    "transform",
+    "unlock",
+    "update",
+    "upload",
+    "validate",
+    "verify",
+    "wait",
+    "watch",
+    "wrap",
+    "write",
+];
+
+/// Split `feature` on `;` and `" and "` outside brackets when every part is
+/// a verb-object phrase of at least two words. `None` leaves it whole.
+fn split_compound_feature(feature: &str) -> Option<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let bytes = feature.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b';' if depth == 0 => {
+                parts.push(&feature[start..i]);
+                start = i + 1;
+            }
+            b' ' if depth == 0 && feature[i..].starts_with(" and ") => {
+                parts.push(&feature[start..i]);
+                i += " and ".len();
+                start = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(&feature[start..]);
+    let parts: Vec<String> = parts
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    (parts.len() > 1 && parts.iter().all(|p| is_verb_object(p))).then_some(parts)
+}
+
+fn is_verb_object(phrase: &str) -> bool {
+    let mut words = phrase.split_whitespace();
+    let Some(first) = words.next() else {
+        return false;
+    };
+    words.next().is_some() && is_verb(first)
+}
+
+/// Whether a lowercase `word` reads as a verb: one of [`FEATURE_VERBS`] or an
+/// `-ize`/`-ise`/`-ify` word.
+pub(crate) fn is_verb(word: &str) -> bool {
+    FEATURE_VERBS.contains(&word)
+        || VERB_SUFFIXES
+            .iter()
+            .any(|s| word.len() > s.len() + 2 && word.ends_with(s))
+}
+
+/// Indices of the `max` features that overlap least with each other, in
+/// their original order. Picks greedily: each step takes the feature whose
+/// highest word overlap with those already kept is lowest, breaking ties by
+/// lowest total overlap with all features, then by position.
+fn most_distinct(features: &[String], max: usize) -> Vec<usize> {
+    let words: Vec<BTreeSet<&str>> = features
+        .iter()
+        .map(|f| f.split_whitespace().collect())
+        .collect();
+    let overlap = |a: usize, b: usize| -> f64 {
+        let union = words[a].union(&words[b]).count();
+        if union == 0 {
+            return 0.0;
+        }
+        words[a].intersection(&words[b]).count() as f64 / union as f64
+    };
+    let total: Vec<f64> = (0..features.len())
+        .map(|i| {
+            (0..features.len())
+                .filter(|&j| j != i)
+                .map(|j| overlap(i, j))
+                .sum()
+        })
+        .collect();
+    let mut kept: Vec<usize> = Vec::with_capacity(max);
+    while kept.len() < max.min(features.len()) {
+        let next = (0..features.len())
+            .filter(|i| !kept.contains(i))
+            .map(|i| {
+                let nearest = kept.iter().map(|&k| overlap(i, k)).fold(0.0, f64::max);
+                (i, nearest, total[i])
+            })
+            .min_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then(a.2.total_cmp(&b.2))
+                    .then(a.0.cmp(&b.0))
+            });
+        match next {
+            Some((i, _, _)) => kept.push(i),
+            None => break,
         }
-        feats.sort();
-        feats.dedup();
-        feats.retain(|f| !f.is_empty());
     }
+    kept.sort_unstable();
+    kept
+}
+
+/// Report section for a [`NormalizationReport`]; empty when nothing changed.
+pub fn format_normalization_report(report: &NormalizationReport) -> String {
+    if report.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("\n## NORMALIZED FEATURES\n\n");
+    for (key, original, parts) in &report.split {
+        out.push_str(&format!(
+            "- split `{}`: \"{}\" -> [{}]\n",
+            key,
+            original,
+            parts.join(", ")
+        ));
+    }
+    for (key, original) in &report.truncated {
+        out.push_str(&format!("- truncated `{}`: \"{}\"\n", key, original));
+    }
+    for (key, feature) in &report.dropped {
+        out.push_str(&format!(
+            "- dropped `{}`: \"{}\" (over max_features_per_entity)\n",
+            key, feature
+        ));
+    }
+    out
 }
 
 /// Apply extracted features to entities, matching by name.
@@ -119,8 +440,8 @@ pub fn apply_features(
 pub fn aggregate_module_features(graph: &mut RPGraph) -> usize {
     let module_data: Vec<(String, Vec<String>)> = graph
         .file_index
-        .iter()
-        .filter_map(|(_, ids)| {
+        .values()
+        .filter_map(|ids| {
             let module_id = ids.iter().find(|id| {
                 graph
                     .entities
@@ -244,7 +565,7 @@ mod tests {
                 "parse config".to_string(),
             ],
         );
-        normalize_features(&mut features);
+        normalize_features(&mut features, &FeatureNormalizationConfig::default());
         let feats = features.get("foo").unwrap();
         assert_eq!(
             feats.len(),
@@ -254,4 +575,132 @@ mod tests {
         assert!(feats.contains(&"parse config".to_string()));
         assert!(feats.contains(&"validate input".to_string()));
     }
+
+    #[test]
+    fn test_split_compound_feature_table() {
+        let cases: &[(&str, Option<&[&str]>)] = &[
+            (
+                "parse config and validate input",
+                Some(&["parse config", "validate input"]),
+            ),
+            (
+                "load users; cache sessions; emit metrics",
+                Some(&["load users", "cache sessions", "emit metrics"]),
+            ),
+            (
+                "serialize payload and normalize headers",
+                Some(&["serialize payload", "normalize headers"]),
+            ),
+            // A part that is not a verb-object phrase keeps the feature whole.
+            ("compute mean and standard deviation", None),
+            ("read and write files", None),
+            ("load users and their roles", None),
+            // Separators inside brackets are not top-level.
+            ("match pairs (key and value)", None),
+            ("validate input", None),
+        ];
+        for (input, expected) in cases {
+            let expected = expected.map(|parts| parts.iter().map(|p| p.to_string()).collect());
+            assert_eq!(split_compound_feature(input), expected, "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_features_table() {
+        // (name, config, input, expected, split, truncated, dropped)
+        type Case = (
+            &'static str,
+            FeatureNormalizationConfig,
+            &'static [&'static str],
+            &'static [&'static str],
+            usize,
+            usize,
+            usize,
+        );
+        let config = |split, max_features, max_words| FeatureNormalizationConfig {
+            split_compound: split,
+            max_features_per_entity: max_features,
+            max_words_per_feature: max_words,
+        };
+        let cases: &[Case] = &[
+            (
+                "split on by default",
+                FeatureNormalizationConfig::default(),
+                &["Parse Config and Validate Input", "validate input"],
+                &["parse config", "validate input"],
+                1,
+                0,
+                0,
+            ),
+            (
+                "split disabled",
+                config(false, None, None),
+                &["parse config and validate input"],
+                &["parse config and validate input"],
+                0,
+                0,
+                0,
+            ),
+            (
+                "word limit cuts long features",
+                config(true, None, Some(3)),
+                &["resolve import paths against the workspace root"],
+                &["resolve import paths"],
+                0,
+                1,
+                0,
+            ),
+            (
+                "feature limit keeps the most distinct",
+                config(true, Some(2), None),
+                &[
+                    "parse config file",
+                    "parse config files",
+                    "send http request",
+                ],
+                &["parse config file", "send http request"],
+                0,
+                0,
+                1,
+            ),
+            (
+                "under the limits nothing changes",
+                config(true, Some(5), Some(10)),
+                &["load users", "cache sessions"],
+                &["cache sessions", "load users"],
+                0,
+                0,
+                0,
+            ),
+        ];
+        for (name, cfg, input, expected, split, truncated, dropped) in cases {
+            let mut features = HashMap::from([(
+                "src/lib.rs:f".to_string(),
+                input.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+            )]);
+            let report = normalize_features(&mut features, cfg);
+            assert_eq!(features["src/lib.rs:f"], *expected, "{name}");
+            assert_eq!(report.split.len(), *split, "{name}: split");
+            assert_eq!(report.truncated.len(), *truncated, "{name}: truncated");
+            assert_eq!(report.dropped.len(), *dropped, "{name}: dropped");
+        }
+    }
+
+    #[test]
+    fn test_format_normalization_report() {
+        assert!(format_normalization_report(&NormalizationReport::default()).is_empty());
+        let report = NormalizationReport {
+            split: vec![(
+                "a".to_string(),
+                "parse config and validate input".to_string(),
+                vec!["parse config".to_string(), "validate input".to_string()],
+            )],
+            truncated: Vec::new(),
+            dropped: vec![("a".to_string(), "parse config files".to_string())],
+        };
+        let out = format_normalization_report(&report);
+        assert!(out.contains("## NORMALIZED FEATURES"));
+        assert!(out.contains("-> [parse config, validate input]"));
+        assert!(out.contains("dropped `a`: \"parse config files\""));
+    }
 }
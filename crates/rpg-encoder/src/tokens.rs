@@ -0,0 +1,91 @@
+//! Token estimator selection (`[tokens]` in `.rpg/config.toml`).
+//!
+//! The heuristic and ratio estimators come from `rpg_core::tokens`; the BPE
+//! estimator counts with a HuggingFace `tokenizer.json` and needs the `bpe`
+//! feature.
+
+use rpg_core::config::{EstimatorKind, TokensConfig};
+use std::path::Path;
+
+pub use rpg_core::tokens::{CharsPerToken, Heuristic, TokenEstimator};
+
+/// Counts tokens with a byte-pair encoding loaded from a `tokenizer.json`.
+#[cfg(feature = "bpe")]
+pub struct Bpe {
+    tokenizer: tokenizers::Tokenizer,
+    name: String,
+}
+
+#[cfg(feature = "bpe")]
+impl Bpe {
+    /// Load the tokenizer at `path`.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let tokenizer = tokenizers::Tokenizer::from_file(path)
+            .map_err(|e| anyhow::anyhow!("loading {}: {}", path.display(), e))?;
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        Ok(Self { tokenizer, name })
+    }
+}
+
+#[cfg(feature = "bpe")]
+impl TokenEstimator for Bpe {
+    fn estimate(&self, text: &str) -> usize {
+        self.tokenizer
+            .encode_fast(text, false)
+            .map_or_else(|_| Heuristic.estimate(text), |encoding| encoding.len())
+    }
+
+    fn describe(&self) -> String {
+        format!("bpe ({})", self.name)
+    }
+}
+
+/// The estimator `config` selects, for `provider` when the caller knows which
+/// model will read the text.
+///
+/// A BPE estimator that cannot be loaded (missing file, or a build without
+/// the `bpe` feature) is reported and replaced by the heuristic, which batch
+/// headers then show.
+pub fn from_config(
+    config: &TokensConfig,
+    provider: Option<&str>,
+    project_root: &Path,
+) -> Box<dyn TokenEstimator> {
+    match config.estimator {
+        EstimatorKind::Heuristic => Box::new(Heuristic),
+        EstimatorKind::Ratio => {
+            let (ratio, provider) = config.chars_per_token_for(provider);
+            Box::new(CharsPerToken { ratio, provider })
+        }
+        EstimatorKind::Bpe => match load_bpe(config, project_root) {
+            Ok(estimator) => estimator,
+            Err(e) => {
+                eprintln!(
+                    "  Warning: bpe token estimator unavailable ({}); using chars/4",
+                    e
+                );
+                Box::new(Heuristic)
+            }
+        },
+    }
+}
+
+#[cfg(feature = "bpe")]
+fn load_bpe(config: &TokensConfig, project_root: &Path) -> anyhow::Result<Box<dyn TokenEstimator>> {
+    let path = config
+        .bpe_tokenizer
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("tokens.bpe_tokenizer is not set"))?;
+    Ok(Box::new(Bpe::load(&project_root.join(path))?))
+}
+
+#[cfg(not(feature = "bpe"))]
+fn load_bpe(
+    _config: &TokensConfig,
+    _project_root: &Path,
+) -> anyhow::Result<Box<dyn TokenEstimator>> {
+    anyhow::bail!("built without the `bpe` feature")
+}
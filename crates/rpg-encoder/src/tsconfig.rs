@@ -0,0 +1,286 @@
+//! TypeScript / JavaScript path aliases from `tsconfig.json` and
+//! `jsconfig.json`.
+//!
+//! `compilerOptions.paths` (`"@app/*": ["src/*"]`) and `baseUrl` map
+//! non-relative specifiers to repository paths. Each config governs the files
+//! under its directory and the nearest one wins; `extends` chains are merged,
+//! the extending config's options taking precedence.
+
+use crate::import_paths::{join, parent_dir};
+use rpg_core::files::FileProvider;
+use rpg_core::graph::normalize_path;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Config files read per directory, in order; the first present wins.
+const CONFIG_NAMES: &[&str] = &["tsconfig.json", "jsconfig.json"];
+
+/// `extends` chains longer than this are cut off (and cycles with them).
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// `paths` patterns and their targets, as declared.
+type Patterns = Vec<(String, Vec<String>)>;
+
+/// Path aliases of every config in the project.
+#[derive(Debug, Clone, Default)]
+pub struct PathAliases {
+    /// Deepest directory first, so the first governing config is the nearest.
+    configs: Vec<AliasConfig>,
+}
+
+#[derive(Debug, Clone)]
+struct AliasConfig {
+    /// Directory of the config file; `""` at the project root.
+    dir: String,
+    /// `baseUrl`, resolved against the config that declared it.
+    base_url: Option<String>,
+    paths: Patterns,
+    /// What `paths` targets are relative to: `baseUrl` when set, else the
+    /// directory of the config that declared `paths`.
+    paths_base: String,
+}
+
+/// Compiler options collected along an `extends` chain.
+#[derive(Default)]
+struct Options {
+    base_url: Option<String>,
+    /// Declaring config's directory and its patterns.
+    paths: Option<(String, Patterns)>,
+}
+
+impl Options {
+    fn merge(&mut self, base: Self) {
+        if self.base_url.is_none() {
+            self.base_url = base.base_url;
+        }
+        if self.paths.is_none() {
+            self.paths = base.paths;
+        }
+    }
+}
+
+impl PathAliases {
+    /// Read the configs in the project root and in every directory holding
+    /// an indexed file.
+    pub fn load<'a>(files: &dyn FileProvider, indexed: impl Iterator<Item = &'a Path>) -> Self {
+        let mut dirs: BTreeSet<PathBuf> = BTreeSet::from([PathBuf::new()]);
+        for file in indexed {
+            dirs.extend(file.ancestors().skip(1).map(Path::to_path_buf));
+        }
+        let mut configs: Vec<AliasConfig> = dirs
+            .iter()
+            .filter_map(|dir| {
+                let options = CONFIG_NAMES
+                    .iter()
+                    .find_map(|name| read_options(files, &normalize_path(&dir.join(name)), 0))?;
+                let (paths_dir, paths) = options.paths.unwrap_or_default();
+                if options.base_url.is_none() && paths.is_empty() {
+                    return None;
+                }
+                Some(AliasConfig {
+                    dir: normalize_path(dir),
+                    paths_base: options.base_url.clone().unwrap_or(paths_dir),
+                    base_url: options.base_url,
+                    paths,
+                })
+            })
+            .collect();
+        configs.sort_by(|a, b| {
+            b.dir
+                .len()
+                .cmp(&a.dir.len())
+                .then_with(|| a.dir.cmp(&b.dir))
+        });
+        Self { configs }
+    }
+
+    /// Repository paths (before extension probing) that a `paths` pattern
+    /// maps `specifier` to, for a file at `importer`. `None` when no pattern
+    /// matches; a matching pattern is authoritative even if none of its
+    /// targets exist. Among matching patterns the longest prefix wins.
+    pub fn paths_match(&self, importer: &str, specifier: &str) -> Option<Vec<String>> {
+        let config = self.governing(importer)?;
+        let (captured, targets) = config
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let captured = match pattern.split_once('*') {
+                    Some((prefix, suffix)) => specifier
+                        .strip_prefix(prefix)?
+                        .strip_suffix(suffix)
+                        .map(|rest| (prefix.len(), rest)),
+                    None => (pattern == specifier).then_some((pattern.len(), "")),
+                }?;
+                Some((captured, targets))
+            })
+            .max_by_key(|((prefix_len, _), _)| *prefix_len)
+            .map(|((_, rest), targets)| (rest, targets))?;
+        Some(
+            targets
+                .iter()
+                .map(|target| join(&config.paths_base, &target.replacen('*', captured, 1)))
+                .collect(),
+        )
+    }
+
+    /// `specifier` under the governing config's `baseUrl`, if it sets one.
+    pub fn base_url_path(&self, importer: &str, specifier: &str) -> Option<String> {
+        let base_url = self.governing(importer)?.base_url.as_deref()?;
+        Some(join(base_url, specifier))
+    }
+
+    fn governing(&self, importer: &str) -> Option<&AliasConfig> {
+        self.configs.iter().find(|config| {
+            config.dir.is_empty()
+                || importer
+                    .strip_prefix(config.dir.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        })
+    }
+}
+
+/// Options of the config at `path` merged over those it extends.
+fn read_options(files: &dyn FileProvider, path: &str, depth: usize) -> Option<Options> {
+    let content = files.read_to_string(Path::new(path)).ok()?;
+    let json = parse_jsonc(&content)?;
+    let dir = parent_dir(path);
+
+    let compiler = &json["compilerOptions"];
+    let mut options = Options {
+        base_url: compiler["baseUrl"].as_str().map(|url| join(dir, url)),
+        paths: compiler["paths"].as_object().map(|paths| {
+            let patterns = paths
+                .iter()
+                .map(|(pattern, targets)| {
+                    let targets = targets
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|t| t.as_str().map(str::to_string))
+                        .collect();
+                    (pattern.clone(), targets)
+                })
+                .collect();
+            (dir.to_string(), patterns)
+        }),
+    };
+
+    // Later entries of an `extends` array override earlier ones.
+    let extends: Vec<&str> = match &json["extends"] {
+        serde_json::Value::String(base) => vec![base.as_str()],
+        serde_json::Value::Array(bases) => bases.iter().rev().filter_map(|b| b.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if depth < MAX_EXTENDS_DEPTH {
+        for base in extends {
+            // Package configs (`@tsconfig/node20`) live in node_modules,
+            // which holds no aliases into the repo.
+            if !base.starts_with('.') {
+                continue;
+            }
+            let mut base_path = join(dir, base);
+            if Path::new(&base_path)
+                .extension()
+                .is_none_or(|ext| !ext.eq_ignore_ascii_case("json"))
+            {
+                base_path.push_str(".json");
+            }
+            if let Some(base_options) = read_options(files, &base_path, depth + 1) {
+                options.merge(base_options);
+            }
+        }
+    }
+    Some(options)
+}
+
+/// Parse JSON with comments and trailing commas, as tsconfig allows.
+pub(crate) fn parse_jsonc(content: &str) -> Option<serde_json::Value> {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (',', _) => {
+                // Drop the comma when only whitespace separates it from `}` or `]`.
+                let next = chars.clone().find(|next| !next.is_whitespace());
+                if !matches!(next, Some('}' | ']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    serde_json::from_str(&out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jsonc_strips_comments_and_trailing_commas() {
+        let json = parse_jsonc(
+            r#"{
+                // line comment
+                "compilerOptions": { /* block */ "paths": { "@a/*": ["src/*",], }, },
+                "url": "http://example.com/*not a comment*/"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(json["compilerOptions"]["paths"]["@a/*"][0], "src/*");
+        assert_eq!(json["url"], "http://example.com/*not a comment*/");
+    }
+
+    #[test]
+    fn test_longest_prefix_pattern_wins() {
+        let aliases = PathAliases {
+            configs: vec![AliasConfig {
+                dir: String::new(),
+                base_url: None,
+                paths: vec![
+                    ("@app/*".into(), vec!["src/*".into()]),
+                    ("@app/ui/*".into(), vec!["packages/ui/src/*".into()]),
+                    ("config".into(), vec!["src/config/index.ts".into()]),
+                ],
+                paths_base: String::new(),
+            }],
+        };
+        assert_eq!(
+            aliases.paths_match("src/main.ts", "@app/ui/button"),
+            Some(vec!["packages/ui/src/button".to_string()])
+        );
+        assert_eq!(
+            aliases.paths_match("src/main.ts", "@app/services/auth"),
+            Some(vec!["src/services/auth".to_string()])
+        );
+        assert_eq!(
+            aliases.paths_match("src/main.ts", "config"),
+            Some(vec!["src/config/index.ts".to_string()])
+        );
+        assert_eq!(aliases.paths_match("src/main.ts", "react"), None);
+    }
+}
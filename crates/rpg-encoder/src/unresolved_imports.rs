@@ -0,0 +1,434 @@
+//! Unresolved import report: which imports never became edges, and why.
+//!
+//! Dependency resolution matches imported names against in-repo entities. An
+//! import whose names match nothing is either third-party (declared in a
+//! manifest or lockfile), in-repo code the resolver failed to map (relative
+//! paths, tsconfig aliases, local packages), or unknown. Grouping them by
+//! module prefix shows which resolver gap costs the most edges.
+
+use rpg_core::files::FileProvider;
+use rpg_core::graph::{
+    ImportOrigin, RPGraph, UNRESOLVED_IMPORT_PREFIX_LIMIT, UnresolvedImportPrefix,
+    UnresolvedImports,
+};
+use rpg_parser::deps::ImportDep;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Path aliases conventionally mapped to the source root.
+const CONVENTIONAL_ALIASES: &[&str] = &["@/", "~/"];
+
+/// Rust paths that always point into the current crate.
+const RUST_LOCAL_ROOTS: &[&str] = &["crate", "super", "self"];
+
+/// Java-style reverse-domain roots, grouped by three segments instead of one.
+const REVERSE_DOMAIN_ROOTS: &[&str] = &["com", "org", "net", "io", "dev", "edu"];
+
+/// Summarize the imports of each file against the graph's entity names.
+pub(crate) fn summarize(
+    graph: &RPGraph,
+    files: &dyn FileProvider,
+    imports: &[(PathBuf, Vec<ImportDep>)],
+) -> UnresolvedImports {
+    let names: HashSet<&str> = graph.entities.values().map(|e| e.name.as_str()).collect();
+    let declared = Declared::load(files, imports.iter().map(|(file, _)| file.as_path()));
+    let local_dirs = local_names(graph);
+
+    let mut report = UnresolvedImports::default();
+    // prefix → (count, files, example)
+    let mut groups: HashMap<String, (usize, BTreeSet<&Path>, &str)> = HashMap::new();
+    for (file, file_imports) in imports {
+        for import in file_imports {
+            report.imports += 1;
+            if imported_names(import).iter().any(|n| names.contains(n)) {
+                continue;
+            }
+            report.unresolved += 1;
+            let group = groups
+                .entry(import_prefix(&import.module))
+                .or_insert_with(|| (0, BTreeSet::new(), import.module.as_str()));
+            group.0 += 1;
+            group.1.insert(file.as_path());
+        }
+    }
+
+    let mut prefixes: Vec<UnresolvedImportPrefix> = groups
+        .into_iter()
+        .map(|(prefix, (count, files, example))| UnresolvedImportPrefix {
+            origin: declared.origin(example, &local_dirs),
+            prefix,
+            count,
+            files: files.len(),
+            example: example.to_string(),
+        })
+        .collect();
+    prefixes.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.prefix.cmp(&b.prefix)));
+    prefixes.truncate(UNRESOLVED_IMPORT_PREFIX_LIMIT);
+    report.prefixes = prefixes;
+    report
+}
+
+/// Names an import brings into scope: its symbols, or the module's last
+/// segment for a bare `import module`.
+fn imported_names(import: &ImportDep) -> Vec<&str> {
+    if !import.symbols.is_empty() {
+        return import.symbols.iter().map(String::as_str).collect();
+    }
+    let module = import.module.as_str();
+    let last = module.rsplit(['/', ':']).next().unwrap_or(module);
+    let name = if module.contains('/') {
+        last.split('.').next()
+    } else {
+        last.rsplit('.').next()
+    };
+    name.into_iter().filter(|n| !n.is_empty()).collect()
+}
+
+/// Group key for a module path: the package for bare imports (`react`,
+/// `@scope/pkg`, `serde`), the first directory for relative and aliased paths
+/// (`./lib`, `@app/utils`, `crate::graph`), and the repository path for
+/// domain-rooted Go and Java imports.
+fn import_prefix(module: &str) -> String {
+    if module.starts_with('.') && !module.contains('/') {
+        // Python relative import: `.models.user` → `.models`
+        let dots = module.len() - module.trim_start_matches('.').len();
+        let first = module[dots..].split('.').next().unwrap_or("");
+        return format!("{}{}", &module[..dots], first);
+    }
+    if module.contains('/') {
+        let segments: Vec<&str> = module.split('/').collect();
+        let skip = segments
+            .iter()
+            .take_while(|s| **s == "." || **s == "..")
+            .count();
+        let take = if skip > 0 {
+            skip + 1
+        } else if segments[0].starts_with('@') {
+            2
+        } else if segments[0].contains('.') {
+            3
+        } else {
+            1
+        };
+        return segments[..take.min(segments.len())].join("/");
+    }
+    if module.contains("::") {
+        let segments: Vec<&str> = module.split("::").collect();
+        let take = if RUST_LOCAL_ROOTS.contains(&segments[0]) {
+            2
+        } else {
+            1
+        };
+        return segments[..take.min(segments.len())].join("::");
+    }
+    let segments: Vec<&str> = module.split('.').collect();
+    let take = if REVERSE_DOMAIN_ROOTS.contains(&segments[0]) {
+        3
+    } else {
+        1
+    };
+    segments[..take.min(segments.len())].join(".")
+}
+
+/// Package name a module belongs to, normalized for manifest lookups.
+fn package_name(module: &str) -> String {
+    let name = if module.starts_with('@') {
+        module.splitn(3, '/').take(2).collect::<Vec<_>>().join("/")
+    } else {
+        let root = module.split(['/', ':']).next().unwrap_or(module);
+        if module.contains('/') || module.contains("::") {
+            root.to_string()
+        } else {
+            root.split('.').next().unwrap_or(root).to_string()
+        }
+    };
+    normalize_package(&name)
+}
+
+fn normalize_package(name: &str) -> String {
+    name.trim().to_lowercase().replace('-', "_")
+}
+
+/// Directory names and file stems of indexed files, normalized like package
+/// names: a bare import naming one of them is in-repo code.
+fn local_names(graph: &RPGraph) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for file in graph.file_index.keys() {
+        if let Some(stem) = file.file_stem() {
+            names.insert(normalize_package(&stem.to_string_lossy()));
+        }
+        if let Some(parent) = file.parent() {
+            for dir in parent {
+                names.insert(normalize_package(&dir.to_string_lossy()));
+            }
+        }
+    }
+    names
+}
+
+/// Dependencies and local names declared by the project's manifests.
+#[derive(Default)]
+struct Declared {
+    /// Normalized third-party package names.
+    packages: HashSet<String>,
+    /// Go module paths from `require`.
+    go_requires: Vec<String>,
+    /// Module prefixes that point into the repo: path aliases, Go module paths.
+    local_prefixes: Vec<String>,
+    /// Normalized names of the repo's own packages.
+    local_packages: HashSet<String>,
+}
+
+impl Declared {
+    /// Read manifests and lockfiles in the project root and in every directory
+    /// holding an indexed file, so workspace members are covered.
+    fn load<'a>(provider: &dyn FileProvider, files: impl Iterator<Item = &'a Path>) -> Self {
+        let mut dirs: BTreeSet<PathBuf> = BTreeSet::from([PathBuf::new()]);
+        for file in files {
+            dirs.extend(file.ancestors().skip(1).map(Path::to_path_buf));
+        }
+        let mut declared = Self {
+            local_prefixes: CONVENTIONAL_ALIASES.iter().map(|a| a.to_string()).collect(),
+            ..Self::default()
+        };
+        for dir in dirs {
+            declared.read_dir(provider, &dir);
+        }
+        declared
+    }
+
+    fn read_dir(&mut self, provider: &dyn FileProvider, dir: &Path) {
+        let read = |name: &str| provider.read_to_string(&dir.join(name)).ok();
+        if let Some(content) = read("package.json") {
+            self.read_package_json(&content);
+        }
+        for name in ["tsconfig.json", "jsconfig.json"] {
+            if let Some(content) = read(name) {
+                self.read_tsconfig(&content);
+            }
+        }
+        if let Some(content) = read("package-lock.json")
+            && let Ok(lock) = serde_json::from_str::<serde_json::Value>(&content)
+            && let Some(packages) = lock["packages"].as_object()
+        {
+            self.packages.extend(
+                packages
+                    .keys()
+                    .filter_map(|k| k.rsplit("node_modules/").next())
+                    .filter(|k| !k.is_empty())
+                    .map(normalize_package),
+            );
+        }
+        if let Some(content) = read("Cargo.toml") {
+            self.read_cargo_toml(&content);
+        }
+        for name in ["Cargo.lock", "poetry.lock", "uv.lock"] {
+            if let Some(lock) = read(name).and_then(|c| c.parse::<toml::Table>().ok())
+                && let Some(packages) = lock.get("package").and_then(|p| p.as_array())
+            {
+                self.packages.extend(
+                    packages
+                        .iter()
+                        .filter_map(|p| p.get("name")?.as_str())
+                        .map(normalize_package),
+                );
+            }
+        }
+        if let Some(content) = read("pyproject.toml") {
+            self.read_pyproject(&content);
+        }
+        if let Some(content) = read("requirements.txt") {
+            self.packages.extend(
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+                    .map(requirement_name),
+            );
+        }
+        if let Some(content) = read("go.mod") {
+            self.read_go_mod(&content);
+        }
+    }
+
+    fn read_package_json(&mut self, content: &str) {
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+            return;
+        };
+        for field in [
+            "dependencies",
+            "devDependencies",
+            "peerDependencies",
+            "optionalDependencies",
+        ] {
+            if let Some(deps) = json[field].as_object() {
+                self.packages
+                    .extend(deps.keys().map(|k| normalize_package(k)));
+            }
+        }
+        if let Some(name) = json["name"].as_str() {
+            self.local_packages.insert(normalize_package(name));
+        }
+        // Subpath imports (`#internal/*`) always resolve inside the package.
+        if let Some(imports) = json["imports"].as_object() {
+            self.local_prefixes
+                .extend(imports.keys().map(|k| k.trim_end_matches('*').to_string()));
+        }
+    }
+
+    fn read_tsconfig(&mut self, content: &str) {
+        let Some(json) = crate::tsconfig::parse_jsonc(content) else {
+            return;
+        };
+        if let Some(paths) = json["compilerOptions"]["paths"].as_object() {
+            self.local_prefixes
+                .extend(paths.keys().map(|k| k.trim_end_matches('*').to_string()));
+        }
+    }
+
+    fn read_cargo_toml(&mut self, content: &str) {
+        let Ok(manifest) = content.parse::<toml::Table>() else {
+            return;
+        };
+        if let Some(name) = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+        {
+            self.local_packages.insert(normalize_package(name));
+        }
+        let workspace_deps = manifest
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"));
+        for deps in ["dependencies", "dev-dependencies", "build-dependencies"]
+            .iter()
+            .filter_map(|section| manifest.get(*section))
+            .chain(workspace_deps)
+        {
+            if let Some(deps) = deps.as_table() {
+                self.packages
+                    .extend(deps.keys().map(|k| normalize_package(k)));
+            }
+        }
+    }
+
+    fn read_pyproject(&mut self, content: &str) {
+        let Ok(manifest) = content.parse::<toml::Table>() else {
+            return;
+        };
+        let project = manifest.get("project");
+        if let Some(name) = project.and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+            self.local_packages.insert(normalize_package(name));
+        }
+        if let Some(deps) = project
+            .and_then(|p| p.get("dependencies"))
+            .and_then(|d| d.as_array())
+        {
+            self.packages
+                .extend(deps.iter().filter_map(|d| d.as_str()).map(requirement_name));
+        }
+        if let Some(deps) = manifest
+            .get("tool")
+            .and_then(|t| t.get("poetry"))
+            .and_then(|p| p.get("dependencies"))
+            .and_then(|d| d.as_table())
+        {
+            self.packages
+                .extend(deps.keys().map(|k| normalize_package(k)));
+        }
+    }
+
+    fn read_go_mod(&mut self, content: &str) {
+        let mut in_require = false;
+        for line in content.lines().map(str::trim) {
+            if let Some(module) = line.strip_prefix("module ") {
+                self.local_prefixes.push(module.trim().to_string());
+            } else if line.starts_with("require (") {
+                in_require = true;
+            } else if in_require && line == ")" {
+                in_require = false;
+            } else if let Some(path) = line
+                .strip_prefix("require ")
+                .or(in_require.then_some(line))
+                .and_then(|l| l.split_whitespace().next())
+            {
+                self.go_requires.push(path.to_string());
+            }
+        }
+    }
+
+    fn origin(&self, module: &str, local_names: &HashSet<String>) -> ImportOrigin {
+        let relative = module.starts_with('.')
+            || module
+                .split("::")
+                .next()
+                .is_some_and(|root| module.contains("::") && RUST_LOCAL_ROOTS.contains(&root));
+        if relative
+            || self
+                .local_prefixes
+                .iter()
+                .any(|p| module.starts_with(p.as_str()))
+            || self.local_packages.contains(&package_name(module))
+        {
+            return ImportOrigin::InRepo;
+        }
+        let go_required = self
+            .go_requires
+            .iter()
+            .any(|r| module == r || module.starts_with(&format!("{}/", r)));
+        if go_required || self.packages.contains(&package_name(module)) {
+            return ImportOrigin::ThirdParty;
+        }
+        if local_names.contains(&package_name(module)) {
+            return ImportOrigin::InRepo;
+        }
+        ImportOrigin::Unknown
+    }
+}
+
+/// Package name from a requirement specifier (`requests>=2.0; python_version>"3"`).
+fn requirement_name(spec: &str) -> String {
+    let end = spec
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(spec.len());
+    normalize_package(&spec[..end])
+}
+
+/// The report as text: totals, then up to `limit` prefix groups, largest first.
+pub fn format_report(report: &UnresolvedImports, limit: usize) -> String {
+    let mut output = format!(
+        "unresolved_imports: {} of {} import statements matched no in-repo entity\n",
+        report.unresolved, report.imports
+    );
+    if report.prefixes.is_empty() {
+        return output;
+    }
+    output.push_str(&format!(
+        "{:<32} {:>6} {:>6}  {:<17} example\n",
+        "prefix", "count", "files", "origin"
+    ));
+    for group in report.prefixes.iter().take(limit) {
+        output.push_str(&format!(
+            "{:<32} {:>6} {:>6}  {:<17} {}\n",
+            group.prefix,
+            group.count,
+            group.files,
+            group.origin.label(),
+            group.example
+        ));
+    }
+    let in_repo: usize = report
+        .prefixes
+        .iter()
+        .filter(|g| g.origin == ImportOrigin::InRepo)
+        .map(|g| g.count)
+        .sum();
+    if in_repo > 0 {
+        output.push_str(&format!(
+            "{} unresolved import(s) look like in-repo code the resolver did not map \
+             (relative paths, path aliases, local packages); these are missing edges.\n",
+            in_repo
+        ));
+    }
+    output
+}
@@ -0,0 +1,210 @@
+//! Feature verification: sample lifted entities for the agent to check
+//! against their current source, and record its verdicts.
+//!
+//! Sampling is reproducible — the same graph, strategy, and seed pick the
+//! same entities in the same order. Entities judged outdated or wrong are
+//! returned for re-lifting; the verdict counts become the graph's accuracy
+//! estimate.
+
+use rpg_core::graph::{FeatureVerification, RPGraph};
+use rpg_parser::entities::RawEntity;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Entities sampled when the caller gives no count.
+pub const DEFAULT_SAMPLE_SIZE: usize = 5;
+
+/// How [`sample_lifted`] orders the lifted entities it picks from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleStrategy {
+    /// Seeded shuffle.
+    Random,
+    /// Least recently lifted first.
+    Stale,
+    /// Most frequently changed first, from the last churn analysis.
+    Churn,
+}
+
+impl SampleStrategy {
+    /// Parse `random`, `stale`, or `churn` (case-insensitive).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "random" => Ok(Self::Random),
+            "stale" => Ok(Self::Stale),
+            "churn" => Ok(Self::Churn),
+            other => Err(format!(
+                "unknown sampling strategy '{}' (expected random, stale, or churn)",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Stale => "stale",
+            Self::Churn => "churn",
+        }
+    }
+}
+
+/// The agent's judgement of one entity's stored features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The features describe the code as it is now.
+    Accurate,
+    /// The features describe an earlier version of the code.
+    Outdated,
+    /// The features never described the code.
+    Wrong,
+}
+
+/// Up to `count` lifted entities, ordered by `strategy`. Ties — and the
+/// whole order under [`SampleStrategy::Random`] — are broken by a hash of
+/// `seed` and the entity ID.
+pub fn sample_lifted(
+    graph: &RPGraph,
+    count: usize,
+    strategy: SampleStrategy,
+    seed: u64,
+) -> Vec<String> {
+    let mut candidates: Vec<_> = graph
+        .entities
+        .values()
+        .filter(|e| graph.is_liftable(e) && !e.semantic_features.is_empty())
+        .map(|e| (e, seeded_rank(seed, &e.id)))
+        .collect();
+    match strategy {
+        SampleStrategy::Random => candidates.sort_by_key(|&(e, rank)| (rank, &e.id)),
+        // Entities lifted before timestamps were recorded count as oldest.
+        SampleStrategy::Stale => candidates.sort_by_key(|&(e, rank)| (e.lifted_at, rank, &e.id)),
+        SampleStrategy::Churn => candidates.sort_by_key(|&(e, rank)| {
+            (std::cmp::Reverse(e.churn.unwrap_or_default()), rank, &e.id)
+        }),
+    }
+    candidates
+        .into_iter()
+        .take(count)
+        .map(|(e, _)| e.id.clone())
+        .collect()
+}
+
+fn seeded_rank(seed: u64, id: &str) -> u64 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&seed.to_le_bytes());
+    hasher.update(id.as_bytes());
+    let bytes = hasher.finalize();
+    u64::from_le_bytes(bytes.as_bytes()[..8].try_into().unwrap_or_default())
+}
+
+/// Outcome of [`apply_verdicts`].
+#[derive(Debug, Clone)]
+pub struct VerificationOutcome {
+    /// Verdict counts, also stored in the graph metadata.
+    pub recorded: FeatureVerification,
+    /// Entities judged outdated or wrong, to be re-lifted.
+    pub requeue: Vec<String>,
+    /// Keys that matched no entity.
+    pub unmatched: Vec<String>,
+}
+
+/// Record verdicts keyed by entity ID as the graph's
+/// `feature_verification`. Verdicts for unknown entities are reported and
+/// not counted.
+pub fn apply_verdicts(
+    graph: &mut RPGraph,
+    verdicts: &BTreeMap<String, Verdict>,
+) -> VerificationOutcome {
+    let mut recorded = FeatureVerification {
+        accurate: 0,
+        outdated: 0,
+        wrong: 0,
+        verified_at: chrono::Utc::now(),
+    };
+    let mut requeue = Vec::new();
+    let mut unmatched = Vec::new();
+    for (id, verdict) in verdicts {
+        if !graph.entities.contains_key(id) {
+            unmatched.push(id.clone());
+            continue;
+        }
+        match verdict {
+            Verdict::Accurate => recorded.accurate += 1,
+            Verdict::Outdated => recorded.outdated += 1,
+            Verdict::Wrong => recorded.wrong += 1,
+        }
+        if *verdict != Verdict::Accurate {
+            requeue.push(id.clone());
+        }
+    }
+    if recorded.sampled() > 0 {
+        graph.metadata.feature_verification = Some(recorded.clone());
+    }
+    VerificationOutcome {
+        recorded,
+        requeue,
+        unmatched,
+    }
+}
+
+/// The sample as the agent sees it: instructions, then each entity's stored
+/// features above its current source. Sampled entities whose source could
+/// not be read are listed at the end.
+pub fn format_sample(
+    graph: &RPGraph,
+    sample: &[String],
+    sources: &[RawEntity],
+    strategy: SampleStrategy,
+    seed: u64,
+) -> String {
+    let mut output = format!(
+        "## FEATURE VERIFICATION ({} entities, strategy: {}, seed: {})\n\n\
+         Compare each entity's stored features with its current source and judge them:\n\
+         - accurate: they describe what the code does now\n\
+         - outdated: they describe an earlier version of the code\n\
+         - wrong: they never described it\n\
+         Then call submit_verification with {{\"<entity id>\": \"accurate\" | \"outdated\" | \"wrong\", ...}}.\n",
+        sample.len(),
+        strategy.name(),
+        seed,
+    );
+    let mut missing: Vec<&str> = Vec::new();
+    for id in sample {
+        let Some(entity) = graph.entities.get(id) else {
+            continue;
+        };
+        let Some(raw) = sources.iter().find(|r| r.id() == *id) else {
+            missing.push(id);
+            continue;
+        };
+        output.push_str(&format!(
+            "\n### {} ({:?})\nFeatures: {}\n```\n{}\n```\n",
+            id,
+            entity.kind,
+            entity.semantic_features.join(", "),
+            raw.source_text,
+        ));
+    }
+    if !missing.is_empty() {
+        output.push_str(&format!(
+            "\nSource unavailable (skip these): {}\n",
+            missing.join(", ")
+        ));
+    }
+    output
+}
+
+/// One status line for the last verification round:
+/// `verified_accuracy: 80% (4 accurate, 1 outdated, 0 wrong of 5 sampled, 2026-01-31)`.
+pub fn format_accuracy(verification: &FeatureVerification) -> String {
+    format!(
+        "verified_accuracy: {:.0}% ({} accurate, {} outdated, {} wrong of {} sampled, {})",
+        verification.accuracy() * 100.0,
+        verification.accurate,
+        verification.outdated,
+        verification.wrong,
+        verification.sampled(),
+        verification.verified_at.format("%Y-%m-%d"),
+    )
+}
@@ -0,0 +1,140 @@
+//! Integration test: annotations imported by entity ID and line range attach
+//! to the right entities and survive rebuilds.
+
+use rpg_core::graph::RPGraph;
+use rpg_encoder::annotations::{apply_annotations, parse_annotations};
+use rpg_encoder::evolution::merge_features;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+
+const SOURCE: &str = r"import os
+
+
+class Session:
+    def __init__(self, user):
+        self.user = user
+
+    def refresh(self):
+        token = os.urandom(16)
+        return token
+
+
+def login(user):
+    return Session(user)
+";
+
+fn build_graph() -> RPGraph {
+    let mut graph = RPGraph::new("python");
+    for raw in extract_entities(Path::new("src/auth.py"), SOURCE, Language::PYTHON) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    graph.refresh_metadata();
+    graph
+}
+
+fn annotation<'a>(graph: &'a RPGraph, id: &str, key: &str) -> Option<&'a str> {
+    graph.entities[id]
+        .attributes
+        .get(&format!("annotation.github.{}", key))
+        .map(String::as_str)
+}
+
+#[test]
+fn test_line_ranges_attach_to_innermost_entities() {
+    let mut graph = build_graph();
+    let doc = parse_annotations(
+        r#"{"source": "github", "annotations": [
+            {"file": "src/auth.py", "lines": [9, 10], "values": {"last_reviewer": "alice"}},
+            {"file": "./src/auth.py", "lines": [6, 13], "values": {"pr": "42"}},
+            {"file": "src/auth.py", "lines": [1, 1], "values": {"pr": "7"}},
+            {"entity": "src/auth.py:login", "values": {"last_reviewer": "bob"}},
+            {"entity": "src/auth.py:gone", "values": {"pr": "1"}}
+        ]}"#,
+    )
+    .unwrap();
+    let report = apply_annotations(&mut graph, &doc);
+
+    // A range inside one method annotates the method, not its class.
+    assert_eq!(
+        annotation(&graph, "src/auth.py:Session::refresh", "last_reviewer"),
+        Some("alice")
+    );
+    assert_eq!(
+        annotation(&graph, "src/auth.py:Session", "last_reviewer"),
+        None
+    );
+
+    // A range spanning two methods and a function annotates the class too.
+    for id in [
+        "src/auth.py:Session",
+        "src/auth.py:Session::__init__",
+        "src/auth.py:Session::refresh",
+        "src/auth.py:login",
+    ] {
+        assert_eq!(annotation(&graph, id, "pr"), Some("42"), "{}", id);
+    }
+
+    // Imports hit no entity: the file's Module takes the annotation.
+    let module_id = graph.file_index[Path::new("src/auth.py")]
+        .iter()
+        .find(|id| graph.entities[*id].kind == rpg_core::graph::EntityKind::Module)
+        .unwrap()
+        .clone();
+    assert_eq!(annotation(&graph, &module_id, "pr"), Some("7"));
+
+    assert_eq!(
+        annotation(&graph, "src/auth.py:login", "last_reviewer"),
+        Some("bob")
+    );
+    assert_eq!(report.unmatched, vec!["src/auth.py:gone"]);
+    assert_eq!(report.entities_annotated, 5);
+}
+
+#[test]
+fn test_annotations_survive_rebuild_and_null_removes() {
+    let mut graph = build_graph();
+    let doc = parse_annotations(
+        r#"{"source": "github", "annotations": [
+            {"entity": "src/auth.py:login", "values": {"last_reviewer": "bob", "pr": "42"}}
+        ]}"#,
+    )
+    .unwrap();
+    apply_annotations(&mut graph, &doc);
+
+    let mut rebuilt = build_graph();
+    let stats = merge_features(&mut rebuilt, &graph);
+    assert_eq!(stats.annotations_restored, 1);
+    assert_eq!(
+        annotation(&rebuilt, "src/auth.py:login", "last_reviewer"),
+        Some("bob")
+    );
+
+    let clear = parse_annotations(
+        r#"{"source": "github", "annotations": [
+            {"entity": "src/auth.py:login", "values": {"last_reviewer": null}}
+        ]}"#,
+    )
+    .unwrap();
+    let report = apply_annotations(&mut rebuilt, &clear);
+    assert_eq!(report.values_removed, 1);
+    assert_eq!(
+        annotation(&rebuilt, "src/auth.py:login", "last_reviewer"),
+        None
+    );
+    assert_eq!(annotation(&rebuilt, "src/auth.py:login", "pr"), Some("42"));
+}
+
+#[test]
+fn test_invalid_documents_are_rejected() {
+    for json in [
+        r#"{"source": "", "annotations": []}"#,
+        r#"{"source": "github", "annotations": [{"values": {"pr": "1"}}]}"#,
+        r#"{"source": "github", "annotations": [{"entity": "a", "file": "b", "values": {}}]}"#,
+        r#"{"source": "github", "annotations": [{"file": "a.py", "lines": [5, 2], "values": {}}]}"#,
+        r#"{"source": "github", "annotations": [{"entity": "a", "values": {"last reviewer": "x"}}]}"#,
+    ] {
+        assert!(parse_annotations(json).is_err(), "{}", json);
+    }
+}
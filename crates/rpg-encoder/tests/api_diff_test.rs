@@ -0,0 +1,155 @@
+//! Integration test: public API changes between two tagged revisions of a
+//! scripted repository.
+
+use rpg_core::graph::EntityKind;
+use rpg_encoder::api_diff::{diff_api, format_markdown};
+use tempfile::TempDir;
+
+/// Write `files`, commit them on HEAD, and tag the commit.
+fn commit(repo: &git2::Repository, files: &[(&str, &str)], tag: &str) {
+    let root = repo.workdir().unwrap();
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo
+        .commit(Some("HEAD"), &sig, &sig, tag, &tree, &parents)
+        .unwrap();
+    repo.tag_lightweight(tag, &repo.find_object(oid, None).unwrap(), false)
+        .unwrap();
+}
+
+const V1_2: &[(&str, &str)] = &[
+    (
+        "src/lib.rs",
+        "pub fn parse(input: &str) -> u32 {\n    helper(input)\n}\n\nfn helper(input: &str) -> u32 {\n    input.len() as u32\n}\n",
+    ),
+    (
+        "src/render.rs",
+        "pub struct Renderer;\n\nimpl Renderer {\n    pub fn render(&self) -> String {\n        String::new()\n    }\n}\n",
+    ),
+];
+
+const V1_3: &[(&str, &str)] = &[
+    (
+        "src/lib.rs",
+        "pub fn parse(input: &str, strict: bool) -> u32 {\n    helper(input, strict)\n}\n\npub fn validate(input: &str) -> bool {\n    !input.is_empty()\n}\n\nfn helper(input: &str, strict: bool) -> u32 {\n    input.len() as u32 + u32::from(strict)\n}\n",
+    ),
+    // Unchanged public API; the body differs.
+    (
+        "src/render.rs",
+        "pub struct Renderer;\n\nimpl Renderer {\n    pub fn render(&self) -> String {\n        \"done\".to_string()\n    }\n}\n",
+    ),
+];
+
+fn scripted_repo() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let repo = git2::Repository::init(tmp.path()).unwrap();
+    commit(&repo, V1_2, "v1.2");
+    commit(&repo, V1_3, "v1.3");
+    tmp
+}
+
+#[test]
+fn test_one_added_and_one_changed_public_function() {
+    let tmp = scripted_repo();
+    let diff = diff_api(tmp.path(), "v1.2", "v1.3", &[]).unwrap();
+
+    let added: Vec<&str> = diff.added.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(added, vec!["src/lib.rs:validate"]);
+    assert!(diff.removed.is_empty(), "{:?}", diff.removed);
+
+    // The private helper's signature changed too, but it is not API.
+    assert_eq!(diff.changed.len(), 1, "{:?}", diff.changed);
+    let change = &diff.changed[0];
+    assert_eq!(change.entity.id, "src/lib.rs:parse");
+    assert_eq!(change.entity.kind, EntityKind::Function);
+    assert_eq!(change.entity.area, "src");
+    let params = |sig: &rpg_core::graph::Signature| {
+        sig.parameters
+            .iter()
+            .map(|p| p.name.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        params(change.previous_signature.as_ref().unwrap()),
+        vec!["input"]
+    );
+    assert_eq!(
+        params(change.entity.signature.as_ref().unwrap()),
+        vec!["input", "strict"]
+    );
+}
+
+#[test]
+fn test_markdown_and_json_reports() {
+    let tmp = scripted_repo();
+    let diff = diff_api(tmp.path(), "v1.2", "v1.3", &[]).unwrap();
+
+    let markdown = format_markdown(&diff);
+    assert!(
+        markdown.starts_with("# API diff: v1.2..v1.3\n"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("1 added, 0 removed, 1 signature changed"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("\n## src\n\n### src/lib.rs\n"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("- added function `validate(input: &str) -> bool`"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains(
+            "- changed function `parse`: `(input: &str) -> u32` -> `(input: &str, strict: bool) -> u32`"
+        ),
+        "{}",
+        markdown
+    );
+    assert!(!markdown.contains("render.rs"), "{}", markdown);
+
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["added"][0]["id"], "src/lib.rs:validate");
+    assert_eq!(json["changed"][0]["file"], "src/lib.rs");
+    assert_eq!(
+        json["changed"][0]["previous_signature"]["parameters"][0]["name"],
+        "input"
+    );
+}
+
+#[test]
+fn test_reverse_diff_and_unknown_revision() {
+    let tmp = scripted_repo();
+    let diff = diff_api(tmp.path(), "v1.3", "v1.2", &[]).unwrap();
+    let removed: Vec<&str> = diff.removed.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(removed, vec!["src/lib.rs:validate"]);
+    assert!(diff.added.is_empty());
+
+    assert!(
+        diff_api(tmp.path(), "v1.2", "v1.2", &[])
+            .unwrap()
+            .is_empty()
+    );
+    let err = diff_api(tmp.path(), "v0.9", "v1.3", &[])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("unknown revision: v0.9"), "{}", err);
+}
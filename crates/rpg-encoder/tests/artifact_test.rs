@@ -0,0 +1,162 @@
+//! Integration test: installing and loading graphs built in another checkout.
+//!
+//! A graph built in one repository (the "CI" checkout) is loaded into a clone
+//! that has moved ahead, and into an unrelated repository.
+
+use rpg_core::graph::RPGraph;
+use rpg_encoder::artifact::{LoadReport, install, load_verified, repo_fingerprint, verify};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Write `files` and commit them on HEAD.
+fn commit(repo: &git2::Repository, files: &[(&str, &str)]) {
+    let root = repo.workdir().unwrap();
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, "edit", &tree, &parents)
+        .unwrap();
+}
+
+fn repo_with(files: &[(&str, &str)]) -> (TempDir, git2::Repository) {
+    let tmp = TempDir::new().unwrap();
+    let repo = git2::Repository::init(tmp.path()).unwrap();
+    commit(&repo, files);
+    (tmp, repo)
+}
+
+const PROJECT: &[(&str, &str)] = &[
+    ("src/lib.rs", "pub fn parse() -> u32 {\n    1\n}\n"),
+    ("src/util.rs", "pub fn helper() {}\n"),
+];
+
+/// Build a graph the way `build` does: entities plus base commit and fingerprint.
+fn build_graph(root: &Path) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (path, _) in PROJECT {
+        let source = std::fs::read_to_string(root.join(path)).unwrap();
+        for raw in extract_entities(Path::new(path), &source, Language::RUST) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    graph.base_commit = rpg_encoder::evolution::get_head_sha(root).ok();
+    graph.metadata.repo_fingerprint = repo_fingerprint(root);
+    graph.refresh_metadata();
+    graph
+}
+
+fn graph_bytes(graph: &RPGraph) -> Vec<u8> {
+    rpg_core::schema::to_json(graph).unwrap().into_bytes()
+}
+
+#[test]
+fn test_graph_matches_its_own_checkout() {
+    let (tmp, _repo) = repo_with(PROJECT);
+    let graph = build_graph(tmp.path());
+    assert!(graph.metadata.repo_fingerprint.is_some());
+
+    let report = verify(&graph, tmp.path()).unwrap();
+    assert_eq!(report.fingerprint_matches, Some(true));
+    assert_eq!(report.changed_files, None);
+    assert_eq!(report.summary(), None);
+}
+
+#[test]
+fn test_graph_behind_head_reports_changes() {
+    let (tmp, repo) = repo_with(PROJECT);
+    let graph = build_graph(tmp.path());
+    commit(
+        &repo,
+        &[("src/lib.rs", "pub fn parse() -> u32 {\n    2\n}\n")],
+    );
+    commit(&repo, &[("src/new.rs", "pub fn added() {}\n")]);
+
+    let report = verify(&graph, tmp.path()).unwrap();
+    assert_eq!(report.fingerprint_matches, Some(false));
+    assert_eq!(report.changed_files, Some(2));
+    assert!(!report.base_commit_missing);
+    let summary = report.summary().unwrap();
+    assert!(summary.contains("2 file(s) changed since"), "{}", summary);
+}
+
+#[test]
+fn test_graph_from_another_repository_is_rejected() {
+    let (ci, _ci_repo) = repo_with(PROJECT);
+    let graph = build_graph(ci.path());
+    let (other, _other_repo) = repo_with(&[
+        ("app/main.py", "def main():\n    pass\n"),
+        ("app/models.py", "class User:\n    pass\n"),
+    ]);
+
+    let err = verify(&graph, other.path()).unwrap_err().to_string();
+    assert!(
+        err.contains("graph was built from a different repository"),
+        "{}",
+        err
+    );
+    assert!(err.contains("is not in this repository"), "{}", err);
+    assert!(err.contains("only 0 of its 2 indexed files"), "{}", err);
+
+    // fetch-graph refuses to install it, leaving no graph behind.
+    assert!(install(other.path(), &graph_bytes(&graph)).is_err());
+    assert!(!rpg_core::storage::rpg_exists(other.path()));
+}
+
+#[test]
+fn test_install_artifact_from_unfetched_commit() {
+    // Same files, but a history the local clone never saw (e.g. a CI build of
+    // a branch that was not fetched): the base commit is unknown locally,
+    // yet the graph's files are all tracked here.
+    let (ci, _ci_repo) = repo_with(PROJECT);
+    let graph = build_graph(ci.path());
+    let (local, local_repo) = repo_with(&[PROJECT[0], PROJECT[1], ("README.md", "local\n")]);
+    commit(
+        &local_repo,
+        &[("src/util.rs", "pub fn helper() {}\npub fn more() {}\n")],
+    );
+
+    let (installed, report) = install(local.path(), &graph_bytes(&graph)).unwrap();
+    assert_eq!(installed.entities.len(), graph.entities.len());
+    assert!(report.base_commit_missing);
+    assert_eq!(report.fingerprint_matches, Some(false));
+    assert!(report.summary().unwrap().contains("not in this repository"));
+
+    let (loaded, reloaded): (RPGraph, LoadReport) = load_verified(local.path()).unwrap();
+    assert_eq!(loaded.base_commit, graph.base_commit);
+    assert_eq!(reloaded, report);
+}
+
+#[test]
+fn test_unfingerprinted_graph_outside_git_loads() {
+    let tmp = TempDir::new().unwrap();
+    let graph = RPGraph::new("rust");
+    let report = verify(&graph, tmp.path()).unwrap();
+    assert_eq!(report, LoadReport::default());
+}
+
+#[test]
+fn test_graph_built_at_head_skips_the_tree_walk() {
+    let (tmp, _repo) = repo_with(PROJECT);
+    let mut graph = build_graph(tmp.path());
+    // A graph whose base commit is HEAD was fingerprinted from this tree, so
+    // the stored value is trusted rather than recomputed.
+    graph.metadata.repo_fingerprint = Some("not-recomputed".into());
+
+    let report = verify(&graph, tmp.path()).unwrap();
+    assert_eq!(report.fingerprint_matches, Some(true));
+    assert_eq!(report.changed_files, None);
+}
@@ -0,0 +1,77 @@
+//! Integration test: TS/JS barrel re-exports become Composes edges from the
+//! barrel's Module entity, resolved by module path.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+use rpg_nav::explore::{Direction, explore};
+use tempfile::TempDir;
+
+fn composed(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == EdgeKind::Composes)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets
+}
+
+fn package() -> (TempDir, RPGraph) {
+    common::build(
+        "typescript",
+        &[
+            (
+                "src/index.ts",
+                "export * from './utils';\n\
+             export { Bar as Baz } from './bar';\n\
+             export * as api from '../lib/api';\n\
+             export * from 'lodash';\n",
+            ),
+            ("src/utils/index.ts", "export function slugify() {}\n"),
+            ("src/bar.ts", "export class Bar {}\n"),
+            // Same names elsewhere: only the specifier's file may match.
+            ("src/legacy/utils.ts", "export function legacy() {}\n"),
+            ("lib/bar.ts", "export class Bar {}\n"),
+            ("lib/api.ts", "export function get() {}\n"),
+        ],
+    )
+}
+
+#[test]
+fn test_reexports_resolve_by_module_path() {
+    let (_tmp, graph) = package();
+    assert_eq!(
+        composed(&graph, "src/index.ts:index"),
+        vec![
+            "lib/api.ts:api",
+            "src/bar.ts:Bar",
+            "src/utils/index.ts:index",
+        ]
+    );
+}
+
+#[test]
+fn test_explore_composes_from_barrel_file() {
+    let (_tmp, graph) = package();
+    let tree = explore(
+        &graph,
+        "src/index.ts",
+        Direction::Downstream,
+        2,
+        Some(EdgeKind::Composes),
+    )
+    .unwrap();
+    assert_eq!(tree.entity_id, "src/index.ts:index");
+    let mut surface: Vec<&str> = tree.children.iter().map(|c| c.entity_id.as_str()).collect();
+    surface.sort_unstable();
+    assert_eq!(
+        surface,
+        vec![
+            "lib/api.ts:api",
+            "src/bar.ts:Bar",
+            "src/utils/index.ts:index"
+        ]
+    );
+}
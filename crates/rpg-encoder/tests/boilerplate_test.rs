@@ -0,0 +1,163 @@
+//! Integration test: paradigm boilerplate skip-lists auto-lift barrel files
+//! (and passthrough layouts) before anything reaches an LLM batch.
+
+use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_encoder::lift::{AutoLiftEngine, LiftConfidence};
+use rpg_encoder::lift_queue::{build_lift_queue, format_batch_preamble};
+use rpg_parser::entities::RawEntity;
+use rpg_parser::languages::Language;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const BARREL: &str = "src/components/index.ts";
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../tests/fixtures/nextjs_project")
+        .canonicalize()
+        .unwrap()
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "ts" || e == "tsx" || e == "js")
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+/// The nextjs fixture parsed with its detected paradigms recorded on the graph.
+fn build_graph(root: &Path) -> RPGraph {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files);
+    let languages = [Language::TYPESCRIPT, Language::JAVASCRIPT];
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(root, &languages, &paradigm_defs);
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    graph.metadata.paradigms = active_defs.iter().map(|d| d.name.clone()).collect();
+    graph
+}
+
+fn in_file<'a>(graph: &'a RPGraph, file: &str) -> Vec<&'a rpg_core::graph::Entity> {
+    graph
+        .entities
+        .values()
+        .filter(|e| e.file == Path::new(file) && e.kind != EntityKind::Module)
+        .collect()
+}
+
+#[test]
+fn test_barrel_files_never_reach_a_batch() {
+    let root = fixture_root();
+    let mut graph = build_graph(&root);
+    let queue = build_lift_queue(
+        &mut graph,
+        "*",
+        &root,
+        &rpg_core::config::EncodingConfig::default(),
+        &HashSet::new(),
+        &rpg_encoder::tokens::Heuristic,
+    )
+    .unwrap();
+
+    assert!(
+        queue
+            .raw_entities
+            .iter()
+            .all(|raw| raw.file != Path::new(BARREL)),
+        "boilerplate must not be batched"
+    );
+    let barrel = in_file(&graph, BARREL);
+    assert_eq!(barrel.len(), 1);
+    assert_eq!(
+        barrel[0].semantic_features,
+        vec!["re-export module members"]
+    );
+    assert_eq!(queue.boilerplate.get("react"), Some(&1));
+    // Ordinary functions are still batched.
+    assert!(
+        queue
+            .raw_entities
+            .iter()
+            .any(|raw| raw.file == Path::new("src/lib/posts.ts"))
+    );
+
+    let preamble = format_batch_preamble(&graph, &queue, "nextjs-fixture");
+    assert!(preamble.contains("BOILERPLATE (canned features from paradigm skip-lists): react 1"));
+}
+
+#[test]
+fn test_passthrough_layout_is_boilerplate() {
+    let defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let engine = AutoLiftEngine::new(&defs, &["react".to_string(), "nextjs".to_string()]);
+    let layout = |source: &str| RawEntity {
+        name: "SettingsLayout".to_string(),
+        kind: EntityKind::Function,
+        file: PathBuf::from("app/dashboard/settings/layout.tsx"),
+        line_start: 1,
+        line_end: source.lines().count(),
+        parent_class: None,
+        source_text: source.to_string(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+    };
+
+    let passthrough = engine
+        .try_lift_detailed(&layout(
+            "export default function SettingsLayout({ children }) {\n    return <>{children}</>;\n}",
+        ))
+        .unwrap();
+    assert_eq!(
+        passthrough.features,
+        vec!["pass page content through unchanged"]
+    );
+    assert_eq!(passthrough.confidence, LiftConfidence::Accept);
+    assert_eq!(passthrough.boilerplate.as_deref(), Some("nextjs"));
+
+    let dashboard =
+        std::fs::read_to_string(fixture_root().join("app/dashboard/layout.tsx")).unwrap();
+    assert!(engine.try_lift_detailed(&layout(&dashboard)).is_none());
+}
+
+#[test]
+fn test_boilerplate_requires_an_active_paradigm() {
+    let root = fixture_root();
+    let mut graph = build_graph(&root);
+    graph.metadata.paradigms.clear();
+    let queue = build_lift_queue(
+        &mut graph,
+        "*",
+        &root,
+        &rpg_core::config::EncodingConfig::default(),
+        &HashSet::new(),
+        &rpg_encoder::tokens::Heuristic,
+    )
+    .unwrap();
+    assert!(queue.boilerplate.is_empty());
+    assert!(
+        queue
+            .raw_entities
+            .iter()
+            .any(|raw| raw.file == Path::new(BARREL))
+    );
+}
@@ -0,0 +1,182 @@
+//! Integration test: churn analysis against a scripted git repository.
+//!
+//! An old commit (outside a 6-month window) creates three functions; four
+//! recent commits edit `hot` every time and `warm` once.
+
+use chrono::{Duration, TimeZone, Utc};
+use rpg_core::graph::{EntityChurn, EntityKind, RPGraph};
+use rpg_encoder::churn::{analyze_churn, parse_since};
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn source(hot: u32, warm: u32) -> String {
+    format!(
+        "pub fn hot() -> u32 {{\n    {hot}\n}}\n\n\
+         pub fn warm() -> u32 {{\n    {warm}\n}}\n\n\
+         pub fn cold() -> u32 {{\n    0\n}}\n"
+    )
+}
+
+/// Write `src/lib.rs` and commit it on HEAD at `time` (seconds since the epoch).
+fn commit(repo: &git2::Repository, content: &str, time: i64) {
+    let root = repo.workdir().unwrap();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(root.join("src/lib.rs"), content).unwrap();
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig =
+        git2::Signature::new("fixture", "fixture@example.com", &git2::Time::new(time, 0)).unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, "edit", &tree, &parents)
+        .unwrap();
+}
+
+fn scripted_repo() -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    let repo = git2::Repository::init(tmp.path()).unwrap();
+    let now = Utc::now();
+    commit(
+        &repo,
+        &source(0, 0),
+        (now - Duration::days(400)).timestamp(),
+    );
+    for (day, warm) in [(1, 0), (2, 0), (3, 7), (4, 7)] {
+        let time = now - Duration::days(40 - i64::from(day));
+        commit(&repo, &source(day, warm), time.timestamp());
+    }
+    tmp
+}
+
+fn build_graph(root: &Path) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let source = std::fs::read_to_string(root.join("src/lib.rs")).unwrap();
+    for raw in extract_entities(Path::new("src/lib.rs"), &source, Language::RUST) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    graph.refresh_metadata();
+    graph
+}
+
+fn churn(graph: &RPGraph, name: &str) -> Option<EntityChurn> {
+    graph.entities[&format!("src/lib.rs:{}", name)].churn
+}
+
+#[test]
+fn test_frequently_edited_function_ranks_first() {
+    let tmp = scripted_repo();
+    let mut graph = build_graph(tmp.path());
+
+    let range = analyze_churn(&mut graph, tmp.path(), "6m").unwrap();
+    assert_eq!(range.commits, 4);
+    assert_eq!(graph.metadata.churn.as_ref(), Some(&range));
+    // Each edit replaces one line: one deleted plus one added.
+    assert_eq!(
+        churn(&graph, "hot"),
+        Some(EntityChurn {
+            commits: 4,
+            lines: 8
+        })
+    );
+    assert_eq!(churn(&graph, "warm").map(|c| c.commits), Some(1));
+    assert_eq!(churn(&graph, "cold"), None);
+    assert!(
+        graph
+            .entities
+            .values()
+            .filter(|e| e.kind == EntityKind::Module)
+            .all(|e| e.churn.is_none())
+    );
+
+    let report = rpg_nav::health::compute_health(&graph, &rpg_nav::health::HealthConfig::default());
+    let top: Vec<&str> = report.top_churn.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(top, ["hot", "warm"]);
+    let text = rpg_nav::toon::format_health_report(&report);
+    assert!(
+        text.contains("## Churn Hotspots (since 6m: 4 commits)"),
+        "{}",
+        text
+    );
+    assert!(text.contains("src/lib.rs:hot (function) | commits=4 lines=8"));
+
+    // A wider window reaches the commit that created every function, and
+    // re-running replaces the previous scores.
+    analyze_churn(&mut graph, tmp.path(), "2y").unwrap();
+    assert_eq!(churn(&graph, "cold").map(|c| c.commits), Some(1));
+    assert_eq!(churn(&graph, "hot").map(|c| c.commits), Some(5));
+}
+
+#[test]
+fn test_search_sorts_matches_by_churn() {
+    let tmp = scripted_repo();
+    let mut graph = build_graph(tmp.path());
+    for (name, features) in [
+        ("hot", vec!["compute value"]),
+        ("warm", vec!["compute value"]),
+        ("cold", vec!["compute value", "compute default value"]),
+    ] {
+        graph
+            .entities
+            .get_mut(&format!("src/lib.rs:{}", name))
+            .unwrap()
+            .semantic_features = features.into_iter().map(String::from).collect();
+    }
+    analyze_churn(&mut graph, tmp.path(), "6m").unwrap();
+
+    let names = |sort, limit| {
+        search_with_params(
+            &graph,
+            &SearchParams {
+                query: "compute value",
+                mode: SearchMode::Features,
+                scope: None,
+                limit,
+                line_nums: None,
+                file_pattern: None,
+                entity_type_filter: None,
+                layer_filter: None,
+                embedding_scores: None,
+                diff_context: None,
+                changed_entities: None,
+                vocabulary: None,
+                sort,
+                include_fixtures: false,
+                exclude_tests: false,
+                async_only: false,
+                deadline: None,
+                group_by_file: false,
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .map(|r| r.entity_name)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(names(SearchSort::Relevance, 1), ["cold"]);
+    assert_eq!(names(SearchSort::Churn, 10), ["hot", "warm", "cold"]);
+    // The whole match set is ranked before the limit applies.
+    assert_eq!(names(SearchSort::Churn, 1), ["hot"]);
+}
+
+#[test]
+fn test_parse_since_windows_and_dates() {
+    let now = Utc.with_ymd_and_hms(2025, 7, 1, 12, 0, 0).unwrap();
+    assert_eq!(parse_since("6m", now).unwrap(), now - Duration::days(180));
+    assert_eq!(parse_since("2w", now).unwrap(), now - Duration::days(14));
+    assert_eq!(parse_since("1y", now).unwrap(), now - Duration::days(365));
+    assert_eq!(
+        parse_since("2025-01-31", now).unwrap(),
+        Utc.with_ymd_and_hms(2025, 1, 31, 0, 0, 0).unwrap()
+    );
+    for bad in ["", "m", "6", "6x", "-6m"] {
+        assert!(parse_since(bad, now).is_err(), "{}", bad);
+    }
+}
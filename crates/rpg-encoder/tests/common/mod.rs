@@ -0,0 +1,31 @@
+//! Fixtures shared by the integration tests.
+
+use rpg_core::graph::RPGraph;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Write `files` into a temp project and build a structural graph with
+/// Module entities and resolved dependencies. Files the parser has no
+/// language for (e.g. configs) are written but not indexed.
+pub fn build(language: &str, files: &[(&str, &str)]) -> (TempDir, RPGraph) {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = RPGraph::new(language);
+    for (rel, source) in files {
+        let path = tmp.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        let Some(language) = Language::from_path(Path::new(rel)) else {
+            continue;
+        };
+        for raw in extract_entities(Path::new(rel), source, language) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.rebuild_edge_index();
+    (tmp, graph)
+}
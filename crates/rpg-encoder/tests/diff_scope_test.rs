@@ -0,0 +1,206 @@
+//! Integration test: diff-scoped search against a scripted git repository.
+//!
+//! The fixture repo gets a base commit, a commit touching one function in each
+//! of two files, and a side commit that only `rev...HEAD` semantics exclude.
+
+use rpg_core::graph::RPGraph;
+use rpg_encoder::evolution::{diff_hunks_since, diff_scope};
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const AUTH_V1: &str = "pub fn login(user: &str) -> bool {
+    !user.is_empty()
+}
+
+pub fn logout(user: &str) {
+    let _ = user;
+}
+";
+
+const AUTH_V2: &str = "pub fn login(user: &str) -> bool {
+    !user.is_empty()
+}
+
+pub fn logout(user: &str) {
+    println!(\"bye {}\", user);
+}
+";
+
+const DB_V1: &str = "pub fn connect() -> u32 {
+    1
+}
+
+pub fn query(id: u32) -> u32 {
+    id
+}
+";
+
+const DB_V2: &str = "pub fn connect() -> u32 {
+    2
+}
+
+pub fn query(id: u32) -> u32 {
+    id
+}
+";
+
+const UTIL: &str = "pub fn helper() -> u32 {
+    0
+}
+";
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Stage the whole worktree and commit it. `update_ref` of `None` leaves HEAD alone.
+fn commit_all(
+    repo: &git2::Repository,
+    message: &str,
+    update_ref: Option<&str>,
+    parents: &[&git2::Commit],
+) -> git2::Oid {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    repo.commit(update_ref, &sig, &sig, message, &tree, parents)
+        .unwrap()
+}
+
+/// Base commit, a side commit off it (touches util.rs only), and HEAD touching
+/// `logout` in auth.rs and `connect` in db.rs. Returns (repo dir, base sha, side sha).
+fn scripted_repo() -> (TempDir, String, String) {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let repo = git2::Repository::init(root).unwrap();
+
+    write(root, "src/auth.rs", AUTH_V1);
+    write(root, "src/db.rs", DB_V1);
+    write(root, "src/util.rs", UTIL);
+    let base = commit_all(&repo, "base", Some("HEAD"), &[]);
+    let base_commit = repo.find_commit(base).unwrap();
+
+    write(root, "src/util.rs", "pub fn helper() -> u32 {\n    42\n}\n");
+    let side = commit_all(&repo, "side", None, &[&base_commit]);
+    write(root, "src/util.rs", UTIL);
+
+    write(root, "src/auth.rs", AUTH_V2);
+    write(root, "src/db.rs", DB_V2);
+    commit_all(&repo, "touch two files", Some("HEAD"), &[&base_commit]);
+
+    (tmp, base.to_string(), side.to_string())
+}
+
+/// Graph of the worktree as it is now, based at HEAD.
+fn build_graph(root: &Path) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for rel in ["src/auth.rs", "src/db.rs", "src/util.rs"] {
+        let source = std::fs::read_to_string(root.join(rel)).unwrap();
+        for raw in extract_entities(Path::new(rel), &source, Language::RUST) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.base_commit = Some(rpg_encoder::evolution::get_head_sha(root).unwrap());
+    graph.refresh_metadata();
+    graph
+}
+
+fn ids(set: &HashSet<String>) -> Vec<&str> {
+    let mut ids: Vec<&str> = set.iter().map(String::as_str).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[test]
+fn test_hunks_cover_only_changed_lines() {
+    let (tmp, base, _) = scripted_repo();
+    let hunks = diff_hunks_since(tmp.path(), &base).unwrap();
+    assert_eq!(
+        hunks.keys().collect::<Vec<_>>(),
+        vec![Path::new("src/auth.rs"), Path::new("src/db.rs")]
+    );
+    assert_eq!(hunks[Path::new("src/auth.rs")], vec![(6, 6)]);
+    assert_eq!(hunks[Path::new("src/db.rs")], vec![(2, 2)]);
+}
+
+#[test]
+fn test_diff_scope_restricts_search_to_touched_entities() {
+    let (tmp, base, _) = scripted_repo();
+    let graph = build_graph(tmp.path());
+
+    let scope = diff_scope(tmp.path(), &graph, &base).unwrap();
+    assert_eq!(
+        ids(&scope.entities),
+        vec!["src/auth.rs:logout", "src/db.rs:connect"]
+    );
+    assert!(scope.stale_files.is_empty());
+
+    let results = search_with_params(
+        &graph,
+        &SearchParams {
+            query: "login logout connect query helper",
+            mode: SearchMode::Snippets,
+            scope: None,
+            limit: 10,
+            line_nums: None,
+            file_pattern: None,
+            entity_type_filter: None,
+            layer_filter: None,
+            embedding_scores: None,
+            diff_context: None,
+            changed_entities: Some(&scope.entities),
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
+        },
+    )
+    .unwrap();
+    let mut found: Vec<&str> = results.iter().map(|r| r.entity_id.as_str()).collect();
+    found.sort_unstable();
+    assert_eq!(found, vec!["src/auth.rs:logout", "src/db.rs:connect"]);
+}
+
+#[test]
+fn test_diff_scope_uses_merge_base() {
+    // The side commit's util.rs change is not on HEAD, so `side...HEAD` is the
+    // same as `base...HEAD`.
+    let (tmp, _, side) = scripted_repo();
+    let graph = build_graph(tmp.path());
+    let scope = diff_scope(tmp.path(), &graph, &side).unwrap();
+    assert_eq!(
+        ids(&scope.entities),
+        vec!["src/auth.rs:logout", "src/db.rs:connect"]
+    );
+}
+
+#[test]
+fn test_diff_scope_marks_files_changed_since_build() {
+    let (tmp, base, _) = scripted_repo();
+    let graph = build_graph(tmp.path());
+    // Uncommitted edit after the graph was built: auth.rs is now stale.
+    write(
+        tmp.path(),
+        "src/auth.rs",
+        &format!("// edited\n{}", AUTH_V2),
+    );
+
+    let scope = diff_scope(tmp.path(), &graph, &base).unwrap();
+    let stale: Vec<&PathBuf> = scope.stale_files.iter().collect();
+    assert_eq!(stale, vec![&PathBuf::from("src/auth.rs")]);
+
+    assert!(diff_scope(tmp.path(), &graph, "no-such-rev").is_err());
+}
@@ -0,0 +1,99 @@
+//! Integration test: event emits link to the handlers registered for the
+//! same event name with Dispatches edges.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+
+fn dispatched(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == EdgeKind::Dispatches)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets
+}
+
+#[test]
+fn test_emit_links_to_handlers_in_other_files() {
+    let (_tmp, graph) = common::build(
+        "typescript",
+        &[
+            (
+                "src/worker.ts",
+                "import { bus } from './bus';\n\n\
+             export function finishJob(id: string) {\n  bus.emit('job:done', id);\n}\n",
+            ),
+            (
+                "src/notify.ts",
+                "import { bus } from './bus';\n\n\
+             export function onJobDone(id: string) {\n  console.log(id);\n}\n\n\
+             bus.on('job:done', onJobDone);\n",
+            ),
+            (
+                "src/audit.ts",
+                "import { bus } from './bus';\n\n\
+             export function registerAudit() {\n  bus.once(`job:done`, (id) => record(id));\n}\n",
+            ),
+            (
+                "src/bus.ts",
+                "import { EventEmitter } from 'events';\n\nexport const bus = new EventEmitter();\n",
+            ),
+        ],
+    );
+
+    // The named handler owns its event; the inline one is owned by the
+    // function registering it.
+    assert_eq!(
+        graph.entities["src/notify.ts:onJobDone"].deps.handles,
+        vec!["job:done"]
+    );
+    assert_eq!(
+        dispatched(&graph, "src/worker.ts:finishJob"),
+        vec!["src/audit.ts:registerAudit", "src/notify.ts:onJobDone"]
+    );
+    assert_eq!(
+        graph.entities["src/notify.ts:onJobDone"].deps.dispatched_by,
+        vec!["src/worker.ts:finishJob"]
+    );
+}
+
+#[test]
+fn test_same_file_handlers_win_and_dynamic_names_stay_unresolved() {
+    let (_tmp, graph) = common::build(
+        "typescript",
+        &[
+            (
+                "src/queue.ts",
+                "export class Queue extends EventEmitter {\n\
+             \x20 push(job: string) {\n    this.emit('added', job);\n  }\n\n\
+             \x20 fire(name: string) {\n    this.emit(name);\n  }\n\n\
+             \x20 start() {\n    this.on('added', this.handleAdded.bind(this));\n  }\n\n\
+             \x20 handleAdded(job: string) {\n    return job;\n  }\n}\n",
+            ),
+            (
+                "src/metrics.ts",
+                "export function countAdded() {\n  return 1;\n}\n\nqueue.on('added', countAdded);\n",
+            ),
+        ],
+    );
+
+    assert_eq!(
+        dispatched(&graph, "src/queue.ts:Queue::push"),
+        vec!["src/queue.ts:Queue::handleAdded"]
+    );
+    let fire = &graph.entities["src/queue.ts:Queue::fire"];
+    assert!(fire.deps.emits.is_empty());
+    assert_eq!(fire.deps.unresolved_events, vec!["name"]);
+    assert!(dispatched(&graph, "src/queue.ts:Queue::fire").is_empty());
+
+    let stats = graph
+        .metadata
+        .edge_resolution
+        .iter()
+        .find(|s| s.language == "typescript" && s.kind == EdgeKind::Dispatches)
+        .unwrap();
+    assert_eq!((stats.raw, stats.resolved), (2, 1));
+}
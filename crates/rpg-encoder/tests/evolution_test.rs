@@ -1,7 +1,10 @@
+use rpg_core::config::RiskConfig;
 use rpg_core::graph::*;
 use rpg_encoder::evolution::{
-    apply_deletions, apply_renames, compute_drift, merge_features, rebuild_hierarchy_from_entities,
+    FileChange, RiskLevel, apply_deletions, apply_renames, assess_change_risk, compute_drift,
+    merge_features, rebuild_hierarchy_from_entities,
 };
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str) -> Entity {
@@ -15,9 +18,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: vec!["test".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -182,9 +198,22 @@ fn make_lifted_entity(id: &str, name: &str, file: &str, features: &[&str]) -> En
         parent_class: None,
         semantic_features: features.iter().map(|s| (*s).to_string()).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -199,9 +228,22 @@ fn make_module_entity(id: &str, name: &str, file: &str, features: &[&str]) -> En
         parent_class: None,
         semantic_features: features.iter().map(|s| (*s).to_string()).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -434,3 +476,96 @@ fn test_merge_hierarchy_path_overwrites_structural() {
         "Security/auth/validate"
     );
 }
+
+// --- assess_change_risk tests ---
+
+/// `core.rs:graph` is invoked from twelve caller files; `util.rs:pad` only from its own file.
+fn make_hub_and_leaf_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("core.rs:graph", "graph", "core.rs"));
+    graph.insert_entity(make_entity("util.rs:pad", "pad", "util.rs"));
+    graph.insert_entity(make_entity("util.rs:format", "format", "util.rs"));
+    graph.edges.push(DependencyEdge {
+        source: "util.rs:format".to_string(),
+        target: "util.rs:pad".to_string(),
+        kind: EdgeKind::Invokes,
+    });
+    for i in 0..12 {
+        let id = format!("caller{}.rs:run", i);
+        graph.insert_entity(make_entity(&id, "run", &format!("caller{}.rs", i)));
+        graph.edges.push(DependencyEdge {
+            source: id,
+            target: "core.rs:graph".to_string(),
+            kind: EdgeKind::Invokes,
+        });
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+#[test]
+fn test_change_risk_hub_file_is_high() {
+    let graph = make_hub_and_leaf_graph();
+    let changes = vec![
+        FileChange::Modified(PathBuf::from("util.rs")),
+        FileChange::Modified(PathBuf::from("core.rs")),
+    ];
+    let risk = assess_change_risk(&graph, &changes, &RiskConfig::default());
+
+    assert_eq!(risk.level, RiskLevel::High);
+    assert_eq!(risk.files[0].path, PathBuf::from("core.rs"));
+    assert_eq!(risk.files[0].fan_in, 12);
+    assert_eq!(risk.files[0].public_entities, 1);
+    assert_eq!(
+        risk.summary(),
+        "risk: high (1 high-fan-in file incl. core.rs)"
+    );
+}
+
+#[test]
+fn test_change_risk_leaf_file_is_low() {
+    let graph = make_hub_and_leaf_graph();
+    let changes = vec![
+        FileChange::Modified(PathBuf::from("util.rs")),
+        FileChange::Added(PathBuf::from("new.rs")),
+    ];
+    let risk = assess_change_risk(&graph, &changes, &RiskConfig::default());
+
+    assert_eq!(risk.level, RiskLevel::Low);
+    // Same-file calls don't count toward fan-in.
+    assert!(risk.files.iter().all(|f| f.fan_in == 0));
+    assert_eq!(risk.summary(), "risk: low (leaf files only)");
+}
+
+#[test]
+fn test_change_risk_thresholds_from_config() {
+    let graph = make_hub_and_leaf_graph();
+    let changes = vec![FileChange::Renamed {
+        from: PathBuf::from("core.rs"),
+        to: PathBuf::from("graph.rs"),
+    }];
+    let config = RiskConfig {
+        high_fan_in: 20,
+        medium_fan_in: 5,
+    };
+    let risk = assess_change_risk(&graph, &changes, &config);
+    assert_eq!(risk.level, RiskLevel::Medium);
+    assert_eq!(
+        risk.summary(),
+        "risk: medium (1 file with outside dependents incl. core.rs)"
+    );
+}
+
+#[test]
+fn test_change_risk_routes_are_at_least_medium() {
+    let mut graph = RPGraph::new("python");
+    let mut route = make_entity("app.py:list_users", "list_users", "app.py");
+    route.kind = EntityKind::Route;
+    graph.insert_entity(route);
+
+    let changes = vec![FileChange::Deleted(PathBuf::from("app.py"))];
+    let risk = assess_change_risk(&graph, &changes, &RiskConfig::default());
+    assert_eq!(risk.level, RiskLevel::Medium);
+    assert_eq!(risk.files[0].fan_in, 0);
+    assert_eq!(risk.files[0].public_entities, 1);
+}
@@ -0,0 +1,146 @@
+//! Integration test: a FastAPI service with Pydantic schemas.
+//!
+//! Decorated handlers become Routes with a `handle METHOD /path` feature
+//! (router prefixes included), and the Pydantic models in their signatures
+//! and `response_model` get Composes edges from the route.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_encoder::grounding;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/fastapi_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path.extension().is_some_and(|e| e == "py") {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph() -> RPGraph {
+    let root = fixture_root();
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(&root, &[Language::PYTHON], &paradigm_defs);
+    assert!(active_defs.iter().any(|d| d.name == "fastapi"));
+
+    let mut graph = RPGraph::new("python");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    let ctx = grounding::ParadigmContext {
+        active_defs,
+        qcache: &qcache,
+    };
+    grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn composes(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Composes && e.source == source)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+    targets
+}
+
+#[test]
+fn test_pyproject_dependency_activates_fastapi() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        tmp.path().join("pyproject.toml"),
+        "[project]\nname = \"svc\"\ndependencies = [\"fastapi>=0.110\", \"uvicorn\"]\n",
+    )
+    .unwrap();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let active = rpg_parser::paradigms::detect_paradigms_toml(
+        tmp.path(),
+        &[Language::PYTHON],
+        &paradigm_defs,
+    );
+    assert!(active.iter().any(|d| d.name == "fastapi"));
+}
+
+#[test]
+fn test_routes_get_method_and_path_features() {
+    let graph = build_graph();
+    let features = |id: &str| {
+        let entity = graph
+            .get_entity(id)
+            .unwrap_or_else(|| panic!("missing {}", id));
+        assert_eq!(entity.kind, EntityKind::Route, "{}", id);
+        assert_eq!(entity.feature_source.as_deref(), Some("paradigm"), "{}", id);
+        assert!(!entity.attributes.contains_key("features"), "{}", id);
+        entity.semantic_features.clone()
+    };
+
+    assert_eq!(features("app/main.py:list_users"), ["handle GET /users"]);
+    assert_eq!(features("app/main.py:create_user"), ["handle POST /users"]);
+    // `APIRouter(prefix="/users")` is joined onto the decorator path.
+    assert_eq!(
+        features("app/users.py:read_user"),
+        ["handle GET /users/{user_id}"]
+    );
+    assert_eq!(
+        graph
+            .get_entity("app/users.py:update_user")
+            .unwrap()
+            .attributes["route"],
+        "PUT /users/{user_id}"
+    );
+
+    // Models are not routes and are left to lifting.
+    let model = graph.get_entity("app/schemas.py:UserCreate").unwrap();
+    assert_eq!(model.kind, EntityKind::Model);
+    assert!(model.semantic_features.is_empty());
+}
+
+#[test]
+fn test_signature_models_compose_into_routes() {
+    let graph = build_graph();
+    // Parameter and return annotations.
+    assert_eq!(
+        composes(&graph, "app/users.py:update_user"),
+        ["app/schemas.py:UserCreate", "app/schemas.py:UserOut"]
+    );
+    // `response_model=`.
+    assert_eq!(
+        composes(&graph, "app/users.py:read_user"),
+        ["app/schemas.py:UserOut"]
+    );
+    // `dict` and `Optional[str]` are not models.
+    assert!(composes(&graph, "app/main.py:create_user").is_empty());
+
+    let model = graph.get_entity("app/schemas.py:UserOut").unwrap();
+    assert!(
+        model
+            .deps
+            .composed_by
+            .contains(&"app/users.py:read_user".to_string())
+    );
+}
@@ -0,0 +1,152 @@
+//! Feature source matrix, age buckets, and re-lift plan over a graph with
+//! auto-lifted, LLM-lifted, synthesized, undated, and unlifted entities.
+
+use chrono::{Duration, TimeZone, Utc};
+use rpg_core::config::EncodingConfig;
+use rpg_core::files::WorktreeFiles;
+use rpg_core::graph::{EntityChurn, RPGraph};
+use rpg_encoder::feature_sources::{FeatureSourceReport, analyze, format_report};
+use rpg_encoder::tokens::Heuristic;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+const AUTH: &str = "def login(user):\n    return user\n\n\
+                    def logout(user):\n    return None\n\n\
+                    def verify(token):\n    return bool(token)\n";
+const DB: &str = "def save(row):\n    return row\n\n\
+                  def load(key):\n    return key\n";
+
+/// (entity ID, area, feature_source, days since lifted)
+type Lifted = (&'static str, &'static str, &'static str, Option<i64>);
+
+fn report() -> (TempDir, FeatureSourceReport) {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = RPGraph::new("python");
+    for (rel, source) in [("src/auth.py", AUTH), ("src/db.py", DB)] {
+        let path = tmp.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        for raw in extract_entities(Path::new(rel), source, Language::PYTHON) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+    let lifted: &[Lifted] = &[
+        ("src/auth.py:login", "Auth", "auto", Some(10)),
+        ("src/auth.py:logout", "Auth", "auto-review", Some(40)),
+        ("src/auth.py:verify", "Auth", "llm", Some(100)),
+        ("src/auth.py:auth", "Auth", "synthesized", None),
+        ("src/db.py:save", "Storage", "llm", Some(5)),
+    ];
+    for (id, area, source, days) in lifted {
+        let entity = graph.entities.get_mut(*id).unwrap();
+        entity.hierarchy_path = format!("{}/core/ops", area);
+        entity.semantic_features = vec!["do work".to_string()];
+        entity.feature_source = Some(source.to_string());
+        entity.lifted_at = days.map(|d| now - Duration::days(d));
+    }
+    for id in ["src/db.py:load", "src/db.py:db"] {
+        graph.entities.get_mut(id).unwrap().hierarchy_path = "Storage/core/ops".to_string();
+    }
+    graph.entities.get_mut("src/db.py:save").unwrap().churn = Some(EntityChurn {
+        commits: 5,
+        lines: 40,
+    });
+
+    let encoding = EncodingConfig {
+        batch_size: 2,
+        ..EncodingConfig::default()
+    };
+    let report = analyze(
+        &graph,
+        &WorktreeFiles::new(tmp.path()),
+        &encoding,
+        &Heuristic,
+        now,
+    );
+    (tmp, report)
+}
+
+fn counts(pairs: &[(&str, usize)]) -> BTreeMap<String, usize> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+#[test]
+fn test_matrix_counts_sources_per_area() {
+    let (_tmp, report) = report();
+    let areas: Vec<&str> = report.areas.iter().map(|a| a.area.as_str()).collect();
+    assert_eq!(areas, vec!["Auth", "Storage"]);
+
+    let auth = &report.areas[0];
+    assert_eq!(auth.entities, 4);
+    assert_eq!(
+        auth.sources,
+        counts(&[
+            ("auto", 1),
+            ("auto-review", 1),
+            ("llm", 1),
+            ("synthesized", 1)
+        ])
+    );
+    let storage = &report.areas[1];
+    assert_eq!(storage.entities, 3);
+    assert_eq!(storage.sources, counts(&[("llm", 1), ("unlifted", 2)]));
+    assert_eq!(storage.churn, 5);
+
+    assert_eq!(report.total.entities, 7);
+    assert_eq!(
+        report.total.sources,
+        counts(&[
+            ("auto", 1),
+            ("auto-review", 1),
+            ("llm", 2),
+            ("synthesized", 1),
+            ("unlifted", 2),
+        ])
+    );
+}
+
+#[test]
+fn test_age_buckets_skip_unlifted_entities() {
+    let (_tmp, report) = report();
+    let auth = &report.areas[0];
+    assert_eq!(
+        (auth.older_than_30d, auth.older_than_90d, auth.undated),
+        (2, 1, 1)
+    );
+    let storage = &report.areas[1];
+    assert_eq!(
+        (
+            storage.older_than_30d,
+            storage.older_than_90d,
+            storage.undated
+        ),
+        (0, 0, 0)
+    );
+}
+
+#[test]
+fn test_plan_orders_by_auto_share_then_churn_and_sizes_batches() {
+    let (_tmp, report) = report();
+    let plan: Vec<(&str, usize, usize, usize)> = report
+        .plan
+        .iter()
+        .map(|s| (s.area.as_str(), s.churn, s.entities, s.batches))
+        .collect();
+    // Auth: 2 of 4 lifted are auto-lifted; 3 liftable entities at 2 per batch.
+    assert_eq!(plan, vec![("Auth", 0, 3, 2), ("Storage", 5, 2, 1)]);
+    assert!((report.plan[0].auto_share - 0.5).abs() < f64::EPSILON);
+
+    let text = format_report(&report, 1);
+    assert!(text.contains("... 1 more area(s)"), "{}", text);
+    assert!(
+        text.contains("re-lifting every area: 5 entities in 3 batches"),
+        "{}",
+        text
+    );
+}
@@ -0,0 +1,184 @@
+//! Fixture files: indexed and tagged, but out of lifting scope, hierarchy
+//! construction, and default search results.
+
+use rpg_core::config::EncodingConfig;
+use rpg_core::graph::*;
+use rpg_encoder::fixtures::tag_fixtures;
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+fn make_entity(file: &str, name: &str) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 3,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+const PAGE: &str = "tests/fixtures/nextjs_app/app/page.tsx";
+const SAMPLE: &str = "web/__fixtures__/sample.ts";
+
+fn project() -> RPGraph {
+    let mut graph = RPGraph::new("typescript");
+    for (file, name) in [
+        ("src/render.ts", "render_page"),
+        ("src/router.ts", "route_request"),
+        (PAGE, "render_home"),
+        (SAMPLE, "sample_render"),
+    ] {
+        graph.insert_entity(make_entity(file, name));
+    }
+    graph.create_module_entities();
+    graph.refresh_metadata();
+    graph
+}
+
+fn search_render(graph: &RPGraph, include_fixtures: bool) -> Vec<String> {
+    let mut ids: Vec<String> = search_with_params(
+        graph,
+        &SearchParams {
+            query: "render",
+            mode: SearchMode::Snippets,
+            scope: None,
+            limit: 10,
+            line_nums: None,
+            file_pattern: None,
+            entity_type_filter: Some(vec![EntityKind::Function]),
+            layer_filter: None,
+            embedding_scores: None,
+            diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
+        },
+    )
+    .unwrap()
+    .into_iter()
+    .map(|r| r.entity_id)
+    .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_fixture_files_tagged_and_excluded_from_lifting() {
+    let mut graph = project();
+    let globs = EncodingConfig::default().fixture_globs;
+    assert_eq!(tag_fixtures(&mut graph, &globs), 2);
+
+    let home = &graph.entities[&format!("{}:render_home", PAGE)];
+    assert!(home.is_fixture());
+    assert!(home.tags.contains(FIXTURE_TAG));
+    // The Module entity of a fixture file is tagged too.
+    assert!(graph.entities.values().any(|e| {
+        e.kind == EntityKind::Module
+            && e.file.as_path() == std::path::Path::new(SAMPLE)
+            && e.is_fixture()
+    }));
+    assert!(!graph.entities["src/render.ts:render_page"].is_fixture());
+
+    // Fixtures are neither liftable nor reachable through any scope form.
+    assert_eq!(graph.lifting_coverage(), (0, 2));
+    assert_eq!(graph.lifting_exclusions().get("fixture"), Some(&2));
+    let mut all = rpg_encoder::lift::resolve_scope(&graph, "*")
+        .unwrap()
+        .entity_ids;
+    all.sort();
+    assert_eq!(
+        all,
+        vec!["src/render.ts:render_page", "src/router.ts:route_request"]
+    );
+    assert!(
+        rpg_encoder::lift::resolve_scope(&graph, "tests/**")
+            .unwrap()
+            .entity_ids
+            .is_empty()
+    );
+    assert!(
+        rpg_encoder::lift::resolve_scope(&graph, &format!("{}:render_home", PAGE))
+            .unwrap()
+            .entity_ids
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_fixture_modules_left_out_of_hierarchy_clusters() {
+    let mut graph = project();
+    tag_fixtures(&mut graph, &EncodingConfig::default().fixture_globs);
+    // Even a fixture lifted before it was classified stays out.
+    for entity in graph.entities.values_mut() {
+        if entity.kind == EntityKind::Module {
+            entity.semantic_features = vec!["render pages".to_string()];
+        }
+    }
+
+    let clusters = rpg_encoder::hierarchy::cluster_files_for_hierarchy(&graph, 70);
+    let files: Vec<&String> = clusters.iter().flat_map(|c| &c.files).collect();
+    assert_eq!(files, vec!["src/render.ts", "src/router.ts"]);
+}
+
+#[test]
+fn test_search_skips_fixtures_unless_requested() {
+    let mut graph = project();
+    tag_fixtures(&mut graph, &EncodingConfig::default().fixture_globs);
+
+    assert_eq!(
+        search_render(&graph, false),
+        vec!["src/render.ts:render_page"]
+    );
+    assert_eq!(
+        search_render(&graph, true),
+        vec![
+            "src/render.ts:render_page".to_string(),
+            format!("{}:render_home", PAGE),
+            format!("{}:sample_render", SAMPLE),
+        ]
+    );
+}
+
+#[test]
+fn test_retagging_follows_glob_changes() {
+    let mut graph = project();
+    tag_fixtures(&mut graph, &EncodingConfig::default().fixture_globs);
+
+    // Narrowing the globs releases the other fixture for lifting.
+    assert_eq!(
+        tag_fixtures(&mut graph, &["tests/fixtures/**".to_string()]),
+        1
+    );
+    assert!(!graph.entities[&format!("{}:sample_render", SAMPLE)].is_fixture());
+    assert_eq!(graph.lifting_coverage(), (0, 3));
+
+    // No globs, no fixtures; invalid globs are skipped.
+    assert_eq!(tag_fixtures(&mut graph, &["[".to_string()]), 0);
+    assert!(graph.entities.values().all(|e| !e.is_fixture()));
+}
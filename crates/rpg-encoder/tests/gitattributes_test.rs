@@ -0,0 +1,90 @@
+//! Tests for skipping files `.gitattributes` marks as vendored, generated, or
+//! documentation.
+
+use rpg_core::config::EncodingConfig;
+use rpg_encoder::evolution::{FileChange, filter_linguist_changes};
+use rpg_encoder::gitattributes::LinguistAttributes;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const ATTRIBUTES: &str = r#"# Vendored dependencies
+* text=auto
+third_party/** linguist-vendored
+vendor/        linguist-vendored
+*.pb.go        linguist-generated=true
+/docs/*.js     linguist-documentation
+third_party/keep/** -linguist-vendored
+"generated file.ts" linguist-generated
+!negated.ts    linguist-vendored
+"#;
+
+fn project(attributes: &str) -> TempDir {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join(".gitattributes"), attributes).unwrap();
+    tmp
+}
+
+#[test]
+fn test_vendored_directory_is_excluded() {
+    let tmp = project(ATTRIBUTES);
+    let attrs = LinguistAttributes::load(tmp.path(), &EncodingConfig::default());
+    for path in [
+        "third_party/lib.js",
+        "third_party/nested/deep/util.py",
+        "api/user.pb.go",
+        "user.pb.go",
+        "docs/example.js",
+        "generated file.ts",
+    ] {
+        assert!(attrs.excludes(Path::new(path)), "{}", path);
+    }
+    for path in [
+        "src/main.rs",
+        // A later line unsets the attribute.
+        "third_party/keep/patched.js",
+        // Patterns with a slash are anchored at the root.
+        "src/third_party/lib.js",
+        "src/docs/example.js",
+        // `*` does not cross directories.
+        "docs/nested/example.js",
+        // Unlike gitignore, directory patterns don't cover their contents.
+        "vendor/lib.go",
+        // Negative patterns are not allowed.
+        "negated.ts",
+    ] {
+        assert!(!attrs.excludes(Path::new(path)), "{}", path);
+    }
+}
+
+#[test]
+fn test_honor_gitattributes_off_and_missing_file() {
+    let tmp = project(ATTRIBUTES);
+    let encoding = EncodingConfig {
+        honor_gitattributes: false,
+        ..Default::default()
+    };
+    let attrs = LinguistAttributes::load(tmp.path(), &encoding);
+    assert!(!attrs.excludes(Path::new("third_party/lib.js")));
+
+    let empty = TempDir::new().unwrap();
+    let attrs = LinguistAttributes::load(empty.path(), &EncodingConfig::default());
+    assert!(!attrs.excludes(Path::new("third_party/lib.js")));
+}
+
+#[test]
+fn test_filter_linguist_changes() {
+    let attrs = LinguistAttributes::parse("third_party/** linguist-vendored\n");
+    let changes = vec![
+        FileChange::Added(PathBuf::from("src/app.ts")),
+        FileChange::Modified(PathBuf::from("third_party/lib.ts")),
+        FileChange::Renamed {
+            from: PathBuf::from("src/old.ts"),
+            to: PathBuf::from("third_party/old.ts"),
+        },
+    ];
+    let filtered = filter_linguist_changes(&attrs, changes);
+    assert_eq!(
+        format!("{:?}", filtered),
+        "[Added(\"src/app.ts\"), Deleted(\"src/old.ts\")]"
+    );
+}
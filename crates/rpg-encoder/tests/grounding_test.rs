@@ -1,5 +1,6 @@
 use rpg_core::graph::*;
 use rpg_encoder::grounding::resolve_dependencies;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str) -> Entity {
@@ -13,9 +14,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: Vec::new(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -346,3 +360,320 @@ fn test_resolve_reads_state_to_selector() {
         "selector should have state_read_by reverse edge"
     );
 }
+
+/// `count` entities in `dir`, each calling the next one. With `mangle`, the
+/// call names match nothing, as if resolution were broken for the language.
+fn call_chain(graph: &mut RPGraph, dir: &str, ext: &str, count: usize, mangle: bool) {
+    for i in 0..count {
+        let file = format!("{}/f{}.{}", dir, i, ext);
+        let name = format!("{}_step{}", dir, i);
+        let mut entity = make_entity(&format!("{}:{}", file, name), &name, &file);
+        if i + 1 < count {
+            let callee = format!("{}_step{}", dir, i + 1);
+            entity.deps.invokes.push(if mangle {
+                format!("unresolvable::{}", callee)
+            } else {
+                callee
+            });
+        }
+        graph.insert_entity(entity);
+    }
+}
+
+#[test]
+fn test_resolution_stats_warn_when_a_language_resolves_nothing() {
+    use rpg_encoder::grounding::{format_resolution_table, resolution_warnings};
+
+    let mut graph = RPGraph::new("python");
+    call_chain(&mut graph, "py", "py", 30, false);
+    call_chain(&mut graph, "go", "go", 30, true);
+    // Too small to judge, even with no calls at all.
+    call_chain(&mut graph, "rs", "rs", 3, true);
+    let mut lone = make_entity("rs/lib.rs:lone", "lone", "rs/lib.rs");
+    lone.kind = EntityKind::Module;
+    graph.insert_entity(lone);
+
+    resolve_dependencies(&mut graph);
+
+    let stats = &graph.metadata.edge_resolution;
+    let row = |language: &str| {
+        stats
+            .iter()
+            .find(|s| s.language == language && s.kind == EdgeKind::Invokes)
+            .unwrap()
+    };
+    assert_eq!(
+        (
+            row("python").entities,
+            row("python").raw,
+            row("python").resolved
+        ),
+        (30, 29, 29)
+    );
+    assert_eq!(
+        (row("go").entities, row("go").raw, row("go").resolved),
+        (30, 29, 0)
+    );
+    assert_eq!(row("go").unresolved(), 29);
+    assert_eq!((row("rust").entities, row("rust").resolved), (3, 0));
+
+    assert_eq!(
+        resolution_warnings(stats),
+        vec![
+            "0 Invokes edges resolved for go (30 entities; 29 of 29 extracted names unresolved — likely a resolution bug)"
+                .to_string()
+        ]
+    );
+
+    let table = format_resolution_table(stats);
+    assert!(table.starts_with("language"), "{}", table);
+    assert!(
+        table
+            .lines()
+            .any(|l| l.split_whitespace().collect::<Vec<_>>()
+                == ["go", "invokes", "30", "29", "0", "29"]),
+        "{}",
+        table
+    );
+
+    // Stats survive a save/load round trip.
+    let json = serde_json::to_string(&graph.metadata).unwrap();
+    let metadata: GraphMetadata = serde_json::from_str(&json).unwrap();
+    assert_eq!(&metadata.edge_resolution, stats);
+}
+
+#[test]
+fn test_resolution_warning_when_nothing_extracted() {
+    let mut graph = RPGraph::new("go");
+    for i in 0..25 {
+        let file = format!("pkg/f{}.go", i);
+        graph.insert_entity(make_entity(
+            &format!("{}:f{}", file, i),
+            &format!("f{}", i),
+            &file,
+        ));
+    }
+    resolve_dependencies(&mut graph);
+    assert_eq!(
+        rpg_encoder::grounding::resolution_warnings(&graph.metadata.edge_resolution),
+        vec![
+            "0 Invokes edges resolved for go (25 entities; none extracted — likely an extraction bug)"
+                .to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_field_types_resolve_to_composes_across_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let files = [
+        (
+            "src/order.rs",
+            "use crate::customer::Customer;\n\npub struct Order {\n    customer: Customer,\n    items: Vec<LineItem>,\n    total: u64,\n}\n\npub struct LineItem {\n    sku: String,\n}\n",
+        ),
+        (
+            "src/customer.rs",
+            "pub struct Customer {\n    address: Option<Address>,\n    token: ExternalToken,\n}\n",
+        ),
+        (
+            "src/address.rs",
+            "pub struct Address {\n    city: String,\n}\n",
+        ),
+    ];
+    let mut graph = RPGraph::new("rust");
+    for (file, source) in files {
+        let path = tmp.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(file),
+            source,
+            rpg_parser::languages::Language::RUST,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+    resolve_dependencies(&mut graph);
+
+    let mut composes: Vec<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Composes)
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+    composes.sort_unstable();
+    // Same-file and cross-file types resolve; the external token does not.
+    assert_eq!(
+        composes,
+        vec![
+            ("src/customer.rs:Customer", "src/address.rs:Address"),
+            ("src/order.rs:Order", "src/customer.rs:Customer"),
+            ("src/order.rs:Order", "src/order.rs:LineItem"),
+        ]
+    );
+    let address = graph.get_entity("src/address.rs:Address").unwrap();
+    assert_eq!(address.deps.composed_by, vec!["src/customer.rs:Customer"]);
+
+    // Following Composes from the aggregate root yields its ownership tree.
+    let tree = rpg_nav::explore::explore(
+        &graph,
+        "src/order.rs:Order",
+        rpg_nav::explore::Direction::Downstream,
+        3,
+        Some(EdgeKind::Composes),
+    )
+    .unwrap();
+    let rendered = rpg_nav::explore::format_tree(&tree, 0);
+    assert!(
+        rendered.contains("composes (downstream): Customer [src/customer.rs]")
+            && rendered.contains("composes (downstream): Address [src/address.rs]")
+            && rendered.contains("composes (downstream): LineItem [src/order.rs]"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_go_interface_satisfaction_and_embedding() {
+    let tmp = tempfile::tempdir().unwrap();
+    let files = [
+        (
+            "store/store.go",
+            "package store\n\ntype Reader interface {\n\tGet(key string) string\n}\n\ntype ReadWriter interface {\n\tReader\n\tPut(key, value string)\n}\n\ntype Any interface{}\n",
+        ),
+        (
+            "store/memory.go",
+            "package store\n\ntype Memory struct {\n\tdata map[string]string\n}\n\nfunc (m *Memory) Get(key string) string { return m.data[key] }\n\nfunc (m *Memory) Put(key, value string) { m.data[key] = value }\n",
+        ),
+        (
+            "store/cache.go",
+            "package store\n\ntype Cache struct {\n\t*Memory\n\thits int\n}\n\ntype ReadOnly struct{}\n\nfunc (r ReadOnly) Get(key string) string { return \"\" }\n",
+        ),
+        (
+            "other/other.go",
+            "package other\n\ntype Remote struct{}\n\nfunc (r Remote) Get(key string) string { return key }\n",
+        ),
+    ];
+    let mut graph = RPGraph::new("go");
+    for (file, source) in files {
+        let path = tmp.path().join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(file),
+            source,
+            rpg_parser::languages::Language::GO,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+    resolve_dependencies(&mut graph);
+
+    let edges = |kind: EdgeKind| -> Vec<(&str, &str)> {
+        let mut edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .filter(|e| e.kind == kind)
+            .map(|e| (e.source.as_str(), e.target.as_str()))
+            .collect();
+        edges.sort_unstable();
+        edges
+    };
+    // Pointer-receiver and promoted methods count; the empty interface and
+    // types in other packages do not.
+    assert_eq!(
+        edges(EdgeKind::Inherits),
+        vec![
+            ("store/cache.go:Cache", "store/store.go:ReadWriter"),
+            ("store/cache.go:Cache", "store/store.go:Reader"),
+            ("store/cache.go:ReadOnly", "store/store.go:Reader"),
+            ("store/memory.go:Memory", "store/store.go:ReadWriter"),
+            ("store/memory.go:Memory", "store/store.go:Reader"),
+        ]
+    );
+    assert_eq!(
+        edges(EdgeKind::Composes),
+        vec![("store/cache.go:Cache", "store/memory.go:Memory")]
+    );
+}
+
+#[test]
+fn test_config_reads_attributed_to_innermost_entity() {
+    let tmp = tempfile::tempdir().unwrap();
+    let source = "const LEVEL: Option<&str> = option_env!(\"LOG_LEVEL\");\n\nstruct Server;\n\nimpl Server {\n    fn read_only(&self) -> bool {\n        std::env::var(\"RPG_READ_ONLY\").is_ok()\n    }\n}\n";
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/server.rs"), source).unwrap();
+    let mut graph = RPGraph::new("rust");
+    for raw in rpg_parser::entities::extract_entities(
+        std::path::Path::new("src/server.rs"),
+        source,
+        rpg_parser::languages::Language::RUST,
+    ) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+
+    let readers: Vec<(&str, &Vec<String>)> = graph
+        .entities
+        .values()
+        .filter(|e| !e.deps.env_reads.is_empty())
+        .map(|e| (e.id.as_str(), &e.deps.env_reads))
+        .collect();
+    assert_eq!(readers.len(), 2, "{:?}", readers);
+    // The method, not the enclosing impl or class, reads the variable.
+    let method = graph.get_entity("src/server.rs:Server::read_only").unwrap();
+    assert_eq!(method.deps.env_reads, vec!["RPG_READ_ONLY"]);
+    // A constant's initializer read belongs to the constant.
+    let level = graph.get_entity("src/server.rs:LEVEL").unwrap();
+    assert_eq!(level.kind, EntityKind::Constant);
+    assert_eq!(level.deps.env_reads, vec!["LOG_LEVEL"]);
+}
+
+#[test]
+fn test_match_arms_and_comparisons_invoke_enums_and_constants() {
+    let tmp = tempfile::tempdir().unwrap();
+    let files = [
+        (
+            "src/kind.rs",
+            "pub enum Kind {\n    Leaf,\n    Branch,\n}\n\npub const MAX_DEPTH: usize = 8;\n",
+        ),
+        (
+            "src/walk.rs",
+            "use crate::kind::{Kind, MAX_DEPTH};\n\npub fn descend(kind: Kind, depth: usize) -> bool {\n    match kind {\n        Kind::Leaf => false,\n        Kind::Branch => depth < MAX_DEPTH,\n    }\n}\n",
+        ),
+    ];
+    let mut graph = RPGraph::new("rust");
+    for (path, source) in files {
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(tmp.path().join(path), source).unwrap();
+        for raw in rpg_parser::entities::extract_entities(
+            std::path::Path::new(path),
+            source,
+            rpg_parser::languages::Language::RUST,
+        ) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+    resolve_dependencies(&mut graph);
+
+    assert_eq!(
+        graph.get_entity("src/kind.rs:Kind").unwrap().kind,
+        EntityKind::Enum
+    );
+    let invoked: Vec<&str> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == "src/walk.rs:descend" && e.kind == EdgeKind::Invokes)
+        .map(|e| e.target.as_str())
+        .collect();
+    assert_eq!(invoked, vec!["src/kind.rs:Kind", "src/kind.rs:MAX_DEPTH"]);
+    let max_depth = graph.get_entity("src/kind.rs:MAX_DEPTH").unwrap();
+    assert_eq!(max_depth.deps.invoked_by, vec!["src/walk.rs:descend"]);
+}
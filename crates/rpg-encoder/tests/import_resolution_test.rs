@@ -0,0 +1,122 @@
+//! Integration test: calls to a name defined in several files resolve to the
+//! file the caller imports.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+
+fn invoked(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == EdgeKind::Invokes)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets
+}
+
+#[test]
+fn test_python_call_resolves_to_imported_module() {
+    let (_tmp, graph) = common::build(
+        "",
+        &[
+            ("app/db/setup.py", "def init():\n    return 1\n"),
+            ("app/cache/setup.py", "def init():\n    return 2\n"),
+            (
+                "app/main.py",
+                "from app.db.setup import init\n\n\ndef start():\n    return init()\n",
+            ),
+            (
+                "app/cache/warm.py",
+                "from .setup import init\n\n\ndef warm():\n    return init()\n",
+            ),
+        ],
+    );
+    assert_eq!(
+        invoked(&graph, "app/main.py:start"),
+        vec!["app/db/setup.py:init"]
+    );
+    assert_eq!(
+        invoked(&graph, "app/cache/warm.py:warm"),
+        vec!["app/cache/setup.py:init"]
+    );
+}
+
+#[test]
+fn test_unimported_ambiguous_name_stays_unresolved() {
+    let (_tmp, graph) = common::build(
+        "",
+        &[
+            ("a/setup.py", "def init():\n    return 1\n"),
+            ("b/setup.py", "def init():\n    return 2\n"),
+            (
+                "main.py",
+                "import os\n\n\ndef start():\n    return init()\n",
+            ),
+        ],
+    );
+    assert!(invoked(&graph, "main.py:start").is_empty());
+}
+
+#[test]
+fn test_rust_crate_and_super_paths() {
+    let (_tmp, graph) = common::build(
+        "",
+        &[
+            ("src/net/mod.rs", "pub mod client;\npub fn init() {}\n"),
+            ("src/store.rs", "pub fn init() {}\n"),
+            (
+                "src/net/client.rs",
+                "use super::init;\n\npub fn connect() {\n    init();\n}\n",
+            ),
+            (
+                "src/main.rs",
+                "use crate::store::init;\n\nfn main() {\n    init();\n}\n",
+            ),
+        ],
+    );
+    assert_eq!(
+        invoked(&graph, "src/net/client.rs:connect"),
+        vec!["src/net/mod.rs:init"]
+    );
+    assert_eq!(
+        invoked(&graph, "src/main.rs:main"),
+        vec!["src/store.rs:init"]
+    );
+}
+
+#[test]
+fn test_typescript_relative_and_go_package_imports() {
+    let (_tmp, graph) = common::build(
+        "",
+        &[
+            (
+                "web/api/client.ts",
+                "export function init() { return 1; }\n",
+            ),
+            (
+                "web/store/index.ts",
+                "export function init() { return 2; }\n",
+            ),
+            (
+                "web/app.ts",
+                "import { init } from './store';\n\nexport function boot() {\n  return init();\n}\n",
+            ),
+            ("svc/db/db.go", "package db\n\nfunc Init() {}\n"),
+            ("svc/cache/cache.go", "package cache\n\nfunc Init() {}\n"),
+            (
+                "svc/main.go",
+                "package main\n\nimport \"example.com/svc/cache\"\n\nfunc main() {\n\tcache.Init()\n}\n",
+            ),
+        ],
+    );
+    assert_eq!(
+        invoked(&graph, "web/app.ts:boot"),
+        vec!["web/store/index.ts:init"]
+    );
+    assert_eq!(
+        invoked(&graph, "svc/main.go:main"),
+        vec!["svc/cache/cache.go:Init"]
+    );
+}
@@ -4,13 +4,16 @@
 //! apply incremental updates → verify graph integrity.
 //! Uses the Python fixture project from tests/fixtures/python_project.
 
-use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_core::config::DEFAULT_ROUTING_NEIGHBOR_WEIGHT;
+use rpg_core::graph::{DependencyEdge, EdgeKind, EntityKind, RPGraph};
 use rpg_encoder::evolution::{
     apply_additions, apply_deletions, apply_modifications, apply_renames, check_drift_and_reroute,
-    compute_drift, find_best_hierarchy_path, reroute_entity, route_new_entity, semantic_similarity,
+    compute_drift, find_best_hierarchy_path, neighborhood_vote, reroute_entity, route_new_entity,
+    semantic_similarity,
 };
 use rpg_parser::entities::{RawEntity, extract_entities};
 use rpg_parser::languages::Language;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 fn fixture_root() -> PathBuf {
@@ -808,7 +811,7 @@ fn test_apply_modifications_updates_entity_kind() {
     // Run apply_modifications — it re-extracts from source and should refresh kind
     let modified_files = vec![file_rel];
     let (modified, _added, _removed, _stale) =
-        apply_modifications(&mut graph, &modified_files, &tmp, None).unwrap();
+        apply_modifications(&mut graph, &modified_files, &tmp, None, None).unwrap();
     assert!(modified > 0, "should report modified entities");
 
     // Verify kind was refreshed back to Function
@@ -823,6 +826,103 @@ fn test_apply_modifications_updates_entity_kind() {
     let _ = std::fs::remove_dir_all(&tmp);
 }
 
+/// Serves one file's previous contents.
+struct PreviousSource(String);
+
+impl rpg_core::files::FileProvider for PreviousSource {
+    fn read_to_string(&self, _rel_path: &Path) -> std::io::Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
+#[test]
+fn test_apply_modifications_parses_edit_of_previous_source() {
+    let tmp = tempfile::tempdir().unwrap();
+    let file_rel = PathBuf::from("src/helper.py");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    let previous = "def helper():\n    pass\n\n\ndef other():\n    return 1\n";
+    let mut graph = RPGraph::new("python");
+    graph.metadata.languages = vec!["python".to_string()];
+    for raw in extract_entities(&file_rel, previous, Language::PYTHON) {
+        graph.insert_entity(raw.into_entity());
+    }
+
+    let current = "def helper():\n    pass\n\n\ndef renamed(x):\n    return x\n";
+    std::fs::write(tmp.path().join(&file_rel), current).unwrap();
+    let (_, added, removed, _) = apply_modifications(
+        &mut graph,
+        std::slice::from_ref(&file_rel),
+        tmp.path(),
+        None,
+        Some(&PreviousSource(previous.to_string())),
+    )
+    .unwrap();
+
+    assert_eq!((added, removed), (1, 1));
+    let renamed = &graph.entities["src/helper.py:renamed"];
+    assert_eq!((renamed.line_start, renamed.line_end), (5, 6));
+    assert!(!graph.entities.contains_key("src/helper.py:other"));
+}
+
+/// Fails the test if the previous contents are ever read.
+struct UnreadableSource;
+
+impl rpg_core::files::FileProvider for UnreadableSource {
+    fn read_to_string(&self, rel_path: &Path) -> std::io::Result<String> {
+        panic!("read previous contents of {}", rel_path.display());
+    }
+}
+
+#[test]
+fn test_apply_modifications_without_cached_tree_parses_once() {
+    let tmp = tempfile::tempdir().unwrap();
+    let file_rel = PathBuf::from("src/helper.py");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    let mut graph = RPGraph::new("python");
+    graph.metadata.languages = vec!["python".to_string()];
+    for raw in extract_entities(&file_rel, "def helper():\n    pass\n", Language::PYTHON) {
+        graph.insert_entity(raw.into_entity());
+    }
+
+    std::fs::write(tmp.path().join(&file_rel), "def helper():\n    return 1\n").unwrap();
+    rpg_parser::treesitter::clear_cache();
+    let before = rpg_parser::treesitter::parse_count();
+    apply_modifications(
+        &mut graph,
+        std::slice::from_ref(&file_rel),
+        tmp.path(),
+        None,
+        Some(&UnreadableSource),
+    )
+    .unwrap();
+
+    assert_eq!(rpg_parser::treesitter::parse_count() - before, 1);
+    assert!(graph.entities.contains_key("src/helper.py:helper"));
+}
+
+#[test]
+fn test_git_provider_compares_contents_by_blob_id() {
+    use rpg_core::files::FileProvider;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let repo = git2::Repository::init(tmp.path()).unwrap();
+    let committed = "def helper():\n    pass\n";
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/helper.py"), committed).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("src/helper.py")).unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+        .unwrap();
+
+    let files = rpg_encoder::git_files::GitFileProvider::open(tmp.path(), "HEAD").unwrap();
+    let file = Path::new("src/helper.py");
+    assert!(files.has_contents(file, committed));
+    assert!(!files.has_contents(file, "def helper():\n    return 1\n"));
+    assert!(!files.has_contents(Path::new("src/missing.py"), committed));
+}
+
 // --- Semantic drift detection and routing tests (Algorithms 3-4) ---
 
 #[test]
@@ -962,9 +1062,22 @@ fn build_semantic_hierarchy_graph() -> RPGraph {
             parent_class: None,
             semantic_features: feats,
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: hier_path.to_string(),
             deps: rpg_core::graph::EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         };
         graph.insert_entity(entity);
         graph.insert_into_hierarchy(hier_path, id);
@@ -983,7 +1096,12 @@ fn test_find_best_hierarchy_path_routes_to_correct_area() {
         "authenticate user".to_string(),
         "validate credentials".to_string(),
     ];
-    let path = find_best_hierarchy_path(&graph, &auth_features);
+    let path = find_best_hierarchy_path(
+        &graph,
+        None,
+        &auth_features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    );
     assert!(path.is_some());
     let path = path.unwrap();
     assert!(
@@ -995,7 +1113,13 @@ fn test_find_best_hierarchy_path_routes_to_correct_area() {
 
     // Features about data should route to DataProcessing
     let data_features = vec!["load dataset".to_string(), "parse csv".to_string()];
-    let path = find_best_hierarchy_path(&graph, &data_features).unwrap();
+    let path = find_best_hierarchy_path(
+        &graph,
+        None,
+        &data_features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    )
+    .unwrap();
     assert!(
         path.starts_with("DataProcessing"),
         "data features should route to DataProcessing, got: {}",
@@ -1013,7 +1137,13 @@ fn test_find_best_hierarchy_path_drills_to_subcategory() {
         "manage session".to_string(),
         "create session token".to_string(),
     ];
-    let path = find_best_hierarchy_path(&graph, &session_features).unwrap();
+    let path = find_best_hierarchy_path(
+        &graph,
+        None,
+        &session_features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    )
+    .unwrap();
     assert_eq!(
         path, "Authentication/session",
         "session features should drill down to Authentication/session"
@@ -1021,7 +1151,13 @@ fn test_find_best_hierarchy_path_drills_to_subcategory() {
 
     // Features about data transformation
     let transform_features = vec!["transform data".to_string(), "normalize values".to_string()];
-    let path = find_best_hierarchy_path(&graph, &transform_features).unwrap();
+    let path = find_best_hierarchy_path(
+        &graph,
+        None,
+        &transform_features,
+        DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+    )
+    .unwrap();
     assert_eq!(
         path, "DataProcessing/transform",
         "transform features should drill down to DataProcessing/transform"
@@ -1031,11 +1167,88 @@ fn test_find_best_hierarchy_path_drills_to_subcategory() {
 #[test]
 fn test_find_best_hierarchy_path_returns_none_for_empty() {
     let graph = build_semantic_hierarchy_graph();
-    assert!(find_best_hierarchy_path(&graph, &[]).is_none());
+    assert!(find_best_hierarchy_path(&graph, None, &[], DEFAULT_ROUTING_NEIGHBOR_WEIGHT).is_none());
 
     let empty_graph = RPGraph::new("python");
     let features = vec!["authenticate user".to_string()];
-    assert!(find_best_hierarchy_path(&empty_graph, &features).is_none());
+    assert!(
+        find_best_hierarchy_path(
+            &empty_graph,
+            None,
+            &features,
+            DEFAULT_ROUTING_NEIGHBOR_WEIGHT
+        )
+        .is_none()
+    );
+}
+
+#[test]
+fn test_neighborhood_vote_outweighs_misleading_features() {
+    let mut graph = build_semantic_hierarchy_graph();
+    let id = "src/data/checksum.py:digest";
+    let features = vec![
+        "authenticate user".to_string(),
+        "validate credentials".to_string(),
+        "hash payload".to_string(),
+    ];
+    graph.insert_entity(rpg_core::graph::Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: "digest".to_string(),
+        file: PathBuf::from("src/data/checksum.py"),
+        line_start: 1,
+        line_end: 5,
+        parent_class: None,
+        semantic_features: features.clone(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: rpg_core::graph::EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    });
+    // Only data-processing code calls it; it imports a session helper.
+    for (source, target, kind) in [
+        ("src/data/loader.py:load_csv", id, EdgeKind::Invokes),
+        ("src/data/transform.py:normalize", id, EdgeKind::Invokes),
+        (id, "src/auth/session.py:create_session", EdgeKind::Imports),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+        });
+    }
+    graph.rebuild_edge_index();
+
+    let vote = neighborhood_vote(&graph, id);
+    let areas = vote.areas();
+    assert_eq!(areas[0].0, "DataProcessing");
+    assert!((areas[0].1 - 2.0 / 2.4).abs() < 1e-9);
+    assert!((vote.share("DataProcessing/loading") - 1.0 / 2.4).abs() < 1e-9);
+
+    // Features alone point at Authentication.
+    let by_features = find_best_hierarchy_path(&graph, None, &features, 0.4).unwrap();
+    assert!(by_features.starts_with("Authentication"), "{}", by_features);
+    let weightless = find_best_hierarchy_path(&graph, Some(id), &features, 0.0).unwrap();
+    assert_eq!(weightless, by_features);
+
+    // At the default weight, the neighbors decide.
+    let path =
+        find_best_hierarchy_path(&graph, Some(id), &features, DEFAULT_ROUTING_NEIGHBOR_WEIGHT)
+            .unwrap();
+    assert!(path.starts_with("DataProcessing"), "{}", path);
 }
 
 #[test]
@@ -1053,9 +1266,22 @@ fn test_reroute_entity_moves_in_hierarchy() {
         parent_class: None,
         semantic_features: vec!["authenticate user".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "DataProcessing/loading".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1112,9 +1338,22 @@ fn test_check_drift_and_reroute_below_threshold() {
             "validate credentials".to_string(),
         ],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Authentication/login".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1154,9 +1393,22 @@ fn test_check_drift_and_reroute_above_threshold() {
         parent_class: None,
         semantic_features: vec!["authenticate user".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Authentication/login".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1201,9 +1453,22 @@ fn test_route_new_entity_places_in_semantic_hierarchy() {
             "authenticate user".to_string(),
         ],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "src/auth/verify".to_string(), // file-path-based
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1245,9 +1510,22 @@ fn test_route_new_entity_skips_without_semantic_hierarchy() {
         parent_class: None,
         semantic_features: vec!["start application".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "src/main".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1288,7 +1566,7 @@ fn test_routing_stops_when_child_sim_not_better_than_parent() {
 
     // Entity features match the parent area well but not any child
     let features = vec!["process request".to_string(), "handle input".to_string()];
-    let path = find_best_hierarchy_path(&graph, &features);
+    let path = find_best_hierarchy_path(&graph, None, &features, DEFAULT_ROUTING_NEIGHBOR_WEIGHT);
     assert_eq!(
         path.as_deref(),
         Some("General"),
@@ -1321,9 +1599,22 @@ fn test_check_drift_and_reroute_restores_on_no_match() {
         parent_class: None,
         semantic_features: vec!["authenticate user".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Authentication".to_string(),
         deps: rpg_core::graph::EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     let eid = entity.id.clone();
     graph.insert_entity(entity);
@@ -1350,3 +1641,74 @@ fn test_check_drift_and_reroute_restores_on_no_match() {
         "entity should be restored to original hierarchy position"
     );
 }
+
+#[test]
+fn test_tags_survive_update_and_rebuild() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    std::fs::create_dir_all(root.join("src/auth")).unwrap();
+    let login_rel = PathBuf::from("src/auth/login.py");
+    let db_rel = PathBuf::from("src/db.py");
+    let login_v1 = "def login(user):\n    return True\n\ndef logout(user):\n    pass\n";
+    std::fs::write(root.join(&login_rel), login_v1).unwrap();
+    std::fs::write(root.join(&db_rel), "def query(sql):\n    pass\n").unwrap();
+
+    let build = || {
+        let mut graph = RPGraph::new("python");
+        graph.metadata.languages = vec!["python".to_string()];
+        for rel in [&login_rel, &db_rel] {
+            let source = std::fs::read_to_string(root.join(rel)).unwrap();
+            for raw in extract_entities(rel, &source, Language::PYTHON) {
+                graph.insert_entity(raw.into_entity());
+            }
+        }
+        graph.refresh_metadata();
+        graph
+    };
+    let tagged = |graph: &RPGraph| -> Vec<String> {
+        let mut ids = rpg_nav::scope::parse("tag:security-sensitive")
+            .unwrap()
+            .resolve(graph);
+        ids.sort();
+        ids
+    };
+
+    let mut graph = build();
+    let selection = rpg_nav::scope::select(&graph, &[], Some("glob:src/auth/**")).unwrap();
+    assert_eq!(selection.entity_ids.len(), 2);
+    let changed = graph.tag_entities(&selection.entity_ids, &["security-sensitive".to_string()]);
+    assert_eq!(changed, 2);
+    let expected = vec![
+        "src/auth/login.py:login".to_string(),
+        "src/auth/login.py:logout".to_string(),
+    ];
+    assert_eq!(tagged(&graph), expected);
+
+    // Incremental update: lines shift and a new, untagged function appears.
+    let login_v2 = format!("import os\n\ndef audit(user):\n    pass\n\n{}", login_v1);
+    std::fs::write(root.join(&login_rel), login_v2).unwrap();
+    let (modified, added, _, _) = apply_modifications(
+        &mut graph,
+        std::slice::from_ref(&login_rel),
+        root,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!((modified, added), (2, 1));
+    assert_eq!(tagged(&graph), expected);
+    assert_eq!(graph.entities["src/auth/login.py:login"].line_start, 6);
+
+    // Persisted and reloaded.
+    rpg_core::storage::save(root, &graph).unwrap();
+    let old = rpg_core::storage::load(root).unwrap();
+    assert_eq!(tagged(&old), expected);
+
+    // Full rebuild restores tags through merge_features.
+    let mut rebuilt = build();
+    assert!(tagged(&rebuilt).is_empty());
+    let stats = rpg_encoder::evolution::merge_features(&mut rebuilt, &old);
+    assert_eq!(stats.tags_restored, 2);
+    assert_eq!(tagged(&rebuilt), expected);
+    assert_eq!(rebuilt.tag_counts()["security-sensitive"], 2);
+}
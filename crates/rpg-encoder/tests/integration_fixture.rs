@@ -3,7 +3,7 @@
 //! Validates entity extraction, dependency resolution, hierarchy construction,
 //! and graph integrity on a small but realistic multi-file project.
 
-use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_core::graph::{EntityKind, HierarchyNode, RPGraph};
 use rpg_parser::entities::{RawEntity, extract_entities};
 use rpg_parser::languages::Language;
 use std::path::{Path, PathBuf};
@@ -48,10 +48,14 @@ fn build_fixture_graph() -> RPGraph {
         "expected at least 4 .py files, got {}",
         files.len()
     );
+    build_graph(&files)
+}
 
+/// Build a complete structural RPG from parsed fixture files.
+fn build_graph(files: &[(PathBuf, String)]) -> RPGraph {
     // Parse all files
     let mut all_entities: Vec<RawEntity> = Vec::new();
-    for (rel_path, source) in &files {
+    for (rel_path, source) in files {
         let raw = extract_entities(rel_path, source, Language::PYTHON);
         all_entities.extend(raw);
     }
@@ -303,7 +307,8 @@ fn test_fixture_search_integration() {
         rpg_nav::search::SearchMode::Snippets,
         None,
         10,
-    );
+    )
+    .unwrap();
 
     assert!(
         !results.is_empty(),
@@ -311,3 +316,41 @@ fn test_fixture_search_integration() {
     );
     assert_eq!(results[0].entity_name, "authenticate");
 }
+
+/// Every hierarchy node ID in the graph, by node path.
+fn hierarchy_ids(graph: &RPGraph) -> Vec<(String, String)> {
+    fn walk(node: &HierarchyNode, path: &str, out: &mut Vec<(String, String)>) {
+        out.push((path.to_string(), node.id.clone()));
+        for (name, child) in &node.children {
+            walk(child, &format!("{}/{}", path, name), out);
+        }
+    }
+    let mut ids = Vec::new();
+    for (name, area) in &graph.hierarchy {
+        walk(area, name, &mut ids);
+    }
+    ids
+}
+
+#[test]
+fn test_fixture_hierarchy_ids_stable_across_rebuilds() {
+    let mut files = collect_fixture_files(&fixture_root());
+    let first = hierarchy_ids(&build_fixture_graph());
+    assert!(first.len() > 1);
+
+    // Files arriving in a different order (another machine's directory
+    // walk, a parallel parse) yield the same IDs.
+    files.reverse();
+    let rebuilt = build_graph(&files);
+    assert_eq!(hierarchy_ids(&rebuilt), first);
+
+    for (path, id) in &first {
+        assert_eq!(id, &rpg_core::graph::hierarchy_node_id(path));
+    }
+
+    // IDs survive a save/load round trip.
+    let tmp = tempfile::tempdir().unwrap();
+    rpg_core::storage::save(tmp.path(), &rebuilt).unwrap();
+    let loaded = rpg_core::storage::load(tmp.path()).unwrap();
+    assert_eq!(hierarchy_ids(&loaded), first);
+}
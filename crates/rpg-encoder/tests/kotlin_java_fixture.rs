@@ -0,0 +1,106 @@
+//! Integration test: a mixed Kotlin/Java (Android-style) project.
+//!
+//! Kotlin extension functions and companion members get addressable IDs, and
+//! Java code calling into Kotlin resolves to those entities.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_nav::fetch::{FetchOutput, fetch};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/kotlin_java_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Language::from_extension)
+            .is_some()
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph(root: &Path) -> RPGraph {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files);
+    let mut graph = RPGraph::new("kotlin");
+    for raw in rpg_parser::parse_files_parallel(files) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, true, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.refresh_metadata();
+    graph
+}
+
+const GREETER: &str = "src/main/kotlin/com/example/Greeter.kt";
+
+#[test]
+fn test_mixed_project_detects_kotlin_and_java() {
+    let detected = Language::detect_all(&fixture_root());
+    // Two .kt files and build.gradle.kts outnumber the one .java file.
+    assert_eq!(detected.first(), Some(&Language::KOTLIN));
+    assert!(detected.contains(&Language::JAVA));
+}
+
+#[test]
+fn test_kotlin_extension_and_companion_ids() {
+    let root = fixture_root();
+    let graph = build_graph(&root);
+
+    let shout = &graph.entities[&format!("{}:String::shout", GREETER)];
+    assert_eq!(shout.kind, EntityKind::Method);
+    // Generic, nullable receivers reduce to the simple type name.
+    assert!(
+        graph
+            .entities
+            .contains_key(&format!("{}:List::greetAll", GREETER))
+    );
+    assert_eq!(
+        graph.entities[&format!("{}:Greeter::Companion", GREETER)].kind,
+        EntityKind::Class
+    );
+    let create = format!("{}:Greeter.Companion::create", GREETER);
+    assert_eq!(graph.entities[&create].kind, EntityKind::Method);
+
+    let Ok(FetchOutput::Entity(fetched)) = fetch(&graph, &shout.id, &root) else {
+        panic!("extension function not fetchable");
+    };
+    assert!(
+        fetched
+            .source_code
+            .as_deref()
+            .is_some_and(|s| s.starts_with("fun String.shout()"))
+    );
+
+    // Kotlin calls through the extension and the aliased import.
+    let invokes = |source: &str, target: &str| {
+        graph
+            .edges
+            .iter()
+            .any(|e| e.kind == EdgeKind::Invokes && e.source == source && e.target == target)
+    };
+    let greet = format!("{}:Greeter::greet", GREETER);
+    assert!(invokes(&greet, &shout.id), "{:?}", graph.edges);
+
+    // Java interop: `Greeter.Companion.create(...)` and `GreeterKt.shout(...)`.
+    let run = "src/main/java/com/example/Main.java:Main::run";
+    assert!(invokes(run, &create), "{:?}", graph.edges);
+    assert!(invokes(run, &shout.id), "{:?}", graph.edges);
+}
@@ -0,0 +1,305 @@
+//! Layer tags: one clear-cut fixture per heuristic, overrides, and the
+//! search filter / rpg_info breakdown built on them.
+
+use rpg_core::graph::*;
+use rpg_encoder::layers::{ImportedNames, LayerOverrides, assign_layers, infer_layer};
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_facets};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn make_entity(file: &str, name: &str, kind: EntityKind) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 3,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// Layer from kind, name, and path alone (no source, no imports).
+fn layer_of(file: &str, name: &str, kind: EntityKind) -> Layer {
+    infer_layer(
+        &make_entity(file, name, kind),
+        None,
+        &ImportedNames::default(),
+    )
+}
+
+#[test]
+fn test_test_code() {
+    use EntityKind::{Class, Function, Test};
+    assert_eq!(
+        layer_of("tests/search.rs", "make_graph", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("web/__tests__/app.js", "render", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("pkg/store/cache_test.go", "setup", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("app/test_models.py", "fixture", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("web/src/cart.spec.ts", "helper", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("src/parser.rs", "test_parse_empty", Function),
+        Layer::Test
+    );
+    assert_eq!(
+        layer_of("pkg/store/cache.go", "TestEvict", Function),
+        Layer::Test
+    );
+    assert_eq!(layer_of("src/app.py", "checks", Test), Layer::Test);
+    // A test prefix needs a word boundary.
+    assert_eq!(layer_of("src/app.py", "Testament", Class), Layer::Domain);
+    assert_eq!(layer_of("src/app.py", "testify", Function), Layer::Domain);
+}
+
+#[test]
+fn test_entry_points_are_api() {
+    use EntityKind::{Controller, Function, Route};
+    assert_eq!(layer_of("app/main.py", "list_users", Route), Layer::Api);
+    assert_eq!(
+        layer_of("app/users.py", "UsersController", Controller),
+        Layer::Api
+    );
+
+    let mut routed = make_entity("server/index.js", "listUsers", Function);
+    routed
+        .attributes
+        .insert("route".to_string(), "GET /users".to_string());
+    assert_eq!(
+        infer_layer(&routed, None, &ImportedNames::default()),
+        Layer::Api
+    );
+}
+
+#[test]
+fn test_naming_conventions() {
+    use EntityKind::{Class, Function};
+    assert_eq!(
+        layer_of("src/users.rs", "handle_signup", Function),
+        Layer::Api
+    );
+    assert_eq!(layer_of("src/users.ts", "SignupHandler", Class), Layer::Api);
+    assert_eq!(
+        layer_of("src/users.rs", "UserRepository", Class),
+        Layer::Infrastructure
+    );
+    assert_eq!(
+        layer_of("src/billing.py", "StripeClient", Class),
+        Layer::Infrastructure
+    );
+    // Names beat directories.
+    assert_eq!(
+        layer_of("src/db/users.rs", "handle_signup", Function),
+        Layer::Api
+    );
+}
+
+#[test]
+fn test_directory_conventions() {
+    use EntityKind::Function;
+    assert_eq!(
+        layer_of("src/routes/users.ts", "listUsers", Function),
+        Layer::Api
+    );
+    assert_eq!(
+        layer_of("src/db/schema.rs", "columns", Function),
+        Layer::Infrastructure
+    );
+    assert_eq!(
+        layer_of("src/domain/pricing.rs", "discount", Function),
+        Layer::Domain
+    );
+    // The deepest matching directory decides.
+    assert_eq!(
+        layer_of("src/api/db/pool.rs", "connect", Function),
+        Layer::Infrastructure
+    );
+    assert_eq!(
+        layer_of("src/util/strings.rs", "slugify", Function),
+        Layer::Domain
+    );
+}
+
+/// Files whose layer comes only from the libraries each entity uses.
+fn write_library_fixture(root: &std::path::Path) -> RPGraph {
+    let files = [
+        (
+            "src/config.rs",
+            "use std::fs;\nuse std::path::Path;\n\nfn load(path: &Path) -> String {\n    fs::read_to_string(path).unwrap()\n}\n\nfn merge(a: u32, b: u32) -> u32 {\n    a + b\n}\n",
+        ),
+        (
+            "app/views_helpers.py",
+            "from flask import jsonify\nimport math\n\ndef user_json(user):\n    return jsonify(user)\n\ndef area(r):\n    return math.pi * r * r\n",
+        ),
+        (
+            "web/user.js",
+            "import axios from 'axios';\n\nexport async function fetchUser(id) {\n  return axios.get(`/users/${id}`);\n}\n",
+        ),
+    ];
+    let entities = [
+        ("src/config.rs", "load", 4, 6),
+        ("src/config.rs", "merge", 8, 10),
+        ("app/views_helpers.py", "user_json", 4, 5),
+        ("app/views_helpers.py", "area", 7, 8),
+        ("web/user.js", "fetchUser", 3, 5),
+    ];
+
+    let mut graph = RPGraph::new("rust");
+    for (file, content) in files {
+        let path = root.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+    for (file, name, start, end) in entities {
+        let mut entity = make_entity(file, name, EntityKind::Function);
+        entity.line_start = start;
+        entity.line_end = end;
+        graph.insert_entity(entity);
+    }
+    graph
+}
+
+fn layer(graph: &RPGraph, id: &str) -> Option<Layer> {
+    graph.entities[id].layer
+}
+
+#[test]
+fn test_library_usage_from_imports() {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = write_library_fixture(tmp.path());
+    assign_layers(&mut graph, tmp.path(), None);
+
+    assert_eq!(
+        layer(&graph, "src/config.rs:load"),
+        Some(Layer::Infrastructure)
+    );
+    // Same file, but no filesystem use in the body.
+    assert_eq!(layer(&graph, "src/config.rs:merge"), Some(Layer::Domain));
+    assert_eq!(
+        layer(&graph, "app/views_helpers.py:user_json"),
+        Some(Layer::Api)
+    );
+    assert_eq!(
+        layer(&graph, "app/views_helpers.py:area"),
+        Some(Layer::Domain)
+    );
+    assert_eq!(
+        layer(&graph, "web/user.js:fetchUser"),
+        Some(Layer::Infrastructure)
+    );
+}
+
+#[test]
+fn test_overrides_pin_layers() {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = write_library_fixture(tmp.path());
+    let rpg_dir = tmp.path().join(".rpg");
+    std::fs::create_dir_all(&rpg_dir).unwrap();
+    std::fs::write(
+        rpg_dir.join("layers-overrides.toml"),
+        "[paths]\n\"src/**\" = \"api\"\n\"src/config.rs\" = \"infra\"\n\n[entities]\n\"src/config.rs:merge\" = \"domain\"\n\"web/user.js:fetchUser\" = \"test\"\n",
+    )
+    .unwrap();
+    assign_layers(&mut graph, tmp.path(), None);
+
+    // Longest path pattern wins; entity IDs beat paths.
+    assert_eq!(
+        layer(&graph, "src/config.rs:load"),
+        Some(Layer::Infrastructure)
+    );
+    assert_eq!(layer(&graph, "src/config.rs:merge"), Some(Layer::Domain));
+    assert_eq!(layer(&graph, "web/user.js:fetchUser"), Some(Layer::Test));
+    assert_eq!(
+        layer(&graph, "app/views_helpers.py:user_json"),
+        Some(Layer::Api)
+    );
+
+    // A bad overrides file is skipped; inferred tags remain.
+    std::fs::write(
+        rpg_dir.join("layers-overrides.toml"),
+        "[entities]\n\"web/user.js:fetchUser\" = \"frontend\"\n",
+    )
+    .unwrap();
+    assign_layers(&mut graph, tmp.path(), None);
+    assert_eq!(
+        layer(&graph, "web/user.js:fetchUser"),
+        Some(Layer::Infrastructure)
+    );
+    assert!(LayerOverrides::parse("[paths]\n\"src/**\" = \"frontend\"\n").is_err());
+    assert!(LayerOverrides::parse("[files]\n\"a\" = \"api\"\n").is_err());
+}
+
+#[test]
+fn test_layer_filter_and_info_breakdown() {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = write_library_fixture(tmp.path());
+    assign_layers(&mut graph, tmp.path(), None);
+    graph.refresh_metadata();
+
+    let outcome = search_with_facets(
+        &graph,
+        &SearchParams {
+            query: "src",
+            mode: SearchMode::Snippets,
+            scope: None,
+            limit: 10,
+            line_nums: None,
+            file_pattern: None,
+            entity_type_filter: None,
+            layer_filter: Some(vec![Layer::Domain]),
+            embedding_scores: None,
+            diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
+        },
+    )
+    .unwrap();
+    let ids: Vec<&str> = outcome
+        .results
+        .iter()
+        .map(|r| r.entity_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["src/config.rs:merge"]);
+
+    let info = rpg_nav::toon::format_rpg_info(&graph);
+    assert!(info.contains("layers[3|]{layer|entities}:"), "{}", info);
+    assert!(info.contains("infrastructure|2"), "{}", info);
+}
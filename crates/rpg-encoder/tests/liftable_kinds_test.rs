@@ -0,0 +1,193 @@
+//! Integration test: `encoding.liftable_kinds` and paradigm `liftable = false`
+//! opt-outs decide what lifting coverage counts and what gets queued.
+
+use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_parser::languages::Language;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+fn fixture_root(name: &str) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "ts" || e == "tsx" || e == "js")
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            let source = std::fs::read_to_string(&path).unwrap();
+            out.push((rel, source));
+        }
+    }
+}
+
+/// Entities of a fixture, plus `extra` files, parsed through the paradigm pipeline.
+fn build_graph(name: &str, extra: &[(&str, &str)]) -> RPGraph {
+    let root = fixture_root(name);
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+    files.extend(
+        extra
+            .iter()
+            .map(|(path, source)| (PathBuf::from(path), source.to_string())),
+    );
+
+    let languages = [Language::TYPESCRIPT, Language::JAVASCRIPT];
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(&root, &languages, &paradigm_defs);
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    graph
+}
+
+fn count_kind(graph: &RPGraph, kind: EntityKind) -> usize {
+    graph.entities.values().filter(|e| e.kind == kind).count()
+}
+
+const LEGACY_API_JS: &str = r"import { createApi, fetchBaseQuery } from '@reduxjs/toolkit/query/react';
+
+export const commentsApi = createApi({
+  reducerPath: 'commentsApi',
+  baseQuery: fetchBaseQuery({ baseUrl: '/api' }),
+  endpoints: (builder) => ({
+    getComments: builder.query({ query: (postId) => `posts/${postId}/comments` }),
+    addComment: builder.mutation({ query: (body) => ({ url: 'comments', method: 'POST', body }) }),
+  }),
+});
+
+export const { useGetCommentsQuery, useAddCommentMutation } = commentsApi;
+";
+
+#[test]
+fn test_default_kinds_leave_structural_entities_out_of_coverage() {
+    let graph = build_graph("nextjs_project", &[]);
+    let routes = count_kind(&graph, EntityKind::Route);
+    let components = count_kind(&graph, EntityKind::Component);
+    assert!(routes >= 6, "App Router files synthesize Route entities");
+    assert!(components > 0);
+
+    let (lifted, total) = graph.lifting_coverage();
+    assert_eq!(lifted, 0);
+    let default_kinds = count_kind(&graph, EntityKind::Function)
+        + count_kind(&graph, EntityKind::Class)
+        + count_kind(&graph, EntityKind::Method);
+    assert_eq!(total, default_kinds);
+
+    let exclusions = graph.lifting_exclusions();
+    assert_eq!(exclusions.get("kind route"), Some(&routes));
+    assert_eq!(exclusions.get("kind component"), Some(&components));
+    let non_module = graph
+        .entities
+        .values()
+        .filter(|e| e.kind != EntityKind::Module)
+        .count();
+    assert_eq!(exclusions.values().sum::<usize>() + total, non_module);
+
+    // The batcher's `*` scope matches the coverage denominator.
+    let scope = rpg_encoder::lift::resolve_scope(&graph, "*").unwrap();
+    assert_eq!(scope.entity_ids.len(), total);
+    assert!(
+        scope
+            .entity_ids
+            .iter()
+            .all(|id| graph.entities[id].kind != EntityKind::Route)
+    );
+}
+
+#[test]
+fn test_configured_kinds_and_paradigm_opt_out() {
+    let mut graph = build_graph(
+        "nextjs_project",
+        &[("src/state/legacyApi.js", LEGACY_API_JS)],
+    );
+    let (_, default_total) = graph.lifting_coverage();
+
+    graph.set_liftable_kinds(&[
+        EntityKind::Function,
+        EntityKind::Class,
+        EntityKind::Method,
+        EntityKind::Route,
+        EntityKind::Hook,
+    ]);
+    let routes = count_kind(&graph, EntityKind::Route);
+    let hooks = count_kind(&graph, EntityKind::Hook);
+    let (_, total) = graph.lifting_coverage();
+
+    // createApi's destructured hooks opt out in redux.toml, in `.ts` and
+    // `.js` files alike; `useAuth` counts.
+    let generated = [
+        "useGetPostsQuery",
+        "useGetUserQuery",
+        "useGetCommentsQuery",
+        "useAddCommentMutation",
+    ];
+    let opted_out: Vec<&str> = graph
+        .entities
+        .values()
+        .filter(|e| !graph.is_liftable(e) && e.kind == EntityKind::Hook)
+        .map(|e| e.name.as_str())
+        .collect();
+    assert_eq!(
+        opted_out.iter().copied().collect::<HashSet<_>>(),
+        generated.into_iter().collect()
+    );
+    assert!(
+        graph
+            .entities
+            .values()
+            .any(|e| e.name == "useAuth" && graph.is_liftable(e))
+    );
+    assert_eq!(total, default_total + routes + hooks - generated.len());
+    assert_eq!(
+        graph.lifting_exclusions().get("paradigm opt-out"),
+        Some(&generated.len())
+    );
+
+    // Listing a kind never makes modules liftable.
+    graph.set_liftable_kinds(&[EntityKind::Module]);
+    assert_eq!(graph.lifting_coverage(), (0, 0));
+}
+
+#[test]
+fn test_lift_queue_batches_only_liftable_entities() {
+    let root = fixture_root("nextjs_project");
+    let mut graph = build_graph("nextjs_project", &[]);
+    let encoding = rpg_core::config::EncodingConfig {
+        liftable_kinds: vec![EntityKind::Class],
+        ..Default::default()
+    };
+    let queue = rpg_encoder::lift_queue::build_lift_queue(
+        &mut graph,
+        "src/**",
+        &root,
+        &encoding,
+        &HashSet::new(),
+        &rpg_encoder::tokens::Heuristic,
+    )
+    .unwrap();
+    assert_eq!(graph.metadata.liftable_kinds, vec![EntityKind::Class]);
+    // A glob scope matches every entity under src/, but only classes are collected.
+    assert!(queue.matched > queue.collected);
+    assert_eq!(queue.collected, count_kind(&graph, EntityKind::Class));
+    let (_, total) = graph.lifting_coverage();
+    assert_eq!(total, queue.collected);
+}
@@ -0,0 +1,102 @@
+//! Integration test: top-level script code becomes a `file:<module>` entity.
+//!
+//! Uses tests/fixtures/python_script: `count_chars.py` wires an argument parser
+//! and calls its helpers at module level; `settings.py` only defines constants.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_parser::entities::{MODULE_SCOPE_NAME, extract_entities};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/python_script")
+}
+
+fn build_graph() -> RPGraph {
+    let root = fixture_root();
+    let mut graph = RPGraph::new("python");
+    for file in ["count_chars.py", "settings.py"] {
+        let source = std::fs::read_to_string(root.join(file)).unwrap();
+        for raw in extract_entities(Path::new(file), &source, Language::PYTHON) {
+            graph.insert_entity(raw.into_entity());
+        }
+    }
+    graph.create_module_entities();
+    graph.build_file_path_hierarchy();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, &root, false, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+    graph
+}
+
+#[test]
+fn test_top_level_statements_become_module_scope_entity() {
+    let graph = build_graph();
+    let entity = graph
+        .get_entity("count_chars.py:<module>")
+        .expect("missing module-scope entity");
+    assert_eq!(entity.name, MODULE_SCOPE_NAME);
+    assert_eq!(entity.kind, EntityKind::Function);
+    // From `parser = argparse.ArgumentParser(...)` to the end of the __main__ block;
+    // the docstring, import, and constant above don't count.
+    assert_eq!((entity.line_start, entity.line_end), (16, 21));
+    assert_eq!(
+        entity.attributes.get("role").map(String::as_str),
+        Some("file initialization (top-level statements)")
+    );
+
+    // Constants and imports alone don't produce one.
+    assert!(graph.get_entity("settings.py:<module>").is_none());
+}
+
+#[test]
+fn test_module_scope_calls_attach_to_entity() {
+    let graph = build_graph();
+    let module = graph.get_entity("count_chars.py:<module>").unwrap();
+    for callee in ["load", "report", "ArgumentParser", "parse_args"] {
+        assert!(
+            module.deps.invokes.iter().any(|c| c == callee),
+            "missing {} in {:?}",
+            callee,
+            module.deps.invokes
+        );
+    }
+    // Calls inside functions stay with their function.
+    assert!(!module.deps.invokes.iter().any(|c| c == "open"));
+
+    for target in ["count_chars.py:load", "count_chars.py:report"] {
+        assert!(
+            graph
+                .edges
+                .iter()
+                .any(|e| e.source == "count_chars.py:<module>"
+                    && e.target == target
+                    && e.kind == EdgeKind::Invokes),
+            "missing edge to {}",
+            target
+        );
+    }
+
+    // Every edge endpoint resolves (what `rpg-encoder validate` checks).
+    for edge in &graph.edges {
+        assert!(
+            graph.entities.contains_key(&edge.source)
+                || graph.find_hierarchy_node_by_id(&edge.source).is_some(),
+            "dangling edge source: {}",
+            edge.source
+        );
+        assert!(
+            graph.entities.contains_key(&edge.target)
+                || graph.find_hierarchy_node_by_id(&edge.target).is_some(),
+            "dangling edge target: {}",
+            edge.target
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Integration test: a scripted superproject with a git submodule at
+//! `vendor/lib` and an untracked nested clone at `tools/scratch`.
+
+use rpg_core::config::EncodingConfig;
+use rpg_core::graph::{NestedRepo, RPGraph};
+use rpg_encoder::evolution::{FileChange, detect_changes, detect_workdir_changes};
+use rpg_encoder::nested_repos::{find_nested_repos, source_walker};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Stage the whole worktree and commit it on HEAD.
+fn commit_all(repo: &git2::Repository, message: &str) -> String {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+        .to_string()
+}
+
+struct Fixture {
+    _tmp: TempDir,
+    /// The superproject's worktree.
+    root: PathBuf,
+    /// The commit the superproject pins `vendor/lib` to.
+    pinned: String,
+    /// HEAD of the untracked nested clone.
+    scratch_head: String,
+}
+
+/// `lib` (one commit), and `app` with `src/main.rs` plus `lib` added as a
+/// submodule at `vendor/lib`; `tools/scratch` is a repository of its own
+/// that `app` doesn't track.
+fn scripted_superproject() -> Fixture {
+    let tmp = TempDir::new().unwrap();
+    let lib_dir = tmp.path().join("lib");
+    let lib = git2::Repository::init(&lib_dir).unwrap();
+    write(&lib_dir, "lib.rs", "pub fn lib() {}\n");
+    let pinned = commit_all(&lib, "lib");
+
+    let app_dir = tmp.path().join("app");
+    let app = git2::Repository::init(&app_dir).unwrap();
+    write(&app_dir, "src/main.rs", "fn main() {}\n");
+    let mut submodule = app
+        .submodule(lib_dir.to_str().unwrap(), Path::new("vendor/lib"), true)
+        .unwrap();
+    submodule.clone(None).unwrap();
+    submodule.add_finalize().unwrap();
+    commit_all(&app, "app");
+
+    let scratch_dir = app_dir.join("tools/scratch");
+    let scratch = git2::Repository::init(&scratch_dir).unwrap();
+    write(&scratch_dir, "notes.rs", "pub fn notes() {}\n");
+    let scratch_head = commit_all(&scratch, "scratch");
+
+    Fixture {
+        _tmp: tmp,
+        root: app_dir,
+        pinned,
+        scratch_head,
+    }
+}
+
+fn graph_for(root: &Path, encoding: &EncodingConfig) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.base_commit = Some(rpg_encoder::evolution::get_head_sha(root).unwrap());
+    graph.metadata.nested_repos = find_nested_repos(root, encoding);
+    graph
+}
+
+fn walked_files(root: &Path, encoding: &EncodingConfig) -> Vec<String> {
+    let mut files: Vec<String> = source_walker(root, encoding)
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .map(|e| {
+            let rel = e.path().strip_prefix(root).unwrap();
+            rpg_core::graph::normalize_path(rel)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn describe(changes: &[FileChange]) -> Vec<String> {
+    let mut out: Vec<String> = changes
+        .iter()
+        .map(|change| match change {
+            FileChange::Added(p) => format!("A {}", p.display()),
+            FileChange::Modified(p) => format!("M {}", p.display()),
+            FileChange::Deleted(p) => format!("D {}", p.display()),
+            FileChange::Renamed { from, to } => {
+                format!("R {} -> {}", from.display(), to.display())
+            }
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+fn commit_in_submodule(root: &Path, rel: &str, content: &str) -> String {
+    let dir = root.join("vendor/lib");
+    let repo = git2::Repository::open(&dir).unwrap();
+    write(&dir, rel, content);
+    commit_all(&repo, "move submodule")
+}
+
+#[test]
+fn test_nested_repos_skipped_by_default_and_recorded() {
+    let fixture = scripted_superproject();
+    let root = fixture.root.as_path();
+    let encoding = EncodingConfig::default();
+
+    assert_eq!(walked_files(root, &encoding), ["src/main.rs"]);
+    assert_eq!(
+        find_nested_repos(root, &encoding),
+        [
+            NestedRepo {
+                path: "tools/scratch".to_string(),
+                commit: Some(fixture.scratch_head.clone()),
+                indexed: false,
+                checked_out: true,
+            },
+            NestedRepo {
+                path: "vendor/lib".to_string(),
+                commit: Some(fixture.pinned.clone()),
+                indexed: false,
+                checked_out: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_moved_submodule_head_is_not_a_change() {
+    let fixture = scripted_superproject();
+    let root = fixture.root.as_path();
+    let graph = graph_for(root, &EncodingConfig::default());
+
+    commit_in_submodule(root, "lib.rs", "pub fn lib() {}\npub fn more() {}\n");
+    assert!(describe(&detect_workdir_changes(root, &graph).unwrap()).is_empty());
+    // The skipped submodule still records the superproject's pin.
+    assert_eq!(
+        find_nested_repos(root, &EncodingConfig::default())[1].commit,
+        Some(fixture.pinned.clone())
+    );
+}
+
+#[test]
+fn test_included_submodule_reports_its_own_changes() {
+    let fixture = scripted_superproject();
+    let root = fixture.root.as_path();
+    let encoding = EncodingConfig {
+        include_submodules: true,
+        ..EncodingConfig::default()
+    };
+    assert_eq!(
+        walked_files(root, &encoding),
+        ["src/main.rs", "tools/scratch/notes.rs", "vendor/lib/lib.rs"]
+    );
+    let graph = graph_for(root, &encoding);
+    assert!(graph.metadata.nested_repos.iter().all(|r| r.indexed));
+
+    commit_in_submodule(root, "extra.rs", "pub fn extra() {}\n");
+    write(
+        root,
+        "vendor/lib/lib.rs",
+        "pub fn lib() -> u32 {\n    1\n}\n",
+    );
+    write(root, "src/main.rs", "fn main() {\n}\n");
+
+    assert_eq!(
+        describe(&detect_workdir_changes(root, &graph).unwrap()),
+        [
+            "A vendor/lib/extra.rs",
+            "M src/main.rs",
+            "M vendor/lib/lib.rs"
+        ]
+    );
+    // Committed changes only: the submodule's HEAD against its recorded commit.
+    assert_eq!(
+        describe(&detect_changes(root, &graph, None).unwrap()),
+        ["A vendor/lib/extra.rs"]
+    );
+}
+
+#[test]
+fn test_missing_submodule_checkout_degrades_to_warning() {
+    let fixture = scripted_superproject();
+    let root = fixture.root.as_path();
+    let encoding = EncodingConfig {
+        include_submodules: true,
+        ..EncodingConfig::default()
+    };
+    let graph = graph_for(root, &encoding);
+
+    std::fs::remove_dir_all(root.join("vendor/lib")).unwrap();
+    std::fs::create_dir(root.join("vendor/lib")).unwrap();
+    write(root, "src/main.rs", "fn main() {\n}\n");
+
+    assert_eq!(
+        describe(&detect_workdir_changes(root, &graph).unwrap()),
+        ["M src/main.rs"]
+    );
+    let submodule = find_nested_repos(root, &encoding)
+        .into_iter()
+        .find(|r| r.path == "vendor/lib")
+        .unwrap();
+    assert!(!submodule.checked_out);
+    assert_eq!(submodule.commit, Some(fixture.pinned.clone()));
+}
@@ -0,0 +1,173 @@
+//! Integration test: a NestJS API with two feature modules.
+//!
+//! Decorated classes become Controllers and Services, `@Get(':id')` handlers
+//! on a `@Controller('users')` become Routes with a `handle METHOD /path`
+//! feature, and each `@Module` composes its `imports` and `providers`.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_encoder::grounding;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/nestjs_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path.extension().is_some_and(|e| e == "ts") {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph() -> RPGraph {
+    let root = fixture_root();
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
+        &root,
+        &[Language::TYPESCRIPT],
+        &paradigm_defs,
+    );
+    assert!(active_defs.iter().any(|d| d.name == "nestjs"));
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    let ctx = grounding::ParadigmContext {
+        active_defs,
+        qcache: &qcache,
+    };
+    grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn kind(graph: &RPGraph, id: &str) -> EntityKind {
+    graph
+        .get_entity(id)
+        .unwrap_or_else(|| panic!("missing {}", id))
+        .kind
+}
+
+fn composes(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Composes && e.source == source)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort_unstable();
+    targets.dedup();
+    targets
+}
+
+#[test]
+fn test_decorated_classes_are_classified() {
+    let graph = build_graph();
+    assert_eq!(
+        kind(&graph, "src/users/users.controller.ts:UsersController"),
+        EntityKind::Controller
+    );
+    assert_eq!(
+        kind(&graph, "src/users/users.service.ts:UsersService"),
+        EntityKind::Service
+    );
+    assert_eq!(
+        kind(&graph, "src/users/logger.middleware.ts:LoggerMiddleware"),
+        EntityKind::Middleware
+    );
+    assert_eq!(
+        kind(&graph, "src/users/users.module.ts:UsersModule"),
+        EntityKind::Class
+    );
+    assert_eq!(
+        graph
+            .get_entity("src/users/users.module.ts:UsersModule")
+            .unwrap()
+            .decorators[0],
+        "Module({ imports: [TypeOrmModule.forFeature([]), AuthModule], controllers: [UsersController], providers: [UsersService], })"
+    );
+}
+
+#[test]
+fn test_handlers_get_method_and_path_features() {
+    let graph = build_graph();
+    let features = |name: &str| {
+        let id = format!("src/users/users.controller.ts:UsersController::{}", name);
+        let entity = graph
+            .get_entity(&id)
+            .unwrap_or_else(|| panic!("missing {}", id));
+        assert_eq!(entity.kind, EntityKind::Route, "{}", id);
+        assert_eq!(entity.feature_source.as_deref(), Some("paradigm"), "{}", id);
+        entity.semantic_features.clone()
+    };
+
+    assert_eq!(features("list"), ["handle GET /users"]);
+    assert_eq!(features("findOne"), ["handle GET /users/:id"]);
+    assert_eq!(features("create"), ["handle POST /users"]);
+    assert_eq!(features("remove"), ["handle DELETE /users/:id"]);
+
+    // Undecorated methods stay methods and are left to lifting.
+    for name in ["constructor", "audit"] {
+        let id = format!("src/users/users.controller.ts:UsersController::{}", name);
+        let entity = graph.get_entity(&id).unwrap();
+        assert_eq!(entity.kind, EntityKind::Method, "{}", id);
+        assert!(entity.semantic_features.is_empty(), "{}", id);
+    }
+    // The handler's span starts at its decorator.
+    let find_one = graph
+        .get_entity("src/users/users.controller.ts:UsersController::findOne")
+        .unwrap();
+    assert_eq!((find_one.line_start, find_one.line_end), (13, 16));
+}
+
+#[test]
+fn test_modules_compose_imports_and_providers() {
+    let graph = build_graph();
+    assert_eq!(
+        composes(&graph, "src/app.module.ts:AppModule"),
+        [
+            "src/auth/auth.module.ts:AuthModule",
+            "src/users/users.module.ts:UsersModule"
+        ]
+    );
+    // Controllers and exports are not composition; the external
+    // `TypeOrmModule` doesn't resolve.
+    assert_eq!(
+        composes(&graph, "src/users/users.module.ts:UsersModule"),
+        [
+            "src/auth/auth.module.ts:AuthModule",
+            "src/users/users.service.ts:UsersService"
+        ]
+    );
+    assert_eq!(
+        composes(&graph, "src/auth/auth.module.ts:AuthModule"),
+        ["src/auth/auth.service.ts:AuthService"]
+    );
+
+    let service = graph
+        .get_entity("src/users/users.service.ts:UsersService")
+        .unwrap();
+    assert_eq!(
+        service.deps.composed_by,
+        ["src/users/users.module.ts:UsersModule"]
+    );
+}
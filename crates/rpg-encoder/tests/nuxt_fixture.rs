@@ -0,0 +1,142 @@
+//! Integration test: a Nuxt project with Pinia.
+//!
+//! Vue SFC scripts (the `<script lang="ts">` blocks, checked in as `.ts`)
+//! become Components, `use*` composables become Hooks, and `defineStore`
+//! stores get ReadsState / WritesState edges from their consumers.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_encoder::grounding;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/nuxt_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path.extension().is_some_and(|e| e == "ts") {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph() -> RPGraph {
+    let root = fixture_root();
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
+        &root,
+        &[Language::TYPESCRIPT],
+        &paradigm_defs,
+    );
+    assert!(active_defs.iter().any(|d| d.name == "vue"));
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    let ctx = grounding::ParadigmContext {
+        active_defs,
+        qcache: &qcache,
+    };
+    grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn edges(graph: &RPGraph, kind: EdgeKind) -> Vec<(&str, &str)> {
+    let mut edges: Vec<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == kind)
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+    edges.sort_unstable();
+    edges
+}
+
+#[test]
+fn test_nuxt_config_alone_activates_vue() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("nuxt.config.mjs"), "export default {};\n").unwrap();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let active = rpg_parser::paradigms::detect_paradigms_toml(
+        tmp.path(),
+        &[Language::JAVASCRIPT],
+        &paradigm_defs,
+    );
+    assert!(active.iter().any(|d| d.name == "vue"));
+}
+
+#[test]
+fn test_components_composables_and_stores() {
+    let graph = build_graph();
+    let kind = |id: &str| graph.get_entity(id).map(|e| e.kind);
+
+    // `export default defineComponent({ name: "CartBadge", ... })`
+    assert_eq!(
+        kind("components/CartBadge.ts:CartBadge"),
+        Some(EntityKind::Component)
+    );
+    // `<script setup>`: named after the file.
+    assert_eq!(
+        kind("components/cart-summary.ts:CartSummary"),
+        Some(EntityKind::Component)
+    );
+    assert_eq!(
+        kind("composables/useCartTotal.ts:useCartTotal"),
+        Some(EntityKind::Hook)
+    );
+
+    let store = graph.get_entity("stores/cart.ts:useCartStore").unwrap();
+    assert_eq!(store.kind, EntityKind::Store);
+    assert_eq!(
+        store.attributes.get("store").map(String::as_str),
+        Some("cart")
+    );
+}
+
+#[test]
+fn test_pinia_state_edges() {
+    let graph = build_graph();
+    let store = "stores/cart.ts:useCartStore";
+
+    let reads = edges(&graph, EdgeKind::ReadsState);
+    for reader in [
+        "components/cart-summary.ts:<module>",
+        "composables/useCartTotal.ts:addToCart",
+        "composables/useCartTotal.ts:clearCart",
+        "composables/useCartTotal.ts:useCartTotal",
+    ] {
+        assert!(
+            reads.contains(&(reader, store)),
+            "{} in {:?}",
+            reader,
+            reads
+        );
+    }
+    assert_eq!(
+        edges(&graph, EdgeKind::WritesState),
+        vec![
+            ("components/cart-summary.ts:emptyCart", store),
+            ("composables/useCartTotal.ts:addToCart", store),
+            ("composables/useCartTotal.ts:clearCart", store),
+        ]
+    );
+}
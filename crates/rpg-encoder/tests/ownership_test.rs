@@ -0,0 +1,188 @@
+//! Integration test: CODEOWNERS ownership assigned to entities and rolled up
+//! into hierarchy areas, rpg_info, and hierarchy-node fetch output.
+
+use rpg_core::graph::*;
+use rpg_encoder::ownership::{CodeOwners, assign_owners};
+use rpg_nav::fetch::{FetchOutput, fetch};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const CODEOWNERS: &str = "\
+# Default owner
+*           @carol
+
+/auth/      @alice
+/billing/   @bob
+/billing/refunds.rs @bob @alice
+";
+
+fn make_entity(id: &str, file: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: id.rsplit(':').next().unwrap().to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 5,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// Two areas: Authentication (3 entities, all @alice) and Billing
+/// (4 entities: 3 @bob-only, 1 shared by @bob and @alice).
+fn make_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (id, file, path) in [
+        (
+            "auth/login.rs:login",
+            "auth/login.rs",
+            "Authentication/session/login",
+        ),
+        (
+            "auth/login.rs:logout",
+            "auth/login.rs",
+            "Authentication/session/login",
+        ),
+        (
+            "auth/token.rs:verify",
+            "auth/token.rs",
+            "Authentication/tokens/verify",
+        ),
+        (
+            "billing/invoice.rs:create",
+            "billing/invoice.rs",
+            "Billing/invoices/create",
+        ),
+        (
+            "billing/invoice.rs:send",
+            "billing/invoice.rs",
+            "Billing/invoices/send",
+        ),
+        (
+            "billing/invoice.rs:void",
+            "billing/invoice.rs",
+            "Billing/invoices/send",
+        ),
+        (
+            "billing/refunds.rs:refund",
+            "billing/refunds.rs",
+            "Billing/refunds/issue",
+        ),
+    ] {
+        let mut entity = make_entity(id, file);
+        entity.hierarchy_path = path.to_string();
+        graph.insert_into_hierarchy(path, id);
+        graph.insert_entity(entity);
+    }
+    graph.assign_hierarchy_ids();
+    graph
+}
+
+fn owners(node: &HierarchyNode) -> Vec<(&str, usize)> {
+    node.owners
+        .iter()
+        .map(|o| (o.owner.as_str(), o.entities))
+        .collect()
+}
+
+#[test]
+fn test_codeowners_last_matching_rule_wins() {
+    let root = Path::new("/repo");
+    let codeowners = CodeOwners::parse(root, CODEOWNERS);
+    assert_eq!(
+        codeowners.owners_for(Path::new("auth/login.rs")),
+        ["@alice"]
+    );
+    assert_eq!(
+        codeowners.owners_for(Path::new("billing/invoice.rs")),
+        ["@bob"]
+    );
+    assert_eq!(
+        codeowners.owners_for(Path::new("billing/refunds.rs")),
+        ["@bob", "@alice"]
+    );
+    assert_eq!(codeowners.owners_for(Path::new("README.md")), ["@carol"]);
+    assert!(
+        CodeOwners::parse(root, "/vendor/ @team\n/vendor/\n")
+            .owners_for(Path::new("vendor/lib.rs"))
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_areas_list_top_owners_by_entity_share() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".github")).unwrap();
+    std::fs::write(tmp.path().join(".github/CODEOWNERS"), CODEOWNERS).unwrap();
+
+    let mut graph = make_graph();
+    assert_eq!(assign_owners(&mut graph, tmp.path()), 7);
+    graph.aggregate_hierarchy_features();
+
+    assert_eq!(
+        owners(&graph.hierarchy["Authentication"]),
+        vec![("@alice", 3)]
+    );
+    assert_eq!(
+        owners(&graph.hierarchy["Billing"]),
+        vec![("@bob", 4), ("@alice", 1)]
+    );
+    let refunds = &graph.hierarchy["Billing"].children["refunds"];
+    assert_eq!(owners(refunds), vec![("@alice", 1), ("@bob", 1)]);
+
+    let info = rpg_nav::toon::format_rpg_info(&graph);
+    assert!(info.contains("@alice 100%"), "{}", info);
+    assert!(info.contains("@bob 100%, @alice 25%"), "{}", info);
+
+    let FetchOutput::Hierarchy(node) = fetch(&graph, "h:Billing/invoices", tmp.path()).unwrap()
+    else {
+        panic!("expected hierarchy fetch");
+    };
+    let output = rpg_nav::toon::format_hierarchy_fetch_result(&node);
+    assert!(output.contains("owners: @bob 100%"), "{}", output);
+}
+
+#[test]
+fn test_ownership_updates_when_codeowners_changes() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("CODEOWNERS"), CODEOWNERS).unwrap();
+    let mut graph = make_graph();
+    assign_owners(&mut graph, tmp.path());
+    graph.aggregate_hierarchy_features();
+
+    std::fs::write(tmp.path().join("CODEOWNERS"), "/auth/ @dave\n").unwrap();
+    assert_eq!(assign_owners(&mut graph, tmp.path()), 3);
+    graph.aggregate_hierarchy_features();
+    assert_eq!(
+        owners(&graph.hierarchy["Authentication"]),
+        vec![("@dave", 3)]
+    );
+    assert!(graph.hierarchy["Billing"].owners.is_empty());
+
+    std::fs::remove_file(tmp.path().join("CODEOWNERS")).unwrap();
+    assert_eq!(assign_owners(&mut graph, tmp.path()), 0);
+    graph.aggregate_hierarchy_features();
+    assert!(graph.hierarchy["Authentication"].owners.is_empty());
+
+    let info = rpg_nav::toon::format_rpg_info(&graph);
+    assert!(!info.contains("owners"), "{}", info);
+}
@@ -0,0 +1,290 @@
+//! Integration test: paradigm attributes (route path + method) flow from the
+//! paradigm pipeline through the graph into navigation output.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_nav::fetch::{FetchOutput, fetch};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root(name: &str) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn collect_files(base: &Path, dir: &Path, exts: &[&str], out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, exts, out);
+        } else if path
+            .extension()
+            .is_some_and(|e| exts.iter().any(|ext| e == *ext))
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            let source = std::fs::read_to_string(&path).unwrap();
+            out.push((rel, source));
+        }
+    }
+}
+
+/// Build a graph from a fixture project through the paradigm pipeline.
+fn build_graph(name: &str, language: Language, exts: &[&str]) -> (RPGraph, PathBuf) {
+    let root = fixture_root(name);
+    let mut files = Vec::new();
+    collect_files(&root, &root, exts, &mut files);
+    assert!(
+        !files.is_empty(),
+        "no {:?} files in {}",
+        exts,
+        root.display()
+    );
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(&root, &[language], &paradigm_defs);
+
+    let mut graph = RPGraph::new(language.name());
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    graph.build_file_path_hierarchy();
+    graph.assign_hierarchy_ids();
+    graph.refresh_metadata();
+    (graph, root)
+}
+
+fn fetch_text(graph: &RPGraph, root: &Path, id: &str) -> String {
+    match fetch(graph, id, root).unwrap() {
+        FetchOutput::Entity(result) => rpg_nav::toon::format_fetch_result(&result),
+        FetchOutput::Hierarchy(_) => panic!("expected entity fetch for {}", id),
+    }
+}
+
+#[test]
+fn test_express_route_attributes_in_fetch() {
+    let (graph, root) = build_graph("express_project", Language::JAVASCRIPT, &["js"]);
+    let id = "src/routes/users.js:registerUserRoutes";
+
+    let entity = graph.get_entity(id).expect("missing route entity");
+    assert_eq!(entity.kind, EntityKind::Route);
+    assert_eq!(
+        entity.attributes.get("route").map(String::as_str),
+        Some("GET /users, POST /users")
+    );
+
+    let output = fetch_text(&graph, &root, id);
+    assert!(output.contains("route"), "{}", output);
+    assert!(output.contains("GET /users"), "{}", output);
+}
+
+#[test]
+fn test_fastapi_route_attributes_in_fetch() {
+    let (graph, root) = build_graph("fastapi_project", Language::PYTHON, &["py"]);
+    let id = "app/main.py:list_users";
+
+    let entity = graph.get_entity(id).expect("missing route entity");
+    assert_eq!(entity.kind, EntityKind::Route);
+
+    let output = fetch_text(&graph, &root, id);
+    assert!(output.contains("route: GET /users"), "{}", output);
+
+    // Plain helpers and module entities carry no attributes.
+    let module = graph
+        .get_entity("app/main.py:main")
+        .expect("missing module");
+    assert!(module.attributes.is_empty());
+}
+
+#[test]
+fn test_route_attributes_in_search_and_explore() {
+    let (graph, _root) = build_graph("fastapi_project", Language::PYTHON, &["py"]);
+
+    let results = rpg_nav::search::search(
+        &graph,
+        "list_users",
+        rpg_nav::search::SearchMode::Snippets,
+        None,
+        5,
+    )
+    .unwrap();
+    let output = rpg_nav::toon::format_search_results(&results, None);
+    assert!(output.contains("route: GET /users"), "{}", output);
+
+    let tree = rpg_nav::explore::explore(
+        &graph,
+        "app/main.py:list_users",
+        rpg_nav::explore::Direction::Both,
+        1,
+        None,
+    )
+    .unwrap();
+    let rendered = rpg_nav::explore::format_tree(&tree, 0);
+    assert!(
+        rendered.starts_with("list_users (route) [app/main.py] {route: GET /users}"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn test_attributes_roundtrip_through_storage() {
+    let (graph, _root) = build_graph("fastapi_project", Language::PYTHON, &["py"]);
+    let json = serde_json::to_string(&graph).unwrap();
+    let restored: RPGraph = serde_json::from_str(&json).unwrap();
+    let entity = restored.get_entity("app/main.py:create_user").unwrap();
+    assert_eq!(
+        entity.attributes.get("route").map(String::as_str),
+        Some("POST /users")
+    );
+}
+
+/// The Next.js fixture with dependencies populated and resolved.
+fn build_nextjs_graph() -> RPGraph {
+    let (mut graph, root) = build_graph("nextjs_project", Language::TYPESCRIPT, &["ts", "tsx"]);
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let ctx = rpg_encoder::grounding::ParadigmContext {
+        active_defs: rpg_parser::paradigms::detect_paradigms_toml(
+            &root,
+            &[Language::TYPESCRIPT],
+            &paradigm_defs,
+        ),
+        qcache: &qcache,
+    };
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn has_edge(graph: &RPGraph, source: &str, target: &str, kind: EdgeKind) -> bool {
+    graph
+        .edges
+        .iter()
+        .any(|e| e.source == source && e.target == target && e.kind == kind)
+}
+
+#[test]
+fn test_nextjs_route_tree_entities() {
+    let graph = build_nextjs_graph();
+    let route = |id: &str| {
+        let entity = graph
+            .get_entity(id)
+            .unwrap_or_else(|| panic!("missing {}", id));
+        assert_eq!(entity.kind, EntityKind::Route);
+        (
+            entity.attributes["route"].as_str(),
+            entity.attributes["route_file"].as_str(),
+        )
+    };
+
+    assert_eq!(
+        route("app/dashboard/settings/page.tsx:dashboard settings page route"),
+        ("/dashboard/settings", "page")
+    );
+    assert_eq!(
+        route("app/users/[id]/page.tsx:users id page route"),
+        ("/users/[id]", "page")
+    );
+    // Route groups don't appear in the URL.
+    assert_eq!(
+        route("app/(marketing)/about/page.tsx:about page route"),
+        ("/about", "page")
+    );
+    assert_eq!(route("app/layout.tsx:root layout route"), ("/", "layout"));
+    assert_eq!(
+        route("app/dashboard/loading.tsx:dashboard loading route"),
+        ("/dashboard", "loading")
+    );
+    assert_eq!(
+        route("app/api/posts/route.ts:api posts handler route"),
+        ("GET /api/posts, POST /api/posts", "route")
+    );
+
+    // The page component itself keeps its own entity.
+    let page = graph
+        .get_entity("app/dashboard/settings/page.tsx:SettingsPage")
+        .unwrap();
+    assert_eq!(page.kind, EntityKind::Page);
+}
+
+#[test]
+fn test_nextjs_route_tree_edges() {
+    let graph = build_nextjs_graph();
+    let root_layout = "app/layout.tsx:root layout route";
+    let dashboard_layout = "app/dashboard/layout.tsx:dashboard layout route";
+
+    for (layout, child) in [
+        (root_layout, dashboard_layout),
+        (root_layout, "app/users/[id]/page.tsx:users id page route"),
+        (
+            root_layout,
+            "app/(marketing)/about/page.tsx:about page route",
+        ),
+        (root_layout, "app/login/page.tsx:login page route"),
+        (
+            dashboard_layout,
+            "app/dashboard/settings/page.tsx:dashboard settings page route",
+        ),
+        (
+            dashboard_layout,
+            "app/dashboard/page.tsx:dashboard page route",
+        ),
+        (
+            dashboard_layout,
+            "app/dashboard/loading.tsx:dashboard loading route",
+        ),
+    ] {
+        assert!(
+            has_edge(&graph, layout, child, EdgeKind::Renders),
+            "{} should render {}",
+            layout,
+            child
+        );
+    }
+    // Only the nearest layout renders a page.
+    assert!(!has_edge(
+        &graph,
+        root_layout,
+        "app/dashboard/settings/page.tsx:dashboard settings page route",
+        EdgeKind::Renders
+    ));
+
+    let handler = "app/api/posts/route.ts:api posts handler route";
+    for target in ["src/lib/posts.ts:listPosts", "src/lib/posts.ts:createPost"] {
+        assert!(
+            has_edge(&graph, handler, target, EdgeKind::Dispatches),
+            "{} should dispatch to {}",
+            handler,
+            target
+        );
+    }
+}
+
+#[test]
+fn test_nextjs_route_found_by_search() {
+    let graph = build_nextjs_graph();
+    let results = rpg_nav::search::search(
+        &graph,
+        "settings page route",
+        rpg_nav::search::SearchMode::Auto,
+        None,
+        5,
+    )
+    .unwrap();
+    assert_eq!(
+        results[0].entity_id,
+        "app/dashboard/settings/page.tsx:dashboard settings page route"
+    );
+    let output = rpg_nav::toon::format_search_results(&results, None);
+    assert!(output.contains("route: /dashboard/settings"), "{}", output);
+}
@@ -0,0 +1,149 @@
+//! Integration test: Redux Toolkit and Zustand state wiring — Store entities
+//! plus ReadsState / WritesState / Dispatches edges from fixture apps.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_encoder::grounding;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root(name: &str) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .is_some_and(|e| e == "ts" || e == "tsx" || e == "js")
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            let source = std::fs::read_to_string(&path).unwrap();
+            out.push((rel, source));
+        }
+    }
+}
+
+/// Build a fixture through the paradigm pipeline with resolved dependencies.
+fn build_graph(name: &str) -> RPGraph {
+    let root = fixture_root(name);
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+
+    let languages = [Language::TYPESCRIPT, Language::JAVASCRIPT];
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(&root, &languages, &paradigm_defs);
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    let ctx = grounding::ParadigmContext {
+        active_defs,
+        qcache: &qcache,
+    };
+    grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn edges(graph: &RPGraph, kind: EdgeKind) -> Vec<(&str, &str)> {
+    let mut edges: Vec<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == kind)
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+    edges.sort_unstable();
+    edges
+}
+
+#[test]
+fn test_redux_state_edges() {
+    let graph = build_graph("nextjs_project");
+    let slice = "src/state/authSlice.ts:authSlice";
+
+    // Selectors reading `state.auth` read the slice whose name is "auth".
+    let reads = edges(&graph, EdgeKind::ReadsState);
+    assert!(
+        reads.contains(&("src/state/selectors.ts:selectUser", slice)),
+        "{:?}",
+        reads
+    );
+    assert!(reads.contains(&(
+        "src/hooks/useAuth.ts:useAuth",
+        "src/state/selectors.ts:selectUser"
+    )));
+
+    // Reducers write their slice.
+    let writes = edges(&graph, EdgeKind::WritesState);
+    assert!(
+        writes.contains(&("src/state/authSlice.ts:authSlice::logout", slice)),
+        "{:?}",
+        writes
+    );
+
+    // Both dispatch(logout()) and dispatch(authActions.loginStarted()) reach the reducer.
+    let dispatches = edges(&graph, EdgeKind::Dispatches);
+    assert!(
+        dispatches.contains(&(
+            "src/hooks/useAuth.ts:useAuth",
+            "src/state/authSlice.ts:authSlice::logout"
+        )),
+        "{:?}",
+        dispatches
+    );
+    assert!(dispatches.contains(&(
+        "src/components/LoginForm.tsx:LoginForm",
+        "src/state/authSlice.ts:authSlice::loginStarted"
+    )));
+
+    let reducer = graph
+        .get_entity("src/state/authSlice.ts:authSlice::loginStarted")
+        .unwrap();
+    assert_eq!(
+        reducer.attributes.get("action").map(String::as_str),
+        Some("auth/loginStarted")
+    );
+    // Reducer bodies touch `state.loading`, which is slice state, not a root key.
+    assert!(reducer.deps.reads_state.is_empty());
+}
+
+#[test]
+fn test_zustand_state_edges() {
+    let graph = build_graph("zustand_project");
+    let store = "src/store/useCartStore.ts:useCartStore";
+    for id in [store, "src/store/useThemeStore.js:useThemeStore"] {
+        assert_eq!(
+            graph.get_entity(id).unwrap().kind,
+            EntityKind::Store,
+            "{}",
+            id
+        );
+    }
+
+    assert_eq!(
+        edges(&graph, EdgeKind::ReadsState),
+        vec![
+            ("src/components/Cart.tsx:Cart", store),
+            ("src/components/Cart.tsx:cartSize", store),
+        ]
+    );
+    assert_eq!(
+        edges(&graph, EdgeKind::WritesState),
+        vec![("src/components/Cart.tsx:resetCart", store)]
+    );
+}
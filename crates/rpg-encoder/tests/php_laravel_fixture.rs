@@ -0,0 +1,92 @@
+//! Integration test: a Laravel-style PHP project.
+//!
+//! Two classes named `Payment` live in different namespaces; static calls
+//! through `use` imports, aliases, and fully qualified names must resolve to
+//! the defining file. A mixed HTML/PHP view must extract cleanly.
+
+use rpg_core::graph::{EntityKind, RPGraph};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/laravel_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Language::from_extension)
+            .is_some()
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph(root: &Path) -> RPGraph {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files);
+    let mut graph = RPGraph::new("php");
+    for raw in rpg_parser::parse_files_parallel(files) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, true, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.refresh_metadata();
+    graph
+}
+
+const CONTROLLER: &str = "app/Http/Controllers/OrderController.php";
+const SERVICE_CHARGE: &str = "app/Services/Payment.php:Payment::charge";
+const LEGACY_CHARGE: &str = "app/Legacy/Payment.php:Payment::charge";
+
+fn invokes(graph: &RPGraph, method: &str) -> Vec<String> {
+    let source = format!("{}:OrderController::{}", CONTROLLER, method);
+    graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == rpg_core::graph::EdgeKind::Invokes)
+        .map(|e| e.target.clone())
+        .collect()
+}
+
+#[test]
+fn test_namespaced_static_calls_resolve_to_defining_file() {
+    let graph = build_graph(&fixture_root());
+    assert_eq!(graph.entities[SERVICE_CHARGE].kind, EntityKind::Method);
+    assert_eq!(graph.entities[LEGACY_CHARGE].kind, EntityKind::Method);
+
+    // `use App\Services\Payment;` then `Payment::charge()`
+    assert_eq!(invokes(&graph, "store"), [SERVICE_CHARGE]);
+    // `use App\Legacy\Payment as LegacyPayment;` then `LegacyPayment::charge()`
+    assert_eq!(invokes(&graph, "migrate"), [LEGACY_CHARGE]);
+    // `\App\Legacy\Payment::charge()`
+    assert_eq!(invokes(&graph, "refund"), [LEGACY_CHARGE]);
+    assert!(
+        graph.entities[SERVICE_CHARGE]
+            .deps
+            .invoked_by
+            .contains(&format!("{}:OrderController::store", CONTROLLER))
+    );
+}
+
+#[test]
+fn test_mixed_html_view_extracts_functions() {
+    let graph = build_graph(&fixture_root());
+    let total = &graph.entities["resources/views/order.php:order_total"];
+    assert_eq!(total.kind, EntityKind::Function);
+    assert_eq!(total.line_start, 4);
+}
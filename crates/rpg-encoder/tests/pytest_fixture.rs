@@ -0,0 +1,162 @@
+//! Integration test: a pytest suite with fixtures at three conftest levels.
+//!
+//! `@pytest.fixture` functions become Fixtures, and each test composes the
+//! fixtures its parameters name — resolved through the conftest chain the
+//! way pytest does, with parametrized arguments and built-in fixtures left
+//! out.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_encoder::grounding;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/pytest_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path.extension().is_some_and(|e| e == "py") {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph() -> RPGraph {
+    let root = fixture_root();
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+
+    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap();
+    let qcache =
+        rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs).unwrap();
+    let active_defs =
+        rpg_parser::paradigms::detect_paradigms_toml(&root, &[Language::PYTHON], &paradigm_defs);
+    assert!(active_defs.iter().any(|d| d.name == "pytest"));
+
+    let mut graph = RPGraph::new("python");
+    for raw in rpg_parser::parse_files_with_paradigms(files, &active_defs, &qcache) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    let ctx = grounding::ParadigmContext {
+        active_defs,
+        qcache: &qcache,
+    };
+    grounding::populate_entity_deps(&mut graph, &root, false, None, Some(&ctx));
+    grounding::resolve_dependencies(&mut graph);
+    graph
+}
+
+fn composes(graph: &RPGraph, source: &str) -> Vec<String> {
+    assert!(graph.get_entity(source).is_some(), "missing {}", source);
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Composes && e.source == source)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort_unstable();
+    targets
+}
+
+#[test]
+fn test_fixtures_are_classified() {
+    let graph = build_graph();
+    let mut fixtures: Vec<&str> = graph
+        .entities
+        .values()
+        .filter(|e| e.kind == EntityKind::Fixture)
+        .map(|e| e.id.as_str())
+        .collect();
+    fixtures.sort_unstable();
+    assert_eq!(
+        fixtures,
+        [
+            "conftest.py:client",
+            "conftest.py:db_session",
+            "tests/api/conftest.py:client",
+            "tests/conftest.py:settings_fixture",
+            "tests/conftest.py:user",
+            "tests/test_local.py:user",
+        ]
+    );
+    let kind = |id: &str| graph.get_entity(id).unwrap().kind;
+    assert_eq!(
+        kind("tests/api/test_users.py:test_lookup"),
+        EntityKind::Test
+    );
+    assert_eq!(kind("app/db.py:connect"), EntityKind::Function);
+}
+
+#[test]
+fn test_tests_compose_fixtures_through_conftest_chain() {
+    let graph = build_graph();
+    // `tmp_path` is built in; nothing provides it.
+    assert_eq!(
+        composes(&graph, "tests/api/test_users.py:test_create_user"),
+        ["tests/api/conftest.py:client", "tests/conftest.py:user"]
+    );
+    // Parametrized arguments aren't fixtures.
+    assert_eq!(
+        composes(&graph, "tests/api/test_users.py:test_lookup"),
+        ["tests/conftest.py:user"]
+    );
+    assert_eq!(
+        composes(&graph, "tests/api/test_users.py:test_schema"),
+        ["conftest.py:db_session"]
+    );
+    assert_eq!(
+        composes(&graph, "tests/api/test_users.py:TestAdmin::test_promote"),
+        ["tests/conftest.py:user"]
+    );
+    // A fixture in the test's own module wins; `name=` renames a fixture.
+    assert_eq!(
+        composes(&graph, "tests/test_local.py:test_local_user"),
+        [
+            "tests/conftest.py:settings_fixture",
+            "tests/test_local.py:user"
+        ]
+    );
+}
+
+#[test]
+fn test_fixtures_compose_fixtures() {
+    let graph = build_graph();
+    // An overriding fixture requesting its own name gets the outer one.
+    assert_eq!(
+        composes(&graph, "tests/api/conftest.py:client"),
+        ["conftest.py:client", "tests/conftest.py:settings_fixture"]
+    );
+    assert_eq!(
+        composes(&graph, "tests/conftest.py:user"),
+        ["conftest.py:db_session"]
+    );
+
+    let db_session = graph.get_entity("conftest.py:db_session").unwrap();
+    let mut users: Vec<&str> = db_session
+        .deps
+        .composed_by
+        .iter()
+        .map(String::as_str)
+        .collect();
+    users.sort_unstable();
+    assert_eq!(
+        users,
+        [
+            "conftest.py:client",
+            "tests/api/test_users.py:test_schema",
+            "tests/conftest.py:user",
+        ]
+    );
+}
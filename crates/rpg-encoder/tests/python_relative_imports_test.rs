@@ -0,0 +1,86 @@
+//! Integration test: Python relative imports become Imports edges between
+//! Module entities, resolved by walking up one package per extra dot.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+use tempfile::TempDir;
+
+fn imported(graph: &RPGraph, source: &str) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == EdgeKind::Imports)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+fn package() -> (TempDir, RPGraph) {
+    common::build(
+        "python",
+        &[
+            ("pkg/__init__.py", "VERSION = '1.0'\n"),
+            ("pkg/utils/__init__.py", "def noop():\n    pass\n"),
+            (
+                "pkg/utils/helpers.py",
+                "def slugify(text):\n    return text.lower()\n",
+            ),
+            (
+                "pkg/api/views.py",
+                "from ..utils.helpers import slugify\n\
+             from . import (forms,)\n\n\
+             def index(title):\n    return slugify(title)\n",
+            ),
+            ("pkg/api/forms.py", "class Form:\n    pass\n"),
+            // Same module name elsewhere: only the relative path may match.
+            (
+                "other/utils/helpers.py",
+                "def slugify(text):\n    return text\n",
+            ),
+            // Namespace packages: no __init__.py anywhere under ns/.
+            ("ns/tools/fmt.py", "def pretty(value):\n    return value\n"),
+            (
+                "ns/app/main.py",
+                "from ..tools.fmt import pretty\n\n\
+             def run():\n    return pretty(1)\n",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_relative_imports_land_on_module_entities() {
+    let (_tmp, graph) = package();
+    assert_eq!(
+        imported(&graph, "pkg/api/views.py:views"),
+        vec![
+            "pkg/api/forms.py:forms".to_string(),
+            "pkg/utils/helpers.py:helpers".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_relative_imports_resolve_in_namespace_packages() {
+    let (_tmp, graph) = package();
+    assert_eq!(
+        imported(&graph, "ns/app/main.py:main"),
+        vec!["ns/tools/fmt.py:fmt".to_string()]
+    );
+}
+
+#[test]
+fn test_relative_import_symbols_still_resolve_to_entities() {
+    let (_tmp, graph) = package();
+    assert!(
+        graph
+            .edges
+            .iter()
+            .any(|e| e.source == "pkg/api/views.py:index"
+                && e.target == "pkg/utils/helpers.py:slugify"
+                && e.kind == EdgeKind::Invokes)
+    );
+}
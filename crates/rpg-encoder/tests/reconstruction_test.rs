@@ -1,4 +1,5 @@
 use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn entity(id: &str, name: &str, hierarchy_path: &str, kind: EntityKind) -> Entity {
@@ -12,9 +13,22 @@ fn entity(id: &str, name: &str, hierarchy_path: &str, kind: EntityKind) -> Entit
         parent_class: None,
         semantic_features: vec![],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: hierarchy_path.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -148,3 +162,47 @@ fn test_topological_execution_order_excludes_modules_by_default() {
         rpg_encoder::reconstruction::build_topological_execution_order(&g, true);
     assert_eq!(order_with_modules.len(), 2);
 }
+
+#[test]
+fn test_reconstruction_state_round_trip_and_next_batch() {
+    use rpg_encoder::reconstruction::{ReconstructionOptions, ReconstructionState};
+
+    let mut g = RPGraph::new("rust");
+    for id in ["a", "b", "c"] {
+        g.insert_entity(entity(id, id, "Core/services/run", EntityKind::Function));
+    }
+    g.edges.push(DependencyEdge {
+        source: "b".into(),
+        target: "a".into(),
+        kind: EdgeKind::Invokes,
+    });
+    g.edges.push(DependencyEdge {
+        source: "c".into(),
+        target: "b".into(),
+        kind: EdgeKind::Invokes,
+    });
+
+    let tmp = tempfile::tempdir().unwrap();
+    assert!(ReconstructionState::load(tmp.path()).unwrap().is_none());
+
+    let options = ReconstructionOptions {
+        max_batch_size: 1,
+        include_modules: false,
+    };
+    let mut state = ReconstructionState::new(&g, options);
+    assert_eq!(
+        state.next_batch().unwrap().entity_ids,
+        vec!["a".to_string()]
+    );
+    let unknown = state.mark_done(&["a".to_string(), "zzz".to_string()]);
+    assert_eq!(unknown, vec!["zzz".to_string()]);
+    state.save(tmp.path()).unwrap();
+
+    let restored = ReconstructionState::load(tmp.path()).unwrap().unwrap();
+    assert_eq!(restored.plan, state.plan);
+    assert_eq!(restored.next_batch().unwrap().batch_index, 2);
+    assert_eq!(
+        rpg_encoder::reconstruction::prerequisites(&g, "c"),
+        vec!["b".to_string()]
+    );
+}
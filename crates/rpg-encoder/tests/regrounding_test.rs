@@ -0,0 +1,184 @@
+//! Integration test: grounded hierarchy paths follow a directory rename
+//! through `run_update`.
+
+use rpg_core::graph::RPGraph;
+use rpg_encoder::evolution::{FileChange, moved_directories, run_update, run_update_from_changes};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const FILES: &[&str] = &["src/net/http.py", "src/net/socket.py", "src/app.py"];
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Stage the whole worktree, including deletions, and commit it on HEAD.
+fn commit_all(repo: &git2::Repository, message: &str) {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.update_all(["*"], None).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    let parent = repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap();
+}
+
+/// A lifted graph with a semantic hierarchy: the networking files under
+/// `Networking/transport`, the app under `App/entry`.
+fn build_graph(root: &Path) -> RPGraph {
+    let mut graph = RPGraph::new("python");
+    for rel in FILES {
+        let source = std::fs::read_to_string(root.join(rel)).unwrap();
+        for raw in extract_entities(Path::new(rel), &source, Language::PYTHON) {
+            let mut entity = raw.into_entity();
+            entity.semantic_features = vec![format!("handle {}", entity.name)];
+            entity.hierarchy_path = if rel.starts_with("src/net/") {
+                "Networking/transport".to_string()
+            } else {
+                "App/entry".to_string()
+            };
+            let (id, path) = (entity.id.clone(), entity.hierarchy_path.clone());
+            graph.insert_entity(entity);
+            graph.insert_into_hierarchy(&path, &id);
+        }
+    }
+    graph.metadata.semantic_hierarchy = true;
+    rpg_encoder::grounding::ground_hierarchy(&mut graph);
+    graph.base_commit = Some(rpg_encoder::evolution::get_head_sha(root).unwrap());
+    graph.refresh_metadata();
+    graph
+}
+
+fn scripted_repo() -> (TempDir, git2::Repository) {
+    let tmp = TempDir::new().unwrap();
+    let repo = git2::Repository::init(tmp.path()).unwrap();
+    write(tmp.path(), FILES[0], "def get(url):\n    return url\n");
+    write(
+        tmp.path(),
+        FILES[1],
+        "def connect(host):\n    return host\n",
+    );
+    write(
+        tmp.path(),
+        FILES[2],
+        "from net.http import get\n\ndef main():\n    return get('x')\n",
+    );
+    commit_all(&repo, "base");
+    (tmp, repo)
+}
+
+fn grounded(graph: &RPGraph, path: &str) -> Vec<PathBuf> {
+    let (area, child) = path.split_once('/').unwrap();
+    graph.hierarchy[area].children[child].grounded_paths.clone()
+}
+
+#[test]
+fn test_grounded_paths_follow_directory_rename() {
+    let (tmp, repo) = scripted_repo();
+    let root = tmp.path();
+    let mut graph = build_graph(root);
+    assert_eq!(
+        grounded(&graph, "Networking/transport"),
+        vec![PathBuf::from("src/net")]
+    );
+
+    std::fs::rename(root.join("src/net"), root.join("src/network")).unwrap();
+    commit_all(&repo, "rename net to network");
+
+    let summary = run_update(&mut graph, root, None, None).unwrap();
+    assert_eq!(
+        summary.regrounded_nodes,
+        vec!["Networking/transport", "Networking"]
+    );
+    assert_eq!(
+        grounded(&graph, "Networking/transport"),
+        vec![PathBuf::from("src/network")]
+    );
+    assert_eq!(grounded(&graph, "App/entry"), vec![PathBuf::from("src")]);
+
+    // The files were migrated, not re-added: features survive the move.
+    let get = &graph.entities["src/network/http.py:get"];
+    assert_eq!(get.semantic_features, vec!["handle get"]);
+    assert_eq!(get.hierarchy_path, "Networking/transport");
+    assert!(!graph.entities.contains_key("src/net/http.py:get"));
+    assert_eq!(summary.entities_added, 0);
+    assert_eq!(summary.entities_removed, 0);
+}
+
+#[test]
+fn test_nodes_without_entities_keep_remapped_paths() {
+    let (tmp, _repo) = scripted_repo();
+    let root = tmp.path();
+    let mut graph = build_graph(root);
+    // A node with no entities can't be re-grounded from its subtree; the
+    // prefix rewrite alone moves it.
+    let mut legacy = rpg_core::graph::HierarchyNode::new("legacy");
+    legacy.grounded_paths = vec![PathBuf::from("src/net/legacy")];
+    graph
+        .hierarchy
+        .get_mut("Networking")
+        .unwrap()
+        .children
+        .insert("legacy".to_string(), legacy);
+
+    std::fs::rename(root.join("src/net"), root.join("src/network")).unwrap();
+    let changes = FILES[..2]
+        .iter()
+        .map(|rel| FileChange::Renamed {
+            from: PathBuf::from(rel),
+            to: PathBuf::from(rel.replace("src/net/", "src/network/")),
+        })
+        .collect();
+    let summary = run_update_from_changes(&mut graph, root, changes, None).unwrap();
+
+    assert_eq!(
+        summary.regrounded_nodes,
+        vec!["Networking/legacy", "Networking/transport", "Networking"]
+    );
+    assert_eq!(
+        grounded(&graph, "Networking/legacy"),
+        vec![PathBuf::from("src/network/legacy")]
+    );
+    assert_eq!(
+        grounded(&graph, "Networking/transport"),
+        vec![PathBuf::from("src/network")]
+    );
+    assert!(graph.entities.contains_key("src/network/socket.py:connect"));
+}
+
+#[test]
+fn test_moved_directories_skips_partial_moves_and_file_renames() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    write(root, "src/core/lib.py", "");
+    let renames = vec![
+        // Whole directory moved: src/net no longer exists.
+        (
+            PathBuf::from("src/net/http/client.py"),
+            PathBuf::from("src/network/http/client.py"),
+        ),
+        // One file moved out of a directory that still exists.
+        (
+            PathBuf::from("src/core/util.py"),
+            PathBuf::from("src/util/util.py"),
+        ),
+        // Renamed file, same directory.
+        (
+            PathBuf::from("src/core/a.py"),
+            PathBuf::from("src/core/b.py"),
+        ),
+    ];
+    assert_eq!(
+        moved_directories(root, &renames),
+        vec![(PathBuf::from("src/net"), PathBuf::from("src/network"))]
+    );
+}
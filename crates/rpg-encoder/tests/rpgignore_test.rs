@@ -2,6 +2,7 @@
 
 use rpg_core::graph::*;
 use rpg_encoder::evolution::{FileChange, filter_rpgignore_changes, find_newly_ignored_files};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
@@ -145,9 +146,22 @@ fn make_test_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: vec![],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -0,0 +1,99 @@
+//! Integration test: a Rust crate's module tree becomes edges between
+//! Module entities — `mod` declarations and `pub use` re-exports as Composes,
+//! `crate::` / `super::` / `self::` and glob imports as Imports.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+use tempfile::TempDir;
+
+fn targets(graph: &RPGraph, source: &str, kind: EdgeKind) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == kind)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+fn fixture_crate() -> (TempDir, RPGraph) {
+    common::build(
+        "rust",
+        &[
+            (
+                "src/lib.rs",
+                "mod utils;\n\
+             pub mod net;\n\
+             pub use utils::*;\n\
+             pub use net::client::Client;\n\n\
+             #[cfg(test)]\n\
+             mod tests {\n    fn inline() {}\n}\n",
+            ),
+            (
+                "src/utils.rs",
+                "mod helpers;\n\npub fn slugify(s: &str) -> String {\n    s.to_string()\n}\n",
+            ),
+            (
+                "src/utils/helpers.rs",
+                "pub fn trim(s: &str) -> &str {\n    s.trim()\n}\n",
+            ),
+            (
+                "src/net/mod.rs",
+                "pub mod client;\nuse super::utils::*;\n\npub fn connect() {\n    slugify(\"x\");\n}\n",
+            ),
+            (
+                "src/net/client.rs",
+                "use crate::utils::helpers::trim;\nuse self::wire::*;\n\n\
+             pub struct Client;\n\n\
+             pub fn send() {\n    trim(\" x \");\n}\n",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_mod_declarations_compose_child_modules() {
+    let (_tmp, graph) = fixture_crate();
+    assert_eq!(
+        targets(&graph, "src/utils.rs:utils", EdgeKind::Composes),
+        vec!["src/utils/helpers.rs:helpers".to_string()]
+    );
+    assert_eq!(
+        targets(&graph, "src/net/mod.rs:mod", EdgeKind::Composes),
+        vec!["src/net/client.rs:client".to_string()]
+    );
+}
+
+#[test]
+fn test_pub_use_reexports_compose_modules_and_items() {
+    let (_tmp, graph) = fixture_crate();
+    assert_eq!(
+        targets(&graph, "src/lib.rs:lib", EdgeKind::Composes),
+        vec![
+            "src/net/client.rs:Client".to_string(),
+            "src/net/mod.rs:mod".to_string(),
+            "src/utils.rs:utils".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_use_paths_and_globs_import_module_files() {
+    let (_tmp, graph) = fixture_crate();
+    assert_eq!(
+        targets(&graph, "src/net/mod.rs:mod", EdgeKind::Imports),
+        vec!["src/utils.rs:utils".to_string()]
+    );
+    // `self::wire` names no file: no edge rather than a guess.
+    assert_eq!(
+        targets(&graph, "src/net/client.rs:client", EdgeKind::Imports),
+        vec!["src/utils/helpers.rs:helpers".to_string()]
+    );
+    assert!(
+        targets(&graph, "src/net/mod.rs:connect", EdgeKind::Invokes)
+            .contains(&"src/utils.rs:slugify".to_string())
+    );
+}
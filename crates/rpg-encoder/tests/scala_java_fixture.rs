@@ -0,0 +1,112 @@
+//! Integration test: a mixed Scala/Java (Spark-style) project.
+//!
+//! Scala companion objects get IDs distinct from their classes, and calls
+//! from both languages resolve to the Scala entities.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir
+        .parent()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("tests/fixtures/scala_java_project")
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<(PathBuf, String)>) {
+    for entry in std::fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Language::from_extension)
+            .is_some()
+        {
+            let rel = path.strip_prefix(base).unwrap().to_path_buf();
+            out.push((rel, std::fs::read_to_string(&path).unwrap()));
+        }
+    }
+}
+
+fn build_graph(root: &Path) -> RPGraph {
+    let mut files = Vec::new();
+    collect_files(root, root, &mut files);
+    let mut graph = RPGraph::new("scala");
+    for raw in rpg_parser::parse_files_parallel(files) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, root, true, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.refresh_metadata();
+    graph
+}
+
+const JOB: &str = "src/main/scala/com/example/Job.scala";
+
+fn has_edge(graph: &RPGraph, source: &str, target: &str, kind: EdgeKind) -> bool {
+    graph
+        .edges
+        .iter()
+        .any(|e| e.source == source && e.target == target && e.kind == kind)
+}
+
+#[test]
+fn test_mixed_project_detects_scala_and_java() {
+    let detected = Language::detect_all(&fixture_root());
+    assert_eq!(detected.first(), Some(&Language::SCALA));
+    assert!(detected.contains(&Language::JAVA));
+}
+
+#[test]
+fn test_companion_object_is_distinct_from_class() {
+    let graph = build_graph(&fixture_root());
+    let class = format!("{}:Job", JOB);
+    let companion = format!("{}:Job$", JOB);
+    let create = format!("{}:Job$::create", JOB);
+    assert_eq!(graph.entities[&class].kind, EntityKind::Class);
+    assert_eq!(graph.entities[&companion].kind, EntityKind::Class);
+    assert_eq!(graph.entities[&create].kind, EntityKind::Method);
+    assert!(graph.entities.contains_key(&format!("{}:Job::run", JOB)));
+    // A standalone object keeps its name.
+    assert!(
+        graph
+            .entities
+            .contains_key("src/main/scala/com/example/Pipeline.scala:Pipeline")
+    );
+
+    // `new Job(name)` in the companion and `Job.create(...)` from Scala and Java.
+    assert!(has_edge(&graph, &create, &class, EdgeKind::Invokes));
+    assert!(has_edge(
+        &graph,
+        "src/main/scala/com/example/Pipeline.scala:Pipeline::main",
+        &create,
+        EdgeKind::Invokes
+    ));
+    assert!(has_edge(
+        &graph,
+        "src/main/java/com/example/Launcher.java:Launcher::start",
+        &create,
+        EdgeKind::Invokes
+    ));
+
+    // `extends Runnable with Logging` and the case class apply.
+    assert!(has_edge(
+        &graph,
+        &class,
+        &format!("{}:Logging", JOB),
+        EdgeKind::Inherits
+    ));
+    assert!(has_edge(
+        &graph,
+        &format!("{}:Job::run", JOB),
+        &format!("{}:Point", JOB),
+        EdgeKind::Invokes
+    ));
+}
@@ -0,0 +1,120 @@
+//! Integration test: calls inside spawned closures mark their Invokes edges
+//! as async boundaries, which impact analysis can highlight or stop at.
+
+use rpg_core::graph::RPGraph;
+use rpg_nav::explore::Direction;
+use rpg_nav::impact::{ImpactOptions, compute_impact_radius_with};
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+use tempfile::TempDir;
+
+const SOURCE: &str = "pub async fn serve() {
+    let handle = tokio::spawn(async move {
+        process_job().await;
+    });
+    log_started();
+    handle.await.unwrap();
+}
+
+async fn process_job() {
+    store_result();
+}
+
+fn store_result() {}
+
+fn log_started() {}
+
+pub fn crunch() {
+    std::thread::scope(|s| {
+        s.spawn(|| store_result());
+    });
+}
+";
+
+fn build() -> (TempDir, RPGraph) {
+    let tmp = TempDir::new().unwrap();
+    let rel = Path::new("src/lib.rs");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join(rel), SOURCE).unwrap();
+    let mut graph = RPGraph::new("rust");
+    for raw in extract_entities(rel, SOURCE, Language::RUST) {
+        graph.insert_entity(raw.into_entity());
+    }
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), false, None, None);
+    rpg_encoder::grounding::resolve_dependencies(&mut graph);
+    graph.rebuild_edge_index();
+    (tmp, graph)
+}
+
+fn impact(graph: &RPGraph, stop_at_async_boundary: bool) -> Vec<(String, bool)> {
+    let result = compute_impact_radius_with(
+        graph,
+        "src/lib.rs:serve",
+        Direction::Downstream,
+        usize::MAX,
+        None,
+        &ImpactOptions {
+            stop_at_async_boundary,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let mut reached: Vec<(String, bool)> = result
+        .reachable
+        .iter()
+        .map(|e| (e.entity_id.clone(), e.async_boundary))
+        .collect();
+    reached.sort();
+    reached
+}
+
+#[test]
+fn test_spawned_calls_are_async_boundaries() {
+    let (_tmp, graph) = build();
+    assert_eq!(
+        graph.entities["src/lib.rs:serve"].deps.spawns,
+        vec!["process_job"]
+    );
+    assert_eq!(
+        graph.entities["src/lib.rs:crunch"].deps.spawns,
+        vec!["store_result"]
+    );
+    assert!(graph.is_async_boundary("src/lib.rs:serve", "src/lib.rs:process_job"));
+    assert!(graph.is_async_boundary("src/lib.rs:crunch", "src/lib.rs:store_result"));
+    assert!(!graph.is_async_boundary("src/lib.rs:serve", "src/lib.rs:log_started"));
+    assert!(!graph.is_async_boundary("src/lib.rs:process_job", "src/lib.rs:store_result"));
+}
+
+#[test]
+fn test_impact_highlights_or_stops_at_spawns() {
+    let (_tmp, graph) = build();
+    assert_eq!(
+        impact(&graph, false),
+        vec![
+            ("src/lib.rs:log_started".to_string(), false),
+            ("src/lib.rs:process_job".to_string(), true),
+            ("src/lib.rs:store_result".to_string(), false),
+        ]
+    );
+    // The spawned task is listed but not walked into.
+    assert_eq!(
+        impact(&graph, true),
+        vec![
+            ("src/lib.rs:log_started".to_string(), false),
+            ("src/lib.rs:process_job".to_string(), true),
+        ]
+    );
+
+    let result = compute_impact_radius_with(
+        &graph,
+        "src/lib.rs:serve",
+        Direction::Downstream,
+        1,
+        None,
+        &ImpactOptions::default(),
+    )
+    .unwrap();
+    let out = rpg_nav::toon::format_impact_radius(&result);
+    assert!(out.contains("src/lib.rs:serve:invokes[spawn]"), "{}", out);
+}
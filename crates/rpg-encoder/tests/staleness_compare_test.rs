@@ -0,0 +1,146 @@
+//! Integration test: `[staleness] compare_to` against a scripted git repository.
+//!
+//! `feature` branches off a base commit on `main`, and `main` moves on
+//! afterwards. The RPG was built on `main`'s tip, so diffing from its
+//! base_commit reports `main`'s own change as if the branch had made it;
+//! the merge base with `main` reports only the branch's work.
+
+use rpg_core::config::CompareTo;
+use rpg_core::graph::RPGraph;
+use rpg_encoder::evolution::{
+    FileChange, detect_changes_against, detect_stale_changes, resolve_compare_to,
+};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+/// Stage the whole worktree and commit it. `update_ref` of `None` leaves HEAD alone.
+fn commit_all(
+    repo: &git2::Repository,
+    message: &str,
+    update_ref: Option<&str>,
+    parents: &[&git2::Commit],
+) -> git2::Oid {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now("fixture", "fixture@example.com").unwrap();
+    repo.commit(update_ref, &sig, &sig, message, &tree, parents)
+        .unwrap()
+}
+
+/// Base commit on `main`; `main` then touches api.rs, while `feature` (HEAD)
+/// adds feature.rs and leaves an uncommitted edit in lib.rs.
+/// Returns (repo dir, base sha, main sha).
+fn scripted_repo() -> (TempDir, String, String) {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let repo = git2::Repository::init(root).unwrap();
+    repo.set_head("refs/heads/feature").unwrap();
+
+    write(root, "src/lib.rs", "pub mod api;\n");
+    write(root, "src/api.rs", "pub fn get() -> u32 {\n    1\n}\n");
+    let base = commit_all(&repo, "base", Some("HEAD"), &[]);
+    let base_commit = repo.find_commit(base).unwrap();
+    repo.branch("main", &base_commit, false).unwrap();
+
+    write(root, "src/api.rs", "pub fn get() -> u32 {\n    2\n}\n");
+    let main = commit_all(
+        &repo,
+        "main moves on",
+        Some("refs/heads/main"),
+        &[&base_commit],
+    );
+    write(root, "src/api.rs", "pub fn get() -> u32 {\n    1\n}\n");
+
+    write(
+        root,
+        "src/feature.rs",
+        "pub fn flag() -> bool {\n    true\n}\n",
+    );
+    commit_all(&repo, "add feature", Some("HEAD"), &[&base_commit]);
+    write(root, "src/lib.rs", "pub mod api;\npub mod feature;\n");
+
+    (tmp, base.to_string(), main.to_string())
+}
+
+fn graph_based_at(commit: &str) -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.base_commit = Some(commit.to_string());
+    graph
+}
+
+fn describe(changes: &[FileChange]) -> Vec<String> {
+    let mut out: Vec<String> = changes
+        .iter()
+        .map(|change| match change {
+            FileChange::Added(p) => format!("A {}", p.display()),
+            FileChange::Modified(p) => format!("M {}", p.display()),
+            FileChange::Deleted(p) => format!("D {}", p.display()),
+            FileChange::Renamed { from, to } => {
+                format!("R {} -> {}", from.display(), to.display())
+            }
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn test_base_commit_reports_mainline_changes_as_stale() {
+    let (tmp, _, main) = scripted_repo();
+    let graph = graph_based_at(&main);
+
+    let (point, changes) =
+        detect_stale_changes(tmp.path(), &graph, &CompareTo::BaseCommit).unwrap();
+    assert_eq!(point.commit, main);
+    assert_eq!(point.label, format!("base_commit {}", &main[..8]));
+    assert_eq!(
+        describe(&changes),
+        ["A src/feature.rs", "M src/api.rs", "M src/lib.rs"]
+    );
+}
+
+#[test]
+fn test_merge_base_reports_only_branch_work() {
+    let (tmp, base, main) = scripted_repo();
+    let graph = graph_based_at(&main);
+    let target: CompareTo = "merge-base:main".parse().unwrap();
+
+    let (point, changes) = detect_stale_changes(tmp.path(), &graph, &target).unwrap();
+    assert_eq!(point.commit, base);
+    assert_eq!(
+        point.label,
+        format!("merge-base with main ({})", &base[..8])
+    );
+    assert_eq!(describe(&changes), ["A src/feature.rs", "M src/lib.rs"]);
+}
+
+#[test]
+fn test_explicit_rev_and_unknown_rev() {
+    let (tmp, _, main) = scripted_repo();
+    let graph = RPGraph::new("rust");
+
+    let point = resolve_compare_to(tmp.path(), &graph, &CompareTo::Rev("main".into())).unwrap();
+    assert_eq!(point.commit, main);
+    assert_eq!(point.label, format!("main ({})", &main[..8]));
+    assert_eq!(
+        describe(&detect_changes_against(tmp.path(), "main").unwrap()),
+        ["A src/feature.rs", "M src/api.rs", "M src/lib.rs"]
+    );
+
+    let err = resolve_compare_to(tmp.path(), &graph, &CompareTo::Rev("nope".into()))
+        .unwrap_err()
+        .to_string();
+    assert_eq!(err, "revision 'nope' not found");
+    // No base_commit to fall back on.
+    assert!(resolve_compare_to(tmp.path(), &graph, &CompareTo::BaseCommit).is_err());
+}
@@ -0,0 +1,115 @@
+//! Integration test: test entities get Tests edges to the code they invoke,
+//! which impact analysis and search can use.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+use rpg_nav::explore::Direction;
+use rpg_nav::impact::compute_impact_radius;
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use tempfile::TempDir;
+
+fn project() -> (TempDir, RPGraph) {
+    common::build(
+        "python",
+        &[
+            (
+                "app/parser.py",
+                "def parse(text):\n    return tokenize(text)\n\n\ndef tokenize(text):\n    return text.split()\n",
+            ),
+            (
+                "tests/test_parser.py",
+                "from app.parser import parse\n\n\n\
+             def make_input():\n    return 'a b'\n\n\n\
+             def test_parse():\n    assert parse(make_input())\n\n\n\
+             def test_parse_twice():\n    test_parse()\n",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_tests_edges_link_tests_to_covered_code() {
+    let (_tmp, graph) = project();
+    let mut tests: Vec<(&str, &str)> = graph
+        .edges
+        .iter()
+        .filter(|e| e.kind == EdgeKind::Tests)
+        .map(|e| (e.source.as_str(), e.target.as_str()))
+        .collect();
+    tests.sort_unstable();
+    // Test-to-test calls are not coverage; helpers in test files are.
+    assert_eq!(
+        tests,
+        vec![
+            ("tests/test_parser.py:test_parse", "app/parser.py:parse"),
+            (
+                "tests/test_parser.py:test_parse",
+                "tests/test_parser.py:make_input"
+            ),
+        ]
+    );
+    assert_eq!(
+        graph.entities["app/parser.py:parse"].deps.tested_by,
+        vec!["tests/test_parser.py:test_parse"]
+    );
+
+    let impact = compute_impact_radius(
+        &graph,
+        "app/parser.py:parse",
+        Direction::Upstream,
+        3,
+        Some(EdgeKind::Tests),
+        None,
+    )
+    .unwrap();
+    let covering: Vec<&str> = impact
+        .reachable
+        .iter()
+        .map(|e| e.entity_id.as_str())
+        .collect();
+    assert_eq!(covering, vec!["tests/test_parser.py:test_parse"]);
+}
+
+#[test]
+fn test_search_can_exclude_tests() {
+    let (_tmp, graph) = project();
+    let search = |exclude_tests: bool| {
+        let mut ids: Vec<String> = search_with_params(
+            &graph,
+            &SearchParams {
+                query: "parse",
+                mode: SearchMode::Snippets,
+                scope: None,
+                limit: 10,
+                line_nums: None,
+                file_pattern: None,
+                entity_type_filter: None,
+                layer_filter: None,
+                embedding_scores: None,
+                diff_context: None,
+                changed_entities: None,
+                vocabulary: None,
+                sort: SearchSort::Relevance,
+                include_fixtures: false,
+                exclude_tests,
+                async_only: false,
+                deadline: None,
+                group_by_file: false,
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .map(|r| r.entity_id)
+        .collect();
+        ids.sort();
+        ids
+    };
+    let test_parse = "tests/test_parser.py:test_parse".to_string();
+    assert!(search(false).contains(&test_parse));
+    let without_tests = search(true);
+    assert!(without_tests.contains(&"app/parser.py:parse".to_string()));
+    assert!(!without_tests.contains(&test_parse));
+    // Helpers in test files are not tests themselves.
+    assert!(without_tests.contains(&"tests/test_parser.py:make_input".to_string()));
+}
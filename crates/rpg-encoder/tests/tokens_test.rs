@@ -0,0 +1,142 @@
+//! Token estimators: how they size code, and the batches they cut.
+
+use rpg_core::config::{EstimatorKind, TokensConfig};
+use rpg_core::graph::RPGraph;
+use rpg_encoder::lift::build_token_aware_batches;
+use rpg_encoder::lift_queue::{LiftQueue, format_batch_header};
+use rpg_encoder::tokens::{CharsPerToken, Heuristic, TokenEstimator, from_config};
+use rpg_parser::entities::{RawEntity, extract_entities};
+use rpg_parser::languages::Language;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Symbol-dense Rust: short identifiers and punctuation, where real
+/// tokenizers spend far fewer than four characters per token.
+fn code_sample() -> String {
+    let mut code = String::new();
+    for i in 0..12 {
+        code.push_str(&format!(
+            "pub fn f{i}(a: &[u8], b: &mut Vec<u8>) -> Result<(), E> {{\n    \
+             for (i, x) in a.iter().enumerate() {{ b[i] = x.wrapping_add({i}) ^ 0x{i}f; }}\n    \
+             Ok(())\n}}\n\n"
+        ));
+    }
+    code
+}
+
+fn sample_entities() -> Vec<RawEntity> {
+    extract_entities(Path::new("src/codec.rs"), &code_sample(), Language::RUST)
+}
+
+fn ratio(ratio: f64) -> CharsPerToken {
+    CharsPerToken {
+        ratio,
+        provider: None,
+    }
+}
+
+#[test]
+fn test_estimates_on_code_sample() {
+    let code = code_sample();
+    let heuristic = Heuristic.estimate(&code);
+    let dense = ratio(2.5).estimate(&code);
+    assert_eq!(heuristic, code.len() / 4);
+    // chars / 2.5, rounded up.
+    assert!(5 * dense >= 2 * code.len() && 5 * (dense - 1) < 2 * code.len());
+    assert!(dense > heuristic, "{} vs {}", dense, heuristic);
+}
+
+#[test]
+fn test_denser_estimate_cuts_more_batches() {
+    let entities = sample_entities();
+    assert_eq!(entities.len(), 12);
+
+    let heuristic = build_token_aware_batches(&entities, 25, 300, &Heuristic);
+    let dense = build_token_aware_batches(&entities, 25, 300, &ratio(2.5));
+    assert!(
+        dense.len() > heuristic.len(),
+        "{:?} vs {:?}",
+        dense,
+        heuristic
+    );
+    // Both cover every entity, in order.
+    for batches in [&heuristic, &dense] {
+        assert_eq!(batches.first().map(|b| b.0), Some(0));
+        assert_eq!(batches.last().map(|b| b.1), Some(entities.len()));
+        assert!(batches.windows(2).all(|w| w[0].1 == w[1].0));
+    }
+}
+
+#[test]
+fn test_config_selects_estimator_per_provider() {
+    let root = Path::new(".");
+    let config = TokensConfig {
+        estimator: EstimatorKind::Ratio,
+        chars_per_token: 3.5,
+        provider_chars_per_token: BTreeMap::from([("anthropic".to_string(), 2.8)]),
+        ..TokensConfig::default()
+    };
+    assert_eq!(
+        from_config(&config, Some("anthropic"), root).describe(),
+        "2.8 chars/token (anthropic)"
+    );
+    assert_eq!(
+        from_config(&config, Some("openai"), root).describe(),
+        "3.5 chars/token"
+    );
+    assert_eq!(
+        from_config(&TokensConfig::default(), None, root).describe(),
+        "chars/4 heuristic"
+    );
+
+    // An unloadable tokenizer falls back to the heuristic.
+    let bpe = TokensConfig {
+        estimator: EstimatorKind::Bpe,
+        bpe_tokenizer: Some("missing/tokenizer.json".into()),
+        ..TokensConfig::default()
+    };
+    assert_eq!(
+        from_config(&bpe, None, root).describe(),
+        "chars/4 heuristic"
+    );
+}
+
+#[test]
+fn test_batch_header_reports_estimator() {
+    let queue = LiftQueue {
+        batch_ranges: vec![(0, 3), (3, 5)],
+        estimator: ratio(3.0).describe(),
+        ..LiftQueue::default()
+    };
+    let header = format_batch_header(&RPGraph::new("rust"), &queue, 1, 2);
+    assert!(header.starts_with("BATCH 2/2 (2 entities)"), "{}", header);
+    assert!(header.ends_with("| tokens: 3 chars/token\n"), "{}", header);
+}
+
+#[cfg(feature = "bpe")]
+#[test]
+fn test_bpe_counts_with_tokenizer_file() {
+    // Characters plus one merge, split on whitespace and punctuation.
+    let tokenizer = r#"{
+        "version": "1.0", "truncation": null, "padding": null, "added_tokens": [],
+        "normalizer": null, "pre_tokenizer": {"type": "Whitespace"},
+        "post_processor": null, "decoder": null,
+        "model": {"type": "BPE", "dropout": null, "unk_token": null,
+            "continuing_subword_prefix": null, "end_of_word_suffix": null,
+            "fuse_unk": false, "byte_fallback": false, "ignore_merges": false,
+            "vocab": {"f": 0, "n": 1, "fn": 2, "(": 3, ")": 4},
+            "merges": ["f n"]}
+    }"#;
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("tokenizer.json"), tokenizer).unwrap();
+    let config = TokensConfig {
+        estimator: EstimatorKind::Bpe,
+        bpe_tokenizer: Some("tokenizer.json".into()),
+        ..TokensConfig::default()
+    };
+
+    let bpe = from_config(&config, None, tmp.path());
+    assert_eq!(bpe.describe(), "bpe (tokenizer.json)");
+    // `fn` merges into one token; `()` is one pre-token of two characters.
+    assert_eq!(bpe.estimate("fn ()"), 3);
+}
@@ -0,0 +1,94 @@
+//! Integration test: TS/JS imports through tsconfig `paths` aliases resolve
+//! to the files they map to, including aliases inherited through `extends`.
+
+mod common;
+
+use rpg_core::graph::{EdgeKind, RPGraph};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn targets(graph: &RPGraph, source: &str, kind: EdgeKind) -> Vec<String> {
+    let mut targets: Vec<String> = graph
+        .edges
+        .iter()
+        .filter(|e| e.source == source && e.kind == kind)
+        .map(|e| e.target.clone())
+        .collect();
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+fn monorepo() -> (TempDir, RPGraph) {
+    common::build(
+        "typescript",
+        &[
+            (
+                "tsconfig.base.json",
+                r#"{
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": {
+                        "@app/*": ["src/*"],
+                        "@shared/*": ["packages/shared/src/*"],
+                    },
+                },
+            }"#,
+            ),
+            (
+                "apps/web/tsconfig.json",
+                "{\n  // Inherits the workspace aliases\n  \"extends\": \"../../tsconfig.base\",\n  \"compilerOptions\": { \"strict\": true }\n}\n",
+            ),
+            (
+                "apps/web/src/main.ts",
+                "import { login } from '@app/services/auth';\n\
+             import { fmt } from '@shared/format';\n\
+             import { gone } from '@app/services/missing';\n\
+             export function start() { login(); fmt(); }\n",
+            ),
+            ("src/services/auth.ts", "export function login() {}\n"),
+            (
+                "packages/shared/src/format/index.ts",
+                "export function fmt() {}\n",
+            ),
+            // Same names elsewhere: only the alias target may match.
+            ("other/services/auth.ts", "export function login() {}\n"),
+            ("other/services/missing.ts", "export function gone() {}\n"),
+        ],
+    )
+}
+
+#[test]
+fn test_aliased_imports_land_on_module_entities() {
+    let (_tmp, graph) = monorepo();
+    assert_eq!(
+        targets(&graph, "apps/web/src/main.ts:main", EdgeKind::Imports),
+        vec![
+            "packages/shared/src/format/index.ts:index".to_string(),
+            "src/services/auth.ts:auth".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_aliased_import_narrows_same_named_callees() {
+    let (_tmp, graph) = monorepo();
+    assert!(
+        targets(&graph, "apps/web/src/main.ts:start", EdgeKind::Invokes)
+            .contains(&"src/services/auth.ts:login".to_string())
+    );
+}
+
+#[test]
+fn test_broken_alias_resolves_to_nothing() {
+    let (_tmp, graph) = monorepo();
+    let imports = graph.file_imports[Path::new("apps/web/src/main.ts")]
+        .iter()
+        .find(|import| import.module == "@app/services/missing")
+        .unwrap();
+    assert_eq!(imports.file, None);
+    assert!(
+        !targets(&graph, "apps/web/src/main.ts:main", EdgeKind::Imports)
+            .contains(&"other/services/missing.ts:missing".to_string())
+    );
+}
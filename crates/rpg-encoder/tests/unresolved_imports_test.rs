@@ -0,0 +1,144 @@
+//! Integration test: grounding records imports that matched no in-repo
+//! entity, grouped by prefix and classified against the project's manifests.
+
+use rpg_core::graph::{ImportOrigin, RPGraph, UnresolvedImportPrefix, UnresolvedImports};
+use std::path::{Path, PathBuf};
+
+/// Write `files` under a temp root, parse the source files, and ground the graph.
+fn ground(files: &[(&str, &str)]) -> (tempfile::TempDir, RPGraph) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut sources = Vec::new();
+    for (rel, content) in files {
+        let path = tmp.path().join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        let is_source = Path::new(rel)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(rpg_parser::languages::Language::from_extension)
+            .is_some();
+        if is_source {
+            sources.push((PathBuf::from(rel), content.to_string()));
+        }
+    }
+
+    let mut graph = RPGraph::new("typescript");
+    for raw in rpg_parser::parse_files_parallel(sources) {
+        graph.insert_entity(raw.into_entity());
+    }
+    graph.create_module_entities();
+    rpg_encoder::grounding::populate_entity_deps(&mut graph, tmp.path(), true, None, None);
+    (tmp, graph)
+}
+
+fn group<'a>(report: &'a UnresolvedImports, prefix: &str) -> &'a UnresolvedImportPrefix {
+    report
+        .prefixes
+        .iter()
+        .find(|g| g.prefix == prefix)
+        .unwrap_or_else(|| panic!("no group {prefix}: {:?}", report.prefixes))
+}
+
+#[test]
+fn test_unmappable_alias_is_reported_as_in_repo() {
+    let (_tmp, graph) = ground(&[
+        (
+            "package.json",
+            r#"{"name": "web", "dependencies": {"react": "^18.0.0", "@tanstack/react-query": "^5.0.0"}}"#,
+        ),
+        (
+            "tsconfig.json",
+            "{\n  // path aliases\n  \"compilerOptions\": {\"paths\": {\"@app/*\": [\"src/*\"]}}\n}",
+        ),
+        (
+            "src/app.ts",
+            "import { useState } from 'react';\n\
+             import { useQuery } from '@tanstack/react-query';\n\
+             import { formatDate } from '@app/utils/dates';\n\
+             import { parseDate } from '@app/utils/parse';\n\
+             import { helper } from './helper';\n\
+             export function render() { return helper(formatDate(parseDate('x'))); }\n",
+        ),
+        (
+            "src/view.ts",
+            "import { useEffect } from 'react';\nexport function view() { useEffect(); }\n",
+        ),
+        ("src/helper.ts", "export function helper(x) { return x; }\n"),
+    ]);
+
+    let report = graph.metadata.unresolved_imports.as_ref().unwrap();
+    assert_eq!(report.imports, 6);
+    // Only `./helper` names an in-repo entity.
+    assert_eq!(report.unresolved, 5);
+
+    let react = group(report, "react");
+    assert_eq!((react.count, react.files), (2, 2));
+    assert_eq!(react.origin, ImportOrigin::ThirdParty);
+    assert_eq!(
+        group(report, "@tanstack/react-query").origin,
+        ImportOrigin::ThirdParty
+    );
+
+    // The tsconfig alias matches no file the resolver knows about.
+    let alias = group(report, "@app/utils");
+    assert_eq!((alias.count, alias.files), (2, 1));
+    assert_eq!(alias.origin, ImportOrigin::InRepo);
+    assert!(alias.example.starts_with("@app/utils/"));
+    assert!(report.prefixes.iter().all(|g| g.prefix != "./helper"));
+
+    let text = rpg_encoder::unresolved_imports::format_report(report, 10);
+    assert!(text.contains("5 of 6 import statements"), "{}", text);
+    assert!(text.contains("in-repo-unmapped"), "{}", text);
+    assert!(
+        text.contains("2 unresolved import(s) look like in-repo code"),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn test_python_imports_classified_by_manifest_and_layout() {
+    let (_tmp, graph) = ground(&[
+        (
+            "requirements.txt",
+            "requests>=2.0\n# comment\nPyYAML==6.0\n",
+        ),
+        (
+            "app/service.py",
+            "import os\n\
+             import requests\n\
+             from app.models import Missing\n\
+             from .models import User\n\
+             def fetch():\n    return requests.get(os.environ['URL'])\n",
+        ),
+        ("app/models.py", "class User:\n    pass\n"),
+    ]);
+
+    let report = graph.metadata.unresolved_imports.as_ref().unwrap();
+    assert_eq!(report.imports, 4);
+    assert_eq!(report.unresolved, 3);
+    assert_eq!(group(report, "requests").origin, ImportOrigin::ThirdParty);
+    assert_eq!(group(report, "os").origin, ImportOrigin::Unknown);
+    // `app` is a directory of indexed files, so this is an unmapped local import.
+    assert_eq!(group(report, "app").origin, ImportOrigin::InRepo);
+}
+
+#[test]
+fn test_incremental_population_keeps_last_full_report() {
+    let (tmp, mut graph) = ground(&[(
+        "src/app.ts",
+        "import { missing } from './gone';\nexport function run() { missing(); }\n",
+    )]);
+    let before = graph.metadata.unresolved_imports.clone();
+    assert_eq!(before.as_ref().unwrap().unresolved, 1);
+
+    let changed = [PathBuf::from("src/app.ts")];
+    rpg_encoder::grounding::populate_entity_deps(
+        &mut graph,
+        tmp.path(),
+        true,
+        Some(&changed),
+        None,
+    );
+    assert_eq!(graph.metadata.unresolved_imports, before);
+}
@@ -2,6 +2,7 @@
 
 use crate::provider::LlmProvider;
 use rpg_core::graph::RPGraph;
+use rpg_encoder::tokens::TokenEstimator;
 
 /// Pre-computed cost estimate for a lifting run.
 #[derive(Debug, Clone)]
@@ -20,6 +21,8 @@ pub struct CostEstimate {
     pub estimated_cost_usd: f64,
     /// Model name.
     pub model: String,
+    /// Token estimator behind the token counts.
+    pub estimator: String,
 }
 
 impl std::fmt::Display for CostEstimate {
@@ -37,8 +40,8 @@ impl std::fmt::Display for CostEstimate {
         )?;
         writeln!(
             f,
-            "  Estimated tokens: ~{} input, ~{} output",
-            self.estimated_input_tokens, self.estimated_output_tokens
+            "  Estimated tokens: ~{} input, ~{} output ({})",
+            self.estimated_input_tokens, self.estimated_output_tokens, self.estimator
         )?;
         writeln!(f, "  Model: {}", self.model)?;
         write!(f, "  Estimated cost: ${:.4}", self.estimated_cost_usd)
@@ -84,19 +87,20 @@ impl CostTracker {
 /// Estimate lifting cost without making API calls.
 ///
 /// Scans the graph to count entities needing LLM lifting (excluding auto-liftable),
-/// estimates token counts using the 4-chars-per-token heuristic, and computes cost.
+/// estimates token counts with `estimator`, and computes cost.
 pub fn estimate_cost(
     graph: &RPGraph,
     provider: &dyn LlmProvider,
     project_root: &std::path::Path,
+    estimator: &dyn TokenEstimator,
 ) -> CostEstimate {
-    let scope = rpg_encoder::lift::resolve_scope(graph, "*");
+    let scope = rpg_encoder::lift::resolve_scope(graph, "*").expect("`*` is a valid scope");
 
     // Try auto-lift on raw entities to estimate how many need LLM
     let raw_entities =
         rpg_encoder::lift::collect_raw_entities(graph, &scope, project_root).unwrap_or_default();
 
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(project_root);
     let active_paradigms: Vec<String> = graph.metadata.paradigms.clone();
     let engine = rpg_encoder::lift::AutoLiftEngine::new(&paradigm_defs, &active_paradigms);
 
@@ -119,7 +123,7 @@ pub fn estimate_cost(
     }
 
     // Estimate tokens for entity lifting batches
-    let batches = rpg_encoder::lift::build_token_aware_batches(&raw_entities, 25, 8000);
+    let batches = rpg_encoder::lift::build_token_aware_batches(&raw_entities, 25, 8000, estimator);
     let llm_batches = if raw_entities.is_empty() {
         0
     } else {
@@ -138,7 +142,7 @@ pub fn estimate_cost(
     } else {
         llm_needed
             .iter()
-            .map(|r| (r.source_text.len() as u64) / 4)
+            .map(|r| estimator.estimate(&r.source_text) as u64)
             .sum::<u64>()
             / llm_needed.len() as u64
     };
@@ -166,5 +170,6 @@ pub fn estimate_cost(
         estimated_output_tokens: total_output,
         estimated_cost_usd: cost,
         model: provider.model_name().to_string(),
+        estimator: estimator.describe(),
     }
 }
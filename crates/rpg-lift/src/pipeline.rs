@@ -9,14 +9,16 @@ use crate::progress::LiftProgress;
 use crate::provider::{LlmProvider, ProviderError};
 use rpg_core::graph::RPGraph;
 use rpg_encoder::lift::{
-    AutoLiftEngine, LiftConfidence, build_token_aware_batches, collect_raw_entities, resolve_scope,
+    AutoLiftEngine, LIFT_SOURCE_LINES, LiftConfidence, build_token_aware_batches,
+    collect_raw_entities, condense_source, entity_names, format_doc_block, resolve_scope,
 };
 use rpg_encoder::semantic_lifting::{
     DOMAIN_DISCOVERY_PROMPT, FILE_SYNTHESIS_SYSTEM, HIERARCHY_CONSTRUCTION_PROMPT,
     SEMANTIC_PARSING_SYSTEM, aggregate_module_features, parse_line_features,
 };
+use rpg_encoder::tokens::TokenEstimator;
 use rpg_parser::entities::RawEntity;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
 /// Configuration for an autonomous lifting run.
@@ -27,6 +29,8 @@ pub struct LiftConfig<'a> {
     pub max_retries: usize,
     pub batch_size: usize,
     pub batch_tokens: usize,
+    /// Sizes entities against `batch_tokens`.
+    pub token_estimator: &'a dyn TokenEstimator,
 }
 
 /// Result of a completed lifting run.
@@ -54,7 +58,8 @@ pub fn run_pipeline(
     let mut errors: Vec<String> = Vec::new();
 
     // Phase 1: Resolve scope and collect raw entities
-    let scope = resolve_scope(graph, config.scope);
+    let scope =
+        resolve_scope(graph, config.scope).map_err(|e| PipelineError::Setup(e.to_string()))?;
     if scope.entity_ids.is_empty() {
         progress.finish();
         return Ok(LiftReport {
@@ -97,22 +102,28 @@ pub fn run_pipeline(
     );
 
     // Phase 2: Auto-lift trivial entities
-    let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+    let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(config.project_root);
     let active_paradigms: Vec<String> = graph.metadata.paradigms.clone();
     let engine = AutoLiftEngine::new(&paradigm_defs, &active_paradigms);
 
     progress.start_phase("Auto-lift", raw_entities.len() as u64);
 
     let mut auto_lifted = 0usize;
+    let mut boilerplate: BTreeMap<String, usize> = BTreeMap::new();
     let mut needs_llm: Vec<&RawEntity> = Vec::new();
 
     for raw in &raw_entities {
-        match engine.try_lift_with_confidence(raw) {
+        let lift = engine.try_lift_detailed(raw);
+        if let Some(paradigm) = lift.as_ref().and_then(|l| l.boilerplate.clone()) {
+            *boilerplate.entry(paradigm).or_default() += 1;
+        }
+        match lift.map(|l| (l.features, l.confidence)) {
             Some((features, LiftConfidence::Accept)) => {
                 let entity_id = raw.id();
                 if let Some(entity) = graph.entities.get_mut(&entity_id) {
                     entity.semantic_features = features;
                     entity.feature_source = Some("auto".to_string());
+                    entity.lifted_at = Some(chrono::Utc::now());
                 }
                 auto_lifted += 1;
             }
@@ -122,6 +133,7 @@ pub fn run_pipeline(
                 if let Some(entity) = graph.entities.get_mut(&entity_id) {
                     entity.semantic_features = features;
                     entity.feature_source = Some("auto-review".to_string());
+                    entity.lifted_at = Some(chrono::Utc::now());
                 }
                 auto_lifted += 1;
                 // Don't add to needs_llm — accept the auto-lift for autonomous mode
@@ -139,6 +151,12 @@ pub fn run_pipeline(
             auto_lifted,
             needs_llm.len()
         );
+        if !boilerplate.is_empty() {
+            eprintln!(
+                "  Boilerplate: {}",
+                rpg_encoder::lift_queue::format_boilerplate_counts(&boilerplate)
+            );
+        }
     });
 
     // Phase 3: LLM entity lifting
@@ -149,16 +167,25 @@ pub fn run_pipeline(
     if !needs_llm.is_empty() {
         // Build owned copies for batching
         let llm_raws: Vec<RawEntity> = needs_llm.iter().map(|r| (*r).clone()).collect();
-        let batches = build_token_aware_batches(&llm_raws, config.batch_size, config.batch_tokens);
+        let batches = build_token_aware_batches(
+            &llm_raws,
+            config.batch_size,
+            config.batch_tokens,
+            config.token_estimator,
+        );
 
         progress.start_phase("LLM Lift", batches.len() as u64);
 
         let repo_info =
             rpg_encoder::lift::generate_repo_info(graph, &project_name(config.project_root));
+        // Owned copy: the graph is updated while the batches run
+        let known_names: HashSet<String> =
+            entity_names(graph).into_iter().map(String::from).collect();
+        let known_names: HashSet<&str> = known_names.iter().map(String::as_str).collect();
 
         for (batch_idx, &(start, end)) in batches.iter().enumerate() {
             let batch = &llm_raws[start..end];
-            let user_prompt = format_entity_batch(batch, batch_idx == 0, &repo_info);
+            let user_prompt = format_entity_batch(batch, batch_idx == 0, &repo_info, &known_names);
 
             match call_with_retry(
                 config.provider,
@@ -188,6 +215,7 @@ pub fn run_pipeline(
                         {
                             entity.semantic_features = feats.clone();
                             entity.feature_source = Some("llm".to_string());
+                            entity.lifted_at = Some(chrono::Utc::now());
                             batch_applied += 1;
                         }
                     }
@@ -277,7 +305,9 @@ fn run_file_synthesis(
         .entities
         .iter()
         .filter(|(_, e)| {
-            e.kind == rpg_core::graph::EntityKind::Module && !e.semantic_features.is_empty()
+            e.kind == rpg_core::graph::EntityKind::Module
+                && !e.semantic_features.is_empty()
+                && !e.is_fixture()
         })
         .map(|(id, e)| (id.clone(), e.semantic_features.clone()))
         .collect();
@@ -371,7 +401,9 @@ fn run_hierarchy_construction(
     progress.start_phase("Discovery", 1);
 
     let file_features = collect_file_features(graph);
-    let discovery_prompt = format_discovery_prompt(&file_features);
+    let charter_guidance = rpg_encoder::hierarchy::format_charter_guidance(graph);
+    let mut discovery_prompt = format_discovery_prompt(&file_features);
+    discovery_prompt.push_str(&charter_guidance);
 
     let areas = match call_with_retry(
         config.provider,
@@ -420,7 +452,8 @@ fn run_hierarchy_construction(
     let mut all_assignments: HashMap<String, String> = HashMap::new();
 
     for (cluster_idx, cluster) in clusters.iter().enumerate() {
-        let user_prompt = format_hierarchy_prompt(&cluster.files, &areas, &file_features);
+        let mut user_prompt = format_hierarchy_prompt(&cluster.files, &areas, &file_features);
+        user_prompt.push_str(&charter_guidance);
 
         match call_with_retry(
             config.provider,
@@ -488,7 +521,12 @@ fn run_hierarchy_construction(
 // ---------------------------------------------------------------------------
 
 /// Format a batch of raw entities for the LLM entity-lifting prompt.
-fn format_entity_batch(batch: &[RawEntity], is_first: bool, repo_info: &str) -> String {
+fn format_entity_batch(
+    batch: &[RawEntity],
+    is_first: bool,
+    repo_info: &str,
+    known_names: &HashSet<&str>,
+) -> String {
     let mut prompt = String::new();
 
     if is_first {
@@ -498,21 +536,23 @@ fn format_entity_batch(batch: &[RawEntity], is_first: bool, repo_info: &str) ->
 
     for raw in batch {
         let kind = format!("{:?}", raw.kind);
-        prompt.push_str(&format!("### {} ({})\n", raw.id(), kind));
+        prompt.push_str(&format!("### {} ({})", raw.id(), kind));
+        let decorators = raw.decorator_summary();
+        if !decorators.is_empty() {
+            prompt.push_str(&format!(" {}", decorators));
+        }
+        let attributes = raw.attribute_summary();
+        if !attributes.is_empty() {
+            prompt.push_str(&format!(" [{}]", attributes));
+        }
+        prompt.push('\n');
         if let Some(parent) = &raw.parent_class {
             prompt.push_str(&format!("Parent: {}\n", parent));
         }
 
-        // Truncate source to 40 lines
-        let lines: Vec<&str> = raw.source_text.lines().collect();
-        let truncated = lines.len() > 40;
-        let source = if truncated {
-            let mut s: String = lines[..40].join("\n");
-            s.push_str("\n// ... truncated ...");
-            s
-        } else {
-            raw.source_text.clone()
-        };
+        prompt.push_str(&format_doc_block(raw.doc_comment.as_deref()));
+
+        let source = condense_source(&raw.source_text, LIFT_SOURCE_LINES, known_names);
 
         prompt.push_str("```\n");
         prompt.push_str(&source);
@@ -543,7 +583,9 @@ fn collect_file_features(graph: &RPGraph) -> HashMap<String, Vec<String>> {
         .entities
         .iter()
         .filter(|(_, e)| {
-            e.kind == rpg_core::graph::EntityKind::Module && !e.semantic_features.is_empty()
+            e.kind == rpg_core::graph::EntityKind::Module
+                && !e.semantic_features.is_empty()
+                && !e.is_fixture()
         })
         .map(|(_, e)| {
             let path = rpg_core::graph::normalize_path(&e.file);
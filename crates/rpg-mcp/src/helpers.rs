@@ -2,38 +2,26 @@
 
 use std::collections::BTreeMap;
 
-/// Truncate source code to `max_lines`, preserving the signature and start of the body.
-/// Appends a `(truncated)` note if the source exceeds the limit.
-pub(crate) fn truncate_source(source: &str, max_lines: usize) -> String {
-    let lines: Vec<&str> = source.lines().collect();
-    if lines.len() <= max_lines {
-        return source.to_string();
-    }
-    let mut out: String = lines[..max_lines].join("\n");
-    out.push_str(&format!(
-        "\n    // ... ({} more lines, truncated for context)",
-        lines.len() - max_lines
-    ));
-    out
-}
-
-/// Format a compact dependency context line for an entity.
-/// Returns empty string if the entity has no deps.
-/// Format: `Deps: Called by: a, b | Calls: c, d | Inherits: e`
-/// Delegates to the shared `format_deps_summary` in rpg-nav's context module.
-pub(crate) fn format_dep_context(deps: &rpg_core::graph::EntityDeps) -> String {
-    let summary = rpg_nav::context::format_deps_summary(deps);
-    if summary.is_empty() {
-        String::new()
-    } else {
-        format!("Deps: {}", summary)
+/// Compile glob patterns into one set that matches if any pattern does.
+/// `None` when there are no patterns.
+pub(crate) fn build_glob_set(patterns: &[String]) -> Result<Option<globset::GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(rpg_nav::scope::compile_glob(pattern)?);
     }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid glob patterns: {}", e))
 }
 
 /// Parse a comma-separated entity type filter string into EntityKind values.
 ///
 /// Accepts entity names: function, class, method, page, layout, component,
-/// hook, store, file, module, directory.
+/// hook, store, enum, constant, fixture, file, module, directory.
 /// "file" is an alias for Module (file-level entity nodes, V_L).
 /// "directory" is mapped to Module for paper-schema compatibility.
 pub(crate) fn parse_entity_type_filter(filter: &str) -> Vec<rpg_core::graph::EntityKind> {
@@ -55,11 +43,218 @@ pub(crate) fn parse_entity_type_filter(filter: &str) -> Vec<rpg_core::graph::Ent
             "middleware" => Some(rpg_core::graph::EntityKind::Middleware),
             "route" => Some(rpg_core::graph::EntityKind::Route),
             "test" => Some(rpg_core::graph::EntityKind::Test),
+            "enum" => Some(rpg_core::graph::EntityKind::Enum),
+            "constant" => Some(rpg_core::graph::EntityKind::Constant),
+            "fixture" => Some(rpg_core::graph::EntityKind::Fixture),
             _ => None,
         })
         .collect()
 }
 
+/// Whether an entity type filter includes the `async` pseudo-type, which
+/// keeps only async functions and combines with the real types.
+pub(crate) fn entity_type_filter_wants_async(filter: &str) -> bool {
+    filter
+        .split(',')
+        .any(|s| s.trim().eq_ignore_ascii_case("async"))
+}
+
+/// Parse a comma-separated layer filter string into layers. Unknown names are skipped.
+pub(crate) fn parse_layer_filter(filter: &str) -> Vec<rpg_core::graph::Layer> {
+    filter
+        .split(',')
+        .filter_map(rpg_core::graph::Layer::parse)
+        .collect()
+}
+
+/// Parse a comma-separated tag filter string. Invalid tags are skipped.
+pub(crate) fn parse_tag_list(filter: &str) -> Vec<String> {
+    filter
+        .split(',')
+        .filter_map(rpg_core::graph::normalize_tag)
+        .collect()
+}
+
+/// Normalize tags for `tag_entities`/`untag_entities`, rejecting invalid ones.
+pub(crate) fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    if tags.is_empty() {
+        return Err("Provide at least one tag.".into());
+    }
+    tags.iter()
+        .map(|t| {
+            rpg_core::graph::normalize_tag(t).ok_or_else(|| {
+                format!(
+                    "Invalid tag '{}': tags must be non-empty and free of whitespace, quotes, parentheses, and commas.",
+                    t
+                )
+            })
+        })
+        .collect()
+}
+
+/// Tag counts as `deprecated (3), hot-path (1)`, or `(none)`.
+pub(crate) fn format_tag_counts(graph: &rpg_core::graph::RPGraph) -> String {
+    let counts = graph.tag_counts();
+    if counts.is_empty() {
+        return "(none)".to_string();
+    }
+    counts
+        .iter()
+        .map(|(tag, n)| format!("{} ({})", tag, n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How many area charters a hierarchy resubmission kept, and which paths
+/// no longer exist. Empty when there were no charters.
+pub(crate) fn format_charter_carryover(
+    before: &BTreeMap<String, String>,
+    graph: &rpg_core::graph::RPGraph,
+) -> String {
+    if before.is_empty() {
+        return String::new();
+    }
+    let after = graph.hierarchy_charters();
+    let dropped: Vec<&str> = before
+        .keys()
+        .filter(|path| !after.contains_key(*path))
+        .map(String::as_str)
+        .collect();
+    let mut out = format!(
+        "charters_kept: {} of {}\n",
+        before.len() - dropped.len(),
+        before.len()
+    );
+    if !dropped.is_empty() {
+        out.push_str(&format!(
+            "charters_dropped: {} (no node at that path; re-attach with set_area_charter)\n",
+            dropped.join(", ")
+        ));
+    }
+    out
+}
+
+/// Render one reconstruction batch: each entity's features, signature, and
+/// prerequisites split into reconstructed and pending. Prerequisites outside
+/// the plan are left out.
+pub(crate) fn format_reconstruction_batch(
+    graph: &rpg_core::graph::RPGraph,
+    state: &rpg_encoder::reconstruction::ReconstructionState,
+    batch: &rpg_encoder::reconstruction::ReconstructionBatch,
+) -> String {
+    let planned: std::collections::HashSet<&str> = state
+        .plan
+        .topological_order
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let list = |ids: &[&String]| {
+        if ids.is_empty() {
+            "(none)".to_string()
+        } else {
+            ids.iter()
+                .map(|id| id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    let mut out = format!(
+        "RECONSTRUCTION BATCH {}/{} (area: {}, {} entities) | progress: {}/{} done\n",
+        batch.batch_index,
+        state.plan.batches.len(),
+        batch.area,
+        batch.entity_ids.len(),
+        state.done.len(),
+        state.plan.topological_order.len(),
+    );
+    for id in &batch.entity_ids {
+        let status = if state.done.contains(id) {
+            "done"
+        } else {
+            "pending"
+        };
+        out.push_str(&format!("\n### {} [{}]\n", id, status));
+        let Some(entity) = graph.entities.get(id) else {
+            out.push_str("(no longer in the graph)\n");
+            continue;
+        };
+        out.push_str(&format!(
+            "kind: {:?} | file: {}:{}-{}\n",
+            entity.kind,
+            entity.file.display(),
+            entity.line_start,
+            entity.line_end,
+        ));
+        if let Some(sig) = &entity.signature {
+            out.push_str(&format!("signature: {}\n", sig));
+        }
+        if !entity.semantic_features.is_empty() {
+            out.push_str(&format!(
+                "features: {}\n",
+                entity.semantic_features.join(", ")
+            ));
+        }
+        let prereqs = rpg_encoder::reconstruction::prerequisites(graph, id);
+        let (done, pending): (Vec<&String>, Vec<&String>) = prereqs
+            .iter()
+            .filter(|p| planned.contains(p.as_str()))
+            .partition(|p| state.done.contains(*p));
+        out.push_str(&format!("deps done: {}\n", list(&done)));
+        out.push_str(&format!("deps pending: {}\n", list(&pending)));
+    }
+    out
+}
+
+/// Bring submitted hierarchy paths (or bare area names, with `areas_only`)
+/// in line with `[encoding.hierarchy_style]`, in place. Returns one
+/// `key: 'old' → 'new'` line per rewrite, or an error listing the keys whose
+/// paths still break the style.
+pub(crate) fn apply_hierarchy_style<'a>(
+    paths: impl IntoIterator<Item = (&'a str, &'a mut String)>,
+    style: &rpg_core::config::HierarchyStyleConfig,
+    areas_only: bool,
+) -> Result<Vec<String>, String> {
+    let mut rewrites = Vec::new();
+    let mut rejected = Vec::new();
+    for (key, path) in paths {
+        let styled = if areas_only {
+            rpg_encoder::hierarchy_style::apply_area_style(path, style)
+        } else {
+            rpg_encoder::hierarchy_style::apply_style(path, style)
+        };
+        match styled {
+            Ok(styled) if styled != *path => {
+                rewrites.push(format!("  {}: '{}' → '{}'", key, path, styled));
+                *path = styled;
+            }
+            Ok(_) => {}
+            Err(violations) => rejected.push(format!("  {}: {}", key, violations.join("; "))),
+        }
+    }
+    if rejected.is_empty() {
+        return Ok(rewrites);
+    }
+    let sample: Vec<&str> = rejected.iter().take(10).map(|s| s.as_str()).collect();
+    Err(format!(
+        "{} hierarchy names break the style guide ([encoding.hierarchy_style], showing up to 10):\n{}",
+        rejected.len(),
+        sample.join("\n"),
+    ))
+}
+
+/// Report section for [`apply_hierarchy_style`] rewrites; empty when none.
+pub(crate) fn format_style_rewrites(rewrites: &[String]) -> String {
+    if rewrites.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\nNormalized {} hierarchy names to the style guide:\n{}\n",
+        rewrites.len(),
+        rewrites.join("\n")
+    )
+}
+
 /// Validate strict paper-style hierarchy path format: `Area/category/subcategory`.
 ///
 /// Rules:
@@ -113,10 +308,43 @@ pub(crate) fn hierarchy_path_exists(
     true
 }
 
+/// Skeleton (signatures and structure, bodies elided) of an entity's span,
+/// read from disk. `None` for unknown languages or unreadable files.
+pub(crate) fn entity_skeleton(
+    project_root: &std::path::Path,
+    entity: &rpg_core::graph::Entity,
+) -> Option<String> {
+    let language = entity
+        .file
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(rpg_parser::languages::Language::from_extension)?;
+    let source = std::fs::read_to_string(project_root.join(&entity.file)).ok()?;
+    rpg_parser::skeleton::skeleton_lines(&source, language, entity.line_start, entity.line_end)
+}
+
+/// Closing note for a batch call cut off by `navigation.max_tool_seconds`:
+/// how far it got and the IDs to pass to `tool` to resume.
+pub(crate) fn budget_exhausted_note(
+    tool: &str,
+    remaining: &[&str],
+    total: usize,
+    max_tool_seconds: f64,
+) -> String {
+    format!(
+        "[time budget exhausted after {} of {} entities (navigation.max_tool_seconds = {})]\nremaining_entity_ids: {}\nresume: call {} again with entity_ids = remaining_entity_ids",
+        total - remaining.len(),
+        total,
+        max_tool_seconds,
+        serde_json::to_string(remaining).unwrap_or_default(),
+        tool,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        format_dep_context, hierarchy_path_exists, is_three_level_hierarchy_path,
+        entity_type_filter_wants_async, hierarchy_path_exists, is_three_level_hierarchy_path,
         parse_entity_type_filter,
     };
     use rpg_core::graph::{EntityKind, HierarchyNode};
@@ -186,36 +414,21 @@ mod tests {
     }
 
     #[test]
-    fn test_format_dep_context_with_deps() {
-        use rpg_core::graph::EntityDeps;
-        let deps = EntityDeps {
-            invoked_by: vec!["main".to_string(), "test".to_string()],
-            invokes: vec!["helper".to_string()],
-            ..Default::default()
-        };
-        let result = format_dep_context(&deps);
-        assert!(result.starts_with("Deps:"));
-        assert!(result.contains("Called by: main, test"));
-        assert!(result.contains("Calls: helper"));
-    }
-
-    #[test]
-    fn test_format_dep_context_empty() {
-        use rpg_core::graph::EntityDeps;
-        let deps = EntityDeps::default();
-        let result = format_dep_context(&deps);
-        assert!(result.is_empty());
+    fn test_parse_entity_type_filter_enum_and_constant() {
+        assert_eq!(
+            parse_entity_type_filter("enum, Constant"),
+            vec![EntityKind::Enum, EntityKind::Constant]
+        );
     }
 
     #[test]
-    fn test_format_dep_context_caps_at_five() {
-        use rpg_core::graph::EntityDeps;
-        let deps = EntityDeps {
-            invokes: (1..=10).map(|i| format!("fn{}", i)).collect(),
-            ..Default::default()
-        };
-        let result = format_dep_context(&deps);
-        assert!(result.contains("fn5"));
-        assert!(!result.contains("fn6"));
+    fn test_async_pseudo_type_combines_with_kinds() {
+        assert_eq!(
+            parse_entity_type_filter("method, Async"),
+            vec![EntityKind::Method]
+        );
+        assert!(entity_type_filter_wants_async("method, Async"));
+        assert!(parse_entity_type_filter("async").is_empty());
+        assert!(!entity_type_filter_wants_async("function,class"));
     }
 }
@@ -71,6 +71,9 @@ impl RpgServer {
             output.push_str("\n\n## Framework-Specific Discovery Guidelines\n\n");
             output.push_str(&discovery_hints);
         }
+        output.push_str(&rpg_encoder::hierarchy::format_charter_guidance(graph));
+        let style = self.config.read().await.encoding.hierarchy_style.clone();
+        output.push_str(&rpg_encoder::hierarchy_style::format_style_guidance(&style));
 
         output.push_str("\n\n### Representative Files (from clusters):\n");
         output.push_str(&representative_features);
@@ -159,6 +162,9 @@ impl RpgServer {
             output.push_str("\n\n## Framework-Specific Hierarchy Patterns\n\n");
             output.push_str(&hierarchy_hints);
         }
+        output.push_str(&rpg_encoder::hierarchy::format_charter_guidance(graph));
+        let style = self.config.read().await.encoding.hierarchy_style.clone();
+        output.push_str(&rpg_encoder::hierarchy_style::format_style_guidance(&style));
 
         output.push_str("\n\n");
         output.push_str(include_str!("prompts/hierarchy_instructions.md"));
@@ -29,6 +29,27 @@ pub(crate) const LARGE_SCOPE_ENTITIES: usize = 100;
 /// `LARGE_SCOPE_ENTITIES` for why).
 pub(crate) const LARGE_SCOPE_BATCHES: usize = 10;
 
+/// Open `submit_lift_results` transactions expire after this long without a call.
+pub(crate) const LIFT_TRANSACTION_TTL: std::time::Duration = std::time::Duration::from_mins(30);
+
+/// Most feature entries one lift transaction may buffer.
+pub(crate) const MAX_LIFT_TRANSACTION_ENTRIES: usize = 5_000;
+
+/// Most lift transactions open at once.
+pub(crate) const MAX_LIFT_TRANSACTIONS: usize = 8;
+
+/// Source snippets kept for `get_snippet`, least recently used evicted first.
+pub(crate) const SNIPPET_CACHE_CAPACITY: usize = 1_000;
+
+/// Split responses stay available to `continue_output` for this long.
+pub(crate) const CONTINUATION_TTL: std::time::Duration = std::time::Duration::from_mins(15);
+
+/// Most split responses held at once; the oldest is dropped first.
+pub(crate) const MAX_PENDING_OUTPUTS: usize = 16;
+
+/// Bytes of each chunk kept free for its `continuation:` line.
+pub(crate) const CONTINUATION_LINE_RESERVE: usize = 128;
+
 use anyhow::Result;
 use rmcp::ServiceExt;
 use rpg_core::storage;
@@ -66,12 +87,13 @@ async fn main() -> Result<()> {
                 );
                 // Detect paradigms for framework-aware classification
                 let detected_langs = RpgServer::resolve_languages(&graph.metadata);
+                let project_root = server.project_root().await;
                 let paradigm_defs =
-                    rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+                    rpg_parser::paradigms::defs::load_defs_or_builtin(&project_root);
                 let qcache_result =
                     rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs);
                 let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
-                    &server.project_root().await,
+                    &project_root,
                     &detected_langs,
                     &paradigm_defs,
                 );
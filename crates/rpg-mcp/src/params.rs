@@ -1,7 +1,7 @@
 //! MCP tool parameter structs — one per tool handler, deserialized from JSON-RPC calls.
 
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Parameters for the `search_node` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -11,34 +11,91 @@ pub(crate) struct SearchNodeParams {
     /// Search mode: 'features', 'snippets', or 'auto' (default: 'auto')
     pub(crate) mode: Option<String>,
     /// Optional hierarchy scope to restrict search (e.g., 'Security/auth'). Comma-separated for multiple scopes.
+    /// Also accepts a scope expression: 'area:Security AND kind:class AND NOT glob:**/tests/**'.
     pub(crate) scope: Option<String>,
     /// Filter to entities within a line range [start, end]
     pub(crate) line_nums: Option<Vec<usize>>,
     /// Glob pattern to filter entities by file path (e.g., "src/**/*.rs")
     pub(crate) file_pattern: Option<String>,
-    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, file, module.
+    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, enum, constant, test, fixture, file, module. Add "async" to keep only async functions (e.g., "method,async").
     pub(crate) entity_type_filter: Option<String>,
+    /// Comma-separated layer filter (e.g., "api,infrastructure"). Valid: api, domain, infrastructure, test.
+    pub(crate) layer_filter: Option<String>,
     /// Git commit to diff from for proximity-based ranking (e.g., "HEAD~10", "abc123"). Boosts entities in changed files and their dependencies.
     pub(crate) since_commit: Option<String>,
+    /// Only search entities touched by `git diff <rev>...HEAD` (e.g., "main", "HEAD~3"): their file changed and their line range intersects a changed hunk. Results whose file also changed since the graph was built are marked stale.
+    pub(crate) changed_since: Option<String>,
+    /// Result order: 'relevance' (default), 'churn' (most frequently changed first, from the last `rpg-encoder churn` run), or 'entry_distance' (fewest call hops from an entry point first; unreachable last).
+    pub(crate) sort: Option<String>,
+    /// Include entities in test fixture files (`encoding.fixture_globs`, tagged `fixture`), which are skipped by default.
+    pub(crate) include_fixtures: Option<bool>,
+    /// Leave out test code: test functions and blocks, and entities in the test layer.
+    pub(crate) exclude_tests: Option<bool>,
+    /// Show up to 3 of each result's strongest dependency neighbors (id, edge, kind, top feature) under it. Off by default to keep output small.
+    pub(crate) include_neighbors: Option<bool>,
+    /// When a file's Module entity and some of its members both match, list the members
+    /// under the Module with their own scores (or drop the Module when a member scores
+    /// higher). Default: true for mode='features', false otherwise.
+    pub(crate) group_by_file: Option<bool>,
 }
 
 /// Parameters for the `fetch_node` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct FetchNodeParams {
-    /// The entity ID to fetch (e.g., 'src/auth.rs:validate_token')
+    /// The entity ID to fetch (e.g., 'src/auth.rs:validate_token'), or a
+    /// hierarchy node by ID ('h:Auth/login') or path ('Auth/login')
+    #[serde(default)]
     pub(crate) entity_id: String,
     /// Multiple entity IDs to fetch in batch (overrides entity_id when provided)
     pub(crate) entity_ids: Option<Vec<String>>,
-    /// Comma-separated fields to include: "features", "source", "deps", "hierarchy". Omit for all fields.
+    /// File path relative to the project root (e.g., "src/auth.rs"): fetch its Module
+    /// entity and every entity in it, in line order (overrides entity_id and entity_ids)
+    pub(crate) file: Option<String>,
+    /// Token budget across all returned entities (default: unlimited). Entities past it
+    /// are listed by ID so they can be fetched separately.
+    pub(crate) token_budget: Option<usize>,
+    /// Comma-separated fields to include: "features", "source", "skeleton", "deps", "hierarchy",
+    /// "doc" (the docstring or doc comment).
+    /// "skeleton" returns the source with function bodies elided to `{ … }` (signatures,
+    /// class/struct scaffolding, fields, and doc comments only). Omit for all fields.
     pub(crate) fields: Option<String>,
     /// Maximum lines of source code to return (default: unlimited). Only applies when "source" is included.
     pub(crate) source_max_lines: Option<usize>,
+    /// Source delivery: "inline" (default) or "hash". With "hash", each source block carries a
+    /// `source_ref` (blake3 content hash); source already sent this session comes back as the
+    /// ref plus its first 3 lines. Use `get_snippet` to fetch the full text for a ref.
+    pub(crate) snippets: Option<String>,
 }
 
-/// Parameters for the `explore_rpg` tool.
+/// Parameters for the `get_snippet` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct GetSnippetParams {
+    /// A `source_ref` hash from a `fetch_node` call made with `snippets: "hash"`
+    pub(crate) source_ref: String,
+}
+
+/// Parameters for the `continue_output` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ContinueOutputParams {
+    /// The token from a response's `continuation:` line (e.g., "out3.1")
+    pub(crate) token: String,
+}
+
+/// Parameters for the `file_symbols` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FileSymbolsParams {
+    /// File path relative to the project root (e.g., "src/server.py")
+    pub(crate) file: String,
+    /// Output format: "text" (default, indented outline) or "json" (LSP DocumentSymbol-style)
+    pub(crate) format: Option<String>,
+}
+
+/// Parameters for the `explore_rpg` tool.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub(crate) struct ExploreRpgParams {
-    /// The entity ID to start exploration from
+    /// The entity ID to start exploration from, a hierarchy node by ID
+    /// ('h:Auth/login') or path ('Auth/login'), or a file path
+    /// ('src/index.ts') to start from its Module entity
     pub(crate) entity_id: String,
     /// Multiple entity IDs to explore from in batch (overrides entity_id when provided)
     pub(crate) entity_ids: Option<Vec<String>>,
@@ -46,10 +103,14 @@ pub(crate) struct ExploreRpgParams {
     pub(crate) direction: Option<String>,
     /// Maximum traversal depth (default: 2). Use -1 for unlimited depth.
     pub(crate) depth: Option<i64>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', or 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', or 'tests'
     pub(crate) edge_filter: Option<String>,
-    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, file, module, page, layout, component, hook, store.
+    /// Comma-separated entity type filter (e.g., "function,class,method"). Valid: function, class, method, enum, constant, fixture, file, module, page, layout, component, hook, store.
     pub(crate) entity_type_filter: Option<String>,
+    /// Comma-separated layer filter (e.g., "domain"). Valid: api, domain, infrastructure, test.
+    pub(crate) layer_filter: Option<String>,
+    /// Comma-separated user tags (e.g., "deprecated,hot-path"); keeps entities carrying any of them.
+    pub(crate) tag_filter: Option<String>,
     /// Output format: "tree" (default, indented tree) or "compact" (pipe-delimited rows with entity_ids)
     pub(crate) format: Option<String>,
     /// Maximum number of nodes to return (default: unlimited). Truncates output for large traversals.
@@ -63,15 +124,54 @@ pub(crate) struct SetProjectRootParams {
     pub(crate) path: String,
 }
 
+/// Parameters for the `set_config` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetConfigParams {
+    /// Setting to change. Supported: "guidance".
+    pub(crate) key: String,
+    /// New value. For "guidance": "full", "terse" (a single `next: <tool>` line), or "off".
+    pub(crate) value: String,
+}
+
+/// Parameters for the `rebuild_embeddings` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct RebuildEmbeddingsParams {
+    /// "repair" (default): drop orphan vectors, embed missing and mismatched entities.
+    /// "full": discard the index and re-embed every lifted entity. "check": report only.
+    pub(crate) mode: Option<String>,
+}
+
 /// Parameters for the `build_rpg` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct BuildRpgParams {
     /// Primary language override (auto-detected if not specified)
     pub(crate) language: Option<String>,
-    /// Glob pattern to include files (e.g., "src/**/*.rs")
-    pub(crate) include: Option<String>,
-    /// Glob pattern to exclude files (e.g., "tests/**")
-    pub(crate) exclude: Option<String>,
+    /// Glob patterns to include files; a file matching any is kept
+    /// (e.g., ["src/**", "lib/**"]). A single string is also accepted.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) include: Option<Vec<String>>,
+    /// Glob patterns to exclude files; a file matching any is skipped
+    /// (e.g., ["**/generated/**", "tests/**"]). A single string is also accepted.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub(crate) exclude: Option<Vec<String>>,
+}
+
+/// Deserialize a string list that older callers send as one string.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => None,
+        Some(OneOrMany::One(s)) => Some(vec![s]),
+        Some(OneOrMany::Many(v)) => Some(v),
+    })
 }
 
 /// Parameters for the `update_rpg` tool.
@@ -85,6 +185,8 @@ pub(crate) struct UpdateRpgParams {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct GetEntitiesForLiftingParams {
     /// Scope specifier: file glob ("src/auth/**"), hierarchy path, entity IDs, or "*"/"all".
+    /// Also accepts a scope expression, which like "*" selects only unlifted or stale entities:
+    /// "area:Auth AND kind:function AND NOT glob:**/tests/**".
     pub(crate) scope: String,
     /// Batch index to retrieve (0-based). Omit or 0 for first batch.
     pub(crate) batch_index: Option<usize>,
@@ -96,6 +198,12 @@ pub(crate) struct SubmitLiftResultsParams {
     /// JSON object mapping function names to feature arrays.
     /// Example: {"my_func": ["validate input", "return result"], "other": ["compute hash"]}
     pub(crate) features: String,
+    /// Split a large submission across calls: every part carries the same id.
+    /// Parts are buffered until the call with `final: true`, which applies them all at once.
+    pub(crate) transaction_id: Option<String>,
+    /// With `transaction_id`: true on the last part. Defaults to false.
+    #[serde(rename = "final")]
+    pub(crate) is_final: Option<bool>,
 }
 
 /// Parameters for the `submit_hierarchy` tool.
@@ -139,12 +247,63 @@ pub(crate) struct ReconstructPlanParams {
     pub(crate) include_modules: Option<bool>,
 }
 
+/// Parameters for the `tag_entities` and `untag_entities` tools.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct TagEntitiesParams {
+    /// Tags to add or remove (e.g., ["deprecated", "hot-path"]). Stored lowercase.
+    pub(crate) tags: Vec<String>,
+    /// Entity IDs to (un)tag.
+    pub(crate) entity_ids: Option<Vec<String>>,
+    /// Select entities by hierarchy path, file glob, or scope expression
+    /// (e.g., "area:Legacy AND kind:function"). Combined with entity_ids.
+    pub(crate) scope: Option<String>,
+}
+
+/// Parameters for the `set_area_charter` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SetAreaCharterParams {
+    /// Hierarchy path or node ID (e.g., "Storage" or "h:Storage/persist graph").
+    pub(crate) path: String,
+    /// What the area is for, e.g. "Everything related to persisting and migrating the graph; new storage backends go here". Omit or pass an empty string to clear.
+    pub(crate) charter: Option<String>,
+}
+
+/// Parameters for the `annotate_entities` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct AnnotateEntitiesParams {
+    /// JSON annotation document: a source name and entries targeting an entity ID or a file line range.
+    /// Example: {"source": "github", "annotations": [{"file": "src/auth.py", "lines": [10, 24], "values": {"last_reviewer": "alice"}}, {"entity": "src/auth.py:login", "values": {"pr": "#123"}}]}
+    /// A null value removes the annotation.
+    pub(crate) annotations: String,
+}
+
+/// Parameters for the `get_reconstruction_batch` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct GetReconstructionBatchParams {
+    /// 1-based batch index from the plan. Omit for the first batch with unfinished entities.
+    pub(crate) batch_index: Option<usize>,
+    /// Maximum entities per batch when a new plan is scheduled (default: 8).
+    pub(crate) max_batch_size: Option<usize>,
+    /// Include file-level Module entities when a new plan is scheduled (default: false).
+    pub(crate) include_modules: Option<bool>,
+    /// Discard saved progress and schedule a new plan (default: false).
+    pub(crate) reset: Option<bool>,
+}
+
+/// Parameters for the `mark_reconstruction_done` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct MarkReconstructionDoneParams {
+    /// IDs of entities that have been reconstructed.
+    pub(crate) entity_ids: Vec<String>,
+}
+
 /// Parameters for the `context_pack` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct ContextPackParams {
     /// The search query describing what context you need
     pub(crate) query: String,
-    /// Optional hierarchy scope to restrict search (e.g., 'Security/auth')
+    /// Optional hierarchy scope to restrict search (e.g., 'Security/auth') or a scope
+    /// expression ('area:Security AND NOT glob:**/tests/**')
     pub(crate) scope: Option<String>,
     /// Target token budget for the packed context (default: 4000)
     pub(crate) token_budget: Option<usize>,
@@ -152,10 +311,13 @@ pub(crate) struct ContextPackParams {
     pub(crate) include_source: Option<bool>,
     /// Neighborhood expansion depth: 0 = primary only, 1 = include 1-hop neighbors (default: 1)
     pub(crate) depth: Option<usize>,
+    /// Source for neighborhood entities: "skeleton" (default; signatures and structure with
+    /// bodies elided) or "none"
+    pub(crate) neighbor_source: Option<String>,
 }
 
 /// Parameters for the `impact_radius` tool.
-#[derive(Debug, Deserialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub(crate) struct ImpactRadiusParams {
     /// The entity ID to compute impact from
     pub(crate) entity_id: String,
@@ -163,10 +325,39 @@ pub(crate) struct ImpactRadiusParams {
     pub(crate) direction: Option<String>,
     /// Maximum traversal depth (default: 3). Use -1 for unlimited.
     pub(crate) max_depth: Option<i64>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'renders', 'reads_state', 'writes_state', 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', or 'tests' (upstream: the tests covering this entity)
     pub(crate) edge_filter: Option<String>,
     /// Maximum number of reachable entities to return (default: 100). Prevents overwhelming output on highly-connected nodes.
     pub(crate) max_results: Option<usize>,
+    /// Stop at task/thread spawn boundaries (calls inside `tokio::spawn`/`thread::spawn` closures): spawned callees are listed, marked `invokes[spawn]`, but not walked past (default: false)
+    pub(crate) stop_at_async_boundary: Option<bool>,
+}
+
+/// Parameters for the `summarize_patch` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SummarizePatchParams {
+    /// Unified diff text (`git diff`, `git format-patch`, or `diff -u` output). Its old side must match the indexed tree.
+    pub(crate) diff: String,
+    /// Upstream hops walked for the blast radius (default: 3)
+    pub(crate) max_depth: Option<usize>,
+    /// Maximum blast-radius entities to list, nearest first (default: 25). The total is always reported.
+    pub(crate) max_results: Option<usize>,
+}
+
+/// Parameters for the `explain_entity` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ExplainEntityParams {
+    /// The entity ID to explain
+    pub(crate) entity_id: String,
+    /// Area similarity below which the entity is flagged as likely misplaced (default: 0.1)
+    pub(crate) misplacement_threshold: Option<f64>,
+}
+
+/// Parameters for the `run_saved_query` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct RunSavedQueryParams {
+    /// Name of the saved query in `.rpg/queries.toml`
+    pub(crate) name: String,
 }
 
 /// Parameters for the `submit_routing_decisions` tool.
@@ -181,12 +372,32 @@ pub(crate) struct SubmitRoutingDecisionsParams {
     pub(crate) graph_revision: String,
 }
 
+/// Parameters for the `verify_features` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct VerifyFeaturesParams {
+    /// Number of lifted entities to sample (default: 5).
+    pub(crate) count: Option<usize>,
+    /// Sampling strategy: 'random' (default), 'stale' (least recently lifted first), or 'churn' (most frequently changed first, from the last `rpg-encoder churn` run).
+    pub(crate) strategy: Option<String>,
+    /// Seed for the sample; the same seed on the same graph picks the same entities (default: 0).
+    pub(crate) seed: Option<u64>,
+}
+
+/// Parameters for the `submit_verification` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SubmitVerificationParams {
+    /// JSON object mapping entity IDs to a verdict: "accurate", "outdated", or "wrong".
+    /// Example: {"src/auth.rs:validate_token": "accurate", "src/db.rs:query": "outdated"}
+    pub(crate) verdicts: String,
+}
+
 /// Parameters for the `plan_change` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct PlanChangeParams {
     /// The goal or intent of the change (e.g., "add rate limiting to API endpoints")
     pub(crate) goal: String,
-    /// Optional hierarchy scope to restrict search (e.g., 'Security/auth')
+    /// Optional hierarchy scope to restrict search (e.g., 'Security/auth') or a scope
+    /// expression ('area:Security AND NOT glob:**/tests/**')
     pub(crate) scope: Option<String>,
     /// Maximum number of relevant entities to include (default: 15)
     pub(crate) max_entities: Option<usize>,
@@ -203,7 +414,7 @@ pub(crate) struct FindPathsParams {
     pub(crate) max_hops: Option<i64>,
     /// Maximum number of paths to return (default: 3)
     pub(crate) max_paths: Option<usize>,
-    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', or 'dispatches'
+    /// Filter edges by kind: 'imports', 'invokes', 'inherits', 'composes', 'contains', 'renders', 'reads_state', 'writes_state', 'dispatches', 'data_flow', or 'tests'
     pub(crate) edge_filter: Option<String>,
 }
 
@@ -234,6 +445,18 @@ pub(crate) struct AnalyzeHealthParams {
     pub(crate) semantic_similarity_threshold: Option<f64>,
 }
 
+/// Parameters for the `find_duplicates` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FindDuplicatesParams {
+    /// Detection method: "features" (Jaccard on lifted features), "source" (MinHash on
+    /// token shingles, works without lifting), or "both" (default: "both").
+    pub(crate) method: Option<String>,
+    /// Similarity threshold (default: 0.6 for features, 0.8 for source).
+    pub(crate) similarity_threshold: Option<f64>,
+    /// Maximum groups to return per method (default: 50).
+    pub(crate) max_results: Option<usize>,
+}
+
 /// Parameters for the `semantic_snapshot` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct SemanticSnapshotParams {
@@ -243,6 +466,13 @@ pub(crate) struct SemanticSnapshotParams {
     pub(crate) include_deps: Option<bool>,
 }
 
+/// Parameters for the `orient` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct OrientParams {
+    /// Target token budget (default: 2000). Category summaries and long lists are trimmed first.
+    pub(crate) token_budget: Option<usize>,
+}
+
 /// Parameters for the `detect_cycles` tool.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct DetectCyclesParams {
@@ -270,6 +500,29 @@ pub(crate) struct DetectCyclesParams {
     pub(crate) ignore_rpgignore: Option<bool>,
 }
 
+/// Parameters for the `resolution_report` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct ResolutionReportParams {
+    /// Maximum unresolved import prefixes to list, largest first (default: 20).
+    pub(crate) max_prefixes: Option<usize>,
+}
+
+/// Parameters for the `feature_sources` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FeatureSourcesParams {
+    /// Maximum areas to list in the re-lift plan (default: 10).
+    pub(crate) max_plan_areas: Option<usize>,
+}
+
+/// Parameters for the `find_config_usage` tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct FindConfigUsageParams {
+    /// Configuration key to look for (e.g., "RPG_READ_ONLY", "database.url").
+    pub(crate) key: String,
+    /// Match mode: 'substring' (default, case-insensitive) or 'exact'.
+    pub(crate) mode: Option<String>,
+}
+
 /// Parameters for the `auto_lift` tool.
 #[derive(Deserialize, JsonSchema)]
 pub(crate) struct AutoLiftParams {
@@ -283,7 +536,7 @@ pub(crate) struct AutoLiftParams {
     pub(crate) model: Option<String>,
     /// Base URL for OpenAI-compatible endpoints (e.g., "https://openrouter.ai/api/v1" for OpenRouter, "https://generativelanguage.googleapis.com/v1beta/openai" for Gemini).
     pub(crate) base_url: Option<String>,
-    /// Scope: file glob ("src/auth/**"), hierarchy path, or "*" for all unlifted. Default: "*".
+    /// Scope: file glob ("src/auth/**"), hierarchy path, scope expression ("area:Auth AND kind:function"), or "*" for all unlifted. Default: "*".
     pub(crate) scope: Option<String>,
     /// Dry run: estimate cost without lifting. Default: false.
     pub(crate) dry_run: Option<bool>,
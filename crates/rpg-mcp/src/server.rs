@@ -1,14 +1,17 @@
 //! `RpgServer` struct definition, non-tool methods, and `ServerHandler` impl.
 
-use rmcp::{ServerHandler, model::ServerInfo, tool_handler};
-use rpg_core::config::RpgConfig;
+use rmcp::{ServerHandler, model::ServerInfo};
+use rpg_core::config::{GuidanceMode, RpgConfig};
 use rpg_core::graph::RPGraph;
 use rpg_core::storage;
 use std::path::PathBuf;
-use std::sync::{Arc, OnceLock};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::types::{HierarchySession, LiftingSession, PendingRouting, load_pending_routing};
+use crate::types::{
+    HierarchySession, LiftTransaction, LiftTransactionStep, LiftingSession, OutputStore,
+    PendingRouting, SnippetCache, load_pending_routing,
+};
 
 /// Cached protocol prompt versions (SHA256 hashes) for deduplication.
 #[derive(Clone)]
@@ -50,6 +53,11 @@ impl PromptVersions {
 /// 6. `config`
 /// 7. `embedding_index`
 /// 8. `project_root_cell`
+/// 9. `guidance_override`
+/// 10. `lift_transactions`
+/// 11. `snippet_cache`
+/// 12. `output_store`
+/// 13. `hierarchy_changes`
 ///
 /// Paths that touch only one lock at a time are unaffected. Paths that
 /// acquire several locks but release each before acquiring the next
@@ -101,6 +109,18 @@ pub(crate) struct RpgServer {
         Arc<RwLock<std::collections::HashSet<std::path::PathBuf>>>,
     /// Guard: true while auto_lift is running. Rejects concurrent lift calls.
     pub(crate) lift_in_progress: Arc<std::sync::atomic::AtomicBool>,
+    /// Guidance mode set via `set_config`. Overrides `[mcp] guidance` from
+    /// config and, unlike it, survives `reload_rpg` and `set_project_root`.
+    pub(crate) guidance_override: Arc<RwLock<Option<GuidanceMode>>>,
+    /// Parts of multi-call `submit_lift_results` submissions, by transaction id.
+    pub(crate) lift_transactions: Arc<RwLock<std::collections::HashMap<String, LiftTransaction>>>,
+    /// Source served by `fetch_node` in `snippets: "hash"` mode, for `get_snippet`.
+    pub(crate) snippet_cache: Arc<RwLock<SnippetCache>>,
+    /// Remaining chunks of responses over `max_response_bytes`, for `continue_output`.
+    pub(crate) output_store: Arc<RwLock<OutputStore>>,
+    /// What the last `submit_hierarchy` moved, for `hierarchy_changes`.
+    pub(crate) hierarchy_changes:
+        Arc<RwLock<Option<rpg_encoder::hierarchy_changes::HierarchyChanges>>>,
 }
 
 impl std::fmt::Debug for RpgServer {
@@ -118,6 +138,26 @@ impl RpgServer {
         self.project_root_cell.read().await.clone()
     }
 
+    /// Record an explore/impact call so it can be saved by name later
+    /// (`rpg-encoder query save`). Best-effort: failures are logged, not surfaced.
+    pub(crate) async fn record_last_query(&self, tool: &str, params: &impl serde::Serialize) {
+        let root = self.project_root().await;
+        let recorded = toml::Table::try_from(params)
+            .map_err(anyhow::Error::from)
+            .and_then(|params| {
+                rpg_nav::queries::record_last(
+                    &root,
+                    &rpg_nav::queries::SavedQuery {
+                        tool: tool.to_string(),
+                        params,
+                    },
+                )
+            });
+        if let Err(e) = recorded {
+            eprintln!("rpg: failed to record last query: {}", e);
+        }
+    }
+
     /// Reload `.rpg/config.toml` into the given config slot.
     /// - File missing → silently use defaults (the no-config-yet case).
     /// - File present but malformed → log a warning, keep the existing config.
@@ -149,7 +189,9 @@ impl RpgServer {
 
     /// Create a new server, loading graph and config from `project_root` if present.
     pub(crate) fn new(project_root: PathBuf) -> Self {
-        let graph = storage::load(&project_root).ok();
+        let graph = rpg_encoder::artifact::load_verified(&project_root)
+            .ok()
+            .map(|(graph, _)| graph);
         let initial_head = graph.as_ref().and_then(|g| g.base_commit.clone());
         let config = RpgConfig::load(&project_root).unwrap_or_default();
         // Restore pending routing from disk if present
@@ -174,6 +216,11 @@ impl RpgServer {
             last_auto_sync_changeset: Arc::new(RwLock::new(None)),
             last_auto_sync_workdir_paths: Arc::new(RwLock::new(std::collections::HashSet::new())),
             lift_in_progress: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            guidance_override: Arc::new(RwLock::new(None)),
+            lift_transactions: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            snippet_cache: Arc::new(RwLock::new(SnippetCache::default())),
+            output_store: Arc::new(RwLock::new(OutputStore::default())),
+            hierarchy_changes: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -184,8 +231,11 @@ impl RpgServer {
         let Some(graph) = guard.as_ref() else {
             return String::new();
         };
-        // Detect workdir changes (committed + staged + unstaged)
-        let Ok(changes) = rpg_encoder::evolution::detect_workdir_changes(&project_root, graph)
+        // Detect workdir changes (committed + staged + unstaged) since the
+        // configured comparison point
+        let compare_to = self.config.read().await.staleness.compare_to.clone();
+        let Ok((point, changes)) =
+            rpg_encoder::evolution::detect_stale_changes(&project_root, graph, &compare_to)
         else {
             return String::new();
         };
@@ -194,14 +244,21 @@ impl RpgServer {
         let source_changes = if languages.is_empty() {
             changes
         } else {
-            rpg_encoder::evolution::filter_source_changes(changes, &languages)
+            rpg_encoder::evolution::filter_source_changes(&project_root, changes, &languages)
         };
         if source_changes.is_empty() {
             return String::new();
         }
+        let risk = rpg_encoder::evolution::assess_change_risk(
+            graph,
+            &source_changes,
+            &self.config.read().await.risk,
+        );
         format!(
-            "[stale: {} source file(s) changed since graph was built — call update_rpg to sync]\n\n",
+            "[stale: {} source file(s) changed since {}, {} — call update_rpg to sync]\n\n",
             source_changes.len(),
+            point.label,
+            risk.summary(),
         )
     }
 
@@ -244,7 +301,7 @@ impl RpgServer {
             let source_changes = if languages.is_empty() {
                 changes
             } else {
-                rpg_encoder::evolution::filter_source_changes(changes, &languages)
+                rpg_encoder::evolution::filter_source_changes(&project_root, changes, &languages)
             };
             let paths = Self::change_paths(&source_changes);
             let hash = Self::compute_changeset_hash(&source_changes, &project_root);
@@ -296,7 +353,7 @@ impl RpgServer {
 
         // Paradigm setup for framework-aware classification
         let detected_langs = Self::resolve_languages(&graph.metadata);
-        let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
+        let paradigm_defs = rpg_parser::paradigms::defs::load_defs_or_builtin(&project_root);
         let qcache_result =
             rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs);
         let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
@@ -496,31 +553,14 @@ impl RpgServer {
 
     /// Collect paradigm-specific prompt hints for detected frameworks.
     ///
-    /// Uses a cached copy of the builtin paradigm defs (parsed once via OnceLock),
-    /// filters by the paradigm names stored in `graph.metadata.paradigms`, and
-    /// concatenates the requested hint type from each active paradigm.
+    /// Filters the builtin paradigm defs by the paradigm names stored in
+    /// `graph.metadata.paradigms` and concatenates the requested hint type
+    /// from each active paradigm.
     pub(crate) fn collect_paradigm_hints(
         paradigm_names: &[String],
         hint_selector: fn(&rpg_parser::paradigms::defs::PromptHints) -> &Option<String>,
     ) -> String {
-        static PARADIGM_DEFS: OnceLock<Vec<rpg_parser::paradigms::defs::ParadigmDef>> =
-            OnceLock::new();
-        let defs = PARADIGM_DEFS
-            .get_or_init(|| rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default());
-        let mut hints = String::new();
-        for name in paradigm_names {
-            if let Some(def) = defs.iter().find(|d| &d.name == name)
-                && let Some(hint) = hint_selector(&def.prompt_hints)
-            {
-                if !hints.is_empty() {
-                    hints.push('\n');
-                }
-                hints.push_str(&format!("### {} Guidelines\n", def.name));
-                hints.push_str(hint.trim());
-                hints.push('\n');
-            }
-        }
-        hints
+        rpg_encoder::lift::paradigm_hints(paradigm_names, hint_selector)
     }
 
     /// Ensure a graph is loaded in memory, attempting disk load if needed.
@@ -532,34 +572,46 @@ impl RpgServer {
         drop(read);
 
         let project_root = self.project_root().await;
-        match storage::load(&project_root) {
-            Ok(g) => {
+        match rpg_encoder::artifact::load_verified(&project_root) {
+            Ok((g, _)) => {
                 *self.graph.write().await = Some(g);
                 Ok(())
             }
+            Err(e) if storage::rpg_exists(&project_root) => {
+                Err(format!("Failed to load RPG: {}", e))
+            }
             Err(_) => {
                 Err("No RPG found. Use the build_rpg tool to index this repository first.".into())
             }
         }
     }
 
-    /// Detailed staleness info: which source files changed (committed + staged + unstaged).
+    /// Detailed staleness info: which source files changed (committed + staged + unstaged)
+    /// since `[staleness] compare_to`.
     pub(crate) async fn staleness_detail(&self, graph: &RPGraph) -> Option<String> {
         let project_root = self.project_root().await;
-        let changes = rpg_encoder::evolution::detect_workdir_changes(&project_root, graph).ok()?;
+        let compare_to = self.config.read().await.staleness.compare_to.clone();
+        let (point, changes) =
+            rpg_encoder::evolution::detect_stale_changes(&project_root, graph, &compare_to).ok()?;
         let changes = rpg_encoder::evolution::filter_rpgignore_changes(&project_root, changes);
         let languages = Self::resolve_languages(&graph.metadata);
-        let changes = rpg_encoder::evolution::filter_source_changes(changes, &languages);
+        let changes =
+            rpg_encoder::evolution::filter_source_changes(&project_root, changes, &languages);
 
         if changes.is_empty() {
             return None;
         }
 
-        let graph_commit = graph.base_commit.as_deref().unwrap_or("unknown");
+        let risk = rpg_encoder::evolution::assess_change_risk(
+            graph,
+            &changes,
+            &self.config.read().await.risk,
+        );
         let mut out = format!(
-            "STALE ({} source file(s) changed since {})\n",
+            "STALE ({} source file(s) changed since {}, {})\n",
             changes.len(),
-            &graph_commit[..8.min(graph_commit.len())],
+            point.label,
+            risk.summary(),
         );
         for change in changes.iter().take(10) {
             let (label, path) = match change {
@@ -632,12 +684,28 @@ impl RpgServer {
              hierarchy: {}\n",
             graph_line, lifted, total, coverage_pct, hierarchy_type,
         );
+        let exclusions = graph.lifting_exclusions();
+        if !exclusions.is_empty() {
+            let reasons: Vec<String> = exclusions
+                .iter()
+                .map(|(reason, count)| format!("{} {}", count, reason))
+                .collect();
+            out.push_str(&format!(
+                "excluded_from_lifting: {} entities ({}; not counted in coverage, see encoding.liftable_kinds)\n",
+                exclusions.values().sum::<usize>(),
+                reasons.join(", "),
+            ));
+        }
         if stale_features_count > 0 {
             out.push_str(&format!(
                 "stale_features: {} entities modified since last lift (features outdated)\n",
                 stale_features_count,
             ));
         }
+        if let Some(verification) = &graph.metadata.feature_verification {
+            out.push_str(&rpg_encoder::verification::format_accuracy(verification));
+            out.push('\n');
+        }
 
         // Per-area coverage
         let area_cov = graph.area_coverage();
@@ -668,8 +736,8 @@ impl RpgServer {
                 out.push_str(&format!(
                     "\nLifting session: active (scope=\"{}\", {} batches, {} entities cached)\n",
                     s.scope_key,
-                    s.batch_ranges.len(),
-                    s.raw_entities.len(),
+                    s.queue.batch_ranges.len(),
+                    s.queue.raw_entities.len(),
                 ));
             }
             None => {
@@ -712,7 +780,8 @@ impl RpgServer {
             }
         }
 
-        // NEXT STEP — state machine guidance, staleness takes priority.
+        // NEXT STEP — state machine guidance, staleness takes priority,
+        // rendered through `next_step` so the guidance mode applies.
         // `LARGE_SCOPE_ENTITIES` is the threshold above which direct
         // foreground lifting is not recommended. See also the matching
         // check in `get_entities_for_lifting` which expresses the same
@@ -725,12 +794,14 @@ impl RpgServer {
         //     `stale_entity_ids`). These look "lifted" in coverage but their
         //     features no longer reflect the source.
         // Their sum is what actually needs LLM work.
-        out.push('\n');
         let remaining = total.saturating_sub(lifted);
         let work_remaining = remaining + stale_features_count;
 
-        if stale_detail.is_some() {
-            out.push_str("NEXT STEP: Graph is stale. Call update_rpg to sync with code changes, then lift any new or modified entities.\n");
+        let (tool, block) = if stale_detail.is_some() {
+            (
+                "update_rpg",
+                "\nNEXT STEP: Graph is stale. Call update_rpg to sync with code changes, then lift any new or modified entities.\n".to_string(),
+            )
         } else if work_remaining >= crate::LARGE_SCOPE_ENTITIES {
             // Large repo — recommend delegating the mechanical loop so the
             // caller doesn't exhaust its own context. Give the dispatch
@@ -760,12 +831,12 @@ impl RpgServer {
             } else {
                 format!("{} entities unlifted", remaining)
             };
-            out.push_str(&format!(
-                "NEXT STEP: Likely-large lifting workload — {} (auto-lift may reduce this). Dispatch a sub-agent to run the LOOP below; do not run it in this context — each batch is ~{}K tokens of source and will exhaust caller context over many iterations.\n",
+            let mut block = format!(
+                "\nNEXT STEP: Likely-large lifting workload — {} (auto-lift may reduce this). Dispatch a sub-agent to run the LOOP below; do not run it in this context — each batch is ~{}K tokens of source and will exhaust caller context over many iterations.\n",
                 workload_desc,
                 batch_tokens.div_ceil(1000),
-            ));
-            out.push_str(
+            );
+            block.push_str(
                 "\nLOOP (sub-agent runs this in its own context):\n  \
                  get_entities_for_lifting(scope=\"*\") -> analyze batch -> submit_lift_results -> repeat until DONE -> finalize_lifting\n\
                  \nDISPATCH:\n  \
@@ -779,46 +850,215 @@ impl RpgServer {
             // modified) is a no-op for the CLI — surfacing it there would
             // be a dead-end recipe.
             if remaining > 0 {
-                out.push_str(
+                block.push_str(
                     "\nFALLBACK (no sub-agent mechanism, API key available, unlifted entities only):\n  \
                      Run `rpg-encoder lift --provider anthropic` (or `openai`) from the terminal — the CLI drives an external LLM directly with no agent involvement. After the CLI finishes, call `reload_rpg` in this session so the server picks up the updated graph from disk. Note: the CLI lifts entities with no features; stale entities (features present but outdated) must be re-lifted via the MCP loop above.\n",
                 );
             }
+            ("get_entities_for_lifting", block)
         } else if lifted == 0 {
-            out.push_str(
-                "NEXT STEP: Call get_entities_for_lifting(scope=\"*\") to start lifting.\n",
-            );
+            (
+                "get_entities_for_lifting",
+                "\nNEXT STEP: Call get_entities_for_lifting(scope=\"*\") to start lifting.\n"
+                    .to_string(),
+            )
         } else if remaining > 0 && stale_features_count > 0 {
-            out.push_str(&format!(
-                "NEXT STEP: {} unlifted + {} stale = {} entities need LLM work. Call get_entities_for_lifting(scope=\"*\") — it returns both unlifted entities and stale ones that need re-lifting in the same batches.\n",
-                remaining, stale_features_count, work_remaining,
-            ));
+            (
+                "get_entities_for_lifting",
+                format!(
+                    "\nNEXT STEP: {} unlifted + {} stale = {} entities need LLM work. Call get_entities_for_lifting(scope=\"*\") — it returns both unlifted entities and stale ones that need re-lifting in the same batches.\n",
+                    remaining, stale_features_count, work_remaining,
+                ),
+            )
         } else if remaining > 0 {
-            out.push_str(&format!(
-                "NEXT STEP: {} entities remaining. Call get_entities_for_lifting(scope=\"*\") to continue lifting.\n",
-                remaining,
-            ));
+            (
+                "get_entities_for_lifting",
+                format!(
+                    "\nNEXT STEP: {} entities remaining. Call get_entities_for_lifting(scope=\"*\") to continue lifting.\n",
+                    remaining,
+                ),
+            )
         } else if stale_features_count > 0 {
             // All entities have features, but some features are outdated.
             // The post-sync delta is what matters here — we track modified
             // entities in stale_entity_ids so agents know to re-lift them.
-            out.push_str(&format!(
-                "NEXT STEP: Coverage is 100% but {} entities have stale features (source modified after lift). Call get_entities_for_lifting(scope=\"*\") to re-lift just those — it surfaces stale entities as if they were unlifted.\n",
-                stale_features_count,
-            ));
+            (
+                "get_entities_for_lifting",
+                format!(
+                    "\nNEXT STEP: Coverage is 100% but {} entities have stale features (source modified after lift). Call get_entities_for_lifting(scope=\"*\") to re-lift just those — it surfaces stale entities as if they were unlifted.\n",
+                    stale_features_count,
+                ),
+            )
         } else if !graph.metadata.semantic_hierarchy {
-            out.push_str(
-                "NEXT STEP: All entities lifted. Call finalize_lifting, then get_files_for_synthesis + submit_file_syntheses for holistic file-level features, then build_semantic_hierarchy + submit_hierarchy.\n",
-            );
+            (
+                "finalize_lifting",
+                "\nNEXT STEP: All entities lifted. Call finalize_lifting, then get_files_for_synthesis + submit_file_syntheses for holistic file-level features, then build_semantic_hierarchy + submit_hierarchy.\n".to_string(),
+            )
         } else {
-            out.push_str(
-                "NEXT STEP: All entities lifted. Graph is complete. Use search_node, fetch_node, explore_rpg to navigate.\n",
-            );
+            (
+                "search_node",
+                "\nNEXT STEP: All entities lifted. Graph is complete. Use search_node, fetch_node, explore_rpg to navigate.\n".to_string(),
+            )
+        };
+        out.push_str(&self.next_step(tool, &block).await);
+
+        Ok(out)
+    }
+
+    /// Active guidance mode: the `set_config` override, else `[mcp] guidance`.
+    pub(crate) async fn guidance_mode(&self) -> GuidanceMode {
+        let config_mode = self.config.read().await.mcp.guidance;
+        self.guidance_override.read().await.unwrap_or(config_mode)
+    }
+
+    /// Apply the response limit for `tool` (`[mcp] max_response_bytes`): output
+    /// over it comes back as its first chunk plus a `continuation:` line.
+    /// `continue_output` chunks are already within the limit.
+    pub(crate) async fn limit_output(&self, tool: &str, output: String) -> String {
+        if tool == "continue_output" {
+            return output;
         }
+        let limit = self.config.read().await.mcp.max_response_bytes_for(tool);
+        match limit {
+            Some(limit) => self.output_store.write().await.split(output, limit),
+            None => output,
+        }
+    }
 
+    /// Render a next-step coaching block under the active guidance mode.
+    ///
+    /// Every tool routes its NEXT STEP text through here so the modes behave
+    /// the same everywhere. `tool` is the tool the block points at; `full` is
+    /// the complete block, including its leading separator.
+    pub(crate) async fn next_step(&self, tool: &str, full: &str) -> String {
+        render_next_step(self.guidance_mode().await, tool, full)
+    }
+
+    /// Shared body of `tag_entities` (`add`) and `untag_entities`: select the
+    /// entities, change their tags, and save the graph.
+    pub(crate) async fn change_tags(
+        &self,
+        params: crate::params::TagEntitiesParams,
+        add: bool,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let tags = crate::helpers::normalize_tags(&params.tags)?;
+        let entity_ids = params.entity_ids.unwrap_or_default();
+        if entity_ids.is_empty() && params.scope.is_none() {
+            return Err("Provide entity_ids, scope, or both.".into());
+        }
+
+        let project_root = self.project_root().await;
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let selection = rpg_nav::scope::select(graph, &entity_ids, params.scope.as_deref())
+            .map_err(|e| e.to_string())?;
+        let changed = if add {
+            graph.tag_entities(&selection.entity_ids, &tags)
+        } else {
+            graph.untag_entities(&selection.entity_ids, &tags)
+        };
+        if changed > 0 {
+            storage::save(&project_root, graph)
+                .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        }
+
+        let mut out = format!(
+            "{} {} of {} selected entities {} {}.\n",
+            if add { "Tagged" } else { "Untagged" },
+            changed,
+            selection.entity_ids.len(),
+            if add { "with" } else { "from" },
+            tags.join(", "),
+        );
+        if !selection.unknown_ids.is_empty() {
+            out.push_str(&format!(
+                "unknown entity IDs: {}\n",
+                selection.unknown_ids.join(", ")
+            ));
+        }
+        out.push_str(&format!(
+            "tags: {}\n",
+            crate::helpers::format_tag_counts(graph)
+        ));
         Ok(out)
     }
 
+    /// Add one part to a `submit_lift_results` transaction, expiring idle
+    /// transactions first. Non-final parts are buffered (up to
+    /// [`crate::MAX_LIFT_TRANSACTION_ENTRIES`] entries); the final part closes
+    /// the transaction and hands back everything buffered. A final part with
+    /// nothing buffered is a complete one-part transaction, unless its earlier
+    /// parts just expired.
+    pub(crate) async fn stage_lift_transaction(
+        &self,
+        id: &str,
+        features: std::collections::HashMap<String, Vec<String>>,
+        is_final: bool,
+    ) -> Result<LiftTransactionStep, String> {
+        let mut transactions = self.lift_transactions.write().await;
+        let mut expired = false;
+        transactions.retain(|key, t| {
+            let live = t.touched_at.elapsed() < crate::LIFT_TRANSACTION_TTL;
+            expired |= !live && key == id;
+            live
+        });
+        if expired {
+            return Err(format!(
+                "No buffered parts left for transaction_id '{}': it was idle for over {} minutes and expired. Resubmit every part.",
+                id,
+                crate::LIFT_TRANSACTION_TTL.as_secs() / 60,
+            ));
+        }
+
+        if is_final {
+            let Some(mut transaction) = transactions.remove(id) else {
+                return Ok(LiftTransactionStep::Complete { features, parts: 1 });
+            };
+            transaction.features.extend(features);
+            return Ok(LiftTransactionStep::Complete {
+                features: transaction.features,
+                parts: transaction.parts + 1,
+            });
+        }
+
+        if !transactions.contains_key(id) && transactions.len() >= crate::MAX_LIFT_TRANSACTIONS {
+            return Err(format!(
+                "Too many open lift transactions ({}). Finish one with final=true first.",
+                crate::MAX_LIFT_TRANSACTIONS,
+            ));
+        }
+        let buffered = transactions.get(id).map(|t| &t.features);
+        let entries = buffered.map_or(0, |b| b.len())
+            + features
+                .keys()
+                .filter(|k| buffered.is_none_or(|b| !b.contains_key(*k)))
+                .count();
+        if entries > crate::MAX_LIFT_TRANSACTION_ENTRIES {
+            return Err(format!(
+                "Transaction '{}' would buffer {} entries (max {}). This part was not buffered; send final=true to apply what is buffered, then start a new transaction.",
+                id,
+                entries,
+                crate::MAX_LIFT_TRANSACTION_ENTRIES,
+            ));
+        }
+
+        let transaction = transactions
+            .entry(id.to_string())
+            .or_insert_with(|| LiftTransaction {
+                features: std::collections::HashMap::new(),
+                parts: 0,
+                touched_at: std::time::Instant::now(),
+            });
+        transaction.features.extend(features);
+        transaction.parts += 1;
+        transaction.touched_at = std::time::Instant::now();
+        Ok(LiftTransactionStep::Buffered {
+            parts: transaction.parts,
+            entries: transaction.features.len(),
+        })
+    }
+
     /// Load the RPG config for the active project.
     pub(crate) async fn load_config(&self) -> RpgConfig {
         let project_root = self.project_root().await;
@@ -882,7 +1122,6 @@ impl RpgServer {
     }
 }
 
-#[tool_handler]
 impl ServerHandler for RpgServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -894,6 +1133,53 @@ impl ServerHandler for RpgServer {
             ..Default::default()
         }
     }
+
+    // Written out instead of `#[tool_handler]` so every text response passes
+    // through `limit_output`; formatters stay unaware of response limits.
+    async fn call_tool(
+        &self,
+        request: rmcp::model::CallToolRequestParams,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+        let tool = request.name.to_string();
+        let tcc = rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
+        let mut result = self.tool_router.call(tcc).await?;
+        if let [content] = result.content.as_mut_slice()
+            && let rmcp::model::RawContent::Text(text) = &mut content.raw
+        {
+            text.text = self
+                .limit_output(&tool, std::mem::take(&mut text.text))
+                .await;
+        }
+        Ok(result)
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<rmcp::model::PaginatedRequestParams>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
+        Ok(rmcp::model::ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+}
+
+/// Mode-dependent rendering behind [`RpgServer::next_step`]: full keeps the
+/// block, terse keeps its leading newlines and emits `next: <tool>`, off drops it.
+pub(crate) fn render_next_step(mode: GuidanceMode, tool: &str, full: &str) -> String {
+    match mode {
+        GuidanceMode::Full => full.to_string(),
+        GuidanceMode::Terse => {
+            let body = full.trim_start_matches('\n');
+            let separator = &full[..full.len() - body.len()];
+            let end = if full.ends_with('\n') { "\n" } else { "" };
+            format!("{}next: {}{}", separator, tool, end)
+        }
+        GuidanceMode::Off => String::new(),
+    }
 }
 
 #[cfg(test)]
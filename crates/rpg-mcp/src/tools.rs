@@ -1,4 +1,4 @@
-//! MCP tool handlers — all 27 `#[tool]` methods in a single `#[tool_router]` impl block.
+//! MCP tool handlers — all `#[tool]` methods in a single `#[tool_router]` impl block.
 //!
 //! The `#[tool_router]` proc macro requires every `#[tool]` method to live in one
 //! `impl` block, so this file cannot be split further without upstream changes.
@@ -15,7 +15,7 @@ use crate::types::*;
 #[tool_router]
 impl RpgServer {
     #[tool(
-        description = "PREFER THIS OVER grep/rg FOR ANY QUESTION ABOUT CODE BEHAVIOR OR NAMES. Search for code entities by intent or keywords. Returns entities with file paths, line numbers, and relevance scores. Use mode='features' for semantic intent search (e.g., 'validate user input') — finds code by what it DOES even when names don't match. Use mode='snippets' for name/path matching (e.g., 'FilterGroupManager' or 'src/auth/'). Use mode='auto' (default) to try both. This replaces grep/rg for every structural query.",
+        description = "PREFER THIS OVER grep/rg FOR ANY QUESTION ABOUT CODE BEHAVIOR OR NAMES. Search for code entities by intent or keywords. Returns entities with file paths, line numbers, and relevance scores, plus facet counts (kind, area, top files) over all matches — refine with entity_type_filter or scope when results are truncated. Use mode='features' for semantic intent search (e.g., 'validate user input') — finds code by what it DOES even when names don't match. Use mode='snippets' for name/path matching (e.g., 'FilterGroupManager' or 'src/auth/'). Use mode='auto' (default) to try both. Use changed_since='main' to search only code touched by `git diff main...HEAD`. Test fixture files (`encoding.fixture_globs`) are skipped unless include_fixtures=true. Set include_neighbors=true to list each result's 3 strongest dependency neighbors under it instead of following up with explore. This replaces grep/rg for every structural query.",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
     async fn search_node(
@@ -23,16 +23,32 @@ impl RpgServer {
         Parameters(params): Parameters<SearchNodeParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
+        if let Some(scope) = params.scope.as_deref() {
+            rpg_nav::scope::validate(scope).map_err(|e| e.to_string())?;
+        }
+        if let Some(pattern) = params.file_pattern.as_deref() {
+            rpg_nav::scope::compile_glob(pattern)?;
+        }
         let notice = self.auto_sync_if_stale().await;
         let guard = self.graph.read().await;
         let graph = guard.as_ref().unwrap();
         let config = self.config.read().await;
+        let deadline = rpg_nav::budget::deadline(config.navigation.max_tool_seconds);
 
         let search_mode = match params.mode.as_deref() {
             Some("features") => rpg_nav::search::SearchMode::Features,
             Some("snippets") => rpg_nav::search::SearchMode::Snippets,
             _ => rpg_nav::search::SearchMode::Auto,
         };
+        let sort = match params.sort.as_deref() {
+            Some(value) => rpg_nav::search::SearchSort::parse(value).ok_or_else(|| {
+                format!(
+                    "Unknown sort: {}. Use 'relevance', 'churn', or 'entry_distance'.",
+                    value
+                )
+            })?,
+            None => rpg_nav::search::SearchSort::Relevance,
+        };
 
         let line_nums = params.line_nums.as_ref().and_then(|v| {
             if v.len() == 2 {
@@ -47,6 +63,11 @@ impl RpgServer {
             .as_deref()
             .map(parse_entity_type_filter)
             .filter(|v| !v.is_empty());
+        let layer_filter = params
+            .layer_filter
+            .as_deref()
+            .map(parse_layer_filter)
+            .filter(|v| !v.is_empty());
 
         // Attempt hybrid embedding search for features/auto modes
         let use_embeddings = matches!(
@@ -126,7 +147,23 @@ impl RpgServer {
             None
         };
 
-        let results = rpg_nav::search::search_with_params(
+        // Restrict candidates to entities touched since `changed_since` (rev...HEAD)
+        let diff_scope = match params.changed_since {
+            Some(ref rev) => Some(
+                rpg_encoder::evolution::diff_scope(&self.project_root().await, graph, rev)
+                    .map_err(|e| format!("Failed to diff changes since {}: {}", rev, e))?,
+            ),
+            None => None,
+        };
+
+        let vocabulary = rpg_nav::vocab::Vocabulary::load(&self.project_root().await);
+        if sort == rpg_nav::search::SearchSort::Churn && graph.metadata.churn.is_none() {
+            diff_warning.push_str(
+                "[Warning: No churn analysis in the graph; run `rpg-encoder churn`. Sorted by relevance.]\n",
+            );
+        }
+
+        let mut outcome = rpg_nav::search::search_with_facets(
             graph,
             &rpg_nav::search::SearchParams {
                 query: &params.query,
@@ -136,12 +173,52 @@ impl RpgServer {
                 line_nums,
                 file_pattern: params.file_pattern.as_deref(),
                 entity_type_filter,
+                layer_filter,
                 embedding_scores: embedding_scores.as_ref(),
                 diff_context: diff_context.as_ref(),
+                changed_entities: diff_scope.as_ref().map(|d| &d.entities),
+                vocabulary: vocabulary.as_ref(),
+                sort,
+                include_fixtures: params.include_fixtures.unwrap_or(false),
+                exclude_tests: params.exclude_tests.unwrap_or(false),
+                async_only: params
+                    .entity_type_filter
+                    .as_deref()
+                    .is_some_and(entity_type_filter_wants_async),
+                deadline,
+                group_by_file: params
+                    .group_by_file
+                    .unwrap_or(search_mode == rpg_nav::search::SearchMode::Features),
             },
-        );
+        )
+        .map_err(|e| e.to_string())?;
+        if outcome.budget_exhausted {
+            diff_warning.push_str(&format!(
+                "[Warning: time budget exhausted (navigation.max_tool_seconds = {}); only part of the candidates were scored. Narrow with scope, file_pattern, or entity_type_filter.]\n",
+                config.navigation.max_tool_seconds
+            ));
+        }
+        if let Some(ref diff_scope) = diff_scope {
+            for result in &mut outcome.results {
+                result.stale = diff_scope
+                    .stale_files
+                    .contains(std::path::Path::new(&result.file));
+                for member in &mut result.members {
+                    member.stale = result.stale;
+                }
+            }
+        }
+        if params.include_neighbors.unwrap_or(false) {
+            for result in &mut outcome.results {
+                result.neighbors = rpg_nav::search::neighbor_previews(
+                    graph,
+                    &result.entity_id,
+                    rpg_nav::search::MAX_NEIGHBOR_PREVIEWS,
+                );
+            }
+        }
 
-        if results.is_empty() {
+        if outcome.results.is_empty() {
             return Ok(format!(
                 "{}{}No results found for: {} (search_mode: {})",
                 notice, diff_warning, params.query, search_mode_label,
@@ -152,13 +229,13 @@ impl RpgServer {
             "{}{}{}\n\nsearch_mode: {}",
             notice,
             diff_warning,
-            rpg_nav::toon::format_search_results(&results),
+            rpg_nav::toon::format_search_results(&outcome.results, Some(&outcome.facets)),
             search_mode_label,
         ))
     }
 
     #[tool(
-        description = "PREFER THIS OVER cat OR WHOLE-FILE READS FOR A SINGLE ENTITY. Fetch detailed metadata and source code for a known entity by ID. Returns the entity's semantic features (what it does), dependencies (what it calls, what calls it), hierarchy position, and full source code. Use this instead of reading the whole file when you only need one function/class/method.",
+        description = "PREFER THIS OVER cat OR WHOLE-FILE READS FOR A SINGLE ENTITY. Fetch detailed metadata and source code for a known entity by ID. Returns the entity's semantic features (what it does), dependencies (what it calls, what calls it), hierarchy position, and full source code. Use this instead of reading the whole file when you only need one function/class/method. For the shape of a class or file without bodies, pass fields='skeleton'. To get everything in one file, pass file='src/auth.rs': its Module entity, then every entity in line order, with token_budget capping the whole set. For repeated or overlapping batches, pass snippets='hash' so source you already received comes back as a short source_ref (see get_snippet).",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
     async fn fetch_node(
@@ -175,15 +252,83 @@ impl RpgServer {
         } else {
             vec![params.entity_id.as_str()]
         };
+        if params.file.is_none() && ids.iter().all(|id| id.trim().is_empty()) {
+            return Err("Pass entity_id, entity_ids, or file.".to_string());
+        }
 
         let projection = rpg_nav::toon::FetchProjection::from_params(
             params.fields.as_deref(),
             params.source_max_lines,
         )?;
+        let hash_snippets = match params.snippets.as_deref() {
+            None | Some("inline") => false,
+            Some("hash") => true,
+            Some(other) => {
+                return Err(format!(
+                    "Invalid snippets mode '{}'. Use \"inline\" or \"hash\".",
+                    other
+                ));
+            }
+        };
 
+        let project_root = self.project_root().await;
+        let mut snippet_cache = if hash_snippets {
+            Some(self.snippet_cache.write().await)
+        } else {
+            None
+        };
+        let fetches: Vec<(String, anyhow::Result<rpg_nav::fetch::FetchOutput>)> =
+            match params.file.as_deref() {
+                Some(file) => rpg_nav::fetch::fetch_file(graph, file, &project_root)
+                    .map_err(|e| e.to_string())?
+                    .into_iter()
+                    .map(|result| {
+                        (
+                            result.entity.id.clone(),
+                            Ok(rpg_nav::fetch::FetchOutput::Entity(Box::new(result))),
+                        )
+                    })
+                    .collect(),
+                None => ids
+                    .iter()
+                    .map(|id| {
+                        (
+                            id.to_string(),
+                            rpg_nav::fetch::fetch(graph, id, &project_root),
+                        )
+                    })
+                    .collect(),
+            };
         let mut outputs = Vec::new();
-        for id in &ids {
-            match rpg_nav::fetch::fetch(graph, id, &self.project_root().await) {
+        let mut output_ids = Vec::new();
+        for (id, mut fetched) in fetches {
+            output_ids.push(id.clone());
+            if projection.skeleton()
+                && let Ok(rpg_nav::fetch::FetchOutput::Entity(result)) = &mut fetched
+            {
+                result.source_code = entity_skeleton(&project_root, &result.entity);
+            }
+            match fetched {
+                Ok(rpg_nav::fetch::FetchOutput::Entity(result)) if snippet_cache.is_some() => {
+                    // First sight of a snippet sends it in full; repeats send only the ref.
+                    let snippet = result
+                        .source_code
+                        .as_deref()
+                        .filter(|_| projection.include("source"))
+                        .zip(snippet_cache.as_mut())
+                        .map(|(source, cache)| {
+                            let hash = rpg_nav::toon::snippet_hash(source);
+                            let seen = cache.insert(&hash, source);
+                            (hash, seen)
+                        });
+                    outputs.push(rpg_nav::toon::format_fetch_result_with_snippet(
+                        &result,
+                        &projection,
+                        snippet
+                            .as_ref()
+                            .map(|(hash, seen)| rpg_nav::toon::SnippetRef { hash, elide: *seen }),
+                    ));
+                }
                 Ok(output) => outputs.push(rpg_nav::toon::format_fetch_output_projected(
                     &output,
                     &projection,
@@ -192,11 +337,92 @@ impl RpgServer {
             }
         }
 
-        Ok(format!("{}{}", notice, outputs.join("\n---\n")))
+        let mut omitted = String::new();
+        if let Some(budget) = params.token_budget {
+            let tokens = self.config.read().await.tokens.clone();
+            let estimator = rpg_encoder::tokens::from_config(&tokens, None, &project_root);
+            let kept = rpg_nav::fetch::fit_token_budget(&outputs, budget, estimator.as_ref());
+            if kept < outputs.len() {
+                outputs.truncate(kept);
+                omitted = format!(
+                    "\n---\nomitted ({} over token_budget {}): {}",
+                    output_ids.len() - kept,
+                    budget,
+                    output_ids[kept..].join(", ")
+                );
+            }
+        }
+
+        Ok(format!("{}{}{}", notice, outputs.join("\n---\n"), omitted))
+    }
+
+    #[tool(
+        description = "Return the full source text for a `source_ref` hash from fetch_node(snippets=\"hash\"). Only snippets served earlier in this session are available; if the ref has been evicted, call fetch_node again.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn get_snippet(
+        &self,
+        Parameters(params): Parameters<GetSnippetParams>,
+    ) -> Result<String, String> {
+        let hash = params.source_ref.trim().to_lowercase();
+        self.snippet_cache
+            .write()
+            .await
+            .get(&hash)
+            .map(str::to_string)
+            .ok_or_else(|| {
+                format!(
+                    "Unknown source_ref '{}': it was never served in this session or has been evicted. Re-fetch the entity with fetch_node.",
+                    params.source_ref
+                )
+            })
+    }
+
+    #[tool(
+        description = "Return the next part of a response that was split because it exceeded `[mcp] max_response_bytes`. Pass the token from the `continuation:` line at the end of the previous part; each part but the last ends with the token for the one after it. Concatenating the parts (without their continuation lines) gives the full response. Parts expire 15 minutes after the original call.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn continue_output(
+        &self,
+        Parameters(params): Parameters<ContinueOutputParams>,
+    ) -> Result<String, String> {
+        self.output_store
+            .write()
+            .await
+            .continue_output(&params.token)
+    }
+
+    #[tool(
+        description = "Outline of one file from the graph, like an editor's document symbols: the module, its classes with their methods (nested classes included), and functions, ordered by line. Each symbol has its kind, line range, visibility, one-line features, and whether it is lifted. format=\"json\" returns LSP DocumentSymbol-shaped JSON (0-based lines).",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn file_symbols(
+        &self,
+        Parameters(params): Parameters<FileSymbolsParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let project_root = self.project_root().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let source = std::fs::read_to_string(project_root.join(&params.file)).ok();
+        let symbols = rpg_nav::symbols::document_symbols(graph, &params.file, source.as_deref())
+            .ok_or_else(|| rpg_nav::fetch::missing_file_message(graph, &params.file))?;
+
+        let output = match params.format.as_deref() {
+            None | Some("text") => rpg_nav::symbols::format_outline(&params.file, &symbols),
+            Some("json") => serde_json::to_string_pretty(&symbols)
+                .map_err(|e| format!("Failed to serialize symbols: {}", e))?,
+            Some(other) => {
+                return Err(format!("Unknown format: {}. Use 'text' or 'json'.", other));
+            }
+        };
+        Ok(format!("{}{}", notice, output))
     }
 
     #[tool(
-        description = "PREFER THIS OVER CHAINED GREPS FOR DEPENDENCY QUESTIONS. Explore the dependency graph starting from an entity. Traverses import, invocation, inheritance, composition, render, state-read/state-write, and dispatch edges. Use direction='downstream' to see what the entity calls, 'upstream' to see what calls it, 'both' for full picture. Replaces the manual \"grep for X, then grep each result, then grep those\" loop with one graph walk.",
+        description = "PREFER THIS OVER CHAINED GREPS FOR DEPENDENCY QUESTIONS. Explore the dependency graph starting from an entity. Traverses import, invocation, inheritance, composition, render, state-read/state-write, and dispatch edges. Use direction='downstream' to see what the entity calls, 'upstream' to see what calls it, 'both' for full picture. Replaces the manual \"grep for X, then grep each result, then grep those\" loop with one graph walk. A batch that runs past `navigation.max_tool_seconds` returns the entities explored so far plus remaining_entity_ids to resume with.",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
     async fn explore_rpg(
@@ -227,6 +453,21 @@ impl RpgServer {
             .as_deref()
             .map(parse_entity_type_filter)
             .filter(|v| !v.is_empty());
+        let layer_filter = params
+            .layer_filter
+            .as_deref()
+            .map(parse_layer_filter)
+            .filter(|v| !v.is_empty());
+        let tag_filter = params
+            .tag_filter
+            .as_deref()
+            .map(parse_tag_list)
+            .filter(|v| !v.is_empty());
+        let neighbor_filter = rpg_nav::explore::NeighborFilter {
+            kinds: entity_type_filter.as_deref(),
+            layers: layer_filter.as_deref(),
+            tags: tag_filter.as_deref(),
+        };
 
         let ids: Vec<&str> = if let Some(ref batch) = params.entity_ids {
             batch.iter().map(|s| s.as_str()).collect()
@@ -235,18 +476,25 @@ impl RpgServer {
         };
 
         let use_compact = matches!(params.format.as_deref(), Some("compact"));
+        let max_tool_seconds = self.config.read().await.navigation.max_tool_seconds;
+        let deadline = rpg_nav::budget::deadline(max_tool_seconds);
 
         let mut outputs = Vec::new();
+        let mut completed = 0;
         for id in &ids {
-            match rpg_nav::explore::explore_filtered(
+            if rpg_nav::budget::expired(deadline) {
+                break;
+            }
+            match rpg_nav::explore::explore_within(
                 graph,
                 id,
                 dir,
                 max_depth,
                 edge_filter,
-                entity_type_filter.as_deref(),
+                neighbor_filter,
+                deadline,
             ) {
-                Some(tree) => {
+                Some((tree, finished)) => {
                     let formatted = if use_compact {
                         rpg_nav::explore::format_compact(&tree)
                     } else {
@@ -268,20 +516,35 @@ impl RpgServer {
                     } else {
                         outputs.push(formatted);
                     }
+                    // A cut-off traversal is shown but left for the resumed call.
+                    if !finished {
+                        outputs.push("... (partial: time budget exhausted)".to_string());
+                        break;
+                    }
                 }
                 None => outputs.push(format!("Entity not found: {}", id)),
             }
+            completed += 1;
+        }
+        if completed < ids.len() {
+            outputs.push(budget_exhausted_note(
+                "explore_rpg",
+                &ids[completed..],
+                ids.len(),
+                max_tool_seconds,
+            ));
         }
 
         if outputs.is_empty() {
             Err("No entities found".to_string())
         } else {
+            self.record_last_query("explore_rpg", &params).await;
             Ok(format!("{}{}", notice, outputs.join("\n")))
         }
     }
 
     #[tool(
-        description = "PREFER THIS OVER wc/find/tree FOR CODEBASE OVERVIEW. RPG statistics: entity count, file count, functional areas, dependency edges, containment edges, inter-area connectivity, hierarchy overview. Call this first on any new codebase to orient yourself before searching.",
+        description = "PREFER THIS OVER wc/find/tree FOR CODEBASE OVERVIEW. RPG statistics: entity count, file count, functional areas, dependency edges, containment edges, inter-area connectivity, hierarchy overview with each area's top code owners (from CODEOWNERS). Call this first on any new codebase to orient yourself before searching.",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
     async fn rpg_info(&self) -> Result<String, String> {
@@ -292,20 +555,36 @@ impl RpgServer {
         #[cfg(feature = "embeddings")]
         let emb_status = {
             let emb_guard = self.embedding_index.read().await;
-            if let Some(ref idx) = *emb_guard {
-                format!(
-                    "\nembedding_index: {} entities indexed (BGE-small-en-v1.5)",
-                    idx.entity_count()
+            let (mut status, last_validation) = if let Some(ref idx) = *emb_guard {
+                (
+                    format!(
+                        "\nembedding_index: {} entities indexed (BGE-small-en-v1.5)",
+                        idx.entity_count()
+                    ),
+                    idx.last_validation().cloned(),
                 )
             } else if self
                 .embedding_init_failed
                 .load(std::sync::atomic::Ordering::Relaxed)
             {
-                "\nembedding_index: init failed (lexical-only search)".to_string()
+                (
+                    "\nembedding_index: init failed (lexical-only search)".to_string(),
+                    None,
+                )
             } else {
-                "\nembedding_index: not initialized (will load on first semantic search)"
-                    .to_string()
+                (
+                    "\nembedding_index: not initialized (will load on first semantic search)"
+                        .to_string(),
+                    rpg_nav::embeddings::load_last_validation(&self.project_root().await),
+                )
+            };
+            if let Some(last) = last_validation {
+                status.push_str(&format!(
+                    "; last validation: {} at {}",
+                    last.summary, last.checked_at
+                ));
             }
+            status
         };
         #[cfg(not(feature = "embeddings"))]
         let emb_status = "\nembedding_index: disabled (compiled without embeddings feature)";
@@ -325,6 +604,33 @@ impl RpgServer {
         ))
     }
 
+    #[tool(
+        description = "CALL THIS FIRST IN A NEW SESSION. One-call orientation: graph status and staleness, hierarchy areas with one-line summaries (top features), detected entry points (main functions, routes, CLI commands), the 10 highest fan-in entities, and suggested next tools for common intents. Replaces the usual rpg_info → semantic_snapshot → search_node warm-up sequence.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn orient(&self, Parameters(params): Parameters<OrientParams>) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let request = rpg_nav::orient::OrientRequest {
+            token_budget: params.token_budget.unwrap_or(2_000),
+            ..Default::default()
+        };
+        let mut result = rpg_nav::orient::build_orientation(graph, &request);
+        result.status.staleness = self
+            .staleness_detail(graph)
+            .await
+            .map(|d| d.lines().next().unwrap_or_default().to_string());
+
+        Ok(format!(
+            "{}{}",
+            notice,
+            rpg_nav::toon::format_orientation(&result)
+        ))
+    }
+
     #[tool(
         description = "PREFER THIS OVER READING MANY FILES FOR WHOLE-REPO CONTEXT. Compact, token-efficient snapshot of the entire repository's semantic understanding: full hierarchy with aggregate features, all entities grouped by functional area with semantic features, condensed dependency skeleton, and coverage stats. Target: ~25-30K tokens for a 1000-entity codebase. Call this at session start to gain whole-repo awareness in a single tool call — then use search_node/fetch_node for drill-down.",
         annotations(read_only_hint = true, open_world_hint = false)
@@ -406,6 +712,205 @@ impl RpgServer {
         ))
     }
 
+    #[tool(
+        description = "Add user tags (e.g., \"deprecated\", \"security-sensitive\", \"hot-path\") to entities selected by entity_ids and/or scope. Tags are saved in the graph, survive updates and rebuilds, and can be filtered on with `tag:<name>` in scope expressions (search_node, lifting scopes) and with explore_rpg's tag_filter."
+    )]
+    async fn tag_entities(
+        &self,
+        Parameters(params): Parameters<TagEntitiesParams>,
+    ) -> Result<String, String> {
+        self.change_tags(params, true).await
+    }
+
+    #[tool(
+        description = "Remove user tags from entities selected by entity_ids and/or scope (e.g., scope=\"tag:deprecated\" to clear a tag everywhere)."
+    )]
+    async fn untag_entities(
+        &self,
+        Parameters(params): Parameters<TagEntitiesParams>,
+    ) -> Result<String, String> {
+        self.change_tags(params, false).await
+    }
+
+    #[tool(
+        description = "Set or clear the charter of a hierarchy node: a human-written statement of what the area is FOR (e.g., \"Everything related to persisting and migrating the graph; new storage backends go here\"), as opposed to its aggregated features, which describe what the code does. Charters are saved in the graph, kept across submit_hierarchy and rebuilds by path, shown by fetch_node and rpg_info, and included in hierarchy construction prompts."
+    )]
+    async fn set_area_charter(
+        &self,
+        Parameters(params): Parameters<SetAreaCharterParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let charter = params
+            .charter
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty());
+
+        let project_root = self.project_root().await;
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        if !graph.set_charter(&params.path, charter.clone()) {
+            return Err(format!(
+                "No hierarchy node at '{}'. Use rpg_info or explore_rpg to list areas.",
+                params.path
+            ));
+        }
+        storage::save(&project_root, graph).map_err(|e| format!("Failed to save RPG: {}", e))?;
+
+        Ok(match charter {
+            Some(_) => format!("Charter set for '{}'.", params.path),
+            None => format!("Charter cleared for '{}'.", params.path),
+        })
+    }
+
+    #[tool(
+        description = "Import key/value annotations from external metadata (e.g., last reviewer or PR number from PR metadata). Each entry targets an entity ID, or a file and [start, end] line range that resolves to the innermost entities it overlaps (the file's Module when it hits none). Values are stored in entity attributes as annotation.<source>.<key>, survive updates and rebuilds, and are shown by fetch_node."
+    )]
+    async fn annotate_entities(
+        &self,
+        Parameters(params): Parameters<AnnotateEntitiesParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let doc = rpg_encoder::annotations::parse_annotations(&params.annotations)
+            .map_err(|e| format!("{:#}", e))?;
+
+        let project_root = self.project_root().await;
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let report = rpg_encoder::annotations::apply_annotations(graph, &doc);
+        if report.values_set + report.values_removed > 0 {
+            storage::save(&project_root, graph)
+                .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        }
+        Ok(rpg_encoder::annotations::format_report(&report))
+    }
+
+    #[tool(
+        description = "Get one batch of the reconstruction plan as a task list: each entity's features, signature, and which of its dependencies are already reconstructed vs still pending. The plan and progress are saved in .rpg/reconstruction_state.json, so a new session resumes where the last one stopped. Omit batch_index to get the first unfinished batch. Call mark_reconstruction_done after reconstructing entities."
+    )]
+    async fn get_reconstruction_batch(
+        &self,
+        Parameters(params): Parameters<GetReconstructionBatchParams>,
+    ) -> Result<String, String> {
+        use rpg_encoder::reconstruction::{ReconstructionOptions, ReconstructionState};
+
+        self.ensure_graph().await?;
+        let project_root = self.project_root().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().ok_or("No RPG loaded")?;
+
+        if !graph.metadata.semantic_hierarchy {
+            return Err(
+                "No semantic hierarchy. Run the full lifting + hierarchy flow first.".into(),
+            );
+        }
+
+        let saved = if params.reset.unwrap_or(false) {
+            None
+        } else {
+            ReconstructionState::load(&project_root)
+                .map_err(|e| format!("Failed to load reconstruction state: {:#}", e))?
+        };
+        let mut notice = String::new();
+        let state = match saved {
+            Some(state) => state,
+            None => {
+                let options = ReconstructionOptions {
+                    max_batch_size: params.max_batch_size.unwrap_or(8).max(1),
+                    include_modules: params.include_modules.unwrap_or(false),
+                };
+                let state = ReconstructionState::new(graph, options);
+                state
+                    .save(&project_root)
+                    .map_err(|e| format!("Failed to save reconstruction state: {:#}", e))?;
+                notice = format!(
+                    "New reconstruction plan: {} entities, {} batches (max_batch_size: {}). Progress is saved in .rpg/reconstruction_state.json.\n\n",
+                    state.plan.topological_order.len(),
+                    state.plan.batches.len(),
+                    options.max_batch_size,
+                );
+                state
+            }
+        };
+
+        let batch = match params.batch_index {
+            Some(index) => state.batch(index).ok_or_else(|| {
+                format!(
+                    "Batch index {} out of range (1..={})",
+                    index,
+                    state.plan.batches.len()
+                )
+            })?,
+            None => match state.next_batch() {
+                Some(batch) => batch,
+                None => {
+                    return Ok(format!(
+                        "{}DONE — all {} entities in {} batches are reconstructed. Pass reset=true to schedule a new plan.",
+                        notice,
+                        state.plan.topological_order.len(),
+                        state.plan.batches.len(),
+                    ));
+                }
+            },
+        };
+
+        let mut out = notice;
+        out.push_str(&format_reconstruction_batch(graph, &state, batch));
+        out.push_str(
+            &self
+                .next_step(
+                    "mark_reconstruction_done",
+                    "\nNEXT: Reconstruct the pending entities in order, then call mark_reconstruction_done with their IDs.",
+                )
+                .await,
+        );
+        Ok(out)
+    }
+
+    #[tool(
+        description = "Record entities as reconstructed in .rpg/reconstruction_state.json. Pass the IDs from get_reconstruction_batch once their code is written. Reports overall progress and the next unfinished batch."
+    )]
+    async fn mark_reconstruction_done(
+        &self,
+        Parameters(params): Parameters<MarkReconstructionDoneParams>,
+    ) -> Result<String, String> {
+        let project_root = self.project_root().await;
+        let mut state = rpg_encoder::reconstruction::ReconstructionState::load(&project_root)
+            .map_err(|e| format!("Failed to load reconstruction state: {:#}", e))?
+            .ok_or("No reconstruction in progress. Call get_reconstruction_batch first.")?;
+
+        let before = state.done.len();
+        let unknown = state.mark_done(&params.entity_ids);
+        state
+            .save(&project_root)
+            .map_err(|e| format!("Failed to save reconstruction state: {:#}", e))?;
+
+        let mut out = format!(
+            "Marked {} entities done. progress: {}/{} done\n",
+            state.done.len() - before,
+            state.done.len(),
+            state.plan.topological_order.len(),
+        );
+        if !unknown.is_empty() {
+            out.push_str(&format!("not in plan: {}\n", unknown.join(", ")));
+        }
+        match state.next_batch() {
+            Some(batch) => out.push_str(
+                &self
+                    .next_step(
+                        "get_reconstruction_batch",
+                        &format!(
+                            "\nNEXT: Call get_reconstruction_batch (batch {} of {} has unfinished entities).",
+                            batch.batch_index,
+                            state.plan.batches.len(),
+                        ),
+                    )
+                    .await,
+            ),
+            None => out.push_str("All batches are reconstructed.\n"),
+        }
+        Ok(out)
+    }
+
     #[tool(
         description = "Switch the active project root for this session. Use this when the server was started from one directory but you want RPG to operate on a different project — e.g. the MCP server launched in your home directory but you want to work on ~/myproject. The server loads the graph from the new directory's .rpg/graph.json if present, resets all session state (lifting sessions, auto-sync markers, pending routing), and points every subsequent tool call at the new root. Path is tilde-expanded and canonicalized.",
         annotations(
@@ -470,6 +975,9 @@ impl RpgServer {
         // Reset all session + sync state — everything is project-scoped
         *self.lifting_session.write().await = None;
         *self.hierarchy_session.write().await = None;
+        self.lift_transactions.write().await.clear();
+        self.snippet_cache.write().await.clear();
+        self.output_store.write().await.clear();
         *self.pending_routing.write().await = load_pending_routing(&canonical)
             .map(|s| s.entries)
             .unwrap_or_default();
@@ -485,7 +993,9 @@ impl RpgServer {
         }
 
         // Load graph from the new root (if one exists there)
-        let loaded = rpg_core::storage::load(&canonical).ok();
+        let loaded = rpg_encoder::artifact::load_verified(&canonical)
+            .ok()
+            .map(|(graph, _)| graph);
         let graph_note = match &loaded {
             Some(g) => format!(
                 "Loaded existing graph: {} entities, {} files, {}",
@@ -509,55 +1019,178 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "Build an RPG (Repository Planning Graph) from the codebase. Indexes all code entities, builds a file-path hierarchy, and resolves dependencies. Completes in seconds without requiring an LLM. To add semantic features (LLM-extracted intent descriptions), use get_entities_for_lifting afterwards. Run this once when first connecting to a repository. Respects .rpgignore files (gitignore syntax) for excluding files from the graph.",
+        description = "Check the semantic-search embedding index against the graph and fix it. Reports orphan vectors (deleted or unlifted entities), lifted entities with no vectors, and vectors embedded from outdated features — all of which silently degrade hybrid search after heavy updates. mode='repair' (default) drops orphans and embeds only missing/mismatched entities; mode='full' discards the index and re-embeds every lifted entity; mode='check' only reports. The result shows up in rpg_info's embedding_index line.",
         annotations(
-            destructive_hint = true,
+            destructive_hint = false,
             idempotent_hint = true,
             open_world_hint = false
         )
     )]
-    async fn build_rpg(
+    async fn rebuild_embeddings(
         &self,
-        Parameters(params): Parameters<BuildRpgParams>,
+        Parameters(params): Parameters<RebuildEmbeddingsParams>,
     ) -> Result<String, String> {
-        use rpg_parser::languages::Language;
+        let mode = params.mode.as_deref().unwrap_or("repair");
+        if !matches!(mode, "check" | "repair" | "full") {
+            return Err(format!(
+                "unknown mode '{}' (expected repair, full, or check)",
+                mode
+            ));
+        }
 
-        let project_root = &self.project_root().await;
+        #[cfg(not(feature = "embeddings"))]
+        {
+            return Err("rebuild_embeddings is not available: this binary was compiled without the embeddings feature.".into());
+        }
 
-        // Detect languages (multi-language support)
-        let languages: Vec<Language> = if let Some(ref l) = params.language {
-            // User specified a single language override
-            let lang = Language::from_name(l)
-                .or_else(|| Language::from_extension(l))
-                .ok_or_else(|| format!("unsupported language: {}", l))?;
-            vec![lang]
-        } else {
-            let detected = Language::detect_all(project_root);
-            if detected.is_empty() {
+        #[cfg(feature = "embeddings")]
+        {
+            self.ensure_graph().await?;
+            let notice = self.auto_sync_if_stale().await;
+            let guard = self.graph.read().await;
+            let graph = guard.as_ref().unwrap();
+            self.try_init_embeddings(graph).await;
+            let mut emb_guard = self.embedding_index.write().await;
+            let Some(ref mut idx) = *emb_guard else {
                 return Err(
-                    "could not detect any supported language; specify the 'language' parameter"
-                        .to_string(),
+                    "embedding index failed to initialize (see server log); search stays lexical-only"
+                        .into(),
                 );
-            }
-            detected
-        };
+            };
 
-        // Auto-preserve: load existing graph if it has lifted features
-        let mut backup_failed = false;
-        let old_graph: Option<RPGraph> = if storage::rpg_exists(project_root) {
-            match storage::load(project_root) {
-                Ok(g) if g.metadata.lifted_entities > 0 => {
-                    // Backup before overwriting
-                    if let Err(e) = storage::create_backup(project_root) {
-                        eprintln!("rpg: WARNING: backup failed: {e}");
-                        backup_failed = true;
-                    }
-                    Some(g)
+            let before = rpg_nav::embeddings::validate(idx, graph);
+            let mut out = format!(
+                "{}=== Embedding Index ===\nmode: {}\nbefore: {}\n",
+                notice,
+                mode,
+                before.summary()
+            );
+            for (label, ids) in [
+                ("orphans", &before.orphans),
+                ("missing", &before.missing),
+                ("mismatched", &before.mismatched),
+            ] {
+                if ids.is_empty() {
+                    continue;
                 }
-                _ => None,
+                let shown: Vec<&str> = ids.iter().take(10).map(String::as_str).collect();
+                let more = if ids.len() > shown.len() {
+                    format!(", ... and {} more", ids.len() - shown.len())
+                } else {
+                    String::new()
+                };
+                out.push_str(&format!("  {}: {}{}\n", label, shown.join(", "), more));
             }
-        } else {
-            None
+
+            let stats = match mode {
+                "repair" => Some(idx.repair(graph, &before)),
+                "full" => Some(idx.rebuild(graph)),
+                _ => None,
+            };
+            if let Some(stats) = stats {
+                let stats = stats.map_err(|e| format!("embedding {} failed: {}", mode, e))?;
+                out.push_str(&format!(
+                    "kept: {}\nre-embedded: {}\nadded: {}\ndropped: {}\n",
+                    stats.kept, stats.changed, stats.added, stats.pruned,
+                ));
+            }
+
+            let after = rpg_nav::embeddings::validate(idx, graph);
+            if mode != "check" {
+                out.push_str(&format!("after: {}\n", after.summary()));
+            }
+            idx.record_validation(&after);
+            idx.save()
+                .map_err(|e| format!("failed to save embedding index: {}", e))?;
+            Ok(out)
+        }
+    }
+
+    #[tool(
+        description = "Change a server-wide setting for this session. Keys: 'guidance' — 'full' (default; NEXT STEP blocks with dispatch recipes), 'terse' (a single `next: <tool>` line), or 'off' (no next-step guidance). Overrides `[mcp] guidance` in .rpg/config.toml and persists across reload_rpg and set_project_root until the server restarts. Protocol lines that carry arguments (batch indexes, DONE, routing revisions) are always emitted.",
+        annotations(
+            destructive_hint = false,
+            idempotent_hint = true,
+            open_world_hint = false
+        )
+    )]
+    async fn set_config(
+        &self,
+        Parameters(params): Parameters<SetConfigParams>,
+    ) -> Result<String, String> {
+        match params.key.trim() {
+            "guidance" => {
+                let mode: rpg_core::config::GuidanceMode = params
+                    .value
+                    .parse()
+                    .map_err(|e: anyhow::Error| e.to_string())?;
+                *self.guidance_override.write().await = Some(mode);
+                Ok(format!("guidance: {}", mode.as_str()))
+            }
+            other => Err(format!(
+                "unknown config key '{}' (supported: guidance)",
+                other
+            )),
+        }
+    }
+
+    #[tool(
+        description = "Build an RPG (Repository Planning Graph) from the codebase. Indexes all code entities, builds a file-path hierarchy, and resolves dependencies. Completes in seconds without requiring an LLM. To add semantic features (LLM-extracted intent descriptions), use get_entities_for_lifting afterwards. Run this once when first connecting to a repository. Respects .rpgignore files (gitignore syntax) for excluding files from the graph, and skips files .gitattributes marks linguist-vendored, linguist-generated, or linguist-documentation.",
+        annotations(
+            destructive_hint = true,
+            idempotent_hint = true,
+            open_world_hint = false
+        )
+    )]
+    async fn build_rpg(
+        &self,
+        Parameters(params): Parameters<BuildRpgParams>,
+    ) -> Result<String, String> {
+        use rpg_parser::languages::Language;
+
+        let project_root = &self.project_root().await;
+
+        // A file is kept if it matches any include pattern (or there are
+        // none) and no exclude pattern.
+        let include = params.include.unwrap_or_default();
+        let exclude = params.exclude.unwrap_or_default();
+        let include_glob = build_glob_set(&include)?;
+        let exclude_glob = build_glob_set(&exclude)?;
+
+        // Detect languages (multi-language support)
+        let languages: Vec<Language> = if let Some(ref l) = params.language {
+            // User specified a single language override
+            let lang = Language::from_name(l)
+                .or_else(|| Language::from_extension(l))
+                .ok_or_else(|| format!("unsupported language: {}", l))?;
+            vec![lang]
+        } else {
+            let detected = Language::detect_all(project_root);
+            if detected.is_empty() {
+                return Err(
+                    "could not detect any supported language; specify the 'language' parameter"
+                        .to_string(),
+                );
+            }
+            detected
+        };
+
+        // Auto-preserve: load existing graph if it has lifted features
+        let mut backup_failed = false;
+        let old_graph: Option<RPGraph> = if storage::rpg_exists(project_root) {
+            match storage::load(project_root) {
+                Ok(g) if g.metadata.lifted_entities > 0 => {
+                    // Backup before overwriting
+                    if let Err(e) = storage::create_backup(project_root) {
+                        eprintln!("rpg: WARNING: backup failed: {e}");
+                        backup_failed = true;
+                    }
+                    Some(g)
+                }
+                _ => None,
+            }
+        } else {
+            None
         };
 
         let primary = languages[0].name();
@@ -565,15 +1198,16 @@ impl RpgServer {
         graph.metadata.languages = languages.iter().map(|l| l.name().to_string()).collect();
 
         // Load TOML paradigm definitions + compile tree-sitter queries
-        let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs().map_err(|errs| {
-            format!(
-                "paradigm definition errors: {}",
-                errs.iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<_>>()
-                    .join("; ")
-            )
-        })?;
+        let paradigm_defs =
+            rpg_parser::paradigms::defs::load_defs(project_root).map_err(|errs| {
+                format!(
+                    "paradigm definition errors: {}",
+                    errs.iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                )
+            })?;
         let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs)
             .map_err(|errs| format!("query compile errors: {}", errs.join("; ")))?;
 
@@ -583,28 +1217,21 @@ impl RpgServer {
         graph.metadata.paradigms = active_defs.iter().map(|d| d.name.clone()).collect();
 
         // Parse code entities (all detected languages)
-        let include_glob = params
-            .include
-            .as_deref()
-            .and_then(|p| globset::Glob::new(p).ok().map(|g| g.compile_matcher()));
-        let exclude_glob = params
-            .exclude
-            .as_deref()
-            .and_then(|p| globset::Glob::new(p).ok().map(|g| g.compile_matcher()));
-
-        let walker = ignore::WalkBuilder::new(project_root)
-            .hidden(true)
-            .git_ignore(true)
-            .add_custom_ignore_filename(".rpgignore")
-            .build();
+        let cfg = self.load_config().await;
+        let linguist =
+            rpg_encoder::gitattributes::LinguistAttributes::load(project_root, &cfg.encoding);
+        let mut linguist_skipped = 0;
+        let mut parse_diagnostics = Vec::new();
+        graph.metadata.nested_repos =
+            rpg_encoder::nested_repos::find_nested_repos(project_root, &cfg.encoding);
+        let walker = rpg_encoder::nested_repos::source_walker(project_root, &cfg.encoding);
 
         for entry in walker.flatten() {
             let path = entry.path();
             if !path.is_file() {
                 continue;
             }
-            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            let Some(file_lang) = Language::from_extension(ext) else {
+            let Some(file_lang) = Language::from_path(path) else {
                 continue;
             };
             if !languages.contains(&file_lang) {
@@ -621,14 +1248,21 @@ impl RpgServer {
             {
                 continue;
             }
+            if linguist.excludes(rel_path_for_glob) {
+                linguist_skipped += 1;
+                continue;
+            }
 
             let Ok(source) = std::fs::read_to_string(path) else {
                 continue;
             };
 
             let rel_path = path.strip_prefix(project_root).unwrap_or(path);
-            let mut raw_entities =
-                rpg_parser::entities::extract_entities(rel_path, &source, file_lang);
+            let (mut raw_entities, diagnostic) =
+                rpg_parser::entities::extract_entities_with_diagnostics(
+                    rel_path, &source, file_lang,
+                );
+            parse_diagnostics.extend(diagnostic);
 
             // TOML-driven paradigm pipeline: classify + entity queries + builtin features
             rpg_parser::paradigms::classify::classify_entities(
@@ -658,19 +1292,25 @@ impl RpgServer {
             }
         }
 
+        graph.metadata.parse_errors = rpg_parser::diagnostics::error_counts(&parse_diagnostics);
+
         // Create Module entities for file-level nodes (paper §3.1)
         graph.create_module_entities();
 
         // Structural hierarchy from file paths (no LLM needed)
-        graph.build_file_path_hierarchy();
+        let packages = rpg_core::structure::detect_packages(project_root);
+        graph.build_file_path_hierarchy_with_packages(&packages);
+        rpg_encoder::ownership::assign_owners(&mut graph, project_root);
+        rpg_encoder::layers::assign_layers(&mut graph, project_root, None);
 
         // Hierarchy enrichment
+        let cfg = self.load_config().await;
+        graph.metadata.max_node_features = cfg.encoding.max_node_features;
         graph.assign_hierarchy_ids();
         graph.aggregate_hierarchy_features();
         graph.materialize_containment_edges();
 
         // Artifact grounding + dependency resolution
-        let cfg = self.load_config().await;
         let paradigm_ctx = rpg_encoder::grounding::ParadigmContext {
             active_defs: active_defs.clone(),
             qcache: &qcache,
@@ -684,11 +1324,18 @@ impl RpgServer {
         );
         rpg_encoder::grounding::ground_hierarchy(&mut graph);
         rpg_encoder::grounding::resolve_dependencies(&mut graph);
+        rpg_nav::orient::assign_entry_distances(&mut graph);
+        let import_cycles = rpg_encoder::grounding::record_import_cycles(
+            &mut graph,
+            cfg.encoding.ignore_same_directory_import_cycles,
+        );
+        graph.set_liftable_kinds(&cfg.encoding.liftable_kinds);
 
         // Set git commit
         if let Ok(sha) = rpg_encoder::evolution::get_head_sha(project_root) {
             graph.base_commit = Some(sha);
         }
+        graph.metadata.repo_fingerprint = rpg_encoder::artifact::repo_fingerprint(project_root);
 
         // Merge old features, hierarchy paths, and module features (auto-preservation)
         let merge_stats = if let Some(ref old) = old_graph {
@@ -702,15 +1349,27 @@ impl RpgServer {
                 graph.aggregate_hierarchy_features();
                 graph.materialize_containment_edges();
             }
+            // Charters are human-written: carry them over by path
+            graph.restore_charters(&old.hierarchy_charters());
 
             Some(stats)
         } else {
             None
         };
 
+        // Tag fixtures after merging, which restores the previous graph's tags
+        let fixture_files =
+            rpg_encoder::fixtures::tag_fixtures(&mut graph, &cfg.encoding.fixture_globs);
+
         // Compute DataFlow edges after merge_features (which may restore signatures)
         rpg_encoder::dataflow::compute_data_flow_edges(&mut graph);
 
+        // Source signatures for source-similarity duplicate detection (opt-in)
+        if cfg.encoding.source_signatures {
+            rpg_nav::duplication::compute_source_signatures(&mut graph, project_root, None);
+            graph.metadata.source_signatures = true;
+        }
+
         // Refresh metadata and save
         graph.refresh_metadata();
         storage::save(project_root, &graph).map_err(|e| format!("Failed to save RPG: {}", e))?;
@@ -790,6 +1449,7 @@ impl RpgServer {
              languages: {}\n\
              entities: {}\n\
              files: {}\n\
+             fixture_files: {} (indexed, not lifted; encoding.fixture_globs)\n\
              functional_areas: {}\n\
              dependency_edges: {}\n\
              containment_edges: {}\n\
@@ -798,6 +1458,7 @@ impl RpgServer {
             lang_display,
             meta.total_entities,
             meta.total_files,
+            fixture_files,
             meta.functional_areas,
             meta.dependency_edges,
             meta.containment_edges,
@@ -805,10 +1466,38 @@ impl RpgServer {
             total_non_module,
             hierarchy_label,
         );
+        if !include.is_empty() {
+            result.push_str(&format!("\ninclude: {}", include.join(", ")));
+        }
+        if !exclude.is_empty() {
+            result.push_str(&format!("\nexclude: {}", exclude.join(", ")));
+        }
+        if linguist_skipped > 0 {
+            result.push_str(&format!(
+                "\nskipped_files: {} (vendored/generated/documentation per .gitattributes; encoding.honor_gitattributes)",
+                linguist_skipped
+            ));
+        }
+        let skipped_repos: Vec<&str> = meta
+            .nested_repos
+            .iter()
+            .filter(|r| !r.indexed)
+            .map(|r| r.path.as_str())
+            .collect();
+        if !skipped_repos.is_empty() {
+            result.push_str(&format!(
+                "
+skipped_nested_repos: {} (encoding.include_submodules)",
+                skipped_repos.join(", ")
+            ));
+        }
 
         if let Some(ref stats) = merge_stats {
-            let total_restored =
-                stats.features_restored + stats.modules_restored + stats.hierarchy_restored;
+            let total_restored = stats.features_restored
+                + stats.modules_restored
+                + stats.hierarchy_restored
+                + stats.tags_restored
+                + stats.annotations_restored;
             if total_restored > 0 {
                 let backup_note = if backup_failed {
                     "backup FAILED"
@@ -820,18 +1509,41 @@ impl RpgServer {
                      features_restored: {}\n\
                      hierarchy_paths_restored: {}\n\
                      module_features_restored: {}\n\
+                     tags_restored: {}\n\
+                     annotations_restored: {}\n\
                      orphaned: {}\n\
                      new_entities: {}",
                     backup_note,
                     stats.features_restored,
                     stats.hierarchy_restored,
                     stats.modules_restored,
+                    stats.tags_restored,
+                    stats.annotations_restored,
                     stats.orphaned,
                     stats.new_entities,
                 ));
             }
         }
 
+        let resolution_warnings =
+            rpg_encoder::grounding::resolution_warnings(&meta.edge_resolution);
+        if !resolution_warnings.is_empty() {
+            result.push_str("\n\nDependency resolution warnings:");
+            for warning in &resolution_warnings {
+                result.push_str(&format!("\n  {}", warning));
+            }
+        }
+        if !import_cycles.is_empty() {
+            result.push_str(&format!("\n\nImport cycles: {}", import_cycles.len()));
+            for cycle in &import_cycles {
+                result.push_str(&format!("\n  {}", cycle.representation()));
+            }
+        }
+        parse_diagnostics.sort_by(|a, b| a.file.cmp(&b.file));
+        if let Some(report) = rpg_parser::diagnostics::format_report(&parse_diagnostics) {
+            result.push_str(&format!("\n\nParse errors: {}", report));
+        }
+
         // NEXT STEP — action-oriented, scale-aware. A build_rpg response is
         // usually the first RPG tool call in a session, so the agent reads
         // this before asking the user what to do next. A weak "tip" gets
@@ -840,25 +1552,31 @@ impl RpgServer {
         // unlifted graph — users notice this as "search doesn't find the
         // thing I know is there" — so the default is "lift now".
         let unlifted = total_non_module.saturating_sub(lifted_non_module);
-        if unlifted == 0 {
-            result.push_str(
-                "\n\nNEXT STEP: Graph is fully lifted. Semantic tools (search_node, context_pack, plan_change, explore_rpg) are ready — prefer them over grep/cat/find for any structural question.",
-            );
+        let (tool, block) = if unlifted == 0 {
+            (
+                "search_node",
+                "\n\nNEXT STEP: Graph is fully lifted. Semantic tools (search_node, context_pack, plan_change, explore_rpg) are ready — prefer them over grep/cat/find for any structural question.".to_string(),
+            )
         } else if unlifted >= crate::LARGE_SCOPE_ENTITIES {
             let batch_tokens = self.config.read().await.encoding.max_batch_tokens;
-            result.push_str(&format!(
+            let block = format!(
                 "\n\nNEXT STEP: {} entities unlifted (of {}). Dispatch a sub-agent now to run the lift loop — don't wait for the user to ask. Each batch is ~{}K tokens of source, so running the loop here would exhaust caller context before any real work begins.\n\
                  \nLOOP (sub-agent runs this in its own context):\n  \
                  get_entities_for_lifting(scope=\"*\") -> analyze batch -> submit_lift_results -> repeat until DONE -> finalize_lifting\n\
                  \nAfter the worker returns, call reload_rpg — some runtimes give sub-agents an isolated MCP session, in which case the caller's in-memory graph is stale until reloaded. Call lifting_status for per-state recommendations at any time.",
-                unlifted, total_non_module, batch_tokens.div_ceil(1000),
-            ));
+                unlifted,
+                total_non_module,
+                batch_tokens.div_ceil(1000),
+            );
+            ("get_entities_for_lifting", block)
         } else {
-            result.push_str(&format!(
+            let block = format!(
                 "\n\nNEXT STEP: {} entities unlifted (of {}). Lift now — don't wait for the user to ask; semantic search/fetch won't find unlifted entities by intent. Call get_entities_for_lifting(scope=\"*\"), analyze the batch, submit via submit_lift_results, repeat until DONE, then finalize_lifting.",
                 unlifted, total_non_module,
-            ));
-        }
+            );
+            ("get_entities_for_lifting", block)
+        };
+        result.push_str(&self.next_step(tool, &block).await);
         Ok(result)
     }
 
@@ -934,14 +1652,26 @@ impl RpgServer {
             .map_err(|e| format!("Failed to create LLM provider: {}", e))?;
 
             let scope = params.scope.as_deref().unwrap_or("*");
+            rpg_nav::scope::validate(scope).map_err(|e| e.to_string())?;
             let dry_run = params.dry_run.unwrap_or(false);
+            let tokens = self.config.read().await.tokens.clone();
+            let estimator = rpg_encoder::tokens::from_config(
+                &tokens,
+                Some(&params.provider),
+                &self.project_root().await,
+            );
 
             // Dry run: estimate cost without lifting
             if dry_run {
                 let project_root = self.project_root().await;
                 let guard = self.graph.read().await;
                 let graph = guard.as_ref().unwrap();
-                let estimate = rpg_lift::estimate_cost(graph, provider.as_ref(), &project_root);
+                let estimate = rpg_lift::estimate_cost(
+                    graph,
+                    provider.as_ref(),
+                    &project_root,
+                    estimator.as_ref(),
+                );
                 return Ok(format!(
                     "Cost estimate for lifting with {} ({}):\n\n{}",
                     params.provider,
@@ -961,17 +1691,19 @@ impl RpgServer {
             // Compute the in-scope entity IDs up front so we can drain
             // them from `stale_entity_ids` after the pipeline runs. For a
             // non-`*` scope the pipeline freshens features for every
-            // in-scope entity (the `*` scope auto-filters to feature-empty
-            // entities, but explicit scopes don't), so any stale entity
+            // in-scope entity (the `*` scope and scope expressions
+            // auto-filter to feature-empty entities, but explicit scopes
+            // don't), so any stale entity
             // in scope is no longer stale once the pipeline returns. We
             // drain *unconditionally* by ID rather than diffing features
             // before/after, because a deterministic re-lift can produce
             // identical features for a cosmetic source change — the
             // entity is still freshly lifted, just to the same value.
-            let in_scope_ids: HashSet<String> = {
-                let lift_scope = rpg_encoder::lift::resolve_scope(graph, scope);
-                lift_scope.entity_ids.into_iter().collect()
-            };
+            let in_scope_ids: HashSet<String> = rpg_encoder::lift::resolve_scope(graph, scope)
+                .map_err(|e| e.to_string())?
+                .entity_ids
+                .into_iter()
+                .collect();
 
             // Run the blocking pipeline on the current thread (tells tokio
             // we're blocking). Safe because (1) the `lift_in_progress`
@@ -987,9 +1719,11 @@ impl RpgServer {
                     max_retries: 2,
                     batch_size: 25,
                     batch_tokens: 8000,
+                    token_estimator: estimator.as_ref(),
                 };
                 let result = rpg_lift::run_pipeline(graph, &config);
                 let _ = rpg_core::storage::save(&project_root, graph);
+                let _ = rpg_nav::vocab::rebuild(&project_root, graph);
                 result
             })
             .map_err(|e| format!("Lift failed: {}", e))?;
@@ -1045,7 +1779,12 @@ impl RpgServer {
             }
 
             out.push_str(
-                "\n\nNEXT STEP: Call semantic_snapshot to see the full repo understanding.",
+                &self
+                    .next_step(
+                        "semantic_snapshot",
+                        "\n\nNEXT STEP: Call semantic_snapshot to see the full repo understanding.",
+                    )
+                    .await,
             );
             Ok(out)
         } // #[cfg(feature = "auto-lift")]
@@ -1071,20 +1810,61 @@ impl RpgServer {
             return self.format_lifting_status(graph).await;
         }
 
-        Ok(
-            "=== RPG Lifting Status ===\ngraph: not built\n\nNEXT STEP: Call build_rpg to index the codebase."
-                .to_string(),
-        )
+        Ok(format!(
+            "=== RPG Lifting Status ===\ngraph: not built{}",
+            self.next_step(
+                "build_rpg",
+                "\n\nNEXT STEP: Call build_rpg to index the codebase."
+            )
+            .await,
+        ))
+    }
+
+    #[tool(
+        description = "Where the graph's features came from before paying for a re-lift. Returns an area x feature_source matrix (auto, auto-review, llm, llm-review, synthesized, paradigm, unknown, unlifted) with counts of features older than 30 and 90 days, then a re-lift plan: areas ordered by auto-lift share and then churn, each with the entities a re-lift covers and the lifting batches they fill (same batching as get_entities_for_lifting).",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn feature_sources(
+        &self,
+        Parameters(params): Parameters<FeatureSourcesParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let (encoding, tokens) = {
+            let config = self.config.read().await;
+            (config.encoding.clone(), config.tokens.clone())
+        };
+        let project_root = self.project_root().await;
+        let estimator = rpg_encoder::tokens::from_config(&tokens, None, &project_root);
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let report = rpg_encoder::feature_sources::analyze(
+            graph,
+            &rpg_core::files::WorktreeFiles::new(&project_root),
+            &encoding,
+            estimator.as_ref(),
+            chrono::Utc::now(),
+        );
+        Ok(format!(
+            "{}## FEATURE SOURCES\n\n{}",
+            notice,
+            rpg_encoder::feature_sources::format_report(
+                &report,
+                params.max_plan_areas.unwrap_or(10)
+            )
+        ))
     }
 
     #[tool(
-        description = "LIFTER PROTOCOL step 1: Get a batch of code entities for YOU to semantically analyze. Returns source code with instructions. After analyzing ALL entities, call submit_lift_results with your features JSON, then check the NEXT_ACTION block and continue until DONE. Scope: file glob ('src/auth/**'), '*' for all unlifted, or hierarchy path. No LLM setup needed."
+        description = "LIFTER PROTOCOL step 1: Get a batch of code entities for YOU to semantically analyze. Returns source code with instructions. After analyzing ALL entities, call submit_lift_results with your features JSON, then check the NEXT_ACTION block and continue until DONE. Scope: file glob ('src/auth/**'), '*' for all unlifted, hierarchy path, or a scope expression ('area:Auth AND kind:function AND NOT glob:**/tests/**'). No LLM setup needed."
     )]
     async fn get_entities_for_lifting(
         &self,
         Parameters(params): Parameters<GetEntitiesForLiftingParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
+        rpg_nav::scope::validate(&params.scope).map_err(|e| e.to_string())?;
 
         let batch_index = params.batch_index.unwrap_or(0);
 
@@ -1118,118 +1898,37 @@ impl RpgServer {
                 let mut session = self.lifting_session.write().await;
                 let graph = guard.as_mut().ok_or("No RPG loaded")?;
 
-                let mut scope = rpg_encoder::lift::resolve_scope(graph, &params.scope);
-
-                // For the "*"/"all" scope, `resolve_scope` filters to entities
-                // with *no* features — which correctly captures unlifted
-                // entities but excludes stale ones (they still have their old
-                // features). Augment the scope with any tracked stale
-                // entities so a single get_entities_for_lifting(scope="*")
-                // call covers both "never lifted" and "lifted-but-outdated".
-                // For other scope kinds (glob, hierarchy path, id list),
-                // `resolve_scope` doesn't filter by lifted state, so any
-                // stale entity matching the scope is already present.
-                let params_scope_trimmed = params.scope.trim();
-                if params_scope_trimmed == "*" || params_scope_trimmed.eq_ignore_ascii_case("all") {
-                    let already: HashSet<&String> = scope.entity_ids.iter().collect();
-                    let to_add: Vec<String> = stale_snapshot
-                        .iter()
-                        .filter(|id| !already.contains(id))
-                        .filter(|id| {
-                            graph
-                                .entities
-                                .get(*id)
-                                .is_some_and(|e| e.kind != rpg_core::graph::EntityKind::Module)
-                        })
-                        .cloned()
-                        .collect();
-                    scope.entity_ids.extend(to_add);
-                }
+                // Per-area settings decide auto-lift and each entity's batch cap.
+                let (encoding, tokens) = {
+                    let config = self.config.read().await;
+                    (config.encoding.clone(), config.tokens.clone())
+                };
+                let project_root = self.project_root().await;
+                let estimator = rpg_encoder::tokens::from_config(&tokens, None, &project_root);
+                let queue = rpg_encoder::lift_queue::build_lift_queue(
+                    graph,
+                    &params.scope,
+                    &project_root,
+                    &encoding,
+                    &stale_snapshot,
+                    estimator.as_ref(),
+                )
+                .map_err(|e| format!("Failed to collect entities: {}", e))?;
 
-                if scope.entity_ids.is_empty() {
+                if queue.matched == 0 {
                     *session = None;
                     return Ok(format!(
                         "No entities matched scope: {}\nTry a file glob like 'src/**' or '*' for all.",
                         params.scope
                     ));
                 }
-
-                let all_raw_entities = rpg_encoder::lift::collect_raw_entities(
-                    graph,
-                    &scope,
-                    &self.project_root().await,
-                )
-                .map_err(|e| format!("Failed to collect entities: {}", e))?;
-
-                if all_raw_entities.is_empty() {
+                if queue.collected == 0 {
                     *session = None;
                     return Ok("No source code found for matched entities.".into());
                 }
 
-                // Auto-lift trivial entities (getters, setters, constructors, etc.)
-                let paradigm_defs =
-                    rpg_parser::paradigms::defs::load_builtin_defs().unwrap_or_default();
-                let engine = rpg_encoder::lift::AutoLiftEngine::new(
-                    &paradigm_defs,
-                    &graph.metadata.paradigms,
-                );
-                let mut auto_lifted = 0usize;
-                let mut needs_llm = Vec::new();
-                let mut review_candidates: Vec<(String, Vec<String>)> = Vec::new();
-                // Track which stale entities got re-lifted (either via auto-lift
-                // above or routed into needs_llm below) so we can drain them
-                // from `stale_entity_ids` once they're written. Auto-lift
-                // persists its features directly inside this function, so it
-                // must drain the set itself; needs_llm entities are drained
-                // in `submit_lift_results` after the caller submits features.
-                let mut auto_relifted_stale: Vec<String> = Vec::new();
-                for raw in all_raw_entities {
-                    let raw_id = raw.id();
-                    // Stale entities get re-lifted regardless of existing
-                    // features — their features are known-outdated because
-                    // the source was modified after the previous lift.
-                    let is_stale = stale_snapshot.contains(&raw_id);
-                    // Otherwise, skip entities that already have curated features.
-                    let already_lifted = !is_stale
-                        && graph
-                            .entities
-                            .get(&raw_id)
-                            .is_some_and(|e| !e.semantic_features.is_empty());
-                    if already_lifted {
-                        continue;
-                    }
-                    match engine.try_lift_with_confidence(&raw) {
-                        Some((features, rpg_encoder::lift::LiftConfidence::Accept)) => {
-                            // High confidence — apply features directly
-                            if let Some(entity) = graph.entities.get_mut(&raw_id) {
-                                entity.semantic_features = features;
-                                entity.feature_source = Some("auto".to_string());
-                                auto_lifted += 1;
-                                if is_stale {
-                                    auto_relifted_stale.push(raw_id.clone());
-                                }
-                            }
-                        }
-                        Some((features, rpg_encoder::lift::LiftConfidence::Review)) => {
-                            // Medium confidence — apply features but flag for review
-                            if let Some(entity) = graph.entities.get_mut(&raw_id) {
-                                entity.semantic_features = features.clone();
-                                entity.feature_source = Some("auto".to_string());
-                                auto_lifted += 1;
-                                if is_stale {
-                                    auto_relifted_stale.push(raw_id.clone());
-                                }
-                            }
-                            review_candidates.push((raw_id, features));
-                        }
-                        Some((_, rpg_encoder::lift::LiftConfidence::Reject)) | None => {
-                            needs_llm.push(raw);
-                        }
-                    }
-                }
-
                 // Save if we auto-lifted anything
-                if auto_lifted > 0 {
+                if queue.auto_lifted > 0 {
                     graph.refresh_metadata();
                     if let Err(e) = rpg_core::storage::save(&self.project_root().await, graph) {
                         eprintln!("Warning: failed to persist auto-lifted features: {e}");
@@ -1239,44 +1938,40 @@ impl RpgServer {
                 // Drain stale tracking for entities auto-lifter just wrote
                 // fresh features for. Without this, lifting_status would keep
                 // counting them as stale forever because the auto-lift path
-                // skips submit_lift_results entirely.
-                if !auto_relifted_stale.is_empty() {
+                // skips submit_lift_results entirely. Entities queued for the
+                // LLM are drained in `submit_lift_results`.
+                if !queue.auto_relifted_stale.is_empty() {
                     let mut stale = self.stale_entity_ids.write().await;
-                    for id in &auto_relifted_stale {
+                    for id in &queue.auto_relifted_stale {
                         stale.remove(id);
                     }
                 }
 
-                if needs_llm.is_empty() {
+                if queue.raw_entities.is_empty() {
                     *session = None;
                     let (lifted, total) = graph.lifting_coverage();
+                    let boilerplate = if queue.boilerplate.is_empty() {
+                        String::new()
+                    } else {
+                        format!(
+                            "\nBOILERPLATE: {}",
+                            rpg_encoder::lift_queue::format_boilerplate_counts(&queue.boilerplate)
+                        )
+                    };
                     return Ok(format!(
-                        "AUTO-LIFTED: {} trivial entities. No entities need LLM analysis.\ncoverage: {}/{}\nNEXT: Call finalize_lifting.",
-                        auto_lifted, lifted, total,
+                        "AUTO-LIFTED: {} trivial entities. No entities need LLM analysis.{}\ncoverage: {}/{}{}",
+                        queue.auto_lifted,
+                        boilerplate,
+                        lifted,
+                        total,
+                        self.next_step("finalize_lifting", "\nNEXT: Call finalize_lifting.")
+                            .await,
                     ));
                 }
 
-                let config = self.config.read().await;
-                let batch_size = config.encoding.batch_size;
-                let max_batch_tokens = config.encoding.max_batch_tokens;
-                drop(config);
-
-                let mcp_batch_size = batch_size.min(25);
-                let batch_ranges = rpg_encoder::lift::build_token_aware_batches(
-                    &needs_llm,
-                    mcp_batch_size,
-                    max_batch_tokens,
-                );
-
-                // Store auto-lift count for batch 0 output
-                let auto_lift_count = auto_lifted;
-
                 *session = Some(LiftingSession {
                     scope_key: params.scope.clone(),
-                    raw_entities: needs_llm,
-                    batch_ranges,
-                    auto_lifted: auto_lift_count,
-                    review_candidates,
+                    queue,
                 });
             }
         }
@@ -1289,34 +1984,17 @@ impl RpgServer {
             return Err("Lifting session expired. Call get_entities_for_lifting with batch_index=0 to restart.".into());
         };
 
-        let total_batches = session.batch_ranges.len();
-        if batch_index >= total_batches {
+        let queue = &session.queue;
+        let total_batches = queue.batch_ranges.len();
+        let Some(batch) = queue.batch(batch_index) else {
             return Ok(format!(
                 "DONE — all {} batches processed. No more entities to lift for this scope.",
                 total_batches
             ));
-        }
-
-        let (batch_start, batch_end) = session.batch_ranges[batch_index];
-        let batch = &session.raw_entities[batch_start..batch_end];
-
-        let (lifted, total) = graph.lifting_coverage();
+        };
 
-        let module_count = graph
-            .entities
-            .values()
-            .filter(|e| e.kind == rpg_core::graph::EntityKind::Module)
-            .count();
-        let auto_lifted_count = session.auto_lifted;
-        let mut output = format!(
-            "BATCH {}/{} ({} entities) | coverage: {}/{} (excludes {} modules)\n",
-            batch_index + 1,
-            total_batches,
-            batch.len(),
-            lifted,
-            total,
-            module_count,
-        );
+        let mut output =
+            rpg_encoder::lift_queue::format_batch_header(graph, queue, batch_index, batch.len());
 
         // Only include repo context and full instructions on batch 0 to save context space
         if batch_index == 0 {
@@ -1333,58 +2011,18 @@ impl RpgServer {
                 ));
             }
 
-            if auto_lifted_count > 0 {
-                output.push_str(&format!(
-                    "AUTO-LIFTED: {} trivial entities (getters/setters/constructors). Override by re-submitting features.\n\n",
-                    auto_lifted_count,
-                ));
-            }
-
-            // Show review candidates — auto-lifted with medium confidence
-            output.push_str(&crate::types::format_review_candidates(
-                &session.review_candidates,
-            ));
             let root = self.project_root().await;
             let project_name = root
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or("unknown");
-            let repo_info = rpg_encoder::lift::generate_repo_info(graph, project_name);
-            output.push_str(&repo_info);
-            output.push_str("\n\n");
-            output.push_str(rpg_encoder::semantic_lifting::SEMANTIC_PARSING_SYSTEM);
-            output.push('\n');
-
-            // Inject paradigm-specific lifting hints
-            let lifting_hints =
-                Self::collect_paradigm_hints(&graph.metadata.paradigms, |h| &h.lifting);
-            if !lifting_hints.is_empty() {
-                output.push_str("\n## Framework-Specific Guidelines\n\n");
-                output.push_str(&lifting_hints);
-            }
-        }
-        output.push_str("\n## Code\n\n");
-
-        // Truncate source to prevent context overflow — signature + key logic
-        // is enough for semantic feature extraction.
-        for entity in batch {
-            let truncated = truncate_source(&entity.source_text, 40);
-            output.push_str(&format!(
-                "### {} ({:?})\n```\n{}\n```\n",
-                entity.id(),
-                entity.kind,
-                truncated,
+            output.push_str(&rpg_encoder::lift_queue::format_batch_preamble(
+                graph,
+                queue,
+                project_name,
             ));
-            // Append compact dependency context when available
-            if let Some(graph_entity) = graph.entities.get(&entity.id()) {
-                let dep_line = format_dep_context(&graph_entity.deps);
-                if !dep_line.is_empty() {
-                    output.push_str(&dep_line);
-                    output.push('\n');
-                }
-            }
-            output.push('\n');
         }
+        output.push_str(&rpg_encoder::lift_queue::format_batch_code(graph, batch));
 
         output.push_str(
             "Submit: call `submit_lift_results` with JSON keys exactly as shown in the ### headers above (e.g., `{\"src/lib.rs:MyStruct::method\": [\"feature1\", ...]}`).\n\n"
@@ -1405,7 +2043,7 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "LIFTER PROTOCOL step 2: Submit semantic features you extracted. Pass a JSON object with keys exactly as shown by get_entities_for_lifting headers. For methods use file:Class::method format. Example: {\"src/main.rs:Server::new\": [\"create server\"], \"src/lib.rs:load\": [\"load config\"]}. After submitting, immediately proceed to the next batch — do NOT stop to ask the user."
+        description = "LIFTER PROTOCOL step 2: Submit semantic features you extracted. Pass a JSON object with keys exactly as shown by get_entities_for_lifting headers. For methods use file:Class::method format. Example: {\"src/main.rs:Server::new\": [\"create server\"], \"src/lib.rs:load\": [\"load config\"]}. For submissions too large for one message, send parts with the same transaction_id and final=true on the last one; nothing is applied until then. After submitting, immediately proceed to the next batch — do NOT stop to ask the user."
     )]
     async fn submit_lift_results(
         &self,
@@ -1417,110 +2055,66 @@ impl RpgServer {
             serde_json::from_str(&params.features)
                 .map_err(|e| format!("Invalid features JSON: {}", e))?;
 
-        // Normalize per paper: trim, lowercase, dedup
-        rpg_encoder::semantic_lifting::normalize_features(&mut features);
+        // Multi-call submission: buffer non-final parts, apply all on the final one
+        let mut transaction_parts = None;
+        if let Some(ref id) = params.transaction_id {
+            match self
+                .stage_lift_transaction(id, features, params.is_final.unwrap_or(false))
+                .await?
+            {
+                LiftTransactionStep::Buffered { parts, entries } => {
+                    return Ok(format!(
+                        "Buffered part {} of transaction '{}' ({} entries so far, nothing applied yet).\nNEXT: send the remaining parts with the same transaction_id, the last one with final=true.",
+                        parts, id, entries,
+                    ));
+                }
+                LiftTransactionStep::Complete {
+                    features: all,
+                    parts,
+                } => {
+                    features = all;
+                    transaction_parts = Some((id.clone(), parts));
+                }
+            }
+        }
+
+        let config = self.load_config().await;
+
+        // Normalize per paper (trim, lowercase, dedup), then split compound
+        // features and apply the configured limits.
+        let normalization = rpg_encoder::semantic_lifting::normalize_features(
+            &mut features,
+            &config.encoding.feature_normalization,
+        );
 
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
 
-        let config = self.load_config().await;
-        let drift_ignore = config.encoding.drift_ignore_threshold;
-        let drift_auto = config.encoding.drift_auto_threshold;
+        let applied = rpg_encoder::lift_queue::apply_features(graph, &features, &config.encoding);
+        let updated = applied.updated;
+        let unmatched = applied.unmatched.len();
+        let drift_reports = applied.drift_reports;
+        let auto_route_ids = applied.drifted;
+        let borderline_ids = applied.borderline;
+        let newly_lifted_ids = applied.newly_lifted;
+        let copied_feature_sets = applied.copied;
+        // Resolved entity_id → features for embedding update (canonical IDs)
+        let resolved_features = applied.resolved;
 
-        let mut updated = 0usize;
-        let mut unmatched = 0usize;
-        let mut drift_reports: Vec<String> = Vec::new();
-        let mut auto_route_ids: Vec<String> = Vec::new();
-        let mut borderline_ids: Vec<(String, f64)> = Vec::new();
-        let mut newly_lifted_ids: Vec<String> = Vec::new();
-        // Track resolved entity_id → features for embedding update (canonical IDs)
-        let mut resolved_features: std::collections::HashMap<String, Vec<String>> =
-            std::collections::HashMap::new();
+        // Accumulate entities needing routing into pending state (LLM-based routing).
+        // Instead of auto-routing via Jaccard, we store candidates and let the agent
+        // make routing decisions via get_routing_candidates + submit_routing_decisions.
+        let needs_routing = graph.metadata.semantic_hierarchy
+            && (!auto_route_ids.is_empty()
+                || !borderline_ids.is_empty()
+                || !newly_lifted_ids.is_empty());
 
-        for (key, feats) in &features {
-            if feats.is_empty() {
-                continue;
-            }
+        let routing_count;
+        if needs_routing {
+            let mut pending = self.pending_routing.write().await;
 
-            // Find matching entities: direct ID match first, then file:name scan (all matches)
-            let entity_ids: Vec<String> = if graph.entities.contains_key(key) {
-                vec![key.clone()]
-            } else if let Some((file_part, name_part)) = key.rsplit_once(':') {
-                graph
-                    .entities
-                    .iter()
-                    .filter(|(_, e)| {
-                        e.name == name_part && e.file.to_string_lossy().as_ref() == file_part
-                    })
-                    .map(|(id, _)| id.clone())
-                    .collect()
-            } else {
-                vec![]
-            };
-
-            if entity_ids.is_empty() {
-                unmatched += 1;
-            } else {
-                for eid in &entity_ids {
-                    // Three-zone drift detection (paper Algorithm 3):
-                    // < drift_ignore: minor edit, in-place update
-                    // drift_ignore..drift_auto: borderline, ask agent to judge
-                    // > drift_auto: clear drift, auto-route
-                    let old_feats = graph
-                        .entities
-                        .get(eid)
-                        .map(|e| e.semantic_features.clone())
-                        .unwrap_or_default();
-
-                    if !old_feats.is_empty() {
-                        let drift = rpg_encoder::evolution::compute_drift(&old_feats, feats);
-                        if drift > drift_auto {
-                            drift_reports.push(format!(
-                                "  {} drifted ({:.2}) — routing required",
-                                eid, drift
-                            ));
-                            auto_route_ids.push(eid.clone());
-                        } else if drift >= drift_ignore {
-                            drift_reports.push(format!(
-                                "  {} borderline drift ({:.2}) — agent review requested",
-                                eid, drift,
-                            ));
-                            borderline_ids.push((eid.clone(), drift));
-                        } else if drift > 0.0 {
-                            drift_reports.push(format!(
-                                "  {} updated ({:.2} drift, below threshold)",
-                                eid, drift,
-                            ));
-                        }
-                    } else {
-                        // First-time lift — candidate for semantic routing
-                        newly_lifted_ids.push(eid.clone());
-                    }
-
-                    if let Some(entity) = graph.entities.get_mut(eid) {
-                        entity.semantic_features = feats.clone();
-                        entity.feature_source = Some("llm".to_string());
-                        resolved_features.insert(eid.clone(), feats.clone());
-                        updated += 1;
-                    }
-                }
-            }
-        }
-
-        // Accumulate entities needing routing into pending state (LLM-based routing).
-        // Instead of auto-routing via Jaccard, we store candidates and let the agent
-        // make routing decisions via get_routing_candidates + submit_routing_decisions.
-        let needs_routing = graph.metadata.semantic_hierarchy
-            && (!auto_route_ids.is_empty()
-                || !borderline_ids.is_empty()
-                || !newly_lifted_ids.is_empty());
-
-        let routing_count;
-        if needs_routing {
-            let mut pending = self.pending_routing.write().await;
-
-            for eid in &auto_route_ids {
-                let original_path = graph
+            for eid in &auto_route_ids {
+                let original_path = graph
                     .entities
                     .get(eid)
                     .map(|e| e.hierarchy_path.clone())
@@ -1624,7 +2218,16 @@ impl RpgServer {
             0.0
         };
 
-        let mut result = format!(
+        let mut result = match transaction_parts {
+            Some((id, parts)) => format!(
+                "Transaction '{}' complete ({} part{}).\n",
+                id,
+                parts,
+                if parts == 1 { "" } else { "s" }
+            ),
+            None => String::new(),
+        };
+        result.push_str(&format!(
             "Applied {} feature sets ({} matched, {} unmatched).\ncoverage: {}/{} ({:.0}%)",
             features.len(),
             updated,
@@ -1632,7 +2235,7 @@ impl RpgServer {
             lifted,
             total,
             coverage_pct,
-        );
+        ));
         if unmatched > 0 {
             result.push_str("\nNote: Unmatched keys must match headers from get_entities_for_lifting (e.g., \"src/main.rs:MyStruct::method\" for methods).");
         }
@@ -1668,7 +2271,13 @@ impl RpgServer {
             if !quality_output.is_empty() {
                 result.push_str(&quality_output);
             }
+            result.push_str(&rpg_encoder::critic::format_copied_feature_sets(
+                &copied_feature_sets,
+            ));
         }
+        result.push_str(&rpg_encoder::semantic_lifting::format_normalization_report(
+            &normalization,
+        ));
 
         // Per-area coverage breakdown
         let area_cov = graph.area_coverage();
@@ -1738,13 +2347,127 @@ impl RpgServer {
             } else {
                 format!("{} unlifted", unlifted)
             };
-            result.push_str(&format!(
+            let block = format!(
                 "\nNEXT: {} entities still need LLM work ({}) — call lifting_status for the recommended re-lift dispatch (likely a sub-agent / cheaper model in your runtime). Continue here only if no dispatch mechanism is available.",
                 work_remaining, breakdown,
-            ));
+            );
+            result.push_str(&self.next_step("lifting_status", &block).await);
         } else {
-            result.push_str("\nNEXT: continue with get_entities_for_lifting, then call finalize_lifting when done.");
+            result.push_str(
+                &self
+                    .next_step(
+                        "get_entities_for_lifting",
+                        "\nNEXT: continue with get_entities_for_lifting, then call finalize_lifting when done.",
+                    )
+                    .await,
+            );
+        }
+        Ok(result)
+    }
+
+    #[tool(
+        description = "Spot-check lifted features for drift: samples lifted entities and returns each with its stored features and CURRENT source. Judge each as accurate / outdated / wrong and send the verdicts to submit_verification. Strategy: 'random' (default), 'stale' (least recently lifted first), or 'churn' (most changed first). The same seed on the same graph returns the same sample.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn verify_features(
+        &self,
+        Parameters(params): Parameters<VerifyFeaturesParams>,
+    ) -> Result<String, String> {
+        use rpg_encoder::verification::{self, SampleStrategy};
+
+        self.ensure_graph().await?;
+        let strategy = SampleStrategy::parse(params.strategy.as_deref().unwrap_or("random"))?;
+        let seed = params.seed.unwrap_or(0);
+        let count = params
+            .count
+            .unwrap_or(verification::DEFAULT_SAMPLE_SIZE)
+            .max(1);
+
+        let project_root = self.project_root().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().ok_or("No RPG loaded")?;
+        let sample = verification::sample_lifted(graph, count, strategy, seed);
+        if sample.is_empty() {
+            return Err(
+                "No lifted entities to verify. Lift entities with get_entities_for_lifting first."
+                    .into(),
+            );
+        }
+        let sources = rpg_encoder::lift::collect_raw_entities(
+            graph,
+            &rpg_encoder::lift::LiftScope {
+                entity_ids: sample.clone(),
+            },
+            &project_root,
+        )
+        .map_err(|e| format!("Failed to read entity source: {}", e))?;
+        Ok(verification::format_sample(
+            graph, &sample, &sources, strategy, seed,
+        ))
+    }
+
+    #[tool(
+        description = "Record verdicts from verify_features: JSON mapping entity IDs to \"accurate\", \"outdated\", or \"wrong\". Outdated and wrong entities are queued for re-lifting (get_entities_for_lifting with scope '*' picks them up), and the verdict counts are saved as the graph's accuracy estimate, shown by lifting_status."
+    )]
+    async fn submit_verification(
+        &self,
+        Parameters(params): Parameters<SubmitVerificationParams>,
+    ) -> Result<String, String> {
+        use rpg_encoder::verification::{self, Verdict};
+
+        self.ensure_graph().await?;
+        let verdicts: std::collections::BTreeMap<String, Verdict> =
+            serde_json::from_str(&params.verdicts).map_err(|e| {
+                format!(
+                    "Invalid verdicts JSON (values must be \"accurate\", \"outdated\", or \"wrong\"): {}",
+                    e
+                )
+            })?;
+
+        let project_root = self.project_root().await;
+        let mut guard = self.graph.write().await;
+        let graph = guard.as_mut().ok_or("No RPG loaded")?;
+        let outcome = verification::apply_verdicts(graph, &verdicts);
+        if outcome.recorded.sampled() == 0 {
+            return Err(format!(
+                "No verdict matched an entity. Unknown IDs: {}",
+                outcome.unmatched.join(", ")
+            ));
+        }
+        storage::save(&project_root, graph).map_err(|e| format!("Failed to save RPG: {}", e))?;
+        self.stale_entity_ids
+            .write()
+            .await
+            .extend(outcome.requeue.iter().cloned());
+
+        let mut result = format!(
+            "Recorded {} verdicts.\n{}\n",
+            outcome.recorded.sampled(),
+            verification::format_accuracy(&outcome.recorded),
+        );
+        if !outcome.unmatched.is_empty() {
+            result.push_str(&format!(
+                "unmatched ({}): {}\n",
+                outcome.unmatched.len(),
+                outcome.unmatched.join(", ")
+            ));
+        }
+        if outcome.requeue.is_empty() {
+            return Ok(result);
         }
+        result.push_str(&format!(
+            "queued_for_relift ({}): {}\n",
+            outcome.requeue.len(),
+            outcome.requeue.join(", ")
+        ));
+        result.push_str(
+            &self
+                .next_step(
+                    "get_entities_for_lifting",
+                    "\nNEXT: call get_entities_for_lifting with scope \"*\" to re-lift the queued entities.",
+                )
+                .await,
+        );
         Ok(result)
     }
 
@@ -1801,8 +2524,9 @@ impl RpgServer {
 
         // List entities to route
         result.push_str("### Entities to Route\n\n");
-        result.push_str("| Entity | Features | Current Path | Reason |\n");
-        result.push_str("|--------|----------|--------------|--------|\n");
+        // Neighbors: areas of the entity's dependency neighbors, weighted by edge kind.
+        result.push_str("| Entity | Features | Current Path | Neighbors | Reason |\n");
+        result.push_str("|--------|----------|--------------|-----------|--------|\n");
         for p in batch {
             let feats_str = if p.features.len() > 4 {
                 format!("{}, ...", p.features[..4].join(", "))
@@ -1814,9 +2538,20 @@ impl RpgServer {
             } else {
                 p.original_path.clone()
             };
+            let areas = rpg_encoder::evolution::neighborhood_vote(graph, &p.entity_id).areas();
+            let neighbors = if areas.is_empty() {
+                "-".to_string()
+            } else {
+                areas
+                    .iter()
+                    .take(3)
+                    .map(|(area, share)| format!("{} {:.0}%", area, share * 100.0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
             result.push_str(&format!(
-                "| {} | {} | {} | {} |\n",
-                p.entity_id, feats_str, path_display, p.reason,
+                "| {} | {} | {} | {} | {} |\n",
+                p.entity_id, feats_str, path_display, neighbors, p.reason,
             ));
         }
 
@@ -1835,7 +2570,7 @@ impl RpgServer {
                 (name.as_str(), sim)
             })
             .collect();
-        area_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        area_scores.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
 
         let top_n = 3.min(area_scores.len());
         for (area_name, _) in &area_scores[..top_n] {
@@ -1867,10 +2602,10 @@ impl RpgServer {
                     } else {
                         cat_node.semantic_features.join(", ")
                     };
-                    result.push_str(&format!("  - {}/{}: {}\n", area_name, cat_name, cat_feats,));
+                    result.push_str(&format!("  - {}/{}: {}\n", area_name, cat_name, cat_feats));
                     for sub_name in cat_node.children.keys() {
                         result
-                            .push_str(&format!("    - {}/{}/{}\n", area_name, cat_name, sub_name,));
+                            .push_str(&format!("    - {}/{}/{}\n", area_name, cat_name, sub_name));
                     }
                 }
             }
@@ -1902,9 +2637,10 @@ impl RpgServer {
     ) -> Result<String, String> {
         self.ensure_graph().await?;
 
-        let decisions: std::collections::HashMap<String, String> =
+        let mut decisions: std::collections::BTreeMap<String, String> =
             serde_json::from_str(&params.decisions)
                 .map_err(|e| format!("Invalid decisions JSON: {}", e))?;
+        let style = self.config.read().await.encoding.hierarchy_style.clone();
 
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
@@ -1943,6 +2679,19 @@ impl RpgServer {
             ));
         }
 
+        // Bring well-formed routes in line with the style guide before
+        // checking that they exist
+        let rewrites = apply_hierarchy_style(
+            decisions
+                .iter_mut()
+                .filter(|(_, action)| {
+                    action.as_str() != "keep" && is_three_level_hierarchy_path(action)
+                })
+                .map(|(entity_id, action)| (entity_id.as_str(), action)),
+            &style,
+            false,
+        )?;
+
         // Validate: non-keep routes must be valid 3-level hierarchy paths
         let invalid_paths: Vec<String> = decisions
             .iter()
@@ -2042,6 +2791,7 @@ impl RpgServer {
             result.push_str(report);
             result.push('\n');
         }
+        result.push_str(&format_style_rewrites(&rewrites));
 
         if remaining > 0 {
             result.push_str(&format!(
@@ -2070,12 +2820,21 @@ impl RpgServer {
 
         // Detect paradigms BEFORE running update so entities get classified
         let detected_langs = Self::resolve_languages(&g.metadata);
-        let paradigm_defs = rpg_parser::paradigms::defs::load_builtin_defs()
-            .map_err(|e| format!("Failed to load paradigm defs: {:?}", e))?;
+        let project_root = self.project_root().await;
+        let paradigm_defs =
+            rpg_parser::paradigms::defs::load_defs(&project_root).map_err(|errs| {
+                format!(
+                    "paradigm definition errors: {}",
+                    errs.iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                )
+            })?;
         let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&paradigm_defs)
             .map_err(|errs| format!("query compile errors: {}", errs.join("; ")))?;
         let active_defs = rpg_parser::paradigms::detect_paradigms_toml(
-            &self.project_root().await,
+            &project_root,
             &detected_langs,
             &paradigm_defs,
         );
@@ -2086,6 +2845,28 @@ impl RpgServer {
             qcache: &qcache,
         };
 
+        // Score the pending changes against the graph before it is updated.
+        let risk = {
+            let project_root = self.project_root().await;
+            let risk_config = self.config.read().await.risk.clone();
+            let changes = match params.since.as_deref() {
+                Some(since) => {
+                    rpg_encoder::evolution::detect_changes(&project_root, g, Some(since))
+                }
+                None => rpg_encoder::evolution::detect_workdir_changes(&project_root, g),
+            };
+            changes.ok().map(|changes| {
+                let changes =
+                    rpg_encoder::evolution::filter_rpgignore_changes(&project_root, changes);
+                let changes = rpg_encoder::evolution::filter_source_changes(
+                    &project_root,
+                    changes,
+                    &detected_langs,
+                );
+                rpg_encoder::evolution::assess_change_risk(g, &changes, &risk_config)
+            })
+        };
+
         // Default: sync from current working tree (committed + staged + unstaged).
         // If `since` is provided, fall back to committed-only diff from that commit.
         let summary = if let Some(since) = params.since.as_deref() {
@@ -2206,6 +2987,16 @@ impl RpgServer {
                 summary.edges_added,
                 summary.edges_removed,
             );
+            if let Some(ref risk) = risk {
+                result.push_str(&format!("\n{}", risk.summary()));
+            }
+            if !summary.regrounded_nodes.is_empty() {
+                result.push_str(&format!(
+                    "\nregrounded_nodes: {} (grounded directories moved: {})",
+                    summary.regrounded_nodes.len(),
+                    summary.regrounded_nodes.join(", ")
+                ));
+            }
 
             let needs_relift = summary.modified_entity_ids.len();
 
@@ -2219,7 +3010,14 @@ impl RpgServer {
                         needs_relift,
                     ));
                 }
-                result.push_str("\n\nNEXT STEP: Call lifting_status to see what needs lifting, then get_entities_for_lifting to lift/re-lift them.");
+                result.push_str(
+                    &self
+                        .next_step(
+                            "lifting_status",
+                            "\n\nNEXT STEP: Call lifting_status to see what needs lifting, then get_entities_for_lifting to lift/re-lift them.",
+                        )
+                        .await,
+                );
             } else {
                 result.push_str("\n\nAll entities have features. Graph is up to date.");
             }
@@ -2245,8 +3043,8 @@ impl RpgServer {
         // existing config (don't clobber a working in-memory config over
         // a temporarily broken edit).
         Self::reload_config_with_warning(&self.config, &project_root).await;
-        match storage::load(&project_root) {
-            Ok(g) => {
+        match rpg_encoder::artifact::load_verified(&project_root) {
+            Ok((g, report)) => {
                 let entities = g.metadata.total_entities;
                 // Prune (don't clear) the drift-tracking set against the
                 // newly-loaded graph. Wholesale clearing was wrong because
@@ -2289,7 +3087,11 @@ impl RpgServer {
                     .map(|s| s.entries)
                     .unwrap_or_default();
                 *self.pending_routing.write().await = pending;
-                Ok(format!("RPG reloaded. {} entities loaded.", entities))
+                let mut out = format!("RPG reloaded. {} entities loaded.", entities);
+                if let Some(summary) = report.summary() {
+                    out.push_str(&format!("\nNote: {}", summary));
+                }
+                Ok(out)
             }
             Err(e) => Err(format!("Failed to reload RPG: {}", e)),
         }
@@ -2311,6 +3113,7 @@ impl RpgServer {
 
         // Drain pending routing via Jaccard fallback if agent didn't route explicitly
         let mut fallback_routed = 0usize;
+        let neighbor_weight = self.config.read().await.encoding.routing_neighbor_weight;
         {
             let mut pending = self.pending_routing.write().await;
             if !pending.is_empty() && graph.metadata.semantic_hierarchy {
@@ -2328,7 +3131,12 @@ impl RpgServer {
                     graph.remove_entity_from_hierarchy(&p.entity_id);
                     graph.aggregate_hierarchy_features();
 
-                    match rpg_encoder::evolution::find_best_hierarchy_path(graph, &feats) {
+                    match rpg_encoder::evolution::find_best_hierarchy_path(
+                        graph,
+                        Some(&p.entity_id),
+                        &feats,
+                        neighbor_weight,
+                    ) {
                         Some(new_path) if new_path != p.original_path => {
                             if let Some(entity) = graph.entities.get_mut(&p.entity_id) {
                                 entity.hierarchy_path = new_path.clone();
@@ -2376,8 +3184,14 @@ impl RpgServer {
         graph.refresh_metadata();
 
         // Save
-        storage::save(&self.project_root().await, graph)
-            .map_err(|e| format!("Failed to save RPG: {}", e))?;
+        let project_root = self.project_root().await;
+        storage::save(&project_root, graph).map_err(|e| format!("Failed to save RPG: {}", e))?;
+
+        // Step 3: Feature vocabulary for query expansion in search_node
+        match rpg_nav::vocab::rebuild(&project_root, graph) {
+            Ok(terms) => steps.push(format!("vocabulary: {} terms", terms)),
+            Err(e) => steps.push(format!("vocabulary: failed ({})", e)),
+        }
 
         let (final_lifted, final_total) = graph.lifting_coverage();
         let coverage_pct = if final_total > 0 {
@@ -2417,20 +3231,26 @@ impl RpgServer {
         }
 
         // NEXT STEP
-        if final_lifted < final_total {
-            result.push_str(&format!(
-                "\nNEXT STEP: {} entities still unlifted. Call get_entities_for_lifting(scope=\"*\") to continue.\n",
-                final_total - final_lifted,
-            ));
+        let (tool, block) = if final_lifted < final_total {
+            (
+                "get_entities_for_lifting",
+                format!(
+                    "\nNEXT STEP: {} entities still unlifted. Call get_entities_for_lifting(scope=\"*\") to continue.\n",
+                    final_total - final_lifted,
+                ),
+            )
         } else if !graph.metadata.semantic_hierarchy {
-            result.push_str(
-                "\nNEXT STEP: Call get_files_for_synthesis to produce holistic file-level features (improves hierarchy quality), then build_semantic_hierarchy + submit_hierarchy.\n",
-            );
+            (
+                "get_files_for_synthesis",
+                "\nNEXT STEP: Call get_files_for_synthesis to produce holistic file-level features (improves hierarchy quality), then build_semantic_hierarchy + submit_hierarchy.\n".to_string(),
+            )
         } else {
-            result.push_str(
-                "\nNEXT STEP: Graph is complete. Use search_node, fetch_node, explore_rpg to navigate.\n",
-            );
-        }
+            (
+                "search_node",
+                "\nNEXT STEP: Graph is complete. Use search_node, fetch_node, explore_rpg to navigate.\n".to_string(),
+            )
+        };
+        result.push_str(&self.next_step(tool, &block).await);
 
         Ok(result)
     }
@@ -2458,6 +3278,7 @@ impl RpgServer {
                     let e = graph.entities.get(id)?;
                     if e.kind == rpg_core::graph::EntityKind::Module
                         || e.semantic_features.is_empty()
+                        || e.is_fixture()
                     {
                         return None;
                     }
@@ -2550,7 +3371,7 @@ impl RpgServer {
     ) -> Result<String, String> {
         self.ensure_graph().await?;
 
-        let syntheses: std::collections::HashMap<String, String> =
+        let syntheses: std::collections::BTreeMap<String, String> =
             serde_json::from_str(&params.syntheses)
                 .map_err(|e| format!("Invalid syntheses JSON: {}", e))?;
 
@@ -2597,6 +3418,7 @@ impl RpgServer {
                     if !features.is_empty() {
                         module.semantic_features = features;
                         module.feature_source = Some("synthesized".to_string());
+                        module.lifted_at = Some(chrono::Utc::now());
                         updated += 1;
                     }
                 }
@@ -2640,7 +3462,14 @@ impl RpgServer {
             ));
         }
 
-        result.push_str("\nNEXT STEP: Call build_semantic_hierarchy to construct domain areas, then submit_hierarchy to apply them.\n");
+        result.push_str(
+            &self
+                .next_step(
+                    "build_semantic_hierarchy",
+                    "\nNEXT STEP: Call build_semantic_hierarchy to construct domain areas, then submit_hierarchy to apply them.\n",
+                )
+                .await,
+        );
 
         Ok(result)
     }
@@ -2674,6 +3503,7 @@ impl RpgServer {
         for entity in graph.entities.values() {
             if entity.kind == rpg_core::graph::EntityKind::Module
                 && !entity.semantic_features.is_empty()
+                && !entity.is_fixture()
             {
                 file_features.push_str(&format!(
                     "- {} ({}): {}\n",
@@ -2757,7 +3587,7 @@ impl RpgServer {
                     *session_guard = Some(HierarchySession {
                         clusters: new_clusters,
                         functional_areas: None,
-                        assignments: std::collections::HashMap::new(),
+                        assignments: std::collections::BTreeMap::new(),
                         batches_completed: 0,
                     });
                     Action::EmitBatch0(snapshot)
@@ -2850,6 +3680,7 @@ impl RpgServer {
             output.push_str("\n\n## Framework-Specific Discovery Guidelines\n\n");
             output.push_str(&discovery_hints);
         }
+        output.push_str(&rpg_encoder::hierarchy::format_charter_guidance(graph));
 
         output.push_str("\n\n### File-Level Features\n");
         output.push_str(&file_features);
@@ -2864,6 +3695,8 @@ impl RpgServer {
             output.push_str("\n\n## Framework-Specific Hierarchy Patterns\n\n");
             output.push_str(&hierarchy_hints);
         }
+        let style = self.config.read().await.encoding.hierarchy_style.clone();
+        output.push_str(&rpg_encoder::hierarchy_style::format_style_guidance(&style));
 
         output.push_str("\n\n");
         output.push_str(include_str!("prompts/hierarchy_instructions.md"));
@@ -2872,7 +3705,7 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "PREFER THIS OVER MANUAL search → fetch → explore CHAINS. Single-call context pack: searches for entities matching your query, fetches their details and source code, expands neighbors to the specified depth (default 1), and trims to a token budget. Returns primary entities with source + features + deps, plus neighborhood entities for broader context. Replaces 3-5 chained tool calls with 1.",
+        description = "PREFER THIS OVER MANUAL search → fetch → explore CHAINS. Single-call context pack: searches for entities matching your query, fetches their details and source code, expands neighbors to the specified depth (default 1), and trims to a token budget. Returns primary entities with source + features + deps, plus neighborhood entities with skeleton source (signatures and structure, bodies elided) for broader context. Replaces 3-5 chained tool calls with 1.",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
     async fn context_pack(
@@ -2880,6 +3713,9 @@ impl RpgServer {
         Parameters(params): Parameters<ContextPackParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
+        if let Some(scope) = params.scope.as_deref() {
+            rpg_nav::scope::validate(scope).map_err(|e| e.to_string())?;
+        }
         let notice = self.auto_sync_if_stale().await;
         let guard = self.graph.read().await;
         let graph = guard.as_ref().unwrap();
@@ -2898,23 +3734,41 @@ impl RpgServer {
         #[cfg(not(feature = "embeddings"))]
         let embedding_scores: Option<std::collections::HashMap<String, f64>> = None;
 
+        let project_root = self.project_root().await;
+        let neighbor_skeleton = match params.neighbor_source.as_deref() {
+            None | Some("skeleton") => {
+                Some(|entity: &rpg_core::graph::Entity| entity_skeleton(&project_root, entity))
+            }
+            Some("none") => None,
+            Some(other) => {
+                return Err(format!(
+                    "Invalid neighbor_source '{}'. Use \"skeleton\" or \"none\".",
+                    other
+                ));
+            }
+        };
+        let tokens = self.config.read().await.tokens.clone();
+        let estimator = rpg_encoder::tokens::from_config(&tokens, None, &project_root);
         let request = rpg_nav::context::ContextPackRequest {
             query: &params.query,
             scope: params.scope.as_deref(),
             token_budget: params.token_budget.unwrap_or(4000),
+            estimator: estimator.as_ref(),
             include_source: params.include_source.unwrap_or(true),
             depth: params.depth.unwrap_or(1),
+            neighbor_skeleton: neighbor_skeleton.as_ref().map(|f| f as _),
         };
 
         let result = rpg_nav::context::build_context_pack(
             graph,
-            &self.project_root().await,
+            &project_root,
             &request,
             embedding_scores.as_ref(),
-        );
+        )
+        .map_err(|e| e.to_string())?;
 
         if result.primary_entities.is_empty() {
-            return Ok(format!("{}No entities found for: {}", notice, params.query,));
+            return Ok(format!("{}No entities found for: {}", notice, params.query));
         }
 
         Ok(format!(
@@ -2925,7 +3779,7 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "PREFER THIS OVER RECURSIVE GREP FOR \"WHAT BREAKS IF I CHANGE X\". Computes the impact radius of an entity: all entities reachable via dependency edges with edge paths and depth. Use direction='upstream' for 'what depends on this?', 'downstream' for 'what does this depend on?'. Returns a flat list with depth, edge paths, and features — one call replaces a dependency trace you'd otherwise grep manually."
+        description = "PREFER THIS OVER RECURSIVE GREP FOR \"WHAT BREAKS IF I CHANGE X\". Computes the impact radius of an entity: all entities reachable via dependency edges with edge paths and depth. Use direction='upstream' for 'what depends on this?', 'downstream' for 'what does this depend on?'. Returns a flat list with depth, edge paths, and features — one call replaces a dependency trace you'd otherwise grep manually. Invokes edges into a spawned task or thread are marked [spawn]; set stop_at_async_boundary=true to keep the walk on the caller's side."
     )]
     async fn impact_radius(
         &self,
@@ -2951,24 +3805,185 @@ impl RpgServer {
         let edge_filter = params.edge_filter.as_deref().and_then(parse_edge_filter);
 
         let max_results = params.max_results.or(Some(100));
+        let max_tool_seconds = self.config.read().await.navigation.max_tool_seconds;
 
-        match rpg_nav::impact::compute_impact_radius(
+        match rpg_nav::impact::compute_impact_radius_with(
             graph,
             &params.entity_id,
             dir,
             max_depth,
             edge_filter,
-            max_results,
+            &rpg_nav::impact::ImpactOptions {
+                max_results,
+                deadline: rpg_nav::budget::deadline(max_tool_seconds),
+                stop_at_async_boundary: params.stop_at_async_boundary.unwrap_or(false),
+            },
         ) {
-            Some(result) => Ok(format!(
-                "{}{}",
-                notice,
-                rpg_nav::toon::format_impact_radius(&result),
-            )),
+            Some(result) => {
+                self.record_last_query("impact_radius", &params).await;
+                let budget_note = if result.budget_exhausted {
+                    format!(
+                        "\n[time budget exhausted after {} reachable entities (navigation.max_tool_seconds = {}); results are partial. Lower max_depth or set edge_filter to narrow the walk.]",
+                        result.total, max_tool_seconds
+                    )
+                } else {
+                    String::new()
+                };
+                Ok(format!(
+                    "{}{}{}",
+                    notice,
+                    rpg_nav::toon::format_impact_radius(&result),
+                    budget_note,
+                ))
+            }
             None => Err(format!("Entity not found: {}", params.entity_id)),
         }
     }
 
+    #[tool(
+        description = "Summarize a unified diff or patch against the graph without applying it. Hunks are matched by file and old-side line range to the entities they touch; each touched entity is listed with its kind, hierarchy path, fan-in, features, and whether a hunk reaches its signature (decorators through the line opening its body) or only its body. The blast radius is the upstream impact of the whole touched set, deduplicated and nearest first. Files the patch creates are listed as unindexed; deleted files mark all their entities touched. Use it to review a PR, a patch from a mailing list, or a proposed change before it lands.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn summarize_patch(
+        &self,
+        Parameters(params): Parameters<SummarizePatchParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let project_root = self.project_root().await;
+        let max_tool_seconds = self.config.read().await.navigation.max_tool_seconds;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let summary = rpg_nav::patch::summarize_patch(
+            graph,
+            &params.diff,
+            &rpg_core::files::WorktreeFiles::new(&project_root),
+            &rpg_nav::patch::PatchOptions {
+                max_depth: params.max_depth.unwrap_or(3),
+                deadline: rpg_nav::budget::deadline(max_tool_seconds),
+            },
+        );
+        if summary.touched.is_empty()
+            && summary.new_files.is_empty()
+            && summary.deleted_files.is_empty()
+            && summary.unindexed_files.is_empty()
+        {
+            return Err(
+                "No file sections found in the diff. Pass unified diff text (`git diff` output)."
+                    .into(),
+            );
+        }
+        let budget_note = if summary.budget_exhausted {
+            format!(
+                "\n[time budget exhausted (navigation.max_tool_seconds = {}); the blast radius is partial. Lower max_depth to narrow the walk.]",
+                max_tool_seconds
+            )
+        } else {
+            String::new()
+        };
+        Ok(format!(
+            "{}{}{}",
+            notice,
+            rpg_nav::toon::format_patch_summary(&summary, params.max_results.unwrap_or(25)),
+            budget_note,
+        ))
+    }
+
+    #[tool(
+        description = "Re-run a named saved query from .rpg/queries.toml (an explore_rpg or impact_radius call saved with `rpg-encoder query save <name>`). Parameters are re-resolved against the current graph, so results reflect the code as it is now. Unknown names list the saved queries.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn run_saved_query(
+        &self,
+        Parameters(params): Parameters<RunSavedQueryParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let root = self.project_root().await;
+        let query = rpg_nav::queries::load(&root, &params.name).map_err(|e| e.to_string())?;
+
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        let output = rpg_nav::queries::run(graph, &query).map_err(|e| e.to_string())?;
+        Ok(format!(
+            "{}saved_query: {} ({})\n{}",
+            notice, params.name, query.tool, output
+        ))
+    }
+
+    #[tool(
+        description = "Explain why an entity sits where it does in the hierarchy: its path and whether it was assigned per-file or routed individually, feature provenance (feature_source, lifted_at), how well its features overlap its area's features, and which areas its dependency neighbors live in. Flags likely misplacement, suggests a better-fitting path, and queues the entity for routing so submit_routing_decisions can move it on its own.",
+        annotations(destructive_hint = false, open_world_hint = false)
+    )]
+    async fn explain_entity(
+        &self,
+        Parameters(params): Parameters<ExplainEntityParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let threshold = params
+            .misplacement_threshold
+            .unwrap_or(rpg_nav::explain::DEFAULT_MISPLACEMENT_THRESHOLD);
+        let Some(mut result) =
+            rpg_nav::explain::explain_entity(graph, &params.entity_id, threshold, 5)
+        else {
+            return Err(format!("Entity not found: {}", params.entity_id));
+        };
+
+        if result.misplaced {
+            // Prefer the routing algorithm's pick; fall back to the best other area
+            // when it lands back on the current area.
+            let current_area = result.hierarchy_path.split('/').next().unwrap_or_default();
+            let neighbor_weight = self.config.read().await.encoding.routing_neighbor_weight;
+            result.suggested_path = rpg_encoder::evolution::find_best_hierarchy_path(
+                graph,
+                Some(&result.entity_id),
+                &result.features,
+                neighbor_weight,
+            )
+            .filter(|p| p.split('/').next() != Some(current_area))
+            .or_else(|| result.best_alternative.as_ref().map(|a| a.area.clone()));
+
+            // Queue the entity so the move is a targeted routing decision rather
+            // than a hierarchy rebuild of its whole file.
+            let revision = graph_revision(graph);
+            let mut pending = self.pending_routing.write().await;
+            if !pending.iter().any(|p| p.entity_id == result.entity_id) {
+                pending.push(PendingRouting {
+                    entity_id: result.entity_id.clone(),
+                    original_path: result.hierarchy_path.clone(),
+                    features: result.features.clone(),
+                    reason: "misplaced (explain_entity)".into(),
+                });
+                let state = PendingRoutingState {
+                    graph_revision: revision.clone(),
+                    entries: pending.clone(),
+                };
+                if let Err(e) = save_pending_routing(&self.project_root().await, &state) {
+                    eprintln!("rpg: failed to persist pending routing: {e}");
+                }
+            }
+            let target = result
+                .suggested_path
+                .as_deref()
+                .unwrap_or("Area/category/subcategory");
+            result.next_step = Some(format!(
+                "submit_routing_decisions(decisions='{{\"{}\": \"{}\"}}', graph_revision=\"{}\") — use a full 3-level path, or \"keep\" to leave it",
+                result.entity_id, target, revision,
+            ));
+        }
+
+        Ok(format!(
+            "{}{}",
+            notice,
+            rpg_nav::toon::format_explanation(&result)
+        ))
+    }
+
     #[tool(
         description = "PREFER THIS OVER MANUALLY TRACING CALLS. Finds shortest dependency paths between two entities (returns up to max_paths results of equal shortest length). Answers 'how does A reach B?' or 'is there any call chain from module X to module Y?' with entity IDs and edge kinds. Replaces the grep-follow-grep chain you'd otherwise walk by hand."
     )]
@@ -3000,6 +4015,7 @@ impl RpgServer {
             "writes_state" => Some(rpg_core::graph::EdgeKind::WritesState),
             "dispatches" => Some(rpg_core::graph::EdgeKind::Dispatches),
             "data_flow" => Some(rpg_core::graph::EdgeKind::DataFlow),
+            "tests" => Some(rpg_core::graph::EdgeKind::Tests),
             _ => None,
         });
 
@@ -3116,6 +4132,9 @@ impl RpgServer {
         Parameters(params): Parameters<PlanChangeParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
+        if let Some(scope) = params.scope.as_deref() {
+            rpg_nav::scope::validate(scope).map_err(|e| e.to_string())?;
+        }
         let notice = self.auto_sync_if_stale().await;
         let guard = self.graph.read().await;
         let graph = guard.as_ref().unwrap();
@@ -3140,7 +4159,8 @@ impl RpgServer {
             max_entities: params.max_entities.unwrap_or(15),
         };
 
-        let plan = rpg_nav::planner::plan_change(graph, &request, embedding_scores.as_ref());
+        let plan = rpg_nav::planner::plan_change(graph, &request, embedding_scores.as_ref())
+            .map_err(|e| e.to_string())?;
 
         if plan.relevant_entities.is_empty() {
             return Ok(format!(
@@ -3164,6 +4184,7 @@ impl RpgServer {
         Parameters(params): Parameters<SubmitHierarchyParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
+        let style = self.config.read().await.encoding.hierarchy_style.clone();
 
         // Check if we have an active hierarchy session
         let mut session_guard = self.hierarchy_session.write().await;
@@ -3187,18 +4208,27 @@ impl RpgServer {
                 if payload.areas.is_empty() {
                     return Err("Empty areas list. Provide at least one functional area.".into());
                 }
-
-                session.functional_areas = Some(payload.areas.clone());
+                let mut areas = payload.areas;
+                let rewrites = apply_hierarchy_style(
+                    areas.iter_mut().map(|area| ("area", area)),
+                    &style,
+                    true,
+                )?;
+                let mut seen = HashSet::new();
+                areas.retain(|area| seen.insert(area.clone()));
+
+                session.functional_areas = Some(areas.clone());
                 session.batches_completed += 1;
 
                 return Ok(format!(
-                    "Functional areas registered: {}\n\nCall build_semantic_hierarchy to get batch 1 for file assignment.",
-                    payload.areas.join(", ")
+                    "Functional areas registered: {}\n{}\nCall build_semantic_hierarchy to get batch 1 for file assignment.",
+                    areas.join(", "),
+                    format_style_rewrites(&rewrites),
                 ));
             }
 
             // Batch 1+: File assignments
-            let assignments: std::collections::HashMap<String, String> =
+            let mut assignments: std::collections::BTreeMap<String, String> =
                 serde_json::from_str(&params.assignments).map_err(|e| {
                     format!(
                         "Invalid JSON: {}. Expected {{\"file_path\": \"Area/cat/subcat\", ...}}",
@@ -3231,6 +4261,13 @@ impl RpgServer {
                     sample.join("\n"),
                 ));
             }
+            let rewrites = apply_hierarchy_style(
+                assignments
+                    .iter_mut()
+                    .map(|(file, path)| (file.as_str(), path)),
+                &style,
+                false,
+            )?;
 
             // Accumulate assignments
             for (file, path) in assignments {
@@ -3302,9 +4339,12 @@ impl RpgServer {
                     }
                 }
 
-                // Clear existing hierarchy and apply new assignments
+                // Snapshot the previous assignments, then clear and apply the new ones
+                let previous = rpg_encoder::hierarchy_changes::file_areas(graph);
+                let charters = graph.hierarchy_charters();
                 graph.hierarchy.clear();
                 rpg_encoder::hierarchy::apply_hierarchy(graph, &entity_assignments);
+                graph.restore_charters(&charters);
                 graph.metadata.semantic_hierarchy = true;
 
                 // Re-enrich hierarchy metadata and grounding
@@ -3313,6 +4353,10 @@ impl RpgServer {
                 graph.materialize_containment_edges();
                 rpg_encoder::grounding::ground_hierarchy(graph);
                 graph.refresh_metadata();
+                let changes = rpg_encoder::hierarchy_changes::compare(
+                    &previous,
+                    &rpg_encoder::hierarchy_changes::file_areas(graph),
+                );
 
                 // Save
                 storage::save(&self.project_root().await, graph)
@@ -3337,6 +4381,9 @@ impl RpgServer {
                     ));
                 }
 
+                result.push_str(&format_style_rewrites(&rewrites));
+                result.push_str(&crate::helpers::format_charter_carryover(&charters, graph));
+
                 // Show hierarchy summary
                 result.push_str("\nHierarchy areas:\n");
                 for (area_name, area_node) in &graph.hierarchy {
@@ -3346,21 +4393,25 @@ impl RpgServer {
                         area_node.entity_count()
                     ));
                 }
+                result.push_str(&rpg_encoder::hierarchy_changes::format_digest(&changes));
+                *self.hierarchy_changes.write().await = Some(changes);
 
                 return Ok(result);
             }
 
             // More batches remaining
             return Ok(format!(
-                "Batch {}/{} complete. Call build_semantic_hierarchy for next batch.",
-                session.batches_completed, total_batches
+                "Batch {}/{} complete. Call build_semantic_hierarchy for next batch.\n{}",
+                session.batches_completed,
+                total_batches,
+                format_style_rewrites(&rewrites),
             ));
         }
 
         // NO SESSION: Single-shot mode (backward compatibility)
         drop(session_guard);
 
-        let assignments: std::collections::HashMap<String, String> =
+        let mut assignments: std::collections::BTreeMap<String, String> =
             serde_json::from_str(&params.assignments).map_err(|e| {
                 format!(
                     "Invalid JSON: {}. Expected {{\"file_path\": \"Area/cat/subcat\", ...}}",
@@ -3393,6 +4444,13 @@ impl RpgServer {
                 sample.join("\n"),
             ));
         }
+        let rewrites = apply_hierarchy_style(
+            assignments
+                .iter_mut()
+                .map(|(file, path)| (file.as_str(), path)),
+            &style,
+            false,
+        )?;
 
         let mut guard = self.graph.write().await;
         let graph = guard.as_mut().ok_or("No RPG loaded")?;
@@ -3448,9 +4506,12 @@ impl RpgServer {
             }
         }
 
-        // Clear existing hierarchy and apply new assignments
+        // Snapshot the previous assignments, then clear and apply the new ones
+        let previous = rpg_encoder::hierarchy_changes::file_areas(graph);
+        let charters = graph.hierarchy_charters();
         graph.hierarchy.clear();
         rpg_encoder::hierarchy::apply_hierarchy(graph, &entity_assignments);
+        graph.restore_charters(&charters);
         graph.metadata.semantic_hierarchy = true;
 
         // Re-enrich hierarchy metadata and grounding
@@ -3459,6 +4520,10 @@ impl RpgServer {
         graph.materialize_containment_edges();
         rpg_encoder::grounding::ground_hierarchy(graph);
         graph.refresh_metadata();
+        let changes = rpg_encoder::hierarchy_changes::compare(
+            &previous,
+            &rpg_encoder::hierarchy_changes::file_areas(graph),
+        );
 
         // Save
         storage::save(&self.project_root().await, graph)
@@ -3482,6 +4547,9 @@ impl RpgServer {
             ));
         }
 
+        result.push_str(&format_style_rewrites(&rewrites));
+        result.push_str(&crate::helpers::format_charter_carryover(&charters, graph));
+
         // Show hierarchy summary
         result.push_str("\nHierarchy areas:\n");
         for (area_name, area_node) in &graph.hierarchy {
@@ -3491,10 +4559,26 @@ impl RpgServer {
                 area_node.entity_count()
             ));
         }
+        result.push_str(&rpg_encoder::hierarchy_changes::format_digest(&changes));
+        *self.hierarchy_changes.write().await = Some(changes);
 
         Ok(result)
     }
 
+    #[tool(
+        description = "Report what the last submit_hierarchy changed relative to the hierarchy it replaced: per (old area -> new area) file counts, areas that look renamed (most of their files moved together under one new name), and the individual moved, newly assigned, and unassigned files. Kept until the next submission.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn hierarchy_changes(&self) -> Result<String, String> {
+        match self.hierarchy_changes.read().await.as_ref() {
+            Some(changes) => Ok(rpg_encoder::hierarchy_changes::format_report(changes)),
+            None => Err(
+                "No hierarchy changes recorded. Submit a hierarchy with submit_hierarchy first."
+                    .into(),
+            ),
+        }
+    }
+
     #[tool(
         description = "PREFER THIS OVER EYEBALLING FOR ARCHITECTURAL SMELLS. Analyzes code health: coupling, instability, centrality, god object detection, optional clone detection. Returns entities with architectural issues and refactoring recommendations. Use `include_duplication=true` for token-level Rabin-Karp clones (reads source, slower). Use `include_semantic_duplication=true` for Jaccard-similarity conceptual duplicates on lifted features (in-memory, fast). Replaces manual review of cross-file patterns.",
         annotations(read_only_hint = true, open_world_hint = false)
@@ -3531,19 +4615,77 @@ impl RpgServer {
     }
 
     #[tool(
-        description = "PREFER THIS OVER MANUAL CYCLE HUNTING. Detects circular dependencies: A→B→C→A chains anywhere in the graph. Returns cycles with entity chains, file counts, and cross-area filtering. First call returns summary + area breakdown; pass filters (area, min_cycle_length, cross_file_only) to get specific cycles. One call replaces hours of import-chain reading.",
+        description = "Find duplicated code. method=\"features\" compares lifted features (Jaccard, conceptual duplicates); method=\"source\" compares MinHash signatures of normalized token shingles, catching copy-paste and renamed-variable copies even in unlifted entities; \"both\" (default) runs each. Returns clusters with similarity and entity locations.",
         annotations(read_only_hint = true, open_world_hint = false)
     )]
-    async fn detect_cycles(
+    async fn find_duplicates(
         &self,
-        Parameters(params): Parameters<DetectCyclesParams>,
+        Parameters(params): Parameters<FindDuplicatesParams>,
     ) -> Result<String, String> {
         self.ensure_graph().await?;
         let notice = self.auto_sync_if_stale().await;
-        let guard = self.graph.read().await;
-        let graph = guard.as_ref().unwrap();
 
-        let cross_file_only = params.cross_file_only.unwrap_or(false);
+        let method = params.method.as_deref().unwrap_or("both");
+        let (run_features, run_source) = match method {
+            "features" => (true, false),
+            "source" => (false, true),
+            "both" => (true, true),
+            other => {
+                return Err(format!(
+                    "Unknown method: {}. Use 'features', 'source', or 'both'.",
+                    other
+                ));
+            }
+        };
+        let max_results = params.max_results.unwrap_or(50);
+        let project_root = self.project_root().await;
+
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let semantic = run_features.then(|| {
+            rpg_nav::duplication::detect_semantic_duplicates(
+                graph,
+                &rpg_nav::duplication::SemanticDuplicationConfig {
+                    similarity_threshold: params.similarity_threshold.unwrap_or(0.6),
+                    max_results,
+                    ..Default::default()
+                },
+            )
+        });
+        let source = run_source.then(|| {
+            rpg_nav::duplication::detect_source_duplicates(
+                graph,
+                &project_root,
+                &rpg_nav::duplication::SourceDuplicationConfig {
+                    similarity_threshold: params.similarity_threshold.unwrap_or(0.8),
+                    max_results,
+                    ..Default::default()
+                },
+            )
+        });
+
+        Ok(format!(
+            "{}{}",
+            notice,
+            rpg_nav::toon::format_duplicates(semantic.as_deref(), source.as_deref())
+        ))
+    }
+
+    #[tool(
+        description = "PREFER THIS OVER MANUAL CYCLE HUNTING. Detects circular dependencies: A→B→C→A chains anywhere in the graph. Returns cycles with entity chains, file counts, and cross-area filtering. First call returns summary + area breakdown; pass filters (area, min_cycle_length, cross_file_only) to get specific cycles. One call replaces hours of import-chain reading.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn detect_cycles(
+        &self,
+        Parameters(params): Parameters<DetectCyclesParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let cross_file_only = params.cross_file_only.unwrap_or(false);
         let cross_area_only = params.cross_area_only.unwrap_or(false);
         let max_cycles = params.max_cycles.unwrap_or(usize::MAX);
         let area = params.area.clone();
@@ -3612,6 +4754,111 @@ impl RpgServer {
             rpg_nav::toon::format_cycle_report(&report, graph, &opts)
         ))
     }
+
+    #[tool(
+        description = "How much of the dependency graph is missing. Lists, per language and edge kind, how many extracted names resolved to edges, then the top module prefixes of imports that matched no in-repo entity, each marked third-party (declared in a manifest or lockfile), in-repo-unmapped (relative path, tsconfig alias, or local package the resolver failed to map: missing edges), or unknown (usually the standard library). Recorded by build_rpg; update_rpg keeps the last full build's report.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn resolution_report(
+        &self,
+        Parameters(params): Parameters<ResolutionReportParams>,
+    ) -> Result<String, String> {
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let mut output = String::from("## DEPENDENCY RESOLUTION\n\n");
+        output.push_str(&rpg_encoder::grounding::format_resolution_table(
+            &graph.metadata.edge_resolution,
+        ));
+        for warning in rpg_encoder::grounding::resolution_warnings(&graph.metadata.edge_resolution)
+        {
+            output.push_str(&format!("warning: {}\n", warning));
+        }
+        output.push_str("\n## UNRESOLVED IMPORTS\n\n");
+        match &graph.metadata.unresolved_imports {
+            Some(report) => output.push_str(&rpg_encoder::unresolved_imports::format_report(
+                report,
+                params.max_prefixes.unwrap_or(20),
+            )),
+            None => output.push_str(
+                "unresolved_imports: not recorded for this graph. Run build_rpg to collect it.\n",
+            ),
+        }
+        Ok(format!("{}{}", notice, output))
+    }
+
+    #[tool(
+        description = "Find the entities that read a configuration key: environment variables (std::env::var, os.environ, process.env, os.Getenv, System.getenv, ENV[...], getenv) and config getters (config.get(\"x\"), settings.get_str(\"x\")). Keys are found by a literal-pattern scan, so only keys spelled out as string literals at the access site are seen. mode='substring' (default, case-insensitive) also lists related keys, e.g. key='RPG_' finds every RPG_* variable; mode='exact' matches the key as written.",
+        annotations(read_only_hint = true, open_world_hint = false)
+    )]
+    async fn find_config_usage(
+        &self,
+        Parameters(params): Parameters<FindConfigUsageParams>,
+    ) -> Result<String, String> {
+        let exact = match params.mode.as_deref().unwrap_or("substring") {
+            "substring" => false,
+            "exact" => true,
+            other => {
+                return Err(format!(
+                    "Unknown mode: {}. Use 'substring' or 'exact'.",
+                    other
+                ));
+            }
+        };
+        let needle = params.key.trim();
+        if needle.is_empty() {
+            return Err("key must not be empty".into());
+        }
+        self.ensure_graph().await?;
+        let notice = self.auto_sync_if_stale().await;
+        let guard = self.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+
+        let lowered = needle.to_lowercase();
+        let mut by_key: std::collections::BTreeMap<&str, Vec<&rpg_core::graph::Entity>> =
+            std::collections::BTreeMap::new();
+        for entity in graph.entities.values() {
+            for key in &entity.deps.env_reads {
+                let matches = if exact {
+                    key == needle
+                } else {
+                    key.to_lowercase().contains(&lowered)
+                };
+                if matches {
+                    by_key.entry(key).or_default().push(entity);
+                }
+            }
+        }
+        if by_key.is_empty() {
+            return Ok(format!(
+                "{}No entity reads a config key {} \"{}\". Only keys written as string literals at the access site are indexed.",
+                notice,
+                if exact { "equal to" } else { "containing" },
+                needle
+            ));
+        }
+
+        let mut output = format!(
+            "## CONFIG USAGE: \"{}\" ({})\n",
+            needle,
+            if exact { "exact" } else { "substring" }
+        );
+        for (key, mut entities) in by_key {
+            entities.sort_by(|a, b| a.id.cmp(&b.id));
+            output.push_str(&format!("\n{} ({} entities)\n", key, entities.len()));
+            for entity in entities {
+                output.push_str(&format!(
+                    "- {} ({}:{})\n",
+                    entity.id,
+                    normalize_path(&entity.file),
+                    entity.line_start
+                ));
+            }
+        }
+        Ok(format!("{}{}", notice, output))
+    }
 }
 
 impl RpgServer {
@@ -3634,6 +4881,7 @@ pub fn parse_edge_filter(filter: &str) -> Option<rpg_core::graph::EdgeKind> {
         "writes_state" => Some(rpg_core::graph::EdgeKind::WritesState),
         "dispatches" => Some(rpg_core::graph::EdgeKind::Dispatches),
         "data_flow" => Some(rpg_core::graph::EdgeKind::DataFlow),
+        "tests" => Some(rpg_core::graph::EdgeKind::Tests),
         "contains" => Some(rpg_core::graph::EdgeKind::Contains),
         _ => None,
     }
@@ -3642,7 +4890,8 @@ pub fn parse_edge_filter(filter: &str) -> Option<rpg_core::graph::EdgeKind> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rpg_core::graph::EdgeKind;
+    use rpg_core::config::GuidanceMode;
+    use rpg_core::graph::{EdgeKind, Entity, EntityDeps, EntityKind};
 
     #[test]
     fn test_parse_edge_filter_data_flow() {
@@ -3663,7 +4912,1962 @@ mod tests {
         );
         assert_eq!(parse_edge_filter("dispatches"), Some(EdgeKind::Dispatches));
         assert_eq!(parse_edge_filter("data_flow"), Some(EdgeKind::DataFlow));
+        assert_eq!(parse_edge_filter("tests"), Some(EdgeKind::Tests));
         assert_eq!(parse_edge_filter("contains"), Some(EdgeKind::Contains));
         assert_eq!(parse_edge_filter("unknown"), None);
     }
+
+    fn set_guidance(value: &str) -> Parameters<SetConfigParams> {
+        Parameters(SetConfigParams {
+            key: "guidance".to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// A saved graph with one unlifted function.
+    fn project_with_graph() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(Entity {
+            id: "src/lib.rs:run".to_string(),
+            kind: EntityKind::Function,
+            name: "run".to_string(),
+            file: "src/lib.rs".into(),
+            line_start: 1,
+            line_end: 3,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: std::collections::BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: std::collections::BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        });
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        tmp
+    }
+
+    #[tokio::test]
+    async fn test_lifting_status_guidance_snapshots() {
+        let tmp = tempfile::tempdir().unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        assert_eq!(
+            server.lifting_status().await.unwrap(),
+            "=== RPG Lifting Status ===\ngraph: not built\n\nNEXT STEP: Call build_rpg to index the codebase."
+        );
+
+        server.set_config(set_guidance("terse")).await.unwrap();
+        assert_eq!(
+            server.lifting_status().await.unwrap(),
+            "=== RPG Lifting Status ===\ngraph: not built\n\nnext: build_rpg"
+        );
+
+        server.set_config(set_guidance("off")).await.unwrap();
+        assert_eq!(
+            server.lifting_status().await.unwrap(),
+            "=== RPG Lifting Status ===\ngraph: not built"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lifting_status_state_machine_honors_guidance() {
+        let tmp = project_with_graph();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        let full = server.lifting_status().await.unwrap();
+        assert!(
+            full.ends_with(
+                "\nNEXT STEP: Call get_entities_for_lifting(scope=\"*\") to start lifting.\n"
+            ),
+            "{}",
+            full
+        );
+
+        server.set_config(set_guidance("terse")).await.unwrap();
+        let terse = server.lifting_status().await.unwrap();
+        assert!(!terse.contains("NEXT STEP"), "{}", terse);
+        assert!(
+            terse.ends_with("\nnext: get_entities_for_lifting\n"),
+            "{}",
+            terse
+        );
+
+        server.set_config(set_guidance("off")).await.unwrap();
+        let off = server.lifting_status().await.unwrap();
+        assert!(!off.contains("next"), "{}", off);
+        assert_eq!(off, full[..full.rfind("\nNEXT STEP").unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn test_guidance_from_config_and_set_config_override() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[mcp]\nguidance = \"off\"\n",
+        )
+        .unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        assert_eq!(server.guidance_mode().await, GuidanceMode::Off);
+
+        assert_eq!(
+            server.set_config(set_guidance("Terse")).await.unwrap(),
+            "guidance: terse"
+        );
+        // The override outlives a config reload.
+        let _ = server.reload_rpg().await;
+        assert_eq!(server.guidance_mode().await, GuidanceMode::Terse);
+
+        assert!(server.set_config(set_guidance("loud")).await.is_err());
+        assert!(
+            server
+                .set_config(Parameters(SetConfigParams {
+                    key: "colour".to_string(),
+                    value: "off".to_string(),
+                }))
+                .await
+                .is_err()
+        );
+        assert_eq!(server.guidance_mode().await, GuidanceMode::Terse);
+    }
+
+    const LIFT_FIXTURE: &[(&str, &str)] = &[
+        ("load", "load config file"),
+        ("parse", "parse config values"),
+        ("validate", "validate config schema"),
+        ("connect", "open database connection"),
+        ("migrate", "apply schema migrations"),
+        ("serve", "serve http requests"),
+    ];
+
+    /// A saved graph with the unlifted functions of [`LIFT_FIXTURE`].
+    fn lift_fixture_project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for (name, _) in LIFT_FIXTURE {
+            graph.insert_entity(Entity {
+                id: format!("src/app.rs:{}", name),
+                kind: EntityKind::Function,
+                name: (*name).to_string(),
+                file: "src/app.rs".into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: Vec::new(),
+                feature_source: None,
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        tmp
+    }
+
+    fn lift_part(
+        range: std::ops::Range<usize>,
+        transaction_id: Option<&str>,
+        is_final: Option<bool>,
+    ) -> Parameters<SubmitLiftResultsParams> {
+        let features: std::collections::HashMap<String, Vec<String>> = LIFT_FIXTURE[range]
+            .iter()
+            .map(|(name, feature)| (format!("src/app.rs:{}", name), vec![feature.to_string()]))
+            .collect();
+        Parameters(SubmitLiftResultsParams {
+            features: serde_json::to_string(&features).unwrap(),
+            transaction_id: transaction_id.map(String::from),
+            is_final,
+        })
+    }
+
+    async fn lifted_features(server: &RpgServer) -> Vec<(String, Vec<String>)> {
+        let guard = server.graph.read().await;
+        let mut features: Vec<(String, Vec<String>)> = guard
+            .as_ref()
+            .unwrap()
+            .entities
+            .iter()
+            .map(|(id, e)| (id.clone(), e.semantic_features.clone()))
+            .collect();
+        features.sort();
+        features
+    }
+
+    #[tokio::test]
+    async fn test_three_part_transaction_matches_single_call() {
+        let single_tmp = lift_fixture_project();
+        let single = RpgServer::new(single_tmp.path().to_path_buf());
+        let single_out = single
+            .submit_lift_results(lift_part(0..6, None, None))
+            .await
+            .unwrap();
+
+        let split_tmp = lift_fixture_project();
+        let split = RpgServer::new(split_tmp.path().to_path_buf());
+        let first = split
+            .submit_lift_results(lift_part(0..2, Some("t1"), None))
+            .await
+            .unwrap();
+        assert!(first.starts_with("Buffered part 1 of transaction 't1' (2 entries"));
+        split
+            .submit_lift_results(lift_part(2..4, Some("t1"), Some(false)))
+            .await
+            .unwrap();
+        // Nothing is applied before the final part.
+        assert!(
+            lifted_features(&split)
+                .await
+                .iter()
+                .all(|(_, f)| f.is_empty())
+        );
+        let split_out = split
+            .submit_lift_results(lift_part(4..6, Some("t1"), Some(true)))
+            .await
+            .unwrap();
+
+        let (header, split_rest) = split_out.split_once('\n').unwrap();
+        assert_eq!(header, "Transaction 't1' complete (3 parts).");
+        assert_eq!(split_rest, single_out);
+        assert_eq!(
+            lifted_features(&split).await,
+            lifted_features(&single).await
+        );
+        assert!(split.lift_transactions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_final_part_is_a_complete_transaction() {
+        let single_tmp = lift_fixture_project();
+        let single = RpgServer::new(single_tmp.path().to_path_buf());
+        let single_out = single
+            .submit_lift_results(lift_part(0..6, None, None))
+            .await
+            .unwrap();
+
+        let tmp = lift_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .submit_lift_results(lift_part(0..6, Some("t1"), Some(true)))
+            .await
+            .unwrap();
+
+        let (header, rest) = out.split_once('\n').unwrap();
+        assert_eq!(header, "Transaction 't1' complete (1 part).");
+        assert_eq!(rest, single_out);
+        assert_eq!(
+            lifted_features(&server).await,
+            lifted_features(&single).await
+        );
+        assert!(server.lift_transactions.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_transaction_expires() {
+        let tmp = lift_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        server
+            .submit_lift_results(lift_part(0..3, Some("old"), None))
+            .await
+            .unwrap();
+        server
+            .lift_transactions
+            .write()
+            .await
+            .get_mut("old")
+            .unwrap()
+            .touched_at = std::time::Instant::now()
+            .checked_sub(crate::LIFT_TRANSACTION_TTL + std::time::Duration::from_secs(1))
+            .unwrap();
+
+        let err = server
+            .submit_lift_results(lift_part(3..6, Some("old"), Some(true)))
+            .await
+            .unwrap_err();
+        assert!(err.contains("No buffered parts left"), "{}", err);
+        assert!(server.lift_transactions.read().await.is_empty());
+        assert!(
+            lifted_features(&server)
+                .await
+                .iter()
+                .all(|(_, f)| f.is_empty())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_per_area_thresholds_route_equal_drift_differently() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for (file, area) in [
+            ("src/core.rs", "Core/state/store"),
+            ("src/web.rs", "Web/http/serve"),
+        ] {
+            graph.insert_entity(Entity {
+                id: format!("{}:run", file),
+                kind: EntityKind::Function,
+                name: "run".to_string(),
+                file: file.into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: vec!["load state".to_string(), "save state".to_string()],
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: area.to_string(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding.per_area.\"Core\"]\ndrift_auto_threshold = 0.5\n",
+        )
+        .unwrap();
+
+        // Both entities drift by the same 0.67.
+        let features: std::collections::HashMap<String, Vec<String>> =
+            ["src/core.rs:run", "src/web.rs:run"]
+                .iter()
+                .map(|id| {
+                    (
+                        id.to_string(),
+                        vec!["load state".to_string(), "reset state".to_string()],
+                    )
+                })
+                .collect();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .submit_lift_results(Parameters(SubmitLiftResultsParams {
+                features: serde_json::to_string(&features).unwrap(),
+                transaction_id: None,
+                is_final: None,
+            }))
+            .await
+            .unwrap();
+
+        assert!(
+            out.contains(
+                "src/core.rs:run drifted (0.67; ignore 0.30, auto 0.50 for Core) — routing required"
+            ),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains(
+                "src/web.rs:run borderline drift (0.67; ignore 0.30, auto 0.70) — agent review requested"
+            ),
+            "{}",
+            out
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compound_features_split_and_reported() {
+        let tmp = lift_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let features = serde_json::json!({
+            "src/app.rs:serve": ["Serve HTTP requests and log access lines"],
+        });
+        let out = server
+            .submit_lift_results(Parameters(SubmitLiftResultsParams {
+                features: features.to_string(),
+                transaction_id: None,
+                is_final: None,
+            }))
+            .await
+            .unwrap();
+
+        assert!(out.contains("## NORMALIZED FEATURES"), "{}", out);
+        assert!(
+            out.contains("-> [serve http requests, log access lines]"),
+            "{}",
+            out
+        );
+        let guard = server.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        assert_eq!(
+            graph.entities["src/app.rs:serve"].semantic_features,
+            vec!["log access lines", "serve http requests"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_copied_features_flagged_for_review() {
+        let tmp = lift_fixture_project();
+        let mut graph = storage::load(tmp.path()).unwrap();
+        graph.metadata.semantic_hierarchy = true;
+        storage::save(tmp.path(), &graph).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding]\ncopied_features_max_entities = 4\n",
+        )
+        .unwrap();
+
+        // Five entities get the same features; `serve` gets its own.
+        let features: std::collections::HashMap<String, Vec<String>> = LIFT_FIXTURE
+            .iter()
+            .map(|(name, _)| {
+                let feats = if *name == "serve" {
+                    vec!["serve http requests".to_string()]
+                } else {
+                    vec!["load config file".to_string(), "parse config".to_string()]
+                };
+                (format!("src/app.rs:{}", name), feats)
+            })
+            .collect();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .submit_lift_results(Parameters(SubmitLiftResultsParams {
+                features: serde_json::to_string(&features).unwrap(),
+                transaction_id: None,
+                is_final: None,
+            }))
+            .await
+            .unwrap();
+
+        assert!(out.contains("## COPIED FEATURES"), "{}", out);
+        assert!(
+            out.contains("- 5 entities: [load config file, parse config]"),
+            "{}",
+            out
+        );
+        assert!(out.contains("  - `src/app.rs:migrate`\n"), "{}", out);
+        assert!(!out.contains("`src/app.rs:serve`"), "{}", out);
+
+        let guard = server.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        let source = |name: &str| {
+            graph.entities[&format!("src/app.rs:{}", name)]
+                .feature_source
+                .clone()
+        };
+        assert_eq!(source("load").as_deref(), Some("llm-review"));
+        assert_eq!(source("serve").as_deref(), Some("llm"));
+        // Only the distinct entity waits for routing.
+        let pending = server.pending_routing.read().await;
+        let pending: Vec<&str> = pending.iter().map(|p| p.entity_id.as_str()).collect();
+        assert_eq!(pending, vec!["src/app.rs:serve"]);
+    }
+
+    fn snippet_fixture_project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(
+            tmp.path().join("src/app.rs"),
+            "fn load_config() {\n    let path = config_path();\n    let raw = read(path);\n    parse(raw);\n    validate();\n}\n",
+        )
+        .unwrap();
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(Entity {
+            id: "src/app.rs:load_config".to_string(),
+            kind: EntityKind::Function,
+            name: "load_config".to_string(),
+            file: "src/app.rs".into(),
+            line_start: 1,
+            line_end: 6,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: std::collections::BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: std::collections::BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        });
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        tmp
+    }
+
+    fn fetch_params(snippets: Option<&str>) -> Parameters<FetchNodeParams> {
+        Parameters(FetchNodeParams {
+            entity_id: "src/app.rs:load_config".to_string(),
+            entity_ids: None,
+            fields: None,
+            source_max_lines: None,
+            snippets: snippets.map(String::from),
+            file: None,
+            token_budget: None,
+        })
+    }
+
+    fn source_ref(output: &str) -> String {
+        output
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("source_ref: "))
+            .unwrap_or_else(|| panic!("no source_ref in {}", output))
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_fetch_sends_only_snippet_ref() {
+        let tmp = snippet_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        let first = server.fetch_node(fetch_params(Some("hash"))).await.unwrap();
+        let hash = source_ref(&first);
+        assert_eq!(hash.len(), 64);
+        assert!(first.contains("validate();"), "{}", first);
+
+        let second = server.fetch_node(fetch_params(Some("hash"))).await.unwrap();
+        assert_eq!(source_ref(&second), hash);
+        assert!(second.contains("let raw = read(path);"), "{}", second);
+        assert!(!second.contains("parse(raw);"), "{}", second);
+        assert!(!second.contains("validate();"), "{}", second);
+
+        let full = server
+            .get_snippet(Parameters(GetSnippetParams {
+                source_ref: hash.clone(),
+            }))
+            .await
+            .unwrap();
+        assert!(full.starts_with("fn load_config() {"));
+        assert!(full.contains("validate();"));
+
+        // Default behavior is unchanged: full source, no ref.
+        let inline = server.fetch_node(fetch_params(None)).await.unwrap();
+        assert!(inline.contains("validate();"));
+        assert!(!inline.contains("source_ref"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_snippet_ref_is_an_error() {
+        let tmp = snippet_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let err = server
+            .get_snippet(Parameters(GetSnippetParams {
+                source_ref: "0".repeat(64),
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.contains("Unknown source_ref"), "{}", err);
+        assert!(server.fetch_node(fetch_params(Some("refs"))).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_by_file_respects_token_budget() {
+        let tmp = lift_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let by_file = |file: &str, token_budget: Option<usize>| {
+            let mut params = fetch_params(None);
+            params.0.entity_id = String::new();
+            params.0.file = Some(file.to_string());
+            params.0.token_budget = token_budget;
+            params
+        };
+
+        let all = server
+            .fetch_node(by_file("./src/app.rs", None))
+            .await
+            .unwrap();
+        for (name, _) in LIFT_FIXTURE {
+            assert!(all.contains(&format!("name: {}\n", name)), "{}", all);
+        }
+        assert!(!all.contains("omitted ("), "{}", all);
+
+        let trimmed = server
+            .fetch_node(by_file("src/app.rs", Some(1)))
+            .await
+            .unwrap();
+        assert!(
+            trimmed.contains("omitted (5 over token_budget 1): "),
+            "{}",
+            trimmed
+        );
+
+        let err = server
+            .fetch_node(by_file("src/ap.rs", None))
+            .await
+            .unwrap_err();
+        assert!(err.contains("did you mean: src/app.rs"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_skeleton_projection_elides_bodies() {
+        let tmp = snippet_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let mut params = fetch_params(None);
+        params.0.fields = Some("skeleton,features".to_string());
+        let output = server.fetch_node(params).await.unwrap();
+        assert!(output.contains("fn load_config() { … }"), "{}", output);
+        assert!(!output.contains("validate();"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_resolution_report_lists_unresolved_prefixes() {
+        let tmp = snippet_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let params = || Parameters(ResolutionReportParams { max_prefixes: None });
+        let output = server.resolution_report(params()).await.unwrap();
+        assert!(output.contains("not recorded"), "{}", output);
+
+        let mut graph = storage::load(tmp.path()).unwrap();
+        graph.metadata.unresolved_imports = Some(rpg_core::graph::UnresolvedImports {
+            imports: 4,
+            unresolved: 3,
+            prefixes: vec![rpg_core::graph::UnresolvedImportPrefix {
+                prefix: "@app/utils".to_string(),
+                count: 3,
+                files: 2,
+                example: "@app/utils/dates".to_string(),
+                origin: rpg_core::graph::ImportOrigin::InRepo,
+            }],
+        });
+        storage::save(tmp.path(), &graph).unwrap();
+        server.reload_rpg().await.unwrap();
+        let output = server.resolution_report(params()).await.unwrap();
+        assert!(output.contains("3 of 4 import statements"), "{}", output);
+        assert!(output.contains("@app/utils"), "{}", output);
+        assert!(output.contains("in-repo-unmapped"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_feature_sources_reports_matrix_and_plan() {
+        let tmp = snippet_fixture_project();
+        let mut graph = storage::load(tmp.path()).unwrap();
+        for entity in graph.entities.values_mut() {
+            entity.hierarchy_path = "Config/load/read".to_string();
+            entity.semantic_features = vec!["load configuration".to_string()];
+            entity.feature_source = Some("auto".to_string());
+        }
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let output = server
+            .feature_sources(Parameters(FeatureSourcesParams {
+                max_plan_areas: None,
+            }))
+            .await
+            .unwrap();
+        assert!(output.contains("## FEATURE SOURCES"), "{}", output);
+        assert!(output.contains("auto"), "{}", output);
+        assert!(output.contains("re-lift plan"), "{}", output);
+        assert!(output.contains("Config  100%"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_patch_maps_hunks_to_entities() {
+        let tmp = snippet_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let diff = "--- a/src/app.rs\n+++ b/src/app.rs\n@@ -4 +4 @@\n-    parse(raw);\n+    parse_strict(raw);\n--- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1 @@\n+fn new() {}\n";
+        let output = server
+            .summarize_patch(Parameters(SummarizePatchParams {
+                diff: diff.to_string(),
+                max_depth: None,
+                max_results: None,
+            }))
+            .await
+            .unwrap();
+        assert!(output.contains("src/app.rs:load_config"), "{}", output);
+        assert!(output.contains("false|false"), "{}", output);
+        assert!(output.contains("src/new.rs"), "{}", output);
+
+        let err = server
+            .summarize_patch(Parameters(SummarizePatchParams {
+                diff: "not a diff".to_string(),
+                max_depth: None,
+                max_results: None,
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.contains("No file sections"), "{}", err);
+    }
+
+    /// Six functions in one area, each calling the previous one, with a
+    /// semantic hierarchy: a three-batch plan at `max_batch_size` 2.
+    fn reconstruction_project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        let names = ["a", "b", "c", "d", "e", "f"];
+        for (i, name) in names.iter().enumerate() {
+            let id = format!("src/chain.rs:{}", name);
+            graph.insert_entity(Entity {
+                id: id.clone(),
+                kind: EntityKind::Function,
+                name: name.to_string(),
+                file: "src/chain.rs".into(),
+                line_start: i * 10 + 1,
+                line_end: i * 10 + 5,
+                parent_class: None,
+                semantic_features: vec![format!("compute step {}", name)],
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: "Chain/steps/compute".to_string(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+            graph.insert_into_hierarchy("Chain/steps/compute", &id);
+            if i > 0 {
+                graph.edges.push(rpg_core::graph::DependencyEdge {
+                    source: id,
+                    target: format!("src/chain.rs:{}", names[i - 1]),
+                    kind: EdgeKind::Invokes,
+                });
+            }
+        }
+        graph.assign_hierarchy_ids();
+        graph.refresh_metadata();
+        graph.metadata.semantic_hierarchy = true;
+        storage::save(tmp.path(), &graph).unwrap();
+        tmp
+    }
+
+    #[tokio::test]
+    async fn test_oversized_output_reassembled_across_continuations() {
+        let tmp = reconstruction_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let snapshot = || {
+            Parameters(SemanticSnapshotParams {
+                token_budget: None,
+                include_deps: Some(true),
+            })
+        };
+        let full = server.semantic_snapshot(snapshot()).await.unwrap();
+        assert_eq!(
+            server.limit_output("semantic_snapshot", full.clone()).await,
+            full
+        );
+
+        let limit = full.len() / 3 + crate::CONTINUATION_LINE_RESERVE + 40;
+        server.config.write().await.mcp.max_response_bytes = Some(limit);
+        let mut response = server.limit_output("semantic_snapshot", full.clone()).await;
+        let mut parts = Vec::new();
+        loop {
+            assert!(response.len() <= limit, "{} > {}", response.len(), limit);
+            let Some((chunk, footer)) = response.split_once("\n\ncontinuation: ") else {
+                parts.push(response);
+                break;
+            };
+            parts.push(chunk.to_string());
+            let token = footer.split_whitespace().next().unwrap().to_string();
+            response = server
+                .continue_output(Parameters(ContinueOutputParams { token }))
+                .await
+                .unwrap();
+        }
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts.concat(), full);
+
+        // The last part releases the output; unknown tokens are rejected.
+        let err = server
+            .continue_output(Parameters(ContinueOutputParams {
+                token: "out1.2".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.contains("Unknown or expired"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_resubmitted_hierarchy_reports_transitions() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for file in [
+            "src/auth/login.rs",
+            "src/auth/token.rs",
+            "src/db/pool.rs",
+            "src/db/query.rs",
+        ] {
+            graph.insert_entity(Entity {
+                id: format!("{}:run", file),
+                kind: EntityKind::Function,
+                name: "run".to_string(),
+                file: file.into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: vec!["run step".to_string()],
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.create_module_entities();
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let submit = |assignments: &str| {
+            Parameters(SubmitHierarchyParams {
+                assignments: assignments.to_string(),
+            })
+        };
+
+        let err = server.hierarchy_changes().await.unwrap_err();
+        assert!(err.contains("No hierarchy changes recorded"), "{}", err);
+
+        let first = server
+            .submit_hierarchy(submit(
+                r#"{"src/auth/login.rs": "Security/sessions/login",
+                    "src/auth/token.rs": "Security/sessions/tokens",
+                    "src/db/pool.rs": "Data/storage/pool",
+                    "src/db/query.rs": "Data/storage/query"}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(
+            first.contains(
+                "Changes vs previous structural hierarchy: 0 of 0 files moved, 4 newly assigned, 0 unassigned"
+            ),
+            "{}",
+            first
+        );
+
+        let second = server
+            .submit_hierarchy(submit(
+                r#"{"src/auth/login.rs": "Identity/sessions/login",
+                    "src/auth/token.rs": "Identity/sessions/tokens",
+                    "src/db/pool.rs": "Data/storage/pool",
+                    "src/db/query.rs": "Identity/lookup/query"}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(
+            second.contains(
+                "Changes vs previous semantic hierarchy: 3 of 4 files moved, 0 newly assigned, 0 unassigned"
+            ),
+            "{}",
+            second
+        );
+        assert!(
+            second.contains("renamed? Security -> Identity (67% overlap)"),
+            "{}",
+            second
+        );
+        assert!(
+            second.contains("Security -> Identity: 2 files"),
+            "{}",
+            second
+        );
+
+        let report = server.hierarchy_changes().await.unwrap();
+        assert!(report.contains("Data -> Data: 1\n"), "{}", report);
+        assert!(report.contains("Data -> Identity: 1\n"), "{}", report);
+        assert!(report.contains("Security -> Identity: 2\n"), "{}", report);
+        assert!(
+            report.contains("- src/db/query.rs: Data -> Identity"),
+            "{}",
+            report
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hierarchy_style_normalizes_or_rejects_submissions() {
+        use rpg_core::config::HierarchyStyleMode;
+
+        let tmp = lift_fixture_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let submit = |assignments: &str| {
+            Parameters(SubmitHierarchyParams {
+                assignments: assignments.to_string(),
+            })
+        };
+
+        server.config.write().await.encoding.hierarchy_style.mode = HierarchyStyleMode::Reject;
+        let err = server
+            .submit_hierarchy(submit(
+                r#"{"src/app.rs": "data_access/Run Queries/build sql"}"#,
+            ))
+            .await
+            .unwrap_err();
+        assert!(
+            err.contains("src/app.rs: area 'data_access' should be PascalCase ('DataAccess'); category 'Run Queries' should be lowercase words ('run queries')"),
+            "{}",
+            err
+        );
+
+        server.config.write().await.encoding.hierarchy_style.mode = HierarchyStyleMode::Normalize;
+        let err = server
+            .submit_hierarchy(submit(r#"{"src/app.rs": "Utils/run queries/build sql"}"#))
+            .await
+            .unwrap_err();
+        assert!(err.contains("area 'Utils' is too generic"), "{}", err);
+
+        let out = server
+            .submit_hierarchy(submit(
+                r#"{"src/app.rs": "data_access/Run Queries/build sql"}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(
+            out.contains("Normalized 1 hierarchy names to the style guide:\n  src/app.rs: 'data_access/Run Queries/build sql' → 'DataAccess/run queries/build sql'\n"),
+            "{}",
+            out
+        );
+        let guard = server.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        assert!(
+            graph
+                .entities
+                .values()
+                .all(|e| e.hierarchy_path == "DataAccess/run queries/build sql")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submission_reports_list_files_in_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for file in ["src/d.rs", "src/b.rs", "src/c.rs", "src/a.rs"] {
+            graph.insert_entity(Entity {
+                id: format!("{}:run", file),
+                kind: EntityKind::Function,
+                name: "run".to_string(),
+                file: file.into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: Vec::new(),
+                feature_source: None,
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.create_module_entities();
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        // Every file has one unlifted entity: ties list by path.
+        let status = server.lifting_status().await.unwrap();
+        assert!(
+            status.contains(
+                "Unlifted files (4 total):\n  src/a.rs (1 entities: run)\n  src/b.rs (1 entities: run)\n  src/c.rs (1 entities: run)\n  src/d.rs (1 entities: run)\n"
+            ),
+            "{}",
+            status
+        );
+
+        let out = server
+            .submit_hierarchy(Parameters(SubmitHierarchyParams {
+                assignments: r#"{"src/zeta.rs": "Core/misc/zeta",
+                    "src/d.rs": "Core/run/steps",
+                    "src/alpha.rs": "Core/misc/alpha",
+                    "src/b.rs": "Core/run/steps",
+                    "src/mid.rs": "Core/misc/mid",
+                    "src/a.rs": "Core/run/steps",
+                    "src/c.rs": "Core/run/steps"}"#
+                    .to_string(),
+            }))
+            .await
+            .unwrap();
+        assert!(
+            out.contains("unmatched_files: src/alpha.rs, src/mid.rs, src/zeta.rs\n"),
+            "{}",
+            out
+        );
+        let guard = server.graph.read().await;
+        let steps = &guard.as_ref().unwrap().hierarchy["Core"].children["run"].children["steps"];
+        // Files in path order, each file's entities as the file index has them.
+        assert_eq!(
+            steps.entities,
+            [
+                "src/a.rs:run",
+                "src/a.rs:a",
+                "src/b.rs:run",
+                "src/b.rs:b",
+                "src/c.rs:run",
+                "src/c.rs:c",
+                "src/d.rs:run",
+                "src/d.rs:d"
+            ]
+        );
+        drop(guard);
+
+        let out = server
+            .submit_file_syntheses(Parameters(SubmitFileSynthesesParams {
+                syntheses: r#"{"src/zeta.rs": "x", "src/alpha.rs": "y", "src/a.rs": "run steps"}"#
+                    .to_string(),
+            }))
+            .await
+            .unwrap();
+        assert!(
+            out.contains("unmatched_files: src/alpha.rs, src/zeta.rs\n"),
+            "{}",
+            out
+        );
+    }
+
+    #[tokio::test]
+    async fn test_area_charter_survives_resubmission() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for file in ["src/auth/login.rs", "src/db/pool.rs"] {
+            graph.insert_entity(Entity {
+                id: format!("{}:run", file),
+                kind: EntityKind::Function,
+                name: "run".to_string(),
+                file: file.into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: vec!["run step".to_string()],
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.create_module_entities();
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let submit = |assignments: &str| {
+            Parameters(SubmitHierarchyParams {
+                assignments: assignments.to_string(),
+            })
+        };
+        let charter = |path: &str, text: &str| {
+            Parameters(SetAreaCharterParams {
+                path: path.to_string(),
+                charter: Some(text.to_string()),
+            })
+        };
+
+        server
+            .submit_hierarchy(submit(
+                r#"{"src/auth/login.rs": "Security/sessions/login",
+                    "src/db/pool.rs": "Data/storage/pool"}"#,
+            ))
+            .await
+            .unwrap();
+        let err = server
+            .set_area_charter(charter("Billing", "Invoices"))
+            .await
+            .unwrap_err();
+        assert!(err.contains("No hierarchy node at 'Billing'"), "{}", err);
+        server
+            .set_area_charter(charter(
+                "Security/sessions",
+                "Login and token lifetimes only",
+            ))
+            .await
+            .unwrap();
+        server
+            .set_area_charter(charter("h:Data", "Persistence, no business rules"))
+            .await
+            .unwrap();
+
+        // Security/sessions survives the resubmission; Data does not exist anymore.
+        let out = server
+            .submit_hierarchy(submit(
+                r#"{"src/auth/login.rs": "Security/sessions/login",
+                    "src/db/pool.rs": "Storage/pool/pool"}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(out.contains("charters_kept: 1 of 2"), "{}", out);
+        assert!(out.contains("charters_dropped: Data"), "{}", out);
+
+        // Persisted: a new session sees the charter in fetch_node and rpg_info.
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let fetched = server
+            .fetch_node(Parameters(FetchNodeParams {
+                entity_id: "h:Security/sessions".to_string(),
+                entity_ids: None,
+                fields: None,
+                source_max_lines: None,
+                snippets: None,
+                file: None,
+                token_budget: None,
+            }))
+            .await
+            .unwrap();
+        assert!(
+            fetched.contains("Login and token lifetimes only"),
+            "{}",
+            fetched
+        );
+        let info = server.rpg_info().await.unwrap();
+        assert!(info.contains("Login and token lifetimes only"), "{}", info);
+        assert!(!info.contains("Persistence, no business rules"), "{}", info);
+
+        let guard = server.graph.read().await;
+        let prompt = rpg_encoder::hierarchy::format_charter_guidance(guard.as_ref().unwrap());
+        assert!(
+            prompt.contains("- Security/sessions: Login and token lifetimes only"),
+            "{}",
+            prompt
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_config_usage_matches_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for (name, line, keys) in [
+            ("read_only", 10, vec!["RPG_READ_ONLY"]),
+            ("log_level", 20, vec!["RPG_LOG", "RUST_LOG"]),
+            ("home", 30, vec!["HOME"]),
+        ] {
+            let deps = EntityDeps {
+                env_reads: keys.into_iter().map(String::from).collect(),
+                ..EntityDeps::default()
+            };
+            graph.insert_entity(Entity {
+                id: format!("src/env.rs:{}", name),
+                kind: EntityKind::Function,
+                name: name.to_string(),
+                file: "src/env.rs".into(),
+                line_start: line,
+                line_end: line + 5,
+                parent_class: None,
+                semantic_features: Vec::new(),
+                feature_source: None,
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps,
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let find = |key: &str, mode: Option<&str>| {
+            Parameters(FindConfigUsageParams {
+                key: key.to_string(),
+                mode: mode.map(String::from),
+            })
+        };
+
+        let exact = server
+            .find_config_usage(find("RPG_READ_ONLY", Some("exact")))
+            .await
+            .unwrap();
+        assert!(
+            exact.contains("RPG_READ_ONLY (1 entities)\n- src/env.rs:read_only (src/env.rs:10)"),
+            "{}",
+            exact
+        );
+        assert!(!exact.contains("RPG_LOG"), "{}", exact);
+
+        let prefix = server.find_config_usage(find("rpg_", None)).await.unwrap();
+        assert!(prefix.contains("RPG_LOG (1 entities)"), "{}", prefix);
+        assert!(prefix.contains("RPG_READ_ONLY (1 entities)"), "{}", prefix);
+        assert!(
+            !prefix.contains("RUST_LOG") && !prefix.contains("HOME"),
+            "{}",
+            prefix
+        );
+
+        let none = server
+            .find_config_usage(find("rpg_", Some("exact")))
+            .await
+            .unwrap();
+        assert!(none.contains("No entity reads a config key"), "{}", none);
+        let err = server
+            .find_config_usage(find("HOME", Some("regex")))
+            .await
+            .unwrap_err();
+        assert!(err.contains("Unknown mode"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_verdict_queues_entity_for_relift() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+        std::fs::write(
+            tmp.path().join("src/app.rs"),
+            "fn load(path: &str) -> Vec<String> {\n    let mut lines = Vec::new();\n    for line in std::fs::read_to_string(path).unwrap().lines() {\n        if !line.starts_with('#') {\n            lines.push(line.to_string());\n        }\n    }\n    lines\n}\n\nfn serve(port: u16) {\n    for attempt in 0..3 {\n        if bind(port + attempt) {\n            return;\n        }\n    }\n}\n",
+        )
+        .unwrap();
+        let mut graph = RPGraph::new("rust");
+        for (name, lines, feature) in [
+            ("load", (1, 9), "load config lines"),
+            ("serve", (11, 17), "serve http requests"),
+        ] {
+            graph.insert_entity(Entity {
+                id: format!("src/app.rs:{}", name),
+                kind: EntityKind::Function,
+                name: name.to_string(),
+                file: "src/app.rs".into(),
+                line_start: lines.0,
+                line_end: lines.1,
+                parent_class: None,
+                semantic_features: vec![feature.to_string()],
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+        }
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let sample = |seed| {
+            Parameters(VerifyFeaturesParams {
+                count: Some(2),
+                strategy: None,
+                seed: Some(seed),
+            })
+        };
+
+        let first = server.verify_features(sample(7)).await.unwrap();
+        assert_eq!(first, server.verify_features(sample(7)).await.unwrap());
+        assert!(
+            first.contains("### src/app.rs:serve (Function)\nFeatures: serve http requests\n```\nfn serve(port: u16) {"),
+            "{}",
+            first
+        );
+
+        let out = server
+            .submit_verification(Parameters(SubmitVerificationParams {
+                verdicts: r#"{"src/app.rs:load": "accurate", "src/app.rs:serve": "wrong"}"#
+                    .to_string(),
+            }))
+            .await
+            .unwrap();
+        assert!(
+            out.contains("verified_accuracy: 50% (1 accurate, 0 outdated, 1 wrong of 2 sampled"),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("queued_for_relift (1): src/app.rs:serve\n"),
+            "{}",
+            out
+        );
+        assert!(
+            server
+                .stale_entity_ids
+                .read()
+                .await
+                .contains("src/app.rs:serve")
+        );
+
+        let batch = server
+            .get_entities_for_lifting(Parameters(GetEntitiesForLiftingParams {
+                scope: "*".to_string(),
+                batch_index: None,
+            }))
+            .await
+            .unwrap();
+        assert!(
+            batch.contains("### src/app.rs:serve (Function)"),
+            "{}",
+            batch
+        );
+        assert!(!batch.contains("### src/app.rs:load"), "{}", batch);
+
+        // The estimate is persisted with the graph.
+        let status = RpgServer::new(tmp.path().to_path_buf())
+            .lifting_status()
+            .await
+            .unwrap();
+        assert!(status.contains("verified_accuracy: 50%"), "{}", status);
+    }
+
+    #[tokio::test]
+    async fn test_explain_entity_queues_misplaced_entity_for_routing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut graph = RPGraph::new("rust");
+        for (id, features, path) in [
+            (
+                "src/auth.rs:validate_token",
+                &["validate token", "reject expired session"][..],
+                "Security/auth/login",
+            ),
+            (
+                "src/auth.rs:run_query",
+                &["execute sql query", "open db connection"][..],
+                "Security/auth/session",
+            ),
+            (
+                "src/db.rs:query",
+                &["execute sql query", "map result rows"][..],
+                "Storage/sql/query",
+            ),
+        ] {
+            let (file, name) = id.split_once(':').unwrap();
+            graph.insert_entity(Entity {
+                id: id.to_string(),
+                kind: EntityKind::Function,
+                name: name.to_string(),
+                file: file.into(),
+                line_start: 1,
+                line_end: 3,
+                parent_class: None,
+                semantic_features: features.iter().map(|f| f.to_string()).collect(),
+                feature_source: Some("llm".to_string()),
+                lifted_at: None,
+                hierarchy_path: path.to_string(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+            graph.insert_into_hierarchy(path, id);
+        }
+        graph.metadata.semantic_hierarchy = true;
+        graph.assign_hierarchy_ids();
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        let out = server
+            .explain_entity(Parameters(ExplainEntityParams {
+                entity_id: "src/auth.rs:run_query".to_string(),
+                misplacement_threshold: None,
+            }))
+            .await
+            .unwrap();
+        assert!(out.contains("misplaced: true"), "{}", out);
+        assert!(out.contains("submit_routing_decisions("), "{}", out);
+        assert!(!out.contains("submit_hierarchy"), "{}", out);
+        let revision = graph_revision(&graph);
+        assert!(out.contains(&revision), "{}", out);
+
+        // Asking again does not queue the entity twice.
+        server
+            .explain_entity(Parameters(ExplainEntityParams {
+                entity_id: "src/auth.rs:run_query".to_string(),
+                misplacement_threshold: None,
+            }))
+            .await
+            .unwrap();
+        let pending: Vec<String> = server
+            .pending_routing
+            .read()
+            .await
+            .iter()
+            .map(|p| p.entity_id.clone())
+            .collect();
+        assert_eq!(pending, vec!["src/auth.rs:run_query"]);
+
+        server
+            .submit_routing_decisions(Parameters(SubmitRoutingDecisionsParams {
+                decisions: r#"{"src/auth.rs:run_query": "Storage/sql/query"}"#.to_string(),
+                graph_revision: revision,
+            }))
+            .await
+            .unwrap();
+        let guard = server.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        assert_eq!(
+            graph.entities["src/auth.rs:run_query"].hierarchy_path,
+            "Storage/sql/query"
+        );
+        assert_eq!(
+            graph.entities["src/auth.rs:validate_token"].hierarchy_path,
+            "Security/auth/login"
+        );
+    }
+
+    fn batch_params(batch_index: Option<usize>) -> Parameters<GetReconstructionBatchParams> {
+        Parameters(GetReconstructionBatchParams {
+            batch_index,
+            max_batch_size: Some(2),
+            include_modules: None,
+            reset: None,
+        })
+    }
+
+    fn done_params(ids: &[&str]) -> Parameters<MarkReconstructionDoneParams> {
+        Parameters(MarkReconstructionDoneParams {
+            entity_ids: ids
+                .iter()
+                .map(|id| format!("src/chain.rs:{}", id))
+                .collect(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reconstruction_batches_resume_after_restart() {
+        let tmp = reconstruction_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+
+        let first = server
+            .get_reconstruction_batch(batch_params(None))
+            .await
+            .unwrap();
+        assert!(
+            first.starts_with("New reconstruction plan: 6 entities, 3 batches"),
+            "{}",
+            first
+        );
+        assert!(
+            first.contains(
+                "RECONSTRUCTION BATCH 1/3 (area: Chain, 2 entities) | progress: 0/6 done"
+            ),
+            "{}",
+            first
+        );
+        assert!(first.contains("### src/chain.rs:a [pending]"), "{}", first);
+        assert!(first.contains("features: compute step b"), "{}", first);
+        assert!(
+            first.contains("deps pending: src/chain.rs:a\n"),
+            "{}",
+            first
+        );
+
+        let marked = server
+            .mark_reconstruction_done(done_params(&["a", "b"]))
+            .await
+            .unwrap();
+        assert!(
+            marked.contains("Marked 2 entities done. progress: 2/6 done"),
+            "{}",
+            marked
+        );
+        assert!(marked.contains("batch 2 of 3"), "{}", marked);
+        drop(server);
+
+        // A new session picks up the saved plan and progress.
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let second = server
+            .get_reconstruction_batch(batch_params(None))
+            .await
+            .unwrap();
+        assert!(!second.contains("New reconstruction plan"), "{}", second);
+        assert!(
+            second.starts_with(
+                "RECONSTRUCTION BATCH 2/3 (area: Chain, 2 entities) | progress: 2/6 done"
+            ),
+            "{}",
+            second
+        );
+        assert!(
+            second.contains("### src/chain.rs:c [pending]"),
+            "{}",
+            second
+        );
+        assert!(second.contains("deps done: src/chain.rs:b\n"), "{}", second);
+
+        let marked = server
+            .mark_reconstruction_done(done_params(&["c", "d", "missing"]))
+            .await
+            .unwrap();
+        assert!(
+            marked.contains("not in plan: src/chain.rs:missing"),
+            "{}",
+            marked
+        );
+        assert!(marked.contains("batch 3 of 3"), "{}", marked);
+
+        // Finished batches stay viewable by index.
+        let revisit = server
+            .get_reconstruction_batch(batch_params(Some(1)))
+            .await
+            .unwrap();
+        assert!(revisit.contains("### src/chain.rs:a [done]"), "{}", revisit);
+
+        server
+            .mark_reconstruction_done(done_params(&["e", "f"]))
+            .await
+            .unwrap();
+        let done = server
+            .get_reconstruction_batch(batch_params(None))
+            .await
+            .unwrap();
+        assert!(
+            done.starts_with("DONE — all 6 entities in 3 batches"),
+            "{}",
+            done
+        );
+
+        let err = server
+            .get_reconstruction_batch(batch_params(Some(4)))
+            .await
+            .unwrap_err();
+        assert!(err.contains("out of range (1..=3)"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_mark_reconstruction_done_without_plan_is_an_error() {
+        let tmp = reconstruction_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let err = server
+            .mark_reconstruction_done(done_params(&["a"]))
+            .await
+            .unwrap_err();
+        assert!(err.contains("get_reconstruction_batch first"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_tag_entities_by_scope_and_filter() {
+        let tmp = reconstruction_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let tag_params = |ids: &[&str], scope: Option<&str>| {
+            Parameters(TagEntitiesParams {
+                tags: vec!["Hot-Path".to_string()],
+                entity_ids: Some(
+                    ids.iter()
+                        .map(|id| format!("src/chain.rs:{}", id))
+                        .collect(),
+                ),
+                scope: scope.map(str::to_string),
+            })
+        };
+
+        let out = server
+            .tag_entities(tag_params(&["zz"], Some("area:Chain")))
+            .await
+            .unwrap();
+        assert!(
+            out.starts_with("Tagged 6 of 6 selected entities with hot-path."),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("unknown entity IDs: src/chain.rs:zz"),
+            "{}",
+            out
+        );
+        let out = server
+            .untag_entities(tag_params(&["c"], None))
+            .await
+            .unwrap();
+        assert!(out.contains("tags: hot-path (5)"), "{}", out);
+
+        let err = server
+            .tag_entities(Parameters(TagEntitiesParams {
+                tags: vec!["two words".to_string()],
+                entity_ids: None,
+                scope: Some("area:Chain".to_string()),
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Invalid tag 'two words'"), "{}", err);
+
+        // Persisted: a new session sees the tags.
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        assert!(server.rpg_info().await.unwrap().contains("hot-path"));
+        let explore_tagged = |id: &str| {
+            Parameters(ExploreRpgParams {
+                entity_id: format!("src/chain.rs:{}", id),
+                entity_ids: None,
+                direction: Some("downstream".to_string()),
+                depth: Some(1),
+                edge_filter: None,
+                entity_type_filter: None,
+                layer_filter: None,
+                tag_filter: Some("hot-path".to_string()),
+                format: Some("compact".to_string()),
+                max_results: None,
+            })
+        };
+        // d's only dependency is c, which lost the tag; e's is d, which kept it.
+        let from_d = server.explore_rpg(explore_tagged("d")).await.unwrap();
+        assert!(!from_d.contains("src/chain.rs:c"), "{}", from_d);
+        let from_e = server.explore_rpg(explore_tagged("e")).await.unwrap();
+        assert!(from_e.contains("src/chain.rs:d"), "{}", from_e);
+    }
+
+    #[tokio::test]
+    async fn test_annotate_entities_by_line_range() {
+        let tmp = reconstruction_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let annotate = |json: &str| {
+            Parameters(AnnotateEntitiesParams {
+                annotations: json.to_string(),
+            })
+        };
+
+        // Lines 12-23 overlap b (11-15) and c (21-25).
+        let out = server
+            .annotate_entities(annotate(
+                r#"{"source": "github", "annotations": [
+                    {"file": "src/chain.rs", "lines": [12, 23], "values": {"last_reviewer": "alice"}},
+                    {"file": "src/missing.rs", "lines": [1, 2], "values": {"pr": "1"}}
+                ]}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(
+            out.starts_with("Annotated 2 entities (2 values set, 0 removed)."),
+            "{}",
+            out
+        );
+        assert!(
+            out.contains("No entity matched: src/missing.rs:1-2"),
+            "{}",
+            out
+        );
+
+        let err = server
+            .annotate_entities(annotate(r#"{"source": "git hub", "annotations": []}"#))
+            .await
+            .unwrap_err();
+        assert!(err.contains("invalid annotation source"), "{}", err);
+
+        // Persisted and shown by fetch_node in a new session.
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let fetched = server
+            .fetch_node(Parameters(FetchNodeParams {
+                entity_id: "src/chain.rs:b".to_string(),
+                entity_ids: None,
+                fields: None,
+                source_max_lines: None,
+                snippets: None,
+                file: None,
+                token_budget: None,
+            }))
+            .await
+            .unwrap();
+        assert!(
+            fetched.contains("annotation.github.last_reviewer"),
+            "{}",
+            fetched
+        );
+        let guard = server.graph.read().await;
+        let graph = guard.as_ref().unwrap();
+        assert!(graph.entities["src/chain.rs:a"].attributes.is_empty());
+    }
+
+    /// Rust files under src/, lib/, src/generated/, and scripts/.
+    fn multi_dir_project() -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        for (file, name) in [
+            ("src/app.rs", "app"),
+            ("lib/util.rs", "util"),
+            ("src/generated/proto.rs", "proto"),
+            ("scripts/gen.rs", "gen"),
+        ] {
+            let path = tmp.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("pub fn {}() -> u32 {{\n    1\n}}\n", name)).unwrap();
+        }
+        tmp
+    }
+
+    fn build_params(json: &str) -> Parameters<BuildRpgParams> {
+        Parameters(serde_json::from_str(json).unwrap())
+    }
+
+    fn indexed_files(server_root: &std::path::Path) -> Vec<String> {
+        let graph = storage::load(server_root).unwrap();
+        graph
+            .file_index
+            .keys()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_build_rpg_with_multiple_include_and_exclude_globs() {
+        let tmp = multi_dir_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .build_rpg(build_params(
+                r#"{"language": "rust", "include": ["src/**", "lib/**"], "exclude": ["**/generated/**"]}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(out.contains("\ninclude: src/**, lib/**\n"), "{}", out);
+        assert!(out.contains("\nexclude: **/generated/**"), "{}", out);
+        assert_eq!(indexed_files(tmp.path()), vec!["lib/util.rs", "src/app.rs"]);
+
+        let err = server
+            .build_rpg(build_params(
+                r#"{"language": "rust", "exclude": ["src/[oops"]}"#,
+            ))
+            .await
+            .unwrap_err();
+        assert!(
+            err.starts_with("Invalid glob pattern 'src/[oops'"),
+            "{}",
+            err
+        );
+        assert!(err.contains("`[[]`"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_build_rpg_accepts_single_string_globs() {
+        let params = build_params(r#"{"include": "src/**", "exclude": "**/generated/**"}"#).0;
+        assert_eq!(params.include, Some(vec!["src/**".to_string()]));
+        assert_eq!(params.exclude, Some(vec!["**/generated/**".to_string()]));
+        let params = build_params(r#"{"include": null}"#).0;
+        assert_eq!((params.include, params.exclude), (None, None));
+
+        let tmp = multi_dir_project();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .build_rpg(build_params(
+                r#"{"language": "rust", "include": "src/**", "exclude": "**/generated/**"}"#,
+            ))
+            .await
+            .unwrap();
+        assert!(out.contains("\ninclude: src/**\n"), "{}", out);
+        assert!(out.contains("\nexclude: **/generated/**"), "{}", out);
+        assert_eq!(indexed_files(tmp.path()), vec!["src/app.rs"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_rpg_skips_linguist_marked_files() {
+        let tmp = multi_dir_project();
+        std::fs::write(
+            tmp.path().join(".gitattributes"),
+            "lib/** linguist-vendored
+src/generated/** linguist-generated=true
+",
+        )
+        .unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .build_rpg(build_params(r#"{"language": "rust"}"#))
+            .await
+            .unwrap();
+        assert!(out.contains("\nskipped_files: 2 ("), "{}", out);
+        assert_eq!(
+            indexed_files(tmp.path()),
+            vec!["scripts/gen.rs", "src/app.rs"]
+        );
+
+        std::fs::create_dir_all(tmp.path().join(".rpg")).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[encoding]\nhonor_gitattributes = false\n",
+        )
+        .unwrap();
+        let out = server
+            .build_rpg(build_params(r#"{"language": "rust"}"#))
+            .await
+            .unwrap();
+        assert!(!out.contains("skipped_files"), "{}", out);
+        assert_eq!(indexed_files(tmp.path()).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_explore_batch_returns_partial_results_past_time_budget() {
+        // A 2000-function binary call tree; each batch entry walks its subtree.
+        let tmp = tempfile::tempdir().unwrap();
+        let id = |i: usize| format!("src/tree.rs:f{}", i);
+        let mut graph = RPGraph::new("rust");
+        for i in 0..2000 {
+            graph.insert_entity(Entity {
+                id: id(i),
+                kind: EntityKind::Function,
+                name: format!("f{}", i),
+                file: "src/tree.rs".into(),
+                line_start: i + 1,
+                line_end: i + 1,
+                parent_class: None,
+                semantic_features: Vec::new(),
+                feature_source: None,
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: std::collections::BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: std::collections::BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            });
+            if i > 0 {
+                graph.edges.push(rpg_core::graph::DependencyEdge {
+                    source: id((i - 1) / 2),
+                    target: id(i),
+                    kind: EdgeKind::Invokes,
+                });
+            }
+        }
+        graph.refresh_metadata();
+        storage::save(tmp.path(), &graph).unwrap();
+        std::fs::write(
+            tmp.path().join(".rpg/config.toml"),
+            "[navigation]\nmax_tool_seconds = 0.000001\n",
+        )
+        .unwrap();
+
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let requested: Vec<String> = (0..20).map(id).collect();
+        let out = server
+            .explore_rpg(Parameters(ExploreRpgParams {
+                entity_id: String::new(),
+                entity_ids: Some(requested.clone()),
+                direction: Some("downstream".to_string()),
+                depth: Some(-1),
+                edge_filter: None,
+                entity_type_filter: None,
+                layer_filter: None,
+                tag_filter: None,
+                format: Some("compact".to_string()),
+                max_results: None,
+            }))
+            .await
+            .unwrap();
+
+        let note = out
+            .lines()
+            .find_map(|l| l.strip_prefix("[time budget exhausted after "))
+            .unwrap_or_else(|| panic!("no budget note: {}", out));
+        let completed: usize = note.split(' ').next().unwrap().parse().unwrap();
+        assert!(note.contains(" of 20 entities (navigation.max_tool_seconds = 0.000001)"));
+        assert!(completed < 20, "{}", out);
+        let remaining: Vec<String> = out
+            .lines()
+            .find_map(|l| l.strip_prefix("remaining_entity_ids: "))
+            .map(|ids| serde_json::from_str(ids).unwrap())
+            .unwrap();
+        assert_eq!(remaining, requested[completed..]);
+        assert!(
+            out.ends_with("resume: call explore_rpg again with entity_ids = remaining_entity_ids")
+        );
+
+        // The default budget covers the same batch.
+        std::fs::remove_file(tmp.path().join(".rpg/config.toml")).unwrap();
+        let server = RpgServer::new(tmp.path().to_path_buf());
+        let out = server
+            .explore_rpg(Parameters(ExploreRpgParams {
+                entity_id: String::new(),
+                entity_ids: Some(requested),
+                direction: Some("downstream".to_string()),
+                depth: Some(-1),
+                edge_filter: None,
+                entity_type_filter: None,
+                layer_filter: None,
+                tag_filter: None,
+                format: Some("compact".to_string()),
+                max_results: None,
+            }))
+            .await
+            .unwrap();
+        assert!(!out.contains("time budget exhausted"));
+    }
 }
@@ -18,12 +18,185 @@ use serde::Serialize;
 /// causing batch indices to shift as entities get lifted between calls.
 pub(crate) struct LiftingSession {
     pub(crate) scope_key: String,
-    pub(crate) raw_entities: Vec<rpg_parser::entities::RawEntity>,
-    pub(crate) batch_ranges: Vec<(usize, usize)>,
-    /// Number of entities auto-lifted during session setup.
-    pub(crate) auto_lifted: usize,
-    /// Entities auto-lifted with Review confidence — shown in batch 0 for LLM verification.
-    pub(crate) review_candidates: Vec<(String, Vec<String>)>,
+    pub(crate) queue: rpg_encoder::lift_queue::LiftQueue,
+}
+
+/// Features buffered by the non-final parts of a `submit_lift_results` transaction.
+pub(crate) struct LiftTransaction {
+    pub(crate) features: std::collections::HashMap<String, Vec<String>>,
+    pub(crate) parts: usize,
+    pub(crate) touched_at: std::time::Instant,
+}
+
+/// Outcome of one part of a `submit_lift_results` transaction.
+pub(crate) enum LiftTransactionStep {
+    /// A non-final part was buffered.
+    Buffered { parts: usize, entries: usize },
+    /// The final part arrived: every buffered feature, later parts winning.
+    Complete {
+        features: std::collections::HashMap<String, Vec<String>>,
+        parts: usize,
+    },
+}
+
+/// Source texts sent by `fetch_node` with `snippets: "hash"`, keyed by content
+/// hash. Holds at most [`crate::SNIPPET_CACHE_CAPACITY`] entries.
+#[derive(Default)]
+pub(crate) struct SnippetCache {
+    /// Hash → (source text, last use).
+    entries: std::collections::HashMap<String, (String, u64)>,
+    clock: u64,
+}
+
+impl SnippetCache {
+    /// Record that `text` was served under `hash`. Returns whether it was
+    /// already cached, i.e. the caller has seen this snippet before.
+    pub(crate) fn insert(&mut self, hash: &str, text: &str) -> bool {
+        self.clock += 1;
+        if let Some(entry) = self.entries.get_mut(hash) {
+            entry.1 = self.clock;
+            return true;
+        }
+        if self.entries.len() >= crate::SNIPPET_CACHE_CAPACITY
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(h, _)| h.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries
+            .insert(hash.to_string(), (text.to_string(), self.clock));
+        false
+    }
+
+    /// The text cached under `hash`, marking it recently used.
+    pub(crate) fn get(&mut self, hash: &str) -> Option<&str> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(hash)?;
+        entry.1 = self.clock;
+        Some(&entry.0)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Responses split at `max_response_bytes`, by output id, for `continue_output`.
+/// Entries expire after [`crate::CONTINUATION_TTL`]; at most
+/// [`crate::MAX_PENDING_OUTPUTS`] are kept.
+#[derive(Default)]
+pub(crate) struct OutputStore {
+    /// Output id → (chunks, created at).
+    outputs: std::collections::HashMap<u64, (Vec<String>, std::time::Instant)>,
+    next_id: u64,
+}
+
+impl OutputStore {
+    /// `text` if it fits in `limit` bytes; otherwise its first chunk with a
+    /// continuation line, keeping the rest for [`Self::continue_output`].
+    pub(crate) fn split(&mut self, text: String, limit: usize) -> String {
+        if text.len() <= limit {
+            return text;
+        }
+        let chunks = split_chunks(
+            &text,
+            limit.saturating_sub(crate::CONTINUATION_LINE_RESERVE),
+        );
+        self.expire();
+        if self.outputs.len() >= crate::MAX_PENDING_OUTPUTS
+            && let Some(oldest) = self
+                .outputs
+                .iter()
+                .min_by_key(|(_, (_, created))| *created)
+                .map(|(id, _)| *id)
+        {
+            self.outputs.remove(&oldest);
+        }
+        self.next_id += 1;
+        let id = self.next_id;
+        let first = with_continuation(&chunks[0], id, 0, chunks.len());
+        self.outputs.insert(id, (chunks, std::time::Instant::now()));
+        first
+    }
+
+    /// The chunk a continuation token points at, with the next token if more
+    /// remain. The output is dropped once its last chunk is served.
+    pub(crate) fn continue_output(&mut self, token: &str) -> Result<String, String> {
+        self.expire();
+        let unknown = || {
+            format!(
+                "Unknown or expired continuation token '{}'. Outputs are kept for {} minutes; re-run the original tool call.",
+                token,
+                crate::CONTINUATION_TTL.as_secs() / 60
+            )
+        };
+        let (id, part) = token
+            .trim()
+            .strip_prefix("out")
+            .and_then(|rest| rest.split_once('.'))
+            .and_then(|(id, part)| Some((id.parse::<u64>().ok()?, part.parse::<usize>().ok()?)))
+            .ok_or_else(unknown)?;
+        let (chunks, _) = self.outputs.get(&id).ok_or_else(unknown)?;
+        let chunk = chunks.get(part).ok_or_else(unknown)?;
+        let response = with_continuation(chunk, id, part, chunks.len());
+        if part + 1 == chunks.len() {
+            self.outputs.remove(&id);
+        }
+        Ok(response)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.outputs.clear();
+    }
+
+    fn expire(&mut self) {
+        self.outputs
+            .retain(|_, (_, created)| created.elapsed() < crate::CONTINUATION_TTL);
+    }
+}
+
+/// Split `text` into chunks of at most `max` bytes (at least one character
+/// each), ending each at its last line break when that is past the midpoint.
+fn split_chunks(text: &str, max: usize) -> Vec<String> {
+    let max = max.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while rest.len() > max {
+        let mut end = max;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        if let Some(newline) = rest[..end].rfind('\n')
+            && newline >= end / 2
+        {
+            end = newline + 1;
+        }
+        chunks.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    chunks.push(rest.to_string());
+    chunks
+}
+
+/// A chunk followed by the token for the next one, if any.
+fn with_continuation(chunk: &str, id: u64, part: usize, total: usize) -> String {
+    if part + 1 == total {
+        return chunk.to_string();
+    }
+    format!(
+        "{}\n\ncontinuation: out{}.{} (part {} of {}; call continue_output with this token for the next part)",
+        chunk,
+        id,
+        part + 1,
+        part + 1,
+        total
+    )
 }
 
 /// An entity pending LLM-based semantic routing.
@@ -49,7 +222,7 @@ pub(crate) struct HierarchySession {
     /// Approved functional areas from batch 0 (domain discovery)
     pub(crate) functional_areas: Option<Vec<String>>,
     /// Accumulated hierarchy assignments across batches
-    pub(crate) assignments: std::collections::HashMap<String, String>,
+    pub(crate) assignments: std::collections::BTreeMap<String, String>,
     /// Number of batches completed (0 = domain discovery, 1+ = file assignment)
     pub(crate) batches_completed: usize,
 }
@@ -84,51 +257,3 @@ pub(crate) fn clear_pending_routing(project_root: &std::path::Path) {
 pub(crate) fn graph_revision(graph: &RPGraph) -> String {
     graph.updated_at.to_rfc3339()
 }
-
-/// Format review candidates section for batch 0 output.
-/// Returns empty string if there are no review candidates.
-pub(crate) fn format_review_candidates(candidates: &[(String, Vec<String>)]) -> String {
-    if candidates.is_empty() {
-        return String::new();
-    }
-    let mut output = format!(
-        "## REVIEW CANDIDATES ({} entities)\n\nThese entities were auto-lifted but have moderate complexity. \
-         Verify the features below are correct. To override, include the entity in your `submit_lift_results` call.\n\n",
-        candidates.len(),
-    );
-    for (eid, features) in candidates {
-        output.push_str(&format!("- `{}`: {}\n", eid, features.join(", ")));
-    }
-    output.push('\n');
-    output
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_review_in_batch_0_output() {
-        let candidates = vec![
-            (
-                "src/server.rs:Server::get_name".to_string(),
-                vec!["return name".to_string()],
-            ),
-            (
-                "src/config.rs:Config::get_value".to_string(),
-                vec!["return value".to_string()],
-            ),
-        ];
-        let output = format_review_candidates(&candidates);
-        assert!(output.contains("## REVIEW CANDIDATES (2 entities)"));
-        assert!(output.contains("src/server.rs:Server::get_name"));
-        assert!(output.contains("return name"));
-        assert!(output.contains("submit_lift_results"));
-    }
-
-    #[test]
-    fn test_review_candidates_empty() {
-        let output = format_review_candidates(&[]);
-        assert!(output.is_empty());
-    }
-}
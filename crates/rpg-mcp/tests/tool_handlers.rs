@@ -8,6 +8,7 @@ use rpg_nav::search::{SearchMode, search};
 use rpg_nav::toon;
 use rpg_parser::entities::extract_entities;
 use rpg_parser::languages::Language;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 
@@ -26,9 +27,22 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         parent_class: None,
         semantic_features: features.into_iter().map(String::from).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -227,7 +241,7 @@ fn test_search_node() {
     let graph = make_test_graph();
 
     // Search by feature keyword "processing"
-    let results = search(&graph, "processing", SearchMode::Features, None, 10);
+    let results = search(&graph, "processing", SearchMode::Features, None, 10).unwrap();
     assert!(!results.is_empty(), "search should find matching entities");
 
     // The "process" entity has "data processing" as a feature
@@ -239,7 +253,7 @@ fn test_search_node() {
     );
 
     // Format as TOON and verify output structure
-    let toon_output = toon::format_search_results(&results);
+    let toon_output = toon::format_search_results(&results, None);
     assert!(
         toon_output.contains("process"),
         "TOON output should contain entity name 'process'"
@@ -250,7 +264,7 @@ fn test_search_node() {
     );
 
     // Also test snippets mode with entity name match
-    let snippet_results = search(&graph, "validate", SearchMode::Snippets, None, 10);
+    let snippet_results = search(&graph, "validate", SearchMode::Snippets, None, 10).unwrap();
     assert!(
         !snippet_results.is_empty(),
         "snippets search should match entity name"
@@ -647,9 +661,22 @@ fn test_restore_only_routing_refreshes_hierarchy_ids_and_containment() {
             "validate token".to_string(),
         ],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Auth/login".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     graph.insert_entity(auth_entity);
     graph.insert_into_hierarchy("Auth/login", "src/auth.py:login");
@@ -665,9 +692,22 @@ fn test_restore_only_routing_refreshes_hierarchy_ids_and_containment() {
         parent_class: None,
         semantic_features: vec!["load dataset".to_string(), "parse csv".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: "Data/loading".to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     };
     graph.insert_entity(data_entity);
     graph.insert_into_hierarchy("Data/loading", "src/data.py:load");
@@ -722,7 +762,12 @@ fn test_restore_only_routing_refreshes_hierarchy_ids_and_containment() {
             entity.semantic_features = features.clone();
         }
 
-        match rpg_encoder::evolution::find_best_hierarchy_path(&graph, &features) {
+        match rpg_encoder::evolution::find_best_hierarchy_path(
+            &graph,
+            Some(eid),
+            &features,
+            rpg_core::config::DEFAULT_ROUTING_NEIGHBOR_WEIGHT,
+        ) {
             Some(new_path) if new_path != original_path => {
                 if let Some(entity) = graph.entities.get_mut(eid) {
                     entity.hierarchy_path = new_path.clone();
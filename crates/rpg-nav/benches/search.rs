@@ -1,6 +1,7 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use rpg_core::graph::*;
 use rpg_nav::search::{SearchMode, search};
+use std::collections::{BTreeMap, BTreeSet};
 use std::hint::black_box;
 use std::path::PathBuf;
 
@@ -15,9 +16,22 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         parent_class: None,
         semantic_features: features.into_iter().map(String::from).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -0,0 +1,187 @@
+//! One-shot answers to "where is X implemented": the top context pack hits,
+//! each with its features, a short source excerpt, and its direct callers and
+//! callees as `file:line` references.
+
+use crate::context::{ContextPackRequest, build_context_pack};
+use rpg_core::graph::RPGraph;
+use rpg_core::scope::ScopeError;
+use rpg_core::tokens::Heuristic;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Most results an answer can hold (the context pack's search limit).
+pub const MAX_ANSWERS: usize = 10;
+
+/// Source lines shown per result.
+const EXCERPT_LINES: usize = 10;
+
+/// Callers and callees listed per result.
+const MAX_NEIGHBORS: usize = 5;
+
+/// A caller or callee of an answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct NeighborRef {
+    pub entity_id: String,
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// One entity that answers the query.
+#[derive(Debug, Clone, Serialize)]
+pub struct Answer {
+    pub entity_id: String,
+    pub name: String,
+    pub kind: String,
+    pub file: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub hierarchy_path: String,
+    pub features: Vec<String>,
+    /// First lines of the entity's source; `None` when the file can't be read.
+    pub excerpt: Option<String>,
+    pub callers: Vec<NeighborRef>,
+    /// Callers beyond those listed.
+    pub more_callers: usize,
+    pub callees: Vec<NeighborRef>,
+    /// Callees beyond those listed.
+    pub more_callees: usize,
+    pub relevance: f64,
+}
+
+/// Answer `query` with its `top` best matches (at most [`MAX_ANSWERS`]).
+/// `embedding_scores` makes the search hybrid, as in `context_pack`. Fails when
+/// `scope` is a malformed scope expression.
+pub fn answer_query(
+    graph: &RPGraph,
+    project_root: &Path,
+    query: &str,
+    scope: Option<&str>,
+    top: usize,
+    embedding_scores: Option<&HashMap<String, f64>>,
+) -> Result<Vec<Answer>, ScopeError> {
+    let request = ContextPackRequest {
+        query,
+        scope,
+        token_budget: usize::MAX,
+        estimator: &Heuristic,
+        include_source: true,
+        depth: 0,
+        neighbor_skeleton: None,
+    };
+    let pack = build_context_pack(graph, project_root, &request, embedding_scores)?;
+    let answers = pack
+        .primary_entities
+        .into_iter()
+        .take(top.min(MAX_ANSWERS))
+        .filter_map(|packed| {
+            let entity = graph.get_entity(&packed.entity_id)?;
+            let (callers, more_callers) = neighbor_refs(graph, &entity.deps.invoked_by);
+            let (callees, more_callees) = neighbor_refs(graph, &entity.deps.invokes);
+            let span = entity.line_end.saturating_sub(entity.line_start) + 1;
+            let excerpt = packed.source.map(|source| {
+                let mut lines: Vec<&str> = source.lines().take(EXCERPT_LINES).collect();
+                let more = format!("… ({} more lines)", span.saturating_sub(EXCERPT_LINES));
+                if span > EXCERPT_LINES {
+                    lines.push(&more);
+                }
+                lines.join("\n")
+            });
+            Some(Answer {
+                entity_id: packed.entity_id,
+                name: packed.name,
+                kind: packed.kind,
+                file: packed.file,
+                line_start: entity.line_start,
+                line_end: entity.line_end,
+                hierarchy_path: entity.hierarchy_path.clone(),
+                features: packed.features,
+                excerpt,
+                callers,
+                more_callers,
+                callees,
+                more_callees,
+                relevance: packed.relevance,
+            })
+        })
+        .collect();
+    Ok(answers)
+}
+
+fn neighbor_refs(graph: &RPGraph, ids: &[String]) -> (Vec<NeighborRef>, usize) {
+    let refs: Vec<NeighborRef> = ids
+        .iter()
+        .filter_map(|id| graph.get_entity(id))
+        .take(MAX_NEIGHBORS)
+        .map(|e| NeighborRef {
+            entity_id: e.id.clone(),
+            name: e.name.clone(),
+            file: e.file.display().to_string(),
+            line: e.line_start,
+        })
+        .collect();
+    let more = ids.len().saturating_sub(refs.len());
+    (refs, more)
+}
+
+/// Text rendering: one block per answer, opening with a sentence that places
+/// the entity, then its neighbors and excerpt.
+pub fn format_answers(query: &str, answers: &[Answer]) -> String {
+    if answers.is_empty() {
+        return format!("Nothing in the graph matches \"{}\".\n", query);
+    }
+    let mut out = format!("\"{}\" — {} result(s)\n", query, answers.len());
+    for (i, answer) in answers.iter().enumerate() {
+        out.push_str(&format!(
+            "\n{}. {} ({}) at {}:{}-{}",
+            i + 1,
+            answer.name,
+            answer.kind,
+            answer.file,
+            answer.line_start,
+            answer.line_end
+        ));
+        if !answer.hierarchy_path.is_empty() {
+            out.push_str(&format!(", in {}", answer.hierarchy_path));
+        }
+        out.push('\n');
+        if answer.features.is_empty() {
+            out.push_str("   Not lifted yet; no features recorded.\n");
+        } else {
+            out.push_str(&format!("   It does: {}.\n", answer.features.join("; ")));
+        }
+        let list = |refs: &[NeighborRef], more: usize| {
+            let mut items: Vec<String> = refs
+                .iter()
+                .map(|r| format!("{} ({}:{})", r.name, r.file, r.line))
+                .collect();
+            if more > 0 {
+                items.push(format!("+{} more", more));
+            }
+            items.join(", ")
+        };
+        if !answer.callers.is_empty() || answer.more_callers > 0 {
+            out.push_str(&format!(
+                "   Called by: {}\n",
+                list(&answer.callers, answer.more_callers)
+            ));
+        }
+        if !answer.callees.is_empty() || answer.more_callees > 0 {
+            out.push_str(&format!(
+                "   Calls: {}\n",
+                list(&answer.callees, answer.more_callees)
+            ));
+        }
+        if let Some(excerpt) = &answer.excerpt {
+            for line in excerpt.lines() {
+                if line.is_empty() {
+                    out.push_str("   |\n");
+                } else {
+                    out.push_str(&format!("   | {}\n", line));
+                }
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,21 @@
+//! Wall-clock budgets for navigation calls (`navigation.max_tool_seconds`).
+//!
+//! A deadline is an `Option<Instant>`; `None` means unbounded. Traversal and
+//! scoring loops check it cooperatively and stop early, reporting partial
+//! results instead of blocking the caller.
+
+use std::time::{Duration, Instant};
+
+/// The deadline `seconds` from now, or `None` when `seconds` is not a
+/// positive finite number.
+pub fn deadline(seconds: f64) -> Option<Instant> {
+    if !(seconds.is_finite() && seconds > 0.0) {
+        return None;
+    }
+    Instant::now().checked_add(Duration::from_secs_f64(seconds))
+}
+
+/// Whether `deadline` has passed.
+pub fn expired(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|d| Instant::now() >= d)
+}
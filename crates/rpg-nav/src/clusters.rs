@@ -0,0 +1,458 @@
+//! Coarse clustered index over embedding vectors, for approximate semantic
+//! search on very large graphs.
+//!
+//! An inverted-file layout: spherical k-means centroids, each with the
+//! entities that have a feature vector closest to it. A query ranks the
+//! centroids and only the members of the closest few clusters are scored
+//! exactly. Entities are added and removed against fixed centroids; the
+//! centroids are retrained once the index has grown or shrunk too far from
+//! the set they were trained on.
+
+use anyhow::{Context, Result, ensure};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Below this many entities, scoring every vector is fast enough and exact.
+pub const MIN_CLUSTERED_ENTITIES: usize = 20_000;
+
+/// Magic bytes for the serialized cluster index.
+const MAGIC: u32 = 0x5250_4743; // "RPGC"
+const FORMAT_VERSION: u32 = 1;
+
+/// Upper bound on the number of clusters.
+const MAX_CLUSTERS: usize = 1024;
+
+/// Vectors sampled per cluster to train the centroids.
+const TRAINING_POINTS_PER_CLUSTER: usize = 32;
+
+/// Lloyd iterations when training.
+const KMEANS_ITERATIONS: usize = 8;
+
+/// Inverted-file index: centroids and the entities listed under each.
+#[derive(Debug, Clone, Default)]
+pub struct ClusterIndex {
+    /// Unit-length centroids.
+    centroids: Vec<Vec<f32>>,
+    /// Entities with at least one vector assigned to each centroid.
+    members: Vec<BTreeSet<String>>,
+    /// Entity → the clusters it is listed under.
+    assignments: HashMap<String, Vec<u32>>,
+    /// Entity count when the centroids were trained.
+    trained_on: usize,
+}
+
+impl ClusterIndex {
+    /// Train centroids on a sample of the vectors and assign every entity.
+    ///
+    /// Deterministic for a given input: the sample and the initial centroids
+    /// are taken at a fixed stride over the vectors in entity ID order.
+    pub fn train<'a>(entities: impl IntoIterator<Item = (&'a str, &'a [Vec<f32>])>) -> Self {
+        let mut entities: Vec<(&str, &[Vec<f32>])> = entities.into_iter().collect();
+        entities.sort_unstable_by_key(|(id, _)| *id);
+        let vectors: Vec<Vec<f32>> = entities
+            .iter()
+            .flat_map(|(_, vs)| vs.iter())
+            .filter_map(|v| normalized(v))
+            .collect();
+
+        let mut index = Self {
+            trained_on: entities.len(),
+            ..Self::default()
+        };
+        if vectors.is_empty() {
+            return index;
+        }
+
+        let k = vectors.len().isqrt().clamp(1, MAX_CLUSTERS);
+        let sample = strided(&vectors, k * TRAINING_POINTS_PER_CLUSTER);
+        let mut centroids: Vec<Vec<f32>> = strided(&sample, k)
+            .into_iter()
+            .map(|v| (*v).clone())
+            .collect();
+        for _ in 0..KMEANS_ITERATIONS {
+            let nearest: Vec<usize> = sample.par_iter().map(|v| nearest(&centroids, v)).collect();
+            let dimension = centroids[0].len();
+            let mut sums = vec![vec![0.0_f32; dimension]; centroids.len()];
+            for (v, &c) in sample.iter().zip(&nearest) {
+                for (s, x) in sums[c].iter_mut().zip(v.iter()) {
+                    *s += x;
+                }
+            }
+            for (centroid, sum) in centroids.iter_mut().zip(sums) {
+                // An empty cluster keeps its previous centroid.
+                if let Some(updated) = normalized(&sum) {
+                    *centroid = updated;
+                }
+            }
+        }
+
+        index.members = vec![BTreeSet::new(); centroids.len()];
+        index.centroids = centroids;
+        let assigned: Vec<(&str, Vec<u32>)> = entities
+            .par_iter()
+            .map(|(id, vs)| (*id, index.clusters_for(vs)))
+            .collect();
+        for (id, clusters) in assigned {
+            index.assign(id, clusters);
+        }
+        index
+    }
+
+    /// Add an entity, or move it after its vectors changed. The centroids
+    /// stay fixed; see [`Self::is_stale`].
+    pub fn insert(&mut self, id: &str, vectors: &[Vec<f32>]) {
+        self.remove(id);
+        if !self.centroids.is_empty() {
+            let clusters = self.clusters_for(vectors);
+            self.assign(id, clusters);
+        }
+    }
+
+    /// Drop an entity from every list it is in.
+    pub fn remove(&mut self, id: &str) {
+        for c in self.assignments.remove(id).unwrap_or_default() {
+            self.members[c as usize].remove(id);
+        }
+    }
+
+    /// Number of indexed entities.
+    pub fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    /// Number of clusters.
+    pub fn cluster_count(&self) -> usize {
+        self.centroids.len()
+    }
+
+    /// The index has doubled or halved since training, so the centroids no
+    /// longer reflect the data well and should be retrained.
+    pub fn is_stale(&self) -> bool {
+        self.len() > 2 * self.trained_on || 2 * self.len() < self.trained_on
+    }
+
+    /// Entities in the clusters closest to `query`: the top eighth of the
+    /// clusters, and at least 8.
+    pub fn candidates(&self, query: &[f32]) -> HashSet<&str> {
+        let Some(query) = normalized(query) else {
+            return HashSet::new();
+        };
+        let mut ranked: Vec<(usize, f32)> = self
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, dot(c, &query)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        let probes = (self.centroids.len() / 8).max(8);
+        ranked
+            .iter()
+            .take(probes)
+            .flat_map(|(i, _)| self.members[*i].iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Serialize for persistence next to the vectors.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let dimension = self.centroids.first().map_or(0, Vec::len);
+        for header in [
+            MAGIC,
+            FORMAT_VERSION,
+            dimension as u32,
+            self.centroids.len() as u32,
+            self.trained_on as u32,
+        ] {
+            buf.extend_from_slice(&header.to_le_bytes());
+        }
+        for (centroid, members) in self.centroids.iter().zip(&self.members) {
+            for val in centroid {
+                buf.extend_from_slice(&val.to_le_bytes());
+            }
+            buf.extend_from_slice(&(members.len() as u32).to_le_bytes());
+            for id in members {
+                buf.extend_from_slice(&(id.len() as u32).to_le_bytes());
+                buf.extend_from_slice(id.as_bytes());
+            }
+        }
+        buf
+    }
+
+    /// Inverse of [`Self::to_bytes`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut cursor = data;
+        ensure!(
+            read_u32(&mut cursor)? == MAGIC,
+            "invalid cluster index magic bytes"
+        );
+        ensure!(
+            read_u32(&mut cursor)? == FORMAT_VERSION,
+            "unsupported cluster index version"
+        );
+        let dimension = read_u32(&mut cursor)? as usize;
+        let cluster_count = read_u32(&mut cursor)? as usize;
+        let trained_on = read_u32(&mut cursor)? as usize;
+        // Each cluster holds at least its centroid and member count; checking
+        // that before allocating keeps a corrupt header from sizing buffers.
+        let min_cluster_len = dimension.saturating_mul(4).saturating_add(4);
+        ensure!(
+            cluster_count.saturating_mul(min_cluster_len) <= cursor.len(),
+            "unexpected end of cluster index"
+        );
+
+        let mut index = Self {
+            trained_on,
+            ..Self::default()
+        };
+        for c in 0..cluster_count {
+            let mut centroid = Vec::with_capacity(dimension);
+            for _ in 0..dimension {
+                centroid.push(f32::from_le_bytes(read_u32(&mut cursor)?.to_le_bytes()));
+            }
+            index.centroids.push(centroid);
+
+            let member_count = read_u32(&mut cursor)? as usize;
+            let mut members = BTreeSet::new();
+            for _ in 0..member_count {
+                let len = read_u32(&mut cursor)? as usize;
+                ensure!(cursor.len() >= len, "unexpected end of cluster index");
+                let id = std::str::from_utf8(&cursor[..len])
+                    .context("invalid entity id in cluster index")?
+                    .to_string();
+                cursor = &cursor[len..];
+                index
+                    .assignments
+                    .entry(id.clone())
+                    .or_default()
+                    .push(c as u32);
+                members.insert(id);
+            }
+            index.members.push(members);
+        }
+        ensure!(cursor.is_empty(), "trailing bytes in cluster index");
+        Ok(index)
+    }
+
+    /// The distinct clusters nearest to each of an entity's vectors.
+    fn clusters_for(&self, vectors: &[Vec<f32>]) -> Vec<u32> {
+        let mut clusters: Vec<u32> = vectors
+            .iter()
+            .filter_map(|v| normalized(v))
+            .map(|v| nearest(&self.centroids, &v) as u32)
+            .collect();
+        clusters.sort_unstable();
+        clusters.dedup();
+        clusters
+    }
+
+    fn assign(&mut self, id: &str, clusters: Vec<u32>) {
+        if clusters.is_empty() {
+            return;
+        }
+        for &c in &clusters {
+            self.members[c as usize].insert(id.to_string());
+        }
+        self.assignments.insert(id.to_string(), clusters);
+    }
+}
+
+/// Index of the centroid with the highest dot product with `v`.
+fn nearest(centroids: &[Vec<f32>], v: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(c, v)))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map_or(0, |(i, _)| i)
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// `v` scaled to unit length; `None` for a zero vector.
+fn normalized(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = dot(v, v).sqrt();
+    (norm > 0.0).then(|| v.iter().map(|x| x / norm).collect())
+}
+
+/// Up to `n` items taken at an even stride.
+fn strided<T>(items: &[T], n: usize) -> Vec<&T> {
+    let step = items.len().div_ceil(n.max(1)).max(1);
+    items.iter().step_by(step).take(n).collect()
+}
+
+fn read_u32(cursor: &mut &[u8]) -> Result<u32> {
+    ensure!(cursor.len() >= 4, "unexpected end of cluster index");
+    let bytes: [u8; 4] = cursor[..4].try_into().unwrap();
+    *cursor = &cursor[4..];
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMENSION: usize = 32;
+
+    /// Deterministic pseudo-random numbers in [-1, 1).
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            ((self.0 >> 40) as f32 / (1u64 << 23) as f32) - 1.0
+        }
+
+        fn vector(&mut self) -> Vec<f32> {
+            (0..DIMENSION).map(|_| self.next()).collect()
+        }
+
+        /// A point near `center`.
+        fn near(&mut self, center: &[f32]) -> Vec<f32> {
+            center.iter().map(|c| c + 0.35 * self.next()).collect()
+        }
+    }
+
+    /// Entities with 1–3 feature vectors around 60 topic centers.
+    fn corpus(entities: usize, seed: u64) -> Vec<(String, Vec<Vec<f32>>)> {
+        let mut rng = Lcg(seed);
+        let topics: Vec<Vec<f32>> = (0..60).map(|_| rng.vector()).collect();
+        (0..entities)
+            .map(|i| {
+                let features = 1 + i % 3;
+                let vectors = (0..features)
+                    .map(|f| rng.near(&topics[(i * 7 + f * 13) % topics.len()]))
+                    .collect();
+                (format!("src/mod{}.rs:entity{}", i % 50, i), vectors)
+            })
+            .collect()
+    }
+
+    fn train(corpus: &[(String, Vec<Vec<f32>>)]) -> ClusterIndex {
+        ClusterIndex::train(corpus.iter().map(|(id, vs)| (id.as_str(), vs.as_slice())))
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        dot(a, b) / (dot(a, a).sqrt() * dot(b, b).sqrt())
+    }
+
+    /// Top 10 entity IDs by max-cosine, optionally restricted to `only`.
+    fn top10<'a>(
+        corpus: &'a [(String, Vec<Vec<f32>>)],
+        query: &[f32],
+        only: Option<&HashSet<&str>>,
+    ) -> Vec<&'a str> {
+        let mut scored: Vec<(&str, f32)> = corpus
+            .iter()
+            .filter(|(id, _)| only.is_none_or(|c| c.contains(id.as_str())))
+            .map(|(id, vs)| {
+                let best = vs
+                    .iter()
+                    .map(|v| cosine(query, v))
+                    .fold(f32::NEG_INFINITY, f32::max);
+                (id.as_str(), best)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        scored.into_iter().take(10).map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn test_top10_recall_against_exhaustive() {
+        let corpus = corpus(6000, 7);
+        let index = train(&corpus);
+        assert!(index.cluster_count() > 50);
+
+        let mut rng = Lcg(99);
+        let mut hits = 0;
+        let mut scanned = 0;
+        let queries = 50;
+        for q in 0..queries {
+            let query = rng.near(&corpus[q * 97].1[0]);
+            let candidates = index.candidates(&query);
+            scanned += candidates.len();
+            let exact = top10(&corpus, &query, None);
+            let approx = top10(&corpus, &query, Some(&candidates));
+            hits += exact.iter().filter(|id| approx.contains(id)).count();
+        }
+        let recall = hits as f64 / (queries * 10) as f64;
+        assert!(recall >= 0.9, "top-10 recall {:.3}", recall);
+        // The pre-filter must actually skip most of the corpus.
+        assert!(
+            scanned / queries < corpus.len() / 2,
+            "scanned {} per query",
+            scanned / queries
+        );
+    }
+
+    #[test]
+    fn test_incremental_insert_and_remove() {
+        let corpus = corpus(2000, 3);
+        let mut index = train(&corpus);
+        assert_eq!(index.len(), 2000);
+
+        let probe = corpus[0].1[0].clone();
+        index.insert("new.rs:twin", std::slice::from_ref(&probe));
+        assert_eq!(index.len(), 2001);
+        assert!(index.candidates(&probe).contains("new.rs:twin"));
+
+        // Re-inserting moves the entity instead of duplicating it.
+        let elsewhere = corpus[1999].1[0].clone();
+        index.insert("new.rs:twin", std::slice::from_ref(&elsewhere));
+        assert_eq!(index.len(), 2001);
+        assert!(index.candidates(&elsewhere).contains("new.rs:twin"));
+
+        index.remove("new.rs:twin");
+        index.remove(&corpus[0].0);
+        assert_eq!(index.len(), 1999);
+        assert!(!index.candidates(&probe).contains(corpus[0].0.as_str()));
+        assert!(index.members.iter().all(|m| !m.contains("new.rs:twin")));
+    }
+
+    #[test]
+    fn test_stale_after_doubling() {
+        let corpus = corpus(1000, 5);
+        let mut index = train(&corpus[..400]);
+        assert!(!index.is_stale());
+        for (id, vectors) in &corpus[400..] {
+            index.insert(id, vectors);
+        }
+        assert!(index.is_stale());
+        assert!(!train(&corpus).is_stale());
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let corpus = corpus(500, 11);
+        let index = train(&corpus);
+        let restored = ClusterIndex::from_bytes(&index.to_bytes()).unwrap();
+        assert_eq!(restored.centroids, index.centroids);
+        assert_eq!(restored.members, index.members);
+        assert_eq!(restored.len(), index.len());
+        assert_eq!(restored.trained_on, index.trained_on);
+
+        let bytes = index.to_bytes();
+        assert!(ClusterIndex::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(ClusterIndex::from_bytes(b"nope").is_err());
+
+        // A header claiming a huge dimension is rejected, not allocated.
+        let mut corrupt = bytes.clone();
+        corrupt[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ClusterIndex::from_bytes(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_train_empty() {
+        let index = ClusterIndex::train(std::iter::empty());
+        assert!(index.is_empty());
+        assert!(index.candidates(&[1.0; DIMENSION]).is_empty());
+    }
+}
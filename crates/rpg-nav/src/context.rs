@@ -1,17 +1,27 @@
 //! ContextPack: single-call retrieval that searches, fetches, and explores in one operation.
 
 use crate::explore::{Direction, get_neighbors};
-use crate::search::{SearchMode, SearchParams, search_with_params};
-use rpg_core::graph::RPGraph;
+use crate::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use rpg_core::graph::{Entity, RPGraph};
+use rpg_core::scope::ScopeError;
+use rpg_core::tokens::TokenEstimator;
 use std::collections::{HashMap, HashSet};
 
+/// Renders an entity's skeleton (signatures and structure, bodies elided).
+pub type SkeletonFn<'a> = &'a dyn Fn(&Entity) -> Option<String>;
+
 /// Request parameters for building a context pack.
 pub struct ContextPackRequest<'a> {
     pub query: &'a str,
     pub scope: Option<&'a str>,
     pub token_budget: usize,
+    /// Sizes the packed entities against `token_budget`.
+    pub estimator: &'a dyn TokenEstimator,
     pub include_source: bool,
     pub depth: usize,
+    /// Skeleton shown as a neighborhood entity's source. `None` leaves
+    /// neighbors without source.
+    pub neighbor_skeleton: Option<SkeletonFn<'a>>,
 }
 
 /// A single entity in the packed context.
@@ -36,12 +46,14 @@ pub struct ContextPackResult {
 }
 
 /// Build a context pack: search → fetch → expand neighbors → budget-trim.
+///
+/// Fails when `request.scope` is a malformed scope expression.
 pub fn build_context_pack(
     graph: &RPGraph,
     project_root: &std::path::Path,
     request: &ContextPackRequest,
     embedding_scores: Option<&HashMap<String, f64>>,
-) -> ContextPackResult {
+) -> Result<ContextPackResult, ScopeError> {
     // Step 1: Search for primary candidates
     let results = search_with_params(
         graph,
@@ -53,10 +65,19 @@ pub fn build_context_pack(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
         },
-    );
+    )?;
 
     let mut primary: Vec<PackedEntity> = Vec::new();
     let mut seen_ids: HashSet<String> = HashSet::new();
@@ -77,7 +98,7 @@ pub fn build_context_pack(
             None
         };
 
-        let deps_summary = format_deps_summary(&entity.deps);
+        let deps_summary = entity.deps.summary();
 
         primary.push(PackedEntity {
             entity_id: r.entity_id.clone(),
@@ -122,7 +143,7 @@ pub fn build_context_pack(
                     file: entity.file.display().to_string(),
                     kind: format!("{:?}", entity.kind).to_lowercase(),
                     features: entity.semantic_features.clone(),
-                    source: None, // No source for neighbors to save tokens
+                    source: request.neighbor_skeleton.and_then(|render| render(entity)),
                     deps_summary: String::new(),
                     relevance: 0.0,
                 });
@@ -133,33 +154,42 @@ pub fn build_context_pack(
     }
 
     // Step 3: Token budgeting — estimate and trim
-    let mut token_estimate = estimate_tokens(&primary, &neighborhood);
+    let mut token_estimate = estimate_tokens(&primary, &neighborhood, request.estimator);
 
-    // Drop lowest-relevance neighborhood entities first, then primary
+    // Drop neighbor skeletons, then the neighbors themselves (farthest
+    // first), then primary entities
+    for i in (0..neighborhood.len()).rev() {
+        if token_estimate <= request.token_budget {
+            break;
+        }
+        if neighborhood[i].source.take().is_some() {
+            token_estimate = estimate_tokens(&primary, &neighborhood, request.estimator);
+        }
+    }
     while token_estimate > request.token_budget && !neighborhood.is_empty() {
         neighborhood.pop();
-        token_estimate = estimate_tokens(&primary, &neighborhood);
+        token_estimate = estimate_tokens(&primary, &neighborhood, request.estimator);
     }
     while token_estimate > request.token_budget && primary.len() > 1 {
         primary.pop();
-        token_estimate = estimate_tokens(&primary, &neighborhood);
+        token_estimate = estimate_tokens(&primary, &neighborhood, request.estimator);
     }
     // If still over budget with 1 primary entity, truncate its source
     if token_estimate > request.token_budget && primary.len() == 1 {
         primary[0].source = None;
-        token_estimate = estimate_tokens(&primary, &neighborhood);
+        token_estimate = estimate_tokens(&primary, &neighborhood, request.estimator);
     }
 
-    ContextPackResult {
+    Ok(ContextPackResult {
         primary_entities: primary,
         neighborhood_entities: neighborhood,
         token_estimate,
-    }
+    })
 }
 
 fn read_source_truncated(
     project_root: &std::path::Path,
-    entity: &rpg_core::graph::Entity,
+    entity: &Entity,
     max_lines: usize,
 ) -> Option<String> {
     let file_path = project_root.join(&entity.file);
@@ -184,69 +214,39 @@ fn read_source_truncated(
     }
 }
 
-/// Format a compact dependency summary line for an entity.
-/// Returns empty string if the entity has no deps.
-/// Format: `Called by: a, b | Calls: c, d | Inherits: e`
-/// Each dep list is capped at 5 entries.
-pub fn format_deps_summary(deps: &rpg_core::graph::EntityDeps) -> String {
-    let mut parts = Vec::new();
-    if !deps.invoked_by.is_empty() {
-        let names: Vec<&str> = deps.invoked_by.iter().take(5).map(|s| s.as_str()).collect();
-        parts.push(format!("Called by: {}", names.join(", ")));
-    }
-    if !deps.invokes.is_empty() {
-        let names: Vec<&str> = deps.invokes.iter().take(5).map(|s| s.as_str()).collect();
-        parts.push(format!("Calls: {}", names.join(", ")));
-    }
-    if !deps.inherits.is_empty() {
-        let names: Vec<&str> = deps.inherits.iter().take(5).map(|s| s.as_str()).collect();
-        parts.push(format!("Inherits: {}", names.join(", ")));
-    }
-    if !deps.renders.is_empty() {
-        let names: Vec<&str> = deps.renders.iter().take(5).map(|s| s.as_str()).collect();
-        parts.push(format!("Renders: {}", names.join(", ")));
-    }
-    if !deps.data_flows_to.is_empty() {
-        let names: Vec<&str> = deps
-            .data_flows_to
-            .iter()
-            .take(5)
-            .map(|s| s.as_str())
-            .collect();
-        parts.push(format!("Data flows to: {}", names.join(", ")));
-    }
-    if !deps.data_flows_from.is_empty() {
-        let names: Vec<&str> = deps
-            .data_flows_from
-            .iter()
-            .take(5)
-            .map(|s| s.as_str())
-            .collect();
-        parts.push(format!("Data from: {}", names.join(", ")));
-    }
-    parts.join(" | ")
-}
-
-fn estimate_tokens(primary: &[PackedEntity], neighborhood: &[PackedEntity]) -> usize {
-    let mut chars = 0usize;
+fn estimate_tokens(
+    primary: &[PackedEntity],
+    neighborhood: &[PackedEntity],
+    estimator: &dyn TokenEstimator,
+) -> usize {
+    let text_tokens = |fields: &[&str], p: &PackedEntity| {
+        fields.iter().map(|f| estimator.estimate(f)).sum::<usize>()
+            + p.features
+                .iter()
+                .map(|f| estimator.estimate(f))
+                .sum::<usize>()
+            + p.source.as_deref().map_or(0, |s| estimator.estimate(s))
+    };
+    let mut tokens = 0usize;
     for p in primary {
-        chars += p.entity_id.len() + p.name.len() + p.file.len() + p.kind.len();
-        chars += p.features.iter().map(|f| f.len()).sum::<usize>();
-        chars += p.source.as_ref().map_or(0, |s| s.len());
-        chars += p.deps_summary.len();
+        tokens += text_tokens(
+            &[&p.entity_id, &p.name, &p.file, &p.kind, &p.deps_summary],
+            p,
+        );
     }
     for n in neighborhood {
-        chars += n.entity_id.len() + n.name.len() + n.file.len();
-        chars += n.features.iter().map(|f| f.len()).sum::<usize>();
+        tokens += text_tokens(&[&n.entity_id, &n.name, &n.file], n);
     }
-    // ~4 chars per token + overhead for formatting
-    chars / 4 + primary.len() * 10 + neighborhood.len() * 5
+    // Plus overhead for formatting
+    tokens + primary.len() * 10 + neighborhood.len() * 5
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+    use rpg_core::tokens::Heuristic;
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, features: Vec<&str>) -> Entity {
@@ -260,9 +260,22 @@ mod tests {
             parent_class: None,
             semantic_features: features.into_iter().map(|s| s.to_string()).collect(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -299,7 +312,7 @@ mod tests {
 
     #[test]
     fn test_estimate_tokens_empty() {
-        assert_eq!(estimate_tokens(&[], &[]), 0);
+        assert_eq!(estimate_tokens(&[], &[], &Heuristic), 0);
     }
 
     #[test]
@@ -314,56 +327,26 @@ mod tests {
             deps_summary: String::new(),
             relevance: 1.0,
         }];
-        let estimate = estimate_tokens(&primary, &[]);
+        let estimate = estimate_tokens(&primary, &[], &Heuristic);
         assert!(estimate > 0, "should produce non-zero token estimate");
         // source has 11 chars ~= 2-3 tokens, plus overhead
         assert!(estimate < 100, "should be reasonable for one small entity");
     }
 
-    #[test]
-    fn test_format_deps_summary_empty() {
-        let deps = EntityDeps::default();
-        assert!(format_deps_summary(&deps).is_empty());
-    }
-
-    #[test]
-    fn test_format_deps_summary_populated() {
-        let deps = EntityDeps {
-            invoked_by: vec!["main".to_string()],
-            invokes: vec!["helper".to_string()],
-            inherits: vec!["Base".to_string()],
-            renders: vec!["Widget".to_string()],
-            ..Default::default()
-        };
-        let summary = format_deps_summary(&deps);
-        assert!(summary.contains("Called by: main"));
-        assert!(summary.contains("Calls: helper"));
-        assert!(summary.contains("Inherits: Base"));
-        assert!(summary.contains("Renders: Widget"));
-    }
-
-    #[test]
-    fn test_format_deps_summary_caps_at_five() {
-        let deps = EntityDeps {
-            invokes: (1..=10).map(|i| format!("fn{}", i)).collect(),
-            ..Default::default()
-        };
-        let summary = format_deps_summary(&deps);
-        assert!(summary.contains("fn5"));
-        assert!(!summary.contains("fn6"));
-    }
-
     #[test]
     fn test_build_context_pack_no_results() {
         let graph = RPGraph::new("rust");
         let request = ContextPackRequest {
             query: "nonexistent",
             scope: None,
+            estimator: &Heuristic,
             token_budget: 4000,
             include_source: false,
             depth: 1,
+            neighbor_skeleton: None,
         };
-        let result = build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None);
+        let result =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None).unwrap();
         assert!(result.primary_entities.is_empty());
         assert!(result.neighborhood_entities.is_empty());
     }
@@ -374,11 +357,14 @@ mod tests {
         let request = ContextPackRequest {
             query: "do stuff",
             scope: None,
+            estimator: &Heuristic,
             token_budget: 10000,
             include_source: false,
             depth: 0,
+            neighbor_skeleton: None,
         };
-        let result = build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None);
+        let result =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None).unwrap();
         // Should find at least one entity matching "do stuff"
         assert!(
             !result.primary_entities.is_empty(),
@@ -393,20 +379,26 @@ mod tests {
         let request_d0 = ContextPackRequest {
             query: "do stuff",
             scope: None,
+            estimator: &Heuristic,
             token_budget: 10000,
             include_source: false,
             depth: 0,
+            neighbor_skeleton: None,
         };
-        let result_d0 = build_context_pack(&graph, std::path::Path::new("/tmp"), &request_d0, None);
+        let result_d0 =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request_d0, None).unwrap();
 
         let request_d1 = ContextPackRequest {
             query: "do stuff",
             scope: None,
+            estimator: &Heuristic,
             token_budget: 10000,
             include_source: false,
             depth: 1,
+            neighbor_skeleton: None,
         };
-        let result_d1 = build_context_pack(&graph, std::path::Path::new("/tmp"), &request_d1, None);
+        let result_d1 =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request_d1, None).unwrap();
 
         let total_d0 = result_d0.primary_entities.len() + result_d0.neighborhood_entities.len();
         let total_d1 = result_d1.primary_entities.len() + result_d1.neighborhood_entities.len();
@@ -416,6 +408,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_neighbor_skeletons_attached_and_trimmed_first() {
+        let graph = make_test_graph();
+        let render = |e: &Entity| Some(format!("pub fn {}() {{ … }}", e.name));
+        let mut request = ContextPackRequest {
+            query: "finish",
+            scope: None,
+            estimator: &Heuristic,
+            token_budget: 10000,
+            include_source: false,
+            depth: 1,
+            neighbor_skeleton: Some(&render),
+        };
+        let result =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None).unwrap();
+        let neighbor = result
+            .neighborhood_entities
+            .iter()
+            .find(|n| n.entity_id == "src/lib.rs:b")
+            .expect("b is a 1-hop neighbor of c");
+        assert_eq!(neighbor.source.as_deref(), Some("pub fn b() { … }"));
+
+        // Just under the full estimate: skeletons go, neighbors stay.
+        request.token_budget = result.token_estimate - 1;
+        let trimmed =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None).unwrap();
+        assert_eq!(
+            trimmed.neighborhood_entities.len(),
+            result.neighborhood_entities.len()
+        );
+        assert!(
+            trimmed
+                .neighborhood_entities
+                .iter()
+                .any(|n| n.source.is_none())
+        );
+    }
+
     #[test]
     fn test_build_context_pack_budget_trimming() {
         let graph = make_test_graph();
@@ -423,11 +453,14 @@ mod tests {
         let request = ContextPackRequest {
             query: "stuff",
             scope: None,
+            estimator: &Heuristic,
             token_budget: 1,
             include_source: false,
             depth: 1,
+            neighbor_skeleton: None,
         };
-        let result = build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None);
+        let result =
+            build_context_pack(&graph, std::path::Path::new("/tmp"), &request, None).unwrap();
         // With budget=1, should keep at most 1 primary
         assert!(
             result.primary_entities.len() <= 1,
@@ -265,25 +265,15 @@ pub fn detect_cycles(graph: &RPGraph, config: &CycleConfig) -> CycleReport {
             cycles.sort_by(|a, b| {
                 let a_files = a.files.len();
                 let b_files = b.files.len();
-                b_files
-                    .cmp(&a_files)
-                    .then_with(|| a.cycle.first().cmp(&b.cycle.first()))
+                b_files.cmp(&a_files).then_with(|| a.cycle.cmp(&b.cycle))
             });
         }
         "entity_count" => {
-            cycles.sort_by(|a, b| {
-                b.length
-                    .cmp(&a.length)
-                    .then_with(|| a.cycle.first().cmp(&b.cycle.first()))
-            });
+            cycles.sort_by(|a, b| b.length.cmp(&a.length).then_with(|| a.cycle.cmp(&b.cycle)));
         }
         _ => {
-            // Default: sort by length, then by first element
-            cycles.sort_by(|a, b| {
-                a.length
-                    .cmp(&b.length)
-                    .then_with(|| a.cycle.first().cmp(&b.cycle.first()))
-            });
+            // Default: sort by length, then by entity IDs
+            cycles.sort_by(|a, b| a.length.cmp(&b.length).then_with(|| a.cycle.cmp(&b.cycle)));
         }
     }
 
@@ -423,7 +413,11 @@ pub fn detect_cycles(graph: &RPGraph, config: &CycleConfig) -> CycleReport {
             stats
         })
         .collect();
-    area_breakdown.sort_by(|a, b| b.cycle_count.cmp(&a.cycle_count));
+    area_breakdown.sort_by(|a, b| {
+        b.cycle_count
+            .cmp(&a.cycle_count)
+            .then_with(|| a.area.cmp(&b.area))
+    });
 
     let areas_in_cycles = area_breakdown.len();
 
@@ -551,6 +545,7 @@ fn deduplicate_cycles(cycles: Vec<Cycle>) -> Vec<Cycle> {
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, kind: EntityKind, file: &str) -> Entity {
@@ -564,9 +559,22 @@ mod tests {
             parent_class: None,
             semantic_features: vec![],
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -105,6 +105,7 @@ fn l1_area(path: &str) -> &str {
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use std::collections::BTreeSet;
     use std::path::PathBuf;
 
     fn make_entity(name: &str, hierarchy: &str) -> Entity {
@@ -118,9 +119,22 @@ mod tests {
             parent_class: None,
             semantic_features: Vec::new(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: hierarchy.to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
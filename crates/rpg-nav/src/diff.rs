@@ -122,6 +122,7 @@ pub fn apply_proximity_boost(
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str, file: &str) -> Entity {
@@ -135,9 +136,22 @@ mod tests {
             parent_class: None,
             semantic_features: vec!["test".to_string()],
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -6,26 +6,27 @@
 //! - Clone detection: HashMap collision-based fingerprint matching
 //!
 //! This approach is language-agnostic and detects Type-1 (exact) and Type-2 (renamed) clones.
+//!
+//! Source-similarity mode ([`detect_source_duplicates`]) reuses the same token stream but
+//! compares token-shingle sets via MinHash signatures, clustering entities whose estimated
+//! Jaccard similarity exceeds a threshold. It works whether or not entities are lifted.
 
 use crate::search::jaccard_similarity;
-use rpg_core::graph::{EntityKind, RPGraph};
-use std::collections::{HashMap, HashSet};
+use rpg_core::graph::{Entity, EntityKind, RPGraph};
+use rpg_core::signature::{
+    HASH_BASE, HASH_MOD, MIN_DUPLICATE_TOKENS, Token, entity_source, tokenize,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
-/// Base multiplier for rolling hash (per paper: typically 256)
-const HASH_BASE: u64 = 256;
-
-/// Large prime modulus to prevent overflow (per paper: 10^9 + 7)
-const HASH_MOD: u64 = 1_000_000_007;
+pub use rpg_core::signature::{
+    SIGNATURE_SIZE, compute_source_signatures, signature_similarity, source_signature,
+};
 
 /// Default window size in tokens for entity-level fingerprinting.
 /// Lowered from 50 (file-level) to 20 to catch function-sized duplicates.
 const DEFAULT_WINDOW_SIZE: usize = 20;
 
-/// Minimum duplicate length in tokens to report (filters noise).
-/// Lowered from 30 (file-level) to 15 for entity-level snippets.
-const MIN_DUPLICATE_TOKENS: usize = 15;
-
 /// A detected clone group with high similarity.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct CloneGroup {
@@ -60,6 +61,41 @@ impl Default for DuplicationConfig {
     }
 }
 
+/// LSH banding for candidate pairs: `SIGNATURE_BANDS` bands of `SIGNATURE_SIZE / SIGNATURE_BANDS` rows.
+const SIGNATURE_BANDS: usize = 16;
+
+/// A cluster of entities whose source token shingles are near-duplicates.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SourceCloneGroup {
+    /// Entity IDs in this cluster, sorted
+    pub entities: Vec<String>,
+    /// Lowest estimated Jaccard similarity among the pairs linking the cluster
+    pub similarity: f64,
+    /// File paths (parallel to `entities`)
+    pub files: Vec<String>,
+}
+
+/// Configuration for source-similarity (MinHash) duplication detection.
+#[derive(Debug, Clone)]
+pub struct SourceDuplicationConfig {
+    /// Estimated Jaccard threshold above which entities are linked (default: 0.8).
+    pub similarity_threshold: f64,
+    /// Skip pairs from the same source file (default: false — copy-paste within a file counts).
+    pub skip_same_file: bool,
+    /// Maximum number of clusters to return (default: 50).
+    pub max_results: usize,
+}
+
+impl Default for SourceDuplicationConfig {
+    fn default() -> Self {
+        Self {
+            similarity_threshold: 0.8,
+            skip_same_file: false,
+            max_results: 50,
+        }
+    }
+}
+
 /// A detected group of conceptual duplicates identified via feature-set Jaccard similarity.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct SemanticCloneGroup {
@@ -202,210 +238,13 @@ pub fn detect_semantic_duplicates(
     // Step 5: Sort by similarity descending, cap results
     groups.sort_by(|a, b| {
         b.similarity
-            .partial_cmp(&a.similarity)
-            .unwrap_or(std::cmp::Ordering::Equal)
+            .total_cmp(&a.similarity)
+            .then_with(|| a.entities.cmp(&b.entities))
     });
     groups.truncate(config.max_results);
     groups
 }
 
-/// Token type for normalized code representation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum TokenType {
-    /// Identifier (variable, function, class name) - normalized
-    Identifier,
-    /// Keyword (if, else, fn, let, etc.)
-    Keyword,
-    /// Operator (+, -, *, /, =, etc.)
-    Operator,
-    /// Literal (number, string - replaced with placeholder)
-    Literal,
-    /// Punctuation ({, }, (, ), ;, etc.)
-    Punctuation,
-}
-
-/// A normalized token for fingerprinting.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Token {
-    kind: TokenType,
-    value: u64,
-}
-
-/// Tokenize source code into normalized tokens.
-///
-/// Per paper §3.4: strip whitespace/comments, normalize identifiers,
-/// replace literals with placeholders for Type-2 clone detection.
-fn tokenize(source: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut chars = source.chars().peekable();
-
-    while let Some(&ch) = chars.peek() {
-        match ch {
-            // Skip whitespace
-            ' ' | '\t' | '\n' | '\r' => {
-                chars.next();
-            }
-            // Single-line comment
-            '/' if chars.clone().nth(1) == Some('/') => {
-                chars.next();
-                chars.next();
-                while let Some(&c) = chars.peek() {
-                    if c == '\n' {
-                        break;
-                    }
-                    chars.next();
-                }
-            }
-            // Multi-line comment (Rust-style)
-            '/' if chars.clone().nth(1) == Some('*') => {
-                chars.next();
-                chars.next();
-                while let Some(&c) = chars.peek() {
-                    if c == '*' && chars.clone().nth(1) == Some('/') {
-                        chars.next();
-                        chars.next();
-                        break;
-                    }
-                    chars.next();
-                }
-            }
-            // String literal
-            '"' | '\'' => {
-                let quote = ch;
-                chars.next();
-                while let Some(&c) = chars.peek() {
-                    chars.next();
-                    if c == quote {
-                        break;
-                    }
-                    if c == '\\' {
-                        chars.next();
-                    }
-                }
-                tokens.push(Token {
-                    kind: TokenType::Literal,
-                    value: hash_str("LIT"),
-                });
-            }
-            // Number literal
-            '0'..='9' => {
-                while let Some(&c) = chars.peek() {
-                    if c.is_ascii_digit()
-                        || c == '.'
-                        || c == 'x'
-                        || c == 'X'
-                        || c == 'e'
-                        || c == 'E'
-                    {
-                        chars.next();
-                    } else {
-                        break;
-                    }
-                }
-                tokens.push(Token {
-                    kind: TokenType::Literal,
-                    value: hash_str("LIT"),
-                });
-            }
-            // Identifier or keyword
-            'a'..='z' | 'A'..='Z' | '_' => {
-                let mut ident = String::new();
-                while let Some(&c) = chars.peek() {
-                    if c.is_ascii_alphanumeric() || c == '_' {
-                        ident.push(c);
-                        chars.next();
-                    } else {
-                        break;
-                    }
-                }
-                let kind = if is_keyword(&ident) {
-                    TokenType::Keyword
-                } else {
-                    TokenType::Identifier
-                };
-                // Normalize identifiers: hash by kind, not by name (Type-2 detection)
-                tokens.push(Token {
-                    kind,
-                    value: if kind == TokenType::Keyword {
-                        hash_str(&ident)
-                    } else {
-                        hash_str("ID")
-                    },
-                });
-            }
-            // Operators (multi-char first)
-            '<' | '>' | '=' | '!' | '&' | '|' | '+' | '-' | '*' | '/' | '%' | '^' => {
-                let mut op = String::new();
-                op.push(chars.next().unwrap());
-                // Check for two-char operators
-                if let Some(&c) = chars.peek()
-                    && matches!(c, '=' | '&' | '|' | '<' | '>' | '+')
-                {
-                    op.push(c);
-                    chars.next();
-                }
-                tokens.push(Token {
-                    kind: TokenType::Operator,
-                    value: hash_str(&op),
-                });
-            }
-            // Punctuation
-            '{' | '}' | '(' | ')' | '[' | ']' | ';' | ':' | ',' | '.' | '#' | '@' | '~' | '?' => {
-                tokens.push(Token {
-                    kind: TokenType::Punctuation,
-                    value: hash_str(&ch.to_string()),
-                });
-                chars.next();
-            }
-            // Unknown - skip
-            _ => {
-                chars.next();
-            }
-        }
-    }
-
-    tokens
-}
-
-/// Check if a string is a programming language keyword.
-#[allow(clippy::match_same_arms)]
-fn is_keyword(s: &str) -> bool {
-    matches!(
-        s,
-        // Rust
-        "fn" | "let" | "mut" | "const" | "static" | "pub" | "mod" | "use" | "crate" | "self"
-        | "Self" | "super" | "struct" | "enum" | "impl" | "trait" | "type" | "where" | "async"
-        | "await" | "move" | "ref" | "match" | "if" | "else" | "loop" | "while" | "for" | "in"
-        | "return" | "break" | "continue" | "unsafe" | "extern" | "dyn" | "as"
-        // TypeScript/JavaScript
-        | "function" | "var" | "class" | "interface" | "extends" | "implements" | "import"
-        | "export" | "from" | "default" | "new" | "this" | "typeof" | "instanceof" | "void"
-        | "null" | "undefined" | "true" | "false" | "try" | "catch" | "finally" | "throw"
-        | "switch" | "case" | "do" | "delete" | "yield" | "constructor" | "readonly"
-        // Python
-        | "def" | "lambda" | "pass" | "raise" | "except" | "with" | "assert" | "global"
-        | "nonlocal" | "print" | "elif"
-        // Go
-        | "package" | "go" | "chan" | "select" | "defer" | "fallthrough" | "goto" | "range"
-        | "map" | "make" | "append" | "copy"
-        // Java
-        | "public" | "private" | "protected" | "final" | "abstract" | "synchronized"
-        | "volatile" | "transient" | "native" | "strictfp" | "throws"
-        // C/C++
-        | "int" | "char" | "float" | "double" | "long" | "short" | "unsigned" | "signed"
-        | "auto" | "register" | "inline" | "restrict" | "sizeof" | "typedef"
-    )
-}
-
-/// Hash a string to a u64 value.
-fn hash_str(s: &str) -> u64 {
-    let mut hash: u64 = 0;
-    for byte in s.bytes() {
-        hash = (hash.wrapping_mul(HASH_BASE).wrapping_add(u64::from(byte))) % HASH_MOD;
-    }
-    hash
-}
-
 /// Compute Rabin-Karp fingerprints for a token stream.
 ///
 /// Per paper Algorithm 4: slide a window of size w over tokens,
@@ -589,8 +428,8 @@ pub fn detect_duplication(
     // Sort by similarity descending
     clone_groups.sort_by(|a, b| {
         b.similarity
-            .partial_cmp(&a.similarity)
-            .unwrap_or(std::cmp::Ordering::Equal)
+            .total_cmp(&a.similarity)
+            .then_with(|| a.entities.cmp(&b.entities))
     });
 
     // Limit to top 50 groups to avoid overwhelming output
@@ -599,61 +438,130 @@ pub fn detect_duplication(
     clone_groups
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_tokenize_simple() {
-        let source = "fn foo() { let x = 1; }";
-        let tokens = tokenize(source);
-
-        // Should have tokens for: fn, foo, (, ), {, let, x, =, 1, ;, }
-        assert!(!tokens.is_empty());
-        assert!(tokens.len() >= 8);
+/// Detect near-duplicate source across entities via MinHash signatures.
+///
+/// Stored signatures (`encoding.source_signatures`) are used when present; entities
+/// without one are signed on the fly from disk. Candidate pairs come from LSH banding,
+/// are confirmed against `similarity_threshold`, and linked into clusters.
+pub fn detect_source_duplicates(
+    graph: &RPGraph,
+    project_root: &Path,
+    config: &SourceDuplicationConfig,
+) -> Vec<SourceCloneGroup> {
+    // Step 1: Gather signatures, reading each file at most once for unsigned entities
+    let mut file_cache: HashMap<&Path, Option<String>> = HashMap::new();
+    let mut signed: Vec<(&Entity, Vec<u16>)> = Vec::new();
+    for entity in graph.entities.values() {
+        if entity.kind == EntityKind::Module {
+            continue;
+        }
+        let signature = if entity.source_signature.is_empty() {
+            let source = file_cache
+                .entry(entity.file.as_path())
+                .or_insert_with(|| std::fs::read_to_string(project_root.join(&entity.file)).ok());
+            source
+                .as_deref()
+                .and_then(|s| entity_source(s, entity))
+                .map(|s| source_signature(&s))
+                .unwrap_or_default()
+        } else {
+            entity.source_signature.clone()
+        };
+        if signature.len() == SIGNATURE_SIZE {
+            signed.push((entity, signature));
+        }
     }
 
-    #[test]
-    fn test_tokenize_normalizes_identifiers() {
-        let source1 = "let foo = 1;";
-        let source2 = "let bar = 2;";
-
-        let tokens1 = tokenize(source1);
-        let tokens2 = tokenize(source2);
-
-        // Both should have same token sequence (identifiers normalized to "ID")
-        let values1: Vec<_> = tokens1.iter().map(|t| t.value).collect();
-        let values2: Vec<_> = tokens2.iter().map(|t| t.value).collect();
-
-        // Keywords and structure should match
-        assert_eq!(tokens1.len(), tokens2.len());
-        assert_eq!(values1, values2);
+    // Step 2: LSH banding — entities sharing any band are candidates
+    let rows = SIGNATURE_SIZE / SIGNATURE_BANDS;
+    let mut buckets: HashMap<(usize, &[u16]), Vec<usize>> = HashMap::new();
+    for (idx, (_, signature)) in signed.iter().enumerate() {
+        for (band, chunk) in signature.chunks(rows).enumerate() {
+            buckets.entry((band, chunk)).or_default().push(idx);
+        }
+    }
+    let mut candidates: HashSet<(usize, usize)> = HashSet::new();
+    for members in buckets.values() {
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                candidates.insert((a.min(b), a.max(b)));
+            }
+        }
     }
 
-    #[test]
-    fn test_tokenize_strips_comments() {
-        let source = "fn foo() { /* comment */ let x = 1; }\n// line comment\nlet y = 2;";
-        let tokens = tokenize(source);
-
-        // Comments should be stripped
-        let token_values: Vec<_> = tokens.iter().map(|t| t.value).collect();
-        assert!(!token_values.iter().any(|&v| v == hash_str("comment")));
+    // Step 3: Confirm candidates and link them (union-find)
+    let mut parent: Vec<usize> = (0..signed.len()).collect();
+    let mut links: Vec<(usize, usize, f64)> = Vec::new();
+    for (a, b) in candidates {
+        let (entity_a, sig_a) = &signed[a];
+        let (entity_b, sig_b) = &signed[b];
+        if config.skip_same_file && entity_a.file == entity_b.file {
+            continue;
+        }
+        let similarity = signature_similarity(sig_a, sig_b);
+        if similarity < config.similarity_threshold {
+            continue;
+        }
+        let (root_a, root_b) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        if root_a != root_b {
+            parent[root_b] = root_a;
+        }
+        links.push((a, b, similarity));
     }
 
-    #[test]
-    fn test_tokenize_normalizes_literals() {
-        let source1 = "let x = 42;";
-        let source2 = "let x = 999999;";
+    // Step 4: Collect clusters; a cluster's similarity is its weakest link
+    let mut clusters: BTreeMap<usize, (Vec<usize>, f64)> = BTreeMap::new();
+    for &(a, b, similarity) in &links {
+        let root = find_root(&mut parent, a);
+        let entry = clusters.entry(root).or_insert_with(|| (Vec::new(), 1.0));
+        entry.0.extend([a, b]);
+        entry.1 = entry.1.min(similarity);
+    }
 
-        let tokens1 = tokenize(source1);
-        let tokens2 = tokenize(source2);
+    let mut groups: Vec<SourceCloneGroup> = clusters
+        .into_values()
+        .map(|(mut members, similarity)| {
+            members.sort_unstable_by(|&x, &y| signed[x].0.id.cmp(&signed[y].0.id));
+            members.dedup();
+            SourceCloneGroup {
+                entities: members.iter().map(|&m| signed[m].0.id.clone()).collect(),
+                similarity: (similarity * 1000.0).round() / 1000.0,
+                files: members
+                    .iter()
+                    .map(|&m| signed[m].0.file.display().to_string())
+                    .collect(),
+            }
+        })
+        .collect();
 
-        // Literals should both be normalized to same value
-        let lit1 = tokens1.iter().find(|t| t.kind == TokenType::Literal);
-        let lit2 = tokens2.iter().find(|t| t.kind == TokenType::Literal);
+    // Step 5: Largest, then most similar clusters first; cap results
+    groups.sort_by(|a, b| {
+        b.entities
+            .len()
+            .cmp(&a.entities.len())
+            .then_with(|| {
+                b.similarity
+                    .partial_cmp(&a.similarity)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| a.entities.cmp(&b.entities))
+    });
+    groups.truncate(config.max_results);
+    groups
+}
 
-        assert_eq!(lit1.map(|t| t.value), lit2.map(|t| t.value));
+fn find_root(parent: &mut [usize], mut x: usize) -> usize {
+    while parent[x] != x {
+        parent[x] = parent[parent[x]];
+        x = parent[x];
     }
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeMap, BTreeSet};
 
     #[test]
     fn test_fingerprints_deterministic() {
@@ -698,48 +606,6 @@ mod tests {
         assert_eq!(fps, fps2);
     }
 
-    #[test]
-    fn test_type2_clone_detection() {
-        // Type-2: same structure, renamed identifiers
-        let source1 = r"
-            fn process_data(input: &str) -> String {
-                let result = input.to_uppercase();
-                result.trim().to_string()
-            }
-        ";
-
-        let source2 = r"
-            fn handle_text(data: &str) -> String {
-                let output = data.to_uppercase();
-                output.trim().to_string()
-            }
-        ";
-
-        let tokens1 = tokenize(source1);
-        let tokens2 = tokenize(source2);
-
-        // Structure should be identical after normalization
-        let values1: Vec<_> = tokens1.iter().map(|t| t.value).collect();
-        let values2: Vec<_> = tokens2.iter().map(|t| t.value).collect();
-
-        assert_eq!(
-            values1, values2,
-            "Type-2 clones should normalize to same tokens"
-        );
-    }
-
-    #[test]
-    fn test_is_keyword() {
-        assert!(is_keyword("fn"));
-        assert!(is_keyword("let"));
-        assert!(is_keyword("function"));
-        assert!(is_keyword("class"));
-        assert!(is_keyword("def"));
-        assert!(!is_keyword("my_function"));
-        assert!(!is_keyword("MyClass"));
-        assert!(!is_keyword("variable_name"));
-    }
-
     // --- Semantic duplication tests ---
 
     use rpg_core::graph::{Entity, EntityDeps};
@@ -756,9 +622,22 @@ mod tests {
             parent_class: None,
             semantic_features: features.into_iter().map(|s| s.to_string()).collect(),
             feature_source: Some("llm".to_string()),
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -878,9 +757,22 @@ mod tests {
             parent_class: None,
             semantic_features: Vec::new(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -1059,4 +951,108 @@ mod tests {
             "missing files should be skipped, not cause errors"
         );
     }
+
+    // --- Source-similarity (MinHash) detection tests ---
+
+    const ORIGINAL: &str = r"fn total_price(items: &[Item], tax: f64) -> f64 {
+    let mut sum = 0.0;
+    for item in items {
+        if item.quantity > 0 {
+            sum += item.price * item.quantity as f64;
+        }
+    }
+    sum * (1.0 + tax)
+}
+";
+
+    const RENAMED: &str = r"fn order_total(lines: &[Line], rate: f64) -> f64 {
+    let mut acc = 0.0;
+    for line in lines {
+        if line.count > 0 {
+            acc += line.cost * line.count as f64;
+        }
+    }
+    acc * (1.0 + rate)
+}
+";
+
+    const UNRELATED: &str = r#"fn parse_header(raw: &str) -> Option<(String, String)> {
+    let (key, value) = raw.split_once(':')?;
+    match key.trim().to_lowercase().as_str() {
+        "" => None,
+        k => Some((k.to_string(), value.trim().to_string())),
+    }
+}
+"#;
+
+    /// Write each `(file, source)` into `dir` and add one unlifted entity per file.
+    fn source_fixture(dir: &Path, files: &[(&str, &str)]) -> RPGraph {
+        let mut graph = RPGraph::new("rust");
+        for (file, source) in files {
+            std::fs::write(dir.join(file), source).unwrap();
+            let id = format!("{}:f", file);
+            graph.insert_entity(make_entity_at_lines(&id, file, 1, source.lines().count()));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_source_signature_ignores_renames() {
+        let original = source_signature(ORIGINAL);
+        assert_eq!(original.len(), SIGNATURE_SIZE);
+        assert_eq!(
+            signature_similarity(&original, &source_signature(ORIGINAL)),
+            1.0
+        );
+        assert_eq!(
+            signature_similarity(&original, &source_signature(RENAMED)),
+            1.0
+        );
+        assert!(signature_similarity(&original, &source_signature(UNRELATED)) < 0.3);
+        assert!(source_signature("fn f() {}").is_empty());
+    }
+
+    #[test]
+    fn test_detect_source_duplicates_clusters_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = source_fixture(
+            dir.path(),
+            &[
+                ("a.rs", ORIGINAL),
+                ("b.rs", ORIGINAL),
+                ("c.rs", RENAMED),
+                ("d.rs", UNRELATED),
+            ],
+        );
+
+        let groups =
+            detect_source_duplicates(&graph, dir.path(), &SourceDuplicationConfig::default());
+        assert_eq!(groups.len(), 1, "{:?}", groups);
+        assert_eq!(groups[0].entities, vec!["a.rs:f", "b.rs:f", "c.rs:f"]);
+        assert_eq!(groups[0].files, vec!["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(groups[0].similarity, 1.0);
+    }
+
+    #[test]
+    fn test_stored_signatures_need_no_disk_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut graph = source_fixture(dir.path(), &[("a.rs", ORIGINAL), ("c.rs", RENAMED)]);
+
+        assert_eq!(compute_source_signatures(&mut graph, dir.path(), None), 2);
+        std::fs::remove_file(dir.path().join("a.rs")).unwrap();
+        std::fs::remove_file(dir.path().join("c.rs")).unwrap();
+
+        let groups =
+            detect_source_duplicates(&graph, dir.path(), &SourceDuplicationConfig::default());
+        assert_eq!(groups.len(), 1);
+
+        let cross_file_only = SourceDuplicationConfig {
+            skip_same_file: true,
+            ..SourceDuplicationConfig::default()
+        };
+        assert_eq!(
+            detect_source_duplicates(&graph, dir.path(), &cross_file_only).len(),
+            1
+        );
+    }
 }
@@ -4,10 +4,11 @@
 //! search that preserves multi-role entity semantics. Uses BGE-small-en-v1.5
 //! (384 dimensions) via the fastembed crate.
 
+use crate::clusters::{ClusterIndex, MIN_CLUSTERED_ENTITIES};
 use anyhow::{Context, Result, ensure};
 use fastembed::{EmbeddingModel, TextEmbedding};
 use rpg_core::graph::RPGraph;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
@@ -16,8 +17,8 @@ const MAGIC: u32 = 0x5250_4745; // "RPGE"
 const FORMAT_VERSION: u32 = 1;
 const DIMENSION: usize = 384;
 
-/// Loaded embeddings: entity map + fingerprints from disk.
-type LoadedEmbeddings = (HashMap<String, EntityEmbeddings>, BTreeMap<String, String>);
+/// Loaded embeddings: entity map + meta sidecar from disk.
+type LoadedEmbeddings = (HashMap<String, EntityEmbeddings>, EmbeddingMeta);
 
 /// Metadata sidecar for the embedding index.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -29,6 +30,53 @@ pub struct EmbeddingMeta {
     /// Per-entity feature fingerprints for incremental sync.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub entity_fingerprints: BTreeMap<String, String>,
+    /// Outcome of the most recent [`validate`] run against the graph.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_validation: Option<LastValidation>,
+}
+
+/// Recorded outcome of a validation run.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LastValidation {
+    /// When the check ran (RFC 3339).
+    pub checked_at: String,
+    /// [`ValidationReport::summary`] at that time.
+    pub summary: String,
+}
+
+/// Mismatches between the embedding index and the graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Entities with vectors in the index.
+    pub indexed: usize,
+    /// Indexed entities that are gone from the graph or no longer have features.
+    pub orphans: Vec<String>,
+    /// Lifted entities with no vectors.
+    pub missing: Vec<String>,
+    /// Entities whose vectors were embedded from different features than they have now.
+    pub mismatched: Vec<String>,
+}
+
+impl ValidationReport {
+    /// True when the index matches the graph exactly.
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty() && self.missing.is_empty() && self.mismatched.is_empty()
+    }
+
+    /// One-line summary, e.g. `2 orphans, 1 missing, 0 mismatched (120 indexed)`.
+    pub fn summary(&self) -> String {
+        if self.is_clean() {
+            format!("ok ({} indexed)", self.indexed)
+        } else {
+            format!(
+                "{} orphans, {} missing, {} mismatched ({} indexed)",
+                self.orphans.len(),
+                self.missing.len(),
+                self.mismatched.len(),
+                self.indexed,
+            )
+        }
+    }
 }
 
 /// Per-entity embedding data: one vector per semantic feature.
@@ -49,6 +97,11 @@ pub struct EmbeddingIndex {
     graph_updated_at: String,
     /// Per-entity feature fingerprints for incremental sync.
     fingerprints: BTreeMap<String, String>,
+    /// Outcome of the most recent validation, persisted in the meta sidecar.
+    last_validation: Option<LastValidation>,
+    /// Clustered pre-filter for [`Self::score_all`], kept once the index
+    /// reaches [`MIN_CLUSTERED_ENTITIES`] and persisted next to the vectors.
+    clusters: Option<ClusterIndex>,
 }
 
 /// Statistics from an incremental embedding sync.
@@ -77,13 +130,17 @@ impl EmbeddingIndex {
         // Try loading existing index (resilient to corruption)
         if embeddings_path.exists() && meta_path.exists() {
             match Self::try_load_existing(&meta_path, &embeddings_path) {
-                Ok(Some((entities, fingerprints))) => {
+                Ok(Some((entities, meta))) => {
+                    let clusters =
+                        load_clusters(&rpg_dir.join("embeddings.clusters.bin"), &entities);
                     return Ok(Self {
                         model,
                         entities,
                         rpg_dir,
                         graph_updated_at: graph_updated_at.to_string(),
-                        fingerprints,
+                        fingerprints: meta.entity_fingerprints,
+                        last_validation: meta.last_validation,
+                        clusters,
                     });
                 }
                 Ok(None) => {
@@ -105,10 +162,12 @@ impl EmbeddingIndex {
             rpg_dir,
             graph_updated_at: graph_updated_at.to_string(),
             fingerprints: BTreeMap::new(),
+            last_validation: None,
+            clusters: None,
         })
     }
 
-    /// Try to load existing embedding data. Returns Ok(Some((entities, fingerprints)))
+    /// Try to load existing embedding data. Returns Ok(Some((entities, meta)))
     /// if valid, Ok(None) if model/dimension mismatch, Err if corrupt.
     fn try_load_existing(
         meta_path: &Path,
@@ -125,7 +184,7 @@ impl EmbeddingIndex {
         }
 
         let entities = load_binary(embeddings_path)?;
-        Ok(Some((entities, meta.entity_fingerprints)))
+        Ok(Some((entities, meta)))
     }
 
     /// Embed features for a set of entities and add/update them in the index.
@@ -165,6 +224,9 @@ impl EmbeddingIndex {
         let mut count = 0;
         for (entity_id, start, feat_count) in &feature_map {
             let vectors: Vec<Vec<f32>> = embeddings[*start..*start + *feat_count].to_vec();
+            if let Some(clusters) = &mut self.clusters {
+                clusters.insert(entity_id, &vectors);
+            }
             self.entities
                 .insert(entity_id.clone(), EntityEmbeddings { vectors });
             count += 1;
@@ -223,6 +285,9 @@ impl EmbeddingIndex {
             .collect();
         for id in &to_prune {
             self.entities.remove(id);
+            if let Some(clusters) = &mut self.clusters {
+                clusters.remove(id);
+            }
             stats.pruned += 1;
         }
 
@@ -235,6 +300,7 @@ impl EmbeddingIndex {
         self.fingerprints = current.into_iter().map(|(id, (fp, _))| (id, fp)).collect();
 
         // Update timestamp and save
+        self.refresh_clusters();
         self.graph_updated_at = graph.updated_at.to_rfc3339();
         self.save()?;
 
@@ -255,13 +321,93 @@ impl EmbeddingIndex {
 
     /// Remove entities that no longer exist in the graph.
     pub fn prune(&mut self, valid_entity_ids: &std::collections::HashSet<String>) {
+        if let Some(clusters) = &mut self.clusters {
+            for id in self.entities.keys() {
+                if !valid_entity_ids.contains(id) {
+                    clusters.remove(id);
+                }
+            }
+        }
         self.entities.retain(|id, _| valid_entity_ids.contains(id));
         self.fingerprints
             .retain(|id, _| valid_entity_ids.contains(id));
     }
 
-    /// Score all entities against a query string using max-cosine similarity.
+    /// Targeted repair from a [`validate`] report: drop orphan vectors and
+    /// embed missing and mismatched entities, then save. Unlike [`Self::sync`],
+    /// this does not trust stored fingerprints to know what has vectors.
+    pub fn repair(&mut self, graph: &RPGraph, report: &ValidationReport) -> Result<SyncStats> {
+        drop_orphans(&mut self.entities, &mut self.fingerprints, &report.orphans);
+        if let Some(clusters) = &mut self.clusters {
+            for id in &report.orphans {
+                clusters.remove(id);
+            }
+        }
+        let to_embed: HashMap<String, Vec<String>> = report
+            .missing
+            .iter()
+            .chain(&report.mismatched)
+            .filter_map(|id| {
+                graph
+                    .entities
+                    .get(id)
+                    .map(|e| (id.clone(), e.semantic_features.clone()))
+            })
+            .collect();
+        self.embed_entities(&to_embed)?;
+        self.update_fingerprints(&to_embed);
+
+        self.refresh_clusters();
+        self.graph_updated_at = graph.updated_at.to_rfc3339();
+        self.save()?;
+        Ok(SyncStats {
+            kept: report.indexed - report.orphans.len() - report.mismatched.len(),
+            changed: report.mismatched.len(),
+            added: report.missing.len(),
+            pruned: report.orphans.len(),
+        })
+    }
+
+    /// Discard every vector and re-embed all lifted entities, then save.
+    pub fn rebuild(&mut self, graph: &RPGraph) -> Result<SyncStats> {
+        let pruned = self.entities.len();
+        self.entities.clear();
+        self.fingerprints.clear();
+        self.clusters = None;
+        let to_embed: HashMap<String, Vec<String>> = graph
+            .entities
+            .iter()
+            .filter(|(_, e)| !e.semantic_features.is_empty())
+            .map(|(id, e)| (id.clone(), e.semantic_features.clone()))
+            .collect();
+        let added = self.embed_entities(&to_embed)?;
+        self.update_fingerprints(&to_embed);
+
+        self.refresh_clusters();
+        self.graph_updated_at = graph.updated_at.to_rfc3339();
+        self.save()?;
+        Ok(SyncStats {
+            added,
+            pruned,
+            ..SyncStats::default()
+        })
+    }
+
+    /// Remember a validation outcome; persisted on the next [`Self::save`].
+    pub fn record_validation(&mut self, report: &ValidationReport) {
+        self.last_validation = Some(LastValidation::now(report));
+    }
+
+    /// Outcome of the most recent validation, if any.
+    pub fn last_validation(&self) -> Option<&LastValidation> {
+        self.last_validation.as_ref()
+    }
+
+    /// Score entities against a query string using max-cosine similarity.
     /// Returns entity_id → score (0.0..1.0).
+    ///
+    /// From [`MIN_CLUSTERED_ENTITIES`] on, only the members of the clusters
+    /// nearest the query are scored; entities outside them get no score.
     pub fn score_all(&mut self, query: &str) -> Result<HashMap<String, f64>> {
         let query_embeddings = self
             .model
@@ -271,7 +417,17 @@ impl EmbeddingIndex {
         let query_vec = &query_embeddings[0];
         let mut scores = HashMap::new();
 
-        for (entity_id, entity_emb) in &self.entities {
+        self.refresh_clusters();
+        let scored: Vec<(&String, &EntityEmbeddings)> = match &self.clusters {
+            Some(clusters) => clusters
+                .candidates(query_vec)
+                .into_iter()
+                .filter_map(|id| self.entities.get_key_value(id))
+                .collect(),
+            None => self.entities.iter().collect(),
+        };
+
+        for (entity_id, entity_emb) in scored {
             let max_sim = entity_emb
                 .vectors
                 .iter()
@@ -291,10 +447,32 @@ impl EmbeddingIndex {
         self.entities.len()
     }
 
-    /// Save the index to disk (binary + meta sidecar with fingerprints).
+    /// Keep the clustered pre-filter in step with the vectors: dropped below
+    /// [`MIN_CLUSTERED_ENTITIES`], (re)trained when missing or stale. Between
+    /// retrains, entities are added and removed incrementally.
+    fn refresh_clusters(&mut self) {
+        if self.entities.len() < MIN_CLUSTERED_ENTITIES {
+            self.clusters = None;
+        } else if self.clusters.as_ref().is_none_or(ClusterIndex::is_stale) {
+            self.clusters = Some(ClusterIndex::train(
+                self.entities
+                    .iter()
+                    .map(|(id, e)| (id.as_str(), e.vectors.as_slice())),
+            ));
+        }
+    }
+
+    /// Save the index to disk (binary + meta sidecar with fingerprints, plus
+    /// the cluster index when there is one).
     pub fn save(&self) -> Result<()> {
         std::fs::create_dir_all(&self.rpg_dir)?;
         save_binary(&self.rpg_dir.join("embeddings.bin"), &self.entities)?;
+        let clusters_path = self.rpg_dir.join("embeddings.clusters.bin");
+        match &self.clusters {
+            Some(clusters) => std::fs::write(&clusters_path, clusters.to_bytes())?,
+            None if clusters_path.exists() => std::fs::remove_file(&clusters_path)?,
+            None => {}
+        }
 
         let meta = EmbeddingMeta {
             model: "BAAI/bge-small-en-v1.5".to_string(),
@@ -302,6 +480,7 @@ impl EmbeddingIndex {
             version: FORMAT_VERSION,
             graph_updated_at: self.graph_updated_at.clone(),
             entity_fingerprints: self.fingerprints.clone(),
+            last_validation: self.last_validation.clone(),
         };
         let meta_json = serde_json::to_string_pretty(&meta)?;
         std::fs::write(self.rpg_dir.join("embeddings.meta.json"), meta_json)?;
@@ -315,6 +494,149 @@ impl EmbeddingIndex {
     }
 }
 
+impl LastValidation {
+    fn now(report: &ValidationReport) -> Self {
+        Self {
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            summary: report.summary(),
+        }
+    }
+}
+
+/// Check the index against the graph: orphan vectors, lifted entities with no
+/// vectors, and vectors embedded from outdated features.
+pub fn validate(index: &EmbeddingIndex, graph: &RPGraph) -> ValidationReport {
+    validate_vectors(&index.entities, &index.fingerprints, graph)
+}
+
+/// Validate the index saved under `.rpg/` without loading the model, and
+/// record the result in its meta sidecar. `None` if there is no saved index
+/// (or it was built with a different model).
+pub fn validate_saved(project_root: &Path, graph: &RPGraph) -> Result<Option<ValidationReport>> {
+    let rpg_dir = project_root.join(".rpg");
+    let meta_path = rpg_dir.join("embeddings.meta.json");
+    let embeddings_path = rpg_dir.join("embeddings.bin");
+    if !meta_path.exists() || !embeddings_path.exists() {
+        return Ok(None);
+    }
+    let Some((entities, mut meta)) =
+        EmbeddingIndex::try_load_existing(&meta_path, &embeddings_path)?
+    else {
+        return Ok(None);
+    };
+
+    let report = validate_vectors(&entities, &meta.entity_fingerprints, graph);
+    meta.last_validation = Some(LastValidation::now(&report));
+    std::fs::write(&meta_path, serde_json::to_string_pretty(&meta)?)?;
+    Ok(Some(report))
+}
+
+/// Mean feature vector per entity from the index saved under `.rpg/`, read
+/// without loading the model. `None` if there is no saved index (or it was
+/// built with a different model).
+pub fn load_saved_mean_vectors(project_root: &Path) -> Result<Option<HashMap<String, Vec<f32>>>> {
+    let rpg_dir = project_root.join(".rpg");
+    let meta_path = rpg_dir.join("embeddings.meta.json");
+    let embeddings_path = rpg_dir.join("embeddings.bin");
+    if !meta_path.exists() || !embeddings_path.exists() {
+        return Ok(None);
+    }
+    let Some((entities, _)) = EmbeddingIndex::try_load_existing(&meta_path, &embeddings_path)?
+    else {
+        return Ok(None);
+    };
+
+    let means = entities
+        .into_iter()
+        .filter(|(_, emb)| !emb.vectors.is_empty())
+        .map(|(id, emb)| {
+            let mut mean = vec![0.0_f32; DIMENSION];
+            for vec in &emb.vectors {
+                for (m, v) in mean.iter_mut().zip(vec) {
+                    *m += v;
+                }
+            }
+            let n = emb.vectors.len() as f32;
+            for m in &mut mean {
+                *m /= n;
+            }
+            (id, mean)
+        })
+        .collect();
+    Ok(Some(means))
+}
+
+/// Last recorded validation of the saved index, read from its meta sidecar.
+pub fn load_last_validation(project_root: &Path) -> Option<LastValidation> {
+    let meta_json = std::fs::read_to_string(project_root.join(".rpg/embeddings.meta.json")).ok()?;
+    serde_json::from_str::<EmbeddingMeta>(&meta_json)
+        .ok()?
+        .last_validation
+}
+
+fn validate_vectors(
+    entities: &HashMap<String, EntityEmbeddings>,
+    fingerprints: &BTreeMap<String, String>,
+    graph: &RPGraph,
+) -> ValidationReport {
+    let is_lifted = |id: &str| {
+        graph
+            .entities
+            .get(id)
+            .is_some_and(|e| !e.semantic_features.is_empty())
+    };
+    // An ID with a fingerprint but no vector is an orphan too: sync would
+    // otherwise never revisit it.
+    let known: BTreeSet<&String> = entities.keys().chain(fingerprints.keys()).collect();
+    let orphans = known
+        .into_iter()
+        .filter(|id| !is_lifted(id))
+        .cloned()
+        .collect();
+
+    let mut missing = Vec::new();
+    let mut mismatched = Vec::new();
+    for (id, entity) in &graph.entities {
+        if entity.semantic_features.is_empty() {
+            continue;
+        }
+        if !entities.contains_key(id) {
+            missing.push(id.clone());
+        } else if fingerprints.get(id) != Some(&compute_fingerprint(&entity.semantic_features)) {
+            mismatched.push(id.clone());
+        }
+    }
+
+    ValidationReport {
+        indexed: entities.len(),
+        orphans,
+        missing,
+        mismatched,
+    }
+}
+
+/// The saved cluster index, if it exists, parses, and lists exactly the
+/// entities that have vectors; otherwise it is retrained on demand.
+fn load_clusters(
+    path: &Path,
+    entities: &HashMap<String, EntityEmbeddings>,
+) -> Option<ClusterIndex> {
+    let clusters = ClusterIndex::from_bytes(&std::fs::read(path).ok()?).ok()?;
+    (clusters.len() == entities.len()).then_some(clusters)
+}
+
+/// Remove orphan IDs from both the vectors and the fingerprints.
+fn drop_orphans(
+    entities: &mut HashMap<String, EntityEmbeddings>,
+    fingerprints: &mut BTreeMap<String, String>,
+    orphans: &[String],
+) {
+    for id in orphans {
+        entities.remove(id);
+        fingerprints.remove(id);
+    }
+}
+
 /// Compute a deterministic fingerprint for an entity's features.
 /// Used to detect when features change without comparing full strings.
 fn compute_fingerprint(features: &[String]) -> String {
@@ -485,7 +807,7 @@ pub fn rank_normalize(scores: &HashMap<String, f64>) -> HashMap<String, f64> {
     }
 
     let mut sorted: Vec<(&String, &f64)> = scores.iter().collect();
-    sorted.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal));
+    sorted.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
 
     let total = sorted.len() as f64;
     sorted
@@ -521,7 +843,7 @@ pub fn hybrid_blend(
         })
         .collect();
 
-    blended.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    blended.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
     blended.truncate(limit);
     blended
 }
@@ -669,6 +991,7 @@ mod tests {
             version: 1,
             graph_updated_at: "2024-01-01T00:00:00Z".to_string(),
             entity_fingerprints: fingerprints.clone(),
+            last_validation: None,
         };
 
         let json = serde_json::to_string_pretty(&meta).unwrap();
@@ -809,9 +1132,171 @@ mod tests {
             version: 1,
             graph_updated_at: "2024-01-01T00:00:00Z".to_string(),
             entity_fingerprints: BTreeMap::new(),
+            last_validation: None,
         };
         let json = serde_json::to_string(&meta).unwrap();
         // Empty fingerprints should be omitted from JSON
         assert!(!json.contains("entity_fingerprints"));
     }
+
+    fn lifted_entity(id: &str, features: &[&str]) -> rpg_core::graph::Entity {
+        rpg_core::graph::Entity {
+            id: id.to_string(),
+            kind: rpg_core::graph::EntityKind::Function,
+            name: id.rsplit(':').next().unwrap().to_string(),
+            file: PathBuf::from(id.split(':').next().unwrap()),
+            line_start: 1,
+            line_end: 5,
+            parent_class: None,
+            semantic_features: features.iter().map(|f| (*f).to_string()).collect(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: rpg_core::graph::EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    /// Vectors and fingerprints exactly as `sync` leaves them for `graph`.
+    fn synced_index(
+        graph: &RPGraph,
+    ) -> (HashMap<String, EntityEmbeddings>, BTreeMap<String, String>) {
+        let mut entities = HashMap::new();
+        let mut fingerprints = BTreeMap::new();
+        for (id, entity) in &graph.entities {
+            entities.insert(
+                id.clone(),
+                EntityEmbeddings {
+                    vectors: vec![vec![0.1; DIMENSION]; entity.semantic_features.len()],
+                },
+            );
+            fingerprints.insert(id.clone(), compute_fingerprint(&entity.semantic_features));
+        }
+        (entities, fingerprints)
+    }
+
+    fn fixture_graph() -> RPGraph {
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(lifted_entity("src/auth.rs:login", &["authenticate user"]));
+        graph.insert_entity(lifted_entity("src/auth.rs:logout", &["end user session"]));
+        graph.insert_entity(lifted_entity(
+            "src/db.rs:connect",
+            &["open database connection"],
+        ));
+        graph
+    }
+
+    #[test]
+    fn test_validate_synced_index_is_clean() {
+        let graph = fixture_graph();
+        let (entities, fingerprints) = synced_index(&graph);
+        let report = validate_vectors(&entities, &fingerprints, &graph);
+        assert!(report.is_clean());
+        assert_eq!(report.summary(), "ok (3 indexed)");
+    }
+
+    #[test]
+    fn test_validate_reports_drift_after_graph_changes() {
+        let mut graph = fixture_graph();
+        let (mut entities, mut fingerprints) = synced_index(&graph);
+
+        // An aggressive update: one entity deleted, one added, one re-lifted,
+        // plus a vector whose fingerprint was lost and a fingerprint whose
+        // vector was lost.
+        graph.remove_entity("src/auth.rs:logout");
+        graph.insert_entity(lifted_entity("src/db.rs:query", &["run sql query"]));
+        graph
+            .entities
+            .get_mut("src/db.rs:connect")
+            .unwrap()
+            .semantic_features = vec!["open pooled database connection".to_string()];
+        entities.insert(
+            "src/old.rs:gone".to_string(),
+            EntityEmbeddings {
+                vectors: vec![vec![0.1; DIMENSION]],
+            },
+        );
+        fingerprints.insert("src/old.rs:ghost".to_string(), "0".repeat(16));
+        entities.remove("src/auth.rs:login");
+
+        let report = validate_vectors(&entities, &fingerprints, &graph);
+        assert_eq!(
+            report.orphans,
+            vec!["src/auth.rs:logout", "src/old.rs:ghost", "src/old.rs:gone"]
+        );
+        assert_eq!(report.missing, vec!["src/auth.rs:login", "src/db.rs:query"]);
+        assert_eq!(report.mismatched, vec!["src/db.rs:connect"]);
+        assert_eq!(report.indexed, 3);
+        assert_eq!(
+            report.summary(),
+            "3 orphans, 2 missing, 1 mismatched (3 indexed)"
+        );
+
+        // Repair: drop orphans, then embed what's missing or mismatched.
+        drop_orphans(&mut entities, &mut fingerprints, &report.orphans);
+        let after_drop = validate_vectors(&entities, &fingerprints, &graph);
+        assert!(after_drop.orphans.is_empty());
+        assert_eq!(after_drop.missing, report.missing);
+        assert_eq!(after_drop.mismatched, report.mismatched);
+
+        for id in report.missing.iter().chain(&report.mismatched) {
+            let features = &graph.entities[id].semantic_features;
+            entities.insert(
+                id.clone(),
+                EntityEmbeddings {
+                    vectors: vec![vec![0.1; DIMENSION]; features.len()],
+                },
+            );
+            fingerprints.insert(id.clone(), compute_fingerprint(features));
+        }
+        assert!(validate_vectors(&entities, &fingerprints, &graph).is_clean());
+    }
+
+    #[test]
+    fn test_validate_saved_records_last_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let rpg_dir = dir.path().join(".rpg");
+        std::fs::create_dir_all(&rpg_dir).unwrap();
+
+        let mut graph = fixture_graph();
+        assert!(validate_saved(dir.path(), &graph).unwrap().is_none());
+
+        let (entities, fingerprints) = synced_index(&graph);
+        save_binary(&rpg_dir.join("embeddings.bin"), &entities).unwrap();
+        let meta = EmbeddingMeta {
+            model: "BAAI/bge-small-en-v1.5".to_string(),
+            dimension: 384,
+            version: 1,
+            graph_updated_at: "2024-01-01T00:00:00Z".to_string(),
+            entity_fingerprints: fingerprints,
+            last_validation: None,
+        };
+        std::fs::write(
+            rpg_dir.join("embeddings.meta.json"),
+            serde_json::to_string(&meta).unwrap(),
+        )
+        .unwrap();
+        assert!(load_last_validation(dir.path()).is_none());
+
+        graph.remove_entity("src/db.rs:connect");
+        let report = validate_saved(dir.path(), &graph).unwrap().unwrap();
+        assert_eq!(report.orphans, vec!["src/db.rs:connect"]);
+        let last = load_last_validation(dir.path()).unwrap();
+        assert_eq!(
+            last.summary,
+            "1 orphans, 0 missing, 0 mismatched (3 indexed)"
+        );
+    }
 }
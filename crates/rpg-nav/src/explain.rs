@@ -0,0 +1,211 @@
+//! Entity explanation: why does an entity sit where it sits in the hierarchy?
+//!
+//! Combines the entity's hierarchy placement, feature provenance, the overlap
+//! between its features and its area's features, and the areas of its
+//! dependency neighbors. Low overlap with its own area while another area fits
+//! better flags a likely misplacement.
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use std::collections::{BTreeMap, HashSet};
+
+/// Default similarity below which an entity is considered a misplacement candidate.
+pub const DEFAULT_MISPLACEMENT_THRESHOLD: f64 = 0.1;
+
+/// How an entity ended up at its hierarchy path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentMode {
+    /// Structural (file-path) hierarchy — no semantic routing happened.
+    Structural,
+    /// File-level Module entity, assigned directly by `submit_hierarchy`.
+    FileLevel,
+    /// Same path as its file's Module entity — inherited from the file assignment.
+    InheritedFromFile,
+    /// Differs from its file's path — routed individually (routing or drift reroute).
+    RoutedIndividually,
+    /// Not assigned to any hierarchy node yet.
+    Unassigned,
+}
+
+impl AssignmentMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Structural => "structural (file path)",
+            Self::FileLevel => "file-level (submit_hierarchy)",
+            Self::InheritedFromFile => "inherited from file",
+            Self::RoutedIndividually => "routed individually",
+            Self::Unassigned => "unassigned",
+        }
+    }
+}
+
+/// Similarity of the entity's features to one top-level area.
+#[derive(Debug, Clone)]
+pub struct AreaFit {
+    pub area: String,
+    pub similarity: f64,
+}
+
+/// Dependency neighbors grouped by the area they live in.
+#[derive(Debug, Clone)]
+pub struct NeighborArea {
+    pub area: String,
+    pub count: usize,
+}
+
+/// Full explanation for one entity.
+#[derive(Debug, Clone)]
+pub struct EntityExplanation {
+    pub entity_id: String,
+    pub kind: EntityKind,
+    pub file: String,
+    pub hierarchy_path: String,
+    pub assignment: AssignmentMode,
+    pub features: Vec<String>,
+    pub feature_source: Option<String>,
+    /// RFC 3339 timestamp of when features were last set, if recorded.
+    pub lifted_at: Option<String>,
+    /// RFC 3339 timestamp of the last graph update.
+    pub graph_updated_at: String,
+    /// Jaccard similarity to the entity's own area (features of other members only).
+    pub area_similarity: Option<f64>,
+    /// Best-fitting other top-level area, if any overlaps at all.
+    pub best_alternative: Option<AreaFit>,
+    pub misplaced: bool,
+    pub threshold: f64,
+    /// Suggested replacement path, filled in by the caller (requires routing logic).
+    pub suggested_path: Option<String>,
+    /// Suggested follow-up tool call, filled in by the caller.
+    pub next_step: Option<String>,
+    pub neighbor_areas: Vec<NeighborArea>,
+}
+
+/// Explain the placement of `entity_id`. Returns `None` if the entity doesn't exist.
+pub fn explain_entity(
+    graph: &RPGraph,
+    entity_id: &str,
+    threshold: f64,
+    max_neighbor_areas: usize,
+) -> Option<EntityExplanation> {
+    let entity = graph.entities.get(entity_id)?;
+
+    let own_area = top_level_area(&entity.hierarchy_path);
+    let assignment = assignment_mode(graph, entity_id);
+
+    // Compare against each area's member features, excluding the entity itself so
+    // its own features don't inflate the fit of the area it already sits in.
+    let own: HashSet<&str> = entity
+        .semantic_features
+        .iter()
+        .map(|s| s.as_str())
+        .collect();
+    let mut area_similarity = None;
+    let mut best_alternative: Option<AreaFit> = None;
+    if !own.is_empty() {
+        for (area_name, node) in &graph.hierarchy {
+            let area_features: HashSet<&str> = node
+                .all_entity_ids()
+                .iter()
+                .filter(|id| id.as_str() != entity_id)
+                .filter_map(|id| graph.entities.get(id))
+                .flat_map(|e| e.semantic_features.iter().map(|s| s.as_str()))
+                .collect();
+            let sim = crate::search::jaccard_similarity(&own, &area_features);
+            if own_area == Some(area_name.as_str()) {
+                area_similarity = Some(sim);
+            } else if sim > 0.0 && best_alternative.as_ref().is_none_or(|b| sim > b.similarity) {
+                best_alternative = Some(AreaFit {
+                    area: area_name.clone(),
+                    similarity: sim,
+                });
+            }
+        }
+    }
+
+    let misplaced = match (area_similarity, &best_alternative) {
+        (Some(own_sim), Some(alt)) => own_sim < threshold && alt.similarity > own_sim,
+        _ => false,
+    };
+
+    Some(EntityExplanation {
+        entity_id: entity_id.to_string(),
+        kind: entity.kind,
+        file: entity.file.display().to_string(),
+        hierarchy_path: entity.hierarchy_path.clone(),
+        assignment,
+        features: entity.semantic_features.clone(),
+        feature_source: entity.feature_source.clone(),
+        lifted_at: entity.lifted_at.map(|t| t.to_rfc3339()),
+        graph_updated_at: graph.updated_at.to_rfc3339(),
+        area_similarity,
+        best_alternative,
+        misplaced,
+        threshold,
+        suggested_path: None,
+        next_step: None,
+        neighbor_areas: neighbor_areas(graph, entity_id, max_neighbor_areas),
+    })
+}
+
+fn top_level_area(path: &str) -> Option<&str> {
+    path.split('/').next().filter(|s| !s.is_empty())
+}
+
+fn assignment_mode(graph: &RPGraph, entity_id: &str) -> AssignmentMode {
+    let Some(entity) = graph.entities.get(entity_id) else {
+        return AssignmentMode::Unassigned;
+    };
+    if entity.hierarchy_path.is_empty() {
+        return AssignmentMode::Unassigned;
+    }
+    if !graph.metadata.semantic_hierarchy {
+        return AssignmentMode::Structural;
+    }
+    if entity.kind == EntityKind::Module {
+        return AssignmentMode::FileLevel;
+    }
+    let module_path = graph.file_index.get(&entity.file).and_then(|ids| {
+        ids.iter()
+            .filter_map(|id| graph.entities.get(id))
+            .find(|e| e.kind == EntityKind::Module)
+            .map(|m| m.hierarchy_path.as_str())
+    });
+    match module_path {
+        Some(p) if p != entity.hierarchy_path => AssignmentMode::RoutedIndividually,
+        _ => AssignmentMode::InheritedFromFile,
+    }
+}
+
+/// Count dependency neighbors (both directions, containment excluded) per top-level area.
+fn neighbor_areas(graph: &RPGraph, entity_id: &str, max: usize) -> Vec<NeighborArea> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for edge in graph.edges_for(entity_id) {
+        if edge.kind == EdgeKind::Contains {
+            continue;
+        }
+        let other = if edge.source == entity_id {
+            edge.target.as_str()
+        } else {
+            edge.source.as_str()
+        };
+        if other == entity_id || !seen.insert(other) {
+            continue;
+        }
+        let area = graph
+            .entities
+            .get(other)
+            .and_then(|e| top_level_area(&e.hierarchy_path))
+            .unwrap_or("(unassigned)");
+        *counts.entry(area).or_default() += 1;
+    }
+    let mut areas: Vec<NeighborArea> = counts
+        .into_iter()
+        .map(|(area, count)| NeighborArea {
+            area: area.to_string(),
+            count,
+        })
+        .collect();
+    areas.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.area.cmp(&b.area)));
+    areas.truncate(max);
+    areas
+}
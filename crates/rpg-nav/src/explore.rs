@@ -1,7 +1,8 @@
 //! ExploreRPG: dependency traversal along graph edges.
 
-use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_core::graph::{EdgeKind, Entity, EntityKind, Layer, RPGraph};
 use std::collections::{HashSet, VecDeque};
+use std::time::Instant;
 
 /// Traversal direction.
 #[derive(Debug, Clone, Copy)]
@@ -23,10 +24,38 @@ pub struct TraversalNode {
     pub edge_kind: Option<EdgeKind>,
     pub direction: Option<String>, // "downstream" or "upstream"
     pub depth: usize,
+    /// Entity kind (`None` for hierarchy nodes).
+    pub kind: Option<EntityKind>,
+    /// Paradigm attributes summary (e.g., `route: GET /users`), empty if none.
+    pub attributes: String,
     pub children: Vec<TraversalNode>,
 }
 
-/// Explore the dependency graph from a starting entity or hierarchy node.
+/// Which neighbor entities a traversal keeps. `None` fields keep everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NeighborFilter<'a> {
+    /// Keep entities of these kinds.
+    pub kinds: Option<&'a [EntityKind]>,
+    /// Keep entities tagged with one of these layers.
+    pub layers: Option<&'a [Layer]>,
+    /// Keep entities carrying one of these user tags.
+    pub tags: Option<&'a [String]>,
+}
+
+impl NeighborFilter<'_> {
+    pub fn keeps(&self, entity: &Entity) -> bool {
+        self.kinds.is_none_or(|kinds| kinds.contains(&entity.kind))
+            && self
+                .layers
+                .is_none_or(|layers| entity.layer.is_some_and(|l| layers.contains(&l)))
+            && self
+                .tags
+                .is_none_or(|tags| tags.iter().any(|t| entity.tags.contains(t)))
+    }
+}
+
+/// Explore the dependency graph from a starting entity, hierarchy node, or
+/// file (its Module entity).
 pub fn explore(
     graph: &RPGraph,
     start_entity_id: &str,
@@ -40,27 +69,65 @@ pub fn explore(
         direction,
         max_depth,
         edge_filter,
-        None,
+        NeighborFilter::default(),
     )
 }
 
-/// Explore with optional entity type filtering on neighbors.
+/// Explore with optional entity kind, layer, and tag filtering on neighbors.
 pub fn explore_filtered(
     graph: &RPGraph,
     start_entity_id: &str,
     direction: Direction,
     max_depth: usize,
     edge_filter: Option<EdgeKind>,
-    entity_type_filter: Option<&[EntityKind]>,
+    neighbor_filter: NeighborFilter<'_>,
 ) -> Option<TraversalNode> {
-    // Try V_L entity first, then V_H hierarchy node
-    let (name, file_or_desc) = if let Some(entity) = graph.get_entity(start_entity_id) {
-        (entity.name.clone(), entity.file.display().to_string())
-    } else if let Some((name, desc)) = graph.get_node_display_info(start_entity_id) {
-        (name, desc)
-    } else {
-        return None;
+    explore_within(
+        graph,
+        start_entity_id,
+        direction,
+        max_depth,
+        edge_filter,
+        neighbor_filter,
+        None,
+    )
+    .map(|(tree, _)| tree)
+}
+
+/// Like [`explore_filtered`], but stops expanding nodes once `deadline`
+/// passes. The flag is `false` when the returned tree is partial.
+pub fn explore_within(
+    graph: &RPGraph,
+    start_entity_id: &str,
+    direction: Direction,
+    max_depth: usize,
+    edge_filter: Option<EdgeKind>,
+    neighbor_filter: NeighborFilter<'_>,
+    deadline: Option<Instant>,
+) -> Option<(TraversalNode, bool)> {
+    // A hierarchy node may be given by path; edges reference its "h:" ID.
+    // A file path starts from the file's Module entity.
+    let start_entity_id = match graph.find_hierarchy_node(start_entity_id) {
+        _ if graph.get_entity(start_entity_id).is_some() => start_entity_id,
+        Some(node) => node.id.as_str(),
+        None => graph
+            .module_entity(std::path::Path::new(start_entity_id))
+            .map_or(start_entity_id, |module| module.id.as_str()),
     };
+    // Try V_L entity first, then V_H hierarchy node
+    let (name, file_or_desc, kind, attributes) =
+        if let Some(entity) = graph.get_entity(start_entity_id) {
+            (
+                entity.name.clone(),
+                entity.file.display().to_string(),
+                Some(entity.kind),
+                entity.attribute_summary(),
+            )
+        } else if let Some((name, desc)) = graph.get_node_display_info(start_entity_id) {
+            (name, desc, None, String::new())
+        } else {
+            return None;
+        };
 
     let mut root = TraversalNode {
         entity_id: start_entity_id.to_string(),
@@ -69,6 +136,8 @@ pub fn explore_filtered(
         edge_kind: None,
         direction: None,
         depth: 0,
+        kind,
+        attributes,
         children: Vec::new(),
     };
 
@@ -83,6 +152,9 @@ pub fn explore_filtered(
         if depth >= max_depth {
             continue;
         }
+        if crate::budget::expired(deadline) {
+            return Some((root, false));
+        }
 
         let neighbors = get_neighbors(graph, &current_id, direction, edge_filter);
 
@@ -93,23 +165,22 @@ pub fn explore_filtered(
             visited.insert(neighbor_id.clone());
 
             // Try V_L entity first, then V_H hierarchy node via unified lookup
-            let (name, file_or_desc) = if let Some(neighbor_entity) = graph.get_entity(&neighbor_id)
-            {
-                // Apply entity type filter
-                if let Some(kinds) = entity_type_filter
-                    && !kinds.contains(&neighbor_entity.kind)
-                {
+            let (name, file_or_desc, kind, attributes) =
+                if let Some(neighbor_entity) = graph.get_entity(&neighbor_id) {
+                    if !neighbor_filter.keeps(neighbor_entity) {
+                        continue;
+                    }
+                    (
+                        neighbor_entity.name.clone(),
+                        neighbor_entity.file.display().to_string(),
+                        Some(neighbor_entity.kind),
+                        neighbor_entity.attribute_summary(),
+                    )
+                } else if let Some((name, desc)) = graph.get_node_display_info(&neighbor_id) {
+                    (name, desc, None, String::new())
+                } else {
                     continue;
-                }
-                (
-                    neighbor_entity.name.clone(),
-                    neighbor_entity.file.display().to_string(),
-                )
-            } else if let Some((name, desc)) = graph.get_node_display_info(&neighbor_id) {
-                (name, desc)
-            } else {
-                continue;
-            };
+                };
 
             let child_node = TraversalNode {
                 entity_id: neighbor_id.clone(),
@@ -118,6 +189,8 @@ pub fn explore_filtered(
                 edge_kind: Some(edge_kind),
                 direction: Some(dir_str.to_string()),
                 depth: depth + 1,
+                kind,
+                attributes,
                 children: Vec::new(),
             };
 
@@ -130,7 +203,7 @@ pub fn explore_filtered(
         }
     }
 
-    Some(root)
+    Some((root, true))
 }
 
 /// Get immediate neighbors of an entity along edges, respecting direction and optional filter.
@@ -209,8 +282,9 @@ fn format_tree_inner(node: &TraversalNode, indent: usize, is_last: bool) -> Stri
     let mut output = String::new();
     let prefix = "  ".repeat(indent);
 
+    let label = node_label(node);
     if indent == 0 {
-        output.push_str(&format!("{} [{}]\n", node.entity_name, node.file));
+        output.push_str(&format!("{}\n", label));
     } else {
         let connector = if is_last { "└──" } else { "├──" };
         let edge_str = node
@@ -219,8 +293,8 @@ fn format_tree_inner(node: &TraversalNode, indent: usize, is_last: bool) -> Stri
             .unwrap_or_default();
         let dir_str = node.direction.as_deref().unwrap_or("");
         output.push_str(&format!(
-            "{}{} {} ({}): {} [{}]\n",
-            prefix, connector, edge_str, dir_str, node.entity_name, node.file
+            "{}{} {} ({}): {}\n",
+            prefix, connector, edge_str, dir_str, label
         ));
     }
 
@@ -232,6 +306,24 @@ fn format_tree_inner(node: &TraversalNode, indent: usize, is_last: bool) -> Stri
     output
 }
 
+/// `name [file]`, tagged with paradigm kinds (e.g. `(route)`) and attributes.
+fn node_label(node: &TraversalNode) -> String {
+    let mut label = node.entity_name.clone();
+    if let Some(kind) = node.kind
+        && !matches!(
+            kind,
+            EntityKind::Function | EntityKind::Class | EntityKind::Method | EntityKind::Module
+        )
+    {
+        label.push_str(&format!(" ({})", format!("{:?}", kind).to_lowercase()));
+    }
+    label.push_str(&format!(" [{}]", node.file));
+    if !node.attributes.is_empty() {
+        label.push_str(&format!(" {{{}}}", node.attributes));
+    }
+    label
+}
+
 /// Format a traversal result as compact pipe-delimited rows.
 /// Each row: `entity_id | edge_kind | direction | depth | file`
 /// Preserves entity_ids for direct follow-up calls.
@@ -269,6 +361,8 @@ mod tests {
             edge_kind: None,
             direction: None,
             depth: 0,
+            kind: None,
+            attributes: String::new(),
             children: vec![TraversalNode {
                 entity_id: "src/lib.rs:bar".to_string(),
                 entity_name: "bar".to_string(),
@@ -276,6 +370,8 @@ mod tests {
                 edge_kind: Some(EdgeKind::Invokes),
                 direction: Some("downstream".to_string()),
                 depth: 1,
+                kind: None,
+                attributes: String::new(),
                 children: vec![],
             }],
         };
@@ -305,6 +401,8 @@ mod tests {
             edge_kind: None,
             direction: None,
             depth: 0,
+            kind: None,
+            attributes: String::new(),
             children: vec![
                 TraversalNode {
                     entity_id: "b".to_string(),
@@ -313,6 +411,8 @@ mod tests {
                     edge_kind: Some(EdgeKind::Invokes),
                     direction: Some("downstream".to_string()),
                     depth: 1,
+                    kind: None,
+                    attributes: String::new(),
                     children: vec![],
                 },
                 TraversalNode {
@@ -322,6 +422,8 @@ mod tests {
                     edge_kind: Some(EdgeKind::Imports),
                     direction: Some("downstream".to_string()),
                     depth: 1,
+                    kind: None,
+                    attributes: String::new(),
                     children: vec![],
                 },
             ],
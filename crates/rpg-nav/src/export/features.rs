@@ -0,0 +1,335 @@
+//! Per-file feature rows for external ML pipelines.
+//!
+//! One row per Module entity, written as JSONL (always available) or Parquet
+//! (behind the `arrow` feature). Both formats share the column schema below;
+//! rename or reorder a column only together with [`COLUMNS`].
+//!
+//! | column      | type                    | content                                        |
+//! |-------------|-------------------------|------------------------------------------------|
+//! | `path`      | utf8                    | file path, `/`-separated                       |
+//! | `area`      | utf8                    | hierarchy path of the module ("" if unplaced)  |
+//! | `features`  | list\<utf8\>            | the module's semantic features                 |
+//! | `embedding` | list\<float32\>, null   | mean feature embedding, null when not embedded |
+//! | `entities`  | uint32                  | non-module entities in the file                |
+//! | `lines`     | uint32                  | last line covered by the module                |
+//! | `fan_in`    | uint32                  | other files with dependency edges into it      |
+//! | `fan_out`   | uint32                  | other files it has dependency edges to         |
+//! | `owners`    | list\<utf8\>            | CODEOWNERS owners of the file                  |
+
+use rpg_core::graph::{EdgeKind, EntityKind, RPGraph, normalize_path};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Column names, in schema order.
+pub const COLUMNS: &[&str] = &[
+    "path",
+    "area",
+    "features",
+    "embedding",
+    "entities",
+    "lines",
+    "fan_in",
+    "fan_out",
+    "owners",
+];
+
+/// One exported row; fields follow [`COLUMNS`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileFeatureRow {
+    pub path: String,
+    pub area: String,
+    pub features: Vec<String>,
+    pub embedding: Option<Vec<f32>>,
+    pub entities: u32,
+    pub lines: u32,
+    pub fan_in: u32,
+    pub fan_out: u32,
+    pub owners: Vec<String>,
+}
+
+/// Rows for every Module entity, sorted by path. `embeddings` maps entity IDs
+/// to vectors (see `embeddings::load_saved_mean_vectors`).
+pub fn file_feature_rows(
+    graph: &RPGraph,
+    embeddings: Option<&HashMap<String, Vec<f32>>>,
+) -> Vec<FileFeatureRow> {
+    let mut entity_counts: HashMap<String, u32> = HashMap::new();
+    for entity in graph.entities.values() {
+        if entity.kind != EntityKind::Module {
+            *entity_counts
+                .entry(normalize_path(&entity.file))
+                .or_default() += 1;
+        }
+    }
+
+    let file_of = |id: &str| graph.entities.get(id).map(|e| normalize_path(&e.file));
+    let mut fan_in: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut fan_out: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for edge in &graph.edges {
+        if edge.kind == EdgeKind::Contains {
+            continue;
+        }
+        if let (Some(source), Some(target)) = (file_of(&edge.source), file_of(&edge.target))
+            && source != target
+        {
+            fan_in
+                .entry(target.clone())
+                .or_default()
+                .insert(source.clone());
+            fan_out.entry(source).or_default().insert(target);
+        }
+    }
+
+    let count = |map: &BTreeMap<String, BTreeSet<String>>, path: &str| {
+        map.get(path).map_or(0, |files| files.len() as u32)
+    };
+    let mut rows: Vec<FileFeatureRow> = graph
+        .entities
+        .iter()
+        .filter(|(_, e)| e.kind == EntityKind::Module)
+        .map(|(id, e)| {
+            let path = normalize_path(&e.file);
+            FileFeatureRow {
+                area: e.hierarchy_path.clone(),
+                features: e.semantic_features.clone(),
+                embedding: embeddings.and_then(|m| m.get(id).cloned()),
+                entities: entity_counts.get(&path).copied().unwrap_or(0),
+                lines: e.line_end as u32,
+                fan_in: count(&fan_in, &path),
+                fan_out: count(&fan_out, &path),
+                owners: e.owners.clone(),
+                path,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.path.cmp(&b.path));
+    rows
+}
+
+/// Rows as JSON Lines, one object per row.
+pub fn to_jsonl(rows: &[FileFeatureRow]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row).expect("row serializes"));
+        out.push('\n');
+    }
+    out
+}
+
+/// Arrow schema of the Parquet export, matching [`COLUMNS`].
+#[cfg(feature = "arrow")]
+pub fn arrow_schema() -> arrow_schema::Schema {
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    let list = |item: DataType| DataType::List(Arc::new(Field::new("item", item, true)));
+    Schema::new(vec![
+        Field::new("path", DataType::Utf8, false),
+        Field::new("area", DataType::Utf8, false),
+        Field::new("features", list(DataType::Utf8), false),
+        Field::new("embedding", list(DataType::Float32), true),
+        Field::new("entities", DataType::UInt32, false),
+        Field::new("lines", DataType::UInt32, false),
+        Field::new("fan_in", DataType::UInt32, false),
+        Field::new("fan_out", DataType::UInt32, false),
+        Field::new("owners", list(DataType::Utf8), false),
+    ])
+}
+
+/// Write rows as a single-row-group Parquet file.
+#[cfg(feature = "arrow")]
+pub fn write_parquet(rows: &[FileFeatureRow], path: &std::path::Path) -> anyhow::Result<()> {
+    use arrow_array::builder::{Float32Builder, ListBuilder, StringBuilder};
+    use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt32Array};
+    use std::sync::Arc;
+
+    let strings = |values: &dyn Fn(&FileFeatureRow) -> &[String]| -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for row in rows {
+            for value in values(row) {
+                builder.values().append_value(value);
+            }
+            builder.append(true);
+        }
+        Arc::new(builder.finish())
+    };
+    let numbers = |value: fn(&FileFeatureRow) -> u32| -> ArrayRef {
+        Arc::new(rows.iter().map(value).collect::<UInt32Array>())
+    };
+    let mut embedding = ListBuilder::new(Float32Builder::new());
+    for row in rows {
+        if let Some(vector) = &row.embedding {
+            embedding.values().append_slice(vector);
+        }
+        embedding.append(row.embedding.is_some());
+    }
+
+    let schema = Arc::new(arrow_schema());
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(rows.iter().map(|r| Some(&r.path)).collect::<StringArray>()),
+            Arc::new(rows.iter().map(|r| Some(&r.area)).collect::<StringArray>()),
+            strings(&|r| &r.features),
+            Arc::new(embedding.finish()),
+            numbers(|r| r.entities),
+            numbers(|r| r.lines),
+            numbers(|r| r.fan_in),
+            numbers(|r| r.fan_out),
+            strings(&|r| &r.owners),
+        ],
+    )?;
+
+    let file = std::fs::File::create(path)?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{DependencyEdge, Entity, EntityDeps};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::PathBuf;
+
+    fn make_entity(file: &str, name: &str, kind: EntityKind, features: &[&str]) -> Entity {
+        Entity {
+            id: format!("{}:{}", file, name),
+            kind,
+            name: name.to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 40,
+            parent_class: None,
+            semantic_features: features.iter().map(|f| f.to_string()).collect(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: "Storage/io".to_string(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: vec!["@storage".to_string()],
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    /// `src/api.rs` calls into `src/store.rs`; only the store module is embedded.
+    fn make_graph() -> (RPGraph, HashMap<String, Vec<f32>>) {
+        let mut graph = RPGraph::new("rust");
+        for entity in [
+            make_entity(
+                "src/store.rs",
+                "store",
+                EntityKind::Module,
+                &["persist graph"],
+            ),
+            make_entity("src/store.rs", "save", EntityKind::Function, &[]),
+            make_entity("src/store.rs", "load", EntityKind::Function, &[]),
+            make_entity("src/api.rs", "api", EntityKind::Module, &[]),
+            make_entity("src/api.rs", "handle", EntityKind::Function, &[]),
+        ] {
+            graph.entities.insert(entity.id.clone(), entity);
+        }
+        graph.edges.push(DependencyEdge {
+            source: "src/api.rs:handle".to_string(),
+            target: "src/store.rs:save".to_string(),
+            kind: EdgeKind::Invokes,
+        });
+        let embeddings = HashMap::from([("src/store.rs:store".to_string(), vec![0.5, -0.25])]);
+        (graph, embeddings)
+    }
+
+    #[test]
+    fn test_rows_and_jsonl_columns() {
+        let (graph, embeddings) = make_graph();
+        let rows = file_feature_rows(&graph, Some(&embeddings));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].path, "src/api.rs");
+        assert_eq!((rows[0].fan_in, rows[0].fan_out), (0, 1));
+        assert_eq!(rows[0].embedding, None);
+        let store = &rows[1];
+        assert_eq!(store.features, vec!["persist graph"]);
+        assert_eq!(store.embedding, Some(vec![0.5, -0.25]));
+        assert_eq!((store.entities, store.lines, store.fan_in), (2, 40, 1));
+        assert_eq!(store.owners, vec!["@storage"]);
+
+        let jsonl = to_jsonl(&rows);
+        let lines: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        let mut keys: Vec<&str> = lines[0]
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        let mut expected = COLUMNS.to_vec();
+        expected.sort_unstable();
+        assert_eq!(keys, expected);
+        assert!(lines[0]["embedding"].is_null());
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_parquet_round_trip_locks_schema() {
+        use arrow_array::Array;
+        use arrow_array::cast::AsArray;
+        use arrow_array::types::{Float32Type, UInt32Type};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let (graph, embeddings) = make_graph();
+        let rows = file_feature_rows(&graph, Some(&embeddings));
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("features.parquet");
+        write_parquet(&rows, &path).unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(Result::unwrap).collect();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.schema().as_ref(), &arrow_schema());
+        let names: Vec<&str> = batch
+            .schema_ref()
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect();
+        assert_eq!(names, COLUMNS);
+        assert_eq!(batch.num_rows(), 2);
+
+        let paths = batch.column(0).as_string::<i32>();
+        assert_eq!(paths.value(1), "src/store.rs");
+        let features = batch.column(2).as_list::<i32>();
+        assert_eq!(
+            features.value(1).as_string::<i32>().value(0),
+            "persist graph"
+        );
+        let embedding = batch.column(3).as_list::<i32>();
+        assert!(embedding.is_null(0));
+        assert_eq!(
+            embedding.value(1).as_primitive::<Float32Type>().values(),
+            &[0.5, -0.25]
+        );
+        let entities = batch.column(4).as_primitive::<UInt32Type>();
+        assert_eq!(entities.value(1), 2);
+        let owners = batch.column(8).as_list::<i32>();
+        assert_eq!(owners.value(0).as_string::<i32>().value(0), "@storage");
+    }
+}
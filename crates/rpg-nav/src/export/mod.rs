@@ -0,0 +1,446 @@
+//! Export RPG graph (or one entity's neighborhood) as DOT (Graphviz) or
+//! Mermaid flowchart, findings as SARIF, and per-file features as JSONL or
+//! Parquet.
+
+pub mod features;
+pub mod sarif;
+
+use crate::explore::TraversalNode;
+use crate::scope::ScopeExpr;
+use rpg_core::graph::{EdgeKind, Entity, EntityKind, HierarchyNode, RPGraph};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+/// Export format for graph visualization.
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+}
+
+/// Export the graph as a DOT (Graphviz) string.
+pub fn export_dot(graph: &RPGraph) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph RPG {{").unwrap();
+    writeln!(out, "  rankdir=LR;").unwrap();
+    writeln!(out, "  node [shape=box, fontsize=10];").unwrap();
+    writeln!(out).unwrap();
+
+    // Hierarchy nodes
+    for (area_name, area) in &graph.hierarchy {
+        writeln!(
+            out,
+            "  \"{}\" [shape=folder, style=filled, fillcolor=\"#e0e0ff\", label=\"{}\"];",
+            area.id, area_name
+        )
+        .unwrap();
+        write_dot_hierarchy_children(&area.id, area, &mut out);
+    }
+
+    writeln!(out).unwrap();
+
+    // Entity nodes
+    for (id, entity) in &graph.entities {
+        let shape = dot_shape(entity.kind);
+        let color = if entity.semantic_features.is_empty() {
+            "#ffffff"
+        } else {
+            "#e0ffe0"
+        };
+        writeln!(
+            out,
+            "  \"{}\" [shape={}, style=filled, fillcolor=\"{}\", label=\"{}\"];",
+            id, shape, color, entity.name
+        )
+        .unwrap();
+    }
+
+    writeln!(out).unwrap();
+
+    // Edges
+    for edge in &graph.edges {
+        let style = dot_edge_style(edge.kind);
+        let label = edge_label(edge.kind);
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [style={}, label=\"{}\"];",
+            edge.source, edge.target, style, label
+        )
+        .unwrap();
+    }
+
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+fn dot_shape(kind: EntityKind) -> &'static str {
+    match kind {
+        EntityKind::Function | EntityKind::Method => "ellipse",
+        EntityKind::Class => "box",
+        EntityKind::Page | EntityKind::Layout => "tab",
+        EntityKind::Component => "box3d",
+        EntityKind::Hook => "ellipse",
+        EntityKind::Store => "cylinder",
+        EntityKind::Module => "component",
+        EntityKind::Controller | EntityKind::Route => "hexagon",
+        EntityKind::Model => "box",
+        EntityKind::Service => "ellipse",
+        EntityKind::Middleware => "trapezium",
+        EntityKind::Test | EntityKind::Fixture => "diamond",
+        EntityKind::Enum => "octagon",
+        EntityKind::Constant => "note",
+    }
+}
+
+fn dot_edge_style(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Invokes => "solid",
+        EdgeKind::Imports => "dashed",
+        EdgeKind::Inherits => "bold",
+        EdgeKind::Composes => "solid",
+        EdgeKind::Renders => "solid",
+        EdgeKind::ReadsState => "dashed",
+        EdgeKind::WritesState => "bold",
+        EdgeKind::Dispatches => "solid",
+        EdgeKind::DataFlow => "dashed",
+        EdgeKind::Tests => "dotted",
+        EdgeKind::Contains => "dotted",
+    }
+}
+
+/// Edge color in neighborhood exports, where kinds sharing a line style
+/// still need telling apart.
+fn dot_edge_color(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Invokes => "#1f77b4",
+        EdgeKind::Imports => "#7f7f7f",
+        EdgeKind::Inherits => "#d62728",
+        EdgeKind::Composes => "#2ca02c",
+        EdgeKind::Renders => "#9467bd",
+        EdgeKind::ReadsState => "#17becf",
+        EdgeKind::WritesState => "#ff7f0e",
+        EdgeKind::Dispatches => "#e377c2",
+        EdgeKind::DataFlow => "#8c564b",
+        EdgeKind::Tests => "#bcbd22",
+        EdgeKind::Contains => "#c7c7c7",
+    }
+}
+
+fn edge_label(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Invokes => "invokes",
+        EdgeKind::Imports => "imports",
+        EdgeKind::Inherits => "inherits",
+        EdgeKind::Composes => "composes",
+        EdgeKind::Renders => "renders",
+        EdgeKind::ReadsState => "reads_state",
+        EdgeKind::WritesState => "writes_state",
+        EdgeKind::Dispatches => "dispatches",
+        EdgeKind::DataFlow => "data_flow",
+        EdgeKind::Tests => "tests",
+        EdgeKind::Contains => "contains",
+    }
+}
+
+/// Fill colors for hierarchy areas in neighborhood exports, assigned in
+/// area name order and reused past the end.
+const AREA_COLORS: &[&str] = &[
+    "#cfe2ff", "#d1e7dd", "#fff3cd", "#f8d7da", "#e2d9f3", "#d2f4ea", "#ffe5d0", "#e9ecef",
+];
+
+/// Fill color of entities outside the semantic hierarchy.
+const NO_AREA_COLOR: &str = "#ffffff";
+
+/// Export the subgraph an explore traversal reached (see
+/// [`crate::explore::explore_filtered`]) as DOT. Nodes are filled by
+/// top-level hierarchy area and the start node is outlined; edges follow
+/// the traversal, so depth, direction, and edge filters carry over, and
+/// are styled and colored by kind. A legend lists the areas and edge
+/// kinds that appear.
+pub fn export_neighborhood_dot(graph: &RPGraph, tree: &TraversalNode) -> String {
+    let mut nodes: BTreeMap<&str, &TraversalNode> = BTreeMap::new();
+    let mut edges: BTreeSet<(&str, &str, EdgeKind)> = BTreeSet::new();
+    collect_neighborhood(tree, &mut nodes, &mut edges);
+
+    let area_of = |id: &str| {
+        graph
+            .entities
+            .get(id)
+            .map(|e| e.hierarchy_path.split('/').next().unwrap_or_default())
+            .filter(|area| !area.is_empty())
+    };
+    let areas: BTreeSet<&str> = nodes.keys().filter_map(|id| area_of(id)).collect();
+    let area_color = |area: Option<&str>| match area {
+        Some(area) => {
+            let index = areas.iter().position(|a| *a == area).unwrap_or_default();
+            AREA_COLORS[index % AREA_COLORS.len()]
+        }
+        None => NO_AREA_COLOR,
+    };
+
+    let mut out = String::new();
+    writeln!(out, "digraph RPG {{").unwrap();
+    writeln!(out, "  rankdir=LR;").unwrap();
+    writeln!(out, "  node [shape=box, fontsize=10];").unwrap();
+    writeln!(out).unwrap();
+
+    for (id, node) in &nodes {
+        let shape = node.kind.map_or("folder", dot_shape);
+        let outline = if *id == tree.entity_id {
+            ", penwidth=2"
+        } else {
+            ""
+        };
+        writeln!(
+            out,
+            "  \"{}\" [shape={}, style=filled, fillcolor=\"{}\"{}, label=\"{}\"];",
+            id,
+            shape,
+            area_color(area_of(id)),
+            outline,
+            node.entity_name
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+
+    for (source, target, kind) in &edges {
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [style={}, color=\"{}\", label=\"{}\"];",
+            source,
+            target,
+            dot_edge_style(*kind),
+            dot_edge_color(*kind),
+            edge_label(*kind)
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+
+    writeln!(out, "  subgraph cluster_legend {{").unwrap();
+    writeln!(out, "    label=\"Legend\";").unwrap();
+    writeln!(out, "    fontsize=10;").unwrap();
+    let has_unassigned = nodes.keys().any(|id| area_of(id).is_none());
+    let legend_areas = areas
+        .iter()
+        .map(|area| (*area, area_color(Some(area))))
+        .chain(has_unassigned.then_some(("(no area)", NO_AREA_COLOR)));
+    for (area, color) in legend_areas {
+        writeln!(
+            out,
+            "    \"legend:area:{}\" [shape=box, style=filled, fillcolor=\"{}\", label=\"{}\"];",
+            area, color, area
+        )
+        .unwrap();
+    }
+    let kinds: BTreeSet<EdgeKind> = edges.iter().map(|(_, _, kind)| *kind).collect();
+    for kind in kinds {
+        let label = edge_label(kind);
+        writeln!(
+            out,
+            "    \"legend:{}:from\" [shape=point]; \"legend:{}:to\" [shape=point];",
+            label, label
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    \"legend:{}:from\" -> \"legend:{}:to\" [style={}, color=\"{}\", label=\"{}\"];",
+            label,
+            label,
+            dot_edge_style(kind),
+            dot_edge_color(kind),
+            label
+        )
+        .unwrap();
+    }
+    writeln!(out, "  }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+/// Gather a traversal's nodes and its parent–child edges, oriented the way
+/// the graph edge points (upstream children are the edge source).
+fn collect_neighborhood<'a>(
+    node: &'a TraversalNode,
+    nodes: &mut BTreeMap<&'a str, &'a TraversalNode>,
+    edges: &mut BTreeSet<(&'a str, &'a str, EdgeKind)>,
+) {
+    nodes.entry(node.entity_id.as_str()).or_insert(node);
+    for child in &node.children {
+        if let Some(kind) = child.edge_kind {
+            let (source, target) = if child.direction.as_deref() == Some("upstream") {
+                (child.entity_id.as_str(), node.entity_id.as_str())
+            } else {
+                (node.entity_id.as_str(), child.entity_id.as_str())
+            };
+            edges.insert((source, target, kind));
+        }
+        collect_neighborhood(child, nodes, edges);
+    }
+}
+
+fn write_dot_hierarchy_children(
+    parent_id: &str,
+    node: &rpg_core::graph::HierarchyNode,
+    out: &mut String,
+) {
+    for (child_name, child) in &node.children {
+        writeln!(
+            out,
+            "  \"{}\" [shape=folder, style=filled, fillcolor=\"#e0e0ff\", label=\"{}\"];",
+            child.id, child_name
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [style=dotted];",
+            parent_id, child.id
+        )
+        .unwrap();
+        write_dot_hierarchy_children(&child.id, child, out);
+    }
+}
+
+/// Export the graph as a Mermaid flowchart string.
+pub fn export_mermaid(graph: &RPGraph) -> String {
+    let mut out = String::new();
+    writeln!(out, "flowchart LR").unwrap();
+    writeln!(out).unwrap();
+
+    // Hierarchy nodes as subgraphs
+    for (area_name, area) in &graph.hierarchy {
+        let safe_id = mermaid_safe_id(&area.id);
+        writeln!(out, "  subgraph {}[\"{}\"]", safe_id, area_name).unwrap();
+
+        // List entities directly in this area
+        for eid in &area.entities {
+            if let Some(entity) = graph.entities.get(eid) {
+                let safe_eid = mermaid_safe_id(eid);
+                writeln!(
+                    out,
+                    "    {}[\"{}\\n({})\"]",
+                    safe_eid,
+                    entity.name,
+                    format!("{:?}", entity.kind).to_lowercase()
+                )
+                .unwrap();
+            }
+        }
+
+        // Recurse into children
+        write_mermaid_hierarchy_children(area, graph, &mut out, 2);
+
+        writeln!(out, "  end").unwrap();
+        writeln!(out).unwrap();
+    }
+
+    // Dependency edges (skip Contains — already shown via subgraphs)
+    for edge in &graph.edges {
+        if edge.kind == EdgeKind::Contains {
+            continue;
+        }
+        let src = mermaid_safe_id(&edge.source);
+        let tgt = mermaid_safe_id(&edge.target);
+        let arrow = match edge.kind {
+            EdgeKind::Invokes
+            | EdgeKind::Contains
+            | EdgeKind::Composes
+            | EdgeKind::Renders
+            | EdgeKind::Dispatches => "-->",
+            EdgeKind::Imports | EdgeKind::DataFlow | EdgeKind::Tests => "-.->",
+            EdgeKind::Inherits | EdgeKind::WritesState => "==>",
+            EdgeKind::ReadsState => "-.->",
+        };
+        let label = edge_label(edge.kind);
+        writeln!(out, "  {} {}|{}| {}", src, arrow, label, tgt).unwrap();
+    }
+
+    out
+}
+
+fn write_mermaid_hierarchy_children(
+    node: &rpg_core::graph::HierarchyNode,
+    graph: &RPGraph,
+    out: &mut String,
+    indent: usize,
+) {
+    let pad = " ".repeat(indent * 2);
+    for (child_name, child) in &node.children {
+        let safe_id = mermaid_safe_id(&child.id);
+        writeln!(out, "{}subgraph {}[\"{}\"]", pad, safe_id, child_name).unwrap();
+
+        for eid in &child.entities {
+            if let Some(entity) = graph.entities.get(eid) {
+                let safe_eid = mermaid_safe_id(eid);
+                writeln!(
+                    out,
+                    "{}  {}[\"{}\\n({})\"]",
+                    pad,
+                    safe_eid,
+                    entity.name,
+                    format!("{:?}", entity.kind).to_lowercase()
+                )
+                .unwrap();
+            }
+        }
+
+        write_mermaid_hierarchy_children(child, graph, out, indent + 1);
+        writeln!(out, "{}end", pad).unwrap();
+    }
+}
+
+/// Make an ID safe for Mermaid (replace special characters).
+fn mermaid_safe_id(id: &str) -> String {
+    id.replace([':', '/', '.', ' ', '-'], "_")
+}
+
+/// Export the graph in the specified format.
+pub fn export(graph: &RPGraph, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Dot => export_dot(graph),
+        ExportFormat::Mermaid => export_mermaid(graph),
+    }
+}
+
+/// Restrict a graph to the entities matching a scope expression.
+///
+/// Hierarchy nodes left without entities are dropped, as are edges whose
+/// endpoints no longer exist.
+pub fn scoped_graph(graph: &RPGraph, scope: &ScopeExpr) -> RPGraph {
+    let mut scoped = graph.clone();
+    scoped.entities.retain(|_, e| scope.matches(e));
+
+    let mut kept_nodes: HashSet<String> = HashSet::new();
+    scoped
+        .hierarchy
+        .retain(|_, area| retain_hierarchy(area, &scoped.entities, &mut kept_nodes));
+
+    scoped.edges.retain(|e| {
+        let present = |id: &str| scoped.entities.contains_key(id) || kept_nodes.contains(id);
+        present(&e.source) && present(&e.target)
+    });
+    for ids in scoped.file_index.values_mut() {
+        ids.retain(|id| scoped.entities.contains_key(id));
+    }
+    scoped.file_index.retain(|_, ids| !ids.is_empty());
+    scoped.rebuild_edge_index();
+    scoped.rebuild_hierarchy_index();
+    scoped
+}
+
+/// Drop entities outside `entities` from a subtree. Returns false if the node ends up empty.
+fn retain_hierarchy(
+    node: &mut HierarchyNode,
+    entities: &BTreeMap<String, Entity>,
+    kept: &mut HashSet<String>,
+) -> bool {
+    node.entities.retain(|id| entities.contains_key(id));
+    node.children
+        .retain(|_, child| retain_hierarchy(child, entities, kept));
+    let keep = !node.entities.is_empty() || !node.children.is_empty();
+    if keep {
+        kept.insert(node.id.clone());
+    }
+    keep
+}
@@ -0,0 +1,308 @@
+//! SARIF 2.1.0 output for graph findings (code-scanning integration).
+//!
+//! Commands that report findings (`validate`, `health`) map them to [`Finding`]s
+//! and render them with [`to_sarif`], so every checker emits the same document
+//! shape: one run, the checker's rules in `tool.driver.rules`, and one result per
+//! finding with a physical location (file + entity line range) when known.
+
+use crate::health::{HealthIssue, HealthReport};
+use rpg_core::graph::{Entity, RPGraph};
+use serde::Serialize;
+use std::path::Path;
+
+/// SARIF version emitted by [`to_sarif`].
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// JSON schema URI for SARIF 2.1.0.
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF result level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A rule a checker can report, listed in `tool.driver.rules`.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub level: Level,
+}
+
+/// Where a finding points: a file and, when known, a 1-based line range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub file: String,
+    pub line_start: Option<usize>,
+    pub line_end: Option<usize>,
+}
+
+impl Location {
+    /// Location spanning an entity's source lines.
+    pub fn entity(entity: &Entity) -> Self {
+        let line_start = (entity.line_start > 0).then_some(entity.line_start);
+        Self {
+            file: artifact_uri(&entity.file),
+            line_start,
+            line_end: line_start.map(|start| entity.line_end.max(start)),
+        }
+    }
+
+    /// Location covering a whole file.
+    pub fn file(path: &Path) -> Self {
+        Self {
+            file: artifact_uri(path),
+            line_start: None,
+            line_end: None,
+        }
+    }
+}
+
+/// One finding from a checker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule_id: &'static str,
+    pub level: Level,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Finding {
+    /// Finding for `rule`, at its default level.
+    pub fn new(rule: &Rule, message: impl Into<String>, location: Option<Location>) -> Self {
+        Self {
+            rule_id: rule.id,
+            level: rule.level,
+            message: message.into(),
+            location,
+        }
+    }
+}
+
+/// Render findings as a SARIF 2.1.0 log with a single run.
+///
+/// `rules` should list every rule the checker can report, not only the ones
+/// that fired, so code-scanning UIs can show rule help for clean runs too.
+pub fn to_sarif(rules: &[Rule], findings: &[Finding]) -> String {
+    let results = findings
+        .iter()
+        .map(|f| SarifResult {
+            rule_id: f.rule_id,
+            rule_index: rules.iter().position(|r| r.id == f.rule_id),
+            level: f.level,
+            message: Message {
+                text: f.message.clone(),
+            },
+            locations: f
+                .location
+                .iter()
+                .map(|loc| SarifLocation {
+                    physical_location: PhysicalLocation {
+                        artifact_location: ArtifactLocation {
+                            uri: loc.file.clone(),
+                            uri_base_id: "%SRCROOT%",
+                        },
+                        region: loc.line_start.map(|start_line| Region {
+                            start_line,
+                            end_line: loc.line_end,
+                        }),
+                    },
+                })
+                .collect(),
+        })
+        .collect();
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "rpg-encoder",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules: rules
+                        .iter()
+                        .map(|r| ReportingDescriptor {
+                            id: r.id,
+                            short_description: Message {
+                                text: r.description.to_string(),
+                            },
+                            default_configuration: Configuration { level: r.level },
+                        })
+                        .collect(),
+                },
+            },
+            results,
+        }],
+    };
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+const GOD_OBJECT: Rule = Rule {
+    id: "health/potential-god-object",
+    description: "High total degree with extreme instability",
+    level: Level::Warning,
+};
+const HIGHLY_UNSTABLE: Rule = Rule {
+    id: "health/highly-unstable",
+    description: "Depends on many entities and is depended on by few",
+    level: Level::Note,
+};
+const HIGHLY_STABLE: Rule = Rule {
+    id: "health/highly-stable",
+    description: "Depended on by many entities; changes ripple widely",
+    level: Level::Note,
+};
+const HUB_ENTITY: Rule = Rule {
+    id: "health/hub-entity",
+    description: "High total dependency degree",
+    level: Level::Note,
+};
+
+/// Rules reported by the health analysis.
+pub const HEALTH_RULES: &[Rule] = &[GOD_OBJECT, HIGHLY_UNSTABLE, HIGHLY_STABLE, HUB_ENTITY];
+
+/// Map a health report to findings, one per detected issue.
+pub fn health_findings(graph: &RPGraph, report: &HealthReport) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for entity in &report.entities {
+        let location = graph.entities.get(&entity.entity_id).map(Location::entity);
+        for issue in &entity.issues {
+            let (rule, message) = match issue {
+                HealthIssue::PotentialGodObject {
+                    total_degree,
+                    instability,
+                } => (
+                    &GOD_OBJECT,
+                    format!(
+                        "{} is a potential god object (degree {}, instability {:.2})",
+                        entity.entity_id, total_degree, instability
+                    ),
+                ),
+                HealthIssue::HighlyUnstable {
+                    instability,
+                    out_degree,
+                } => (
+                    &HIGHLY_UNSTABLE,
+                    format!(
+                        "{} is highly unstable (instability {:.2}, {} outgoing dependencies)",
+                        entity.entity_id, instability, out_degree
+                    ),
+                ),
+                HealthIssue::HighlyStable {
+                    instability,
+                    in_degree,
+                } => (
+                    &HIGHLY_STABLE,
+                    format!(
+                        "{} is highly stable (instability {:.2}, {} dependents)",
+                        entity.entity_id, instability, in_degree
+                    ),
+                ),
+                HealthIssue::HubEntity { total_degree } => (
+                    &HUB_ENTITY,
+                    format!("{} is a hub (degree {})", entity.entity_id, total_degree),
+                ),
+            };
+            findings.push(Finding::new(rule, message, location.clone()));
+        }
+    }
+    findings
+}
+
+/// Repo-relative path with forward slashes, as SARIF artifact URIs expect.
+fn artifact_uri(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: &'static str,
+    short_description: Message,
+    default_configuration: Configuration,
+}
+
+#[derive(Serialize)]
+struct Configuration {
+    level: Level,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: Level,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    uri_base_id: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_line: Option<usize>,
+}
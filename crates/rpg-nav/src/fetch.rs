@@ -1,8 +1,10 @@
 //! FetchNode: precise entity metadata and source retrieval.
 
 use anyhow::Result;
-use rpg_core::graph::{Entity, HierarchyNode, RPGraph};
-use std::fs;
+use rpg_core::files::{FileProvider, WorktreeFiles};
+use rpg_core::graph::{Entity, EntityKind, HierarchyNode, RPGraph, normalize_path};
+use rpg_core::tokens::TokenEstimator;
+use std::path::Path;
 
 /// Detailed entity information returned by FetchNode.
 #[derive(Debug, Clone)]
@@ -24,21 +26,31 @@ pub struct HierarchyFetchResult {
 #[derive(Debug, Clone)]
 pub enum FetchOutput {
     Entity(Box<FetchResult>),
-    Hierarchy(HierarchyFetchResult),
+    Hierarchy(Box<HierarchyFetchResult>),
 }
 
 /// Fetch full details for an entity or hierarchy node by ID.
 /// For V_L entities: includes source code from disk.
-/// For V_H hierarchy nodes (IDs starting with "h:"): includes children and aggregated features.
+/// For V_H hierarchy nodes (an "h:" ID or a bare hierarchy path): includes
+/// children and aggregated features.
 pub fn fetch(
     graph: &RPGraph,
     entity_id: &str,
     project_root: &std::path::Path,
+) -> Result<FetchOutput> {
+    fetch_from(graph, entity_id, &WorktreeFiles::new(project_root))
+}
+
+/// [`fetch`] reading entity source from `files`, e.g. a git revision.
+pub fn fetch_from(
+    graph: &RPGraph,
+    entity_id: &str,
+    files: &dyn FileProvider,
 ) -> Result<FetchOutput> {
     // Try V_L entity first
     if let Some(entity) = graph.get_entity(entity_id) {
         let entity = entity.clone();
-        let source_code = read_entity_source(project_root, &entity);
+        let source_code = read_entity_source(files, &entity);
         let hierarchy_context = find_siblings(graph, &entity);
         return Ok(FetchOutput::Entity(Box::new(FetchResult {
             entity,
@@ -48,24 +60,126 @@ pub fn fetch(
     }
 
     // Try V_H hierarchy node
-    if entity_id.starts_with("h:")
-        && let Some(node) = graph.find_hierarchy_node_by_id(entity_id)
-    {
+    if let Some(node) = graph.find_hierarchy_node(entity_id) {
         let child_names: Vec<String> = node.children.keys().cloned().collect();
         let entity_count = node.entity_count();
-        return Ok(FetchOutput::Hierarchy(HierarchyFetchResult {
+        return Ok(FetchOutput::Hierarchy(Box::new(HierarchyFetchResult {
             node: node.clone(),
             child_names,
             entity_count,
-        }));
+        })));
     }
 
     Err(anyhow::anyhow!("entity not found: {}", entity_id))
 }
 
-fn read_entity_source(project_root: &std::path::Path, entity: &Entity) -> Option<String> {
-    let file_path = project_root.join(&entity.file);
-    let content = fs::read_to_string(&file_path).ok()?;
+/// Every entity in one file: the Module entity first, then the rest in line
+/// order. `file` is relative to the project root; `./` prefixes and
+/// backslashes are accepted. A file the graph does not index fails with
+/// [`missing_file_message`].
+pub fn fetch_file(graph: &RPGraph, file: &str, project_root: &Path) -> Result<Vec<FetchResult>> {
+    fetch_file_from(graph, file, &WorktreeFiles::new(project_root))
+}
+
+/// [`fetch_file`] reading entity source from `files`, e.g. a git revision.
+pub fn fetch_file_from(
+    graph: &RPGraph,
+    file: &str,
+    files: &dyn FileProvider,
+) -> Result<Vec<FetchResult>> {
+    let normalized = normalize_file_arg(file);
+    let ids = graph
+        .file_index
+        .get(Path::new(&normalized))
+        .ok_or_else(|| anyhow::anyhow!(missing_file_message(graph, file)))?;
+    let mut entities: Vec<&Entity> = ids.iter().filter_map(|id| graph.entities.get(id)).collect();
+    entities.sort_by_key(|e| {
+        (
+            e.kind != EntityKind::Module,
+            e.line_start,
+            e.line_end,
+            &e.id,
+        )
+    });
+    Ok(entities
+        .into_iter()
+        .map(|entity| FetchResult {
+            entity: entity.clone(),
+            source_code: read_entity_source(files, entity),
+            hierarchy_context: find_siblings(graph, entity),
+        })
+        .collect())
+}
+
+fn normalize_file_arg(file: &str) -> String {
+    let file = normalize_path(Path::new(file.trim()));
+    file.trim_start_matches("./").to_string()
+}
+
+/// Most paths [`suggest_files`] returns.
+const MAX_PATH_SUGGESTIONS: usize = 3;
+
+/// Indexed files close to `file`: those with the same file name first, then
+/// paths at least 60% similar by edit distance, best first.
+pub fn suggest_files(graph: &RPGraph, file: &str) -> Vec<String> {
+    let wanted = normalize_file_arg(file);
+    let wanted_name = wanted.rsplit('/').next().unwrap_or(&wanted);
+    let mut scored: Vec<(bool, f64, String)> = graph
+        .file_index
+        .keys()
+        .map(|path| {
+            let path = normalize_path(path);
+            let same_name = path.rsplit('/').next() == Some(wanted_name);
+            let similarity = strsim::normalized_levenshtein(&wanted, &path);
+            (same_name, similarity, path)
+        })
+        .filter(|(same_name, similarity, _)| *same_name || *similarity >= 0.6)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then(b.1.total_cmp(&a.1))
+            .then_with(|| a.2.cmp(&b.2))
+    });
+    scored
+        .into_iter()
+        .take(MAX_PATH_SUGGESTIONS)
+        .map(|(_, _, path)| path)
+        .collect()
+}
+
+/// Error text for a file path the graph does not index, with suggestions.
+pub fn missing_file_message(graph: &RPGraph, file: &str) -> String {
+    let suggestions = suggest_files(graph, file);
+    if suggestions.is_empty() {
+        format!("No entities for file: {}", file)
+    } else {
+        format!(
+            "No entities for file: {} (did you mean: {}?)",
+            file,
+            suggestions.join(", ")
+        )
+    }
+}
+
+/// How many of `blocks`, taken in order, fit in `token_budget`. The first
+/// block is always kept so a tight budget still returns something.
+pub fn fit_token_budget(
+    blocks: &[String],
+    token_budget: usize,
+    estimator: &dyn TokenEstimator,
+) -> usize {
+    let mut used = 0;
+    for (i, block) in blocks.iter().enumerate() {
+        used += estimator.estimate(block);
+        if used > token_budget && i > 0 {
+            return i;
+        }
+    }
+    blocks.len()
+}
+
+fn read_entity_source(files: &dyn FileProvider, entity: &Entity) -> Option<String> {
+    let content = files.read_to_string(&entity.file).ok()?;
 
     let lines: Vec<&str> = content.lines().collect();
     let start = entity.line_start.saturating_sub(1);
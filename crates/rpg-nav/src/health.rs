@@ -11,7 +11,7 @@ use crate::duplication::{
     CloneGroup, DuplicationConfig, SemanticCloneGroup, SemanticDuplicationConfig,
     detect_duplication, detect_semantic_duplicates,
 };
-use rpg_core::graph::{EdgeKind, EntityKind, RPGraph};
+use rpg_core::graph::{ChurnRange, EdgeKind, EntityChurn, EntityKind, RPGraph};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::path::Path;
@@ -66,6 +66,12 @@ pub struct EntityHealth {
     /// Detected health issues for this entity.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub issues: Vec<HealthIssue>,
+    /// Change frequency from the last churn analysis, if it touched this entity.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub churn: Option<EntityChurn>,
+    /// Call hops from the nearest entry point; `None` if unreachable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_distance: Option<u32>,
 }
 
 /// Aggregate health statistics for the codebase.
@@ -82,6 +88,10 @@ pub struct HealthSummary {
     pub highly_unstable_count: usize,
     pub highly_stable_count: usize,
     pub hub_count: usize,
+    /// Non-test entities no entry point reaches over Invokes/Dispatches
+    /// edges; `None` when the graph has no entry points.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unreachable_count: Option<usize>,
 }
 
 /// Complete health analysis report.
@@ -97,6 +107,16 @@ pub struct HealthReport {
     pub top_unstable: Vec<EntityHealth>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub top_god_objects: Vec<EntityHealth>,
+    /// History window of the graph's churn analysis; `None` if never run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub churn_range: Option<ChurnRange>,
+    /// Most frequently changed entities (by commits, then lines).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub top_churn: Vec<EntityHealth>,
+    /// First `top_n` unreachable non-test entities by ID: dead-code
+    /// candidates.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unreachable: Vec<String>,
 }
 
 /// Configuration for health analysis.
@@ -240,6 +260,8 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
             instability: clean_float(instability),
             centrality: clean_float(centrality),
             issues,
+            churn: entity.churn,
+            entry_distance: entity.entry_distance,
         });
     }
 
@@ -275,8 +297,8 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
     let mut sorted_by_instability = entities.clone();
     sorted_by_instability.sort_by(|a, b| {
         b.instability
-            .partial_cmp(&a.instability)
-            .unwrap_or(std::cmp::Ordering::Equal)
+            .total_cmp(&a.instability)
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
     });
     let top_unstable: Vec<EntityHealth> = sorted_by_instability
         .into_iter()
@@ -297,11 +319,14 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
             .iter()
             .filter(|i| matches!(i, HealthIssue::PotentialGodObject { .. }))
             .count();
-        b_score.cmp(&a_score).then_with(|| {
-            let a_degree: usize = a.in_degree + a.out_degree;
-            let b_degree: usize = b.in_degree + b.out_degree;
-            b_degree.cmp(&a_degree)
-        })
+        b_score
+            .cmp(&a_score)
+            .then_with(|| {
+                let a_degree: usize = a.in_degree + a.out_degree;
+                let b_degree: usize = b.in_degree + b.out_degree;
+                b_degree.cmp(&a_degree)
+            })
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
     });
     let top_god_objects: Vec<EntityHealth> = sorted_by_god
         .into_iter()
@@ -313,6 +338,35 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         .take(config.top_n)
         .collect();
 
+    let mut top_churn: Vec<EntityHealth> = entities
+        .iter()
+        .filter(|e| e.churn.is_some())
+        .cloned()
+        .collect();
+    top_churn.sort_by(|a, b| {
+        b.churn
+            .cmp(&a.churn)
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+    top_churn.truncate(config.top_n);
+
+    // Distances are only meaningful once some entity is an entry point.
+    let has_entry_points = graph.entities.values().any(|e| e.entry_distance == Some(0));
+    let mut unreachable: Vec<String> = if has_entry_points {
+        graph
+            .entities
+            .values()
+            .filter(|e| e.kind != EntityKind::Module && !e.is_test())
+            .filter(|e| e.entry_distance.is_none())
+            .map(|e| e.id.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    unreachable.sort();
+    let unreachable_count = has_entry_points.then_some(unreachable.len());
+    unreachable.truncate(config.top_n);
+
     let summary = HealthSummary {
         total_entities,
         analyzed_entities: analyzed,
@@ -325,6 +379,7 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         highly_unstable_count,
         highly_stable_count,
         hub_count,
+        unreachable_count,
     };
 
     // Sort entities by entity_id for deterministic output
@@ -337,6 +392,9 @@ pub fn compute_health(graph: &RPGraph, config: &HealthConfig) -> HealthReport {
         semantic_duplicates: None,
         top_unstable,
         top_god_objects,
+        churn_range: graph.metadata.churn.clone(),
+        top_churn,
+        unreachable,
     }
 }
 
@@ -379,6 +437,7 @@ fn clean_float(v: f64) -> f64 {
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, Entity, EntityDeps};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str, kind: EntityKind) -> Entity {
@@ -392,9 +451,22 @@ mod tests {
             parent_class: None,
             semantic_features: vec![],
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -466,6 +538,30 @@ mod tests {
         assert!((b.instability - 0.5).abs() < 0.001); // Balanced
     }
 
+    #[test]
+    fn test_unreachable_entities_reported_with_entry_points() {
+        let mut graph = make_test_graph();
+        let report = compute_health(&graph, &HealthConfig::default());
+        assert_eq!(report.summary.unreachable_count, None);
+        assert!(report.unreachable.is_empty());
+
+        graph.insert_entity(make_entity("main", "main", EntityKind::Function));
+        graph.edges.push(DependencyEdge {
+            source: "main".to_string(),
+            target: "b".to_string(),
+            kind: EdgeKind::Invokes,
+        });
+        crate::orient::assign_entry_distances(&mut graph);
+        let report = compute_health(&graph, &HealthConfig::default());
+        assert_eq!(report.summary.unreachable_count, Some(1));
+        assert_eq!(report.unreachable, vec!["a".to_string()]);
+        let c = report.entities.iter().find(|e| e.entity_id == "c").unwrap();
+        assert_eq!(c.entry_distance, Some(2));
+        assert!(
+            crate::toon::format_health_report(&report).contains("unreachable_from_entry_points: 1")
+        );
+    }
+
     #[test]
     fn test_god_object_detection() {
         let mut graph = RPGraph::new("rust");
@@ -3,6 +3,7 @@
 use crate::explore::{Direction, get_neighbors};
 use rpg_core::graph::{EdgeKind, RPGraph};
 use std::collections::{HashSet, VecDeque};
+use std::time::Instant;
 
 /// Edge kinds that represent dependency relationships (not structural containment).
 const DEPENDENCY_EDGE_KINDS: &[EdgeKind] = &[
@@ -15,6 +16,7 @@ const DEPENDENCY_EDGE_KINDS: &[EdgeKind] = &[
     EdgeKind::WritesState,
     EdgeKind::Dispatches,
     EdgeKind::DataFlow,
+    EdgeKind::Tests,
 ];
 
 /// A single entity in the impact set with its path from the origin.
@@ -26,6 +28,9 @@ pub struct ImpactEntry {
     pub depth: usize,
     pub edge_path: Vec<(String, EdgeKind)>,
     pub features: Vec<String>,
+    /// The last hop is an Invokes edge into a spawned task or thread
+    /// ([`RPGraph::is_async_boundary`]).
+    pub async_boundary: bool,
 }
 
 /// The result of an impact radius computation.
@@ -36,6 +41,20 @@ pub struct ImpactResult {
     pub reachable: Vec<ImpactEntry>,
     pub total: usize,
     pub max_depth_reached: usize,
+    /// The traversal hit its time budget, so `reachable` is partial.
+    pub budget_exhausted: bool,
+}
+
+/// Optional limits on an impact traversal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImpactOptions {
+    /// Cap on the number of reachable entities returned.
+    pub max_results: Option<usize>,
+    /// Stop once this passes ([`crate::budget`]).
+    pub deadline: Option<Instant>,
+    /// Report entities reached across an async boundary, but don't walk
+    /// past them.
+    pub stop_at_async_boundary: bool,
 }
 
 /// Compute the impact radius from a starting entity via BFS.
@@ -48,6 +67,29 @@ pub fn compute_impact_radius(
     max_depth: usize,
     edge_filter: Option<EdgeKind>,
     max_results: Option<usize>,
+) -> Option<ImpactResult> {
+    compute_impact_radius_with(
+        graph,
+        entity_id,
+        direction,
+        max_depth,
+        edge_filter,
+        &ImpactOptions {
+            max_results,
+            ..Default::default()
+        },
+    )
+}
+
+/// Like [`compute_impact_radius`], with [`ImpactOptions`]. Past the
+/// deadline it returns what was reached so far with `budget_exhausted` set.
+pub fn compute_impact_radius_with(
+    graph: &RPGraph,
+    entity_id: &str,
+    direction: Direction,
+    max_depth: usize,
+    edge_filter: Option<EdgeKind>,
+    options: &ImpactOptions,
 ) -> Option<ImpactResult> {
     // Validate start entity exists
     if graph.get_entity(entity_id).is_none() && graph.get_node_display_info(entity_id).is_none() {
@@ -71,16 +113,21 @@ pub fn compute_impact_radius(
     let mut reachable = Vec::new();
     let mut max_depth_reached = 0;
 
-    let result_cap = max_results.unwrap_or(usize::MAX);
+    let result_cap = options.max_results.unwrap_or(usize::MAX);
+    let mut budget_exhausted = false;
 
     while let Some((current_id, depth, path)) = queue.pop_front() {
         if depth >= max_depth || reachable.len() >= result_cap {
             continue;
         }
+        if crate::budget::expired(options.deadline) {
+            budget_exhausted = true;
+            break;
+        }
 
         let neighbors = get_neighbors(graph, &current_id, direction, edge_filter);
 
-        for (neighbor_id, edge_kind, _dir_str) in neighbors {
+        for (neighbor_id, edge_kind, dir_str) in neighbors {
             if reachable.len() >= result_cap {
                 break;
             }
@@ -114,6 +161,14 @@ pub fn compute_impact_radius(
                 continue;
             };
 
+            // Upstream hops walk the edge from its target back to its source.
+            let async_boundary = edge_kind == EdgeKind::Invokes
+                && if dir_str == "upstream" {
+                    graph.is_async_boundary(&neighbor_id, &current_id)
+                } else {
+                    graph.is_async_boundary(&current_id, &neighbor_id)
+                };
+
             reachable.push(ImpactEntry {
                 entity_id: neighbor_id.clone(),
                 name,
@@ -121,9 +176,12 @@ pub fn compute_impact_radius(
                 depth: new_depth,
                 edge_path: new_path.clone(),
                 features,
+                async_boundary,
             });
 
-            queue.push_back((neighbor_id, new_depth, new_path));
+            if !(async_boundary && options.stop_at_async_boundary) {
+                queue.push_back((neighbor_id, new_depth, new_path));
+            }
         }
     }
 
@@ -135,6 +193,7 @@ pub fn compute_impact_radius(
         reachable,
         total,
         max_depth_reached,
+        budget_exhausted,
     })
 }
 
@@ -142,6 +201,7 @@ pub fn compute_impact_radius(
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(id: &str, name: &str) -> Entity {
@@ -155,9 +215,22 @@ mod tests {
             parent_class: None,
             semantic_features: vec!["test feature".to_string()],
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
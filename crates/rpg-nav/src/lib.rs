@@ -1,9 +1,12 @@
 //! Navigation tools for querying the Repository Planning Graph.
 //!
 //! Provides SearchNode (intent-based discovery), FetchNode (entity details),
-//! ExploreRPG (dependency traversal), Health analysis, Duplication detection,
-//! and TOON serialization for LLM-optimized output.
+//! ExploreRPG (dependency traversal), per-file symbol outlines, Health analysis,
+//! Duplication detection, and TOON serialization for LLM-optimized output.
 
+pub mod answer;
+pub mod budget;
+pub mod clusters;
 pub mod context;
 pub mod cycles;
 pub mod dataflow;
@@ -11,14 +14,22 @@ pub mod diff;
 pub mod duplication;
 #[cfg(feature = "embeddings")]
 pub mod embeddings;
+pub mod explain;
 pub mod explore;
 pub mod export;
 pub mod fetch;
 pub mod health;
 pub mod impact;
+pub mod orient;
+pub mod patch;
 pub mod paths;
 pub mod planner;
+pub mod queries;
 pub mod search;
 pub mod slice;
 pub mod snapshot;
+pub mod symbols;
 pub mod toon;
+pub mod vocab;
+
+pub use rpg_core::scope;
@@ -0,0 +1,398 @@
+//! Orientation: a single-call "where am I" summary for new agent sessions.
+//!
+//! Composes graph status, a per-area hierarchy summary, detected entry points,
+//! the highest fan-in entities, and suggested next tools into one compact
+//! document. Everything is derived from data already in the graph — no source
+//! reads, no embeddings.
+
+use rpg_core::graph::{Entity, HierarchyNode, RPGraph};
+use std::collections::HashMap;
+
+pub use rpg_core::entry_points::{EntryPoint, assign_entry_distances, detect_entry_points};
+
+/// Request parameters for building an orientation summary.
+pub struct OrientRequest {
+    /// Target token budget for the formatted output (default: 2_000).
+    pub token_budget: usize,
+    /// Max features shown per area/category summary (default: 3).
+    pub features_per_area: usize,
+    /// Max entry points listed (default: 15).
+    pub max_entry_points: usize,
+    /// Number of highest fan-in entities listed (default: 10).
+    pub top_fan_in: usize,
+}
+
+impl Default for OrientRequest {
+    fn default() -> Self {
+        Self {
+            token_budget: 2_000,
+            features_per_area: 3,
+            max_entry_points: 15,
+            top_fan_in: 10,
+        }
+    }
+}
+
+/// Top-level orientation result.
+pub struct OrientResult {
+    pub status: OrientStatus,
+    pub areas: Vec<AreaSummary>,
+    pub entry_points: Vec<EntryPoint>,
+    /// Entry points found beyond `max_entry_points` (omitted from the list).
+    pub entry_points_omitted: usize,
+    pub fan_in: Vec<FanInEntity>,
+    pub next_tools: Vec<ToolHint>,
+    pub token_estimate: usize,
+}
+
+/// Graph-level status line.
+pub struct OrientStatus {
+    pub entities: usize,
+    pub files: usize,
+    pub lifted: usize,
+    pub liftable: usize,
+    pub languages: String,
+    pub hierarchy_type: String,
+    pub paradigms: Vec<String>,
+    pub commit: Option<String>,
+    /// Staleness detail, filled in by the caller (requires git access).
+    pub staleness: Option<String>,
+}
+
+/// A top-level area or one of its categories with a one-line summary.
+pub struct AreaSummary {
+    /// Hierarchy path ("Area" or "Area/category").
+    pub path: String,
+    pub entity_count: usize,
+    /// Most frequent features across the subtree's entities.
+    pub top_features: Vec<String>,
+}
+
+/// An entity ranked by how many other entities depend on it.
+pub struct FanInEntity {
+    pub entity_id: String,
+    pub kind: String,
+    pub fan_in: usize,
+    pub features: Vec<String>,
+}
+
+/// A suggested tool chain for a common intent.
+pub struct ToolHint {
+    pub intent: &'static str,
+    pub tools: &'static str,
+}
+
+/// Build an orientation summary for the graph.
+pub fn build_orientation(graph: &RPGraph, request: &OrientRequest) -> OrientResult {
+    let (lifted, liftable) = graph.lifting_coverage();
+    let status = OrientStatus {
+        entities: graph.metadata.total_entities,
+        files: graph.metadata.total_files,
+        lifted,
+        liftable,
+        languages: if graph.metadata.languages.is_empty() {
+            graph.metadata.language.clone()
+        } else {
+            graph.metadata.languages.join(", ")
+        },
+        hierarchy_type: if graph.metadata.semantic_hierarchy {
+            "semantic".to_string()
+        } else {
+            "structural".to_string()
+        },
+        paradigms: graph.metadata.paradigms.clone(),
+        commit: graph
+            .base_commit
+            .as_ref()
+            .map(|s| s[..8.min(s.len())].to_string()),
+        staleness: None,
+    };
+
+    let mut areas = Vec::new();
+    for (area_name, area) in &graph.hierarchy {
+        areas.push(summarize_node(
+            graph,
+            area_name,
+            area,
+            request.features_per_area,
+        ));
+        for (cat_name, cat) in &area.children {
+            areas.push(summarize_node(
+                graph,
+                &format!("{}/{}", area_name, cat_name),
+                cat,
+                request.features_per_area,
+            ));
+        }
+    }
+
+    let all_entry_points = detect_entry_points(graph);
+    let entry_points_omitted = all_entry_points
+        .len()
+        .saturating_sub(request.max_entry_points);
+    let entry_points = all_entry_points
+        .into_iter()
+        .take(request.max_entry_points)
+        .collect();
+
+    let mut result = OrientResult {
+        status,
+        areas,
+        entry_points,
+        entry_points_omitted,
+        fan_in: top_fan_in(graph, request.top_fan_in),
+        next_tools: next_tool_hints(),
+        token_estimate: 0,
+    };
+    result.token_estimate = estimate_tokens(&result);
+    if result.token_estimate > request.token_budget {
+        trim_to_budget(&mut result, request.token_budget);
+    }
+    result
+}
+
+/// Summarize a hierarchy node by its most frequent entity features.
+fn summarize_node(
+    graph: &RPGraph,
+    path: &str,
+    node: &HierarchyNode,
+    max_features: usize,
+) -> AreaSummary {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for id in node.all_entity_ids() {
+        if let Some(entity) = graph.entities.get(&id) {
+            for feature in &entity.semantic_features {
+                *counts.entry(feature.as_str()).or_insert(0) += 1;
+            }
+        }
+    }
+    let mut ranked: Vec<(&str, usize)> = counts.into_iter().collect();
+    // Most frequent first, alphabetical for ties (deterministic output)
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    AreaSummary {
+        path: path.to_string(),
+        entity_count: node.entity_count(),
+        top_features: ranked
+            .into_iter()
+            .take(max_features)
+            .map(|(f, _)| f.to_string())
+            .collect(),
+    }
+}
+
+fn top_fan_in(graph: &RPGraph, top_n: usize) -> Vec<FanInEntity> {
+    let mut scored: Vec<(&String, &Entity, usize)> = graph
+        .entities
+        .iter()
+        .map(|(id, e)| (id, e, e.deps.fan_in()))
+        .filter(|(_, _, n)| *n > 0)
+        .collect();
+    scored.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+    scored
+        .into_iter()
+        .take(top_n)
+        .map(|(id, e, n)| FanInEntity {
+            entity_id: id.clone(),
+            kind: format!("{:?}", e.kind).to_lowercase(),
+            fan_in: n,
+            features: e.semantic_features.iter().take(2).cloned().collect(),
+        })
+        .collect()
+}
+
+fn next_tool_hints() -> Vec<ToolHint> {
+    vec![
+        ToolHint {
+            intent: "find code",
+            tools: "search_node -> fetch_node (or context_pack for one call)",
+        },
+        ToolHint {
+            intent: "assess a change",
+            tools: "plan_change or impact_radius(direction=upstream)",
+        },
+        ToolHint {
+            intent: "trace dependencies",
+            tools: "explore_rpg or find_paths",
+        },
+        ToolHint {
+            intent: "lift",
+            tools: "lifting_status -> get_entities_for_lifting -> submit_lift_results",
+        },
+    ]
+}
+
+fn estimate_tokens(result: &OrientResult) -> usize {
+    let mut chars = 300; // status block + headers
+    for area in &result.areas {
+        chars += area.path.len() + 12;
+        for f in &area.top_features {
+            chars += f.len() + 2;
+        }
+    }
+    for ep in &result.entry_points {
+        chars += ep.entity_id.len() + ep.reason.len() + 4;
+    }
+    for f in &result.fan_in {
+        chars += f.entity_id.len() + f.kind.len() + 10;
+        for feat in &f.features {
+            chars += feat.len() + 2;
+        }
+    }
+    for hint in &result.next_tools {
+        chars += hint.intent.len() + hint.tools.len() + 4;
+    }
+    chars / 4
+}
+
+/// Progressively trim the orientation to fit within the token budget.
+fn trim_to_budget(result: &mut OrientResult, budget: usize) {
+    // Step 1: Drop category-level summaries, keep top-level areas
+    result.areas.retain(|a| !a.path.contains('/'));
+    result.token_estimate = estimate_tokens(result);
+    if result.token_estimate <= budget {
+        return;
+    }
+
+    // Step 2: One feature per area, none on fan-in rows
+    for area in &mut result.areas {
+        area.top_features.truncate(1);
+    }
+    for f in &mut result.fan_in {
+        f.features.clear();
+    }
+    result.token_estimate = estimate_tokens(result);
+    if result.token_estimate <= budget {
+        return;
+    }
+
+    // Step 3: Shorten entry point and fan-in lists
+    if result.entry_points.len() > 5 {
+        result.entry_points_omitted += result.entry_points.len() - 5;
+        result.entry_points.truncate(5);
+    }
+    result.fan_in.truncate(5);
+    result.token_estimate = estimate_tokens(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rpg_core::graph::{EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::PathBuf;
+
+    fn make_entity(id: &str, name: &str, kind: EntityKind, file: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            kind,
+            name: name.to_string(),
+            file: PathBuf::from(file),
+            line_start: 1,
+            line_end: 5,
+            parent_class: None,
+            semantic_features: Vec::new(),
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        }
+    }
+
+    #[test]
+    fn test_detect_entry_points_order_and_reasons() {
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(make_entity(
+            "src/cli/main.rs:main",
+            "main",
+            EntityKind::Function,
+            "src/cli/main.rs",
+        ));
+        graph.insert_entity(make_entity(
+            "src/cli/main.rs:build",
+            "build",
+            EntityKind::Function,
+            "src/cli/main.rs",
+        ));
+        graph.insert_entity(make_entity(
+            "src/api.py:get_users",
+            "get_users",
+            EntityKind::Route,
+            "src/api.py",
+        ));
+        graph.insert_entity(make_entity(
+            "src/ops.rs:cmd_sync",
+            "cmd_sync",
+            EntityKind::Function,
+            "src/ops.rs",
+        ));
+        graph.insert_entity(make_entity(
+            "src/lib.rs:helper",
+            "helper",
+            EntityKind::Function,
+            "src/lib.rs",
+        ));
+
+        let points = detect_entry_points(&graph);
+        let got: Vec<(&str, &str)> = points
+            .iter()
+            .map(|p| (p.entity_id.as_str(), p.reason))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("src/cli/main.rs:main", "main"),
+                ("src/api.py:get_users", "route"),
+                ("src/cli/main.rs:build", "cli"),
+                ("src/ops.rs:cmd_sync", "cli"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_area_summary_ranks_by_frequency() {
+        let mut graph = RPGraph::new("rust");
+        let mut a = make_entity("a.rs:a", "a", EntityKind::Function, "a.rs");
+        a.semantic_features = vec!["parse input".into(), "validate token".into()];
+        let mut b = make_entity("b.rs:b", "b", EntityKind::Function, "b.rs");
+        b.semantic_features = vec!["validate token".into(), "emit event".into()];
+        graph.insert_entity(a);
+        graph.insert_entity(b);
+        graph.insert_into_hierarchy("Auth/tokens/check", "a.rs:a");
+        graph.insert_into_hierarchy("Auth/tokens/check", "b.rs:b");
+
+        let result = build_orientation(&graph, &OrientRequest::default());
+        let area = &result.areas[0];
+        assert_eq!(area.path, "Auth");
+        assert_eq!(area.entity_count, 2);
+        assert_eq!(area.top_features[0], "validate token");
+        assert_eq!(result.areas[1].path, "Auth/tokens");
+    }
+
+    #[test]
+    fn test_trim_drops_categories_first() {
+        let mut graph = RPGraph::new("rust");
+        graph.insert_entity(make_entity("a.rs:a", "a", EntityKind::Function, "a.rs"));
+        graph.insert_into_hierarchy("Core/run/start", "a.rs:a");
+
+        let request = OrientRequest {
+            token_budget: 1,
+            ..Default::default()
+        };
+        let result = build_orientation(&graph, &request);
+        assert_eq!(result.areas.len(), 1);
+        assert_eq!(result.areas[0].path, "Core");
+    }
+}
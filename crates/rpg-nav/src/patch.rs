@@ -0,0 +1,360 @@
+//! Patch summaries: map an arbitrary unified diff onto the graph.
+//!
+//! The patch's old side is taken to be the indexed tree, so hunks are matched
+//! by their `-a,b` ranges against entity line spans. Each touched entity is
+//! reported with its features, hierarchy path, fan-in, and whether a hunk
+//! reaches its signature; the upstream impact of the whole touched set gives
+//! the blast radius. Files the patch adds, and modified files the graph does
+//! not index, are listed separately.
+
+use crate::explore::Direction;
+use crate::impact::{ImpactEntry, ImpactOptions, compute_impact_radius_with};
+use rpg_core::files::FileProvider;
+use rpg_core::graph::{Entity, EntityKind, RPGraph};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// One file section of a unified diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Path before the patch; `None` when the patch creates the file.
+    pub old_path: Option<PathBuf>,
+    /// Path after the patch; `None` when the patch deletes the file.
+    pub new_path: Option<PathBuf>,
+    /// Old-side line ranges (1-based, inclusive). A pure insertion is
+    /// recorded as the single line it follows.
+    pub old_ranges: Vec<(usize, usize)>,
+}
+
+/// An entity a patch touches.
+#[derive(Debug, Clone)]
+pub struct TouchedEntity {
+    pub entity_id: String,
+    pub name: String,
+    pub kind: EntityKind,
+    pub file: String,
+    pub hierarchy_path: String,
+    pub features: Vec<String>,
+    /// Reverse dependency edges pointing at the entity.
+    pub fan_in: usize,
+    /// A hunk overlaps the entity's declaration header (decorators through
+    /// the line opening its body), not just its body.
+    pub signature_touched: bool,
+    /// The patch deletes the entity's file.
+    pub deleted: bool,
+}
+
+/// Limits on the blast-radius walk.
+#[derive(Debug, Clone, Copy)]
+pub struct PatchOptions {
+    /// Upstream hops walked from each touched entity.
+    pub max_depth: usize,
+    /// Stop walking once this passes ([`crate::budget`]).
+    pub deadline: Option<Instant>,
+}
+
+impl Default for PatchOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 3,
+            deadline: None,
+        }
+    }
+}
+
+/// Touched entities, blast radius, and files outside the graph.
+#[derive(Debug, Clone, Default)]
+pub struct PatchSummary {
+    /// In file order, then line order.
+    pub touched: Vec<TouchedEntity>,
+    /// Upstream dependents of the touched set (not themselves touched),
+    /// nearest first. Each is reported at its smallest depth.
+    pub blast_radius: Vec<ImpactEntry>,
+    /// Files the patch creates; the graph does not index them yet.
+    pub new_files: Vec<String>,
+    /// Files the patch deletes.
+    pub deleted_files: Vec<String>,
+    /// Modified files the graph does not index (docs, configs, ignored paths).
+    pub unindexed_files: Vec<String>,
+    /// The blast-radius walk hit its deadline, so `blast_radius` is partial.
+    pub budget_exhausted: bool,
+}
+
+/// Split a unified diff (`git diff` or `diff -u`) into file sections.
+///
+/// Hunk bodies are consumed by their line counts, so removed lines that
+/// happen to start with `--` are not taken for file headers.
+pub fn parse_patch(diff: &str) -> Vec<FilePatch> {
+    let mut files: Vec<FilePatch> = Vec::new();
+    let mut pending_old: Option<Option<PathBuf>> = None;
+    let (mut old_left, mut new_left) = (0usize, 0usize);
+    for line in diff.lines() {
+        if old_left > 0 || new_left > 0 {
+            match line.as_bytes().first() {
+                Some(b' ') | None => {
+                    old_left = old_left.saturating_sub(1);
+                    new_left = new_left.saturating_sub(1);
+                    continue;
+                }
+                Some(b'-') => {
+                    old_left = old_left.saturating_sub(1);
+                    continue;
+                }
+                Some(b'+') => {
+                    new_left = new_left.saturating_sub(1);
+                    continue;
+                }
+                Some(b'\\') => continue,
+                // A truncated hunk: fall through and read it as a header.
+                _ => (old_left, new_left) = (0, 0),
+            }
+        }
+        if let Some(path) = line.strip_prefix("--- ") {
+            pending_old = Some(header_path(path, "a/"));
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            files.push(FilePatch {
+                old_path: pending_old.take().flatten(),
+                new_path: header_path(path, "b/"),
+                old_ranges: Vec::new(),
+            });
+        } else if line.starts_with("@@ ")
+            && let Some(file) = files.last_mut()
+            && let Some((old, new)) = parse_hunk_sides(line)
+        {
+            let (start, count) = old;
+            let start = start.max(1);
+            file.old_ranges.push((start, start + count.max(1) - 1));
+            (old_left, new_left) = (count, new.1);
+        }
+    }
+    files
+}
+
+/// Path from a `---`/`+++` header, without its `a/`/`b/` prefix or a
+/// `diff -u` timestamp; `None` for `/dev/null`.
+fn header_path(header: &str, prefix: &str) -> Option<PathBuf> {
+    let path = header.split('\t').next().unwrap_or(header).trim_end();
+    let path = path.trim_matches('"');
+    (path != "/dev/null").then(|| PathBuf::from(path.strip_prefix(prefix).unwrap_or(path)))
+}
+
+/// `(start, count)` of the old and new sides of a `@@ -a,b +c,d @@` header.
+fn parse_hunk_sides(line: &str) -> Option<((usize, usize), (usize, usize))> {
+    let mut parts = line.split_whitespace().skip(1);
+    let old = parse_side(parts.next()?.strip_prefix('-')?)?;
+    let new = parse_side(parts.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+fn parse_side(side: &str) -> Option<(usize, usize)> {
+    match side.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((side.parse().ok()?, 1)),
+    }
+}
+
+/// Map `diff` onto `graph`, reading entity sources from `files` (the
+/// indexed tree) to find signature regions.
+pub fn summarize_patch(
+    graph: &RPGraph,
+    diff: &str,
+    files: &dyn FileProvider,
+    options: &PatchOptions,
+) -> PatchSummary {
+    let mut summary = PatchSummary::default();
+    for patch in parse_patch(diff) {
+        let Some(old_path) = patch.old_path else {
+            if let Some(new_path) = patch.new_path {
+                summary.new_files.push(display_path(&new_path));
+            }
+            continue;
+        };
+        let deleted = patch.new_path.is_none();
+        if deleted {
+            summary.deleted_files.push(display_path(&old_path));
+        }
+        let Some(ids) = graph.file_index.get(&old_path) else {
+            if !deleted {
+                summary.unindexed_files.push(display_path(&old_path));
+            }
+            continue;
+        };
+        let mut entities: Vec<&Entity> =
+            ids.iter().filter_map(|id| graph.entities.get(id)).collect();
+        entities.sort_by_key(|e| (e.line_start, e.line_end, &e.id));
+        let ranges = if deleted {
+            vec![(1, usize::MAX)]
+        } else {
+            patch.old_ranges
+        };
+        let source = files.read_to_string(&old_path).ok();
+        let lines: Vec<&str> = source
+            .as_deref()
+            .map_or_else(Vec::new, |s| s.lines().collect());
+
+        let mut touched_module = false;
+        let mut file_touched: Vec<TouchedEntity> = Vec::new();
+        for &(start, end) in &ranges {
+            let hits: Vec<&&Entity> = entities
+                .iter()
+                .filter(|e| e.kind != EntityKind::Module)
+                .filter(|e| e.line_start <= end && e.line_end >= start)
+                .collect();
+            // Hunks outside every declaration (imports, top-level code)
+            // touch the file's Module entity.
+            touched_module |= hits.is_empty();
+            for entity in hits {
+                let signature = deleted || {
+                    let header_end = header_end(entity, &lines);
+                    entity.line_start <= end && header_end >= start
+                };
+                match file_touched.iter_mut().find(|t| t.entity_id == entity.id) {
+                    Some(existing) => existing.signature_touched |= signature,
+                    None => file_touched.push(touched(entity, signature, deleted)),
+                }
+            }
+        }
+        if touched_module
+            && let Some(module) = entities.iter().find(|e| e.kind == EntityKind::Module)
+        {
+            file_touched.push(touched(module, false, deleted));
+        }
+        file_touched.sort_by_key(|t| {
+            let entity = &graph.entities[&t.entity_id];
+            (entity.kind != EntityKind::Module, entity.line_start)
+        });
+        summary.touched.extend(file_touched);
+    }
+
+    let touched_ids: Vec<&str> = summary
+        .touched
+        .iter()
+        .map(|t| t.entity_id.as_str())
+        .collect();
+    let mut reached: HashMap<String, ImpactEntry> = HashMap::new();
+    for id in &touched_ids {
+        let Some(result) = compute_impact_radius_with(
+            graph,
+            id,
+            Direction::Upstream,
+            options.max_depth,
+            None,
+            &ImpactOptions {
+                deadline: options.deadline,
+                ..Default::default()
+            },
+        ) else {
+            continue;
+        };
+        summary.budget_exhausted |= result.budget_exhausted;
+        for entry in result.reachable {
+            if touched_ids.contains(&entry.entity_id.as_str()) {
+                continue;
+            }
+            match reached.get(&entry.entity_id) {
+                Some(existing) if existing.depth <= entry.depth => {}
+                _ => {
+                    reached.insert(entry.entity_id.clone(), entry);
+                }
+            }
+        }
+        if summary.budget_exhausted {
+            break;
+        }
+    }
+    summary.blast_radius = reached.into_values().collect();
+    summary.blast_radius.sort_by(|a, b| {
+        a.depth
+            .cmp(&b.depth)
+            .then_with(|| a.entity_id.cmp(&b.entity_id))
+    });
+    summary
+}
+
+fn touched(entity: &Entity, signature_touched: bool, deleted: bool) -> TouchedEntity {
+    TouchedEntity {
+        entity_id: entity.id.clone(),
+        name: entity.name.clone(),
+        kind: entity.kind,
+        file: display_path(&entity.file),
+        hierarchy_path: entity.hierarchy_path.clone(),
+        features: entity.semantic_features.clone(),
+        fan_in: entity.deps.fan_in(),
+        signature_touched,
+        deleted,
+    }
+}
+
+/// Last line of the entity's declaration header: the first line, skipping
+/// comments, that ends with a body opener (`{`, `:`, `=>`, `=`) or a
+/// terminator (`;`). Falls back to the first line when the source is
+/// unavailable and to the whole entity when no such line exists.
+fn header_end(entity: &Entity, lines: &[&str]) -> usize {
+    if lines.is_empty() {
+        return entity.line_start;
+    }
+    let last = entity.line_end.min(lines.len());
+    (entity.line_start..=last)
+        .find(|&n| {
+            let line = lines[n - 1].trim();
+            let comment = ["//", "/*", "*", "#"].iter().any(|m| line.starts_with(m));
+            !comment
+                && [";", "{", ":", "=>", "="]
+                    .iter()
+                    .any(|end| line.ends_with(end))
+        })
+        .unwrap_or(entity.line_end)
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_patch_tracks_new_deleted_and_renamed_files() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3,2 +3,3 @@ fn a() {
+ keep
+--- not a header
++added
++added
+@@ -10,0 +12,1 @@
++inserted
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,2 @@
++fn new() {}
++
+diff --git a/src/old.rs b/src/old.rs
+--- a/src/old.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn old() {}
+-
+--- before.rs\t2024-01-01 00:00:00
++++ after.rs\t2024-01-02 00:00:00
+@@ -1 +1 @@
+-x
++y
+";
+        let files = parse_patch(diff);
+        assert_eq!(files.len(), 4);
+        assert_eq!(files[0].old_path, Some(PathBuf::from("src/a.rs")));
+        assert_eq!(files[0].old_ranges, vec![(3, 4), (10, 10)]);
+        assert_eq!(files[1].old_path, None);
+        assert_eq!(files[1].new_path, Some(PathBuf::from("src/new.rs")));
+        assert_eq!(files[2].new_path, None);
+        assert_eq!(files[2].old_ranges, vec![(1, 2)]);
+        assert_eq!(files[3].old_path, Some(PathBuf::from("before.rs")));
+        assert_eq!(files[3].new_path, Some(PathBuf::from("after.rs")));
+    }
+}
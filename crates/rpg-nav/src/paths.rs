@@ -309,6 +309,7 @@ fn find_shortest_path_excluding(
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, Entity, EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str) -> Entity {
@@ -322,9 +323,22 @@ mod tests {
             parent_class: None,
             semantic_features: Vec::new(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
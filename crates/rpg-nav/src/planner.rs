@@ -5,8 +5,9 @@
 
 use crate::explore::{Direction, get_neighbors};
 use crate::impact::compute_impact_radius;
-use crate::search::{SearchMode, SearchParams, search_with_params};
+use crate::search::{SearchMode, SearchParams, SearchSort, search_with_params};
 use rpg_core::graph::RPGraph;
+use rpg_core::scope::ScopeError;
 use std::collections::{HashMap, HashSet};
 
 /// Request parameters for change planning.
@@ -49,11 +50,13 @@ pub struct ChangePlan {
 }
 
 /// Plan code changes: find relevant entities, compute modification order, assess impact.
+///
+/// Fails when `request.scope` is a malformed scope expression.
 pub fn plan_change(
     graph: &RPGraph,
     request: &PlanChangeRequest,
     embedding_scores: Option<&HashMap<String, f64>>,
-) -> ChangePlan {
+) -> Result<ChangePlan, ScopeError> {
     let (lifted, total) = graph.lifting_coverage();
     let coverage_pct = if total > 0 {
         lifted as f64 / total as f64 * 100.0
@@ -72,10 +75,19 @@ pub fn plan_change(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
         },
-    );
+    )?;
 
     let relevant_entities: Vec<RelevantEntity> = results
         .iter()
@@ -129,14 +141,14 @@ pub fn plan_change(
     // Step 4: Find test entities that reference targets
     let test_coverage = find_test_coverage(graph, &target_ids);
 
-    ChangePlan {
+    Ok(ChangePlan {
         goal: request.goal.to_string(),
         relevant_entities,
         modification_order,
         impact_summary,
         test_coverage,
         coverage_pct,
-    }
+    })
 }
 
 /// Topological sort of target entities based on their internal dependency edges.
@@ -201,11 +213,9 @@ fn topological_sort(graph: &RPGraph, target_ids: &HashSet<String>) -> Vec<String
     }
 
     // Any remaining (cycles) — append in sorted order
-    for id in target_ids {
-        if !order.contains(id) {
-            order.push(id.clone());
-        }
-    }
+    let mut remaining: Vec<&String> = target_ids.iter().filter(|id| !order.contains(id)).collect();
+    remaining.sort();
+    order.extend(remaining.into_iter().cloned());
 
     order
 }
@@ -312,6 +322,7 @@ pub fn format_change_plan(plan: &ChangePlan) -> String {
 mod tests {
     use super::*;
     use rpg_core::graph::{DependencyEdge, EdgeKind, Entity, EntityDeps, EntityKind, RPGraph};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_entity(
@@ -331,9 +342,22 @@ mod tests {
             parent_class: None,
             semantic_features: features.into_iter().map(|s| s.to_string()).collect(),
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -403,7 +427,7 @@ mod tests {
             scope: None,
             max_entities: 10,
         };
-        let plan = plan_change(&graph, &request, None);
+        let plan = plan_change(&graph, &request, None).unwrap();
         assert!(
             !plan.relevant_entities.is_empty(),
             "should find entities matching 'server'"
@@ -421,7 +445,8 @@ mod tests {
                 max_entities: 10,
             },
             None,
-        );
+        )
+        .unwrap();
         let root_scoped = plan_change(
             &graph,
             &PlanChangeRequest {
@@ -430,7 +455,8 @@ mod tests {
                 max_entities: 10,
             },
             None,
-        );
+        )
+        .unwrap();
 
         assert_eq!(
             root_scoped.relevant_entities.len(),
@@ -479,7 +505,7 @@ mod tests {
             scope: None,
             max_entities: 10,
         };
-        let plan = plan_change(&graph, &request, None);
+        let plan = plan_change(&graph, &request, None).unwrap();
         // Server::start should have upstream dependents (test_start invokes it)
         let start_impact = plan
             .impact_summary
@@ -504,7 +530,7 @@ mod tests {
             scope: None,
             max_entities: 5,
         };
-        let plan = plan_change(&graph, &request, None);
+        let plan = plan_change(&graph, &request, None).unwrap();
         let output = format_change_plan(&plan);
         assert!(output.contains("Change Plan for:"));
     }
@@ -540,7 +566,7 @@ mod tests {
             scope: None,
             max_entities: 10,
         };
-        let plan = plan_change(&graph, &request, None);
+        let plan = plan_change(&graph, &request, None).unwrap();
 
         // Lexical name match should find Server even without semantic features
         assert!(
@@ -0,0 +1,293 @@
+//! Named saved queries: reusable explore/impact traversals stored in `.rpg/queries.toml`.
+//!
+//! A saved query is a tool name plus the parameters it was called with. Only the
+//! parameters are stored — every run re-resolves them against the current graph,
+//! so results track the code as it changes.
+//!
+//! The most recent explore/impact call is recorded in `.rpg/last_query.toml` so it
+//! can be saved under a name after the fact (`rpg-encoder query save <name>`).
+
+use crate::explore::{self, Direction};
+use anyhow::{Context, Result, bail};
+use rpg_core::graph::{EdgeKind, EntityKind, Layer, RPGraph};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Tools whose calls can be saved and re-run.
+pub const SAVABLE_TOOLS: &[&str] = &["explore_rpg", "impact_radius"];
+
+/// One saved query: the tool and its parameters (same keys as the MCP tool).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub tool: String,
+    #[serde(default)]
+    pub params: toml::Table,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueriesFile {
+    #[serde(default)]
+    queries: BTreeMap<String, SavedQuery>,
+}
+
+/// Path to the saved queries file.
+pub fn queries_file(project_root: &Path) -> PathBuf {
+    rpg_core::storage::rpg_dir(project_root).join("queries.toml")
+}
+
+/// Path to the record of the last executed explore/impact call.
+pub fn last_query_file(project_root: &Path) -> PathBuf {
+    rpg_core::storage::rpg_dir(project_root).join("last_query.toml")
+}
+
+/// Load all saved queries, keyed by name. A missing file means none are saved.
+pub fn load_all(project_root: &Path) -> Result<BTreeMap<String, SavedQuery>> {
+    let path = queries_file(project_root);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let file: QueriesFile =
+        toml::from_str(&content).with_context(|| format!("invalid {}", path.display()))?;
+    Ok(file.queries)
+}
+
+/// Look up one saved query by name.
+pub fn load(project_root: &Path, name: &str) -> Result<SavedQuery> {
+    let mut queries = load_all(project_root)?;
+    match queries.remove(name) {
+        Some(query) => Ok(query),
+        None if queries.is_empty() => bail!("No saved query named '{}' (none saved yet)", name),
+        None => bail!(
+            "No saved query named '{}'. Saved: {}",
+            name,
+            queries.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// Save (or overwrite) a named query.
+pub fn save(project_root: &Path, name: &str, query: &SavedQuery) -> Result<()> {
+    validate_name(name)?;
+    validate_tool(&query.tool)?;
+    let mut queries = load_all(project_root)?;
+    queries.insert(name.to_string(), query.clone());
+    write_toml(&queries_file(project_root), &QueriesFile { queries })
+}
+
+/// Delete a named query. Returns `false` if it didn't exist.
+pub fn remove(project_root: &Path, name: &str) -> Result<bool> {
+    let mut queries = load_all(project_root)?;
+    if queries.remove(name).is_none() {
+        return Ok(false);
+    }
+    write_toml(&queries_file(project_root), &QueriesFile { queries })?;
+    Ok(true)
+}
+
+/// Record the parameters of the last executed explore/impact call.
+pub fn record_last(project_root: &Path, query: &SavedQuery) -> Result<()> {
+    validate_tool(&query.tool)?;
+    write_toml(&last_query_file(project_root), query)
+}
+
+/// The last executed explore/impact call, if any was recorded.
+pub fn load_last(project_root: &Path) -> Result<Option<SavedQuery>> {
+    let path = last_query_file(project_root);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let query = toml::from_str(&content).with_context(|| format!("invalid {}", path.display()))?;
+    Ok(Some(query))
+}
+
+fn write_toml<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let content = toml::to_string_pretty(value)?;
+    std::fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!(
+            "Invalid query name '{}': use letters, digits, '-', '_', or '.'",
+            name
+        );
+    }
+    Ok(())
+}
+
+fn validate_tool(tool: &str) -> Result<()> {
+    if !SAVABLE_TOOLS.contains(&tool) {
+        bail!(
+            "Cannot save '{}' queries (supported: {})",
+            tool,
+            SAVABLE_TOOLS.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Run a saved query against `graph` and return the formatted output.
+///
+/// Parameters are interpreted the same way as the corresponding MCP tool.
+pub fn run(graph: &RPGraph, query: &SavedQuery) -> Result<String> {
+    let params = &query.params;
+    let entity_id = str_param(params, "entity_id")?
+        .context("saved query is missing 'entity_id'")?
+        .to_string();
+    let edge_filter = str_param(params, "edge_filter")?
+        .map(|f| parse_edge_kind(f).with_context(|| format!("unknown edge_filter '{}'", f)))
+        .transpose()?;
+    let max_results = int_param(params, "max_results")?.map(|n| usize::try_from(n).unwrap_or(0));
+
+    match query.tool.as_str() {
+        "explore_rpg" => {
+            let direction = parse_direction(str_param(params, "direction")?, Direction::Downstream);
+            let depth = depth_param(int_param(params, "depth")?, 2);
+            let kinds: Option<Vec<EntityKind>> = str_param(params, "entity_type_filter")?
+                .map(|f| {
+                    f.split(',')
+                        .filter_map(parse_entity_kind)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|v| !v.is_empty());
+            let layers: Option<Vec<Layer>> = str_param(params, "layer_filter")?
+                .map(|f| f.split(',').filter_map(Layer::parse).collect::<Vec<_>>())
+                .filter(|v| !v.is_empty());
+            let tags: Option<Vec<String>> = str_param(params, "tag_filter")?
+                .map(|f| {
+                    f.split(',')
+                        .filter_map(rpg_core::graph::normalize_tag)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|v| !v.is_empty());
+            let filter = explore::NeighborFilter {
+                kinds: kinds.as_deref(),
+                layers: layers.as_deref(),
+                tags: tags.as_deref(),
+            };
+            let ids: Vec<String> = match params.get("entity_ids") {
+                Some(toml::Value::Array(items)) => items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect(),
+                _ => vec![entity_id],
+            };
+            let compact = str_param(params, "format")? == Some("compact");
+
+            let mut outputs = Vec::new();
+            for id in &ids {
+                let Some(tree) =
+                    explore::explore_filtered(graph, id, direction, depth, edge_filter, filter)
+                else {
+                    outputs.push(format!("Entity not found: {}", id));
+                    continue;
+                };
+                let formatted = if compact {
+                    explore::format_compact(&tree)
+                } else {
+                    explore::format_tree(&tree, 0)
+                };
+                let lines: Vec<&str> = formatted.lines().collect();
+                match max_results {
+                    Some(max) if lines.len() > max => outputs.push(format!(
+                        "{}\n... ({} more nodes, truncated. Use max_results to increase.)",
+                        lines[..max].join("\n"),
+                        lines.len() - max
+                    )),
+                    _ => outputs.push(formatted),
+                }
+            }
+            Ok(outputs.join("\n---\n"))
+        }
+        "impact_radius" => {
+            let direction = parse_direction(str_param(params, "direction")?, Direction::Upstream);
+            let depth = depth_param(int_param(params, "max_depth")?, 3);
+            let options = crate::impact::ImpactOptions {
+                max_results: max_results.or(Some(100)),
+                stop_at_async_boundary: bool_param(params, "stop_at_async_boundary")?
+                    .unwrap_or(false),
+                ..Default::default()
+            };
+            let result = crate::impact::compute_impact_radius_with(
+                graph,
+                &entity_id,
+                direction,
+                depth,
+                edge_filter,
+                &options,
+            )
+            .with_context(|| format!("Entity not found: {}", entity_id))?;
+            Ok(crate::toon::format_impact_radius(&result))
+        }
+        other => bail!(
+            "Cannot run '{}' queries (supported: {})",
+            other,
+            SAVABLE_TOOLS.join(", ")
+        ),
+    }
+}
+
+fn str_param<'a>(params: &'a toml::Table, key: &str) -> Result<Option<&'a str>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(toml::Value::String(s)) => Ok(Some(s.as_str())),
+        Some(other) => bail!("'{}' must be a string, got {}", key, other),
+    }
+}
+
+fn int_param(params: &toml::Table, key: &str) -> Result<Option<i64>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(toml::Value::Integer(n)) => Ok(Some(*n)),
+        Some(other) => bail!("'{}' must be an integer, got {}", key, other),
+    }
+}
+
+fn bool_param(params: &toml::Table, key: &str) -> Result<Option<bool>> {
+    match params.get(key) {
+        None => Ok(None),
+        Some(toml::Value::Boolean(b)) => Ok(Some(*b)),
+        Some(other) => bail!("'{}' must be a boolean, got {}", key, other),
+    }
+}
+
+/// `-1` means unlimited; missing or invalid values fall back to `default`.
+fn depth_param(value: Option<i64>, default: usize) -> usize {
+    match value {
+        Some(-1) => usize::MAX,
+        Some(d) if d >= 0 => usize::try_from(d).unwrap_or(default),
+        _ => default,
+    }
+}
+
+fn parse_direction(value: Option<&str>, default: Direction) -> Direction {
+    match value {
+        Some("upstream" | "up") => Direction::Upstream,
+        Some("downstream" | "down") => Direction::Downstream,
+        Some("both") => Direction::Both,
+        _ => default,
+    }
+}
+
+fn parse_edge_kind(value: &str) -> Option<EdgeKind> {
+    serde_json::from_value(serde_json::Value::String(value.trim().to_string())).ok()
+}
+
+fn parse_entity_kind(value: &str) -> Option<EntityKind> {
+    match value.trim().to_lowercase().as_str() {
+        "file" | "directory" => Some(EntityKind::Module),
+        other => serde_json::from_value(serde_json::Value::String(other.to_string())).ok(),
+    }
+}
@@ -1,10 +1,24 @@
 //! SearchNode: intent-based code entity discovery.
 
-use rpg_core::graph::{Entity, EntityKind, RPGraph};
-use std::collections::{HashMap, HashSet};
+use crate::vocab::{Vocabulary, feature_terms};
+use rpg_core::graph::{EdgeKind, Entity, EntityKind, Layer, RPGraph};
+use rpg_core::scope::ScopeError;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Instant;
+
+/// Maximum number of files listed in [`SearchFacets::files`].
+pub const MAX_FACET_FILES: usize = 5;
+
+/// Vocabulary neighbors added per query term in feature search.
+pub const EXPANSION_TOP_K: usize = 3;
+
+/// Weight of a feature matched only through an expansion term, relative to a
+/// full direct match, before scaling by the neighbor's association.
+pub const EXPANSION_WEIGHT: f64 = 0.5;
 
 /// Search mode (matching the paper's SearchNode tool).
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchMode {
     /// Match against semantic features.
     Features,
@@ -14,8 +28,33 @@ pub enum SearchMode {
     Auto,
 }
 
+/// Order of search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchSort {
+    /// Highest relevance score first.
+    #[default]
+    Relevance,
+    /// Most frequently changed first (see `rpg-encoder churn`), relevance
+    /// breaking ties. Entities without churn come last.
+    Churn,
+    /// Closest to an entry point first (`Entity::entry_distance`), relevance
+    /// breaking ties. Unreachable entities come last.
+    EntryDistance,
+}
+
+impl SearchSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "relevance" => Some(Self::Relevance),
+            "churn" => Some(Self::Churn),
+            "entry_distance" => Some(Self::EntryDistance),
+            _ => None,
+        }
+    }
+}
+
 /// A search result with relevance score.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub entity_id: String,
     pub entity_name: String,
@@ -24,9 +63,143 @@ pub struct SearchResult {
     pub score: f64,
     pub matched_features: Vec<String>,
     pub lifted: bool,
+    /// Paradigm attributes summary (e.g., `route: GET /users`), empty if none.
+    pub attributes: String,
+    /// The entity's file changed since the graph was built, so its line range
+    /// and features may be out of date. Set by callers that know the workdir state.
+    pub stale: bool,
+    /// Vocabulary expansions that matched this entity, as `query_term→neighbor`.
+    pub expanded_via: Vec<String>,
+    /// Strongest dependency neighbors, empty unless the caller asked for them
+    /// (see [`neighbor_previews`]).
+    pub neighbors: Vec<NeighborPreview>,
+    /// Matching entities from this Module result's file, grouped under it
+    /// (see [`SearchParams::group_by_file`]).
+    pub members: Vec<SearchResult>,
+}
+
+/// Neighbor previews attached to each search result on request.
+pub const MAX_NEIGHBOR_PREVIEWS: usize = 3;
+
+/// A one-line preview of an entity's dependency neighbor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NeighborPreview {
+    pub entity_id: String,
+    /// The connecting edge kind, prefixed with `<-` when the neighbor is the
+    /// edge's source (it depends on the result).
+    pub edge: String,
+    pub kind: EntityKind,
+    /// The neighbor's first semantic feature, empty when it is not lifted.
+    pub feature: String,
+}
+
+/// Rank of an edge kind for neighbor previews; lower is stronger. Calls and
+/// type relationships say the most about an entity, imports and test
+/// coverage the least.
+fn edge_strength(kind: EdgeKind) -> u8 {
+    match kind {
+        EdgeKind::Invokes => 0,
+        EdgeKind::Inherits => 1,
+        EdgeKind::Composes => 2,
+        EdgeKind::Renders => 3,
+        EdgeKind::Dispatches => 4,
+        EdgeKind::DataFlow => 5,
+        EdgeKind::WritesState => 6,
+        EdgeKind::ReadsState => 7,
+        EdgeKind::Imports => 8,
+        EdgeKind::Tests => 9,
+        EdgeKind::Contains => 10,
+    }
+}
+
+/// Up to `limit` of the entity's strongest dependency neighbors, read from the
+/// edge index. Ranked by edge kind, then dependencies before dependents, then
+/// ID; each neighbor appears once, through its strongest edge.
+pub fn neighbor_previews(graph: &RPGraph, entity_id: &str, limit: usize) -> Vec<NeighborPreview> {
+    let mut best: HashMap<&str, (u8, bool, EdgeKind)> = HashMap::new();
+    for edge in graph.edges_for(entity_id) {
+        if edge.kind == EdgeKind::Contains || edge.source == edge.target {
+            continue;
+        }
+        let incoming = edge.target == entity_id;
+        let neighbor = if incoming { &edge.source } else { &edge.target };
+        let rank = (edge_strength(edge.kind), incoming, edge.kind);
+        best.entry(neighbor.as_str())
+            .and_modify(|current| {
+                if (rank.0, rank.1) < (current.0, current.1) {
+                    *current = rank;
+                }
+            })
+            .or_insert(rank);
+    }
+
+    let mut ranked: Vec<_> = best.into_iter().collect();
+    ranked.sort_by(|a, b| (a.1.0, a.1.1, a.0).cmp(&(b.1.0, b.1.1, b.0)));
+    ranked
+        .into_iter()
+        .filter_map(|(id, (_, incoming, kind))| {
+            let entity = graph.entities.get(id)?;
+            let edge = format!("{:?}", kind).to_lowercase();
+            Some(NeighborPreview {
+                entity_id: id.to_string(),
+                edge: if incoming {
+                    format!("<-{}", edge)
+                } else {
+                    edge
+                },
+                kind: entity.kind,
+                feature: entity
+                    .semantic_features
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// One facet value and how many matches carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Aggregate counts over the full match set, computed before truncation.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchFacets {
+    /// Number of entities matching the query and filters.
+    pub total_matches: usize,
+    /// Matches per entity kind, most common first.
+    pub kinds: Vec<FacetCount>,
+    /// Matches per top-level hierarchy area, most common first.
+    pub areas: Vec<FacetCount>,
+    /// Files with the most matches (at most [`MAX_FACET_FILES`]).
+    pub files: Vec<FacetCount>,
+}
+
+/// Truncated results plus facets over the untruncated match set.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    pub facets: SearchFacets,
+    /// Query term → vocabulary neighbors feature search also looked for.
+    pub expansions: BTreeMap<String, Vec<String>>,
+    /// Scoring stopped at [`SearchParams::deadline`], so only part of the
+    /// candidates were considered.
+    pub budget_exhausted: bool,
+}
+
+/// One vocabulary neighbor added to a feature search.
+struct Expansion {
+    from: String,
+    term: String,
+    weight: f64,
 }
 
 /// Full search parameters matching the paper's SearchNode spec.
+#[allow(clippy::struct_excessive_bools)]
 pub struct SearchParams<'a> {
     pub query: &'a str,
     pub mode: SearchMode,
@@ -38,21 +211,44 @@ pub struct SearchParams<'a> {
     pub file_pattern: Option<&'a str>,
     /// Filter results to specific entity kinds (function, class, method).
     pub entity_type_filter: Option<Vec<EntityKind>>,
+    /// Filter results to entities tagged with one of these layers.
+    pub layer_filter: Option<Vec<Layer>>,
     /// Pre-computed embedding scores (entity_id → cosine score) for hybrid blending.
     /// When provided, features-mode search uses rank-based hybrid scoring.
     pub embedding_scores: Option<&'a std::collections::HashMap<String, f64>>,
     /// Diff-aware search context for proximity-based ranking boost.
     pub diff_context: Option<&'a crate::diff::DiffContext>,
+    /// Restrict candidates to these entity IDs (e.g., entities touched by a diff).
+    pub changed_entities: Option<&'a HashSet<String>>,
+    /// Feature vocabulary for query expansion in feature search.
+    pub vocabulary: Option<&'a Vocabulary>,
+    /// Result order. Non-relevance orders rank the full match set before truncating.
+    pub sort: SearchSort,
+    /// Include entities tagged `fixture` (test fixture files), which are
+    /// skipped by default.
+    pub include_fixtures: bool,
+    /// Leave out test code ([`rpg_core::graph::Entity::is_test`]).
+    pub exclude_tests: bool,
+    /// Keep only `async` functions ([`rpg_core::graph::Entity::is_async`]).
+    pub async_only: bool,
+    /// Stop scoring candidates once this passes ([`crate::budget`]).
+    pub deadline: Option<Instant>,
+    /// When a file's Module entity and some of its members both match, list
+    /// the members under the Module, or drop the Module when a member
+    /// outscores it, instead of spending a result slot on each.
+    pub group_by_file: bool,
 }
 
 /// Search the RPG for entities matching a query with a configurable result limit.
+///
+/// Fails when `scope` is a malformed scope expression.
 pub fn search(
     graph: &RPGraph,
     query: &str,
     mode: SearchMode,
     scope: Option<&str>,
     limit: usize,
-) -> Vec<SearchResult> {
+) -> Result<Vec<SearchResult>, ScopeError> {
     search_with_params(
         graph,
         &SearchParams {
@@ -63,12 +259,29 @@ pub fn search(
             line_nums: None,
             file_pattern: None,
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores: None,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: mode == SearchMode::Features,
         },
     )
 }
 
+/// Highest score first; equal scores in entity ID order, so identical
+/// queries return identically ordered results.
+fn by_score_then_id(a: &SearchResult, b: &SearchResult) -> std::cmp::Ordering {
+    b.score
+        .total_cmp(&a.score)
+        .then_with(|| a.entity_id.cmp(&b.entity_id))
+}
+
 /// Apply diff-aware proximity boost to search results if context is provided.
 fn apply_diff_boost(
     mut results: Vec<SearchResult>,
@@ -92,21 +305,31 @@ fn apply_diff_boost(
         }
 
         // Re-sort by boosted scores
-        results.sort_by(|a, b| {
-            b.score
-                .partial_cmp(&a.score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        results.sort_by(by_score_then_id);
     }
     results
 }
 
 /// Search with full parameters (paper-complete SearchNode).
-pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchResult> {
+///
+/// Fails when `params.scope` is a malformed scope expression.
+pub fn search_with_params(
+    graph: &RPGraph,
+    params: &SearchParams,
+) -> Result<Vec<SearchResult>, ScopeError> {
+    Ok(search_with_facets(graph, params)?.results)
+}
+
+/// Like [`search_with_params`], but also returns facet counts (kind, area, file)
+/// over every match, so callers can see the distribution the limit hides.
+pub fn search_with_facets(
+    graph: &RPGraph,
+    params: &SearchParams,
+) -> Result<SearchOutcome, ScopeError> {
     let query_lower = params.query.to_lowercase();
     let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
     if query_terms.is_empty() {
-        return Vec::new();
+        return Ok(SearchOutcome::default());
     }
 
     // Build file pattern matcher if specified
@@ -116,7 +339,7 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
 
     let entities: Box<dyn Iterator<Item = (&String, &Entity)>> = if let Some(scope) = params.scope {
         let scoped_ids: HashSet<String> =
-            collect_scoped_entities(graph, scope).into_iter().collect();
+            collect_scoped_entities(graph, scope)?.into_iter().collect();
         Box::new(
             graph
                 .entities
@@ -148,6 +371,28 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
             {
                 return false;
             }
+            // Layer filter
+            if let Some(ref layers) = params.layer_filter
+                && !entity.layer.is_some_and(|l| layers.contains(&l))
+            {
+                return false;
+            }
+            // Fixture files only on request
+            if !params.include_fixtures && entity.is_fixture() {
+                return false;
+            }
+            if params.exclude_tests && entity.is_test() {
+                return false;
+            }
+            if params.async_only && !entity.is_async {
+                return false;
+            }
+            // Changed-entity filter
+            if let Some(changed) = params.changed_entities
+                && !changed.contains(&entity.id)
+            {
+                return false;
+            }
             true
         })
         .collect();
@@ -158,16 +403,30 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
 
     // When diff-aware boosting is requested, expand search limit to ensure changed
     // entities outside the normal top-N have a chance to be boosted into results.
-    // Apply boost before final truncation.
-    let search_limit = if params.diff_context.is_some() {
+    // Apply boost before final truncation. Grouping frees slots, so it ranks
+    // the full match set too.
+    let search_limit = if params.sort != SearchSort::Relevance || params.group_by_file {
+        entities.len()
+    } else if params.diff_context.is_some() {
         params.limit * 10
     } else {
         params.limit
     };
 
+    let expansions = params
+        .vocabulary
+        .map(|vocab| query_expansions(vocab, &query_terms))
+        .unwrap_or_default();
+
+    // Score every candidate, record the full match set for facets, then truncate
+    // exactly where the per-mode limits used to apply.
+    let mut matched_ids: HashSet<String> = HashSet::new();
     let mut results = match params.mode {
         SearchMode::Features => {
-            let lexical = search_features(&entities, &query_terms, search_limit);
+            let mut lexical =
+                search_features(&entities, &query_terms, &expansions, params.deadline);
+            matched_ids.extend(lexical.iter().map(|r| r.entity_id.clone()));
+            lexical.truncate(search_limit);
             maybe_hybrid_rerank(
                 graph,
                 &candidate_ids,
@@ -176,11 +435,21 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
                 search_limit,
             )
         }
-        SearchMode::Snippets => search_snippets(&entities, &query_terms, search_limit),
+        SearchMode::Snippets => {
+            let mut snippets = search_snippets(&entities, &query_terms, params.deadline);
+            matched_ids.extend(snippets.iter().map(|r| r.entity_id.clone()));
+            snippets.truncate(search_limit);
+            snippets
+        }
         SearchMode::Auto => {
             // Merge features + snippets.
-            let feat_results = search_features(&entities, &query_terms, search_limit * 2);
-            let snip_results = search_snippets(&entities, &query_terms, search_limit * 2);
+            let mut feat_results =
+                search_features(&entities, &query_terms, &expansions, params.deadline);
+            let mut snip_results = search_snippets(&entities, &query_terms, params.deadline);
+            matched_ids.extend(feat_results.iter().map(|r| r.entity_id.clone()));
+            matched_ids.extend(snip_results.iter().map(|r| r.entity_id.clone()));
+            feat_results.truncate(search_limit * 2);
+            snip_results.truncate(search_limit * 2);
 
             let mut score_map: std::collections::HashMap<String, SearchResult> =
                 std::collections::HashMap::new();
@@ -205,11 +474,7 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
             }
 
             let mut merged: Vec<SearchResult> = score_map.into_values().collect();
-            merged.sort_by(|a, b| {
-                b.score
-                    .partial_cmp(&a.score)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+            merged.sort_by(by_score_then_id);
             merged.truncate(search_limit);
 
             // Apply hybrid reranking if embeddings available
@@ -223,10 +488,160 @@ pub fn search_with_params(graph: &RPGraph, params: &SearchParams) -> Vec<SearchR
         }
     };
 
+    let budget_exhausted = crate::budget::expired(params.deadline);
+
+    // Semantic-only hits from hybrid reranking are matches too.
+    matched_ids.extend(results.iter().map(|r| r.entity_id.clone()));
+    let facets = compute_facets(graph, &matched_ids);
+
     // Apply diff-aware proximity boost if provided, then truncate to requested limit
     results = apply_diff_boost(results, params.diff_context);
+    // Stable sorts: equal keys keep relevance order.
+    match params.sort {
+        SearchSort::Relevance => {}
+        SearchSort::Churn => results.sort_by_key(|r| {
+            std::cmp::Reverse(graph.entities.get(&r.entity_id).and_then(|e| e.churn))
+        }),
+        SearchSort::EntryDistance => results.sort_by_key(|r| {
+            graph
+                .entities
+                .get(&r.entity_id)
+                .and_then(|e| e.entry_distance)
+                .unwrap_or(u32::MAX)
+        }),
+    }
+    if params.group_by_file {
+        results = group_by_file(graph, results);
+    }
     results.truncate(params.limit);
-    results
+
+    // Report only expansions that matched something.
+    let mut used: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for expansion in &expansions {
+        let via = format!("{}→{}", expansion.from, expansion.term);
+        if results.iter().any(|r| r.expanded_via.contains(&via)) {
+            used.entry(expansion.from.clone())
+                .or_default()
+                .push(expansion.term.clone());
+        }
+    }
+    Ok(SearchOutcome {
+        results,
+        facets,
+        expansions: used,
+        budget_exhausted,
+    })
+}
+
+/// Fold results into the Module result of their file when it scores at
+/// least as well as all of them; drop the Module when one of them scores
+/// higher. Order is otherwise kept, and members keep their own scores.
+fn group_by_file(graph: &RPGraph, results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let is_module = |r: &SearchResult| {
+        graph
+            .entities
+            .get(&r.entity_id)
+            .is_some_and(|e| e.kind == EntityKind::Module)
+    };
+    let module_scores: HashMap<String, f64> = results
+        .iter()
+        .filter(|r| is_module(r))
+        .map(|r| (r.file.clone(), r.score))
+        .collect();
+    let mut best_member: HashMap<String, f64> = HashMap::new();
+    for r in results.iter().filter(|r| !is_module(r)) {
+        if module_scores.contains_key(&r.file) {
+            let best = best_member.entry(r.file.clone()).or_insert(r.score);
+            *best = best.max(r.score);
+        }
+    }
+    // Files whose Module result absorbs its members.
+    let grouping: HashSet<&String> = best_member
+        .iter()
+        .filter(|(file, best)| **best <= module_scores[*file])
+        .map(|(file, _)| file)
+        .collect();
+
+    let mut members: HashMap<String, Vec<SearchResult>> = HashMap::new();
+    let mut kept = Vec::with_capacity(results.len());
+    for r in results {
+        if is_module(&r) {
+            if !best_member.contains_key(&r.file) || grouping.contains(&r.file) {
+                kept.push(r);
+            }
+        } else if grouping.contains(&r.file) {
+            members.entry(r.file.clone()).or_default().push(r);
+        } else {
+            kept.push(r);
+        }
+    }
+    for r in &mut kept {
+        if is_module(r)
+            && let Some(grouped) = members.remove(&r.file)
+        {
+            r.members = grouped;
+        }
+    }
+    kept
+}
+
+/// Top vocabulary neighbors for each query term, skipping terms the query
+/// already has. A neighbor shared by several query terms is added once.
+fn query_expansions(vocab: &Vocabulary, query_terms: &[&str]) -> Vec<Expansion> {
+    let query: HashSet<String> = query_terms.iter().flat_map(|t| feature_terms(t)).collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut expansions = Vec::new();
+    for from in query_terms.iter().flat_map(|t| feature_terms(t)) {
+        for neighbor in vocab.expand(&from, EXPANSION_TOP_K) {
+            if query.contains(&neighbor.term) || !seen.insert(neighbor.term.clone()) {
+                continue;
+            }
+            expansions.push(Expansion {
+                from: from.clone(),
+                term: neighbor.term.clone(),
+                weight: EXPANSION_WEIGHT * neighbor.association,
+            });
+        }
+    }
+    expansions
+}
+
+/// Count matches per kind, top-level area, and file.
+fn compute_facets(graph: &RPGraph, matched_ids: &HashSet<String>) -> SearchFacets {
+    let mut kinds: HashMap<String, usize> = HashMap::new();
+    let mut areas: HashMap<String, usize> = HashMap::new();
+    let mut files: HashMap<String, usize> = HashMap::new();
+    for entity in matched_ids.iter().filter_map(|id| graph.entities.get(id)) {
+        *kinds
+            .entry(format!("{:?}", entity.kind).to_lowercase())
+            .or_default() += 1;
+        let area = entity
+            .hierarchy_path
+            .split('/')
+            .next()
+            .filter(|s| !s.is_empty())
+            .unwrap_or("(unassigned)");
+        *areas.entry(area.to_string()).or_default() += 1;
+        *files.entry(entity.file.display().to_string()).or_default() += 1;
+    }
+    let mut files = sorted_facet(files);
+    files.truncate(MAX_FACET_FILES);
+    SearchFacets {
+        total_matches: matched_ids.len(),
+        kinds: sorted_facet(kinds),
+        areas: sorted_facet(areas),
+        files,
+    }
+}
+
+/// Most common first; ties broken alphabetically for stable output.
+fn sorted_facet(counts: HashMap<String, usize>) -> Vec<FacetCount> {
+    let mut facet: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    facet.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    facet
 }
 
 /// Compute Jaccard similarity between two token sets.
@@ -348,18 +763,36 @@ fn multi_signal_score(
 fn search_features(
     entities: &[(&String, &Entity)],
     query_terms: &[&str],
-    result_limit: usize,
+    expansions: &[Expansion],
+    deadline: Option<Instant>,
 ) -> Vec<SearchResult> {
     let idf = compute_idf(entities);
     let mut results: Vec<SearchResult> = Vec::new();
     let query_joined = query_terms.join(" ");
 
-    for (id, entity) in entities {
+    for (id, entity) in entities
+        .iter()
+        .take_while(|_| !crate::budget::expired(deadline))
+    {
         let mut score = 0.0;
         let mut matched = Vec::new();
+        let mut expanded_via: BTreeSet<String> = BTreeSet::new();
 
         for feature in &entity.semantic_features {
-            let feature_score = multi_signal_score(feature, &query_joined, query_terms, &idf);
+            let mut feature_score = multi_signal_score(feature, &query_joined, query_terms, &idf);
+
+            // The strongest expansion term in the feature adds at reduced weight.
+            if !expansions.is_empty() {
+                let terms: HashSet<String> = feature_terms(feature).collect();
+                let best = expansions
+                    .iter()
+                    .filter(|e| terms.contains(&e.term))
+                    .max_by(|a, b| a.weight.total_cmp(&b.weight));
+                if let Some(expansion) = best {
+                    feature_score += expansion.weight;
+                    expanded_via.insert(format!("{}→{}", expansion.from, expansion.term));
+                }
+            }
 
             if feature_score > 0.05 {
                 score += feature_score;
@@ -376,30 +809,33 @@ fn search_features(
                 score,
                 matched_features: matched,
                 lifted: !entity.semantic_features.is_empty(),
+                attributes: entity.attribute_summary(),
+                stale: false,
+                expanded_via: expanded_via.into_iter().collect(),
+                neighbors: Vec::new(),
+                members: Vec::new(),
             });
         }
     }
 
-    results.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    results.truncate(result_limit);
+    results.sort_by(by_score_then_id);
     results
 }
 
 fn search_snippets(
     entities: &[(&String, &Entity)],
     query_terms: &[&str],
-    result_limit: usize,
+    deadline: Option<Instant>,
 ) -> Vec<SearchResult> {
     // Snippets match against names/paths, not features — use empty IDF (equal weights)
     let empty_idf = std::collections::HashMap::new();
     let mut results: Vec<SearchResult> = Vec::new();
     let query_joined = query_terms.join(" ");
 
-    for (id, entity) in entities {
+    for (id, entity) in entities
+        .iter()
+        .take_while(|_| !crate::budget::expired(deadline))
+    {
         // Name: high weight (2x)
         let name_score =
             multi_signal_score(&entity.name, &query_joined, query_terms, &empty_idf) * 2.0;
@@ -427,16 +863,16 @@ fn search_snippets(
                 score,
                 matched_features: Vec::new(),
                 lifted: !entity.semantic_features.is_empty(),
+                attributes: entity.attribute_summary(),
+                stale: false,
+                expanded_via: Vec::new(),
+                neighbors: Vec::new(),
+                members: Vec::new(),
             });
         }
     }
 
-    results.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    results.truncate(result_limit);
+    results.sort_by(by_score_then_id);
     results
 }
 
@@ -448,7 +884,7 @@ fn rank_normalize(scores: &HashMap<String, f64>) -> HashMap<String, f64> {
     }
 
     let mut sorted: Vec<(&String, &f64)> = scores.iter().collect();
-    sorted.sort_by(|a, b| b.1.partial_cmp(a.1).unwrap_or(std::cmp::Ordering::Equal));
+    sorted.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
 
     let total = sorted.len() as f64;
     sorted
@@ -516,7 +952,7 @@ fn maybe_hybrid_rerank(
         })
         .collect();
 
-    blended.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    blended.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
     blended.truncate(limit);
 
     // Rebuild SearchResult vec in blended order, preserving matched_features etc.
@@ -533,7 +969,7 @@ fn maybe_hybrid_rerank(
                 SearchResult { score, ..r.clone() }
             } else {
                 // Semantic-only discovery — create stub from graph entity
-                let (name, file, line_start, lifted) = graph
+                let (name, file, line_start, lifted, attributes) = graph
                     .entities
                     .get(&id)
                     .map(|e| {
@@ -542,9 +978,10 @@ fn maybe_hybrid_rerank(
                             e.file.display().to_string(),
                             e.line_start,
                             !e.semantic_features.is_empty(),
+                            e.attribute_summary(),
                         )
                     })
-                    .unwrap_or_else(|| (id.clone(), String::new(), 0, false));
+                    .unwrap_or_else(|| (id.clone(), String::new(), 0, false, String::new()));
                 SearchResult {
                     entity_id: id,
                     entity_name: name,
@@ -553,6 +990,11 @@ fn maybe_hybrid_rerank(
                     score,
                     matched_features: Vec::new(),
                     lifted,
+                    attributes,
+                    stale: false,
+                    expanded_via: Vec::new(),
+                    neighbors: Vec::new(),
+                    members: Vec::new(),
                 }
             }
         })
@@ -560,10 +1002,14 @@ fn maybe_hybrid_rerank(
 }
 
 /// Collect entities from one or more hierarchy scopes.
-/// Supports comma-separated scopes per paper's `search_scopes` (list of paths).
-fn collect_scoped_entities(graph: &RPGraph, scope: &str) -> Vec<String> {
+/// Supports comma-separated scopes per paper's `search_scopes` (list of paths),
+/// or a scope expression (see [`crate::scope`]). Fails when the expression does not parse.
+fn collect_scoped_entities(graph: &RPGraph, scope: &str) -> Result<Vec<String>, ScopeError> {
     if matches!(scope.trim(), "" | ".") {
-        return graph.entities.keys().cloned().collect();
+        return Ok(graph.entities.keys().cloned().collect());
+    }
+    if crate::scope::is_expression(scope) {
+        return Ok(crate::scope::parse(scope)?.resolve(graph));
     }
 
     let scopes: Vec<&str> = scope.split(',').map(|s| s.trim()).collect();
@@ -572,7 +1018,7 @@ fn collect_scoped_entities(graph: &RPGraph, scope: &str) -> Vec<String> {
 
     for single_scope in scopes {
         if single_scope == "." {
-            return graph.entities.keys().cloned().collect();
+            return Ok(graph.entities.keys().cloned().collect());
         }
         if single_scope.is_empty() {
             continue;
@@ -583,7 +1029,7 @@ fn collect_scoped_entities(graph: &RPGraph, scope: &str) -> Vec<String> {
             }
         }
     }
-    all_ids
+    Ok(all_ids)
 }
 
 fn collect_single_scope(graph: &RPGraph, scope: &str) -> Vec<String> {
@@ -1,7 +1,7 @@
 //! Minimal connecting subgraph extraction (Steiner tree approximation).
 
 use rpg_core::graph::{DependencyEdge, EdgeKind, RPGraph};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 /// Path with edges: (nodes, edges_on_path)
 type PathWithEdges = (Vec<String>, Vec<(String, String, EdgeKind)>);
@@ -14,7 +14,7 @@ pub struct SubgraphSlice {
     /// Edges in the subgraph
     pub edges: Vec<DependencyEdge>,
     /// Optional entity metadata (if requested)
-    pub metadata: Option<HashMap<String, EntityMetadata>>,
+    pub metadata: Option<BTreeMap<String, EntityMetadata>>,
 }
 
 /// Lightweight entity metadata for subgraph output.
@@ -99,7 +99,9 @@ pub fn slice_between(
     let mut entities: Vec<String> = subgraph_nodes.into_iter().collect();
     entities.sort();
 
-    let edges: Vec<DependencyEdge> = subgraph_edges
+    let mut edges: Vec<(String, String, EdgeKind)> = subgraph_edges.into_iter().collect();
+    edges.sort();
+    let edges: Vec<DependencyEdge> = edges
         .into_iter()
         .map(|(source, target, kind)| DependencyEdge {
             source,
@@ -207,6 +209,7 @@ fn find_shortest_path_with_edges(
 mod tests {
     use super::*;
     use rpg_core::graph::{Entity, EntityDeps, EntityKind};
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::PathBuf;
 
     fn make_test_entity(id: &str, name: &str) -> Entity {
@@ -220,9 +223,22 @@ mod tests {
             parent_class: None,
             semantic_features: vec!["test feature".to_string()],
             feature_source: None,
+            lifted_at: None,
             hierarchy_path: "Test".to_string(),
             deps: EntityDeps::default(),
             signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
         }
     }
 
@@ -299,7 +299,7 @@ fn build_hot_spots(graph: &RPGraph, top_n: usize) -> Vec<HotSpot> {
             (id.as_str(), connections)
         })
         .collect();
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
 
     scored
         .into_iter()
@@ -412,6 +412,7 @@ fn trim_to_budget(result: &mut SnapshotResult, budget: usize) {
 mod tests {
     use super::*;
     use rpg_core::graph::{Entity, EntityDeps, EntityKind, GraphMetadata, HierarchyNode, RPGraph};
+    use std::collections::BTreeSet;
     use std::path::PathBuf;
 
     fn make_test_graph() -> RPGraph {
@@ -430,6 +431,17 @@ mod tests {
             semantic_hierarchy: true,
             repo_summary: None,
             paradigms: Vec::new(),
+            source_signatures: false,
+            edge_resolution: Vec::new(),
+            import_cycles: 0,
+            parse_errors: BTreeMap::new(),
+            liftable_kinds: rpg_core::graph::DEFAULT_LIFTABLE_KINDS.to_vec(),
+            unresolved_imports: None,
+            churn: None,
+            repo_fingerprint: None,
+            feature_verification: None,
+            max_node_features: rpg_core::graph::DEFAULT_MAX_NODE_FEATURES,
+            nested_repos: Vec::new(),
         };
 
         let entities = vec![
@@ -446,12 +458,25 @@ mod tests {
                     "reject expired sessions".into(),
                 ],
                 feature_source: Some("llm".into()),
+                lifted_at: None,
                 hierarchy_path: "Security/auth/validate".into(),
                 deps: EntityDeps {
                     invoked_by: vec!["src/api.rs:handle_login".into()],
                     ..Default::default()
                 },
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
             Entity {
                 id: "src/api.rs:handle_login".into(),
@@ -467,12 +492,25 @@ mod tests {
                     "return auth response".into(),
                 ],
                 feature_source: Some("llm".into()),
+                lifted_at: None,
                 hierarchy_path: "API/endpoints/auth".into(),
                 deps: EntityDeps {
                     invokes: vec!["src/auth.rs:validate_token".into()],
                     ..Default::default()
                 },
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
             Entity {
                 id: "src/db.rs:query".into(),
@@ -484,9 +522,22 @@ mod tests {
                 parent_class: None,
                 semantic_features: vec!["execute database queries".into()],
                 feature_source: Some("llm".into()),
+                lifted_at: None,
                 hierarchy_path: "Data/storage/query".into(),
                 deps: EntityDeps::default(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
             Entity {
                 id: "src/new.rs:unlifted".into(),
@@ -498,9 +549,22 @@ mod tests {
                 parent_class: None,
                 semantic_features: Vec::new(),
                 feature_source: None,
+                lifted_at: None,
                 hierarchy_path: "Unplaced".into(),
                 deps: EntityDeps::default(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
         ];
 
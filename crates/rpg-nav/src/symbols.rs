@@ -0,0 +1,221 @@
+//! Per-file document outline, shaped like LSP `DocumentSymbol`.
+//!
+//! Entities are nested by line-range containment: the file's Module entity
+//! holds everything, classes hold their methods and nested classes. Lines and
+//! ranges are 0-based as in LSP; `rpgKind`, `visibility`, `lifted`, and
+//! `entityId` are extensions an LSP client will ignore.
+
+use rpg_core::graph::{Entity, EntityKind, RPGraph};
+use rpg_core::visibility::{declaration_line, infer_visibility};
+use serde::Serialize;
+use std::path::Path;
+
+pub use rpg_core::visibility::Visibility;
+
+/// LSP `Position` (0-based line and character).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+/// LSP `Range`. Whole lines: `end` is the start of the line after the last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// One outline node.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// Semantic features on one line; absent until the entity is lifted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// LSP `SymbolKind` number.
+    pub kind: u8,
+    pub range: Range,
+    pub selection_range: Range,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DocumentSymbol>,
+    pub entity_id: String,
+    pub rpg_kind: EntityKind,
+    pub visibility: Visibility,
+    pub lifted: bool,
+}
+
+impl DocumentSymbol {
+    /// 1-based inclusive line span, as shown elsewhere in the graph.
+    pub fn lines(&self) -> (usize, usize) {
+        (self.range.start.line + 1, self.range.end.line)
+    }
+
+    /// Number of symbols in this subtree, including this one.
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(Self::count).sum::<usize>()
+    }
+
+    /// Number of lifted symbols in this subtree, including this one.
+    pub fn lifted_count(&self) -> usize {
+        usize::from(self.lifted) + self.children.iter().map(Self::lifted_count).sum::<usize>()
+    }
+}
+
+/// LSP `SymbolKind` for an entity kind.
+pub fn lsp_symbol_kind(kind: EntityKind) -> u8 {
+    const MODULE: u8 = 2;
+    const CLASS: u8 = 5;
+    const METHOD: u8 = 6;
+    const ENUM: u8 = 10;
+    const FUNCTION: u8 = 12;
+    const CONSTANT: u8 = 14;
+    const OBJECT: u8 = 19;
+    match kind {
+        EntityKind::Module => MODULE,
+        EntityKind::Class
+        | EntityKind::Controller
+        | EntityKind::Model
+        | EntityKind::Service
+        | EntityKind::Page
+        | EntityKind::Layout
+        | EntityKind::Component => CLASS,
+        EntityKind::Method => METHOD,
+        EntityKind::Store => OBJECT,
+        EntityKind::Enum => ENUM,
+        EntityKind::Constant => CONSTANT,
+        EntityKind::Function
+        | EntityKind::Hook
+        | EntityKind::Middleware
+        | EntityKind::Route
+        | EntityKind::Test
+        | EntityKind::Fixture => FUNCTION,
+    }
+}
+
+/// Outline of `file`, ordered by line. `source` is the file's text, used to
+/// read declaration lines for visibility; without it visibility falls back
+/// to naming conventions. Returns `None` if the graph has no entities for
+/// the file.
+pub fn document_symbols(
+    graph: &RPGraph,
+    file: &str,
+    source: Option<&str>,
+) -> Option<Vec<DocumentSymbol>> {
+    let normalized = file.trim_start_matches("./").replace('\\', "/");
+    let ids = graph.file_index.get(Path::new(&normalized))?;
+
+    let mut entities: Vec<&Entity> = ids.iter().filter_map(|id| graph.entities.get(id)).collect();
+    // Outer ranges before the ranges they contain; the Module wins ties.
+    entities.sort_by(|a, b| {
+        a.line_start
+            .cmp(&b.line_start)
+            .then(b.line_end.cmp(&a.line_end))
+            .then((b.kind == EntityKind::Module).cmp(&(a.kind == EntityKind::Module)))
+            .then(a.id.cmp(&b.id))
+    });
+
+    let lines: Vec<&str> = source.map(|s| s.lines().collect()).unwrap_or_default();
+    let mut roots: Vec<DocumentSymbol> = Vec::new();
+    // Open containers as (last line, symbol), innermost last.
+    let mut stack: Vec<(usize, DocumentSymbol)> = Vec::new();
+    for entity in entities {
+        while stack
+            .last()
+            .is_some_and(|(end, _)| entity.line_start > *end || entity.line_end > *end)
+        {
+            close_innermost(&mut stack, &mut roots);
+        }
+        stack.push((entity.line_end, symbol_for(entity, &lines)));
+    }
+    while !stack.is_empty() {
+        close_innermost(&mut stack, &mut roots);
+    }
+    Some(roots)
+}
+
+fn close_innermost(stack: &mut Vec<(usize, DocumentSymbol)>, roots: &mut Vec<DocumentSymbol>) {
+    if let Some((_, done)) = stack.pop() {
+        match stack.last_mut() {
+            Some((_, parent)) => parent.children.push(done),
+            None => roots.push(done),
+        }
+    }
+}
+
+fn symbol_for(entity: &Entity, lines: &[&str]) -> DocumentSymbol {
+    let range = Range {
+        start: Position {
+            line: entity.line_start.saturating_sub(1),
+            character: 0,
+        },
+        end: Position {
+            line: entity.line_end,
+            character: 0,
+        },
+    };
+    let decl = declaration_line(entity, lines);
+    let selection_range = match decl {
+        Some((line, text)) => {
+            let character = text.find(entity.name.as_str()).unwrap_or(0);
+            Range {
+                start: Position { line, character },
+                end: Position {
+                    line,
+                    character: character + entity.name.len(),
+                },
+            }
+        }
+        None => range,
+    };
+    let lifted = !entity.semantic_features.is_empty();
+    DocumentSymbol {
+        name: entity.name.clone(),
+        detail: lifted.then(|| entity.semantic_features.join(", ")),
+        kind: lsp_symbol_kind(entity.kind),
+        range,
+        selection_range,
+        children: Vec::new(),
+        entity_id: entity.id.clone(),
+        rpg_kind: entity.kind,
+        visibility: infer_visibility(entity, decl.map(|(_, text)| text)),
+        lifted,
+    }
+}
+
+/// Format an outline as indented text, one symbol per line:
+/// `kind name L<start>-<end> <visibility> [lifted|unlifted] — features`.
+pub fn format_outline(file: &str, symbols: &[DocumentSymbol]) -> String {
+    let total: usize = symbols.iter().map(DocumentSymbol::count).sum();
+    let lifted: usize = symbols.iter().map(DocumentSymbol::lifted_count).sum();
+    let mut output = format!(
+        "{} ({} symbols, {}/{} lifted)\n",
+        file, total, lifted, total
+    );
+    for symbol in symbols {
+        format_outline_inner(symbol, 0, &mut output);
+    }
+    output
+}
+
+fn format_outline_inner(symbol: &DocumentSymbol, indent: usize, output: &mut String) {
+    let (start, end) = symbol.lines();
+    output.push_str(&format!(
+        "{}{} {} L{}-{} {} [{}]",
+        "  ".repeat(indent),
+        format!("{:?}", symbol.rpg_kind).to_lowercase(),
+        symbol.name,
+        start,
+        end,
+        symbol.visibility.as_str(),
+        if symbol.lifted { "lifted" } else { "unlifted" },
+    ));
+    if let Some(detail) = &symbol.detail {
+        output.push_str(&format!(" — {}", detail));
+    }
+    output.push('\n');
+    for child in &symbol.children {
+        format_outline_inner(child, indent + 1, output);
+    }
+}
@@ -6,12 +6,16 @@
 //! TOON format: <https://github.com/toon-format/toon>
 
 use crate::context::ContextPackResult;
+use crate::explain::EntityExplanation;
 use crate::fetch::{FetchOutput, FetchResult, HierarchyFetchResult};
 use crate::impact::ImpactResult;
-use crate::search::SearchResult;
+use crate::orient::OrientResult;
+use crate::patch::PatchSummary;
+use crate::search::{FacetCount, SearchFacets, SearchResult};
 use crate::snapshot::SnapshotResult;
-use rpg_core::graph::RPGraph;
+use rpg_core::graph::{HierarchyNode, Layer, RPGraph};
 use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use toon_format::{EncodeOptions, encode};
 
 /// Get default encoding options: pipe delimiter, 2-space indent.
@@ -34,21 +38,98 @@ struct SearchResultRow {
     score: f64,
     lifted: bool,
     features: String,
+    /// Only emitted when at least one result carries paradigm attributes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes: Option<String>,
+    /// Only emitted when at least one result's file changed since the graph was built.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stale: Option<bool>,
+    /// Only emitted when vocabulary expansion matched at least one result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    via: Option<String>,
+    /// Only emitted when neighbor previews were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    neighbors: Option<Vec<NeighborRow>>,
+    /// Only emitted when some Module result has same-file members grouped under it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    members: Option<Vec<MemberRow>>,
+}
+
+#[derive(Serialize)]
+struct MemberRow {
+    entity_id: String,
+    name: String,
+    line: usize,
+    score: f64,
+    features: String,
+}
+
+#[derive(Serialize)]
+struct NeighborRow {
+    id: String,
+    edge: String,
+    kind: String,
+    feature: String,
+}
+
+#[derive(Serialize)]
+struct FacetsOutput {
+    total_matches: usize,
+    kind: String,
+    area: String,
+    file: String,
 }
 
 #[derive(Serialize)]
 struct SearchOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    facets: Option<FacetsOutput>,
+    /// Vocabulary expansions that matched, e.g. `auth→credentials, auth→token`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expanded: Option<String>,
     results: Vec<SearchResultRow>,
     lifted_count: usize,
     total_count: usize,
 }
 
 /// Format search results as TOON.
-pub fn format_search_results(results: &[SearchResult]) -> String {
-    let lifted_count = results.iter().filter(|r| r.lifted).count();
-    let total_count = results.len();
+/// Render `value=count` pairs, e.g. `function=12, class=3`.
+fn format_facet(facet: &[FacetCount]) -> String {
+    facet
+        .iter()
+        .map(|f| format!("{}={}", f.value, f.count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Format search results; `facets`, when given, are rendered as a block at the top.
+/// Members grouped under a Module result are listed beneath it with their own scores.
+pub fn format_search_results(results: &[SearchResult], facets: Option<&SearchFacets>) -> String {
+    let all = || {
+        results
+            .iter()
+            .chain(results.iter().flat_map(|r| &r.members))
+    };
+    let lifted_count = all().filter(|r| r.lifted).count();
+    let total_count = all().count();
+    let has_members = results.iter().any(|r| !r.members.is_empty());
+    let has_attributes = results.iter().any(|r| !r.attributes.is_empty());
+    let has_stale = results.iter().any(|r| r.stale);
+    let expanded: BTreeSet<&str> = results
+        .iter()
+        .flat_map(|r| r.expanded_via.iter().map(String::as_str))
+        .collect();
+    let has_neighbors = results.iter().any(|r| !r.neighbors.is_empty());
 
     let output = SearchOutput {
+        facets: facets.map(|f| FacetsOutput {
+            total_matches: f.total_matches,
+            kind: format_facet(&f.kinds),
+            area: format_facet(&f.areas),
+            file: format_facet(&f.files),
+        }),
+        expanded: (!expanded.is_empty())
+            .then(|| expanded.iter().copied().collect::<Vec<_>>().join(", ")),
         results: results
             .iter()
             .map(|r| SearchResultRow {
@@ -59,6 +140,32 @@ pub fn format_search_results(results: &[SearchResult]) -> String {
                 score: clean_score(r.score),
                 lifted: r.lifted,
                 features: r.matched_features.join(", "),
+                attributes: has_attributes.then(|| r.attributes.clone()),
+                stale: has_stale.then_some(r.stale),
+                via: (!expanded.is_empty()).then(|| r.expanded_via.join(", ")),
+                neighbors: has_neighbors.then(|| {
+                    r.neighbors
+                        .iter()
+                        .map(|n| NeighborRow {
+                            id: n.entity_id.clone(),
+                            edge: n.edge.clone(),
+                            kind: format!("{:?}", n.kind).to_lowercase(),
+                            feature: n.feature.clone(),
+                        })
+                        .collect()
+                }),
+                members: has_members.then(|| {
+                    r.members
+                        .iter()
+                        .map(|m| MemberRow {
+                            entity_id: m.entity_id.clone(),
+                            name: m.entity_name.clone(),
+                            line: m.line_start,
+                            score: clean_score(m.score),
+                            features: m.matched_features.join(", "),
+                        })
+                        .collect()
+                }),
             })
             .collect(),
         lifted_count,
@@ -85,10 +192,25 @@ pub fn format_search_results(results: &[SearchResult]) -> String {
 struct FetchEntityOutput {
     name: String,
     kind: String,
+    /// `async`, `generator`, `unsafe`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    modifiers: Vec<&'static str>,
     file: String,
     lines: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    attributes: BTreeMap<String, String>,
     #[serde(skip_serializing_if = "String::is_empty")]
     hierarchy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layer: Option<&'static str>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<String>,
+    /// `<commits> commits, <lines> lines` from the last churn analysis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    churn: Option<String>,
+    /// Invokes/Dispatches hops from the nearest entry point.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_distance: Option<u32>,
     lifted: bool,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     features: Vec<String>,
@@ -126,12 +248,34 @@ struct FetchEntityOutput {
     data_flows_from: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     signature: Option<String>,
+    /// The author's docstring or doc comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     siblings: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    source_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source: Option<String>,
 }
 
+/// Source lines kept beside a `source_ref` when the full text is elided.
+pub const SNIPPET_PREVIEW_LINES: usize = 3;
+
+/// Content address of a source snippet: the blake3 hex digest of its text.
+pub fn snippet_hash(source: &str) -> String {
+    blake3::hash(source.as_bytes()).to_hex().to_string()
+}
+
+/// How a fetch result's source block is tied to its content hash.
+#[derive(Debug, Clone, Copy)]
+pub struct SnippetRef<'a> {
+    /// [`snippet_hash`] of the entity's full source.
+    pub hash: &'a str,
+    /// Replace the source with its first [`SNIPPET_PREVIEW_LINES`] lines.
+    pub elide: bool,
+}
+
 /// Field projection options for fetch output.
 #[derive(Debug)]
 pub struct FetchProjection {
@@ -139,7 +283,7 @@ pub struct FetchProjection {
     pub source_max_lines: Option<usize>,
 }
 
-const VALID_FETCH_FIELDS: &[&str] = &["features", "source", "deps", "hierarchy"];
+const VALID_FETCH_FIELDS: &[&str] = &["features", "source", "skeleton", "deps", "hierarchy", "doc"];
 
 impl FetchProjection {
     /// Parse a comma-separated fields string into a projection.
@@ -177,50 +321,92 @@ impl FetchProjection {
         })
     }
 
-    fn include(&self, field: &str) -> bool {
+    /// Whether `field` is part of the projection. `skeleton` implies `source`.
+    pub fn include(&self, field: &str) -> bool {
         match &self.fields {
             None => true,
-            Some(fields) => fields.iter().any(|f| f == field),
+            Some(fields) => fields
+                .iter()
+                .any(|f| f == field || (field == "source" && f == "skeleton")),
         }
     }
+
+    /// Whether the source block should be the skeleton (bodies elided)
+    /// rather than the full text. Only when `skeleton` is listed explicitly.
+    pub fn skeleton(&self) -> bool {
+        self.fields
+            .as_ref()
+            .is_some_and(|fields| fields.iter().any(|f| f == "skeleton"))
+    }
 }
 
 /// Format a fetch result as TOON with optional field projection.
 pub fn format_fetch_result_projected(result: &FetchResult, projection: &FetchProjection) -> String {
+    format_fetch_result_with_snippet(result, projection, None)
+}
+
+/// Like [`format_fetch_result_projected`], but with a `source_ref` line
+/// carrying the source's content hash. When `snippet.elide` is set, only the
+/// first [`SNIPPET_PREVIEW_LINES`] lines of source follow it.
+pub fn format_fetch_result_with_snippet(
+    result: &FetchResult,
+    projection: &FetchProjection,
+    snippet: Option<SnippetRef<'_>>,
+) -> String {
     let entity = &result.entity;
     let include_features = projection.include("features");
     let include_source = projection.include("source");
     let include_deps = projection.include("deps");
     let include_hierarchy = projection.include("hierarchy");
 
-    let source = if include_source {
-        match (&result.source_code, projection.source_max_lines) {
-            (Some(src), Some(max)) => {
-                let lines: Vec<&str> = src.lines().collect();
-                if lines.len() > max {
-                    let mut truncated = lines[..max].join("\n");
-                    truncated.push_str(&format!("\n// ... ({} more lines)", lines.len() - max));
-                    Some(truncated)
-                } else {
-                    Some(src.clone())
+    let snippet = snippet.filter(|_| include_source && result.source_code.is_some());
+    let source =
+        if let (Some(SnippetRef { elide: true, .. }), Some(src)) = (snippet, &result.source_code) {
+            let lines: Vec<&str> = src.lines().collect();
+            let mut preview = lines[..lines.len().min(SNIPPET_PREVIEW_LINES)].join("\n");
+            if lines.len() > SNIPPET_PREVIEW_LINES {
+                preview.push_str(&format!(
+                    "\n// ... ({} more lines, get_snippet source_ref for the full text)",
+                    lines.len() - SNIPPET_PREVIEW_LINES
+                ));
+            }
+            Some(preview)
+        } else if include_source {
+            match (&result.source_code, projection.source_max_lines) {
+                (Some(src), Some(max)) => {
+                    let lines: Vec<&str> = src.lines().collect();
+                    if lines.len() > max {
+                        let mut truncated = lines[..max].join("\n");
+                        truncated.push_str(&format!("\n// ... ({} more lines)", lines.len() - max));
+                        Some(truncated)
+                    } else {
+                        Some(src.clone())
+                    }
                 }
+                (src, _) => src.clone(),
             }
-            (src, _) => src.clone(),
-        }
-    } else {
-        None
-    };
+        } else {
+            None
+        };
 
     let output = FetchEntityOutput {
         name: entity.name.clone(),
         kind: format!("{:?}", entity.kind).to_lowercase(),
+        modifiers: entity.modifiers(),
         file: entity.file.display().to_string(),
         lines: format!("{}-{}", entity.line_start, entity.line_end),
+        attributes: entity.attributes.clone(),
         hierarchy: if include_hierarchy {
             entity.hierarchy_path.clone()
         } else {
             String::new()
         },
+        layer: entity.layer.map(Layer::as_str),
+        tags: entity.tags.clone(),
+        churn: entity
+            .churn
+            .map(|c| format!("{} commits, {} lines", c.commits, c.lines)),
+        entry_distance: entity.entry_distance,
         lifted: !entity.semantic_features.is_empty(),
         features: if include_features {
             entity.semantic_features.clone()
@@ -307,12 +493,17 @@ pub fn format_fetch_result_projected(result: &FetchResult, projection: &FetchPro
         } else {
             Vec::new()
         },
-        signature: entity.signature.as_ref().map(format_signature),
+        signature: entity.signature.as_ref().map(ToString::to_string),
+        doc: entity
+            .doc_comment
+            .clone()
+            .filter(|_| projection.include("doc")),
         siblings: if include_hierarchy {
             result.hierarchy_context.clone()
         } else {
             Vec::new()
         },
+        source_ref: snippet.map(|s| s.hash.to_string()),
         source,
     };
 
@@ -334,9 +525,17 @@ pub fn format_fetch_result(result: &FetchResult) -> String {
     let output = FetchEntityOutput {
         name: entity.name.clone(),
         kind: format!("{:?}", entity.kind).to_lowercase(),
+        modifiers: entity.modifiers(),
         file: entity.file.display().to_string(),
         lines: format!("{}-{}", entity.line_start, entity.line_end),
+        attributes: entity.attributes.clone(),
         hierarchy: entity.hierarchy_path.clone(),
+        layer: entity.layer.map(Layer::as_str),
+        tags: entity.tags.clone(),
+        churn: entity
+            .churn
+            .map(|c| format!("{} commits, {} lines", c.commits, c.lines)),
+        entry_distance: entity.entry_distance,
         lifted: !entity.semantic_features.is_empty(),
         features: entity.semantic_features.clone(),
         invokes: entity.deps.invokes.clone(),
@@ -355,8 +554,10 @@ pub fn format_fetch_result(result: &FetchResult) -> String {
         dispatched_by: entity.deps.dispatched_by.clone(),
         data_flows_to: entity.deps.data_flows_to.clone(),
         data_flows_from: entity.deps.data_flows_from.clone(),
-        signature: entity.signature.as_ref().map(format_signature),
+        signature: entity.signature.as_ref().map(ToString::to_string),
+        doc: entity.doc_comment.clone(),
         siblings: result.hierarchy_context.clone(),
+        source_ref: None,
         source: result.source_code.clone(),
     };
 
@@ -371,23 +572,6 @@ pub fn format_fetch_result(result: &FetchResult) -> String {
     toon
 }
 
-/// Format a Signature into a human-readable string like `(x: i32, y: String) -> bool`.
-fn format_signature(sig: &rpg_core::graph::Signature) -> String {
-    let params: Vec<String> = sig
-        .parameters
-        .iter()
-        .map(|p| match &p.type_annotation {
-            Some(t) => format!("{}: {}", p.name, t),
-            None => p.name.clone(),
-        })
-        .collect();
-    let params_str = format!("({})", params.join(", "));
-    match &sig.return_type {
-        Some(rt) => format!("{} -> {}", params_str, rt),
-        None => params_str,
-    }
-}
-
 // ---------------------------------------------------------------------------
 // Hierarchy node fetch result
 // ---------------------------------------------------------------------------
@@ -397,6 +581,8 @@ struct HierarchyNodeOutput {
     r#type: String,
     name: String,
     id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    charter: Option<String>,
     entities: usize,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     grounded_paths: Vec<String>,
@@ -406,6 +592,18 @@ struct HierarchyNodeOutput {
     features: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     entity_ids: Vec<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    owners: String,
+}
+
+/// Top owners of a hierarchy node with their entity share, e.g. `@alice 67%, @bob 33%`.
+pub fn format_owners(node: &HierarchyNode) -> String {
+    let total = node.entity_count().max(1);
+    node.owners
+        .iter()
+        .map(|o| format!("{} {}%", o.owner, o.entities * 100 / total))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Format a hierarchy node fetch result as TOON.
@@ -416,6 +614,7 @@ pub fn format_hierarchy_fetch_result(result: &HierarchyFetchResult) -> String {
         r#type: "hierarchy_node".to_string(),
         name: node.name.clone(),
         id: node.id.clone(),
+        charter: node.charter.clone(),
         entities: result.entity_count,
         grounded_paths: node
             .grounded_paths
@@ -425,6 +624,7 @@ pub fn format_hierarchy_fetch_result(result: &HierarchyFetchResult) -> String {
         children: result.child_names.clone(),
         features: node.semantic_features.iter().take(20).cloned().collect(),
         entity_ids: node.entities.iter().take(20).cloned().collect(),
+        owners: format_owners(node),
     };
 
     encode(&output, &encode_opts()).unwrap_or_else(|_| format!("{:?}", result))
@@ -455,6 +655,33 @@ struct AreaInfo {
     name: String,
     entities: usize,
     children: Vec<String>,
+    /// Top owners by entity share; only emitted when some area has owners.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owners: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LayerInfo {
+    layer: &'static str,
+    entities: usize,
+}
+
+#[derive(Serialize)]
+struct TagInfo {
+    tag: String,
+    entities: usize,
+}
+
+#[derive(Serialize)]
+struct ParseErrorInfo {
+    file: String,
+    regions: usize,
+}
+
+#[derive(Serialize)]
+struct CharterInfo {
+    path: String,
+    charter: String,
 }
 
 #[derive(Serialize)]
@@ -479,16 +706,30 @@ struct RpgInfoOutput {
     edges: usize,
     data_flow_edges: usize,
     hierarchy_type: String,
+    /// Entities per inferred layer; omitted for graphs built without layers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    layers: Vec<LayerInfo>,
+    /// Entities per user tag; omitted when nothing is tagged.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<TagInfo>,
+    /// Files with syntax errors at the last build; entities in the broken
+    /// regions are missing.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parse_errors: Vec<ParseErrorInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     summary: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     hierarchy: Vec<AreaInfo>,
+    /// Human-written area charters, by hierarchy path.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    charters: Vec<CharterInfo>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     coverage_by_area: Vec<CoverageInfo>,
 }
 
 /// Format RPG info as TOON.
 pub fn format_rpg_info(graph: &RPGraph) -> String {
+    let has_owners = graph.hierarchy.values().any(|a| !a.owners.is_empty());
     let hierarchy: Vec<AreaInfo> = graph
         .hierarchy
         .iter()
@@ -496,9 +737,28 @@ pub fn format_rpg_info(graph: &RPGraph) -> String {
             name: name.clone(),
             entities: area.entity_count(),
             children: area.children.keys().cloned().collect(),
+            owners: has_owners.then(|| format_owners(area)),
         })
         .collect();
 
+    let mut layer_counts: BTreeMap<Layer, usize> = BTreeMap::new();
+    for layer in graph.entities.values().filter_map(|e| e.layer) {
+        *layer_counts.entry(layer).or_default() += 1;
+    }
+    let layers: Vec<LayerInfo> = layer_counts
+        .into_iter()
+        .map(|(layer, entities)| LayerInfo {
+            layer: layer.as_str(),
+            entities,
+        })
+        .collect();
+
+    let tags: Vec<TagInfo> = graph
+        .tag_counts()
+        .into_iter()
+        .map(|(tag, entities)| TagInfo { tag, entities })
+        .collect();
+
     let area_cov = graph.area_coverage();
     let coverage_by_area: Vec<CoverageInfo> = area_cov
         .iter()
@@ -544,8 +804,24 @@ pub fn format_rpg_info(graph: &RPGraph) -> String {
         } else {
             "structural".to_string()
         },
+        layers,
+        tags,
+        parse_errors: graph
+            .metadata
+            .parse_errors
+            .iter()
+            .map(|(file, regions)| ParseErrorInfo {
+                file: file.clone(),
+                regions: *regions,
+            })
+            .collect(),
         summary: graph.metadata.repo_summary.clone(),
         hierarchy,
+        charters: graph
+            .hierarchy_charters()
+            .into_iter()
+            .map(|(path, charter)| CharterInfo { path, charter })
+            .collect(),
         coverage_by_area,
     };
 
@@ -610,6 +886,12 @@ pub fn format_impact_radius(result: &ImpactResult) -> String {
                     .map(|(id, kind)| format!("{}:{}", id, format!("{:?}", kind).to_lowercase()))
                     .collect::<Vec<_>>()
                     .join(" -> ");
+                // The hop into a spawned task reads `invokes[spawn]`.
+                let path_str = if e.async_boundary {
+                    format!("{}[spawn]", path_str)
+                } else {
+                    path_str
+                };
                 ImpactEntryRow {
                     entity_id: e.entity_id.clone(),
                     name: e.name.clone(),
@@ -625,6 +907,78 @@ pub fn format_impact_radius(result: &ImpactResult) -> String {
     encode(&output, &encode_opts()).unwrap_or_else(|_| format!("{:?}", result))
 }
 
+// ---------------------------------------------------------------------------
+// Patch summary output
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct TouchedEntityRow {
+    entity_id: String,
+    kind: String,
+    hierarchy_path: String,
+    fan_in: usize,
+    signature: bool,
+    deleted: bool,
+    features: String,
+}
+
+#[derive(Serialize)]
+struct BlastRadiusRow {
+    entity_id: String,
+    file: String,
+    depth: usize,
+}
+
+#[derive(Serialize)]
+struct PatchSummaryOutput {
+    touched: Vec<TouchedEntityRow>,
+    blast_radius_total: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    blast_radius: Vec<BlastRadiusRow>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    new_files_unindexed: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    deleted_files: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unindexed_files: Vec<String>,
+}
+
+/// Format a patch summary as TOON, listing the `max_blast` nearest
+/// blast-radius entities.
+pub fn format_patch_summary(summary: &PatchSummary, max_blast: usize) -> String {
+    let output = PatchSummaryOutput {
+        touched: summary
+            .touched
+            .iter()
+            .map(|t| TouchedEntityRow {
+                entity_id: t.entity_id.clone(),
+                kind: format!("{:?}", t.kind).to_lowercase(),
+                hierarchy_path: t.hierarchy_path.clone(),
+                fan_in: t.fan_in,
+                signature: t.signature_touched,
+                deleted: t.deleted,
+                features: t.features.join("; "),
+            })
+            .collect(),
+        blast_radius_total: summary.blast_radius.len(),
+        blast_radius: summary
+            .blast_radius
+            .iter()
+            .take(max_blast)
+            .map(|e| BlastRadiusRow {
+                entity_id: e.entity_id.clone(),
+                file: e.file.clone(),
+                depth: e.depth,
+            })
+            .collect(),
+        new_files_unindexed: summary.new_files.clone(),
+        deleted_files: summary.deleted_files.clone(),
+        unindexed_files: summary.unindexed_files.clone(),
+    };
+
+    encode(&output, &encode_opts()).unwrap_or_else(|_| format!("{:?}", summary))
+}
+
 // ---------------------------------------------------------------------------
 // Context pack output
 // ---------------------------------------------------------------------------
@@ -678,7 +1032,7 @@ pub fn format_context_pack(result: &ContextPackResult) -> String {
                 file: n.file.clone(),
                 kind: n.kind.clone(),
                 features: n.features.clone(),
-                source: None,
+                source: n.source.clone(),
                 deps: String::new(),
                 relevance: 0.0,
             })
@@ -709,7 +1063,7 @@ fn clean_score(v: f64) -> f64 {
 // Health report output
 // ---------------------------------------------------------------------------
 
-use crate::health::HealthReport;
+use crate::health::{HealthIssue, HealthReport};
 
 /// Format a health report as TOON for LLM consumption.
 pub fn format_health_report(report: &HealthReport) -> String {
@@ -746,6 +1100,9 @@ pub fn format_health_report(report: &HealthReport) -> String {
         report.summary.highly_stable_count
     ));
     output.push_str(&format!("hubs: {}\n", report.summary.hub_count));
+    if let Some(count) = report.summary.unreachable_count {
+        output.push_str(&format!("unreachable_from_entry_points: {}\n", count));
+    }
 
     // Top unstable entities
     if !report.top_unstable.is_empty() {
@@ -776,6 +1133,35 @@ pub fn format_health_report(report: &HealthReport) -> String {
         }
     }
 
+    // Churn hotspots, when `rpg-encoder churn` has been run
+    if let Some(ref range) = report.churn_range {
+        output.push_str(&format!(
+            "\n## Churn Hotspots (since {}: {} commits)\n\n",
+            range.since, range.commits
+        ));
+        if report.top_churn.is_empty() {
+            output.push_str("No entity changed in this window.\n");
+        }
+        for entity in &report.top_churn {
+            let churn = entity.churn.unwrap_or_default();
+            output.push_str(&format!(
+                "- {} ({}) | commits={} lines={} | degree={}\n",
+                entity.entity_id,
+                entity.kind,
+                churn.commits,
+                churn.lines,
+                entity.in_degree + entity.out_degree
+            ));
+        }
+    }
+
+    if !report.unreachable.is_empty() {
+        output.push_str("\n## Unreachable From Entry Points\n\n");
+        for id in &report.unreachable {
+            output.push_str(&format!("- {}\n", id));
+        }
+    }
+
     // Duplication info if present
     if let Some(ref dupes) = report.duplicates {
         output.push_str("\n## Duplication Hotspots\n\n");
@@ -843,6 +1229,25 @@ pub fn format_health_report(report: &HealthReport) -> String {
             sem_dupes.len()
         ));
     }
+    let risky_churn = report
+        .top_churn
+        .iter()
+        .filter(|e| {
+            e.issues.iter().any(|i| {
+                matches!(
+                    i,
+                    HealthIssue::PotentialGodObject { .. } | HealthIssue::HubEntity { .. }
+                )
+            })
+        })
+        .count();
+    if risky_churn > 0 {
+        output.push_str(&format!(
+            "5. **Stabilize churn hotspots**: {} frequently changed entities are also hubs or god objects. \
+             Changes there ripple widely; add tests or split them first.\n",
+            risky_churn
+        ));
+    }
     if report.summary.god_object_count == 0
         && report.summary.highly_unstable_count == 0
         && report.summary.hub_count == 0
@@ -855,6 +1260,57 @@ pub fn format_health_report(report: &HealthReport) -> String {
     output
 }
 
+// ---------------------------------------------------------------------------
+// Duplicate report output
+// ---------------------------------------------------------------------------
+
+use crate::duplication::{SemanticCloneGroup, SourceCloneGroup};
+
+/// Format duplicate groups from feature-based and/or source-based detection.
+/// `None` means that method was not run.
+pub fn format_duplicates(
+    semantic: Option<&[SemanticCloneGroup]>,
+    source: Option<&[SourceCloneGroup]>,
+) -> String {
+    let mut output = String::from("# Duplicates\n");
+
+    if let Some(groups) = semantic {
+        output.push_str("\n## Feature Similarity (lifted entities)\n\n");
+        if groups.is_empty() {
+            output.push_str("No feature-based duplicates detected.\n");
+        }
+        for group in groups {
+            output.push_str(&format!(
+                "- similarity={:.1}% | shared: [{}]\n",
+                group.similarity * 100.0,
+                group.shared_features.join(", ")
+            ));
+            for (id, file) in group.entities.iter().zip(group.files.iter()) {
+                output.push_str(&format!("    {} ({})\n", id, file));
+            }
+        }
+    }
+
+    if let Some(groups) = source {
+        output.push_str("\n## Source Similarity (token shingles)\n\n");
+        if groups.is_empty() {
+            output.push_str("No source-based duplicates detected.\n");
+        }
+        for group in groups {
+            output.push_str(&format!(
+                "- similarity={:.1}% | entities={}\n",
+                group.similarity * 100.0,
+                group.entities.len()
+            ));
+            for (id, file) in group.entities.iter().zip(group.files.iter()) {
+                output.push_str(&format!("    {} ({})\n", id, file));
+            }
+        }
+    }
+
+    output
+}
+
 // ---------------------------------------------------------------------------
 // Cycle report output
 // ---------------------------------------------------------------------------
@@ -1166,6 +1622,201 @@ pub fn format_semantic_snapshot(result: &SnapshotResult) -> String {
     encode(&output, &encode_opts()).unwrap_or_else(|_| "Failed to encode snapshot".into())
 }
 
+// ---------------------------------------------------------------------------
+// Orientation output
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct OrientStatusRow {
+    entities: usize,
+    files: usize,
+    lifted: String,
+    languages: String,
+    hierarchy: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    paradigms: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
+    staleness: String,
+}
+
+#[derive(Serialize)]
+struct OrientAreaRow {
+    path: String,
+    entities: usize,
+    summary: String,
+}
+
+#[derive(Serialize)]
+struct OrientEntryRow {
+    entity_id: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct OrientFanInRow {
+    entity_id: String,
+    kind: String,
+    fan_in: usize,
+    features: String,
+}
+
+#[derive(Serialize)]
+struct OrientToolRow {
+    intent: String,
+    tools: String,
+}
+
+#[derive(Serialize)]
+struct OrientOutput {
+    status: OrientStatusRow,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    areas: Vec<OrientAreaRow>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entry_points: Vec<OrientEntryRow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_points_omitted: Option<usize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    top_fan_in: Vec<OrientFanInRow>,
+    next_tools: Vec<OrientToolRow>,
+}
+
+/// Format an orientation result as TOON.
+pub fn format_orientation(result: &OrientResult) -> String {
+    let s = &result.status;
+    let output = OrientOutput {
+        status: OrientStatusRow {
+            entities: s.entities,
+            files: s.files,
+            lifted: format!("{}/{}", s.lifted, s.liftable),
+            languages: s.languages.clone(),
+            hierarchy: s.hierarchy_type.clone(),
+            paradigms: s.paradigms.join(", "),
+            commit: s.commit.clone(),
+            staleness: s
+                .staleness
+                .clone()
+                .unwrap_or_else(|| "up to date".to_string()),
+        },
+        areas: result
+            .areas
+            .iter()
+            .map(|a| OrientAreaRow {
+                path: a.path.clone(),
+                entities: a.entity_count,
+                summary: a.top_features.join("; "),
+            })
+            .collect(),
+        entry_points: result
+            .entry_points
+            .iter()
+            .map(|e| OrientEntryRow {
+                entity_id: e.entity_id.clone(),
+                reason: e.reason.to_string(),
+            })
+            .collect(),
+        entry_points_omitted: (result.entry_points_omitted > 0)
+            .then_some(result.entry_points_omitted),
+        top_fan_in: result
+            .fan_in
+            .iter()
+            .map(|f| OrientFanInRow {
+                entity_id: f.entity_id.clone(),
+                kind: f.kind.clone(),
+                fan_in: f.fan_in,
+                features: f.features.join("; "),
+            })
+            .collect(),
+        next_tools: result
+            .next_tools
+            .iter()
+            .map(|h| OrientToolRow {
+                intent: h.intent.to_string(),
+                tools: h.tools.to_string(),
+            })
+            .collect(),
+    };
+
+    encode(&output, &encode_opts()).unwrap_or_else(|_| "Failed to encode orientation".into())
+}
+
+// ---------------------------------------------------------------------------
+// Entity explanation output
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct ExplainFitRow {
+    area: String,
+    similarity: f64,
+}
+
+#[derive(Serialize)]
+struct ExplainNeighborRow {
+    area: String,
+    deps: usize,
+}
+
+#[derive(Serialize)]
+struct ExplainOutput {
+    entity_id: String,
+    kind: String,
+    file: String,
+    hierarchy_path: String,
+    assignment: String,
+    features: String,
+    feature_source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifted_at: Option<String>,
+    graph_updated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    area_similarity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    best_alternative: Option<ExplainFitRow>,
+    misplaced: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggested_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_step: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    neighbor_areas: Vec<ExplainNeighborRow>,
+}
+
+/// Format an entity explanation as TOON.
+pub fn format_explanation(result: &EntityExplanation) -> String {
+    let output = ExplainOutput {
+        entity_id: result.entity_id.clone(),
+        kind: format!("{:?}", result.kind).to_lowercase(),
+        file: result.file.clone(),
+        hierarchy_path: result.hierarchy_path.clone(),
+        assignment: result.assignment.as_str().to_string(),
+        features: result.features.join("; "),
+        feature_source: result
+            .feature_source
+            .clone()
+            .unwrap_or_else(|| "none".to_string()),
+        lifted_at: result.lifted_at.clone(),
+        graph_updated_at: result.graph_updated_at.clone(),
+        area_similarity: result.area_similarity.map(clean_score),
+        best_alternative: result.best_alternative.as_ref().map(|a| ExplainFitRow {
+            area: a.area.clone(),
+            similarity: clean_score(a.similarity),
+        }),
+        misplaced: result.misplaced,
+        suggested_path: result.suggested_path.clone(),
+        next_step: result.next_step.clone(),
+        neighbor_areas: result
+            .neighbor_areas
+            .iter()
+            .map(|n| ExplainNeighborRow {
+                area: n.area.clone(),
+                deps: n.count,
+            })
+            .collect(),
+    };
+
+    encode(&output, &encode_opts()).unwrap_or_else(|_| "Failed to encode explanation".into())
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -1176,7 +1827,7 @@ mod tests {
 
     #[test]
     fn test_format_search_results_empty() {
-        let result = format_search_results(&[]);
+        let result = format_search_results(&[], None);
         // Should contain results as empty array
         assert!(result.contains("results"));
     }
@@ -1191,8 +1842,13 @@ mod tests {
             score: 1.5,
             matched_features: vec!["entry point".to_string()],
             lifted: true,
+            attributes: String::new(),
+            stale: false,
+            expanded_via: Vec::new(),
+            neighbors: Vec::new(),
+            members: Vec::new(),
         }];
-        let output = format_search_results(&results);
+        let output = format_search_results(&results, None);
         assert!(output.contains("main"));
         assert!(output.contains("src/main.rs"));
         assert!(output.contains("entry point"));
@@ -1210,8 +1866,13 @@ mod tests {
             score: 0.8,
             matched_features: vec![],
             lifted: false,
+            attributes: String::new(),
+            stale: false,
+            expanded_via: Vec::new(),
+            neighbors: Vec::new(),
+            members: Vec::new(),
         }];
-        let output = format_search_results(&results);
+        let output = format_search_results(&results, None);
         // Each line should not have trailing whitespace
         for line in output.lines() {
             assert!(!line.ends_with(' '), "trailing space in: {:?}", line);
@@ -1244,12 +1905,25 @@ mod tests {
                 parent_class: None,
                 semantic_features: vec!["validate input".to_string()],
                 feature_source: None,
+                lifted_at: None,
                 hierarchy_path: "Core/util/validate".to_string(),
                 deps: EntityDeps {
                     invokes: vec!["bar".to_string()],
                     ..Default::default()
                 },
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
             source_code: Some("fn foo() { bar() }".to_string()),
             hierarchy_context: vec!["sibling".to_string()],
@@ -1291,9 +1965,22 @@ mod tests {
                 parent_class: None,
                 semantic_features: vec![],
                 feature_source: None,
+                lifted_at: None,
                 hierarchy_path: String::new(),
                 deps: EntityDeps::default(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
             },
             source_code: Some(long_source),
             hierarchy_context: vec![],
@@ -1307,6 +1994,57 @@ mod tests {
         assert!(output.contains("more lines"));
     }
 
+    #[test]
+    fn test_format_fetch_result_renders_doc_comment() {
+        use crate::fetch::FetchResult;
+        use rpg_core::graph::{Entity, EntityDeps, EntityKind};
+        use std::path::PathBuf;
+
+        let result = FetchResult {
+            entity: Entity {
+                id: "src/lib.rs:parse".to_string(),
+                kind: EntityKind::Function,
+                name: "parse".to_string(),
+                file: PathBuf::from("src/lib.rs"),
+                line_start: 3,
+                line_end: 5,
+                parent_class: None,
+                semantic_features: vec!["parse config".to_string()],
+                feature_source: None,
+                lifted_at: None,
+                hierarchy_path: String::new(),
+                deps: EntityDeps::default(),
+                signature: None,
+                attributes: BTreeMap::new(),
+                source_signature: Vec::new(),
+                owners: Vec::new(),
+                layer: None,
+                tags: BTreeSet::new(),
+                churn: None,
+                doc_comment: Some("Parse the config file.".to_string()),
+                decorators: Vec::new(),
+                is_async: true,
+                is_generator: false,
+                is_unsafe: false,
+                entry_distance: None,
+            },
+            source_code: Some("fn parse() {}".to_string()),
+            hierarchy_context: vec![],
+        };
+
+        assert!(format_fetch_result(&result).contains("Parse the config file."));
+        let rendered = format_fetch_result(&result);
+        assert!(rendered.contains("modifiers[1|]: async"), "{}", rendered);
+        let full = FetchProjection::from_params(None, None).unwrap();
+        assert!(format_fetch_result_projected(&result, &full).contains("Parse the config file."));
+        let doc_only = FetchProjection::from_params(Some("doc"), None).unwrap();
+        let output = format_fetch_result_projected(&result, &doc_only);
+        assert!(output.contains("Parse the config file."), "{}", output);
+        assert!(!output.contains("fn parse()"), "{}", output);
+        let features = FetchProjection::from_params(Some("features"), None).unwrap();
+        assert!(!format_fetch_result_projected(&result, &features).contains("Parse the config"));
+    }
+
     #[test]
     fn test_fetch_projection_rejects_invalid_fields() {
         let result = FetchProjection::from_params(Some("features,bogus,deps"), None);
@@ -1316,6 +2054,16 @@ mod tests {
         assert!(err.contains("Valid fields"), "should list valid fields");
     }
 
+    #[test]
+    fn test_fetch_projection_skeleton_implies_source() {
+        let projection = FetchProjection::from_params(Some("skeleton"), None).unwrap();
+        assert!(projection.skeleton());
+        assert!(projection.include("source"));
+        assert!(!projection.include("deps"));
+        let full = FetchProjection::from_params(None, None).unwrap();
+        assert!(!full.skeleton());
+    }
+
     #[test]
     fn test_format_context_pack() {
         use crate::context::{ContextPackResult, PackedEntity};
@@ -1357,9 +2105,11 @@ mod tests {
                 depth: 1,
                 edge_path: vec![("src/lib.rs:foo".to_string(), EdgeKind::Invokes)],
                 features: vec!["entry point".to_string()],
+                async_boundary: false,
             }],
             total: 1,
             max_depth_reached: 1,
+            budget_exhausted: false,
         };
 
         let output = format_impact_radius(&result);
@@ -0,0 +1,139 @@
+//! Feature vocabulary: a co-occurrence thesaurus built from the graph's own
+//! lifted features, stored in `.rpg/vocab.json`.
+//!
+//! Two terms are neighbors when they often describe the same entity ("auth" and
+//! "credentials"). Lexical feature search expands each query term with its top
+//! neighbors at reduced weight, so "auth" can find an entity whose features
+//! only say "verify credentials" even without embeddings.
+
+use anyhow::{Context, Result};
+use rpg_core::graph::RPGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Neighbors kept per term in the stored vocabulary.
+pub const MAX_NEIGHBORS: usize = 5;
+
+/// Entities two terms must share before they count as neighbors.
+pub const MIN_COOCCURRENCE: usize = 2;
+
+/// Minimum association (Jaccard overlap of the entity sets) for a neighbor.
+pub const MIN_ASSOCIATION: f64 = 0.2;
+
+/// Words too common to say anything about an entity.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "into", "is", "it", "of", "on", "or",
+    "the", "to", "via", "with",
+];
+
+/// A term's neighbor and how strongly the two co-occur (0.0..=1.0).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Neighbor {
+    pub term: String,
+    pub association: f64,
+}
+
+/// Co-occurrence thesaurus over feature terms.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vocabulary {
+    /// Term → neighbors, strongest first.
+    pub neighbors: BTreeMap<String, Vec<Neighbor>>,
+}
+
+/// Path to the stored vocabulary.
+pub fn vocab_file(project_root: &Path) -> PathBuf {
+    rpg_core::storage::rpg_dir(project_root).join("vocab.json")
+}
+
+/// Split a feature into the terms the vocabulary and search expansion use:
+/// lowercase, edge punctuation trimmed, stopwords dropped.
+pub fn feature_terms(feature: &str) -> impl Iterator<Item = String> + '_ {
+    feature.split_whitespace().filter_map(|raw| {
+        let term = raw
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        (term.len() >= 2 && !STOPWORDS.contains(&term.as_str())).then_some(term)
+    })
+}
+
+impl Vocabulary {
+    /// Build the thesaurus from every lifted entity's features.
+    pub fn build(graph: &RPGraph) -> Self {
+        let mut df: HashMap<String, usize> = HashMap::new();
+        let mut pairs: HashMap<(String, String), usize> = HashMap::new();
+        for entity in graph.entities.values() {
+            let terms: BTreeSet<String> = entity
+                .semantic_features
+                .iter()
+                .flat_map(|f| feature_terms(f))
+                .collect();
+            let terms: Vec<&String> = terms.iter().collect();
+            for (i, a) in terms.iter().enumerate() {
+                *df.entry((*a).clone()).or_default() += 1;
+                for b in &terms[i + 1..] {
+                    *pairs.entry(((*a).clone(), (*b).clone())).or_default() += 1;
+                }
+            }
+        }
+
+        let mut neighbors: BTreeMap<String, Vec<Neighbor>> = BTreeMap::new();
+        for ((a, b), together) in pairs {
+            if together < MIN_COOCCURRENCE {
+                continue;
+            }
+            let association = together as f64 / (df[&a] + df[&b] - together) as f64;
+            if association < MIN_ASSOCIATION {
+                continue;
+            }
+            neighbors.entry(a.clone()).or_default().push(Neighbor {
+                term: b.clone(),
+                association,
+            });
+            neighbors.entry(b).or_default().push(Neighbor {
+                term: a,
+                association,
+            });
+        }
+        for list in neighbors.values_mut() {
+            list.sort_by(|x, y| {
+                y.association
+                    .total_cmp(&x.association)
+                    .then_with(|| x.term.cmp(&y.term))
+            });
+            list.truncate(MAX_NEIGHBORS);
+        }
+        Self { neighbors }
+    }
+
+    /// Up to `k` strongest neighbors of `term`.
+    pub fn expand(&self, term: &str, k: usize) -> &[Neighbor] {
+        self.neighbors
+            .get(term)
+            .map_or(&[], |list| &list[..k.min(list.len())])
+    }
+
+    /// Load `.rpg/vocab.json`. `None` when it hasn't been built or is unreadable.
+    pub fn load(project_root: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(vocab_file(project_root)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write `.rpg/vocab.json`.
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = vocab_file(project_root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// Rebuild the vocabulary from the graph and save it. Returns the number of
+/// terms that have neighbors.
+pub fn rebuild(project_root: &Path, graph: &RPGraph) -> Result<usize> {
+    let vocab = Vocabulary::build(graph);
+    vocab.save(project_root)?;
+    Ok(vocab.neighbors.len())
+}
@@ -0,0 +1,160 @@
+//! One-shot query answers: the text block for a lifted fixture, and the
+//! same answer as JSON.
+
+use rpg_core::graph::*;
+use rpg_nav::answer::{answer_query, format_answers};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+const NET_RS: &str = "use std::time::Duration;
+
+pub fn send_with_retry(req: &Request) -> Result<Response> {
+    let mut delay = Duration::from_millis(100);
+    for _ in 0..3 {
+        match send(req) {
+            Ok(resp) => return Ok(resp),
+            Err(_) => sleep(delay),
+        }
+
+        delay *= 2;
+    }
+    send(req)
+}
+
+pub fn send(req: &Request) -> Result<Response> {
+    transport::post(req)
+}
+";
+
+const MAIN_RS: &str = "fn main() {
+    net::send_with_retry(&request()).unwrap();
+}
+";
+
+fn entity(id: &str, lines: (usize, usize), features: &[&str], path: &str) -> Entity {
+    let (file, name) = id.split_once(':').unwrap();
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        parent_class: None,
+        semantic_features: features.iter().map(|f| f.to_string()).collect(),
+        feature_source: Some("llm".to_string()),
+        lifted_at: None,
+        hierarchy_path: path.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// `main` → `send_with_retry` → `send`, lifted and placed in the hierarchy.
+fn fixture() -> (tempfile::TempDir, RPGraph) {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/net.rs"), NET_RS).unwrap();
+    std::fs::write(tmp.path().join("src/main.rs"), MAIN_RS).unwrap();
+
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(entity(
+        "src/net.rs:send_with_retry",
+        (3, 14),
+        &["retry failed requests", "back off between attempts"],
+        "Network/send requests/retry calls",
+    ));
+    graph.insert_entity(entity(
+        "src/net.rs:send",
+        (16, 18),
+        &["post request over transport"],
+        "Network/send requests/post payloads",
+    ));
+    graph.insert_entity(entity(
+        "src/main.rs:main",
+        (1, 3),
+        &["start application"],
+        "Cli/start program/run main",
+    ));
+    for (source, target) in [
+        ("src/main.rs:main", "src/net.rs:send_with_retry"),
+        ("src/net.rs:send_with_retry", "src/net.rs:send"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+        graph
+            .entities
+            .get_mut(source)
+            .unwrap()
+            .deps
+            .invokes
+            .push(target.to_string());
+        graph
+            .entities
+            .get_mut(target)
+            .unwrap()
+            .deps
+            .push_reverse(EdgeKind::Invokes, source.to_string());
+    }
+    graph.rebuild_edge_index();
+    (tmp, graph)
+}
+
+#[test]
+fn test_answer_text_snapshot() {
+    let (tmp, graph) = fixture();
+    let answers = answer_query(&graph, tmp.path(), "retry failed requests", None, 1, None).unwrap();
+    assert_eq!(
+        format_answers("retry failed requests", &answers),
+        "\"retry failed requests\" — 1 result(s)
+
+1. send_with_retry (function) at src/net.rs:3-14, in Network/send requests/retry calls
+   It does: retry failed requests; back off between attempts.
+   Called by: main (src/main.rs:1)
+   Calls: send (src/net.rs:16)
+   | pub fn send_with_retry(req: &Request) -> Result<Response> {
+   |     let mut delay = Duration::from_millis(100);
+   |     for _ in 0..3 {
+   |         match send(req) {
+   |             Ok(resp) => return Ok(resp),
+   |             Err(_) => sleep(delay),
+   |         }
+   |
+   |         delay *= 2;
+   |     }
+   | … (2 more lines)
+"
+    );
+}
+
+#[test]
+fn test_answer_json_and_empty_result() {
+    let (tmp, graph) = fixture();
+    let answers = answer_query(&graph, tmp.path(), "retry failed requests", None, 1, None).unwrap();
+    let json = serde_json::to_value(&answers).unwrap();
+    assert_eq!(json[0]["entity_id"], "src/net.rs:send_with_retry");
+    assert_eq!(json[0]["callers"][0]["file"], "src/main.rs");
+    assert_eq!(json[0]["callees"][0]["line"], 16);
+
+    let none = answer_query(&graph, tmp.path(), "render pdf invoices", None, 3, None).unwrap();
+    assert!(none.is_empty(), "{:?}", none);
+    assert_eq!(
+        format_answers("render pdf invoices", &none),
+        "Nothing in the graph matches \"render pdf invoices\".\n"
+    );
+}
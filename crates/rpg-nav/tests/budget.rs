@@ -0,0 +1,167 @@
+//! Time budgets: explore, impact, and search stop at their deadline and say
+//! their results are partial.
+
+use rpg_core::graph::*;
+use rpg_nav::budget;
+use rpg_nav::explore::{Direction, NeighborFilter, explore_within};
+use rpg_nav::impact::{ImpactOptions, compute_impact_radius_with};
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_facets};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Entities in the synthetic graph.
+const GRAPH_SIZE: usize = 5_000;
+
+fn node_id(i: usize) -> String {
+    format!("src/m{}.rs:step_{}", i / 50, i)
+}
+
+/// A binary call tree: `step_i` invokes `step_{2i+1}` and `step_{2i+2}`.
+fn tree_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for i in 0..GRAPH_SIZE {
+        let id = node_id(i);
+        graph.insert_entity(Entity {
+            id: id.clone(),
+            kind: EntityKind::Function,
+            name: format!("step_{}", i),
+            file: PathBuf::from(format!("src/m{}.rs", i / 50)),
+            line_start: 1,
+            line_end: 5,
+            parent_class: None,
+            semantic_features: vec!["advance pipeline step".to_string()],
+            feature_source: None,
+            lifted_at: None,
+            hierarchy_path: String::new(),
+            deps: EntityDeps::default(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+            entry_distance: None,
+        });
+        if i > 0 {
+            graph.edges.push(DependencyEdge {
+                source: node_id((i - 1) / 2),
+                target: id,
+                kind: EdgeKind::Invokes,
+            });
+        }
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+fn tree_size(node: &rpg_nav::explore::TraversalNode) -> usize {
+    1 + node.children.iter().map(tree_size).sum::<usize>()
+}
+
+fn search_params(deadline: Option<Instant>) -> SearchParams<'static> {
+    SearchParams {
+        query: "pipeline step",
+        mode: SearchMode::Auto,
+        scope: None,
+        limit: 10,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        layer_filter: None,
+        embedding_scores: None,
+        diff_context: None,
+        changed_entities: None,
+        vocabulary: None,
+        sort: SearchSort::Relevance,
+        include_fixtures: false,
+        exclude_tests: false,
+        async_only: false,
+        deadline,
+        group_by_file: false,
+    }
+}
+
+#[test]
+fn test_deadline_from_config_seconds() {
+    assert!(budget::deadline(0.0).is_none());
+    assert!(budget::deadline(f64::NAN).is_none());
+    assert!(!budget::expired(None));
+    assert!(!budget::expired(budget::deadline(3600.0)));
+    let tiny = budget::deadline(1e-9);
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    assert!(budget::expired(tiny));
+}
+
+#[test]
+fn test_explore_stops_at_deadline_with_partial_tree() {
+    let graph = tree_graph();
+    let explore = |deadline| {
+        explore_within(
+            &graph,
+            &node_id(0),
+            Direction::Downstream,
+            usize::MAX,
+            None,
+            NeighborFilter::default(),
+            deadline,
+        )
+        .unwrap()
+    };
+
+    let (full, finished) = explore(None);
+    assert!(finished);
+    assert_eq!(tree_size(&full), GRAPH_SIZE);
+
+    let (partial, finished) = explore(Some(Instant::now()));
+    assert!(!finished);
+    assert_eq!(partial.entity_id, node_id(0));
+    assert!(tree_size(&partial) < GRAPH_SIZE);
+}
+
+#[test]
+fn test_impact_stops_at_deadline() {
+    let graph = tree_graph();
+    let impact = |deadline| {
+        compute_impact_radius_with(
+            &graph,
+            &node_id(0),
+            Direction::Downstream,
+            usize::MAX,
+            None,
+            &ImpactOptions {
+                deadline,
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    };
+
+    let full = impact(None);
+    assert!(!full.budget_exhausted);
+    assert_eq!(full.total, GRAPH_SIZE - 1);
+
+    let partial = impact(Some(Instant::now()));
+    assert!(partial.budget_exhausted);
+    assert!(partial.total < GRAPH_SIZE - 1);
+    assert_eq!(partial.total, partial.reachable.len());
+}
+
+#[test]
+fn test_search_stops_scoring_at_deadline() {
+    let graph = tree_graph();
+
+    let full = search_with_facets(&graph, &search_params(None)).unwrap();
+    assert!(!full.budget_exhausted);
+    assert_eq!(full.facets.total_matches, GRAPH_SIZE);
+
+    let partial = search_with_facets(&graph, &search_params(Some(Instant::now()))).unwrap();
+    assert!(partial.budget_exhausted);
+    assert!(partial.facets.total_matches < GRAPH_SIZE);
+}
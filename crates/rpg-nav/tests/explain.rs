@@ -0,0 +1,188 @@
+use rpg_core::graph::*;
+use rpg_nav::explain::{AssignmentMode, DEFAULT_MISPLACEMENT_THRESHOLD, explain_entity};
+use rpg_nav::toon::format_explanation;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+fn make_entity(id: &str, kind: EntityKind, file: &str, features: &[&str], path: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind,
+        name: id.rsplit(':').next().unwrap().to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: features.iter().map(|s| s.to_string()).collect(),
+        feature_source: Some("llm".to_string()),
+        lifted_at: None,
+        hierarchy_path: path.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// Security and Storage areas, with a SQL helper deliberately routed into Security.
+fn misrouted_fixture() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let entities = [
+        (
+            "src/auth.rs:auth",
+            EntityKind::Module,
+            "src/auth.rs",
+            &["validate token", "check password"][..],
+            "Security/auth/login",
+        ),
+        (
+            "src/auth.rs:validate_token",
+            EntityKind::Function,
+            "src/auth.rs",
+            &["validate token", "reject expired session"][..],
+            "Security/auth/login",
+        ),
+        (
+            "src/auth.rs:check_password",
+            EntityKind::Function,
+            "src/auth.rs",
+            &["check password", "validate token"][..],
+            "Security/auth/login",
+        ),
+        // Misrouted: pure storage behavior living under Security.
+        (
+            "src/auth.rs:run_query",
+            EntityKind::Function,
+            "src/auth.rs",
+            &["execute sql query", "open db connection"][..],
+            "Security/auth/session",
+        ),
+        (
+            "src/db.rs:query",
+            EntityKind::Function,
+            "src/db.rs",
+            &["execute sql query", "map result rows"][..],
+            "Storage/sql/query",
+        ),
+        (
+            "src/db.rs:connect",
+            EntityKind::Function,
+            "src/db.rs",
+            &["open db connection"][..],
+            "Storage/sql/pool",
+        ),
+    ];
+    for (id, kind, file, features, path) in entities {
+        graph.insert_entity(make_entity(id, kind, file, features, path));
+        graph.insert_into_hierarchy(path, id);
+    }
+
+    for (source, target) in [
+        ("src/auth.rs:run_query", "src/db.rs:query"),
+        ("src/auth.rs:run_query", "src/db.rs:connect"),
+        ("src/auth.rs:check_password", "src/auth.rs:run_query"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+    }
+
+    graph.metadata.semantic_hierarchy = true;
+    graph.assign_hierarchy_ids();
+    graph.aggregate_hierarchy_features();
+    graph.rebuild_edge_index();
+    graph.refresh_metadata();
+    graph
+}
+
+#[test]
+fn test_misrouted_entity_is_flagged() {
+    let graph = misrouted_fixture();
+    let result = explain_entity(
+        &graph,
+        "src/auth.rs:run_query",
+        DEFAULT_MISPLACEMENT_THRESHOLD,
+        5,
+    )
+    .unwrap();
+
+    assert!(result.misplaced);
+    assert_eq!(result.area_similarity, Some(0.0));
+    let alt = result.best_alternative.as_ref().unwrap();
+    assert_eq!(alt.area, "Storage");
+    assert!(alt.similarity > 0.5, "similarity = {}", alt.similarity);
+    assert_eq!(result.assignment, AssignmentMode::RoutedIndividually);
+}
+
+#[test]
+fn test_well_placed_entity_is_not_flagged() {
+    let graph = misrouted_fixture();
+    let result = explain_entity(
+        &graph,
+        "src/auth.rs:validate_token",
+        DEFAULT_MISPLACEMENT_THRESHOLD,
+        5,
+    )
+    .unwrap();
+
+    assert!(!result.misplaced);
+    assert!(result.area_similarity.unwrap() > 0.0);
+    assert!(result.best_alternative.is_none());
+    assert_eq!(result.assignment, AssignmentMode::InheritedFromFile);
+}
+
+#[test]
+fn test_assignment_modes() {
+    let mut graph = misrouted_fixture();
+    let module = explain_entity(&graph, "src/auth.rs:auth", 0.1, 5).unwrap();
+    assert_eq!(module.assignment, AssignmentMode::FileLevel);
+
+    graph.metadata.semantic_hierarchy = false;
+    let structural = explain_entity(&graph, "src/db.rs:query", 0.1, 5).unwrap();
+    assert_eq!(structural.assignment, AssignmentMode::Structural);
+}
+
+#[test]
+fn test_neighbor_areas_count_both_directions() {
+    let graph = misrouted_fixture();
+    let result = explain_entity(&graph, "src/auth.rs:run_query", 0.1, 5).unwrap();
+    let areas: Vec<(&str, usize)> = result
+        .neighbor_areas
+        .iter()
+        .map(|n| (n.area.as_str(), n.count))
+        .collect();
+    assert_eq!(areas, vec![("Storage", 2), ("Security", 1)]);
+}
+
+#[test]
+fn test_unknown_entity_returns_none() {
+    let graph = misrouted_fixture();
+    assert!(explain_entity(&graph, "src/nope.rs:missing", 0.1, 5).is_none());
+}
+
+#[test]
+fn test_format_explanation_reports_misplacement() {
+    let graph = misrouted_fixture();
+    let mut result = explain_entity(&graph, "src/auth.rs:run_query", 0.1, 5).unwrap();
+    result.suggested_path = Some("Storage/sql/query".to_string());
+
+    let output = format_explanation(&result);
+    assert!(output.contains("hierarchy_path: Security/auth/session"));
+    assert!(output.contains("assignment: routed individually"));
+    assert!(output.contains("feature_source: llm"));
+    assert!(output.contains("misplaced: true"));
+    assert!(output.contains("suggested_path: Storage/sql/query"));
+    assert!(!output.contains("lifted_at"));
+}
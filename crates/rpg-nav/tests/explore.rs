@@ -1,5 +1,6 @@
 use rpg_core::graph::*;
 use rpg_nav::explore::{Direction, explore, format_tree};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str) -> Entity {
@@ -13,9 +14,22 @@ fn make_entity(id: &str, name: &str, file: &str) -> Entity {
         parent_class: None,
         semantic_features: Vec::new(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -1,5 +1,6 @@
 use rpg_core::graph::*;
-use rpg_nav::export::{ExportFormat, export};
+use rpg_nav::export::{ExportFormat, export, scoped_graph};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str, kind: EntityKind) -> Entity {
@@ -13,9 +14,22 @@ fn make_entity(id: &str, name: &str, file: &str, kind: EntityKind) -> Entity {
         parent_class: None,
         semantic_features: vec!["test feature".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: String::new(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -143,3 +157,137 @@ fn test_export_mermaid_skips_contains() {
         "Mermaid should skip contains edge labels"
     );
 }
+
+#[test]
+fn test_scoped_graph_drops_out_of_scope_entities_and_edges() {
+    let mut graph = make_graph();
+    graph.insert_into_hierarchy("Core/a/a", "a.rs:a");
+    graph.insert_into_hierarchy("Model/b/b", "b.rs:b");
+    graph.assign_hierarchy_ids();
+
+    let scope = rpg_nav::scope::parse("kind:function").unwrap();
+    let scoped = scoped_graph(&graph, &scope);
+
+    assert_eq!(scoped.entities.len(), 1);
+    assert!(scoped.entities.contains_key("a.rs:a"));
+    assert!(scoped.edges.is_empty());
+    assert!(scoped.hierarchy.contains_key("Core"));
+    assert!(!scoped.hierarchy.contains_key("Model"));
+
+    let dot = export(&scoped, ExportFormat::Dot);
+    assert!(!dot.contains("b.rs:b"));
+}
+
+#[test]
+fn test_neighborhood_dot_snapshot() {
+    let mut graph = RPGraph::new("rust");
+    for (id, name, file, kind, path) in [
+        (
+            "src/api.rs:handle",
+            "handle",
+            "src/api.rs",
+            EntityKind::Function,
+            "Api/http/handle",
+        ),
+        (
+            "src/auth.rs:check",
+            "check",
+            "src/auth.rs",
+            EntityKind::Function,
+            "Security/auth/check",
+        ),
+        (
+            "src/db.rs:Pool",
+            "Pool",
+            "src/db.rs",
+            EntityKind::Class,
+            "Storage/pool/manage",
+        ),
+        (
+            "src/db.rs:Conn",
+            "Conn",
+            "src/db.rs",
+            EntityKind::Class,
+            "Storage/pool/manage",
+        ),
+        (
+            "src/main.rs:main",
+            "main",
+            "src/main.rs",
+            EntityKind::Function,
+            "",
+        ),
+        (
+            "src/log.rs:log",
+            "log",
+            "src/log.rs",
+            EntityKind::Function,
+            "Storage/log/write",
+        ),
+    ] {
+        let mut entity = make_entity(id, name, file, kind);
+        entity.hierarchy_path = path.to_string();
+        graph.insert_entity(entity);
+    }
+    for (source, target, kind) in [
+        ("src/main.rs:main", "src/api.rs:handle", EdgeKind::Invokes),
+        ("src/api.rs:handle", "src/auth.rs:check", EdgeKind::Invokes),
+        ("src/api.rs:handle", "src/db.rs:Pool", EdgeKind::Imports),
+        ("src/db.rs:Pool", "src/db.rs:Conn", EdgeKind::Composes),
+        // Three hops out: past the depth limit.
+        ("src/db.rs:Conn", "src/log.rs:log", EdgeKind::Invokes),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+        });
+    }
+    graph.rebuild_edge_index();
+
+    let tree = rpg_nav::explore::explore_filtered(
+        &graph,
+        "src/api.rs:handle",
+        rpg_nav::explore::Direction::Both,
+        2,
+        None,
+        rpg_nav::explore::NeighborFilter::default(),
+    )
+    .unwrap();
+    let dot = rpg_nav::export::export_neighborhood_dot(&graph, &tree);
+    assert_eq!(
+        dot,
+        concat!(
+            "digraph RPG {\n",
+            "  rankdir=LR;\n",
+            "  node [shape=box, fontsize=10];\n",
+            "\n",
+            "  \"src/api.rs:handle\" [shape=ellipse, style=filled, fillcolor=\"#cfe2ff\", penwidth=2, label=\"handle\"];\n",
+            "  \"src/auth.rs:check\" [shape=ellipse, style=filled, fillcolor=\"#d1e7dd\", label=\"check\"];\n",
+            "  \"src/db.rs:Conn\" [shape=box, style=filled, fillcolor=\"#fff3cd\", label=\"Conn\"];\n",
+            "  \"src/db.rs:Pool\" [shape=box, style=filled, fillcolor=\"#fff3cd\", label=\"Pool\"];\n",
+            "  \"src/main.rs:main\" [shape=ellipse, style=filled, fillcolor=\"#ffffff\", label=\"main\"];\n",
+            "\n",
+            "  \"src/api.rs:handle\" -> \"src/auth.rs:check\" [style=solid, color=\"#1f77b4\", label=\"invokes\"];\n",
+            "  \"src/api.rs:handle\" -> \"src/db.rs:Pool\" [style=dashed, color=\"#7f7f7f\", label=\"imports\"];\n",
+            "  \"src/db.rs:Pool\" -> \"src/db.rs:Conn\" [style=solid, color=\"#2ca02c\", label=\"composes\"];\n",
+            "  \"src/main.rs:main\" -> \"src/api.rs:handle\" [style=solid, color=\"#1f77b4\", label=\"invokes\"];\n",
+            "\n",
+            "  subgraph cluster_legend {\n",
+            "    label=\"Legend\";\n",
+            "    fontsize=10;\n",
+            "    \"legend:area:Api\" [shape=box, style=filled, fillcolor=\"#cfe2ff\", label=\"Api\"];\n",
+            "    \"legend:area:Security\" [shape=box, style=filled, fillcolor=\"#d1e7dd\", label=\"Security\"];\n",
+            "    \"legend:area:Storage\" [shape=box, style=filled, fillcolor=\"#fff3cd\", label=\"Storage\"];\n",
+            "    \"legend:area:(no area)\" [shape=box, style=filled, fillcolor=\"#ffffff\", label=\"(no area)\"];\n",
+            "    \"legend:imports:from\" [shape=point]; \"legend:imports:to\" [shape=point];\n",
+            "    \"legend:imports:from\" -> \"legend:imports:to\" [style=dashed, color=\"#7f7f7f\", label=\"imports\"];\n",
+            "    \"legend:invokes:from\" [shape=point]; \"legend:invokes:to\" [shape=point];\n",
+            "    \"legend:invokes:from\" -> \"legend:invokes:to\" [style=solid, color=\"#1f77b4\", label=\"invokes\"];\n",
+            "    \"legend:composes:from\" [shape=point]; \"legend:composes:to\" [shape=point];\n",
+            "    \"legend:composes:from\" -> \"legend:composes:to\" [style=solid, color=\"#2ca02c\", label=\"composes\"];\n",
+            "  }\n",
+            "}\n",
+        )
+    );
+}
@@ -1,5 +1,6 @@
 use rpg_core::graph::*;
-use rpg_nav::fetch::{FetchOutput, fetch};
+use rpg_nav::fetch::{FetchOutput, fetch, fetch_file, fit_token_budget, missing_file_message};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use tempfile::TempDir;
 
@@ -14,9 +15,22 @@ fn make_entity(id: &str, name: &str, file: &str, hierarchy: &str) -> Entity {
         parent_class: None,
         semantic_features: vec!["test feature".to_string()],
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -146,3 +160,111 @@ fn test_fetch_hierarchy_node() {
         FetchOutput::Entity(_) => panic!("expected Hierarchy result"),
     }
 }
+
+#[test]
+fn test_fetch_and_explore_hierarchy_node_by_path() {
+    let tmp = TempDir::new().unwrap();
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity(
+        "auth.rs:login",
+        "login",
+        "auth.rs",
+        "Auth/login/validate",
+    ));
+    graph.insert_into_hierarchy("Auth/login/validate", "auth.rs:login");
+    graph.assign_hierarchy_ids();
+    graph.materialize_containment_edges();
+
+    let FetchOutput::Hierarchy(result) = fetch(&graph, "Auth/login", tmp.path()).unwrap() else {
+        panic!("expected Hierarchy result");
+    };
+    assert_eq!(result.node.id, "h:Auth/login");
+
+    let tree = rpg_nav::explore::explore(
+        &graph,
+        "Auth/login",
+        rpg_nav::explore::Direction::Downstream,
+        2,
+        None,
+    )
+    .unwrap();
+    assert_eq!(tree.entity_id, "h:Auth/login");
+    assert_eq!(tree.children[0].entity_id, "h:Auth/login/validate");
+}
+
+/// `src/auth.rs` with three functions out of line order in the graph, plus
+/// its Module entity.
+fn auth_file_graph(root: &std::path::Path) -> RPGraph {
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::write(
+        root.join("src/auth.rs"),
+        "fn login() {\n    check();\n}\n\nfn check() {\n    hash();\n}\n\nfn hash() {\n    todo!()\n}\n",
+    )
+    .unwrap();
+    let mut graph = RPGraph::new("rust");
+    for (name, start) in [("hash", 9), ("login", 1), ("check", 5)] {
+        let mut entity = make_entity(
+            &format!("src/auth.rs:{}", name),
+            name,
+            "src/auth.rs",
+            "Security/auth/login",
+        );
+        entity.line_start = start;
+        entity.line_end = start + 2;
+        graph.insert_entity(entity);
+    }
+    graph.insert_entity(make_entity("src/db.rs:connect", "connect", "src/db.rs", ""));
+    graph.create_module_entities();
+    graph
+}
+
+#[test]
+fn test_fetch_file_returns_module_then_line_order() {
+    let tmp = TempDir::new().unwrap();
+    let graph = auth_file_graph(tmp.path());
+
+    let results = fetch_file(&graph, "./src/auth.rs", tmp.path()).unwrap();
+    let ids: Vec<&str> = results.iter().map(|r| r.entity.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "src/auth.rs:auth",
+            "src/auth.rs:login",
+            "src/auth.rs:check",
+            "src/auth.rs:hash",
+        ]
+    );
+    assert_eq!(
+        results[2].source_code.as_deref(),
+        Some("fn check() {\n    hash();\n}")
+    );
+}
+
+#[test]
+fn test_fetch_file_missing_path_suggests_known_files() {
+    let tmp = TempDir::new().unwrap();
+    let graph = auth_file_graph(tmp.path());
+
+    let err = fetch_file(&graph, "auth.rs", tmp.path())
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        err,
+        "No entities for file: auth.rs (did you mean: src/auth.rs?)"
+    );
+    assert_eq!(
+        missing_file_message(&graph, "lib/unrelated/thing.py"),
+        "No entities for file: lib/unrelated/thing.py"
+    );
+}
+
+#[test]
+fn test_fit_token_budget_trims_in_order() {
+    let blocks: Vec<String> = ["a".repeat(40), "b".repeat(40), "c".repeat(40)].to_vec();
+    let estimator = rpg_core::tokens::Heuristic;
+    // 10 tokens each under the chars/4 heuristic.
+    assert_eq!(fit_token_budget(&blocks, 30, &estimator), 3);
+    assert_eq!(fit_token_budget(&blocks, 25, &estimator), 2);
+    // The first block is kept even when it alone is over budget.
+    assert_eq!(fit_token_budget(&blocks, 5, &estimator), 1);
+}
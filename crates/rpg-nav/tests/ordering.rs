@@ -0,0 +1,226 @@
+//! Formatter output is stable: identical calls return identically ordered
+//! text, with ties broken by ID or path.
+
+use rpg_core::graph::*;
+use rpg_nav::cycles::{CycleConfig, detect_cycles};
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_params};
+use rpg_nav::slice::slice_between;
+use rpg_nav::toon::{CycleReportOptions, format_cycle_report, format_search_results};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+
+/// Runs per check: a HashMap-ordering regression shows up within a few.
+const RUNS: usize = 16;
+
+fn make_entity(file: &str, name: &str, area: &str) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 5,
+        parent_class: None,
+        semantic_features: vec!["load config file".to_string()],
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: area.to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// Six same-named, same-featured functions in two areas, wired into one
+/// two-entity cycle per area.
+fn tied_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (file, area) in [
+        ("src/f.rs", "Runtime/boot/env"),
+        ("src/b.rs", "Config/load/file"),
+        ("src/d.rs", "Runtime/boot/args"),
+        ("src/a.rs", "Config/load/file"),
+        ("src/e.rs", "Runtime/boot/env"),
+        ("src/c.rs", "Config/load/env"),
+    ] {
+        let entity = make_entity(file, "load_config", area);
+        let id = entity.id.clone();
+        graph.insert_entity(entity);
+        graph.insert_into_hierarchy(area, &id);
+    }
+    for (source, target) in [
+        ("src/a.rs", "src/b.rs"),
+        ("src/b.rs", "src/a.rs"),
+        ("src/e.rs", "src/f.rs"),
+        ("src/f.rs", "src/e.rs"),
+        ("src/b.rs", "src/c.rs"),
+        ("src/d.rs", "src/e.rs"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: format!("{}:load_config", source),
+            target: format!("{}:load_config", target),
+            kind: EdgeKind::Invokes,
+        });
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+/// Run `f` repeatedly and require one distinct output.
+fn stable(f: impl Fn() -> String) -> String {
+    let first = f();
+    for _ in 1..RUNS {
+        assert_eq!(f(), first);
+    }
+    first
+}
+
+fn params(mode: SearchMode, embedding_scores: Option<&HashMap<String, f64>>) -> SearchParams<'_> {
+    SearchParams {
+        query: "load config",
+        mode,
+        scope: None,
+        limit: 4,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        layer_filter: None,
+        embedding_scores,
+        diff_context: None,
+        changed_entities: None,
+        vocabulary: None,
+        sort: SearchSort::Relevance,
+        include_fixtures: false,
+        exclude_tests: false,
+        async_only: false,
+        deadline: None,
+        group_by_file: false,
+    }
+}
+
+#[test]
+fn test_tied_search_scores_order_by_id() {
+    let graph = tied_graph();
+    let out = stable(|| {
+        let results = search_with_params(&graph, &params(SearchMode::Auto, None)).unwrap();
+        format_search_results(&results, None)
+    });
+    assert_eq!(
+        out,
+        "results[4|]{entity_id|name|file|line|score|lifted|features}:
+  \"src/a.rs:load_config\"|load_config|src/a.rs|1|0.5|true|load config file
+  \"src/b.rs:load_config\"|load_config|src/b.rs|1|0.5|true|load config file
+  \"src/c.rs:load_config\"|load_config|src/c.rs|1|0.5|true|load config file
+  \"src/d.rs:load_config\"|load_config|src/d.rs|1|0.5|true|load config file
+lifted_count: 4
+total_count: 4"
+    );
+
+    // Hybrid reranking with tied semantic scores too.
+    let embedding_scores: HashMap<String, f64> =
+        graph.entities.keys().map(|id| (id.clone(), 0.5)).collect();
+    let out = stable(|| {
+        let results = search_with_params(
+            &graph,
+            &params(SearchMode::Features, Some(&embedding_scores)),
+        )
+        .unwrap();
+        format_search_results(&results, None)
+    });
+    assert_eq!(
+        out,
+        "results[4|]{entity_id|name|file|line|score|lifted|features}:
+  \"src/a.rs:load_config\"|load_config|src/a.rs|1|1|true|load config file
+  \"src/b.rs:load_config\"|load_config|src/b.rs|1|0.8|true|load config file
+  \"src/c.rs:load_config\"|load_config|src/c.rs|1|0.6|true|load config file
+  \"src/d.rs:load_config\"|load_config|src/d.rs|1|0.4|true|load config file
+lifted_count: 4
+total_count: 4"
+    );
+}
+
+#[test]
+fn test_cycle_report_snapshot() {
+    let graph = tied_graph();
+    let out = stable(|| {
+        let report = detect_cycles(&graph, &CycleConfig::default());
+        let opts = CycleReportOptions {
+            has_filters: true,
+            max_cycles: 10,
+            filter_summary: None,
+        };
+        format_cycle_report(&report, &graph, &opts)
+    });
+    assert_eq!(
+        out,
+        "# Circular Dependencies
+
+cycles: {total: 2, entities: 4, files: 4, areas: 2, cross_file: 2, cross_area: 0}
+
+length_dist: len2=2 len3=0 len4=0 len5+=0
+
+area_breakdown[2]{area,cycles,len2,len3,len4+,files}:
+  Config,1,1,0,0,2
+  Runtime,1,1,0,0,2
+
+cycles[2]{chain,len,files}:
+  a.rs:load_config->b.rs:load_config,len=2,a.rs,b.rs
+  e.rs:load_config->f.rs:load_config,len=2,e.rs,f.rs
+
+---
+next_step: Use area/max_cycles/cross_file_only/cross_area_only to filter
+"
+    );
+}
+
+#[test]
+fn test_slice_edges_sorted() {
+    let graph = tied_graph();
+    let ids = vec![
+        "src/a.rs:load_config".to_string(),
+        "src/c.rs:load_config".to_string(),
+        "src/f.rs:load_config".to_string(),
+        "src/d.rs:load_config".to_string(),
+    ];
+    let out = stable(|| {
+        let slice = slice_between(&graph, &ids, 3, true).unwrap();
+        let mut out = slice
+            .metadata
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+        for edge in &slice.edges {
+            out.push_str(&format!(
+                "\n{} --{:?}--> {}",
+                edge.source, edge.kind, edge.target
+            ));
+        }
+        out
+    });
+    assert_eq!(
+        out,
+        "src/a.rs:load_config
+src/b.rs:load_config
+src/c.rs:load_config
+src/d.rs:load_config
+src/e.rs:load_config
+src/f.rs:load_config
+src/a.rs:load_config --Invokes--> src/b.rs:load_config
+src/b.rs:load_config --Invokes--> src/c.rs:load_config
+src/d.rs:load_config --Invokes--> src/e.rs:load_config
+src/f.rs:load_config --Invokes--> src/e.rs:load_config"
+    );
+}
@@ -0,0 +1,256 @@
+use rpg_core::graph::*;
+use rpg_nav::orient::{OrientRequest, assign_entry_distances, build_orientation};
+use rpg_nav::toon::format_orientation;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+fn make_entity(id: &str, name: &str, kind: EntityKind, file: &str, features: &[&str]) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: features.iter().map(|s| s.to_string()).collect(),
+        feature_source: Some("llm".to_string()),
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// A small, fully lifted graph with a semantic hierarchy.
+fn lifted_fixture() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.base_commit = Some("0123456789abcdef".to_string());
+
+    let entities = [
+        (
+            "src/main.rs:main",
+            "main",
+            EntityKind::Function,
+            "src/main.rs",
+            &["start application", "parse arguments"][..],
+            "Runtime/startup/entry",
+        ),
+        (
+            "src/api.rs:get_user",
+            "get_user",
+            EntityKind::Route,
+            "src/api.rs",
+            &["serve user lookup", "validate session"][..],
+            "Api/http/users",
+        ),
+        (
+            "src/auth.rs:validate_session",
+            "validate_session",
+            EntityKind::Function,
+            "src/auth.rs",
+            &["validate session", "check token expiry"][..],
+            "Api/auth/session",
+        ),
+        (
+            "src/db.rs:query",
+            "query",
+            EntityKind::Function,
+            "src/db.rs",
+            &["execute sql query"][..],
+            "Storage/sql/query",
+        ),
+    ];
+    for (id, name, kind, file, features, path) in entities {
+        let mut e = make_entity(id, name, kind, file, features);
+        e.hierarchy_path = path.to_string();
+        graph.insert_entity(e);
+        graph.insert_into_hierarchy(path, id);
+    }
+
+    for (source, target) in [
+        ("src/main.rs:main", "src/api.rs:get_user"),
+        ("src/api.rs:get_user", "src/auth.rs:validate_session"),
+        ("src/api.rs:get_user", "src/db.rs:query"),
+        ("src/auth.rs:validate_session", "src/db.rs:query"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+        graph
+            .entities
+            .get_mut(source)
+            .unwrap()
+            .deps
+            .invokes
+            .push(target.to_string());
+        graph
+            .entities
+            .get_mut(target)
+            .unwrap()
+            .deps
+            .invoked_by
+            .push(source.to_string());
+    }
+
+    graph.metadata.semantic_hierarchy = true;
+    graph.assign_hierarchy_ids();
+    graph.aggregate_hierarchy_features();
+    graph.refresh_metadata();
+    graph
+}
+
+#[test]
+fn test_orientation_snapshot() {
+    let graph = lifted_fixture();
+    let result = build_orientation(&graph, &OrientRequest::default());
+    let expected = r#"status:
+  entities: 4
+  files: 4
+  lifted: 3/3
+  languages: rust
+  hierarchy: semantic
+  commit: "01234567"
+  staleness: up to date
+areas[7|]{path|entities|summary}:
+  Api|2|validate session; check token expiry; serve user lookup
+  Api/auth|1|check token expiry; validate session
+  Api/http|1|serve user lookup; validate session
+  Runtime|1|parse arguments; start application
+  Runtime/startup|1|parse arguments; start application
+  Storage|1|execute sql query
+  Storage/sql|1|execute sql query
+entry_points[2|]{entity_id|reason}:
+  "src/main.rs:main"|main
+  "src/api.rs:get_user"|route
+top_fan_in[3|]{entity_id|kind|fan_in|features}:
+  "src/db.rs:query"|function|2|execute sql query
+  "src/api.rs:get_user"|route|1|serve user lookup; validate session
+  "src/auth.rs:validate_session"|function|1|validate session; check token expiry
+next_tools[4|]{intent|tools}:
+  find code|"search_node -> fetch_node (or context_pack for one call)"
+  assess a change|plan_change or impact_radius(direction=upstream)
+  trace dependencies|explore_rpg or find_paths
+  lift|"lifting_status -> get_entities_for_lifting -> submit_lift_results""#;
+    assert_eq!(format_orientation(&result), expected);
+}
+
+#[test]
+fn test_orientation_respects_token_budget() {
+    let graph = lifted_fixture();
+    let request = OrientRequest {
+        token_budget: 150,
+        ..Default::default()
+    };
+    let result = build_orientation(&graph, &request);
+    assert!(
+        result.areas.iter().all(|a| !a.path.contains('/')),
+        "category rows are trimmed first"
+    );
+    assert!(result.token_estimate <= 150 || result.fan_in.len() <= 5);
+}
+
+/// `main → load → parse → tokenize` over Invokes edges, a route that
+/// dispatches to `notify`, and an orphan nothing calls.
+fn call_chain_fixture() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (id, name, kind) in [
+        ("src/main.rs:main", "main", EntityKind::Function),
+        ("src/config.rs:load", "load", EntityKind::Function),
+        ("src/config.rs:parse", "parse", EntityKind::Function),
+        ("src/lexer.rs:tokenize", "tokenize", EntityKind::Function),
+        ("src/api.rs:post_order", "post_order", EntityKind::Route),
+        ("src/events.rs:notify", "notify", EntityKind::Function),
+        ("src/legacy.rs:orphan", "orphan", EntityKind::Function),
+    ] {
+        let file = id.split(':').next().unwrap();
+        graph.insert_entity(make_entity(id, name, kind, file, &[]));
+    }
+    for (source, target, kind) in [
+        ("src/main.rs:main", "src/config.rs:load", EdgeKind::Invokes),
+        (
+            "src/config.rs:load",
+            "src/config.rs:parse",
+            EdgeKind::Invokes,
+        ),
+        (
+            "src/config.rs:parse",
+            "src/lexer.rs:tokenize",
+            EdgeKind::Invokes,
+        ),
+        // A shortcut does not lengthen the distance.
+        ("src/main.rs:main", "src/config.rs:parse", EdgeKind::Invokes),
+        (
+            "src/api.rs:post_order",
+            "src/events.rs:notify",
+            EdgeKind::Dispatches,
+        ),
+        // Calls out of an unreachable entity do not count.
+        (
+            "src/legacy.rs:orphan",
+            "src/lexer.rs:tokenize",
+            EdgeKind::Invokes,
+        ),
+        // Imports are not calls.
+        (
+            "src/main.rs:main",
+            "src/legacy.rs:orphan",
+            EdgeKind::Imports,
+        ),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+        });
+    }
+    graph
+}
+
+#[test]
+fn test_entry_distance_follows_shortest_call_path() {
+    let mut graph = call_chain_fixture();
+    assert_eq!(assign_entry_distances(&mut graph), 6);
+
+    let distance = |id: &str| graph.entities[id].entry_distance;
+    assert_eq!(distance("src/main.rs:main"), Some(0));
+    assert_eq!(distance("src/config.rs:load"), Some(1));
+    assert_eq!(distance("src/config.rs:parse"), Some(1));
+    assert_eq!(distance("src/lexer.rs:tokenize"), Some(2));
+    assert_eq!(distance("src/api.rs:post_order"), Some(0));
+    assert_eq!(distance("src/events.rs:notify"), Some(1));
+    assert_eq!(distance("src/legacy.rs:orphan"), None);
+}
+
+#[test]
+fn test_entry_distance_three_hops_and_reset() {
+    let mut graph = call_chain_fixture();
+    graph
+        .edges
+        .retain(|e| !(e.source == "src/main.rs:main" && e.target == "src/config.rs:parse"));
+    assign_entry_distances(&mut graph);
+    assert_eq!(
+        graph.entities["src/lexer.rs:tokenize"].entry_distance,
+        Some(3)
+    );
+
+    // Without entry points, stale distances are cleared.
+    graph.remove_entity("src/main.rs:main");
+    graph.remove_entity("src/api.rs:post_order");
+    assert_eq!(assign_entry_distances(&mut graph), 0);
+    assert!(graph.entities.values().all(|e| e.entry_distance.is_none()));
+}
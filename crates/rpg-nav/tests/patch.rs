@@ -0,0 +1,189 @@
+//! Patch summaries: a crafted diff over a small fixture maps to the entities
+//! it touches, their signature regions, and their upstream blast radius.
+
+use rpg_core::files::WorktreeFiles;
+use rpg_core::graph::*;
+use rpg_nav::patch::{PatchOptions, summarize_patch};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+const CONFIG_RS: &str = "use std::fs;
+
+pub fn load(path: &str) -> String {
+    let raw = fs::read_to_string(path).unwrap();
+    raw.trim().to_string()
+}
+
+pub fn parse(
+    raw: &str,
+) -> Vec<String> {
+    raw.lines().map(String::from).collect()
+}
+";
+
+const DIFF: &str = "diff --git a/src/config.rs b/src/config.rs
+--- a/src/config.rs
++++ b/src/config.rs
+@@ -1 +1,2 @@
+ use std::fs;
++use std::io;
+@@ -5 +6 @@
+-    raw.trim().to_string()
++    raw.trim_end().to_string()
+@@ -9 +10 @@
+-    raw: &str,
++    raw: &String,
+diff --git a/src/old.rs b/src/old.rs
+deleted file mode 100644
+--- a/src/old.rs
++++ /dev/null
+@@ -1,3 +0,0 @@
+-fn legacy() {
+-    todo!()
+-}
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1 @@
++fn fresh() {}
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -1 +1 @@
+-# Old
++# New
+";
+
+fn entity(id: &str, kind: EntityKind, lines: (usize, usize)) -> Entity {
+    let (file, name) = id.split_once(':').unwrap();
+    Entity {
+        id: id.to_string(),
+        kind,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        parent_class: None,
+        semantic_features: vec![format!("{} things", name)],
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: "Config/load".to_string(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// `cli::run` → `main::main` → `config::load`, plus `parse` and a file the
+/// diff deletes.
+fn fixture() -> (tempfile::TempDir, RPGraph) {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/config.rs"), CONFIG_RS).unwrap();
+
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(entity("src/config.rs:config", EntityKind::Module, (1, 12)));
+    graph.insert_entity(entity("src/config.rs:load", EntityKind::Function, (3, 6)));
+    graph.insert_entity(entity("src/config.rs:parse", EntityKind::Function, (8, 12)));
+    graph.insert_entity(entity("src/main.rs:main", EntityKind::Function, (1, 3)));
+    graph.insert_entity(entity("src/cli.rs:run", EntityKind::Function, (1, 3)));
+    graph.insert_entity(entity("src/old.rs:legacy", EntityKind::Function, (1, 3)));
+    for (source, target) in [
+        ("src/main.rs:main", "src/config.rs:load"),
+        ("src/cli.rs:run", "src/main.rs:main"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+        graph
+            .entities
+            .get_mut(target)
+            .unwrap()
+            .deps
+            .push_reverse(EdgeKind::Invokes, source.to_string());
+    }
+    graph.rebuild_edge_index();
+    (tmp, graph)
+}
+
+#[test]
+fn test_hunks_map_to_touched_entities() {
+    let (tmp, graph) = fixture();
+    let summary = summarize_patch(
+        &graph,
+        DIFF,
+        &WorktreeFiles::new(tmp.path()),
+        &PatchOptions::default(),
+    );
+
+    let touched: Vec<(&str, bool, bool)> = summary
+        .touched
+        .iter()
+        .map(|t| (t.entity_id.as_str(), t.signature_touched, t.deleted))
+        .collect();
+    assert_eq!(
+        touched,
+        vec![
+            ("src/config.rs:config", false, false),
+            ("src/config.rs:load", false, false),
+            ("src/config.rs:parse", true, false),
+            ("src/old.rs:legacy", true, true),
+        ]
+    );
+    let load = &summary.touched[1];
+    assert_eq!(load.fan_in, 1);
+    assert_eq!(load.hierarchy_path, "Config/load");
+    assert_eq!(load.features, vec!["load things".to_string()]);
+}
+
+#[test]
+fn test_new_deleted_and_unindexed_files_are_listed() {
+    let (tmp, graph) = fixture();
+    let summary = summarize_patch(
+        &graph,
+        DIFF,
+        &WorktreeFiles::new(tmp.path()),
+        &PatchOptions::default(),
+    );
+    assert_eq!(summary.new_files, vec!["src/new.rs".to_string()]);
+    assert_eq!(summary.deleted_files, vec!["src/old.rs".to_string()]);
+    assert_eq!(summary.unindexed_files, vec!["README.md".to_string()]);
+}
+
+#[test]
+fn test_blast_radius_walks_upstream_of_touched_set() {
+    let (tmp, graph) = fixture();
+    let files = WorktreeFiles::new(tmp.path());
+    let summary = summarize_patch(&graph, DIFF, &files, &PatchOptions::default());
+    let blast: Vec<(&str, usize)> = summary
+        .blast_radius
+        .iter()
+        .map(|e| (e.entity_id.as_str(), e.depth))
+        .collect();
+    assert_eq!(blast, vec![("src/main.rs:main", 1), ("src/cli.rs:run", 2)]);
+
+    let shallow = summarize_patch(
+        &graph,
+        DIFF,
+        &files,
+        &PatchOptions {
+            max_depth: 1,
+            ..PatchOptions::default()
+        },
+    );
+    assert_eq!(shallow.blast_radius.len(), 1);
+}
@@ -0,0 +1,159 @@
+use rpg_core::graph::*;
+use rpg_nav::queries::{self, SavedQuery};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn make_entity(id: &str, name: &str, file: &str) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// api -> service -> db (invokes)
+fn make_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (id, name, file) in [
+        ("api.rs:handler", "handler", "api.rs"),
+        ("service.rs:save_user", "save_user", "service.rs"),
+        ("db.rs:insert", "insert", "db.rs"),
+    ] {
+        graph.insert_entity(make_entity(id, name, file));
+    }
+    for (source, target) in [
+        ("api.rs:handler", "service.rs:save_user"),
+        ("service.rs:save_user", "db.rs:insert"),
+    ] {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+fn impact_query() -> SavedQuery {
+    let mut params = toml::Table::new();
+    params.insert("entity_id".into(), "db.rs:insert".into());
+    params.insert("direction".into(), "upstream".into());
+    params.insert("max_depth".into(), 3.into());
+    params.insert("edge_filter".into(), "invokes".into());
+    SavedQuery {
+        tool: "impact_radius".to_string(),
+        params,
+    }
+}
+
+#[test]
+fn test_save_list_and_run_round_trip() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    let query = impact_query();
+
+    queries::save(root, "db-dependents", &query).unwrap();
+    let all = queries::load_all(root).unwrap();
+    assert_eq!(all.keys().collect::<Vec<_>>(), vec!["db-dependents"]);
+    assert_eq!(all["db-dependents"], query);
+
+    let output = queries::run(
+        &make_graph(),
+        &queries::load(root, "db-dependents").unwrap(),
+    )
+    .unwrap();
+    assert!(output.contains("service.rs:save_user"), "{}", output);
+    assert!(output.contains("api.rs:handler"), "{}", output);
+}
+
+#[test]
+fn test_run_re_resolves_against_current_graph() {
+    let tmp = TempDir::new().unwrap();
+    queries::save(tmp.path(), "db-dependents", &impact_query()).unwrap();
+    let query = queries::load(tmp.path(), "db-dependents").unwrap();
+
+    let mut graph = make_graph();
+    graph.insert_entity(make_entity("cli.rs:import", "import", "cli.rs"));
+    graph.edges.push(DependencyEdge {
+        source: "cli.rs:import".to_string(),
+        target: "db.rs:insert".to_string(),
+        kind: EdgeKind::Invokes,
+    });
+    graph.rebuild_edge_index();
+
+    let output = queries::run(&graph, &query).unwrap();
+    assert!(output.contains("cli.rs:import"), "{}", output);
+}
+
+#[test]
+fn test_last_query_can_be_saved() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    assert!(queries::load_last(root).unwrap().is_none());
+
+    let mut params = toml::Table::new();
+    params.insert("entity_id".into(), "api.rs:handler".into());
+    params.insert("direction".into(), "down".into());
+    params.insert("depth".into(), 2.into());
+    let last = SavedQuery {
+        tool: "explore_rpg".to_string(),
+        params,
+    };
+    queries::record_last(root, &last).unwrap();
+
+    let recorded = queries::load_last(root).unwrap().unwrap();
+    assert_eq!(recorded, last);
+    queries::save(root, "handler-calls", &recorded).unwrap();
+
+    let output = queries::run(
+        &make_graph(),
+        &queries::load(root, "handler-calls").unwrap(),
+    )
+    .unwrap();
+    assert!(output.starts_with("handler [api.rs]"), "{}", output);
+    assert!(output.contains("db.rs"), "{}", output);
+}
+
+#[test]
+fn test_rejects_unknown_names_and_tools() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path();
+    queries::save(root, "db-dependents", &impact_query()).unwrap();
+
+    let err = queries::load(root, "missing").unwrap_err().to_string();
+    assert!(err.contains("db-dependents"), "{}", err);
+
+    let search = SavedQuery {
+        tool: "search_node".to_string(),
+        params: toml::Table::new(),
+    };
+    assert!(queries::save(root, "s", &search).is_err());
+    assert!(queries::save(root, "bad name", &impact_query()).is_err());
+
+    assert!(queries::remove(root, "db-dependents").unwrap());
+    assert!(!queries::remove(root, "db-dependents").unwrap());
+}
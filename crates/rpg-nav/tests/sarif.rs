@@ -0,0 +1,223 @@
+use rpg_core::graph::*;
+use rpg_nav::export::sarif::{self, Finding, HEALTH_RULES, Level, Location, Rule};
+use rpg_nav::health::{HealthConfig, compute_health};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+fn make_entity(id: &str, file: &str, lines: (usize, usize)) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: id.rsplit(':').next().unwrap().to_string(),
+        file: PathBuf::from(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        parent_class: None,
+        semantic_features: Vec::new(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// `src/app.rs:run` calls two helpers; nothing calls it.
+fn make_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity("src/app.rs:run", "src/app.rs", (3, 12)));
+    graph.insert_entity(make_entity("src/db.rs:load", "src/db.rs", (1, 4)));
+    graph.insert_entity(make_entity("src/db.rs:save", "src/db.rs", (6, 9)));
+    for target in ["src/db.rs:load", "src/db.rs:save"] {
+        graph.edges.push(DependencyEdge {
+            source: "src/app.rs:run".to_string(),
+            target: target.to_string(),
+            kind: EdgeKind::Invokes,
+        });
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+/// Structural checks for the parts of the SARIF 2.1.0 schema we emit.
+fn assert_valid_sarif(doc: &Value) {
+    assert_eq!(doc["version"], "2.1.0");
+    assert_eq!(doc["$schema"], sarif::SARIF_SCHEMA);
+    let runs = doc["runs"].as_array().expect("runs must be an array");
+    assert!(!runs.is_empty());
+    for run in runs {
+        let driver = &run["tool"]["driver"];
+        assert!(driver["name"].as_str().is_some_and(|n| !n.is_empty()));
+        let rules = driver["rules"].as_array().expect("rules must be an array");
+        for rule in rules {
+            assert!(rule["id"].is_string());
+            assert!(rule["shortDescription"]["text"].is_string());
+        }
+        for result in run["results"].as_array().expect("results must be an array") {
+            assert!(result["message"]["text"].is_string(), "{}", result);
+            assert!(
+                matches!(
+                    result["level"].as_str(),
+                    Some("none" | "note" | "warning" | "error")
+                ),
+                "{}",
+                result
+            );
+            if let Some(index) = result["ruleIndex"].as_u64() {
+                assert_eq!(rules[index as usize]["id"], result["ruleId"]);
+            }
+            for location in result["locations"].as_array().into_iter().flatten() {
+                let physical = &location["physicalLocation"];
+                assert!(physical["artifactLocation"]["uri"].is_string());
+                if let Some(region) = physical.get("region") {
+                    let start = region["startLine"].as_u64().unwrap();
+                    assert!(start >= 1, "{}", region);
+                    if let Some(end) = region["endLine"].as_u64() {
+                        assert!(end >= start, "{}", region);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_health_findings_match_expected_document() {
+    let graph = make_graph();
+    let config = HealthConfig {
+        hub_threshold: 2,
+        ..HealthConfig::default()
+    };
+    let report = compute_health(&graph, &config);
+    let findings = sarif::health_findings(&graph, &report);
+    let doc: Value = serde_json::from_str(&sarif::to_sarif(HEALTH_RULES, &findings)).unwrap();
+    assert_valid_sarif(&doc);
+
+    let rules: Vec<Value> = HEALTH_RULES
+        .iter()
+        .map(|r| {
+            json!({
+                "id": r.id,
+                "shortDescription": { "text": r.description },
+                "defaultConfiguration": { "level": r.level },
+            })
+        })
+        .collect();
+    let location = |uri: &str, start: u64, end: u64| {
+        json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                "region": { "startLine": start, "endLine": end }
+            }
+        }])
+    };
+    let expected = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rpg-encoder",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/userFRM/rpg-encoder",
+                    "rules": rules,
+                }
+            },
+            "results": [
+                {
+                    "ruleId": "health/highly-unstable",
+                    "ruleIndex": 1,
+                    "level": "note",
+                    "message": { "text": "src/app.rs:run is highly unstable (instability 1.00, 2 outgoing dependencies)" },
+                    "locations": location("src/app.rs", 3, 12),
+                },
+                {
+                    "ruleId": "health/hub-entity",
+                    "ruleIndex": 3,
+                    "level": "note",
+                    "message": { "text": "src/app.rs:run is a hub (degree 2)" },
+                    "locations": location("src/app.rs", 3, 12),
+                },
+                {
+                    "ruleId": "health/highly-stable",
+                    "ruleIndex": 2,
+                    "level": "note",
+                    "message": { "text": "src/db.rs:load is highly stable (instability 0.00, 1 dependents)" },
+                    "locations": location("src/db.rs", 1, 4),
+                },
+                {
+                    "ruleId": "health/highly-stable",
+                    "ruleIndex": 2,
+                    "level": "note",
+                    "message": { "text": "src/db.rs:save is highly stable (instability 0.00, 1 dependents)" },
+                    "locations": location("src/db.rs", 6, 9),
+                },
+            ]
+        }]
+    });
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn test_findings_without_region_or_location() {
+    const RULE: Rule = Rule {
+        id: "integrity/test",
+        description: "Test rule",
+        level: Level::Warning,
+    };
+    let findings = vec![
+        Finding::new(
+            &RULE,
+            "whole file",
+            Some(Location::file(Path::new("src/lib.rs"))),
+        ),
+        Finding::new(&RULE, "nowhere", None),
+    ];
+    let doc: Value = serde_json::from_str(&sarif::to_sarif(&[RULE], &findings)).unwrap();
+    assert_valid_sarif(&doc);
+
+    let results = doc["runs"][0]["results"].as_array().unwrap();
+    let physical = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(physical["artifactLocation"]["uri"], "src/lib.rs");
+    assert!(physical.get("region").is_none());
+    assert!(results[1].get("locations").is_none());
+    assert_eq!(results[1]["level"], "warning");
+}
+
+#[test]
+fn test_clean_run_still_lists_rules() {
+    let doc: Value = serde_json::from_str(&sarif::to_sarif(HEALTH_RULES, &[])).unwrap();
+    assert_valid_sarif(&doc);
+    assert_eq!(doc["runs"][0]["results"], json!([]));
+    assert_eq!(
+        doc["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .len(),
+        HEALTH_RULES.len()
+    );
+}
+
+#[test]
+fn test_entity_location_clamps_line_range() {
+    let entity = make_entity("src/a.rs:f", "src/a.rs", (7, 5));
+    let location = Location::entity(&entity);
+    assert_eq!(location.line_start, Some(7));
+    assert_eq!(location.line_end, Some(7));
+
+    let unknown = make_entity("src/a.rs:g", "src/a.rs", (0, 0));
+    assert_eq!(Location::entity(&unknown).line_start, None);
+}
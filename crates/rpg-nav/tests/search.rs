@@ -1,5 +1,9 @@
 use rpg_core::graph::*;
-use rpg_nav::search::{SearchMode, SearchParams, search, search_with_params};
+use rpg_nav::search::{
+    MAX_FACET_FILES, MAX_NEIGHBOR_PREVIEWS, SearchMode, SearchParams, SearchSort,
+    neighbor_previews, search, search_with_facets, search_with_params,
+};
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 
 fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy: &str) -> Entity {
@@ -13,9 +17,22 @@ fn make_entity(id: &str, name: &str, file: &str, features: Vec<&str>, hierarchy:
         parent_class: None,
         semantic_features: features.into_iter().map(String::from).collect(),
         feature_source: None,
+        lifted_at: None,
         hierarchy_path: hierarchy.to_string(),
         deps: EntityDeps::default(),
         signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
     }
 }
 
@@ -62,7 +79,7 @@ fn make_graph() -> RPGraph {
 #[test]
 fn test_feature_search_single_match() {
     let graph = make_graph();
-    let results = search(&graph, "JWT", SearchMode::Features, None, 10);
+    let results = search(&graph, "JWT", SearchMode::Features, None, 10).unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "validate_token");
 }
@@ -70,7 +87,7 @@ fn test_feature_search_single_match() {
 #[test]
 fn test_feature_search_multiple_matches() {
     let graph = make_graph();
-    let results = search(&graph, "authentication", SearchMode::Features, None, 10);
+    let results = search(&graph, "authentication", SearchMode::Features, None, 10).unwrap();
     assert!(results.len() >= 2);
     let names: Vec<&str> = results.iter().map(|r| r.entity_name.as_str()).collect();
     assert!(names.contains(&"validate_token"));
@@ -86,14 +103,15 @@ fn test_feature_search_no_match() {
         SearchMode::Features,
         None,
         10,
-    );
+    )
+    .unwrap();
     assert!(results.is_empty());
 }
 
 #[test]
 fn test_snippet_search_by_name() {
     let graph = make_graph();
-    let results = search(&graph, "parse_config", SearchMode::Snippets, None, 10);
+    let results = search(&graph, "parse_config", SearchMode::Snippets, None, 10).unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "parse_config");
 }
@@ -101,7 +119,7 @@ fn test_snippet_search_by_name() {
 #[test]
 fn test_snippet_search_by_file() {
     let graph = make_graph();
-    let results = search(&graph, "db.rs", SearchMode::Snippets, None, 10);
+    let results = search(&graph, "db.rs", SearchMode::Snippets, None, 10).unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "query_users");
 }
@@ -109,7 +127,7 @@ fn test_snippet_search_by_file() {
 #[test]
 fn test_auto_mode_uses_features_first() {
     let graph = make_graph();
-    let results = search(&graph, "database query", SearchMode::Auto, None, 10);
+    let results = search(&graph, "database query", SearchMode::Auto, None, 10).unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "query_users");
     assert!(!results[0].matched_features.is_empty());
@@ -119,7 +137,7 @@ fn test_auto_mode_uses_features_first() {
 fn test_auto_mode_falls_back_to_snippets() {
     let graph = make_graph();
     // "handle_login" won't match any feature exactly but matches entity name
-    let results = search(&graph, "handle_login", SearchMode::Auto, None, 10);
+    let results = search(&graph, "handle_login", SearchMode::Auto, None, 10).unwrap();
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "handle_login");
 }
@@ -133,7 +151,8 @@ fn test_scope_filtering() {
         SearchMode::Features,
         Some("Security"),
         10,
-    );
+    )
+    .unwrap();
     // Should only find entities under Security hierarchy
     for r in &results {
         assert!(
@@ -153,7 +172,8 @@ fn test_scope_filtering_narrow() {
         SearchMode::Features,
         Some("Security/auth/token"),
         10,
-    );
+    )
+    .unwrap();
     // Only validate_token is in Security/auth/token
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].entity_name, "validate_token");
@@ -162,14 +182,14 @@ fn test_scope_filtering_narrow() {
 #[test]
 fn test_empty_query() {
     let graph = make_graph();
-    let results = search(&graph, "", SearchMode::Features, None, 10);
+    let results = search(&graph, "", SearchMode::Features, None, 10).unwrap();
     assert!(results.is_empty());
 }
 
 #[test]
 fn test_results_sorted_by_score() {
     let graph = make_graph();
-    let results = search(&graph, "authentication", SearchMode::Features, None, 10);
+    let results = search(&graph, "authentication", SearchMode::Features, None, 10).unwrap();
     if results.len() >= 2 {
         for i in 0..results.len() - 1 {
             assert!(results[i].score >= results[i + 1].score);
@@ -189,7 +209,8 @@ fn test_multi_scope_comma_separated() {
         SearchMode::Features,
         Some("Security/auth/token, DataAccess"),
         10,
-    );
+    )
+    .unwrap();
     // Should find entities from both scopes
     // validate_token is in Security/auth/token, query_users is in DataAccess
     // Both should be eligible (actual presence depends on query match)
@@ -212,21 +233,24 @@ fn test_multi_scope_union_not_intersection() {
         SearchMode::Features,
         Some("Security/auth/token"),
         10,
-    );
+    )
+    .unwrap();
     let results_narrow2 = search(
         &graph,
         "authentication",
         SearchMode::Features,
         Some("Security/auth/login"),
         10,
-    );
+    )
+    .unwrap();
     let results_combined = search(
         &graph,
         "authentication",
         SearchMode::Features,
         Some("Security/auth/token, Security/auth/login"),
         10,
-    );
+    )
+    .unwrap();
     // Combined should have at least as many results as each individual
     assert!(results_combined.len() >= results_narrow1.len());
     assert!(results_combined.len() >= results_narrow2.len());
@@ -247,10 +271,20 @@ fn test_search_with_file_pattern() {
             line_nums: None,
             file_pattern: Some("auth*"),
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores: None,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
         },
-    );
+    )
+    .unwrap();
     // Only auth.rs matches the pattern "auth*"
     assert!(!results.is_empty());
     for r in &results {
@@ -295,10 +329,20 @@ fn test_search_with_line_nums() {
             line_nums: Some((40, 70)),
             file_pattern: None,
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores: None,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
         },
-    );
+    )
+    .unwrap();
     // Only "late" should match (lines 50-60 overlaps 40-70)
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].entity_name, "late");
@@ -336,10 +380,20 @@ fn test_search_params_combined() {
             line_nums: Some((1, 20)),
             file_pattern: Some("auth*"),
             entity_type_filter: None,
+            layer_filter: None,
             embedding_scores: None,
             diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
         },
-    );
+    )
+    .unwrap();
     assert_eq!(results.len(), 1);
     assert_eq!(results[0].entity_id, "auth.rs:validate");
 }
@@ -354,7 +408,8 @@ fn test_multi_scope_with_invalid_segment() {
         SearchMode::Features,
         Some("Security/auth/token, NonExistent/bogus/path"),
         10,
-    );
+    )
+    .unwrap();
     // Should still find validate_token from the valid scope
     assert!(!results.is_empty());
     assert_eq!(results[0].entity_name, "validate_token");
@@ -363,14 +418,15 @@ fn test_multi_scope_with_invalid_segment() {
 #[test]
 fn test_root_scope_matches_unscoped_search() {
     let graph = make_graph();
-    let unscoped = search(&graph, "authentication", SearchMode::Features, None, 10);
+    let unscoped = search(&graph, "authentication", SearchMode::Features, None, 10).unwrap();
     let root_scoped = search(
         &graph,
         "authentication",
         SearchMode::Features,
         Some("."),
         10,
-    );
+    )
+    .unwrap();
 
     let unscoped_ids: Vec<&str> = unscoped.iter().map(|r| r.entity_id.as_str()).collect();
     let root_ids: Vec<&str> = root_scoped.iter().map(|r| r.entity_id.as_str()).collect();
@@ -390,14 +446,16 @@ fn test_multi_scope_with_empty_segment_does_not_widen() {
         SearchMode::Features,
         Some("Security/auth/token,"),
         10,
-    );
+    )
+    .unwrap();
     let expected = search(
         &graph,
         "authentication",
         SearchMode::Features,
         Some("Security/auth/token"),
         10,
-    );
+    )
+    .unwrap();
 
     let scoped_ids: Vec<&str> = scoped.iter().map(|r| r.entity_id.as_str()).collect();
     let expected_ids: Vec<&str> = expected.iter().map(|r| r.entity_id.as_str()).collect();
@@ -418,7 +476,8 @@ fn test_multi_scope_dedup_overlapping() {
         SearchMode::Features,
         Some("Security, Security/auth"),
         10,
-    );
+    )
+    .unwrap();
     // Should not have duplicate entity IDs in results
     let ids: Vec<&str> = results.iter().map(|r| r.entity_id.as_str()).collect();
     let unique_ids: std::collections::HashSet<&str> = ids.iter().copied().collect();
@@ -428,3 +487,452 @@ fn test_multi_scope_dedup_overlapping() {
         "results should contain no duplicate entity IDs"
     );
 }
+
+// --- Scope expression tests ---
+
+fn scoped_ids(graph: &RPGraph, query: &str, scope: &str) -> Vec<String> {
+    let mut ids: Vec<String> = search_with_params(
+        graph,
+        &SearchParams {
+            query,
+            mode: SearchMode::Features,
+            scope: Some(scope),
+            limit: 10,
+            line_nums: None,
+            file_pattern: None,
+            entity_type_filter: None,
+            layer_filter: None,
+            embedding_scores: None,
+            diff_context: None,
+            changed_entities: None,
+            vocabulary: None,
+            sort: SearchSort::Relevance,
+            include_fixtures: false,
+            exclude_tests: false,
+            async_only: false,
+            deadline: None,
+            group_by_file: false,
+        },
+    )
+    .unwrap()
+    .into_iter()
+    .map(|r| r.entity_id)
+    .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn test_scope_expression_combines_area_and_glob() {
+    let graph = make_graph();
+    assert_eq!(
+        scoped_ids(
+            &graph,
+            "authentication",
+            "area:Security AND NOT glob:api.rs"
+        ),
+        vec!["auth.rs:validate_token"]
+    );
+    assert_eq!(
+        scoped_ids(
+            &graph,
+            "authentication",
+            "area:Security/auth AND kind:function"
+        ),
+        vec!["api.rs:handle_login", "auth.rs:validate_token"]
+    );
+}
+
+#[test]
+fn test_scope_expression_or_across_areas() {
+    let graph = make_graph();
+    let ids = scoped_ids(
+        &graph,
+        "database query configuration parsing",
+        "area:DataAccess OR area:Core",
+    );
+    assert_eq!(ids, vec!["db.rs:query_users", "utils.rs:parse_config"]);
+}
+
+#[test]
+fn test_scope_expression_kind_excludes_everything() {
+    let graph = make_graph();
+    assert!(scoped_ids(&graph, "authentication", "area:Security AND kind:class").is_empty());
+}
+
+#[test]
+fn test_malformed_scope_expression_is_an_error() {
+    let graph = make_graph();
+    let err = search(
+        &graph,
+        "authentication",
+        SearchMode::Features,
+        Some("area:Security AND"),
+        10,
+    )
+    .unwrap_err();
+    assert_eq!(err.position, "area:Security AND".len());
+    assert!(rpg_nav::scope::validate("area:Security AND").is_err());
+}
+
+/// 30 "cache" entities spread over kinds, areas, and files, plus one non-match.
+fn make_facet_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for i in 0..30 {
+        let area = if i % 3 == 0 { "Storage" } else { "Core" };
+        let mut entity = make_entity(
+            &format!("src/f{}.rs:cache_{}", i % 7, i),
+            &format!("cache_{}", i),
+            &format!("src/f{}.rs", i % 7),
+            vec!["cache lookup"],
+            &format!("{}/cache/lookup", area),
+        );
+        if i % 5 == 0 {
+            entity.kind = EntityKind::Class;
+        }
+        graph.insert_entity(entity);
+    }
+    graph.insert_entity(make_entity(
+        "src/other.rs:parse",
+        "parse",
+        "src/other.rs",
+        vec!["parse input"],
+        "Core/input/parse",
+    ));
+    graph
+}
+
+fn facet_params(query: &str, mode: SearchMode, limit: usize) -> SearchParams<'_> {
+    SearchParams {
+        query,
+        mode,
+        scope: None,
+        limit,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        layer_filter: None,
+        embedding_scores: None,
+        diff_context: None,
+        changed_entities: None,
+        vocabulary: None,
+        sort: SearchSort::Relevance,
+        include_fixtures: false,
+        exclude_tests: false,
+        async_only: false,
+        deadline: None,
+        group_by_file: false,
+    }
+}
+
+#[test]
+fn test_facets_count_untruncated_matches() {
+    let graph = make_facet_graph();
+    for mode in [SearchMode::Features, SearchMode::Snippets, SearchMode::Auto] {
+        let untruncated = search_with_params(&graph, &facet_params("cache", mode, 1000))
+            .unwrap()
+            .len();
+        let outcome = search_with_facets(&graph, &facet_params("cache", mode, 5)).unwrap();
+
+        assert_eq!(outcome.results.len(), 5);
+        assert_eq!(outcome.facets.total_matches, untruncated, "{:?}", mode);
+        let kind_total: usize = outcome.facets.kinds.iter().map(|f| f.count).sum();
+        let area_total: usize = outcome.facets.areas.iter().map(|f| f.count).sum();
+        assert_eq!(kind_total, untruncated);
+        assert_eq!(area_total, untruncated);
+    }
+}
+
+#[test]
+fn test_facet_breakdown() {
+    let graph = make_facet_graph();
+    let outcome =
+        search_with_facets(&graph, &facet_params("cache", SearchMode::Features, 5)).unwrap();
+    let facets = &outcome.facets;
+
+    assert_eq!(facets.total_matches, 30);
+    let kinds: Vec<(&str, usize)> = facets
+        .kinds
+        .iter()
+        .map(|f| (f.value.as_str(), f.count))
+        .collect();
+    assert_eq!(kinds, vec![("function", 24), ("class", 6)]);
+    let areas: Vec<(&str, usize)> = facets
+        .areas
+        .iter()
+        .map(|f| (f.value.as_str(), f.count))
+        .collect();
+    assert_eq!(areas, vec![("Core", 20), ("Storage", 10)]);
+    assert_eq!(facets.files.len(), MAX_FACET_FILES);
+    assert!(facets.files.windows(2).all(|w| w[0].count >= w[1].count));
+}
+
+#[test]
+fn test_facets_rendered_at_top() {
+    let graph = make_facet_graph();
+    let outcome =
+        search_with_facets(&graph, &facet_params("cache", SearchMode::Features, 5)).unwrap();
+    let output = rpg_nav::toon::format_search_results(&outcome.results, Some(&outcome.facets));
+
+    let facets_at = output.find("facets").expect("facet block missing");
+    assert!(facets_at < output.find("results").unwrap());
+    assert!(output.contains("total_matches: 30"));
+    assert!(output.contains("function=24, class=6"));
+    assert!(output.contains("Core=20, Storage=10"));
+
+    let without = rpg_nav::toon::format_search_results(&outcome.results, None);
+    assert!(!without.contains("facets"));
+}
+
+fn make_neighbor_graph() -> RPGraph {
+    let mut graph = make_graph();
+    graph.insert_entity(make_entity(
+        "auth.rs:decode_claims",
+        "decode_claims",
+        "auth.rs",
+        vec!["decode JWT claims"],
+        "Security/auth/token",
+    ));
+    graph.insert_entity(make_entity(
+        "tests.rs:test_validate",
+        "test_validate",
+        "tests.rs",
+        vec![],
+        "Testing/auth",
+    ));
+    let edges = [
+        (
+            "auth.rs:validate_token",
+            "utils.rs:parse_config",
+            EdgeKind::Imports,
+        ),
+        (
+            "tests.rs:test_validate",
+            "auth.rs:validate_token",
+            EdgeKind::Tests,
+        ),
+        (
+            "api.rs:handle_login",
+            "auth.rs:validate_token",
+            EdgeKind::Invokes,
+        ),
+        (
+            "auth.rs:validate_token",
+            "auth.rs:decode_claims",
+            EdgeKind::Invokes,
+        ),
+        (
+            "auth.rs:validate_token",
+            "auth.rs:decode_claims",
+            EdgeKind::DataFlow,
+        ),
+        (
+            "auth.rs:validate_token",
+            "db.rs:query_users",
+            EdgeKind::ReadsState,
+        ),
+    ];
+    for (source, target, kind) in edges {
+        graph.edges.push(DependencyEdge {
+            source: source.to_string(),
+            target: target.to_string(),
+            kind,
+        });
+    }
+    graph.rebuild_edge_index();
+    graph
+}
+
+#[test]
+fn test_neighbor_previews_rank_and_cap() {
+    let graph = make_neighbor_graph();
+    let previews = neighbor_previews(&graph, "auth.rs:validate_token", MAX_NEIGHBOR_PREVIEWS);
+    let summary: Vec<(&str, &str, &str)> = previews
+        .iter()
+        .map(|n| (n.entity_id.as_str(), n.edge.as_str(), n.feature.as_str()))
+        .collect();
+    // Calls first (dependencies before dependents), each neighbor once; the
+    // import and the test fall past the cap.
+    assert_eq!(
+        summary,
+        vec![
+            ("auth.rs:decode_claims", "invokes", "decode JWT claims"),
+            ("api.rs:handle_login", "<-invokes", "login endpoint"),
+            ("db.rs:query_users", "readsstate", "database query"),
+        ]
+    );
+
+    let all = neighbor_previews(&graph, "auth.rs:validate_token", usize::MAX);
+    assert_eq!(all.len(), 5);
+    assert_eq!(all[4].entity_id, "tests.rs:test_validate");
+    assert_eq!(all[4].feature, "");
+}
+
+#[test]
+fn test_neighbors_rendered_under_results() {
+    let graph = make_neighbor_graph();
+    let mut results = search(&graph, "token validation", SearchMode::Features, None, 1).unwrap();
+    let output = rpg_nav::toon::format_search_results(&results, None);
+    assert!(!output.contains("neighbors"));
+
+    for result in &mut results {
+        result.neighbors = neighbor_previews(&graph, &result.entity_id, MAX_NEIGHBOR_PREVIEWS);
+    }
+    let output = rpg_nav::toon::format_search_results(&results, None);
+    assert!(
+        output.contains("neighbors[3|]{id|edge|kind|feature}:"),
+        "{}",
+        output
+    );
+    assert!(output.contains("\"auth.rs:decode_claims\"|invokes|function|decode JWT claims"));
+    assert!(output.contains("\"api.rs:handle_login\"|\"<-invokes\"|function|login endpoint"));
+    assert!(!output.contains("tests.rs:test_validate"));
+}
+
+#[test]
+fn test_async_only_keeps_async_functions() {
+    let mut graph = make_facet_graph();
+    graph
+        .entities
+        .get_mut("src/other.rs:parse")
+        .unwrap()
+        .is_async = true;
+
+    let mut params = facet_params("parse", SearchMode::Auto, 10);
+    assert!(!search_with_params(&graph, &params).unwrap().is_empty());
+    params.async_only = true;
+    let ids: Vec<String> = search_with_params(&graph, &params)
+        .unwrap()
+        .into_iter()
+        .map(|r| r.entity_id)
+        .collect();
+    assert_eq!(ids, vec!["src/other.rs:parse".to_string()]);
+
+    let mut params = facet_params("cache", SearchMode::Auto, 10);
+    params.async_only = true;
+    assert!(search_with_params(&graph, &params).unwrap().is_empty());
+}
+
+#[test]
+fn test_entry_distance_sort_puts_unreachable_last() {
+    let mut graph = make_facet_graph();
+    let ids: Vec<String> = graph.entities.keys().cloned().collect();
+    for (i, id) in ids.iter().enumerate() {
+        let entity = graph.entities.get_mut(id).unwrap();
+        // Every third entity is unreachable; the rest count down.
+        entity.entry_distance = (i % 3 != 0).then(|| (ids.len() - i) as u32);
+    }
+
+    let mut params = facet_params("cache", SearchMode::Features, 100);
+    params.sort = SearchSort::parse("entry_distance").unwrap();
+    let distances: Vec<Option<u32>> = search_with_params(&graph, &params)
+        .unwrap()
+        .iter()
+        .map(|r| graph.entities[&r.entity_id].entry_distance)
+        .collect();
+    let reachable = distances.iter().take_while(|d| d.is_some()).count();
+    assert!(reachable > 0 && reachable < distances.len());
+    assert!(distances[reachable..].iter().all(Option::is_none));
+    assert!(distances[..reachable].windows(2).all(|w| w[0] <= w[1]));
+}
+
+/// `src/session.rs` has a Module entity and two members matching "session
+/// cache"; `src/store.rs` has a Module whose member outscores it.
+fn make_module_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    let mut module = make_entity(
+        "src/session.rs",
+        "session",
+        "src/session.rs",
+        vec!["session cache management", "session expiry"],
+        "Core/session",
+    );
+    module.kind = EntityKind::Module;
+    graph.insert_entity(module);
+    let mut get = make_entity(
+        "src/session.rs:get",
+        "get",
+        "src/session.rs",
+        vec!["read session cache"],
+        "Core/session",
+    );
+    get.line_start = 12;
+    graph.insert_entity(get);
+    graph.insert_entity(make_entity(
+        "src/session.rs:evict",
+        "evict",
+        "src/session.rs",
+        vec!["evict stale session"],
+        "Core/session",
+    ));
+    let mut store = make_entity(
+        "src/store.rs",
+        "store",
+        "src/store.rs",
+        vec!["key value store"],
+        "Core/store",
+    );
+    store.kind = EntityKind::Module;
+    graph.insert_entity(store);
+    graph.insert_entity(make_entity(
+        "src/store.rs:cache_session",
+        "cache_session",
+        "src/store.rs",
+        vec!["session cache write", "store session cache entry"],
+        "Core/store",
+    ));
+    graph
+}
+
+#[test]
+fn test_group_by_file_nests_members_under_module() {
+    let graph = make_module_graph();
+    let mut params = facet_params("session cache", SearchMode::Features, 10);
+    params.group_by_file = true;
+    let results = search_with_params(&graph, &params).unwrap();
+
+    let ids: Vec<&str> = results.iter().map(|r| r.entity_id.as_str()).collect();
+    assert!(ids.contains(&"src/session.rs"), "{:?}", ids);
+    assert!(!ids.contains(&"src/session.rs:get"), "{:?}", ids);
+    let module = results
+        .iter()
+        .find(|r| r.entity_id == "src/session.rs")
+        .unwrap();
+    let members: Vec<&str> = module
+        .members
+        .iter()
+        .map(|m| m.entity_id.as_str())
+        .collect();
+    assert_eq!(members, vec!["src/session.rs:get", "src/session.rs:evict"]);
+    assert!(module.members.iter().all(|m| m.score <= module.score));
+
+    // The store member beats its Module, so the Module is dropped.
+    assert!(ids.contains(&"src/store.rs:cache_session"), "{:?}", ids);
+    assert!(!ids.contains(&"src/store.rs"), "{:?}", ids);
+}
+
+#[test]
+fn test_group_by_file_off_lists_every_match() {
+    let graph = make_module_graph();
+    let params = facet_params("session cache", SearchMode::Features, 10);
+    let results = search_with_params(&graph, &params).unwrap();
+    assert!(results.iter().all(|r| r.members.is_empty()));
+    assert!(results.iter().any(|r| r.entity_id == "src/session.rs:get"));
+    // search() groups in features mode.
+    let grouped = search(&graph, "session cache", SearchMode::Features, None, 10).unwrap();
+    assert!(grouped.iter().any(|r| !r.members.is_empty()));
+}
+
+#[test]
+fn test_grouped_members_rendered_with_scores() {
+    let graph = make_module_graph();
+    let results = search(&graph, "session cache", SearchMode::Features, None, 10).unwrap();
+    let output = rpg_nav::toon::format_search_results(&results, None);
+    assert!(output.contains("members"), "{}", output);
+    let member_line = output
+        .lines()
+        .find(|l| l.contains("src/session.rs:get"))
+        .unwrap();
+    assert!(member_line.starts_with("    "), "{:?}", member_line);
+    assert!(member_line.contains("12"), "{:?}", member_line);
+}
@@ -0,0 +1,184 @@
+use rpg_core::graph::*;
+use rpg_nav::symbols::{Visibility, document_symbols, format_outline};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+const SHAPES_PY: &str = r#"import os
+
+class Shape:
+    """Base shape."""
+
+    def area(self):
+        return 0
+
+    def _scale(self, k):
+        return k
+
+    class Meta:
+        def describe(self):
+            return "shape"
+
+@register
+def make_shape(kind):
+    return Shape()
+
+def _helper():
+    pass
+"#;
+
+fn make_entity(
+    file: &str,
+    name: &str,
+    kind: EntityKind,
+    lines: (usize, usize),
+    features: &[&str],
+) -> Entity {
+    Entity {
+        id: format!("{}:{}", file, name),
+        kind,
+        name: name.to_string(),
+        file: PathBuf::from(file),
+        line_start: lines.0,
+        line_end: lines.1,
+        parent_class: None,
+        semantic_features: features.iter().map(|s| s.to_string()).collect(),
+        feature_source: (!features.is_empty()).then(|| "llm".to_string()),
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// `shapes.py` with a nested class, inserted out of line order.
+fn shapes_graph() -> RPGraph {
+    let file = "src/shapes.py";
+    let mut graph = RPGraph::new("python");
+    for entity in [
+        make_entity(file, "_helper", EntityKind::Function, (20, 21), &[]),
+        make_entity(file, "describe", EntityKind::Method, (13, 14), &[]),
+        make_entity(
+            file,
+            "make_shape",
+            EntityKind::Function,
+            (16, 18),
+            &["construct shape by kind", "register shape factory"],
+        ),
+        make_entity(file, "Meta", EntityKind::Class, (12, 14), &[]),
+        make_entity(file, "area", EntityKind::Method, (6, 7), &["compute area"]),
+        make_entity(file, "_scale", EntityKind::Method, (9, 10), &[]),
+        make_entity(
+            file,
+            "Shape",
+            EntityKind::Class,
+            (3, 14),
+            &["model geometric shape"],
+        ),
+    ] {
+        graph.insert_entity(entity);
+    }
+    graph.create_module_entities();
+    graph
+}
+
+#[test]
+fn test_outline_nests_classes_and_orders_by_line() {
+    let graph = shapes_graph();
+    let symbols = document_symbols(&graph, "./src/shapes.py", Some(SHAPES_PY)).unwrap();
+    assert_eq!(
+        format_outline("src/shapes.py", &symbols),
+        "\
+src/shapes.py (8 symbols, 3/8 lifted)
+module shapes L1-21 public [unlifted]
+  class Shape L3-14 public [lifted] — model geometric shape
+    method area L6-7 public [lifted] — compute area
+    method _scale L9-10 private [unlifted]
+    class Meta L12-14 public [unlifted]
+      method describe L13-14 public [unlifted]
+  function make_shape L16-18 public [lifted] — construct shape by kind, register shape factory
+  function _helper L20-21 private [unlifted]
+"
+    );
+}
+
+#[test]
+fn test_json_mirrors_lsp_document_symbol() {
+    let graph = shapes_graph();
+    let symbols = document_symbols(&graph, "src/shapes.py", Some(SHAPES_PY)).unwrap();
+    let json = serde_json::to_value(&symbols).unwrap();
+
+    let module = &json[0];
+    assert_eq!(module["name"], "shapes");
+    assert_eq!(module["kind"], 2);
+    let shape = &module["children"][0];
+    assert_eq!(shape["kind"], 5);
+    assert_eq!(shape["detail"], "model geometric shape");
+    assert_eq!(
+        shape["range"],
+        serde_json::json!({"start": {"line": 2, "character": 0}, "end": {"line": 14, "character": 0}})
+    );
+    assert_eq!(
+        shape["selectionRange"],
+        serde_json::json!({"start": {"line": 2, "character": 6}, "end": {"line": 2, "character": 11}})
+    );
+    assert_eq!(shape["rpgKind"], "class");
+    assert_eq!(shape["entityId"], "src/shapes.py:Shape");
+    assert_eq!(shape["children"][2]["children"][0]["kind"], 6);
+
+    // The decorator line is skipped when locating the declaration.
+    let factory = &module["children"][1];
+    assert_eq!(factory["kind"], 12);
+    assert_eq!(factory["selectionRange"]["start"]["line"], 16);
+    assert_eq!(factory["lifted"], true);
+    assert!(module["children"][2].get("detail").is_none());
+    assert!(module["children"][2].get("children").is_none());
+}
+
+#[test]
+fn test_visibility_from_declaration_keywords() {
+    let file = "src/lib.rs";
+    let source = "pub fn open() {}\nfn close() {}\npub(crate) fn flush() {}\n";
+    let mut graph = RPGraph::new("rust");
+    graph.insert_entity(make_entity(file, "open", EntityKind::Function, (1, 1), &[]));
+    graph.insert_entity(make_entity(
+        file,
+        "close",
+        EntityKind::Function,
+        (2, 2),
+        &[],
+    ));
+    graph.insert_entity(make_entity(
+        file,
+        "flush",
+        EntityKind::Function,
+        (3, 3),
+        &[],
+    ));
+
+    let visibility = |source: Option<&str>| -> Vec<Visibility> {
+        document_symbols(&graph, file, source)
+            .unwrap()
+            .iter()
+            .map(|s| s.visibility)
+            .collect()
+    };
+    assert_eq!(
+        visibility(Some(source)),
+        vec![Visibility::Public, Visibility::Private, Visibility::Public]
+    );
+    // Without the source there is no declaration to read.
+    assert_eq!(visibility(None), vec![Visibility::Unknown; 3]);
+    assert!(document_symbols(&graph, "src/missing.rs", None).is_none());
+}
@@ -0,0 +1,157 @@
+use rpg_core::graph::*;
+use rpg_nav::search::{SearchMode, SearchParams, SearchSort, search_with_facets};
+use rpg_nav::vocab::{Vocabulary, feature_terms, rebuild};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn make_entity(id: &str, features: &[&str]) -> Entity {
+    Entity {
+        id: id.to_string(),
+        kind: EntityKind::Function,
+        name: id.rsplit(':').next().unwrap().to_string(),
+        file: PathBuf::from(id.split(':').next().unwrap()),
+        line_start: 1,
+        line_end: 10,
+        parent_class: None,
+        semantic_features: features.iter().map(|f| f.to_string()).collect(),
+        feature_source: None,
+        lifted_at: None,
+        hierarchy_path: String::new(),
+        deps: EntityDeps::default(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        source_signature: Vec::new(),
+        owners: Vec::new(),
+        layer: None,
+        tags: BTreeSet::new(),
+        churn: None,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+        entry_distance: None,
+    }
+}
+
+/// "auth" and "credentials" describe the same entities twice; `verify_password`
+/// only ever says "credentials", so a query for "auth" needs expansion to find it.
+fn make_graph() -> RPGraph {
+    let mut graph = RPGraph::new("rust");
+    for (id, features) in [
+        (
+            "src/auth.rs:login",
+            &["check auth credentials", "start user session"][..],
+        ),
+        (
+            "src/auth.rs:refresh",
+            &["refresh auth token", "reload stored credentials"],
+        ),
+        ("src/auth.rs:logout", &["clear auth session"]),
+        (
+            "src/password.rs:verify_password",
+            &["verify credentials", "compare password hash"],
+        ),
+        ("src/report.rs:render", &["render html report"]),
+        ("src/report.rs:export", &["export report as csv"]),
+        ("src/cache.rs:evict", &["evict stale cache entries"]),
+    ] {
+        graph.insert_entity(make_entity(id, features));
+    }
+    graph
+}
+
+fn params<'a>(query: &'a str, vocabulary: Option<&'a Vocabulary>) -> SearchParams<'a> {
+    SearchParams {
+        query,
+        mode: SearchMode::Features,
+        scope: None,
+        limit: 10,
+        line_nums: None,
+        file_pattern: None,
+        entity_type_filter: None,
+        layer_filter: None,
+        embedding_scores: None,
+        diff_context: None,
+        changed_entities: None,
+        vocabulary,
+        sort: SearchSort::Relevance,
+        include_fixtures: false,
+        exclude_tests: false,
+        async_only: false,
+        deadline: None,
+        group_by_file: false,
+    }
+}
+
+#[test]
+fn test_feature_terms_normalize() {
+    let terms: Vec<String> = feature_terms("Validate the JWT, via Auth-header.").collect();
+    assert_eq!(terms, vec!["validate", "jwt", "auth-header"]);
+}
+
+#[test]
+fn test_vocabulary_links_cooccurring_terms() {
+    let vocab = Vocabulary::build(&make_graph());
+    let neighbors: Vec<&str> = vocab
+        .expand("auth", 5)
+        .iter()
+        .map(|n| n.term.as_str())
+        .collect();
+    assert!(neighbors.contains(&"credentials"), "{:?}", neighbors);
+    // One shared entity isn't enough evidence.
+    assert!(!neighbors.contains(&"token"), "{:?}", neighbors);
+    assert!(vocab.expand("report", 5).is_empty());
+    assert_eq!(vocab.expand("auth", 1).len(), 1);
+}
+
+#[test]
+fn test_expansion_finds_entity_without_query_term() {
+    let graph = make_graph();
+    let target = "src/password.rs:verify_password";
+
+    let plain = search_with_facets(&graph, &params("auth", None)).unwrap();
+    assert!(plain.results.iter().all(|r| r.entity_id != target));
+    assert!(plain.expansions.is_empty());
+
+    let vocab = Vocabulary::build(&graph);
+    let expanded = search_with_facets(&graph, &params("auth", Some(&vocab))).unwrap();
+    let rank = expanded
+        .results
+        .iter()
+        .position(|r| r.entity_id == target)
+        .expect("target missing with expansion");
+    // Right behind the three entities that say "auth" outright.
+    assert_eq!(rank, 3);
+    assert_eq!(
+        expanded.results[rank].expanded_via,
+        vec!["auth→credentials".to_string()]
+    );
+    assert!(expanded.expansions["auth"].contains(&"credentials".to_string()));
+
+    for direct in &expanded.results[..rank] {
+        assert!(
+            direct.matched_features.iter().any(|f| f.contains("auth")),
+            "{:?}",
+            direct
+        );
+    }
+
+    let output = rpg_nav::toon::format_search_results(&expanded.results, None);
+    assert!(output.contains("expanded: auth→credentials"), "{}", output);
+}
+
+#[test]
+fn test_vocabulary_roundtrip() {
+    let tmp = TempDir::new().unwrap();
+    assert!(Vocabulary::load(tmp.path()).is_none());
+
+    let graph = make_graph();
+    let terms = rebuild(tmp.path(), &graph).unwrap();
+    assert!(terms > 0);
+    assert_eq!(
+        Vocabulary::load(tmp.path()).unwrap(),
+        Vocabulary::build(&graph)
+    );
+}
@@ -1,6 +1,8 @@
-use criterion::{Criterion, criterion_group, criterion_main};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use rpg_parser::deps::extract_deps;
 use rpg_parser::entities::extract_entities;
 use rpg_parser::languages::Language;
+use rpg_parser::treesitter;
 use std::hint::black_box;
 use std::path::PathBuf;
 
@@ -108,24 +110,31 @@ pub fn process_files(dir: &Path) -> Vec<String> {
 fn bench_python_extraction(c: &mut Criterion) {
     let path = PathBuf::from("test.py");
     c.bench_function("extract_entities_python", |b| {
-        b.iter(|| extract_entities(black_box(&path), black_box(SAMPLE_PYTHON), Language::PYTHON))
+        b.iter(|| {
+            treesitter::clear_cache();
+            extract_entities(black_box(&path), black_box(SAMPLE_PYTHON), Language::PYTHON)
+        })
     });
 }
 
 fn bench_rust_extraction(c: &mut Criterion) {
     let path = PathBuf::from("test.rs");
     c.bench_function("extract_entities_rust", |b| {
-        b.iter(|| extract_entities(black_box(&path), black_box(SAMPLE_RUST), Language::RUST))
+        b.iter(|| {
+            treesitter::clear_cache();
+            extract_entities(black_box(&path), black_box(SAMPLE_RUST), Language::RUST)
+        })
     });
 }
 
 fn bench_parallel_parsing(c: &mut Criterion) {
-    // Generate multiple files to parse in parallel
+    // Generate multiple files to parse in parallel. Distinct contents, so no
+    // file reuses another's cached tree.
     let files: Vec<(PathBuf, String)> = (0..50)
         .map(|i| {
             (
                 PathBuf::from(format!("file_{}.py", i)),
-                SAMPLE_PYTHON.to_string(),
+                format!("{}# file {}\n", SAMPLE_PYTHON, i),
             )
         })
         .collect();
@@ -135,10 +144,72 @@ fn bench_parallel_parsing(c: &mut Criterion) {
     });
 }
 
+/// Entity plus dependency extraction for one file, sharing the cached tree
+/// versus dropping it in between (the cost of parsing twice).
+fn bench_entities_and_deps(c: &mut Criterion) {
+    let path = PathBuf::from("test.py");
+    let mut group = c.benchmark_group("entities_and_deps_python");
+    group.bench_function("shared_tree", |b| {
+        b.iter(|| {
+            treesitter::clear_cache();
+            let entities = extract_entities(&path, black_box(SAMPLE_PYTHON), Language::PYTHON);
+            let deps = extract_deps(&path, black_box(SAMPLE_PYTHON), Language::PYTHON);
+            (entities, deps)
+        })
+    });
+    group.bench_function("separate_parses", |b| {
+        b.iter(|| {
+            treesitter::clear_cache();
+            let entities = extract_entities(&path, black_box(SAMPLE_PYTHON), Language::PYTHON);
+            treesitter::clear_cache();
+            let deps = extract_deps(&path, black_box(SAMPLE_PYTHON), Language::PYTHON);
+            (entities, deps)
+        })
+    });
+    group.finish();
+}
+
+/// Reparsing a large file after a one-line edit, as `update` does it: when
+/// the thread still holds the tree of the previous contents (left by the last
+/// parse, not made for the update) the edit is reparsed incrementally;
+/// otherwise `parse_edited` declines and the file is parsed once from scratch.
+fn bench_incremental_reparse(c: &mut Criterion) {
+    let previous = SAMPLE_PYTHON.repeat(40);
+    let edited = previous.replacen("return False", "return None", 1);
+    let grammar = Language::PYTHON.ts_language();
+    let mut group = c.benchmark_group("reparse_after_edit_python");
+    group.bench_function("previous_tree_cached", |b| {
+        b.iter_batched(
+            || {
+                treesitter::clear_cache();
+                treesitter::parse_source(&previous, &grammar);
+            },
+            |()| {
+                treesitter::parse_edited(black_box(&edited), &grammar, |text| text == previous)
+                    .or_else(|| treesitter::parse_source(&edited, &grammar))
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("no_cached_tree", |b| {
+        b.iter_batched(
+            treesitter::clear_cache,
+            |()| {
+                treesitter::parse_edited(black_box(&edited), &grammar, |text| text == previous)
+                    .or_else(|| treesitter::parse_source(&edited, &grammar))
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_python_extraction,
     bench_rust_extraction,
     bench_parallel_parsing,
+    bench_entities_and_deps,
+    bench_incremental_reparse,
 );
 criterion_main!(benches);
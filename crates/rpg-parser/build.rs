@@ -57,6 +57,9 @@ struct LangToml {
     schema_version: u32,
     name: String,
     extensions: Vec<String>,
+    /// Interpreter names that identify extensionless scripts by shebang.
+    #[serde(default)]
+    interpreters: Vec<String>,
     glob: String,
     grammar: GrammarSection,
     builtin: Option<BuiltinSection>,
@@ -236,6 +239,33 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20   }\n\n",
     );
 
+    // from_interpreter()
+    code.push_str(
+        "    /// Detect language from a shebang interpreter name (`python`, `node`).\n\
+         \x20   pub fn from_interpreter(name: &str) -> Option<Self> {\n\
+         \x20       match name {\n",
+    );
+    for (i, def) in defs.iter().enumerate() {
+        if def.interpreters.is_empty() {
+            continue;
+        }
+        let patterns: Vec<String> = def
+            .interpreters
+            .iter()
+            .map(|e| format!("\"{}\"", e))
+            .collect();
+        code.push_str(&format!(
+            "            {} => Some(LangId({})),\n",
+            patterns.join(" | "),
+            i,
+        ));
+    }
+    code.push_str(
+        "            _ => None,\n\
+         \x20       }\n\
+         \x20   }\n\n",
+    );
+
     // from_name()
     code.push_str(
         "    /// Parse language from name string.\n\
@@ -291,7 +321,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20           .filter(|(_, count)| **count > 0)\n\
          \x20           .map(|(idx, count)| (Self::from_index(idx), *count))\n\
          \x20           .collect();\n\
-         \x20       langs.sort_by(|a, b| b.1.cmp(&a.1));\n\
+         \x20       langs.sort_by_key(|l| std::cmp::Reverse(l.1));\n\
          \x20       langs.into_iter().map(|(lang, _)| lang).collect()\n\
          \x20   }\n\n\
          \x20   /// Count files per language in the project.\n\
@@ -303,8 +333,8 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
          \x20           .add_custom_ignore_filename(\".rpgignore\")\n\
          \x20           .build();\n\
          \x20       for entry in walker.flatten() {\n\
-         \x20           if let Some(ext) = entry.path().extension().and_then(|e| e.to_str())\n\
-         \x20               && let Some(lang) = Self::from_extension(ext)\n\
+         \x20           if entry.file_type().is_some_and(|t| t.is_file())\n\
+         \x20               && let Some(lang) = Self::from_path(entry.path())\n\
          \x20           {\n\
          \x20               counts[lang.index()] += 1;\n\
          \x20           }\n\
@@ -373,7 +403,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
     );
     for def in defs {
         if !def.grammar.aliases.is_empty() {
-            code.push_str(&format!("        \"{}\" => match file_ext {{\n", def.name,));
+            code.push_str(&format!("        \"{}\" => match file_ext {{\n", def.name));
             for alias in &def.grammar.aliases {
                 let ext_patterns: Vec<String> = alias
                     .for_extensions
@@ -429,7 +459,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
         if let Some(ref builtin) = def.builtin
             && let Some(ref extractor) = builtin.dep_extractor
         {
-            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor,));
+            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor));
         }
     }
     code.push_str("        _ => None,\n    }\n}\n\n");
@@ -444,7 +474,7 @@ fn generate_lang_registry(defs: &[LangToml]) -> String {
         if let Some(ref builtin) = def.builtin
             && let Some(ref extractor) = builtin.entity_extractor
         {
-            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor,));
+            code.push_str(&format!("        {} => Some(\"{}\"),\n", i, extractor));
         }
     }
     code.push_str("        _ => None,\n    }\n}\n");
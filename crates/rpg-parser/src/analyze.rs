@@ -0,0 +1,166 @@
+//! Single-file analysis for editor integrations (`rpg-encoder analyze`).
+//!
+//! Runs entity and dependency extraction on one buffer, with optional
+//! paradigm processing, and returns a serializable report. Nothing is read
+//! from or written to a project's `.rpg/` directory.
+//!
+//! The JSON shape is a stable interface: fields are only ever added, and any
+//! breaking change bumps [`ANALYSIS_SCHEMA_VERSION`].
+
+use crate::deps::{self, RawDeps};
+use crate::entities::{RawEntity, extract_entities};
+use crate::languages::Language;
+use crate::paradigms::defs::ParadigmDef;
+use crate::paradigms::query_engine::QueryCache;
+use crate::paradigms::{classify, features, query_engine};
+use rpg_core::graph::{EntityKind, Signature};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Version of the [`FileAnalysis`] JSON shape.
+pub const ANALYSIS_SCHEMA_VERSION: u32 = 1;
+
+/// Entities and raw dependencies of one source file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAnalysis {
+    pub schema_version: u32,
+    /// Path used for entity IDs and paradigm file rules.
+    pub file: String,
+    pub language: String,
+    /// Paradigms applied, in priority order.
+    pub paradigms: Vec<String>,
+    pub entities: Vec<AnalyzedEntity>,
+    pub imports: Vec<AnalyzedImport>,
+    pub calls: Vec<AnalyzedCall>,
+    pub inherits: Vec<AnalyzedInherit>,
+}
+
+/// An entity, without source text.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedEntity {
+    pub id: String,
+    pub name: String,
+    pub kind: EntityKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    pub line_start: usize,
+    pub line_end: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedImport {
+    pub module: String,
+    pub symbols: Vec<String>,
+}
+
+/// A call site; `caller` is the enclosing function's name, or `<module>`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedCall {
+    pub caller: String,
+    pub callee: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzedInherit {
+    pub child: String,
+    pub parent: String,
+}
+
+/// Analyze `source` as the file at `path`. `active_defs` (with their
+/// compiled `qcache`) are applied as in a build: classification, entity
+/// queries, builtin features, then dependency queries.
+pub fn analyze_file(
+    path: &Path,
+    source: &str,
+    language: Language,
+    active_defs: &[&ParadigmDef],
+    qcache: &QueryCache,
+) -> FileAnalysis {
+    let mut raw = extract_entities(path, source, language);
+    let mut raw_deps = deps::extract_deps(path, source, language);
+    if !active_defs.is_empty() {
+        classify::classify_entities(active_defs, path, &mut raw);
+        let extra =
+            query_engine::execute_entity_queries(qcache, active_defs, path, source, language, &raw);
+        raw.extend(extra);
+        features::apply_builtin_entity_features(active_defs, path, source, language, &mut raw);
+
+        let scopes = deps::build_scopes(source, language);
+        query_engine::execute_dep_queries(
+            qcache,
+            active_defs,
+            path,
+            source,
+            language,
+            &scopes,
+            &mut raw_deps,
+        );
+        features::apply_builtin_dep_features(
+            active_defs,
+            path,
+            source,
+            language,
+            &raw,
+            &mut raw_deps,
+        );
+    }
+    raw.sort_by_key(|e| (e.line_start, e.line_end));
+    let (imports, calls) = dep_lists(&raw_deps);
+
+    FileAnalysis {
+        schema_version: ANALYSIS_SCHEMA_VERSION,
+        file: rpg_core::graph::normalize_path(path),
+        language: language.name().to_string(),
+        paradigms: active_defs.iter().map(|d| d.name.clone()).collect(),
+        entities: raw.into_iter().map(analyzed_entity).collect(),
+        imports,
+        calls,
+        inherits: raw_deps
+            .inherits
+            .iter()
+            .map(|i| AnalyzedInherit {
+                child: i.child_class.clone(),
+                parent: i.parent_class.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn analyzed_entity(raw: RawEntity) -> AnalyzedEntity {
+    let entity = raw.into_entity();
+    AnalyzedEntity {
+        id: entity.id,
+        name: entity.name,
+        kind: entity.kind,
+        parent: entity.parent_class,
+        line_start: entity.line_start,
+        line_end: entity.line_end,
+        signature: entity.signature,
+        attributes: entity.attributes,
+    }
+}
+
+fn dep_lists(raw_deps: &RawDeps) -> (Vec<AnalyzedImport>, Vec<AnalyzedCall>) {
+    let imports = raw_deps
+        .imports
+        .iter()
+        .map(|i| AnalyzedImport {
+            module: i.module.clone(),
+            symbols: i.symbols.clone(),
+        })
+        .collect();
+    let calls = raw_deps
+        .calls
+        .iter()
+        .map(|c| AnalyzedCall {
+            caller: c.caller_entity.clone(),
+            callee: c.callee.clone(),
+        })
+        .collect();
+    (imports, calls)
+}
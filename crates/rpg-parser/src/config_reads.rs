@@ -0,0 +1,239 @@
+//! Configuration keys read through string literals.
+//!
+//! A literal-pattern scan over the parsed tree, not dataflow: a read is
+//! recorded only when the key is spelled out at the access site.
+//!
+//! - Environment lookups: `std::env::var("X")`, `env!("X")`, `os.getenv("X")`,
+//!   `os.environ["X"]`, `process.env.X`, `os.Getenv("X")`, `System.getenv("X")`,
+//!   `Environment.GetEnvironmentVariable("X")`, `ENV["X"]`, `getenv("X")`, ...
+//! - Config getters: a `get`-style method on a receiver named like a config
+//!   object (`config.get("x")`, `self.settings.get_str("x")`, `Config::get("x")`),
+//!   and Laravel's `config("app.name")` / `env("X")` helpers.
+
+use crate::languages::Language;
+use tree_sitter::Node;
+
+/// One configuration key read at a source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigRead {
+    /// 1-based line of the access.
+    pub line: usize,
+    pub key: String,
+}
+
+/// Calls whose first string argument is an environment variable or config key.
+const KEY_FUNCTIONS: &[&str] = &[
+    // Rust
+    "std::env::var",
+    "std::env::var_os",
+    "env::var",
+    "env::var_os",
+    // Python
+    "os.getenv",
+    "os.environ.get",
+    "environ.get",
+    // Go
+    "os.Getenv",
+    "os.LookupEnv",
+    // Java / Kotlin / Scala
+    "System.getenv",
+    "System.getProperty",
+    // C#
+    "Environment.GetEnvironmentVariable",
+    // Ruby
+    "ENV.fetch",
+    // C / C++ / PHP
+    "getenv",
+    "std::getenv",
+    "secure_getenv",
+    // PHP (Laravel helpers)
+    "env",
+    "config",
+];
+
+/// Rust macros reading environment variables at compile time.
+const KEY_MACROS: &[&str] = &["env", "option_env"];
+
+/// Objects indexed (or, in JS, accessed as properties) by variable name.
+const ENV_MAPS: &[&str] = &[
+    "process.env",
+    "import.meta.env",
+    "os.environ",
+    "environ",
+    "ENV",
+    "$_ENV",
+];
+
+/// Getter methods that take a key, on config-like receivers.
+const CONFIG_GETTERS: &[&str] = &[
+    "get",
+    "get_str",
+    "get_string",
+    "get_bool",
+    "get_int",
+    "get_float",
+    "getString",
+    "getBoolean",
+    "getInt",
+    "getInteger",
+    "getLong",
+    "getProperty",
+    "getValue",
+    "GetValue",
+    "GetSection",
+];
+
+/// Whether `source` may contain a config read; skips parsing files that cannot.
+fn may_read_config(source: &str) -> bool {
+    let lower = source.to_ascii_lowercase();
+    ["env", "conf", "settings", "cfg", "getproperty"]
+        .iter()
+        .any(|hint| lower.contains(hint))
+}
+
+/// Extract the configuration keys read in `source`, in source order.
+pub fn extract_config_reads(source: &str, language: Language) -> Vec<ConfigRead> {
+    if !may_read_config(source) {
+        return Vec::new();
+    }
+    let Some(tree) = crate::treesitter::parse_source(source, &language.ts_language()) else {
+        return Vec::new();
+    };
+    let mut reads = Vec::new();
+    collect(&tree.root_node(), source, &mut reads);
+    reads
+}
+
+fn collect(node: &Node, source: &str, reads: &mut Vec<ConfigRead>) {
+    if let Some(key) = read_key(node, source) {
+        reads.push(ConfigRead {
+            line: node.start_position().row + 1,
+            key,
+        });
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect(&child, source, reads);
+    }
+}
+
+/// The key `node` reads, if it is a config access with a literal key.
+fn read_key(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "macro_invocation" => {
+            let name = compact(&source[node.child_by_field_name("macro")?.byte_range()]);
+            let name = name.rsplit("::").next().unwrap_or(&name);
+            if !KEY_MACROS.contains(&name) {
+                return None;
+            }
+            let mut cursor = node.walk();
+            let args = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "token_tree")?;
+            first_string_arg(&args, source)
+        }
+        "member_expression" => {
+            let object = compact(&source[node.child_by_field_name("object")?.byte_range()]);
+            if !ENV_MAPS.contains(&object.as_str()) {
+                return None;
+            }
+            let property = node.child_by_field_name("property")?;
+            (property.kind() == "property_identifier")
+                .then(|| source[property.byte_range()].to_string())
+        }
+        "subscript_expression" | "subscript" | "element_reference" => {
+            let object = node.named_child(0)?;
+            if !ENV_MAPS.contains(&compact(&source[object.byte_range()]).as_str()) {
+                return None;
+            }
+            let index = node
+                .child_by_field_name("index")
+                .or_else(|| node.child_by_field_name("subscript"))
+                .or_else(|| node.named_child(1))?;
+            string_literal(&unwrap_argument(index), source)
+        }
+        _ => {
+            let args = node.child_by_field_name("arguments")?;
+            let callee = compact(&source[node.start_byte()..args.start_byte()]);
+            if is_key_function(&callee) {
+                first_string_arg(&args, source)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Whether a call to `callee` (whitespace removed) takes a config key first.
+fn is_key_function(callee: &str) -> bool {
+    if KEY_FUNCTIONS.contains(&callee) {
+        return true;
+    }
+    let Some((receiver, method)) = split_member(callee) else {
+        return false;
+    };
+    if !CONFIG_GETTERS.contains(&method) {
+        return false;
+    }
+    let receiver = receiver.trim_end_matches("()");
+    let name = split_member(receiver)
+        .map(|(_, last)| last)
+        .unwrap_or(receiver)
+        .to_ascii_lowercase();
+    ["config", "conf", "settings", "cfg"]
+        .iter()
+        .any(|hint| name.contains(hint))
+}
+
+/// Split `a.b.c` / `a::b` / `$a->b` at the last member separator.
+fn split_member(path: &str) -> Option<(&str, &str)> {
+    [".", "::", "->"]
+        .iter()
+        .filter_map(|sep| path.rfind(sep).map(|i| (i, sep.len())))
+        .max_by_key(|(i, _)| *i)
+        .map(|(i, len)| (&path[..i], &path[i + len..]))
+}
+
+fn first_string_arg(args: &Node, source: &str) -> Option<String> {
+    let mut cursor = args.walk();
+    let first = args.named_children(&mut cursor).next()?;
+    string_literal(&unwrap_argument(first), source)
+}
+
+/// C# and PHP wrap each argument in an `argument` node.
+fn unwrap_argument(node: Node) -> Node {
+    if node.kind() == "argument" {
+        node.named_child(0).unwrap_or(node)
+    } else {
+        node
+    }
+}
+
+/// The content of a plain string literal; interpolated strings are rejected.
+fn string_literal(node: &Node, source: &str) -> Option<String> {
+    let kind = node.kind();
+    if !(kind.contains("string") && kind != "string_content") {
+        return None;
+    }
+    if has_interpolation(node) {
+        return None;
+    }
+    let text = &source[node.byte_range()];
+    let key = text
+        .trim_start_matches(|c: char| c.is_ascii_alphabetic() || c == '@' || c == '#')
+        .trim_matches(|c| matches!(c, '"' | '\'' | '`' | '#'));
+    (!key.is_empty() && key.len() <= 200 && !key.contains(char::is_whitespace))
+        .then(|| key.to_string())
+}
+
+fn has_interpolation(node: &Node) -> bool {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).any(|child| {
+        let kind = child.kind();
+        kind.contains("interpolation") || kind.contains("substitution") || kind == "variable_name"
+    })
+}
+
+fn compact(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
@@ -1,6 +1,8 @@
 //! Extract dependencies (imports, calls, inheritance) from AST.
 
+use crate::entities::is_constant_name;
 use crate::languages::Language;
+use std::collections::HashMap;
 use std::path::Path;
 
 /// Raw dependency information extracted from a single file.
@@ -14,6 +16,13 @@ pub struct RawDeps {
     pub reads_state: Vec<CallDep>,
     pub writes_state: Vec<CallDep>,
     pub dispatches: Vec<CallDep>,
+    /// Configuration keys read through string literals, by line.
+    pub config_reads: Vec<crate::config_reads::ConfigRead>,
+    /// Event emits and listener registrations (TS/JS), by line.
+    pub events: Vec<crate::events::EventUse>,
+    /// Calls made inside closures handed to a task or thread spawner
+    /// (Rust), from the function doing the spawning.
+    pub spawns: Vec<CallDep>,
 }
 
 impl RawDeps {
@@ -52,7 +61,10 @@ pub struct InheritDep {
     pub parent_class: String,
 }
 
-/// A raw composition dependency (re-export or aggregation).
+/// A raw composition dependency: a re-export (`source_entity` is
+/// `<module>`) or a field whose type is `target_name` (`source_entity` is the
+/// owning class or struct). Re-exported or declared Rust modules are named
+/// by path as `path::*` (`mod foo;` is `self::foo::*`).
 #[derive(Debug, Clone)]
 pub struct ComposeDep {
     pub source_entity: String,
@@ -70,11 +82,7 @@ pub struct FunctionScope {
 /// Extract dependency info from a Python source file.
 pub fn extract_python_deps(_path: &Path, source: &str) -> RawDeps {
     let lang: tree_sitter::Language = tree_sitter_python::LANGUAGE.into();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -114,6 +122,15 @@ pub fn extract_python_deps(_path: &Path, source: &str) -> RawDeps {
 
     // Second pass: collect call expressions
     collect_python_calls(&root, source, &scopes, &mut deps.calls);
+    collect_value_refs(
+        &root,
+        source,
+        &scopes,
+        &PYTHON_VALUE_REFS,
+        false,
+        &mut deps.calls,
+    );
+    collect_python_field_types(&root, source, &mut deps);
 
     deps
 }
@@ -228,6 +245,9 @@ fn parse_python_import(text: &str) -> Option<ImportDep> {
         if parts.len() == 2 {
             let module = parts[0].trim_start_matches("from ").trim().to_string();
             let symbols: Vec<String> = parts[1]
+                .trim()
+                .trim_start_matches('(')
+                .trim_end_matches(')')
                 .split(',')
                 .map(|s| {
                     s.trim()
@@ -268,11 +288,7 @@ fn parse_python_bases(text: &str) -> Vec<String> {
 /// Extract dependency info from a Rust source file.
 pub fn extract_rust_deps(_path: &Path, source: &str) -> RawDeps {
     let lang: tree_sitter::Language = tree_sitter_rust::LANGUAGE.into();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -285,19 +301,112 @@ pub fn extract_rust_deps(_path: &Path, source: &str) -> RawDeps {
     collect_rust_scopes(&root, source, &mut scopes, None);
 
     for child in root.children(&mut cursor) {
-        if child.kind() == "use_declaration" {
-            let text = &source[child.byte_range()];
-            let import = parse_rust_use(text);
-            deps.imports.push(import);
+        match child.kind() {
+            "use_declaration" => {
+                let text = &source[child.byte_range()];
+                let import = parse_rust_use(text);
+                let is_pub = child
+                    .named_child(0)
+                    .is_some_and(|n| n.kind() == "visibility_modifier");
+                if is_pub {
+                    deps.composes.extend(rust_reexports(&import));
+                }
+                deps.imports.push(import);
+            }
+            // `mod foo;` composes the child module file; inline modules
+            // (`mod tests { .. }`) have a body and no file.
+            "mod_item" if child.child_by_field_name("body").is_none() => {
+                if let Some(name) = child.child_by_field_name("name") {
+                    deps.composes.push(ComposeDep {
+                        source_entity: "<module>".to_string(),
+                        target_name: format!("self::{}::*", &source[name.byte_range()]),
+                    });
+                }
+            }
+            _ => {}
         }
     }
 
     // Collect call expressions
     collect_rust_calls(&root, source, &scopes, &mut deps.calls);
+    collect_value_refs(
+        &root,
+        source,
+        &scopes,
+        &RUST_VALUE_REFS,
+        false,
+        &mut deps.calls,
+    );
+    collect_rust_field_types(&root, source, &mut deps);
+    collect_rust_trait_impls(&root, source, &mut deps.inherits);
+    collect_rust_spawns(&root, source, &scopes, &mut deps.spawns);
 
     deps
 }
 
+/// Spawner functions whose closure or async block runs as a separate task
+/// or thread.
+const RUST_SPAWN_FUNCTIONS: &[&str] = &[
+    "spawn",
+    "tokio::spawn",
+    "tokio::task::spawn",
+    "task::spawn",
+    "tokio::task::spawn_blocking",
+    "task::spawn_blocking",
+    "spawn_blocking",
+    "tokio::task::spawn_local",
+    "task::spawn_local",
+    "std::thread::spawn",
+    "thread::spawn",
+    "rayon::spawn",
+    "rayon::spawn_fifo",
+    "async_std::task::spawn",
+    "smol::spawn",
+];
+
+/// Spawner methods: scoped threads and rayon scopes (`s.spawn(|_| ..)`),
+/// runtime handles, and `JoinSet`.
+const RUST_SPAWN_METHODS: &[&str] = &["spawn", "spawn_blocking", "spawn_local", "spawn_fifo"];
+
+/// Calls inside closures and async blocks passed to a spawner, attributed to
+/// the enclosing function like any other call.
+fn collect_rust_spawns(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    spawns: &mut Vec<CallDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "call_expression"
+            && let Some(func_node) = child.child_by_field_name("function")
+            && is_rust_spawner(&func_node, source)
+            && let Some(args) = child.child_by_field_name("arguments")
+        {
+            let mut args_cursor = args.walk();
+            for arg in args.named_children(&mut args_cursor) {
+                if matches!(arg.kind(), "closure_expression" | "async_block") {
+                    collect_rust_calls(&arg, source, scopes, spawns);
+                }
+            }
+        }
+        collect_rust_spawns(&child, source, scopes, spawns);
+    }
+}
+
+fn is_rust_spawner(func_node: &tree_sitter::Node, source: &str) -> bool {
+    match func_node.kind() {
+        "identifier" | "scoped_identifier" => {
+            let path: String = source[func_node.byte_range()].split_whitespace().collect();
+            RUST_SPAWN_FUNCTIONS.contains(&path.as_str())
+        }
+        "field_expression" => func_node
+            .child_by_field_name("field")
+            .is_some_and(|f| RUST_SPAWN_METHODS.contains(&&source[f.byte_range()])),
+        _ => false,
+    }
+}
+
 /// Recursively collect function/method scopes from Rust AST.
 fn collect_rust_scopes(
     node: &tree_sitter::Node,
@@ -360,22 +469,20 @@ fn collect_rust_calls(
                     }
                 }
             }
-            "method_call_expression" | "field_expression" => {
-                // obj.method() — tree-sitter-rust uses "method_call_expression" for x.foo()
-                // but some versions nest it differently
-                if child.kind() == "method_call_expression" {
-                    // The method name is in the "name" field
-                    if let Some(method_node) = child.child_by_field_name("name") {
-                        let callee = source[method_node.byte_range()].to_string();
-                        if !callee.is_empty() {
-                            let call_row = child.start_position().row;
-                            let caller = find_enclosing_scope(scopes, call_row)
-                                .unwrap_or_else(|| "<module>".to_string());
-                            calls.push(CallDep {
-                                caller_entity: caller,
-                                callee,
-                            });
-                        }
+            // obj.method() — tree-sitter-rust uses "method_call_expression" for x.foo()
+            // but some versions nest it differently
+            "method_call_expression" => {
+                // The method name is in the "name" field
+                if let Some(method_node) = child.child_by_field_name("name") {
+                    let callee = source[method_node.byte_range()].to_string();
+                    if !callee.is_empty() {
+                        let call_row = child.start_position().row;
+                        let caller = find_enclosing_scope(scopes, call_row)
+                            .unwrap_or_else(|| "<module>".to_string());
+                        calls.push(CallDep {
+                            caller_entity: caller,
+                            callee,
+                        });
                     }
                 }
             }
@@ -385,6 +492,27 @@ fn collect_rust_calls(
     }
 }
 
+/// What a `pub use` re-exports: the whole module for `path::*`,
+/// `path::{self}`, and bare `path` (as `path::*`, which grounding resolves to
+/// the module's file), otherwise each name.
+fn rust_reexports(import: &ImportDep) -> Vec<ComposeDep> {
+    let compose = |target_name: String| ComposeDep {
+        source_entity: "<module>".to_string(),
+        target_name,
+    };
+    if import.symbols.is_empty() {
+        return vec![compose(format!("{}::*", import.module))];
+    }
+    import
+        .symbols
+        .iter()
+        .map(|symbol| match symbol.as_str() {
+            "*" | "self" => compose(format!("{}::*", import.module)),
+            name => compose(name.to_string()),
+        })
+        .collect()
+}
+
 fn parse_rust_use(text: &str) -> ImportDep {
     let mut text = text.trim();
     // Strip visibility modifiers: pub, pub(crate), pub(super), pub(in path)
@@ -434,8 +562,455 @@ fn parse_rust_use(text: &str) -> ImportDep {
     }
 }
 
-/// Generic dependency extraction dispatching to the correct language extractor.
+// ---------------------------------------------------------------------------
+// Field types (composition: "A has a field of type B")
+// ---------------------------------------------------------------------------
+
+/// Standard-library and builtin type names that never resolve to an in-repo
+/// entity; skipped so they don't count as unresolved Composes deps.
+const BUILTIN_FIELD_TYPES: &[&str] = &[
+    // Rust
+    "String",
+    "Vec",
+    "Option",
+    "Box",
+    "Rc",
+    "Arc",
+    "Weak",
+    "Cell",
+    "RefCell",
+    "Mutex",
+    "RwLock",
+    "HashMap",
+    "HashSet",
+    "BTreeMap",
+    "BTreeSet",
+    "VecDeque",
+    "Result",
+    "PathBuf",
+    "Cow",
+    "Self",
+    "PhantomData",
+    // Python
+    "int",
+    "str",
+    "float",
+    "bool",
+    "bytes",
+    "list",
+    "dict",
+    "set",
+    "frozenset",
+    "tuple",
+    "object",
+    "type",
+    "None",
+    "Any",
+    "Optional",
+    "Union",
+    "List",
+    "Dict",
+    "Set",
+    "FrozenSet",
+    "Tuple",
+    "Type",
+    "ClassVar",
+    "Final",
+    "Callable",
+    "Sequence",
+    "Mapping",
+    "Iterable",
+    "Iterator",
+    "Literal",
+    "Annotated",
+    // Java
+    "Integer",
+    "Long",
+    "Short",
+    "Byte",
+    "Double",
+    "Float",
+    "Boolean",
+    "Character",
+    "Object",
+    "Map",
+    "Collection",
+    "ArrayList",
+    "LinkedList",
+    "HashMap",
+    "TreeMap",
+    "Optional",
+    // TypeScript
+    "Array",
+    "ReadonlyArray",
+    "Record",
+    "Partial",
+    "Readonly",
+    "Required",
+    "Pick",
+    "Omit",
+    "Promise",
+    "Date",
+    "RegExp",
+    "Error",
+    "Function",
+];
+
+/// Push one ComposeDep per distinct type name referenced by a field of `owner`.
+fn push_field_composes(deps: &mut RawDeps, owner: &str, type_names: Vec<String>) {
+    for name in type_names {
+        if name == owner || BUILTIN_FIELD_TYPES.contains(&name.as_str()) {
+            continue;
+        }
+        let exists = deps
+            .composes
+            .iter()
+            .any(|c| c.source_entity == owner && c.target_name == name);
+        if !exists {
+            deps.composes.push(ComposeDep {
+                source_entity: owner.to_string(),
+                target_name: name,
+            });
+        }
+    }
+}
+
+/// Every `type_identifier` under a type node (`Vec<Option<Order>>` → Vec,
+/// Option, Order). Path segments of qualified types are plain identifiers, so
+/// only the last segment is collected.
+fn collect_type_identifiers(node: &tree_sitter::Node, source: &str, out: &mut Vec<String>) {
+    if node.kind() == "type_identifier" {
+        out.push(source[node.byte_range()].to_string());
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_type_identifiers(&child, source, out);
+    }
+}
+
+/// Rust: field types of structs (named and tuple fields), in any module.
+fn collect_rust_field_types(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "struct_item" {
+            if let (Some(name), Some(body)) = (
+                child.child_by_field_name("name"),
+                child.child_by_field_name("body"),
+            ) {
+                // Field names are field_identifiers, so the whole body only
+                // yields the field types.
+                let mut types = Vec::new();
+                collect_type_identifiers(&body, source, &mut types);
+                push_field_composes(deps, &source[name.byte_range()], types);
+            }
+        } else {
+            collect_rust_field_types(&child, source, deps);
+        }
+    }
+}
+
+/// Rust: `impl Trait for Type` blocks, in any module, as the type
+/// inheriting from the trait. Paths and generic arguments are dropped
+/// (`impl<T> fmt::Display for Wrapper<T>` gives `Wrapper` → `Display`);
+/// blanket impls over a type parameter (`impl<T: Bound> Trait for T`) are
+/// skipped.
+fn collect_rust_trait_impls(
+    node: &tree_sitter::Node,
+    source: &str,
+    inherits: &mut Vec<InheritDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "impl_item"
+            && let Some(trait_name) = child
+                .child_by_field_name("trait")
+                .and_then(|t| rust_base_type_name(&t, source))
+            && let Some(type_name) = child
+                .child_by_field_name("type")
+                .and_then(|t| rust_base_type_name(&t, source))
+            && !rust_type_parameters(&child, source).contains(&type_name)
+        {
+            inherits.push(InheritDep {
+                child_class: type_name,
+                parent_class: trait_name,
+            });
+        }
+        collect_rust_trait_impls(&child, source, inherits);
+    }
+}
+
+/// The named type under paths, generic arguments, and references:
+/// `std::fmt::Display` → `Display`, `&'a Vec<T>` → `Vec`.
+fn rust_base_type_name(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "type_identifier" => Some(source[node.byte_range()].to_string()),
+        "scoped_type_identifier" => node
+            .child_by_field_name("name")
+            .and_then(|n| rust_base_type_name(&n, source)),
+        "generic_type" | "reference_type" | "pointer_type" => node
+            .child_by_field_name("type")
+            .and_then(|n| rust_base_type_name(&n, source)),
+        _ => None,
+    }
+}
+
+/// Names of the type parameters an `impl<...>` declares.
+fn rust_type_parameters(impl_node: &tree_sitter::Node, source: &str) -> Vec<String> {
+    let Some(params) = impl_node.child_by_field_name("type_parameters") else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    let mut cursor = params.walk();
+    for param in params.named_children(&mut cursor) {
+        let name = match param.kind() {
+            "type_identifier" => Some(param),
+            _ => param.child_by_field_name("name").or_else(|| {
+                let mut pc = param.walk();
+                param
+                    .named_children(&mut pc)
+                    .find(|c| c.kind() == "type_identifier")
+            }),
+        };
+        if let Some(name) = name {
+            names.push(source[name.byte_range()].to_string());
+        }
+    }
+    names
+}
+
+/// Python: annotated class attributes (`owner: User`, `items: list["Item"]`).
+fn collect_python_field_types(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "class_definition"
+            && let (Some(name), Some(body)) = (
+                child.child_by_field_name("name"),
+                child.child_by_field_name("body"),
+            )
+        {
+            let mut types = Vec::new();
+            let mut bc = body.walk();
+            for stmt in body.children(&mut bc) {
+                let assignment = stmt
+                    .child(0)
+                    .filter(|_| stmt.kind() == "expression_statement");
+                if let Some(annotation) = assignment
+                    .filter(|a| a.kind() == "assignment")
+                    .and_then(|a| a.child_by_field_name("type"))
+                {
+                    collect_python_annotation_names(&annotation, source, &mut types);
+                }
+            }
+            push_field_composes(deps, &source[name.byte_range()], types);
+        }
+        collect_python_field_types(&child, source, deps);
+    }
+}
+
+/// Names in a Python annotation: identifiers, the last segment of dotted
+/// names, and quoted forward references.
+fn collect_python_annotation_names(node: &tree_sitter::Node, source: &str, out: &mut Vec<String>) {
+    match node.kind() {
+        "identifier" => out.push(source[node.byte_range()].to_string()),
+        "attribute" => {
+            if let Some(attr) = node.child_by_field_name("attribute") {
+                out.push(source[attr.byte_range()].to_string());
+            }
+        }
+        "string" => {
+            let text = source[node.byte_range()].trim_matches(|c| c == '"' || c == '\'');
+            if !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                out.push(text.to_string());
+            }
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_python_annotation_names(&child, source, out);
+            }
+        }
+    }
+}
+
+/// TypeScript: class property types and interface member types.
+fn collect_js_field_types(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let member_kind = match child.kind() {
+            "class_declaration" | "abstract_class_declaration" | "class" => {
+                Some("public_field_definition")
+            }
+            "interface_declaration" => Some("property_signature"),
+            _ => None,
+        };
+        if let Some(member_kind) = member_kind
+            && let (Some(name), Some(body)) = (
+                child.child_by_field_name("name"),
+                child.child_by_field_name("body"),
+            )
+        {
+            let mut types = Vec::new();
+            let mut bc = body.walk();
+            for member in body.children(&mut bc) {
+                if member.kind() == member_kind
+                    && let Some(annotation) = member.child_by_field_name("type")
+                {
+                    collect_type_identifiers(&annotation, source, &mut types);
+                }
+            }
+            push_field_composes(deps, &source[name.byte_range()], types);
+        }
+        collect_js_field_types(&child, source, deps);
+    }
+}
+
+/// Java: field types of classes (nested classes report their own fields).
+fn collect_java_field_types(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "class_declaration"
+            && let (Some(name), Some(body)) = (
+                child.child_by_field_name("name"),
+                child.child_by_field_name("body"),
+            )
+        {
+            let mut types = Vec::new();
+            let mut bc = body.walk();
+            for member in body.children(&mut bc) {
+                if member.kind() == "field_declaration"
+                    && let Some(field_type) = member.child_by_field_name("type")
+                {
+                    collect_type_identifiers(&field_type, source, &mut types);
+                }
+            }
+            push_field_composes(deps, &source[name.byte_range()], types);
+        }
+        collect_java_field_types(&child, source, deps);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Enum and constant references
+// ---------------------------------------------------------------------------
+
+/// Grammar node kinds where enum variants and constants are read as values.
+struct ValueRefKinds {
+    /// Nodes whose whole subtree is a value context (match patterns,
+    /// `case` labels, Python comparisons).
+    contexts: &'static [&'static str],
+    /// `switch_case`-style nodes whose `value` field is a value context.
+    case_values: Option<&'static str>,
+    /// Binary expressions, a value context when the operator compares.
+    binary: Option<&'static str>,
+    /// Qualified names such as `Kind::Enum` or `Color.RED`.
+    qualified: &'static [&'static str],
+    separator: &'static str,
+}
+
+const RUST_VALUE_REFS: ValueRefKinds = ValueRefKinds {
+    contexts: &["match_pattern"],
+    case_values: None,
+    binary: Some("binary_expression"),
+    qualified: &["scoped_identifier"],
+    separator: "::",
+};
+
+const PYTHON_VALUE_REFS: ValueRefKinds = ValueRefKinds {
+    contexts: &["case_pattern", "comparison_operator"],
+    case_values: None,
+    binary: None,
+    qualified: &["attribute", "dotted_name"],
+    separator: ".",
+};
+
+const JS_VALUE_REFS: ValueRefKinds = ValueRefKinds {
+    contexts: &[],
+    case_values: Some("switch_case"),
+    binary: Some("binary_expression"),
+    qualified: &["member_expression"],
+    separator: ".",
+};
+
+const JAVA_VALUE_REFS: ValueRefKinds = ValueRefKinds {
+    contexts: &["switch_label"],
+    case_values: None,
+    binary: Some("binary_expression"),
+    qualified: &["field_access"],
+    separator: ".",
+};
+
+const COMPARISON_OPERATORS: &[&str] = &["==", "!=", "===", "!==", "<", ">", "<=", ">="];
+
+impl ValueRefKinds {
+    fn is_context(&self, node: &tree_sitter::Node, source: &str) -> bool {
+        if self.contexts.contains(&node.kind()) {
+            return true;
+        }
+        if Some(node.kind()) == self.binary {
+            return node
+                .child_by_field_name("operator")
+                .is_some_and(|op| COMPARISON_OPERATORS.contains(&&source[op.byte_range()]));
+        }
+        self.case_values.is_some_and(|case| {
+            node.parent().is_some_and(|parent| {
+                parent.kind() == case && parent.child_by_field_name("value") == Some(*node)
+            })
+        })
+    }
+
+    /// The enum or constant a value node names: the type of a qualified
+    /// variant (`Kind::Enum` → `Kind`), or a SCREAMING_CASE constant.
+    fn referenced_name(&self, node: &tree_sitter::Node, source: &str) -> Option<String> {
+        let text = &source[node.byte_range()];
+        if self.qualified.contains(&node.kind()) {
+            let (owner, member) = text.rsplit_once(self.separator)?;
+            let owner = owner.rsplit(self.separator).next().unwrap_or(owner);
+            if owner.starts_with(|c: char| c.is_ascii_uppercase()) && owner != "Self" {
+                return Some(owner.to_string());
+            }
+            return is_constant_name(member).then(|| member.to_string());
+        }
+        (node.kind() == "identifier" && is_constant_name(text)).then(|| text.to_string())
+    }
+}
+
+/// Enum and constant references in match arms, `case` labels, and
+/// comparisons, recorded as calls from the enclosing scope so they become
+/// Invokes edges.
+fn collect_value_refs(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    kinds: &ValueRefKinds,
+    in_context: bool,
+    calls: &mut Vec<CallDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let in_context = in_context || kinds.is_context(&child, source);
+        if in_context && let Some(callee) = kinds.referenced_name(&child, source) {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            calls.push(CallDep {
+                caller_entity: caller,
+                callee,
+            });
+            continue;
+        }
+        collect_value_refs(&child, source, scopes, kinds, in_context, calls);
+    }
+}
+
+/// Generic dependency extraction dispatching to the correct language extractor,
+/// plus the language-independent config read scan.
 pub fn extract_deps(path: &Path, source: &str, language: Language) -> RawDeps {
+    let mut deps = extract_language_deps(path, source, language);
+    deps.config_reads = crate::config_reads::extract_config_reads(source, language);
+    deps
+}
+
+fn extract_language_deps(path: &Path, source: &str, language: Language) -> RawDeps {
     if let Some(name) = crate::languages::builtin_dep_extractor_name(language) {
         match name {
             "extract_python_deps" => return extract_python_deps(path, source),
@@ -469,11 +1044,7 @@ pub fn extract_deps(path: &Path, source: &str, language: Language) -> RawDeps {
 /// Extract deps from TypeScript or JavaScript source.
 pub fn extract_js_deps(_path: &Path, source: &str, language: Language) -> RawDeps {
     let ts_lang = language.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
         return RawDeps::default();
     };
 
@@ -489,6 +1060,16 @@ pub fn extract_js_deps(_path: &Path, source: &str, language: Language) -> RawDep
     // by the TOML-driven paradigm engine (query_engine.rs + features.rs).
     collect_js_imports(&root, source, &mut deps);
     collect_js_calls(&root, source, &scopes, &mut deps.calls);
+    deps.events = crate::events::collect_js_events(&root, source);
+    collect_value_refs(
+        &root,
+        source,
+        &scopes,
+        &JS_VALUE_REFS,
+        false,
+        &mut deps.calls,
+    );
+    collect_js_field_types(&root, source, &mut deps);
 
     deps
 }
@@ -502,11 +1083,7 @@ pub fn build_scopes(source: &str, language: Language) -> Vec<FunctionScope> {
         return Vec::new();
     }
     let ts_lang = language.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
         return Vec::new();
     };
     let root = tree.root_node();
@@ -523,6 +1100,17 @@ pub fn collect_js_scopes(
 ) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
+        // Top-level test blocks are Test entities named by their title
+        if node.kind() == "program"
+            && let Some(title) = crate::testing::js_test_block_title(&child, source)
+        {
+            scopes.push(FunctionScope {
+                name: title,
+                start_row: child.start_position().row,
+                end_row: child.end_position().row,
+            });
+            continue;
+        }
         match child.kind() {
             "function_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
@@ -654,14 +1242,21 @@ fn collect_js_imports(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps
                                 let mut sc = ec.walk();
                                 for spec in ec.children(&mut sc) {
                                     if spec.kind() == "export_specifier" {
-                                        // Use alias if present (export { default as Foo }),
-                                        // otherwise use the name field
-                                        let name_node = spec
+                                        // Use the original name so the target resolves
+                                        // to its definition (export { Bar as Baz });
+                                        // `default` has none, so fall back to the alias
+                                        let name = spec
+                                            .child_by_field_name("name")
+                                            .map(|n| &source[n.byte_range()]);
+                                        let alias = spec
                                             .child_by_field_name("alias")
-                                            .or_else(|| spec.child_by_field_name("name"));
-                                        if let Some(n) = name_node {
-                                            specifier_names
-                                                .push(source[n.byte_range()].to_string());
+                                            .map(|n| &source[n.byte_range()]);
+                                        let name = match (name, alias) {
+                                            (Some("default"), Some(alias)) => Some(alias),
+                                            (name, alias) => name.or(alias),
+                                        };
+                                        if let Some(name) = name {
+                                            specifier_names.push(name.to_string());
                                         }
                                     }
                                 }
@@ -674,15 +1269,12 @@ fn collect_js_imports(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps
                     }
 
                     if has_star {
-                        // export * from './module' — compose the module file path
-                        // Use the module path so resolve_dep can match the Module entity
-                        let target = module
-                            .trim_start_matches("./")
-                            .trim_start_matches("../")
-                            .to_string();
+                        // export * from './module' — compose the module itself; the
+                        // specifier is kept as written so resolution can follow it to
+                        // the file's Module entity
                         deps.composes.push(ComposeDep {
                             source_entity: "<module>".to_string(),
-                            target_name: target,
+                            target_name: module.clone(),
                         });
                         deps.imports.push(ImportDep {
                             module: module.clone(),
@@ -770,11 +1362,7 @@ fn has_child_kind(node: &tree_sitter::Node, kind: &str) -> bool {
 /// Extract deps from Go source.
 pub fn extract_go_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::GO.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -795,10 +1383,31 @@ pub fn extract_go_deps(_path: &Path, source: &str) -> RawDeps {
 
     // Collect calls
     collect_go_calls(&root, source, &scopes, &mut deps.calls);
+    collect_go_embeds(&root, source, &mut deps);
 
     deps
 }
 
+/// Go: struct embedding (`type Server struct { *Logger }`) composes the
+/// embedded type.
+fn collect_go_embeds(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "type_spec"
+            && let (Some(name), Some(type_node)) = (
+                child.child_by_field_name("name"),
+                child.child_by_field_name("type"),
+            )
+            && type_node.kind() == "struct_type"
+        {
+            let embeds = crate::entities::go_embedded_fields(&type_node, source);
+            push_field_composes(deps, &source[name.byte_range()], embeds);
+        } else {
+            collect_go_embeds(&child, source, deps);
+        }
+    }
+}
+
 fn collect_go_scopes(node: &tree_sitter::Node, source: &str, scopes: &mut Vec<FunctionScope>) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -898,11 +1507,7 @@ fn collect_go_calls(
 /// Extract deps from Java source.
 pub fn extract_java_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::JAVA.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -918,6 +1523,15 @@ pub fn extract_java_deps(_path: &Path, source: &str) -> RawDeps {
 
     // Collect calls
     collect_java_calls(&root, source, &scopes, &mut deps.calls);
+    collect_value_refs(
+        &root,
+        source,
+        &scopes,
+        &JAVA_VALUE_REFS,
+        false,
+        &mut deps.calls,
+    );
+    collect_java_field_types(&root, source, &mut deps);
 
     deps
 }
@@ -1064,11 +1678,7 @@ fn collect_java_calls(
 /// Extract deps from C or C++ source.
 pub fn extract_c_deps(_path: &Path, source: &str, language: Language) -> RawDeps {
     let ts_lang = language.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
         return RawDeps::default();
     };
 
@@ -1192,11 +1802,7 @@ fn collect_c_calls(
 /// Extract deps from C# source.
 pub fn extract_csharp_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::CSHARP.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -1355,13 +1961,14 @@ fn collect_csharp_calls(
 // ---------------------------------------------------------------------------
 
 /// Extract deps from PHP source.
+///
+/// Static calls on a class (`Payment::charge()`, `\App\Services\Payment::charge()`)
+/// are recorded as `Namespace\Class::method`, with the class resolved through
+/// the file's `namespace` and `use` aliases, so grounding can tell apart
+/// same-named methods on classes in different namespaces.
 pub fn extract_php_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::PHP.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -1373,14 +1980,92 @@ pub fn extract_php_deps(_path: &Path, source: &str) -> RawDeps {
     collect_php_scopes(&root, source, &mut scopes, None);
 
     // Collect imports and inheritance
-    collect_php_imports_and_inheritance(&root, source, &mut deps);
+    let mut names = PhpNames::default();
+    collect_php_imports_and_inheritance(&root, source, &mut deps, &mut names);
 
     // Collect calls
-    collect_php_calls(&root, source, &scopes, &mut deps.calls);
+    collect_php_calls(&root, source, &scopes, &names, &mut deps.calls);
 
     deps
 }
 
+/// Name context for resolving PHP class references: the file's namespace and
+/// its `use` aliases (alias → fully qualified name).
+#[derive(Default)]
+struct PhpNames {
+    namespace: Option<String>,
+    aliases: HashMap<String, String>,
+}
+
+impl PhpNames {
+    /// Fully qualify a class reference as written in source, without the
+    /// leading backslash.
+    fn qualify(&self, class: &str) -> String {
+        if let Some(absolute) = class.strip_prefix('\\') {
+            return absolute.to_string();
+        }
+        let (first, rest) = match class.split_once('\\') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (class, None),
+        };
+        if let Some(target) = self.aliases.get(first) {
+            return match rest {
+                Some(rest) => format!("{}\\{}", target, rest),
+                None => target.clone(),
+            };
+        }
+        match &self.namespace {
+            Some(ns) => format!("{}\\{}", ns, class),
+            None => class.to_string(),
+        }
+    }
+}
+
+/// The unqualified name of a PHP class reference (`\App\Models\User` → `User`).
+fn php_simple_name(name: &str) -> &str {
+    name.rsplit('\\').next().unwrap_or(name).trim()
+}
+
+/// Split a `use` declaration into `(fully qualified name, alias)` pairs,
+/// expanding group syntax (`use App\{Foo, Bar as Baz};`).
+fn php_use_clauses(text: &str) -> Vec<(String, String)> {
+    let body = text
+        .trim()
+        .trim_start_matches("use ")
+        .trim_start()
+        .trim_start_matches("function ")
+        .trim_start_matches("const ")
+        .trim_end_matches(';')
+        .trim();
+    let (prefix, items) = match body.split_once('{') {
+        Some((prefix, group)) => (
+            prefix.trim().trim_end_matches('\\'),
+            group.trim_end_matches('}'),
+        ),
+        None => ("", body),
+    };
+    items
+        .split(',')
+        .filter_map(|item| {
+            let item = item.trim();
+            let (path, alias) = match item.split_once(" as ") {
+                Some((path, alias)) => (path.trim(), Some(alias.trim())),
+                None => (item, None),
+            };
+            if path.is_empty() {
+                return None;
+            }
+            let full = if prefix.is_empty() {
+                path.trim_start_matches('\\').to_string()
+            } else {
+                format!("{}\\{}", prefix.trim_start_matches('\\'), path)
+            };
+            let alias = alias.unwrap_or_else(|| php_simple_name(path)).to_string();
+            Some((full, alias))
+        })
+        .collect()
+}
+
 fn collect_php_scopes(
     node: &tree_sitter::Node,
     source: &str,
@@ -1418,7 +2103,10 @@ fn collect_php_scopes(
                     });
                 }
             }
-            "class_declaration" => {
+            "class_declaration"
+            | "interface_declaration"
+            | "trait_declaration"
+            | "enum_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let cls = source[name_node.byte_range()].to_string();
                     collect_php_scopes(&child, source, scopes, Some(&cls));
@@ -1431,68 +2119,70 @@ fn collect_php_scopes(
     }
 }
 
-fn collect_php_imports_and_inheritance(node: &tree_sitter::Node, source: &str, deps: &mut RawDeps) {
+fn collect_php_imports_and_inheritance(
+    node: &tree_sitter::Node,
+    source: &str,
+    deps: &mut RawDeps,
+    names: &mut PhpNames,
+) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
+            "namespace_definition" => {
+                if let Some(name_node) = child.child_by_field_name("name")
+                    && names.namespace.is_none()
+                {
+                    let ns = source[name_node.byte_range()].trim_start_matches('\\');
+                    names.namespace = Some(ns.to_string());
+                }
+                collect_php_imports_and_inheritance(&child, source, deps, names);
+            }
             "namespace_use_declaration" => {
-                // use Foo\Bar\Baz; or use Foo\Bar\{Baz, Qux};
-                let text = source[child.byte_range()].trim().to_string();
-                let module = text
-                    .trim_start_matches("use ")
-                    .trim_start_matches("function ")
-                    .trim_start_matches("const ")
-                    .trim_end_matches(';')
-                    .trim()
-                    .to_string();
-                deps.imports.push(ImportDep {
-                    module,
-                    symbols: Vec::new(),
-                });
+                // use Foo\Bar\Baz; use Foo\Bar as Qux; or use Foo\Bar\{Baz, Qux};
+                // Each imported name becomes a symbol of its namespace.
+                for (full, alias) in php_use_clauses(&source[child.byte_range()]) {
+                    let (module, symbol) = match full.rsplit_once('\\') {
+                        Some((module, symbol)) => (module.to_string(), vec![symbol.to_string()]),
+                        None => (full.clone(), Vec::new()),
+                    };
+                    match deps.imports.iter_mut().find(|imp| imp.module == module) {
+                        Some(existing) if !symbol.is_empty() => existing.symbols.extend(symbol),
+                        _ => deps.imports.push(ImportDep {
+                            module,
+                            symbols: symbol,
+                        }),
+                    }
+                    names.aliases.insert(alias, full);
+                }
             }
-            "class_declaration" => {
+            "class_declaration" | "interface_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let class_name = source[name_node.byte_range()].to_string();
-                    // Check for extends (base_clause)
+                    // extends Parent (base_clause) and implements A, B (class_interface_clause)
                     let mut ic = child.walk();
                     for c in child.children(&mut ic) {
-                        match c.kind() {
-                            "base_clause" => {
-                                // extends ParentClass
-                                let text = &source[c.byte_range()];
-                                let text = text.trim_start_matches("extends").trim();
-                                for parent in text.split(',') {
-                                    let parent = parent.trim().to_string();
-                                    if !parent.is_empty() {
-                                        deps.inherits.push(InheritDep {
-                                            child_class: class_name.clone(),
-                                            parent_class: parent,
-                                        });
-                                    }
-                                }
-                            }
-                            "class_interface_clause" => {
-                                // implements Interface1, Interface2
-                                let text = &source[c.byte_range()];
-                                let text = text.trim_start_matches("implements").trim();
-                                for iface in text.split(',') {
-                                    let iface = iface.trim().to_string();
-                                    if !iface.is_empty() {
-                                        deps.inherits.push(InheritDep {
-                                            child_class: class_name.clone(),
-                                            parent_class: iface,
-                                        });
-                                    }
-                                }
+                        let keyword = match c.kind() {
+                            "base_clause" => "extends",
+                            "class_interface_clause" => "implements",
+                            _ => continue,
+                        };
+                        let text = &source[c.byte_range()];
+                        let text = text.trim_start_matches(keyword).trim();
+                        for parent in text.split(',') {
+                            let parent = php_simple_name(parent);
+                            if !parent.is_empty() {
+                                deps.inherits.push(InheritDep {
+                                    child_class: class_name.clone(),
+                                    parent_class: parent.to_string(),
+                                });
                             }
-                            _ => {}
                         }
                     }
                 }
-                collect_php_imports_and_inheritance(&child, source, deps);
+                collect_php_imports_and_inheritance(&child, source, deps, names);
             }
             _ => {
-                collect_php_imports_and_inheritance(&child, source, deps);
+                collect_php_imports_and_inheritance(&child, source, deps, names);
             }
         }
     }
@@ -1502,53 +2192,52 @@ fn collect_php_calls(
     node: &tree_sitter::Node,
     source: &str,
     scopes: &[FunctionScope],
+    names: &PhpNames,
     calls: &mut Vec<CallDep>,
 ) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        match child.kind() {
-            "function_call_expression" => {
-                if let Some(func_node) = child.child_by_field_name("function") {
-                    let callee = extract_callee_name(&func_node, source);
-                    if !callee.is_empty() {
-                        let caller = find_enclosing_scope(scopes, child.start_position().row)
-                            .unwrap_or_else(|| "<module>".to_string());
-                        calls.push(CallDep {
-                            caller_entity: caller,
-                            callee,
-                        });
-                    }
-                }
-            }
-            "member_call_expression" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let callee = source[name_node.byte_range()].to_string();
-                    if !callee.is_empty() {
-                        let caller = find_enclosing_scope(scopes, child.start_position().row)
-                            .unwrap_or_else(|| "<module>".to_string());
-                        calls.push(CallDep {
-                            caller_entity: caller,
-                            callee,
-                        });
+        let callee = match child.kind() {
+            // foo(), \App\helpers\foo()
+            "function_call_expression" => child
+                .child_by_field_name("function")
+                .map(|func| php_simple_name(&source[func.byte_range()]).to_string()),
+            // $obj->method()
+            "member_call_expression" => child
+                .child_by_field_name("name")
+                .map(|name| source[name.byte_range()].to_string()),
+            // Class::method(), self::method()
+            "scoped_call_expression" => child.child_by_field_name("name").map(|name| {
+                let method = &source[name.byte_range()];
+                match child.child_by_field_name("scope") {
+                    Some(scope) if matches!(scope.kind(), "name" | "qualified_name") => {
+                        let class = names.qualify(&source[scope.byte_range()]);
+                        format!("{}::{}", class, method)
                     }
+                    // self::, static::, parent::, $var:: — the class is not
+                    // known statically.
+                    _ => method.to_string(),
                 }
-            }
-            "scoped_call_expression" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let callee = source[name_node.byte_range()].to_string();
-                    if !callee.is_empty() {
-                        let caller = find_enclosing_scope(scopes, child.start_position().row)
-                            .unwrap_or_else(|| "<module>".to_string());
-                        calls.push(CallDep {
-                            caller_entity: caller,
-                            callee,
-                        });
-                    }
-                }
-            }
-            _ => {}
+            }),
+            // new Foo()
+            "object_creation_expression" => {
+                let mut cc = child.walk();
+                child
+                    .children(&mut cc)
+                    .find(|c| matches!(c.kind(), "name" | "qualified_name"))
+                    .map(|class| php_simple_name(&source[class.byte_range()]).to_string())
+            }
+            _ => None,
+        };
+        if let Some(callee) = callee.filter(|c| !c.is_empty()) {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            calls.push(CallDep {
+                caller_entity: caller,
+                callee,
+            });
         }
-        collect_php_calls(&child, source, scopes, calls);
+        collect_php_calls(&child, source, scopes, names, calls);
     }
 }
 
@@ -1559,11 +2248,7 @@ fn collect_php_calls(
 /// Extract deps from Ruby source.
 pub fn extract_ruby_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::RUBY.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -1757,11 +2442,7 @@ fn collect_ruby_calls(
 /// Extract deps from Kotlin source.
 pub fn extract_kotlin_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::KOTLIN.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -1793,7 +2474,10 @@ fn collect_kotlin_scopes(
             "function_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = source[name_node.byte_range()].to_string();
-                    let scope_name = match parent_class {
+                    // Extension functions are scoped by their receiver, matching entity IDs.
+                    let receiver =
+                        crate::entities::kotlin_receiver_type(&child, &name_node, source);
+                    let scope_name = match receiver.as_deref().or(parent_class) {
                         Some(cls) => format!("{}.{}", cls, name),
                         None => name,
                     };
@@ -1811,6 +2495,18 @@ fn collect_kotlin_scopes(
                     continue;
                 }
             }
+            "companion_object" => {
+                let name = match child.child_by_field_name("name") {
+                    Some(name_node) => &source[name_node.byte_range()],
+                    None => crate::entities::kotlin_companion_name(&child, source),
+                };
+                let cls = match parent_class {
+                    Some(outer) => format!("{}.{}", outer, name),
+                    None => name.to_string(),
+                };
+                collect_kotlin_scopes(&child, source, scopes, Some(&cls));
+                continue;
+            }
             _ => {}
         }
         collect_kotlin_scopes(&child, source, scopes, parent_class);
@@ -1828,13 +2524,24 @@ fn collect_kotlin_imports_and_inheritance(
             // "import_header" (old grammar) / "import" (kotlin-ng grammar)
             "import_header" | "import" if child.is_named() => {
                 let text = source[child.byte_range()].trim().to_string();
-                let module = text.trim_start_matches("import ").trim().to_string();
+                // `import a.b.C as D` binds C; `import a.b.*` names no symbol.
+                let module = text
+                    .trim_start_matches("import ")
+                    .split(" as ")
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_string();
                 if !module.is_empty() {
                     let parts: Vec<&str> = module.rsplitn(2, '.').collect();
                     if parts.len() == 2 {
                         deps.imports.push(ImportDep {
                             module: parts[1].to_string(),
-                            symbols: vec![parts[0].to_string()],
+                            symbols: if parts[0] == "*" {
+                                Vec::new()
+                            } else {
+                                vec![parts[0].to_string()]
+                            },
                         });
                     } else {
                         deps.imports.push(ImportDep {
@@ -1935,11 +2642,7 @@ fn collect_kotlin_calls(
 /// Extract deps from Swift source.
 pub fn extract_swift_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::SWIFT.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -2124,11 +2827,7 @@ fn collect_swift_calls(
 /// Extract deps from Scala source.
 pub fn extract_scala_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::SCALA.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -2154,6 +2853,7 @@ fn collect_scala_scopes(
     scopes: &mut Vec<FunctionScope>,
     parent_class: Option<&str>,
 ) {
+    let companions = crate::entities::scala_companion_classes(node, source);
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
@@ -2171,9 +2871,8 @@ fn collect_scala_scopes(
                     });
                 }
             }
-            "class_definition" | "trait_definition" | "object_definition" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let cls = source[name_node.byte_range()].to_string();
+            "class_definition" | "trait_definition" | "object_definition" | "enum_definition" => {
+                if let Some(cls) = crate::entities::scala_type_name(&child, source, &companions) {
                     collect_scala_scopes(&child, source, scopes, Some(&cls));
                     continue;
                 }
@@ -2184,51 +2883,94 @@ fn collect_scala_scopes(
     }
 }
 
+/// Simple name of a Scala type reference (`org.base.Runner[T]` → `Runner`).
+fn scala_simple_type(text: &str) -> &str {
+    let text = text.split('[').next().unwrap_or("").trim();
+    text.rsplit('.').next().unwrap_or(text)
+}
+
+/// `import a.b.C` → module `a.b`, symbol `C`; `import a.b.{C, D => E}` →
+/// symbols `C`, `D`; `import a.b._` (or `*`) → module `a.b`, no symbols.
+fn scala_import(node: &tree_sitter::Node, source: &str) -> Option<ImportDep> {
+    let mut cursor = node.walk();
+    let mut path: Vec<&str> = node
+        .children_by_field_name("path", &mut cursor)
+        .filter(|n| n.is_named())
+        .map(|n| &source[n.byte_range()])
+        .collect();
+    let mut symbols = Vec::new();
+    // Whether the path already ends at the module (`._` or `.{...}`).
+    let mut path_is_module = false;
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "namespace_wildcard" => path_is_module = true,
+            "namespace_selectors" => {
+                path_is_module = true;
+                let mut sc = child.walk();
+                for selector in child.named_children(&mut sc) {
+                    let name = match selector.kind() {
+                        "identifier" => Some(selector),
+                        "arrow_renamed_identifier" | "as_renamed_identifier" => {
+                            selector.child_by_field_name("name")
+                        }
+                        _ => None,
+                    };
+                    if let Some(name) = name {
+                        symbols.push(source[name.byte_range()].to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !path_is_module && path.len() > 1 {
+        symbols.push(path.pop()?.to_string());
+    }
+    if path.is_empty() {
+        return None;
+    }
+    Some(ImportDep {
+        module: path.join("."),
+        symbols,
+    })
+}
+
 fn collect_scala_imports_and_inheritance(
     node: &tree_sitter::Node,
     source: &str,
     deps: &mut RawDeps,
 ) {
+    let companions = crate::entities::scala_companion_classes(node, source);
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
             "import_declaration" => {
-                let text = source[child.byte_range()].trim().to_string();
-                let module = text.trim_start_matches("import ").trim().to_string();
-                if !module.is_empty() {
-                    deps.imports.push(ImportDep {
-                        module,
-                        symbols: Vec::new(),
-                    });
+                if let Some(import) = scala_import(&child, source) {
+                    deps.imports.push(import);
                 }
             }
-            "class_definition" | "trait_definition" | "object_definition" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let class_name = source[name_node.byte_range()].to_string();
-                    // Look for extends_clause
+            "class_definition" | "trait_definition" | "object_definition" | "enum_definition" => {
+                if let Some(class_name) =
+                    crate::entities::scala_type_name(&child, source, &companions)
+                {
+                    // extends Base(args) with Mixin[T] with other.Trait
                     let mut ic = child.walk();
                     for c in child.children(&mut ic) {
-                        if c.kind() == "extends_clause" {
-                            let text = &source[c.byte_range()];
-                            let text = text.trim_start_matches("extends").trim();
-                            // May contain "with" for trait mixing
-                            for part in text.split(" with ") {
-                                let parent = part
-                                    .trim()
-                                    .split('(')
-                                    .next()
-                                    .unwrap_or("")
-                                    .split('[')
-                                    .next()
-                                    .unwrap_or("")
-                                    .trim()
-                                    .to_string();
-                                if !parent.is_empty() {
-                                    deps.inherits.push(InheritDep {
-                                        child_class: class_name.clone(),
-                                        parent_class: parent,
-                                    });
-                                }
+                        if c.kind() != "extends_clause" {
+                            continue;
+                        }
+                        let mut tc = c.walk();
+                        for ty in c
+                            .children_by_field_name("type", &mut tc)
+                            .filter(|n| n.is_named())
+                        {
+                            let parent = scala_simple_type(&source[ty.byte_range()]);
+                            if !parent.is_empty() {
+                                deps.inherits.push(InheritDep {
+                                    child_class: class_name.clone(),
+                                    parent_class: parent.to_string(),
+                                });
                             }
                         }
                     }
@@ -2250,29 +2992,26 @@ fn collect_scala_calls(
 ) {
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
-        if child.kind() == "call_expression" {
-            if let Some(func_node) = child.child_by_field_name("function") {
-                let callee = extract_callee_name(&func_node, source);
-                if !callee.is_empty() {
-                    let caller = find_enclosing_scope(scopes, child.start_position().row)
-                        .unwrap_or_else(|| "<module>".to_string());
-                    calls.push(CallDep {
-                        caller_entity: caller,
-                        callee,
-                    });
-                }
-            } else if let Some(func_node) = child.child(0) {
-                // Fallback: first child is the callee
-                let callee = extract_callee_name(&func_node, source);
-                if !callee.is_empty() {
-                    let caller = find_enclosing_scope(scopes, child.start_position().row)
-                        .unwrap_or_else(|| "<module>".to_string());
-                    calls.push(CallDep {
-                        caller_entity: caller,
-                        callee,
-                    });
-                }
-            }
+        let callee = match child.kind() {
+            // f(x), obj.method(x), Point(1, 2) (case class / companion apply)
+            "call_expression" => child
+                .child_by_field_name("function")
+                .or_else(|| child.child(0))
+                .map(|func| extract_callee_name(&func, source)),
+            // new Job(name)
+            "instance_expression" => child
+                .named_child(0)
+                .filter(|ty| ty.kind() != "template_body")
+                .map(|ty| scala_simple_type(&source[ty.byte_range()]).to_string()),
+            _ => None,
+        };
+        if let Some(callee) = callee.filter(|c| !c.is_empty()) {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            calls.push(CallDep {
+                caller_entity: caller,
+                callee,
+            });
         }
         collect_scala_calls(&child, source, scopes, calls);
     }
@@ -2285,11 +3024,7 @@ fn collect_scala_calls(
 /// Extract deps from Bash/Shell source.
 pub fn extract_bash_deps(_path: &Path, source: &str) -> RawDeps {
     let lang = Language::BASH.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return RawDeps::default();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return RawDeps::default();
     };
 
@@ -0,0 +1,138 @@
+//! Syntax errors tree-sitter recovered from while parsing a file.
+//!
+//! Entity extraction walks whatever tree the parser produced, so a file with
+//! syntax errors yields partial entities (or none) without failing. These
+//! diagnostics make that visible in `build` output and `rpg_info`.
+
+use crate::languages::Language;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Files listed individually under the summary; the rest are counted.
+pub const MAX_LISTED_FILES: usize = 10;
+
+/// Syntax errors in one parsed file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDiagnostic {
+    pub file: PathBuf,
+    /// 1-based inclusive line ranges of ERROR and MISSING nodes; nested
+    /// errors are covered by their outermost range.
+    pub error_ranges: Vec<(usize, usize)>,
+    /// Entities extracted from the file despite the errors.
+    pub entity_count: usize,
+}
+
+/// Line ranges of syntax errors in `source`, parsed with the grammar entity
+/// extraction uses for `language`. Empty for clean files.
+pub fn syntax_error_ranges(path: &Path, source: &str, language: Language) -> Vec<(usize, usize)> {
+    let Ok(tree) = crate::treesitter::parse_file(path, source.as_bytes(), &language.ts_language())
+    else {
+        return Vec::new();
+    };
+    let mut ranges = Vec::new();
+    if tree.root_node().has_error() {
+        collect_error_ranges(tree.root_node(), &mut ranges);
+        // Several errors on one line count as one region.
+        ranges.dedup();
+    }
+    ranges
+}
+
+fn collect_error_ranges(node: tree_sitter::Node, ranges: &mut Vec<(usize, usize)>) {
+    if node.is_error() || node.is_missing() {
+        ranges.push((node.start_position().row + 1, node.end_position().row + 1));
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.has_error() {
+            collect_error_ranges(child, ranges);
+        }
+    }
+}
+
+/// A diagnostic for `path` if `source` has syntax errors.
+pub fn diagnose(
+    path: &Path,
+    source: &str,
+    language: Language,
+    entity_count: usize,
+) -> Option<ParseDiagnostic> {
+    let error_ranges = syntax_error_ranges(path, source, language);
+    (!error_ranges.is_empty()).then(|| ParseDiagnostic {
+        file: path.to_path_buf(),
+        error_ranges,
+        entity_count,
+    })
+}
+
+/// One-line summary, e.g. `3 files had syntax errors (12 regions skipped)`.
+/// `None` when there are no diagnostics.
+pub fn format_summary(diagnostics: &[ParseDiagnostic]) -> Option<String> {
+    if diagnostics.is_empty() {
+        return None;
+    }
+    let regions: usize = diagnostics.iter().map(|d| d.error_ranges.len()).sum();
+    Some(format!(
+        "{} file{} had syntax errors ({} region{} skipped)",
+        diagnostics.len(),
+        if diagnostics.len() == 1 { "" } else { "s" },
+        regions,
+        if regions == 1 { "" } else { "s" },
+    ))
+}
+
+/// The summary followed by one line per file (up to [`MAX_LISTED_FILES`]):
+/// `path: lines 3-5, 9 (1 entity extracted)`.
+pub fn format_report(diagnostics: &[ParseDiagnostic]) -> Option<String> {
+    let mut out = format_summary(diagnostics)?;
+    for diagnostic in diagnostics.iter().take(MAX_LISTED_FILES) {
+        let lines: Vec<String> = diagnostic
+            .error_ranges
+            .iter()
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, end)
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "\n  {}: line{} {} ({} entit{} extracted)",
+            rpg_core::graph::normalize_path(&diagnostic.file),
+            if lines.len() == 1 && !lines[0].contains('-') {
+                ""
+            } else {
+                "s"
+            },
+            lines.join(", "),
+            diagnostic.entity_count,
+            if diagnostic.entity_count == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+        ));
+    }
+    if diagnostics.len() > MAX_LISTED_FILES {
+        out.push_str(&format!(
+            "\n  … and {} more",
+            diagnostics.len() - MAX_LISTED_FILES
+        ));
+    }
+    Some(out)
+}
+
+/// Error regions per file, for `GraphMetadata::parse_errors`.
+pub fn error_counts(diagnostics: &[ParseDiagnostic]) -> BTreeMap<String, usize> {
+    diagnostics
+        .iter()
+        .map(|d| {
+            (
+                rpg_core::graph::normalize_path(&d.file),
+                d.error_ranges.len(),
+            )
+        })
+        .collect()
+}
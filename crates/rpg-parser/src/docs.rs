@@ -0,0 +1,199 @@
+//! Author documentation for extracted entities: Python docstrings, Rust `///`
+//! blocks, Go doc comments, and `/** … */` blocks (JSDoc, Javadoc).
+//!
+//! Docs are stored without comment markers or quotes and cut to
+//! [`MAX_DOC_LINES`] lines, so lifting prompts and `fetch_node` stay compact.
+
+use crate::entities::{MODULE_SCOPE_NAME, RawEntity};
+use crate::languages::Language;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Longer docs are cut to this many lines plus a `… (N more lines)` marker.
+pub const MAX_DOC_LINES: usize = 20;
+
+/// Fill `doc_comment` on entities that have documentation in `source`.
+pub fn attach_doc_comments(entities: &mut [RawEntity], source: &str, language: Language) {
+    if language == Language::PYTHON {
+        let docstrings = python_docstrings(source);
+        for entity in entities.iter_mut().filter(|e| e.doc_comment.is_none()) {
+            entity.doc_comment = docstrings
+                .get(&(entity.line_start, entity.name.as_str()))
+                .cloned();
+        }
+        return;
+    }
+
+    let line_prefixes = doc_line_prefixes(language);
+    let lines: Vec<&str> = source.lines().collect();
+    for entity in entities
+        .iter_mut()
+        .filter(|e| e.doc_comment.is_none() && e.name != MODULE_SCOPE_NAME)
+    {
+        entity.doc_comment =
+            comment_above(&lines, entity.line_start, line_prefixes).map(|doc| truncate_doc(&doc));
+    }
+}
+
+/// Line-comment markers that document the next item. `/** … */` blocks are
+/// recognized in every language.
+fn doc_line_prefixes(language: Language) -> &'static [&'static str] {
+    if language == Language::RUST {
+        &["///"]
+    } else if language == Language::GO {
+        &["//"]
+    } else {
+        &[]
+    }
+}
+
+/// The doc comment ending right above 1-based `line_start`, skipping
+/// attribute and annotation lines (`#[derive(..)]`, `@Override`).
+fn comment_above(lines: &[&str], line_start: usize, line_prefixes: &[&str]) -> Option<String> {
+    let mut end = line_start.checked_sub(1)?;
+    while end > 0 {
+        let line = lines.get(end - 1)?.trim();
+        if !(line.starts_with("#[") || line.starts_with('@')) {
+            break;
+        }
+        end -= 1;
+    }
+    let last = lines.get(end.checked_sub(1)?)?.trim();
+
+    if last.ends_with("*/") {
+        let start = (0..end).rev().find(|&i| lines[i].contains("/*"))?;
+        if !lines[start].trim_start().starts_with("/**") {
+            return None;
+        }
+        return strip_block(&lines[start..end]);
+    }
+
+    let prefix = line_prefixes.iter().find(|p| last.starts_with(*p))?;
+    let start = (0..end)
+        .rev()
+        .take_while(|&i| lines[i].trim_start().starts_with(prefix))
+        .last()?;
+    let body: Vec<&str> = lines[start..end]
+        .iter()
+        .map(|line| {
+            let text = &line.trim_start()[prefix.len()..];
+            text.strip_prefix(' ').unwrap_or(text)
+        })
+        .collect();
+    non_empty(body.join("\n"))
+}
+
+/// `/** … */` lines without the delimiters and leading `*`s.
+fn strip_block(lines: &[&str]) -> Option<String> {
+    let count = lines.len();
+    let body: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut text = line.trim();
+            if i == 0 {
+                text = text.trim_start_matches("/**");
+            }
+            if i + 1 == count {
+                text = text.trim_end_matches("*/");
+            }
+            let text = text.trim();
+            let text = text.strip_prefix('*').unwrap_or(text);
+            text.strip_prefix(' ').unwrap_or(text).trim_end()
+        })
+        .collect();
+    non_empty(body.join("\n"))
+}
+
+/// Python docstrings keyed by the definition's first line (its first
+/// decorator, matching entity spans) and name.
+fn python_docstrings(source: &str) -> HashMap<(usize, &str), String> {
+    let mut docs = HashMap::new();
+    let Ok(tree) = crate::treesitter::parse_file(
+        Path::new("docstrings.py"),
+        source.as_bytes(),
+        &Language::PYTHON.ts_language(),
+    ) else {
+        return docs;
+    };
+    collect_python_docstrings(tree.root_node(), source, &mut docs);
+    docs
+}
+
+fn collect_python_docstrings<'a>(
+    node: tree_sitter::Node,
+    source: &'a str,
+    docs: &mut HashMap<(usize, &'a str), String>,
+) {
+    if matches!(node.kind(), "function_definition" | "class_definition")
+        && let Some(name) = node.child_by_field_name("name")
+        && let Some(doc) = node
+            .child_by_field_name("body")
+            .and_then(|body| body.named_child(0))
+            .filter(|stmt| stmt.kind() == "expression_statement")
+            .and_then(|stmt| stmt.named_child(0))
+            .filter(|expr| expr.kind() == "string")
+            .and_then(|string| strip_docstring(&source[string.byte_range()]))
+    {
+        let span = node
+            .parent()
+            .filter(|p| p.kind() == "decorated_definition")
+            .unwrap_or(node);
+        docs.insert(
+            (span.start_position().row + 1, &source[name.byte_range()]),
+            truncate_doc(&doc),
+        );
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_python_docstrings(child, source, docs);
+    }
+}
+
+/// A docstring literal without its prefix and quotes, dedented.
+fn strip_docstring(literal: &str) -> Option<String> {
+    let text = literal.trim_start_matches(|c: char| "rRuUbBfF".contains(c));
+    let quote = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|q| text.starts_with(q) && text.len() >= 2 * q.len())?;
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    let mut lines = inner.lines();
+    let first = lines.next().unwrap_or("").trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut out = vec![first];
+    out.extend(
+        rest.iter()
+            .map(|line| line.get(indent..).unwrap_or("").trim_end()),
+    );
+    non_empty(out.join("\n"))
+}
+
+/// Trimmed of surrounding blank lines; `None` if nothing is left.
+fn non_empty(doc: String) -> Option<String> {
+    let lines: Vec<&str> = doc
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+    let doc = lines.join("\n");
+    let doc = doc.trim_end();
+    (!doc.is_empty()).then(|| doc.to_string())
+}
+
+/// Cut to [`MAX_DOC_LINES`] lines.
+fn truncate_doc(doc: &str) -> String {
+    let total = doc.lines().count();
+    if total <= MAX_DOC_LINES {
+        return doc.to_string();
+    }
+    let mut out: Vec<&str> = doc.lines().take(MAX_DOC_LINES).collect();
+    let marker = format!("… ({} more lines)", total - MAX_DOC_LINES);
+    out.push(&marker);
+    out.join("\n")
+}
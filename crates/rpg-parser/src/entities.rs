@@ -1,7 +1,10 @@
-//! Extract code entities (functions, classes, methods) from AST.
+//! Extract code entities (functions, classes, methods, enums, constants) from AST.
 
 use crate::languages::Language;
-use rpg_core::graph::{Entity, EntityDeps, EntityKind, Param, Signature, normalize_path};
+use rpg_core::graph::{
+    Entity, EntityDeps, EntityKind, FEATURES_ATTRIBUTE, Param, Signature, normalize_path,
+};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::Path;
 
 /// A raw parameter extracted from AST.
@@ -29,6 +32,18 @@ pub struct RawEntity {
     pub parent_class: Option<String>,
     pub source_text: String,
     pub signature: Option<RawSignature>,
+    /// Paradigm-specific metadata carried through to `Entity::attributes`.
+    pub attributes: BTreeMap<String, String>,
+    /// The author's documentation: a docstring or the doc comment above the
+    /// definition, without comment markers (see [`crate::docs`]).
+    pub doc_comment: Option<String>,
+    /// Python and JS/TS decorators as written, without the `@` (see
+    /// [`python_decorators`] and [`js_decorators`]).
+    pub decorators: Vec<String>,
+    /// Function modifiers (see [`crate::flags`]).
+    pub is_async: bool,
+    pub is_generator: bool,
+    pub is_unsafe: bool,
 }
 
 impl RawEntity {
@@ -40,9 +55,34 @@ impl RawEntity {
         }
     }
 
-    /// Convert to a full Entity (with empty semantic features and deps).
-    pub fn into_entity(self) -> Entity {
+    /// Render paradigm attributes as `key: value` pairs joined by `; ` (empty if none).
+    pub fn attribute_summary(&self) -> String {
+        self.attributes
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Render decorators as `@name` joined by spaces (empty if none).
+    pub fn decorator_summary(&self) -> String {
+        self.decorators
+            .iter()
+            .map(|d| format!("@{}", d))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Convert to a full Entity (with empty deps). Semantic features are
+    /// empty unless a paradigm synthesized some (see [`FEATURES_ATTRIBUTE`]).
+    pub fn into_entity(mut self) -> Entity {
         let id = self.id();
+        let semantic_features: Vec<String> = self
+            .attributes
+            .remove(FEATURES_ATTRIBUTE)
+            .map(|f| f.split("; ").map(str::to_string).collect())
+            .unwrap_or_default();
+        let feature_source = (!semantic_features.is_empty()).then(|| "paradigm".to_string());
         let signature = self.signature.map(|sig| Signature {
             parameters: sig
                 .parameters
@@ -62,11 +102,24 @@ impl RawEntity {
             line_start: self.line_start,
             line_end: self.line_end,
             parent_class: self.parent_class,
-            semantic_features: Vec::new(),
-            feature_source: None,
+            semantic_features,
+            feature_source,
+            lifted_at: None,
             hierarchy_path: String::new(),
             deps: EntityDeps::default(),
             signature,
+            attributes: self.attributes,
+            source_signature: Vec::new(),
+            owners: Vec::new(),
+            layer: None,
+            tags: BTreeSet::new(),
+            churn: None,
+            doc_comment: self.doc_comment,
+            decorators: self.decorators,
+            is_async: self.is_async,
+            is_generator: self.is_generator,
+            is_unsafe: self.is_unsafe,
+            entry_distance: None,
         }
     }
 }
@@ -74,16 +127,20 @@ impl RawEntity {
 /// Extract entities from a Python source file using tree-sitter.
 pub fn extract_python_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang: tree_sitter::Language = tree_sitter_python::LANGUAGE.into();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
 
     let mut entities = Vec::new();
     extract_python_node(&tree.root_node(), path, source, None, &mut entities);
+    extract_python_constants(&tree.root_node(), path, source, &mut entities);
+    entities.extend(module_script_entity(
+        &tree.root_node(),
+        path,
+        source,
+        &entities,
+        &PYTHON_SCRIPT_KINDS,
+    ));
     entities
 }
 
@@ -98,6 +155,7 @@ fn extract_python_node(
     for child in node.children(&mut cursor) {
         match child.kind() {
             "function_definition" => {
+                let span = decorated_span(child);
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = &source[name_node.byte_range()];
                     let kind = if parent_class.is_some() {
@@ -109,11 +167,17 @@ fn extract_python_node(
                         name: name.to_string(),
                         kind,
                         file: path.to_path_buf(),
-                        line_start: child.start_position().row + 1,
+                        line_start: span.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
-                        source_text: source[child.byte_range()].to_string(),
+                        source_text: source[span.byte_range()].to_string(),
                         signature: extract_python_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: python_decorators(span, source),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -123,17 +187,28 @@ fn extract_python_node(
                 extract_python_node(&child, path, source, parent_class, entities);
             }
             "class_definition" => {
+                let span = decorated_span(child);
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let class_name = &source[name_node.byte_range()];
                     entities.push(RawEntity {
                         name: class_name.to_string(),
-                        kind: EntityKind::Class,
+                        kind: if is_python_enum(&child, source) {
+                            EntityKind::Enum
+                        } else {
+                            EntityKind::Class
+                        },
                         file: path.to_path_buf(),
-                        line_start: child.start_position().row + 1,
+                        line_start: span.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: None,
-                        source_text: source[child.byte_range()].to_string(),
+                        source_text: source[span.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: python_decorators(span, source),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     // Recurse into class body for methods
                     if let Some(body) = child.child_by_field_name("body") {
@@ -151,14 +226,269 @@ fn extract_python_node(
     }
 }
 
+/// Python: a decorated definition's span includes its decorators, so framework
+/// markers like `@app.get("/users")` are part of the entity's source text.
+fn decorated_span(node: tree_sitter::Node) -> tree_sitter::Node {
+    node.parent()
+        .filter(|p| p.kind() == "decorated_definition")
+        .unwrap_or(node)
+}
+
+/// Python: the decorators on a definition (given its [`decorated_span`]), as
+/// written without the `@` and with whitespace collapsed, top to bottom.
+fn python_decorators(span: tree_sitter::Node, source: &str) -> Vec<String> {
+    if span.kind() != "decorated_definition" {
+        return Vec::new();
+    }
+    let mut cursor = span.walk();
+    span.children(&mut cursor)
+        .filter(|c| c.kind() == "decorator")
+        .map(|d| decorator_text(d, source))
+        .collect()
+}
+
+/// A decorator without the `@` and with whitespace collapsed.
+fn decorator_text(decorator: tree_sitter::Node, source: &str) -> String {
+    source[decorator.byte_range()]
+        .trim_start_matches('@')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ---------------------------------------------------------------------------
+// Enums and module-level constants
+// ---------------------------------------------------------------------------
+
+/// Python base classes that make a class an enumeration.
+const PYTHON_ENUM_BASES: &[&str] = &["Enum", "IntEnum", "StrEnum", "Flag", "IntFlag"];
+
+/// JS/TS initializer kinds that make a top-level `const` a constant rather
+/// than a computed value (`const app = express()`).
+const JS_CONSTANT_VALUES: &[&str] = &[
+    "string",
+    "template_string",
+    "number",
+    "true",
+    "false",
+    "null",
+    "object",
+    "array",
+    "regex",
+    "unary_expression",
+    "as_expression",
+    "satisfies_expression",
+];
+
+/// Whether `name` follows the SCREAMING_CASE constant convention: at least
+/// two characters, starting with a letter, with no lowercase letters.
+pub fn is_constant_name(name: &str) -> bool {
+    name.len() >= 2
+        && name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// A module-level constant declared by `node`.
+fn constant_entity(name: &str, node: &tree_sitter::Node, path: &Path, source: &str) -> RawEntity {
+    RawEntity {
+        name: name.to_string(),
+        kind: EntityKind::Constant,
+        file: path.to_path_buf(),
+        line_start: node.start_position().row + 1,
+        line_end: node.end_position().row + 1,
+        parent_class: None,
+        source_text: source[node.byte_range()].to_string(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+    }
+}
+
+/// Python: whether a class derives from `Enum` (or `enum.IntEnum`, ...).
+fn is_python_enum(class: &tree_sitter::Node, source: &str) -> bool {
+    let Some(bases) = class.child_by_field_name("superclasses") else {
+        return false;
+    };
+    let mut cursor = bases.walk();
+    bases
+        .named_children(&mut cursor)
+        .filter(|base| matches!(base.kind(), "identifier" | "attribute"))
+        .any(|base| {
+            let name = source[base.byte_range()].rsplit('.').next().unwrap_or("");
+            PYTHON_ENUM_BASES.contains(&name)
+        })
+}
+
+/// Python: SCREAMING_CASE assignments directly under the module, including
+/// annotated ones (`TIMEOUT: Final = 30`).
+fn extract_python_constants(
+    root: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = root.walk();
+    for stmt in root.children(&mut cursor) {
+        if stmt.kind() == "expression_statement"
+            && let Some(assignment) = stmt.named_child(0)
+            && assignment.kind() == "assignment"
+            && let Some(left) = assignment.child_by_field_name("left")
+            && left.kind() == "identifier"
+            && is_constant_name(&source[left.byte_range()])
+        {
+            entities.push(constant_entity(
+                &source[left.byte_range()],
+                &stmt,
+                path,
+                source,
+            ));
+        }
+    }
+}
+
+/// JS/TS: whether `node` is the program, or an `export` directly under it.
+fn is_js_top_level(node: &tree_sitter::Node) -> bool {
+    match node.kind() {
+        "program" => true,
+        "export_statement" => node.parent().is_some_and(|p| p.kind() == "program"),
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Module-level script code
+// ---------------------------------------------------------------------------
+
+/// Name of the synthesized entity holding a file's top-level executable code.
+/// The dep extractors attribute calls made outside any function to this scope.
+pub const MODULE_SCOPE_NAME: &str = "<module>";
+
+/// Grammar node kinds used to find top-level executable statements.
+struct ScriptKinds {
+    imports: &'static [&'static str],
+    calls: &'static [&'static str],
+    /// Nested scopes whose calls belong to their own entity.
+    scopes: &'static [&'static str],
+    /// Calls that are really imports (e.g., CommonJS `require`).
+    ignored_callees: &'static [&'static str],
+}
+
+const PYTHON_SCRIPT_KINDS: ScriptKinds = ScriptKinds {
+    imports: &[
+        "import_statement",
+        "import_from_statement",
+        "future_import_statement",
+    ],
+    calls: &["call"],
+    scopes: &["function_definition", "class_definition"],
+    ignored_callees: &[],
+};
+
+const JS_SCRIPT_KINDS: ScriptKinds = ScriptKinds {
+    imports: &["import_statement"],
+    calls: &["call_expression", "new_expression"],
+    scopes: &[
+        "function_declaration",
+        "generator_function_declaration",
+        "function_expression",
+        "function",
+        "arrow_function",
+        "class_declaration",
+        "class",
+        "method_definition",
+    ],
+    ignored_callees: &["require"],
+};
+
+/// Synthesize a `file:<module>` Function entity for a file's top-level
+/// executable statements (argument parsing, app wiring, `if __name__ == ...`).
+///
+/// A top-level statement counts when it makes a call outside any nested
+/// function or class and isn't already part of an extracted entity, so imports,
+/// definitions, and constant-only files produce nothing. The line range spans
+/// the first to the last such statement.
+fn module_script_entity(
+    root: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    entities: &[RawEntity],
+    kinds: &ScriptKinds,
+) -> Option<RawEntity> {
+    let mut cursor = root.walk();
+    let statements: Vec<tree_sitter::Node> = root
+        .children(&mut cursor)
+        .filter(|stmt| !kinds.imports.contains(&stmt.kind()))
+        .filter(|stmt| {
+            let line = stmt.start_position().row + 1;
+            // Constants don't claim their statement: calls in an initializer
+            // still run at import time.
+            !entities.iter().any(|e| {
+                e.parent_class.is_none()
+                    && e.kind != EntityKind::Constant
+                    && e.line_start <= line
+                    && line <= e.line_end
+            })
+        })
+        .filter(|stmt| makes_top_level_call(stmt, source, kinds))
+        .collect();
+    let (first, last) = (statements.first()?, statements.last()?);
+
+    let mut attributes = BTreeMap::new();
+    attributes.insert(
+        "role".to_string(),
+        "file initialization (top-level statements)".to_string(),
+    );
+    Some(RawEntity {
+        name: MODULE_SCOPE_NAME.to_string(),
+        kind: EntityKind::Function,
+        file: path.to_path_buf(),
+        line_start: first.start_position().row + 1,
+        line_end: last.end_position().row + 1,
+        parent_class: None,
+        source_text: statements
+            .iter()
+            .map(|stmt| &source[stmt.byte_range()])
+            .collect::<Vec<_>>()
+            .join("\n"),
+        signature: None,
+        attributes,
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+    })
+}
+
+/// Whether `node` contains a call that isn't inside a nested scope.
+fn makes_top_level_call(node: &tree_sitter::Node, source: &str, kinds: &ScriptKinds) -> bool {
+    if kinds.scopes.contains(&node.kind()) {
+        return false;
+    }
+    if kinds.calls.contains(&node.kind()) {
+        let callee = node
+            .child_by_field_name("function")
+            .or_else(|| node.child_by_field_name("constructor"))
+            .map(|f| &source[f.byte_range()]);
+        if !callee.is_some_and(|c| kinds.ignored_callees.contains(&c)) {
+            return true;
+        }
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| makes_top_level_call(&child, source, kinds))
+}
+
 /// Extract entities from a Rust source file using tree-sitter.
 pub fn extract_rust_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang: tree_sitter::Language = tree_sitter_rust::LANGUAGE.into();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
 
@@ -193,6 +523,12 @@ fn extract_rust_node(
                         parent_class: parent_struct.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_rust_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -201,16 +537,38 @@ fn extract_rust_node(
                     let name = &source[name_node.byte_range()];
                     entities.push(RawEntity {
                         name: name.to_string(),
-                        kind: EntityKind::Class,
+                        kind: if child.kind() == "enum_item" {
+                            EntityKind::Enum
+                        } else {
+                            EntityKind::Class
+                        },
                         file: path.to_path_buf(),
                         line_start: child.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
+            // Module-level `const` and `static` items; associated consts stay
+            // part of their impl or trait.
+            "const_item" | "static_item" if parent_struct.is_none() => {
+                if let Some(name_node) = child.child_by_field_name("name") {
+                    entities.push(constant_entity(
+                        &source[name_node.byte_range()],
+                        &child,
+                        path,
+                        source,
+                    ));
+                }
+            }
             "impl_item" => {
                 // Find the type name being impl'd
                 if let Some(type_node) = child.child_by_field_name("type") {
@@ -232,6 +590,12 @@ fn extract_rust_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     // Recurse into trait body for default method implementations
                     if let Some(body) = child.child_by_field_name("body") {
@@ -250,17 +614,34 @@ fn extract_rust_node(
 
 /// Generic entity extraction dispatching to the correct language extractor.
 ///
-/// Tries the builtin extractor registered in the language TOML first.
-/// Falls back to an empty result for languages without a builtin extractor.
+/// Tries the builtin extractor registered in the language TOML first, then
+/// attaches doc comments and function modifiers. Falls back to an empty result for languages
+/// without a builtin extractor.
 pub fn extract_entities(path: &Path, source: &str, language: Language) -> Vec<RawEntity> {
     if let Some(extractor_name) = crate::languages::builtin_entity_extractor_name(language)
         && let Some(extractor) = crate::languages::builtin_entity_extractor(extractor_name)
     {
-        return extractor(path, source);
+        let mut entities = extractor(path, source);
+        crate::testing::mark_test_entities(path, &mut entities, source, language);
+        crate::docs::attach_doc_comments(&mut entities, source, language);
+        crate::flags::attach_flags(path, &mut entities, source, language);
+        return entities;
     }
     Vec::new()
 }
 
+/// [`extract_entities`], plus a diagnostic when the file has syntax errors
+/// (entities inside the broken regions are missing).
+pub fn extract_entities_with_diagnostics(
+    path: &Path,
+    source: &str,
+    language: Language,
+) -> (Vec<RawEntity>, Option<crate::diagnostics::ParseDiagnostic>) {
+    let entities = extract_entities(path, source, language);
+    let diagnostic = crate::diagnostics::diagnose(path, source, language, entities.len());
+    (entities, diagnostic)
+}
+
 // ---------------------------------------------------------------------------
 // TypeScript / JavaScript
 // ---------------------------------------------------------------------------
@@ -277,19 +658,88 @@ pub fn extract_javascript_entities(path: &Path, source: &str) -> Vec<RawEntity>
 
 fn extract_js_like_entities(path: &Path, source: &str, lang: Language) -> Vec<RawEntity> {
     let ts_lang = lang.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
         return Vec::new();
     };
 
     let mut entities = Vec::new();
     extract_js_node(&tree.root_node(), path, source, None, &mut entities);
+    extract_js_test_blocks(&tree.root_node(), path, source, &mut entities);
+    entities.extend(module_script_entity(
+        &tree.root_node(),
+        path,
+        source,
+        &entities,
+        &JS_SCRIPT_KINDS,
+    ));
+    if entities.is_empty() {
+        entities.extend(barrel_module_entity(&tree.root_node(), path, source));
+    }
     entities
 }
 
+/// Top-level `describe`/`it`/`test` blocks as Test entities named by their
+/// title. Calls inside a block belong to it, not to the file's
+/// `<module>` scope.
+fn extract_js_test_blocks(
+    root: &tree_sitter::Node,
+    path: &Path,
+    source: &str,
+    entities: &mut Vec<RawEntity>,
+) {
+    let mut cursor = root.walk();
+    for stmt in root.children(&mut cursor) {
+        let Some(title) = crate::testing::js_test_block_title(&stmt, source) else {
+            continue;
+        };
+        entities.push(RawEntity {
+            name: title,
+            kind: EntityKind::Test,
+            file: path.to_path_buf(),
+            line_start: stmt.start_position().row + 1,
+            line_end: stmt.end_position().row + 1,
+            parent_class: None,
+            source_text: source[stmt.byte_range()].to_string(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        });
+    }
+}
+
+/// A Module entity for a pure barrel file (`index.ts` holding only
+/// `export ... from` statements), which defines nothing else and would
+/// otherwise drop out of the graph along with its re-exports.
+fn barrel_module_entity(root: &tree_sitter::Node, path: &Path, source: &str) -> Option<RawEntity> {
+    let mut cursor = root.walk();
+    let reexports = root.children(&mut cursor).any(|stmt| {
+        stmt.kind() == "export_statement" && stmt.child_by_field_name("source").is_some()
+    });
+    if !reexports {
+        return None;
+    }
+    Some(RawEntity {
+        name: path.file_stem()?.to_str()?.to_string(),
+        kind: EntityKind::Module,
+        file: path.to_path_buf(),
+        line_start: 1,
+        line_end: source.lines().count().max(1),
+        parent_class: None,
+        source_text: source.to_string(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+    })
+}
+
 fn extract_js_node(
     node: &tree_sitter::Node,
     path: &Path,
@@ -300,7 +750,7 @@ fn extract_js_node(
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
-            "function_declaration" => {
+            "function_declaration" | "generator_function_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = &source[name_node.byte_range()];
                     let default_kind = if parent_class.is_some() {
@@ -323,12 +773,19 @@ fn extract_js_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_js_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
             "class_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let class_name = &source[name_node.byte_range()];
+                    let start = js_decorated_start(child);
                     entities.push(RawEntity {
                         name: class_name.to_string(),
                         kind: classify_js_entity_kind(
@@ -339,45 +796,68 @@ fn extract_js_node(
                             None,
                         ),
                         file: path.to_path_buf(),
-                        line_start: child.start_position().row + 1,
+                        line_start: start.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: None,
-                        source_text: source[child.byte_range()].to_string(),
+                        source_text: source[start.start_byte()..child.end_byte()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: js_decorators(child, source),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_js_node(&body, path, source, Some(class_name), entities);
                     }
                 }
             }
-            // TS: interface_declaration / type_alias_declaration
-            "interface_declaration" | "type_alias_declaration" => {
+            // TS: interface_declaration / type_alias_declaration / enum_declaration
+            "interface_declaration" | "type_alias_declaration" | "enum_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = &source[name_node.byte_range()];
                     entities.push(RawEntity {
                         name: name.to_string(),
-                        kind: EntityKind::Class,
+                        kind: if child.kind() == "enum_declaration" {
+                            EntityKind::Enum
+                        } else {
+                            EntityKind::Class
+                        },
                         file: path.to_path_buf(),
                         line_start: child.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
             "method_definition" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = &source[name_node.byte_range()];
+                    let start = js_decorated_start(child);
                     entities.push(RawEntity {
                         name: name.to_string(),
                         kind: EntityKind::Method,
                         file: path.to_path_buf(),
-                        line_start: child.start_position().row + 1,
+                        line_start: start.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
-                        source_text: source[child.byte_range()].to_string(),
+                        source_text: source[start.start_byte()..child.end_byte()].to_string(),
                         signature: extract_js_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: js_decorators(child, source),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -387,7 +867,9 @@ fn extract_js_node(
                 for decl in child.children(&mut inner) {
                     if decl.kind() == "variable_declarator" {
                         let has_arrow = has_child_kind(&decl, "arrow_function");
-                        let has_func = has_child_kind(&decl, "function");
+                        let has_func = ["function", "function_expression", "generator_function"]
+                            .iter()
+                            .any(|kind| has_child_kind(&decl, kind));
                         let decl_source = &source[decl.byte_range()];
 
                         if (has_arrow || has_func)
@@ -415,7 +897,15 @@ fn extract_js_node(
                                 line_end: child.end_position().row + 1,
                                 parent_class: parent_class.map(String::from),
                                 source_text: source[child.byte_range()].to_string(),
-                                signature: None,
+                                signature: decl
+                                    .child_by_field_name("value")
+                                    .and_then(|value| extract_js_signature(&value, source)),
+                                attributes: BTreeMap::new(),
+                                doc_comment: None,
+                                decorators: Vec::new(),
+                                is_async: false,
+                                is_generator: false,
+                                is_unsafe: false,
                             });
                         } else if let Some(name_node) = decl.child_by_field_name("name") {
                             let name_kind = name_node.kind();
@@ -443,10 +933,25 @@ fn extract_js_node(
                                     parent_class: parent_class.map(String::from),
                                     source_text: source[child.byte_range()].to_string(),
                                     signature: None,
+                                    attributes: BTreeMap::new(),
+                                    doc_comment: None,
+                                    decorators: Vec::new(),
+                                    is_async: false,
+                                    is_generator: false,
+                                    is_unsafe: false,
                                 });
                             } else {
                                 let name = &source[name_node.byte_range()];
-                                if looks_like_store_entity(name, decl_source) {
+                                let is_constant = parent_class.is_none()
+                                    && is_js_top_level(node)
+                                    && has_child_kind(&child, "const")
+                                    && name_kind == "identifier"
+                                    && decl
+                                        .child_by_field_name("value")
+                                        .is_some_and(|v| JS_CONSTANT_VALUES.contains(&v.kind()));
+                                if is_constant {
+                                    entities.push(constant_entity(name, &child, path, source));
+                                } else if looks_like_store_entity(name, decl_source) {
                                     entities.push(RawEntity {
                                         name: name.to_string(),
                                         kind: EntityKind::Store,
@@ -456,6 +961,12 @@ fn extract_js_node(
                                         parent_class: parent_class.map(String::from),
                                         source_text: decl_source.to_string(),
                                         signature: None,
+                                        attributes: BTreeMap::new(),
+                                        doc_comment: None,
+                                        decorators: Vec::new(),
+                                        is_async: false,
+                                        is_generator: false,
+                                        is_unsafe: false,
                                     });
                                     // Extract createSlice reducer keys as child entities
                                     if decl_source.contains("createSlice(") {
@@ -482,6 +993,55 @@ fn extract_js_node(
     }
 }
 
+/// JS/TS: the first decorator of a class or method (or the node itself when
+/// undecorated), so markers like `@Controller('users')` are part of the
+/// entity's source text. An exported class carries its decorators on the
+/// `export_statement`; a method's are the siblings before it in the class body.
+fn js_decorated_start(node: tree_sitter::Node) -> tree_sitter::Node {
+    if node.kind() == "class_declaration" {
+        return node
+            .parent()
+            .filter(|p| p.kind() == "export_statement" && has_child_kind(p, "decorator"))
+            .unwrap_or(node);
+    }
+    let mut start = node;
+    while let Some(prev) = start.prev_sibling().filter(|p| p.kind() == "decorator") {
+        start = prev;
+    }
+    start
+}
+
+/// JS/TS: the decorators on a class or method (see [`js_decorated_start`]),
+/// as [`python_decorators`] records them, top to bottom.
+fn js_decorators(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    let mut decorators = Vec::new();
+    let start = js_decorated_start(node);
+    let mut sibling = Some(start);
+    while let Some(current) = sibling.filter(|s| s.id() != node.id()) {
+        if current.kind() == "decorator" {
+            decorators.push(decorator_text(current, source));
+        } else if current.kind() == "export_statement" {
+            let mut cursor = current.walk();
+            decorators.extend(
+                current
+                    .children(&mut cursor)
+                    .filter(|c| c.kind() == "decorator")
+                    .map(|d| decorator_text(d, source)),
+            );
+            break;
+        }
+        sibling = current.next_sibling();
+    }
+    // `@Injectable() class Foo {}` outside an export keeps them on the class.
+    let mut cursor = node.walk();
+    decorators.extend(
+        node.children(&mut cursor)
+            .filter(|c| c.kind() == "decorator")
+            .map(|d| decorator_text(d, source)),
+    );
+    decorators
+}
+
 fn has_child_kind(node: &tree_sitter::Node, kind: &str) -> bool {
     let mut cursor = node.walk();
     node.children(&mut cursor).any(|c| c.kind() == kind)
@@ -643,6 +1203,12 @@ fn extract_create_slice_reducers(
                     parent_class: Some(slice_name.to_string()),
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    attributes: BTreeMap::new(),
+                    doc_comment: None,
+                    decorators: Vec::new(),
+                    is_async: false,
+                    is_generator: false,
+                    is_unsafe: false,
                 });
             }
         }
@@ -700,6 +1266,12 @@ fn extract_destructured_hooks(
                 parent_class: parent_class.map(String::from),
                 source_text: source[outer_decl.byte_range()].to_string(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
             });
         }
     }
@@ -712,11 +1284,7 @@ fn extract_destructured_hooks(
 /// Extract entities from a Go source file.
 pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::GO.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
 
@@ -736,6 +1304,12 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_go_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -766,6 +1340,12 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                         parent_class: receiver,
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_go_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -786,6 +1366,12 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
                             parent_class: None,
                             source_text: source[spec.byte_range()].to_string(),
                             signature: None,
+                            attributes: go_type_attributes(&spec, source),
+                            doc_comment: None,
+                            decorators: Vec::new(),
+                            is_async: false,
+                            is_generator: false,
+                            is_unsafe: false,
                         });
                     }
                 }
@@ -796,6 +1382,92 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     entities
 }
 
+/// Attributes of a Go `type_spec` that interface satisfaction needs:
+/// `interface_methods` (the interface's own method names) and `embeds`
+/// (embedded type names), each joined by `", "`. Interfaces with neither,
+/// and types that embed nothing, get none.
+fn go_type_attributes(spec: &tree_sitter::Node, source: &str) -> BTreeMap<String, String> {
+    let mut attributes = BTreeMap::new();
+    let Some(type_node) = spec.child_by_field_name("type") else {
+        return attributes;
+    };
+    let mut methods = Vec::new();
+    let mut embeds = Vec::new();
+    match type_node.kind() {
+        "interface_type" => {
+            let mut cursor = type_node.walk();
+            for elem in type_node.named_children(&mut cursor) {
+                match elem.kind() {
+                    "method_elem" => {
+                        if let Some(name) = elem.child_by_field_name("name") {
+                            methods.push(source[name.byte_range()].to_string());
+                        }
+                    }
+                    // A single embedded interface; unions are type constraints.
+                    "type_elem" if elem.named_child_count() == 1 => {
+                        embeds.extend(
+                            elem.named_child(0)
+                                .and_then(|t| go_base_type_name(&t, source)),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+            if !methods.is_empty() || !embeds.is_empty() {
+                methods.sort();
+                attributes.insert("interface_methods".to_string(), methods.join(", "));
+            }
+        }
+        "struct_type" => embeds = go_embedded_fields(&type_node, source),
+        _ => {}
+    }
+    if !embeds.is_empty() {
+        attributes.insert("embeds".to_string(), embeds.join(", "));
+    }
+    attributes
+}
+
+/// Type names of a Go struct's embedded (unnamed) fields.
+pub(crate) fn go_embedded_fields(struct_type: &tree_sitter::Node, source: &str) -> Vec<String> {
+    let mut embeds = Vec::new();
+    let mut cursor = struct_type.walk();
+    for list in struct_type
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "field_declaration_list")
+    {
+        let mut fc = list.walk();
+        for field in list.named_children(&mut fc) {
+            if field.kind() == "field_declaration"
+                && field.child_by_field_name("name").is_none()
+                && let Some(name) = field
+                    .child_by_field_name("type")
+                    .and_then(|t| go_base_type_name(&t, source))
+            {
+                embeds.push(name);
+            }
+        }
+    }
+    embeds
+}
+
+/// The named type under pointers, package qualifiers, and type arguments:
+/// `*pkg.Store[K]` → `Store`.
+pub(crate) fn go_base_type_name(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "type_identifier" => Some(source[node.byte_range()].to_string()),
+        "qualified_type" => node
+            .child_by_field_name("name")
+            .map(|n| source[n.byte_range()].to_string()),
+        "generic_type" => node
+            .child_by_field_name("type")
+            .and_then(|n| go_base_type_name(&n, source)),
+        "pointer_type" => node
+            .named_child(0)
+            .and_then(|n| go_base_type_name(&n, source)),
+        _ => None,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Java
 // ---------------------------------------------------------------------------
@@ -803,11 +1475,7 @@ pub fn extract_go_entities(path: &Path, source: &str) -> Vec<RawEntity> {
 /// Extract entities from a Java source file.
 pub fn extract_java_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::JAVA.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
 
@@ -834,13 +1502,23 @@ fn extract_java_node(
                     let class_name = &source[name_node.byte_range()];
                     entities.push(RawEntity {
                         name: class_name.to_string(),
-                        kind: EntityKind::Class,
+                        kind: if child.kind() == "enum_declaration" {
+                            EntityKind::Enum
+                        } else {
+                            EntityKind::Class
+                        },
                         file: path.to_path_buf(),
                         line_start: child.start_position().row + 1,
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_java_node(&body, path, source, Some(class_name), entities);
@@ -859,6 +1537,12 @@ fn extract_java_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_java_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -885,11 +1569,7 @@ pub fn extract_cpp_entities(path: &Path, source: &str) -> Vec<RawEntity> {
 
 fn extract_c_like_entities(path: &Path, source: &str, lang: Language) -> Vec<RawEntity> {
     let ts_lang = lang.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
         return Vec::new();
     };
 
@@ -927,6 +1607,12 @@ fn extract_c_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_c_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -942,6 +1628,12 @@ fn extract_c_node(
                         parent_class: None,
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     // C++: recurse into class/struct body for methods
                     if lang == Language::CPP
@@ -992,11 +1684,7 @@ pub fn extract_c_declarator_name(node: &tree_sitter::Node, source: &str) -> Opti
 /// Extract entities from a C# source file.
 pub fn extract_csharp_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::CSHARP.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1030,6 +1718,12 @@ fn extract_csharp_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_csharp_node(&body, path, source, Some(class_name), entities);
@@ -1048,6 +1742,12 @@ fn extract_csharp_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: extract_csharp_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1070,11 +1770,7 @@ fn extract_csharp_node(
 /// Extract entities from a PHP source file.
 pub fn extract_php_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::PHP.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1107,6 +1803,12 @@ fn extract_php_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_php_node(&body, path, source, Some(class_name), entities);
@@ -1124,7 +1826,13 @@ fn extract_php_node(
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
-                        signature: None,
+                        signature: extract_php_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1139,7 +1847,13 @@ fn extract_php_node(
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
-                        signature: None,
+                        signature: extract_php_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1157,11 +1871,7 @@ fn extract_php_node(
 /// Extract entities from a Ruby source file.
 pub fn extract_ruby_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::RUBY.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1191,6 +1901,12 @@ fn extract_ruby_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     // Recurse into class/module body for methods
                     if let Some(body) = child.child_by_field_name("body") {
@@ -1214,7 +1930,13 @@ fn extract_ruby_node(
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
-                        signature: None,
+                        signature: extract_ruby_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1229,7 +1951,13 @@ fn extract_ruby_node(
                         line_end: child.end_position().row + 1,
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
-                        signature: None,
+                        signature: extract_ruby_signature(&child, source),
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1247,11 +1975,7 @@ fn extract_ruby_node(
 /// Extract entities from a Kotlin source file.
 pub fn extract_kotlin_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::KOTLIN.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1269,35 +1993,58 @@ fn extract_kotlin_node(
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
-            "class_declaration" | "object_declaration" | "interface_declaration" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let class_name = &source[name_node.byte_range()];
-                    entities.push(RawEntity {
-                        name: class_name.to_string(),
-                        kind: EntityKind::Class,
-                        file: path.to_path_buf(),
-                        line_start: child.start_position().row + 1,
-                        line_end: child.end_position().row + 1,
-                        parent_class: parent_class.map(String::from),
-                        source_text: source[child.byte_range()].to_string(),
-                        signature: None,
-                    });
-                    // kotlin-ng uses "class_body" / "enum_class_body" child nodes (not a "body" field)
-                    let body = child.child_by_field_name("body").or_else(|| {
-                        let mut c = child.walk();
-                        child
-                            .children(&mut c)
-                            .find(|n| n.kind() == "class_body" || n.kind() == "enum_class_body")
-                    });
-                    if let Some(body) = body {
-                        extract_kotlin_node(&body, path, source, Some(class_name), entities);
+            "class_declaration"
+            | "object_declaration"
+            | "interface_declaration"
+            | "companion_object" => {
+                let class_name = match child.child_by_field_name("name") {
+                    Some(name_node) => &source[name_node.byte_range()],
+                    // `companion object { }` without a name is `Companion`
+                    None if child.kind() == "companion_object" => {
+                        kotlin_companion_name(&child, source)
                     }
+                    None => continue,
+                };
+                entities.push(RawEntity {
+                    name: class_name.to_string(),
+                    kind: EntityKind::Class,
+                    file: path.to_path_buf(),
+                    line_start: child.start_position().row + 1,
+                    line_end: child.end_position().row + 1,
+                    parent_class: parent_class.map(String::from),
+                    source_text: source[child.byte_range()].to_string(),
+                    signature: None,
+                    attributes: BTreeMap::new(),
+                    doc_comment: None,
+                    decorators: Vec::new(),
+                    is_async: false,
+                    is_generator: false,
+                    is_unsafe: false,
+                });
+                // Companion members are scoped by the outer class (`Foo.Companion`),
+                // so two companions in one file don't collide.
+                let scope = match (child.kind(), parent_class) {
+                    ("companion_object", Some(outer)) => format!("{}.{}", outer, class_name),
+                    _ => class_name.to_string(),
+                };
+                // kotlin-ng uses "class_body" / "enum_class_body" child nodes (not a "body" field)
+                let body = child.child_by_field_name("body").or_else(|| {
+                    let mut c = child.walk();
+                    child
+                        .children(&mut c)
+                        .find(|n| n.kind() == "class_body" || n.kind() == "enum_class_body")
+                });
+                if let Some(body) = body {
+                    extract_kotlin_node(&body, path, source, Some(&scope), entities);
                 }
             }
             "function_declaration" => {
                 if let Some(name_node) = child.child_by_field_name("name") {
                     let name = &source[name_node.byte_range()];
-                    let kind = if parent_class.is_some() {
+                    // Extension functions (`fun String.shout()`) are addressed by
+                    // their receiver type, like Go methods: `file.kt:String::shout`.
+                    let receiver = kotlin_receiver_type(&child, &name_node, source);
+                    let kind = if parent_class.is_some() || receiver.is_some() {
                         EntityKind::Method
                     } else {
                         EntityKind::Function
@@ -1308,9 +2055,15 @@ fn extract_kotlin_node(
                         file: path.to_path_buf(),
                         line_start: child.start_position().row + 1,
                         line_end: child.end_position().row + 1,
-                        parent_class: parent_class.map(String::from),
+                        parent_class: receiver.or_else(|| parent_class.map(String::from)),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1321,6 +2074,42 @@ fn extract_kotlin_node(
     }
 }
 
+/// Name of a `companion_object` node: its identifier, or `Companion` if unnamed.
+pub(crate) fn kotlin_companion_name<'a>(node: &tree_sitter::Node, source: &'a str) -> &'a str {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .find(|n| {
+            matches!(
+                n.kind(),
+                "identifier" | "simple_identifier" | "type_identifier"
+            )
+        })
+        .map_or("Companion", |n| &source[n.byte_range()])
+}
+
+/// Receiver type of an extension function: the type node before the function
+/// name, reduced to its simple name (`List<T>?` → `List`, `a.b.Foo` → `Foo`).
+pub(crate) fn kotlin_receiver_type(
+    func: &tree_sitter::Node,
+    name_node: &tree_sitter::Node,
+    source: &str,
+) -> Option<String> {
+    let mut cursor = func.walk();
+    let receiver = func
+        .children(&mut cursor)
+        .take_while(|n| n.end_byte() <= name_node.start_byte())
+        .find(|n| {
+            matches!(
+                n.kind(),
+                "receiver_type" | "user_type" | "nullable_type" | "parenthesized_type"
+            )
+        })?;
+    let text = source[receiver.byte_range()]
+        .trim_matches(|c: char| matches!(c, '(' | ')' | '?') || c.is_whitespace());
+    let simple = text.split('<').next()?.rsplit('.').next()?.trim();
+    (!simple.is_empty()).then(|| simple.to_string())
+}
+
 // ---------------------------------------------------------------------------
 // Swift
 // ---------------------------------------------------------------------------
@@ -1328,11 +2117,7 @@ fn extract_kotlin_node(
 /// Extract entities from a Swift source file.
 pub fn extract_swift_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::SWIFT.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1365,6 +2150,12 @@ fn extract_swift_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
                         extract_swift_node(&body, path, source, Some(class_name), entities);
@@ -1388,6 +2179,12 @@ fn extract_swift_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1402,6 +2199,12 @@ fn extract_swift_node(
                     parent_class: parent_class.map(String::from),
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    attributes: BTreeMap::new(),
+                    doc_comment: None,
+                    decorators: Vec::new(),
+                    is_async: false,
+                    is_generator: false,
+                    is_unsafe: false,
                 });
             }
             "extension_declaration" => {
@@ -1428,11 +2231,7 @@ fn extract_swift_node(
 /// Extract entities from a Scala source file.
 pub fn extract_scala_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::SCALA.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1447,14 +2246,14 @@ fn extract_scala_node(
     parent_class: Option<&str>,
     entities: &mut Vec<RawEntity>,
 ) {
+    let companions = scala_companion_classes(node, source);
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
         match child.kind() {
-            "class_definition" | "object_definition" | "trait_definition" => {
-                if let Some(name_node) = child.child_by_field_name("name") {
-                    let class_name = &source[name_node.byte_range()];
+            "class_definition" | "object_definition" | "trait_definition" | "enum_definition" => {
+                if let Some(name) = scala_type_name(&child, source, &companions) {
                     entities.push(RawEntity {
-                        name: class_name.to_string(),
+                        name: name.clone(),
                         kind: EntityKind::Class,
                         file: path.to_path_buf(),
                         line_start: child.start_position().row + 1,
@@ -1462,9 +2261,15 @@ fn extract_scala_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                     if let Some(body) = child.child_by_field_name("body") {
-                        extract_scala_node(&body, path, source, Some(class_name), entities);
+                        extract_scala_node(&body, path, source, Some(&name), entities);
                     }
                 }
             }
@@ -1485,6 +2290,12 @@ fn extract_scala_node(
                         parent_class: parent_class.map(String::from),
                         source_text: source[child.byte_range()].to_string(),
                         signature: None,
+                        attributes: BTreeMap::new(),
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -1495,6 +2306,39 @@ fn extract_scala_node(
     }
 }
 
+/// Names of the classes, traits, and enums declared directly under `node`;
+/// an `object` with one of these names is their companion.
+pub(crate) fn scala_companion_classes(node: &tree_sitter::Node, source: &str) -> HashSet<String> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|c| {
+            matches!(
+                c.kind(),
+                "class_definition" | "trait_definition" | "enum_definition"
+            )
+        })
+        .filter_map(|c| c.child_by_field_name("name"))
+        .map(|n| source[n.byte_range()].to_string())
+        .collect()
+}
+
+/// Entity name of a Scala type definition. A companion object takes the
+/// JVM's `$` suffix (`object Foo` next to `class Foo` → `Foo$`) so the two
+/// get distinct IDs (`Foo.scala:Foo` and `Foo.scala:Foo$`); standalone
+/// objects keep their name.
+pub(crate) fn scala_type_name(
+    node: &tree_sitter::Node,
+    source: &str,
+    companions: &HashSet<String>,
+) -> Option<String> {
+    let name = &source[node.child_by_field_name("name")?.byte_range()];
+    if node.kind() == "object_definition" && companions.contains(name) {
+        Some(format!("{}$", name))
+    } else {
+        Some(name.to_string())
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Bash
 // ---------------------------------------------------------------------------
@@ -1502,11 +2346,7 @@ fn extract_scala_node(
 /// Extract entities from a Bash/shell script source file.
 pub fn extract_bash_entities(path: &Path, source: &str) -> Vec<RawEntity> {
     let lang = Language::BASH.ts_language();
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&lang).is_err() {
-        return Vec::new();
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
+    let Some(tree) = crate::treesitter::parse_source(source, &lang) else {
         return Vec::new();
     };
     let mut entities = Vec::new();
@@ -1541,6 +2381,12 @@ fn extract_bash_node(
                     parent_class: None,
                     source_text: source[child.byte_range()].to_string(),
                     signature: None,
+                    attributes: BTreeMap::new(),
+                    doc_comment: None,
+                    decorators: Vec::new(),
+                    is_async: false,
+                    is_generator: false,
+                    is_unsafe: false,
                 });
             }
         } else {
@@ -1700,6 +2546,16 @@ pub fn extract_python_signature(node: &tree_sitter::Node, source: &str) -> Optio
 
 /// Extract signature from a JS/TS function_declaration, method_definition, or arrow_function.
 pub fn extract_js_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    // Arrow function with a single unparenthesized parameter: `x => x * 2`
+    if let Some(param) = node.child_by_field_name("parameter") {
+        return Some(RawSignature {
+            parameters: vec![RawParam {
+                name: source[param.byte_range()].to_string(),
+                type_annotation: None,
+            }],
+            return_type: None,
+        });
+    }
     // Find formal_parameters (may be direct child or via field)
     let params_node = node.child_by_field_name("parameters").or_else(|| {
         let mut c = node.walk();
@@ -1932,6 +2788,79 @@ pub fn extract_csharp_signature(node: &tree_sitter::Node, source: &str) -> Optio
     })
 }
 
+/// Extract signature from a PHP function_definition or method_declaration.
+pub fn extract_php_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let params_node = node.child_by_field_name("parameters")?;
+    let mut params = Vec::new();
+    let mut cursor = params_node.walk();
+    for child in params_node.children(&mut cursor) {
+        if matches!(
+            child.kind(),
+            "simple_parameter" | "variadic_parameter" | "property_promotion_parameter"
+        ) {
+            let name = child.child_by_field_name("name").map(|n| {
+                let name = &source[n.byte_range()];
+                if child.kind() == "variadic_parameter" {
+                    format!("...{}", name)
+                } else {
+                    name.to_string()
+                }
+            });
+            let type_ann = child
+                .child_by_field_name("type")
+                .map(|t| source[t.byte_range()].to_string());
+            if let Some(name) = name {
+                params.push(RawParam {
+                    name,
+                    type_annotation: type_ann,
+                });
+            }
+        }
+    }
+    let return_type = node.child_by_field_name("return_type").map(|rt| {
+        source[rt.byte_range()]
+            .trim_start_matches(':')
+            .trim()
+            .to_string()
+    });
+    Some(RawSignature {
+        parameters: params,
+        return_type,
+    })
+}
+
+/// Extract signature from a Ruby method or singleton_method. Ruby has no
+/// type annotations; splat, keyword-splat, and block parameters keep their
+/// `*`, `**`, and `&` markers.
+pub fn extract_ruby_signature(node: &tree_sitter::Node, source: &str) -> Option<RawSignature> {
+    let mut params = Vec::new();
+    if let Some(params_node) = node.child_by_field_name("parameters") {
+        let mut cursor = params_node.walk();
+        for child in params_node.named_children(&mut cursor) {
+            let name = match child.kind() {
+                "identifier" => Some(&source[child.byte_range()]),
+                "optional_parameter" | "keyword_parameter" => child
+                    .child_by_field_name("name")
+                    .map(|n| &source[n.byte_range()]),
+                "splat_parameter" | "hash_splat_parameter" | "block_parameter" => {
+                    Some(&source[child.byte_range()])
+                }
+                _ => None,
+            };
+            if let Some(name) = name {
+                params.push(RawParam {
+                    name: name.to_string(),
+                    type_annotation: None,
+                });
+            }
+        }
+    }
+    Some(RawSignature {
+        parameters: params,
+        return_type: None,
+    })
+}
+
 /// Find the first child of a specific kind (helper for C signature extraction).
 fn find_child_kind<'a>(node: &tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
     let mut cursor = node.walk();
@@ -0,0 +1,144 @@
+//! Event emitter calls in TypeScript/JavaScript.
+//!
+//! A literal-pattern scan, like [`crate::config_reads`]: member calls to the
+//! Node `EventEmitter` API whose first argument names the event.
+//!
+//! - Emits: `emitter.emit('job:done', ...)`, `this.emit("ready")`.
+//! - Listeners: `on`, `once`, `addListener`, `prependListener`, and
+//!   `prependOnceListener`, with the handler as the second argument.
+//!
+//! Grounding pairs emits with listeners of the same event name as
+//! Dispatches edges. Names that are not literals are kept as written and
+//! reported unresolved.
+
+use tree_sitter::Node;
+
+/// Methods that emit an event.
+const EMIT_METHODS: &[&str] = &["emit"];
+
+/// Methods that register a handler for an event.
+const LISTEN_METHODS: &[&str] = &[
+    "on",
+    "once",
+    "addListener",
+    "prependListener",
+    "prependOnceListener",
+];
+
+/// Whether a call emits an event or listens for one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventRole {
+    Emit,
+    Listen,
+}
+
+/// One emit or listener registration at a source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventUse {
+    /// 1-based line of the call.
+    pub line: usize,
+    pub role: EventRole,
+    /// The event name, or the argument's source text when `literal` is false.
+    pub event: String,
+    /// The name is a string literal (or a template without substitutions).
+    pub literal: bool,
+    /// For listeners, the handler when it names a function (`onDone`,
+    /// `this.onDone`, `this.onDone.bind(this)` → `onDone`); `None` for
+    /// inline functions.
+    pub handler: Option<String>,
+}
+
+/// Collect the event emits and listener registrations under `root`, in
+/// source order.
+pub fn collect_js_events(root: &Node, source: &str) -> Vec<EventUse> {
+    let mut events = Vec::new();
+    collect(root, source, &mut events);
+    events
+}
+
+fn collect(node: &Node, source: &str, events: &mut Vec<EventUse>) {
+    if node.kind() == "call_expression"
+        && let Some(event) = event_use(node, source)
+    {
+        events.push(event);
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect(&child, source, events);
+    }
+}
+
+fn event_use(call: &Node, source: &str) -> Option<EventUse> {
+    let function = call.child_by_field_name("function")?;
+    if function.kind() != "member_expression" {
+        return None;
+    }
+    let method = &source[function.child_by_field_name("property")?.byte_range()];
+    let role = if EMIT_METHODS.contains(&method) {
+        EventRole::Emit
+    } else if LISTEN_METHODS.contains(&method) {
+        EventRole::Listen
+    } else {
+        return None;
+    };
+
+    let args = call.child_by_field_name("arguments")?;
+    let mut cursor = args.walk();
+    let mut args = args
+        .named_children(&mut cursor)
+        .filter(|a| a.kind() != "comment");
+    let name = args.next()?;
+    let (event, literal) = match literal_string(&name, source) {
+        Some(event) => (event, true),
+        None => (source[name.byte_range()].to_string(), false),
+    };
+    let handler = match role {
+        EventRole::Emit => None,
+        // A listener needs a handler; `on('x')` alone is something else.
+        EventRole::Listen => handler_name(&args.next()?, source),
+    };
+    Some(EventUse {
+        line: call.start_position().row + 1,
+        role,
+        event,
+        literal,
+        handler,
+    })
+}
+
+/// The value of a string literal or a template string without substitutions.
+fn literal_string(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "string" => {}
+        "template_string" if !has_substitution(node) => {}
+        _ => return None,
+    }
+    let text = source[node.byte_range()].trim_matches(['\'', '"', '`']);
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn has_substitution(node: &Node) -> bool {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .any(|c| c.kind() == "template_substitution")
+}
+
+/// The function a handler argument names, if it names one.
+fn handler_name(node: &Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" => Some(source[node.byte_range()].to_string()),
+        "member_expression" => node
+            .child_by_field_name("property")
+            .map(|p| source[p.byte_range()].to_string()),
+        // `this.onDone.bind(this)`
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            let property = function.child_by_field_name("property")?;
+            if function.kind() != "member_expression" || &source[property.byte_range()] != "bind" {
+                return None;
+            }
+            handler_name(&function.child_by_field_name("object")?, source)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,137 @@
+//! Function modifiers that change how a call site must look: `is_async`,
+//! `is_generator`, and `is_unsafe`.
+//!
+//! - Python: `async def`; a `yield` in the body (not in a nested
+//!   function, lambda, or class) makes a generator.
+//! - Rust: `async fn` and `unsafe fn`.
+//! - JS/TS: `async` functions, methods, and arrow functions; `function*`
+//!   and `*method()` generators.
+//!
+//! Go has none of these.
+
+use crate::entities::RawEntity;
+use crate::languages::Language;
+use std::path::Path;
+
+/// Modifiers of one function-like node, found by name.
+struct FunctionFlags<'a> {
+    name: &'a str,
+    /// 1-based line the node starts on.
+    line: usize,
+    is_async: bool,
+    is_generator: bool,
+    is_unsafe: bool,
+}
+
+/// Set `is_async`, `is_generator`, and `is_unsafe` on the function entities
+/// in `entities`.
+pub fn attach_flags(path: &Path, entities: &mut [RawEntity], source: &str, language: Language) {
+    let js_like = language == Language::TYPESCRIPT || language == Language::JAVASCRIPT;
+    if !(js_like || language == Language::PYTHON || language == Language::RUST) {
+        return;
+    }
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let Some(grammar) = crate::languages::grammar_for(crate::languages::effective_grammar_name(
+        language.name(),
+        ext,
+    )) else {
+        return;
+    };
+    let Ok(tree) = crate::treesitter::parse_file(path, source.as_bytes(), &grammar) else {
+        return;
+    };
+    let mut found = Vec::new();
+    collect_flags(tree.root_node(), source, &mut found);
+    if found.is_empty() {
+        return;
+    }
+    for entity in entities.iter_mut() {
+        // The outermost matching node: an entity's span starts at or above
+        // its definition (decorators, `export`, `const f =`).
+        if let Some(flags) = found.iter().find(|f| {
+            f.name == entity.name && (entity.line_start..=entity.line_end).contains(&f.line)
+        }) {
+            entity.is_async = flags.is_async;
+            entity.is_generator = flags.is_generator;
+            entity.is_unsafe = flags.is_unsafe;
+        }
+    }
+}
+
+fn collect_flags<'a>(node: tree_sitter::Node, source: &'a str, found: &mut Vec<FunctionFlags<'a>>) {
+    if let Some(name) = function_name(node, source) {
+        // Rust keeps `async`/`unsafe` in a `function_modifiers` child.
+        let modifiers = first_child_of_kind(node, "function_modifiers");
+        let is_async = has_token(node, "async") || modifiers.is_some_and(|m| has_token(m, "async"));
+        let is_unsafe = modifiers.is_some_and(|m| has_token(m, "unsafe"));
+        let is_generator = match node.kind() {
+            "generator_function_declaration" | "generator_function" => true,
+            "method_definition" => has_token(node, "*"),
+            "function_definition" => node
+                .child_by_field_name("body")
+                .is_some_and(|body| contains_own_yield(body)),
+            _ => false,
+        };
+        found.push(FunctionFlags {
+            name,
+            line: node.start_position().row + 1,
+            is_async,
+            is_generator,
+            is_unsafe,
+        });
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_flags(child, source, found);
+    }
+}
+
+/// The name an entity for this function-like node is extracted under, if it
+/// is one: its own name, or for a function expression the variable it is
+/// assigned to.
+fn function_name<'a>(node: tree_sitter::Node, source: &'a str) -> Option<&'a str> {
+    let name_node = match node.kind() {
+        "function_definition"
+        | "function_item"
+        | "function_signature_item"
+        | "function_declaration"
+        | "generator_function_declaration"
+        | "method_definition" => node.child_by_field_name("name"),
+        "arrow_function" | "function" | "function_expression" | "generator_function" => node
+            .parent()
+            .filter(|p| p.kind() == "variable_declarator")
+            .and_then(|p| p.child_by_field_name("name")),
+        _ => None,
+    }?;
+    Some(&source[name_node.byte_range()])
+}
+
+fn first_child_of_kind<'t>(
+    node: tree_sitter::Node<'t>,
+    kind: &str,
+) -> Option<tree_sitter::Node<'t>> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find(|c| c.kind() == kind)
+}
+
+/// Whether `node` has a direct (anonymous) token child of this kind.
+fn has_token(node: tree_sitter::Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|c| !c.is_named() && c.kind() == kind)
+}
+
+/// Python: a `yield` in `node` outside nested scopes.
+fn contains_own_yield(node: tree_sitter::Node) -> bool {
+    if node.kind() == "yield" {
+        return true;
+    }
+    if matches!(
+        node.kind(),
+        "function_definition" | "lambda" | "class_definition"
+    ) {
+        return false;
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).any(contains_own_yield)
+}
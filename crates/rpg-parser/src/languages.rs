@@ -4,9 +4,62 @@
 //! by build.rs. Adding a new language requires only a TOML file +
 //! the tree-sitter grammar dependency — zero Rust source changes.
 
-// Generated: LangId struct, Language alias, from_extension, from_name,
-// name, glob_pattern, ts_language, detect_primary, detect_all,
+// Generated: LangId struct, Language alias, from_extension, from_interpreter,
+// from_name, name, glob_pattern, ts_language, detect_primary, detect_all,
 // grammar_for, expand_lang_aliases, effective_grammar_name,
 // builtin_entity_extractor, builtin_dep_extractor_name,
 // builtin_entity_extractor_name
 include!(concat!(env!("OUT_DIR"), "/lang_registry.rs"));
+
+/// Bytes read from an extensionless file to find its shebang. A NUL in this
+/// prefix marks the file as binary.
+pub const SHEBANG_PROBE_BYTES: usize = 512;
+
+impl LangId {
+    /// Detect language from a script's first line: `#!/usr/bin/env python3`,
+    /// `#!/bin/bash`, `#!/usr/bin/env -S node --flag`. Version suffixes on the
+    /// interpreter (`python3.12`) are ignored.
+    pub fn from_shebang(first_line: &str) -> Option<Self> {
+        let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+        let mut interpreter = words.next()?.rsplit('/').next()?;
+        if interpreter == "env" {
+            // Skip env flags (`-S`) and variable assignments (`FOO=1`).
+            interpreter = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+        }
+        Self::from_interpreter(interpreter).or_else(|| {
+            Self::from_interpreter(
+                interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.'),
+            )
+        })
+    }
+
+    /// Detect language from a file's extension or, for extensionless files,
+    /// the shebang on the first line of `source`.
+    pub fn from_source(path: &std::path::Path, source: &str) -> Option<Self> {
+        match path.extension() {
+            Some(ext) => ext.to_str().and_then(Self::from_extension),
+            None => source.lines().next().and_then(Self::from_shebang),
+        }
+    }
+
+    /// Detect language from a file's extension or, for extensionless files,
+    /// a shebang read from disk. Binary files are skipped after reading
+    /// [`SHEBANG_PROBE_BYTES`].
+    pub fn from_path(path: &std::path::Path) -> Option<Self> {
+        use std::io::Read;
+        if let Some(ext) = path.extension() {
+            return ext.to_str().and_then(Self::from_extension);
+        }
+        let mut head = Vec::with_capacity(SHEBANG_PROBE_BYTES);
+        std::fs::File::open(path)
+            .ok()?
+            .take(SHEBANG_PROBE_BYTES as u64)
+            .read_to_end(&mut head)
+            .ok()?;
+        if !head.starts_with(b"#!") || head.contains(&0) {
+            return None;
+        }
+        let first_line = head.split(|&b| b == b'\n').next()?;
+        Self::from_shebang(std::str::from_utf8(first_line).ok()?)
+    }
+}
@@ -4,29 +4,35 @@
 //! Extracts functions, classes, methods, traits, import statements,
 //! function calls, and inheritance relationships.
 
+pub mod analyze;
+pub mod config_reads;
 pub mod deps;
+pub mod diagnostics;
+pub mod docs;
 pub mod entities;
+pub mod events;
+pub mod flags;
 pub mod languages;
 pub mod paradigms;
 pub mod signals;
+pub mod skeleton;
+pub mod testing;
 pub mod treesitter;
 
 use entities::RawEntity;
 use languages::Language;
 use rayon::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Parse multiple source files in parallel using rayon.
 /// Each entry is `(relative_path, source_code)`.
-/// Language is determined per-file from extension; files with unrecognized extensions are skipped.
+/// Language is determined per-file from extension, or the shebang of extensionless
+/// scripts; files in unrecognized languages are skipped.
 pub fn parse_files_parallel(files: Vec<(PathBuf, String)>) -> Vec<RawEntity> {
     files
         .into_par_iter()
         .flat_map(|(rel_path, source)| {
-            let lang = rel_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .and_then(Language::from_extension);
+            let lang = Language::from_source(&rel_path, &source);
             match lang {
                 Some(language) => entities::extract_entities(&rel_path, &source, language),
                 None => Vec::new(),
@@ -48,33 +54,72 @@ pub fn parse_files_with_paradigms(
     files
         .into_par_iter()
         .flat_map(|(rel_path, source)| {
-            let lang = rel_path
-                .extension()
-                .and_then(|e| e.to_str())
-                .and_then(Language::from_extension);
-            let Some(language) = lang else {
+            let Some(language) = Language::from_source(&rel_path, &source) else {
                 return Vec::new();
             };
-
-            let mut raw = entities::extract_entities(&rel_path, &source, language);
-            paradigms::classify::classify_entities(active_defs, &rel_path, &mut raw);
-            let extra = paradigms::query_engine::execute_entity_queries(
-                qcache,
-                active_defs,
-                &rel_path,
-                &source,
-                language,
-                &raw,
-            );
-            raw.extend(extra);
-            paradigms::features::apply_builtin_entity_features(
-                active_defs,
-                &rel_path,
-                &source,
-                language,
-                &mut raw,
-            );
-            raw
+            extract_with_paradigms(&rel_path, &source, language, active_defs, qcache)
         })
         .collect()
 }
+
+/// Like [`parse_files_with_paradigms`] (or [`parse_files_parallel`] when
+/// `active_defs` is empty), also returning a diagnostic for each file that
+/// had syntax errors, sorted by path.
+pub fn parse_files_with_diagnostics(
+    files: Vec<(PathBuf, String)>,
+    active_defs: &[&paradigms::defs::ParadigmDef],
+    qcache: &paradigms::query_engine::QueryCache,
+) -> (Vec<RawEntity>, Vec<diagnostics::ParseDiagnostic>) {
+    let parsed: Vec<(Vec<RawEntity>, Option<diagnostics::ParseDiagnostic>)> = files
+        .into_par_iter()
+        .filter_map(|(rel_path, source)| {
+            let language = Language::from_source(&rel_path, &source)?;
+            let raw = if active_defs.is_empty() {
+                entities::extract_entities(&rel_path, &source, language)
+            } else {
+                extract_with_paradigms(&rel_path, &source, language, active_defs, qcache)
+            };
+            let diagnostic = diagnostics::diagnose(&rel_path, &source, language, raw.len());
+            Some((raw, diagnostic))
+        })
+        .collect();
+
+    let mut all_entities = Vec::new();
+    let mut all_diagnostics = Vec::new();
+    for (raw, diagnostic) in parsed {
+        all_entities.extend(raw);
+        all_diagnostics.extend(diagnostic);
+    }
+    all_diagnostics.sort_by(|a, b| a.file.cmp(&b.file));
+    (all_entities, all_diagnostics)
+}
+
+/// One file through the paradigm pipeline: classify → entity queries →
+/// builtin features.
+fn extract_with_paradigms(
+    rel_path: &Path,
+    source: &str,
+    language: Language,
+    active_defs: &[&paradigms::defs::ParadigmDef],
+    qcache: &paradigms::query_engine::QueryCache,
+) -> Vec<RawEntity> {
+    let mut raw = entities::extract_entities(rel_path, source, language);
+    paradigms::classify::classify_entities(active_defs, rel_path, &mut raw);
+    let extra = paradigms::query_engine::execute_entity_queries(
+        qcache,
+        active_defs,
+        rel_path,
+        source,
+        language,
+        &raw,
+    );
+    raw.extend(extra);
+    paradigms::features::apply_builtin_entity_features(
+        active_defs,
+        rel_path,
+        source,
+        language,
+        &mut raw,
+    );
+    raw
+}
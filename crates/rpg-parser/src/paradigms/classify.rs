@@ -140,9 +140,30 @@ pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool
         }
     }
 
+    // decorator filter (OR within the list)
+    if let Some(ref names) = m.decorator
+        && !entity
+            .decorators
+            .iter()
+            .any(|d| names.iter().any(|n| decorator_matches(d, n)))
+    {
+        return false;
+    }
+
     true
 }
 
+/// Whether `decorator` (as stored, e.g. `app.route("/x")`) is `name` or
+/// ends with `.name`, ignoring its call arguments and a leading `@` on `name`.
+fn decorator_matches(decorator: &str, name: &str) -> bool {
+    let called = decorator.split('(').next().unwrap_or(decorator).trim();
+    let name = name.trim_start_matches('@');
+    called == name
+        || called
+            .strip_suffix(name)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -151,6 +172,7 @@ pub fn matches_entity(m: &EntityMatch, entity: &RawEntity, file: &Path) -> bool
 mod tests {
     use super::*;
     use crate::paradigms::defs::load_builtin_defs;
+    use std::collections::BTreeMap;
     use std::path::PathBuf;
 
     fn make_entity(name: &str, kind: EntityKind, source: &str, file: &str) -> RawEntity {
@@ -163,6 +185,12 @@ mod tests {
             parent_class: None,
             source_text: source.to_string(),
             signature: None,
+            attributes: BTreeMap::new(),
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
         }
     }
 
@@ -311,6 +339,29 @@ mod tests {
         assert!(!matches_entity(&m, &entity, Path::new("src/lib.rs")));
     }
 
+    #[test]
+    fn test_decorator_filter() {
+        use crate::paradigms::defs::EntityMatch;
+
+        let m = EntityMatch {
+            decorator: Some(vec!["app.route".to_string(), "@fixture".to_string()]),
+            ..EntityMatch::default()
+        };
+        let with = |decorators: &[&str]| {
+            let mut entity = make_entity("view", EntityKind::Function, "", "app.py");
+            entity.decorators = decorators.iter().map(|d| (*d).to_string()).collect();
+            matches_entity(&m, &entity, Path::new("app.py"))
+        };
+
+        assert!(with(&["app.route(\"/users\", methods=[\"GET\"])"]));
+        assert!(with(&["login_required", "app.route(\"/\")"]));
+        assert!(with(&["pytest.fixture"]));
+        assert!(with(&["fixture(scope=\"module\")"]));
+        assert!(!with(&["myapp.route(\"/\")"]));
+        assert!(!with(&["app.router"]));
+        assert!(!with(&[]));
+    }
+
     #[test]
     fn test_max_lines_filter() {
         use crate::paradigms::defs::EntityMatch;
@@ -8,6 +8,7 @@ use rpg_core::graph::{EdgeKind, EntityKind};
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::path::Path;
 
 // ---------------------------------------------------------------------------
 // Core types
@@ -29,6 +30,8 @@ pub struct ParadigmDef {
     #[serde(default)]
     pub auto_lift: Vec<AutoLiftRule>,
     #[serde(default)]
+    pub boilerplate: Vec<BoilerplateRule>,
+    #[serde(default)]
     pub features: FeatureFlags,
     #[serde(default)]
     pub prompt_hints: PromptHints,
@@ -44,6 +47,10 @@ pub struct DetectRules {
     pub config_files: Vec<String>,
     #[serde(default)]
     pub dir_with_files: Vec<DirWithFiles>,
+    /// Active whenever one of the paradigm's languages is present, without
+    /// detection (user extractors from `.rpg/extractors/`).
+    #[serde(default)]
+    pub always: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -89,19 +96,37 @@ pub struct EntityMatch {
     pub source_contains_any: Option<Vec<String>>,
     pub file_name_stem: Option<String>,
     pub file_path_contains: Option<String>,
+    /// Decorator names (`app.route`, `pytest.fixture`); an entity matches if
+    /// any of its decorators has one of them, ignoring call arguments. A name
+    /// also matches as a dotted suffix, so `route` matches `bp.route`.
+    pub decorator: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct EntityQuery {
     pub id: String,
     #[serde(default)]
     pub languages: Vec<String>,
     pub entity_kind: String,
     pub entity_name: String,
+    /// Capture spanning the whole entity (its lines and source text).
+    /// Defaults to the parent node of the name capture.
+    pub entity_body: Option<String>,
     pub parent: Option<String>,
+    /// Tree-sitter query; `#eq?`, `#match?`, and `#any-of?` predicates
+    /// filter captures by text ([`super::query_engine`]).
     pub query: String,
     #[serde(default)]
     pub query_by_language: HashMap<String, String>,
+    /// Whether matched entities are lifted. Set `liftable = false` when the
+    /// entity's meaning is already structural (e.g. generated hooks).
+    #[serde(default = "default_liftable")]
+    pub liftable: bool,
+}
+
+fn default_liftable() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -113,6 +138,8 @@ pub struct DepQuery {
     pub caller: String,
     pub callee: String,
     pub filter_callee: Option<String>,
+    /// Tree-sitter query; `#eq?`, `#match?`, and `#any-of?` predicates
+    /// filter captures by text ([`super::query_engine`]).
     pub query: String,
     #[serde(default)]
     pub query_by_language: HashMap<String, String>,
@@ -130,10 +157,53 @@ pub struct AutoLiftRule {
     pub prefix_verb: HashMap<String, String>,
 }
 
+/// Framework boilerplate (barrel files, passthrough layouts, app configs)
+/// that gets the same generic description every time. Matching entities are
+/// auto-lifted with the canned `features` instead of going to an LLM batch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoilerplateRule {
+    pub id: String,
+    /// Globs matched against the entity's file path, e.g. `**/index.ts`.
+    pub files: Vec<String>,
+    #[serde(rename = "match", default)]
+    pub match_rule: EntityMatch,
+    pub features: Vec<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct FeatureFlags {
     #[serde(default)]
     pub redux_state_signals: bool,
+    /// Zustand store hooks: calls read the store, `setState` writes it.
+    #[serde(default)]
+    pub zustand_state_signals: bool,
+    /// Pinia store hooks: calls read the store; `$patch`/`$reset` and
+    /// assignments through the returned store write it.
+    #[serde(default)]
+    pub pinia_state_signals: bool,
+    /// Synthesize a Component entity for each Vue single-file component script.
+    #[serde(default)]
+    pub sfc_components: bool,
+    /// Record route paths/methods and store names as entity attributes.
+    #[serde(default)]
+    pub entity_attributes: bool,
+    /// Prefix FastAPI route paths with their `APIRouter(prefix=...)`.
+    #[serde(default)]
+    pub router_prefixes: bool,
+    /// Turn NestJS `@Get(...)`/`@Post(...)` methods of `@Controller` classes
+    /// into Routes, with the controller prefix joined onto the path.
+    #[serde(default)]
+    pub nest_routes: bool,
+    /// Give each route a `handle METHOD /path` feature from its `route` attribute.
+    #[serde(default)]
+    pub route_features: bool,
+    /// Synthesize Route entities from Next.js App Router files (`app/**/page.tsx`, ...).
+    #[serde(default)]
+    pub route_tree: bool,
+    /// Link pytest tests and fixtures to the fixtures their parameters name.
+    #[serde(default)]
+    pub pytest_fixtures: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -182,6 +252,9 @@ pub fn parse_entity_kind(s: &str) -> Option<EntityKind> {
         "middleware" => Some(EntityKind::Middleware),
         "route" => Some(EntityKind::Route),
         "test" => Some(EntityKind::Test),
+        "enum" => Some(EntityKind::Enum),
+        "constant" => Some(EntityKind::Constant),
+        "fixture" => Some(EntityKind::Fixture),
         _ => None,
     }
 }
@@ -208,6 +281,7 @@ pub fn parse_edge_kind(s: &str) -> Option<EdgeKind> {
 /// - `schema_version` is 1
 /// - All `entity_kind`/`edge_kind` values are valid variants
 /// - All `name_regex` fields compile as `Regex`
+/// - All boilerplate file patterns compile as globs
 /// - No duplicate rule IDs across all loaded definitions
 pub fn validate_defs(defs: &[ParadigmDef]) -> Result<(), Vec<ValidationError>> {
     let mut errors = Vec::new();
@@ -389,6 +463,49 @@ pub fn validate_defs(defs: &[ParadigmDef]) -> Result<(), Vec<ValidationError>> {
                 }
             }
         }
+
+        // Boilerplate rules
+        for rule in &def.boilerplate {
+            if !all_rule_ids.insert(rule.id.clone()) {
+                errors.push(ValidationError {
+                    paradigm: def.name.clone(),
+                    rule_id: Some(rule.id.clone()),
+                    message: "duplicate rule ID".to_string(),
+                });
+            }
+            if rule.features.is_empty() {
+                errors.push(ValidationError {
+                    paradigm: def.name.clone(),
+                    rule_id: Some(rule.id.clone()),
+                    message: "boilerplate rule must have at least one feature".to_string(),
+                });
+            }
+            if rule.files.is_empty() {
+                errors.push(ValidationError {
+                    paradigm: def.name.clone(),
+                    rule_id: Some(rule.id.clone()),
+                    message: "boilerplate rule must have at least one file pattern".to_string(),
+                });
+            }
+            for pattern in &rule.files {
+                if globset::Glob::new(pattern).is_err() {
+                    errors.push(ValidationError {
+                        paradigm: def.name.clone(),
+                        rule_id: Some(rule.id.clone()),
+                        message: format!("invalid file pattern '{}'", pattern),
+                    });
+                }
+            }
+            if let Some(ref regex_str) = rule.match_rule.name_regex
+                && Regex::new(regex_str).is_err()
+            {
+                errors.push(ValidationError {
+                    paradigm: def.name.clone(),
+                    rule_id: Some(rule.id.clone()),
+                    message: format!("invalid regex '{}'", regex_str),
+                });
+            }
+        }
     }
 
     if errors.is_empty() {
@@ -423,6 +540,164 @@ pub fn load_builtin_defs() -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
     Ok(defs)
 }
 
+/// Directory of project paradigm definitions, relative to the project root.
+pub const PROJECT_DEFS_DIR: &str = ".rpg/paradigms";
+
+/// Paradigm definitions from the project's `.rpg/paradigms/*.toml`, in file
+/// name order. Each is validated on its own and its queries compiled, with
+/// errors naming the file and the line of the offending rule. A missing
+/// directory yields no defs.
+pub fn load_project_defs(root: &Path) -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
+    let mut defs = Vec::new();
+    let mut errors = Vec::new();
+    for (display, source) in read_toml_dir(root, PROJECT_DEFS_DIR, &mut errors) {
+        let def: ParadigmDef = match toml::from_str(&source) {
+            Ok(def) => def,
+            Err(e) => {
+                errors.push(toml_error(&display, &source, &e));
+                continue;
+            }
+        };
+        let mut def_errors = validate_defs(std::slice::from_ref(&def))
+            .err()
+            .unwrap_or_default();
+        if def_errors.is_empty()
+            && let Err(query_errors) =
+                super::query_engine::QueryCache::compile_all(std::slice::from_ref(&def))
+        {
+            def_errors.extend(query_errors.into_iter().map(|message| ValidationError {
+                paradigm: def.name.clone(),
+                rule_id: None,
+                message,
+            }));
+        }
+        if def_errors.is_empty() {
+            defs.push(def);
+        }
+        errors.extend(def_errors.into_iter().map(|e| {
+            let line = e.rule_id.as_deref().and_then(|id| rule_line(&source, id));
+            located(&display, line, e.rule_id.as_deref(), e.message)
+        }));
+    }
+    if errors.is_empty() {
+        Ok(defs)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Built-in definitions merged with the project's: `.rpg/paradigms/`
+/// definitions replace built-ins of the same name, and user extractors
+/// ([`super::extractors`]) are added. Sorted by priority and validated
+/// together.
+pub fn load_defs(root: &Path) -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
+    let mut defs = load_builtin_defs()?;
+    let (project, extractors) = match (
+        load_project_defs(root),
+        super::extractors::load_extractor_defs(root),
+    ) {
+        (Ok(project), Ok(extractors)) => (project, extractors),
+        (project, extractors) => {
+            return Err(project
+                .err()
+                .into_iter()
+                .chain(extractors.err())
+                .flatten()
+                .collect());
+        }
+    };
+    defs.retain(|def| !project.iter().any(|p| p.name == def.name));
+    defs.extend(project);
+    defs.extend(extractors);
+    defs.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    validate_defs(&defs)?;
+    Ok(defs)
+}
+
+/// [`load_defs`], falling back to the built-ins when the project's
+/// definitions don't load, for background paths that can't report errors.
+pub fn load_defs_or_builtin(root: &Path) -> Vec<ParadigmDef> {
+    load_defs(root)
+        .or_else(|_| load_builtin_defs())
+        .unwrap_or_default()
+}
+
+/// `(display path, contents)` of every `*.toml` in `root/dir`, in file name
+/// order. Unreadable files are reported to `errors`.
+pub(super) fn read_toml_dir(
+    root: &Path,
+    dir: &str,
+    errors: &mut Vec<ValidationError>,
+) -> Vec<(String, String)> {
+    let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let display = format!("{}/{}", dir, name);
+            match std::fs::read_to_string(&path) {
+                Ok(source) => Some((display, source)),
+                Err(e) => {
+                    errors.push(located(&display, None, None, e.to_string()));
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// A TOML syntax or schema error at its line.
+pub(super) fn toml_error(display: &str, source: &str, error: &toml::de::Error) -> ValidationError {
+    let line = error.span().map(|span| line_of(source, span.start));
+    located(display, line, None, error.message().to_string())
+}
+
+/// An error reported against `display` (a file path), at `line` if known.
+pub(super) fn located(
+    display: &str,
+    line: Option<usize>,
+    rule_id: Option<&str>,
+    message: String,
+) -> ValidationError {
+    ValidationError {
+        paradigm: match line {
+            Some(line) => format!("{}:{}", display, line),
+            None => display.to_string(),
+        },
+        rule_id: rule_id.map(str::to_string),
+        message,
+    }
+}
+
+/// 1-based line of byte `offset` in `source`.
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// Line of the `id = "..."` key declaring rule `id`.
+pub(super) fn rule_line(source: &str, id: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| {
+            let line = line.trim_start();
+            line.starts_with("id")
+                && (line.contains(&format!("\"{}\"", id)) || line.contains(&format!("'{}'", id)))
+        })
+        .map(|index| index + 1)
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -434,13 +709,14 @@ mod tests {
     #[test]
     fn test_load_builtin_defs() {
         let defs = load_builtin_defs().expect("built-in defs should load and validate");
-        assert!(defs.len() >= 31, "expected at least 31 paradigm defs");
+        assert!(defs.len() >= 32, "expected at least 32 paradigm defs");
 
         // Verify priority ordering (ascending priority, then alphabetical name)
         let names: Vec<&str> = defs.iter().map(|d| d.name.as_str()).collect();
         assert_eq!(
             names,
             &[
+                "pytest",  // 5
                 "angular", // 8
                 "aspnet",  // 10
                 "compose", // 10
@@ -457,6 +733,7 @@ mod tests {
                 "fastapi", // 15
                 "flask",   // 20
                 "redux",   // 20
+                "zustand", // 20
                 "express", // 25
                 "react",   // 30
                 "c",       // 50
@@ -496,6 +773,7 @@ mod tests {
         );
         assert_eq!(parse_entity_kind("route"), Some(EntityKind::Route));
         assert_eq!(parse_entity_kind("test"), Some(EntityKind::Test));
+        assert_eq!(parse_entity_kind("fixture"), Some(EntityKind::Fixture));
         assert_eq!(parse_entity_kind("bogus"), None);
     }
 
@@ -531,6 +809,7 @@ mod tests {
             entity_queries: Vec::new(),
             dep_queries: Vec::new(),
             auto_lift: Vec::new(),
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -557,6 +836,7 @@ mod tests {
             entity_queries: Vec::new(),
             dep_queries: Vec::new(),
             auto_lift: Vec::new(),
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -652,6 +932,7 @@ mod tests {
             entity_queries: Vec::new(),
             dep_queries: Vec::new(),
             auto_lift: Vec::new(),
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -669,6 +950,7 @@ mod tests {
             entity_queries: Vec::new(),
             dep_queries: Vec::new(),
             auto_lift: Vec::new(),
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -719,6 +1001,7 @@ mod tests {
                 strip_prefix: Vec::new(),
                 prefix_verb: HashMap::new(),
             }],
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -744,6 +1027,7 @@ mod tests {
                 strip_prefix: Vec::new(),
                 prefix_verb: HashMap::new(),
             }],
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -769,6 +1053,7 @@ mod tests {
                 strip_prefix: Vec::new(),
                 prefix_verb: HashMap::new(),
             }],
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -794,6 +1079,7 @@ mod tests {
                 strip_prefix: vec!["get_".to_string()],
                 prefix_verb: HashMap::new(), // empty — should fail
             }],
+            boilerplate: Vec::new(),
             features: FeatureFlags::default(),
             prompt_hints: PromptHints::default(),
         };
@@ -801,6 +1087,44 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_boilerplate_bad_glob() {
+        let def = ParadigmDef {
+            schema_version: 1,
+            name: "test".to_string(),
+            priority: 50,
+            languages: vec![],
+            detect: DetectRules::default(),
+            classify: Vec::new(),
+            entity_queries: Vec::new(),
+            dep_queries: Vec::new(),
+            auto_lift: Vec::new(),
+            boilerplate: vec![BoilerplateRule {
+                id: "test.bad_glob".to_string(),
+                files: vec!["**/index.{ts".to_string()],
+                match_rule: EntityMatch::default(),
+                features: vec!["re-export module members".to_string()],
+            }],
+            features: FeatureFlags::default(),
+            prompt_hints: PromptHints::default(),
+        };
+        let errors = validate_defs(&[def]).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].rule_id.as_deref(), Some("test.bad_glob"));
+    }
+
+    #[test]
+    fn test_builtin_boilerplate_rules_load() {
+        let defs = load_builtin_defs().unwrap();
+        let ids: Vec<&str> = defs
+            .iter()
+            .flat_map(|d| d.boilerplate.iter().map(|r| r.id.as_str()))
+            .collect();
+        assert!(ids.contains(&"react.barrel_file"));
+        assert!(ids.contains(&"nextjs.passthrough_layout"));
+        assert!(ids.contains(&"django.app_config"));
+    }
+
     #[test]
     fn test_core_toml_loads() {
         let defs = load_builtin_defs().unwrap();
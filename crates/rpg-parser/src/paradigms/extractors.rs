@@ -0,0 +1,170 @@
+//! User-defined entity extractors from `.rpg/extractors/*.toml`.
+//!
+//! An extractor file holds `[[entity_queries]]` in the paradigm format for
+//! constructs the built-in extraction doesn't cover, with a file-wide
+//! default `languages` list:
+//!
+//! ```toml
+//! languages = ["python"]
+//!
+//! [[entity_queries]]
+//! id = "sqlalchemy.table"
+//! entity_kind = "class"
+//! entity_name = "@name"
+//! entity_body = "@body"
+//! query = '''
+//! (expression_statement
+//!   (assignment
+//!     left: (identifier) @name
+//!     right: (call function: (identifier) @fn (#eq? @fn "Table")))) @body
+//! '''
+//! ```
+//!
+//! Each file becomes a paradigm def named `extractors/<stem>` that is always
+//! active for its languages, so its queries run wherever paradigm entity
+//! queries do. Problems are reported as `path:line` of the offending rule.
+
+use super::defs::{
+    DetectRules, EntityQuery, FeatureFlags, ParadigmDef, PromptHints, ValidationError, located,
+    parse_entity_kind, read_toml_dir, rule_line, toml_error,
+};
+use super::query_engine::compile_query;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Directory of extractor files, relative to the project root.
+pub const EXTRACTORS_DIR: &str = ".rpg/extractors";
+
+/// Extractors run after every built-in paradigm.
+const EXTRACTOR_PRIORITY: i32 = 200;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExtractorFile {
+    /// Languages for queries that don't list their own.
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    entity_queries: Vec<EntityQuery>,
+}
+
+/// Load every `*.toml` under [`EXTRACTORS_DIR`], in file name order. A
+/// missing directory yields no defs.
+pub fn load_extractor_defs(root: &Path) -> Result<Vec<ParadigmDef>, Vec<ValidationError>> {
+    let mut defs = Vec::new();
+    let mut errors = Vec::new();
+    for (display, source) in read_toml_dir(root, EXTRACTORS_DIR, &mut errors) {
+        let stem = Path::new(&display).file_stem().unwrap_or_default();
+        match parse_extractor(&stem.to_string_lossy(), &display, &source) {
+            Ok(def) => defs.push(def),
+            Err(file_errors) => errors.extend(file_errors),
+        }
+    }
+    if errors.is_empty() {
+        Ok(defs)
+    } else {
+        Err(errors)
+    }
+}
+
+/// One extractor file as a paradigm def, with its queries compiled once to
+/// catch errors at load time.
+fn parse_extractor(
+    stem: &str,
+    display: &str,
+    source: &str,
+) -> Result<ParadigmDef, Vec<ValidationError>> {
+    let file: ExtractorFile =
+        toml::from_str(source).map_err(|e| vec![toml_error(display, source, &e)])?;
+
+    let mut errors = Vec::new();
+    let mut languages = file.languages.clone();
+    for eq in &file.entity_queries {
+        let line = rule_line(source, &eq.id);
+        let mut error = |message: String| {
+            errors.push(located(display, line, Some(&eq.id), message));
+        };
+        if parse_entity_kind(&eq.entity_kind).is_none() {
+            error(format!("unknown entity_kind '{}'", eq.entity_kind));
+        }
+        let query_langs = if eq.languages.is_empty() {
+            &file.languages
+        } else {
+            &eq.languages
+        };
+        if query_langs.is_empty() {
+            error("no languages; set `languages` on the query or the file".to_string());
+        }
+        for lang in query_langs {
+            if !languages.contains(lang) {
+                languages.push(lang.clone());
+            }
+        }
+        for lang in crate::languages::expand_lang_aliases(query_langs) {
+            let query_str = eq.query_by_language.get(&lang).unwrap_or(&eq.query);
+            let query = match compile_query(&lang, query_str) {
+                Ok(query) => query,
+                Err(e) => {
+                    error(format!("query for '{}': {}", lang, e));
+                    continue;
+                }
+            };
+            for capture in std::iter::once(&eq.entity_name).chain(&eq.entity_body) {
+                if query
+                    .capture_index_for_name(capture.trim_start_matches('@'))
+                    .is_none()
+                {
+                    error(format!("query for '{}' has no capture {}", lang, capture));
+                }
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(ParadigmDef {
+        schema_version: 1,
+        name: format!("extractors/{}", stem),
+        priority: EXTRACTOR_PRIORITY,
+        languages,
+        detect: DetectRules {
+            always: true,
+            ..DetectRules::default()
+        },
+        classify: Vec::new(),
+        entity_queries: file.entity_queries,
+        dep_queries: Vec::new(),
+        auto_lift: Vec::new(),
+        boilerplate: Vec::new(),
+        features: FeatureFlags::default(),
+        prompt_hints: PromptHints::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_reports_file_and_line() {
+        let source = "languages = [\"python\"]\n\n[[entity_queries]]\nid = \"t\"\nentity_kind = \"class\"\nentity_nam = \"@name\"\nquery = \"(identifier) @name\"\n";
+        let errors = parse_extractor("tables", ".rpg/extractors/tables.toml", source).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].paradigm, ".rpg/extractors/tables.toml:6");
+    }
+
+    #[test]
+    fn test_rule_errors_point_at_rule_id_line() {
+        let source = "languages = [\"python\"]\n\n[[entity_queries]]\nid = \"bad\"\nentity_kind = \"tabel\"\nentity_name = \"@missing\"\nquery = \"(identifier) @name\"\n";
+        let errors = parse_extractor("tables", ".rpg/extractors/tables.toml", source).unwrap_err();
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "[.rpg/extractors/tables.toml:4] rule bad: unknown entity_kind 'tabel'",
+                "[.rpg/extractors/tables.toml:4] rule bad: query for 'python' has no capture @missing",
+            ]
+        );
+    }
+}
@@ -4,17 +4,28 @@
 //! These are activated by feature flags in paradigm TOML files.
 
 use super::defs::ParadigmDef;
-use crate::deps::{self, CallDep, FunctionScope, RawDeps, find_enclosing_scope};
+use crate::deps::{self, CallDep, ComposeDep, FunctionScope, RawDeps, find_enclosing_scope};
 use crate::entities::RawEntity;
 use crate::languages::Language;
-use rpg_core::graph::EntityKind;
-use std::collections::HashSet;
+use regex::Regex;
+use rpg_core::graph::{EntityKind, FEATURES_ATTRIBUTE, LIFTABLE_ATTRIBUTE};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
+use std::sync::OnceLock;
 
 /// Apply built-in entity feature extractors for active paradigms.
 ///
 /// Currently supports:
 /// - Redux: extract createSlice reducer keys and destructured RTK Query hooks
+/// - Route/store attributes: HTTP method + path for routes, slice name for stores
+/// - FastAPI router prefixes: `APIRouter(prefix=...)` joined onto route paths
+/// - NestJS routes: `@Get(':id')` methods of `@Controller('users')` classes
+/// - Route features: a `handle METHOD /path` feature per route
+/// - Next.js route tree: one Route entity per App Router page/layout/loading/route file
+/// - Vue single-file components: one Component entity per SFC script
+///
+/// Entity query opt-outs (`liftable = false`) then carry over to entities
+/// the extractors produced again under the same ID.
 pub fn apply_builtin_entity_features(
     active_defs: &[&ParadigmDef],
     file: &Path,
@@ -22,18 +33,74 @@ pub fn apply_builtin_entity_features(
     language: Language,
     entities: &mut Vec<RawEntity>,
 ) {
+    extract_builtin_entities(active_defs, file, source, language, entities);
+    propagate_liftable_opt_outs(entities);
+}
+
+/// Mark every entity sharing an ID with an opted-out entity as opted out,
+/// so the opt-out survives whichever duplicate ends up in the graph.
+fn propagate_liftable_opt_outs(entities: &mut [RawEntity]) {
+    let opted_out: HashSet<String> = entities
+        .iter()
+        .filter(|e| {
+            e.attributes
+                .get(LIFTABLE_ATTRIBUTE)
+                .is_some_and(|v| v == "false")
+        })
+        .map(RawEntity::id)
+        .collect();
+    if opted_out.is_empty() {
+        return;
+    }
+    for entity in entities.iter_mut() {
+        if opted_out.contains(&entity.id()) {
+            entity
+                .attributes
+                .insert(LIFTABLE_ATTRIBUTE.to_string(), "false".to_string());
+        }
+    }
+}
+
+fn extract_builtin_entities(
+    active_defs: &[&ParadigmDef],
+    file: &Path,
+    source: &str,
+    language: Language,
+    entities: &mut Vec<RawEntity>,
+) {
+    if active_defs.iter().any(|d| d.features.nest_routes) {
+        annotate_nest_routes(entities);
+    }
+    if active_defs.iter().any(|d| d.features.entity_attributes) {
+        annotate_entity_attributes(entities);
+    }
+    if active_defs.iter().any(|d| d.features.router_prefixes) {
+        apply_router_prefixes(source, entities);
+    }
+    if active_defs.iter().any(|d| d.features.route_features) {
+        synthesize_route_features(entities);
+    }
+
     if !(language == Language::TYPESCRIPT || language == Language::JAVASCRIPT) {
         return;
     }
 
+    if active_defs.iter().any(|d| d.features.route_tree)
+        && let Some(route) = app_route(file)
+    {
+        entities.push(route.to_entity(file, source, entities));
+    }
+
+    if active_defs.iter().any(|d| d.features.sfc_components)
+        && let Some(component) = sfc_component(file, source, entities)
+    {
+        entities.push(component);
+    }
+
     for def in active_defs {
         if def.features.redux_state_signals {
             let ts_lang = language.ts_language();
-            let mut parser = tree_sitter::Parser::new();
-            if parser.set_language(&ts_lang).is_err() {
-                return;
-            }
-            let Some(tree) = parser.parse(source.as_bytes(), None) else {
+            let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
                 return;
             };
             let root = tree.root_node();
@@ -48,34 +115,499 @@ pub fn apply_builtin_entity_features(
 
             // Extract destructured RTK Query hooks
             extract_destructured_hooks(&root, file, source, entities);
+
+            annotate_reducer_actions(entities);
         }
     }
 }
 
+/// Record paradigm metadata on route and store entities.
+///
+/// Routes get `route` (e.g. `GET /users`, comma-joined when a handler serves
+/// several); stores get `store` from a Pinia `defineStore("id", ...)` or a
+/// `name: "..."` option (createSlice).
+fn annotate_entity_attributes(entities: &mut [RawEntity]) {
+    for entity in entities.iter_mut() {
+        match entity.kind {
+            EntityKind::Route if !entity.attributes.contains_key("route") => {
+                let routes = extract_routes(&entity.source_text);
+                if !routes.is_empty() {
+                    entity
+                        .attributes
+                        .insert("route".to_string(), routes.join(", "));
+                }
+            }
+            EntityKind::Store if !entity.attributes.contains_key("store") => {
+                if let Some(name) = extract_store_name(&entity.source_text) {
+                    entity.attributes.insert("store".to_string(), name);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Extract `METHOD /path` pairs from Express-style calls (`router.get("/x")`),
+/// FastAPI-style decorators (`@app.post("/x")`), and Flask `.route("/x", methods=[...])`.
+fn extract_routes(source: &str) -> Vec<String> {
+    static VERB_RE: OnceLock<Regex> = OnceLock::new();
+    static FLASK_RE: OnceLock<Regex> = OnceLock::new();
+    let verb_re = VERB_RE.get_or_init(|| {
+        Regex::new(r#"\.(get|post|put|delete|patch)\(\s*["'`](/[^"'`]*)["'`]"#).unwrap()
+    });
+    let flask_re = FLASK_RE.get_or_init(|| {
+        Regex::new(r#"\.route\(\s*["'](/[^"']*)["'](?:[^)]*?methods\s*=\s*\[([^\]]*)\])?"#).unwrap()
+    });
+
+    let mut routes: Vec<String> = Vec::new();
+    let mut push = |route: String| {
+        if !routes.contains(&route) {
+            routes.push(route);
+        }
+    };
+    for cap in verb_re.captures_iter(source) {
+        push(format!("{} {}", cap[1].to_uppercase(), &cap[2]));
+    }
+    for cap in flask_re.captures_iter(source) {
+        let path = &cap[1];
+        let methods: Vec<String> = cap
+            .get(2)
+            .map(|m| {
+                m.as_str()
+                    .split(',')
+                    .map(|s| s.trim().trim_matches(['"', '\'']).to_uppercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if methods.is_empty() {
+            push(format!("GET {}", path));
+        } else {
+            for method in methods {
+                push(format!("{} {}", method, path));
+            }
+        }
+    }
+    routes
+}
+
+/// Rewrite the `route` of FastAPI handlers registered on an `APIRouter`
+/// declared with a `prefix` in the same file, so `@router.get("/{id}")` on
+/// `APIRouter(prefix="/users")` records `GET /users/{id}`.
+fn apply_router_prefixes(source: &str, entities: &mut [RawEntity]) {
+    static DECORATOR_RE: OnceLock<Regex> = OnceLock::new();
+    let prefixes = router_prefixes(source);
+    if prefixes.is_empty() {
+        return;
+    }
+    let decorator_re = DECORATOR_RE.get_or_init(|| {
+        Regex::new(r#"@(\w+)\.(get|post|put|delete|patch)\(\s*["']([^"']*)["']"#).unwrap()
+    });
+    for entity in entities.iter_mut().filter(|e| e.kind == EntityKind::Route) {
+        let mut routes: Vec<String> = Vec::new();
+        let mut prefixed = false;
+        for cap in decorator_re.captures_iter(&entity.source_text) {
+            let path = match prefixes.get(&cap[1]) {
+                Some(prefix) => {
+                    prefixed = true;
+                    format!("{}{}", prefix.trim_end_matches('/'), &cap[3])
+                }
+                None => cap[3].to_string(),
+            };
+            let path = if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            };
+            let route = format!("{} {}", cap[2].to_uppercase(), path);
+            if !routes.contains(&route) {
+                routes.push(route);
+            }
+        }
+        if prefixed {
+            entity
+                .attributes
+                .insert("route".to_string(), routes.join(", "));
+        }
+    }
+}
+
+/// Reclassify NestJS handler methods as Routes: a method of a
+/// `@Controller('users')` class decorated with `@Get(':id')` records
+/// `GET /users/:id`. `@Controller({ path: 'users' })` is read too.
+fn annotate_nest_routes(entities: &mut [RawEntity]) {
+    static CONTROLLER_RE: OnceLock<Regex> = OnceLock::new();
+    static VERB_RE: OnceLock<Regex> = OnceLock::new();
+    let controller_re = CONTROLLER_RE.get_or_init(|| {
+        Regex::new(r#"^Controller\(\s*(?:\{[^}]*?\bpath\s*:\s*)?(?:["'`]([^"'`]*)["'`])?"#).unwrap()
+    });
+    let verb_re = VERB_RE.get_or_init(|| {
+        Regex::new(
+            r#"^(Get|Post|Put|Delete|Patch|Options|Head|All)\(\s*(?:["'`]([^"'`]*)["'`])?\s*\)$"#,
+        )
+        .unwrap()
+    });
+    let prefixes: HashMap<String, String> = entities
+        .iter()
+        .filter(|e| e.parent_class.is_none())
+        .filter_map(|e| {
+            let cap = e
+                .decorators
+                .iter()
+                .find_map(|d| controller_re.captures(d))?;
+            let prefix = cap.get(1).map_or("", |m| m.as_str());
+            Some((e.name.clone(), prefix.to_string()))
+        })
+        .collect();
+    if prefixes.is_empty() {
+        return;
+    }
+    for entity in entities.iter_mut().filter(|e| e.kind == EntityKind::Method) {
+        let Some(prefix) = entity.parent_class.as_ref().and_then(|c| prefixes.get(c)) else {
+            continue;
+        };
+        let mut routes: Vec<String> = Vec::new();
+        for cap in entity.decorators.iter().filter_map(|d| verb_re.captures(d)) {
+            let path: Vec<&str> = [prefix.as_str(), cap.get(2).map_or("", |m| m.as_str())]
+                .iter()
+                .flat_map(|part| part.split('/'))
+                .filter(|segment| !segment.is_empty())
+                .collect();
+            let route = format!("{} /{}", cap[1].to_uppercase(), path.join("/"));
+            if !routes.contains(&route) {
+                routes.push(route);
+            }
+        }
+        if !routes.is_empty() {
+            entity.kind = EntityKind::Route;
+            entity
+                .attributes
+                .insert("route".to_string(), routes.join(", "));
+        }
+    }
+}
+
+/// `APIRouter` variables declared with a `prefix`, by variable name.
+fn router_prefixes(source: &str) -> HashMap<String, String> {
+    static ROUTER_RE: OnceLock<Regex> = OnceLock::new();
+    static PREFIX_RE: OnceLock<Regex> = OnceLock::new();
+    let router_re = ROUTER_RE.get_or_init(|| {
+        Regex::new(r"(?m)^(\w+)\s*(?::\s*[\w.]+\s*)?=\s*(?:fastapi\.)?APIRouter\(([^)]*)\)")
+            .unwrap()
+    });
+    let prefix_re =
+        PREFIX_RE.get_or_init(|| Regex::new(r#"\bprefix\s*=\s*["']([^"']*)["']"#).unwrap());
+    router_re
+        .captures_iter(source)
+        .filter_map(|cap| {
+            let prefix = prefix_re.captures(&cap[2])?;
+            Some((cap[1].to_string(), prefix[1].to_string()))
+        })
+        .collect()
+}
+
+/// Give each route entity one `handle METHOD /path` feature per route it
+/// serves, recorded under [`FEATURES_ATTRIBUTE`].
+fn synthesize_route_features(entities: &mut [RawEntity]) {
+    for entity in entities.iter_mut().filter(|e| e.kind == EntityKind::Route) {
+        if entity.attributes.contains_key(FEATURES_ATTRIBUTE) {
+            continue;
+        }
+        let Some(route) = entity.attributes.get("route") else {
+            continue;
+        };
+        let features: Vec<String> = route.split(", ").map(|r| format!("handle {}", r)).collect();
+        entity
+            .attributes
+            .insert(FEATURES_ATTRIBUTE.to_string(), features.join("; "));
+    }
+}
+
+/// Record each createSlice reducer's action type (`auth/loginStarted`) from
+/// the `store` name of its slice.
+fn annotate_reducer_actions(entities: &mut [RawEntity]) {
+    let slices: HashMap<String, String> = entities
+        .iter()
+        .filter(|e| e.kind == EntityKind::Store)
+        .filter_map(|e| {
+            e.attributes
+                .get("store")
+                .map(|s| (e.name.clone(), s.clone()))
+        })
+        .collect();
+    for entity in entities.iter_mut() {
+        if entity.kind == EntityKind::Function
+            && let Some(slice) = entity.parent_class.as_ref().and_then(|p| slices.get(p))
+        {
+            entity
+                .attributes
+                .insert("action".to_string(), format!("{}/{}", slice, entity.name));
+        }
+    }
+}
+
+/// Extract the store name from a `name: "auth"` option (e.g. `createSlice({ name: "auth" })`).
+fn extract_store_name(source: &str) -> Option<String> {
+    static DEFINE_STORE_RE: OnceLock<Regex> = OnceLock::new();
+    static NAME_RE: OnceLock<Regex> = OnceLock::new();
+    let define_store = DEFINE_STORE_RE
+        .get_or_init(|| Regex::new(r#"\bdefineStore\s*\(\s*["'`]([^"'`]+)["'`]"#).unwrap());
+    let name = NAME_RE.get_or_init(|| Regex::new(r#"\bname\s*:\s*["'`]([^"'`]+)["'`]"#).unwrap());
+    define_store
+        .captures(source)
+        .or_else(|| name.captures(source))
+        .map(|cap| cap[1].to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Vue single-file components
+// ---------------------------------------------------------------------------
+
+/// The Component entity for a Vue single-file component script, spanning the
+/// whole script: one that default-exports `defineComponent(...)`, or a
+/// `<script setup>` block (it calls a compiler macro such as `defineProps`).
+/// Named by the component's `name` option, else the file stem in PascalCase
+/// (`cart-badge.vue` → `CartBadge`). `None` for other files, or when an
+/// entity of that name already exists.
+fn sfc_component(file: &Path, source: &str, entities: &[RawEntity]) -> Option<RawEntity> {
+    static SETUP_MACRO_RE: OnceLock<Regex> = OnceLock::new();
+    let setup_macro = SETUP_MACRO_RE.get_or_init(|| {
+        Regex::new(r"\bdefine(Props|Emits|Model|Expose|Slots|Options)\s*[<(]").unwrap()
+    });
+
+    let name = if let Some(at) = source.find("export default defineComponent(") {
+        extract_store_name(&source[at..]).unwrap_or_else(|| pascal_case_stem(file))
+    } else if setup_macro.is_match(source) {
+        pascal_case_stem(file)
+    } else {
+        return None;
+    };
+    if name.is_empty() || entities.iter().any(|e| e.name == name) {
+        return None;
+    }
+    Some(RawEntity {
+        name,
+        kind: EntityKind::Component,
+        file: file.to_path_buf(),
+        line_start: 1,
+        line_end: source.lines().count().max(1),
+        parent_class: None,
+        source_text: source.to_string(),
+        signature: None,
+        attributes: BTreeMap::new(),
+        doc_comment: None,
+        decorators: Vec::new(),
+        is_async: false,
+        is_generator: false,
+        is_unsafe: false,
+    })
+}
+
+/// `cart-badge.vue` → `CartBadge`.
+fn pascal_case_stem(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    stem.split(['-', '_', '.'])
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Next.js App Router route tree
+// ---------------------------------------------------------------------------
+
+/// HTTP method handlers a Next.js `route.ts` file can export.
+const HTTP_METHOD_HANDLERS: &[&str] = &["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// A file that defines part of a Next.js App Router route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppRoute {
+    /// URL path, e.g. `/users/[id]` (route groups and parallel slots removed).
+    pub url: String,
+    /// File convention: `page`, `layout`, `loading`, or `route`.
+    pub file_kind: &'static str,
+    /// Entity name: the URL as words plus the file role, e.g. `users id page route`.
+    pub name: String,
+}
+
+/// The App Router route a file defines, if it is a `page`, `layout`,
+/// `loading`, or `route` file under an `app/` directory.
+pub fn app_route(file: &Path) -> Option<AppRoute> {
+    let ext = file.extension()?.to_str()?;
+    if !matches!(ext, "js" | "jsx" | "ts" | "tsx") {
+        return None;
+    }
+    let file_kind = match file.file_stem()?.to_str()? {
+        "page" => "page",
+        "layout" => "layout",
+        "loading" => "loading",
+        "route" => "route",
+        _ => return None,
+    };
+    let dirs: Vec<&str> = file
+        .parent()?
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    let app = dirs.iter().position(|d| *d == "app")?;
+
+    // `(group)` folders organize files without adding a URL segment, and
+    // `@slot` folders are parallel routes rendered into the parent's URL.
+    let segments: Vec<&str> = dirs[app + 1..]
+        .iter()
+        .copied()
+        .filter(|d| !(d.starts_with('@') || (d.starts_with('(') && d.ends_with(')'))))
+        .collect();
+    let url = format!("/{}", segments.join("/"));
+
+    let mut words: Vec<String> = segments
+        .iter()
+        .flat_map(|s| s.trim_matches(['[', ']', '.']).split(['-', '_']))
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        words.push("root".to_string());
+    }
+    let role = if file_kind == "route" {
+        "handler"
+    } else {
+        file_kind
+    };
+    Some(AppRoute {
+        name: format!("{} {} route", words.join(" "), role),
+        url,
+        file_kind,
+    })
+}
+
+impl AppRoute {
+    /// The synthesized Route entity spanning the whole file. Route handlers
+    /// record `METHOD /url` for each exported method, like other routes.
+    fn to_entity(&self, file: &Path, source: &str, entities: &[RawEntity]) -> RawEntity {
+        let mut route = self.url.clone();
+        if self.file_kind == "route" {
+            let methods: Vec<String> = HTTP_METHOD_HANDLERS
+                .iter()
+                .filter(|m| entities.iter().any(|e| e.name == **m))
+                .map(|m| format!("{} {}", m, self.url))
+                .collect();
+            if !methods.is_empty() {
+                route = methods.join(", ");
+            }
+        }
+        let mut attributes = BTreeMap::new();
+        attributes.insert("route".to_string(), route);
+        attributes.insert("route_file".to_string(), self.file_kind.to_string());
+        RawEntity {
+            name: self.name.clone(),
+            kind: EntityKind::Route,
+            file: file.to_path_buf(),
+            line_start: 1,
+            line_end: source.lines().count().max(1),
+            parent_class: None,
+            source_text: source.to_string(),
+            signature: None,
+            attributes,
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        }
+    }
+}
+
+/// Layout nesting for App Router files: pairs each page, loading, or layout
+/// file with the nearest layout in an enclosing directory (for a layout, the
+/// search starts at its parent directory). Returns `(layout, child)` indices
+/// into `files`; route handlers are never rendered.
+pub fn app_route_nesting<P: AsRef<Path>>(files: &[P]) -> Vec<(usize, usize)> {
+    let routes: Vec<Option<AppRoute>> = files.iter().map(|f| app_route(f.as_ref())).collect();
+    let layouts: HashMap<&Path, usize> = files
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| routes[*i].as_ref().is_some_and(|r| r.file_kind == "layout"))
+        .filter_map(|(i, f)| f.as_ref().parent().map(|dir| (dir, i)))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (child, file) in files.iter().enumerate() {
+        let Some(route) = &routes[child] else {
+            continue;
+        };
+        if route.file_kind == "route" {
+            continue;
+        }
+        let mut dir = file.as_ref().parent();
+        if route.file_kind == "layout" {
+            dir = dir.and_then(Path::parent);
+        }
+        while let Some(d) = dir {
+            if let Some(&layout) = layouts.get(d) {
+                pairs.push((layout, child));
+                break;
+            }
+            if d.file_name().is_some_and(|n| n == "app") {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+    pairs
+}
+
 /// Apply built-in dependency feature extractors for active paradigms.
 ///
 /// Currently supports:
-/// - Redux: state signal collection (reads_state, writes_state, dispatches)
+/// - Redux: state signal collection (reads_state, writes_state, dispatches),
+///   reducers writing their slice, and `state.<key>` reads of a slice
+/// - Zustand: store hook calls and `getState`/`setState` (reads_state, writes_state)
+/// - Next.js route tree: route handler files dispatch to what their method handlers call
+/// - pytest: tests and fixtures compose the fixtures they request
 pub fn apply_builtin_dep_features(
     active_defs: &[&ParadigmDef],
-    _file: &Path,
+    file: &Path,
     source: &str,
     language: Language,
-    _entities: &[RawEntity],
+    entities: &[RawEntity],
     raw_deps: &mut RawDeps,
 ) {
+    if language == Language::PYTHON && active_defs.iter().any(|d| d.features.pytest_fixtures) {
+        collect_fixture_requests(entities, &mut raw_deps.composes);
+    }
+
     if !(language == Language::TYPESCRIPT || language == Language::JAVASCRIPT) {
         return;
     }
 
+    if active_defs.iter().any(|d| d.features.route_tree)
+        && let Some(route) = app_route(file)
+        && route.file_kind == "route"
+    {
+        let handled: Vec<CallDep> = raw_deps
+            .calls
+            .iter()
+            .filter(|c| HTTP_METHOD_HANDLERS.contains(&c.caller_entity.as_str()))
+            .map(|c| CallDep {
+                caller_entity: route.name.clone(),
+                callee: c.callee.clone(),
+            })
+            .collect();
+        raw_deps.dispatches.extend(handled);
+    }
+
     for def in active_defs {
         if def.features.redux_state_signals {
             let ts_lang = language.ts_language();
-            let mut parser = tree_sitter::Parser::new();
-            if parser.set_language(&ts_lang).is_err() {
-                return;
-            }
-            let Some(tree) = parser.parse(source.as_bytes(), None) else {
+            let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
                 return;
             };
             let root = tree.root_node();
@@ -92,10 +624,334 @@ pub fn apply_builtin_dep_features(
                 &mut raw_deps.writes_state,
                 &mut raw_deps.dispatches,
             );
+            collect_reducer_writes(entities, &mut raw_deps.writes_state);
+            collect_state_key_reads(&root, source, &scopes, entities, &mut raw_deps.reads_state);
+        }
+
+        if def.features.zustand_state_signals {
+            let ts_lang = language.ts_language();
+            let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
+                return;
+            };
+            let root = tree.root_node();
+            let mut scopes = Vec::new();
+            deps::collect_js_scopes(&root, source, &mut scopes, None);
+
+            collect_zustand_signals(
+                &root,
+                source,
+                &scopes,
+                &mut raw_deps.reads_state,
+                &mut raw_deps.writes_state,
+            );
+        }
+
+        if def.features.pinia_state_signals {
+            let ts_lang = language.ts_language();
+            let Some(tree) = crate::treesitter::parse_source(source, &ts_lang) else {
+                return;
+            };
+            let root = tree.root_node();
+            let mut scopes = Vec::new();
+            deps::collect_js_scopes(&root, source, &mut scopes, None);
+
+            let mut bindings = HashMap::new();
+            collect_pinia_bindings(&root, source, &mut bindings);
+            collect_pinia_signals(
+                &root,
+                source,
+                &scopes,
+                &bindings,
+                &mut raw_deps.reads_state,
+                &mut raw_deps.writes_state,
+            );
         }
     }
 }
 
+/// Prefix marking a read of a root state key (`state.auth`) rather than an
+/// entity name. Grounding resolves it to the Store whose `store` attribute is
+/// the key.
+pub const STATE_KEY_PREFIX: &str = "state.";
+
+/// Prefix marking a pytest fixture requested by name (`fixture.db_session`)
+/// rather than an entity name. Grounding resolves it through the conftest
+/// chain to a Fixture entity.
+pub const FIXTURE_PREFIX: &str = "fixture.";
+
+/// Tests and fixtures compose the fixtures they request: parameters not
+/// supplied by `@pytest.mark.parametrize`, and the names given to
+/// `@pytest.mark.usefixtures`.
+fn collect_fixture_requests(entities: &[RawEntity], composes: &mut Vec<ComposeDep>) {
+    for entity in entities {
+        if !matches!(entity.kind, EntityKind::Test | EntityKind::Fixture) {
+            continue;
+        }
+        let mut parametrized = HashSet::new();
+        let mut requested = Vec::new();
+        for decorator in &entity.decorators {
+            let (name, args) = decorator.split_once('(').unwrap_or((decorator, ""));
+            if name.ends_with("parametrize") {
+                parametrized.extend(parametrized_names(args));
+            } else if name.ends_with("usefixtures") {
+                requested.extend(string_literals(args));
+            }
+        }
+        let params = entity.signature.iter().flat_map(|s| &s.parameters);
+        requested.extend(params.map(|p| p.name.clone()).filter(|name| {
+            !matches!(name.as_str(), "self" | "cls")
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                && !parametrized.contains(name)
+        }));
+        for name in requested {
+            composes.push(ComposeDep {
+                source_entity: entity.name.clone(),
+                target_name: format!("{}{}", FIXTURE_PREFIX, name),
+            });
+        }
+    }
+}
+
+/// Argument names a `parametrize(...)` call supplies: `"a, b"` or
+/// `("a", "b")` as its first argument.
+fn parametrized_names(args: &str) -> Vec<String> {
+    let args = args.trim_start();
+    if let Some(rest) = args.strip_prefix(['(', '[']) {
+        let end = rest.find([')', ']']).unwrap_or(rest.len());
+        return string_literals(&rest[..end]);
+    }
+    string_literals(args)
+        .into_iter()
+        .next()
+        .map(|names| {
+            names
+                .split(',')
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Contents of the quoted strings in `text`, in order.
+fn string_literals(text: &str) -> Vec<String> {
+    static STRING: OnceLock<Regex> = OnceLock::new();
+    let re = STRING.get_or_init(|| Regex::new(r#""([^"]*)"|'([^']*)'"#).unwrap());
+    re.captures_iter(text)
+        .filter_map(|c| c.get(1).or_else(|| c.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// createSlice reducers (functions whose parent is a Store in this file)
+/// write their slice.
+fn collect_reducer_writes(entities: &[RawEntity], writes_state: &mut Vec<CallDep>) {
+    for entity in entities {
+        if let Some(slice) = &entity.parent_class
+            && entity.kind == EntityKind::Function
+            && entities
+                .iter()
+                .any(|e| e.kind == EntityKind::Store && &e.name == slice)
+        {
+            writes_state.push(CallDep {
+                caller_entity: entity.name.clone(),
+                callee: slice.clone(),
+            });
+        }
+    }
+}
+
+/// Reads of a root state key (`state.auth.user` in a selector or an inline
+/// `useSelector`), recorded as `state.auth`. Assignments and reducers are
+/// skipped: there `state` is the slice's own state.
+fn collect_state_key_reads(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    entities: &[RawEntity],
+    reads_state: &mut Vec<CallDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "member_expression"
+            && let Some(object) = child.child_by_field_name("object")
+            && object.kind() == "identifier"
+            && &source[object.byte_range()] == "state"
+            && let Some(key) = child.child_by_field_name("property")
+            && !child.parent().is_some_and(|p| {
+                p.kind() == "assignment_expression"
+                    && p.child_by_field_name("left")
+                        .is_some_and(|l| l.id() == child.id())
+            })
+        {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            let is_reducer = entities
+                .iter()
+                .any(|e| e.name == caller && e.parent_class.is_some());
+            if !is_reducer {
+                reads_state.push(CallDep {
+                    caller_entity: caller,
+                    callee: format!("{}{}", STATE_KEY_PREFIX, &source[key.byte_range()]),
+                });
+            }
+        }
+        collect_state_key_reads(&child, source, scopes, entities, reads_state);
+    }
+}
+
+/// Zustand store hooks (`useCartStore`): calling the hook or `getState`/
+/// `subscribe` on it reads the store; `setState` writes it.
+fn collect_zustand_signals(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    reads_state: &mut Vec<CallDep>,
+    writes_state: &mut Vec<CallDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "call_expression"
+            && let Some(func_node) = child.child_by_field_name("function")
+        {
+            let (store, writes) = match func_node.kind() {
+                "identifier" => (&source[func_node.byte_range()], false),
+                "member_expression" => {
+                    let object = func_node.child_by_field_name("object");
+                    let property = func_node.child_by_field_name("property");
+                    match (object, property) {
+                        (Some(o), Some(p)) if o.kind() == "identifier" => {
+                            match &source[p.byte_range()] {
+                                "getState" | "subscribe" => (&source[o.byte_range()], false),
+                                "setState" => (&source[o.byte_range()], true),
+                                _ => ("", false),
+                            }
+                        }
+                        _ => ("", false),
+                    }
+                }
+                _ => ("", false),
+            };
+            if looks_like_store_hook(store) {
+                let caller = find_enclosing_scope(scopes, child.start_position().row)
+                    .unwrap_or_else(|| "<module>".to_string());
+                let dep = CallDep {
+                    caller_entity: caller,
+                    callee: store.to_string(),
+                };
+                if writes {
+                    writes_state.push(dep);
+                } else {
+                    reads_state.push(dep);
+                }
+            }
+        }
+        collect_zustand_signals(&child, source, scopes, reads_state, writes_state);
+    }
+}
+
+/// Variables holding a Pinia store (`const cart = useCartStore()`), mapped to
+/// the store hook.
+fn collect_pinia_bindings(
+    node: &tree_sitter::Node,
+    source: &str,
+    bindings: &mut HashMap<String, String>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "variable_declarator"
+            && let Some(name) = child.child_by_field_name("name")
+            && name.kind() == "identifier"
+            && let Some(value) = child.child_by_field_name("value")
+            && value.kind() == "call_expression"
+            && let Some(func_node) = value.child_by_field_name("function")
+            && func_node.kind() == "identifier"
+            && looks_like_store_hook(&source[func_node.byte_range()])
+        {
+            bindings.insert(
+                source[name.byte_range()].to_string(),
+                source[func_node.byte_range()].to_string(),
+            );
+        }
+        collect_pinia_bindings(&child, source, bindings);
+    }
+}
+
+/// Pinia store hooks (`useCartStore`): calling the hook reads the store;
+/// `$patch`/`$reset` calls and assignments through a bound store
+/// (`cart.items = []`, `cart.$state = ...`) write it.
+fn collect_pinia_signals(
+    node: &tree_sitter::Node,
+    source: &str,
+    scopes: &[FunctionScope],
+    bindings: &HashMap<String, String>,
+    reads_state: &mut Vec<CallDep>,
+    writes_state: &mut Vec<CallDep>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        let signal = match child.kind() {
+            "call_expression" => {
+                child
+                    .child_by_field_name("function")
+                    .and_then(|f| match f.kind() {
+                        "identifier" if looks_like_store_hook(&source[f.byte_range()]) => {
+                            Some((source[f.byte_range()].to_string(), false))
+                        }
+                        "member_expression" => {
+                            let property = &source[f.child_by_field_name("property")?.byte_range()];
+                            let store = pinia_bound_store(&f, source, bindings)?;
+                            matches!(property, "$patch" | "$reset").then_some((store, true))
+                        }
+                        _ => None,
+                    })
+            }
+            "assignment_expression" | "augmented_assignment_expression" => child
+                .child_by_field_name("left")
+                .filter(|l| l.kind() == "member_expression")
+                .and_then(|l| pinia_bound_store(&l, source, bindings))
+                .map(|store| (store, true)),
+            _ => None,
+        };
+        if let Some((store, writes)) = signal {
+            let caller = find_enclosing_scope(scopes, child.start_position().row)
+                .unwrap_or_else(|| "<module>".to_string());
+            let dep = CallDep {
+                caller_entity: caller,
+                callee: store,
+            };
+            if writes {
+                writes_state.push(dep);
+            } else {
+                reads_state.push(dep);
+            }
+        }
+        collect_pinia_signals(&child, source, scopes, bindings, reads_state, writes_state);
+    }
+}
+
+/// The store hook behind a member chain rooted at a bound store variable
+/// (`cart.items.length` → `useCartStore`).
+fn pinia_bound_store(
+    member: &tree_sitter::Node,
+    source: &str,
+    bindings: &HashMap<String, String>,
+) -> Option<String> {
+    let mut object = member.child_by_field_name("object")?;
+    while object.kind() == "member_expression" {
+        object = object.child_by_field_name("object")?;
+    }
+    if object.kind() != "identifier" {
+        return None;
+    }
+    bindings.get(&source[object.byte_range()]).cloned()
+}
+
+/// Check if a name looks like a store hook (`useCartStore`).
+fn looks_like_store_hook(name: &str) -> bool {
+    looks_like_custom_hook(name) && name.ends_with("Store") && name.len() > "useStore".len()
+}
+
 // ---------------------------------------------------------------------------
 // Redux state signal helpers (moved from redux.rs)
 // ---------------------------------------------------------------------------
@@ -508,6 +1364,12 @@ fn extract_reducer_keys(
                 parent_class: Some(slice_name.to_string()),
                 source_text: source[child.byte_range()].to_string(),
                 signature: None,
+                attributes: BTreeMap::new(),
+                doc_comment: None,
+                decorators: Vec::new(),
+                is_async: false,
+                is_generator: false,
+                is_unsafe: false,
             });
         }
     }
@@ -559,6 +1421,12 @@ fn extract_destructured_hooks(
                                 parent_class: None,
                                 source_text: source[child.byte_range()].to_string(),
                                 signature: None,
+                                attributes: BTreeMap::new(),
+                                doc_comment: None,
+                                decorators: Vec::new(),
+                                is_async: false,
+                                is_generator: false,
+                                is_unsafe: false,
                             });
                         }
                     }
@@ -632,4 +1500,132 @@ mod tests {
         assert!(!looks_like_custom_hook("use"));
         assert!(!looks_like_custom_hook("useless"));
     }
+
+    #[test]
+    fn test_looks_like_store_hook() {
+        assert!(looks_like_store_hook("useCartStore"));
+        assert!(!looks_like_store_hook("useStore"));
+        assert!(!looks_like_store_hook("useCart"));
+        assert!(!looks_like_store_hook("cartStore"));
+    }
+
+    #[test]
+    fn test_router_prefixes() {
+        let source =
+            "api = APIRouter()\nrouter = APIRouter(prefix=\"/items/\", tags=[\"items\"])\n";
+        let prefixes = router_prefixes(source);
+        assert_eq!(prefixes.len(), 1);
+        assert_eq!(prefixes["router"], "/items/");
+
+        let mut entities = vec![RawEntity {
+            name: "list_items".into(),
+            kind: EntityKind::Route,
+            file: "items.py".into(),
+            line_start: 3,
+            line_end: 5,
+            parent_class: None,
+            source_text: "@router.get(\"\")\n@router.get(\"/{item_id}\")\ndef list_items(): ..."
+                .into(),
+            signature: None,
+            attributes: BTreeMap::new(),
+            doc_comment: None,
+            decorators: Vec::new(),
+            is_async: false,
+            is_generator: false,
+            is_unsafe: false,
+        }];
+        apply_router_prefixes(source, &mut entities);
+        synthesize_route_features(&mut entities);
+        assert_eq!(
+            entities[0].attributes["route"],
+            "GET /items, GET /items/{item_id}"
+        );
+        assert_eq!(
+            entities[0].attributes[FEATURES_ATTRIBUTE],
+            "handle GET /items; handle GET /items/{item_id}"
+        );
+    }
+
+    #[test]
+    fn test_extract_routes() {
+        assert_eq!(
+            extract_routes("router.get('/users', list);\nrouter.post(\"/users\", create);"),
+            vec!["GET /users", "POST /users"]
+        );
+        assert_eq!(
+            extract_routes("@app.get(\"/users/{id}\")\ndef read_user(id): ..."),
+            vec!["GET /users/{id}"]
+        );
+        assert_eq!(
+            extract_routes("@bp.route(\"/login\", methods=[\"GET\", \"POST\"])"),
+            vec!["GET /login", "POST /login"]
+        );
+        assert_eq!(extract_routes("@app.route('/health')"), vec!["GET /health"]);
+        assert!(extract_routes("req.get('Content-Type')").is_empty());
+    }
+
+    #[test]
+    fn test_app_route() {
+        let route = |p: &str| app_route(Path::new(p)).map(|r| (r.url, r.file_kind, r.name));
+        assert_eq!(
+            route("app/page.tsx"),
+            Some(("/".into(), "page", "root page route".into()))
+        );
+        assert_eq!(
+            route("src/app/(marketing)/about-us/layout.js"),
+            Some(("/about-us".into(), "layout", "about us layout route".into()))
+        );
+        assert_eq!(
+            route("app/users/[id]/@modal/page.tsx"),
+            Some(("/users/[id]".into(), "page", "users id page route".into()))
+        );
+        assert_eq!(
+            route("app/docs/[...slug]/route.ts"),
+            Some((
+                "/docs/[...slug]".into(),
+                "route",
+                "docs slug handler route".into()
+            ))
+        );
+        assert_eq!(route("app/dashboard/chart.tsx"), None);
+        assert_eq!(route("pages/page.tsx"), None);
+        assert_eq!(route("app/page.css"), None);
+    }
+
+    #[test]
+    fn test_app_route_nesting() {
+        let files = [
+            "app/layout.tsx",
+            "app/page.tsx",
+            "app/dashboard/layout.tsx",
+            "app/dashboard/settings/page.tsx",
+            "app/dashboard/loading.tsx",
+            "app/(marketing)/about/page.tsx",
+            "app/api/posts/route.ts",
+        ];
+        assert_eq!(
+            app_route_nesting(&files),
+            vec![(0, 1), (0, 2), (2, 3), (2, 4), (0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_extract_store_name() {
+        assert_eq!(
+            extract_store_name("createSlice({ name: 'auth', initialState })"),
+            Some("auth".to_string())
+        );
+        assert_eq!(extract_store_name("configureStore({ reducer })"), None);
+    }
+
+    #[test]
+    fn test_parametrized_names() {
+        assert_eq!(
+            parametrized_names("\"user_id, expected\", [(1, True)])"),
+            ["user_id", "expected"]
+        );
+        assert_eq!(parametrized_names("('a', 'b'), [('x', 'y')])"), ["a", "b"]);
+        assert_eq!(parametrized_names("[\"n\"], [[1], [2]])"), ["n"]);
+        assert!(parametrized_names("names, values)").is_empty());
+    }
 }
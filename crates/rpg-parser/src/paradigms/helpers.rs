@@ -111,12 +111,14 @@ pub fn has_dep(manifest: &str, dep_name: &str) -> bool {
     // Gemfile / Ruby (single-quoted)
     || manifest.contains(&format!("'{}'", dep_name))
     // go.mod / requirements.txt (unquoted, whitespace-bounded token)
-    // Also handles version specifiers: flask==3.0.0, django~=4.2, etc.
+    // Also handles version specifiers and extras: flask==3.0.0, django~=4.2,
+    // fastapi[standard], and PEP 621 arrays: dependencies = ["fastapi>=0.110"]
     || manifest.lines().any(|line| {
         line.split_whitespace().any(|token| {
             token == dep_name
                 || token
-                    .split(&['=', '>', '<', '~', '!', ';', '@'][..])
+                    .trim_start_matches(['[', '"', '\''])
+                    .split(&['=', '>', '<', '~', '!', ';', '@', '[', '"', '\'', ','][..])
                     .next()
                     .is_some_and(|prefix| prefix == dep_name)
         })
@@ -232,6 +234,16 @@ mod tests {
         assert!(has_dep(req, "requests"));
         assert!(has_dep(req, "django"));
         assert!(!has_dep(req, "fastapi"));
+        assert!(has_dep("fastapi[standard]>=0.110", "fastapi"));
+    }
+
+    #[test]
+    fn test_has_dep_pep621_array() {
+        let pyproject = "[project]\ndependencies = [\"fastapi>=0.110\", \"uvicorn[standard]\"]";
+        assert!(has_dep(pyproject, "fastapi"));
+        assert!(has_dep(pyproject, "uvicorn"));
+        assert!(!has_dep(pyproject, "project"));
+        assert!(!has_dep(pyproject, "flask"));
     }
 
     #[test]
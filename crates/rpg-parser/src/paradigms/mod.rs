@@ -11,6 +11,7 @@
 
 pub mod classify;
 pub mod defs;
+pub mod extractors;
 pub mod features;
 pub mod helpers;
 pub mod query_engine;
@@ -27,7 +28,8 @@ use std::path::Path;
 ///
 /// A paradigm is active if:
 /// 1. At least one of its declared languages is present in the project
-/// 2. Its detection rules match (deps in manifest, config files exist, etc.)
+/// 2. Its detection rules match (deps in manifest, config files exist, etc.),
+///    or it sets `detect.always`
 pub fn detect_paradigms_toml<'a>(
     root: &Path,
     languages: &[Language],
@@ -48,6 +50,10 @@ pub fn detect_paradigms_toml<'a>(
 
 /// Check if detection rules from a TOML paradigm definition match.
 fn matches_detect_rules(root: &Path, manifest: &str, rules: &defs::DetectRules) -> bool {
+    if rules.always {
+        return true;
+    }
+
     // deps: any listed dependency found in manifest
     if !rules.deps.is_empty() && rules.deps.iter().any(|d| helpers::has_dep(manifest, d)) {
         return true;
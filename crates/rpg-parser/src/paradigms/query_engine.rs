@@ -2,13 +2,19 @@
 //!
 //! Compiles tree-sitter queries from TOML definitions at startup and executes them
 //! against source files to extract additional entities and dependencies.
+//!
+//! Queries may filter captures by their text with `#eq?`, `#match?` (a Rust
+//! regex), and `#any-of?`, or their `#not-` forms, e.g.
+//! `(call_expression function: (identifier) @callee (#match? @callee "^use[A-Z]"))`.
+//! A match is kept only when all of its predicates hold. Other predicates
+//! fail compilation rather than being ignored.
 
 use super::defs::{ParadigmDef, parse_edge_kind, parse_entity_kind};
-use crate::deps::{CallDep, FunctionScope, RawDeps, find_enclosing_scope};
+use crate::deps::{CallDep, ComposeDep, FunctionScope, RawDeps, find_enclosing_scope};
 use crate::entities::RawEntity;
 use crate::languages::Language;
-use rpg_core::graph::EdgeKind;
-use std::collections::HashMap;
+use rpg_core::graph::{EdgeKind, LIFTABLE_ATTRIBUTE};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use tree_sitter::StreamingIterator;
 
@@ -92,11 +98,37 @@ impl QueryCache {
     }
 }
 
+/// Predicates tree-sitter evaluates on capture text while matching.
+const TEXT_PREDICATES: &[&str] = &[
+    "#eq?",
+    "#not-eq?",
+    "#match?",
+    "#not-match?",
+    "#any-of?",
+    "#not-any-of?",
+];
+
 /// Compile a tree-sitter query string for a given language.
-fn compile_query(lang_name: &str, query_str: &str) -> Result<tree_sitter::Query, String> {
+///
+/// Any other predicate is rejected: tree-sitter would skip it and the
+/// pattern would fire on every node it otherwise matches.
+pub(crate) fn compile_query(
+    lang_name: &str,
+    query_str: &str,
+) -> Result<tree_sitter::Query, String> {
     let ts_lang =
         ts_language_for(lang_name).ok_or_else(|| format!("unknown language '{lang_name}'"))?;
-    tree_sitter::Query::new(&ts_lang, query_str).map_err(|e| e.to_string())
+    let query = tree_sitter::Query::new(&ts_lang, query_str).map_err(|e| e.to_string())?;
+    for pattern in 0..query.pattern_count() {
+        if let Some(predicate) = query.general_predicates(pattern).first() {
+            return Err(format!(
+                "unsupported predicate '#{}' (supported: {})",
+                predicate.operator,
+                TEXT_PREDICATES.join(", ")
+            ));
+        }
+    }
+    Ok(query)
 }
 
 /// Expand language lists with grammar aliases (e.g., "typescript" implies "tsx").
@@ -122,6 +154,47 @@ fn effective_lang_name(lang: Language, file: &Path) -> &'static str {
     crate::languages::effective_grammar_name(lang.name(), ext)
 }
 
+/// A file's syntax trees, parsed on first use per grammar. A query compiled
+/// only for the base language must run on a tree from the base grammar: node
+/// kinds differ between grammars, so `typescript` queries (compiled against
+/// the TSX grammar) never match a `.ts` file's `typescript_strict` tree.
+struct FileTrees<'a> {
+    source: &'a str,
+    lang_name: &'static str,
+    eff_lang: &'static str,
+    trees: HashMap<&'static str, Option<tree_sitter::Tree>>,
+}
+
+impl<'a> FileTrees<'a> {
+    fn new(source: &'a str, language: Language, file: &Path) -> Self {
+        Self {
+            source,
+            lang_name: language_to_name(language),
+            eff_lang: effective_lang_name(language, file),
+            trees: HashMap::new(),
+        }
+    }
+
+    /// The query `id`, preferring the one compiled for the effective
+    /// grammar, with a tree from the grammar it was compiled for.
+    fn query_and_tree<'q>(
+        &mut self,
+        qcache: &'q QueryCache,
+        id: &str,
+    ) -> Option<(&'q tree_sitter::Query, &tree_sitter::Tree)> {
+        let (grammar, query) = match qcache.get(self.eff_lang, id) {
+            Some(query) => (self.eff_lang, query),
+            None => (self.lang_name, qcache.get(self.lang_name, id)?),
+        };
+        let source = self.source;
+        let tree = self.trees.entry(grammar).or_insert_with(|| {
+            ts_language_for(grammar)
+                .and_then(|ts_lang| crate::treesitter::parse_source(source, &ts_lang))
+        });
+        Some((query, tree.as_ref()?))
+    }
+}
+
 /// Execute entity queries from paradigm defs and return additional entities.
 pub fn execute_entity_queries(
     qcache: &QueryCache,
@@ -134,18 +207,7 @@ pub fn execute_entity_queries(
     let lang_name = language_to_name(language);
     let eff_lang = effective_lang_name(language, file);
     let mut additional = Vec::new();
-
-    // Parse the source once
-    let Some(ts_lang) = ts_language_for(eff_lang) else {
-        return additional;
-    };
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return additional;
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
-        return additional;
-    };
+    let mut trees = FileTrees::new(source, language, file);
 
     for def in active_defs {
         for eq in &def.entity_queries {
@@ -159,11 +221,7 @@ pub fn execute_entity_queries(
                 continue;
             }
 
-            // Prefer effective language (matches the grammar used to parse the tree)
-            let query = qcache
-                .get(eff_lang, &eq.id)
-                .or_else(|| qcache.get(lang_name, &eq.id));
-            let Some(query) = query else {
+            let Some((query, tree)) = trees.query_and_tree(qcache, &eq.id) else {
                 continue;
             };
 
@@ -177,6 +235,11 @@ pub fn execute_entity_queries(
                 continue;
             };
 
+            let body_idx = eq
+                .entity_body
+                .as_deref()
+                .and_then(|b| query.capture_index_for_name(b.trim_start_matches('@')));
+
             // If parent starts with '@', resolve it from a query capture
             let parent_capture_idx = eq.parent.as_deref().and_then(|p| {
                 let cap_name = p.trim_start_matches('@');
@@ -193,10 +256,14 @@ pub fn execute_entity_queries(
                 let name_node = m.captures.iter().find(|c| c.index == name_idx);
                 if let Some(cap) = name_node {
                     let name = source[cap.node.byte_range()].to_string();
-                    let src_range = cap.node.parent().map_or_else(
+                    let body = body_idx
+                        .and_then(|idx| m.captures.iter().find(|c| c.index == idx))
+                        .map(|c| c.node);
+                    let src_range = body.or_else(|| cap.node.parent()).map_or_else(
                         || cap.node.byte_range(),
                         |p: tree_sitter::Node<'_>| p.byte_range(),
                     );
+                    let span = body.unwrap_or(cap.node);
 
                     // Resolve parent: from capture, literal string, or None
                     let parent_class = if let Some(pidx) = parent_capture_idx {
@@ -212,11 +279,21 @@ pub fn execute_entity_queries(
                         name,
                         kind: entity_kind,
                         file: file.to_path_buf(),
-                        line_start: cap.node.start_position().row + 1,
-                        line_end: cap.node.end_position().row + 1,
+                        line_start: span.start_position().row + 1,
+                        line_end: span.end_position().row + 1,
                         parent_class,
                         source_text: source[src_range].to_string(),
                         signature: None,
+                        attributes: if eq.liftable {
+                            BTreeMap::new()
+                        } else {
+                            BTreeMap::from([(LIFTABLE_ATTRIBUTE.to_string(), "false".to_string())])
+                        },
+                        doc_comment: None,
+                        decorators: Vec::new(),
+                        is_async: false,
+                        is_generator: false,
+                        is_unsafe: false,
                     });
                 }
             }
@@ -227,6 +304,8 @@ pub fn execute_entity_queries(
 }
 
 /// Execute dep queries from paradigm defs and append to raw_deps.
+///
+/// `composes` queries record the caller as composing the callee type.
 pub fn execute_dep_queries(
     qcache: &QueryCache,
     active_defs: &[&ParadigmDef],
@@ -238,18 +317,7 @@ pub fn execute_dep_queries(
 ) {
     let lang_name = language_to_name(language);
     let eff_lang = effective_lang_name(language, file);
-
-    // Parse the source once
-    let Some(ts_lang) = ts_language_for(eff_lang) else {
-        return;
-    };
-    let mut parser = tree_sitter::Parser::new();
-    if parser.set_language(&ts_lang).is_err() {
-        return;
-    }
-    let Some(tree) = parser.parse(source.as_bytes(), None) else {
-        return;
-    };
+    let mut trees = FileTrees::new(source, language, file);
 
     for def in active_defs {
         for dq in &def.dep_queries {
@@ -262,11 +330,7 @@ pub fn execute_dep_queries(
                 continue;
             }
 
-            // Prefer effective language (matches the grammar used to parse the tree)
-            let query = qcache
-                .get(eff_lang, &dq.id)
-                .or_else(|| qcache.get(lang_name, &dq.id));
-            let Some(query) = query else {
+            let Some((query, tree)) = trees.query_and_tree(qcache, &dq.id) else {
                 continue;
             };
 
@@ -312,6 +376,13 @@ pub fn execute_dep_queries(
                         }
                     };
 
+                    if edge_kind == EdgeKind::Composes {
+                        raw_deps.composes.push(ComposeDep {
+                            source_entity: caller,
+                            target_name: callee,
+                        });
+                        continue;
+                    }
                     let dep = CallDep {
                         caller_entity: caller,
                         callee,
@@ -354,6 +425,128 @@ mod tests {
         );
     }
 
+    /// A def with one React-style dep query, tagging calls as Invokes.
+    fn hook_def(predicate: &str) -> ParadigmDef {
+        toml::from_str(&format!(
+            r#"
+schema_version = 1
+name = "hooks"
+priority = 50
+languages = ["javascript"]
+[detect]
+[[dep_queries]]
+id = "test.hook_calls"
+edge_kind = "invokes"
+caller = "enclosing_scope"
+callee = "@callee"
+query = '(call_expression function: (identifier) @callee {predicate})'
+"#
+        ))
+        .unwrap()
+    }
+
+    fn hook_calls(predicate: &str) -> Vec<String> {
+        let source = "function App() {\n  useEffect(load);\n  const [a] = useState(0);\n  fetchData();\n  user();\n}\n";
+        let defs = vec![hook_def(predicate)];
+        let qcache = QueryCache::compile_all(&defs).unwrap();
+        let active: Vec<&ParadigmDef> = defs.iter().collect();
+        let mut deps = RawDeps::default();
+        execute_dep_queries(
+            &qcache,
+            &active,
+            Path::new("app.js"),
+            source,
+            Language::JAVASCRIPT,
+            &[],
+            &mut deps,
+        );
+        deps.calls.into_iter().map(|c| c.callee).collect()
+    }
+
+    #[test]
+    fn test_match_predicate_limits_to_hook_calls() {
+        assert_eq!(
+            hook_calls(r#"(#match? @callee "^use[A-Z]\\w+$")"#),
+            vec!["useEffect", "useState"]
+        );
+        assert_eq!(
+            hook_calls(r#"(#not-match? @callee "^use[A-Z]")"#),
+            vec!["fetchData", "user"]
+        );
+    }
+
+    #[test]
+    fn test_eq_and_any_of_predicates() {
+        assert_eq!(
+            hook_calls(r#"(#eq? @callee "useEffect")"#),
+            vec!["useEffect"]
+        );
+        assert_eq!(
+            hook_calls(r#"(#any-of? @callee "useState" "fetchData")"#),
+            vec!["useState", "fetchData"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_predicate_is_rejected() {
+        let defs = vec![hook_def(r#"(#matches? @callee "^use")"#)];
+        let errors = QueryCache::compile_all(&defs).err().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].contains("unsupported predicate '#matches?'"),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn test_typescript_query_matches_ts_and_tsx_files() {
+        let def: ParadigmDef = toml::from_str(
+            r#"
+schema_version = 1
+name = "classes"
+priority = 50
+languages = ["typescript"]
+[detect]
+[[dep_queries]]
+id = "test.extends"
+edge_kind = "composes"
+caller = "@caller"
+callee = "@base"
+query = '(class_declaration name: (type_identifier) @caller (class_heritage (extends_clause value: (identifier) @base)))'
+"#,
+        )
+        .unwrap();
+        let defs = vec![def];
+        let qcache = QueryCache::compile_all(&defs).unwrap();
+        let active: Vec<&ParadigmDef> = defs.iter().collect();
+        // `.ts` files parse with `typescript_strict`, which has no compiled
+        // query of its own.
+        for file in ["a.ts", "a.tsx"] {
+            let mut deps = RawDeps::default();
+            execute_dep_queries(
+                &qcache,
+                &active,
+                Path::new(file),
+                "class Admin extends User {}\n",
+                Language::TYPESCRIPT,
+                &[],
+                &mut deps,
+            );
+            let composes: Vec<(String, String)> = deps
+                .composes
+                .into_iter()
+                .map(|c| (c.source_entity, c.target_name))
+                .collect();
+            assert_eq!(
+                composes,
+                vec![("Admin".to_string(), "User".to_string())],
+                "{}",
+                file
+            );
+        }
+    }
+
     #[test]
     fn test_ts_language_for() {
         assert!(ts_language_for("typescript").is_some());
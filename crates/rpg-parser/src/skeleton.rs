@@ -0,0 +1,110 @@
+//! Skeleton projection: source with function bodies elided.
+//!
+//! Signatures, class/struct scaffolding, field lists, and doc comments are
+//! kept verbatim; each function, method, constructor, or closure body is
+//! replaced by `{ … }` (`...` in Python, keeping its docstring). Bodies are
+//! located from the tree-sitter tree, so an elision always covers a whole
+//! syntax node and never cuts mid-expression.
+
+use crate::languages::Language;
+use std::path::Path;
+
+/// Node kinds whose `body` field is elided.
+const BODY_OWNER_MARKERS: &[&str] = &["function", "method", "constructor", "lambda", "closure"];
+
+/// Skeleton of the whole file, or `None` if it fails to parse.
+pub fn skeleton(source: &str, language: Language) -> Option<String> {
+    skeleton_lines(source, language, 1, source.lines().count().max(1))
+}
+
+/// Skeleton of the 1-based inclusive line range `line_start..=line_end` (an
+/// entity's span). Bodies of functions nested inside the range are elided
+/// with their enclosing body.
+pub fn skeleton_lines(
+    source: &str,
+    language: Language,
+    line_start: usize,
+    line_end: usize,
+) -> Option<String> {
+    let tree = crate::treesitter::parse_file(
+        Path::new(language.name()),
+        source.as_bytes(),
+        &language.ts_language(),
+    )
+    .ok()?;
+
+    let (start, end) = line_byte_range(source, line_start, line_end)?;
+    let mut elisions = Vec::new();
+    collect_elisions(tree.root_node(), source, language, &mut elisions);
+    elisions.sort_by_key(|(s, e, _)| (*s, std::cmp::Reverse(*e)));
+
+    let mut out = String::new();
+    let mut cursor = start;
+    for (elided_start, elided_end, replacement) in elisions {
+        // Outer bodies sort first, so nested ones fall behind the cursor.
+        if elided_start < cursor || elided_end > end {
+            continue;
+        }
+        out.push_str(&source[cursor..elided_start]);
+        out.push_str(&replacement);
+        cursor = elided_end;
+    }
+    out.push_str(&source[cursor..end]);
+    Some(out)
+}
+
+/// Byte range covering the given 1-based lines, without the trailing newline.
+fn line_byte_range(source: &str, line_start: usize, line_end: usize) -> Option<(usize, usize)> {
+    let mut offsets = vec![0];
+    offsets.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+    let start = *offsets.get(line_start.max(1) - 1)?;
+    let end = offsets
+        .get(line_end)
+        .map_or(source.len(), |next| next - 1)
+        .max(start);
+    Some((start, end))
+}
+
+fn collect_elisions(
+    node: tree_sitter::Node,
+    source: &str,
+    language: Language,
+    out: &mut Vec<(usize, usize, String)>,
+) {
+    let kind = node.kind();
+    if BODY_OWNER_MARKERS.iter().any(|m| kind.contains(m))
+        && let Some(body) = node.child_by_field_name("body")
+    {
+        out.push((
+            body.start_byte(),
+            body.end_byte(),
+            elided_body(body, source, language),
+        ));
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_elisions(child, source, language, out);
+    }
+}
+
+fn elided_body(body: tree_sitter::Node, source: &str, language: Language) -> String {
+    if source[body.byte_range()].starts_with('{') {
+        return "{ … }".to_string();
+    }
+    if language != Language::PYTHON {
+        return "…".to_string();
+    }
+    // Python blocks start at their first statement; keep a leading docstring.
+    let docstring = body
+        .named_child(0)
+        .filter(|stmt| stmt.kind() == "expression_statement")
+        .and_then(|stmt| stmt.named_child(0))
+        .filter(|expr| expr.kind() == "string");
+    match docstring {
+        Some(doc) => {
+            let indent = " ".repeat(body.start_position().column);
+            format!("{}\n{}...", &source[doc.byte_range()], indent)
+        }
+        None => "...".to_string(),
+    }
+}
@@ -0,0 +1,129 @@
+//! Test function detection: entities the language's test runner would
+//! collect become [`EntityKind::Test`].
+//!
+//! - Rust: functions carrying `#[test]`, `#[<runtime>::test]`
+//!   (`#[tokio::test]`), or `#[rstest]`.
+//! - Python (pytest): in `test_*.py` / `*_test.py` files, `test*` functions
+//!   and `test*` methods of `Test*` classes; anywhere, `test*` methods of
+//!   `unittest.TestCase` subclasses.
+//! - Go: `Test*`, `Benchmark*`, `Fuzz*`, and `Example*` functions in
+//!   `*_test.go` files.
+//! - JS/TS: top-level `describe`/`it`/`test` blocks are synthesized as Test
+//!   entities during extraction ([`js_test_block_title`]).
+
+use crate::entities::RawEntity;
+use crate::languages::Language;
+use rpg_core::graph::EntityKind;
+use std::path::Path;
+
+/// JS/TS test-runner globals whose top-level calls are test blocks.
+const JS_TEST_BLOCKS: &[&str] = &["describe", "it", "test"];
+
+/// Go test function prefixes (`go test` collects `TestXxx`, not `Testxxx`).
+const GO_TEST_PREFIXES: &[&str] = &["Test", "Benchmark", "Fuzz", "Example"];
+
+/// Set `kind` to [`EntityKind::Test`] on the test functions in `entities`.
+pub fn mark_test_entities(
+    path: &Path,
+    entities: &mut [RawEntity],
+    source: &str,
+    language: Language,
+) {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    if language == Language::RUST {
+        let lines: Vec<&str> = source.lines().collect();
+        for entity in entities.iter_mut() {
+            if is_function(entity) && has_rust_test_attribute(&lines, entity.line_start) {
+                entity.kind = EntityKind::Test;
+            }
+        }
+    } else if language == Language::PYTHON {
+        let pytest_file = file_name.starts_with("test_") || file_name.ends_with("_test.py");
+        let test_case_classes: Vec<String> = entities
+            .iter()
+            .filter(|e| e.kind == EntityKind::Class)
+            .filter(|e| {
+                let header = e.source_text.lines().find(|l| l.contains("class "));
+                header.is_some_and(|h| h.contains("TestCase"))
+            })
+            .map(|e| e.name.clone())
+            .collect();
+        for entity in entities.iter_mut() {
+            if !is_function(entity) || !entity.name.starts_with("test") {
+                continue;
+            }
+            let collected = match &entity.parent_class {
+                None => pytest_file,
+                Some(class) => {
+                    (pytest_file && class.starts_with("Test")) || test_case_classes.contains(class)
+                }
+            };
+            if collected {
+                entity.kind = EntityKind::Test;
+            }
+        }
+    } else if language == Language::GO && file_name.ends_with("_test.go") {
+        for entity in entities.iter_mut() {
+            if entity.kind == EntityKind::Function && is_go_test_name(&entity.name) {
+                entity.kind = EntityKind::Test;
+            }
+        }
+    }
+}
+
+fn is_function(entity: &RawEntity) -> bool {
+    matches!(entity.kind, EntityKind::Function | EntityKind::Method)
+}
+
+/// Whether the attribute lines right above 1-based `line_start` include a
+/// test attribute.
+fn has_rust_test_attribute(lines: &[&str], line_start: usize) -> bool {
+    lines[..line_start.saturating_sub(1).min(lines.len())]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("#[") || line.starts_with("//"))
+        .filter_map(|line| line.strip_prefix("#["))
+        .any(|attr| {
+            let path = attr.split(['(', ']']).next().unwrap_or_default().trim();
+            path == "test" || path.ends_with("::test") || path == "rstest"
+        })
+}
+
+/// `TestParse`, `Test_parse`, `Test`; not `Testify`.
+fn is_go_test_name(name: &str) -> bool {
+    GO_TEST_PREFIXES.iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase()))
+    })
+}
+
+/// The title of a top-level JS/TS test block statement
+/// (`describe('Button', () => ...)`, `it.only("renders", ...)`).
+pub fn js_test_block_title(stmt: &tree_sitter::Node, source: &str) -> Option<String> {
+    if stmt.kind() != "expression_statement" {
+        return None;
+    }
+    let call = stmt
+        .named_child(0)
+        .filter(|c| c.kind() == "call_expression")?;
+    let function = call.child_by_field_name("function")?;
+    let runner = match function.kind() {
+        "identifier" => function,
+        // describe.only / it.skip / test.concurrent
+        "member_expression" => function.child_by_field_name("object")?,
+        _ => return None,
+    };
+    if !JS_TEST_BLOCKS.contains(&&source[runner.byte_range()]) {
+        return None;
+    }
+    let title = call.child_by_field_name("arguments")?.named_child(0)?;
+    if !matches!(title.kind(), "string" | "template_string") {
+        return None;
+    }
+    let title = source[title.byte_range()].trim_matches(['\'', '"', '`']);
+    (!title.is_empty()).then(|| title.to_string())
+}
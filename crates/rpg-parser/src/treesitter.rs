@@ -1,19 +1,225 @@
 //! Tree-sitter integration for multi-language AST parsing.
+//!
+//! Parsers are pooled per thread and grammar, and each thread keeps the most
+//! recent tree per grammar. Entity extraction, dependency extraction,
+//! paradigm queries, and diagnostics all parse through here, so running them
+//! back to back on one file parses it once.
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::path::Path;
+use tree_sitter::{InputEdit, Language, Parser, Point, Tree};
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<Language, Parser>> = RefCell::new(HashMap::new());
+    static LAST_TREES: RefCell<HashMap<Language, (Vec<u8>, Tree)>> =
+        RefCell::new(HashMap::new());
+    static PARSE_COUNT: Cell<usize> = const { Cell::new(0) };
+}
 
 /// Parse a source file and return the tree-sitter tree.
-pub fn parse_file(
-    path: &Path,
-    source: &[u8],
-    language: &tree_sitter::Language,
-) -> Result<tree_sitter::Tree> {
-    let mut parser = tree_sitter::Parser::new();
-    parser
-        .set_language(language)
-        .context("failed to set tree-sitter language")?;
-    parser
-        .parse(source, None)
+pub fn parse_file(path: &Path, source: &[u8], language: &Language) -> Result<Tree> {
+    parse_bytes(source, language)
         .ok_or_else(|| anyhow::anyhow!("failed to parse {}", path.display()))
 }
+
+/// Parse `source`, reusing this thread's tree when it was the last source
+/// parsed with `language`.
+pub fn parse_source(source: &str, language: &Language) -> Option<Tree> {
+    parse_bytes(source.as_bytes(), language)
+}
+
+/// Parse `source` as an edit of this thread's last tree for `language`, when
+/// `is_previous` accepts that tree's text as the prior version of `source`:
+/// the tree is edited and handed to tree-sitter as the old tree, so only the
+/// changed region is reparsed. Returns `None` without parsing when there is
+/// no such tree.
+pub fn parse_edited(
+    source: &str,
+    language: &Language,
+    is_previous: impl FnOnce(&str) -> bool,
+) -> Option<Tree> {
+    if let Some(tree) = cached(source.as_bytes(), language) {
+        return Some(tree);
+    }
+    let old_tree = LAST_TREES.with(|trees| {
+        let trees = trees.borrow();
+        let (text, tree) = trees.get(language)?;
+        let previous = std::str::from_utf8(text).ok()?;
+        if !is_previous(previous) {
+            return None;
+        }
+        let mut tree = tree.clone();
+        tree.edit(&input_edit(previous, source));
+        Some(tree)
+    })?;
+    let tree = run_parser(source.as_bytes(), language, Some(&old_tree))?;
+    remember(source.as_bytes(), language, &tree);
+    Some(tree)
+}
+
+/// Number of parses (full or incremental) this thread has run. Cache hits
+/// don't count.
+pub fn parse_count() -> usize {
+    PARSE_COUNT.with(Cell::get)
+}
+
+/// Drop this thread's cached trees.
+pub fn clear_cache() {
+    LAST_TREES.with(|trees| trees.borrow_mut().clear());
+}
+
+fn parse_bytes(source: &[u8], language: &Language) -> Option<Tree> {
+    if let Some(tree) = cached(source, language) {
+        return Some(tree);
+    }
+    let tree = run_parser(source, language, None)?;
+    remember(source, language, &tree);
+    Some(tree)
+}
+
+fn cached(source: &[u8], language: &Language) -> Option<Tree> {
+    LAST_TREES.with(|trees| {
+        trees
+            .borrow()
+            .get(language)
+            .filter(|(text, _)| text.as_slice() == source)
+            .map(|(_, tree)| tree.clone())
+    })
+}
+
+fn remember(source: &[u8], language: &Language, tree: &Tree) {
+    LAST_TREES.with(|trees| {
+        trees
+            .borrow_mut()
+            .insert(language.clone(), (source.to_vec(), tree.clone()));
+    });
+}
+
+fn run_parser(source: &[u8], language: &Language, old_tree: Option<&Tree>) -> Option<Tree> {
+    PARSERS.with(|parsers| {
+        let mut parsers = parsers.borrow_mut();
+        let parser = match parsers.entry(language.clone()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let mut parser = Parser::new();
+                parser.set_language(language).ok()?;
+                entry.insert(parser)
+            }
+        };
+        PARSE_COUNT.with(|count| count.set(count.get() + 1));
+        parser.parse(source, old_tree)
+    })
+}
+
+/// The single edit turning `previous` into `source`: everything between
+/// their common prefix and common suffix.
+fn input_edit(previous: &str, source: &str) -> InputEdit {
+    let mut prefix = previous
+        .bytes()
+        .zip(source.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !source.is_char_boundary(prefix) || !previous.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+    let max_suffix = previous.len().min(source.len()) - prefix;
+    let mut suffix = previous
+        .bytes()
+        .rev()
+        .zip(source.bytes().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !source.is_char_boundary(source.len() - suffix)
+        || !previous.is_char_boundary(previous.len() - suffix)
+    {
+        suffix -= 1;
+    }
+    let old_end_byte = previous.len() - suffix;
+    let new_end_byte = source.len() - suffix;
+    InputEdit {
+        start_byte: prefix,
+        old_end_byte,
+        new_end_byte,
+        start_position: point_at(source, prefix),
+        old_end_position: point_at(previous, old_end_byte),
+        new_end_position: point_at(source, new_end_byte),
+    }
+}
+
+/// Row and byte column of `byte` in `text`.
+fn point_at(text: &str, byte: usize) -> Point {
+    let before = &text[..byte];
+    let row = before.matches('\n').count();
+    let column = before
+        .rfind('\n')
+        .map_or(byte, |newline| byte - newline - 1);
+    Point { row, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn python() -> Language {
+        tree_sitter_python::LANGUAGE.into()
+    }
+
+    #[test]
+    fn test_reparsing_same_source_hits_cache() {
+        let source = "def cached_twice():\n    return 1\n";
+        let before = parse_count();
+        let first = parse_source(source, &python()).unwrap();
+        let second = parse_source(source, &python()).unwrap();
+        assert_eq!(parse_count() - before, 1);
+        assert_eq!(first.root_node().to_sexp(), second.root_node().to_sexp());
+    }
+
+    #[test]
+    fn test_entity_and_dep_extraction_share_one_parse() {
+        let source = "import os\n\ndef shared(path):\n    return os.path.exists(path)\n";
+        let path = Path::new("shared.py");
+        let before = parse_count();
+        let entities =
+            crate::entities::extract_entities(path, source, crate::languages::Language::PYTHON);
+        let deps = crate::deps::extract_deps(path, source, crate::languages::Language::PYTHON);
+        assert_eq!(parse_count() - before, 1);
+        assert!(entities.iter().any(|e| e.name == "shared"));
+        assert_eq!(deps.imports.len(), 1);
+    }
+
+    #[test]
+    fn test_incremental_parse_matches_fresh_parse() {
+        let previous = "def a():\n    return 1\n\ndef b():\n    return 2\n";
+        let source = "def a():\n    return 1\n\ndef renamed(x):\n    return 2\n";
+        parse_source(previous, &python()).unwrap();
+        let edited = parse_edited(source, &python(), |text| text == previous).unwrap();
+        clear_cache();
+        let fresh = parse_source(source, &python()).unwrap();
+        assert_eq!(edited.root_node().to_sexp(), fresh.root_node().to_sexp());
+    }
+
+    #[test]
+    fn test_incremental_parse_needs_the_previous_tree() {
+        let previous = "def a():\n    return 1\n";
+        let source = "def a():\n    return 2\n";
+        clear_cache();
+        let before = parse_count();
+        assert!(parse_edited(source, &python(), |_| true).is_none());
+        parse_source("def unrelated():\n    pass\n", &python()).unwrap();
+        assert!(parse_edited(source, &python(), |text| text == previous).is_none());
+        assert_eq!(parse_count() - before, 1);
+    }
+
+    #[test]
+    fn test_input_edit_spans_changed_region() {
+        let edit = input_edit("ab\ncd\nef", "ab\ncXd\nef");
+        assert_eq!(edit.start_byte, 4);
+        assert_eq!(edit.old_end_byte, 4);
+        assert_eq!(edit.new_end_byte, 5);
+        assert_eq!(edit.start_position, Point { row: 1, column: 1 });
+        assert_eq!(edit.new_end_position, Point { row: 1, column: 2 });
+    }
+}
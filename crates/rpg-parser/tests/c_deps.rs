@@ -36,3 +36,17 @@ int main() {
             .any(|c| c.callee == "printf" && c.caller_entity == "main")
     );
 }
+
+#[test]
+fn test_c_config_reads() {
+    let source = r#"#include <stdlib.h>
+
+const char *home(void) {
+    return getenv("HOME");
+}
+"#;
+    let deps = extract_deps(Path::new("home.c"), source, Language::C);
+    assert_eq!(deps.config_reads.len(), 1);
+    assert_eq!(deps.config_reads[0].key, "HOME");
+    assert_eq!(deps.config_reads[0].line, 4);
+}
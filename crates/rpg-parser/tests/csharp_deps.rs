@@ -53,3 +53,17 @@ fn csharp_static_using() {
     assert_eq!(deps.imports.len(), 1);
     assert_eq!(deps.imports[0].module, "System.Math");
 }
+
+#[test]
+fn csharp_config_reads() {
+    let source = r#"class Settings {
+    string Region() {
+        return Environment.GetEnvironmentVariable("AWS_REGION");
+    }
+}
+"#;
+    let deps = extract_deps(Path::new("Settings.cs"), source, Language::CSHARP);
+    assert_eq!(deps.config_reads.len(), 1);
+    assert_eq!(deps.config_reads[0].key, "AWS_REGION");
+    assert_eq!(deps.config_reads[0].line, 3);
+}
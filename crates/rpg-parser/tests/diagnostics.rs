@@ -0,0 +1,71 @@
+use rpg_parser::diagnostics::{format_report, format_summary, syntax_error_ranges};
+use rpg_parser::languages::Language;
+use std::path::{Path, PathBuf};
+
+fn fixture_files() -> Vec<(PathBuf, String)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../tests/fixtures/broken_syntax");
+    ["ok.py", "broken.py"]
+        .iter()
+        .map(|name| {
+            (
+                PathBuf::from(name),
+                std::fs::read_to_string(root.join(name)).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_broken_fixture_is_diagnosed_without_failing() {
+    let qcache = rpg_parser::paradigms::query_engine::QueryCache::compile_all(&[]).unwrap();
+    let (entities, diagnostics) =
+        rpg_parser::parse_files_with_diagnostics(fixture_files(), &[], &qcache);
+
+    // Both files still produce entities; only the broken one is diagnosed.
+    assert!(entities.iter().any(|e| e.name == "greet"));
+    assert!(entities.iter().any(|e| e.name == "load"));
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.file, Path::new("broken.py"));
+    assert!(!diagnostic.error_ranges.is_empty());
+    assert!(
+        diagnostic
+            .error_ranges
+            .iter()
+            .all(|&(start, end)| start >= 9 && start <= end),
+        "{:?}",
+        diagnostic.error_ranges
+    );
+    assert_eq!(
+        diagnostic.entity_count,
+        entities
+            .iter()
+            .filter(|e| e.file == Path::new("broken.py"))
+            .count()
+    );
+
+    let report = format_report(&diagnostics).unwrap();
+    assert!(
+        report.starts_with("1 file had syntax errors ("),
+        "{}",
+        report
+    );
+    assert!(report.contains("\n  broken.py: line"), "{}", report);
+}
+
+#[test]
+fn test_clean_sources_have_no_errors() {
+    let source = "def ok():\n    return 1\n";
+    assert!(syntax_error_ranges(Path::new("ok.py"), source, Language::PYTHON).is_empty());
+    let source = "export function ok(): number { return 1; }\n";
+    assert!(syntax_error_ranges(Path::new("ok.ts"), source, Language::TYPESCRIPT).is_empty());
+    assert_eq!(format_summary(&[]), None);
+}
+
+#[test]
+fn test_missing_token_is_reported() {
+    // tree-sitter inserts a MISSING `}` rather than an ERROR node.
+    let source = "fn main() {\n    let x = 1;\n";
+    let ranges = syntax_error_ranges(Path::new("main.rs"), source, Language::RUST);
+    assert_eq!(ranges.len(), 1, "{:?}", ranges);
+}
@@ -0,0 +1,143 @@
+use rpg_parser::docs::MAX_DOC_LINES;
+use rpg_parser::entities::{RawEntity, extract_entities};
+use rpg_parser::languages::Language;
+use std::path::Path;
+
+fn doc_of(entities: &[RawEntity], name: &str) -> Option<String> {
+    entities
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no entity {}", name))
+        .doc_comment
+        .clone()
+}
+
+#[test]
+fn test_python_docstrings() {
+    let source = r#"def load(path):
+    """Load the config file.
+
+    Returns defaults when the file is missing.
+    """
+    return read(path)
+
+
+class Cache:
+    '''LRU cache.'''
+
+    @staticmethod
+    def key_for(*parts):
+        """Join parts into a key."""
+        return ":".join(parts)
+
+    def clear(self):
+        self.items = {}
+"#;
+    let entities = extract_entities(Path::new("config.py"), source, Language::PYTHON);
+    assert_eq!(
+        doc_of(&entities, "load").as_deref(),
+        Some("Load the config file.\n\nReturns defaults when the file is missing.")
+    );
+    assert_eq!(doc_of(&entities, "Cache").as_deref(), Some("LRU cache."));
+    assert_eq!(
+        doc_of(&entities, "key_for").as_deref(),
+        Some("Join parts into a key.")
+    );
+    assert_eq!(doc_of(&entities, "clear"), None);
+}
+
+#[test]
+fn test_rust_doc_comments_skip_attributes() {
+    let source = r"/// A parsed config.
+///
+/// Built by [`parse`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub name: String,
+}
+
+// Not a doc comment.
+fn helper() {}
+
+/// Parse `input` into a [`Config`].
+pub fn parse(input: &str) -> Config {
+    Config { name: input.to_string() }
+}
+";
+    let entities = extract_entities(Path::new("src/config.rs"), source, Language::RUST);
+    assert_eq!(
+        doc_of(&entities, "Config").as_deref(),
+        Some("A parsed config.\n\nBuilt by [`parse`].")
+    );
+    assert_eq!(doc_of(&entities, "helper"), None);
+    assert_eq!(
+        doc_of(&entities, "parse").as_deref(),
+        Some("Parse `input` into a [`Config`].")
+    );
+}
+
+#[test]
+fn test_jsdoc_and_javadoc_blocks() {
+    let ts = r"/**
+ * Fetch a user by ID.
+ * @param id the user ID
+ */
+export function getUser(id: string) {
+  return api.get(id);
+}
+
+/* plain block comment */
+function internal() {}
+";
+    let entities = extract_entities(Path::new("src/users.ts"), ts, Language::TYPESCRIPT);
+    assert_eq!(
+        doc_of(&entities, "getUser").as_deref(),
+        Some("Fetch a user by ID.\n@param id the user ID")
+    );
+    assert_eq!(doc_of(&entities, "internal"), None);
+
+    let java = r"public class Service {
+    /** Start the service. */
+    @Override
+    public void start() {}
+}
+";
+    let entities = extract_entities(Path::new("Service.java"), java, Language::JAVA);
+    assert_eq!(
+        doc_of(&entities, "start").as_deref(),
+        Some("Start the service.")
+    );
+}
+
+#[test]
+fn test_go_doc_comments() {
+    let source = r"package server
+
+// Serve starts the HTTP server
+// on the given address.
+func Serve(addr string) error {
+	return nil
+}
+";
+    let entities = extract_entities(Path::new("server.go"), source, Language::GO);
+    assert_eq!(
+        doc_of(&entities, "Serve").as_deref(),
+        Some("Serve starts the HTTP server\non the given address.")
+    );
+}
+
+#[test]
+fn test_long_docs_are_truncated() {
+    let mut source: String = (1..=30)
+        .map(|i| format!("/// line {}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    source.push_str("\nfn documented() {}\n");
+    let entities = extract_entities(Path::new("src/lib.rs"), &source, Language::RUST);
+    let doc = doc_of(&entities, "documented").unwrap();
+    let lines: Vec<&str> = doc.lines().collect();
+    assert_eq!(lines.len(), MAX_DOC_LINES + 1);
+    assert_eq!(lines[0], "line 1");
+    assert_eq!(lines[MAX_DOC_LINES - 1], "line 20");
+    assert_eq!(lines[MAX_DOC_LINES], "… (10 more lines)");
+}
@@ -0,0 +1,103 @@
+use rpg_parser::entities::{RawEntity, extract_entities};
+use rpg_parser::languages::Language;
+use std::path::Path;
+
+/// `(is_async, is_generator, is_unsafe)` of the entity named `name`.
+fn flags_of(entities: &[RawEntity], name: &str) -> (bool, bool, bool) {
+    let entity = entities
+        .iter()
+        .find(|e| e.name == name)
+        .unwrap_or_else(|| panic!("no entity {}", name));
+    (entity.is_async, entity.is_generator, entity.is_unsafe)
+}
+
+#[test]
+fn test_python_async_and_generators() {
+    let source = r#"async def fetch(url):
+    return await get(url)
+
+
+def numbers(n):
+    for i in range(n):
+        yield i
+
+
+def collect(items):
+    def inner():
+        yield 1
+    return [x for x in items]
+
+
+class Stream:
+    @staticmethod
+    async def chunks(self):
+        yield b""
+"#;
+    let entities = extract_entities(Path::new("io.py"), source, Language::PYTHON);
+    assert_eq!(flags_of(&entities, "fetch"), (true, false, false));
+    assert_eq!(flags_of(&entities, "numbers"), (false, true, false));
+    // A nested generator does not make its parent one.
+    assert_eq!(flags_of(&entities, "collect"), (false, false, false));
+    assert_eq!(flags_of(&entities, "chunks"), (true, true, false));
+}
+
+#[test]
+fn test_rust_async_and_unsafe() {
+    let source = r"pub async fn serve() {}
+
+unsafe fn raw_copy(dst: *mut u8) {}
+
+struct Pool;
+
+impl Pool {
+    pub async unsafe fn acquire(&self) {}
+    fn size(&self) -> usize { 0 }
+}
+";
+    let entities = extract_entities(Path::new("src/lib.rs"), source, Language::RUST);
+    assert_eq!(flags_of(&entities, "serve"), (true, false, false));
+    assert_eq!(flags_of(&entities, "raw_copy"), (false, false, true));
+    assert_eq!(flags_of(&entities, "acquire"), (true, false, true));
+    assert_eq!(flags_of(&entities, "size"), (false, false, false));
+}
+
+#[test]
+fn test_typescript_async_and_generators() {
+    let source = r"export async function loadUser(id: string) {
+  return api.get(id);
+}
+
+export const saveUser = async (user: User) => {
+  await api.put(user);
+};
+
+function* ids() {
+  yield 1;
+}
+
+class Queue {
+  async drain() {}
+  *[Symbol.iterator]() {}
+  *items() {
+    yield 1;
+  }
+  size() {
+    return 0;
+  }
+}
+";
+    let entities = extract_entities(Path::new("src/users.ts"), source, Language::TYPESCRIPT);
+    assert_eq!(flags_of(&entities, "loadUser"), (true, false, false));
+    assert_eq!(flags_of(&entities, "saveUser"), (true, false, false));
+    assert_eq!(flags_of(&entities, "ids"), (false, true, false));
+    assert_eq!(flags_of(&entities, "drain"), (true, false, false));
+    assert_eq!(flags_of(&entities, "items"), (false, true, false));
+    assert_eq!(flags_of(&entities, "size"), (false, false, false));
+}
+
+#[test]
+fn test_javascript_async_function_expression() {
+    let source = "const sync = async function () {\n  await flush();\n};\n";
+    let entities = extract_entities(Path::new("sync.js"), source, Language::JAVASCRIPT);
+    assert_eq!(flags_of(&entities, "sync"), (true, false, false));
+}
@@ -50,3 +50,19 @@ func main() {
     let callees: Vec<&str> = deps.calls.iter().map(|c| c.callee.as_str()).collect();
     assert!(callees.contains(&"Println"));
 }
+
+#[test]
+fn go_config_reads() {
+    let source = r#"package main
+
+import "os"
+
+func port() string {
+	return os.Getenv("PORT")
+}
+"#;
+    let deps = extract_deps(Path::new("main.go"), source, Language::GO);
+    assert_eq!(deps.config_reads.len(), 1);
+    assert_eq!(deps.config_reads[0].key, "PORT");
+    assert_eq!(deps.config_reads[0].line, 6);
+}
@@ -38,3 +38,68 @@ fn java_interface_implementation() {
     assert_eq!(inherit.child_class, "Foo");
     assert_eq!(inherit.parent_class, "Bar");
 }
+
+#[test]
+fn java_field_types_compose() {
+    let source = r"public class Order {
+    private Customer customer;
+    private final List<LineItem> items = new ArrayList<>();
+    private int total;
+    private String note;
+
+    static class Audit {
+        private Clock clock;
+    }
+}
+";
+    let deps = extract_deps(Path::new("Order.java"), source, Language::JAVA);
+    let targets = |owner: &str| -> Vec<&str> {
+        deps.composes
+            .iter()
+            .filter(|c| c.source_entity == owner)
+            .map(|c| c.target_name.as_str())
+            .collect()
+    };
+    assert_eq!(targets("Order"), vec!["Customer", "LineItem"]);
+    assert_eq!(targets("Audit"), vec!["Clock"]);
+}
+
+#[test]
+fn java_config_reads() {
+    let source = r#"class Settings {
+    String home() {
+        return System.getenv("APP_HOME");
+    }
+}
+"#;
+    let deps = extract_deps(Path::new("Settings.java"), source, Language::JAVA);
+    assert_eq!(deps.config_reads.len(), 1);
+    assert_eq!(deps.config_reads[0].key, "APP_HOME");
+    assert_eq!(deps.config_reads[0].line, 3);
+}
+
+#[test]
+fn java_enum_and_constant_references() {
+    let source = r"public class Gate {
+    public boolean open(Level level, int load) {
+        switch (level) {
+            case HIGH:
+                return false;
+            default:
+                return level == Level.LOW && load < Limits.MAX_LOAD;
+        }
+    }
+}
+";
+    let deps = extract_deps(Path::new("Gate.java"), source, Language::JAVA);
+    let mut refs: Vec<&str> = deps
+        .calls
+        .iter()
+        .filter(|c| c.caller_entity.ends_with("open"))
+        .map(|c| c.callee.as_str())
+        .collect();
+    refs.sort_unstable();
+    // `case HIGH` names an enum constant, not a module constant entity, but
+    // still follows the SCREAMING_CASE convention.
+    assert_eq!(refs, vec!["HIGH", "Level", "Limits"]);
+}
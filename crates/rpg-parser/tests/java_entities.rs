@@ -41,7 +41,7 @@ fn java_extract_enum() {
     let source = "public enum Color { RED, GREEN, BLUE }";
     let entities = extract_entities(Path::new("Color.java"), source, Language::JAVA);
     let en = entities.iter().find(|e| e.name == "Color").unwrap();
-    assert_eq!(en.kind, EntityKind::Class);
+    assert_eq!(en.kind, EntityKind::Enum);
 }
 
 #[test]
@@ -94,3 +94,13 @@ fn java_signature_void_return() {
     assert_eq!(sig.parameters[0].name, "name");
     assert_eq!(sig.return_type.as_deref(), Some("void"));
 }
+
+#[test]
+fn java_enum_methods_keep_enum_parent() {
+    let source =
+        "public enum Level { LOW, HIGH; public boolean isHigh() { return this == HIGH; } }";
+    let entities = extract_entities(Path::new("Level.java"), source, Language::JAVA);
+    let method = entities.iter().find(|e| e.name == "isHigh").unwrap();
+    assert_eq!(method.kind, EntityKind::Method);
+    assert_eq!(method.parent_class.as_deref(), Some("Level"));
+}
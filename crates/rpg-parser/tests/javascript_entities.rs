@@ -138,3 +138,31 @@ class Person {
     assert_eq!(greet.kind, EntityKind::Method);
     assert_eq!(greet.parent_class.as_deref(), Some("Person"));
 }
+
+#[test]
+fn test_top_level_wiring_becomes_module_scope_entity() {
+    let source = "const express = require('express');
+const app = express();
+
+function health(req, res) {
+  res.send('ok');
+}
+
+app.get('/health', health);
+app.listen(3000);
+";
+    let entities = extract_entities(Path::new("server.js"), source, Language::JAVASCRIPT);
+    let module = entities
+        .iter()
+        .find(|e| e.name == "<module>")
+        .expect("missing module-scope entity");
+    assert_eq!(module.id(), "server.js:<module>");
+    assert_eq!(module.kind, EntityKind::Function);
+    // `require` is an import, so the range starts at `express()`.
+    assert_eq!((module.line_start, module.line_end), (2, 9));
+    assert!(!module.source_text.contains("function health"));
+
+    let library = "const x = require('x');\nexport function f() { return x(); }\n";
+    let entities = extract_entities(Path::new("lib.js"), library, Language::JAVASCRIPT);
+    assert!(entities.iter().all(|e| e.name != "<module>"));
+}
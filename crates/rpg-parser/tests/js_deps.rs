@@ -90,3 +90,15 @@ function App() {
     );
     assert_eq!(button_call.unwrap().caller_entity, "App");
 }
+
+#[test]
+fn test_config_reads() {
+    let source = r#"export function apiBase() {
+  return process.env.API_BASE || process.env["API_FALLBACK"];
+}
+"#;
+    let deps = extract_deps(Path::new("src/api.js"), source, Language::JAVASCRIPT);
+    let keys: Vec<&str> = deps.config_reads.iter().map(|r| r.key.as_str()).collect();
+    assert_eq!(keys, vec!["API_BASE", "API_FALLBACK"]);
+    assert!(deps.config_reads.iter().all(|r| r.line == 2));
+}
@@ -45,3 +45,40 @@ fn kotlin_interface_method_call() {
     );
     assert_eq!(call.unwrap().caller_entity, "Validator.validate");
 }
+
+#[test]
+fn kotlin_import_alias_and_wildcard() {
+    let source = r"import com.example.util.Formatter as Fmt
+import kotlinx.coroutines.*
+";
+    let deps = extract_deps(Path::new("Foo.kt"), source, Language::KOTLIN);
+    assert_eq!(deps.imports.len(), 2);
+    assert_eq!(deps.imports[0].module, "com.example.util");
+    assert_eq!(deps.imports[0].symbols, vec!["Formatter"]);
+    assert_eq!(deps.imports[1].module, "kotlinx.coroutines");
+    assert!(deps.imports[1].symbols.is_empty());
+}
+
+#[test]
+fn kotlin_extension_and_companion_call_scopes() {
+    let source = r"fun String.shout(): String {
+    return uppercase()
+}
+
+class Repo {
+    companion object {
+        fun create(): Repo {
+            return build()
+        }
+    }
+}";
+    let deps = extract_deps(Path::new("Repo.kt"), source, Language::KOTLIN);
+    let caller = |callee: &str| {
+        deps.calls
+            .iter()
+            .find(|c| c.callee == callee)
+            .map(|c| c.caller_entity.as_str())
+    };
+    assert_eq!(caller("uppercase"), Some("String.shout"));
+    assert_eq!(caller("build"), Some("Repo.Companion.create"));
+}
@@ -51,3 +51,67 @@ fn kotlin_extract_interface() {
     let iface = entities.iter().find(|e| e.name == "Drawable").unwrap();
     assert_eq!(iface.kind, EntityKind::Class);
 }
+
+#[test]
+fn kotlin_extract_companion_object() {
+    let source = r"class Repo {
+    companion object {
+        fun create(): Repo = Repo()
+    }
+}
+
+class Cache {
+    companion object Factory {
+        fun create(): Cache = Cache()
+    }
+}";
+    let entities = extract_entities(Path::new("Repo.kt"), source, Language::KOTLIN);
+
+    let companion = entities.iter().find(|e| e.name == "Companion").unwrap();
+    assert_eq!(companion.kind, EntityKind::Class);
+    assert_eq!(companion.id(), "Repo.kt:Repo::Companion");
+    assert!(entities.iter().any(|e| e.id() == "Repo.kt:Cache::Factory"));
+
+    // Members are scoped by the outer class, so both `create`s stay distinct.
+    let ids: Vec<String> = entities
+        .iter()
+        .filter(|e| e.name == "create")
+        .map(|e| e.id())
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            "Repo.kt:Repo.Companion::create",
+            "Repo.kt:Cache.Factory::create"
+        ]
+    );
+}
+
+#[test]
+fn kotlin_extension_function_addressed_by_receiver() {
+    let source = r#"fun String.shout(): String = uppercase() + "!"
+
+fun <T> List<T>?.second(): T? = this?.getOrNull(1)
+
+fun plain() { }"#;
+    let entities = extract_entities(Path::new("Strings.kt"), source, Language::KOTLIN);
+
+    let shout = entities.iter().find(|e| e.name == "shout").unwrap();
+    assert_eq!(shout.kind, EntityKind::Method);
+    assert_eq!(shout.id(), "Strings.kt:String::shout");
+
+    let second = entities.iter().find(|e| e.name == "second").unwrap();
+    assert_eq!(second.id(), "Strings.kt:List::second");
+
+    let plain = entities.iter().find(|e| e.name == "plain").unwrap();
+    assert_eq!(plain.kind, EntityKind::Function);
+    assert_eq!(plain.id(), "Strings.kt:plain");
+}
+
+#[test]
+fn kotlin_script_file_extracts_functions() {
+    let source = "fun configure() { }\n\nconfigure()\n";
+    let entities = extract_entities(Path::new("build.gradle.kts"), source, Language::KOTLIN);
+    assert!(entities.iter().any(|e| e.name == "configure"));
+    assert_eq!(Language::from_extension("kts"), Some(Language::KOTLIN));
+}
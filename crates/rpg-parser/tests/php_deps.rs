@@ -12,7 +12,8 @@ class Foo { }
 ";
     let deps = extract_deps(Path::new("Foo.php"), source, Language::PHP);
     assert_eq!(deps.imports.len(), 1);
-    assert_eq!(deps.imports[0].module, r"App\Models\User");
+    assert_eq!(deps.imports[0].module, r"App\Models");
+    assert_eq!(deps.imports[0].symbols, vec!["User"]);
 }
 
 #[test]
@@ -47,12 +48,89 @@ use Foo\{Bar, Baz};
 class MyClass { }
 ";
     let deps = extract_deps(Path::new("MyClass.php"), source, Language::PHP);
-    assert!(!deps.imports.is_empty());
-    // The parser captures the grouped use as a single import with the full text
+    assert_eq!(deps.imports.len(), 1);
+    // The group expands into one symbol per imported name
     let import = &deps.imports[0];
-    assert!(
-        import.module.contains("Foo"),
-        "expected module to contain 'Foo', got: {:?}",
-        import.module
+    assert_eq!(import.module, "Foo");
+    assert_eq!(import.symbols, vec!["Bar", "Baz"]);
+}
+
+fn callees(deps: &rpg_parser::deps::RawDeps, caller: &str) -> Vec<String> {
+    deps.calls
+        .iter()
+        .filter(|c| c.caller_entity == caller)
+        .map(|c| c.callee.clone())
+        .collect()
+}
+
+#[test]
+fn php_static_calls_are_namespace_qualified() {
+    let source = r"<?php
+namespace App\Http\Controllers;
+
+use App\Services\Payment;
+use App\Services\{Mailer as Mail};
+
+class OrderController extends \Illuminate\Routing\Controller {
+    public function store() {
+        Payment::charge();
+        \App\Billing\Invoice::issue();
+        Mail::send();
+        Helper::format();
+        self::validate();
+        \App\helpers\log_event();
+        new Payment();
+    }
+}
+";
+    let deps = extract_deps(Path::new("OrderController.php"), source, Language::PHP);
+    assert_eq!(
+        callees(&deps, "OrderController.store"),
+        vec![
+            r"App\Services\Payment::charge",
+            r"App\Billing\Invoice::issue",
+            r"App\Services\Mailer::send",
+            r"App\Http\Controllers\Helper::format",
+            "validate",
+            "log_event",
+            "Payment",
+        ]
     );
+    assert_eq!(deps.inherits[0].parent_class, "Controller");
+    assert_eq!(deps.imports.len(), 1);
+    assert_eq!(deps.imports[0].symbols, vec!["Payment", "Mailer"]);
+}
+
+#[test]
+fn php_trait_methods_are_scoped() {
+    let source = "<?php trait Loggable { public function log() { write(); } }";
+    let deps = extract_deps(Path::new("Loggable.php"), source, Language::PHP);
+    assert_eq!(callees(&deps, "Loggable.log"), vec!["write"]);
+}
+
+#[test]
+fn php_mixed_html_template() {
+    let source = r"<html>
+<body>
+<?php if ($user) { ?>
+  <p><?= render_name($user) ?></p>
+<?php } ?>
+</body>
+</html>
+";
+    let deps = extract_deps(Path::new("view.php"), source, Language::PHP);
+    assert_eq!(callees(&deps, "<module>"), vec!["render_name"]);
+}
+
+#[test]
+fn php_config_reads() {
+    let source = r#"<?php
+function app_name() {
+    return config('app.name') ?? getenv("APP_NAME") ?? $_ENV['APP_FALLBACK'] ?? getenv("X_$suffix");
+}
+"#;
+    let deps = extract_deps(Path::new("helpers.php"), source, Language::PHP);
+    let keys: Vec<&str> = deps.config_reads.iter().map(|r| r.key.as_str()).collect();
+    // The interpolated key is not a literal and is skipped.
+    assert_eq!(keys, vec!["app.name", "APP_NAME", "APP_FALLBACK"]);
 }
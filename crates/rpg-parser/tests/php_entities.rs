@@ -50,3 +50,38 @@ fn php_extract_interface() {
     let iface = entities.iter().find(|e| e.name == "Printable").unwrap();
     assert_eq!(iface.kind, EntityKind::Class);
 }
+
+#[test]
+fn php_extract_from_mixed_html() {
+    let source = r#"<!DOCTYPE html>
+<html>
+<?php function title($page) { return ucfirst($page); } ?>
+<h1><?= title("home") ?></h1>
+<?php class Widget { public function render() { } } ?>
+</html>
+"#;
+    let entities = extract_entities(Path::new("page.php"), source, Language::PHP);
+    let func = entities.iter().find(|e| e.name == "title").unwrap();
+    assert_eq!(func.kind, EntityKind::Function);
+    assert_eq!(func.line_start, 3);
+    let method = entities.iter().find(|e| e.name == "render").unwrap();
+    assert_eq!(method.parent_class.as_deref(), Some("Widget"));
+}
+
+#[test]
+fn php_signature_types_and_return() {
+    let source = "<?php\nfunction greet(string $name, int ...$ids): string { return $name; }\nclass User { public function save(?array $opts = null) { } }";
+    let entities = extract_entities(Path::new("greet.php"), source, Language::PHP);
+    let greet = entities.iter().find(|e| e.name == "greet").unwrap();
+    let sig = greet.signature.as_ref().expect("should have signature");
+    assert_eq!(sig.parameters.len(), 2);
+    assert_eq!(sig.parameters[0].name, "$name");
+    assert_eq!(sig.parameters[0].type_annotation.as_deref(), Some("string"));
+    assert_eq!(sig.parameters[1].name, "...$ids");
+    assert_eq!(sig.return_type.as_deref(), Some("string"));
+
+    let save = entities.iter().find(|e| e.name == "save").unwrap();
+    let sig = save.signature.as_ref().expect("should have signature");
+    assert_eq!(sig.parameters[0].type_annotation.as_deref(), Some("?array"));
+    assert!(sig.return_type.is_none());
+}
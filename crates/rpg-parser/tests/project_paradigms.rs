@@ -0,0 +1,109 @@
+//! Project-local paradigm definitions from `.rpg/paradigms/*.toml`, merged
+//! with the built-ins by `load_defs`.
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::languages::Language;
+use rpg_parser::paradigms::defs::{load_defs, load_project_defs};
+use rpg_parser::paradigms::detect_paradigms_toml;
+use rpg_parser::paradigms::query_engine::QueryCache;
+use std::path::PathBuf;
+
+const ACME_TOML: &str = r#"
+schema_version = 1
+name = "acme"
+priority = 15
+languages = ["python"]
+
+[detect]
+config_files = ["acme.yaml"]
+
+[[classify]]
+id = "acme.handler"
+action = { reclassify = "controller" }
+[classify.match]
+kind = "class"
+name_regex = "Handler$"
+"#;
+
+const HANDLERS_PY: &str =
+    "class UserHandler:\n    def get(self):\n        return 1\n\nclass Helper:\n    pass\n";
+
+fn project(defs: &[(&str, &str)]) -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".rpg/paradigms")).unwrap();
+    for (name, toml) in defs {
+        std::fs::write(tmp.path().join(".rpg/paradigms").join(name), toml).unwrap();
+    }
+    tmp
+}
+
+#[test]
+fn test_project_paradigm_classifies_entities() {
+    let tmp = project(&[("acme.toml", ACME_TOML)]);
+    std::fs::write(tmp.path().join("acme.yaml"), "").unwrap();
+    let defs = load_defs(tmp.path()).unwrap();
+    let qcache = QueryCache::compile_all(&defs).unwrap();
+    let active = detect_paradigms_toml(tmp.path(), &[Language::PYTHON], &defs);
+    assert!(active.iter().any(|d| d.name == "acme"));
+
+    let raw = rpg_parser::parse_files_with_paradigms(
+        vec![(PathBuf::from("handlers.py"), HANDLERS_PY.to_string())],
+        &active,
+        &qcache,
+    );
+    let kind = |name: &str| raw.iter().find(|e| e.name == name).map(|e| e.kind);
+    assert_eq!(kind("UserHandler"), Some(EntityKind::Controller));
+    assert_eq!(kind("Helper"), Some(EntityKind::Class));
+}
+
+#[test]
+fn test_project_def_replaces_builtin_of_same_name() {
+    let flask = ACME_TOML
+        .replace("name = \"acme\"", "name = \"flask\"")
+        .replace("priority = 15", "priority = 5");
+    let tmp = project(&[("flask.toml", &flask)]);
+    let defs = load_defs(tmp.path()).unwrap();
+    let flasks: Vec<_> = defs.iter().filter(|d| d.name == "flask").collect();
+    assert_eq!(flasks.len(), 1);
+    assert_eq!(flasks[0].priority, 5);
+    assert_eq!(flasks[0].classify[0].id, "acme.handler");
+    // Priority order still holds: the override now sorts first.
+    assert_eq!(defs[0].name, "flask");
+}
+
+#[test]
+fn test_bad_project_def_names_the_file() {
+    let tmp = project(&[
+        ("acme.toml", ACME_TOML),
+        (
+            "broken.toml",
+            &ACME_TOML
+                .replace("\"acme\"", "\"broken\"")
+                .replace("acme.handler", "broken.handler")
+                .replace("reclassify = \"controller\"", "reclassify = \"controler\""),
+        ),
+    ]);
+    let errors = load_project_defs(tmp.path()).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "[.rpg/paradigms/broken.toml:11] rule broken.handler: unknown entity_kind 'controler' in reclassify action"
+        ]
+    );
+
+    std::fs::write(
+        tmp.path().join(".rpg/paradigms/broken.toml"),
+        "schema_version = 1\nname = \"broken\"\n",
+    )
+    .unwrap();
+    let errors = load_defs(tmp.path()).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(
+        errors[0]
+            .to_string()
+            .starts_with("[.rpg/paradigms/broken.toml"),
+        "{}",
+        errors[0]
+    );
+}
@@ -125,3 +125,76 @@ fn test_empty_file() {
     assert!(deps.calls.is_empty());
     assert!(deps.inherits.is_empty());
 }
+
+#[test]
+fn test_annotated_class_attributes_compose() {
+    let source = r#"
+from dataclasses import dataclass
+
+@dataclass
+class Order:
+    customer: Customer
+    items: list["LineItem"]
+    shipping: Optional[models.Address] = None
+    total: int = 0
+    label = "plain"
+
+    def describe(self) -> Receipt:
+        note: Memo = make_memo()
+        return note
+"#;
+    let deps = extract_python_deps(Path::new("order.py"), source);
+    let targets: Vec<&str> = deps
+        .composes
+        .iter()
+        .filter(|c| c.source_entity == "Order")
+        .map(|c| c.target_name.as_str())
+        .collect();
+    // Method locals and return types are not attributes.
+    assert_eq!(targets, vec!["Customer", "LineItem", "Address"]);
+    assert_eq!(deps.composes.len(), 3);
+}
+
+#[test]
+fn test_config_reads() {
+    let source = r#"import os
+
+def database_url():
+    return os.environ["DATABASE_URL"]
+
+def debug(config):
+    return os.getenv("DEBUG") or config.get("app.debug") or os.environ.get(f"X_{name}")
+"#;
+    let deps = rpg_parser::deps::extract_deps(
+        Path::new("settings.py"),
+        source,
+        rpg_parser::languages::Language::PYTHON,
+    );
+    let keys: Vec<&str> = deps.config_reads.iter().map(|r| r.key.as_str()).collect();
+    // The f-string key is not a literal and is skipped.
+    assert_eq!(keys, vec!["DATABASE_URL", "DEBUG", "app.debug"]);
+    assert_eq!(deps.config_reads[0].line, 4);
+}
+
+#[test]
+fn test_enum_and_constant_references() {
+    let source = "\
+def check(order):
+    if order.status == Status.SHIPPED or order.retries >= limits.MAX_RETRIES:
+        return True
+    match order.color:
+        case Color.RED:
+            return False
+        case _:
+            return order.total > THRESHOLD
+";
+    let deps = extract_python_deps(Path::new("test.py"), source);
+    let mut refs: Vec<&str> = deps
+        .calls
+        .iter()
+        .filter(|c| c.caller_entity == "check")
+        .map(|c| c.callee.as_str())
+        .collect();
+    refs.sort_unstable();
+    assert_eq!(refs, vec!["Color", "MAX_RETRIES", "Status", "THRESHOLD"]);
+}
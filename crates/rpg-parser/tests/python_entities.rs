@@ -225,3 +225,139 @@ fn test_source_text_captured() {
     assert!(entities[0].source_text.contains("def hello()"));
     assert!(entities[0].source_text.contains("return 42"));
 }
+
+#[test]
+fn test_decorated_function_includes_decorators() {
+    let source = "\
+x = 1
+
+@app.get(\"/users\")
+def list_users():
+    return []
+";
+    let entities = extract_python_entities(Path::new("test.py"), source);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].line_start, 3);
+    assert!(entities[0].source_text.starts_with("@app.get(\"/users\")"));
+}
+
+#[test]
+fn test_enum_subclasses_and_module_constants() {
+    let source = "\
+import enum
+from enum import Enum, IntFlag
+
+MAX_RETRIES = 3
+TIMEOUT: float = 2.5
+default_name = \"x\"
+
+
+class Color(Enum):
+    RED = 1
+
+    def label(self):
+        return self.name.lower()
+
+
+class Perm(IntFlag):
+    READ = 4
+
+
+class Level(enum.IntEnum):
+    LOW = 1
+
+
+class Plain(Base):
+    LIMIT = 5
+";
+    let entities = extract_python_entities(Path::new("test.py"), source);
+    let kind_of = |name: &str| entities.iter().find(|e| e.name == name).map(|e| e.kind);
+    assert_eq!(kind_of("Color"), Some(EntityKind::Enum));
+    assert_eq!(kind_of("Perm"), Some(EntityKind::Enum));
+    assert_eq!(kind_of("Level"), Some(EntityKind::Enum));
+    assert_eq!(kind_of("Plain"), Some(EntityKind::Class));
+    assert_eq!(kind_of("label"), Some(EntityKind::Method));
+
+    let mut constants: Vec<&str> = entities
+        .iter()
+        .filter(|e| e.kind == EntityKind::Constant)
+        .map(|e| e.name.as_str())
+        .collect();
+    constants.sort_unstable();
+    // Lowercase assignments and class attributes are not module constants.
+    assert_eq!(constants, vec!["MAX_RETRIES", "TIMEOUT"]);
+}
+
+#[test]
+fn test_constant_initializer_call_stays_in_module_scope() {
+    let source = "import logging\n\nLOGGER = logging.getLogger(__name__)\n";
+    let entities = extract_python_entities(Path::new("test.py"), source);
+    let names: Vec<(&str, EntityKind)> =
+        entities.iter().map(|e| (e.name.as_str(), e.kind)).collect();
+    assert!(
+        names.contains(&("LOGGER", EntityKind::Constant)),
+        "{:?}",
+        names
+    );
+    assert!(
+        names.contains(&("<module>", EntityKind::Function)),
+        "{:?}",
+        names
+    );
+}
+
+#[test]
+fn test_decorators_captured_in_order() {
+    let source = "\
+@app.route(
+    \"/users\",
+    methods=[\"GET\"],
+)
+@login_required
+def list_users():
+    return []
+
+@dataclass(frozen=True)
+class User:
+    name: str
+
+    @property
+    def display(self):
+        return self.name
+
+def plain():
+    pass
+";
+    let entities = extract_python_entities(Path::new("app.py"), source);
+    let decorators = |name: &str| {
+        entities
+            .iter()
+            .find(|e| e.name == name)
+            .unwrap()
+            .decorators
+            .clone()
+    };
+    assert_eq!(
+        decorators("list_users"),
+        vec![
+            "app.route( \"/users\", methods=[\"GET\"], )".to_string(),
+            "login_required".to_string(),
+        ]
+    );
+    assert_eq!(
+        decorators("User"),
+        vec!["dataclass(frozen=True)".to_string()]
+    );
+    assert_eq!(decorators("display"), vec!["property".to_string()]);
+    assert!(decorators("plain").is_empty());
+
+    let entity = entities
+        .into_iter()
+        .find(|e| e.name == "User")
+        .unwrap()
+        .into_entity();
+    assert_eq!(
+        entity.decorators,
+        vec!["dataclass(frozen=True)".to_string()]
+    );
+}
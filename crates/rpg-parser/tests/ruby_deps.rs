@@ -68,3 +68,17 @@ fn ruby_extend_mixin() {
     assert_eq!(mixin.child_class, "Bar");
     assert_eq!(mixin.parent_class, "ClassMethods");
 }
+
+#[test]
+fn ruby_config_reads() {
+    let source = r#"class Mailer
+  def host
+    ENV["SMTP_HOST"]
+  end
+end
+"#;
+    let deps = extract_deps(Path::new("mailer.rb"), source, Language::RUBY);
+    assert_eq!(deps.config_reads.len(), 1);
+    assert_eq!(deps.config_reads[0].key, "SMTP_HOST");
+    assert_eq!(deps.config_reads[0].line, 3);
+}
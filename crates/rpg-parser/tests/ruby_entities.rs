@@ -51,3 +51,20 @@ fn ruby_extract_singleton_method() {
     assert_eq!(singleton.kind, EntityKind::Method);
     assert_eq!(singleton.parent_class.as_deref(), Some("Foo"));
 }
+
+#[test]
+fn ruby_signature_parameter_kinds() {
+    let source = "def greet(name, greeting = \"hi\", *rest, key:, **opts, &blk)\nend";
+    let entities = extract_entities(Path::new("greet.rb"), source, Language::RUBY);
+    let sig = entities[0]
+        .signature
+        .as_ref()
+        .expect("should have signature");
+    let names: Vec<&str> = sig.parameters.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["name", "greeting", "*rest", "key", "**opts", "&blk"]
+    );
+    assert!(sig.parameters.iter().all(|p| p.type_annotation.is_none()));
+    assert!(sig.return_type.is_none());
+}
@@ -96,3 +96,117 @@ fn test_use_with_alias() {
     // Note: simple use aliases not yet stripped (only grouped use aliases are)
     assert!(deps.imports[0].symbols[0].starts_with("Result"));
 }
+
+#[test]
+fn test_struct_field_types_compose() {
+    let source = "\
+pub struct Order {
+    #[serde(default)]
+    pub customer: Customer,
+    items: Vec<LineItem>,
+    shipping: Option<Box<crate::shipping::Address>>,
+    next: Option<Box<Order>>,
+    total: u64,
+}
+
+mod inner {
+    struct Pair(Left, std::sync::Arc<Right>);
+}
+";
+    let deps = extract_rust_deps(Path::new("order.rs"), source);
+    let targets = |owner: &str| -> Vec<&str> {
+        deps.composes
+            .iter()
+            .filter(|c| c.source_entity == owner)
+            .map(|c| c.target_name.as_str())
+            .collect()
+    };
+    // Std containers, primitives, and the struct itself are skipped.
+    assert_eq!(targets("Order"), vec!["Customer", "LineItem", "Address"]);
+    assert_eq!(targets("Pair"), vec!["Left", "Right"]);
+}
+
+#[test]
+fn test_trait_impls_inherit() {
+    let source = "\
+impl Storage for FileStore {
+    fn load(&self) {}
+}
+
+impl<T> fmt::Display for Wrapper<T> {
+    fn fmt(&self) {}
+}
+
+impl<'a> Iterator<Item = u8> for &'a Bytes {}
+
+impl<T: Clone> Marker for T {}
+
+impl Plain {
+    fn new() -> Self { Plain }
+}
+
+mod inner {
+    impl crate::api::Handler for Router {}
+}
+";
+    let deps = extract_rust_deps(Path::new("impls.rs"), source);
+    let pairs: Vec<(&str, &str)> = deps
+        .inherits
+        .iter()
+        .map(|i| (i.child_class.as_str(), i.parent_class.as_str()))
+        .collect();
+    // Inherent impls and blanket impls over a type parameter give no edge.
+    assert_eq!(
+        pairs,
+        vec![
+            ("FileStore", "Storage"),
+            ("Wrapper", "Display"),
+            ("Bytes", "Iterator"),
+            ("Router", "Handler"),
+        ]
+    );
+}
+
+#[test]
+fn test_config_reads() {
+    let source = r#"fn read_only() -> bool {
+    std::env::var("RPG_READ_ONLY").is_ok()
+}
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+"#;
+    let deps = rpg_parser::deps::extract_deps(
+        Path::new("src/config.rs"),
+        source,
+        rpg_parser::languages::Language::RUST,
+    );
+    let reads: Vec<(usize, &str)> = deps
+        .config_reads
+        .iter()
+        .map(|r| (r.line, r.key.as_str()))
+        .collect();
+    assert_eq!(reads, vec![(2, "RPG_READ_ONLY"), (5, "CARGO_PKG_VERSION")]);
+}
+
+#[test]
+fn test_enum_and_constant_references_in_matches_and_comparisons() {
+    let source = "\
+fn classify(kind: EntityKind, depth: usize) -> bool {
+    match kind {
+        EntityKind::Enum | crate::graph::Layer::Api => depth < MAX_DEPTH,
+        Self::Other(inner) => inner == DEFAULT,
+        other => other.is_leaf(),
+    }
+}
+";
+    let deps = extract_rust_deps(Path::new("test.rs"), source);
+    let mut refs: Vec<&str> = deps
+        .calls
+        .iter()
+        .filter(|c| c.caller_entity == "classify" && !c.callee.ends_with("is_leaf"))
+        .map(|c| c.callee.as_str())
+        .collect();
+    refs.sort_unstable();
+    // Variants resolve to their enum; `Self::` and bindings are skipped.
+    assert_eq!(refs, vec!["DEFAULT", "EntityKind", "Layer", "MAX_DEPTH"]);
+}
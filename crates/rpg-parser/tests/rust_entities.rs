@@ -101,7 +101,7 @@ enum Color {
     let entities = extract_rust_entities(Path::new("test.rs"), source);
     assert_eq!(entities.len(), 1);
     assert_eq!(entities[0].name, "Color");
-    assert_eq!(entities[0].kind, EntityKind::Class);
+    assert_eq!(entities[0].kind, EntityKind::Enum);
 }
 
 #[test]
@@ -192,7 +192,7 @@ fn render() {}
     assert!(
         entities
             .iter()
-            .any(|e| e.name == "Shape" && e.kind == EntityKind::Class)
+            .any(|e| e.name == "Shape" && e.kind == EntityKind::Enum)
     );
     assert!(
         entities
@@ -205,3 +205,37 @@ fn render() {}
             .any(|e| e.name == "render" && e.kind == EntityKind::Function)
     );
 }
+
+#[test]
+fn test_module_constants_and_statics() {
+    let source = "\
+pub const MAX_DEPTH: usize = 8;
+static GREETING: &str = \"hi\";
+
+mod limits {
+    pub const MIN_DEPTH: usize = 1;
+}
+
+struct Walker;
+
+impl Walker {
+    const STEP: usize = 2;
+}
+
+fn walk() {
+    const LOCAL: u8 = 0;
+}
+";
+    let entities = extract_rust_entities(Path::new("test.rs"), source);
+    let constants: Vec<(&str, usize, usize)> = entities
+        .iter()
+        .filter(|e| e.kind == EntityKind::Constant)
+        .map(|e| (e.name.as_str(), e.line_start, e.line_end))
+        .collect();
+    // Associated and function-local consts are not module-level.
+    assert_eq!(
+        constants,
+        vec![("MAX_DEPTH", 1, 1), ("GREETING", 2, 2), ("MIN_DEPTH", 5, 5)]
+    );
+    assert_eq!(entities[0].id(), "test.rs:MAX_DEPTH");
+}
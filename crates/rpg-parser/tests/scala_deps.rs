@@ -12,7 +12,45 @@ class Foo { }
     let deps = extract_deps(Path::new("Foo.scala"), source, Language::SCALA);
     assert!(!deps.imports.is_empty());
     let import = &deps.imports[0];
-    assert_eq!(import.module, "scala.collection.mutable.ListBuffer");
+    assert_eq!(import.module, "scala.collection.mutable");
+    assert_eq!(import.symbols, vec!["ListBuffer"]);
+}
+
+#[test]
+fn scala_grouped_and_wildcard_imports() {
+    let source = r"import org.apache.spark.sql.{DataFrame, SparkSession => Session}
+import org.apache.spark.sql.functions._
+
+object Foo { }
+";
+    let deps = extract_deps(Path::new("Foo.scala"), source, Language::SCALA);
+    assert_eq!(deps.imports.len(), 2);
+    assert_eq!(deps.imports[0].module, "org.apache.spark.sql");
+    assert_eq!(deps.imports[0].symbols, vec!["DataFrame", "SparkSession"]);
+    assert_eq!(deps.imports[1].module, "org.apache.spark.sql.functions");
+    assert!(deps.imports[1].symbols.is_empty());
+}
+
+#[test]
+fn scala_companion_calls_and_instantiation() {
+    let source = r"class Job(name: String) extends base.Runner[String](name) {
+  def run(): Unit = helper(name)
+}
+
+object Job {
+  def create(name: String): Job = new Job(name)
+}
+";
+    let deps = extract_deps(Path::new("Job.scala"), source, Language::SCALA);
+    let calls: Vec<(&str, &str)> = deps
+        .calls
+        .iter()
+        .map(|c| (c.caller_entity.as_str(), c.callee.as_str()))
+        .collect();
+    assert_eq!(calls, vec![("Job.run", "helper"), ("Job$.create", "Job")]);
+    assert_eq!(deps.inherits.len(), 1);
+    assert_eq!(deps.inherits[0].child_class, "Job");
+    assert_eq!(deps.inherits[0].parent_class, "Runner");
 }
 
 #[test]
@@ -54,3 +54,28 @@ fn scala_extract_function() {
     assert_eq!(func.kind, EntityKind::Method);
     assert_eq!(func.parent_class.as_deref(), Some("Main"));
 }
+
+#[test]
+fn scala_companion_object_has_distinct_id() {
+    let source = r"case class Point(x: Int, y: Int)
+
+object Point {
+  def origin: Point = Point(0, 0)
+}
+
+object Main {
+  def run(): Unit = { }
+}";
+    let entities = extract_entities(Path::new("Point.scala"), source, Language::SCALA);
+    let ids: Vec<String> = entities.iter().map(|e| e.id()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "Point.scala:Point",
+            "Point.scala:Point$",
+            "Point.scala:Point$::origin",
+            "Point.scala:Main",
+            "Point.scala:Main::run",
+        ]
+    );
+}
@@ -0,0 +1,74 @@
+use rpg_parser::languages::{Language, SHEBANG_PROBE_BYTES};
+use std::path::{Path, PathBuf};
+
+fn fixture(rel: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../tests/fixtures")
+        .join(rel)
+}
+
+#[test]
+fn test_from_shebang() {
+    let cases = [
+        ("#!/usr/bin/env python3", Some(Language::PYTHON)),
+        ("#!/usr/bin/python3.12 -u", Some(Language::PYTHON)),
+        (
+            "#!/usr/bin/env -S node --no-warnings",
+            Some(Language::JAVASCRIPT),
+        ),
+        (
+            "#!/usr/bin/env NODE_ENV=production node",
+            Some(Language::JAVASCRIPT),
+        ),
+        ("#!/bin/bash", Some(Language::BASH)),
+        ("#!/bin/sh -e", Some(Language::BASH)),
+        ("#!/usr/bin/env ruby", Some(Language::RUBY)),
+        ("#!/usr/bin/env perl", None),
+        ("#!/usr/bin/env", None),
+        ("# not a shebang", None),
+        ("", None),
+    ];
+    for (line, expected) in cases {
+        assert_eq!(Language::from_shebang(line), expected, "{}", line);
+    }
+}
+
+#[test]
+fn test_extensionless_fixture_script_produces_entities() {
+    let path = fixture("python_project/bin/deploy");
+    assert_eq!(Language::from_path(&path), Some(Language::PYTHON));
+
+    let source = std::fs::read_to_string(&path).unwrap();
+    let entities = rpg_parser::parse_files_parallel(vec![(PathBuf::from("bin/deploy"), source)]);
+    assert!(
+        entities.iter().any(|e| e.name == "deploy"),
+        "{:?}",
+        entities.iter().map(|e| &e.name).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_binary_and_unknown_files_are_skipped() {
+    let tmp = tempfile::tempdir().unwrap();
+    // A shebang followed by a NUL inside the probed prefix: binary.
+    let mut binary = b"#!/usr/bin/env python3\n".to_vec();
+    binary.extend([0u8; 16]);
+    std::fs::write(tmp.path().join("blob"), &binary).unwrap();
+    assert_eq!(Language::from_path(&tmp.path().join("blob")), None);
+
+    // A NUL past the probe still allows detection.
+    let mut late_nul = b"#!/bin/bash\n".to_vec();
+    late_nul.extend(vec![b'#'; SHEBANG_PROBE_BYTES]);
+    late_nul.push(0);
+    std::fs::write(tmp.path().join("tool"), &late_nul).unwrap();
+    assert_eq!(
+        Language::from_path(&tmp.path().join("tool")),
+        Some(Language::BASH)
+    );
+
+    std::fs::write(tmp.path().join("LICENSE"), "MIT License\n").unwrap();
+    assert_eq!(Language::from_path(&tmp.path().join("LICENSE")), None);
+    // Extensions win: a shebang in a `.txt` file doesn't make it a script.
+    std::fs::write(tmp.path().join("notes.txt"), "#!/usr/bin/env python3\n").unwrap();
+    assert_eq!(Language::from_path(&tmp.path().join("notes.txt")), None);
+}
@@ -0,0 +1,105 @@
+use rpg_parser::languages::Language;
+use rpg_parser::skeleton::{skeleton, skeleton_lines};
+
+const CACHE_PY: &str = r#"import time
+
+
+class Cache:
+    """LRU cache with expiry."""
+
+    max_size: int = 128
+
+    def __init__(self, max_size: int = 128):
+        self.max_size = max_size
+        self.items = {}
+
+    def get(self, key: str) -> object | None:
+        """Return the cached value, or None if expired."""
+        entry = self.items.get(key)
+        if entry is None or entry[1] < time.time():
+            return None
+        return entry[0]
+
+    @staticmethod
+    def key_for(*parts) -> str:
+        return ":".join(str(p) for p in parts)
+"#;
+
+const STORE_RS: &str = r"/// In-memory key-value store.
+pub struct Store {
+    /// Values by key.
+    items: HashMap<String, Vec<u8>>,
+    hits: u64,
+}
+
+impl Store {
+    /// Look up a key, counting hits.
+    pub fn get(&mut self, key: &str) -> Option<&[u8]> {
+        let value = self.items.get(key)?;
+        self.hits += 1;
+        Some(value.as_slice())
+    }
+
+    pub fn insert(&mut self, key: String, value: Vec<u8>) {
+        let merge = |old: &mut Vec<u8>| old.extend_from_slice(&value);
+        self.items.entry(key).and_modify(merge).or_default();
+    }
+}
+";
+
+#[test]
+fn test_python_class_skeleton_snapshot() {
+    let class = skeleton_lines(CACHE_PY, Language::PYTHON, 4, 22).unwrap();
+    assert_eq!(
+        class,
+        r#"class Cache:
+    """LRU cache with expiry."""
+
+    max_size: int = 128
+
+    def __init__(self, max_size: int = 128):
+        ...
+
+    def get(self, key: str) -> object | None:
+        """Return the cached value, or None if expired."""
+        ...
+
+    @staticmethod
+    def key_for(*parts) -> str:
+        ..."#
+    );
+}
+
+#[test]
+fn test_rust_skeleton_snapshot() {
+    assert_eq!(
+        skeleton(STORE_RS, Language::RUST).unwrap(),
+        r"/// In-memory key-value store.
+pub struct Store {
+    /// Values by key.
+    items: HashMap<String, Vec<u8>>,
+    hits: u64,
+}
+
+impl Store {
+    /// Look up a key, counting hits.
+    pub fn get(&mut self, key: &str) -> Option<&[u8]> { … }
+
+    pub fn insert(&mut self, key: String, value: Vec<u8>) { … }
+}"
+    );
+    // A method's own span elides just its body; the nested closure goes with it.
+    assert_eq!(
+        skeleton_lines(STORE_RS, Language::RUST, 16, 19).unwrap(),
+        "    pub fn insert(&mut self, key: String, value: Vec<u8>) { … }"
+    );
+}
+
+#[test]
+fn test_typescript_class_keeps_fields_and_elides_arrow_bodies() {
+    let source = "export class Queue<T> {\n  private items: T[] = [];\n  push(item: T): void {\n    this.items.push(item);\n  }\n  size = (): number => this.items.length;\n}\n";
+    assert_eq!(
+        skeleton(source, Language::TYPESCRIPT).unwrap(),
+        "export class Queue<T> {\n  private items: T[] = [];\n  push(item: T): void { … }\n  size = (): number => …;\n}"
+    );
+}
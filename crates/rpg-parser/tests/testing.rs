@@ -0,0 +1,140 @@
+use rpg_core::graph::EntityKind;
+use rpg_parser::deps::extract_deps;
+use rpg_parser::entities::extract_entities;
+use rpg_parser::languages::Language;
+use std::path::Path;
+
+/// Names of the Test entities extracted from `source`.
+fn tests_in(path: &str, source: &str, language: Language) -> Vec<String> {
+    extract_entities(Path::new(path), source, language)
+        .into_iter()
+        .filter(|e| e.kind == EntityKind::Test)
+        .map(|e| e.id())
+        .collect()
+}
+
+#[test]
+fn test_rust_test_attributes() {
+    let source = r"pub fn parse() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses() {
+        parse();
+    }
+
+    /// Async.
+    #[tokio::test]
+    async fn parses_async() {}
+
+    #[rstest]
+    #[case(1)]
+    fn cases(#[case] n: u32) {}
+
+    fn helper() {}
+}
+";
+    assert_eq!(
+        tests_in("src/lib.rs", source, Language::RUST),
+        vec![
+            "src/lib.rs:parses",
+            "src/lib.rs:parses_async",
+            "src/lib.rs:cases"
+        ]
+    );
+}
+
+#[test]
+fn test_pytest_and_unittest_functions() {
+    let source = r"import unittest
+
+
+def test_parse():
+    pass
+
+
+def helper():
+    pass
+
+
+class TestParser:
+    def test_tokens(self):
+        pass
+
+    def setup_method(self):
+        pass
+
+
+class ParserCase(unittest.TestCase):
+    def test_empty(self):
+        pass
+";
+    assert_eq!(
+        tests_in("tests/test_parser.py", source, Language::PYTHON),
+        vec![
+            "tests/test_parser.py:test_parse",
+            "tests/test_parser.py:TestParser::test_tokens",
+            "tests/test_parser.py:ParserCase::test_empty",
+        ]
+    );
+    // Outside a pytest file only unittest cases count.
+    assert_eq!(
+        tests_in("src/parser.py", source, Language::PYTHON),
+        vec!["src/parser.py:ParserCase::test_empty"]
+    );
+}
+
+#[test]
+fn test_go_test_functions() {
+    let source = r#"package parser
+
+import "testing"
+
+func TestParse(t *testing.T) {}
+func BenchmarkParse(b *testing.B) {}
+func Testify() {}
+func helper() {}
+"#;
+    assert_eq!(
+        tests_in("parser_test.go", source, Language::GO),
+        vec!["parser_test.go:TestParse", "parser_test.go:BenchmarkParse"]
+    );
+    assert!(tests_in("parser.go", source, Language::GO).is_empty());
+}
+
+#[test]
+fn test_js_test_blocks_own_their_calls() {
+    let source = r"import { render } from './render';
+
+describe('Button', () => {
+  it('renders', () => {
+    render();
+  });
+});
+
+test.only(`clicks`, () => {
+  click();
+});
+
+setup();
+";
+    let path = Path::new("src/button.test.ts");
+    assert_eq!(
+        tests_in("src/button.test.ts", source, Language::TYPESCRIPT),
+        vec!["src/button.test.ts:Button", "src/button.test.ts:clicks"]
+    );
+
+    let deps = extract_deps(path, source, Language::TYPESCRIPT);
+    let caller_of = |callee: &str| {
+        deps.calls
+            .iter()
+            .find(|c| c.callee == callee)
+            .map(|c| c.caller_entity.as_str())
+    };
+    assert_eq!(caller_of("render"), Some("Button"));
+    assert_eq!(caller_of("click"), Some("clicks"));
+    assert_eq!(caller_of("setup"), Some("<module>"));
+}
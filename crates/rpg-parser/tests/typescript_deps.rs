@@ -172,8 +172,8 @@ fn test_barrel_reexport_star() {
         "expected exactly 1 compose dep, got: {:?}",
         deps.composes
     );
-    // Star re-export target is the module path (stripped of ./ prefix)
-    assert_eq!(deps.composes[0].target_name, "utils");
+    // Star re-export target is the module specifier as written
+    assert_eq!(deps.composes[0].target_name, "./utils");
     // Should also produce an import dep
     assert!(!deps.imports.is_empty());
 }
@@ -269,10 +269,23 @@ fn test_barrel_reexport_aliased() {
         "expected 1 compose dep for aliased re-export, got: {:?}",
         deps.composes
     );
-    // Should use the alias name, not the original name
+    // `default` names nothing, so the alias stands in for it
     assert_eq!(deps.composes[0].target_name, "Foo");
 }
 
+#[test]
+fn test_barrel_reexport_renamed_uses_original_name() {
+    let source = "export { Bar as Baz } from './bar';";
+    let deps = extract_deps(Path::new("index.ts"), source, Language::TYPESCRIPT);
+    let targets: Vec<&str> = deps
+        .composes
+        .iter()
+        .map(|c| c.target_name.as_str())
+        .collect();
+    assert_eq!(targets, vec!["Bar"]);
+    assert_eq!(deps.imports[0].symbols, vec!["Bar"]);
+}
+
 #[test]
 fn test_jsx_dotted_component() {
     let source = r"
@@ -589,3 +602,72 @@ export const { useFoo, useBar } = createSomething();
         hooks
     );
 }
+
+#[test]
+fn test_ts_class_and_interface_field_types_compose() {
+    let source = "\
+export interface OrderProps {
+  customer: Customer;
+  items: Array<LineItem>;
+  onSave(order: Draft): void;
+  count: number;
+}
+
+export class OrderStore {
+  private current?: Order | null;
+  readonly cache: Map<string, Receipt> = new Map();
+  total = 0;
+}
+";
+    let deps = extract_deps(Path::new("order.ts"), source, Language::TYPESCRIPT);
+    let targets = |owner: &str| -> Vec<&str> {
+        deps.composes
+            .iter()
+            .filter(|c| c.source_entity == owner)
+            .map(|c| c.target_name.as_str())
+            .collect()
+    };
+    // Method signatures are behavior, not members holding data.
+    assert_eq!(targets("OrderProps"), vec!["Customer", "LineItem"]);
+    assert_eq!(targets("OrderStore"), vec!["Order", "Receipt"]);
+}
+
+#[test]
+fn test_config_reads() {
+    let source = r"const mode: string = import.meta.env.VITE_MODE;
+
+export function timeout(config: Config): number {
+  return config.get(`request.timeout`);
+}
+";
+    let deps = extract_deps(Path::new("src/env.ts"), source, Language::TYPESCRIPT);
+    let reads: Vec<(usize, &str)> = deps
+        .config_reads
+        .iter()
+        .map(|r| (r.line, r.key.as_str()))
+        .collect();
+    assert_eq!(reads, vec![(1, "VITE_MODE"), (4, "request.timeout")]);
+}
+
+#[test]
+fn test_enum_and_constant_references() {
+    let source = "\
+function label(status: Status, count: number): string {
+    switch (status) {
+        case Status.Active:
+            return count > MAX_ITEMS ? \"full\" : \"open\";
+        default:
+            return status === config.FALLBACK ? \"fallback\" : \"closed\";
+    }
+}
+";
+    let deps = extract_deps(Path::new("label.ts"), source, Language::TYPESCRIPT);
+    let mut refs: Vec<&str> = deps
+        .calls
+        .iter()
+        .filter(|c| c.caller_entity == "label")
+        .map(|c| c.callee.as_str())
+        .collect();
+    refs.sort_unstable();
+    assert_eq!(refs, vec!["FALLBACK", "MAX_ITEMS", "Status"]);
+}
@@ -71,6 +71,25 @@ fn test_signature_rest_parameter() {
     assert_eq!(sig.parameters[1].name, "...rest");
 }
 
+#[test]
+fn test_signature_arrow_function_const() {
+    let source =
+        "export const add = (a: number, b: number): number => a + b;\nconst double = x => x * 2;\n";
+    let entities = extract_entities(Path::new("test.ts"), source, Language::TYPESCRIPT);
+    let add = entities.iter().find(|e| e.name == "add").unwrap();
+    let sig = add.signature.as_ref().expect("should have signature");
+    assert_eq!(sig.parameters.len(), 2);
+    assert_eq!(sig.parameters[1].name, "b");
+    assert_eq!(sig.parameters[1].type_annotation.as_deref(), Some("number"));
+    assert_eq!(sig.return_type.as_deref(), Some("number"));
+
+    let double = entities.iter().find(|e| e.name == "double").unwrap();
+    let sig = double.signature.as_ref().expect("should have signature");
+    assert_eq!(sig.parameters.len(), 1);
+    assert_eq!(sig.parameters[0].name, "x");
+    assert!(sig.parameters[0].type_annotation.is_none());
+}
+
 #[test]
 fn test_class_with_method() {
     let source = "\
@@ -116,6 +135,48 @@ interface Animal {
     assert!(iface.parent_class.is_none());
 }
 
+#[test]
+fn test_enum_declaration() {
+    let source = "\
+export enum Status {
+    Active = \"active\",
+    Archived = \"archived\",
+}
+const enum Direction { Up, Down }
+";
+    let entities = extract_entities(Path::new("test.ts"), source, Language::TYPESCRIPT);
+    let enums: Vec<(&str, usize, usize)> = entities
+        .iter()
+        .filter(|e| e.kind == EntityKind::Enum)
+        .map(|e| (e.name.as_str(), e.line_start, e.line_end))
+        .collect();
+    assert_eq!(enums, vec![("Status", 1, 4), ("Direction", 5, 5)]);
+}
+
+#[test]
+fn test_top_level_literal_consts_are_constants() {
+    let source = "\
+export const API_URL = \"https://example.com\";
+const RETRY_LIMIT = 3;
+const DEFAULTS = { retries: 3 } as const;
+const app = createApp();
+let counter = 0;
+
+function run() {
+    const LOCAL = 1;
+    return LOCAL;
+}
+";
+    let entities = extract_entities(Path::new("config.ts"), source, Language::TYPESCRIPT);
+    let constants: Vec<&str> = entities
+        .iter()
+        .filter(|e| e.kind == EntityKind::Constant)
+        .map(|e| e.name.as_str())
+        .collect();
+    // Computed values, `let` bindings, and locals are not constants.
+    assert_eq!(constants, vec!["API_URL", "RETRY_LIMIT", "DEFAULTS"]);
+}
+
 #[test]
 fn test_named_arrow_function() {
     let source = "const add = (a: number, b: number): number => a + b;";
@@ -378,3 +439,38 @@ export const { useGetPostsQuery, useGetUserQuery } = postsApi;
         hook_names
     );
 }
+
+#[test]
+fn test_pure_barrel_file_becomes_module_entity() {
+    let source = "export * from './utils';\nexport { Bar } from './bar';\n";
+    let entities = extract_entities(Path::new("src/index.ts"), source, Language::TYPESCRIPT);
+    assert_eq!(entities.len(), 1);
+    assert_eq!(entities[0].id(), "src/index.ts:index");
+    assert_eq!(entities[0].kind, EntityKind::Module);
+    assert_eq!((entities[0].line_start, entities[0].line_end), (1, 2));
+
+    // Files that define something get their Module later, from the graph.
+    let source = "export * from './utils';\nexport function f() {}\n";
+    let entities = extract_entities(Path::new("src/index.ts"), source, Language::TYPESCRIPT);
+    assert!(entities.iter().all(|e| e.kind != EntityKind::Module));
+}
+
+#[test]
+fn test_decorators_recorded_and_included_in_span() {
+    let source = "@Controller('users')\nexport class Users {\n  @Get(':id')\n  @UseGuards(AuthGuard)\n  find(@Param('id') id: string) {}\n\n  plain() {}\n}\n\n@Injectable()\nclass Repo {}\n";
+    let entities = extract_entities(Path::new("users.ts"), source, Language::TYPESCRIPT);
+    let entity = |name: &str| entities.iter().find(|e| e.name == name).unwrap();
+
+    let users = entity("Users");
+    assert_eq!(users.decorators, ["Controller('users')"]);
+    assert_eq!((users.line_start, users.line_end), (1, 8));
+    assert!(users.source_text.starts_with("@Controller('users')"));
+
+    // Parameter decorators belong to the parameter, not the method.
+    let find = entity("find");
+    assert_eq!(find.decorators, ["Get(':id')", "UseGuards(AuthGuard)"]);
+    assert_eq!((find.line_start, find.line_end), (3, 5));
+    assert!(entity("plain").decorators.is_empty());
+
+    assert_eq!(entity("Repo").decorators, ["Injectable()"]);
+}
@@ -0,0 +1,89 @@
+//! User-defined entity extractors from `.rpg/extractors/*.toml` run during
+//! `parse_files_with_paradigms` without any detection rule.
+
+use rpg_core::graph::EntityKind;
+use rpg_parser::languages::Language;
+use rpg_parser::paradigms::defs::load_defs;
+use rpg_parser::paradigms::detect_paradigms_toml;
+use rpg_parser::paradigms::query_engine::QueryCache;
+use std::path::PathBuf;
+
+const TABLES_TOML: &str = r#"
+languages = ["python"]
+
+[[entity_queries]]
+id = "sqlalchemy.table"
+entity_kind = "class"
+entity_name = "@name"
+entity_body = "@body"
+query = '''
+(expression_statement
+  (assignment
+    left: (identifier) @name
+    right: (call function: (identifier) @fn (#eq? @fn "Table")))) @body
+'''
+"#;
+
+const MODELS_PY: &str = r#"from sqlalchemy import Column, Integer, MetaData, Table
+
+metadata = MetaData()
+
+users = Table(
+    "users",
+    metadata,
+    Column("id", Integer, primary_key=True),
+)
+
+engine = create_engine("sqlite://")
+"#;
+
+fn project(extractor: &str) -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".rpg/extractors")).unwrap();
+    std::fs::write(tmp.path().join(".rpg/extractors/tables.toml"), extractor).unwrap();
+    tmp
+}
+
+#[test]
+fn test_extractor_queries_synthesize_entities() {
+    let tmp = project(TABLES_TOML);
+    let defs = load_defs(tmp.path()).unwrap();
+    let qcache = QueryCache::compile_all(&defs).unwrap();
+    let active = detect_paradigms_toml(tmp.path(), &[Language::PYTHON], &defs);
+    assert!(active.iter().any(|d| d.name == "extractors/tables"));
+
+    let raw = rpg_parser::parse_files_with_paradigms(
+        vec![(PathBuf::from("models.py"), MODELS_PY.to_string())],
+        &active,
+        &qcache,
+    );
+    let tables: Vec<_> = raw.iter().filter(|e| e.kind == EntityKind::Class).collect();
+    assert_eq!(tables.len(), 1, "{:?}", tables);
+    let users = tables[0];
+    assert_eq!(users.name, "users");
+    assert_eq!((users.line_start, users.line_end), (5, 9));
+    assert!(users.source_text.starts_with("users = Table("));
+}
+
+#[test]
+fn test_extractors_only_run_for_their_languages() {
+    let tmp = project(TABLES_TOML);
+    let defs = load_defs(tmp.path()).unwrap();
+    let active = detect_paradigms_toml(tmp.path(), &[Language::RUST], &defs);
+    assert!(!active.iter().any(|d| d.name.starts_with("extractors/")));
+}
+
+#[test]
+fn test_bad_extractor_is_reported_with_file_and_line() {
+    let tmp = project(&TABLES_TOML.replace("(identifier) @fn", "(identifer) @fn"));
+    let errors = load_defs(tmp.path()).unwrap_err();
+    let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(
+        messages[0].starts_with(
+            "[.rpg/extractors/tables.toml:5] rule sqlalchemy.table: query for 'python'"
+        ),
+        "{}",
+        messages[0]
+    );
+}
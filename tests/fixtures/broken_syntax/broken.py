@@ -0,0 +1,16 @@
+"""Deliberately broken: a half-written function between two good ones."""
+
+
+def load(path):
+    with open(path) as f:
+        return f.read()
+
+
+def parse(text:
+    for line in text.splitlines(
+        yield line.split("=")
+
+
+def save(path, data):
+    with open(path, "w") as f:
+        f.write(data)
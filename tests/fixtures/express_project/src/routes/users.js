@@ -0,0 +1,16 @@
+const express = require("express");
+
+function registerUserRoutes(router) {
+  router.get("/users", listUsers);
+  router.post("/users", createUser);
+}
+
+function listUsers(req, res) {
+  res.json([]);
+}
+
+function createUser(req, res) {
+  res.status(201).json(req.body);
+}
+
+module.exports = { registerUserRoutes };
@@ -0,0 +1,11 @@
+from pydantic import BaseModel
+
+
+class UserCreate(BaseModel):
+    name: str
+    email: str
+
+
+class UserOut(BaseModel):
+    id: int
+    name: str
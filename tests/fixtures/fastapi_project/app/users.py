@@ -0,0 +1,17 @@
+from typing import Optional
+
+from fastapi import APIRouter
+
+from app.schemas import UserCreate, UserOut
+
+router = APIRouter(prefix="/users", tags=["users"])
+
+
+@router.get("/{user_id}", response_model=UserOut)
+def read_user(user_id: int):
+    return {"id": user_id, "name": ""}
+
+
+@router.put("/{user_id}")
+def update_user(user_id: int, body: UserCreate, note: Optional[str] = None) -> UserOut:
+    return {"id": user_id, "name": body.name}
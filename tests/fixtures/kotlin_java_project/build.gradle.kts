@@ -0,0 +1,8 @@
+plugins {
+    kotlin("jvm") version "1.9.22"
+    application
+}
+
+application {
+    mainClass.set("com.example.Main")
+}
@@ -0,0 +1,12 @@
+package com.example;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(run("world"));
+    }
+
+    static String run(String name) {
+        Greeter greeter = Greeter.Companion.create(name);
+        return GreeterKt.shout(greeter.greet());
+    }
+}
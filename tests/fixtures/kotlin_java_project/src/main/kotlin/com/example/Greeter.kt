@@ -0,0 +1,16 @@
+package com.example
+
+import com.example.util.Formatter as Fmt
+import kotlinx.coroutines.*
+
+class Greeter(private val name: String) {
+    fun greet(): String = Fmt.format("Hello, $name").shout()
+
+    companion object {
+        fun create(name: String): Greeter = Greeter(name.trim())
+    }
+}
+
+fun String.shout(): String = uppercase() + "!"
+
+fun List<Greeter>?.greetAll(): List<String> = this?.map { it.greet() } ?: emptyList()
@@ -0,0 +1,5 @@
+package com.example.util
+
+object Formatter {
+    fun format(text: String): String = text.trim()
+}
@@ -0,0 +1,24 @@
+<?php
+
+namespace App\Http\Controllers;
+
+use App\Services\Payment;
+use App\Legacy\Payment as LegacyPayment;
+
+class OrderController
+{
+    public function store(int $cents): bool
+    {
+        return Payment::charge($cents);
+    }
+
+    public function migrate(int $cents): bool
+    {
+        return LegacyPayment::charge($cents);
+    }
+
+    public function refund(int $cents): bool
+    {
+        return \App\Legacy\Payment::charge(-$cents);
+    }
+}
@@ -0,0 +1,11 @@
+<?php
+
+namespace App\Legacy;
+
+class Payment
+{
+    public static function charge(int $cents): bool
+    {
+        return false;
+    }
+}
@@ -0,0 +1,11 @@
+<?php
+
+namespace App\Services;
+
+class Payment
+{
+    public static function charge(int $cents): bool
+    {
+        return $cents > 0;
+    }
+}
@@ -0,0 +1,7 @@
+<!DOCTYPE html>
+<html>
+<body>
+<?php function order_total(array $items): int { return array_sum($items); } ?>
+<p>Total: <?= order_total([1, 2]) ?></p>
+</body>
+</html>
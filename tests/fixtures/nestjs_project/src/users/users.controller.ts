@@ -0,0 +1,31 @@
+import { Body, Controller, Delete, Get, Param, Post } from '@nestjs/common';
+import { UsersService } from './users.service';
+
+@Controller('users')
+export class UsersController {
+  constructor(private readonly users: UsersService) {}
+
+  @Get()
+  list() {
+    return [];
+  }
+
+  @Get(':id')
+  findOne(@Param('id') id: string) {
+    return this.users.findOne(id);
+  }
+
+  @Post()
+  create(@Body() body: { name: string }) {
+    return this.users.create(body.name);
+  }
+
+  @Delete(':id')
+  remove(@Param('id') id: string) {
+    return id;
+  }
+
+  private audit(action: string) {
+    return action;
+  }
+}
@@ -0,0 +1,16 @@
+import { Injectable } from '@nestjs/common';
+
+@Injectable()
+export class UsersService {
+  private readonly users = new Map<string, string>();
+
+  findOne(id: string): string | undefined {
+    return this.users.get(id);
+  }
+
+  create(name: string): string {
+    const id = String(this.users.size + 1);
+    this.users.set(id, name);
+    return id;
+  }
+}
@@ -0,0 +1,7 @@
+export default function AboutPage() {
+    return (
+        <main>
+            <h1>About</h1>
+        </main>
+    );
+}
@@ -0,0 +1,12 @@
+import { createPost, listPosts } from "../../../src/lib/posts";
+
+export async function GET() {
+    const posts = await listPosts();
+    return Response.json(posts);
+}
+
+export async function POST(request: Request) {
+    const body = await request.json();
+    const post = await createPost(body);
+    return Response.json(post, { status: 201 });
+}
@@ -0,0 +1,8 @@
+export default function DashboardLayout({ children }: { children: React.ReactNode }) {
+    return (
+        <section>
+            <nav>Dashboard</nav>
+            {children}
+        </section>
+    );
+}
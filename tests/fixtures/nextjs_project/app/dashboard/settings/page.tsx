@@ -0,0 +1,10 @@
+import { useAuth } from "../../../src/hooks/useAuth";
+
+export default function SettingsPage() {
+    const { user } = useAuth();
+    return (
+        <main>
+            <h1>Settings for {user}</h1>
+        </main>
+    );
+}
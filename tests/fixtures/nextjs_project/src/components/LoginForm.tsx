@@ -1,12 +1,14 @@
 import { useSelector, useDispatch } from "react-redux";
 import { selectAuthLoading } from "../state/selectors";
 import { loginUser } from "../state/thunks";
+import { authActions } from "../state/authSlice";
 
 export function LoginForm() {
     const loading = useSelector(selectAuthLoading);
     const dispatch = useDispatch();
     const handleSubmit = async (e: React.FormEvent) => {
         e.preventDefault();
+        dispatch(authActions.loginStarted());
         dispatch(loginUser({ email: "a@b.com", password: "123" }));
     };
     return (
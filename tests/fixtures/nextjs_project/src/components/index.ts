@@ -0,0 +1,6 @@
+import { lazy } from 'react';
+
+export { LoginForm } from './LoginForm';
+export * from './PostList';
+
+export const LazyPostList = lazy(() => import('./PostList'));
@@ -0,0 +1,16 @@
+export interface Post {
+    id: string;
+    title: string;
+}
+
+const posts: Post[] = [];
+
+export async function listPosts(): Promise<Post[]> {
+    return posts;
+}
+
+export async function createPost(input: { title: string }): Promise<Post> {
+    const post = { id: String(posts.length + 1), title: input.title };
+    posts.push(post);
+    return post;
+}
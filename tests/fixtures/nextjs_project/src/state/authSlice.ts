@@ -33,4 +33,5 @@ const authSlice = createSlice({
 });
 
 export const { loginStarted, loginSucceeded, logout } = authSlice.actions;
+export const authActions = authSlice.actions;
 export default authSlice.reducer;
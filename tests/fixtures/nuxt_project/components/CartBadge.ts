@@ -0,0 +1,11 @@
+// <script lang="ts"> block of components/CartBadge.vue
+import { defineComponent } from "vue";
+import { useCartTotal } from "../composables/useCartTotal";
+
+export default defineComponent({
+  name: "CartBadge",
+  setup() {
+    const total = useCartTotal();
+    return { total };
+  },
+});
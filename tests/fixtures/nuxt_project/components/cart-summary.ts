@@ -0,0 +1,9 @@
+// <script setup lang="ts"> block of components/cart-summary.vue
+import { useCartStore } from "../stores/cart";
+
+const props = defineProps<{ title: string }>();
+const cart = useCartStore();
+
+function emptyCart() {
+  cart.items = [];
+}
@@ -0,0 +1,17 @@
+import { computed } from "vue";
+import { useCartStore } from "../stores/cart";
+
+export function useCartTotal() {
+  const cart = useCartStore();
+  return computed(() => cart.items.length);
+}
+
+export function clearCart() {
+  const cart = useCartStore();
+  cart.$reset();
+}
+
+export function addToCart(item: string) {
+  const cart = useCartStore();
+  cart.$patch((state) => state.items.push(item));
+}
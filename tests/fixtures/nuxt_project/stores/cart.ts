@@ -0,0 +1,13 @@
+import { defineStore } from "pinia";
+
+export const useCartStore = defineStore("cart", {
+  state: () => ({ items: [] as string[] }),
+  getters: {
+    count: (state) => state.items.length,
+  },
+  actions: {
+    add(item: string) {
+      this.items.push(item);
+    },
+  },
+});
@@ -0,0 +1,6 @@
+from sqlalchemy import create_engine
+from sqlalchemy.orm import Session
+
+
+def connect(url):
+    return Session(create_engine(url))
@@ -0,0 +1,15 @@
+import pytest
+
+from app.db import connect
+
+
+@pytest.fixture(scope="session")
+def db_session():
+    session = connect("sqlite://")
+    yield session
+    session.rollback()
+
+
+@pytest.fixture
+def client(db_session):
+    return {"db": db_session}
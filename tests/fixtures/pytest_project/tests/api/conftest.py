@@ -0,0 +1,7 @@
+import pytest
+
+
+@pytest.fixture
+def client(client, settings):
+    client["settings"] = settings
+    return client
@@ -0,0 +1,21 @@
+import pytest
+
+
+def test_create_user(client, user, tmp_path):
+    assert client["db"] is not None
+    assert user is not None
+
+
+@pytest.mark.parametrize("user_id, expected", [(1, True), (2, False)])
+def test_lookup(user, user_id, expected):
+    assert (user is not None) == expected or user_id
+
+
+@pytest.mark.usefixtures("db_session")
+def test_schema():
+    assert True
+
+
+class TestAdmin:
+    def test_promote(self, user):
+        assert user is not None
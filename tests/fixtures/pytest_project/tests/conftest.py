@@ -0,0 +1,11 @@
+import pytest
+
+
+@pytest.fixture
+def user(db_session):
+    return db_session.add({"name": "ada"})
+
+
+@pytest.fixture(name="settings")
+def settings_fixture():
+    return {"debug": True}
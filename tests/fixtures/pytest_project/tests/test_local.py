@@ -0,0 +1,11 @@
+import pytest
+
+
+@pytest.fixture
+def user():
+    return {"name": "local"}
+
+
+def test_local_user(user, settings):
+    assert user["name"] == "local"
+    assert settings["debug"]
@@ -0,0 +1,21 @@
+"""Count the characters in a file."""
+import argparse
+
+VERSION = "1.0"
+
+
+def load(path):
+    with open(path) as f:
+        return f.read()
+
+
+def report(text):
+    print(len(text))
+
+
+parser = argparse.ArgumentParser(description="Count characters")
+parser.add_argument("path")
+
+if __name__ == "__main__":
+    args = parser.parse_args()
+    report(load(args.path))
@@ -0,0 +1,5 @@
+"""Constants only: no top-level calls, so no module-scope entity."""
+import os
+
+DEBUG = False
+ROOT = "."
@@ -0,0 +1,8 @@
+package com.example;
+
+public class Launcher {
+    public void start() {
+        Job job = Job.create("adhoc");
+        job.run();
+    }
+}
@@ -0,0 +1,22 @@
+package com.example
+
+import org.apache.spark.sql.{DataFrame, SparkSession}
+
+trait Logging {
+  def log(message: String): Unit = println(message)
+}
+
+case class Point(x: Int, y: Int)
+
+class Job(name: String) extends Runnable with Logging {
+  def run(): Unit = {
+    log(s"running $name")
+    val origin = Point(0, 0)
+  }
+
+  def load(spark: SparkSession): DataFrame = spark.read.parquet(name)
+}
+
+object Job {
+  def create(name: String): Job = new Job(name)
+}
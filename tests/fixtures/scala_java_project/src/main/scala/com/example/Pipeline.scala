@@ -0,0 +1,8 @@
+package com.example
+
+object Pipeline {
+  def main(args: Array[String]): Unit = {
+    val job = Job.create("daily")
+    job.run()
+  }
+}
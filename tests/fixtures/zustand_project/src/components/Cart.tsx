@@ -0,0 +1,18 @@
+import { useCartStore } from "../store/useCartStore";
+
+export function Cart() {
+    const items = useCartStore((state) => state.items);
+    return (
+        <ul>
+            {items.map((item) => <li key={item}>{item}</li>)}
+        </ul>
+    );
+}
+
+export function resetCart() {
+    useCartStore.setState({ items: [] });
+}
+
+export function cartSize() {
+    return useCartStore.getState().items.length;
+}
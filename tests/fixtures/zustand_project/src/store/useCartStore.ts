@@ -0,0 +1,13 @@
+import { create } from "zustand";
+
+interface CartState {
+    items: string[];
+    addItem: (item: string) => void;
+    clear: () => void;
+}
+
+export const useCartStore = create<CartState>()((set) => ({
+    items: [],
+    addItem: (item) => set((state) => ({ items: [...state.items, item] })),
+    clear: () => set({ items: [] }),
+}));